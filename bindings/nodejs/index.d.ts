@@ -17,6 +17,35 @@ export interface ClickResult {
   method: string
   coordinates?: Coordinates
   details: string
+  /** How the click reached the application: 'uia_pattern', 'send_input_cursor' or 'post_message' */
+  strategy?: string
+}
+/** Expected effect of a verified action */
+export interface ExpectedCondition {
+  /** 'gone', 'focused', 'enabled', 'disabled', 'nameEquals', 'valueEquals', 'valueContains' or 'textContains' */
+  kind: string
+  /** Expected string for the Equals and Contains kinds */
+  value?: string
+}
+/** One try of a verified action */
+export interface ActionAttempt {
+  /** Input method used, e.g. 'click', 'mouse' or 'clipboard' */
+  method: string
+  /** Error returned by the action itself, if it failed */
+  error?: string
+  /** Whether the expected condition held after this attempt */
+  conditionMet: boolean
+  durationMs: number
+}
+/** Outcome of an action whose effect was verified */
+export interface VerifiedActionResult {
+  /** Input method of the attempt that produced the expected effect */
+  method: string
+  /** 1-based number of that attempt */
+  attempt: number
+  attempts: Array<ActionAttempt>
+  /** Click details of the successful attempt, for clicks */
+  click?: ClickResult
 }
 export interface CommandOutput {
   exitStatus?: number
@@ -28,6 +57,14 @@ export interface ScreenshotResult {
   height: number
   imageData: Array<number>
 }
+/** A screenshot encoded as an image file. */
+export interface EncodedImage {
+  /** Format the image is encoded in, e.g. "png" or "jpeg:85". */
+  format: string
+  width: number
+  height: number
+  data: Buffer
+}
 export interface UIElementAttributes {
   role: string
   name?: string
@@ -56,8 +93,163 @@ export interface ExploreResponse {
 export interface UINode {
   id?: string
   attributes: UIElementAttributes
+  /** Path selector that re-locates this element. */
+  path?: string
+  /** Whether this node or any node below it is missing children. */
+  truncated: boolean
+  /** Whether this node's own children were all collected: 'complete', 'timed_out' or 'failed'. */
+  status: string
   children: Array<UINode>
 }
+export interface WindowInfo {
+  pid: number
+  title: string
+  id?: string
+  bounds?: Bounds
+}
+export interface WindowTree {
+  window: WindowInfo
+  tree: UINode
+}
+/** Visible contents of a console screen buffer */
+export interface ConsoleScreen {
+  width: number
+  height: number
+  cursorX: number
+  cursorY: number
+  /** Visible lines, top to bottom, with trailing spaces removed */
+  lines: Array<string>
+  /** All visible lines joined with newlines */
+  text: string
+}
+/** OS settings that affect UI trees and timings. Unknown settings are null. */
+export interface EnvironmentInfo {
+  /** Operating system family: 'windows', 'macos' or 'linux' */
+  os: string
+  osVersion?: string
+  osBuild?: string
+  /** 'light' or 'dark' */
+  theme?: string
+  animationsEnabled?: boolean
+  /** Accessibility text size multiplier (1.0 = 100%) */
+  textScaleFactor?: number
+  /** Display scale factor of the primary monitor (1.0 = 100%) */
+  displayScaleFactor?: number
+  screenReaderActive?: boolean
+}
+/** A virtual desktop, as shown in Task View */
+export interface VirtualDesktop {
+  /** GUID of the desktop, passed to `Element.moveToDesktop` */
+  id: string
+  name: string
+  /** Position in Task View, from 0 */
+  index: number
+  /** Whether this is the desktop currently shown */
+  isCurrent: boolean
+}
+/** A stretch of text with the same formatting, from `Element.textWithFormatting` */
+export interface TextRun {
+  text: string
+  /** Character offset of the run in the document */
+  start: number
+  fontName?: string
+  /** Size in points */
+  fontSize?: number
+  bold: boolean
+  italic: boolean
+  underline: boolean
+  /** Text color as `#rrggbb` */
+  foregroundColor?: string
+  /** Highlight color as `#rrggbb` */
+  backgroundColor?: string
+}
+/** One problem found by `Element.audit` */
+export interface AuditFinding {
+  /** `missing_name`, `not_focusable`, `duplicate_automation_id` or `low_contrast` */
+  rule: string
+  /** `error` or `warning` */
+  severity: string
+  message: string
+  role: string
+  name?: string
+  automationId?: string
+  /** Roles and names from the audited root down to the element */
+  location: string
+  bounds?: Bounds
+  contrastRatio?: number
+}
+/** Outcome of `Element.audit` */
+export interface AuditReport {
+  root: string
+  elementsChecked: number
+  findings: Array<AuditFinding>
+  /** Whether anything blocks users of assistive technology */
+  hasErrors: boolean
+}
+/** What this process can automate */
+export interface AutomationCapabilities {
+  /** `untrusted`, `low`, `medium`, `high` or `system`; absent where the platform has no integrity levels */
+  integrityLevel?: string
+  elevated: boolean
+  uiAccess: boolean
+  /** Whether input reaches applications running as administrator */
+  reachesElevatedWindows: boolean
+}
+/** A form field that was filled */
+export interface FilledField {
+  label: string
+  /** `text`, `check_box`, `radio_button`, `combo_box` or `range` */
+  kind: string
+  /** `labeled_by`, `name`, `proximity` or `fuzzy_name` */
+  matchedBy: string
+  controlName?: string
+}
+/** A form field whose control could not be set */
+export interface FailedField {
+  label: string
+  error: string
+}
+/** Outcome of `Element.fillForm` */
+export interface FormFillReport {
+  filled: Array<FilledField>
+  /** Labels no control was found for */
+  unmatched: Array<string>
+  failed: Array<FailedField>
+  /** Whether every field was filled */
+  isComplete: boolean
+}
+/** An operation for `Desktop.batch` */
+export interface BatchOperation {
+  /** `find`, `click`, `typeText` or `getText` */
+  op: string
+  selector: string
+  /** Text to type, for `typeText` */
+  text?: string
+}
+/** Result of one operation of `Desktop.batch` */
+export interface BatchResult {
+  index: number
+  op: string
+  selector: string
+  /** `ok`, `failed` or `skipped` */
+  status: string
+  /** Text read by a `getText` operation */
+  text?: string
+  error?: string
+  errorCode?: string
+  element?: Element
+}
+/** A connected display */
+export interface MonitorInfo {
+  name: string
+  /** Position and size in virtual screen coordinates (physical pixels) */
+  bounds: Bounds
+  /** Ratio of physical to logical pixels (1.0 = 100% scaling) */
+  scaleFactor: number
+  /** Refresh rate in Hz */
+  refreshRate: number
+  isPrimary: boolean
+}
 export const enum PropertyLoadingMode {
   /** Only load essential properties (role + name) - fastest */
   Fast = 'Fast',
@@ -75,6 +267,197 @@ export interface TreeBuildConfig {
   yieldEveryNElements?: number
   /** Optional batch size for processing elements */
   batchSize?: number
+  /** Optional time budget for the whole tree in milliseconds; a partial tree is returned when it runs out */
+  maxTotalDurationMs?: number
+  /** Fetch children with their properties in one call (Windows UIA cache requests), on by default */
+  useCacheRequest?: boolean
+}
+/** How a window tree was built */
+export interface TreeBuildStats {
+  elements: number
+  maxDepth: number
+  /** Elements whose attributes were read from a cache request */
+  cachedElements: number
+  /** Cache requests made, one per element whose children were fetched */
+  cacheRequests: number
+  /** Lookups the cache could not serve, done without it */
+  uncachedFallbacks: number
+  errors: number
+  /** Subtrees left incomplete when the time budget ran out */
+  truncatedSubtrees: number
+  durationMs: number
+}
+/** A window tree with how it was built, from `Desktop.getWindowTreeWithStats` */
+export interface TreeBuildResult {
+  tree: UINode
+  stats: TreeBuildStats
+}
+export interface RetryPolicy {
+  /** Delay before the second attempt in milliseconds (default 100) */
+  initialDelayMs?: number
+  /** Upper bound for the delay between attempts in milliseconds (default 5000) */
+  maxDelayMs?: number
+  /** Factor applied to the delay after each failed attempt (default 1.0) */
+  multiplier?: number
+  /** Maximum number of attempts, unlimited if omitted */
+  maxAttempts?: number
+  /** Random spread applied to each delay, as a fraction (0.0 - 1.0) */
+  jitter?: number
+  /** Optional cap on how long a single attempt may search, in milliseconds */
+  attemptTimeoutMs?: number
+}
+/** A single failed check. */
+export interface VerificationFailure {
+  check: string
+  message: string
+}
+/** Outcome of all checks made by a SoftVerifier. */
+export interface VerificationReport {
+  step: string
+  checks: number
+  failures: Array<VerificationFailure>
+}
+/** Options for a WorkflowRecorder. Omitted fields keep the recorder's defaults. */
+export interface WorkflowRecorderOptions {
+  /** Name stored in the recorded workflow (default "Recorded workflow") */
+  name?: string
+  recordMouse?: boolean
+  recordKeyboard?: boolean
+  recordWindow?: boolean
+  /** Attach the UI element under the pointer or with focus to each event */
+  captureUiElements?: boolean
+  recordClipboard?: boolean
+  recordTextSelection?: boolean
+  recordDragDrop?: boolean
+  recordHotkeys?: boolean
+  recordUiFocusChanges?: boolean
+  recordUiStructureChanges?: boolean
+  recordUiPropertyChanges?: boolean
+  /** Minimum time between mouse move events in milliseconds */
+  mouseMoveThrottleMs?: number
+  /** Window titles whose UI events are dropped (case-insensitive), added to the defaults */
+  ignoreWindowTitles?: Array<string>
+  /** Process names whose UI events are dropped (case-insensitive), added to the defaults */
+  ignoreApplications?: Array<string>
+  /** Attach a JPEG thumbnail to clicks and the start of typing bursts */
+  screenshots?: boolean
+  /** Record the microphone into this WAV file, referenced from the workflow */
+  audioPath?: string
+  /** Record what the speakers play instead of the microphone */
+  audioLoopback?: boolean
+}
+/** A finished recording. */
+export interface RecordedWorkflow {
+  name: string
+  /** Milliseconds since the Unix epoch */
+  startTime: number
+  /** Milliseconds since the Unix epoch, missing while recording */
+  endTime?: number
+  /** Recorded events, in the same JSON form as a saved recording */
+  events: Array<any>
+  /** The narration track (`path`, `start_time`, `duration_ms`, ...), if audio was recorded */
+  audio?: any
+}
+/** A process started with desktop.launch(). */
+export declare class AppHandle {
+  /** Process ID of the launched process. */
+  get pid(): number
+  /**
+   * Whether the process is still running.
+   *
+   * @returns {boolean} True while the process has not exited.
+   */
+  isRunning(): boolean
+  /**
+   * Exit code of the process, or null while it is running.
+   *
+   * @returns {number | null} The exit code, if the process exited with one.
+   */
+  exitCode(): number | null
+  /** Terminate the process. Does nothing if it already exited. */
+  kill(): void
+  /**
+   * (async) Wait for the process to show a window.
+   *
+   * @param {number} [timeoutMs] - Timeout in milliseconds (default 30000).
+   * @returns {Promise<Element>} The application element of the process.
+   */
+  waitForWindow(timeoutMs?: number | undefined | null): Promise<Element>
+  /**
+   * (async) Wait for the process to exit.
+   *
+   * @param {number} [timeoutMs] - Timeout in milliseconds. Waits forever if omitted.
+   * @returns {Promise<number | null>} The exit code, or null if the process was terminated without one.
+   */
+  waitForExit(timeoutMs?: number | undefined | null): Promise<number | null>
+}
+/**
+ * Locators, trees, screenshots and events of one application, from
+ * desktop.attach(). The session follows the app when its process restarts.
+ */
+export declare class AppSession {
+  /**
+   * Process ID of the attached process, attaching again if it restarted.
+   *
+   * @returns {number} The process ID.
+   */
+  pid(): number
+  /**
+   * Whether the attached process is still running.
+   *
+   * @returns {boolean} False once the process exited and no new one was found yet.
+   */
+  isAttached(): boolean
+  /**
+   * The application element of the attached process.
+   *
+   * @returns {Element} The application element.
+   */
+  application(): Element
+  /**
+   * Create a locator that searches the attached application only.
+   *
+   * @param {string} selector - The selector string.
+   * @returns {Locator} A new locator for finding elements.
+   */
+  locator(selector: string): Locator
+  /**
+   * Get the UI tree of the application's window.
+   *
+   * @param {TreeBuildConfig} [config] - Optional configuration for tree building.
+   * @returns {UINode} Complete UI tree starting from the window.
+   */
+  getWindowTree(config?: TreeBuildConfig | undefined | null): UINode
+  /**
+   * Capture a screenshot of the application's window.
+   *
+   * @returns {ScreenshotResult} The screenshot data.
+   */
+  capture(): ScreenshotResult
+  /**
+   * Call a function for UI events raised by the attached process, following
+   * it across restarts. Windows only.
+   *
+   * @param {Array<string>} kinds - Any of 'focusChanged', 'structureChanged', 'propertyChanged'.
+   * @param {function} callback - Called with each event.
+   * @returns {UiEventSubscription} Call `unsubscribe()` to stop receiving events.
+   */
+  subscribeUiEvents(kinds: Array<string>, callback: (event: { kind: string, element?: Element, detail?: string, value?: string }) => void): UiEventSubscription
+  /**
+   * Call a function when the session attaches to a process, at first or after
+   * a restart, and when the attached process exits.
+   *
+   * @param {function} callback - Called with `{ kind: 'attached' | 'detached', pid }`.
+   */
+  onLifecycle(callback: (event: { kind: 'attached' | 'detached', pid: number }) => void): void
+  /**
+   * (async) Wait until the application runs again after it exited.
+   * Resolves at once while attached.
+   *
+   * @param {number} [timeoutMs] - Timeout in milliseconds (default 30000).
+   * @returns {Promise<Element>} The application element of the new process.
+   */
+  waitForAttach(timeoutMs?: number | undefined | null): Promise<Element>
 }
 /** Main entry point for desktop automation. */
 export declare class Desktop {
@@ -112,6 +495,24 @@ export declare class Desktop {
    * @param {string} name - The name of the application to open.
    */
   openApplication(name: string): Element
+  /**
+   * Pin locators, trees, screenshots and events to one application. The
+   * session follows the app when its process restarts.
+   *
+   * @param {string | number} target - Application name, or process ID.
+   * @returns {AppSession} The session, attached to the running app.
+   */
+  attach(target: number | string): AppSession
+  /**
+   * Start an executable and keep a handle to its process.
+   *
+   * @param {string} path - Path or name of the executable.
+   * @param {string[]} [args] - Command line arguments.
+   * @param {Record<string, string>} [env] - Variables added to the inherited environment.
+   * @param {string} [cwd] - Working directory.
+   * @returns {AppHandle} A handle to wait for the window or exit, or to kill the process.
+   */
+  launch(path: string, args?: Array<string> | undefined | null, env?: Record<string, string> | undefined | null, cwd?: string | undefined | null): AppHandle
   /**
    * Activate an application by name.
    *
@@ -124,6 +525,13 @@ export declare class Desktop {
    * @returns {Promise<ScreenshotResult>} The screenshot data.
    */
   captureScreen(): Promise<ScreenshotResult>
+  /**
+   * (async) Capture the primary monitor encoded as an image file.
+   *
+   * @param {string} format - "png", "jpeg", "jpeg:<quality>" or "webp".
+   * @returns {Promise<EncodedImage>} The encoded screenshot.
+   */
+  captureScreenAs(format: string): Promise<EncodedImage>
   /**
    * (async) Run a shell command.
    *
@@ -138,6 +546,42 @@ export declare class Desktop {
    * @returns {Promise<string>} The name of the active monitor.
    */
   getActiveMonitorName(): Promise<string>
+  /**
+   * List the connected monitors.
+   *
+   * @returns {Array<MonitorInfo>} Name, bounds, scale factor, refresh rate and primary flag of each monitor.
+   */
+  monitors(): Array<MonitorInfo>
+  /**
+   * Highlight several elements at once, e.g. everything a locator matched.
+   *
+   * @param {Array<Element>} elements - Elements to draw a border around.
+   * @param {number} [color] - Optional BGR color code (32-bit integer). Default: 0x0000FF (red)
+   * @param {number} [durationMs] - Optional duration in milliseconds. Default: 500
+   * @returns {void}
+   */
+  highlightAll(elements: Array<Element>, color?: number | undefined | null, durationMs?: number | undefined | null): void
+  /**
+   * Get the OS version, theme, animation, scaling and screen reader settings.
+   * Attach this to bug reports when automation behaves differently between machines.
+   *
+   * @returns {EnvironmentInfo} The environment; settings that cannot be read are null.
+   */
+  environment(): EnvironmentInfo
+  /**
+   * Report what this process can automate: its integrity level, whether it runs as
+   * administrator and whether it has UIAccess. Without elevation or UIAccess, actions on
+   * applications running as administrator throw an ElevationRequiredError.
+   *
+   * @returns {AutomationCapabilities} The capabilities of this process.
+   */
+  capabilities(): AutomationCapabilities
+  /**
+   * List the virtual desktops in Task View order (Windows only).
+   *
+   * @returns {Array<VirtualDesktop>} The desktops; the one shown has isCurrent set.
+   */
+  virtualDesktops(): Array<VirtualDesktop>
   /**
    * (async) Capture a screenshot of a specific monitor.
    *
@@ -145,6 +589,14 @@ export declare class Desktop {
    * @returns {Promise<ScreenshotResult>} The screenshot data.
    */
   captureMonitorByName(name: string): Promise<ScreenshotResult>
+  /**
+   * (async) Capture a specific monitor encoded as an image file.
+   *
+   * @param {string} name - The name of the monitor to capture.
+   * @param {string} format - "png", "jpeg", "jpeg:<quality>" or "webp".
+   * @returns {Promise<EncodedImage>} The encoded screenshot.
+   */
+  captureMonitorByNameAs(name: string, format: string): Promise<EncodedImage>
   /**
    * (async) Perform OCR on an image file.
    *
@@ -172,6 +624,24 @@ export declare class Desktop {
    * @returns {Locator} A locator for finding elements.
    */
   locator(selector: string): Locator
+  /**
+   * Create a locator for one logical target that tries several selectors in order.
+   * With the selector cache enabled, the selector that resolved the target is
+   * remembered per application version and tried first next time.
+   *
+   * @param {string} target - Name of what is being looked for, e.g. "save button".
+   * @param {string[]} selectors - Candidate selectors, most specific first.
+   * @returns {Locator} A locator for finding elements.
+   */
+  locatorAny(target: string, selectors: Array<string>): Locator
+  /**
+   * Remember which selector resolved each target of locatorAny.
+   *
+   * @param {string} [path] - JSON file to persist learned selectors in. Kept in memory if omitted.
+   */
+  enableSelectorCache(path?: string | undefined | null): void
+  /** Stop learning and preferring selectors for locatorAny. */
+  disableSelectorCache(): void
   /**
    * (async) Get the currently focused window.
    *
@@ -191,12 +661,23 @@ export declare class Desktop {
    */
   focusedElement(): Element
   /**
-   * Open a URL in a browser.
+   * Open a URL in a browser. Returns as soon as the browser is launched;
+   * use `openUrlAndWait` to get the loaded page.
    *
    * @param {string} url - The URL to open.
    * @param {string} [browser] - The browser to use.
+   * @returns {Element} The browser window.
    */
-  openUrl(url: string, browser?: string | undefined | null): void
+  openUrl(url: string, browser?: string | undefined | null): Element
+  /**
+   * (async) Open a URL and wait until the browser shows the page.
+   *
+   * @param {string} url - The URL to open.
+   * @param {string} [browser] - The browser to use.
+   * @param {number} [timeoutMs] - How long to wait in milliseconds. Defaults to 30 seconds.
+   * @returns {Promise<Element>} The page's document element, ready for chaining locators.
+   */
+  openUrlAndWait(url: string, browser?: string | undefined | null, timeoutMs?: number | undefined | null): Promise<Element>
   /**
    * Open a file with its default application.
    *
@@ -218,6 +699,163 @@ export declare class Desktop {
    * @returns {UINode} Complete UI tree starting from the identified window.
    */
   getWindowTree(pid: number, title?: string | undefined | null, config?: TreeBuildConfig | undefined | null): UINode
+  /**
+   * Get the UI tree for a window, with how it was built: element count,
+   * elements served from cache requests and duration.
+   *
+   * @param {number} pid - Process ID of the target application.
+   * @param {string} [title] - Optional window title filter.
+   * @param {TreeBuildConfig} [config] - Optional configuration for tree building.
+   * @returns {TreeBuildResult} The tree and its build statistics.
+   */
+  getWindowTreeWithStats(pid: number, title?: string | undefined | null, config?: TreeBuildConfig | undefined | null): TreeBuildResult
+  /**
+   * Get the UI tree for a window, keeping the live element behind each node
+   * so a node found in the tree can be acted on without another search.
+   *
+   * @param {number} pid - Process ID of the target application.
+   * @param {string} [title] - Optional window title filter.
+   * @param {TreeBuildConfig} [config] - Optional configuration for tree building.
+   * @returns {UINodeWithHandles} The tree with an element for each node.
+   */
+  getWindowTreeWithHandles(pid: number, title?: string | undefined | null, config?: TreeBuildConfig | undefined | null): UINodeWithHandles
+  /**
+   * Get the UI trees of all visible top-level windows, built concurrently.
+   *
+   * @param {TreeBuildConfig} [config] - Optional configuration for tree building.
+   * @returns {Array<WindowTree>} Each window with its UI tree, in z-order.
+   */
+  getAllWindowTrees(config?: TreeBuildConfig | undefined | null): Array<WindowTree>
+  /**
+   * Get the state of the desktop session this process runs in.
+   *
+   * @returns {string} One of 'active', 'locked', 'disconnected', 'service' or 'unknown'.
+   */
+  sessionState(): string
+  /** Throw a UIUnavailableError if the session is locked, disconnected or non-interactive. */
+  ensureUiAvailable(): void
+  /**
+   * Reconnect the current remote session to the console so it stays interactive
+   * after the RDP client disconnects. Windows only, requires administrator rights.
+   */
+  attachToConsole(): void
+  /**
+   * Read the visible screen of the console attached to a process (e.g. cmd.exe). Windows only.
+   *
+   * @param {number} pid - Process id of the console application.
+   * @returns {ConsoleScreen} The visible lines and cursor position.
+   */
+  readConsole(pid: number): ConsoleScreen
+  /**
+   * Type text into the console attached to a process. Newlines are sent as Enter. Windows only.
+   *
+   * @param {number} pid - Process id of the console application.
+   * @param {string} text - The text to type.
+   */
+  sendConsoleInput(pid: number, text: string): void
+  /**
+   * (async) Run several element operations in one call, resolving each selector once.
+   *
+   * @param {Array<BatchOperation>} ops - Operations such as `{ op: 'click', selector: 'name:OK' }`.
+   * @param {number} [timeoutMs] - How long each selector may take to resolve.
+   * @param {boolean} [stopOnError] - Skip the remaining operations after one fails. Default: true.
+   * @returns {Promise<Array<BatchResult>>} One result per operation.
+   */
+  batch(ops: Array<BatchOperation>, timeoutMs?: number | undefined | null, stopOnError?: boolean | undefined | null): Promise<Array<BatchResult>>
+  /**
+   * (async) Wait until text appears on the screen of the console attached to a process.
+   *
+   * @param {number} pid - Process id of the console application.
+   * @param {string} text - The text to wait for.
+   * @param {number} timeoutMs - How long to wait in milliseconds.
+   * @returns {Promise<ConsoleScreen>} The screen that contained the text.
+   */
+  waitForConsoleText(pid: number, text: string, timeoutMs: number): Promise<ConsoleScreen>
+  /**
+   * (async) Wait until an application is idle: started up, its UI thread responsive,
+   * and no elements added or removed for a quiet period. Windows only.
+   *
+   * @param {number} pid - Process id of the application.
+   * @param {number} timeoutMs - How long to wait in milliseconds.
+   * @param {number} [quietMs] - Quiet period without UI structure changes in milliseconds. Default: 500.
+   * @returns {Promise<void>} Resolves once the application is idle.
+   */
+  waitForIdle(pid: number, timeoutMs: number, quietMs?: number | undefined | null): Promise<void>
+  /**
+   * Call a function for every UI event of the given kinds. Events are raised on a
+   * background watcher thread and delivered on the JS thread. Prefer
+   * `desktop.on('focusChanged', cb)`, which manages subscriptions for you. Windows only.
+   *
+   * @param {Array<string>} kinds - Any of 'focusChanged', 'structureChanged', 'propertyChanged'.
+   * @param {function} callback - Called with each event.
+   * @returns {UiEventSubscription} Call `unsubscribe()` to stop receiving events.
+   */
+  subscribeUiEvents(kinds: Array<string>, callback: (event: { kind: string, element?: Element, detail?: string, value?: string }) => void): UiEventSubscription
+  /**
+   * Call a function each time a system-wide hotkey is pressed. Presses are detected on
+   * a background dispatcher thread and delivered on the JS thread. Fails if another
+   * application already owns the combination. Windows only.
+   *
+   * @param {string} hotkey - Modifiers and a key, e.g. "Ctrl+Shift+H" or "Alt+F9".
+   * @param {function} callback - Called on each press.
+   * @returns {HotkeyRegistration} Call `unregister()` to release the hotkey.
+   */
+  registerHotkey(hotkey: string, callback: () => void): HotkeyRegistration
+  /**
+   * List the notifications on screen: Windows toasts or macOS Notification Center banners.
+   *
+   * @returns {Array<Notification>} The notifications, with their app, title, body and actions.
+   */
+  notifications(): Array<Notification>
+  /**
+   * Call a function for each notification that appears. Notifications already on screen
+   * are not reported. New ones are found by polling on a background thread and delivered
+   * on the JS thread.
+   *
+   * @param {function} callback - Called with each new Notification.
+   * @returns {UiEventSubscription} Call `unsubscribe()` to stop receiving notifications.
+   */
+  subscribeNotifications(callback: (notification: Notification) => void): UiEventSubscription
+  /**
+   * (async) Wait until a notification whose app name, title or body contains text is on screen.
+   *
+   * @param {string} text - The text to wait for, ignoring case.
+   * @param {number} timeoutMs - How long to wait in milliseconds.
+   * @returns {Promise<Notification>} The first matching notification.
+   */
+  waitForNotification(text: string, timeoutMs: number): Promise<Notification>
+  /**
+   * Abort the run with a StalledRunError if no action completes successfully for the given time.
+   * A screenshot, the foreground window and the active application's tree are captured
+   * when the watchdog fires. Calling this again replaces the previous watchdog.
+   *
+   * @param {number} timeoutMs - Idle time in milliseconds before the run is considered stalled.
+   */
+  idleTimeout(timeoutMs: number): void
+  /** Disarm the idle watchdog, if one is running. */
+  clearIdleTimeout(): void
+  /**
+   * Block physical keyboard and mouse input until called again with false.
+   * Input sent by terminator still gets through, and Ctrl+Alt+Del always
+   * releases the block. Windows only.
+   *
+   * @param {boolean} enabled - Whether to block input.
+   */
+  blockUserInput(enabled: boolean): void
+  /**
+   * Block physical input while each element action runs. Windows only.
+   *
+   * @param {boolean} enabled - Whether to guard every action.
+   */
+  setAutoInputGuard(enabled: boolean): void
+  /**
+   * Pace synthetic keyboard and mouse input. Some applications drop keys
+   * typed instantly or only react to a pointer that hovered first.
+   *
+   * @param {string} profile - `instant`, `fast` (the default) or `human` for
+   * irregular typing, curved pointer movement and a pause before clicks.
+   */
+  setInputProfile(profile: string): void
 }
 /** A UI element in the accessibility tree. */
 export declare class Element {
@@ -257,18 +895,121 @@ export declare class Element {
    * @returns {Element | null} The parent element, if available.
    */
   parent(): Element | null
+  /**
+   * Get the parent, its parent and so on up to the root, nearest first.
+   *
+   * @returns {Array<Element>} The ancestor elements.
+   */
+  ancestors(): Array<Element>
+  /**
+   * Get the element after this one among its parent's children.
+   *
+   * @returns {Element | null} The next sibling, or null for the last child.
+   */
+  nextSibling(): Element | null
+  /**
+   * Get the element before this one among its parent's children.
+   *
+   * @returns {Element | null} The previous sibling, or null for the first child.
+   */
+  previousSibling(): Element | null
+  /**
+   * Get the nearest of this element and its ancestors matching a selector,
+   * e.g. the dialog a button is in with `role:Window`.
+   *
+   * @param {string} selector - A role, id, name, text, nativeid or classname selector, or a not/and/or combination of them.
+   * @returns {Element | null} The matching element, if any.
+   */
+  closest(selector: string): Element | null
   /**
    * Get element bounds.
    *
    * @returns {Bounds} The element's bounds (x, y, width, height).
    */
   bounds(): Bounds
+  /**
+   * Get the monitor this element is displayed on.
+   *
+   * @returns {MonitorInfo | null} The monitor, or null if the element is off-screen.
+   */
+  monitor(): MonitorInfo | null
+  /**
+   * Get the center of the element in physical screen pixels.
+   *
+   * @returns {Coordinates} The element's center point.
+   */
+  center(): Coordinates
+  /**
+   * Get a point relative to the element, e.g. 10px left of its center.
+   *
+   * @param {string} anchor - One of 'center', 'top_left', 'top', 'top_right', 'left', 'right', 'bottom_left', 'bottom', 'bottom_right', or a percentage like '30%,50%'.
+   * @param {number} [offsetX=0] - Horizontal offset in pixels.
+   * @param {number} [offsetY=0] - Vertical offset in pixels.
+   * @returns {Coordinates} The resulting screen point.
+   */
+  pointAt(anchor: string, offsetX?: number | undefined | null, offsetY?: number | undefined | null): Coordinates
+  /**
+   * Check whether this element's bounds overlap another element's bounds.
+   *
+   * @param {Element} other - The element to test against.
+   * @returns {boolean} True if the bounds intersect.
+   */
+  intersects(other: Element): boolean
+  /**
+   * Check whether a screen point lies inside this element.
+   *
+   * @param {number} x - X coordinate in pixels.
+   * @param {number} y - Y coordinate in pixels.
+   * @returns {boolean} True if the point is inside the element's bounds.
+   */
+  containsPoint(x: number, y: number): boolean
   /**
    * Click on this element.
    *
    * @returns {ClickResult} Result of the click operation.
    */
   click(): ClickResult
+  /**
+   * Click on this element delivering the click one way only.
+   *
+   * @param {string} strategy - 'uia_pattern' (the accessibility action), 'send_input_cursor' (real cursor input) or 'post_message' (window messages, Windows only).
+   * @returns {ClickResult} Result of the click operation.
+   */
+  clickWithStrategy(strategy: string): ClickResult
+  /**
+   * Click on this element trying strategies in order until one delivers the click.
+   *
+   * @param {string[]} [strategies] - Strategies to try, by default 'uia_pattern', 'send_input_cursor' then 'post_message'.
+   * @returns {ClickResult} Result of the click, with the strategy that worked.
+   */
+  clickEscalating(strategies?: Array<string> | undefined | null): ClickResult
+  /**
+   * Click at an anchor point of this element instead of its center.
+   * The point is kept inside the element before the offset is applied.
+   *
+   * @param {string} anchor - One of 'center', 'top_left', 'top', 'top_right', 'left', 'right', 'bottom_left', 'bottom', 'bottom_right', or a percentage like '30%,50%'.
+   * @param {number} [offsetX=0] - Horizontal offset in pixels.
+   * @param {number} [offsetY=0] - Vertical offset in pixels.
+   * @returns {ClickResult} Result of the click, with the clicked point in `coordinates`.
+   */
+  clickAtAnchor(anchor: string, offsetX?: number | undefined | null, offsetY?: number | undefined | null): ClickResult
+  /**
+   * Click this element and wait for the expected effect, retrying with a
+   * physical mouse click if the first click had no effect.
+   *
+   * @param {ExpectedCondition} condition - The effect to wait for, e.g. { kind: 'gone' }.
+   * @param {number} [timeoutMs=2000] - How long to wait for the effect after each attempt.
+   * @returns {VerifiedActionResult} Which attempt and method produced the effect.
+   */
+  clickAndExpect(condition: ExpectedCondition, timeoutMs?: number | undefined | null): VerifiedActionResult
+  /**
+   * Replace this element's value with text and check that it was taken,
+   * retrying by pasting and then through the value pattern.
+   *
+   * @param {string} text - The text to enter.
+   * @returns {VerifiedActionResult} Which attempt and method set the value.
+   */
+  typeTextVerified(text: string): VerifiedActionResult
   /**
    * Double click on this element.
    *
@@ -305,8 +1046,9 @@ export declare class Element {
    *
    * @param {string} text - The text to type.
    * @param {boolean} [useClipboard] - Whether to use clipboard for pasting.
+   * @param {boolean} [waitForEditable=true] - Wait until the element is enabled, focusable and not read-only before typing.
    */
-  typeText(text: string, useClipboard?: boolean | undefined | null): void
+  typeText(text: string, useClipboard?: boolean | undefined | null, waitForEditable?: boolean | undefined | null): void
   /**
    * Press a key while this element is focused.
    *
@@ -325,6 +1067,81 @@ export declare class Element {
    * @param {string} action - The action to perform.
    */
   performAction(action: string): void
+  /** Trigger the element's default action without moving the mouse. */
+  invoke(): void
+  /** Move a check box or toggle button to its next state without clicking it. */
+  toggle(): void
+  /** Expand a combo box, menu item or tree item. */
+  expand(): void
+  /** Collapse a combo box, menu item or tree item. */
+  collapse(): void
+  /** Select the element in its list, combo box or tab strip without clicking it. */
+  select(): void
+  /**
+   * Start assertions on this element that retry until they hold, e.g.
+   * `element.expect().toHaveText('Saved')`.
+   *
+   * @param {number} [timeoutMs=5000] - How long to retry.
+   * @param {string} [screenshotDir] - Where failure screenshots go, the temp directory by default.
+   * @returns {Expect} The assertions.
+   */
+  expect(timeoutMs?: number | undefined | null, screenshotDir?: string | undefined | null): Expect
+  /**
+   * Text selected in an edit control or document.
+   *
+   * @returns {string} The selection, empty when nothing is selected.
+   */
+  selectedText(): string
+  /**
+   * Character offset of the caret in an edit control or document.
+   *
+   * @returns {number | null} The offset, or null when the element shows no caret.
+   */
+  caretPosition(): number | null
+  /**
+   * Select the characters from offset `start` up to `end`. `start == end`
+   * places the caret.
+   *
+   * @param {number} start - Offset of the first selected character.
+   * @param {number} end - Offset just past the last selected character.
+   */
+  selectRange(start: number, end: number): void
+  /**
+   * The text of an edit control or document as runs of the same font,
+   * weight, style and colors.
+   *
+   * @returns {Array<TextRun>} The runs, in document order.
+   */
+  textWithFormatting(): Array<TextRun>
+  /**
+   * Fill the controls below this element (a window or group) by label.
+   * Each label is matched to a control through the label it declares, its
+   * name, the text next to it, or a fuzzy match of its name, and set the
+   * way its type needs.
+   *
+   * @param {Record<string, string>} fields - Values by label, e.g. `{ 'First name': 'Ada', Newsletter: 'yes' }`.
+   * @returns {FormFillReport} Filled, unmatched and failed fields.
+   */
+  fillForm(fields: Record<string, string>): FormFillReport
+  /**
+   * Audit the accessibility of the elements below this element (usually a window):
+   * controls without a name, interactive controls the keyboard can't reach,
+   * duplicate automation IDs and, optionally, text with low contrast.
+   *
+   * @param {boolean} [checkContrast] - Screenshot the element and check text contrast. It must be visible on screen. Default: false
+   * @param {number} [maxDepth] - How deep below the element to look. Default: 30
+   * @returns {AuditReport} The findings.
+   */
+  audit(checkContrast?: boolean | undefined | null, maxDepth?: number | undefined | null): AuditReport
+  /**
+   * Audit the accessibility of the elements below this element, like `audit`,
+   * and render the findings as a self-contained HTML page.
+   *
+   * @param {boolean} [checkContrast] - Screenshot the element and check text contrast. Default: false
+   * @param {number} [maxDepth] - How deep below the element to look. Default: 30
+   * @returns {string} The HTML page.
+   */
+  auditHtml(checkContrast?: boolean | undefined | null, maxDepth?: number | undefined | null): string
   /**
    * Scroll the element in a given direction.
    *
@@ -334,6 +1151,13 @@ export declare class Element {
   scroll(direction: string, amount: number): void
   /** Activate the window containing this element. */
   activateWindow(): void
+  /**
+   * Check whether the control behind this handle no longer exists, e.g. a
+   * list row recreated by virtualization. Stale elements fail every action.
+   *
+   * @returns {boolean} True if the element is gone.
+   */
+  isStale(): boolean
   /**
    * Check if element is focused.
    *
@@ -410,12 +1234,86 @@ export declare class Element {
    * @returns {ScreenshotResult} The screenshot data containing image data and dimensions.
    */
   capture(): ScreenshotResult
+  /**
+   * Capture a screenshot of this element encoded as an image file.
+   *
+   * @param {string} format - "png", "jpeg", "jpeg:<quality>" or "webp".
+   * @returns {EncodedImage} The encoded screenshot.
+   */
+  captureAs(format: string): EncodedImage
   /**
    * Get the process ID of the application containing this element.
    *
    * @returns {number} The process ID.
    */
   processId(): number
+  /**
+   * Check whether the window containing this element is always on top.
+   *
+   * @returns {boolean} True if the window is topmost.
+   */
+  isTopmost(): boolean
+  /**
+   * Make the window containing this element always on top, or clear it.
+   *
+   * @param {boolean} topmost - Whether the window should stay on top.
+   */
+  setTopmost(topmost: boolean): void
+  /**
+   * Get the opacity of the window containing this element.
+   *
+   * @returns {number} Opacity between 0.0 (transparent) and 1.0 (opaque).
+   */
+  opacity(): number
+  /**
+   * Set the opacity of the window containing this element.
+   *
+   * @param {number} opacity - Opacity between 0.0 (transparent) and 1.0 (opaque).
+   */
+  setOpacity(opacity: number): void
+  /**
+   * Get the show state of the window containing this element.
+   *
+   * @returns {string} One of 'normal', 'minimized' or 'maximized'.
+   */
+  windowState(): string
+  /** Maximize the window containing this element. */
+  maximize(): void
+  /** Minimize the window containing this element. */
+  minimize(): void
+  /** Restore the window containing this element from the minimized or maximized state. */
+  restore(): void
+  /**
+   * Move and resize the window containing this element.
+   *
+   * @param {number} x - Left edge in screen coordinates.
+   * @param {number} y - Top edge in screen coordinates.
+   * @param {number} width - Window width.
+   * @param {number} height - Window height.
+   */
+  setBounds(x: number, y: number, width: number, height: number): void
+  /** Raise the window containing this element above all others and focus it. */
+  bringToFront(): void
+  /**
+   * Get the id of the virtual desktop the window containing this element is on.
+   *
+   * @returns {string} The desktop GUID.
+   */
+  virtualDesktop(): string
+  /**
+   * Check whether the window containing this element is on the virtual desktop shown.
+   * Elements on other desktops cannot be clicked.
+   *
+   * @returns {boolean} True if the window is on the current desktop.
+   */
+  isOnCurrentVirtualDesktop(): boolean
+  /**
+   * Move the window containing this element to another virtual desktop.
+   * Windows only allows this for windows of the calling process.
+   *
+   * @param {string} desktopId - Id from `Desktop.virtualDesktops()`.
+   */
+  moveToDesktop(desktopId: string): void
 }
 /** Locator for finding UI elements by selector. */
 export declare class Locator {
@@ -447,6 +1345,13 @@ export declare class Locator {
    * @returns {Locator} A new locator with the specified timeout.
    */
   timeout(timeoutMs: number): Locator
+  /**
+   * Configure how this locator polls while waiting for an element.
+   *
+   * @param {RetryPolicy} policy - Backoff, attempt limit and jitter settings.
+   * @returns {Locator} A new locator with the specified retry policy.
+   */
+  withRetry(policy: RetryPolicy): Locator
   /**
    * Set the root element for this locator.
    *
@@ -454,6 +1359,13 @@ export declare class Locator {
    * @returns {Locator} A new locator with the specified root element.
    */
   within(element: Element): Locator
+  /**
+   * Only match elements inside the visible region of a scrollable container.
+   *
+   * @param {Element} container - The scrollable container to search in.
+   * @returns {Locator} A new locator restricted to the container's viewport.
+   */
+  withinViewport(container: Element): Locator
   /**
    * Chain another selector.
    *
@@ -461,4 +1373,212 @@ export declare class Locator {
    * @returns {Locator} A new locator with the chained selector.
    */
   locator(selector: string): Locator
+  /**
+   * Only match the element at a position among the matches.
+   *
+   * @param {number} index - Zero-based position of the element.
+   * @returns {Locator} A new locator matching a single element.
+   */
+  nth(index: number): Locator
+  /**
+   * Only match the first visible element among the matches.
+   *
+   * @returns {Locator} A new locator matching the first visible element.
+   */
+  firstVisible(): Locator
+  /**
+   * (async) Count the matching elements.
+   *
+   * @param {number} [timeoutMs] - Timeout in milliseconds.
+   * @returns {Promise<number>} Number of matching elements, 0 if none.
+   */
+  count(timeoutMs?: number | undefined | null): Promise<number>
+}
+/** Collects failed checks across one step and throws them together. */
+export declare class SoftVerifier {
+  /**
+   * Create a verifier for a named step.
+   *
+   * @param {string} step - Name of the verified step, used in the failure report.
+   * @param {boolean} [captureArtifacts=false] - Capture a screenshot of the element for each failed element check.
+   */
+  constructor(step: string, captureArtifacts?: boolean | undefined | null)
+  /**
+   * Record a check on an arbitrary condition.
+   *
+   * @param {string} description - What is being checked.
+   * @param {boolean} condition - Whether the check passed.
+   * @param {string} [message] - Explanation reported when the check fails.
+   * @returns {boolean} The condition.
+   */
+  check(description: string, condition: boolean, message?: string | undefined | null): boolean
+  /** Check that the element is visible. */
+  expectVisible(element: Element): boolean
+  /** Check that the element is enabled. */
+  expectEnabled(element: Element): boolean
+  /** Check that the element's text contains the expected string. */
+  expectText(element: Element, expected: string): boolean
+  /** Check that the element's name equals the expected string. */
+  expectName(element: Element, expected: string): boolean
+  /** Check that the element's value equals the expected string. */
+  expectValue(element: Element, expected: string): boolean
+  /** Whether all checks so far passed. */
+  passed(): boolean
+  /**
+   * Get the report of all checks so far.
+   *
+   * @returns {VerificationReport} The step name, check count and failures.
+   */
+  report(): VerificationReport
+  /**
+   * Write report.json and failure screenshots into a directory.
+   *
+   * @param {string} dir - Target directory, created if missing.
+   * @returns {Array<string>} Paths of the written files.
+   */
+  writeBundle(dir: string): Array<string>
+  /** Throw a VerificationFailedError listing every failed check, if any. */
+  finish(): void
+}
+/** An active UI event subscription. */
+export declare class UiEventSubscription {
+  /** Stop delivering events to the callback. */
+  unsubscribe(): void
+  /** Whether events are still being delivered. */
+  get active(): boolean
+}
+/**
+ * Assertions on one element that retry until they hold or time out.
+ * Failures throw a VerificationFailedError with the last value seen, an
+ * excerpt of the element's subtree and the path of a screenshot.
+ */
+export declare class Expect {
+  /**
+   * Invert the assertions, e.g. `element.expect().not().toBeVisible()`.
+   *
+   * @returns {Expect} The inverted assertions.
+   */
+  not(): Expect
+  /** Wait until the element is visible. */
+  toBeVisible(): void
+  /** Wait until the element is enabled. */
+  toBeEnabled(): void
+  /** Wait until the element has keyboard focus. */
+  toBeFocused(): void
+  /** Wait until the element's text contains the expected string. */
+  toHaveText(expected: string): void
+  /** Wait until the element's value equals the expected string. */
+  toHaveValue(expected: string): void
+  /** Wait until the element's name equals the expected string. */
+  toHaveName(expected: string): void
+  /**
+   * Wait until an attribute equals the expected value.
+   *
+   * @param {string} name - Attribute name, e.g. 'value', 'enabled' or 'description'.
+   * @param {string} [expected] - Expected value; omitted, the attribute only needs a value.
+   */
+  toHaveAttribute(name: string, expected?: string | undefined | null): void
+}
+/** A notification on screen: a Windows toast or a macOS Notification Center banner. */
+export declare class Notification {
+  /** The app that raised the notification, where the platform shows it. */
+  get app(): string | null
+  get title(): string | null
+  get body(): string | null
+  /** Labels of the action buttons, e.g. ["Open", "Show in folder"]. */
+  get actions(): Array<string>
+  /**
+   * The element of the whole notification.
+   *
+   * @returns {Element} The notification element.
+   */
+  element(): Element
+  /** Click the notification itself, which usually opens the app that raised it. */
+  click(): void
+  /**
+   * Click one of the notification's action buttons.
+   *
+   * @param {string} action - Label of the button, ignoring case.
+   */
+  clickAction(action: string): void
+  /** Close the notification with its dismiss button. */
+  dismiss(): void
+}
+/** A registered system-wide hotkey. */
+/**
+ * A window tree that keeps the live element behind each node. Nodes are
+ * numbered depth-first, the window being 0, so a node found in the tree can
+ * be acted on without searching for it again.
+ */
+export declare class UINodeWithHandles {
+  /** The tree, as returned by getWindowTree. */
+  get tree(): UINode
+  /** Number of nodes in the tree. */
+  get length(): number
+  /**
+   * The element of a node.
+   *
+   * @param {number} index - Depth-first number of the node.
+   * @returns {Element | null} The element, or null past the last node.
+   */
+  element(index: number): Element | null
+  /**
+   * A node by its depth-first number.
+   *
+   * @param {number} index - Depth-first number of the node.
+   * @returns {UINode | null} The node, or null past the last node.
+   */
+  node(index: number): UINode | null
+  /**
+   * The element of the node reached by following child indices from the root.
+   *
+   * @param {number[]} indexPath - Child index at each level, [] for the window.
+   * @returns {Element | null} The element, or null if the path leads nowhere.
+   */
+  elementAt(indexPath: Array<number>): Element | null
+  /**
+   * Numbers of the nodes with the given role and name, depth-first.
+   *
+   * @param {string} [role] - Role to match, e.g. "Button".
+   * @param {string} [name] - Name to match exactly.
+   * @returns {number[]} Depth-first numbers of the matching nodes.
+   */
+  findAll(role?: string | undefined | null, name?: string | undefined | null): Array<number>
+}
+export declare class HotkeyRegistration {
+  /** Release the hotkey so other applications can use it again. */
+  unregister(): void
+  /** The registered key combination, e.g. "Ctrl+Shift+H". */
+  get hotkey(): string
+  /** Whether the hotkey is still registered. */
+  get active(): boolean
+}
+/** Records user input and UI events into a replayable workflow. Windows only. */
+export declare class WorkflowRecorder {
+  /**
+   * Create a recorder. Nothing is captured until `start()` is called.
+   *
+   * @param {WorkflowRecorderOptions} [options] - What to record.
+   */
+  constructor(options?: WorkflowRecorderOptions | undefined | null)
+  /**
+   * Install the input hooks and start recording.
+   *
+   * @returns {Promise<void>} Resolves once the hooks are installed.
+   */
+  start(): Promise<void>
+  /**
+   * Stop recording and return everything recorded.
+   *
+   * @returns {Promise<RecordedWorkflow>} The recorded workflow.
+   */
+  stop(): Promise<RecordedWorkflow>
+  /**
+   * Call a function with each event as it is recorded, in the same JSON form as
+   * `RecordedWorkflow.events`. Events are delivered on the JS thread without
+   * blocking the recorder. Register before `start()` to see every event.
+   *
+   * @param {function} callback - Called with each recorded event.
+   */
+  onEvent(callback: (event: any) => void): void
 }
@@ -0,0 +1,125 @@
+use napi_derive::napi;
+use std::time::Duration;
+use terminator::AppSession as TerminatorAppSession;
+
+use crate::{map_error, Element, Locator, ScreenshotResult, TreeBuildConfig, UINode, UiEventSubscription};
+
+/// Locators, trees, screenshots and events of one application, from
+/// desktop.attach(). The session follows the app when its process restarts.
+#[napi(js_name = "AppSession")]
+pub struct AppSession {
+    inner: TerminatorAppSession,
+}
+
+impl From<TerminatorAppSession> for AppSession {
+    fn from(session: TerminatorAppSession) -> Self {
+        AppSession { inner: session }
+    }
+}
+
+#[napi]
+impl AppSession {
+    /// Process ID of the attached process, attaching again if it restarted.
+    ///
+    /// @returns {number} The process ID.
+    #[napi]
+    pub fn pid(&self) -> napi::Result<u32> {
+        self.inner.pid().map_err(map_error)
+    }
+
+    /// Whether the attached process is still running.
+    ///
+    /// @returns {boolean} False once the process exited and no new one was found yet.
+    #[napi]
+    pub fn is_attached(&self) -> bool {
+        self.inner.is_attached()
+    }
+
+    /// The application element of the attached process.
+    ///
+    /// @returns {Element} The application element.
+    #[napi]
+    pub fn application(&self) -> napi::Result<Element> {
+        self.inner.application().map(Element::from).map_err(map_error)
+    }
+
+    /// Create a locator that searches the attached application only.
+    ///
+    /// @param {string} selector - The selector string.
+    /// @returns {Locator} A new locator for finding elements.
+    #[napi]
+    pub fn locator(&self, selector: String) -> napi::Result<Locator> {
+        self.inner
+            .locator(selector.as_str())
+            .map(Locator::from)
+            .map_err(map_error)
+    }
+
+    /// Get the UI tree of the application's window.
+    ///
+    /// @param {TreeBuildConfig} [config] - Optional configuration for tree building.
+    /// @returns {UINode} Complete UI tree starting from the window.
+    #[napi]
+    pub fn get_window_tree(&self, config: Option<TreeBuildConfig>) -> napi::Result<UINode> {
+        self.inner
+            .get_window_tree(config.map(|c| c.into()))
+            .map(UINode::from)
+            .map_err(map_error)
+    }
+
+    /// Capture a screenshot of the application's window.
+    ///
+    /// @returns {ScreenshotResult} The screenshot data.
+    #[napi]
+    pub fn capture(&self) -> napi::Result<ScreenshotResult> {
+        self.inner
+            .capture()
+            .map(|result| ScreenshotResult {
+                image_data: result.image_data,
+                width: result.width,
+                height: result.height,
+            })
+            .map_err(map_error)
+    }
+
+    /// Call a function for UI events raised by the attached process, following
+    /// it across restarts. Windows only.
+    ///
+    /// @param {Array<string>} kinds - Any of 'focusChanged', 'structureChanged', 'propertyChanged'.
+    /// @param {function} callback - Called with each event.
+    /// @returns {UiEventSubscription} Call `unsubscribe()` to stop receiving events.
+    #[napi(ts_args_type = "kinds: Array<string>, callback: (event: { kind: string, element?: Element, detail?: string, value?: string }) => void")]
+    pub fn subscribe_ui_events(&self, kinds: Vec<String>, callback: napi::JsFunction) -> napi::Result<UiEventSubscription> {
+        let kinds = crate::events::parse_kinds(&kinds)?;
+        let forward = crate::events::js_event_forwarder(callback)?;
+        self.inner
+            .on_ui_event(&kinds, forward)
+            .map(UiEventSubscription::from)
+            .map_err(map_error)
+    }
+
+    /// Call a function when the session attaches to a process, at first or after
+    /// a restart, and when the attached process exits.
+    ///
+    /// @param {function} callback - Called with `{ kind: 'attached' | 'detached', pid }`.
+    #[napi(ts_args_type = "callback: (event: { kind: 'attached' | 'detached', pid: number }) => void")]
+    pub fn on_lifecycle(&self, callback: napi::JsFunction) -> napi::Result<()> {
+        let forward = crate::events::js_lifecycle_forwarder(callback)?;
+        self.inner.on_lifecycle(forward);
+        Ok(())
+    }
+
+    /// (async) Wait until the application runs again after it exited.
+    /// Resolves at once while attached.
+    ///
+    /// @param {number} [timeoutMs] - Timeout in milliseconds (default 30000).
+    /// @returns {Promise<Element>} The application element of the new process.
+    #[napi]
+    pub async fn wait_for_attach(&self, timeout_ms: Option<f64>) -> napi::Result<Element> {
+        self.inner
+            .wait_for_attach(timeout_ms.map(|ms| Duration::from_millis(ms as u64)))
+            .await
+            .map(Element::from)
+            .map_err(map_error)
+    }
+}
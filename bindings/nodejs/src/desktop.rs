@@ -1,14 +1,31 @@
+use napi::bindgen_prelude::ClassInstance;
 use napi_derive::napi;
 use std::sync::Once;
 use terminator::Desktop as TerminatorDesktop;
 
 use crate::{
+    AppHandle,
+    AppSession,
     Element,
     Locator,
+    Notification,
     ScreenshotResult,
+    EncodedImage,
     CommandOutput,
     UINode,
     TreeBuildConfig,
+    TreeBuildResult,
+    UINodeWithHandles,
+    WindowTree,
+    ConsoleScreen,
+    MonitorInfo,
+    EnvironmentInfo,
+    VirtualDesktop,
+    AutomationCapabilities,
+    BatchOperation,
+    BatchResult,
+    UiEventSubscription,
+    HotkeyRegistration,
     map_error,
 };
 
@@ -83,6 +100,46 @@ impl Desktop {
             .map_err(map_error)
     }
 
+    /// Pin locators, trees, screenshots and events to one application. The
+    /// session follows the app when its process restarts.
+    /// 
+    /// @param {string | number} target - Application name, or process ID.
+    /// @returns {AppSession} The session, attached to the running app.
+    #[napi]
+    pub fn attach(&self, target: napi::Either<u32, String>) -> napi::Result<AppSession> {
+        let target = match target {
+            napi::Either::A(pid) => terminator::AppTarget::Pid(pid),
+            napi::Either::B(name) => terminator::AppTarget::from(name),
+        };
+        self.inner.attach(target)
+            .map(AppSession::from)
+            .map_err(map_error)
+    }
+
+    /// Start an executable and keep a handle to its process.
+    /// 
+    /// @param {string} path - Path or name of the executable.
+    /// @param {string[]} [args] - Command line arguments.
+    /// @param {Record<string, string>} [env] - Variables added to the inherited environment.
+    /// @param {string} [cwd] - Working directory.
+    /// @returns {AppHandle} A handle to wait for the window or exit, or to kill the process.
+    #[napi]
+    pub fn launch(
+        &self,
+        path: String,
+        args: Option<Vec<String>>,
+        env: Option<std::collections::HashMap<String, String>>,
+        cwd: Option<String>,
+    ) -> napi::Result<AppHandle> {
+        let args = args.unwrap_or_default();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let env = env.unwrap_or_default();
+        let env: Vec<(&str, &str)> = env.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        self.inner.launch(&path, &args, &env, cwd.as_deref())
+            .map(AppHandle::from)
+            .map_err(map_error)
+    }
+
     /// Activate an application by name.
     /// 
     /// @param {string} name - The name of the application to activate.
@@ -106,6 +163,18 @@ impl Desktop {
             .map_err(map_error)
     }
 
+    /// (async) Capture the primary monitor encoded as an image file.
+    /// 
+    /// @param {string} format - "png", "jpeg", "jpeg:<quality>" or "webp".
+    /// @returns {Promise<EncodedImage>} The encoded screenshot.
+    #[napi]
+    pub async fn capture_screen_as(&self, format: String) -> napi::Result<EncodedImage> {
+        let format = crate::types::parse_image_format(&format)?;
+        self.inner.capture_screen_as(format).await
+            .map(EncodedImage::from)
+            .map_err(map_error)
+    }
+
     /// (async) Run a shell command.
     /// 
     /// @param {string} [windowsCommand] - Command to run on Windows.
@@ -131,6 +200,65 @@ impl Desktop {
             .map_err(map_error)
     }
 
+    /// List the connected monitors.
+    /// 
+    /// @returns {Array<MonitorInfo>} Name, bounds, scale factor, refresh rate and primary flag of each monitor.
+    #[napi]
+    pub fn monitors(&self) -> napi::Result<Vec<MonitorInfo>> {
+        self.inner.monitors()
+            .map(|monitors| monitors.into_iter().map(MonitorInfo::from).collect())
+            .map_err(map_error)
+    }
+
+    /// Highlight several elements at once, e.g. everything a locator matched.
+    /// 
+    /// @param {Array<Element>} elements - Elements to draw a border around.
+    /// @param {number} [color] - Optional BGR color code (32-bit integer). Default: 0x0000FF (red)
+    /// @param {number} [durationMs] - Optional duration in milliseconds. Default: 500
+    /// @returns {void}
+    #[napi]
+    pub fn highlight_all(
+        &self,
+        elements: Vec<ClassInstance<Element>>,
+        color: Option<u32>,
+        duration_ms: Option<f64>,
+    ) -> napi::Result<()> {
+        let elements: Vec<_> = elements.iter().map(|e| e.inner.clone()).collect();
+        let duration = duration_ms.map(|ms| std::time::Duration::from_millis(ms as u64));
+        self.inner.highlight_all(&elements, color, duration).map_err(map_error)
+    }
+
+    /// Get the OS version, theme, animation, scaling and screen reader settings.
+    /// Attach this to bug reports when automation behaves differently between machines.
+    /// 
+    /// @returns {EnvironmentInfo} The environment; settings that cannot be read are null.
+    #[napi]
+    pub fn environment(&self) -> napi::Result<EnvironmentInfo> {
+        self.inner.environment()
+            .map(EnvironmentInfo::from)
+            .map_err(map_error)
+    }
+
+    /// Report what this process can automate: its integrity level, whether it runs as
+    /// administrator and whether it has UIAccess. Without elevation or UIAccess, actions on
+    /// applications running as administrator throw an ElevationRequiredError.
+    /// 
+    /// @returns {AutomationCapabilities} The capabilities of this process.
+    #[napi]
+    pub fn capabilities(&self) -> AutomationCapabilities {
+        AutomationCapabilities::from(self.inner.capabilities())
+    }
+
+    /// List the virtual desktops in Task View order (Windows only).
+    /// 
+    /// @returns {Array<VirtualDesktop>} The desktops; the one shown has isCurrent set.
+    #[napi]
+    pub fn virtual_desktops(&self) -> napi::Result<Vec<VirtualDesktop>> {
+        self.inner.virtual_desktops()
+            .map(|desktops| desktops.into_iter().map(VirtualDesktop::from).collect())
+            .map_err(map_error)
+    }
+
     /// (async) Capture a screenshot of a specific monitor.
     /// 
     /// @param {string} name - The name of the monitor to capture.
@@ -146,6 +274,19 @@ impl Desktop {
             .map_err(map_error)
     }
 
+    /// (async) Capture a specific monitor encoded as an image file.
+    /// 
+    /// @param {string} name - The name of the monitor to capture.
+    /// @param {string} format - "png", "jpeg", "jpeg:<quality>" or "webp".
+    /// @returns {Promise<EncodedImage>} The encoded screenshot.
+    #[napi]
+    pub async fn capture_monitor_by_name_as(&self, name: String, format: String) -> napi::Result<EncodedImage> {
+        let format = crate::types::parse_image_format(&format)?;
+        self.inner.capture_monitor_by_name_as(&name, format).await
+            .map(EncodedImage::from)
+            .map_err(map_error)
+    }
+
     /// (async) Perform OCR on an image file.
     /// 
     /// @param {string} imagePath - Path to the image file.
@@ -192,6 +333,38 @@ impl Desktop {
         Ok(Locator::from(loc))
     }
 
+    /// Create a locator for one logical target that tries several selectors in order.
+    /// With the selector cache enabled, the selector that resolved the target is
+    /// remembered per application version and tried first next time.
+    ///
+    /// @param {string} target - Name of what is being looked for, e.g. "save button".
+    /// @param {string[]} selectors - Candidate selectors, most specific first.
+    /// @returns {Locator} A locator for finding elements.
+    #[napi]
+    pub fn locator_any(&self, target: String, selectors: Vec<String>) -> napi::Result<Locator> {
+        let selectors: Vec<&str> = selectors.iter().map(String::as_str).collect();
+        Ok(Locator::from(self.inner.locator_any(&target, &selectors)))
+    }
+
+    /// Remember which selector resolved each target of locatorAny.
+    ///
+    /// @param {string} [path] - JSON file to persist learned selectors in. Kept in memory if omitted.
+    #[napi]
+    pub fn enable_selector_cache(&self, path: Option<String>) -> napi::Result<()> {
+        let cache = match path {
+            Some(path) => terminator::SelectorCache::open(path).map_err(map_error)?,
+            None => terminator::SelectorCache::in_memory(),
+        };
+        self.inner.set_selector_cache(Some(std::sync::Arc::new(cache)));
+        Ok(())
+    }
+
+    /// Stop learning and preferring selectors for locatorAny.
+    #[napi]
+    pub fn disable_selector_cache(&self) {
+        self.inner.set_selector_cache(None)
+    }
+
     /// (async) Get the currently focused window.
     /// 
     /// @returns {Promise<Element>} The current window element.
@@ -222,13 +395,29 @@ impl Desktop {
             .map_err(map_error)
     }
 
-    /// Open a URL in a browser.
+    /// Open a URL in a browser. Returns as soon as the browser is launched;
+    /// use `openUrlAndWait` to get the loaded page.
     /// 
     /// @param {string} url - The URL to open.
     /// @param {string} [browser] - The browser to use.
+    /// @returns {Element} The browser window.
     #[napi]
-    pub fn open_url(&self, url: String, browser: Option<String>) -> napi::Result<()> {
+    pub fn open_url(&self, url: String, browser: Option<String>) -> napi::Result<Element> {
         self.inner.open_url(&url, browser.as_deref())
+            .map(Element::from)
+            .map_err(map_error)
+    }
+
+    /// (async) Open a URL and wait until the browser shows the page.
+    ///
+    /// @param {string} url - The URL to open.
+    /// @param {string} [browser] - The browser to use.
+    /// @param {number} [timeoutMs] - How long to wait in milliseconds. Defaults to 30 seconds.
+    /// @returns {Promise<Element>} The page's document element, ready for chaining locators.
+    #[napi]
+    pub async fn open_url_and_wait(&self, url: String, browser: Option<String>, timeout_ms: Option<f64>) -> napi::Result<Element> {
+        self.inner.open_url_and_wait(&url, browser.as_deref(), timeout_ms.map(|ms| std::time::Duration::from_millis(ms as u64))).await
+            .map(Element::from)
             .map_err(map_error)
     }
 
@@ -263,4 +452,262 @@ impl Desktop {
             .map(UINode::from)
             .map_err(map_error)
     }
+
+    /// Get the UI tree for a window, with how it was built: element count,
+    /// elements served from cache requests and duration.
+    /// 
+    /// @param {number} pid - Process ID of the target application.
+    /// @param {string} [title] - Optional window title filter.
+    /// @param {TreeBuildConfig} [config] - Optional configuration for tree building.
+    /// @returns {TreeBuildResult} The tree and its build statistics.
+    #[napi]
+    pub fn get_window_tree_with_stats(&self, pid: u32, title: Option<String>, config: Option<TreeBuildConfig>) -> napi::Result<TreeBuildResult> {
+        let rust_config = config.map(|c| c.into());
+        self.inner.get_window_tree_with_stats(pid, title.as_deref(), rust_config)
+            .map(|(tree, stats)| TreeBuildResult { tree: UINode::from(tree), stats: stats.into() })
+            .map_err(map_error)
+    }
+
+    /// Get the UI tree for a window, keeping the live element behind each node
+    /// so a node found in the tree can be acted on without another search.
+    /// 
+    /// @param {number} pid - Process ID of the target application.
+    /// @param {string} [title] - Optional window title filter.
+    /// @param {TreeBuildConfig} [config] - Optional configuration for tree building.
+    /// @returns {UINodeWithHandles} The tree with an element for each node.
+    #[napi]
+    pub fn get_window_tree_with_handles(&self, pid: u32, title: Option<String>, config: Option<TreeBuildConfig>) -> napi::Result<UINodeWithHandles> {
+        let rust_config = config.map(|c| c.into());
+        self.inner.get_window_tree_with_handles(pid, title.as_deref(), rust_config)
+            .map(UINodeWithHandles::from)
+            .map_err(map_error)
+    }
+
+    /// Get the UI trees of all visible top-level windows, built concurrently.
+    /// 
+    /// @param {TreeBuildConfig} [config] - Optional configuration for tree building.
+    /// @returns {Array<WindowTree>} Each window with its UI tree, in z-order.
+    #[napi]
+    pub fn get_all_window_trees(&self, config: Option<TreeBuildConfig>) -> napi::Result<Vec<WindowTree>> {
+        let rust_config = config.map(|c| c.into());
+        self.inner.get_all_window_trees(rust_config)
+            .map(|trees| trees.into_iter().map(WindowTree::from).collect())
+            .map_err(map_error)
+    }
+
+    /// Get the state of the desktop session this process runs in.
+    ///
+    /// @returns {string} One of 'active', 'locked', 'disconnected', 'service' or 'unknown'.
+    #[napi]
+    pub fn session_state(&self) -> napi::Result<String> {
+        self.inner.session_state()
+            .map(|state| state.to_string())
+            .map_err(map_error)
+    }
+
+    /// Throw a UIUnavailableError if the session is locked, disconnected or non-interactive.
+    #[napi]
+    pub fn ensure_ui_available(&self) -> napi::Result<()> {
+        self.inner.ensure_ui_available()
+            .map_err(map_error)
+    }
+
+    /// Reconnect the current remote session to the console so it stays interactive
+    /// after the RDP client disconnects. Windows only, requires administrator rights.
+    #[napi]
+    pub fn attach_to_console(&self) -> napi::Result<()> {
+        self.inner.attach_to_console()
+            .map_err(map_error)
+    }
+
+    /// Read the visible screen of the console attached to a process (e.g. cmd.exe). Windows only.
+    ///
+    /// @param {number} pid - Process id of the console application.
+    /// @returns {ConsoleScreen} The visible lines and cursor position.
+    #[napi]
+    pub fn read_console(&self, pid: u32) -> napi::Result<ConsoleScreen> {
+        self.inner.read_console(pid)
+            .map(ConsoleScreen::from)
+            .map_err(map_error)
+    }
+
+    /// Type text into the console attached to a process. Newlines are sent as Enter. Windows only.
+    ///
+    /// @param {number} pid - Process id of the console application.
+    /// @param {string} text - The text to type.
+    #[napi]
+    pub fn send_console_input(&self, pid: u32, text: String) -> napi::Result<()> {
+        self.inner.send_console_input(pid, &text)
+            .map_err(map_error)
+    }
+
+    /// (async) Run several element operations in one call, resolving each selector once.
+    ///
+    /// @param {Array<BatchOperation>} ops - Operations such as `{ op: 'click', selector: 'name:OK' }`.
+    /// @param {number} [timeoutMs] - How long each selector may take to resolve.
+    /// @param {boolean} [stopOnError] - Skip the remaining operations after one fails. Default: true.
+    /// @returns {Promise<Array<BatchResult>>} One result per operation.
+    #[napi]
+    pub async fn batch(
+        &self,
+        ops: Vec<BatchOperation>,
+        timeout_ms: Option<f64>,
+        stop_on_error: Option<bool>,
+    ) -> napi::Result<Vec<BatchResult>> {
+        let mut batch = self.inner.batch().stop_on_error(stop_on_error.unwrap_or(true));
+        if let Some(timeout_ms) = timeout_ms {
+            batch = batch.timeout(std::time::Duration::from_millis(timeout_ms as u64));
+        }
+        for op in ops {
+            batch = batch.push(op.try_into()?);
+        }
+        Ok(batch.run().await.into_iter().map(BatchResult::from).collect())
+    }
+
+    /// (async) Wait until text appears on the screen of the console attached to a process.
+    ///
+    /// @param {number} pid - Process id of the console application.
+    /// @param {string} text - The text to wait for.
+    /// @param {number} timeoutMs - How long to wait in milliseconds.
+    /// @returns {Promise<ConsoleScreen>} The screen that contained the text.
+    #[napi]
+    pub async fn wait_for_console_text(&self, pid: u32, text: String, timeout_ms: f64) -> napi::Result<ConsoleScreen> {
+        self.inner.wait_for_console_text(pid, &text, std::time::Duration::from_millis(timeout_ms as u64)).await
+            .map(ConsoleScreen::from)
+            .map_err(map_error)
+    }
+
+    /// (async) Wait until an application is idle: started up, its UI thread responsive,
+    /// and no elements added or removed for a quiet period. Windows only.
+    ///
+    /// @param {number} pid - Process id of the application.
+    /// @param {number} timeoutMs - How long to wait in milliseconds.
+    /// @param {number} [quietMs] - Quiet period without UI structure changes in milliseconds. Default: 500.
+    /// @returns {Promise<void>} Resolves once the application is idle.
+    #[napi]
+    pub async fn wait_for_idle(&self, pid: u32, timeout_ms: f64, quiet_ms: Option<f64>) -> napi::Result<()> {
+        let quiet = quiet_ms
+            .map(|ms| std::time::Duration::from_millis(ms as u64))
+            .unwrap_or(terminator::DEFAULT_IDLE_QUIET_PERIOD);
+        self.inner.wait_for_idle_with(pid, std::time::Duration::from_millis(timeout_ms as u64), quiet).await
+            .map_err(map_error)
+    }
+
+    /// Call a function for every UI event of the given kinds. Events are raised on a
+    /// background watcher thread and delivered on the JS thread. Prefer
+    /// `desktop.on('focusChanged', cb)`, which manages subscriptions for you. Windows only.
+    ///
+    /// @param {Array<string>} kinds - Any of 'focusChanged', 'structureChanged', 'propertyChanged'.
+    /// @param {function} callback - Called with each event.
+    /// @returns {UiEventSubscription} Call `unsubscribe()` to stop receiving events.
+    #[napi(ts_args_type = "kinds: Array<string>, callback: (event: { kind: string, element?: Element, detail?: string, value?: string }) => void")]
+    pub fn subscribe_ui_events(&self, kinds: Vec<String>, callback: napi::JsFunction) -> napi::Result<UiEventSubscription> {
+        let kinds = crate::events::parse_kinds(&kinds)?;
+        let forward = crate::events::js_event_forwarder(callback)?;
+        self.inner.on_ui_event(&kinds, forward)
+            .map(UiEventSubscription::from)
+            .map_err(map_error)
+    }
+
+    /// Call a function each time a system-wide hotkey is pressed. Presses are detected on
+    /// a background dispatcher thread and delivered on the JS thread. Fails if another
+    /// application already owns the combination. Windows only.
+    ///
+    /// @param {string} hotkey - Modifiers and a key, e.g. "Ctrl+Shift+H" or "Alt+F9".
+    /// @param {function} callback - Called on each press.
+    /// @returns {HotkeyRegistration} Call `unregister()` to release the hotkey.
+    #[napi(ts_args_type = "hotkey: string, callback: () => void")]
+    pub fn register_hotkey(&self, hotkey: String, callback: napi::JsFunction) -> napi::Result<HotkeyRegistration> {
+        let forward = crate::events::js_hotkey_forwarder(callback)?;
+        self.inner.register_hotkey(&hotkey, forward)
+            .map(HotkeyRegistration::from)
+            .map_err(map_error)
+    }
+
+    /// List the notifications on screen: Windows toasts or macOS Notification Center banners.
+    ///
+    /// @returns {Array<Notification>} The notifications, with their app, title, body and actions.
+    #[napi]
+    pub fn notifications(&self) -> napi::Result<Vec<Notification>> {
+        self.inner.notifications()
+            .map(|notifications| notifications.into_iter().map(Notification::from).collect())
+            .map_err(map_error)
+    }
+
+    /// Call a function for each notification that appears. Notifications already on screen
+    /// are not reported. New ones are found by polling on a background thread and delivered
+    /// on the JS thread.
+    ///
+    /// @param {function} callback - Called with each new Notification.
+    /// @returns {UiEventSubscription} Call `unsubscribe()` to stop receiving notifications.
+    #[napi(ts_args_type = "callback: (notification: Notification) => void")]
+    pub fn subscribe_notifications(&self, callback: napi::JsFunction) -> napi::Result<UiEventSubscription> {
+        let forward = crate::events::js_notification_forwarder(callback)?;
+        self.inner.subscribe_notifications(forward)
+            .map(UiEventSubscription::from)
+            .map_err(map_error)
+    }
+
+    /// (async) Wait until a notification whose app name, title or body contains text is on screen.
+    ///
+    /// @param {string} text - The text to wait for, ignoring case.
+    /// @param {number} timeoutMs - How long to wait in milliseconds.
+    /// @returns {Promise<Notification>} The first matching notification.
+    #[napi]
+    pub async fn wait_for_notification(&self, text: String, timeout_ms: f64) -> napi::Result<Notification> {
+        self.inner.wait_for_notification(&text, std::time::Duration::from_millis(timeout_ms as u64)).await
+            .map(Notification::from)
+            .map_err(map_error)
+    }
+
+    /// Abort the run with a StalledRunError if no action completes successfully for the given time.
+    /// A screenshot, the foreground window and the active application's tree are captured
+    /// when the watchdog fires. Calling this again replaces the previous watchdog.
+    ///
+    /// @param {number} timeoutMs - Idle time in milliseconds before the run is considered stalled.
+    #[napi]
+    pub fn idle_timeout(&self, timeout_ms: f64) {
+        self.inner.idle_timeout(std::time::Duration::from_millis(timeout_ms as u64), None)
+    }
+
+    /// Disarm the idle watchdog, if one is running.
+    #[napi]
+    pub fn clear_idle_timeout(&self) {
+        self.inner.clear_idle_timeout()
+    }
+
+    /// Block physical keyboard and mouse input until called again with false.
+    /// Input sent by terminator still gets through, and Ctrl+Alt+Del always
+    /// releases the block. Windows only.
+    ///
+    /// @param {boolean} enabled - Whether to block input.
+    #[napi]
+    pub fn block_user_input(&self, enabled: bool) -> napi::Result<()> {
+        self.inner.block_user_input(enabled).map_err(map_error)
+    }
+
+    /// Block physical input while each element action runs. Windows only.
+    ///
+    /// @param {boolean} enabled - Whether to guard every action.
+    #[napi]
+    pub fn set_auto_input_guard(&self, enabled: bool) {
+        self.inner.set_auto_input_guard(enabled)
+    }
+
+    /// Pace synthetic keyboard and mouse input. Some applications drop keys
+    /// typed instantly or only react to a pointer that hovered first.
+    ///
+    /// @param {string} profile - `instant`, `fast` (the default) or `human` for
+    /// irregular typing, curved pointer movement and a pause before clicks.
+    #[napi]
+    pub fn set_input_profile(&self, profile: String) -> napi::Result<()> {
+        let profile = terminator::InputProfile::named(&profile).ok_or_else(|| {
+            napi::Error::new(
+                napi::Status::InvalidArg,
+                format!("Unknown input profile '{}'. Expected instant, fast or human", profile),
+            )
+        })?;
+        self.inner.set_input_profile(profile);
+        Ok(())
+    }
 } 
\ No newline at end of file
@@ -6,11 +6,17 @@ use terminator::{UIElement as TerminatorUIElement, UIElementAttributes as Termin
 use crate::{
     Bounds,
     ClickResult,
+    Coordinates,
     UIElementAttributes,
     Locator,
+    MonitorInfo,
+    ExpectedCondition,
+    VerifiedActionResult,
     map_error,
-    types::{ExploreResponse, ExploredElementDetail},
+    types::{AuditReport, ExploreResponse, ExploredElementDetail, FormFillReport, TextRun},
     ScreenshotResult,
+    EncodedImage,
+    Expect,
 };
 
 /// A UI element in the accessibility tree.
@@ -101,6 +107,48 @@ impl Element {
             .map_err(map_error)
     }
 
+    /// Get the parent, its parent and so on up to the root, nearest first.
+    /// 
+    /// @returns {Array<Element>} The ancestor elements.
+    #[napi]
+    pub fn ancestors(&self) -> napi::Result<Vec<Element>> {
+        self.inner.ancestors()
+            .map(|ancestors| ancestors.into_iter().map(Element::from).collect())
+            .map_err(map_error)
+    }
+
+    /// Get the element after this one among its parent's children.
+    /// 
+    /// @returns {Element | null} The next sibling, or null for the last child.
+    #[napi]
+    pub fn next_sibling(&self) -> napi::Result<Option<Element>> {
+        self.inner.next_sibling()
+            .map(|opt| opt.map(Element::from))
+            .map_err(map_error)
+    }
+
+    /// Get the element before this one among its parent's children.
+    /// 
+    /// @returns {Element | null} The previous sibling, or null for the first child.
+    #[napi]
+    pub fn previous_sibling(&self) -> napi::Result<Option<Element>> {
+        self.inner.previous_sibling()
+            .map(|opt| opt.map(Element::from))
+            .map_err(map_error)
+    }
+
+    /// Get the nearest of this element and its ancestors matching a selector,
+    /// e.g. the dialog a button is in with `role:Window`.
+    /// 
+    /// @param {string} selector - A role, id, name, text, nativeid or classname selector, or a not/and/or combination of them.
+    /// @returns {Element | null} The matching element, if any.
+    #[napi]
+    pub fn closest(&self, selector: String) -> napi::Result<Option<Element>> {
+        self.inner.closest(selector.as_str())
+            .map(|opt| opt.map(Element::from))
+            .map_err(map_error)
+    }
+
     /// Get element bounds.
     /// 
     /// @returns {Bounds} The element's bounds (x, y, width, height).
@@ -111,6 +159,62 @@ impl Element {
             .map_err(map_error)
     }
 
+    /// Get the monitor this element is displayed on.
+    /// 
+    /// @returns {MonitorInfo | null} The monitor, or null if the element is off-screen.
+    #[napi]
+    pub fn monitor(&self) -> napi::Result<Option<MonitorInfo>> {
+        self.inner.monitor()
+            .map(|monitor| monitor.map(MonitorInfo::from))
+            .map_err(map_error)
+    }
+
+    /// Get the center of the element in physical screen pixels.
+    /// 
+    /// @returns {Coordinates} The element's center point.
+    #[napi]
+    pub fn center(&self) -> napi::Result<Coordinates> {
+        self.inner.center()
+            .map(|p| Coordinates { x: p.x, y: p.y })
+            .map_err(map_error)
+    }
+
+    /// Get a point relative to the element, e.g. 10px left of its center.
+    /// 
+    /// @param {string} anchor - One of 'center', 'top_left', 'top', 'top_right', 'left', 'right', 'bottom_left', 'bottom', 'bottom_right', or a percentage like '30%,50%'.
+    /// @param {number} [offsetX=0] - Horizontal offset in pixels.
+    /// @param {number} [offsetY=0] - Vertical offset in pixels.
+    /// @returns {Coordinates} The resulting screen point.
+    #[napi]
+    pub fn point_at(&self, anchor: String, offset_x: Option<f64>, offset_y: Option<f64>) -> napi::Result<Coordinates> {
+        let anchor: terminator::Anchor = anchor.parse()
+            .map_err(|e: String| map_error(terminator::AutomationError::InvalidArgument(e)))?;
+        self.inner.point_at(anchor, (offset_x.unwrap_or(0.0), offset_y.unwrap_or(0.0)))
+            .map(|p| Coordinates { x: p.x, y: p.y })
+            .map_err(map_error)
+    }
+
+    /// Check whether this element's bounds overlap another element's bounds.
+    /// 
+    /// @param {Element} other - The element to test against.
+    /// @returns {boolean} True if the bounds intersect.
+    #[napi]
+    pub fn intersects(&self, other: &Element) -> napi::Result<bool> {
+        self.inner.intersects(&other.inner)
+            .map_err(map_error)
+    }
+
+    /// Check whether a screen point lies inside this element.
+    /// 
+    /// @param {number} x - X coordinate in pixels.
+    /// @param {number} y - Y coordinate in pixels.
+    /// @returns {boolean} True if the point is inside the element's bounds.
+    #[napi]
+    pub fn contains_point(&self, x: f64, y: f64) -> napi::Result<bool> {
+        self.inner.contains_point(x, y)
+            .map_err(map_error)
+    }
+
     /// Click on this element.
     /// 
     /// @returns {ClickResult} Result of the click operation.
@@ -121,6 +225,81 @@ impl Element {
             .map_err(map_error)
     }
 
+    /// Click on this element delivering the click one way only.
+    /// 
+    /// @param {string} strategy - 'uia_pattern' (the accessibility action), 'send_input_cursor' (real cursor input) or 'post_message' (window messages, Windows only).
+    /// @returns {ClickResult} Result of the click operation.
+    #[napi]
+    pub fn click_with_strategy(&self, strategy: String) -> napi::Result<ClickResult> {
+        let strategy = strategy.parse().map_err(map_error)?;
+        self.inner.click_with_strategy(strategy)
+            .map(ClickResult::from)
+            .map_err(map_error)
+    }
+
+    /// Click on this element trying strategies in order until one delivers the click.
+    /// 
+    /// @param {string[]} [strategies] - Strategies to try, by default 'uia_pattern', 'send_input_cursor' then 'post_message'.
+    /// @returns {ClickResult} Result of the click, with the strategy that worked.
+    #[napi]
+    pub fn click_escalating(&self, strategies: Option<Vec<String>>) -> napi::Result<ClickResult> {
+        let strategies = match strategies {
+            Some(names) => names
+                .iter()
+                .map(|name| name.parse())
+                .collect::<Result<Vec<terminator::ActionStrategy>, _>>()
+                .map_err(map_error)?,
+            None => terminator::ActionStrategy::ESCALATION.to_vec(),
+        };
+        self.inner.click_escalating(&strategies)
+            .map(ClickResult::from)
+            .map_err(map_error)
+    }
+
+    /// Click at an anchor point of this element instead of its center.
+    /// The point is kept inside the element before the offset is applied.
+    /// 
+    /// @param {string} anchor - One of 'center', 'top_left', 'top', 'top_right', 'left', 'right', 'bottom_left', 'bottom', 'bottom_right', or a percentage like '30%,50%'.
+    /// @param {number} [offsetX=0] - Horizontal offset in pixels.
+    /// @param {number} [offsetY=0] - Vertical offset in pixels.
+    /// @returns {ClickResult} Result of the click, with the clicked point in `coordinates`.
+    #[napi]
+    pub fn click_at_anchor(&self, anchor: String, offset_x: Option<f64>, offset_y: Option<f64>) -> napi::Result<ClickResult> {
+        let anchor: terminator::Anchor = anchor.parse()
+            .map_err(|e: String| map_error(terminator::AutomationError::InvalidArgument(e)))?;
+        self.inner.click_at_anchor(anchor, (offset_x.unwrap_or(0.0), offset_y.unwrap_or(0.0)))
+            .map(ClickResult::from)
+            .map_err(map_error)
+    }
+
+    /// Click this element and wait for the expected effect, retrying with a
+    /// physical mouse click if the first click had no effect.
+    /// 
+    /// @param {ExpectedCondition} condition - The effect to wait for, e.g. { kind: 'gone' }.
+    /// @param {number} [timeoutMs=2000] - How long to wait for the effect after each attempt.
+    /// @returns {VerifiedActionResult} Which attempt and method produced the effect.
+    #[napi]
+    pub fn click_and_expect(&self, condition: ExpectedCondition, timeout_ms: Option<f64>) -> napi::Result<VerifiedActionResult> {
+        let condition = terminator::Condition::from_kind(&condition.kind, condition.value)
+            .map_err(|e| map_error(terminator::AutomationError::InvalidArgument(e)))?;
+        let timeout = std::time::Duration::from_millis(timeout_ms.unwrap_or(2000.0) as u64);
+        self.inner.click_and_expect(&condition, timeout)
+            .map(VerifiedActionResult::from)
+            .map_err(map_error)
+    }
+
+    /// Replace this element's value with text and check that it was taken,
+    /// retrying by pasting and then through the value pattern.
+    /// 
+    /// @param {string} text - The text to enter.
+    /// @returns {VerifiedActionResult} Which attempt and method set the value.
+    #[napi]
+    pub fn type_text_verified(&self, text: String) -> napi::Result<VerifiedActionResult> {
+        self.inner.type_text_verified(&text)
+            .map(VerifiedActionResult::from)
+            .map_err(map_error)
+    }
+
     /// Double click on this element.
     /// 
     /// @returns {ClickResult} Result of the click operation.
@@ -178,9 +357,15 @@ impl Element {
     /// 
     /// @param {string} text - The text to type.
     /// @param {boolean} [useClipboard] - Whether to use clipboard for pasting.
-    #[napi]
-    pub fn type_text(&self, text: String, use_clipboard: Option<bool>) -> napi::Result<()> {
-        self.inner.type_text(&text, use_clipboard.unwrap_or(false)).map_err(map_error)
+    /// @param {boolean} [waitForEditable=true] - Wait until the element is enabled, focusable and not read-only before typing.
+    #[napi]
+    pub fn type_text(&self, text: String, use_clipboard: Option<bool>, wait_for_editable: Option<bool>) -> napi::Result<()> {
+        let editable_timeout = wait_for_editable
+            .unwrap_or(true)
+            .then_some(terminator::element::DEFAULT_EDITABLE_TIMEOUT);
+        self.inner
+            .type_text_with_wait(&text, use_clipboard.unwrap_or(false), editable_timeout)
+            .map_err(map_error)
     }
 
     /// Press a key while this element is focused.
@@ -207,6 +392,139 @@ impl Element {
         self.inner.perform_action(&action).map_err(map_error)
     }
 
+    /// Trigger the element's default action without moving the mouse.
+    #[napi]
+    pub fn invoke(&self) -> napi::Result<()> {
+        self.inner.invoke().map_err(map_error)
+    }
+
+    /// Move a check box or toggle button to its next state without clicking it.
+    #[napi]
+    pub fn toggle(&self) -> napi::Result<()> {
+        self.inner.toggle().map_err(map_error)
+    }
+
+    /// Expand a combo box, menu item or tree item.
+    #[napi]
+    pub fn expand(&self) -> napi::Result<()> {
+        self.inner.expand().map_err(map_error)
+    }
+
+    /// Collapse a combo box, menu item or tree item.
+    #[napi]
+    pub fn collapse(&self) -> napi::Result<()> {
+        self.inner.collapse().map_err(map_error)
+    }
+
+    /// Select the element in its list, combo box or tab strip without clicking it.
+    #[napi]
+    pub fn select(&self) -> napi::Result<()> {
+        self.inner.select().map_err(map_error)
+    }
+
+    /// Start assertions on this element that retry until they hold, e.g.
+    /// `element.expect().toHaveText('Saved')`.
+    ///
+    /// @param {number} [timeoutMs=5000] - How long to retry.
+    /// @param {string} [screenshotDir] - Where failure screenshots go, the temp directory by default.
+    /// @returns {Expect} The assertions.
+    #[napi]
+    pub fn expect(&self, timeout_ms: Option<f64>, screenshot_dir: Option<String>) -> Expect {
+        let mut inner = terminator::expect(&self.inner);
+        if let Some(timeout_ms) = timeout_ms {
+            inner = inner.with_timeout(std::time::Duration::from_millis(timeout_ms as u64));
+        }
+        if let Some(dir) = screenshot_dir {
+            inner = inner.with_screenshot_dir(Some(dir.into()));
+        }
+        Expect::from(inner)
+    }
+
+    /// Text selected in an edit control or document.
+    ///
+    /// @returns {string} The selection, empty when nothing is selected.
+    #[napi]
+    pub fn selected_text(&self) -> napi::Result<String> {
+        self.inner.selected_text().map_err(map_error)
+    }
+
+    /// Character offset of the caret in an edit control or document.
+    ///
+    /// @returns {number | null} The offset, or null when the element shows no caret.
+    #[napi]
+    pub fn caret_position(&self) -> napi::Result<Option<u32>> {
+        self.inner
+            .caret_position()
+            .map(|position| position.map(|p| p as u32))
+            .map_err(map_error)
+    }
+
+    /// Select the characters from offset `start` up to `end`. `start == end`
+    /// places the caret.
+    ///
+    /// @param {number} start - Offset of the first selected character.
+    /// @param {number} end - Offset just past the last selected character.
+    #[napi]
+    pub fn select_range(&self, start: u32, end: u32) -> napi::Result<()> {
+        self.inner.select_range(start as usize, end as usize).map_err(map_error)
+    }
+
+    /// The text of an edit control or document as runs of the same font,
+    /// weight, style and colors.
+    ///
+    /// @returns {Array<TextRun>} The runs, in document order.
+    #[napi]
+    pub fn text_with_formatting(&self) -> napi::Result<Vec<TextRun>> {
+        self.inner
+            .text_with_formatting()
+            .map(|runs| runs.into_iter().map(TextRun::from).collect())
+            .map_err(map_error)
+    }
+
+    /// Fill the controls below this element (a window or group) by label.
+    /// Each label is matched to a control through the label it declares, its
+    /// name, the text next to it, or a fuzzy match of its name, and set the
+    /// way its type needs.
+    ///
+    /// @param {Record<string, string>} fields - Values by label, e.g. `{ 'First name': 'Ada', Newsletter: 'yes' }`.
+    /// @returns {FormFillReport} Filled, unmatched and failed fields.
+    #[napi]
+    pub fn fill_form(&self, fields: std::collections::HashMap<String, String>) -> napi::Result<FormFillReport> {
+        terminator::Form::new(&self.inner)
+            .fill(fields)
+            .map(FormFillReport::from)
+            .map_err(map_error)
+    }
+
+    /// Audit the accessibility of the elements below this element (usually a window):
+    /// controls without a name, interactive controls the keyboard can't reach,
+    /// duplicate automation IDs and, optionally, text with low contrast.
+    ///
+    /// @param {boolean} [checkContrast] - Screenshot the element and check text contrast. It must be visible on screen. Default: false
+    /// @param {number} [maxDepth] - How deep below the element to look. Default: 30
+    /// @returns {AuditReport} The findings.
+    #[napi]
+    pub fn audit(&self, check_contrast: Option<bool>, max_depth: Option<u32>) -> napi::Result<AuditReport> {
+        self.build_audit(check_contrast, max_depth)
+            .run()
+            .map(AuditReport::from)
+            .map_err(map_error)
+    }
+
+    /// Audit the accessibility of the elements below this element, like `audit`,
+    /// and render the findings as a self-contained HTML page.
+    ///
+    /// @param {boolean} [checkContrast] - Screenshot the element and check text contrast. Default: false
+    /// @param {number} [maxDepth] - How deep below the element to look. Default: 30
+    /// @returns {string} The HTML page.
+    #[napi]
+    pub fn audit_html(&self, check_contrast: Option<bool>, max_depth: Option<u32>) -> napi::Result<String> {
+        self.build_audit(check_contrast, max_depth)
+            .run()
+            .map(|report| report.to_html())
+            .map_err(map_error)
+    }
+
     /// Scroll the element in a given direction.
     /// 
     /// @param {string} direction - The direction to scroll.
@@ -222,6 +540,15 @@ impl Element {
         self.inner.activate_window().map_err(map_error)
     }
 
+    /// Check whether the control behind this handle no longer exists, e.g. a
+    /// list row recreated by virtualization. Stale elements fail every action.
+    /// 
+    /// @returns {boolean} True if the element is gone.
+    #[napi]
+    pub fn is_stale(&self) -> bool {
+        self.inner.is_stale()
+    }
+
     /// Check if element is focused.
     /// 
     /// @returns {boolean} True if the element is focused.
@@ -353,6 +680,18 @@ impl Element {
             .map_err(map_error)
     }
 
+    /// Capture a screenshot of this element encoded as an image file.
+    /// 
+    /// @param {string} format - "png", "jpeg", "jpeg:<quality>" or "webp".
+    /// @returns {EncodedImage} The encoded screenshot.
+    #[napi]
+    pub fn capture_as(&self, format: String) -> napi::Result<EncodedImage> {
+        let format = crate::types::parse_image_format(&format)?;
+        self.inner.capture_as(format)
+            .map(EncodedImage::from)
+            .map_err(map_error)
+    }
+
     /// Get the process ID of the application containing this element.
     /// 
     /// @returns {number} The process ID.
@@ -360,4 +699,114 @@ impl Element {
     pub fn process_id(&self) -> napi::Result<u32> {
         self.inner.process_id().map_err(map_error)
     }
-} 
\ No newline at end of file
+
+    /// Check whether the window containing this element is always on top.
+    /// 
+    /// @returns {boolean} True if the window is topmost.
+    #[napi]
+    pub fn is_topmost(&self) -> napi::Result<bool> {
+        self.inner.is_topmost().map_err(map_error)
+    }
+
+    /// Make the window containing this element always on top, or clear it.
+    /// 
+    /// @param {boolean} topmost - Whether the window should stay on top.
+    #[napi]
+    pub fn set_topmost(&self, topmost: bool) -> napi::Result<()> {
+        self.inner.set_topmost(topmost).map_err(map_error)
+    }
+
+    /// Get the opacity of the window containing this element.
+    /// 
+    /// @returns {number} Opacity between 0.0 (transparent) and 1.0 (opaque).
+    #[napi]
+    pub fn opacity(&self) -> napi::Result<f64> {
+        self.inner.opacity().map(|o| o as f64).map_err(map_error)
+    }
+
+    /// Set the opacity of the window containing this element.
+    /// 
+    /// @param {number} opacity - Opacity between 0.0 (transparent) and 1.0 (opaque).
+    #[napi]
+    pub fn set_opacity(&self, opacity: f64) -> napi::Result<()> {
+        self.inner.set_opacity(opacity as f32).map_err(map_error)
+    }
+
+    /// Get the show state of the window containing this element.
+    /// 
+    /// @returns {string} One of 'normal', 'minimized' or 'maximized'.
+    #[napi]
+    pub fn window_state(&self) -> napi::Result<String> {
+        self.inner.window_state().map(|s| s.to_string()).map_err(map_error)
+    }
+
+    /// Maximize the window containing this element.
+    #[napi]
+    pub fn maximize(&self) -> napi::Result<()> {
+        self.inner.maximize().map_err(map_error)
+    }
+
+    /// Minimize the window containing this element.
+    #[napi]
+    pub fn minimize(&self) -> napi::Result<()> {
+        self.inner.minimize().map_err(map_error)
+    }
+
+    /// Restore the window containing this element from the minimized or maximized state.
+    #[napi]
+    pub fn restore(&self) -> napi::Result<()> {
+        self.inner.restore().map_err(map_error)
+    }
+
+    /// Move and resize the window containing this element.
+    /// 
+    /// @param {number} x - Left edge in screen coordinates.
+    /// @param {number} y - Top edge in screen coordinates.
+    /// @param {number} width - Window width.
+    /// @param {number} height - Window height.
+    #[napi]
+    pub fn set_bounds(&self, x: f64, y: f64, width: f64, height: f64) -> napi::Result<()> {
+        self.inner.set_bounds(x, y, width, height).map_err(map_error)
+    }
+
+    /// Raise the window containing this element above all others and focus it.
+    #[napi]
+    pub fn bring_to_front(&self) -> napi::Result<()> {
+        self.inner.bring_to_front().map_err(map_error)
+    }
+
+    /// Get the id of the virtual desktop the window containing this element is on.
+    /// 
+    /// @returns {string} The desktop GUID.
+    #[napi]
+    pub fn virtual_desktop(&self) -> napi::Result<String> {
+        self.inner.virtual_desktop().map_err(map_error)
+    }
+
+    /// Check whether the window containing this element is on the virtual desktop shown.
+    /// Elements on other desktops cannot be clicked.
+    /// 
+    /// @returns {boolean} True if the window is on the current desktop.
+    #[napi]
+    pub fn is_on_current_virtual_desktop(&self) -> napi::Result<bool> {
+        self.inner.is_on_current_virtual_desktop().map_err(map_error)
+    }
+
+    /// Move the window containing this element to another virtual desktop.
+    /// Windows only allows this for windows of the calling process.
+    /// 
+    /// @param {string} desktopId - Id from `Desktop.virtualDesktops()`.
+    #[napi]
+    pub fn move_to_desktop(&self, desktop_id: String) -> napi::Result<()> {
+        self.inner.move_to_desktop(&desktop_id).map_err(map_error)
+    }
+} 
+impl Element {
+    fn build_audit(&self, check_contrast: Option<bool>, max_depth: Option<u32>) -> terminator::Audit {
+        let audit = terminator::Audit::new(&self.inner).check_contrast(check_contrast.unwrap_or(false));
+        match max_depth {
+            Some(depth) => audit.max_depth(depth as usize),
+            None => audit,
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use napi::threadsafe_function::{
+    ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode,
+};
+use napi::JsFunction;
+use napi::JsUndefined;
+use napi_derive::napi;
+use terminator::{
+    AppLifecycleEvent, HotkeyRegistration as TerminatorHotkeyRegistration, Notification as TerminatorNotification,
+    UIEvent, UIEventKind, UIEventSubscription as TerminatorUIEventSubscription,
+};
+
+use crate::{map_error, Element, Notification};
+
+// JS event names, as used with desktop.on(...)
+pub(crate) fn event_name(kind: UIEventKind) -> &'static str {
+    match kind {
+        UIEventKind::FocusChanged => "focusChanged",
+        UIEventKind::StructureChanged => "structureChanged",
+        UIEventKind::PropertyChanged => "propertyChanged",
+    }
+}
+
+pub(crate) fn parse_kinds(kinds: &[String]) -> napi::Result<Vec<UIEventKind>> {
+    kinds
+        .iter()
+        .map(|k| {
+            k.parse::<UIEventKind>()
+                .map_err(|e: String| map_error(terminator::AutomationError::InvalidArgument(e)))
+        })
+        .collect()
+}
+
+// Convert a UI event into a plain JS object on the JS thread
+fn to_js_event(ctx: ThreadSafeCallContext<UIEvent>) -> napi::Result<Vec<napi::JsObject>> {
+    let event = ctx.value;
+    let mut obj = ctx.env.create_object()?;
+    obj.set("kind", event_name(event.kind))?;
+    if let Some(element) = event.element {
+        obj.set("element", Element::from(element).into_instance(ctx.env)?)?;
+    }
+    if let Some(detail) = event.detail {
+        obj.set("detail", detail)?;
+    }
+    if let Some(value) = event.value {
+        obj.set("value", value)?;
+    }
+    Ok(vec![obj])
+}
+
+/// Build the native callback that forwards events to a JS function.
+/// Events are queued without blocking the watcher thread.
+pub(crate) fn js_event_forwarder(
+    callback: JsFunction,
+) -> napi::Result<impl Fn(UIEvent) + Send + Sync + 'static> {
+    let tsfn: ThreadsafeFunction<UIEvent, ErrorStrategy::Fatal> =
+        callback.create_threadsafe_function(0, to_js_event)?;
+    Ok(move |event: UIEvent| {
+        tsfn.call(event, ThreadsafeFunctionCallMode::NonBlocking);
+    })
+}
+
+/// An active UI event subscription.
+#[napi(js_name = "UiEventSubscription")]
+pub struct UiEventSubscription {
+    inner: Option<TerminatorUIEventSubscription>,
+}
+
+impl From<TerminatorUIEventSubscription> for UiEventSubscription {
+    fn from(s: TerminatorUIEventSubscription) -> Self {
+        UiEventSubscription { inner: Some(s) }
+    }
+}
+
+#[napi]
+impl UiEventSubscription {
+    /// Stop delivering events to the callback.
+    #[napi]
+    pub fn unsubscribe(&mut self) -> napi::Result<()> {
+        if let Some(subscription) = self.inner.take() {
+            subscription.unsubscribe();
+        }
+        Ok(())
+    }
+
+    /// Whether events are still being delivered.
+    #[napi(getter)]
+    pub fn active(&self) -> bool {
+        self.inner.is_some()
+    }
+}
+
+/// Build the native callback that calls a JS function for each hotkey press.
+pub(crate) fn js_hotkey_forwarder(
+    callback: JsFunction,
+) -> napi::Result<impl Fn() + Send + Sync + 'static> {
+    let tsfn: ThreadsafeFunction<(), ErrorStrategy::Fatal> = callback
+        .create_threadsafe_function(0, |_ctx: ThreadSafeCallContext<()>| {
+            Ok(Vec::<JsUndefined>::new())
+        })?;
+    Ok(move || {
+        tsfn.call((), ThreadsafeFunctionCallMode::NonBlocking);
+    })
+}
+
+/// Build the native callback that forwards new notifications to a JS function.
+pub(crate) fn js_notification_forwarder(
+    callback: JsFunction,
+) -> napi::Result<impl Fn(TerminatorNotification) + Send + Sync + 'static> {
+    let tsfn: ThreadsafeFunction<TerminatorNotification, ErrorStrategy::Fatal> = callback
+        .create_threadsafe_function(0, |ctx: ThreadSafeCallContext<TerminatorNotification>| {
+            Ok(vec![Notification::from(ctx.value).into_instance(ctx.env)?])
+        })?;
+    Ok(move |notification: TerminatorNotification| {
+        tsfn.call(notification, ThreadsafeFunctionCallMode::NonBlocking);
+    })
+}
+
+/// Build the native callback that forwards app session attach and detach
+/// events to a JS function.
+pub(crate) fn js_lifecycle_forwarder(
+    callback: JsFunction,
+) -> napi::Result<impl Fn(AppLifecycleEvent) + Send + Sync + 'static> {
+    let tsfn: ThreadsafeFunction<AppLifecycleEvent, ErrorStrategy::Fatal> = callback
+        .create_threadsafe_function(0, |ctx: ThreadSafeCallContext<AppLifecycleEvent>| {
+            let (kind, pid) = match ctx.value {
+                AppLifecycleEvent::Attached { pid } => ("attached", pid),
+                AppLifecycleEvent::Detached { pid } => ("detached", pid),
+            };
+            let mut obj = ctx.env.create_object()?;
+            obj.set("kind", kind)?;
+            obj.set("pid", pid)?;
+            Ok(vec![obj])
+        })?;
+    Ok(move |event: AppLifecycleEvent| {
+        tsfn.call(event, ThreadsafeFunctionCallMode::NonBlocking);
+    })
+}
+
+/// A registered system-wide hotkey.
+#[napi(js_name = "HotkeyRegistration")]
+pub struct HotkeyRegistration {
+    hotkey: String,
+    inner: Option<TerminatorHotkeyRegistration>,
+}
+
+impl From<TerminatorHotkeyRegistration> for HotkeyRegistration {
+    fn from(r: TerminatorHotkeyRegistration) -> Self {
+        HotkeyRegistration {
+            hotkey: r.hotkey().to_string(),
+            inner: Some(r),
+        }
+    }
+}
+
+#[napi]
+impl HotkeyRegistration {
+    /// Release the hotkey so other applications can use it again.
+    #[napi]
+    pub fn unregister(&mut self) -> napi::Result<()> {
+        if let Some(registration) = self.inner.take() {
+            registration.unregister();
+        }
+        Ok(())
+    }
+
+    /// The registered key combination, e.g. "Ctrl+Shift+H".
+    #[napi(getter)]
+    pub fn hotkey(&self) -> String {
+        self.hotkey.clone()
+    }
+
+    /// Whether the hotkey is still registered.
+    #[napi(getter)]
+    pub fn active(&self) -> bool {
+        self.inner.is_some()
+    }
+}
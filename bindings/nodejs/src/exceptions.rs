@@ -1,32 +1,25 @@
 use napi::{self, Status};
-use terminator::errors::AutomationError;
+use terminator::errors::{AutomationError, ErrorCode};
 
-/// Map Terminator errors to NAPI errors
+/// Separates the message of a mapped error from its JSON details, which
+/// wrapper.js turns into `errorCode` and `context` properties
+const DETAILS_SEPARATOR: &str = "\n@@terminator-error@@";
+
+/// Map Terminator errors to NAPI errors. The message starts with the error's
+/// symbolic code, e.g. `ELEMENT_NOT_FOUND: ...`, which wrapper.js maps to an
+/// error class
 pub fn map_error(err: AutomationError) -> napi::Error {
-    match err {
-        AutomationError::ElementNotFound(msg) => {
-            napi::Error::new(Status::InvalidArg, format!("ELEMENT_NOT_FOUND: {}", msg))
-        }
-        AutomationError::Timeout(msg) => {
-            napi::Error::new(Status::GenericFailure, format!("OPERATION_TIMED_OUT: {}", msg))
-        }
-        AutomationError::PermissionDenied(msg) => {
-            napi::Error::new(Status::GenericFailure, format!("PERMISSION_DENIED: {}", msg))
-        }
-        AutomationError::PlatformError(e) => {
-            napi::Error::new(Status::GenericFailure, format!("PLATFORM_ERROR: {}", e))
-        }
-        AutomationError::UnsupportedOperation(msg) => {
-            napi::Error::new(Status::InvalidArg, format!("UNSUPPORTED_OPERATION: {}", msg))
-        }
-        AutomationError::UnsupportedPlatform(msg) => {
-            napi::Error::new(Status::InvalidArg, format!("UNSUPPORTED_PLATFORM: {}", msg))
-        }
-        AutomationError::InvalidArgument(e) => {
-            napi::Error::new(Status::InvalidArg, format!("INVALID_ARGUMENT: {}", e))
-        }
-        AutomationError::Internal(e) => {
-            napi::Error::new(Status::GenericFailure, format!("INTERNAL_ERROR: {}", e))
-        }
-    }
-} 
\ No newline at end of file
+    let code = err.code();
+    let status = match code {
+        ErrorCode::ElementNotFound
+        | ErrorCode::UnsupportedOperation
+        | ErrorCode::UnsupportedPlatform
+        | ErrorCode::InvalidArgument => Status::InvalidArg,
+        _ => Status::GenericFailure,
+    };
+    let details = serde_json::json!({ "code": code.as_u16(), "context": err.context() });
+    napi::Error::new(
+        status,
+        format!("{}: {}{}{}", code.name(), err.detail(), DETAILS_SEPARATOR, details),
+    )
+}
@@ -0,0 +1,76 @@
+use napi_derive::napi;
+use terminator::Expect as TerminatorExpect;
+
+use crate::map_error;
+
+/// Assertions on one element that retry until they hold or time out.
+/// Failures throw a VerificationFailedError with the last value seen, an
+/// excerpt of the element's subtree and the path of a screenshot.
+#[napi(js_name = "Expect")]
+pub struct Expect {
+    inner: TerminatorExpect,
+}
+
+impl From<TerminatorExpect> for Expect {
+    fn from(expect: TerminatorExpect) -> Self {
+        Expect { inner: expect }
+    }
+}
+
+#[napi]
+impl Expect {
+    /// Invert the assertions, e.g. `element.expect().not().toBeVisible()`.
+    ///
+    /// @returns {Expect} The inverted assertions.
+    #[napi]
+    pub fn not(&self) -> Expect {
+        Expect { inner: self.inner.clone().not() }
+    }
+
+    /// Wait until the element is visible.
+    #[napi]
+    pub fn to_be_visible(&self) -> napi::Result<()> {
+        self.inner.to_be_visible().map_err(map_error)
+    }
+
+    /// Wait until the element is enabled.
+    #[napi]
+    pub fn to_be_enabled(&self) -> napi::Result<()> {
+        self.inner.to_be_enabled().map_err(map_error)
+    }
+
+    /// Wait until the element has keyboard focus.
+    #[napi]
+    pub fn to_be_focused(&self) -> napi::Result<()> {
+        self.inner.to_be_focused().map_err(map_error)
+    }
+
+    /// Wait until the element's text contains the expected string.
+    #[napi]
+    pub fn to_have_text(&self, expected: String) -> napi::Result<()> {
+        self.inner.to_have_text(&expected).map_err(map_error)
+    }
+
+    /// Wait until the element's value equals the expected string.
+    #[napi]
+    pub fn to_have_value(&self, expected: String) -> napi::Result<()> {
+        self.inner.to_have_value(&expected).map_err(map_error)
+    }
+
+    /// Wait until the element's name equals the expected string.
+    #[napi]
+    pub fn to_have_name(&self, expected: String) -> napi::Result<()> {
+        self.inner.to_have_name(&expected).map_err(map_error)
+    }
+
+    /// Wait until an attribute equals the expected value.
+    ///
+    /// @param {string} name - Attribute name, e.g. 'value', 'enabled' or 'description'.
+    /// @param {string} [expected] - Expected value; omitted, the attribute only needs a value.
+    #[napi]
+    pub fn to_have_attribute(&self, name: String, expected: Option<String>) -> napi::Result<()> {
+        self.inner
+            .to_have_attribute(&name, expected.as_deref())
+            .map_err(map_error)
+    }
+}
@@ -1,23 +1,61 @@
+mod app_session;
 mod desktop;
 mod element;
 mod locator;
 mod types;
+mod verify;
+mod events;
+mod expect;
+mod notification;
+mod process;
+mod recorder;
+mod tree_handles;
 mod exceptions;
 
 // Main types first
 pub use desktop::Desktop;
 pub use element::Element;
 pub use locator::Locator;
+pub use verify::{SoftVerifier, VerificationFailure, VerificationReport};
+pub use events::{HotkeyRegistration, UiEventSubscription};
+pub use expect::Expect;
+pub use notification::Notification;
+pub use process::AppHandle;
+pub use app_session::AppSession;
+pub use recorder::{RecordedWorkflow, WorkflowRecorder, WorkflowRecorderOptions};
+pub use tree_handles::UINodeWithHandles;
 pub use types::{
     Bounds,
     Coordinates,
     ClickResult,
+    ExpectedCondition,
+    ActionAttempt,
+    VerifiedActionResult,
     CommandOutput,
     ScreenshotResult,
+    EncodedImage,
     UIElementAttributes,
     UINode,
+    WindowInfo,
+    WindowTree,
+    ConsoleScreen,
+    MonitorInfo,
+    EnvironmentInfo,
+    VirtualDesktop,
+    AutomationCapabilities,
+    TextRun,
+    AuditFinding,
+    AuditReport,
+    BatchOperation,
+    BatchResult,
+    FilledField,
+    FailedField,
+    FormFillReport,
     TreeBuildConfig,
+    TreeBuildStats,
+    TreeBuildResult,
     PropertyLoadingMode,
+    RetryPolicy,
 };
 
 // Error handling - see exceptions.rs for detailed architecture
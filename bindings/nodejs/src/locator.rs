@@ -2,6 +2,7 @@ use napi_derive::napi;
 use terminator::Locator as TerminatorLocator;
 
 use crate::Element;
+use crate::RetryPolicy;
 use crate::map_error;
 
 /// Locator for finding UI elements by selector.
@@ -60,6 +61,16 @@ impl Locator {
         Locator::from(loc)
     }
 
+    /// Configure how this locator polls while waiting for an element.
+    /// 
+    /// @param {RetryPolicy} policy - Backoff, attempt limit and jitter settings.
+    /// @returns {Locator} A new locator with the specified retry policy.
+    #[napi]
+    pub fn with_retry(&self, policy: RetryPolicy) -> Locator {
+        let loc = self.inner.clone().with_retry(policy.into());
+        Locator::from(loc)
+    }
+
     /// Set the root element for this locator.
     /// 
     /// @param {Element} element - The root element.
@@ -70,6 +81,16 @@ impl Locator {
         Locator::from(loc)
     }
 
+    /// Only match elements inside the visible region of a scrollable container.
+    /// 
+    /// @param {Element} container - The scrollable container to search in.
+    /// @returns {Locator} A new locator restricted to the container's viewport.
+    #[napi]
+    pub fn within_viewport(&self, container: &Element) -> Locator {
+        let loc = self.inner.clone().within_viewport(container.inner.clone());
+        Locator::from(loc)
+    }
+
     /// Chain another selector.
     /// 
     /// @param {string} selector - The selector string.
@@ -80,4 +101,32 @@ impl Locator {
         let loc = self.inner.clone().locator(sel);
         Ok(Locator::from(loc))
     }
+
+    /// Only match the element at a position among the matches.
+    /// 
+    /// @param {number} index - Zero-based position of the element.
+    /// @returns {Locator} A new locator matching a single element.
+    #[napi]
+    pub fn nth(&self, index: u32) -> Locator {
+        Locator::from(self.inner.clone().nth(index as usize))
+    }
+
+    /// Only match the first visible element among the matches.
+    /// 
+    /// @returns {Locator} A new locator matching the first visible element.
+    #[napi]
+    pub fn first_visible(&self) -> Locator {
+        Locator::from(self.inner.clone().first_visible())
+    }
+
+    /// (async) Count the matching elements.
+    /// 
+    /// @param {number} [timeoutMs] - Timeout in milliseconds.
+    /// @returns {Promise<number>} Number of matching elements, 0 if none.
+    #[napi]
+    pub async fn count(&self, timeout_ms: Option<f64>) -> napi::Result<u32> {
+        use std::time::Duration;
+        let timeout = timeout_ms.map(|ms| Duration::from_millis(ms as u64));
+        self.inner.count(timeout).await.map(|n| n as u32).map_err(map_error)
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,69 @@
+use napi_derive::napi;
+use terminator::Notification as TerminatorNotification;
+
+use crate::{map_error, Element};
+
+/// A notification on screen: a Windows toast or a macOS Notification Center banner.
+#[napi(js_name = "Notification")]
+pub struct Notification {
+    inner: TerminatorNotification,
+}
+
+impl From<TerminatorNotification> for Notification {
+    fn from(notification: TerminatorNotification) -> Self {
+        Notification { inner: notification }
+    }
+}
+
+#[napi]
+impl Notification {
+    /// The app that raised the notification, where the platform shows it.
+    #[napi(getter)]
+    pub fn app(&self) -> Option<String> {
+        self.inner.app.clone()
+    }
+
+    #[napi(getter)]
+    pub fn title(&self) -> Option<String> {
+        self.inner.title.clone()
+    }
+
+    #[napi(getter)]
+    pub fn body(&self) -> Option<String> {
+        self.inner.body.clone()
+    }
+
+    /// Labels of the action buttons, e.g. ["Open", "Show in folder"].
+    #[napi(getter)]
+    pub fn actions(&self) -> Vec<String> {
+        self.inner.actions.clone()
+    }
+
+    /// The element of the whole notification.
+    ///
+    /// @returns {Element} The notification element.
+    #[napi]
+    pub fn element(&self) -> Element {
+        Element::from(self.inner.element().clone())
+    }
+
+    /// Click the notification itself, which usually opens the app that raised it.
+    #[napi]
+    pub fn click(&self) -> napi::Result<()> {
+        self.inner.click().map_err(map_error)
+    }
+
+    /// Click one of the notification's action buttons.
+    ///
+    /// @param {string} action - Label of the button, ignoring case.
+    #[napi]
+    pub fn click_action(&self, action: String) -> napi::Result<()> {
+        self.inner.click_action(&action).map_err(map_error)
+    }
+
+    /// Close the notification with its dismiss button.
+    #[napi]
+    pub fn dismiss(&self) -> napi::Result<()> {
+        self.inner.dismiss().map_err(map_error)
+    }
+}
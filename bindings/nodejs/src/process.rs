@@ -0,0 +1,73 @@
+use napi_derive::napi;
+use std::time::Duration;
+use terminator::AppHandle as TerminatorAppHandle;
+
+use crate::{map_error, Element};
+
+/// A process started with desktop.launch().
+#[napi(js_name = "AppHandle")]
+pub struct AppHandle {
+    inner: TerminatorAppHandle,
+}
+
+impl From<TerminatorAppHandle> for AppHandle {
+    fn from(handle: TerminatorAppHandle) -> Self {
+        AppHandle { inner: handle }
+    }
+}
+
+#[napi]
+impl AppHandle {
+    /// Process ID of the launched process.
+    #[napi(getter)]
+    pub fn pid(&self) -> u32 {
+        self.inner.pid()
+    }
+
+    /// Whether the process is still running.
+    ///
+    /// @returns {boolean} True while the process has not exited.
+    #[napi]
+    pub fn is_running(&self) -> bool {
+        self.inner.is_running()
+    }
+
+    /// Exit code of the process, or null while it is running.
+    ///
+    /// @returns {number | null} The exit code, if the process exited with one.
+    #[napi]
+    pub fn exit_code(&self) -> napi::Result<Option<i32>> {
+        self.inner.exit_code().map_err(map_error)
+    }
+
+    /// Terminate the process. Does nothing if it already exited.
+    #[napi]
+    pub fn kill(&self) -> napi::Result<()> {
+        self.inner.kill().map_err(map_error)
+    }
+
+    /// (async) Wait for the process to show a window.
+    ///
+    /// @param {number} [timeoutMs] - Timeout in milliseconds (default 30000).
+    /// @returns {Promise<Element>} The application element of the process.
+    #[napi]
+    pub async fn wait_for_window(&self, timeout_ms: Option<f64>) -> napi::Result<Element> {
+        self.inner
+            .wait_for_window(timeout_ms.map(|ms| Duration::from_millis(ms as u64)))
+            .await
+            .map(Element::from)
+            .map_err(map_error)
+    }
+
+    /// (async) Wait for the process to exit.
+    ///
+    /// @param {number} [timeoutMs] - Timeout in milliseconds. Waits forever if omitted.
+    /// @returns {Promise<number | null>} The exit code, or null if the process was terminated without one.
+    #[napi]
+    pub async fn wait_for_exit(&self, timeout_ms: Option<f64>) -> napi::Result<Option<i32>> {
+        self.inner
+            .wait_for_exit(timeout_ms.map(|ms| Duration::from_millis(ms as u64)))
+            .await
+            .map_err(map_error)
+    }
+}
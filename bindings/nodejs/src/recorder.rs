@@ -0,0 +1,198 @@
+use std::sync::Arc;
+
+use napi::threadsafe_function::{
+    ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode,
+};
+use napi::{JsFunction, Status};
+use napi_derive::napi;
+use terminator_workflow_recorder::events::SerializableRecordedWorkflow;
+use terminator_workflow_recorder::{
+    AudioConfig, CustomSink, RecordingLine, ScreenshotConfig, WorkflowRecorder as TerminatorWorkflowRecorder,
+    WorkflowRecorderConfig, WorkflowRecorderError,
+};
+use tokio::sync::Mutex;
+
+/// Options for a WorkflowRecorder. Omitted fields keep the recorder's defaults.
+#[napi(object)]
+#[derive(Default)]
+pub struct WorkflowRecorderOptions {
+    /// Name stored in the recorded workflow (default "Recorded workflow")
+    pub name: Option<String>,
+    pub record_mouse: Option<bool>,
+    pub record_keyboard: Option<bool>,
+    pub record_window: Option<bool>,
+    /// Attach the UI element under the pointer or with focus to each event
+    pub capture_ui_elements: Option<bool>,
+    pub record_clipboard: Option<bool>,
+    pub record_text_selection: Option<bool>,
+    pub record_drag_drop: Option<bool>,
+    pub record_hotkeys: Option<bool>,
+    pub record_ui_focus_changes: Option<bool>,
+    pub record_ui_structure_changes: Option<bool>,
+    pub record_ui_property_changes: Option<bool>,
+    /// Minimum time between mouse move events in milliseconds
+    pub mouse_move_throttle_ms: Option<u32>,
+    /// Window titles whose UI events are dropped (case-insensitive), added to the defaults
+    pub ignore_window_titles: Option<Vec<String>>,
+    /// Process names whose UI events are dropped (case-insensitive), added to the defaults
+    pub ignore_applications: Option<Vec<String>>,
+    /// Attach a JPEG thumbnail to clicks and the start of typing bursts
+    pub screenshots: Option<bool>,
+    /// Record the microphone into this WAV file, referenced from the workflow
+    pub audio_path: Option<String>,
+    /// Record what the speakers play instead of the microphone
+    pub audio_loopback: Option<bool>,
+}
+
+impl From<WorkflowRecorderOptions> for WorkflowRecorderConfig {
+    fn from(options: WorkflowRecorderOptions) -> Self {
+        let mut config = WorkflowRecorderConfig::default();
+        let flags = [
+            (options.record_mouse, &mut config.record_mouse),
+            (options.record_keyboard, &mut config.record_keyboard),
+            (options.record_window, &mut config.record_window),
+            (options.capture_ui_elements, &mut config.capture_ui_elements),
+            (options.record_clipboard, &mut config.record_clipboard),
+            (options.record_text_selection, &mut config.record_text_selection),
+            (options.record_drag_drop, &mut config.record_drag_drop),
+            (options.record_hotkeys, &mut config.record_hotkeys),
+            (options.record_ui_focus_changes, &mut config.record_ui_focus_changes),
+            (options.record_ui_structure_changes, &mut config.record_ui_structure_changes),
+            (options.record_ui_property_changes, &mut config.record_ui_property_changes),
+        ];
+        for (option, flag) in flags {
+            if let Some(value) = option {
+                *flag = value;
+            }
+        }
+        if let Some(ms) = options.mouse_move_throttle_ms {
+            config.mouse_move_throttle_ms = ms as u64;
+        }
+        config.ignore_window_titles.extend(options.ignore_window_titles.unwrap_or_default());
+        config.ignore_applications.extend(options.ignore_applications.unwrap_or_default());
+        if options.screenshots == Some(true) {
+            config.screenshots = Some(ScreenshotConfig::default());
+        }
+        if let Some(path) = options.audio_path {
+            config.audio = Some(match options.audio_loopback {
+                Some(true) => AudioConfig::loopback(path),
+                _ => AudioConfig::microphone(path),
+            });
+        }
+        config
+    }
+}
+
+/// A finished recording.
+#[napi(object)]
+pub struct RecordedWorkflow {
+    pub name: String,
+    /// Milliseconds since the Unix epoch
+    pub start_time: f64,
+    /// Milliseconds since the Unix epoch, missing while recording
+    pub end_time: Option<f64>,
+    /// Recorded events, in the same JSON form as a saved recording
+    pub events: Vec<serde_json::Value>,
+    /// The narration track (`path`, `start_time`, `duration_ms`, ...), if audio was recorded
+    pub audio: Option<serde_json::Value>,
+}
+
+impl TryFrom<SerializableRecordedWorkflow> for RecordedWorkflow {
+    type Error = napi::Error;
+
+    fn try_from(workflow: SerializableRecordedWorkflow) -> napi::Result<Self> {
+        let events = workflow
+            .events
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<_, _>>()
+            .map_err(|e| napi::Error::new(Status::GenericFailure, format!("INTERNAL_ERROR: {}", e)))?;
+        let audio = workflow
+            .audio
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| napi::Error::new(Status::GenericFailure, format!("INTERNAL_ERROR: {}", e)))?;
+        Ok(RecordedWorkflow {
+            name: workflow.name,
+            start_time: workflow.start_time as f64,
+            end_time: workflow.end_time.map(|t| t as f64),
+            events,
+            audio,
+        })
+    }
+}
+
+fn map_recorder_error(err: WorkflowRecorderError) -> napi::Error {
+    napi::Error::new(Status::GenericFailure, format!("PLATFORM_ERROR: {}", err))
+}
+
+/// Records user input and UI events into a replayable workflow. Windows only.
+#[napi(js_name = "WorkflowRecorder")]
+pub struct WorkflowRecorder {
+    inner: Arc<Mutex<TerminatorWorkflowRecorder>>,
+}
+
+#[napi]
+impl WorkflowRecorder {
+    /// Create a recorder. Nothing is captured until `start()` is called.
+    ///
+    /// @param {WorkflowRecorderOptions} [options] - What to record.
+    #[napi(constructor)]
+    pub fn new(options: Option<WorkflowRecorderOptions>) -> Self {
+        let mut options = options.unwrap_or_default();
+        let name = options.name.take().unwrap_or_else(|| "Recorded workflow".to_string());
+        let recorder = TerminatorWorkflowRecorder::new(name, options.into());
+        WorkflowRecorder {
+            inner: Arc::new(Mutex::new(recorder)),
+        }
+    }
+
+    /// Install the input hooks and start recording.
+    ///
+    /// @returns {Promise<void>} Resolves once the hooks are installed.
+    #[napi]
+    pub async fn start(&self) -> napi::Result<()> {
+        self.inner.lock().await.start().await.map_err(map_recorder_error)
+    }
+
+    /// Stop recording and return everything recorded.
+    ///
+    /// @returns {Promise<RecordedWorkflow>} The recorded workflow.
+    #[napi]
+    pub async fn stop(&self) -> napi::Result<RecordedWorkflow> {
+        let mut recorder = self.inner.lock().await;
+        recorder.stop().await.map_err(map_recorder_error)?;
+        recorder.workflow().map_err(map_recorder_error)?.try_into()
+    }
+
+    /// Call a function with each event as it is recorded, in the same JSON form as
+    /// `RecordedWorkflow.events`. Events are delivered on the JS thread without
+    /// blocking the recorder. Register before `start()` to see every event.
+    ///
+    /// @param {function} callback - Called with each recorded event.
+    #[napi(ts_args_type = "callback: (event: any) => void")]
+    pub fn on_event(&self, callback: JsFunction) -> napi::Result<()> {
+        let tsfn: ThreadsafeFunction<serde_json::Value, ErrorStrategy::Fatal> = callback
+            .create_threadsafe_function(0, |ctx: ThreadSafeCallContext<serde_json::Value>| {
+                ctx.env.to_js_value(&ctx.value).map(|value| vec![value])
+            })?;
+        let sink = CustomSink::new(move |line: &RecordingLine| {
+            if let RecordingLine::Event(event) = line {
+                let event = serde_json::to_value(event)?;
+                tsfn.call(event, ThreadsafeFunctionCallMode::NonBlocking);
+            }
+            Ok(())
+        });
+        self.inner
+            .try_lock()
+            .map_err(|_| {
+                napi::Error::new(
+                    Status::GenericFailure,
+                    "INVALID_ARGUMENT: Cannot add an event callback while the recorder is starting or stopping",
+                )
+            })?
+            .add_sink(sink);
+        Ok(())
+    }
+}
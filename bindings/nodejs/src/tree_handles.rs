@@ -0,0 +1,78 @@
+use napi_derive::napi;
+use terminator::UINodeWithHandles as TerminatorUINodeWithHandles;
+
+use crate::{Element, UINode};
+
+/// A window tree that keeps the live element behind each node. Nodes are
+/// numbered depth-first, the window being 0, so a node found in the tree can
+/// be acted on without searching for it again.
+#[napi(js_name = "UINodeWithHandles")]
+pub struct UINodeWithHandles {
+    inner: TerminatorUINodeWithHandles,
+}
+
+impl From<TerminatorUINodeWithHandles> for UINodeWithHandles {
+    fn from(tree: TerminatorUINodeWithHandles) -> Self {
+        UINodeWithHandles { inner: tree }
+    }
+}
+
+#[napi]
+impl UINodeWithHandles {
+    /// The tree, as returned by getWindowTree.
+    #[napi(getter)]
+    pub fn tree(&self) -> UINode {
+        UINode::from(self.inner.tree.clone())
+    }
+
+    /// Number of nodes in the tree.
+    #[napi(getter)]
+    pub fn length(&self) -> u32 {
+        self.inner.len() as u32
+    }
+
+    /// The element of a node.
+    ///
+    /// @param {number} index - Depth-first number of the node.
+    /// @returns {Element | null} The element, or null past the last node.
+    #[napi]
+    pub fn element(&self, index: u32) -> Option<Element> {
+        self.inner.element(index as usize).cloned().map(Element::from)
+    }
+
+    /// A node by its depth-first number.
+    ///
+    /// @param {number} index - Depth-first number of the node.
+    /// @returns {UINode | null} The node, or null past the last node.
+    #[napi]
+    pub fn node(&self, index: u32) -> Option<UINode> {
+        self.inner.node(index as usize).cloned().map(UINode::from)
+    }
+
+    /// The element of the node reached by following child indices from the root.
+    ///
+    /// @param {number[]} indexPath - Child index at each level, [] for the window.
+    /// @returns {Element | null} The element, or null if the path leads nowhere.
+    #[napi]
+    pub fn element_at(&self, index_path: Vec<u32>) -> Option<Element> {
+        let index_path: Vec<usize> = index_path.into_iter().map(|i| i as usize).collect();
+        self.inner.element_at(&index_path).cloned().map(Element::from)
+    }
+
+    /// Numbers of the nodes with the given role and name, depth-first.
+    ///
+    /// @param {string} [role] - Role to match, e.g. "Button".
+    /// @param {string} [name] - Name to match exactly.
+    /// @returns {number[]} Depth-first numbers of the matching nodes.
+    #[napi]
+    pub fn find_all(&self, role: Option<String>, name: Option<String>) -> Vec<u32> {
+        self.inner
+            .find_all(|node| {
+                role.as_deref().is_none_or(|role| node.attributes.role == role)
+                    && name.as_deref().is_none_or(|name| node.attributes.name.as_deref() == Some(name))
+            })
+            .into_iter()
+            .map(|index| index as u32)
+            .collect()
+    }
+}
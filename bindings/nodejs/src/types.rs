@@ -1,3 +1,4 @@
+use napi::bindgen_prelude::Buffer;
 use napi_derive::napi;
 use std::collections::HashMap;
 use crate::Element;
@@ -21,6 +22,41 @@ pub struct ClickResult {
     pub method: String,
     pub coordinates: Option<Coordinates>,
     pub details: String,
+    /// How the click reached the application: 'uia_pattern', 'send_input_cursor' or 'post_message'
+    pub strategy: Option<String>,
+}
+
+/// Expected effect of a verified action
+#[napi(object, js_name = "ExpectedCondition")]
+pub struct ExpectedCondition {
+    /// 'gone', 'focused', 'enabled', 'disabled', 'nameEquals', 'valueEquals', 'valueContains' or 'textContains'
+    pub kind: String,
+    /// Expected string for the Equals and Contains kinds
+    pub value: Option<String>,
+}
+
+/// One try of a verified action
+#[napi(object, js_name = "ActionAttempt")]
+pub struct ActionAttempt {
+    /// Input method used, e.g. 'click', 'mouse' or 'clipboard'
+    pub method: String,
+    /// Error returned by the action itself, if it failed
+    pub error: Option<String>,
+    /// Whether the expected condition held after this attempt
+    pub condition_met: bool,
+    pub duration_ms: f64,
+}
+
+/// Outcome of an action whose effect was verified
+#[napi(object, js_name = "VerifiedActionResult")]
+pub struct VerifiedActionResult {
+    /// Input method of the attempt that produced the expected effect
+    pub method: String,
+    /// 1-based number of that attempt
+    pub attempt: u32,
+    pub attempts: Vec<ActionAttempt>,
+    /// Click details of the successful attempt, for clicks
+    pub click: Option<ClickResult>,
 }
 
 #[napi(object, js_name = "CommandOutput")]
@@ -37,6 +73,32 @@ pub struct ScreenshotResult {
     pub image_data: Vec<u8>,
 }
 
+/// A screenshot encoded as an image file.
+#[napi(object)]
+pub struct EncodedImage {
+    /// Format the image is encoded in, e.g. "png" or "jpeg:85".
+    pub format: String,
+    pub width: u32,
+    pub height: u32,
+    pub data: Buffer,
+}
+
+impl From<terminator::EncodedImage> for EncodedImage {
+    fn from(image: terminator::EncodedImage) -> Self {
+        EncodedImage {
+            format: image.format.to_string(),
+            width: image.width,
+            height: image.height,
+            data: image.data.into(),
+        }
+    }
+}
+
+/// Parse a format name such as "png", "jpeg", "jpeg:70" or "webp"
+pub fn parse_image_format(format: &str) -> napi::Result<terminator::ImageFormat> {
+    format.parse().map_err(crate::map_error)
+}
+
 #[napi(object, js_name = "UIElementAttributes")]
 pub struct UIElementAttributes {
     pub role: String,
@@ -72,9 +134,194 @@ pub struct ExploreResponse {
 pub struct UINode {
     pub id: Option<String>,
     pub attributes: UIElementAttributes,
+    /// Path selector that re-locates this element.
+    pub path: Option<String>,
+    /// Whether this node or any node below it is missing children.
+    pub truncated: bool,
+    /// Whether this node's own children were all collected: 'complete', 'timed_out' or 'failed'.
+    pub status: String,
     pub children: Vec<UINode>,
 }
 
+#[napi(object, js_name = "WindowInfo")]
+pub struct WindowInfo {
+    pub pid: u32,
+    pub title: String,
+    pub id: Option<String>,
+    pub bounds: Option<Bounds>,
+}
+
+#[napi(object, js_name = "WindowTree")]
+pub struct WindowTree {
+    pub window: WindowInfo,
+    pub tree: UINode,
+}
+
+/// Visible contents of a console screen buffer
+#[napi(object, js_name = "ConsoleScreen")]
+pub struct ConsoleScreen {
+    pub width: u32,
+    pub height: u32,
+    pub cursor_x: u32,
+    pub cursor_y: u32,
+    /// Visible lines, top to bottom, with trailing spaces removed
+    pub lines: Vec<String>,
+    /// All visible lines joined with newlines
+    pub text: String,
+}
+
+/// A connected display
+#[napi(object, js_name = "MonitorInfo")]
+pub struct MonitorInfo {
+    pub name: String,
+    /// Position and size in virtual screen coordinates (physical pixels)
+    pub bounds: Bounds,
+    /// Ratio of physical to logical pixels (1.0 = 100% scaling)
+    pub scale_factor: f64,
+    /// Refresh rate in Hz
+    pub refresh_rate: f64,
+    pub is_primary: bool,
+}
+
+/// OS settings that affect UI trees and timings. Unknown settings are null.
+#[napi(object, js_name = "EnvironmentInfo")]
+pub struct EnvironmentInfo {
+    /// Operating system family: 'windows', 'macos' or 'linux'
+    pub os: String,
+    pub os_version: Option<String>,
+    pub os_build: Option<String>,
+    /// 'light' or 'dark'
+    pub theme: Option<String>,
+    pub animations_enabled: Option<bool>,
+    /// Accessibility text size multiplier (1.0 = 100%)
+    pub text_scale_factor: Option<f64>,
+    /// Display scale factor of the primary monitor (1.0 = 100%)
+    pub display_scale_factor: Option<f64>,
+    pub screen_reader_active: Option<bool>,
+}
+
+/// A virtual desktop, as shown in Task View
+#[napi(object, js_name = "VirtualDesktop")]
+pub struct VirtualDesktop {
+    /// GUID of the desktop, passed to `Element.moveToDesktop`
+    pub id: String,
+    pub name: String,
+    /// Position in Task View, from 0
+    pub index: u32,
+    /// Whether this is the desktop currently shown
+    pub is_current: bool,
+}
+
+/// A stretch of text with the same formatting, from `Element.textWithFormatting`
+#[napi(object, js_name = "TextRun")]
+pub struct TextRun {
+    pub text: String,
+    /// Character offset of the run in the document
+    pub start: u32,
+    pub font_name: Option<String>,
+    /// Size in points
+    pub font_size: Option<f64>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    /// Text color as `#rrggbb`
+    pub foreground_color: Option<String>,
+    /// Highlight color as `#rrggbb`
+    pub background_color: Option<String>,
+}
+
+/// One problem found by `Element.audit`
+#[napi(object, js_name = "AuditFinding")]
+pub struct AuditFinding {
+    /// `missing_name`, `not_focusable`, `duplicate_automation_id` or `low_contrast`
+    pub rule: String,
+    /// `error` or `warning`
+    pub severity: String,
+    pub message: String,
+    pub role: String,
+    pub name: Option<String>,
+    pub automation_id: Option<String>,
+    /// Roles and names from the audited root down to the element
+    pub location: String,
+    pub bounds: Option<Bounds>,
+    pub contrast_ratio: Option<f64>,
+}
+
+/// Outcome of `Element.audit`
+#[napi(object, js_name = "AuditReport")]
+pub struct AuditReport {
+    pub root: String,
+    pub elements_checked: u32,
+    pub findings: Vec<AuditFinding>,
+    /// Whether anything blocks users of assistive technology
+    pub has_errors: bool,
+}
+
+/// What this process can automate
+#[napi(object, js_name = "AutomationCapabilities")]
+pub struct AutomationCapabilities {
+    /// `untrusted`, `low`, `medium`, `high` or `system`; absent where the platform has no integrity levels
+    pub integrity_level: Option<String>,
+    pub elevated: bool,
+    pub ui_access: bool,
+    /// Whether input reaches applications running as administrator
+    pub reaches_elevated_windows: bool,
+}
+
+/// A form field that was filled
+#[napi(object, js_name = "FilledField")]
+pub struct FilledField {
+    pub label: String,
+    /// `text`, `check_box`, `radio_button`, `combo_box` or `range`
+    pub kind: String,
+    /// `labeled_by`, `name`, `proximity` or `fuzzy_name`
+    pub matched_by: String,
+    pub control_name: Option<String>,
+}
+
+/// A form field whose control could not be set
+#[napi(object, js_name = "FailedField")]
+pub struct FailedField {
+    pub label: String,
+    pub error: String,
+}
+
+/// Outcome of `Element.fillForm`
+#[napi(object, js_name = "FormFillReport")]
+pub struct FormFillReport {
+    pub filled: Vec<FilledField>,
+    /// Labels no control was found for
+    pub unmatched: Vec<String>,
+    pub failed: Vec<FailedField>,
+    /// Whether every field was filled
+    pub is_complete: bool,
+}
+
+/// An operation for `Desktop.batch`
+#[napi(object, js_name = "BatchOperation")]
+pub struct BatchOperation {
+    /// `find`, `click`, `typeText` or `getText`
+    pub op: String,
+    pub selector: String,
+    /// Text to type, for `typeText`
+    pub text: Option<String>,
+}
+
+/// Result of one operation of `Desktop.batch`
+#[napi(object, js_name = "BatchResult")]
+pub struct BatchResult {
+    pub index: u32,
+    pub op: String,
+    pub selector: String,
+    /// `ok`, `failed` or `skipped`
+    pub status: String,
+    /// Text read by a `getText` operation
+    pub text: Option<String>,
+    pub error: Option<String>,
+    pub error_code: Option<String>,
+    pub element: Option<Element>,
+}
+
 #[napi(string_enum)]
 pub enum PropertyLoadingMode {
     /// Only load essential properties (role + name) - fastest
@@ -95,6 +342,50 @@ pub struct TreeBuildConfig {
     pub yield_every_n_elements: Option<i32>,
     /// Optional batch size for processing elements
     pub batch_size: Option<i32>,
+    /// Optional time budget for the whole tree in milliseconds; a partial tree is returned when it runs out
+    pub max_total_duration_ms: Option<f64>,
+    /// Fetch children with their properties in one call (Windows UIA cache requests), on by default
+    pub use_cache_request: Option<bool>,
+}
+
+/// How a window tree was built
+#[napi(object, js_name = "TreeBuildStats")]
+pub struct TreeBuildStats {
+    pub elements: u32,
+    pub max_depth: u32,
+    /// Elements whose attributes were read from a cache request
+    pub cached_elements: u32,
+    /// Cache requests made, one per element whose children were fetched
+    pub cache_requests: u32,
+    /// Lookups the cache could not serve, done without it
+    pub uncached_fallbacks: u32,
+    pub errors: u32,
+    /// Subtrees left incomplete when the time budget ran out
+    pub truncated_subtrees: u32,
+    pub duration_ms: f64,
+}
+
+/// A window tree with how it was built, from `Desktop.getWindowTreeWithStats`
+#[napi(object, js_name = "TreeBuildResult")]
+pub struct TreeBuildResult {
+    pub tree: UINode,
+    pub stats: TreeBuildStats,
+}
+
+#[napi(object, js_name = "RetryPolicy")]
+pub struct RetryPolicy {
+    /// Delay before the second attempt in milliseconds (default 100)
+    pub initial_delay_ms: Option<f64>,
+    /// Upper bound for the delay between attempts in milliseconds (default 5000)
+    pub max_delay_ms: Option<f64>,
+    /// Factor applied to the delay after each failed attempt (default 1.0)
+    pub multiplier: Option<f64>,
+    /// Maximum number of attempts, unlimited if omitted
+    pub max_attempts: Option<u32>,
+    /// Random spread applied to each delay, as a fraction (0.0 - 1.0)
+    pub jitter: Option<f64>,
+    /// Optional cap on how long a single attempt may search, in milliseconds
+    pub attempt_timeout_ms: Option<f64>,
 }
 
 impl From<(f64, f64, f64, f64)> for Bounds {
@@ -115,6 +406,23 @@ impl From<terminator::ClickResult> for ClickResult {
             method: r.method,
             coordinates: r.coordinates.map(Coordinates::from),
             details: r.details,
+            strategy: r.strategy.map(|strategy| strategy.to_string()),
+        }
+    }
+}
+
+impl From<terminator::VerifiedActionResult> for VerifiedActionResult {
+    fn from(r: terminator::VerifiedActionResult) -> Self {
+        VerifiedActionResult {
+            method: r.method,
+            attempt: r.attempt as u32,
+            attempts: r.attempts.into_iter().map(|a| ActionAttempt {
+                method: a.method,
+                error: a.error,
+                condition_met: a.condition_met,
+                duration_ms: a.duration_ms as f64,
+            }).collect(),
+            click: r.click.map(ClickResult::from),
         }
     }
 }
@@ -124,11 +432,214 @@ impl From<terminator::UINode> for UINode {
         UINode {
             id: node.id,
             attributes: UIElementAttributes::from(node.attributes),
+            path: node.path,
+            truncated: node.truncated,
+            status: node.status.as_str().to_string(),
             children: node.children.into_iter().map(UINode::from).collect(),
         }
     }
 }
 
+impl From<(terminator::WindowInfo, terminator::UINode)> for WindowTree {
+    fn from((info, tree): (terminator::WindowInfo, terminator::UINode)) -> Self {
+        WindowTree {
+            window: WindowInfo {
+                pid: info.pid,
+                title: info.title,
+                id: info.id,
+                bounds: info.bounds.map(Bounds::from),
+            },
+            tree: UINode::from(tree),
+        }
+    }
+}
+
+impl From<terminator::ConsoleScreen> for ConsoleScreen {
+    fn from(screen: terminator::ConsoleScreen) -> Self {
+        ConsoleScreen {
+            width: screen.width as u32,
+            height: screen.height as u32,
+            cursor_x: screen.cursor.0 as u32,
+            cursor_y: screen.cursor.1 as u32,
+            text: screen.text(),
+            lines: screen.lines,
+        }
+    }
+}
+
+impl From<terminator::MonitorInfo> for MonitorInfo {
+    fn from(monitor: terminator::MonitorInfo) -> Self {
+        MonitorInfo {
+            name: monitor.name,
+            bounds: Bounds::from(<(f64, f64, f64, f64)>::from(monitor.bounds)),
+            scale_factor: monitor.scale_factor,
+            refresh_rate: monitor.refresh_rate,
+            is_primary: monitor.is_primary,
+        }
+    }
+}
+
+impl From<terminator::TextRun> for TextRun {
+    fn from(run: terminator::TextRun) -> Self {
+        TextRun {
+            text: run.text,
+            start: run.start as u32,
+            font_name: run.font_name,
+            font_size: run.font_size,
+            bold: run.bold,
+            italic: run.italic,
+            underline: run.underline,
+            foreground_color: run.foreground_color,
+            background_color: run.background_color,
+        }
+    }
+}
+
+impl From<terminator::VirtualDesktop> for VirtualDesktop {
+    fn from(desktop: terminator::VirtualDesktop) -> Self {
+        VirtualDesktop {
+            id: desktop.id,
+            name: desktop.name,
+            index: desktop.index as u32,
+            is_current: desktop.is_current,
+        }
+    }
+}
+
+impl From<terminator::AuditReport> for AuditReport {
+    fn from(report: terminator::AuditReport) -> Self {
+        // Same names as the serialized report
+        let name = |value: serde_json::Result<serde_json::Value>| {
+            value.ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()
+        };
+        AuditReport {
+            has_errors: report.has_errors(),
+            root: report.root,
+            elements_checked: report.elements_checked as u32,
+            findings: report
+                .findings
+                .into_iter()
+                .map(|finding| AuditFinding {
+                    rule: finding.rule.to_string(),
+                    severity: name(serde_json::to_value(finding.severity)),
+                    message: finding.message,
+                    role: finding.role,
+                    name: finding.name,
+                    automation_id: finding.automation_id,
+                    location: finding.location,
+                    bounds: finding.bounds.map(|b| Bounds { x: b.x, y: b.y, width: b.width, height: b.height }),
+                    contrast_ratio: finding.contrast_ratio,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<terminator::AutomationCapabilities> for AutomationCapabilities {
+    fn from(capabilities: terminator::AutomationCapabilities) -> Self {
+        AutomationCapabilities {
+            integrity_level: capabilities.integrity_level.map(|level| level.to_string()),
+            elevated: capabilities.elevated,
+            ui_access: capabilities.ui_access,
+            reaches_elevated_windows: capabilities.reaches_elevated_windows(),
+        }
+    }
+}
+
+impl From<terminator::FormFillReport> for FormFillReport {
+    fn from(report: terminator::FormFillReport) -> Self {
+        // Same names as the serialized report
+        let name = |value: serde_json::Result<serde_json::Value>| {
+            value.ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()
+        };
+        FormFillReport {
+            is_complete: report.is_complete(),
+            filled: report
+                .filled
+                .into_iter()
+                .map(|field| FilledField {
+                    kind: name(serde_json::to_value(field.kind)),
+                    matched_by: name(serde_json::to_value(field.matched_by)),
+                    label: field.label,
+                    control_name: field.control_name,
+                })
+                .collect(),
+            unmatched: report.unmatched,
+            failed: report
+                .failed
+                .into_iter()
+                .map(|field| FailedField { label: field.label, error: field.error })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<BatchOperation> for terminator::BatchOp {
+    type Error = napi::Error;
+
+    fn try_from(operation: BatchOperation) -> napi::Result<Self> {
+        let selector = operation.selector;
+        match (operation.op.as_str(), operation.text) {
+            ("find", _) => Ok(terminator::BatchOp::Find { selector }),
+            ("click", _) => Ok(terminator::BatchOp::Click { selector }),
+            ("typeText", Some(text)) => Ok(terminator::BatchOp::TypeText { selector, text }),
+            ("typeText", None) => Err(napi::Error::new(
+                napi::Status::InvalidArg,
+                format!("typeText on '{}' needs text", selector),
+            )),
+            ("getText", _) => Ok(terminator::BatchOp::GetText { selector }),
+            (other, _) => Err(napi::Error::new(
+                napi::Status::InvalidArg,
+                format!("Unknown batch operation '{}'. Expected find, click, typeText or getText", other),
+            )),
+        }
+    }
+}
+
+impl From<terminator::BatchResult> for BatchResult {
+    fn from(result: terminator::BatchResult) -> Self {
+        let op = match &result.op {
+            terminator::BatchOp::Find { .. } => "find",
+            terminator::BatchOp::Click { .. } => "click",
+            terminator::BatchOp::TypeText { .. } => "typeText",
+            terminator::BatchOp::GetText { .. } => "getText",
+        };
+        let status = match result.status {
+            terminator::BatchStatus::Ok => "ok",
+            terminator::BatchStatus::Failed => "failed",
+            terminator::BatchStatus::Skipped => "skipped",
+        };
+        BatchResult {
+            index: result.index as u32,
+            op: op.to_string(),
+            selector: result.op.selector().to_string(),
+            status: status.to_string(),
+            text: result.text,
+            error: result.error,
+            error_code: result.error_code.map(|code| code.name().to_string()),
+            element: result.element.map(Element::from),
+        }
+    }
+}
+
+impl From<terminator::EnvironmentInfo> for EnvironmentInfo {
+    fn from(env: terminator::EnvironmentInfo) -> Self {
+        EnvironmentInfo {
+            os: env.os,
+            os_version: env.os_version,
+            os_build: env.os_build,
+            theme: env.theme.map(|theme| match theme {
+                terminator::Theme::Light => "light".to_string(),
+                terminator::Theme::Dark => "dark".to_string(),
+            }),
+            animations_enabled: env.animations_enabled,
+            text_scale_factor: env.text_scale_factor,
+            display_scale_factor: env.display_scale_factor,
+            screen_reader_active: env.screen_reader_active,
+        }
+    }
+}
+
 impl From<terminator::UIElementAttributes> for UIElementAttributes {
     fn from(attrs: terminator::UIElementAttributes) -> Self {
         // Convert HashMap<String, Option<serde_json::Value>> to HashMap<String, Option<String>>
@@ -159,6 +670,75 @@ impl From<TreeBuildConfig> for terminator::platforms::TreeBuildConfig {
             timeout_per_operation_ms: config.timeout_per_operation_ms.map(|x| x as u64),
             yield_every_n_elements: config.yield_every_n_elements.map(|x| x as usize),
             batch_size: config.batch_size.map(|x| x as usize),
+            max_total_duration: config.max_total_duration_ms.map(|ms| std::time::Duration::from_millis(ms as u64)),
+            use_cache_request: config.use_cache_request.unwrap_or(true),
+        }
+    }
+}
+
+impl From<terminator::platforms::TreeBuildStats> for TreeBuildStats {
+    fn from(stats: terminator::platforms::TreeBuildStats) -> Self {
+        TreeBuildStats {
+            elements: stats.elements as u32,
+            max_depth: stats.max_depth as u32,
+            cached_elements: stats.cached_elements as u32,
+            cache_requests: stats.cache_requests as u32,
+            uncached_fallbacks: stats.uncached_fallbacks as u32,
+            errors: stats.errors as u32,
+            truncated_subtrees: stats.truncated_subtrees as u32,
+            duration_ms: stats.duration_ms as f64,
+        }
+    }
+} 
+
+impl From<RetryPolicy> for terminator::RetryPolicy {
+    fn from(p: RetryPolicy) -> Self {
+        use std::time::Duration;
+        let defaults = terminator::RetryPolicy::default();
+        let initial_delay = p.initial_delay_ms
+            .map(|ms| Duration::from_millis(ms as u64))
+            .unwrap_or(defaults.initial_delay);
+        terminator::RetryPolicy {
+            initial_delay,
+            max_delay: p.max_delay_ms
+                .map(|ms| Duration::from_millis(ms as u64))
+                .unwrap_or(terminator::RetryPolicy::DEFAULT_MAX_DELAY),
+            multiplier: p.multiplier.unwrap_or(defaults.multiplier),
+            max_attempts: p.max_attempts,
+            jitter: p.jitter.unwrap_or(defaults.jitter).clamp(0.0, 1.0),
+            attempt_timeout: p.attempt_timeout_ms.map(|ms| Duration::from_millis(ms as u64)),
         }
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RetryPolicy;
+    use std::time::Duration;
+
+    #[test]
+    fn test_backoff_without_max_delay_grows_to_the_default_ceiling() {
+        let policy: terminator::RetryPolicy = RetryPolicy {
+            initial_delay_ms: Some(100.0),
+            max_delay_ms: None,
+            multiplier: Some(2.0),
+            max_attempts: None,
+            jitter: None,
+            attempt_timeout_ms: None,
+        }
+        .into();
+        assert_eq!(policy.delay_for_attempt(1), Duration::from_millis(200));
+        assert_eq!(policy.delay_for_attempt(10), Duration::from_secs(5));
+
+        let fixed: terminator::RetryPolicy = RetryPolicy {
+            initial_delay_ms: None,
+            max_delay_ms: None,
+            multiplier: None,
+            max_attempts: None,
+            jitter: None,
+            attempt_timeout_ms: None,
+        }
+        .into();
+        assert_eq!(fixed.delay_for_attempt(10), Duration::from_millis(100));
+    }
+}
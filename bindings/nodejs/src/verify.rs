@@ -0,0 +1,123 @@
+use napi_derive::napi;
+use terminator::verify::SoftVerifier as TerminatorSoftVerifier;
+
+use crate::{Element, map_error};
+
+/// A single failed check.
+#[napi(object, js_name = "VerificationFailure")]
+pub struct VerificationFailure {
+    pub check: String,
+    pub message: String,
+}
+
+/// Outcome of all checks made by a SoftVerifier.
+#[napi(object, js_name = "VerificationReport")]
+pub struct VerificationReport {
+    pub step: String,
+    pub checks: u32,
+    pub failures: Vec<VerificationFailure>,
+}
+
+/// Collects failed checks across one step and throws them together.
+#[napi(js_name = "SoftVerifier")]
+pub struct SoftVerifier {
+    inner: TerminatorSoftVerifier,
+}
+
+#[napi]
+impl SoftVerifier {
+    /// Create a verifier for a named step.
+    /// 
+    /// @param {string} step - Name of the verified step, used in the failure report.
+    /// @param {boolean} [captureArtifacts=false] - Capture a screenshot of the element for each failed element check.
+    #[napi(constructor)]
+    pub fn new(step: String, capture_artifacts: Option<bool>) -> Self {
+        SoftVerifier {
+            inner: TerminatorSoftVerifier::new(step).with_artifacts(capture_artifacts.unwrap_or(false)),
+        }
+    }
+
+    /// Record a check on an arbitrary condition.
+    /// 
+    /// @param {string} description - What is being checked.
+    /// @param {boolean} condition - Whether the check passed.
+    /// @param {string} [message] - Explanation reported when the check fails.
+    /// @returns {boolean} The condition.
+    #[napi]
+    pub fn check(&mut self, description: String, condition: bool, message: Option<String>) -> bool {
+        self.inner.check(&description, condition, message.as_deref().unwrap_or(""))
+    }
+
+    /// Check that the element is visible.
+    #[napi]
+    pub fn expect_visible(&mut self, element: &Element) -> bool {
+        self.inner.expect_visible(&element.inner)
+    }
+
+    /// Check that the element is enabled.
+    #[napi]
+    pub fn expect_enabled(&mut self, element: &Element) -> bool {
+        self.inner.expect_enabled(&element.inner)
+    }
+
+    /// Check that the element's text contains the expected string.
+    #[napi]
+    pub fn expect_text(&mut self, element: &Element, expected: String) -> bool {
+        self.inner.expect_text(&element.inner, &expected)
+    }
+
+    /// Check that the element's name equals the expected string.
+    #[napi]
+    pub fn expect_name(&mut self, element: &Element, expected: String) -> bool {
+        self.inner.expect_name(&element.inner, &expected)
+    }
+
+    /// Check that the element's value equals the expected string.
+    #[napi]
+    pub fn expect_value(&mut self, element: &Element, expected: String) -> bool {
+        self.inner.expect_value(&element.inner, &expected)
+    }
+
+    /// Whether all checks so far passed.
+    #[napi]
+    pub fn passed(&self) -> bool {
+        self.inner.passed()
+    }
+
+    /// Get the report of all checks so far.
+    /// 
+    /// @returns {VerificationReport} The step name, check count and failures.
+    #[napi]
+    pub fn report(&self) -> VerificationReport {
+        let report = self.inner.report();
+        VerificationReport {
+            step: report.step,
+            checks: report.checks as u32,
+            failures: report.failures.into_iter()
+                .map(|f| VerificationFailure { check: f.check, message: f.message })
+                .collect(),
+        }
+    }
+
+    /// Write report.json and failure screenshots into a directory.
+    /// 
+    /// @param {string} dir - Target directory, created if missing.
+    /// @returns {Array<string>} Paths of the written files.
+    #[napi]
+    pub fn write_bundle(&self, dir: String) -> napi::Result<Vec<String>> {
+        self.inner.report().write_bundle(dir)
+            .map(|paths| paths.into_iter().map(|p| p.display().to_string()).collect())
+            .map_err(map_error)
+    }
+
+    /// Throw a VerificationFailedError listing every failed check, if any.
+    #[napi]
+    pub fn finish(&self) -> napi::Result<()> {
+        let report = self.inner.report();
+        if report.passed() {
+            Ok(())
+        } else {
+            Err(map_error(terminator::AutomationError::VerificationFailed(Box::new(report))))
+        }
+    }
+}
@@ -1,11 +1,45 @@
 // Re-export all types and interfaces from the original declaration file
 export * from './index.d';
+import { Element } from './index.d';
+
+/** Names of the UI events that can be listened to with `desktop.on(...)`. */
+export type UiEventName = 'focusChanged' | 'structureChanged' | 'propertyChanged';
+
+/** A UI event delivered to `desktop.on(...)` listeners. */
+export interface UiEvent {
+    kind: UiEventName;
+    /** The element the event was raised on. */
+    element?: Element;
+    /** The changed property for property changes, the change type for structure changes. */
+    detail?: string;
+    /** The new property value for property changes. */
+    value?: string;
+}
+
+declare module './index.d' {
+    interface Desktop {
+        /** Listen for UI events. The native watcher starts with the first listener. Windows only. */
+        on(event: UiEventName, listener: (event: UiEvent) => void): this;
+        addListener(event: UiEventName, listener: (event: UiEvent) => void): this;
+        /** Listen for the next UI event of a kind. */
+        once(event: UiEventName, listener: (event: UiEvent) => void): this;
+        /** Remove a listener. The native watcher stops with the last listener. */
+        off(event: UiEventName, listener: (event: UiEvent) => void): this;
+        removeListener(event: UiEventName, listener: (event: UiEvent) => void): this;
+        removeAllListeners(event?: UiEventName): this;
+    }
+}
 
 /** Thrown when an element is not found. */
 export class ElementNotFoundError extends Error {
     constructor(message: string);
 }
 
+/** Thrown when an element stays disabled, unfocusable or read-only while waiting to type into it. */
+export class ElementNotEditableError extends Error {
+    constructor(message: string);
+}
+
 /** Thrown when an operation times out. */
 export class TimeoutError extends Error {
     constructor(message: string);
@@ -36,6 +70,36 @@ export class InvalidArgumentError extends Error {
     constructor(message: string);
 }
 
+/** Thrown when the desktop session is locked, disconnected or non-interactive. */
+export class UIUnavailableError extends Error {
+    constructor(message: string);
+}
+
+/** Thrown when the idle watchdog aborts a stalled run. */
+export class StalledRunError extends Error {
+    constructor(message: string);
+}
+
+/** Thrown when a soft verification step has failed checks. */
+export class VerificationFailedError extends Error {
+    constructor(message: string);
+}
+
+/** Thrown when the target application runs as administrator and this process neither does nor has UIAccess. */
+export class ElevationRequiredError extends Error {
+    constructor(message: string);
+}
+
+/** Thrown on macOS when the Accessibility or Screen Recording permission the operation needs is not granted. */
+export class MissingPermissionError extends Error {
+    constructor(message: string);
+}
+
+/** Thrown when an element handle no longer refers to an element, e.g. a list item recreated by virtualization. */
+export class StaleElementError extends Error {
+    constructor(message: string);
+}
+
 /** Thrown for internal errors. */
 export class InternalError extends Error {
     constructor(message: string);
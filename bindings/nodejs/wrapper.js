@@ -1,3 +1,4 @@
+const EventEmitter = require('events');
 const native = require('./index.js');
 
 // Custom error classes
@@ -8,6 +9,13 @@ class ElementNotFoundError extends Error {
     }
 }
 
+class ElementNotEditableError extends Error {
+    constructor(message) {
+        super(message);
+        this.name = 'ElementNotEditableError';
+    }
+}
+
 class TimeoutError extends Error {
     constructor(message) {
         super(message);
@@ -50,6 +58,48 @@ class InvalidArgumentError extends Error {
     }
 }
 
+class UIUnavailableError extends Error {
+    constructor(message) {
+        super(message);
+        this.name = 'UIUnavailableError';
+    }
+}
+
+class StalledRunError extends Error {
+    constructor(message) {
+        super(message);
+        this.name = 'StalledRunError';
+    }
+}
+
+class VerificationFailedError extends Error {
+    constructor(message) {
+        super(message);
+        this.name = 'VerificationFailedError';
+    }
+}
+
+class ElevationRequiredError extends Error {
+    constructor(message) {
+        super(message);
+        this.name = 'ElevationRequiredError';
+    }
+}
+
+class MissingPermissionError extends Error {
+    constructor(message) {
+        super(message);
+        this.name = 'MissingPermissionError';
+    }
+}
+
+class StaleElementError extends Error {
+    constructor(message) {
+        super(message);
+        this.name = 'StaleElementError';
+    }
+}
+
 class InternalError extends Error {
     constructor(message) {
         super(message);
@@ -57,14 +107,43 @@ class InternalError extends Error {
     }
 }
 
-// Error mapping function
+// Native errors end with JSON details after this separator
+const DETAILS_SEPARATOR = '\n@@terminator-error@@';
+
+// Error mapping function. Mapped errors get the stable numeric `errorCode`
+// and the `context` (selector, root, timeout_ms, platform_code) of the failure
 function mapNativeError(error) {
     if (!error.message) return error;
-    
-    const message = error.message;
+
+    let message = error.message;
+    let details = {};
+    const at = message.indexOf(DETAILS_SEPARATOR);
+    if (at !== -1) {
+        try {
+            details = JSON.parse(message.slice(at + DETAILS_SEPARATOR.length));
+        } catch (_) {
+            // Keep the message without details
+        }
+        message = message.slice(0, at);
+    }
+
+    const mapped = typedError(message);
+    if (!mapped) {
+        error.message = message;
+    }
+    const result = mapped || error;
+    result.errorCode = details.code ?? null;
+    result.context = details.context ?? null;
+    return result;
+}
+
+function typedError(message) {
     if (message.startsWith('ELEMENT_NOT_FOUND:')) {
         return new ElementNotFoundError(message.replace('ELEMENT_NOT_FOUND:', '').trim());
     }
+    if (message.startsWith('ELEMENT_NOT_EDITABLE:')) {
+        return new ElementNotEditableError(message.replace('ELEMENT_NOT_EDITABLE:', '').trim());
+    }
     if (message.startsWith('OPERATION_TIMED_OUT:')) {
         return new TimeoutError(message.replace('OPERATION_TIMED_OUT:', '').trim());
     }
@@ -83,10 +162,28 @@ function mapNativeError(error) {
     if (message.startsWith('INVALID_ARGUMENT:')) {
         return new InvalidArgumentError(message.replace('INVALID_ARGUMENT:', '').trim());
     }
+    if (message.startsWith('UI_UNAVAILABLE:')) {
+        return new UIUnavailableError(message.replace('UI_UNAVAILABLE:', '').trim());
+    }
+    if (message.startsWith('STALLED_RUN:')) {
+        return new StalledRunError(message.replace('STALLED_RUN:', '').trim());
+    }
+    if (message.startsWith('VERIFICATION_FAILED:')) {
+        return new VerificationFailedError(message.replace('VERIFICATION_FAILED:', '').trim());
+    }
+    if (message.startsWith('ELEVATION_REQUIRED:')) {
+        return new ElevationRequiredError(message.replace('ELEVATION_REQUIRED:', '').trim());
+    }
+    if (message.startsWith('MISSING_PERMISSION:')) {
+        return new MissingPermissionError(message.replace('MISSING_PERMISSION:', '').trim());
+    }
+    if (message.startsWith('STALE_ELEMENT:')) {
+        return new StaleElementError(message.replace('STALE_ELEMENT:', '').trim());
+    }
     if (message.startsWith('INTERNAL_ERROR:')) {
         return new InternalError(message.replace('INTERNAL_ERROR:', '').trim());
     }
-    return error;
+    return null;
 }
 
 // Wrap native functions to handle errors
@@ -122,22 +219,108 @@ function wrapClassMethods(Class) {
 }
 
 // Wrap the native classes
+const AppHandle = wrapClassMethods(native.AppHandle);
+const AppSession = wrapClassMethods(native.AppSession);
 const Desktop = wrapClassMethods(native.Desktop);
 const Element = wrapClassMethods(native.Element);
+const Expect = wrapClassMethods(native.Expect);
+const HotkeyRegistration = wrapClassMethods(native.HotkeyRegistration);
 const Locator = wrapClassMethods(native.Locator);
+const Notification = wrapClassMethods(native.Notification);
+const SoftVerifier = wrapClassMethods(native.SoftVerifier);
+const UINodeWithHandles = wrapClassMethods(native.UINodeWithHandles);
+const UiEventSubscription = wrapClassMethods(native.UiEventSubscription);
+const WorkflowRecorder = wrapClassMethods(native.WorkflowRecorder);
+
+// UI events: desktop.on('focusChanged', cb) subscribes on the native side when the
+// first listener for an event is added and unsubscribes when the last one is removed.
+const UI_EVENTS = ['focusChanged', 'structureChanged', 'propertyChanged'];
+const uiEventState = new WeakMap();
+
+function uiEvents(desktop) {
+    let state = uiEventState.get(desktop);
+    if (!state) {
+        state = { emitter: new EventEmitter(), subscriptions: new Map() };
+        uiEventState.set(desktop, state);
+    }
+    return state;
+}
+
+function syncSubscription(desktop, event) {
+    if (!UI_EVENTS.includes(event)) return;
+    const { emitter, subscriptions } = uiEvents(desktop);
+    const listening = emitter.listenerCount(event) > 0;
+    if (listening && !subscriptions.has(event)) {
+        subscriptions.set(event, desktop.subscribeUiEvents([event], e => emitter.emit(event, e)));
+    } else if (!listening && subscriptions.has(event)) {
+        subscriptions.get(event).unsubscribe();
+        subscriptions.delete(event);
+    }
+}
+
+Desktop.prototype.on = function(event, listener) {
+    uiEvents(this).emitter.on(event, listener);
+    try {
+        syncSubscription(this, event);
+    } catch (error) {
+        uiEvents(this).emitter.off(event, listener);
+        throw error;
+    }
+    return this;
+};
+Desktop.prototype.addListener = Desktop.prototype.on;
+
+Desktop.prototype.once = function(event, listener) {
+    const desktop = this;
+    function onceListener(...args) {
+        desktop.off(event, onceListener);
+        listener.apply(desktop, args);
+    }
+    return this.on(event, onceListener);
+};
+
+Desktop.prototype.off = function(event, listener) {
+    uiEvents(this).emitter.off(event, listener);
+    syncSubscription(this, event);
+    return this;
+};
+Desktop.prototype.removeListener = Desktop.prototype.off;
+
+Desktop.prototype.removeAllListeners = function(event) {
+    const { emitter } = uiEvents(this);
+    emitter.removeAllListeners(event);
+    (event === undefined ? UI_EVENTS : [event]).forEach(e => syncSubscription(this, e));
+    return this;
+};
 
 // Export everything
 module.exports = {
+    AppHandle,
+    AppSession,
     Desktop,
     Element,
+    Expect,
+    HotkeyRegistration,
     Locator,
+    Notification,
+    SoftVerifier,
+    UINodeWithHandles,
+    UiEventSubscription,
+    WorkflowRecorder,
     // Export error classes
     ElementNotFoundError,
+    ElementNotEditableError,
     TimeoutError,
     PermissionDeniedError,
     PlatformError,
     UnsupportedOperationError,
     UnsupportedPlatformError,
     InvalidArgumentError,
+    UIUnavailableError,
+    StalledRunError,
+    VerificationFailedError,
+    ElevationRequiredError,
+    MissingPermissionError,
+    StaleElementError,
     InternalError
 }; 
\ No newline at end of file
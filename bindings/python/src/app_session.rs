@@ -0,0 +1,133 @@
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::*;
+use pyo3_async_runtimes::tokio as pyo3_tokio;
+use pyo3_async_runtimes::TaskLocals;
+use std::time::Duration;
+use ::terminator_core::AppSession as TerminatorAppSession;
+use crate::exceptions::automation_error_to_pyerr;
+use crate::element::UIElement;
+use crate::locator::Locator;
+
+/// Locators, trees and screenshots of one application, from Desktop.attach().
+/// The session follows the app when its process restarts.
+#[gen_stub_pyclass]
+#[pyclass(name = "AppSession")]
+#[derive(Clone)]
+pub struct AppSession {
+    pub inner: TerminatorAppSession,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl AppSession {
+    #[pyo3(name = "pid", text_signature = "($self)")]
+    /// Process ID of the attached process, attaching again if it restarted.
+    /// 
+    /// Returns:
+    ///     int: The process ID.
+    pub fn pid(&self) -> PyResult<u32> {
+        self.inner.pid().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "is_attached", text_signature = "($self)")]
+    /// Whether the attached process is still running.
+    /// 
+    /// Returns:
+    ///     bool: False once the process exited and no new one was found yet.
+    pub fn is_attached(&self) -> bool {
+        self.inner.is_attached()
+    }
+
+    /// Used as `with desktop.attach("notepad") as app:`, fails at once if the
+    /// application is not running.
+    pub fn __enter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        slf.inner.pid().map_err(|e| automation_error_to_pyerr(e))?;
+        Ok(slf)
+    }
+
+    /// Stops following the application once the session is no longer
+    /// referenced; exceptions propagate.
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    pub fn __exit__(
+        &self,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> bool {
+        false
+    }
+
+    #[pyo3(name = "application", text_signature = "($self)")]
+    /// The application element of the attached process.
+    /// 
+    /// Returns:
+    ///     UIElement: The application element.
+    pub fn application(&self) -> PyResult<UIElement> {
+        self.inner
+            .application()
+            .map(|e| UIElement { inner: e })
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "locator", text_signature = "($self, selector)")]
+    /// Create a locator that searches the attached application only.
+    /// 
+    /// Args:
+    ///     selector (str): The selector string.
+    /// 
+    /// Returns:
+    ///     Locator: A new locator for finding elements.
+    pub fn locator(&self, selector: &str) -> PyResult<Locator> {
+        self.inner
+            .locator(selector)
+            .map(|inner| Locator { inner })
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "get_window_tree", signature = (config=None), text_signature = "($self, config=None)")]
+    /// Get the UI tree of the application's window.
+    /// 
+    /// Args:
+    ///     config (Optional[TreeBuildConfig]): Optional configuration for tree building.
+    /// 
+    /// Returns:
+    ///     UINode: Complete UI tree starting from the window.
+    pub fn get_window_tree(&self, config: Option<crate::types::TreeBuildConfig>) -> PyResult<crate::types::UINode> {
+        self.inner
+            .get_window_tree(config.map(|c| c.into()))
+            .map(|tree| tree.into())
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "capture", text_signature = "($self)")]
+    /// Capture a screenshot of the application's window.
+    /// 
+    /// Returns:
+    ///     ScreenshotResult: The screenshot data.
+    pub fn capture(&self) -> PyResult<crate::types::ScreenshotResult> {
+        self.inner
+            .capture()
+            .map(crate::types::ScreenshotResult::from)
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "wait_for_attach", signature = (timeout_ms=None), text_signature = "($self, timeout_ms=None)")]
+    /// (async) Wait until the application runs again after it exited.
+    /// Returns at once while attached.
+    /// 
+    /// Args:
+    ///     timeout_ms (Optional[int]): Timeout in milliseconds (default 30000).
+    /// 
+    /// Returns:
+    ///     UIElement: The application element of the new process.
+    pub fn wait_for_attach<'py>(&self, py: Python<'py>, timeout_ms: Option<u64>) -> PyResult<Bound<'py, PyAny>> {
+        let session = self.inner.clone();
+        pyo3_tokio::future_into_py_with_locals(py, TaskLocals::with_running_loop(py)?, async move {
+            let element = session
+                .wait_for_attach(timeout_ms.map(Duration::from_millis))
+                .await
+                .map_err(|e| automation_error_to_pyerr(e))?;
+            Ok(UIElement { inner: element })
+        })
+    }
+}
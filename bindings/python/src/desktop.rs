@@ -2,12 +2,17 @@ use pyo3::prelude::*;
 use pyo3_stub_gen::derive::*;
 use pyo3_async_runtimes::tokio as pyo3_tokio;
 use pyo3_async_runtimes::TaskLocals;
+use std::collections::HashMap;
 use std::sync::Once;
 use ::terminator_core::Desktop as TerminatorDesktop;
 use crate::exceptions::automation_error_to_pyerr;
-use crate::types::{ScreenshotResult, CommandOutput};
+use crate::types::{parse_image_format, EncodedImage, ScreenshotResult, CommandOutput, ConsoleScreen, EnvironmentInfo, MonitorInfo, VirtualDesktop, BatchResult, AutomationCapabilities};
 use crate::element::UIElement;
 use crate::locator::Locator;
+use crate::process::AppHandle;
+use crate::notification::Notification;
+use crate::tree_handles::UINodeWithHandles;
+use crate::app_session::AppSession;
 
 /// Main entry point for desktop automation.
 #[gen_stub_pyclass]
@@ -96,6 +101,52 @@ impl Desktop {
             .map_err(|e| automation_error_to_pyerr(e))
     }
 
+    #[pyo3(name = "launch", signature = (path, args=None, env=None, cwd=None), text_signature = "($self, path, args=None, env=None, cwd=None)")]
+    /// Start an executable and keep a handle to its process.
+    /// 
+    /// Args:
+    ///     path (str): Path or name of the executable.
+    ///     args (Optional[List[str]]): Command line arguments.
+    ///     env (Optional[Dict[str, str]]): Variables added to the inherited environment.
+    ///     cwd (Optional[str]): Working directory.
+    /// 
+    /// Returns:
+    ///     AppHandle: A handle to wait for the window or exit, or to kill the process.
+    pub fn launch(
+        &self,
+        path: &str,
+        args: Option<Vec<String>>,
+        env: Option<std::collections::HashMap<String, String>>,
+        cwd: Option<String>,
+    ) -> PyResult<AppHandle> {
+        let args = args.unwrap_or_default();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let env = env.unwrap_or_default();
+        let env: Vec<(&str, &str)> = env.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        self.inner.launch(path, &args, &env, cwd.as_deref())
+            .map(|handle| AppHandle { inner: handle })
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "attach", text_signature = "($self, target)")]
+    /// Pin locators, trees and screenshots to one application. The session
+    /// follows the app when its process restarts.
+    /// 
+    /// Args:
+    ///     target (Union[str, int]): Application name, or process ID.
+    /// 
+    /// Returns:
+    ///     AppSession: The session, attached to the running app.
+    pub fn attach(&self, target: &Bound<'_, PyAny>) -> PyResult<AppSession> {
+        let target = match target.extract::<u32>() {
+            Ok(pid) => ::terminator_core::AppTarget::Pid(pid),
+            Err(_) => ::terminator_core::AppTarget::from(target.extract::<String>()?),
+        };
+        self.inner.attach(target)
+            .map(|inner| AppSession { inner })
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
     #[pyo3(name = "activate_application", text_signature = "($self, name)")]
     /// Activate an application by name.
     /// 
@@ -119,6 +170,44 @@ impl Desktop {
         Ok(Locator { inner: locator })
     }
 
+    #[pyo3(name = "locator_any", text_signature = "($self, target, selectors)")]
+    /// Create a locator for one logical target that tries several selectors in order.
+    /// 
+    /// With the selector cache enabled, the selector that resolved the target is
+    /// remembered per application version and tried first next time.
+    /// 
+    /// Args:
+    ///     target (str): Name of what is being looked for, e.g. "save button".
+    ///     selectors (List[str]): Candidate selectors, most specific first.
+    /// 
+    /// Returns:
+    ///     Locator: A locator for finding elements.
+    pub fn locator_any(&self, target: &str, selectors: Vec<String>) -> PyResult<Locator> {
+        let selectors: Vec<&str> = selectors.iter().map(String::as_str).collect();
+        Ok(Locator { inner: self.inner.locator_any(target, &selectors) })
+    }
+
+    #[pyo3(name = "enable_selector_cache", signature = (path=None), text_signature = "($self, path=None)")]
+    /// Remember which selector resolved each target of locator_any.
+    /// 
+    /// Args:
+    ///     path (Optional[str]): JSON file to persist learned selectors in. Kept in memory if omitted.
+    pub fn enable_selector_cache(&self, path: Option<String>) -> PyResult<()> {
+        let cache = match path {
+            Some(path) => ::terminator_core::SelectorCache::open(path)
+                .map_err(|e| automation_error_to_pyerr(e))?,
+            None => ::terminator_core::SelectorCache::in_memory(),
+        };
+        self.inner.set_selector_cache(Some(std::sync::Arc::new(cache)));
+        Ok(())
+    }
+
+    #[pyo3(name = "disable_selector_cache", text_signature = "($self)")]
+    /// Stop learning and preferring selectors for locator_any.
+    pub fn disable_selector_cache(&self) {
+        self.inner.set_selector_cache(None)
+    }
+
     #[pyo3(name = "capture_screen", text_signature = "($self)")]
     /// (async) Capture a screenshot of the primary monitor.
     /// 
@@ -133,6 +222,23 @@ impl Desktop {
         })
     }
 
+    #[pyo3(name = "capture_screen_as", text_signature = "($self, format)")]
+    /// (async) Capture the primary monitor encoded as an image file.
+    /// 
+    /// Args:
+    ///     format (str): "png", "jpeg", "jpeg:<quality>" or "webp".
+    /// 
+    /// Returns:
+    ///     EncodedImage: The encoded screenshot.
+    pub fn capture_screen_as<'py>(&self, py: Python<'py>, format: &str) -> PyResult<Bound<'py, PyAny>> {
+        let desktop = self.inner.clone();
+        let format = parse_image_format(format)?;
+        pyo3_tokio::future_into_py_with_locals(py, TaskLocals::with_running_loop(py)?, async move {
+            let result = desktop.capture_screen_as(format).await.map_err(|e| automation_error_to_pyerr(e))?;
+            Ok(EncodedImage::from(result))
+        })
+    }
+
     #[pyo3(name = "run_command", signature = (windows_command=None, unix_command=None))]
     #[pyo3(text_signature = "($self, windows_command, unix_command)")]
     /// (async) Run a shell command.
@@ -152,6 +258,42 @@ impl Desktop {
         })
     }
 
+    #[pyo3(name = "batch", signature = (ops, timeout_ms=None, stop_on_error=true))]
+    #[pyo3(text_signature = "($self, ops, timeout_ms=None, stop_on_error=True)")]
+    /// (async) Run several element operations in one call, resolving each
+    /// selector once.
+    /// 
+    /// Args:
+    ///     ops (List[Dict[str, str]]): Operations such as {"op": "click", "selector": "name:OK"}.
+    ///         op is "find", "click", "type_text" (with "text") or "get_text".
+    ///     timeout_ms (Optional[int]): How long each selector may take to resolve.
+    ///     stop_on_error (bool): Skip the remaining operations after one fails. Defaults to True.
+    /// 
+    /// Returns:
+    ///     List[BatchResult]: One result per operation.
+    pub fn batch<'py>(
+        &self,
+        py: Python<'py>,
+        ops: Vec<HashMap<String, String>>,
+        timeout_ms: Option<u64>,
+        stop_on_error: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut batch = self.inner.batch().stop_on_error(stop_on_error);
+        if let Some(timeout_ms) = timeout_ms {
+            batch = batch.timeout(std::time::Duration::from_millis(timeout_ms));
+        }
+        for (index, op) in ops.into_iter().enumerate() {
+            let op = serde_json::to_value(op)
+                .and_then(serde_json::from_value::<::terminator_core::BatchOp>)
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid operation {}: {}", index, e)))?;
+            batch = batch.push(op);
+        }
+        pyo3_tokio::future_into_py_with_locals(py, TaskLocals::with_running_loop(py)?, async move {
+            let results = batch.run().await;
+            Ok(results.into_iter().map(BatchResult::from).collect::<Vec<_>>())
+        })
+    }
+
     #[pyo3(name = "get_active_monitor_name", text_signature = "($self)")]
     /// (async) Get the name of the currently active monitor.
     /// 
@@ -165,6 +307,68 @@ impl Desktop {
         })
     }
 
+    #[pyo3(name = "monitors", text_signature = "($self)")]
+    /// List the connected monitors.
+    /// 
+    /// Returns:
+    ///     List[MonitorInfo]: Name, bounds, scale factor, refresh rate and primary flag of each monitor.
+    pub fn monitors(&self) -> PyResult<Vec<MonitorInfo>> {
+        self.inner.monitors()
+            .map(|monitors| monitors.into_iter().map(MonitorInfo::from).collect())
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "highlight_all", signature = (elements, color=None, duration_ms=None))]
+    #[pyo3(text_signature = "($self, elements, color, duration_ms)")]
+    /// Highlight several elements at once, e.g. everything a locator matched.
+    /// 
+    /// Args:
+    ///     elements (List[UIElement]): Elements to draw a border around.
+    ///     color (Optional[int]): BGR color code (32-bit integer). Default: 0x0000FF (red)
+    ///     duration_ms (Optional[int]): Duration in milliseconds. Default: 500
+    /// 
+    /// Returns:
+    ///     None
+    pub fn highlight_all(&self, elements: Vec<PyRef<UIElement>>, color: Option<u32>, duration_ms: Option<u64>) -> PyResult<()> {
+        let elements: Vec<_> = elements.iter().map(|e| e.inner.clone()).collect();
+        let duration = duration_ms.map(std::time::Duration::from_millis);
+        self.inner.highlight_all(&elements, color, duration).map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "environment", text_signature = "($self)")]
+    /// Get the OS version, theme, animation, scaling and screen reader settings.
+    /// Attach this to bug reports when automation behaves differently between machines.
+    /// 
+    /// Returns:
+    ///     EnvironmentInfo: The environment; settings that cannot be read are None.
+    pub fn environment(&self) -> PyResult<EnvironmentInfo> {
+        self.inner.environment()
+            .map(EnvironmentInfo::from)
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "capabilities", text_signature = "($self)")]
+    /// Report what this process can automate: its integrity level, whether it runs as
+    /// administrator and whether it has UIAccess. Without elevation or UIAccess, actions on
+    /// applications running as administrator raise ElevationRequiredError.
+    /// 
+    /// Returns:
+    ///     AutomationCapabilities: The capabilities of this process.
+    pub fn capabilities(&self) -> AutomationCapabilities {
+        AutomationCapabilities::from(self.inner.capabilities())
+    }
+
+    #[pyo3(name = "virtual_desktops", text_signature = "($self)")]
+    /// List the virtual desktops in Task View order (Windows only).
+    /// 
+    /// Returns:
+    ///     List[VirtualDesktop]: The desktops; the one shown has is_current set.
+    pub fn virtual_desktops(&self) -> PyResult<Vec<VirtualDesktop>> {
+        self.inner.virtual_desktops()
+            .map(|desktops| desktops.into_iter().map(VirtualDesktop::from).collect())
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
     #[pyo3(name = "capture_monitor_by_name", text_signature = "($self, name)")]
     /// (async) Capture a screenshot of a specific monitor.
     /// 
@@ -183,6 +387,25 @@ impl Desktop {
         })
     }
 
+    #[pyo3(name = "capture_monitor_by_name_as", text_signature = "($self, name, format)")]
+    /// (async) Capture a specific monitor encoded as an image file.
+    /// 
+    /// Args:
+    ///     name (str): The name of the monitor to capture.
+    ///     format (str): "png", "jpeg", "jpeg:<quality>" or "webp".
+    /// 
+    /// Returns:
+    ///     EncodedImage: The encoded screenshot.
+    pub fn capture_monitor_by_name_as<'py>(&self, py: Python<'py>, name: &str, format: &str) -> PyResult<Bound<'py, PyAny>> {
+        let desktop = self.inner.clone();
+        let name = name.to_string();
+        let format = parse_image_format(format)?;
+        pyo3_tokio::future_into_py_with_locals(py, TaskLocals::with_running_loop(py)?, async move {
+            let result = desktop.capture_monitor_by_name_as(&name, format).await.map_err(|e| automation_error_to_pyerr(e))?;
+            Ok(EncodedImage::from(result))
+        })
+    }
+
     #[pyo3(name = "ocr_image_path", text_signature = "($self, image_path)")]
     /// (async) Perform OCR on an image file.
     /// 
@@ -267,14 +490,41 @@ impl Desktop {
     #[pyo3(text_signature = "($self, url, browser)")]
     /// Open a URL in a browser.
     /// 
+    /// Returns as soon as the browser is launched. Use open_url_and_wait to get the loaded page.
+    /// 
     /// Args:
     ///     url (str): The URL to open.
     ///     browser (Optional[str]): The browser to use.
-    pub fn open_url(&self, url: &str, browser: Option<&str>) -> PyResult<()> {
+    /// 
+    /// Returns:
+    ///     UIElement: The browser window.
+    pub fn open_url(&self, url: &str, browser: Option<&str>) -> PyResult<UIElement> {
         self.inner.open_url(url, browser)
+            .map(|e| UIElement { inner: e })
             .map_err(|e| automation_error_to_pyerr(e))
     }
 
+    #[pyo3(name = "open_url_and_wait", signature = (url, browser=None, timeout_ms=None), text_signature = "($self, url, browser=None, timeout_ms=None)")]
+    /// (async) Open a URL and wait until the browser shows the page.
+    /// 
+    /// Args:
+    ///     url (str): The URL to open.
+    ///     browser (Optional[str]): The browser to use.
+    ///     timeout_ms (Optional[int]): How long to wait in milliseconds. Defaults to 30 seconds.
+    /// 
+    /// Returns:
+    ///     UIElement: The page's document element, ready for chaining locators.
+    pub fn open_url_and_wait<'py>(&self, py: Python<'py>, url: String, browser: Option<String>, timeout_ms: Option<u64>) -> PyResult<Bound<'py, PyAny>> {
+        let desktop = self.inner.clone();
+        pyo3_tokio::future_into_py_with_locals(py, TaskLocals::with_running_loop(py)?, async move {
+            let page = desktop
+                .open_url_and_wait(&url, browser.as_deref(), timeout_ms.map(std::time::Duration::from_millis))
+                .await
+                .map_err(|e| automation_error_to_pyerr(e))?;
+            Ok(UIElement { inner: page })
+        })
+    }
+
     #[pyo3(name = "open_file", text_signature = "($self, file_path)")]
     /// Open a file with its default application.
     /// 
@@ -323,4 +573,238 @@ impl Desktop {
             .map(crate::types::UINode::from)
             .map_err(|e| automation_error_to_pyerr(e))
     }
+
+    #[pyo3(name = "get_window_tree_with_stats", signature = (pid, title=None, config=None))]
+    #[pyo3(text_signature = "($self, pid, title, config)")]
+    /// Get the UI tree for a window, with how it was built: element count,
+    /// elements served from cache requests and duration.
+    /// 
+    /// Args:
+    ///     pid (int): Process ID of the target application.
+    ///     title (Optional[str]): Optional window title filter.
+    ///     config (Optional[TreeBuildConfig]): Optional configuration for tree building.
+    /// 
+    /// Returns:
+    ///     Tuple[UINode, TreeBuildStats]: The tree and its build statistics.
+    pub fn get_window_tree_with_stats(&self, pid: u32, title: Option<&str>, config: Option<crate::types::TreeBuildConfig>) -> PyResult<(crate::types::UINode, crate::types::TreeBuildStats)> {
+        let rust_config = config.map(|c| c.into());
+        self.inner.get_window_tree_with_stats(pid, title, rust_config)
+            .map(|(tree, stats)| (tree.into(), stats.into()))
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "get_window_tree_with_handles", signature = (pid, title=None, config=None))]
+    #[pyo3(text_signature = "($self, pid, title, config)")]
+    /// Get the UI tree for a window, keeping the live element behind each node
+    /// so a node found in the tree can be acted on without another search.
+    /// 
+    /// Args:
+    ///     pid (int): Process ID of the target application.
+    ///     title (Optional[str]): Optional window title filter.
+    ///     config (Optional[TreeBuildConfig]): Optional configuration for tree building.
+    /// 
+    /// Returns:
+    ///     UINodeWithHandles: The tree with an element for each node.
+    pub fn get_window_tree_with_handles(&self, pid: u32, title: Option<&str>, config: Option<crate::types::TreeBuildConfig>) -> PyResult<UINodeWithHandles> {
+        let rust_config = config.map(|c| c.into());
+        self.inner.get_window_tree_with_handles(pid, title, rust_config)
+            .map(|inner| UINodeWithHandles { inner })
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "get_all_window_trees", signature = (config=None))]
+    #[pyo3(text_signature = "($self, config)")]
+    /// Get the UI trees of all visible top-level windows, built concurrently.
+    /// 
+    /// Args:
+    ///     config (Optional[TreeBuildConfig]): Optional configuration for tree building.
+    /// 
+    /// Returns:
+    ///     List[WindowTree]: Each window with its UI tree, in z-order.
+    pub fn get_all_window_trees(&self, config: Option<crate::types::TreeBuildConfig>) -> PyResult<Vec<crate::types::WindowTree>> {
+        let rust_config = config.map(|c| c.into());
+        self.inner.get_all_window_trees(rust_config)
+            .map(|trees| trees.into_iter().map(crate::types::WindowTree::from).collect())
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "session_state", text_signature = "($self)")]
+    /// Get the state of the desktop session this process runs in.
+    ///
+    /// Returns:
+    ///     str: One of 'active', 'locked', 'disconnected', 'service' or 'unknown'.
+    pub fn session_state(&self) -> PyResult<String> {
+        self.inner.session_state()
+            .map(|state| state.to_string())
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "ensure_ui_available", text_signature = "($self)")]
+    /// Raise UIUnavailableError if the session is locked, disconnected or non-interactive.
+    pub fn ensure_ui_available(&self) -> PyResult<()> {
+        self.inner.ensure_ui_available()
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "attach_to_console", text_signature = "($self)")]
+    /// Reconnect the current remote session to the console so it stays interactive
+    /// after the RDP client disconnects. Windows only, requires administrator rights.
+    pub fn attach_to_console(&self) -> PyResult<()> {
+        self.inner.attach_to_console()
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "read_console", text_signature = "($self, pid)")]
+    /// Read the visible screen of the console attached to a process (e.g. cmd.exe). Windows only.
+    /// 
+    /// Args:
+    ///     pid (int): Process id of the console application.
+    /// 
+    /// Returns:
+    ///     ConsoleScreen: The visible lines and cursor position.
+    pub fn read_console(&self, pid: u32) -> PyResult<ConsoleScreen> {
+        self.inner.read_console(pid)
+            .map(ConsoleScreen::from)
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "send_console_input", text_signature = "($self, pid, text)")]
+    /// Type text into the console attached to a process. Newlines are sent as Enter. Windows only.
+    /// 
+    /// Args:
+    ///     pid (int): Process id of the console application.
+    ///     text (str): The text to type.
+    pub fn send_console_input(&self, pid: u32, text: &str) -> PyResult<()> {
+        self.inner.send_console_input(pid, text)
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "wait_for_console_text", text_signature = "($self, pid, text, timeout_ms)")]
+    /// (async) Wait until text appears on the screen of the console attached to a process.
+    /// 
+    /// Args:
+    ///     pid (int): Process id of the console application.
+    ///     text (str): The text to wait for.
+    ///     timeout_ms (int): How long to wait in milliseconds.
+    /// 
+    /// Returns:
+    ///     ConsoleScreen: The screen that contained the text.
+    pub fn wait_for_console_text<'py>(&self, py: Python<'py>, pid: u32, text: String, timeout_ms: u64) -> PyResult<Bound<'py, PyAny>> {
+        let desktop = self.inner.clone();
+        pyo3_tokio::future_into_py_with_locals(py, TaskLocals::with_running_loop(py)?, async move {
+            let screen = desktop.wait_for_console_text(pid, &text, std::time::Duration::from_millis(timeout_ms))
+                .await
+                .map_err(|e| automation_error_to_pyerr(e))?;
+            Ok(ConsoleScreen::from(screen))
+        })
+    }
+
+    #[pyo3(name = "notifications", text_signature = "($self)")]
+    /// The notifications on screen: Windows toasts or macOS Notification Center banners.
+    /// 
+    /// Returns:
+    ///     List[Notification]: The notifications, with their app, title, body and actions.
+    pub fn notifications(&self) -> PyResult<Vec<Notification>> {
+        self.inner.notifications()
+            .map(|notifications| notifications.into_iter().map(|inner| Notification { inner }).collect())
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "wait_for_notification", text_signature = "($self, text, timeout_ms)")]
+    /// (async) Wait until a notification whose app name, title or body contains text is on screen.
+    /// 
+    /// Args:
+    ///     text (str): The text to wait for, ignoring case.
+    ///     timeout_ms (int): How long to wait in milliseconds.
+    /// 
+    /// Returns:
+    ///     Notification: The first matching notification.
+    pub fn wait_for_notification<'py>(&self, py: Python<'py>, text: String, timeout_ms: u64) -> PyResult<Bound<'py, PyAny>> {
+        let desktop = self.inner.clone();
+        pyo3_tokio::future_into_py_with_locals(py, TaskLocals::with_running_loop(py)?, async move {
+            let inner = desktop.wait_for_notification(&text, std::time::Duration::from_millis(timeout_ms))
+                .await
+                .map_err(|e| automation_error_to_pyerr(e))?;
+            Ok(Notification { inner })
+        })
+    }
+
+    #[pyo3(name = "wait_for_idle", signature = (pid, timeout_ms, quiet_ms=None))]
+    #[pyo3(text_signature = "($self, pid, timeout_ms, quiet_ms=None)")]
+    /// (async) Wait until an application is idle: started up, its UI thread responsive,
+    /// and no elements added or removed for a quiet period. Windows only.
+    /// 
+    /// Args:
+    ///     pid (int): Process id of the application.
+    ///     timeout_ms (int): How long to wait in milliseconds.
+    ///     quiet_ms (Optional[int]): Quiet period without UI structure changes in milliseconds. Default: 500.
+    pub fn wait_for_idle<'py>(&self, py: Python<'py>, pid: u32, timeout_ms: u64, quiet_ms: Option<u64>) -> PyResult<Bound<'py, PyAny>> {
+        let desktop = self.inner.clone();
+        let quiet = quiet_ms
+            .map(std::time::Duration::from_millis)
+            .unwrap_or(::terminator_core::DEFAULT_IDLE_QUIET_PERIOD);
+        pyo3_tokio::future_into_py_with_locals(py, TaskLocals::with_running_loop(py)?, async move {
+            desktop.wait_for_idle_with(pid, std::time::Duration::from_millis(timeout_ms), quiet)
+                .await
+                .map_err(|e| automation_error_to_pyerr(e))?;
+            Ok(())
+        })
+    }
+
+    #[pyo3(name = "idle_timeout", text_signature = "($self, timeout_ms)")]
+    /// Abort the run with StalledRunError if no action completes successfully for the given time.
+    /// 
+    /// A screenshot, the foreground window and the active application's tree are
+    /// captured when the watchdog fires. Calling this again replaces the previous watchdog.
+    /// 
+    /// Args:
+    ///     timeout_ms (int): Idle time in milliseconds before the run is considered stalled.
+    pub fn idle_timeout(&self, timeout_ms: u64) {
+        self.inner.idle_timeout(std::time::Duration::from_millis(timeout_ms), None)
+    }
+
+    #[pyo3(name = "clear_idle_timeout", text_signature = "($self)")]
+    /// Disarm the idle watchdog, if one is running.
+    pub fn clear_idle_timeout(&self) {
+        self.inner.clear_idle_timeout()
+    }
+
+    #[pyo3(name = "block_user_input", text_signature = "($self, enabled)")]
+    /// Block physical keyboard and mouse input until called again with False.
+    /// 
+    /// Input sent by terminator still gets through, and Ctrl+Alt+Del always
+    /// releases the block. Windows only.
+    /// 
+    /// Args:
+    ///     enabled (bool): Whether to block input.
+    pub fn block_user_input(&self, enabled: bool) -> PyResult<()> {
+        self.inner.block_user_input(enabled).map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "set_auto_input_guard", text_signature = "($self, enabled)")]
+    /// Block physical input while each element action runs. Windows only.
+    /// 
+    /// Args:
+    ///     enabled (bool): Whether to guard every action.
+    pub fn set_auto_input_guard(&self, enabled: bool) {
+        self.inner.set_auto_input_guard(enabled)
+    }
+
+    #[pyo3(name = "set_input_profile", text_signature = "($self, profile)")]
+    /// Pace synthetic keyboard and mouse input. Some applications drop keys
+    /// typed instantly or only react to a pointer that hovered first.
+    /// 
+    /// Args:
+    ///     profile (str): "instant", "fast" (the default) or "human" for
+    ///         irregular typing, curved pointer movement and a pause before clicks.
+    pub fn set_input_profile(&self, profile: &str) -> PyResult<()> {
+        let profile = ::terminator_core::InputProfile::named(profile).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown input profile '{}'. Expected 'instant', 'fast' or 'human'",
+                profile
+            ))
+        })?;
+        self.inner.set_input_profile(profile);
+        Ok(())
+    }
 } 
\ No newline at end of file
@@ -2,7 +2,10 @@ use pyo3::prelude::*;
 use pyo3_stub_gen::derive::*;
 use ::terminator_core::element::UIElement as TerminatorUIElement;
 use crate::exceptions::automation_error_to_pyerr;
-use crate::types::{UIElementAttributes, Bounds, ClickResult};
+use crate::types::{UIElementAttributes, AuditReport, Bounds, ClickResult, Coordinates, FormFillReport, MonitorInfo, VerifiedActionResult};
+use pyo3::exceptions::PyKeyError;
+use pyo3::types::PyDict;
+use pyo3::IntoPyObjectExt;
 use serde::ser::{Serialize, Serializer, SerializeStruct};
 
 /// Represents a UI element in the desktop UI tree.
@@ -77,6 +80,51 @@ impl UIElement {
         })
     }
 
+    /// An attribute by name, e.g. element["name"]: role, name, id, label,
+    /// value, description, is_keyboard_focusable, or a platform property.
+    /// Raises KeyError for attributes the element does not have.
+    pub fn __getitem__<'py>(&self, py: Python<'py>, key: &str) -> PyResult<Bound<'py, PyAny>> {
+        let attrs = self.inner.attributes();
+        let value = match key {
+            "role" => attrs.role.into_bound_py_any(py)?,
+            "name" => attrs.name.into_bound_py_any(py)?,
+            "id" => self.inner.id().into_bound_py_any(py)?,
+            "label" => attrs.label.into_bound_py_any(py)?,
+            "value" => attrs.value.into_bound_py_any(py)?,
+            "description" => attrs.description.into_bound_py_any(py)?,
+            "is_keyboard_focusable" => attrs.is_keyboard_focusable.into_bound_py_any(py)?,
+            _ => match attrs.properties.get(key) {
+                Some(property) => property
+                    .as_ref()
+                    .map(|value| match value {
+                        serde_json::Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    })
+                    .into_bound_py_any(py)?,
+                None => return Err(PyKeyError::new_err(key.to_string())),
+            },
+        };
+        Ok(value)
+    }
+
+    /// Activates the element's window for the `with` block:
+    /// `with desktop.application("notepad") as app:`.
+    pub fn __enter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        slf.inner.activate_window().map_err(|e| automation_error_to_pyerr(e))?;
+        Ok(slf)
+    }
+
+    /// Leaves the application running; exceptions propagate.
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    pub fn __exit__(
+        &self,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> bool {
+        false
+    }
+
     #[pyo3(name = "children", text_signature = "($self)")]
     /// Get child elements.
     /// 
@@ -99,6 +147,54 @@ impl UIElement {
             .map_err(|e| automation_error_to_pyerr(e))
     }
 
+    #[pyo3(name = "ancestors", text_signature = "($self)")]
+    /// Get the parent, its parent and so on up to the root, nearest first.
+    /// 
+    /// Returns:
+    ///     List[UIElement]: The ancestor elements.
+    pub fn ancestors(&self) -> PyResult<Vec<UIElement>> {
+        self.inner.ancestors()
+            .map(|ancestors| ancestors.into_iter().map(|e| UIElement { inner: e }).collect())
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "next_sibling", text_signature = "($self)")]
+    /// Get the element after this one among its parent's children.
+    /// 
+    /// Returns:
+    ///     Optional[UIElement]: The next sibling, or None for the last child.
+    pub fn next_sibling(&self) -> PyResult<Option<UIElement>> {
+        self.inner.next_sibling()
+            .map(|opt| opt.map(|e| UIElement { inner: e }))
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "previous_sibling", text_signature = "($self)")]
+    /// Get the element before this one among its parent's children.
+    /// 
+    /// Returns:
+    ///     Optional[UIElement]: The previous sibling, or None for the first child.
+    pub fn previous_sibling(&self) -> PyResult<Option<UIElement>> {
+        self.inner.previous_sibling()
+            .map(|opt| opt.map(|e| UIElement { inner: e }))
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "closest", text_signature = "($self, selector)")]
+    /// Get the nearest of this element and its ancestors matching a selector,
+    /// e.g. the dialog a button is in with "role:Window".
+    /// 
+    /// Args:
+    ///     selector (str): A role, id, name, text, nativeid or classname selector, or a not/and/or combination of them.
+    /// 
+    /// Returns:
+    ///     Optional[UIElement]: The matching element, if any.
+    pub fn closest(&self, selector: &str) -> PyResult<Option<UIElement>> {
+        self.inner.closest(selector)
+            .map(|opt| opt.map(|e| UIElement { inner: e }))
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
     #[pyo3(name = "bounds", text_signature = "($self)")]
     /// Get element bounds (x, y, width, height).
     /// 
@@ -109,6 +205,70 @@ impl UIElement {
         Ok(Bounds { x, y, width, height })
     }
 
+    #[pyo3(name = "monitor", text_signature = "($self)")]
+    /// Get the monitor this element is displayed on.
+    /// 
+    /// Returns:
+    ///     Optional[MonitorInfo]: The monitor, or None if the element is off-screen.
+    pub fn monitor(&self) -> PyResult<Option<MonitorInfo>> {
+        self.inner.monitor()
+            .map(|monitor| monitor.map(MonitorInfo::from))
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "center", text_signature = "($self)")]
+    /// Get the center of the element in physical screen pixels.
+    /// 
+    /// Returns:
+    ///     Coordinates: The element's center point.
+    pub fn center(&self) -> PyResult<Coordinates> {
+        let p = self.inner.center().map_err(|e| automation_error_to_pyerr(e))?;
+        Ok(Coordinates { x: p.x, y: p.y })
+    }
+
+    #[pyo3(name = "point_at", signature = (anchor, offset_x=0.0, offset_y=0.0))]
+    #[pyo3(text_signature = "($self, anchor, offset_x, offset_y)")]
+    /// Get a point relative to the element, e.g. 10px left of its center.
+    /// 
+    /// Args:
+    ///     anchor (str): One of 'center', 'top_left', 'top', 'top_right', 'left', 'right', 'bottom_left', 'bottom', 'bottom_right', or a percentage like '30%,50%'.
+    ///     offset_x (float): Horizontal offset in pixels.
+    ///     offset_y (float): Vertical offset in pixels.
+    /// 
+    /// Returns:
+    ///     Coordinates: The resulting screen point.
+    pub fn point_at(&self, anchor: &str, offset_x: f64, offset_y: f64) -> PyResult<Coordinates> {
+        let anchor: ::terminator_core::Anchor = anchor.parse()
+            .map_err(|e: String| automation_error_to_pyerr(::terminator_core::AutomationError::InvalidArgument(e)))?;
+        let p = self.inner.point_at(anchor, (offset_x, offset_y)).map_err(|e| automation_error_to_pyerr(e))?;
+        Ok(Coordinates { x: p.x, y: p.y })
+    }
+
+    #[pyo3(name = "intersects", text_signature = "($self, other)")]
+    /// Check whether this element's bounds overlap another element's bounds.
+    /// 
+    /// Args:
+    ///     other (UIElement): The element to test against.
+    /// 
+    /// Returns:
+    ///     bool: True if the bounds intersect.
+    pub fn intersects(&self, other: &UIElement) -> PyResult<bool> {
+        self.inner.intersects(&other.inner).map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "contains_point", text_signature = "($self, x, y)")]
+    /// Check whether a screen point lies inside this element.
+    /// 
+    /// Args:
+    ///     x (float): X coordinate in pixels.
+    ///     y (float): Y coordinate in pixels.
+    /// 
+    /// Returns:
+    ///     bool: True if the point is inside the element's bounds.
+    pub fn contains_point(&self, x: f64, y: f64) -> PyResult<bool> {
+        self.inner.contains_point(x, y).map_err(|e| automation_error_to_pyerr(e))
+    }
+
     #[pyo3(name = "click", text_signature = "($self)")]
     /// Click on this element.
     /// 
@@ -120,6 +280,99 @@ impl UIElement {
             .map_err(|e| automation_error_to_pyerr(e))
     }
 
+    #[pyo3(name = "click_with_strategy", text_signature = "($self, strategy)")]
+    /// Click on this element delivering the click one way only.
+    /// 
+    /// Args:
+    ///     strategy (str): "uia_pattern" (the accessibility action), "send_input_cursor" (real cursor input) or "post_message" (window messages, Windows only).
+    /// 
+    /// Returns:
+    ///     ClickResult: Result of the click operation.
+    pub fn click_with_strategy(&self, strategy: &str) -> PyResult<ClickResult> {
+        let strategy = strategy.parse().map_err(automation_error_to_pyerr)?;
+        self.inner.click_with_strategy(strategy)
+            .map(ClickResult::from)
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "click_escalating", signature = (strategies=None))]
+    #[pyo3(text_signature = "($self, strategies)")]
+    /// Click on this element trying strategies in order until one delivers the click.
+    /// 
+    /// Args:
+    ///     strategies (Optional[List[str]]): Strategies to try, by default "uia_pattern", "send_input_cursor" then "post_message".
+    /// 
+    /// Returns:
+    ///     ClickResult: Result of the click, with the strategy that worked.
+    pub fn click_escalating(&self, strategies: Option<Vec<String>>) -> PyResult<ClickResult> {
+        let strategies = match strategies {
+            Some(names) => names
+                .iter()
+                .map(|name| name.parse())
+                .collect::<Result<Vec<::terminator_core::ActionStrategy>, _>>()
+                .map_err(automation_error_to_pyerr)?,
+            None => ::terminator_core::ActionStrategy::ESCALATION.to_vec(),
+        };
+        self.inner.click_escalating(&strategies)
+            .map(ClickResult::from)
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "click_at_anchor", signature = (anchor, offset_x=0.0, offset_y=0.0))]
+    #[pyo3(text_signature = "($self, anchor, offset_x, offset_y)")]
+    /// Click at an anchor point of this element instead of its center.
+    /// The point is kept inside the element before the offset is applied.
+    /// 
+    /// Args:
+    ///     anchor (str): One of 'center', 'top_left', 'top', 'top_right', 'left', 'right', 'bottom_left', 'bottom', 'bottom_right', or a percentage like '30%,50%'.
+    ///     offset_x (float): Horizontal offset in pixels.
+    ///     offset_y (float): Vertical offset in pixels.
+    /// 
+    /// Returns:
+    ///     ClickResult: Result of the click, with the clicked point in `coordinates`.
+    pub fn click_at_anchor(&self, anchor: &str, offset_x: f64, offset_y: f64) -> PyResult<ClickResult> {
+        let anchor: ::terminator_core::Anchor = anchor.parse()
+            .map_err(|e: String| automation_error_to_pyerr(::terminator_core::AutomationError::InvalidArgument(e)))?;
+        self.inner.click_at_anchor(anchor, (offset_x, offset_y))
+            .map(ClickResult::from)
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "click_and_expect", signature = (condition, value=None, timeout_ms=2000))]
+    #[pyo3(text_signature = "($self, condition, value, timeout_ms)")]
+    /// Click this element and wait for the expected effect, retrying with a
+    /// physical mouse click if the first click had no effect.
+    /// 
+    /// Args:
+    ///     condition (str): One of 'gone', 'focused', 'enabled', 'disabled', 'name_equals', 'value_equals', 'value_contains', 'text_contains'.
+    ///     value (Optional[str]): Expected string for the equals and contains conditions.
+    ///     timeout_ms (int): How long to wait for the effect after each attempt.
+    /// 
+    /// Returns:
+    ///     VerifiedActionResult: Which attempt and method produced the effect.
+    pub fn click_and_expect(&self, condition: &str, value: Option<String>, timeout_ms: u64) -> PyResult<VerifiedActionResult> {
+        let condition = ::terminator_core::Condition::from_kind(condition, value)
+            .map_err(|e| automation_error_to_pyerr(::terminator_core::AutomationError::InvalidArgument(e)))?;
+        self.inner.click_and_expect(&condition, std::time::Duration::from_millis(timeout_ms))
+            .map(VerifiedActionResult::from)
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "type_text_verified", text_signature = "($self, text)")]
+    /// Replace this element's value with text and check that it was taken,
+    /// retrying by pasting and then through the value pattern.
+    /// 
+    /// Args:
+    ///     text (str): The text to enter.
+    /// 
+    /// Returns:
+    ///     VerifiedActionResult: Which attempt and method set the value.
+    pub fn type_text_verified(&self, text: &str) -> PyResult<VerifiedActionResult> {
+        self.inner.type_text_verified(text)
+            .map(VerifiedActionResult::from)
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
     #[pyo3(name = "double_click", text_signature = "($self)")]
     /// Double click on this element.
     /// 
@@ -191,18 +444,22 @@ impl UIElement {
         self.inner.text(max_depth.unwrap_or(1)).map_err(|e| automation_error_to_pyerr(e))
     }
 
-    #[pyo3(name = "type_text", signature = (text, use_clipboard=None))]
-    #[pyo3(text_signature = "($self, text, use_clipboard)")]
+    #[pyo3(name = "type_text", signature = (text, use_clipboard=None, wait_for_editable=true))]
+    #[pyo3(text_signature = "($self, text, use_clipboard, wait_for_editable)")]
     /// Type text into this element.
     /// 
     /// Args:
     ///     text (str): The text to type.
     ///     use_clipboard (Optional[bool]): Whether to use clipboard for pasting.
+    ///     wait_for_editable (bool): Wait until the element is enabled, focusable and not read-only before typing.
     /// 
     /// Returns:
     ///     None
-    pub fn type_text(&self, text: &str, use_clipboard: Option<bool>) -> PyResult<()> {
-        self.inner.type_text(text, use_clipboard.unwrap_or(false)).map_err(|e| automation_error_to_pyerr(e))
+    pub fn type_text(&self, text: &str, use_clipboard: Option<bool>, wait_for_editable: bool) -> PyResult<()> {
+        let editable_timeout = wait_for_editable.then_some(::terminator_core::element::DEFAULT_EDITABLE_TIMEOUT);
+        self.inner
+            .type_text_with_wait(text, use_clipboard.unwrap_or(false), editable_timeout)
+            .map_err(|e| automation_error_to_pyerr(e))
     }
 
     #[pyo3(name = "press_key", text_signature = "($self, key)")]
@@ -241,6 +498,144 @@ impl UIElement {
         self.inner.perform_action(action).map_err(|e| automation_error_to_pyerr(e))
     }
 
+    #[pyo3(name = "invoke", text_signature = "($self)")]
+    /// Trigger the element's default action without moving the mouse.
+    pub fn invoke(&self) -> PyResult<()> {
+        self.inner.invoke().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "toggle", text_signature = "($self)")]
+    /// Move a check box or toggle button to its next state without clicking it.
+    pub fn toggle(&self) -> PyResult<()> {
+        self.inner.toggle().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "expand", text_signature = "($self)")]
+    /// Expand a combo box, menu item or tree item.
+    pub fn expand(&self) -> PyResult<()> {
+        self.inner.expand().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "collapse", text_signature = "($self)")]
+    /// Collapse a combo box, menu item or tree item.
+    pub fn collapse(&self) -> PyResult<()> {
+        self.inner.collapse().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "select", text_signature = "($self)")]
+    /// Select the element in its list, combo box or tab strip without clicking it.
+    pub fn select(&self) -> PyResult<()> {
+        self.inner.select().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "expect", signature = (timeout_ms=None, screenshot_dir=None))]
+    #[pyo3(text_signature = "($self, timeout_ms, screenshot_dir)")]
+    /// Start assertions on this element that retry until they hold, e.g.
+    /// element.expect().to_have_text("Saved").
+    /// 
+    /// Args:
+    ///     timeout_ms (Optional[int]): How long to retry, 5000 by default.
+    ///     screenshot_dir (Optional[str]): Where failure screenshots go, the temp directory by default.
+    /// 
+    /// Returns:
+    ///     Expect: The assertions.
+    pub fn expect(&self, timeout_ms: Option<u64>, screenshot_dir: Option<String>) -> crate::expect::Expect {
+        let mut inner = ::terminator_core::expect(&self.inner);
+        if let Some(timeout_ms) = timeout_ms {
+            inner = inner.with_timeout(std::time::Duration::from_millis(timeout_ms));
+        }
+        if let Some(dir) = screenshot_dir {
+            inner = inner.with_screenshot_dir(Some(dir.into()));
+        }
+        crate::expect::Expect { inner }
+    }
+
+    #[pyo3(name = "selected_text", text_signature = "($self)")]
+    /// Text selected in an edit control or document.
+    /// 
+    /// Returns:
+    ///     str: The selection, empty when nothing is selected.
+    pub fn selected_text(&self) -> PyResult<String> {
+        self.inner.selected_text().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "caret_position", text_signature = "($self)")]
+    /// Character offset of the caret in an edit control or document.
+    /// 
+    /// Returns:
+    ///     Optional[int]: The offset, or None when the element shows no caret.
+    pub fn caret_position(&self) -> PyResult<Option<usize>> {
+        self.inner.caret_position().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "select_range", text_signature = "($self, start, end)")]
+    /// Select the characters from offset start up to end. start == end places the caret.
+    /// 
+    /// Args:
+    ///     start (int): Offset of the first selected character.
+    ///     end (int): Offset just past the last selected character.
+    /// 
+    /// Returns:
+    ///     None
+    pub fn select_range(&self, start: usize, end: usize) -> PyResult<()> {
+        self.inner.select_range(start, end).map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "text_with_formatting", text_signature = "($self)")]
+    /// The text of an edit control or document as runs of the same font, weight, style and colors.
+    /// 
+    /// Returns:
+    ///     List[TextRun]: The runs, in document order.
+    pub fn text_with_formatting(&self) -> PyResult<Vec<crate::types::TextRun>> {
+        self.inner
+            .text_with_formatting()
+            .map(|runs| runs.into_iter().map(Into::into).collect())
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "fill_form", text_signature = "($self, fields)")]
+    /// Fill the controls below this element (a window or group) by label.
+    /// Each label is matched to a control through the label it declares,
+    /// its name, the text next to it, or a fuzzy match of its name, and set
+    /// the way its type needs.
+    /// 
+    /// Args:
+    ///     fields (Dict[str, str]): Values by label, e.g. {"First name": "Ada", "Newsletter": "yes"}.
+    ///         Check boxes take yes/no, sliders a number, combo boxes the option name.
+    /// 
+    /// Returns:
+    ///     FormFillReport: Filled, unmatched and failed fields.
+    pub fn fill_form(&self, fields: &Bound<'_, PyDict>) -> PyResult<FormFillReport> {
+        let mut pairs = Vec::with_capacity(fields.len());
+        for (label, value) in fields.iter() {
+            pairs.push((label.extract::<String>()?, value.str()?.to_string()));
+        }
+        ::terminator_core::Form::new(&self.inner)
+            .fill(pairs)
+            .map(FormFillReport::from)
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "audit", signature = (check_contrast=false, max_depth=None))]
+    #[pyo3(text_signature = "($self, check_contrast, max_depth)")]
+    /// Audit the accessibility of the elements below this element (usually a window):
+    /// controls without a name, interactive controls the keyboard can't reach,
+    /// duplicate automation IDs and, optionally, text with low contrast.
+    /// 
+    /// Args:
+    ///     check_contrast (bool): Screenshot the element and check text contrast. It must be visible on screen.
+    ///     max_depth (Optional[int]): How deep below the element to look. Defaults to 30.
+    /// 
+    /// Returns:
+    ///     AuditReport: The findings, also available as HTML with to_html().
+    pub fn audit(&self, check_contrast: bool, max_depth: Option<usize>) -> PyResult<AuditReport> {
+        let mut audit = ::terminator_core::Audit::new(&self.inner).check_contrast(check_contrast);
+        if let Some(depth) = max_depth {
+            audit = audit.max_depth(depth);
+        }
+        audit.run().map(AuditReport::from).map_err(|e| automation_error_to_pyerr(e))
+    }
+
     #[pyo3(name = "scroll", text_signature = "($self, direction, amount)")]
     /// Scroll the element in a given direction.
     /// 
@@ -263,6 +658,16 @@ impl UIElement {
         self.inner.activate_window().map_err(|e| automation_error_to_pyerr(e))
     }
 
+    #[pyo3(name = "is_stale", text_signature = "($self)")]
+    /// Check whether the control behind this handle no longer exists, e.g. a
+    /// list row recreated by virtualization. Stale elements fail every action.
+    /// 
+    /// Returns:
+    ///     bool: True if the element is gone.
+    pub fn is_stale(&self) -> bool {
+        self.inner.is_stale()
+    }
+
     #[pyo3(name = "is_focused", text_signature = "($self)")]
     /// Check if element is focused.
     /// 
@@ -429,4 +834,129 @@ impl UIElement {
             })
             .map_err(|e| automation_error_to_pyerr(e))
     }
+
+    #[pyo3(name = "capture_as", text_signature = "($self, format)")]
+    /// Capture a screenshot of this element encoded as an image file.
+    /// 
+    /// Args:
+    ///     format (str): "png", "jpeg", "jpeg:<quality>" or "webp".
+    /// 
+    /// Returns:
+    ///     EncodedImage: The encoded screenshot.
+    pub fn capture_as(&self, format: &str) -> PyResult<crate::types::EncodedImage> {
+        let format = crate::types::parse_image_format(format)?;
+        self.inner.capture_as(format)
+            .map(crate::types::EncodedImage::from)
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "is_topmost", text_signature = "($self)")]
+    /// Check whether the window containing this element is always on top.
+    /// 
+    /// Returns:
+    ///     bool: True if the window is topmost.
+    pub fn is_topmost(&self) -> PyResult<bool> {
+        self.inner.is_topmost().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "set_topmost", text_signature = "($self, topmost)")]
+    /// Make the window containing this element always on top, or clear it.
+    /// 
+    /// Args:
+    ///     topmost (bool): Whether the window should stay on top.
+    pub fn set_topmost(&self, topmost: bool) -> PyResult<()> {
+        self.inner.set_topmost(topmost).map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "opacity", text_signature = "($self)")]
+    /// Get the opacity of the window containing this element.
+    /// 
+    /// Returns:
+    ///     float: Opacity between 0.0 (transparent) and 1.0 (opaque).
+    pub fn opacity(&self) -> PyResult<f32> {
+        self.inner.opacity().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "set_opacity", text_signature = "($self, opacity)")]
+    /// Set the opacity of the window containing this element.
+    /// 
+    /// Args:
+    ///     opacity (float): Opacity between 0.0 (transparent) and 1.0 (opaque).
+    pub fn set_opacity(&self, opacity: f32) -> PyResult<()> {
+        self.inner.set_opacity(opacity).map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "window_state", text_signature = "($self)")]
+    /// Get the show state of the window containing this element.
+    /// 
+    /// Returns:
+    ///     str: One of 'normal', 'minimized' or 'maximized'.
+    pub fn window_state(&self) -> PyResult<String> {
+        self.inner.window_state().map(|s| s.to_string()).map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "maximize", text_signature = "($self)")]
+    /// Maximize the window containing this element.
+    pub fn maximize(&self) -> PyResult<()> {
+        self.inner.maximize().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "minimize", text_signature = "($self)")]
+    /// Minimize the window containing this element.
+    pub fn minimize(&self) -> PyResult<()> {
+        self.inner.minimize().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "restore", text_signature = "($self)")]
+    /// Restore the window containing this element from the minimized or maximized state.
+    pub fn restore(&self) -> PyResult<()> {
+        self.inner.restore().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "set_bounds", text_signature = "($self, x, y, width, height)")]
+    /// Move and resize the window containing this element.
+    /// 
+    /// Args:
+    ///     x (float): Left edge in screen coordinates.
+    ///     y (float): Top edge in screen coordinates.
+    ///     width (float): Window width.
+    ///     height (float): Window height.
+    pub fn set_bounds(&self, x: f64, y: f64, width: f64, height: f64) -> PyResult<()> {
+        self.inner.set_bounds(x, y, width, height).map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "bring_to_front", text_signature = "($self)")]
+    /// Raise the window containing this element above all others and focus it.
+    pub fn bring_to_front(&self) -> PyResult<()> {
+        self.inner.bring_to_front().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "virtual_desktop", text_signature = "($self)")]
+    /// Get the id of the virtual desktop the window containing this element is on.
+    /// 
+    /// Returns:
+    ///     str: The desktop GUID.
+    pub fn virtual_desktop(&self) -> PyResult<String> {
+        self.inner.virtual_desktop().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "is_on_current_virtual_desktop", text_signature = "($self)")]
+    /// Check whether the window containing this element is on the virtual desktop shown.
+    /// Elements on other desktops cannot be clicked.
+    /// 
+    /// Returns:
+    ///     bool: True if the window is on the current desktop.
+    pub fn is_on_current_virtual_desktop(&self) -> PyResult<bool> {
+        self.inner.is_on_current_virtual_desktop().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "move_to_desktop", text_signature = "($self, desktop_id)")]
+    /// Move the window containing this element to another virtual desktop.
+    /// Windows only allows this for windows of the calling process.
+    /// 
+    /// Args:
+    ///     desktop_id (str): Id from Desktop.virtual_desktops().
+    pub fn move_to_desktop(&self, desktop_id: &str) -> PyResult<()> {
+        self.inner.move_to_desktop(desktop_id).map_err(|e| automation_error_to_pyerr(e))
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,90 @@
+use pyo3::exceptions::PyIndexError;
+use pyo3::prelude::*;
+use pyo3::types::PySlice;
+use pyo3_stub_gen::derive::*;
+use ::terminator_core::element::UIElement as TerminatorUIElement;
+use crate::element::UIElement;
+
+/// Elements returned by Locator.all(). Supports len(), iteration, indexing
+/// with negative indices and slicing, which returns another ElementList.
+#[gen_stub_pyclass]
+#[pyclass(name = "ElementList", sequence)]
+#[derive(Clone)]
+pub struct ElementList {
+    pub inner: Vec<TerminatorUIElement>,
+}
+
+/// Iterator over an ElementList.
+#[gen_stub_pyclass]
+#[pyclass(name = "ElementListIterator")]
+pub struct ElementListIterator {
+    elements: std::vec::IntoIter<TerminatorUIElement>,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl ElementList {
+    pub fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn __bool__(&self) -> bool {
+        !self.inner.is_empty()
+    }
+
+    pub fn __iter__(&self) -> ElementListIterator {
+        ElementListIterator { elements: self.inner.clone().into_iter() }
+    }
+
+    /// An element by position, or an ElementList for a slice.
+    pub fn __getitem__<'py>(&self, py: Python<'py>, index: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        if let Ok(slice) = index.downcast::<PySlice>() {
+            let indices = slice.indices(self.inner.len() as isize)?;
+            let elements = (0..indices.slicelength)
+                .map(|i| self.inner[(indices.start + i as isize * indices.step) as usize].clone())
+                .collect();
+            return Ok(Bound::new(py, ElementList { inner: elements })?.into_any());
+        }
+        let index: isize = index.extract()?;
+        let position = if index < 0 { index + self.inner.len() as isize } else { index };
+        let element = usize::try_from(position)
+            .ok()
+            .and_then(|position| self.inner.get(position))
+            .ok_or_else(|| PyIndexError::new_err(format!("ElementList index {} out of range", index)))?;
+        Ok(Bound::new(py, UIElement { inner: element.clone() })?.into_any())
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("ElementList(len={})", self.inner.len())
+    }
+
+    #[pyo3(name = "first", text_signature = "($self)")]
+    /// The first element.
+    ///
+    /// Returns:
+    ///     Optional[UIElement]: The first element, or None if the list is empty.
+    pub fn first(&self) -> Option<UIElement> {
+        self.inner.first().map(|e| UIElement { inner: e.clone() })
+    }
+
+    #[pyo3(name = "to_list", text_signature = "($self)")]
+    /// The elements as a list.
+    ///
+    /// Returns:
+    ///     List[UIElement]: The elements.
+    pub fn to_list(&self) -> Vec<UIElement> {
+        self.inner.iter().map(|e| UIElement { inner: e.clone() }).collect()
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl ElementListIterator {
+    pub fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    pub fn __next__(&mut self) -> Option<UIElement> {
+        self.elements.next().map(|inner| UIElement { inner })
+    }
+}
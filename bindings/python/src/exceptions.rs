@@ -2,27 +2,54 @@ use pyo3_stub_gen::create_exception;
 
 // Custom Python exceptions for advanced error mapping
 create_exception!(terminator, ElementNotFoundError, pyo3::exceptions::PyRuntimeError);
+create_exception!(terminator, ElementNotEditableError, pyo3::exceptions::PyRuntimeError);
 create_exception!(terminator, TimeoutError, pyo3::exceptions::PyRuntimeError);
 create_exception!(terminator, PermissionDeniedError, pyo3::exceptions::PyRuntimeError);
 create_exception!(terminator, PlatformError, pyo3::exceptions::PyRuntimeError);
 create_exception!(terminator, UnsupportedOperationError, pyo3::exceptions::PyRuntimeError);
 create_exception!(terminator, UnsupportedPlatformError, pyo3::exceptions::PyRuntimeError);
 create_exception!(terminator, InvalidArgumentError, pyo3::exceptions::PyRuntimeError);
+create_exception!(terminator, UIUnavailableError, pyo3::exceptions::PyRuntimeError);
+create_exception!(terminator, StalledRunError, pyo3::exceptions::PyRuntimeError);
+create_exception!(terminator, VerificationFailedError, pyo3::exceptions::PyAssertionError);
+create_exception!(terminator, ElevationRequiredError, pyo3::exceptions::PyPermissionError);
+create_exception!(terminator, MissingPermissionError, pyo3::exceptions::PyPermissionError);
+create_exception!(terminator, StaleElementError, ElementNotFoundError);
 create_exception!(terminator, InternalError, pyo3::exceptions::PyRuntimeError);
 
 use ::terminator_core::errors::AutomationError;
+use pyo3::prelude::*;
 
-// Advanced error mapping
+// Advanced error mapping. Besides the message, every exception carries the
+// stable numeric `code` of the error and its `context` (selector, root,
+// timeout_ms, platform_code) as a dict, or None
 pub fn automation_error_to_pyerr(e: AutomationError) -> pyo3::PyErr {
     let msg = format!("{e}");
-    match e {
+    let err = match e.kind() {
         AutomationError::ElementNotFound(_) => ElementNotFoundError::new_err(msg),
+        AutomationError::ElementNotEditable(_) => ElementNotEditableError::new_err(msg),
         AutomationError::Timeout(_) => TimeoutError::new_err(msg),
         AutomationError::PermissionDenied(_) => PermissionDeniedError::new_err(msg),
         AutomationError::PlatformError(_) => PlatformError::new_err(msg),
         AutomationError::UnsupportedOperation(_) => UnsupportedOperationError::new_err(msg),
         AutomationError::UnsupportedPlatform(_) => UnsupportedPlatformError::new_err(msg),
         AutomationError::InvalidArgument(_) => InvalidArgumentError::new_err(msg),
-        AutomationError::Internal(_) => InternalError::new_err(msg),
-    }
-} 
\ No newline at end of file
+        AutomationError::UIUnavailable(_) => UIUnavailableError::new_err(msg),
+        AutomationError::StalledRun(_) => StalledRunError::new_err(msg),
+        AutomationError::VerificationFailed(_) => VerificationFailedError::new_err(msg),
+        AutomationError::ElevationRequired(_) => ElevationRequiredError::new_err(msg),
+        AutomationError::MissingPermission(_) => MissingPermissionError::new_err(msg),
+        AutomationError::StaleElement(_) => StaleElementError::new_err(msg),
+        AutomationError::Internal(_) | AutomationError::WithContext { .. } => InternalError::new_err(msg),
+    };
+    Python::with_gil(|py| {
+        let value = err.value(py);
+        let _ = value.setattr("code", e.code().as_u16());
+        let context = e
+            .context()
+            .and_then(|context| serde_json::to_string(context).ok())
+            .and_then(|json| py.import("json").ok()?.call_method1("loads", (json,)).ok());
+        let _ = value.setattr("context", context);
+    });
+    err
+}
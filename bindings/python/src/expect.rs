@@ -0,0 +1,74 @@
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::*;
+use ::terminator_core::Expect as TerminatorExpect;
+use crate::exceptions::automation_error_to_pyerr;
+
+/// Assertions on one element that retry until they hold or time out.
+/// Failures raise VerificationFailedError with the last value seen, an
+/// excerpt of the element's subtree and the path of a screenshot.
+#[gen_stub_pyclass]
+#[pyclass(name = "Expect")]
+#[derive(Clone)]
+pub struct Expect {
+    pub inner: TerminatorExpect,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Expect {
+    #[pyo3(name = "not_", text_signature = "($self)")]
+    /// Invert the assertions, e.g. element.expect().not_().to_be_visible().
+    /// 
+    /// Returns:
+    ///     Expect: The inverted assertions.
+    pub fn not_(&self) -> Expect {
+        Expect { inner: self.inner.clone().not() }
+    }
+
+    #[pyo3(name = "to_be_visible", text_signature = "($self)")]
+    /// Wait until the element is visible.
+    pub fn to_be_visible(&self) -> PyResult<()> {
+        self.inner.to_be_visible().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "to_be_enabled", text_signature = "($self)")]
+    /// Wait until the element is enabled.
+    pub fn to_be_enabled(&self) -> PyResult<()> {
+        self.inner.to_be_enabled().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "to_be_focused", text_signature = "($self)")]
+    /// Wait until the element has keyboard focus.
+    pub fn to_be_focused(&self) -> PyResult<()> {
+        self.inner.to_be_focused().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "to_have_text", text_signature = "($self, expected)")]
+    /// Wait until the element's text contains the expected string.
+    pub fn to_have_text(&self, expected: &str) -> PyResult<()> {
+        self.inner.to_have_text(expected).map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "to_have_value", text_signature = "($self, expected)")]
+    /// Wait until the element's value equals the expected string.
+    pub fn to_have_value(&self, expected: &str) -> PyResult<()> {
+        self.inner.to_have_value(expected).map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "to_have_name", text_signature = "($self, expected)")]
+    /// Wait until the element's name equals the expected string.
+    pub fn to_have_name(&self, expected: &str) -> PyResult<()> {
+        self.inner.to_have_name(expected).map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "to_have_attribute", signature = (name, expected=None))]
+    #[pyo3(text_signature = "($self, name, expected)")]
+    /// Wait until an attribute equals the expected value.
+    /// 
+    /// Args:
+    ///     name (str): Attribute name, e.g. 'value', 'enabled' or 'description'.
+    ///     expected (Optional[str]): Expected value; None only requires the attribute to have a value.
+    pub fn to_have_attribute(&self, name: &str, expected: Option<&str>) -> PyResult<()> {
+        self.inner.to_have_attribute(name, expected).map_err(|e| automation_error_to_pyerr(e))
+    }
+}
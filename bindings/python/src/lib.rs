@@ -7,26 +7,66 @@ use pyo3_stub_gen::define_stub_info_gatherer;
 mod exceptions;
 mod types;
 mod element;
+mod element_list;
 mod locator;
 mod desktop;
+mod verify;
+mod process;
+mod notification;
+mod expect;
+mod tree_handles;
+mod app_session;
 
 use exceptions::*;
 use types::*;
 use element::UIElement;
+use element_list::{ElementList, ElementListIterator};
 use locator::Locator;
 use desktop::Desktop;
+use verify::SoftVerifier;
+use process::AppHandle;
+use notification::Notification;
+use expect::Expect;
+use tree_handles::UINodeWithHandles;
+use app_session::AppSession;
 
 #[pymodule]
 fn terminator(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Desktop>()?;
     m.add_class::<UIElement>()?;
     m.add_class::<Locator>()?;
+    m.add_class::<ElementList>()?;
+    m.add_class::<ElementListIterator>()?;
+    m.add_class::<SoftVerifier>()?;
+    m.add_class::<AppHandle>()?;
+    m.add_class::<AppSession>()?;
+    m.add_class::<Notification>()?;
+    m.add_class::<Expect>()?;
     m.add_class::<ScreenshotResult>()?;
+    m.add_class::<EncodedImage>()?;
     m.add_class::<ClickResult>()?;
     m.add_class::<CommandOutput>()?;
     m.add_class::<UIElementAttributes>()?;
     m.add_class::<UINode>()?;
+    m.add_class::<UINodeWithHandles>()?;
+    m.add_class::<WindowInfo>()?;
+    m.add_class::<WindowTree>()?;
+    m.add_class::<ConsoleScreen>()?;
+    m.add_class::<MonitorInfo>()?;
+    m.add_class::<EnvironmentInfo>()?;
+    m.add_class::<VirtualDesktop>()?;
+    m.add_class::<AutomationCapabilities>()?;
+    m.add_class::<TextRun>()?;
+    m.add_class::<BatchResult>()?;
+    m.add_class::<FilledField>()?;
+    m.add_class::<FailedField>()?;
+    m.add_class::<FormFillReport>()?;
+    m.add_class::<AuditFinding>()?;
+    m.add_class::<AuditReport>()?;
+    m.add_class::<ActionAttempt>()?;
+    m.add_class::<VerifiedActionResult>()?;
     m.add_class::<TreeBuildConfig>()?;
+    m.add_class::<TreeBuildStats>()?;
     m.add_class::<PropertyLoadingMode>()?;
     m.add_class::<Coordinates>()?;
     m.add_class::<Bounds>()?;
@@ -34,12 +74,19 @@ fn terminator(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<ExploredElementDetail>()?;
 
     m.add("ElementNotFoundError", _py.get_type::<ElementNotFoundError>())?;
+    m.add("ElementNotEditableError", _py.get_type::<ElementNotEditableError>())?;
     m.add("TimeoutError", _py.get_type::<TimeoutError>())?;
     m.add("PermissionDeniedError", _py.get_type::<PermissionDeniedError>())?;
     m.add("PlatformError", _py.get_type::<PlatformError>())?;
     m.add("UnsupportedOperationError", _py.get_type::<UnsupportedOperationError>())?;
     m.add("UnsupportedPlatformError", _py.get_type::<UnsupportedPlatformError>())?;
     m.add("InvalidArgumentError", _py.get_type::<InvalidArgumentError>())?;
+    m.add("UIUnavailableError", _py.get_type::<UIUnavailableError>())?;
+    m.add("StalledRunError", _py.get_type::<StalledRunError>())?;
+    m.add("VerificationFailedError", _py.get_type::<VerificationFailedError>())?;
+    m.add("ElevationRequiredError", _py.get_type::<ElevationRequiredError>())?;
+    m.add("MissingPermissionError", _py.get_type::<MissingPermissionError>())?;
+    m.add("StaleElementError", _py.get_type::<StaleElementError>())?;
     m.add("InternalError", _py.get_type::<InternalError>())?;
     Ok(())
 }
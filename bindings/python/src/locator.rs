@@ -5,6 +5,7 @@ use pyo3_async_runtimes::TaskLocals;
 use ::terminator_core::locator::Locator as TerminatorLocator;
 use crate::exceptions::automation_error_to_pyerr;
 use crate::element::UIElement;
+use crate::element_list::ElementList;
 
 /// Locator for finding UI elements by selector.
 #[gen_stub_pyclass]
@@ -38,12 +39,12 @@ impl Locator {
     ///     depth (Optional[int]): Maximum depth to search.
     /// 
     /// Returns:
-    ///     List[UIElement]: List of matching elements.
+    ///     ElementList: The matching elements, supporting len(), iteration and slicing.
     pub fn all<'py>(&self, py: Python<'py>, timeout_ms: Option<u64>, depth: Option<usize>) -> PyResult<Bound<'py, PyAny>> {
         let locator = self.inner.clone();
         pyo3_tokio::future_into_py_with_locals(py, TaskLocals::with_running_loop(py)?, async move {
             let elements = locator.all(timeout_ms.map(std::time::Duration::from_millis), depth).await.map_err(|e| automation_error_to_pyerr(e))?;
-            Ok(elements.into_iter().map(|e| UIElement { inner: e }).collect::<Vec<_>>())
+            Ok(ElementList { inner: elements })
         })
     }
 
@@ -76,6 +77,33 @@ impl Locator {
         Locator { inner: self.inner.clone().set_default_timeout(std::time::Duration::from_millis(timeout_ms)) }
     }
 
+    #[pyo3(name = "with_retry", signature = (max_attempts=None, initial_delay_ms=100, max_delay_ms=None, multiplier=1.0, jitter=0.0, attempt_timeout_ms=None))]
+    #[pyo3(text_signature = "($self, max_attempts, initial_delay_ms, max_delay_ms, multiplier, jitter, attempt_timeout_ms)")]
+    /// Configure how this locator polls while waiting for an element.
+    /// 
+    /// Args:
+    ///     max_attempts (Optional[int]): Maximum number of attempts, unlimited if None.
+    ///     initial_delay_ms (int): Delay before the second attempt. Defaults to 100.
+    ///     max_delay_ms (Optional[int]): Upper bound for the delay between attempts. Defaults to 5000.
+    ///     multiplier (float): Factor applied to the delay after each failed attempt. Defaults to 1.0.
+    ///     jitter (float): Random spread applied to each delay, as a fraction (0.0 - 1.0).
+    ///     attempt_timeout_ms (Optional[int]): Cap on how long a single attempt may search.
+    /// 
+    /// Returns:
+    ///     Locator: A new locator with the specified retry policy.
+    pub fn with_retry(
+        &self,
+        max_attempts: Option<u32>,
+        initial_delay_ms: u64,
+        max_delay_ms: Option<u64>,
+        multiplier: f64,
+        jitter: f64,
+        attempt_timeout_ms: Option<u64>,
+    ) -> Locator {
+        let policy = retry_policy(max_attempts, initial_delay_ms, max_delay_ms, multiplier, jitter, attempt_timeout_ms);
+        Locator { inner: self.inner.clone().with_retry(policy) }
+    }
+
     #[pyo3(name = "locator", text_signature = "($self, selector)")]
     /// Chain another selector.
     /// 
@@ -100,4 +128,92 @@ impl Locator {
     pub fn within(&self, element: &UIElement) -> Locator {
         Locator { inner: self.inner.clone().within(element.inner.clone()) }
     }
-} 
\ No newline at end of file
+
+    #[pyo3(name = "within_viewport", text_signature = "($self, container)")]
+    /// Only match elements inside the visible region of a scrollable container.
+    /// 
+    /// Args:
+    ///     container (UIElement): The scrollable container to search in.
+    /// 
+    /// Returns:
+    ///     Locator: A new locator restricted to the container's viewport.
+    pub fn within_viewport(&self, container: &UIElement) -> Locator {
+        Locator { inner: self.inner.clone().within_viewport(container.inner.clone()) }
+    }
+
+    #[pyo3(name = "nth", text_signature = "($self, index)")]
+    /// Only match the element at a position among the matches.
+    /// 
+    /// Args:
+    ///     index (int): Zero-based position of the element.
+    /// 
+    /// Returns:
+    ///     Locator: A new locator matching a single element.
+    pub fn nth(&self, index: usize) -> Locator {
+        Locator { inner: self.inner.clone().nth(index) }
+    }
+
+    #[pyo3(name = "first_visible", text_signature = "($self)")]
+    /// Only match the first visible element among the matches.
+    /// 
+    /// Returns:
+    ///     Locator: A new locator matching the first visible element.
+    pub fn first_visible(&self) -> Locator {
+        Locator { inner: self.inner.clone().first_visible() }
+    }
+
+    #[pyo3(name = "count", signature = (timeout_ms=None))]
+    #[pyo3(text_signature = "($self, timeout_ms)")]
+    /// (async) Count the matching elements.
+    /// 
+    /// Args:
+    ///     timeout_ms (Optional[int]): Timeout in milliseconds.
+    /// 
+    /// Returns:
+    ///     int: Number of matching elements, 0 if none.
+    pub fn count<'py>(&self, py: Python<'py>, timeout_ms: Option<u64>) -> PyResult<Bound<'py, PyAny>> {
+        let locator = self.inner.clone();
+        pyo3_tokio::future_into_py_with_locals(py, TaskLocals::with_running_loop(py)?, async move {
+            locator.count(timeout_ms.map(std::time::Duration::from_millis)).await.map_err(|e| automation_error_to_pyerr(e))
+        })
+    }
+} 
+// The policy `with_retry` builds. Backoffs without `max_delay_ms` stop
+// growing at the core's default ceiling
+fn retry_policy(
+    max_attempts: Option<u32>,
+    initial_delay_ms: u64,
+    max_delay_ms: Option<u64>,
+    multiplier: f64,
+    jitter: f64,
+    attempt_timeout_ms: Option<u64>,
+) -> ::terminator_core::RetryPolicy {
+    use std::time::Duration;
+    ::terminator_core::RetryPolicy {
+        initial_delay: Duration::from_millis(initial_delay_ms),
+        max_delay: max_delay_ms
+            .map(Duration::from_millis)
+            .unwrap_or(::terminator_core::RetryPolicy::DEFAULT_MAX_DELAY),
+        multiplier,
+        max_attempts,
+        jitter: jitter.clamp(0.0, 1.0),
+        attempt_timeout: attempt_timeout_ms.map(Duration::from_millis),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::retry_policy;
+    use std::time::Duration;
+
+    #[test]
+    fn test_backoff_without_max_delay_grows_to_the_default_ceiling() {
+        let policy = retry_policy(None, 100, None, 2.0, 0.0, None);
+        assert_eq!(policy.delay_for_attempt(1), Duration::from_millis(200));
+        assert_eq!(policy.delay_for_attempt(10), Duration::from_secs(5));
+
+        let capped = retry_policy(Some(3), 100, Some(300), 2.0, 0.0, None);
+        assert_eq!(capped.delay_for_attempt(10), Duration::from_millis(300));
+        assert_eq!(retry_policy(None, 100, None, 1.0, 2.0, None).jitter, 1.0);
+    }
+}
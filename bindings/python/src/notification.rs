@@ -0,0 +1,80 @@
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::*;
+use ::terminator_core::Notification as TerminatorNotification;
+use crate::exceptions::automation_error_to_pyerr;
+use crate::element::UIElement;
+
+/// A notification on screen: a Windows toast or a macOS Notification Center banner.
+#[gen_stub_pyclass]
+#[pyclass(name = "Notification")]
+#[derive(Clone)]
+pub struct Notification {
+    pub inner: TerminatorNotification,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl Notification {
+    #[getter]
+    /// The app that raised the notification, where the platform shows it.
+    pub fn app(&self) -> Option<String> {
+        self.inner.app.clone()
+    }
+
+    #[getter]
+    pub fn title(&self) -> Option<String> {
+        self.inner.title.clone()
+    }
+
+    #[getter]
+    pub fn body(&self) -> Option<String> {
+        self.inner.body.clone()
+    }
+
+    #[getter]
+    /// Labels of the action buttons, e.g. ["Open", "Show in folder"].
+    pub fn actions(&self) -> Vec<String> {
+        self.inner.actions.clone()
+    }
+
+    #[pyo3(name = "element", text_signature = "($self)")]
+    /// The element of the whole notification.
+    /// 
+    /// Returns:
+    ///     UIElement: The notification element.
+    pub fn element(&self) -> UIElement {
+        UIElement { inner: self.inner.element().clone() }
+    }
+
+    #[pyo3(name = "click", text_signature = "($self)")]
+    /// Click the notification itself, which usually opens the app that raised it.
+    pub fn click(&self) -> PyResult<()> {
+        self.inner.click().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "click_action", text_signature = "($self, action)")]
+    /// Click one of the notification's action buttons.
+    /// 
+    /// Args:
+    ///     action (str): Label of the button, ignoring case.
+    pub fn click_action(&self, action: &str) -> PyResult<()> {
+        self.inner.click_action(action).map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "dismiss", text_signature = "($self)")]
+    /// Close the notification with its dismiss button.
+    pub fn dismiss(&self) -> PyResult<()> {
+        self.inner.dismiss().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Notification(app={:?}, title={:?}, body={:?}, actions={:?})",
+            self.inner.app, self.inner.title, self.inner.body, self.inner.actions
+        )
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
@@ -0,0 +1,106 @@
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::*;
+use pyo3_async_runtimes::tokio as pyo3_tokio;
+use pyo3_async_runtimes::TaskLocals;
+use std::time::Duration;
+use ::terminator_core::AppHandle as TerminatorAppHandle;
+use crate::exceptions::automation_error_to_pyerr;
+use crate::element::UIElement;
+
+/// A process started with Desktop.launch().
+#[gen_stub_pyclass]
+#[pyclass(name = "AppHandle")]
+pub struct AppHandle {
+    pub inner: TerminatorAppHandle,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl AppHandle {
+    #[getter]
+    /// Process ID of the launched process.
+    pub fn pid(&self) -> u32 {
+        self.inner.pid()
+    }
+
+    #[pyo3(name = "is_running", text_signature = "($self)")]
+    /// Whether the process is still running.
+    /// 
+    /// Returns:
+    ///     bool: True while the process has not exited.
+    pub fn is_running(&self) -> bool {
+        self.inner.is_running()
+    }
+
+    #[pyo3(name = "exit_code", text_signature = "($self)")]
+    /// Exit code of the process, or None while it is running.
+    /// 
+    /// Returns:
+    ///     Optional[int]: The exit code, if the process exited with one.
+    pub fn exit_code(&self) -> PyResult<Option<i32>> {
+        self.inner.exit_code().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "kill", text_signature = "($self)")]
+    /// Terminate the process. Does nothing if it already exited.
+    pub fn kill(&self) -> PyResult<()> {
+        self.inner.kill().map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    /// Used as `with desktop.launch("notepad") as app:`, the process is
+    /// killed when the block exits.
+    pub fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Kills the process if it is still running; exceptions propagate.
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    pub fn __exit__(
+        &self,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<bool> {
+        if self.inner.is_running() {
+            self.inner.kill().map_err(|e| automation_error_to_pyerr(e))?;
+        }
+        Ok(false)
+    }
+
+    #[pyo3(name = "wait_for_window", signature = (timeout_ms=None), text_signature = "($self, timeout_ms=None)")]
+    /// (async) Wait for the process to show a window.
+    /// 
+    /// Args:
+    ///     timeout_ms (Optional[int]): Timeout in milliseconds (default 30000).
+    /// 
+    /// Returns:
+    ///     UIElement: The application element of the process.
+    pub fn wait_for_window<'py>(&self, py: Python<'py>, timeout_ms: Option<u64>) -> PyResult<Bound<'py, PyAny>> {
+        let handle = self.inner.clone();
+        pyo3_tokio::future_into_py_with_locals(py, TaskLocals::with_running_loop(py)?, async move {
+            let element = handle
+                .wait_for_window(timeout_ms.map(Duration::from_millis))
+                .await
+                .map_err(|e| automation_error_to_pyerr(e))?;
+            Ok(UIElement { inner: element })
+        })
+    }
+
+    #[pyo3(name = "wait_for_exit", signature = (timeout_ms=None), text_signature = "($self, timeout_ms=None)")]
+    /// (async) Wait for the process to exit.
+    /// 
+    /// Args:
+    ///     timeout_ms (Optional[int]): Timeout in milliseconds. Waits forever if omitted.
+    /// 
+    /// Returns:
+    ///     Optional[int]: The exit code, or None if the process was terminated without one.
+    pub fn wait_for_exit<'py>(&self, py: Python<'py>, timeout_ms: Option<u64>) -> PyResult<Bound<'py, PyAny>> {
+        let handle = self.inner.clone();
+        pyo3_tokio::future_into_py_with_locals(py, TaskLocals::with_running_loop(py)?, async move {
+            handle
+                .wait_for_exit(timeout_ms.map(Duration::from_millis))
+                .await
+                .map_err(|e| automation_error_to_pyerr(e))
+        })
+    }
+}
@@ -0,0 +1,81 @@
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::*;
+use ::terminator_core::UINodeWithHandles as TerminatorUINodeWithHandles;
+use crate::element::UIElement;
+use crate::types::UINode;
+
+/// A window tree that keeps the live element behind each node. Nodes are
+/// numbered depth-first, the window being 0, so a node found in the tree can
+/// be acted on without searching for it again.
+#[gen_stub_pyclass]
+#[pyclass(name = "UINodeWithHandles")]
+#[derive(Clone)]
+pub struct UINodeWithHandles {
+    pub inner: TerminatorUINodeWithHandles,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl UINodeWithHandles {
+    #[getter]
+    /// The tree, as returned by get_window_tree.
+    pub fn tree(&self) -> UINode {
+        UINode::from(self.inner.tree.clone())
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[pyo3(name = "element", text_signature = "($self, index)")]
+    /// The element of a node.
+    /// 
+    /// Args:
+    ///     index (int): Depth-first number of the node.
+    /// 
+    /// Returns:
+    ///     Optional[UIElement]: The element, or None past the last node.
+    pub fn element(&self, index: usize) -> Option<UIElement> {
+        self.inner.element(index).map(|e| UIElement { inner: e.clone() })
+    }
+
+    #[pyo3(name = "node", text_signature = "($self, index)")]
+    /// A node by its depth-first number.
+    /// 
+    /// Args:
+    ///     index (int): Depth-first number of the node.
+    /// 
+    /// Returns:
+    ///     Optional[UINode]: The node, or None past the last node.
+    pub fn node(&self, index: usize) -> Option<UINode> {
+        self.inner.node(index).cloned().map(UINode::from)
+    }
+
+    #[pyo3(name = "element_at", text_signature = "($self, index_path)")]
+    /// The element of the node reached by following child indices from the root.
+    /// 
+    /// Args:
+    ///     index_path (List[int]): Child index at each level, [] for the window.
+    /// 
+    /// Returns:
+    ///     Optional[UIElement]: The element, or None if the path leads nowhere.
+    pub fn element_at(&self, index_path: Vec<usize>) -> Option<UIElement> {
+        self.inner.element_at(&index_path).map(|e| UIElement { inner: e.clone() })
+    }
+
+    #[pyo3(name = "find_all", signature = (role=None, name=None), text_signature = "($self, role=None, name=None)")]
+    /// Numbers of the nodes with the given role and name, depth-first.
+    /// 
+    /// Args:
+    ///     role (Optional[str]): Role to match, e.g. "Button".
+    ///     name (Optional[str]): Name to match exactly.
+    /// 
+    /// Returns:
+    ///     List[int]: Depth-first numbers of the matching nodes.
+    pub fn find_all(&self, role: Option<String>, name: Option<String>) -> Vec<usize> {
+        self.inner.find_all(|node| {
+            role.as_deref().is_none_or(|role| node.attributes.role == role)
+                && name.as_deref().is_none_or(|name| node.attributes.name.as_deref() == Some(name))
+        })
+    }
+}
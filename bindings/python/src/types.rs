@@ -1,13 +1,17 @@
 use pyo3::prelude::*;
 use pyo3_stub_gen::derive::*;
 use std::collections::HashMap;
+use pyo3::types::PyBytes;
 use ::terminator_core::{
+    EncodedImage as CoreEncodedImage,
+    ImageFormat as CoreImageFormat,
     ScreenshotResult as CoreScreenshotResult,
     ClickResult as CoreClickResult,
     CommandOutput as CoreCommandOutput,
 };
 use serde_json;
 use serde::Serialize;
+use crate::element::UIElement;
 
 /// Result of a screenshot operation.
 #[gen_stub_pyclass]
@@ -22,10 +26,24 @@ pub struct ScreenshotResult {
     pub image_data: Vec<u8>,
 }
 
+/// A screenshot encoded as an image file.
+#[gen_stub_pyclass]
+#[pyclass(name = "EncodedImage")]
+pub struct EncodedImage {
+    /// Format the image is encoded in, e.g. "png" or "jpeg:85".
+    #[pyo3(get)]
+    pub format: String,
+    #[pyo3(get)]
+    pub width: u32,
+    #[pyo3(get)]
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
 /// Result of a click operation.
 #[gen_stub_pyclass]
 #[pyclass(name = "ClickResult")]
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct ClickResult {
     #[pyo3(get)]
     pub method: String,
@@ -33,6 +51,39 @@ pub struct ClickResult {
     pub coordinates: Option<Coordinates>,
     #[pyo3(get)]
     pub details: String,
+    /// How the click reached the application: "uia_pattern", "send_input_cursor" or "post_message".
+    #[pyo3(get)]
+    pub strategy: Option<String>,
+}
+
+/// One try of a verified action.
+#[gen_stub_pyclass]
+#[pyclass(name = "ActionAttempt")]
+#[derive(Clone, Serialize)]
+pub struct ActionAttempt {
+    #[pyo3(get)]
+    pub method: String,
+    #[pyo3(get)]
+    pub error: Option<String>,
+    #[pyo3(get)]
+    pub condition_met: bool,
+    #[pyo3(get)]
+    pub duration_ms: u64,
+}
+
+/// Outcome of an action whose effect was verified.
+#[gen_stub_pyclass]
+#[pyclass(name = "VerifiedActionResult")]
+#[derive(Clone, Serialize)]
+pub struct VerifiedActionResult {
+    #[pyo3(get)]
+    pub method: String,
+    #[pyo3(get)]
+    pub attempt: usize,
+    #[pyo3(get)]
+    pub attempts: Vec<ActionAttempt>,
+    #[pyo3(get)]
+    pub click: Option<ClickResult>,
 }
 
 /// Result of a command execution.
@@ -143,9 +194,271 @@ pub struct UINode {
     #[pyo3(get)]
     pub attributes: UIElementAttributes,
     #[pyo3(get)]
+    pub path: Option<String>,
+    #[pyo3(get)]
+    pub truncated: bool,
+    #[pyo3(get)]
+    pub status: String,
+    #[pyo3(get)]
     pub children: Vec<UINode>,
 }
 
+/// A top-level window captured in a desktop snapshot
+#[gen_stub_pyclass]
+#[pyclass(name = "WindowInfo")]
+#[derive(Clone, Serialize)]
+pub struct WindowInfo {
+    #[pyo3(get)]
+    pub pid: u32,
+    #[pyo3(get)]
+    pub title: String,
+    #[pyo3(get)]
+    pub id: Option<String>,
+    #[pyo3(get)]
+    pub bounds: Option<Bounds>,
+}
+
+/// A window together with its UI tree
+#[gen_stub_pyclass]
+#[pyclass(name = "WindowTree")]
+#[derive(Clone, Serialize)]
+pub struct WindowTree {
+    #[pyo3(get)]
+    pub window: WindowInfo,
+    #[pyo3(get)]
+    pub tree: UINode,
+}
+
+/// Visible contents of a console screen buffer
+#[gen_stub_pyclass]
+#[pyclass(name = "ConsoleScreen")]
+#[derive(Clone, Serialize)]
+pub struct ConsoleScreen {
+    #[pyo3(get)]
+    pub width: u16,
+    #[pyo3(get)]
+    pub height: u16,
+    #[pyo3(get)]
+    pub cursor: (u16, u16),
+    #[pyo3(get)]
+    pub lines: Vec<String>,
+    #[pyo3(get)]
+    pub text: String,
+}
+
+/// A connected display
+#[gen_stub_pyclass]
+#[pyclass(name = "MonitorInfo")]
+#[derive(Clone, Serialize)]
+pub struct MonitorInfo {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub bounds: Bounds,
+    #[pyo3(get)]
+    pub scale_factor: f64,
+    #[pyo3(get)]
+    pub refresh_rate: f64,
+    #[pyo3(get)]
+    pub is_primary: bool,
+}
+
+/// OS settings that affect UI trees and timings. Unknown settings are None.
+#[gen_stub_pyclass]
+#[pyclass(name = "EnvironmentInfo")]
+#[derive(Clone, Serialize)]
+pub struct EnvironmentInfo {
+    #[pyo3(get)]
+    pub os: String,
+    #[pyo3(get)]
+    pub os_version: Option<String>,
+    #[pyo3(get)]
+    pub os_build: Option<String>,
+    #[pyo3(get)]
+    pub theme: Option<String>,
+    #[pyo3(get)]
+    pub animations_enabled: Option<bool>,
+    #[pyo3(get)]
+    pub text_scale_factor: Option<f64>,
+    #[pyo3(get)]
+    pub display_scale_factor: Option<f64>,
+    #[pyo3(get)]
+    pub screen_reader_active: Option<bool>,
+}
+
+/// A virtual desktop, as shown in Task View
+#[gen_stub_pyclass]
+#[pyclass(name = "VirtualDesktop")]
+#[derive(Clone, Serialize)]
+pub struct VirtualDesktop {
+    #[pyo3(get)]
+    pub id: String,
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub index: usize,
+    #[pyo3(get)]
+    pub is_current: bool,
+}
+
+/// A stretch of text with the same formatting. Colors are "#rrggbb".
+#[gen_stub_pyclass]
+#[pyclass(name = "TextRun")]
+#[derive(Clone, Serialize)]
+pub struct TextRun {
+    #[pyo3(get)]
+    pub text: String,
+    /// Character offset of the run in the document
+    #[pyo3(get)]
+    pub start: usize,
+    #[pyo3(get)]
+    pub font_name: Option<String>,
+    #[pyo3(get)]
+    pub font_size: Option<f64>,
+    #[pyo3(get)]
+    pub bold: bool,
+    #[pyo3(get)]
+    pub italic: bool,
+    #[pyo3(get)]
+    pub underline: bool,
+    #[pyo3(get)]
+    pub foreground_color: Option<String>,
+    #[pyo3(get)]
+    pub background_color: Option<String>,
+}
+
+/// What this process can automate.
+#[gen_stub_pyclass]
+#[pyclass(name = "AutomationCapabilities")]
+#[derive(Clone, Serialize)]
+pub struct AutomationCapabilities {
+    /// "untrusted", "low", "medium", "high" or "system"; None where the platform has no integrity levels
+    #[pyo3(get)]
+    pub integrity_level: Option<String>,
+    #[pyo3(get)]
+    pub elevated: bool,
+    #[pyo3(get)]
+    pub ui_access: bool,
+    /// Whether input reaches applications running as administrator
+    #[pyo3(get)]
+    pub reaches_elevated_windows: bool,
+}
+
+/// A form field that was filled.
+#[gen_stub_pyclass]
+#[pyclass(name = "FilledField")]
+#[derive(Clone, Serialize)]
+pub struct FilledField {
+    #[pyo3(get)]
+    pub label: String,
+    /// "text", "check_box", "radio_button", "combo_box" or "range"
+    #[pyo3(get)]
+    pub kind: String,
+    /// "labeled_by", "name", "proximity" or "fuzzy_name"
+    #[pyo3(get)]
+    pub matched_by: String,
+    #[pyo3(get)]
+    pub control_name: Option<String>,
+}
+
+/// A form field whose control could not be set.
+#[gen_stub_pyclass]
+#[pyclass(name = "FailedField")]
+#[derive(Clone, Serialize)]
+pub struct FailedField {
+    #[pyo3(get)]
+    pub label: String,
+    #[pyo3(get)]
+    pub error: String,
+}
+
+/// One problem found by UIElement.audit.
+#[gen_stub_pyclass]
+#[pyclass(name = "AuditFinding")]
+#[derive(Clone, Serialize)]
+pub struct AuditFinding {
+    /// "missing_name", "not_focusable", "duplicate_automation_id" or "low_contrast"
+    #[pyo3(get)]
+    pub rule: String,
+    /// "error" or "warning"
+    #[pyo3(get)]
+    pub severity: String,
+    #[pyo3(get)]
+    pub message: String,
+    #[pyo3(get)]
+    pub role: String,
+    #[pyo3(get)]
+    pub name: Option<String>,
+    #[pyo3(get)]
+    pub automation_id: Option<String>,
+    /// Roles and names from the audited root down to the element
+    #[pyo3(get)]
+    pub location: String,
+    #[pyo3(get)]
+    pub bounds: Option<Bounds>,
+    #[pyo3(get)]
+    pub contrast_ratio: Option<f64>,
+}
+
+/// Outcome of UIElement.audit.
+#[gen_stub_pyclass]
+#[pyclass(name = "AuditReport")]
+#[derive(Clone, Serialize)]
+pub struct AuditReport {
+    #[pyo3(get)]
+    pub root: String,
+    #[pyo3(get)]
+    pub elements_checked: usize,
+    #[pyo3(get)]
+    pub findings: Vec<AuditFinding>,
+    /// Whether anything blocks users of assistive technology
+    #[pyo3(get)]
+    pub has_errors: bool,
+    #[serde(skip)]
+    report: ::terminator_core::AuditReport,
+}
+
+/// Outcome of UIElement.fill_form.
+#[gen_stub_pyclass]
+#[pyclass(name = "FormFillReport")]
+#[derive(Clone, Serialize)]
+pub struct FormFillReport {
+    #[pyo3(get)]
+    pub filled: Vec<FilledField>,
+    /// Labels no control was found for
+    #[pyo3(get)]
+    pub unmatched: Vec<String>,
+    #[pyo3(get)]
+    pub failed: Vec<FailedField>,
+    /// Whether every field was filled
+    #[pyo3(get)]
+    pub is_complete: bool,
+}
+
+/// Result of one operation of Desktop.batch.
+#[gen_stub_pyclass]
+#[pyclass(name = "BatchResult")]
+#[derive(Clone, Serialize)]
+pub struct BatchResult {
+    #[pyo3(get)]
+    pub index: usize,
+    #[pyo3(get)]
+    pub op: String,
+    #[pyo3(get)]
+    pub selector: String,
+    /// "ok", "failed" or "skipped"
+    #[pyo3(get)]
+    pub status: String,
+    #[pyo3(get)]
+    pub text: Option<String>,
+    #[pyo3(get)]
+    pub error: Option<String>,
+    #[pyo3(get)]
+    pub error_code: Option<String>,
+    #[pyo3(get)]
+    pub element: Option<UIElement>,
+}
+
 /// Property loading strategy for tree building
 #[gen_stub_pyclass]
 #[pyclass(name = "PropertyLoadingMode")]
@@ -174,14 +487,42 @@ impl PropertyLoadingMode {
 #[pyclass(name = "TreeBuildConfig")]
 #[derive(Clone, Serialize)]
 pub struct TreeBuildConfig {
-    #[pyo3(get)]
+    #[pyo3(get, set)]
     pub property_mode: PropertyLoadingMode,
-    #[pyo3(get)]
+    #[pyo3(get, set)]
     pub timeout_per_operation_ms: Option<u64>,
-    #[pyo3(get)]
+    #[pyo3(get, set)]
     pub yield_every_n_elements: Option<usize>,
-    #[pyo3(get)]
+    #[pyo3(get, set)]
     pub batch_size: Option<usize>,
+    #[pyo3(get, set)]
+    pub max_total_duration_ms: Option<u64>,
+    #[pyo3(get, set)]
+    pub use_cache_request: Option<bool>,
+}
+
+/// How a window tree was built, from Desktop.get_window_tree_with_stats
+#[gen_stub_pyclass]
+#[pyclass(name = "TreeBuildStats")]
+#[derive(Clone, Serialize)]
+pub struct TreeBuildStats {
+    #[pyo3(get)]
+    pub elements: usize,
+    #[pyo3(get)]
+    pub max_depth: usize,
+    /// Elements whose attributes were read from a cache request
+    #[pyo3(get)]
+    pub cached_elements: usize,
+    #[pyo3(get)]
+    pub cache_requests: usize,
+    #[pyo3(get)]
+    pub uncached_fallbacks: usize,
+    #[pyo3(get)]
+    pub errors: usize,
+    #[pyo3(get)]
+    pub truncated_subtrees: usize,
+    #[pyo3(get)]
+    pub duration_ms: u64,
 }
 
 impl From<CoreScreenshotResult> for ScreenshotResult {
@@ -194,12 +535,45 @@ impl From<CoreScreenshotResult> for ScreenshotResult {
     }
 }
 
+impl From<CoreEncodedImage> for EncodedImage {
+    fn from(image: CoreEncodedImage) -> Self {
+        EncodedImage {
+            format: image.format.to_string(),
+            width: image.width,
+            height: image.height,
+            data: image.data,
+        }
+    }
+}
+
+/// Parse a format name such as "png", "jpeg", "jpeg:70" or "webp"
+pub fn parse_image_format(format: &str) -> PyResult<CoreImageFormat> {
+    format.parse().map_err(crate::exceptions::automation_error_to_pyerr)
+}
+
 impl From<CoreClickResult> for ClickResult {
     fn from(r: CoreClickResult) -> Self {
         ClickResult {
             method: r.method,
             coordinates: r.coordinates.map(|(x, y)| Coordinates { x, y }),
             details: r.details,
+            strategy: r.strategy.map(|strategy| strategy.to_string()),
+        }
+    }
+}
+
+impl From<::terminator_core::VerifiedActionResult> for VerifiedActionResult {
+    fn from(r: ::terminator_core::VerifiedActionResult) -> Self {
+        VerifiedActionResult {
+            method: r.method,
+            attempt: r.attempt,
+            attempts: r.attempts.into_iter().map(|a| ActionAttempt {
+                method: a.method,
+                error: a.error,
+                condition_met: a.condition_met,
+                duration_ms: a.duration_ms,
+            }).collect(),
+            click: r.click.map(ClickResult::from),
         }
     }
 }
@@ -219,11 +593,193 @@ impl From<::terminator_core::UINode> for UINode {
         UINode {
             id: node.id,
             attributes: UIElementAttributes::from(node.attributes),
+            path: node.path,
+            truncated: node.truncated,
+            status: node.status.as_str().to_string(),
             children: node.children.into_iter().map(UINode::from).collect(),
         }
     }
 }
 
+impl From<(::terminator_core::WindowInfo, ::terminator_core::UINode)> for WindowTree {
+    fn from((info, tree): (::terminator_core::WindowInfo, ::terminator_core::UINode)) -> Self {
+        WindowTree {
+            window: WindowInfo {
+                pid: info.pid,
+                title: info.title,
+                id: info.id,
+                bounds: info.bounds.map(|(x, y, width, height)| Bounds { x, y, width, height }),
+            },
+            tree: UINode::from(tree),
+        }
+    }
+}
+
+impl From<::terminator_core::ConsoleScreen> for ConsoleScreen {
+    fn from(screen: ::terminator_core::ConsoleScreen) -> Self {
+        ConsoleScreen {
+            width: screen.width,
+            height: screen.height,
+            cursor: screen.cursor,
+            text: screen.text(),
+            lines: screen.lines,
+        }
+    }
+}
+
+impl From<::terminator_core::MonitorInfo> for MonitorInfo {
+    fn from(monitor: ::terminator_core::MonitorInfo) -> Self {
+        let bounds = monitor.bounds;
+        MonitorInfo {
+            name: monitor.name,
+            bounds: Bounds { x: bounds.x, y: bounds.y, width: bounds.width, height: bounds.height },
+            scale_factor: monitor.scale_factor,
+            refresh_rate: monitor.refresh_rate,
+            is_primary: monitor.is_primary,
+        }
+    }
+}
+
+impl From<::terminator_core::EnvironmentInfo> for EnvironmentInfo {
+    fn from(env: ::terminator_core::EnvironmentInfo) -> Self {
+        EnvironmentInfo {
+            os: env.os,
+            os_version: env.os_version,
+            os_build: env.os_build,
+            theme: env.theme.map(|theme| match theme {
+                ::terminator_core::Theme::Light => "light".to_string(),
+                ::terminator_core::Theme::Dark => "dark".to_string(),
+            }),
+            animations_enabled: env.animations_enabled,
+            text_scale_factor: env.text_scale_factor,
+            display_scale_factor: env.display_scale_factor,
+            screen_reader_active: env.screen_reader_active,
+        }
+    }
+}
+
+impl From<::terminator_core::VirtualDesktop> for VirtualDesktop {
+    fn from(desktop: ::terminator_core::VirtualDesktop) -> Self {
+        VirtualDesktop {
+            id: desktop.id,
+            name: desktop.name,
+            index: desktop.index,
+            is_current: desktop.is_current,
+        }
+    }
+}
+
+impl From<::terminator_core::TextRun> for TextRun {
+    fn from(run: ::terminator_core::TextRun) -> Self {
+        TextRun {
+            text: run.text,
+            start: run.start,
+            font_name: run.font_name,
+            font_size: run.font_size,
+            bold: run.bold,
+            italic: run.italic,
+            underline: run.underline,
+            foreground_color: run.foreground_color,
+            background_color: run.background_color,
+        }
+    }
+}
+
+impl From<::terminator_core::AutomationCapabilities> for AutomationCapabilities {
+    fn from(capabilities: ::terminator_core::AutomationCapabilities) -> Self {
+        AutomationCapabilities {
+            integrity_level: capabilities.integrity_level.map(|level| level.to_string()),
+            elevated: capabilities.elevated,
+            ui_access: capabilities.ui_access,
+            reaches_elevated_windows: capabilities.reaches_elevated_windows(),
+        }
+    }
+}
+
+impl From<::terminator_core::AuditReport> for AuditReport {
+    fn from(report: ::terminator_core::AuditReport) -> Self {
+        // Same names as the serialized report
+        let name = |value: serde_json::Result<serde_json::Value>| {
+            value.ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()
+        };
+        AuditReport {
+            root: report.root.clone(),
+            elements_checked: report.elements_checked,
+            has_errors: report.has_errors(),
+            findings: report
+                .findings
+                .iter()
+                .map(|finding| AuditFinding {
+                    rule: finding.rule.to_string(),
+                    severity: name(serde_json::to_value(finding.severity)),
+                    message: finding.message.clone(),
+                    role: finding.role.clone(),
+                    name: finding.name.clone(),
+                    automation_id: finding.automation_id.clone(),
+                    location: finding.location.clone(),
+                    bounds: finding.bounds.map(|b| Bounds { x: b.x, y: b.y, width: b.width, height: b.height }),
+                    contrast_ratio: finding.contrast_ratio,
+                })
+                .collect(),
+            report,
+        }
+    }
+}
+
+impl From<::terminator_core::FormFillReport> for FormFillReport {
+    fn from(report: ::terminator_core::FormFillReport) -> Self {
+        // Same names as the serialized report
+        let name = |value: serde_json::Result<serde_json::Value>| {
+            value.ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()
+        };
+        FormFillReport {
+            is_complete: report.is_complete(),
+            filled: report
+                .filled
+                .into_iter()
+                .map(|field| FilledField {
+                    kind: name(serde_json::to_value(field.kind)),
+                    matched_by: name(serde_json::to_value(field.matched_by)),
+                    label: field.label,
+                    control_name: field.control_name,
+                })
+                .collect(),
+            unmatched: report.unmatched,
+            failed: report
+                .failed
+                .into_iter()
+                .map(|field| FailedField { label: field.label, error: field.error })
+                .collect(),
+        }
+    }
+}
+
+impl From<::terminator_core::BatchResult> for BatchResult {
+    fn from(result: ::terminator_core::BatchResult) -> Self {
+        let op = match &result.op {
+            ::terminator_core::BatchOp::Find { .. } => "find",
+            ::terminator_core::BatchOp::Click { .. } => "click",
+            ::terminator_core::BatchOp::TypeText { .. } => "type_text",
+            ::terminator_core::BatchOp::GetText { .. } => "get_text",
+        };
+        let status = match result.status {
+            ::terminator_core::BatchStatus::Ok => "ok",
+            ::terminator_core::BatchStatus::Failed => "failed",
+            ::terminator_core::BatchStatus::Skipped => "skipped",
+        };
+        BatchResult {
+            index: result.index,
+            op: op.to_string(),
+            selector: result.op.selector().to_string(),
+            status: status.to_string(),
+            text: result.text,
+            error: result.error,
+            error_code: result.error_code.map(|code| code.name().to_string()),
+            element: result.element.map(|inner| UIElement { inner }),
+        }
+    }
+}
+
 impl From<::terminator_core::UIElementAttributes> for UIElementAttributes {
     fn from(attrs: ::terminator_core::UIElementAttributes) -> Self {
         // Convert HashMap<String, Option<serde_json::Value>> to HashMap<String, Option<String>>
@@ -257,6 +813,23 @@ impl From<TreeBuildConfig> for ::terminator_core::platforms::TreeBuildConfig {
             timeout_per_operation_ms: config.timeout_per_operation_ms,
             yield_every_n_elements: config.yield_every_n_elements,
             batch_size: config.batch_size,
+            max_total_duration: config.max_total_duration_ms.map(std::time::Duration::from_millis),
+            use_cache_request: config.use_cache_request.unwrap_or(true),
+        }
+    }
+}
+
+impl From<::terminator_core::platforms::TreeBuildStats> for TreeBuildStats {
+    fn from(stats: ::terminator_core::platforms::TreeBuildStats) -> Self {
+        TreeBuildStats {
+            elements: stats.elements,
+            max_depth: stats.max_depth,
+            cached_elements: stats.cached_elements,
+            cache_requests: stats.cache_requests,
+            uncached_fallbacks: stats.uncached_fallbacks,
+            errors: stats.errors,
+            truncated_subtrees: stats.truncated_subtrees,
+            duration_ms: stats.duration_ms,
         }
     }
 }
@@ -298,6 +871,15 @@ impl UIElementAttributes {
         serde_json::to_string_pretty(self)
             .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
     }
+
+    #[pyo3(name = "to_dict", text_signature = "($self)")]
+    /// Convert the attributes to a dict.
+    /// 
+    /// Returns:
+    ///     dict: The attributes, keyed by field name.
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py_json(py, self)
+    }
 }
 
 #[gen_stub_pymethods]
@@ -311,6 +893,70 @@ impl ScreenshotResult {
         serde_json::to_string_pretty(self)
             .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
     }
+
+    #[pyo3(name = "to_png", text_signature = "($self)")]
+    /// Encode the screenshot as PNG.
+    /// 
+    /// Returns:
+    ///     bytes: The PNG file contents.
+    pub fn to_png<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let png = self.to_core().to_png().map_err(crate::exceptions::automation_error_to_pyerr)?;
+        Ok(PyBytes::new(py, &png))
+    }
+
+    #[pyo3(name = "to_jpeg", signature = (quality=85))]
+    #[pyo3(text_signature = "($self, quality=85)")]
+    /// Encode the screenshot as JPEG.
+    /// 
+    /// Args:
+    ///     quality (int): JPEG quality from 1 to 100. Defaults to 85.
+    /// 
+    /// Returns:
+    ///     bytes: The JPEG file contents.
+    pub fn to_jpeg<'py>(&self, py: Python<'py>, quality: u8) -> PyResult<Bound<'py, PyBytes>> {
+        let jpeg = self.to_core().to_jpeg(quality).map_err(crate::exceptions::automation_error_to_pyerr)?;
+        Ok(PyBytes::new(py, &jpeg))
+    }
+
+    #[pyo3(name = "to_webp", text_signature = "($self)")]
+    /// Encode the screenshot as lossless WebP.
+    /// 
+    /// Returns:
+    ///     bytes: The WebP file contents.
+    pub fn to_webp<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let webp = self.to_core().to_webp().map_err(crate::exceptions::automation_error_to_pyerr)?;
+        Ok(PyBytes::new(py, &webp))
+    }
+}
+
+impl ScreenshotResult {
+    fn to_core(&self) -> CoreScreenshotResult {
+        CoreScreenshotResult {
+            image_data: self.image_data.clone(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl EncodedImage {
+    #[getter]
+    /// The encoded file contents.
+    pub fn data<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.data)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "EncodedImage(format={:?}, width={}, height={}, size={})",
+            self.format,
+            self.width,
+            self.height,
+            self.data.len()
+        )
+    }
 }
 
 #[gen_stub_pymethods]
@@ -339,6 +985,166 @@ impl Coordinates {
     }
 }
 
+#[gen_stub_pymethods]
+#[pymethods]
+impl ActionAttempt {
+    fn __repr__(&self) -> PyResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+    fn __str__(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl VerifiedActionResult {
+    fn __repr__(&self) -> PyResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+    fn __str__(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl EnvironmentInfo {
+    fn __repr__(&self) -> PyResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+    fn __str__(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl VirtualDesktop {
+    fn __repr__(&self) -> PyResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+    fn __str__(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl AuditFinding {
+    fn __repr__(&self) -> PyResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+    fn __str__(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl AuditReport {
+    #[pyo3(name = "to_html", text_signature = "($self)")]
+    /// Render the report as a self-contained HTML page.
+    /// 
+    /// Returns:
+    ///     str: The HTML page.
+    pub fn to_html(&self) -> String {
+        self.report.to_html()
+    }
+
+    #[pyo3(name = "to_json", text_signature = "($self)")]
+    /// Render the report as indented JSON.
+    /// 
+    /// Returns:
+    ///     str: The JSON document.
+    pub fn to_json(&self) -> PyResult<String> {
+        self.report.to_json().map_err(crate::exceptions::automation_error_to_pyerr)
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+    fn __str__(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl TextRun {
+    fn __repr__(&self) -> PyResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+    fn __str__(&self) -> PyResult<String> {
+        Ok(self.text.clone())
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl AutomationCapabilities {
+    fn __repr__(&self) -> PyResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+    fn __str__(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl FormFillReport {
+    fn __repr__(&self) -> PyResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+    fn __str__(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl BatchResult {
+    fn __repr__(&self) -> PyResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+    fn __str__(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl MonitorInfo {
+    fn __repr__(&self) -> PyResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+    fn __str__(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+}
+
 #[gen_stub_pymethods]
 #[pymethods]
 impl Bounds {
@@ -376,11 +1182,105 @@ impl UINode {
         serde_json::to_string_pretty(self)
             .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
     }
+
+    #[pyo3(name = "to_dict", text_signature = "($self)")]
+    /// Convert the node and its descendants to plain dicts and lists.
+    /// 
+    /// Returns:
+    ///     dict: The tree, with the same keys as the node's attributes.
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py_json(py, self)
+    }
+
+    #[pyo3(name = "count", text_signature = "($self)")]
+    /// Count the nodes in this tree, including this one.
+    /// 
+    /// Returns:
+    ///     int: The number of nodes.
+    pub fn count(&self) -> usize {
+        1 + self.children.iter().map(UINode::count).sum::<usize>()
+    }
+
+    #[pyo3(name = "walk", text_signature = "($self)")]
+    /// List this node and all its descendants in depth-first order.
+    /// 
+    /// Returns:
+    ///     List[UINode]: Every node of the tree, starting with this one.
+    pub fn walk(&self) -> Vec<UINode> {
+        let mut nodes = Vec::new();
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            nodes.push(node.clone());
+            stack.extend(node.children.iter().rev());
+        }
+        nodes
+    }
+}
+
+/// Convert a serializable value to Python dicts, lists and scalars
+fn to_py_json<'py>(py: Python<'py>, value: &impl Serialize) -> PyResult<Bound<'py, PyAny>> {
+    let json = serde_json::to_string(value)
+        .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))?;
+    py.import("json")?.call_method1("loads", (json,))
 }
 
 #[gen_stub_pymethods]
 #[pymethods]
 impl PropertyLoadingMode {
+    #[new]
+    #[pyo3(signature = (mode="Fast"))]
+    #[pyo3(text_signature = "(mode='Fast')")]
+    /// Create a property loading mode.
+    /// 
+    /// Args:
+    ///     mode (str): 'Fast' for essential properties only, 'Complete' for all
+    ///         properties, or 'Smart' to load extra properties by element type.
+    pub fn new(mode: &str) -> PyResult<Self> {
+        match mode.to_lowercase().as_str() {
+            "fast" => Ok(Self::fast()),
+            "complete" => Ok(Self::complete()),
+            "smart" => Ok(Self::smart()),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown property loading mode '{}'. Expected 'Fast', 'Complete' or 'Smart'",
+                mode
+            ))),
+        }
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "fast")]
+    /// Load only essential properties.
+    pub fn py_fast() -> Self {
+        Self::fast()
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "complete")]
+    /// Load all properties.
+    pub fn py_complete() -> Self {
+        Self::complete()
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "smart")]
+    /// Load extra properties depending on the element type.
+    pub fn py_smart() -> Self {
+        Self::smart()
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+    fn __str__(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl TreeBuildStats {
     fn __repr__(&self) -> PyResult<String> {
         serde_json::to_string(self)
             .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
@@ -394,6 +1294,49 @@ impl PropertyLoadingMode {
 #[gen_stub_pymethods]
 #[pymethods]
 impl TreeBuildConfig {
+    #[new]
+    #[pyo3(signature = (property_mode=None, timeout_per_operation_ms=None, yield_every_n_elements=None, batch_size=None, max_total_duration_ms=None, use_cache_request=None))]
+    #[pyo3(text_signature = "(property_mode=None, timeout_per_operation_ms=None, yield_every_n_elements=None, batch_size=None, max_total_duration_ms=None, use_cache_request=None)")]
+    /// Create a tree build configuration.
+    /// 
+    /// Args:
+    ///     property_mode (Optional[PropertyLoadingMode]): Which properties to load. Defaults to Fast.
+    ///     timeout_per_operation_ms (Optional[int]): Timeout for each property lookup.
+    ///     yield_every_n_elements (Optional[int]): Yield to other tasks after this many elements.
+    ///     batch_size (Optional[int]): Number of elements processed per batch.
+    ///     max_total_duration_ms (Optional[int]): Stop building and mark the tree truncated after this long.
+    ///     use_cache_request (Optional[bool]): Fetch children with their properties in one call (Windows). Defaults to True.
+    pub fn new(
+        property_mode: Option<PropertyLoadingMode>,
+        timeout_per_operation_ms: Option<u64>,
+        yield_every_n_elements: Option<usize>,
+        batch_size: Option<usize>,
+        max_total_duration_ms: Option<u64>,
+        use_cache_request: Option<bool>,
+    ) -> Self {
+        TreeBuildConfig {
+            property_mode: property_mode.unwrap_or_else(PropertyLoadingMode::fast),
+            timeout_per_operation_ms,
+            yield_every_n_elements,
+            batch_size,
+            max_total_duration_ms,
+            use_cache_request,
+        }
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+    fn __str__(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+} 
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl WindowInfo {
     fn __repr__(&self) -> PyResult<String> {
         serde_json::to_string(self)
             .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
@@ -402,4 +1345,38 @@ impl TreeBuildConfig {
         serde_json::to_string_pretty(self)
             .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
     }
-} 
\ No newline at end of file
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl WindowTree {
+    fn __repr__(&self) -> PyResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+    fn __str__(&self) -> PyResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+
+    #[pyo3(name = "to_dict", text_signature = "($self)")]
+    /// Convert the window and its tree to plain dicts and lists.
+    /// 
+    /// Returns:
+    ///     dict: A dict with 'window' and 'tree' keys.
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py_json(py, self)
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl ConsoleScreen {
+    fn __repr__(&self) -> PyResult<String> {
+        serde_json::to_string(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+    fn __str__(&self) -> PyResult<String> {
+        Ok(self.text.clone())
+    }
+}
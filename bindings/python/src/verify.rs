@@ -0,0 +1,116 @@
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::*;
+use ::terminator_core::verify::SoftVerifier as TerminatorSoftVerifier;
+use ::terminator_core::errors::AutomationError;
+use crate::element::UIElement;
+use crate::exceptions::automation_error_to_pyerr;
+
+/// Collects failed checks across one step and raises them together.
+#[gen_stub_pyclass]
+#[pyclass(name = "SoftVerifier")]
+pub struct SoftVerifier {
+    inner: TerminatorSoftVerifier,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl SoftVerifier {
+    #[new]
+    #[pyo3(signature = (step, capture_artifacts=false))]
+    /// Create a verifier for a named step.
+    /// 
+    /// Args:
+    ///     step (str): Name of the verified step, used in the failure report.
+    ///     capture_artifacts (bool): Capture a screenshot of the element for each failed element check.
+    /// 
+    /// Returns:
+    ///     SoftVerifier: A new verifier.
+    pub fn new(step: String, capture_artifacts: bool) -> Self {
+        SoftVerifier {
+            inner: TerminatorSoftVerifier::new(step).with_artifacts(capture_artifacts),
+        }
+    }
+
+    #[pyo3(name = "check", signature = (description, condition, message=""))]
+    #[pyo3(text_signature = "($self, description, condition, message)")]
+    /// Record a check on an arbitrary condition.
+    /// 
+    /// Args:
+    ///     description (str): What is being checked.
+    ///     condition (bool): Whether the check passed.
+    ///     message (str): Explanation reported when the check fails.
+    /// 
+    /// Returns:
+    ///     bool: The condition.
+    pub fn check(&mut self, description: &str, condition: bool, message: &str) -> bool {
+        self.inner.check(description, condition, message)
+    }
+
+    #[pyo3(name = "expect_visible", text_signature = "($self, element)")]
+    /// Check that the element is visible.
+    pub fn expect_visible(&mut self, element: &UIElement) -> bool {
+        self.inner.expect_visible(&element.inner)
+    }
+
+    #[pyo3(name = "expect_enabled", text_signature = "($self, element)")]
+    /// Check that the element is enabled.
+    pub fn expect_enabled(&mut self, element: &UIElement) -> bool {
+        self.inner.expect_enabled(&element.inner)
+    }
+
+    #[pyo3(name = "expect_text", text_signature = "($self, element, expected)")]
+    /// Check that the element's text contains the expected string.
+    pub fn expect_text(&mut self, element: &UIElement, expected: &str) -> bool {
+        self.inner.expect_text(&element.inner, expected)
+    }
+
+    #[pyo3(name = "expect_name", text_signature = "($self, element, expected)")]
+    /// Check that the element's name equals the expected string.
+    pub fn expect_name(&mut self, element: &UIElement, expected: &str) -> bool {
+        self.inner.expect_name(&element.inner, expected)
+    }
+
+    #[pyo3(name = "expect_value", text_signature = "($self, element, expected)")]
+    /// Check that the element's value equals the expected string.
+    pub fn expect_value(&mut self, element: &UIElement, expected: &str) -> bool {
+        self.inner.expect_value(&element.inner, expected)
+    }
+
+    #[pyo3(name = "passed", text_signature = "($self)")]
+    /// Whether all checks so far passed.
+    pub fn passed(&self) -> bool {
+        self.inner.passed()
+    }
+
+    #[pyo3(name = "report", text_signature = "($self)")]
+    /// Get the report of all checks so far as a JSON string.
+    pub fn report(&self) -> PyResult<String> {
+        serde_json::to_string(&self.inner.report())
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))
+    }
+
+    #[pyo3(name = "write_bundle", text_signature = "($self, dir)")]
+    /// Write report.json and failure screenshots into a directory.
+    /// 
+    /// Args:
+    ///     dir (str): Target directory, created if missing.
+    /// 
+    /// Returns:
+    ///     List[str]: Paths of the written files.
+    pub fn write_bundle(&self, dir: &str) -> PyResult<Vec<String>> {
+        self.inner.report().write_bundle(dir)
+            .map(|paths| paths.into_iter().map(|p| p.display().to_string()).collect())
+            .map_err(|e| automation_error_to_pyerr(e))
+    }
+
+    #[pyo3(name = "finish", text_signature = "($self)")]
+    /// Raise VerificationFailedError listing every failed check, if any.
+    pub fn finish(&self) -> PyResult<()> {
+        let report = self.inner.report();
+        if report.passed() {
+            Ok(())
+        } else {
+            Err(automation_error_to_pyerr(AutomationError::VerificationFailed(Box::new(report))))
+        }
+    }
+}
@@ -4,6 +4,216 @@
 import builtins
 import typing
 
+class ActionAttempt:
+    r"""
+    One try of a verified action.
+    """
+    method: builtins.str
+    error: typing.Optional[builtins.str]
+    condition_met: builtins.bool
+    duration_ms: builtins.int
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+
+class AppHandle:
+    r"""
+    A process started with Desktop.launch().
+    """
+    @property
+    def pid(self) -> builtins.int:
+        r"""
+        Process ID of the launched process.
+        """
+    def is_running(self) -> builtins.bool:
+        r"""
+        Whether the process is still running.
+        
+        Returns:
+            bool: True while the process has not exited.
+        """
+    def exit_code(self) -> typing.Optional[builtins.int]:
+        r"""
+        Exit code of the process, or None while it is running.
+        
+        Returns:
+            Optional[int]: The exit code, if the process exited with one.
+        """
+    def kill(self) -> None:
+        r"""
+        Terminate the process. Does nothing if it already exited.
+        """
+    def __enter__(self) -> AppHandle:
+        r"""
+        Used as `with desktop.launch("notepad") as app:`, the process is
+        killed when the block exits.
+        """
+    def __exit__(self, _exc_type:typing.Optional[typing.Any]=None, _exc_value:typing.Optional[typing.Any]=None, _traceback:typing.Optional[typing.Any]=None) -> builtins.bool:
+        r"""
+        Kills the process if it is still running; exceptions propagate.
+        """
+    def wait_for_window(self, timeout_ms:typing.Optional[builtins.int]=None) -> typing.Any:
+        r"""
+        (async) Wait for the process to show a window.
+        
+        Args:
+            timeout_ms (Optional[int]): Timeout in milliseconds (default 30000).
+        
+        Returns:
+            UIElement: The application element of the process.
+        """
+    def wait_for_exit(self, timeout_ms:typing.Optional[builtins.int]=None) -> typing.Any:
+        r"""
+        (async) Wait for the process to exit.
+        
+        Args:
+            timeout_ms (Optional[int]): Timeout in milliseconds. Waits forever if omitted.
+        
+        Returns:
+            Optional[int]: The exit code, or None if the process was terminated without one.
+        """
+
+class AppSession:
+    r"""
+    Locators, trees and screenshots of one application, from Desktop.attach().
+    The session follows the app when its process restarts.
+    """
+    def pid(self) -> builtins.int:
+        r"""
+        Process ID of the attached process, attaching again if it restarted.
+        
+        Returns:
+            int: The process ID.
+        """
+    def is_attached(self) -> builtins.bool:
+        r"""
+        Whether the attached process is still running.
+        
+        Returns:
+            bool: False once the process exited and no new one was found yet.
+        """
+    def __enter__(self) -> AppSession:
+        r"""
+        Used as `with desktop.attach("notepad") as app:`, fails at once if the
+        application is not running.
+        """
+    def __exit__(self, _exc_type:typing.Optional[typing.Any]=None, _exc_value:typing.Optional[typing.Any]=None, _traceback:typing.Optional[typing.Any]=None) -> builtins.bool:
+        r"""
+        Stops following the application once the session is no longer
+        referenced; exceptions propagate.
+        """
+    def application(self) -> UIElement:
+        r"""
+        The application element of the attached process.
+        
+        Returns:
+            UIElement: The application element.
+        """
+    def locator(self, selector:builtins.str) -> Locator:
+        r"""
+        Create a locator that searches the attached application only.
+        
+        Args:
+            selector (str): The selector string.
+        
+        Returns:
+            Locator: A new locator for finding elements.
+        """
+    def get_window_tree(self, config:typing.Optional[TreeBuildConfig]=None) -> UINode:
+        r"""
+        Get the UI tree of the application's window.
+        
+        Args:
+            config (Optional[TreeBuildConfig]): Optional configuration for tree building.
+        
+        Returns:
+            UINode: Complete UI tree starting from the window.
+        """
+    def capture(self) -> ScreenshotResult:
+        r"""
+        Capture a screenshot of the application's window.
+        
+        Returns:
+            ScreenshotResult: The screenshot data.
+        """
+    def wait_for_attach(self, timeout_ms:typing.Optional[builtins.int]=None) -> typing.Any:
+        r"""
+        (async) Wait until the application runs again after it exited.
+        Returns at once while attached.
+        
+        Args:
+            timeout_ms (Optional[int]): Timeout in milliseconds (default 30000).
+        
+        Returns:
+            UIElement: The application element of the new process.
+        """
+
+class AuditFinding:
+    r"""
+    One problem found by UIElement.audit.
+    """
+    rule: builtins.str
+    severity: builtins.str
+    message: builtins.str
+    role: builtins.str
+    name: typing.Optional[builtins.str]
+    automation_id: typing.Optional[builtins.str]
+    location: builtins.str
+    bounds: typing.Optional[Bounds]
+    contrast_ratio: typing.Optional[builtins.float]
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+
+class AuditReport:
+    r"""
+    Outcome of UIElement.audit.
+    """
+    root: builtins.str
+    elements_checked: builtins.int
+    findings: builtins.list[AuditFinding]
+    has_errors: builtins.bool
+    def to_html(self) -> builtins.str:
+        r"""
+        Render the report as a self-contained HTML page.
+        
+        Returns:
+            str: The HTML page.
+        """
+    def to_json(self) -> builtins.str:
+        r"""
+        Render the report as indented JSON.
+        
+        Returns:
+            str: The JSON document.
+        """
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+
+class AutomationCapabilities:
+    r"""
+    What this process can automate.
+    """
+    integrity_level: typing.Optional[builtins.str]
+    elevated: builtins.bool
+    ui_access: builtins.bool
+    reaches_elevated_windows: builtins.bool
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+
+class BatchResult:
+    r"""
+    Result of one operation of Desktop.batch.
+    """
+    index: builtins.int
+    op: builtins.str
+    selector: builtins.str
+    status: builtins.str
+    text: typing.Optional[builtins.str]
+    error: typing.Optional[builtins.str]
+    error_code: typing.Optional[builtins.str]
+    element: typing.Optional[UIElement]
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+
 class Bounds:
     r"""
     Bounds for element coordinates
@@ -22,6 +232,7 @@ class ClickResult:
     method: builtins.str
     coordinates: typing.Optional[Coordinates]
     details: builtins.str
+    strategy: typing.Optional[builtins.str]
     def __repr__(self) -> builtins.str: ...
     def __str__(self) -> builtins.str: ...
 
@@ -35,6 +246,18 @@ class CommandOutput:
     def __repr__(self) -> builtins.str: ...
     def __str__(self) -> builtins.str: ...
 
+class ConsoleScreen:
+    r"""
+    Visible contents of a console screen buffer
+    """
+    width: builtins.int
+    height: builtins.int
+    cursor: tuple[builtins.int, builtins.int]
+    lines: builtins.list[builtins.str]
+    text: builtins.str
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+
 class Coordinates:
     r"""
     Coordinates for mouse operations
@@ -91,6 +314,30 @@ class Desktop:
         Args:
             name (str): The name of the application to open.
         """
+    def launch(self, path:builtins.str, args:typing.Optional[typing.Sequence[builtins.str]]=None, env:typing.Optional[typing.Mapping[builtins.str, builtins.str]]=None, cwd:typing.Optional[builtins.str]=None) -> AppHandle:
+        r"""
+        Start an executable and keep a handle to its process.
+        
+        Args:
+            path (str): Path or name of the executable.
+            args (Optional[List[str]]): Command line arguments.
+            env (Optional[Dict[str, str]]): Variables added to the inherited environment.
+            cwd (Optional[str]): Working directory.
+        
+        Returns:
+            AppHandle: A handle to wait for the window or exit, or to kill the process.
+        """
+    def attach(self, target:typing.Union[builtins.str, builtins.int]) -> AppSession:
+        r"""
+        Pin locators, trees and screenshots to one application. The session
+        follows the app when its process restarts.
+        
+        Args:
+            target (Union[str, int]): Application name, or process ID.
+        
+        Returns:
+            AppSession: The session, attached to the running app.
+        """
     def activate_application(self, name:builtins.str) -> None:
         r"""
         Activate an application by name.
@@ -108,6 +355,31 @@ class Desktop:
         Returns:
             Locator: A locator for finding elements.
         """
+    def locator_any(self, target:builtins.str, selectors:typing.Sequence[builtins.str]) -> Locator:
+        r"""
+        Create a locator for one logical target that tries several selectors in order.
+        
+        With the selector cache enabled, the selector that resolved the target is
+        remembered per application version and tried first next time.
+        
+        Args:
+            target (str): Name of what is being looked for, e.g. "save button".
+            selectors (List[str]): Candidate selectors, most specific first.
+        
+        Returns:
+            Locator: A locator for finding elements.
+        """
+    def enable_selector_cache(self, path:typing.Optional[builtins.str]=None) -> None:
+        r"""
+        Remember which selector resolved each target of locator_any.
+        
+        Args:
+            path (Optional[str]): JSON file to persist learned selectors in. Kept in memory if omitted.
+        """
+    def disable_selector_cache(self) -> None:
+        r"""
+        Stop learning and preferring selectors for locator_any.
+        """
     def capture_screen(self) -> typing.Any:
         r"""
         (async) Capture a screenshot of the primary monitor.
@@ -115,6 +387,16 @@ class Desktop:
         Returns:
             ScreenshotResult: The screenshot data.
         """
+    def capture_screen_as(self, format:builtins.str) -> typing.Any:
+        r"""
+        (async) Capture the primary monitor encoded as an image file.
+        
+        Args:
+            format (str): "png", "jpeg", "jpeg:<quality>" or "webp".
+        
+        Returns:
+            EncodedImage: The encoded screenshot.
+        """
     def run_command(self, windows_command:typing.Optional[builtins.str]=None, unix_command:typing.Optional[builtins.str]=None) -> typing.Any:
         r"""
         (async) Run a shell command.
@@ -126,6 +408,20 @@ class Desktop:
         Returns:
             CommandOutput: The command output.
         """
+    def batch(self, ops:typing.Sequence[typing.Mapping[builtins.str, builtins.str]], timeout_ms:typing.Optional[builtins.int]=None, stop_on_error:builtins.bool=True) -> typing.Any:
+        r"""
+        (async) Run several element operations in one call, resolving each
+        selector once.
+        
+        Args:
+            ops (List[Dict[str, str]]): Operations such as {"op": "click", "selector": "name:OK"}.
+                op is "find", "click", "type_text" (with "text") or "get_text".
+            timeout_ms (Optional[int]): How long each selector may take to resolve.
+            stop_on_error (bool): Skip the remaining operations after one fails. Defaults to True.
+        
+        Returns:
+            List[BatchResult]: One result per operation.
+        """
     def get_active_monitor_name(self) -> typing.Any:
         r"""
         (async) Get the name of the currently active monitor.
@@ -133,6 +429,49 @@ class Desktop:
         Returns:
             str: The name of the active monitor.
         """
+    def monitors(self) -> builtins.list[MonitorInfo]:
+        r"""
+        List the connected monitors.
+        
+        Returns:
+            List[MonitorInfo]: Name, bounds, scale factor, refresh rate and primary flag of each monitor.
+        """
+    def highlight_all(self, elements:typing.Sequence[UIElement], color:typing.Optional[builtins.int]=None, duration_ms:typing.Optional[builtins.int]=None) -> None:
+        r"""
+        Highlight several elements at once, e.g. everything a locator matched.
+        
+        Args:
+            elements (List[UIElement]): Elements to draw a border around.
+            color (Optional[int]): BGR color code (32-bit integer). Default: 0x0000FF (red)
+            duration_ms (Optional[int]): Duration in milliseconds. Default: 500
+        
+        Returns:
+            None
+        """
+    def environment(self) -> EnvironmentInfo:
+        r"""
+        Get the OS version, theme, animation, scaling and screen reader settings.
+        Attach this to bug reports when automation behaves differently between machines.
+        
+        Returns:
+            EnvironmentInfo: The environment; settings that cannot be read are None.
+        """
+    def capabilities(self) -> AutomationCapabilities:
+        r"""
+        Report what this process can automate: its integrity level, whether it runs as
+        administrator and whether it has UIAccess. Without elevation or UIAccess, actions on
+        applications running as administrator raise ElevationRequiredError.
+        
+        Returns:
+            AutomationCapabilities: The capabilities of this process.
+        """
+    def virtual_desktops(self) -> builtins.list[VirtualDesktop]:
+        r"""
+        List the virtual desktops in Task View order (Windows only).
+        
+        Returns:
+            List[VirtualDesktop]: The desktops; the one shown has is_current set.
+        """
     def capture_monitor_by_name(self, name:builtins.str) -> typing.Any:
         r"""
         (async) Capture a screenshot of a specific monitor.
@@ -143,6 +482,17 @@ class Desktop:
         Returns:
             ScreenshotResult: The screenshot data.
         """
+    def capture_monitor_by_name_as(self, name:builtins.str, format:builtins.str) -> typing.Any:
+        r"""
+        (async) Capture a specific monitor encoded as an image file.
+        
+        Args:
+            name (str): The name of the monitor to capture.
+            format (str): "png", "jpeg", "jpeg:<quality>" or "webp".
+        
+        Returns:
+            EncodedImage: The encoded screenshot.
+        """
     def ocr_image_path(self, image_path:builtins.str) -> typing.Any:
         r"""
         (async) Perform OCR on an image file.
@@ -184,13 +534,30 @@ class Desktop:
         Returns:
             UIElement: The current application element.
         """
-    def open_url(self, url:builtins.str, browser:typing.Optional[builtins.str]=None) -> None:
+    def open_url(self, url:builtins.str, browser:typing.Optional[builtins.str]=None) -> UIElement:
         r"""
         Open a URL in a browser.
         
+        Returns as soon as the browser is launched. Use open_url_and_wait to get the loaded page.
+        
+        Args:
+            url (str): The URL to open.
+            browser (Optional[str]): The browser to use.
+        
+        Returns:
+            UIElement: The browser window.
+        """
+    def open_url_and_wait(self, url:builtins.str, browser:typing.Optional[builtins.str]=None, timeout_ms:typing.Optional[builtins.int]=None) -> typing.Any:
+        r"""
+        (async) Open a URL and wait until the browser shows the page.
+        
         Args:
             url (str): The URL to open.
             browser (Optional[str]): The browser to use.
+            timeout_ms (Optional[int]): How long to wait in milliseconds. Defaults to 30 seconds.
+        
+        Returns:
+            UIElement: The page's document element, ready for chaining locators.
         """
     def open_file(self, file_path:builtins.str) -> None:
         r"""
@@ -225,6 +592,266 @@ class Desktop:
         Returns:
             UINode: Complete UI tree starting from the identified window.
         """
+    def get_window_tree_with_stats(self, pid:builtins.int, title:typing.Optional[builtins.str]=None, config:typing.Optional[TreeBuildConfig]=None) -> tuple[UINode, TreeBuildStats]:
+        r"""
+        Get the UI tree for a window, with how it was built: element count,
+        elements served from cache requests and duration.
+        
+        Args:
+            pid (int): Process ID of the target application.
+            title (Optional[str]): Optional window title filter.
+            config (Optional[TreeBuildConfig]): Optional configuration for tree building.
+        
+        Returns:
+            Tuple[UINode, TreeBuildStats]: The tree and its build statistics.
+        """
+    def get_window_tree_with_handles(self, pid:builtins.int, title:typing.Optional[builtins.str]=None, config:typing.Optional[TreeBuildConfig]=None) -> UINodeWithHandles:
+        r"""
+        Get the UI tree for a window, keeping the live element behind each node
+        so a node found in the tree can be acted on without another search.
+        
+        Args:
+            pid (int): Process ID of the target application.
+            title (Optional[str]): Optional window title filter.
+            config (Optional[TreeBuildConfig]): Optional configuration for tree building.
+        
+        Returns:
+            UINodeWithHandles: The tree with an element for each node.
+        """
+    def get_all_window_trees(self, config:typing.Optional[TreeBuildConfig]=None) -> builtins.list[WindowTree]:
+        r"""
+        Get the UI trees of all visible top-level windows, built concurrently.
+        
+        Args:
+            config (Optional[TreeBuildConfig]): Optional configuration for tree building.
+        
+        Returns:
+            List[WindowTree]: Each window with its UI tree, in z-order.
+        """
+    def session_state(self) -> builtins.str:
+        r"""
+        Get the state of the desktop session this process runs in.
+
+        Returns:
+            str: One of 'active', 'locked', 'disconnected', 'service' or 'unknown'.
+        """
+    def ensure_ui_available(self) -> None:
+        r"""
+        Raise UIUnavailableError if the session is locked, disconnected or non-interactive.
+        """
+    def attach_to_console(self) -> None:
+        r"""
+        Reconnect the current remote session to the console so it stays interactive
+        after the RDP client disconnects. Windows only, requires administrator rights.
+        """
+    def read_console(self, pid:builtins.int) -> ConsoleScreen:
+        r"""
+        Read the visible screen of the console attached to a process (e.g. cmd.exe). Windows only.
+        
+        Args:
+            pid (int): Process id of the console application.
+        
+        Returns:
+            ConsoleScreen: The visible lines and cursor position.
+        """
+    def send_console_input(self, pid:builtins.int, text:builtins.str) -> None:
+        r"""
+        Type text into the console attached to a process. Newlines are sent as Enter. Windows only.
+        
+        Args:
+            pid (int): Process id of the console application.
+            text (str): The text to type.
+        """
+    def wait_for_console_text(self, pid:builtins.int, text:builtins.str, timeout_ms:builtins.int) -> typing.Any:
+        r"""
+        (async) Wait until text appears on the screen of the console attached to a process.
+        
+        Args:
+            pid (int): Process id of the console application.
+            text (str): The text to wait for.
+            timeout_ms (int): How long to wait in milliseconds.
+        
+        Returns:
+            ConsoleScreen: The screen that contained the text.
+        """
+    def notifications(self) -> builtins.list[Notification]:
+        r"""
+        The notifications on screen: Windows toasts or macOS Notification Center banners.
+        
+        Returns:
+            List[Notification]: The notifications, with their app, title, body and actions.
+        """
+    def wait_for_notification(self, text:builtins.str, timeout_ms:builtins.int) -> typing.Any:
+        r"""
+        (async) Wait until a notification whose app name, title or body contains text is on screen.
+        
+        Args:
+            text (str): The text to wait for, ignoring case.
+            timeout_ms (int): How long to wait in milliseconds.
+        
+        Returns:
+            Notification: The first matching notification.
+        """
+    def wait_for_idle(self, pid:builtins.int, timeout_ms:builtins.int, quiet_ms:typing.Optional[builtins.int]=None) -> typing.Any:
+        r"""
+        (async) Wait until an application is idle: started up, its UI thread responsive,
+        and no elements added or removed for a quiet period. Windows only.
+        
+        Args:
+            pid (int): Process id of the application.
+            timeout_ms (int): How long to wait in milliseconds.
+            quiet_ms (Optional[int]): Quiet period without UI structure changes in milliseconds. Default: 500.
+        """
+    def idle_timeout(self, timeout_ms:builtins.int) -> None:
+        r"""
+        Abort the run with StalledRunError if no action completes successfully for the given time.
+        
+        A screenshot, the foreground window and the active application's tree are
+        captured when the watchdog fires. Calling this again replaces the previous watchdog.
+        
+        Args:
+            timeout_ms (int): Idle time in milliseconds before the run is considered stalled.
+        """
+    def clear_idle_timeout(self) -> None:
+        r"""
+        Disarm the idle watchdog, if one is running.
+        """
+    def block_user_input(self, enabled:builtins.bool) -> None:
+        r"""
+        Block physical keyboard and mouse input until called again with False.
+        
+        Input sent by terminator still gets through, and Ctrl+Alt+Del always
+        releases the block. Windows only.
+        
+        Args:
+            enabled (bool): Whether to block input.
+        """
+    def set_auto_input_guard(self, enabled:builtins.bool) -> None:
+        r"""
+        Block physical input while each element action runs. Windows only.
+        
+        Args:
+            enabled (bool): Whether to guard every action.
+        """
+    def set_input_profile(self, profile:builtins.str) -> None:
+        r"""
+        Pace synthetic keyboard and mouse input. Some applications drop keys
+        typed instantly or only react to a pointer that hovered first.
+        
+        Args:
+            profile (str): "instant", "fast" (the default) or "human" for
+                irregular typing, curved pointer movement and a pause before clicks.
+        """
+
+class ElementList:
+    r"""
+    Elements returned by Locator.all(). Supports len(), iteration, indexing
+    with negative indices and slicing, which returns another ElementList.
+    """
+    def __len__(self) -> builtins.int: ...
+    def __bool__(self) -> builtins.bool: ...
+    def __iter__(self) -> ElementListIterator: ...
+    @typing.overload
+    def __getitem__(self, index:builtins.int) -> UIElement: ...
+    @typing.overload
+    def __getitem__(self, index:builtins.slice) -> ElementList: ...
+    def __repr__(self) -> builtins.str: ...
+    def first(self) -> typing.Optional[UIElement]:
+        r"""
+        The first element.
+        
+        Returns:
+            Optional[UIElement]: The first element, or None if the list is empty.
+        """
+    def to_list(self) -> builtins.list[UIElement]:
+        r"""
+        The elements as a list.
+        
+        Returns:
+            List[UIElement]: The elements.
+        """
+
+class ElementListIterator:
+    r"""
+    Iterator over an ElementList.
+    """
+    def __iter__(self) -> ElementListIterator: ...
+    def __next__(self) -> UIElement: ...
+
+class EncodedImage:
+    r"""
+    A screenshot encoded as an image file.
+    """
+    format: builtins.str
+    width: builtins.int
+    height: builtins.int
+    @property
+    def data(self) -> builtins.bytes:
+        r"""
+        The encoded file contents.
+        """
+    def __repr__(self) -> builtins.str: ...
+
+class EnvironmentInfo:
+    r"""
+    OS settings that affect UI trees and timings. Unknown settings are None.
+    """
+    os: builtins.str
+    os_version: typing.Optional[builtins.str]
+    os_build: typing.Optional[builtins.str]
+    theme: typing.Optional[builtins.str]
+    animations_enabled: typing.Optional[builtins.bool]
+    text_scale_factor: typing.Optional[builtins.float]
+    display_scale_factor: typing.Optional[builtins.float]
+    screen_reader_active: typing.Optional[builtins.bool]
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+
+class Expect:
+    r"""
+    Assertions on one element that retry until they hold or time out.
+    Failures raise VerificationFailedError with the last value seen, an
+    excerpt of the element's subtree and the path of a screenshot.
+    """
+    def not_(self) -> Expect:
+        r"""
+        Invert the assertions, e.g. element.expect().not_().to_be_visible().
+        
+        Returns:
+            Expect: The inverted assertions.
+        """
+    def to_be_visible(self) -> None:
+        r"""
+        Wait until the element is visible.
+        """
+    def to_be_enabled(self) -> None:
+        r"""
+        Wait until the element is enabled.
+        """
+    def to_be_focused(self) -> None:
+        r"""
+        Wait until the element has keyboard focus.
+        """
+    def to_have_text(self, expected:builtins.str) -> None:
+        r"""
+        Wait until the element's text contains the expected string.
+        """
+    def to_have_value(self, expected:builtins.str) -> None:
+        r"""
+        Wait until the element's value equals the expected string.
+        """
+    def to_have_name(self, expected:builtins.str) -> None:
+        r"""
+        Wait until the element's name equals the expected string.
+        """
+    def to_have_attribute(self, name:builtins.str, expected:typing.Optional[builtins.str]=None) -> None:
+        r"""
+        Wait until an attribute equals the expected value.
+        
+        Args:
+            name (str): Attribute name, e.g. 'value', 'enabled' or 'description'.
+            expected (Optional[str]): Expected value; None only requires the attribute to have a value.
+        """
 
 class ExploreResponse:
     r"""
@@ -252,6 +879,33 @@ class ExploredElementDetail:
     def __repr__(self) -> builtins.str: ...
     def __str__(self) -> builtins.str: ...
 
+class FailedField:
+    r"""
+    A form field whose control could not be set.
+    """
+    label: builtins.str
+    error: builtins.str
+
+class FilledField:
+    r"""
+    A form field that was filled.
+    """
+    label: builtins.str
+    kind: builtins.str
+    matched_by: builtins.str
+    control_name: typing.Optional[builtins.str]
+
+class FormFillReport:
+    r"""
+    Outcome of UIElement.fill_form.
+    """
+    filled: builtins.list[FilledField]
+    unmatched: builtins.list[builtins.str]
+    failed: builtins.list[FailedField]
+    is_complete: builtins.bool
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+
 class Locator:
     r"""
     Locator for finding UI elements by selector.
@@ -272,7 +926,7 @@ class Locator:
             depth (Optional[int]): Maximum depth to search.
         
         Returns:
-            List[UIElement]: List of matching elements.
+            ElementList: The matching elements, supporting len(), iteration and slicing.
         """
     def wait(self, timeout_ms:typing.Optional[builtins.int]=None) -> typing.Any:
         r"""
@@ -294,6 +948,21 @@ class Locator:
         Returns:
             Locator: A new locator with the specified timeout.
         """
+    def with_retry(self, max_attempts:typing.Optional[builtins.int]=None, initial_delay_ms:builtins.int=100, max_delay_ms:typing.Optional[builtins.int]=None, multiplier:builtins.float=1.0, jitter:builtins.float=0.0, attempt_timeout_ms:typing.Optional[builtins.int]=None) -> Locator:
+        r"""
+        Configure how this locator polls while waiting for an element.
+        
+        Args:
+            max_attempts (Optional[int]): Maximum number of attempts, unlimited if None.
+            initial_delay_ms (int): Delay before the second attempt. Defaults to 100.
+            max_delay_ms (Optional[int]): Upper bound for the delay between attempts. Defaults to 5000.
+            multiplier (float): Factor applied to the delay after each failed attempt. Defaults to 1.0.
+            jitter (float): Random spread applied to each delay, as a fraction (0.0 - 1.0).
+            attempt_timeout_ms (Optional[int]): Cap on how long a single attempt may search.
+        
+        Returns:
+            Locator: A new locator with the specified retry policy.
+        """
     def locator(self, selector:builtins.str) -> Locator:
         r"""
         Chain another selector.
@@ -314,12 +983,127 @@ class Locator:
         Returns:
             Locator: A new locator with the specified root element.
         """
+    def within_viewport(self, container:UIElement) -> Locator:
+        r"""
+        Only match elements inside the visible region of a scrollable container.
+        
+        Args:
+            container (UIElement): The scrollable container to search in.
+        
+        Returns:
+            Locator: A new locator restricted to the container's viewport.
+        """
+    def nth(self, index:builtins.int) -> Locator:
+        r"""
+        Only match the element at a position among the matches.
+        
+        Args:
+            index (int): Zero-based position of the element.
+        
+        Returns:
+            Locator: A new locator matching a single element.
+        """
+    def first_visible(self) -> Locator:
+        r"""
+        Only match the first visible element among the matches.
+        
+        Returns:
+            Locator: A new locator matching the first visible element.
+        """
+    def count(self, timeout_ms:typing.Optional[builtins.int]=None) -> typing.Any:
+        r"""
+        (async) Count the matching elements.
+        
+        Args:
+            timeout_ms (Optional[int]): Timeout in milliseconds.
+        
+        Returns:
+            int: Number of matching elements, 0 if none.
+        """
+
+class MonitorInfo:
+    r"""
+    A connected display
+    """
+    name: builtins.str
+    bounds: Bounds
+    scale_factor: builtins.float
+    refresh_rate: builtins.float
+    is_primary: builtins.bool
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+
+class Notification:
+    r"""
+    A notification on screen: a Windows toast or a macOS Notification Center banner.
+    """
+    @property
+    def app(self) -> typing.Optional[builtins.str]:
+        r"""
+        The app that raised the notification, where the platform shows it.
+        """
+    @property
+    def title(self) -> typing.Optional[builtins.str]: ...
+    @property
+    def body(self) -> typing.Optional[builtins.str]: ...
+    @property
+    def actions(self) -> builtins.list[builtins.str]:
+        r"""
+        Labels of the action buttons, e.g. ["Open", "Show in folder"].
+        """
+    def element(self) -> UIElement:
+        r"""
+        The element of the whole notification.
+        
+        Returns:
+            UIElement: The notification element.
+        """
+    def click(self) -> None:
+        r"""
+        Click the notification itself, which usually opens the app that raised it.
+        """
+    def click_action(self, action:builtins.str) -> None:
+        r"""
+        Click one of the notification's action buttons.
+        
+        Args:
+            action (str): Label of the button, ignoring case.
+        """
+    def dismiss(self) -> None:
+        r"""
+        Close the notification with its dismiss button.
+        """
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
 
 class PropertyLoadingMode:
     r"""
     Property loading strategy for tree building
     """
     mode: builtins.str
+    def __new__(cls, mode:builtins.str='Fast') -> PropertyLoadingMode:
+        r"""
+        Create a property loading mode.
+        
+        Args:
+            mode (str): 'Fast' for essential properties only, 'Complete' for all
+                properties, or 'Smart' to load extra properties by element type.
+        """
+    @staticmethod
+    def fast() -> PropertyLoadingMode:
+        r"""
+        Load only essential properties.
+        """
+    @staticmethod
+    def complete() -> PropertyLoadingMode:
+        r"""
+        Load all properties.
+        """
+    @staticmethod
+    def smart() -> PropertyLoadingMode:
+        r"""
+        Load extra properties depending on the element type.
+        """
     def __repr__(self) -> builtins.str: ...
     def __str__(self) -> builtins.str: ...
 
@@ -332,6 +1116,116 @@ class ScreenshotResult:
     image_data: builtins.list[builtins.int]
     def __repr__(self) -> builtins.str: ...
     def __str__(self) -> builtins.str: ...
+    def to_png(self) -> builtins.bytes:
+        r"""
+        Encode the screenshot as PNG.
+        
+        Returns:
+            bytes: The PNG file contents.
+        """
+    def to_jpeg(self, quality:builtins.int=85) -> builtins.bytes:
+        r"""
+        Encode the screenshot as JPEG.
+        
+        Args:
+            quality (int): JPEG quality from 1 to 100. Defaults to 85.
+        
+        Returns:
+            bytes: The JPEG file contents.
+        """
+    def to_webp(self) -> builtins.bytes:
+        r"""
+        Encode the screenshot as lossless WebP.
+        
+        Returns:
+            bytes: The WebP file contents.
+        """
+
+class SoftVerifier:
+    r"""
+    Collects failed checks across one step and raises them together.
+    """
+    def __new__(cls, step:builtins.str, capture_artifacts:builtins.bool=False) -> SoftVerifier:
+        r"""
+        Create a verifier for a named step.
+        
+        Args:
+            step (str): Name of the verified step, used in the failure report.
+            capture_artifacts (bool): Capture a screenshot of the element for each failed element check.
+        
+        Returns:
+            SoftVerifier: A new verifier.
+        """
+    def check(self, description:builtins.str, condition:builtins.bool, message:builtins.str="") -> builtins.bool:
+        r"""
+        Record a check on an arbitrary condition.
+        
+        Args:
+            description (str): What is being checked.
+            condition (bool): Whether the check passed.
+            message (str): Explanation reported when the check fails.
+        
+        Returns:
+            bool: The condition.
+        """
+    def expect_visible(self, element:UIElement) -> builtins.bool:
+        r"""
+        Check that the element is visible.
+        """
+    def expect_enabled(self, element:UIElement) -> builtins.bool:
+        r"""
+        Check that the element is enabled.
+        """
+    def expect_text(self, element:UIElement, expected:builtins.str) -> builtins.bool:
+        r"""
+        Check that the element's text contains the expected string.
+        """
+    def expect_name(self, element:UIElement, expected:builtins.str) -> builtins.bool:
+        r"""
+        Check that the element's name equals the expected string.
+        """
+    def expect_value(self, element:UIElement, expected:builtins.str) -> builtins.bool:
+        r"""
+        Check that the element's value equals the expected string.
+        """
+    def passed(self) -> builtins.bool:
+        r"""
+        Whether all checks so far passed.
+        """
+    def report(self) -> builtins.str:
+        r"""
+        Get the report of all checks so far as a JSON string.
+        """
+    def write_bundle(self, dir:builtins.str) -> builtins.list[builtins.str]:
+        r"""
+        Write report.json and failure screenshots into a directory.
+        
+        Args:
+            dir (str): Target directory, created if missing.
+        
+        Returns:
+            List[str]: Paths of the written files.
+        """
+    def finish(self) -> None:
+        r"""
+        Raise VerificationFailedError listing every failed check, if any.
+        """
+
+class TextRun:
+    r"""
+    A stretch of text with the same formatting. Colors are "#rrggbb".
+    """
+    text: builtins.str
+    start: builtins.int
+    font_name: typing.Optional[builtins.str]
+    font_size: typing.Optional[builtins.float]
+    bold: builtins.bool
+    italic: builtins.bool
+    underline: builtins.bool
+    foreground_color: typing.Optional[builtins.str]
+    background_color: typing.Optional[builtins.str]
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
 
 class TreeBuildConfig:
     r"""
@@ -341,6 +1235,35 @@ class TreeBuildConfig:
     timeout_per_operation_ms: typing.Optional[builtins.int]
     yield_every_n_elements: typing.Optional[builtins.int]
     batch_size: typing.Optional[builtins.int]
+    max_total_duration_ms: typing.Optional[builtins.int]
+    use_cache_request: typing.Optional[builtins.bool]
+    def __new__(cls, property_mode:typing.Optional[PropertyLoadingMode]=None, timeout_per_operation_ms:typing.Optional[builtins.int]=None, yield_every_n_elements:typing.Optional[builtins.int]=None, batch_size:typing.Optional[builtins.int]=None, max_total_duration_ms:typing.Optional[builtins.int]=None, use_cache_request:typing.Optional[builtins.bool]=None) -> TreeBuildConfig:
+        r"""
+        Create a tree build configuration.
+        
+        Args:
+            property_mode (Optional[PropertyLoadingMode]): Which properties to load. Defaults to Fast.
+            timeout_per_operation_ms (Optional[int]): Timeout for each property lookup.
+            yield_every_n_elements (Optional[int]): Yield to other tasks after this many elements.
+            batch_size (Optional[int]): Number of elements processed per batch.
+            max_total_duration_ms (Optional[int]): Stop building and mark the tree truncated after this long.
+            use_cache_request (Optional[bool]): Fetch children with their properties in one call (Windows). Defaults to True.
+        """
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+
+class TreeBuildStats:
+    r"""
+    How a window tree was built, from Desktop.get_window_tree_with_stats
+    """
+    elements: builtins.int
+    max_depth: builtins.int
+    cached_elements: builtins.int
+    cache_requests: builtins.int
+    uncached_fallbacks: builtins.int
+    errors: builtins.int
+    truncated_subtrees: builtins.int
+    duration_ms: builtins.int
     def __repr__(self) -> builtins.str: ...
     def __str__(self) -> builtins.str: ...
 
@@ -376,6 +1299,21 @@ class UIElement:
         Returns:
             UIElementAttributes: The element's attributes.
         """
+    def __getitem__(self, key:builtins.str) -> typing.Any:
+        r"""
+        An attribute by name, e.g. element["name"]: role, name, id, label,
+        value, description, is_keyboard_focusable, or a platform property.
+        Raises KeyError for attributes the element does not have.
+        """
+    def __enter__(self) -> UIElement:
+        r"""
+        Activates the element's window for the `with` block:
+        `with desktop.application("notepad") as app:`.
+        """
+    def __exit__(self, _exc_type:typing.Optional[typing.Any]=None, _exc_value:typing.Optional[typing.Any]=None, _traceback:typing.Optional[typing.Any]=None) -> builtins.bool:
+        r"""
+        Leaves the application running; exceptions propagate.
+        """
     def children(self) -> builtins.list[UIElement]:
         r"""
         Get child elements.
@@ -390,6 +1328,38 @@ class UIElement:
         Returns:
             Optional[UIElement]: The parent element, if available.
         """
+    def ancestors(self) -> builtins.list[UIElement]:
+        r"""
+        Get the parent, its parent and so on up to the root, nearest first.
+        
+        Returns:
+            List[UIElement]: The ancestor elements.
+        """
+    def next_sibling(self) -> typing.Optional[UIElement]:
+        r"""
+        Get the element after this one among its parent's children.
+        
+        Returns:
+            Optional[UIElement]: The next sibling, or None for the last child.
+        """
+    def previous_sibling(self) -> typing.Optional[UIElement]:
+        r"""
+        Get the element before this one among its parent's children.
+        
+        Returns:
+            Optional[UIElement]: The previous sibling, or None for the first child.
+        """
+    def closest(self, selector:builtins.str) -> typing.Optional[UIElement]:
+        r"""
+        Get the nearest of this element and its ancestors matching a selector,
+        e.g. the dialog a button is in with "role:Window".
+        
+        Args:
+            selector (str): A role, id, name, text, nativeid or classname selector, or a not/and/or combination of them.
+        
+        Returns:
+            Optional[UIElement]: The matching element, if any.
+        """
     def bounds(self) -> Bounds:
         r"""
         Get element bounds (x, y, width, height).
@@ -397,6 +1367,53 @@ class UIElement:
         Returns:
             Bounds: The element's bounds.
         """
+    def monitor(self) -> typing.Optional[MonitorInfo]:
+        r"""
+        Get the monitor this element is displayed on.
+        
+        Returns:
+            Optional[MonitorInfo]: The monitor, or None if the element is off-screen.
+        """
+    def center(self) -> Coordinates:
+        r"""
+        Get the center of the element in physical screen pixels.
+        
+        Returns:
+            Coordinates: The element's center point.
+        """
+    def point_at(self, anchor:builtins.str, offset_x:builtins.float=0.0, offset_y:builtins.float=0.0) -> Coordinates:
+        r"""
+        Get a point relative to the element, e.g. 10px left of its center.
+        
+        Args:
+            anchor (str): One of 'center', 'top_left', 'top', 'top_right', 'left', 'right', 'bottom_left', 'bottom', 'bottom_right', or a percentage like '30%,50%'.
+            offset_x (float): Horizontal offset in pixels.
+            offset_y (float): Vertical offset in pixels.
+        
+        Returns:
+            Coordinates: The resulting screen point.
+        """
+    def intersects(self, other:UIElement) -> builtins.bool:
+        r"""
+        Check whether this element's bounds overlap another element's bounds.
+        
+        Args:
+            other (UIElement): The element to test against.
+        
+        Returns:
+            bool: True if the bounds intersect.
+        """
+    def contains_point(self, x:builtins.float, y:builtins.float) -> builtins.bool:
+        r"""
+        Check whether a screen point lies inside this element.
+        
+        Args:
+            x (float): X coordinate in pixels.
+            y (float): Y coordinate in pixels.
+        
+        Returns:
+            bool: True if the point is inside the element's bounds.
+        """
     def click(self) -> ClickResult:
         r"""
         Click on this element.
@@ -404,6 +1421,63 @@ class UIElement:
         Returns:
             ClickResult: Result of the click operation.
         """
+    def click_with_strategy(self, strategy:builtins.str) -> ClickResult:
+        r"""
+        Click on this element delivering the click one way only.
+        
+        Args:
+            strategy (str): "uia_pattern" (the accessibility action), "send_input_cursor" (real cursor input) or "post_message" (window messages, Windows only).
+        
+        Returns:
+            ClickResult: Result of the click operation.
+        """
+    def click_escalating(self, strategies:typing.Optional[typing.Sequence[builtins.str]]=None) -> ClickResult:
+        r"""
+        Click on this element trying strategies in order until one delivers the click.
+        
+        Args:
+            strategies (Optional[List[str]]): Strategies to try, by default "uia_pattern", "send_input_cursor" then "post_message".
+        
+        Returns:
+            ClickResult: Result of the click, with the strategy that worked.
+        """
+    def click_at_anchor(self, anchor:builtins.str, offset_x:builtins.float=0.0, offset_y:builtins.float=0.0) -> ClickResult:
+        r"""
+        Click at an anchor point of this element instead of its center.
+        The point is kept inside the element before the offset is applied.
+        
+        Args:
+            anchor (str): One of 'center', 'top_left', 'top', 'top_right', 'left', 'right', 'bottom_left', 'bottom', 'bottom_right', or a percentage like '30%,50%'.
+            offset_x (float): Horizontal offset in pixels.
+            offset_y (float): Vertical offset in pixels.
+        
+        Returns:
+            ClickResult: Result of the click, with the clicked point in `coordinates`.
+        """
+    def click_and_expect(self, condition:builtins.str, value:typing.Optional[builtins.str]=None, timeout_ms:builtins.int=2000) -> VerifiedActionResult:
+        r"""
+        Click this element and wait for the expected effect, retrying with a
+        physical mouse click if the first click had no effect.
+        
+        Args:
+            condition (str): One of 'gone', 'focused', 'enabled', 'disabled', 'name_equals', 'value_equals', 'value_contains', 'text_contains'.
+            value (Optional[str]): Expected string for the equals and contains conditions.
+            timeout_ms (int): How long to wait for the effect after each attempt.
+        
+        Returns:
+            VerifiedActionResult: Which attempt and method produced the effect.
+        """
+    def type_text_verified(self, text:builtins.str) -> VerifiedActionResult:
+        r"""
+        Replace this element's value with text and check that it was taken,
+        retrying by pasting and then through the value pattern.
+        
+        Args:
+            text (str): The text to enter.
+        
+        Returns:
+            VerifiedActionResult: Which attempt and method set the value.
+        """
     def double_click(self) -> ClickResult:
         r"""
         Double click on this element.
@@ -456,13 +1530,14 @@ class UIElement:
         Returns:
             str: The element's text content.
         """
-    def type_text(self, text:builtins.str, use_clipboard:typing.Optional[builtins.bool]=None) -> None:
+    def type_text(self, text:builtins.str, use_clipboard:typing.Optional[builtins.bool]=None, wait_for_editable:builtins.bool=True) -> None:
         r"""
         Type text into this element.
         
         Args:
             text (str): The text to type.
             use_clipboard (Optional[bool]): Whether to use clipboard for pasting.
+            wait_for_editable (bool): Wait until the element is enabled, focusable and not read-only before typing.
         
         Returns:
             None
@@ -497,6 +1572,97 @@ class UIElement:
         Returns:
             None
         """
+    def invoke(self) -> None:
+        r"""
+        Trigger the element's default action without moving the mouse.
+        """
+    def toggle(self) -> None:
+        r"""
+        Move a check box or toggle button to its next state without clicking it.
+        """
+    def expand(self) -> None:
+        r"""
+        Expand a combo box, menu item or tree item.
+        """
+    def collapse(self) -> None:
+        r"""
+        Collapse a combo box, menu item or tree item.
+        """
+    def select(self) -> None:
+        r"""
+        Select the element in its list, combo box or tab strip without clicking it.
+        """
+    def expect(self, timeout_ms:typing.Optional[builtins.int]=None, screenshot_dir:typing.Optional[builtins.str]=None) -> Expect:
+        r"""
+        Start assertions on this element that retry until they hold, e.g.
+        element.expect().to_have_text("Saved").
+        
+        Args:
+            timeout_ms (Optional[int]): How long to retry, 5000 by default.
+            screenshot_dir (Optional[str]): Where failure screenshots go, the temp directory by default.
+        
+        Returns:
+            Expect: The assertions.
+        """
+    def selected_text(self) -> builtins.str:
+        r"""
+        Text selected in an edit control or document.
+        
+        Returns:
+            str: The selection, empty when nothing is selected.
+        """
+    def caret_position(self) -> typing.Optional[builtins.int]:
+        r"""
+        Character offset of the caret in an edit control or document.
+        
+        Returns:
+            Optional[int]: The offset, or None when the element shows no caret.
+        """
+    def select_range(self, start:builtins.int, end:builtins.int) -> None:
+        r"""
+        Select the characters from offset start up to end. start == end places the caret.
+        
+        Args:
+            start (int): Offset of the first selected character.
+            end (int): Offset just past the last selected character.
+        
+        Returns:
+            None
+        """
+    def text_with_formatting(self) -> builtins.list[TextRun]:
+        r"""
+        The text of an edit control or document as runs of the same font, weight, style and colors.
+        
+        Returns:
+            List[TextRun]: The runs, in document order.
+        """
+    def fill_form(self, fields:typing.Mapping[builtins.str, builtins.str]) -> FormFillReport:
+        r"""
+        Fill the controls below this element (a window or group) by label.
+        Each label is matched to a control through the label it declares,
+        its name, the text next to it, or a fuzzy match of its name, and set
+        the way its type needs.
+        
+        Args:
+            fields (Dict[str, str]): Values by label, e.g. {"First name": "Ada", "Newsletter": "yes"}.
+                Check boxes take yes/no, sliders a number, combo boxes the option name.
+        
+        Returns:
+            FormFillReport: Filled, unmatched and failed fields.
+        """
+    def audit(self, check_contrast:builtins.bool=False, max_depth:typing.Optional[builtins.int]=None) -> AuditReport:
+        r"""
+        Audit the accessibility of the elements below this element (usually a window):
+        controls without a name, interactive controls the keyboard can't reach,
+        duplicate automation IDs and, optionally, text with low contrast.
+        
+        Args:
+            check_contrast (bool): Screenshot the element and check text contrast. It must be visible on screen.
+            max_depth (Optional[int]): How deep below the element to look. Defaults to 30.
+        
+        Returns:
+            AuditReport: The findings, also available as HTML with to_html().
+        """
     def scroll(self, direction:builtins.str, amount:builtins.float) -> None:
         r"""
         Scroll the element in a given direction.
@@ -515,6 +1681,14 @@ class UIElement:
         Returns:
             None
         """
+    def is_stale(self) -> builtins.bool:
+        r"""
+        Check whether the control behind this handle no longer exists, e.g. a
+        list row recreated by virtualization. Stale elements fail every action.
+        
+        Returns:
+            bool: True if the element is gone.
+        """
     def is_focused(self) -> builtins.bool:
         r"""
         Check if element is focused.
@@ -627,6 +1801,100 @@ class UIElement:
         Returns:
             ScreenshotResult: The screenshot data containing image data and dimensions.
         """
+    def capture_as(self, format:builtins.str) -> EncodedImage:
+        r"""
+        Capture a screenshot of this element encoded as an image file.
+        
+        Args:
+            format (str): "png", "jpeg", "jpeg:<quality>" or "webp".
+        
+        Returns:
+            EncodedImage: The encoded screenshot.
+        """
+    def is_topmost(self) -> builtins.bool:
+        r"""
+        Check whether the window containing this element is always on top.
+        
+        Returns:
+            bool: True if the window is topmost.
+        """
+    def set_topmost(self, topmost:builtins.bool) -> None:
+        r"""
+        Make the window containing this element always on top, or clear it.
+        
+        Args:
+            topmost (bool): Whether the window should stay on top.
+        """
+    def opacity(self) -> builtins.float:
+        r"""
+        Get the opacity of the window containing this element.
+        
+        Returns:
+            float: Opacity between 0.0 (transparent) and 1.0 (opaque).
+        """
+    def set_opacity(self, opacity:builtins.float) -> None:
+        r"""
+        Set the opacity of the window containing this element.
+        
+        Args:
+            opacity (float): Opacity between 0.0 (transparent) and 1.0 (opaque).
+        """
+    def window_state(self) -> builtins.str:
+        r"""
+        Get the show state of the window containing this element.
+        
+        Returns:
+            str: One of 'normal', 'minimized' or 'maximized'.
+        """
+    def maximize(self) -> None:
+        r"""
+        Maximize the window containing this element.
+        """
+    def minimize(self) -> None:
+        r"""
+        Minimize the window containing this element.
+        """
+    def restore(self) -> None:
+        r"""
+        Restore the window containing this element from the minimized or maximized state.
+        """
+    def set_bounds(self, x:builtins.float, y:builtins.float, width:builtins.float, height:builtins.float) -> None:
+        r"""
+        Move and resize the window containing this element.
+        
+        Args:
+            x (float): Left edge in screen coordinates.
+            y (float): Top edge in screen coordinates.
+            width (float): Window width.
+            height (float): Window height.
+        """
+    def bring_to_front(self) -> None:
+        r"""
+        Raise the window containing this element above all others and focus it.
+        """
+    def virtual_desktop(self) -> builtins.str:
+        r"""
+        Get the id of the virtual desktop the window containing this element is on.
+        
+        Returns:
+            str: The desktop GUID.
+        """
+    def is_on_current_virtual_desktop(self) -> builtins.bool:
+        r"""
+        Check whether the window containing this element is on the virtual desktop shown.
+        Elements on other desktops cannot be clicked.
+        
+        Returns:
+            bool: True if the window is on the current desktop.
+        """
+    def move_to_desktop(self, desktop_id:builtins.str) -> None:
+        r"""
+        Move the window containing this element to another virtual desktop.
+        Windows only allows this for windows of the calling process.
+        
+        Args:
+            desktop_id (str): Id from Desktop.virtual_desktops().
+        """
 
 class UIElementAttributes:
     r"""
@@ -641,13 +1909,144 @@ class UIElementAttributes:
     is_keyboard_focusable: typing.Optional[builtins.bool]
     def __repr__(self) -> builtins.str: ...
     def __str__(self) -> builtins.str: ...
+    def to_dict(self) -> builtins.dict[builtins.str, typing.Any]:
+        r"""
+        Convert the attributes to a dict.
+        
+        Returns:
+            dict: The attributes, keyed by field name.
+        """
 
 class UINode:
     r"""
     UI Node representing a tree structure of UI elements
     """
+    id: typing.Optional[builtins.str]
     attributes: UIElementAttributes
+    path: typing.Optional[builtins.str]
+    truncated: builtins.bool
+    status: builtins.str
     children: builtins.list[UINode]
     def __repr__(self) -> builtins.str: ...
     def __str__(self) -> builtins.str: ...
+    def to_dict(self) -> builtins.dict[builtins.str, typing.Any]:
+        r"""
+        Convert the node and its descendants to plain dicts and lists.
+        
+        Returns:
+            dict: The tree, with the same keys as the node's attributes.
+        """
+    def count(self) -> builtins.int:
+        r"""
+        Count the nodes in this tree, including this one.
+        
+        Returns:
+            int: The number of nodes.
+        """
+    def walk(self) -> builtins.list[UINode]:
+        r"""
+        List this node and all its descendants in depth-first order.
+        
+        Returns:
+            List[UINode]: Every node of the tree, starting with this one.
+        """
+
+class UINodeWithHandles:
+    r"""
+    A window tree that keeps the live element behind each node. Nodes are
+    numbered depth-first, the window being 0, so a node found in the tree can
+    be acted on without searching for it again.
+    """
+    tree: UINode
+    def __len__(self) -> builtins.int: ...
+    def element(self, index:builtins.int) -> typing.Optional[UIElement]:
+        r"""
+        The element of a node.
+        
+        Args:
+            index (int): Depth-first number of the node.
+        
+        Returns:
+            Optional[UIElement]: The element, or None past the last node.
+        """
+    def node(self, index:builtins.int) -> typing.Optional[UINode]:
+        r"""
+        A node by its depth-first number.
+        
+        Args:
+            index (int): Depth-first number of the node.
+        
+        Returns:
+            Optional[UINode]: The node, or None past the last node.
+        """
+    def element_at(self, index_path:typing.Sequence[builtins.int]) -> typing.Optional[UIElement]:
+        r"""
+        The element of the node reached by following child indices from the root.
+        
+        Args:
+            index_path (List[int]): Child index at each level, [] for the window.
+        
+        Returns:
+            Optional[UIElement]: The element, or None if the path leads nowhere.
+        """
+    def find_all(self, role:typing.Optional[builtins.str]=None, name:typing.Optional[builtins.str]=None) -> builtins.list[builtins.int]:
+        r"""
+        Numbers of the nodes with the given role and name, depth-first.
+        
+        Args:
+            role (Optional[str]): Role to match, e.g. "Button".
+            name (Optional[str]): Name to match exactly.
+        
+        Returns:
+            List[int]: Depth-first numbers of the matching nodes.
+        """
+
+class VerifiedActionResult:
+    r"""
+    Outcome of an action whose effect was verified.
+    """
+    method: builtins.str
+    attempt: builtins.int
+    attempts: builtins.list[ActionAttempt]
+    click: typing.Optional[ClickResult]
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+
+class VirtualDesktop:
+    r"""
+    A virtual desktop, as shown in Task View
+    """
+    id: builtins.str
+    name: builtins.str
+    index: builtins.int
+    is_current: builtins.bool
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+
+class WindowInfo:
+    r"""
+    A top-level window captured in a desktop snapshot
+    """
+    pid: builtins.int
+    title: builtins.str
+    id: typing.Optional[builtins.str]
+    bounds: typing.Optional[Bounds]
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+
+class WindowTree:
+    r"""
+    A window together with its UI tree
+    """
+    window: WindowInfo
+    tree: UINode
+    def __repr__(self) -> builtins.str: ...
+    def __str__(self) -> builtins.str: ...
+    def to_dict(self) -> builtins.dict[builtins.str, typing.Any]:
+        r"""
+        Convert the window and its tree to plain dicts and lists.
+        
+        Returns:
+            dict: A dict with 'window' and 'tree' keys.
+        """
 
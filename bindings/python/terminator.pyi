@@ -2,6 +2,8 @@
 # ruff: noqa: E501, F401
 
 
+class ElementNotEditableError(RuntimeError): ...
+
 class ElementNotFoundError(RuntimeError): ...
 
 class InternalError(RuntimeError): ...
@@ -17,3 +19,15 @@ class TimeoutError(RuntimeError): ...
 class UnsupportedOperationError(RuntimeError): ...
 
 class UnsupportedPlatformError(RuntimeError): ...
+
+class UIUnavailableError(RuntimeError): ...
+
+class StalledRunError(RuntimeError): ...
+
+class VerificationFailedError(AssertionError): ...
+
+class ElevationRequiredError(PermissionError): ...
+
+class MissingPermissionError(PermissionError): ...
+
+class StaleElementError(ElementNotFoundError): ...
@@ -0,0 +1,100 @@
+//! `terminator-cli bench`
+//!
+//! Runs the [`terminator::bench`] scenarios, prints a line per scenario and
+//! the JSON report, and fails when a scenario failed or got slower than a
+//! baseline report. Without `--app` the standard scenarios run against the
+//! platform's text editor.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use terminator::bench::{BenchReport, Benchmark, Scenario};
+use terminator::Desktop;
+
+pub struct BenchOptions {
+    pub app: Option<String>,
+    pub selector: Option<String>,
+    pub warmup: usize,
+    pub iterations: usize,
+    pub timeout: Duration,
+    pub output: Option<PathBuf>,
+    pub baseline: Option<PathBuf>,
+    pub tolerance: f64,
+}
+
+/// The scenarios for `app`; the selector ones only with a selector
+fn scenarios(options: &BenchOptions) -> Vec<Scenario> {
+    let Some(app) = &options.app else {
+        return Scenario::standard();
+    };
+    let mut scenarios = vec![Scenario::TreeBuild { app: app.clone() }];
+    if let Some(selector) = &options.selector {
+        scenarios.push(Scenario::SelectorResolution {
+            app: app.clone(),
+            selector: selector.clone(),
+        });
+        scenarios.push(Scenario::ClickLatency {
+            app: app.clone(),
+            selector: selector.clone(),
+        });
+    }
+    scenarios
+}
+
+pub async fn run(desktop: &Desktop, options: &BenchOptions) -> Result<()> {
+    // Read first so a bad path fails before the benchmark runs
+    let baseline = match &options.baseline {
+        Some(path) => {
+            let json =
+                std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+            Some(BenchReport::from_json(&json).with_context(|| format!("Invalid baseline {}", path.display()))?)
+        }
+        None => None,
+    };
+
+    let report = Benchmark::new(scenarios(options))
+        .warmup(options.warmup)
+        .iterations(options.iterations)
+        .timeout(options.timeout)
+        .run(desktop)
+        .await;
+    for result in &report.results {
+        match &result.stats {
+            Some(stats) => eprintln!(
+                "{}: median {:.1} ms, p95 {:.1} ms, min {:.1} ms, max {:.1} ms",
+                result.name, stats.median_ms, stats.p95_ms, stats.min_ms, stats.max_ms
+            ),
+            None => eprintln!("{}: failed", result.name),
+        }
+    }
+
+    let json = report.to_json()?;
+    match &options.output {
+        Some(path) => {
+            std::fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!("Wrote the report to {}", path.display());
+        }
+        None => println!("{}", json),
+    }
+
+    for result in &report.results {
+        for error in &result.errors {
+            eprintln!("{}: {}", result.name, error);
+        }
+    }
+    // Failed runs are counted on their own, with or without a baseline
+    let mut regressions = match &baseline {
+        Some(baseline) => report.compare(baseline, options.tolerance),
+        None => Vec::new(),
+    };
+    regressions.retain(|r| r.metric != "errors");
+    for regression in &regressions {
+        eprintln!("Regression: {}", regression);
+    }
+    let failed = report.results.iter().filter(|r| !r.errors.is_empty()).count();
+    if failed > 0 || !regressions.is_empty() {
+        bail!("{} scenarios failed, {} regressions", failed, regressions.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,447 @@
+mod bench;
+mod replay;
+mod script;
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use serde_json::json;
+use terminator::{Desktop, ImageFormat, Locator, Selector, UIElement};
+use terminator_workflow_recorder::{
+    FlowGraph, IntentEngine, RecordedWorkflow, WorkflowRecorder, WorkflowRecorderConfig,
+};
+use tracing::info;
+use tracing_subscriber::EnvFilter;
+
+/// Inspect, record, replay and script desktop automation
+#[derive(Parser)]
+#[command(name = "terminator-cli", version)]
+struct Cli {
+    /// Log progress to stderr (set RUST_LOG for finer control)
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the UI tree of an application's window as JSON
+    Inspect {
+        /// Application name or process id
+        app: String,
+        /// Only the window whose title contains this text
+        #[arg(long)]
+        title: Option<String>,
+        /// Write the tree to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Print the elements a selector chain matches, to test selectors
+    Find {
+        /// Selectors, each searched within the matches of the previous one
+        #[arg(required = true)]
+        selectors: Vec<String>,
+        /// How long to wait for a match
+        #[arg(long, default_value_t = 3000)]
+        timeout_ms: u64,
+        /// Print every match instead of the first
+        #[arg(long)]
+        all: bool,
+    },
+    /// Record user input until Ctrl+C and save it as a workflow (Windows only)
+    Record {
+        #[arg(short, long, default_value = "workflow.json")]
+        output: PathBuf,
+        /// Name stored in the workflow
+        #[arg(long, default_value = "Recorded workflow")]
+        name: String,
+    },
+    /// Replay the clicks, text and hotkeys of a recorded workflow
+    Replay {
+        /// A workflow saved by `record`
+        workflow: PathBuf,
+        /// Playback speed relative to the recording. Steps still wait as long as
+        /// the UI took to respond while recording
+        #[arg(long, default_value_t = 1.0)]
+        speed: f64,
+        /// Longest pause between two steps, however long the user paused
+        #[arg(long, default_value_t = 2000)]
+        max_wait_ms: u64,
+        /// How long to wait for each step's element
+        #[arg(long, default_value_t = 5000)]
+        timeout_ms: u64,
+        /// Print the steps without performing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Write the steps of a recorded workflow as a flow diagram
+    Export {
+        /// A workflow saved by `record`
+        workflow: PathBuf,
+        /// `dot` for Graphviz, `bpmn` for BPMN 2.0 XML
+        #[arg(long, default_value = "dot")]
+        format: String,
+        /// Write the diagram to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Save a screenshot as PNG
+    Screenshot {
+        #[arg(short, long, default_value = "screenshot.png")]
+        output: PathBuf,
+        /// Capture this monitor instead of the primary one
+        #[arg(long, conflicts_with = "app")]
+        monitor: Option<String>,
+        /// Capture this application's window instead of a monitor
+        #[arg(long)]
+        app: Option<String>,
+    },
+    /// Run the steps of a YAML or JSON script
+    Run {
+        script: PathBuf,
+        /// Variable for the script, as name=value; repeatable
+        #[arg(long = "var", value_name = "NAME=VALUE")]
+        variables: Vec<String>,
+        /// Resolve every element but only print the actions instead of performing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Benchmark tree building, selector resolution and clicks, printing a
+    /// JSON report. Fails if a scenario failed or regressed against a baseline
+    Bench {
+        /// Application to benchmark instead of the platform's text editor
+        #[arg(long)]
+        app: Option<String>,
+        /// Element of `--app` to find and click; without it only the tree is built
+        #[arg(long, requires = "app")]
+        selector: Option<String>,
+        /// Runs per scenario before the measured ones
+        #[arg(long, default_value_t = 2)]
+        warmup: usize,
+        /// Measured runs per scenario
+        #[arg(long, default_value_t = 10)]
+        iterations: usize,
+        /// How long to wait for the selector's element
+        #[arg(long, default_value_t = 5000)]
+        timeout_ms: u64,
+        /// Write the report to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// A report saved earlier to compare the medians against
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        /// Slowdown over the baseline that counts as a regression, 0.25 for 25%
+        #[arg(long, default_value_t = 0.25)]
+        tolerance: f64,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let default_level = if cli.verbose { "info" } else { "warn" };
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_level)))
+        .with_writer(std::io::stderr)
+        .init();
+
+    match cli.command {
+        Command::Inspect { app, title, output } => inspect(&app, title.as_deref(), output).await,
+        Command::Find { selectors, timeout_ms, all } => find(&selectors, timeout_ms, all).await,
+        Command::Record { output, name } => record(output, name).await,
+        Command::Replay {
+            workflow,
+            speed,
+            max_wait_ms,
+            timeout_ms,
+            dry_run,
+        } => {
+            if speed <= 0.0 {
+                bail!("--speed must be greater than 0");
+            }
+            let options = replay::ReplayOptions {
+                speed,
+                max_wait: Duration::from_millis(max_wait_ms),
+                timeout: Duration::from_millis(timeout_ms),
+                dry_run,
+            };
+            replay::replay(&desktop()?, &workflow, &options).await
+        }
+        Command::Export { workflow, format, output } => export(&workflow, &format, output),
+        Command::Screenshot { output, monitor, app } => screenshot(output, monitor, app).await,
+        Command::Run {
+            script,
+            variables,
+            dry_run,
+        } => {
+            let variables = script::parse_variables(&variables)?;
+            script::run(&desktop()?, &script, variables, dry_run).await
+        }
+        Command::Bench {
+            app,
+            selector,
+            warmup,
+            iterations,
+            timeout_ms,
+            output,
+            baseline,
+            tolerance,
+        } => {
+            let options = bench::BenchOptions {
+                app,
+                selector,
+                warmup,
+                iterations,
+                timeout: Duration::from_millis(timeout_ms),
+                output,
+                baseline,
+                tolerance,
+            };
+            bench::run(&desktop()?, &options).await
+        }
+    }
+}
+
+fn desktop() -> Result<Desktop> {
+    Desktop::new(false, false).context("Failed to initialize the desktop")
+}
+
+/// A locator for a chain of selectors, each within the previous one
+fn chain_locator(desktop: &Desktop, selectors: &[String]) -> Result<Locator> {
+    let Some((first, rest)) = selectors.split_first() else {
+        bail!("At least one selector is required");
+    };
+    let mut locator = desktop.locator(Selector::from(first.as_str()));
+    for selector in rest {
+        locator = locator.locator(Selector::from(selector.as_str()));
+    }
+    Ok(locator)
+}
+
+/// Short description of an element for printing
+fn element_summary(element: &UIElement) -> serde_json::Value {
+    let attributes = element.attributes();
+    json!({
+        "role": attributes.role,
+        "name": attributes.name,
+        "value": attributes.value,
+        "id": element.id(),
+        "application": element.application_name(),
+        "bounds": element.bounds().ok(),
+    })
+}
+
+// A process id, or an application name
+fn application_pid(desktop: &Desktop, app: &str) -> Result<u32> {
+    if let Ok(pid) = app.trim().parse() {
+        return Ok(pid);
+    }
+    let element = desktop
+        .application(app)
+        .with_context(|| format!("Application '{}' not found", app))?;
+    Ok(element.process_id()?)
+}
+
+async fn inspect(app: &str, title: Option<&str>, output: Option<PathBuf>) -> Result<()> {
+    let desktop = desktop()?;
+    let pid = application_pid(&desktop, app)?;
+    let tree = desktop.get_window_tree(pid, title, None)?;
+    let json = serde_json::to_string_pretty(&tree)?;
+    match output {
+        Some(path) => {
+            std::fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!("Wrote {} nodes to {}", tree.count(), path.display());
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+async fn find(selectors: &[String], timeout_ms: u64, all: bool) -> Result<()> {
+    let desktop = desktop()?;
+    let locator = chain_locator(&desktop, selectors)?;
+    let timeout = Some(Duration::from_millis(timeout_ms));
+    let elements = if all {
+        locator.all(timeout, None).await?
+    } else {
+        vec![locator.first(timeout).await?]
+    };
+    let summaries: Vec<_> = elements.iter().map(element_summary).collect();
+    println!("{}", serde_json::to_string_pretty(&summaries)?);
+    Ok(())
+}
+
+async fn record(output: PathBuf, name: String) -> Result<()> {
+    let mut recorder = WorkflowRecorder::new(name, WorkflowRecorderConfig::default());
+    recorder.start().await?;
+    eprintln!("Recording. Press Ctrl+C to stop and save to {}", output.display());
+
+    tokio::signal::ctrl_c().await?;
+    recorder.stop().await?;
+    recorder
+        .save(&output)
+        .with_context(|| format!("Failed to save {}", output.display()))?;
+    let events = recorder.workflow()?.events.len();
+    eprintln!("Saved {} events to {}", events, output.display());
+    Ok(())
+}
+
+fn export(path: &Path, format: &str, output: Option<PathBuf>) -> Result<()> {
+    let workflow = RecordedWorkflow::load_from_file(path)
+        .map_err(|e| anyhow::anyhow!("Failed to load {}: {}", path.display(), e))?;
+    let intents = IntentEngine::default().infer(&workflow.recorded_events());
+    let graph = FlowGraph::from_intents(workflow.name.clone(), &intents);
+    let diagram = match format {
+        "dot" => graph.to_dot(),
+        "bpmn" => graph.to_bpmn(),
+        other => bail!("Unknown format '{}', expected dot or bpmn", other),
+    };
+    match output {
+        Some(output) => {
+            std::fs::write(&output, diagram).with_context(|| format!("Failed to write {}", output.display()))?;
+            eprintln!("Wrote {} steps to {}", graph.nodes.len(), output.display());
+        }
+        None => print!("{}", diagram),
+    }
+    Ok(())
+}
+
+async fn screenshot(output: PathBuf, monitor: Option<String>, app: Option<String>) -> Result<()> {
+    let desktop = desktop()?;
+    let image = match (monitor, app) {
+        (Some(monitor), _) => desktop.capture_monitor_by_name_as(&monitor, ImageFormat::Png).await?,
+        (None, Some(app)) => {
+            let element = desktop
+                .application(&app)
+                .with_context(|| format!("Application '{}' not found", app))?;
+            element.capture()?.encode(ImageFormat::Png)?
+        }
+        (None, None) => desktop.capture_screen_as(ImageFormat::Png).await?,
+    };
+    std::fs::write(&output, &image.data).with_context(|| format!("Failed to write {}", output.display()))?;
+    info!(width = image.width, height = image.height, "Screenshot saved");
+    eprintln!("Saved {}x{} screenshot to {}", image.width, image.height, output.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    fn parse(args: &[&str]) -> Command {
+        Cli::try_parse_from(std::iter::once("terminator-cli").chain(args.iter().copied()))
+            .unwrap()
+            .command
+    }
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_inspect() {
+        let args = ["inspect", "notepad", "--title", "Untitled", "-o", "tree.json"];
+        let Command::Inspect { app, title, output } = parse(&args) else {
+            panic!("not inspect");
+        };
+        assert_eq!((app.as_str(), title.as_deref()), ("notepad", Some("Untitled")));
+        assert_eq!(output, Some(PathBuf::from("tree.json")));
+    }
+
+    #[test]
+    fn test_find() {
+        let Command::Find { selectors, timeout_ms, all } = parse(&["find", "name:Notepad", "role:button", "--all"])
+        else {
+            panic!("not find");
+        };
+        assert_eq!(selectors, ["name:Notepad", "role:button"]);
+        assert_eq!(timeout_ms, 3000);
+        assert!(all);
+        assert!(Cli::try_parse_from(["terminator-cli", "find"]).is_err());
+    }
+
+    #[test]
+    fn test_record() {
+        let Command::Record { output, name } = parse(&["record"]) else {
+            panic!("not record");
+        };
+        assert_eq!(output, PathBuf::from("workflow.json"));
+        assert_eq!(name, "Recorded workflow");
+    }
+
+    #[test]
+    fn test_replay() {
+        let Command::Replay {
+            workflow,
+            speed,
+            max_wait_ms,
+            timeout_ms,
+            dry_run,
+        } = parse(&["replay", "flow.json", "--speed", "2", "--dry-run"])
+        else {
+            panic!("not replay");
+        };
+        assert_eq!(workflow, PathBuf::from("flow.json"));
+        assert_eq!((speed, max_wait_ms, timeout_ms, dry_run), (2.0, 2000, 5000, true));
+    }
+
+    #[test]
+    fn test_export() {
+        let Command::Export { workflow, format, output } = parse(&["export", "flow.json", "--format", "bpmn"]) else {
+            panic!("not export");
+        };
+        assert_eq!(workflow, PathBuf::from("flow.json"));
+        assert_eq!(format, "bpmn");
+        assert_eq!(output, None);
+    }
+
+    #[test]
+    fn test_screenshot() {
+        let Command::Screenshot { output, monitor, app } = parse(&["screenshot", "--app", "notepad"]) else {
+            panic!("not screenshot");
+        };
+        assert_eq!(output, PathBuf::from("screenshot.png"));
+        assert_eq!((monitor, app.as_deref()), (None, Some("notepad")));
+        assert!(Cli::try_parse_from(["terminator-cli", "screenshot", "--app", "notepad", "--monitor", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_run() {
+        let args = ["run", "steps.yaml", "--var", "app=notepad", "--var", "text=hi", "-v"];
+        let Command::Run { script, variables, dry_run } = parse(&args) else {
+            panic!("not run");
+        };
+        assert_eq!(script, PathBuf::from("steps.yaml"));
+        assert_eq!(variables, ["app=notepad", "text=hi"]);
+        assert!(!dry_run);
+    }
+
+    #[test]
+    fn test_bench() {
+        let Command::Bench {
+            app,
+            selector,
+            warmup,
+            iterations,
+            timeout_ms,
+            output,
+            baseline,
+            tolerance,
+        } = parse(&["bench", "--app", "notepad", "--selector", "role:button", "--baseline", "base.json"])
+        else {
+            panic!("not bench");
+        };
+        assert_eq!((app.as_deref(), selector.as_deref()), (Some("notepad"), Some("role:button")));
+        assert_eq!((warmup, iterations, timeout_ms, tolerance), (2, 10, 5000, 0.25));
+        assert_eq!((output, baseline), (None, Some(PathBuf::from("base.json"))));
+        // A selector needs the application it belongs to
+        assert!(Cli::try_parse_from(["terminator-cli", "bench", "--selector", "role:button"]).is_err());
+    }
+}
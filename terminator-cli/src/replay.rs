@@ -0,0 +1,184 @@
+//! `terminator-cli replay`
+//!
+//! A recording is folded into intents by the recorder's [`IntentEngine`]
+//! (clicks, typed text, hotkeys) and each intent is performed on the element
+//! found by its recorded selector. Clicks whose element cannot be found fall
+//! back to the recorded screen position. Pauses follow the recording retimed
+//! by [`PlaybackWorkflow`]: scaled by the speed and capped, but never shorter
+//! than the UI took to respond to the previous step while recording.
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use terminator::{Desktop, Selector, UIElement};
+use terminator_workflow_recorder::{
+    ElementContext, Intent, IntentEngine, PlaybackConfig, PlaybackWorkflow, RecordedWorkflow,
+};
+use tracing::{debug, warn};
+
+pub struct ReplayOptions {
+    /// Playback speed relative to the recording
+    pub speed: f64,
+    /// Longest pause between two intents
+    pub max_wait: Duration,
+    /// How long to wait for each intent's element
+    pub timeout: Duration,
+    /// Print the intents without performing them
+    pub dry_run: bool,
+}
+
+pub async fn replay(desktop: &Desktop, path: &Path, options: &ReplayOptions) -> Result<()> {
+    let workflow = RecordedWorkflow::load_from_file(path)
+        .map_err(|e| anyhow::anyhow!("Failed to load {}: {}", path.display(), e))?;
+    let config = PlaybackConfig {
+        speed: options.speed,
+        max_idle_ms: options.max_wait.as_millis() as u64,
+        ..PlaybackConfig::default()
+    };
+    let playback = PlaybackWorkflow::from_events(workflow.name.clone(), &workflow.recorded_events(), &config);
+    debug!(
+        recorded_ms = playback.recorded_duration_ms,
+        playback_ms = playback.duration_ms,
+        "Retimed the recording"
+    );
+    let intents = IntentEngine::default().infer(&playback.recorded_events());
+    if intents.is_empty() {
+        bail!("{} has no clicks, text or hotkeys to replay", path.display());
+    }
+
+    let mut previous_end: Option<u64> = None;
+    for (index, intent) in intents.iter().enumerate() {
+        if let Some(end) = previous_end {
+            let pause = Duration::from_millis(intent.start_time.saturating_sub(end));
+            if !options.dry_run && !pause.is_zero() {
+                tokio::time::sleep(pause).await;
+            }
+        }
+        previous_end = Some(intent.end_time);
+
+        println!("{} {}", index + 1, intent.description);
+        if options.dry_run {
+            continue;
+        }
+        perform(desktop, intent, options.timeout)
+            .await
+            .with_context(|| format!("Step {} failed: {}", index + 1, intent.description))?;
+    }
+    Ok(())
+}
+
+async fn perform(desktop: &Desktop, intent: &Intent, timeout: Duration) -> Result<()> {
+    match intent.kind.as_str() {
+        "click" | "select_menu_item" => {
+            click_target(desktop, intent, timeout).await?.click()?;
+        }
+        "double_click" => {
+            click_target(desktop, intent, timeout).await?.double_click()?;
+        }
+        "right_click" => {
+            click_target(desktop, intent, timeout).await?.right_click()?;
+        }
+        "type_text" => {
+            let text = intent.details.get("text").map(String::as_str).unwrap_or_default();
+            let element = match &intent.target {
+                Some(target) => locate(desktop, target, timeout).await?,
+                None => desktop.focused_element()?,
+            };
+            element.type_text(text, false)?;
+        }
+        "hotkey" => {
+            let combination = intent.details.get("combination").map(String::as_str).unwrap_or_default();
+            let keys = hotkey_keys(combination)
+                .with_context(|| format!("Cannot replay hotkey '{}'", combination))?;
+            desktop.focused_element()?.press_key(&keys)?;
+        }
+        // The keys that caused them are replayed as hotkeys
+        "copy" | "cut" | "paste" => debug!(kind = %intent.kind, "Skipping clipboard intent"),
+        other => warn!(kind = other, "Skipping intent the replay does not support"),
+    }
+    Ok(())
+}
+
+/// The recorded element, or the element at the recorded position
+async fn click_target(desktop: &Desktop, intent: &Intent, timeout: Duration) -> Result<UIElement> {
+    let located = match &intent.target {
+        Some(target) => locate(desktop, target, timeout).await,
+        None => Err(anyhow::anyhow!("No element was recorded")),
+    };
+    let position = || -> Option<(f64, f64)> {
+        let x = intent.details.get("x")?.parse().ok()?;
+        let y = intent.details.get("y")?.parse().ok()?;
+        Some((x, y))
+    };
+    match (located, position()) {
+        (Ok(element), _) => Ok(element),
+        (Err(e), Some((x, y))) => {
+            warn!("{:#}; clicking the element at ({}, {}) instead", e, x, y);
+            Ok(desktop.element_at_point(x, y)?)
+        }
+        (Err(e), None) => Err(e),
+    }
+}
+
+/// Find the recorded element by its selector, within its application when
+/// that is running
+async fn locate(desktop: &Desktop, target: &ElementContext, timeout: Duration) -> Result<UIElement> {
+    let mut locator = desktop.locator(Selector::from(target.selector.as_str()));
+    if let Some(application) = &target.application {
+        match desktop.application(application) {
+            Ok(app) => locator = locator.within(app),
+            Err(e) => debug!(application, "Searching the whole desktop: {}", e),
+        }
+    }
+    locator
+        .wait(Some(timeout))
+        .await
+        .with_context(|| format!("Element '{}' not found", target.selector))
+}
+
+/// A recorded hotkey as keys for `press_key`: `Ctrl+S` becomes `{Ctrl}s`.
+/// Also reads the recorder's virtual-key list form, e.g. `[162, 83]`
+fn hotkey_keys(combination: &str) -> Option<String> {
+    let names: Vec<String> = match combination.trim().strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        Some(codes) => codes
+            .split(',')
+            .map(|code| code.trim().parse().ok().and_then(virtual_key_name))
+            .collect::<Option<_>>()?,
+        None => combination.split('+').map(|key| key.trim().to_string()).collect(),
+    };
+    let (key, modifiers) = names.split_last()?;
+    let mut keys = String::new();
+    for modifier in modifiers {
+        keys.push_str(&format!("{{{}}}", modifier));
+    }
+    match key.chars().count() {
+        0 => return None,
+        1 => keys.push_str(&key.to_lowercase()),
+        _ => keys.push_str(&format!("{{{}}}", key)),
+    }
+    Some(keys)
+}
+
+fn virtual_key_name(code: u32) -> Option<String> {
+    let name = match code {
+        0x10 | 0xA0 | 0xA1 => "Shift",
+        0x11 | 0xA2 | 0xA3 => "Ctrl",
+        0x12 | 0xA4 | 0xA5 => "Alt",
+        0x5B | 0x5C => "Win",
+        0x08 => "Backspace",
+        0x09 => "Tab",
+        0x0D => "Enter",
+        0x1B => "Esc",
+        0x20 => "Space",
+        0x2E => "Delete",
+        0x25 => "Left",
+        0x26 => "Up",
+        0x27 => "Right",
+        0x28 => "Down",
+        0x30..=0x39 | 0x41..=0x5A => return char::from_u32(code).map(|c| c.to_string()),
+        0x70..=0x7B => return Some(format!("F{}", code - 0x6F)),
+        _ => return None,
+    };
+    Some(name.to_string())
+}
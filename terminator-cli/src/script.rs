@@ -0,0 +1,136 @@
+//! Scripts for `terminator-cli run`
+//!
+//! A script is a [`Workflow`] in YAML or JSON, the format the MCP agent's
+//! `execute_workflow` tool takes, so a plan an agent produced can be saved
+//! and run again from the command line. See [`terminator::workflow`] for the
+//! steps.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use terminator::{Desktop, StepStatus, Workflow};
+
+/// Load a script; JSON is read as YAML, which it is a subset of
+pub fn load(path: &Path) -> Result<Workflow> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let workflow = Workflow::from_yaml(&text).with_context(|| format!("Invalid script {}", path.display()))?;
+    workflow.validate()?;
+    Ok(workflow)
+}
+
+/// `name=value` pairs from the command line
+pub fn parse_variables(pairs: &[String]) -> Result<BTreeMap<String, String>> {
+    pairs
+        .iter()
+        .map(|pair| match pair.split_once('=') {
+            Some((name, value)) if !name.trim().is_empty() => Ok((name.trim().to_string(), value.to_string())),
+            _ => bail!("Invalid variable '{}', expected name=value", pair),
+        })
+        .collect()
+}
+
+/// Run the script at `path`, printing each step's outcome. Fails if a step
+/// failed. A dry run prints the actions the steps would have performed
+pub async fn run(desktop: &Desktop, path: &Path, variables: BTreeMap<String, String>, dry_run: bool) -> Result<()> {
+    let workflow = load(path)?;
+    desktop.dry_run(dry_run);
+    let report = workflow.run(desktop, variables).await;
+    desktop.dry_run(false);
+    let report = report?;
+    for result in &report.results {
+        // Steps are numbered from 1, nested ones by their branch
+        let step = match &result.branch {
+            Some(branch) => format!("{}.{}", result.step + 1, branch),
+            None => (result.step + 1).to_string(),
+        };
+        match (result.status, &result.details, &result.reason) {
+            (StepStatus::Success, Some(details), _) => {
+                println!("✓ {} {} ({}ms) {}", step, result.action, result.duration_ms, details)
+            }
+            (StepStatus::Success, None, _) => println!("✓ {} {} ({}ms)", step, result.action, result.duration_ms),
+            (StepStatus::Error, _, reason) => println!(
+                "✗ {} {} ({}ms) {}",
+                step,
+                result.action,
+                result.duration_ms,
+                reason.as_deref().unwrap_or_default()
+            ),
+        }
+    }
+    if dry_run {
+        let simulated = desktop.simulated_actions();
+        println!("Dry run, {} actions not performed:", simulated.len());
+        for action in simulated {
+            let target = match (&action.role, &action.name) {
+                (Some(role), Some(name)) => format!(" {} '{}'", role, name),
+                (Some(role), None) => format!(" {}", role),
+                _ => String::new(),
+            };
+            let details = action.details.map(|d| format!(": {}", d)).unwrap_or_default();
+            println!("  {}{}{}", action.action, target, details);
+        }
+    }
+    if report.steps_failed > 0 {
+        bail!("{} of {} steps failed", report.steps_failed, report.results.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(name: &str, text: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("terminator-cli-{}-{}", std::process::id(), name));
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_variables_are_name_value_pairs() {
+        let pairs = ["app = notepad", "text=a=b", "empty=", "app=calc"].map(String::from);
+        let variables = parse_variables(&pairs).unwrap();
+        // Names are trimmed, values kept as given, and later pairs win
+        assert_eq!(variables["app"], "calc");
+        assert_eq!(variables["text"], "a=b");
+        assert_eq!(variables["empty"], "");
+        assert_eq!(variables.len(), 3);
+
+        for invalid in ["app", "=notepad", " =notepad"] {
+            assert!(parse_variables(&[invalid.to_string()]).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_scripts_keep_their_default_variables() {
+        let path = script(
+            "defaults.yaml",
+            "variables:\n  app: notepad\n  text: hello\n\
+             steps:\n  - action: open_application\n    app: ${app}\n  - action: type\n    text: ${text} world\n",
+        );
+        let workflow = load(&path);
+        std::fs::remove_file(&path).unwrap();
+        let workflow = workflow.unwrap();
+        assert_eq!(workflow.variables["app"], "notepad");
+
+        let expanded = terminator::workflow::expand_variables("${app}: ${text} world", &workflow.variables).unwrap();
+        assert_eq!(expanded, "notepad: hello world");
+    }
+
+    #[test]
+    fn test_invalid_scripts_are_rejected_when_loaded() {
+        let json = script("steps.json", r#"{"steps": [{"action": "wait", "duration_ms": 10}]}"#);
+        let loaded = load(&json);
+        std::fs::remove_file(&json).unwrap();
+        assert_eq!(loaded.unwrap().steps.len(), 1);
+
+        // A step acting on an element no earlier step located
+        let invalid = script("invalid.yaml", "steps:\n  - action: type\n    text: ${text}\n");
+        let loaded = load(&invalid);
+        std::fs::remove_file(&invalid).unwrap();
+        assert!(loaded.is_err());
+
+        assert!(load(Path::new("missing-terminator-cli-script.yaml")).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+//! Local IPC transport for embedding the agent in other processes.
+//!
+//! Serves the same MCP JSON-RPC protocol as the stdio transport over a Windows
+//! named pipe or a Unix domain socket, so a host on the same machine (e.g. a
+//! .NET app) can drive terminator without opening a network port. Every
+//! connection gets its own session.
+//!
+//! Clients speak MCP here. For the HTTP API over a socket or pipe, run
+//! `terminator-server --transport ipc` instead.
+
+use anyhow::Result;
+use rmcp::ServiceExt;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncWrite};
+
+use crate::policy::Policy;
+use crate::utils::DesktopWrapper;
+
+/// Pipe name (Windows) or socket path (Unix) used when none is given. On
+/// Unix it is in the user's runtime directory when there is one
+pub fn default_ipc_path() -> String {
+    #[cfg(windows)]
+    {
+        r"\\.\pipe\terminator-mcp".to_string()
+    }
+    #[cfg(not(windows))]
+    {
+        std::env::var_os("XDG_RUNTIME_DIR")
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(std::env::temp_dir)
+            .join("terminator-mcp.sock")
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+async fn serve_connection<S>(stream: S, policy: Arc<Policy>)
+where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    let wrapper = match DesktopWrapper::new(policy).await {
+        Ok(wrapper) => wrapper,
+        Err(e) => {
+            tracing::error!("Failed to initialize desktop for IPC client: {:?}", e);
+            return;
+        }
+    };
+
+    match wrapper.serve(tokio::io::split(stream)).await {
+        Ok(service) => {
+            if let Err(e) = service.waiting().await {
+                tracing::warn!("IPC session ended with error: {:?}", e);
+            }
+        }
+        Err(e) => tracing::error!("Serving error: {:?}", e),
+    }
+    tracing::info!("IPC client disconnected");
+}
+
+/// Accept clients on the named pipe `path` until the process exits. Clients
+/// share `policy`, rate limits included
+#[cfg(windows)]
+pub async fn serve(path: &str, policy: Arc<Policy>) -> Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let mut server = ServerOptions::new().first_pipe_instance(true).create(path)?;
+    tracing::info!("Listening on named pipe {}", path);
+
+    loop {
+        server.connect().await?;
+        let client = server;
+        // Create the next instance before serving this one so new clients never
+        // find the pipe missing
+        server = ServerOptions::new().create(path)?;
+        tracing::info!("IPC client connected");
+        tokio::spawn(serve_connection(client, policy.clone()));
+    }
+}
+
+/// Accept clients on the Unix socket `path` until the process exits. Clients
+/// share `policy`, rate limits included
+#[cfg(unix)]
+pub async fn serve(path: &str, policy: Arc<Policy>) -> Result<()> {
+    use std::os::unix::fs::{FileTypeExt, MetadataExt};
+    use tokio::net::UnixListener;
+
+    // A socket file left behind by a previous run makes bind fail. Anything
+    // else at the path is not ours to delete
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path)?,
+        Ok(_) => anyhow::bail!("{} exists and is not a socket", path),
+        Err(_) => {}
+    }
+    let listener = UnixListener::bind(path)?;
+    // Only the current user may drive the desktop. The socket's permissions
+    // come from the umask and may let others connect, so every client is
+    // checked against the socket's owner, the user running the agent
+    let uid = std::fs::metadata(path)?.uid();
+    tracing::info!("Listening on unix socket {}", path);
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        match stream.peer_cred() {
+            Ok(peer) if peer.uid() == uid => {}
+            Ok(peer) => {
+                tracing::warn!(uid = peer.uid(), "Rejected IPC client of another user");
+                continue;
+            }
+            Err(e) => {
+                tracing::warn!("Rejected IPC client whose user is unknown: {}", e);
+                continue;
+            }
+        }
+        tracing::info!("IPC client connected");
+        tokio::spawn(serve_connection(stream, policy.clone()));
+    }
+}
@@ -1,25 +1,76 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use rmcp::{ServiceExt, transport::stdio};
+use std::sync::Arc;
+use crate::policy::Policy;
 use crate::utils::{init_logging, DesktopWrapper};
 
+pub mod ipc;
+pub mod policy;
 pub mod utils;
 pub mod server;
 
+/// How the agent talks to its client
+enum Transport {
+    /// MCP over stdin/stdout (default)
+    Stdio,
+    /// MCP over a named pipe (Windows) or unix socket at the given path
+    Ipc(String),
+}
+
+// Parse `--transport stdio|ipc`, `--ipc-path <path>` and `--policy <path>`
+fn parse_args() -> Result<(Transport, Option<String>)> {
+    let mut transport = "stdio".to_string();
+    let mut ipc_path = None;
+    let mut policy_path = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--transport" => match args.next() {
+                Some(value) => transport = value,
+                None => bail!("--transport requires a value (stdio or ipc)"),
+            },
+            "--ipc-path" => match args.next() {
+                Some(value) => ipc_path = Some(value),
+                None => bail!("--ipc-path requires a value"),
+            },
+            "--policy" => match args.next() {
+                Some(value) => policy_path = Some(value),
+                None => bail!("--policy requires a value"),
+            },
+            other => bail!("Unknown argument: {}", other),
+        }
+    }
+
+    let transport = match transport.as_str() {
+        "stdio" => Transport::Stdio,
+        "ipc" => Transport::Ipc(ipc_path.unwrap_or_else(ipc::default_ipc_path)),
+        other => bail!("Unknown transport '{}', expected stdio or ipc", other),
+    };
+    Ok((transport, policy_path))
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     init_logging()?;
+    let (transport, policy_path) = parse_args()?;
+    let policy = Arc::new(Policy::load(policy_path.as_deref())?);
 
     tracing::info!("Initializing Terminator MCP server...");
 
-    let service = DesktopWrapper::new()
-        .await?
-        .serve(stdio())
-        .await
-        .inspect_err(|e| {
-            tracing::error!("Serving error: {:?}", e);
-        })?;
+    match transport {
+        Transport::Stdio => {
+            let service = DesktopWrapper::new(policy)
+                .await?
+                .serve(stdio())
+                .await
+                .inspect_err(|e| {
+                    tracing::error!("Serving error: {:?}", e);
+                })?;
 
-    service.waiting().await?;
+            service.waiting().await?;
+        }
+        Transport::Ipc(path) => ipc::serve(&path, policy).await?,
+    }
     Ok(())
 }
-
@@ -0,0 +1,482 @@
+//! Limits on what the model may do through the agent.
+//!
+//! A policy is read at start from the file given with `--policy <path>` or
+//! from `TERMINATOR_MCP_POLICY`, which holds a path or the JSON itself:
+//!
+//! ```json
+//! {
+//!   "denied_tools": ["run_command"],
+//!   "allowed_applications": ["notepad", "excel"],
+//!   "rate_limits": { "*": 120, "click_element": 30 },
+//!   "confirm_tools": ["close_element", "execute_workflow"],
+//!   "confirm_command": "python confirm.py"
+//! }
+//! ```
+//!
+//! - `allowed_tools` / `denied_tools`: tools the model may call. Every tool
+//!   when no allowlist is given; the denylist wins over the allowlist.
+//! - `allowed_applications`: process names, without `.exe`, of the
+//!   applications the model may act on. Element actions on other
+//!   applications fail, as do tools whose `app_name`, `app`, `browser` or
+//!   `pid` argument names another application. List the process of each app
+//!   (`CalculatorApp` for Calculator, `ApplicationFrameHost` for UWP frames).
+//!   Tools that act on no element are limited too: `global_key_press` and
+//!   the clipboard tools need an allowed application in the foreground,
+//!   `mouse_drag` must start and end on allowed applications,
+//!   `navigate_browser` needs a `browser` argument, and `run_command` is
+//!   denied. Screen captures and text or image searches on the screen still
+//!   see every window, and nothing stops focus from changing between the
+//!   check and the call; deny those tools as well where that matters.
+//! - `rate_limits`: most calls per minute, per tool or for all tools with
+//!   `*`.
+//! - `confirm_tools`: tools that need approval for each call. The
+//!   `confirm_command` runs in a shell with `{"tool": ..., "arguments": ...}`
+//!   on its standard input and approves the call by exiting with 0.
+//!   Embedders can approve calls in code with [`Policy::with_confirmation`]
+//!   instead. Without either, these tools are denied.
+//!
+//! Steps of `execute_workflow` are checked one by one, as calls of the tool
+//! doing the same: `click` as `click_element`, `type` as
+//! `type_into_element`, `press_key` as `press_key`, `open_application` as
+//! `open_application`, and `locate`, `assert` and `extract` as
+//! `validate_element`. A denied tool is denied as a step too, each step
+//! counts against the rate limits, and steps of `confirm_tools` need
+//! approval. The refused step fails like any other failed step.
+//!
+//! Without a policy everything is allowed, as before policies existed.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use terminator::{ActionContext, AutomationError, Desktop, Middleware, MiddlewareId, StepGuard, WorkflowStep};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Environment variable holding a policy file path or the policy JSON
+pub const POLICY_ENV: &str = "TERMINATOR_MCP_POLICY";
+
+/// Key of `rate_limits` that limits all tools together
+const ALL_TOOLS: &str = "*";
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// How long the confirmation command may take before the call is denied
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The policy as configured, see the module documentation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PolicyConfig {
+    pub allowed_tools: Vec<String>,
+    pub denied_tools: Vec<String>,
+    pub allowed_applications: Vec<String>,
+    pub rate_limits: BTreeMap<String, u32>,
+    pub confirm_tools: Vec<String>,
+    pub confirm_command: Option<String>,
+}
+
+/// Decides whether a tool call may run, given its name and arguments
+pub type ConfirmCallback = Arc<dyn Fn(&str, &Value) -> bool + Send + Sync>;
+
+/// A [`PolicyConfig`] with the state needed to enforce it
+#[derive(Default)]
+pub struct Policy {
+    config: PolicyConfig,
+    confirm: Option<ConfirmCallback>,
+    // Start times of the calls of the last minute, per rate limit key
+    calls: Mutex<HashMap<String, VecDeque<Instant>>>,
+    guard: OnceLock<Option<MiddlewareId>>,
+}
+
+impl Policy {
+    pub fn new(config: PolicyConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Approve calls of `confirm_tools` with `confirm` rather than the
+    /// configured command
+    pub fn with_confirmation(mut self, confirm: ConfirmCallback) -> Self {
+        self.confirm = Some(confirm);
+        self
+    }
+
+    /// The policy given on the command line or in the environment, allowing
+    /// everything if there is none
+    pub fn load(path: Option<&str>) -> Result<Self> {
+        let text = match path {
+            Some(path) => std::fs::read_to_string(path).with_context(|| format!("Failed to read policy {}", path))?,
+            None => match std::env::var(POLICY_ENV) {
+                Ok(value) if value.trim_start().starts_with('{') => value,
+                Ok(path) if !path.trim().is_empty() => std::fs::read_to_string(path.trim())
+                    .with_context(|| format!("Failed to read policy {} from {}", path, POLICY_ENV))?,
+                _ => return Ok(Self::default()),
+            },
+        };
+        let config: PolicyConfig = serde_json::from_str(&text).context("Invalid policy")?;
+        tracing::info!(?config, "Policy loaded");
+        Ok(Self::new(config))
+    }
+
+    pub fn config(&self) -> &PolicyConfig {
+        &self.config
+    }
+
+    /// Make element actions through `desktop` and its clones on applications
+    /// outside `allowed_applications` fail. Only the first call installs
+    /// the guard, as the server drives a single desktop
+    pub fn install(&self, desktop: &Desktop) {
+        self.guard.get_or_init(|| {
+            if self.config.allowed_applications.is_empty() {
+                return None;
+            }
+            Some(desktop.add_middleware(ApplicationGuard {
+                desktop: desktop.clone(),
+                allowed: self.config.allowed_applications.iter().map(|a| normalize_app(a)).collect(),
+            }))
+        });
+    }
+
+    /// Whether the model may call `tool` at all. Other tools are not listed
+    pub fn lists_tool(&self, tool: &str) -> bool {
+        !self.config.denied_tools.iter().any(|t| t == tool)
+            && (self.config.allowed_tools.is_empty() || self.config.allowed_tools.iter().any(|t| t == tool))
+    }
+
+    /// Check a tool call before it runs. The error explains the refusal
+    pub async fn check(&self, desktop: &Desktop, tool: &str, arguments: &Value) -> Result<(), String> {
+        if !self.lists_tool(tool) {
+            return Err(format!("Tool '{}' is not allowed by the policy", tool));
+        }
+        self.check_applications(desktop, arguments)?;
+        self.check_unscoped(desktop, tool, arguments)?;
+        self.check_rate(tool)?;
+        if self.config.confirm_tools.iter().any(|t| t == tool) && !self.confirm(tool, arguments).await {
+            return Err(format!("Call of '{}' was not confirmed", tool));
+        }
+        Ok(())
+    }
+
+    /// Check a workflow step as a call of the tool doing the same. Steps
+    /// that act on nothing, like `wait` and `set`, always pass
+    pub async fn check_step(
+        &self,
+        desktop: &Desktop,
+        step: &WorkflowStep,
+        variables: &BTreeMap<String, String>,
+    ) -> Result<(), String> {
+        match step_call(step, variables)? {
+            Some((tool, arguments)) => self.check(desktop, tool, &arguments).await,
+            None => Ok(()),
+        }
+    }
+
+    // Applications named by `app_name`, `app`, `browser` and `pid` arguments, nested
+    // ones included (workflow steps)
+    fn check_applications(&self, desktop: &Desktop, arguments: &Value) -> Result<(), String> {
+        if self.config.allowed_applications.is_empty() {
+            return Ok(());
+        }
+        let mut named = Vec::new();
+        collect_applications(arguments, &mut named);
+        for app in named {
+            let name = match app {
+                NamedApp::Name(name) => name,
+                NamedApp::Pid(pid) => desktop
+                    .process_name(pid)
+                    .map_err(|e| format!("Cannot tell which application process {} is: {}", pid, e))?,
+            };
+            self.check_application(&name)?;
+        }
+        Ok(())
+    }
+
+    // Tools that act on no element reach whatever has focus or lies under
+    // the pointer, or any application at all
+    fn check_unscoped(&self, desktop: &Desktop, tool: &str, arguments: &Value) -> Result<(), String> {
+        if self.config.allowed_applications.is_empty() {
+            return Ok(());
+        }
+        match tool {
+            "run_command" => Err(format!(
+                "Tool '{}' can reach any application and is denied while allowed_applications is set",
+                tool
+            )),
+            "navigate_browser" if arguments.get("browser").and_then(Value::as_str).is_none() => Err(format!(
+                "Tool '{}' needs a browser argument while allowed_applications is set",
+                tool
+            )),
+            "global_key_press" | "set_clipboard" | "get_clipboard" => {
+                let name = desktop
+                    .focused_element()
+                    .and_then(|element| element.process_id())
+                    .and_then(|pid| desktop.process_name(pid))
+                    .map_err(|e| format!("Cannot tell which application has focus: {}", e))?;
+                self.check_application(&name)
+            }
+            "mouse_drag" => {
+                for (x, y) in [("start_x", "start_y"), ("end_x", "end_y")] {
+                    let (Some(x), Some(y)) = (
+                        arguments.get(x).and_then(Value::as_f64),
+                        arguments.get(y).and_then(Value::as_f64),
+                    ) else {
+                        continue;
+                    };
+                    let name = desktop
+                        .element_at_point(x, y)
+                        .and_then(|element| element.process_id())
+                        .and_then(|pid| desktop.process_name(pid))
+                        .map_err(|e| format!("Cannot tell which application is at ({}, {}): {}", x, y, e))?;
+                    self.check_application(&name)?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_application(&self, name: &str) -> Result<(), String> {
+        let name_key = normalize_app(name);
+        if self.config.allowed_applications.iter().any(|a| normalize_app(a) == name_key) {
+            Ok(())
+        } else {
+            Err(format!("Application '{}' is not allowed by the policy", name))
+        }
+    }
+
+    fn check_rate(&self, tool: &str) -> Result<(), String> {
+        let now = Instant::now();
+        let mut calls = self.calls.lock().unwrap_or_else(|e| e.into_inner());
+        let keys = [tool, ALL_TOOLS];
+        // Only count the call once every limit let it through
+        for key in keys {
+            let Some(&limit) = self.config.rate_limits.get(key) else {
+                continue;
+            };
+            let recent = calls.entry(key.to_string()).or_default();
+            while recent.front().is_some_and(|start| now.duration_since(*start) >= RATE_WINDOW) {
+                recent.pop_front();
+            }
+            if recent.len() >= limit as usize {
+                return Err(match key {
+                    ALL_TOOLS => format!("Rate limit of {} tool calls per minute reached", limit),
+                    _ => format!("Rate limit of {} calls per minute reached for '{}'", limit, tool),
+                });
+            }
+        }
+        for key in keys {
+            if self.config.rate_limits.contains_key(key) {
+                calls.entry(key.to_string()).or_default().push_back(now);
+            }
+        }
+        Ok(())
+    }
+
+    async fn confirm(&self, tool: &str, arguments: &Value) -> bool {
+        if let Some(confirm) = &self.confirm {
+            return confirm(tool, arguments);
+        }
+        let Some(command) = &self.config.confirm_command else {
+            tracing::warn!(tool, "No confirmation configured, denying call");
+            return false;
+        };
+        let request = json!({"tool": tool, "arguments": arguments}).to_string();
+        match tokio::time::timeout(CONFIRM_TIMEOUT, run_confirm_command(command, &request)).await {
+            Ok(Ok(approved)) => approved,
+            Ok(Err(e)) => {
+                tracing::warn!(tool, "Confirmation command failed: {}", e);
+                false
+            }
+            Err(_) => {
+                tracing::warn!(tool, "Confirmation command timed out");
+                false
+            }
+        }
+    }
+}
+
+async fn run_confirm_command(command: &str, request: &str) -> Result<bool> {
+    let mut shell = if cfg!(target_os = "windows") {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    };
+    let mut child = shell
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    stdin.write_all(request.as_bytes()).await?;
+    drop(stdin);
+    Ok(child.wait().await?.success())
+}
+
+/// The tool a workflow step stands for, with the arguments it would be
+/// called with. Variables are expanded so the policy sees the values used
+fn step_call(
+    step: &WorkflowStep,
+    variables: &BTreeMap<String, String>,
+) -> Result<Option<(&'static str, Value)>, String> {
+    let expand = |text: &str| terminator::workflow::expand_variables(text, variables).map_err(|e| e.to_string());
+    let call = match step {
+        WorkflowStep::Click { selector_chain, .. } => ("click_element", json!({"selector_chain": selector_chain})),
+        WorkflowStep::Type { selector_chain, text, .. } => (
+            "type_into_element",
+            json!({"selector_chain": selector_chain, "text_to_type": expand(text)?}),
+        ),
+        WorkflowStep::PressKey { selector_chain, key, .. } => {
+            ("press_key", json!({"selector_chain": selector_chain, "key": expand(key)?}))
+        }
+        WorkflowStep::OpenApplication { app } => ("open_application", json!({"app_name": expand(app)?})),
+        WorkflowStep::Locate { selector_chain, .. } => {
+            ("validate_element", json!({"selector_chain": selector_chain}))
+        }
+        WorkflowStep::Assert { selector_chain, .. } | WorkflowStep::Extract { selector_chain, .. } => {
+            ("validate_element", json!({"selector_chain": selector_chain}))
+        }
+        WorkflowStep::Wait { .. } | WorkflowStep::Set { .. } | WorkflowStep::If { .. } => return Ok(None),
+    };
+    Ok(Some(call))
+}
+
+/// Applies a policy to each step of an `execute_workflow` run
+pub struct WorkflowGuard<'a> {
+    pub policy: &'a Policy,
+    pub desktop: &'a Desktop,
+}
+
+#[async_trait::async_trait]
+impl StepGuard for WorkflowGuard<'_> {
+    async fn before_step(
+        &self,
+        step: &WorkflowStep,
+        variables: &BTreeMap<String, String>,
+    ) -> Result<(), AutomationError> {
+        self.policy.check_step(self.desktop, step, variables).await.map_err(|reason| {
+            tracing::warn!(action = step.action(), "Workflow step refused: {}", reason);
+            AutomationError::PermissionDenied(reason)
+        })
+    }
+}
+
+enum NamedApp {
+    Name(String),
+    Pid(u32),
+}
+
+fn collect_applications(value: &Value, named: &mut Vec<NamedApp>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                match (key.as_str(), value) {
+                    ("app_name" | "app" | "browser", Value::String(name)) => named.push(NamedApp::Name(name.clone())),
+                    ("pid", Value::Number(pid)) => {
+                        if let Some(pid) = pid.as_u64() {
+                            named.push(NamedApp::Pid(pid as u32));
+                        }
+                    }
+                    _ => collect_applications(value, named),
+                }
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|value| collect_applications(value, named)),
+        _ => {}
+    }
+}
+
+// `Notepad.exe` and `notepad` alike
+fn normalize_app(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    name.strip_suffix(".exe").map(str::to_string).unwrap_or(name)
+}
+
+/// Fails element actions on applications the policy does not allow
+struct ApplicationGuard {
+    desktop: Desktop,
+    allowed: Vec<String>,
+}
+
+impl Middleware for ApplicationGuard {
+    fn before_action(&self, ctx: &ActionContext) -> Result<(), AutomationError> {
+        let pid = ctx.element.process_id()?;
+        let name = self.desktop.process_name(pid)?;
+        if self.allowed.contains(&normalize_app(&name)) {
+            Ok(())
+        } else {
+            Err(AutomationError::PermissionDenied(format!(
+                "{} on application '{}' is not allowed by the policy",
+                ctx.action, name
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(json: Value) -> WorkflowStep {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn policy(config: Value) -> Policy {
+        Policy::new(serde_json::from_value(config).unwrap())
+    }
+
+    #[test]
+    fn steps_are_checked_as_their_tools() {
+        let variables = BTreeMap::from([("app".to_string(), "notepad".to_string())]);
+        let tool = |json: Value| step_call(&step(json), &variables).unwrap().map(|(tool, _)| tool);
+        assert_eq!(tool(json!({"action": "click", "selector_chain": ["name:OK"]})), Some("click_element"));
+        assert_eq!(tool(json!({"action": "type", "text": "hi"})), Some("type_into_element"));
+        assert_eq!(tool(json!({"action": "press_key", "key": "{Enter}"})), Some("press_key"));
+        assert_eq!(tool(json!({"action": "open_application", "app": "${app}"})), Some("open_application"));
+        assert_eq!(tool(json!({"action": "locate", "selector_chain": ["name:OK"]})), Some("validate_element"));
+        assert_eq!(tool(json!({"action": "wait", "duration_ms": 10})), None);
+
+        let (_, arguments) = step_call(&step(json!({"action": "open_application", "app": "${app}"})), &variables)
+            .unwrap()
+            .unwrap();
+        assert_eq!(arguments["app_name"], "notepad");
+    }
+
+    #[test]
+    fn denied_tool_cannot_be_reached_through_a_workflow() {
+        let policy = policy(json!({"denied_tools": ["click_element"]}));
+        assert!(policy.lists_tool("execute_workflow"));
+        let workflow: terminator::Workflow = serde_json::from_value(json!({
+            "steps": [
+                {"action": "locate", "selector_chain": ["name:Form"]},
+                {"action": "if", "condition": {"equals": {"variable": "a", "value": "b"}},
+                 "else": [{"action": "click"}]}
+            ]
+        }))
+        .unwrap();
+        let WorkflowStep::If { otherwise, .. } = &workflow.steps[1] else {
+            panic!("expected an if step");
+        };
+        let (tool, _) = step_call(&otherwise[0], &BTreeMap::new()).unwrap().unwrap();
+        assert!(!policy.lists_tool(tool));
+    }
+
+    #[test]
+    fn workflow_steps_count_against_rate_limits() {
+        let policy = policy(json!({"rate_limits": {"press_key": 1}}));
+        let (tool, _) = step_call(&step(json!({"action": "press_key", "key": "a"})), &BTreeMap::new())
+            .unwrap()
+            .unwrap();
+        assert!(policy.check_rate(tool).is_ok());
+        assert!(policy.check_rate(tool).is_err());
+    }
+}
@@ -1,22 +1,41 @@
 use crate::utils::{
-    get_timeout, DesktopWrapper, EmptyArgs, GetWindowTreeArgs,
+    get_clipboard_text, get_timeout, set_clipboard_text, DesktopWrapper, DiffWindowTreeArgs, EmptyArgs, GetWindowTreeArgs,
     GetWindowsArgs, LocatorArgs, PressKeyArgs, RunCommandArgs, TypeIntoElementArgs,
     ClipboardArgs, GetClipboardArgs, MouseDragArgs, ValidateElementArgs, 
     HighlightElementArgs, WaitForElementArgs, NavigateBrowserArgs, OpenApplicationArgs,
-    ScrollElementArgs,
+    ScrollElementArgs, ExecuteWorkflowArgs, GlobalKeyPressArgs, FindTextOnScreenArgs,
+    FindImageOnScreenArgs, FindElementsArgs, ExploreElementArgs, ElementRefArgs, TypeIntoRefArgs, ELEMENT_REF_TTL,
+    WaitCondition, WaitForArgs,
 };
+use crate::policy::{Policy, WorkflowGuard};
 use chrono::Local;
+use rmcp::handler::server::tool::ToolCallContext;
 use rmcp::model::{
-    CallToolResult, Content, Implementation, ProtocolVersion, ServerCapabilities, ServerInfo,
+    CallToolRequestParam, CallToolResult, Content, Implementation, ListToolsResult,
+    PaginatedRequestParam, ProtocolVersion, ServerCapabilities, ServerInfo,
 };
-use rmcp::{tool, Error as McpError, ServerHandler};
+use rmcp::service::RequestContext;
+use rmcp::{tool, Error as McpError, RoleServer, ServerHandler};
 use serde_json::json;
 use std::env;
-use terminator::{Desktop, Locator, Selector};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use terminator::{Desktop, ErrorCode, ImageSearch, Locator, Rect, Selector, UIElement, Workflow};
+
+/// Elements `find_elements` returns unless asked for more
+const DEFAULT_FIND_RESULTS: usize = 20;
+
+/// How long `wait_for` waits unless asked otherwise, and at most
+const DEFAULT_WAIT_FOR: Duration = Duration::from_secs(10);
+const MAX_WAIT_FOR: Duration = Duration::from_secs(120);
+
+/// Pause between two `wait_for` searches, doubling up to the longest
+const WAIT_FOR_FIRST_POLL: Duration = Duration::from_millis(100);
+const WAIT_FOR_LONGEST_POLL: Duration = Duration::from_secs(1);
 
 #[tool(tool_box)]
 impl DesktopWrapper {
-    pub async fn new() -> Result<Self, McpError> {
+    pub async fn new(policy: Arc<Policy>) -> Result<Self, McpError> {
         #[cfg(target_os = "windows")]
         let desktop = match Desktop::new(false, false) {
             Ok(d) => d,
@@ -39,7 +58,13 @@ impl DesktopWrapper {
             }
         };
 
-        Ok(Self { desktop })
+        policy.install(&desktop);
+        Ok(Self {
+            desktop,
+            tree_snapshots: Default::default(),
+            element_refs: Default::default(),
+            policy,
+        })
     }
 
     #[tool(
@@ -59,13 +84,52 @@ impl DesktopWrapper {
             .map_err(|e| {
                 McpError::resource_not_found(
                     "Failed to get window tree",
-                    Some(json!({"reason": e.to_string()})),
+                    Some(json!({"reason": e.to_string(), "error": e})),
                 )
             })?;
 
         Ok(CallToolResult::success(vec![Content::json(&tree)?]))
     }
 
+    #[tool(
+        description = "Compare the UI tree of an application with the snapshot taken by the previous call for the same PID. Returns added, removed and changed nodes with their paths; the first call only records a baseline."
+    )]
+    async fn diff_window_tree(
+        &self,
+        #[tool(param)] args: DiffWindowTreeArgs,
+    ) -> Result<CallToolResult, McpError> {
+        let tree = self
+            .desktop
+            .get_window_tree(args.pid, args.title.as_deref(), None)
+            .map_err(|e| {
+                McpError::resource_not_found(
+                    "Failed to get window tree",
+                    Some(json!({"reason": e.to_string(), "error": e})),
+                )
+            })?;
+
+        let previous = {
+            let mut snapshots = self.tree_snapshots.lock().map_err(|e| {
+                McpError::internal_error(
+                    "Tree snapshot store is poisoned",
+                    Some(json!({"reason": e.to_string()})),
+                )
+            })?;
+            if args.reset.unwrap_or(false) {
+                snapshots.remove(&args.pid);
+            }
+            snapshots.insert(args.pid, tree.clone())
+        };
+
+        match previous {
+            Some(before) => Ok(CallToolResult::success(vec![Content::json(&before.diff(&tree))?])),
+            None => Ok(CallToolResult::success(vec![Content::json(&json!({
+                "baseline_recorded": true,
+                "pid": args.pid,
+            }))?])),
+        }
+    }
+
     #[tool(description = "Get all applications currently running.")]
     async fn get_applications(
         &self,
@@ -74,7 +138,7 @@ impl DesktopWrapper {
         let apps = self.desktop.applications().map_err(|e| {
             McpError::resource_not_found(
                 "Failed to get applications",
-                Some(json!({"reason": e.to_string()})),
+                Some(json!({"reason": e.to_string(), "error": e})),
             )
         })?;
 
@@ -109,7 +173,7 @@ impl DesktopWrapper {
             .map_err(|e| {
                 McpError::resource_not_found(
                     "Failed to get windows for application",
-                    Some(json!({"reason": e.to_string()})),
+                    Some(json!({"reason": e.to_string(), "error": e})),
                 )
             })?;
 
@@ -147,7 +211,7 @@ impl DesktopWrapper {
             .map_err(|e| {
                 McpError::internal_error(
                     "Failed to locate element",
-                    Some(json!({"reason": e.to_string(), "selector_chain": args.selector_chain})),
+                    Some(json!({"reason": e.to_string(), "error": e, "selector_chain": args.selector_chain, "suggestions": e.suggestions()})),
                 )
             })?;
         
@@ -167,6 +231,7 @@ impl DesktopWrapper {
                 "Failed to type text",
                 Some(json!({
                     "reason": e.to_string(),
+                    "error": e,
                     "selector_chain": args.selector_chain,
                     "text_to_type": args.text_to_type,
                     "element_info": element_info
@@ -196,7 +261,7 @@ impl DesktopWrapper {
             .map_err(|e| {
                 McpError::internal_error(
                     "Failed to locate element",
-                    Some(json!({"reason": e.to_string(), "selector_chain": args.selector_chain})),
+                    Some(json!({"reason": e.to_string(), "error": e, "selector_chain": args.selector_chain, "suggestions": e.suggestions()})),
                 )
             })?;
         
@@ -216,6 +281,7 @@ impl DesktopWrapper {
                 "Failed to click on element",
                 Some(json!({
                     "reason": e.to_string(),
+                    "error": e,
                     "selector_chain": args.selector_chain,
                     "element_info": element_info
                 })),
@@ -243,7 +309,7 @@ impl DesktopWrapper {
             .map_err(|e| {
                 McpError::internal_error(
                     "Failed to locate element",
-                    Some(json!({"reason": e.to_string(), "selector_chain": args.selector_chain})),
+                    Some(json!({"reason": e.to_string(), "error": e, "selector_chain": args.selector_chain, "suggestions": e.suggestions()})),
                 )
             })?;
         // Get element details before pressing key for better feedback
@@ -262,6 +328,7 @@ impl DesktopWrapper {
                 "Failed to press key",
                 Some(json!({
                     "reason": e.to_string(),
+                    "error": e,
                     "selector_chain": args.selector_chain,
                     "key_pressed": args.key,
                     "element_info": element_info
@@ -291,7 +358,7 @@ impl DesktopWrapper {
             .map_err(|e| {
                 McpError::internal_error(
                     "Failed to run command",
-                    Some(json!({"reason": e.to_string()})),
+                    Some(json!({"reason": e.to_string(), "error": e})),
                 )
             })?;
 
@@ -316,13 +383,13 @@ impl DesktopWrapper {
             .map_err(|e| {
                 McpError::internal_error(
                     "Failed to locate element",
-                    Some(json!({"reason": e.to_string()})),
+                    Some(json!({"reason": e.to_string(), "error": e, "suggestions": e.suggestions()})),
                 )
             })?;
         element.activate_window().map_err(|e| {
             McpError::resource_not_found(
                 "Failed to activate window with that element",
-                Some(json!({"reason": e.to_string()})),
+                Some(json!({"reason": e.to_string(), "error": e})),
             )
         })?;
         Ok(CallToolResult::success(vec![Content::json(
@@ -340,7 +407,7 @@ impl DesktopWrapper {
         let screenshot = self.desktop.capture_screen().await.map_err(|e| {
             McpError::internal_error(
                 "Failed to capture screen",
-                Some(json!({"reason": e.to_string()})),
+                Some(json!({"reason": e.to_string(), "error": e})),
             )
         })?;
 
@@ -351,81 +418,197 @@ impl DesktopWrapper {
             .map_err(|e| {
                 McpError::internal_error(
                     "Failed to perform OCR",
-                    Some(json!({"reason": e.to_string()})),
+                    Some(json!({"reason": e.to_string(), "error": e})),
                 )
             })?;
 
         Ok(CallToolResult::success(vec![Content::json(&ocr_text)?]))
     }
 
-    #[tool(description = "Sets text to the system clipboard using shell commands.")]
+    #[tool(
+        description = "Finds text on screen by OCR and returns where it is shown, optionally clicking it. Use it for apps that draw their own UI (games, canvas apps, remote desktops) and have no elements in the window tree."
+    )]
+    async fn find_text_on_screen(
+        &self,
+        #[tool(param)] args: FindTextOnScreenArgs,
+    ) -> Result<CallToolResult, McpError> {
+        let region = args.region.map(|[x, y, width, height]| Rect::new(x, y, width, height));
+        let matches = self
+            .desktop
+            .find_text_on_screen(&args.text, region)
+            .await
+            .map_err(|e| {
+                McpError::internal_error(
+                    "Failed to find text on screen",
+                    Some(json!({"reason": e.to_string(), "error": e, "text": args.text})),
+                )
+            })?;
+
+        let mut clicked = None;
+        if args.click.unwrap_or(false) {
+            let index = args.index.unwrap_or(0);
+            let target = matches.get(index).ok_or_else(|| {
+                McpError::resource_not_found(
+                    "No match to click",
+                    Some(json!({"text": args.text, "index": index, "matches": matches.len()})),
+                )
+            })?;
+            target.click().map_err(|e| {
+                McpError::internal_error(
+                    "Failed to click text on screen",
+                    Some(json!({"reason": e.to_string(), "error": e, "match": target})),
+                )
+            })?;
+            clicked = Some(index);
+        }
+
+        Ok(CallToolResult::success(vec![Content::json(&json!({
+            "action": "find_text_on_screen",
+            "status": "success",
+            "text": args.text,
+            "matches": matches,
+            "clicked": clicked,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))?]))
+    }
+
+    #[tool(
+        description = "Finds an image, such as an icon without a name, on screen by template matching and returns where it is shown with a confidence, optionally clicking the best match."
+    )]
+    async fn find_image_on_screen(
+        &self,
+        #[tool(param)] args: FindImageOnScreenArgs,
+    ) -> Result<CallToolResult, McpError> {
+        let template = tokio::fs::read(&args.template_path).await.map_err(|e| {
+            McpError::invalid_params(
+                "Failed to read template image",
+                Some(json!({"reason": e.to_string(), "template_path": args.template_path})),
+            )
+        })?;
+        let mut search = ImageSearch {
+            region: args.region.map(|[x, y, width, height]| Rect::new(x, y, width, height)),
+            ..Default::default()
+        };
+        if let Some(threshold) = args.threshold {
+            search.threshold = threshold;
+        }
+        if args.multi_scale.unwrap_or(false) {
+            search = search.multi_scale();
+        }
+
+        let matches = self
+            .desktop
+            .find_image_with(&template, &search)
+            .await
+            .map_err(|e| {
+                McpError::internal_error(
+                    "Failed to find image on screen",
+                    Some(json!({"reason": e.to_string(), "error": e, "template_path": args.template_path})),
+                )
+            })?;
+
+        let clicked = args.click.unwrap_or(false);
+        if clicked {
+            let best = matches.first().ok_or_else(|| {
+                McpError::resource_not_found(
+                    "No match to click",
+                    Some(json!({"template_path": args.template_path, "threshold": search.threshold})),
+                )
+            })?;
+            best.click().map_err(|e| {
+                McpError::internal_error(
+                    "Failed to click image on screen",
+                    Some(json!({"reason": e.to_string(), "error": e, "match": best})),
+                )
+            })?;
+        }
+
+        Ok(CallToolResult::success(vec![Content::json(&json!({
+            "action": "find_image_on_screen",
+            "status": "success",
+            "template_path": args.template_path,
+            "matches": matches,
+            "clicked": clicked,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))?]))
+    }
+
+    #[tool(
+        description = "Sets text to the system clipboard. Handles large and multi-line text; combine with global_key_press to paste it."
+    )]
     async fn set_clipboard(
         &self,
         #[tool(param)] args: ClipboardArgs,
     ) -> Result<CallToolResult, McpError> {
-        let result = if cfg!(target_os = "windows") {
-            // Windows: echo "text" | clip
-            let command = format!("echo \"{}\" | clip", args.text.replace("\"", "\\\""));
-            self.desktop.run_command(Some(&command), None).await
-        } else if cfg!(target_os = "macos") {
-            // macOS: echo "text" | pbcopy
-            let command = format!("echo \"{}\" | pbcopy", args.text.replace("\"", "\\\""));
-            self.desktop.run_command(None, Some(&command)).await
-        } else {
-            // Linux: echo "text" | xclip -selection clipboard
-            let command = format!("echo \"{}\" | xclip -selection clipboard", args.text.replace("\"", "\\\""));
-            self.desktop.run_command(None, Some(&command)).await
-        };
-        
-        result.map_err(|e| {
+        set_clipboard_text(&args.text).await.map_err(|e| {
             McpError::internal_error(
-                "Failed to set clipboard", 
-                Some(json!({"reason": e.to_string(), "text": args.text}))
+                "Failed to set clipboard",
+                Some(json!({"reason": e.to_string(), "length": args.text.chars().count()})),
             )
         })?;
-        
+
         Ok(CallToolResult::success(vec![Content::json(&json!({
             "action": "set_clipboard",
             "status": "success",
-            "text": args.text,
-            "method": "shell_command",
+            "length": args.text.chars().count(),
             "timestamp": chrono::Utc::now().to_rfc3339()
         }))?]))
     }
 
-    #[tool(description = "Gets text from the system clipboard using shell commands.")]
+    #[tool(description = "Gets text from the system clipboard.")]
     async fn get_clipboard(
         &self,
         #[tool(param)] _args: GetClipboardArgs,
     ) -> Result<CallToolResult, McpError> {
-        let command_result = if cfg!(target_os = "windows") {
-            // Windows: powershell Get-Clipboard
-            self.desktop.run_command(Some("powershell -command \"Get-Clipboard\""), None).await
-        } else if cfg!(target_os = "macos") {
-            // macOS: pbpaste
-            self.desktop.run_command(None, Some("pbpaste")).await
-        } else {
-            // Linux: xclip -selection clipboard -o
-            self.desktop.run_command(None, Some("xclip -selection clipboard -o")).await
-        };
+        let text = get_clipboard_text().await.map_err(|e| {
+            McpError::internal_error(
+                "Failed to get clipboard text",
+                Some(json!({"reason": e.to_string()})),
+            )
+        })?;
 
-        match command_result {
-            Ok(output) => {
-                Ok(CallToolResult::success(vec![Content::json(&json!({
-                    "action": "get_clipboard", 
-                    "status": "success",
-                    "text": output.stdout.trim(),
-                    "method": "shell_command",
-                    "timestamp": chrono::Utc::now().to_rfc3339()
-                }))?]))
-            }
-            Err(e) => {
-                Err(McpError::internal_error(
-                    "Failed to get clipboard text",
-                    Some(json!({"reason": e.to_string()})),
-                ))
-            }
-        }
+        Ok(CallToolResult::success(vec![Content::json(&json!({
+            "action": "get_clipboard",
+            "status": "success",
+            "text": text,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))?]))
+    }
+
+    #[tool(
+        description = "Sends a key press to whatever element has keyboard focus, without a selector. Use it for Escape, Enter, Tab or shortcuts like Ctrl+V."
+    )]
+    async fn global_key_press(
+        &self,
+        #[tool(param)] args: GlobalKeyPressArgs,
+    ) -> Result<CallToolResult, McpError> {
+        let element = self.desktop.focused_element().map_err(|e| {
+            McpError::internal_error(
+                "Failed to get focused element",
+                Some(json!({"reason": e.to_string(), "error": e, "key_pressed": args.key})),
+            )
+        })?;
+        let element_info = element_summary(&element);
+
+        element.press_key(&args.key).map_err(|e| {
+            McpError::internal_error(
+                "Failed to press key",
+                Some(json!({
+                    "reason": e.to_string(),
+                    "error": e,
+                    "key_pressed": args.key,
+                    "element_info": element_info
+                })),
+            )
+        })?;
+
+        Ok(CallToolResult::success(vec![Content::json(&json!({
+            "action": "global_key_press",
+            "status": "success",
+            "key_pressed": args.key,
+            "element": element_info,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))?]))
     }
 
     #[tool(description = "Performs a mouse drag operation from start to end coordinates.")]
@@ -440,7 +623,7 @@ impl DesktopWrapper {
             .map_err(|e| {
                 McpError::internal_error(
                     "Failed to locate element",
-                    Some(json!({"reason": e.to_string(), "selector_chain": args.selector_chain})),
+                    Some(json!({"reason": e.to_string(), "error": e, "selector_chain": args.selector_chain, "suggestions": e.suggestions()})),
                 )
             })?;
         
@@ -460,6 +643,7 @@ impl DesktopWrapper {
                 "Failed to perform mouse drag",
                 Some(json!({
                     "reason": e.to_string(),
+                    "error": e,
                     "selector_chain": args.selector_chain,
                     "start": (args.start_x, args.start_y),
                     "end": (args.end_x, args.end_y),
@@ -518,6 +702,8 @@ impl DesktopWrapper {
                     "status": "failed",
                     "exists": false,
                     "reason": e.to_string(),
+                    "error": e,
+                    "suggestions": e.suggestions(),
                     "selector_chain": args.selector_chain,
                     "timestamp": chrono::Utc::now().to_rfc3339()
                 }))?]))
@@ -525,6 +711,151 @@ impl DesktopWrapper {
         }
     }
 
+    #[tool(description = "Finds the elements matching a selector chain and returns a short-lived reference for each, which click_ref, type_into_ref and explore_element accept instead of a selector chain.")]
+    async fn find_elements(
+        &self,
+        #[tool(param)] args: FindElementsArgs,
+    ) -> Result<CallToolResult, McpError> {
+        let locator = self.create_locator_for_chain(&args.selector_chain)?;
+        let elements = locator
+            .all(get_timeout(args.timeout_ms), None)
+            .await
+            .map_err(|e| {
+                McpError::resource_not_found(
+                    "Failed to locate elements",
+                    Some(json!({"reason": e.to_string(), "error": e, "selector_chain": args.selector_chain, "suggestions": e.suggestions()})),
+                )
+            })?;
+
+        let total = elements.len();
+        let max_results = args.max_results.unwrap_or(DEFAULT_FIND_RESULTS);
+        let found: Vec<_> = elements
+            .into_iter()
+            .take(max_results)
+            .map(|element| self.element_with_ref(element))
+            .collect();
+
+        Ok(CallToolResult::success(vec![Content::json(&json!({
+            "action": "find_elements",
+            "status": "success",
+            "elements": found,
+            "count": found.len(),
+            "total_matches": total,
+            "ref_ttl_seconds": ELEMENT_REF_TTL.as_secs(),
+            "selector_chain": args.selector_chain,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))?]))
+    }
+
+    #[tool(description = "Lists the direct children of an element, given by reference or selector chain, each with a short-lived reference of its own.")]
+    async fn explore_element(
+        &self,
+        #[tool(param)] args: ExploreElementArgs,
+    ) -> Result<CallToolResult, McpError> {
+        let element = match (&args.element_ref, &args.selector_chain) {
+            (Some(element_ref), _) => self.resolve_ref(element_ref)?,
+            (None, Some(selector_chain)) => {
+                let locator = self.create_locator_for_chain(selector_chain)?;
+                locator
+                    .wait(get_timeout(args.timeout_ms))
+                    .await
+                    .map_err(|e| {
+                        McpError::resource_not_found(
+                            "Failed to locate element",
+                            Some(json!({"reason": e.to_string(), "error": e, "selector_chain": selector_chain, "suggestions": e.suggestions()})),
+                        )
+                    })?
+            }
+            (None, None) => {
+                return Err(McpError::invalid_params(
+                    "Either element_ref or selector_chain is required",
+                    None,
+                ))
+            }
+        };
+
+        let children = element.children().map_err(|e| {
+            McpError::internal_error(
+                "Failed to get children of element",
+                Some(json!({"reason": e.to_string(), "error": e, "element_ref": args.element_ref})),
+            )
+        })?;
+        let children: Vec<_> = children
+            .into_iter()
+            .map(|child| self.element_with_ref(child))
+            .collect();
+
+        Ok(CallToolResult::success(vec![Content::json(&json!({
+            "action": "explore_element",
+            "status": "success",
+            "element": self.element_with_ref(element),
+            "children": children,
+            "count": children.len(),
+            "ref_ttl_seconds": ELEMENT_REF_TTL.as_secs(),
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))?]))
+    }
+
+    #[tool(description = "Clicks the element of a reference returned by find_elements or explore_element.")]
+    async fn click_ref(
+        &self,
+        #[tool(param)] args: ElementRefArgs,
+    ) -> Result<CallToolResult, McpError> {
+        let element = self.resolve_ref(&args.element_ref)?;
+        let element_info = element_info(&element);
+
+        element.click().map_err(|e| {
+            McpError::resource_not_found(
+                "Failed to click on element",
+                Some(json!({
+                    "reason": e.to_string(),
+                    "error": e,
+                    "element_ref": args.element_ref,
+                    "element_info": element_info
+                })),
+            )
+        })?;
+
+        Ok(CallToolResult::success(vec![Content::json(&json!({
+            "action": "click",
+            "status": "success",
+            "element": element_info,
+            "element_ref": args.element_ref,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))?]))
+    }
+
+    #[tool(description = "Types text into the element of a reference returned by find_elements or explore_element.")]
+    async fn type_into_ref(
+        &self,
+        #[tool(param)] args: TypeIntoRefArgs,
+    ) -> Result<CallToolResult, McpError> {
+        let element = self.resolve_ref(&args.element_ref)?;
+        let element_info = element_info(&element);
+
+        element.type_text(&args.text_to_type, false).map_err(|e| {
+            McpError::resource_not_found(
+                "Failed to type text",
+                Some(json!({
+                    "reason": e.to_string(),
+                    "error": e,
+                    "element_ref": args.element_ref,
+                    "text_to_type": args.text_to_type,
+                    "element_info": element_info
+                })),
+            )
+        })?;
+
+        Ok(CallToolResult::success(vec![Content::json(&json!({
+            "action": "type",
+            "status": "success",
+            "text_typed": args.text_to_type,
+            "element": element_info,
+            "element_ref": args.element_ref,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))?]))
+    }
+
     #[tool(description = "Highlights an element with a colored border for visual confirmation.")]
     async fn highlight_element(
         &self,
@@ -537,7 +868,7 @@ impl DesktopWrapper {
             .map_err(|e| {
                 McpError::internal_error(
                     "Failed to locate element for highlighting",
-                    Some(json!({"reason": e.to_string(), "selector_chain": args.selector_chain})),
+                    Some(json!({"reason": e.to_string(), "error": e, "selector_chain": args.selector_chain, "suggestions": e.suggestions()})),
                 )
             })?;
 
@@ -545,7 +876,7 @@ impl DesktopWrapper {
         element.highlight(args.color, duration).map_err(|e| {
             McpError::internal_error(
                 "Failed to highlight element",
-                Some(json!({"reason": e.to_string(), "selector_chain": args.selector_chain})),
+                Some(json!({"reason": e.to_string(), "error": e, "selector_chain": args.selector_chain})),
             )
         })?;
 
@@ -569,66 +900,76 @@ impl DesktopWrapper {
         }))?]))
     }
 
-    #[tool(description = "Waits for an element to meet a specific condition (visible, enabled, focused, exists).")]
+    #[tool(description = "Deprecated, use wait_for. Waits for an element to meet a specific condition (visible, enabled, focused, exists).")]
     async fn wait_for_element(
         &self,
         #[tool(param)] args: WaitForElementArgs,
     ) -> Result<CallToolResult, McpError> {
         let locator = self.create_locator_for_chain(&args.selector_chain)?;
-        let timeout = get_timeout(args.timeout_ms);
-        
-        let condition_lower = args.condition.to_lowercase();
-        let result = match condition_lower.as_str() {
-            "exists" => {
-                match locator.wait(timeout).await {
-                    Ok(_) => Ok(true),
-                    Err(_) => Ok(false)
-                }
-            }
-            "visible" => {
-                match locator.wait(timeout).await {
-                    Ok(element) => element.is_visible().map_err(|e| 
-                        McpError::internal_error("Failed to check visibility", Some(json!({"reason": e.to_string()})))
-                    ),
-                    Err(e) => Err(McpError::internal_error("Element not found", Some(json!({"reason": e.to_string()}))))
-                }
-            }
-            "enabled" => {
-                match locator.wait(timeout).await {
-                    Ok(element) => element.is_enabled().map_err(|e| 
-                        McpError::internal_error("Failed to check enabled state", Some(json!({"reason": e.to_string()})))
-                    ),
-                    Err(e) => Err(McpError::internal_error("Element not found", Some(json!({"reason": e.to_string()}))))
-                }
-            }
-            "focused" => {
-                match locator.wait(timeout).await {
-                    Ok(element) => element.is_focused().map_err(|e| 
-                        McpError::internal_error("Failed to check focus state", Some(json!({"reason": e.to_string()})))
-                    ),
-                    Err(e) => Err(McpError::internal_error("Element not found", Some(json!({"reason": e.to_string()}))))
-                }
+        let condition = match args.condition.to_lowercase().as_str() {
+            "exists" => WaitCondition::Exists,
+            "visible" => WaitCondition::Visible,
+            "enabled" => WaitCondition::Enabled,
+            "focused" => WaitCondition::Focused,
+            _ => {
+                return Err(McpError::invalid_params(
+                    "Invalid condition. Valid conditions: exists, visible, enabled, focused",
+                    Some(json!({"provided_condition": args.condition})),
+                ))
             }
-            _ => Err(McpError::invalid_params(
-                "Invalid condition. Valid conditions: exists, visible, enabled, focused",
-                Some(json!({"provided_condition": args.condition}))
-            ))
         };
+        let timeout = wait_for_timeout(args.timeout_ms);
+        let outcome = poll_condition(&locator, condition, None, timeout, &args.selector_chain).await?;
 
-        match result {
-            Ok(condition_met) => {
-                Ok(CallToolResult::success(vec![Content::json(&json!({
-                    "action": "wait_for_element",
-                    "status": "success",
-                    "condition": args.condition,
-                    "condition_met": condition_met,
-                    "selector_chain": args.selector_chain,
-                    "timeout_ms": args.timeout_ms.unwrap_or(5000),
-                    "timestamp": chrono::Utc::now().to_rfc3339()
-                }))?]))
-            }
-            Err(e) => Err(e)
+        Ok(CallToolResult::success(vec![Content::json(&json!({
+            "action": "wait_for_element",
+            "status": "success",
+            "condition": args.condition,
+            "condition_met": outcome.condition_met,
+            "selector_chain": args.selector_chain,
+            "timeout_ms": timeout.as_millis() as u64,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))?]))
+    }
+
+    #[tool(description = "Waits until one of the elements of a selector chain exists, is visible, is enabled, has focus or contains a text, or until none is left, checking repeatedly on the server. Use it instead of calling find tools in a loop. Returns whether the condition was met, the time waited and the final state of the element that met it; a timeout is not an error.")]
+    async fn wait_for(
+        &self,
+        #[tool(param)] args: WaitForArgs,
+    ) -> Result<CallToolResult, McpError> {
+        let locator = self.create_locator_for_chain(&args.selector_chain)?;
+        let text = args.text.as_deref().map(str::to_lowercase);
+        if args.condition == WaitCondition::TextContains && text.is_none() {
+            return Err(McpError::invalid_params(
+                "The text_contains condition needs a text",
+                Some(json!({"condition": args.condition})),
+            ));
         }
+        let timeout = wait_for_timeout(args.timeout_ms);
+        let outcome = poll_condition(&locator, args.condition, text.as_deref(), timeout, &args.selector_chain).await?;
+
+        let final_state = match &outcome.element {
+            Some(element) => json!({
+                "exists": true,
+                "visible": element.is_visible().unwrap_or(false),
+                "text": element_text(element),
+                "element": element_info(element),
+            }),
+            None => json!({"exists": false}),
+        };
+        Ok(CallToolResult::success(vec![Content::json(&json!({
+            "action": "wait_for",
+            "status": if outcome.condition_met { "success" } else { "timeout" },
+            "condition": args.condition,
+            "text": args.text,
+            "condition_met": outcome.condition_met,
+            "elapsed_ms": outcome.elapsed.as_millis() as u64,
+            "timeout_ms": timeout.as_millis() as u64,
+            "checks": outcome.checks,
+            "final_state": final_state,
+            "selector_chain": args.selector_chain,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))?]))
     }
 
     #[tool(description = "Opens a URL in the specified browser (uses SDK's built-in browser automation).")]
@@ -639,7 +980,7 @@ impl DesktopWrapper {
         self.desktop.open_url(&args.url, args.browser.as_deref()).map_err(|e| {
             McpError::internal_error(
                 "Failed to open URL",
-                Some(json!({"reason": e.to_string(), "url": args.url, "browser": args.browser})),
+                Some(json!({"reason": e.to_string(), "error": e, "url": args.url, "browser": args.browser})),
             )
         })?;
 
@@ -660,7 +1001,7 @@ impl DesktopWrapper {
         let result = self.desktop.open_application(&args.app_name).map_err(|e| {
             McpError::internal_error(
                 "Failed to open application",
-                Some(json!({"reason": e.to_string(), "app_name": args.app_name})),
+                Some(json!({"reason": e.to_string(), "error": e, "app_name": args.app_name})),
             )
         })?;
 
@@ -692,7 +1033,7 @@ impl DesktopWrapper {
             .map_err(|e| {
                 McpError::internal_error(
                     "Failed to locate element for closing",
-                    Some(json!({"reason": e.to_string(), "selector_chain": args.selector_chain})),
+                    Some(json!({"reason": e.to_string(), "error": e, "selector_chain": args.selector_chain, "suggestions": e.suggestions()})),
                 )
             })?;
         
@@ -713,6 +1054,7 @@ impl DesktopWrapper {
                 "Failed to close element",
                 Some(json!({
                     "reason": e.to_string(),
+                    "error": e,
                     "selector_chain": args.selector_chain,
                     "element_info": element_info
                 })),
@@ -740,7 +1082,7 @@ impl DesktopWrapper {
             .map_err(|e| {
                 McpError::internal_error(
                     "Failed to locate element for scrolling",
-                    Some(json!({"reason": e.to_string(), "selector_chain": args.selector_chain})),
+                    Some(json!({"reason": e.to_string(), "error": e, "selector_chain": args.selector_chain, "suggestions": e.suggestions()})),
                 )
             })?;
         
@@ -759,6 +1101,7 @@ impl DesktopWrapper {
                 "Failed to scroll element",
                 Some(json!({
                     "reason": e.to_string(),
+                    "error": e,
                     "selector_chain": args.selector_chain,
                     "direction": args.direction,
                     "amount": args.amount,
@@ -778,6 +1121,76 @@ impl DesktopWrapper {
         }))?]))
     }
 
+    #[tool(
+        description = "Runs a list of steps (locate, click, type, press_key, assert, wait, open_application, set, extract, if) in one call and reports the result of each. ${name} in selectors, text, keys and values is replaced by a variable from `variables`, a set step or an extract step. All steps are checked before any runs, and by default the run stops at the first failed step."
+    )]
+    async fn execute_workflow(
+        &self,
+        #[tool(param)] args: ExecuteWorkflowArgs,
+    ) -> Result<CallToolResult, McpError> {
+        // The core's workflow steps, so a plan can be saved and run again
+        // with `terminator-cli run`
+        let mut workflow = Workflow::new(args.steps);
+        workflow.stop_on_error = args.stop_on_error.unwrap_or(true);
+        // Reject the whole plan up front rather than failing halfway through it
+        workflow
+            .validate()
+            .map_err(|e| McpError::invalid_params("Invalid workflow step", Some(json!({"reason": e.to_string()}))))?;
+
+        // The policy checked execute_workflow itself; each step is checked
+        // as the tool doing the same before it runs
+        let guard = WorkflowGuard {
+            policy: &self.policy,
+            desktop: &self.desktop,
+        };
+        let report = workflow
+            .run_guarded(&self.desktop, args.variables.unwrap_or_default(), &guard)
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+        Ok(CallToolResult::success(vec![Content::json(&json!({
+            "action": "execute_workflow",
+            "status": report.status,
+            "steps_total": workflow.step_count(),
+            "steps_run": report.results.len(),
+            "steps_failed": report.steps_failed,
+            "results": report.results,
+            "variables": report.variables,
+            "duration_ms": report.duration_ms,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }))?]))
+    }
+
+    /// Element of a reference handed out earlier. Unknown and expired
+    /// references tell the client to find the element again
+    fn resolve_ref(&self, element_ref: &str) -> Result<UIElement, McpError> {
+        let refs = self.element_refs.lock().unwrap_or_else(|e| e.into_inner());
+        refs.get(element_ref).ok_or_else(|| {
+            McpError::resource_not_found(
+                "Unknown or expired element reference",
+                Some(json!({
+                    "element_ref": element_ref,
+                    "reason": format!(
+                        "References expire {} seconds after they are returned; call find_elements again for a fresh one",
+                        ELEMENT_REF_TTL.as_secs()
+                    )
+                })),
+            )
+        })
+    }
+
+    /// `element_info` of `element` with a new reference to it
+    fn element_with_ref(&self, element: UIElement) -> serde_json::Value {
+        let mut info = element_info(&element);
+        let element_ref = self
+            .element_refs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(element);
+        info["ref"] = json!(element_ref);
+        info
+    }
+
     // keep in wrapperr to avoid creating new instance
     fn create_locator_for_chain(&self, selector_chain: &[String]) -> Result<Locator, McpError> {
         if selector_chain.is_empty() {
@@ -799,7 +1212,6 @@ impl DesktopWrapper {
     }
 }
 
-#[tool(tool_box)]
 impl ServerHandler for DesktopWrapper {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
@@ -813,6 +1225,145 @@ impl ServerHandler for DesktopWrapper {
             instructions: Some(get_server_instructions().to_string()),
         }
     }
+
+    // What `#[tool(tool_box)]` generates, with the policy applied
+    async fn list_tools(
+        &self,
+        _: PaginatedRequestParam,
+        _: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        let tools = Self::tool_box()
+            .list()
+            .into_iter()
+            .filter(|tool| self.policy.lists_tool(&tool.name))
+            .collect();
+        Ok(ListToolsResult {
+            next_cursor: None,
+            tools,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let arguments = serde_json::Value::Object(request.arguments.clone().unwrap_or_default());
+        if let Err(reason) = self.policy.check(&self.desktop, &request.name, &arguments).await {
+            tracing::warn!(tool = %request.name, "Tool call refused: {}", reason);
+            return Err(McpError::invalid_request(
+                reason,
+                Some(json!({"tool": request.name})),
+            ));
+        }
+        let context = ToolCallContext::new(self, request, context);
+        Self::tool_box().call(context).await
+    }
+}
+
+fn element_summary(element: &UIElement) -> serde_json::Value {
+    json!({
+        "name": element.name().unwrap_or_default(),
+        "role": element.role(),
+        "id": element.id().unwrap_or_default(),
+    })
+}
+
+/// Longest `wait_for` wait for a requested timeout
+fn wait_for_timeout(timeout_ms: Option<u64>) -> Duration {
+    timeout_ms.map(Duration::from_millis).unwrap_or(DEFAULT_WAIT_FOR).min(MAX_WAIT_FOR)
+}
+
+/// How a `wait_for` wait ended
+struct WaitOutcome {
+    condition_met: bool,
+    /// The element that met the condition, else the first one found by the
+    /// last check, if any
+    element: Option<UIElement>,
+    elapsed: Duration,
+    checks: u32,
+}
+
+/// Search for the elements of `locator` until `condition` holds for one of
+/// them, or for none being found, or `timeout` passes. `text` is lowercase
+async fn poll_condition(
+    locator: &Locator,
+    condition: WaitCondition,
+    text: Option<&str>,
+    timeout: Duration,
+    selector_chain: &[String],
+) -> Result<WaitOutcome, McpError> {
+    let start = Instant::now();
+    let mut interval = WAIT_FOR_FIRST_POLL;
+    let mut checks = 0;
+    loop {
+        checks += 1;
+        // One search per check; the locator's own waiting would retry
+        // and look for suggestions on every miss
+        let elements = match locator.all(Some(Duration::ZERO), None).await {
+            Ok(elements) => elements,
+            Err(e) if e.is_element_not_found() || e.code() == ErrorCode::Timeout => Vec::new(),
+            Err(e) => {
+                return Err(McpError::internal_error(
+                    "Failed to search for the element",
+                    Some(json!({"reason": e.to_string(), "error": e, "selector_chain": selector_chain})),
+                ))
+            }
+        };
+        // Any match will do, e.g. the one visible copy of a hidden template
+        let met = match condition {
+            WaitCondition::Gone => None,
+            _ => elements.iter().position(|element| wait_condition_met(condition, element, text)),
+        };
+        let condition_met = met.is_some() || (condition == WaitCondition::Gone && elements.is_empty());
+        let element = elements.into_iter().nth(met.unwrap_or(0));
+        let remaining = timeout.saturating_sub(start.elapsed());
+        if condition_met || remaining.is_zero() {
+            return Ok(WaitOutcome {
+                condition_met,
+                element,
+                elapsed: start.elapsed(),
+                checks,
+            });
+        }
+        tokio::time::sleep(interval.min(remaining)).await;
+        interval = (interval * 2).min(WAIT_FOR_LONGEST_POLL);
+    }
+}
+
+/// Whether a `wait_for` condition holds for a found element. `text` is
+/// lowercase
+fn wait_condition_met(condition: WaitCondition, element: &UIElement, text: Option<&str>) -> bool {
+    match condition {
+        WaitCondition::Gone => false,
+        WaitCondition::Exists => true,
+        WaitCondition::Visible => element.is_visible().unwrap_or(false),
+        WaitCondition::Enabled => element.is_enabled().unwrap_or(false),
+        WaitCondition::Focused => element.is_focused().unwrap_or(false),
+        WaitCondition::TextContains => {
+            let text = text.unwrap_or_default();
+            element_text(element).to_lowercase().contains(text)
+                || element.name().unwrap_or_default().to_lowercase().contains(text)
+        }
+    }
+}
+
+/// Text shown by an element and its close descendants
+fn element_text(element: &UIElement) -> String {
+    element.text(3).unwrap_or_default()
+}
+
+/// What the element tools report about the element they acted on
+fn element_info(element: &UIElement) -> serde_json::Value {
+    json!({
+        "name": element.name().unwrap_or_default(),
+        "role": element.role(),
+        "id": element.id().unwrap_or_default(),
+        "bounds": element.bounds().map(|b| json!({
+            "x": b.0, "y": b.1, "width": b.2, "height": b.3
+        })).unwrap_or(json!(null)),
+        "enabled": element.is_enabled().unwrap_or(false),
+    })
 }
 
 fn get_server_instructions() -> String {
@@ -849,14 +1400,19 @@ Your most reliable strategy is to inspect the application's UI structure *before
     *   `press_key`: To send special keys. Use curly braces for special keys like Enter, Tab, Ctrl+V, Shift+F5, etc.
     *   `activate_element`: To bring a window to the foreground.
     *   `mouse_drag`: To perform drag and drop operations.
-    *   `set_clipboard`: To set text to the system clipboard.
+    *   `global_key_press`: To send a key to whatever has focus, e.g. Escape to close a popup or Enter to submit, when there is no element to select.
+    *   `set_clipboard`: To set text to the system clipboard. To enter long text, set it and then paste with `global_key_press`.
     *   `get_clipboard`: To get text from the system clipboard.
     *   `scroll_element`: To scroll within elements like web pages, documents, or lists.
+    *   `find_text_on_screen`: To locate or click text by OCR when the window tree has no usable elements, e.g. in games, canvas apps or remote desktops.
+    *   `find_image_on_screen`: To locate or click an icon or other image without a name, given a picture of it.
+
+6.  **Reuse Element References:** `find_elements` and `explore_element` return a short-lived `ref` (e.g. e12) for each element. Pass it to `click_ref`, `type_into_ref` or `explore_element` to act on exactly that element again without resolving a selector. References expire after a few minutes; when one is reported unknown or expired, call `find_elements` again.
 
-6.  **Handle Scrolling for Full Context:** When working with pages or long content, ALWAYS scroll to see all content. Use `scroll_element` to scroll pages up/down to get the full context before making decisions or extracting information.
+7.  **Handle Scrolling for Full Context:** When working with pages or long content, ALWAYS scroll to see all content. Use `scroll_element` to scroll pages up/down to get the full context before making decisions or extracting information.
 
 **Important: Key Syntax for press_key Tool**
-When using the `press_key` tool, you MUST use curly braces for special keys:
+When using the `press_key` or `global_key_press` tools, you MUST use curly braces for special keys:
 - Single special key: Enter, Tab, Escape, Delete - wrap each in curly braces
 - Key combinations: For Ctrl+V use curly-Ctrl curly-V, for Alt+F4 use curly-Alt curly-F4
 - Windows key: For Win+D use curly-Win curly-D (shows desktop)
@@ -888,10 +1444,15 @@ When using the `press_key` tool, you MUST use curly braces for special keys:
 *   `get_clipboard`: Gets text from the system clipboard using native commands.
 *   `mouse_drag`: Performs a mouse drag operation from start to end coordinates.
 *   `validate_element`: Validates that an element exists and provides detailed information.
+*   `find_elements`: Finds all elements matching a selector chain and returns a reference for each.
+*   `explore_element`: Lists the children of an element, given by reference or selector chain, with references.
+*   `click_ref`: Clicks the element of a reference.
+*   `type_into_ref`: Types text into the element of a reference.
 *   `highlight_element`: Highlights an element with a colored border for visual confirmation.
-*   `wait_for_element`: Waits for an element to meet a specific condition (visible, enabled, focused, exists).
+*   `wait_for`: Waits until an element exists, is visible, is enabled, has focus, contains a text or is gone, and reports how long it took. Call it after an action that takes time (loading, saving, a dialog opening or closing) instead of calling find tools repeatedly.
 *   `navigate_browser`: Opens a URL in the specified browser.
 *   `open_application`: Opens an application by name.
+*   `execute_workflow`: Runs several steps (locate, click, type, press_key, assert, wait) in one call. Use it once you know the selectors, e.g. to fill and submit a form.
 
 Contextual information:
 - The current date and time is {}.
@@ -1,15 +1,69 @@
 use anyhow::Result;
 use rmcp::{schemars, schemars::JsonSchema};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::env;
-use std::time::Duration;
-use terminator::{Desktop};
+use std::sync::{Arc, Mutex};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use terminator::{Desktop, UIElement, UINode, WorkflowStep};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
 use tracing::Level;
+use crate::policy::Policy;
 use tracing_subscriber::EnvFilter;
 
 #[derive(Clone)]
 pub struct DesktopWrapper {
     pub desktop: Desktop,
+    /// Last tree returned by `diff_window_tree`, per process ID
+    pub tree_snapshots: Arc<Mutex<HashMap<u32, UINode>>>,
+    /// Elements handed out as references by `find_elements` and `explore_element`
+    pub element_refs: Arc<Mutex<ElementRefs>>,
+    /// Limits on the tools and applications the model may use
+    pub policy: Arc<Policy>,
+}
+
+/// How long an element reference stays usable after it was handed out
+pub const ELEMENT_REF_TTL: Duration = Duration::from_secs(300);
+/// Most references kept at once, the oldest are dropped first
+const MAX_ELEMENT_REFS: usize = 1000;
+
+/// Elements found by earlier tool calls, under short IDs ("e1", "e2", ...)
+/// that later calls pass instead of resolving a selector chain again
+#[derive(Default)]
+pub struct ElementRefs {
+    next_id: u64,
+    elements: HashMap<String, (UIElement, Instant)>,
+}
+
+impl ElementRefs {
+    /// Keep `element` and return its reference
+    pub fn insert(&mut self, element: UIElement) -> String {
+        self.elements.retain(|_, (_, created)| created.elapsed() < ELEMENT_REF_TTL);
+        if self.elements.len() >= MAX_ELEMENT_REFS {
+            let oldest = self
+                .elements
+                .iter()
+                .min_by_key(|(_, (_, created))| *created)
+                .map(|(id, _)| id.clone());
+            if let Some(oldest) = oldest {
+                self.elements.remove(&oldest);
+            }
+        }
+        self.next_id += 1;
+        let id = format!("e{}", self.next_id);
+        self.elements.insert(id.clone(), (element, Instant::now()));
+        id
+    }
+
+    /// The element of a reference that hasn't expired
+    pub fn get(&self, id: &str) -> Option<UIElement> {
+        self.elements
+            .get(id.trim())
+            .filter(|(_, created)| created.elapsed() < ELEMENT_REF_TTL)
+            .map(|(element, _)| element.clone())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
@@ -20,6 +74,16 @@ pub struct GetWindowTreeArgs {
     pub title: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DiffWindowTreeArgs {
+    #[schemars(description = "Process ID of the target application")]
+    pub pid: u32,
+    #[schemars(description = "Optional window title filter")]
+    pub title: Option<String>,
+    #[schemars(description = "Discard the stored snapshot and only record a new baseline")]
+    pub reset: Option<bool>,
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct GetWindowsArgs {
     #[schemars(description = "Name of the application to get windows for")]
@@ -34,6 +98,40 @@ pub struct LocatorArgs {
     pub timeout_ms: Option<u64>,
 }
 
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FindElementsArgs {
+    #[schemars(description = "An array of selector strings to locate the elements")]
+    pub selector_chain: Vec<String>,
+    #[schemars(description = "Most elements to return (default: 20)")]
+    pub max_results: Option<usize>,
+    #[schemars(description = "Optional timeout in milliseconds for the first match")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ExploreElementArgs {
+    #[schemars(description = "Reference of the element to explore, as returned by find_elements or explore_element")]
+    pub element_ref: Option<String>,
+    #[schemars(description = "An array of selector strings to locate the element, when no element_ref is given")]
+    pub selector_chain: Option<Vec<String>>,
+    #[schemars(description = "Optional timeout in milliseconds for the selector chain")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ElementRefArgs {
+    #[schemars(description = "Reference of the element, as returned by find_elements or explore_element")]
+    pub element_ref: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct TypeIntoRefArgs {
+    #[schemars(description = "Reference of the element, as returned by find_elements or explore_element")]
+    pub element_ref: String,
+    #[schemars(description = "The text to type into the element")]
+    pub text_to_type: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct TypeIntoElementArgs {
     #[schemars(description = "An array of selector strings to locate the element")]
@@ -54,6 +152,38 @@ pub struct PressKeyArgs {
     pub timeout_ms: Option<u64>,
 }
 
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GlobalKeyPressArgs {
+    #[schemars(description = "The key or key combination to send to the focused element, in the same syntax as press_key (e.g., '{Escape}', '{Ctrl}v')")]
+    pub key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FindTextOnScreenArgs {
+    #[schemars(description = "The text to find, matched case-insensitively; may span several words of one line")]
+    pub text: String,
+    #[schemars(description = "Optional screen region to search as [x, y, width, height] in physical pixels; all monitors when omitted")]
+    pub region: Option<[f64; 4]>,
+    #[schemars(description = "Whether to click a match (default: false)")]
+    pub click: Option<bool>,
+    #[schemars(description = "Which match to click, 0-based in reading order (default: 0)")]
+    pub index: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FindImageOnScreenArgs {
+    #[schemars(description = "Path to an image file (e.g. PNG) of what to find, such as a toolbar icon")]
+    pub template_path: String,
+    #[schemars(description = "Minimum confidence of a match from 0.0 to 1.0 (default: 0.9)")]
+    pub threshold: Option<f32>,
+    #[schemars(description = "Optional screen region to search as [x, y, width, height] in physical pixels; all monitors when omitted")]
+    pub region: Option<[f64; 4]>,
+    #[schemars(description = "Whether to also try the image at other sizes, for screens at a different scaling (default: false)")]
+    pub multi_scale: Option<bool>,
+    #[schemars(description = "Whether to click the best match (default: false)")]
+    pub click: Option<bool>,
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct RunCommandArgs {
     #[schemars(description = "The command to run on Windows")]
@@ -117,6 +247,35 @@ pub struct WaitForElementArgs {
     pub timeout_ms: Option<u64>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(description = "Condition of a wait_for call")]
+pub enum WaitCondition {
+    /// The selector chain finds an element
+    Exists,
+    /// One of the elements found is visible on screen
+    Visible,
+    /// One of the elements found is enabled
+    Enabled,
+    /// One of the elements found has keyboard focus
+    Focused,
+    /// The name or text of one of the elements found contains `text`
+    TextContains,
+    /// The selector chain finds nothing
+    Gone,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct WaitForArgs {
+    #[schemars(description = "An array of selector strings to locate the element")]
+    pub selector_chain: Vec<String>,
+    pub condition: WaitCondition,
+    #[schemars(description = "Text the element must contain, for the text_contains condition (case-insensitive)")]
+    pub text: Option<String>,
+    #[schemars(description = "Longest wait in milliseconds (default 10000, at most 120000)")]
+    pub timeout_ms: Option<u64>,
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct NavigateBrowserArgs {
     #[schemars(description = "URL to navigate to")]
@@ -150,6 +309,16 @@ pub struct ScrollElementArgs {
     pub timeout_ms: Option<u64>,
 }
 
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ExecuteWorkflowArgs {
+    #[schemars(description = "Steps to run in order")]
+    pub steps: Vec<WorkflowStep>,
+    #[schemars(description = "Stop at the first failed step (default true)")]
+    pub stop_on_error: Option<bool>,
+    #[schemars(description = "Variables used as ${name} in selectors, text, keys and values")]
+    pub variables: Option<BTreeMap<String, String>>,
+}
+
 pub fn init_logging() -> Result<()> {
     let log_level = env::var("LOG_LEVEL")
         .map(|level| match level.to_lowercase().as_str() {
@@ -173,3 +342,74 @@ pub fn init_logging() -> Result<()> {
 pub fn get_timeout(timeout_ms: Option<u64>) -> Option<Duration> {
     timeout_ms.map(Duration::from_millis)
 }
+
+/// Command that reads the clipboard contents from its standard input, and
+/// one that writes them to its standard output. The text goes through a pipe
+/// rather than the command line, so any length and any characters work
+fn clipboard_commands() -> (Command, Command) {
+    if cfg!(target_os = "windows") {
+        let powershell = |script: &str| {
+            let mut command = Command::new("powershell");
+            command.args(["-NoProfile", "-NonInteractive", "-Command", script]);
+            command
+        };
+        (
+            powershell(
+                "[Console]::InputEncoding = [Text.Encoding]::UTF8; \
+                 Set-Clipboard -Value ([Console]::In.ReadToEnd())",
+            ),
+            powershell(
+                "[Console]::OutputEncoding = [Text.Encoding]::UTF8; \
+                 Get-Clipboard -Raw",
+            ),
+        )
+    } else if cfg!(target_os = "macos") {
+        (Command::new("pbcopy"), Command::new("pbpaste"))
+    } else {
+        let mut write = Command::new("xclip");
+        write.args(["-selection", "clipboard"]);
+        let mut read = Command::new("xclip");
+        read.args(["-selection", "clipboard", "-o"]);
+        (write, read)
+    }
+}
+
+pub async fn set_clipboard_text(text: &str) -> Result<()> {
+    let (mut command, _) = clipboard_commands();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    stdin.write_all(text.as_bytes()).await?;
+    // Closing stdin tells the command the text is complete
+    drop(stdin);
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Clipboard command failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+pub async fn get_clipboard_text() -> Result<String> {
+    let (_, mut command) = clipboard_commands();
+    let output = command.stdin(Stdio::null()).output().await?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Clipboard command failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    // Get-Clipboard ends its output with a newline of its own
+    if cfg!(target_os = "windows") && text.ends_with("\r\n") {
+        text.truncate(text.len() - 2);
+    }
+    Ok(text)
+}
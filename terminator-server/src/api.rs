@@ -0,0 +1,288 @@
+//! REST routes on elements addressed by handles
+
+use std::time::Duration;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use terminator::{AutomationError, BatchOp, BatchStatus, UIElement};
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::error::{ApiError, ErrorBody};
+use crate::handles::DEFAULT_SESSION;
+use crate::AppState;
+
+pub(crate) const ACTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Find the first element matching a selector
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FirstRequest {
+    /// Selector such as `name:Save` or `role:Button >> name:OK`
+    #[schema(example = "name:Save")]
+    pub selector: String,
+    /// How long to wait for the element, 10 seconds by default
+    pub timeout_ms: Option<u64>,
+    /// Handle of an element found earlier to search inside, instead of
+    /// resolving the selector from the desktop root
+    pub within: Option<String>,
+    /// Session the handle belongs to, `default` when omitted
+    pub session: Option<String>,
+}
+
+/// An element found by a selector
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FirstResponse {
+    /// Opaque token addressing the element in later requests
+    pub handle: String,
+    pub role: String,
+    pub name: Option<String>,
+}
+
+/// Click an element
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ClickRequest {
+    pub handle: String,
+    pub session: Option<String>,
+}
+
+/// Type text into an element
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TypeTextRequest {
+    pub handle: String,
+    pub session: Option<String>,
+    pub text: String,
+}
+
+/// Press a key or key combination on an element
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PressKeyRequest {
+    pub handle: String,
+    pub session: Option<String>,
+    /// Key in terminator syntax, e.g. `{Enter}` or `{Ctrl}a`
+    #[schema(example = "{Enter}")]
+    pub key: String,
+}
+
+/// Result of an action
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ActionResponse {
+    pub ok: bool,
+}
+
+/// Several element operations run in one request
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchRequest {
+    /// Operations tagged by `op`: `find`, `click`, `type_text` (with `text`)
+    /// or `get_text`, each with a `selector`, e.g.
+    /// `{"op": "click", "selector": "name:Submit"}`
+    #[schema(value_type = Vec<Object>)]
+    pub ops: Vec<BatchOp>,
+    /// How long each selector may take to resolve, 10 seconds by default
+    pub timeout_ms: Option<u64>,
+    /// Skip the remaining operations after one fails, true by default
+    pub stop_on_error: Option<bool>,
+    /// Handle of an element found earlier to resolve the selectors inside
+    pub within: Option<String>,
+    /// Session the handles of found elements belong to, `default` when omitted
+    pub session: Option<String>,
+}
+
+/// Result of one operation of a batch
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchItem {
+    pub index: usize,
+    /// `ok`, `failed` or `skipped`
+    pub status: String,
+    /// Handle of the element the operation acted on
+    pub handle: Option<String>,
+    /// Text read by a `get_text` operation
+    pub text: Option<String>,
+    pub error: Option<String>,
+    pub error_code: Option<String>,
+}
+
+/// Results of a batch, one per operation
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchResponse {
+    pub results: Vec<BatchItem>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/elements/first",
+    tag = "elements",
+    request_body = FirstRequest,
+    responses(
+        (status = 200, description = "Element found", body = FirstResponse),
+        (status = 404, description = "No element matches the selector, or unknown `within` handle", body = ErrorBody),
+        (status = 410, description = "The `within` element no longer exists", body = ErrorBody),
+        (status = 504, description = "Timed out waiting for the element", body = ErrorBody),
+    )
+)]
+pub(crate) async fn first(
+    State(state): State<AppState>,
+    Json(request): Json<FirstRequest>,
+) -> Result<Json<FirstResponse>, ApiError> {
+    let timeout = request.timeout_ms.map(Duration::from_millis).unwrap_or(ACTION_TIMEOUT);
+    let mut locator = state.desktop.locator(request.selector.as_str());
+    if let Some(within) = &request.within {
+        locator = locator.within(resolve(&state, request.session.as_deref(), within).await?);
+    }
+    let element = locator.first(Some(timeout)).await?;
+    let session = request.session.as_deref().unwrap_or(DEFAULT_SESSION);
+    Ok(Json(FirstResponse {
+        role: element.role(),
+        name: element.name(),
+        handle: state.handles.insert(session, element).await?,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/elements/click",
+    tag = "elements",
+    request_body = ClickRequest,
+    responses(
+        (status = 200, description = "Element clicked", body = ActionResponse),
+        (status = 404, description = "Unknown handle", body = ErrorBody),
+        (status = 410, description = "The element no longer exists", body = ErrorBody),
+    )
+)]
+pub(crate) async fn click(
+    State(state): State<AppState>,
+    Json(request): Json<ClickRequest>,
+) -> Result<Json<ActionResponse>, ApiError> {
+    let element = resolve(&state, request.session.as_deref(), &request.handle).await?;
+    perform(&element, "click", None, None)?;
+    Ok(Json(ActionResponse { ok: true }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/elements/type_text",
+    tag = "elements",
+    request_body = TypeTextRequest,
+    responses(
+        (status = 200, description = "Text typed", body = ActionResponse),
+        (status = 404, description = "Unknown handle", body = ErrorBody),
+        (status = 410, description = "The element no longer exists", body = ErrorBody),
+    )
+)]
+pub(crate) async fn type_text(
+    State(state): State<AppState>,
+    Json(request): Json<TypeTextRequest>,
+) -> Result<Json<ActionResponse>, ApiError> {
+    let element = resolve(&state, request.session.as_deref(), &request.handle).await?;
+    perform(&element, "type_text", Some(&request.text), None)?;
+    Ok(Json(ActionResponse { ok: true }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/elements/press_key",
+    tag = "elements",
+    request_body = PressKeyRequest,
+    responses(
+        (status = 200, description = "Key pressed", body = ActionResponse),
+        (status = 404, description = "Unknown handle", body = ErrorBody),
+        (status = 410, description = "The element no longer exists", body = ErrorBody),
+    )
+)]
+pub(crate) async fn press_key(
+    State(state): State<AppState>,
+    Json(request): Json<PressKeyRequest>,
+) -> Result<Json<ActionResponse>, ApiError> {
+    let element = resolve(&state, request.session.as_deref(), &request.handle).await?;
+    perform(&element, "press_key", None, Some(&request.key))?;
+    Ok(Json(ActionResponse { ok: true }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/batch",
+    tag = "elements",
+    request_body = BatchRequest,
+    responses(
+        (status = 200, description = "Operations run; failures are reported per operation", body = BatchResponse),
+        (status = 404, description = "Unknown `within` handle", body = ErrorBody),
+        (status = 410, description = "The `within` element no longer exists", body = ErrorBody),
+    )
+)]
+pub(crate) async fn batch(
+    State(state): State<AppState>,
+    Json(request): Json<BatchRequest>,
+) -> Result<Json<BatchResponse>, ApiError> {
+    let timeout = request.timeout_ms.map(Duration::from_millis).unwrap_or(ACTION_TIMEOUT);
+    let session = request.session.as_deref().unwrap_or(DEFAULT_SESSION);
+    let mut batch = state.desktop.batch();
+    if let Some(within) = &request.within {
+        batch = batch.within(resolve(&state, Some(session), within).await?);
+    }
+    let batch = request
+        .ops
+        .into_iter()
+        .fold(batch, |batch, op| batch.push(op))
+        .timeout(timeout)
+        .stop_on_error(request.stop_on_error.unwrap_or(true));
+
+    let mut results = Vec::new();
+    for result in batch.run().await {
+        let handle = match result.element {
+            Some(element) => Some(state.handles.insert(session, element).await?),
+            None => None,
+        };
+        results.push(BatchItem {
+            index: result.index,
+            status: match result.status {
+                BatchStatus::Ok => "ok",
+                BatchStatus::Failed => "failed",
+                BatchStatus::Skipped => "skipped",
+            }
+            .to_string(),
+            handle,
+            text: result.text,
+            error: result.error,
+            error_code: result.error_code.map(|code| code.name().to_string()),
+        });
+    }
+    Ok(Json(BatchResponse { results }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/sessions/{session}",
+    tag = "sessions",
+    params(("session" = String, Path, description = "Session whose handles to release")),
+    responses((status = 204, description = "Handles released"))
+)]
+pub(crate) async fn release_session(
+    State(state): State<AppState>,
+    Path(session): Path<String>,
+) -> StatusCode {
+    let released = state.handles.release_session(&session);
+    info!(session, released, "Released session handles");
+    StatusCode::NO_CONTENT
+}
+
+async fn resolve(state: &AppState, session: Option<&str>, handle: &str) -> Result<UIElement, ApiError> {
+    Ok(state
+        .handles
+        .resolve(state.desktop.clone(), session.unwrap_or(DEFAULT_SESSION), handle)
+        .await?)
+}
+
+pub(crate) fn perform(
+    element: &UIElement,
+    action: &str,
+    text: Option<&str>,
+    key: Option<&str>,
+) -> Result<(), AutomationError> {
+    match action {
+        "click" => element.click().map(|_| ()),
+        "type_text" => element.type_text(text.unwrap_or_default(), false),
+        "press_key" => element.press_key(key.unwrap_or_default()),
+        other => Err(AutomationError::InvalidArgument(format!("Unknown action '{}'", other))),
+    }
+}
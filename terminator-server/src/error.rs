@@ -0,0 +1,98 @@
+//! Errors returned by the HTTP API
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use terminator::{AutomationError, ErrorCode};
+use utoipa::ToSchema;
+
+use crate::handles::HandleError;
+
+/// Body of every error response
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ErrorBody {
+    /// Human-readable description of the error
+    pub error: String,
+    /// Code and context of an automation error
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<serde_json::Value>,
+}
+
+/// HTTP error with a JSON body
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub body: ErrorBody,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body)).into_response()
+    }
+}
+
+impl From<HandleError> for ApiError {
+    fn from(e: HandleError) -> Self {
+        let status = match e {
+            HandleError::Unknown(_) => StatusCode::NOT_FOUND,
+            HandleError::Stale(_) => StatusCode::GONE,
+            HandleError::Refresh(..) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError {
+            status,
+            body: ErrorBody { error: e.to_string(), details: None },
+        }
+    }
+}
+
+impl From<AutomationError> for ApiError {
+    fn from(e: AutomationError) -> Self {
+        let status = match e.code() {
+            ErrorCode::ElementNotFound => StatusCode::NOT_FOUND,
+            ErrorCode::Timeout => StatusCode::GATEWAY_TIMEOUT,
+            ErrorCode::InvalidArgument => StatusCode::BAD_REQUEST,
+            ErrorCode::ElevationRequired | ErrorCode::MissingPermission => StatusCode::FORBIDDEN,
+            ErrorCode::StaleElement => StatusCode::GONE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError {
+            status,
+            body: ErrorBody {
+                error: e.to_string(),
+                details: serde_json::to_value(&e).ok(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_automation_errors_map_to_statuses() {
+        let status = |e: AutomationError| ApiError::from(e).status;
+        assert_eq!(status(AutomationError::ElementNotFound("x".to_string())), StatusCode::NOT_FOUND);
+        assert_eq!(status(AutomationError::Timeout("x".to_string())), StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(status(AutomationError::InvalidArgument("x".to_string())), StatusCode::BAD_REQUEST);
+        assert_eq!(status(AutomationError::StaleElement("x".to_string())), StatusCode::GONE);
+        assert_eq!(status(AutomationError::Internal("x".to_string())), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let error = ApiError::from(AutomationError::ElementNotFound("no Save button".to_string()));
+        assert!(error.body.error.contains("no Save button"));
+        assert_eq!(error.body.details.unwrap()["name"], ErrorCode::ElementNotFound.name());
+    }
+
+    #[test]
+    fn test_handle_errors_map_to_statuses() {
+        let status = |e: HandleError| ApiError::from(e).status;
+        assert_eq!(status(HandleError::Unknown("h".to_string())), StatusCode::NOT_FOUND);
+        assert_eq!(status(HandleError::Stale("h".to_string())), StatusCode::GONE);
+        assert_eq!(
+            status(HandleError::Refresh("h".to_string(), AutomationError::Internal("x".to_string()))),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! Elements found by clients, addressed by opaque handles
+
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher, RandomState};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use terminator::{AutomationError, Desktop, RefreshableElement, UIElement};
+
+/// Session used by requests that name none
+pub const DEFAULT_SESSION: &str = "default";
+
+/// Elements found by clients, per session, keyed by opaque handle tokens.
+/// Handles follow their element when the application recreates it
+#[derive(Default)]
+pub struct HandleStore {
+    sessions: Mutex<HashMap<String, HashMap<String, RefreshableElement>>>,
+    counter: AtomicU64,
+}
+
+#[derive(Debug)]
+pub enum HandleError {
+    Unknown(String),
+    Stale(String),
+    /// The element went stale and finding it again failed
+    Refresh(String, AutomationError),
+}
+
+/// A fresh opaque token. Unguessable, so one client cannot use another's
+/// handles or jobs by counting
+pub(crate) fn new_token(counter: &AtomicU64) -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(counter.fetch_add(1, Ordering::Relaxed));
+    format!("{:016x}", hasher.finish())
+}
+
+impl HandleStore {
+    pub async fn insert(&self, session: &str, element: UIElement) -> Result<String, AutomationError> {
+        let handle = new_token(&self.counter);
+        // Describing walks the ancestors and their children, which blocks
+        // the thread it runs on for a while
+        let element = tokio::task::spawn_blocking(move || RefreshableElement::new(element))
+            .await
+            .map_err(|e| AutomationError::Internal(format!("Describing the element failed: {}", e)))?;
+        self.lock()
+            .entry(session.to_string())
+            .or_default()
+            .insert(handle.clone(), element);
+        Ok(handle)
+    }
+
+    /// The element behind `handle`, found again if the application recreated
+    /// it. Drops the handle if the element is gone for good
+    pub async fn resolve(&self, desktop: Arc<Desktop>, session: &str, handle: &str) -> Result<UIElement, HandleError> {
+        let element = self
+            .lock()
+            .get(session)
+            .and_then(|handles| handles.get(handle))
+            .cloned()
+            .ok_or_else(|| HandleError::Unknown(handle.to_string()))?;
+        // Finding a stale element again walks the tree from the desktop
+        // root, like describing it in insert
+        let refreshed = tokio::task::spawn_blocking(move || element.get(&desktop))
+            .await
+            .map_err(|e| {
+                HandleError::Refresh(
+                    handle.to_string(),
+                    AutomationError::Internal(format!("Refreshing the element failed: {}", e)),
+                )
+            })?;
+        match refreshed {
+            Ok(element) => Ok(element),
+            Err(e) if matches!(e.kind(), AutomationError::StaleElement(_)) => {
+                if let Some(handles) = self.lock().get_mut(session) {
+                    handles.remove(handle);
+                }
+                Err(HandleError::Stale(handle.to_string()))
+            }
+            Err(e) => Err(HandleError::Refresh(handle.to_string(), e)),
+        }
+    }
+
+    pub fn release_session(&self, session: &str) -> usize {
+        self.lock().remove(session).map(|handles| handles.len()).unwrap_or(0)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, HashMap<String, RefreshableElement>>> {
+        self.sessions.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl std::fmt::Display for HandleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HandleError::Unknown(handle) => write!(f, "Unknown handle '{}'", handle),
+            HandleError::Stale(handle) => {
+                write!(f, "Handle '{}' is stale, the element no longer exists", handle)
+            }
+            HandleError::Refresh(handle, e) => {
+                write!(f, "Handle '{}' is stale and finding its element again failed: {}", handle, e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_are_distinct_and_opaque() {
+        let counter = AtomicU64::new(0);
+        let tokens: std::collections::HashSet<String> = (0..1000).map(|_| new_token(&counter)).collect();
+        assert_eq!(tokens.len(), 1000);
+        assert!(tokens.iter().all(|token| token.len() == 16));
+        // Not the counter itself
+        assert!(!tokens.contains(&format!("{:016x}", 0)));
+    }
+
+    #[test]
+    fn test_releasing_an_unknown_session_releases_nothing() {
+        let store = HandleStore::default();
+        assert_eq!(store.release_session(DEFAULT_SESSION), 0);
+    }
+
+    #[test]
+    fn test_handle_errors_name_the_handle() {
+        assert_eq!(HandleError::Unknown("abc".to_string()).to_string(), "Unknown handle 'abc'");
+        let refresh = HandleError::Refresh("abc".to_string(), AutomationError::Timeout("slow".to_string()));
+        assert!(refresh.to_string().contains("'abc'"));
+        assert!(refresh.to_string().contains("slow"));
+    }
+}
@@ -0,0 +1,204 @@
+//! The HTTP API over a Unix socket or a Windows named pipe
+//!
+//! For hosts on the same machine, e.g. a .NET app, that should not need a
+//! TCP port. Requests and responses are the same as over TCP, so any HTTP
+//! client that can dial a socket or pipe works:
+//!
+//! ```text
+//! terminator-server --transport ipc
+//! curl --unix-socket $XDG_RUNTIME_DIR/terminator-server.sock http://localhost/v1/jobs
+//! ```
+//!
+//! Only the user running the server may connect. On Unix, clients of other
+//! users are dropped when accepted, whatever the socket's permissions were
+//! when they connected. Named pipes keep the default security, which only
+//! grants write access to the creator, administrators and the system, and
+//! refuse remote clients.
+
+use std::io;
+
+use axum::Router;
+
+/// Pipe name (Windows) or socket path (Unix) used when none is given. On
+/// Unix it is in the user's runtime directory when there is one
+pub fn default_path() -> String {
+    #[cfg(windows)]
+    {
+        r"\\.\pipe\terminator-server".to_string()
+    }
+    #[cfg(not(windows))]
+    {
+        std::env::var_os("XDG_RUNTIME_DIR")
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(std::env::temp_dir)
+            .join("terminator-server.sock")
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+/// Serve `app` on the Unix socket `path` until the process exits. A socket
+/// left behind at `path` by an earlier run is replaced
+#[cfg(unix)]
+pub async fn serve(path: &str, app: Router) -> io::Result<()> {
+    use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+    // Anything else at the path is not ours to delete
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path)?,
+        Ok(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} exists and is not a socket", path),
+            ))
+        }
+        Err(_) => {}
+    }
+    let listener = tokio::net::UnixListener::bind(path)?;
+    // The socket is owned by the user the server runs as
+    let uid = std::fs::metadata(path)?.uid();
+    tracing::info!("Listening on unix socket {}", path);
+    axum::serve(unix::SameUser { listener, uid }, app).await
+}
+
+/// Serve `app` on the named pipe `path` until the process exits
+#[cfg(windows)]
+pub async fn serve(path: &str, app: Router) -> io::Result<()> {
+    let listener = pipe::PipeListener::create(path)?;
+    tracing::info!("Listening on named pipe {}", path);
+    axum::serve(listener, app).await
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::io;
+
+    use axum::serve::Listener;
+    use tokio::net::unix::SocketAddr;
+    use tokio::net::{UnixListener, UnixStream};
+    use tracing::warn;
+
+    /// Accepts only clients running as `uid`
+    pub(super) struct SameUser {
+        pub(super) listener: UnixListener,
+        pub(super) uid: u32,
+    }
+
+    impl Listener for SameUser {
+        type Io = UnixStream;
+        type Addr = SocketAddr;
+
+        async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+            loop {
+                let (stream, addr) = Listener::accept(&mut self.listener).await;
+                match stream.peer_cred() {
+                    Ok(peer) if peer.uid() == self.uid => return (stream, addr),
+                    Ok(peer) => warn!(uid = peer.uid(), "Rejected IPC client of another user"),
+                    Err(e) => warn!("Rejected IPC client whose user is unknown: {}", e),
+                }
+            }
+        }
+
+        fn local_addr(&self) -> io::Result<Self::Addr> {
+            self.listener.local_addr()
+        }
+    }
+}
+
+#[cfg(windows)]
+mod pipe {
+    use std::io;
+    use std::time::Duration;
+
+    use axum::serve::Listener;
+    use tokio::net::windows::named_pipe::{NamedPipeServer, ServerOptions};
+    use tracing::warn;
+
+    /// Hands out connected instances of a named pipe
+    pub(super) struct PipeListener {
+        path: String,
+        /// The instance waiting for the next client
+        next: NamedPipeServer,
+    }
+
+    impl PipeListener {
+        pub(super) fn create(path: &str) -> io::Result<Self> {
+            Ok(Self {
+                path: path.to_string(),
+                next: ServerOptions::new().first_pipe_instance(true).create(path)?,
+            })
+        }
+    }
+
+    impl Listener for PipeListener {
+        type Io = NamedPipeServer;
+        type Addr = String;
+
+        async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+            loop {
+                // The next instance is created before this one is handed out,
+                // so clients never find the pipe missing
+                match self.next.connect().await.and_then(|()| ServerOptions::new().create(&self.path)) {
+                    Ok(next) => return (std::mem::replace(&mut self.next, next), self.path.clone()),
+                    Err(e) => {
+                        warn!("Failed to accept IPC client: {}", e);
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                }
+            }
+        }
+
+        fn local_addr(&self) -> io::Result<Self::Addr> {
+            Ok(self.path.clone())
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn socket_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("terminator-server-{}-{}.sock", std::process::id(), name));
+        path.to_string_lossy().into_owned()
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_served_over_the_socket() {
+        let path = socket_path("serve");
+        let app = Router::new().route("/v1/ping", get(|| async { "pong" }));
+        tokio::spawn({
+            let path = path.clone();
+            async move { serve(&path, app).await }
+        });
+
+        let mut stream = loop {
+            match tokio::net::UnixStream::connect(&path).await {
+                Ok(stream) => break stream,
+                Err(_) => tokio::time::sleep(std::time::Duration::from_millis(10)).await,
+            }
+        };
+        stream
+            .write_all(b"GET /v1/ping HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        assert!(response.ends_with("pong"), "{}", response);
+    }
+
+    #[tokio::test]
+    async fn test_only_stale_sockets_are_replaced() {
+        let path = socket_path("file");
+        std::fs::write(&path, "not a socket").unwrap();
+        let error = serve(&path, Router::new()).await.unwrap_err();
+        let kept = std::fs::read_to_string(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(kept.unwrap(), "not a socket");
+    }
+}
@@ -0,0 +1,478 @@
+//! Long-running operations run in the background and polled by job id
+//!
+//! Dumping every window tree, OCR and workflows can take minutes, longer
+//! than clients and proxies keep a request open. `POST /v1/jobs` starts one
+//! and answers at once with the job's id; `GET /v1/jobs/{id}` reports its
+//! status and, once done, its result or error, and `DELETE /v1/jobs/{id}`
+//! cancels it. Finished jobs are kept for an hour.
+//!
+//! At most [`MAX_RUNNING_JOBS`] run at once and [`MAX_JOBS`] are kept,
+//! finished or not; past either, new jobs are refused with
+//! `429 Too Many Requests` until jobs finish or are deleted.
+
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use terminator::{AutomationError, Workflow};
+use tokio::task::AbortHandle;
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::error::{ApiError, ErrorBody};
+use crate::handles::new_token;
+use crate::AppState;
+
+/// How long finished jobs can still be polled
+const RETENTION: Duration = Duration::from_secs(60 * 60);
+/// Jobs running at once
+pub const MAX_RUNNING_JOBS: usize = 16;
+/// Jobs kept, running or finished
+pub const MAX_JOBS: usize = 1000;
+
+/// An operation to run as a job, tagged by `op`
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum JobRequest {
+    /// Tree of a window of a process, the one titled `title` if given
+    WindowTree { pid: u32, title: Option<String> },
+    /// Trees of all windows
+    AllWindowTrees,
+    /// Text on the primary screen, read by OCR
+    Ocr,
+    /// Run a workflow in the format of `terminator-cli run`, with
+    /// `variables` overriding its defaults
+    Workflow {
+        #[schema(value_type = Object)]
+        workflow: Workflow,
+        #[serde(default)]
+        variables: BTreeMap<String, String>,
+    },
+}
+
+impl JobRequest {
+    fn op(&self) -> &'static str {
+        match self {
+            JobRequest::WindowTree { .. } => "window_tree",
+            JobRequest::AllWindowTrees => "all_window_trees",
+            JobRequest::Ocr => "ocr",
+            JobRequest::Workflow { .. } => "workflow",
+        }
+    }
+}
+
+/// State of a job
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobResponse {
+    pub id: String,
+    /// Operation the job runs, e.g. `window_tree`
+    pub op: String,
+    /// `running`, `succeeded`, `failed` or `cancelled`
+    pub status: String,
+    /// Time the job has run for, or ran for once finished
+    pub elapsed_ms: u64,
+    /// What the operation returned, once it succeeded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub result: Option<Value>,
+    /// Why the operation failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorBody>,
+}
+
+/// Jobs known to the server
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobListResponse {
+    /// Jobs without their results, oldest first
+    pub jobs: Vec<JobResponse>,
+}
+
+enum JobState {
+    Running,
+    Succeeded(Value),
+    Failed(ErrorBody),
+    Cancelled,
+}
+
+struct Job {
+    op: &'static str,
+    started: Instant,
+    finished: Option<Instant>,
+    state: JobState,
+    task: Option<AbortHandle>,
+}
+
+impl Job {
+    fn response(&self, id: &str, with_result: bool) -> JobResponse {
+        let (status, result, error) = match &self.state {
+            JobState::Running => ("running", None, None),
+            JobState::Succeeded(value) => ("succeeded", with_result.then(|| value.clone()), None),
+            JobState::Failed(error) => ("failed", None, Some(error.clone())),
+            JobState::Cancelled => ("cancelled", None, None),
+        };
+        let elapsed = self.finished.unwrap_or_else(Instant::now).duration_since(self.started);
+        JobResponse {
+            id: id.to_string(),
+            op: self.op.to_string(),
+            status: status.to_string(),
+            elapsed_ms: elapsed.as_millis() as u64,
+            result,
+            error,
+        }
+    }
+}
+
+/// Jobs started by clients, keyed by opaque ids
+pub struct JobStore {
+    jobs: Mutex<HashMap<String, Job>>,
+    counter: AtomicU64,
+    max_jobs: usize,
+    max_running: usize,
+}
+
+impl Default for JobStore {
+    fn default() -> Self {
+        Self::with_limits(MAX_JOBS, MAX_RUNNING_JOBS)
+    }
+}
+
+impl JobStore {
+    /// Store keeping at most `max_jobs` jobs, of which `max_running` run
+    pub fn with_limits(max_jobs: usize, max_running: usize) -> Self {
+        Self {
+            jobs: Mutex::default(),
+            counter: AtomicU64::new(0),
+            max_jobs,
+            max_running,
+        }
+    }
+
+    /// Run `work` in the background as a job, returning its id. Fails with
+    /// `429 Too Many Requests` when the store is full
+    pub fn spawn<F>(self: &Arc<Self>, op: &'static str, work: F) -> Result<String, ApiError>
+    where
+        F: Future<Output = Result<Value, ApiError>> + Send + 'static,
+    {
+        let id = new_token(&self.counter);
+        self.prune(Instant::now());
+        {
+            let mut jobs = self.lock();
+            let running = jobs.values().filter(|job| matches!(job.state, JobState::Running)).count();
+            if running >= self.max_running {
+                return Err(too_many_jobs(format!("{} jobs are already running", running)));
+            }
+            if jobs.len() >= self.max_jobs {
+                return Err(too_many_jobs(format!(
+                    "{} jobs are kept; delete finished ones to start more",
+                    jobs.len()
+                )));
+            }
+            jobs.insert(
+                id.clone(),
+                Job {
+                    op,
+                    started: Instant::now(),
+                    finished: None,
+                    state: JobState::Running,
+                    task: None,
+                },
+            );
+        }
+        let store = self.clone();
+        let job_id = id.clone();
+        let task = tokio::spawn(async move {
+            let outcome = work.await;
+            store.finish(&job_id, outcome);
+        });
+        if let Some(job) = self.lock().get_mut(&id) {
+            job.task = Some(task.abort_handle());
+        }
+        Ok(id)
+    }
+
+    // Forget jobs finished more than `RETENTION` before `now`
+    fn prune(&self, now: Instant) {
+        self.lock()
+            .retain(|_, job| job.finished.is_none_or(|finished| now.duration_since(finished) < RETENTION));
+    }
+
+    fn finish(&self, id: &str, outcome: Result<Value, ApiError>) {
+        let mut jobs = self.lock();
+        // Cancelled jobs keep their state
+        let Some(job) = jobs.get_mut(id).filter(|job| matches!(job.state, JobState::Running)) else {
+            return;
+        };
+        job.finished = Some(Instant::now());
+        job.task = None;
+        job.state = match outcome {
+            Ok(value) => JobState::Succeeded(value),
+            Err(e) => JobState::Failed(e.body),
+        };
+        info!(id, op = job.op, elapsed_ms = job.started.elapsed().as_millis(), "Job finished");
+    }
+
+    /// State of the job, with its result if `with_result`
+    pub fn get(&self, id: &str, with_result: bool) -> Option<JobResponse> {
+        self.lock().get(id).map(|job| job.response(id, with_result))
+    }
+
+    /// All jobs, without results, oldest first
+    pub fn list(&self) -> Vec<JobResponse> {
+        let jobs = self.lock();
+        let mut listed: Vec<(&String, &Job)> = jobs.iter().collect();
+        listed.sort_by_key(|(_, job)| job.started);
+        listed.into_iter().map(|(id, job)| job.response(id, false)).collect()
+    }
+
+    /// Cancel a running job, or forget a finished one. `None` for unknown
+    /// ids. Work already handed to a blocking thread, like a tree dump,
+    /// runs to its end, but its result is dropped
+    pub fn cancel(&self, id: &str) -> Option<JobResponse> {
+        let mut jobs = self.lock();
+        let job = jobs.get_mut(id)?;
+        if !matches!(job.state, JobState::Running) {
+            return jobs.remove(id).map(|job| job.response(id, false));
+        }
+        if let Some(task) = job.task.take() {
+            task.abort();
+        }
+        job.state = JobState::Cancelled;
+        job.finished = Some(Instant::now());
+        Some(job.response(id, false))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Job>> {
+        self.jobs.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn unknown_job(id: &str) -> ApiError {
+    ApiError {
+        status: StatusCode::NOT_FOUND,
+        body: ErrorBody {
+            error: format!("Unknown job '{}'", id),
+            details: None,
+        },
+    }
+}
+
+fn too_many_jobs(reason: String) -> ApiError {
+    ApiError {
+        status: StatusCode::TOO_MANY_REQUESTS,
+        body: ErrorBody {
+            error: format!("Too many jobs: {}", reason),
+            details: None,
+        },
+    }
+}
+
+fn to_value<T: Serialize>(value: T) -> Result<Value, ApiError> {
+    serde_json::to_value(value)
+        .map_err(|e| AutomationError::Internal(format!("Failed to serialize job result: {}", e)).into())
+}
+
+// Tree reads block the thread they run on for as long as they take
+async fn blocking<T, F>(work: F) -> Result<Value, ApiError>
+where
+    T: Serialize + Send + 'static,
+    F: FnOnce() -> Result<T, AutomationError> + Send + 'static,
+{
+    let value = tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| AutomationError::Internal(format!("Job thread failed: {}", e)))??;
+    to_value(value)
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/jobs",
+    tag = "jobs",
+    request_body = JobRequest,
+    responses(
+        (status = 202, description = "Job started; poll `/v1/jobs/{id}` for its result", body = JobResponse),
+        (status = 400, description = "Invalid workflow", body = ErrorBody),
+        (status = 429, description = "Too many jobs running or kept", body = ErrorBody),
+    )
+)]
+pub(crate) async fn start(
+    State(state): State<AppState>,
+    Json(request): Json<JobRequest>,
+) -> Result<(StatusCode, Json<JobResponse>), ApiError> {
+    let op = request.op();
+    let desktop = state.desktop.clone();
+    let id = match request {
+        JobRequest::WindowTree { pid, title } => state.jobs.spawn(
+            op,
+            blocking(move || desktop.get_window_tree(pid, title.as_deref(), None)),
+        )?,
+        JobRequest::AllWindowTrees => state.jobs.spawn(
+            op,
+            blocking(move || {
+                let trees = desktop.get_all_window_trees(None)?;
+                Ok(trees
+                    .into_iter()
+                    .map(|(window, tree)| serde_json::json!({"window": window, "tree": tree}))
+                    .collect::<Vec<_>>())
+            }),
+        )?,
+        JobRequest::Ocr => state.jobs.spawn(op, async move {
+            let screenshot = desktop.capture_screen().await?;
+            let text = desktop.ocr_screenshot(&screenshot).await?;
+            to_value(serde_json::json!({"text": text}))
+        })?,
+        JobRequest::Workflow { workflow, variables } => {
+            // Rejected now rather than as a failed job
+            workflow.validate()?;
+            state.jobs.spawn(op, async move {
+                let report = workflow.run(&desktop, variables).await?;
+                to_value(report)
+            })?
+        }
+    };
+    info!(id, op, "Job started");
+    let job = state.jobs.get(&id, false).ok_or_else(|| unknown_job(&id))?;
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/jobs",
+    tag = "jobs",
+    responses((status = 200, description = "Jobs without their results", body = JobListResponse))
+)]
+pub(crate) async fn list(State(state): State<AppState>) -> Json<JobListResponse> {
+    Json(JobListResponse { jobs: state.jobs.list() })
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/jobs/{id}",
+    tag = "jobs",
+    params(("id" = String, Path, description = "Job id returned when the job started")),
+    responses(
+        (status = 200, description = "Status of the job, with its result or error once finished", body = JobResponse),
+        (status = 404, description = "Unknown job, or finished more than an hour ago", body = ErrorBody),
+    )
+)]
+pub(crate) async fn status(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<JobResponse>, ApiError> {
+    state.jobs.get(&id, true).map(Json).ok_or_else(|| unknown_job(&id))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/jobs/{id}",
+    tag = "jobs",
+    params(("id" = String, Path, description = "Job to cancel or forget")),
+    responses(
+        (status = 200, description = "Running job cancelled, or finished job forgotten", body = JobResponse),
+        (status = 404, description = "Unknown job", body = ErrorBody),
+    )
+)]
+pub(crate) async fn cancel(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<JobResponse>, ApiError> {
+    let job = state.jobs.cancel(&id).ok_or_else(|| unknown_job(&id))?;
+    info!(id, status = job.status, "Job cancelled");
+    Ok(Json(job))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn finished(store: &JobStore, id: &str) -> JobResponse {
+        for _ in 0..100 {
+            match store.get(id, true) {
+                Some(job) if job.status != "running" => return job,
+                _ => tokio::time::sleep(Duration::from_millis(10)).await,
+            }
+        }
+        panic!("job {} did not finish", id);
+    }
+
+    #[tokio::test]
+    async fn test_jobs_report_their_result_or_error() {
+        let store = Arc::new(JobStore::default());
+        let ok = store.spawn("ocr", async { Ok(serde_json::json!({"text": "hello"})) }).unwrap();
+        let failed = store
+            .spawn("window_tree", async {
+                Err(AutomationError::ElementNotFound("no window".to_string()).into())
+            })
+            .unwrap();
+        assert_ne!(ok, failed);
+
+        let job = finished(&store, &ok).await;
+        assert_eq!((job.op.as_str(), job.status.as_str()), ("ocr", "succeeded"));
+        assert_eq!(job.result.unwrap()["text"], "hello");
+        // Results are left out where they are not asked for
+        assert!(store.get(&ok, false).unwrap().result.is_none());
+
+        let job = finished(&store, &failed).await;
+        assert_eq!(job.status, "failed");
+        assert!(job.error.unwrap().error.contains("no window"));
+
+        let listed: Vec<String> = store.list().into_iter().map(|job| job.id).collect();
+        assert_eq!(listed, vec![ok, failed]);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stops_running_jobs_and_forgets_finished_ones() {
+        let store = Arc::new(JobStore::default());
+        let running = store.spawn("workflow", std::future::pending()).unwrap();
+        let job = store.cancel(&running).unwrap();
+        assert_eq!(job.status, "cancelled");
+        // Still reported as cancelled, then forgotten by a second cancel
+        assert_eq!(store.get(&running, true).unwrap().status, "cancelled");
+        assert!(store.cancel(&running).is_some());
+        assert!(store.get(&running, true).is_none());
+        assert!(store.cancel(&running).is_none());
+        assert!(store.cancel("unknown").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_finished_jobs_are_kept_for_the_retention_period() {
+        let store = Arc::new(JobStore::default());
+        let done = store.spawn("ocr", async { Ok(Value::Null) }).unwrap();
+        finished(&store, &done).await;
+        let running = store.spawn("workflow", std::future::pending()).unwrap();
+
+        store.prune(Instant::now() + RETENTION / 2);
+        assert!(store.get(&done, false).is_some());
+        store.prune(Instant::now() + RETENTION);
+        assert!(store.get(&done, false).is_none());
+        assert!(store.get(&running, false).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_new_jobs_are_refused_once_the_store_is_full() {
+        let store = Arc::new(JobStore::with_limits(3, 2));
+        store.spawn("workflow", std::future::pending()).unwrap();
+        store.spawn("workflow", std::future::pending()).unwrap();
+        let error = store.spawn("ocr", async { Ok(Value::Null) }).unwrap_err();
+        assert_eq!(error.status, StatusCode::TOO_MANY_REQUESTS);
+        assert!(error.body.error.contains("running"), "{}", error.body.error);
+
+        // Cancelled jobs stop running but are still kept
+        let first = store.list()[0].id.clone();
+        store.cancel(&first).unwrap();
+        let done = store.spawn("ocr", async { Ok(Value::Null) }).unwrap();
+        finished(&store, &done).await;
+        let error = store.spawn("ocr", async { Ok(Value::Null) }).unwrap_err();
+        assert_eq!(error.status, StatusCode::TOO_MANY_REQUESTS);
+
+        // Deleting a finished job makes room again
+        store.cancel(&done).unwrap();
+        assert!(store.spawn("ocr", async { Ok(Value::Null) }).is_ok());
+    }
+}
@@ -0,0 +1,178 @@
+//! HTTP and WebSocket API for terminator
+//!
+//! Every route is versioned under `/v1` and described by an OpenAPI 3
+//! document generated from the request and response types, served at
+//! `/v1/openapi.json` and browsable at `/swagger-ui`. Clients in any language
+//! can be generated from it, e.g. with `openapi-generator`.
+//!
+//! Elements are found once and then addressed by an opaque handle:
+//!
+//! - `POST /v1/elements/first` with `{"selector": "name:Save", "timeout_ms": 5000}`
+//!   returns `{"handle": "...", "role": "...", "name": "..."}`. With
+//!   `"within": "<handle>"` the selector is resolved inside that element
+//!   rather than from the desktop root, so nested lookups don't repeat the
+//!   whole chain
+//! - `POST /v1/elements/click`, `POST /v1/elements/type_text` (with `text`) and
+//!   `POST /v1/elements/press_key` (with `key`) take `{"handle": "..."}`
+//! - `POST /v1/batch` runs several find, click, type and get-text operations
+//!   by selector in one request, returning a handle per element it acted on;
+//!   it takes a `within` handle as well
+//! - `DELETE /v1/sessions/{session}` releases all handles of a session
+//!
+//! Operations that can take minutes run as jobs, so no request stays open
+//! for their duration: `POST /v1/jobs` with `{"op": "window_tree", "pid": 1234}`,
+//! `{"op": "all_window_trees"}`, `{"op": "ocr"}` or
+//! `{"op": "workflow", "workflow": {...}}` answers `202` with the job's `id`.
+//! `GET /v1/jobs/{id}` reports its `status` (`running`, `succeeded`, `failed`
+//! or `cancelled`) with the `result` or `error` once finished, and
+//! `DELETE /v1/jobs/{id}` cancels it.
+//!
+//! Every request may carry a `session` name (`"default"` otherwise); handles
+//! only resolve within the session that created them. A handle whose control
+//! the application re-created, e.g. a row of a virtualized list, follows it
+//! to the new control. A handle whose element has gone away for good (window
+//! closed) answers `410 Gone` and is dropped, so clients know to look the
+//! element up again. Errors have a JSON
+//! body with an `error` message and, for automation errors, `details`.
+//!
+//! `GET /v1/live?fps=2&quality=60` upgrades to a WebSocket pushing JSON
+//! messages tagged by `type`:
+//!
+//! - `screenshot`: a base64 JPEG of the primary screen, `fps` times per second
+//! - `ui_event`: focus, structure and property changes (Windows only)
+//! - `progress`: status of actions requested by the client
+//!
+//! Clients start actions by sending e.g.
+//! `{"type": "action", "id": "1", "action": "click", "selector": "name:Save"}`.
+//! Supported actions are `click`, `type_text` (with `text`) and `press_key`
+//! (with `key`). Instead of a `selector`, an action may name a `handle`.
+//!
+//! Besides TCP, the server can listen on a Unix socket or Windows named pipe
+//! for hosts on the same machine, see [`ipc`].
+
+use std::sync::Arc;
+
+use axum::routing::{delete, get, post};
+use axum::Router;
+use terminator::Desktop;
+use tower_http::cors::CorsLayer;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+mod api;
+mod error;
+mod handles;
+pub mod ipc;
+mod jobs;
+mod live_view;
+
+pub use api::{
+    ActionResponse, BatchItem, BatchRequest, BatchResponse, ClickRequest, FirstRequest, FirstResponse,
+    PressKeyRequest, TypeTextRequest,
+};
+pub use error::{ApiError, ErrorBody};
+pub use handles::{HandleError, HandleStore};
+pub use jobs::{JobListResponse, JobRequest, JobResponse, JobStore};
+
+/// Path the OpenAPI document is served at
+pub const OPENAPI_PATH: &str = "/v1/openapi.json";
+
+/// The OpenAPI document of the server
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Terminator API", description = "Desktop automation over HTTP"),
+    paths(
+        api::first,
+        api::click,
+        api::type_text,
+        api::press_key,
+        api::batch,
+        api::release_session,
+        jobs::start,
+        jobs::list,
+        jobs::status,
+        jobs::cancel,
+        live_view::live,
+    ),
+    components(schemas(
+        FirstRequest,
+        FirstResponse,
+        ClickRequest,
+        TypeTextRequest,
+        PressKeyRequest,
+        ActionResponse,
+        BatchRequest,
+        BatchItem,
+        BatchResponse,
+        JobRequest,
+        JobResponse,
+        JobListResponse,
+        ErrorBody,
+    )),
+    tags(
+        (name = "elements", description = "Find elements and act on them through handles"),
+        (name = "sessions", description = "Groups of handles released together"),
+        (name = "jobs", description = "Long-running operations polled for their result"),
+        (name = "live", description = "WebSocket streaming screenshots, UI events and action progress"),
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+pub(crate) struct AppState {
+    desktop: Arc<Desktop>,
+    handles: Arc<HandleStore>,
+    jobs: Arc<JobStore>,
+}
+
+/// All routes, the OpenAPI document and Swagger UI, driving `desktop`
+pub fn router(desktop: Arc<Desktop>) -> Router {
+    let state = AppState {
+        desktop,
+        handles: Arc::new(HandleStore::default()),
+        jobs: Arc::new(JobStore::default()),
+    };
+
+    let v1 = Router::new()
+        .route("/v1/live", get(live_view::live))
+        .route("/v1/elements/first", post(api::first))
+        .route("/v1/elements/click", post(api::click))
+        .route("/v1/elements/type_text", post(api::type_text))
+        .route("/v1/elements/press_key", post(api::press_key))
+        .route("/v1/batch", post(api::batch))
+        .route("/v1/sessions/{session}", delete(api::release_session))
+        .route("/v1/jobs", post(jobs::start).get(jobs::list))
+        .route("/v1/jobs/{id}", get(jobs::status).delete(jobs::cancel))
+        .with_state(state);
+
+    Router::new()
+        .merge(SwaggerUi::new("/swagger-ui").url(OPENAPI_PATH, ApiDoc::openapi()))
+        .merge(v1)
+        .layer(CorsLayer::permissive())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_document_lists_every_versioned_route() {
+        let doc = ApiDoc::openapi();
+        let paths: Vec<&str> = doc.paths.paths.keys().map(String::as_str).collect();
+        for route in [
+            "/v1/live",
+            "/v1/elements/first",
+            "/v1/elements/click",
+            "/v1/elements/type_text",
+            "/v1/elements/press_key",
+            "/v1/batch",
+            "/v1/sessions/{session}",
+            "/v1/jobs",
+            "/v1/jobs/{id}",
+        ] {
+            assert!(paths.contains(&route), "{} missing from {:?}", route, paths);
+        }
+        assert!(paths.iter().all(|path| path.starts_with("/v1/")));
+        assert!(serde_json::to_string(&doc).is_ok());
+    }
+}
@@ -0,0 +1,225 @@
+//! WebSocket streaming the desktop to web frontends
+
+use std::time::Duration;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::response::Response;
+use base64::{engine::general_purpose, Engine as _};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use terminator::{AutomationError, Desktop, ImageFormat, UIEvent, UIEventKind};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+use utoipa::IntoParams;
+
+use crate::api::{perform, ACTION_TIMEOUT};
+use crate::handles::DEFAULT_SESSION;
+use crate::AppState;
+
+const MAX_FPS: f64 = 30.0;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct StreamParams {
+    /// Screenshots per second, 0 disables screenshots
+    #[serde(default = "default_fps")]
+    #[param(default = 2.0, maximum = 30.0)]
+    fps: f64,
+    /// JPEG quality from 1 to 100
+    #[serde(default = "default_quality")]
+    #[param(default = 60, minimum = 1, maximum = 100)]
+    quality: u8,
+}
+
+fn default_fps() -> f64 {
+    2.0
+}
+
+fn default_quality() -> u8 {
+    60
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    Action {
+        id: String,
+        action: String,
+        selector: Option<String>,
+        handle: Option<String>,
+        session: Option<String>,
+        text: Option<String>,
+        key: Option<String>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Screenshot {
+        width: u32,
+        height: u32,
+        /// Base64-encoded JPEG
+        data: String,
+    },
+    UiEvent {
+        kind: UIEventKind,
+        role: Option<String>,
+        name: Option<String>,
+        detail: Option<String>,
+        value: Option<String>,
+    },
+    Progress {
+        id: String,
+        status: &'static str,
+        message: Option<String>,
+    },
+}
+
+impl From<UIEvent> for ServerMessage {
+    fn from(event: UIEvent) -> Self {
+        ServerMessage::UiEvent {
+            kind: event.kind,
+            role: event.element.as_ref().map(|e| e.role()),
+            name: event.element.as_ref().and_then(|e| e.name()),
+            detail: event.detail,
+            value: event.value,
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/live",
+    tag = "live",
+    params(StreamParams),
+    responses((status = 101, description = "Switching to the WebSocket protocol"))
+)]
+pub(crate) async fn live(
+    ws: WebSocketUpgrade,
+    Query(params): Query<StreamParams>,
+    State(state): State<AppState>,
+) -> Response {
+    ws.on_upgrade(move |socket| stream_desktop(socket, state, params))
+}
+
+async fn stream_desktop(socket: WebSocket, state: AppState, params: StreamParams) {
+    let desktop = state.desktop.clone();
+    let (mut sender, mut receiver) = socket.split();
+    // UI events and action progress arrive from other threads and tasks
+    let (tx, mut rx) = mpsc::unbounded_channel::<ServerMessage>();
+
+    let event_tx = tx.clone();
+    let _subscription = match desktop.on_ui_event(&UIEventKind::ALL, move |event| {
+        let _ = event_tx.send(event.into());
+    }) {
+        Ok(subscription) => Some(subscription),
+        Err(e) => {
+            warn!("UI events unavailable: {}", e);
+            None
+        }
+    };
+
+    let quality = params.quality.clamp(1, 100);
+    let mut ticker = (params.fps > 0.0).then(|| {
+        let period = Duration::from_secs_f64(1.0 / params.fps.min(MAX_FPS));
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        interval
+    });
+
+    info!(fps = params.fps, quality, "Live view client connected");
+
+    loop {
+        let outgoing = tokio::select! {
+            _ = next_tick(&mut ticker) => {
+                match capture_jpeg(&desktop, quality).await {
+                    Ok(message) => message,
+                    Err(e) => {
+                        warn!("Screenshot failed: {}", e);
+                        continue;
+                    }
+                }
+            }
+            Some(message) = rx.recv() => message,
+            incoming = receiver.next() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    match serde_json::from_str::<ClientMessage>(&text) {
+                        Ok(message) => spawn_action(state.clone(), message, tx.clone()),
+                        Err(e) => warn!("Ignoring invalid client message: {}", e),
+                    }
+                    continue;
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+
+        let json = match serde_json::to_string(&outgoing) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!("Failed to serialize message: {}", e);
+                continue;
+            }
+        };
+        if sender.send(Message::Text(json.into())).await.is_err() {
+            break;
+        }
+    }
+
+    info!("Live view client disconnected");
+}
+
+// Wait for the next screenshot, or forever when screenshots are disabled
+async fn next_tick(ticker: &mut Option<tokio::time::Interval>) {
+    match ticker {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+async fn capture_jpeg(desktop: &Desktop, quality: u8) -> Result<ServerMessage, AutomationError> {
+    let jpeg = desktop.capture_screen_as(ImageFormat::Jpeg { quality }).await?;
+    Ok(ServerMessage::Screenshot {
+        width: jpeg.width,
+        height: jpeg.height,
+        data: general_purpose::STANDARD.encode(jpeg.data),
+    })
+}
+
+fn spawn_action(state: AppState, message: ClientMessage, tx: mpsc::UnboundedSender<ServerMessage>) {
+    let ClientMessage::Action { id, action, selector, handle, session, text, key } = message;
+    tokio::spawn(async move {
+        let progress = |status: &'static str, message: Option<String>| {
+            let _ = tx.send(ServerMessage::Progress { id: id.clone(), status, message });
+        };
+
+        progress("started", None);
+        let element = match (handle, selector) {
+            (Some(handle), _) => state
+                .handles
+                .resolve(state.desktop.clone(), session.as_deref().unwrap_or(DEFAULT_SESSION), &handle)
+                .await
+                .map_err(|e| e.to_string()),
+            (None, Some(selector)) => state
+                .desktop
+                .locator(selector.as_str())
+                .first(Some(ACTION_TIMEOUT))
+                .await
+                .map_err(|e| e.to_string()),
+            (None, None) => Err("Action needs a selector or a handle".to_string()),
+        };
+        let element = match element {
+            Ok(element) => element,
+            Err(e) => return progress("failed", Some(e)),
+        };
+        progress("element_found", None);
+
+        match perform(&element, &action, text.as_deref(), key.as_deref()) {
+            Ok(()) => progress("completed", None),
+            Err(e) => progress("failed", Some(e.to_string())),
+        }
+    });
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use terminator::Desktop;
+use terminator_server::{ipc, router, ApiDoc, OPENAPI_PATH};
+use tracing::info;
+use tracing_subscriber::EnvFilter;
+use utoipa::OpenApi;
+
+const DEFAULT_BIND: &str = "127.0.0.1:3000";
+
+enum Transport {
+    Tcp,
+    /// A Unix socket or Windows named pipe
+    Ipc,
+}
+
+struct Options {
+    transport: Transport,
+    bind: String,
+    ipc_path: String,
+    print_openapi: bool,
+}
+
+// Parse `--transport tcp|ipc`, `--bind <addr>`, `--ipc-path <path>` and
+// `--print-openapi`
+fn parse_options() -> Result<Options> {
+    let mut options = Options {
+        transport: Transport::Tcp,
+        bind: DEFAULT_BIND.to_string(),
+        ipc_path: ipc::default_path(),
+        print_openapi: false,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--transport" => match args.next().as_deref() {
+                Some("tcp") => options.transport = Transport::Tcp,
+                Some("ipc") => options.transport = Transport::Ipc,
+                Some(other) => bail!("Unknown transport '{}', expected tcp or ipc", other),
+                None => bail!("--transport requires a value (tcp or ipc)"),
+            },
+            "--ipc-path" => match args.next() {
+                Some(value) => options.ipc_path = value,
+                None => bail!("--ipc-path requires a value"),
+            },
+            "--bind" => match args.next() {
+                Some(value) => options.bind = value,
+                None => bail!("--bind requires an address, e.g. {}", DEFAULT_BIND),
+            },
+            "--print-openapi" => options.print_openapi = true,
+            other => bail!("Unknown argument: {}", other),
+        }
+    }
+    Ok(options)
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let options = parse_options()?;
+
+    // For generating clients without starting a server
+    if options.print_openapi {
+        println!("{}", ApiDoc::openapi().to_pretty_json()?);
+        return Ok(());
+    }
+
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .init();
+
+    let desktop = Arc::new(Desktop::new(false, false)?);
+    match options.transport {
+        Transport::Tcp => {
+            let listener = tokio::net::TcpListener::bind(&options.bind).await?;
+            info!("Terminator server listening on http://{}", options.bind);
+            info!("OpenAPI document at http://{}{}", options.bind, OPENAPI_PATH);
+            axum::serve(listener, router(desktop)).await?;
+        }
+        Transport::Ipc => ipc::serve(&options.ipc_path, router(desktop)).await?,
+    }
+    Ok(())
+}
@@ -0,0 +1,245 @@
+//! Narration recorded alongside the events
+//!
+//! With [`WorkflowRecorderConfig::audio`](crate::WorkflowRecorderConfig::audio)
+//! set, the recorder captures the default microphone (or, in loopback mode,
+//! whatever the default speakers play) into a WAV file next to the
+//! recording while events are recorded. The workflow references the file as
+//! an [`AudioTrack`], whose `start_time` is on the same clock as the event
+//! timestamps, so the spoken explanation for an event can be found with
+//! [`AudioTrack::offset_of`]. While recording is paused the track gets
+//! silence, keeping it in sync with the events. Capture is Windows only
+//! (WASAPI).
+
+use serde::{Deserialize, Serialize};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+
+#[cfg(target_os = "windows")]
+mod wasapi;
+
+#[cfg(target_os = "windows")]
+pub(crate) use self::wasapi::AudioCapture;
+
+/// Samples are stored as 16-bit PCM whatever the device delivers
+const BITS_PER_SAMPLE: u16 = 16;
+/// Size of the RIFF header written before the samples
+const WAV_HEADER_LEN: u32 = 44;
+
+/// Device the narration is captured from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioSource {
+    /// The default microphone
+    Microphone,
+    /// What the default speakers play, e.g. a narration recorded by another
+    /// application
+    Loopback,
+}
+
+/// Audio capture settings
+#[derive(Debug, Clone)]
+pub struct AudioConfig {
+    pub source: AudioSource,
+    /// WAV file the track is written to, replaced if it exists
+    pub path: PathBuf,
+}
+
+impl AudioConfig {
+    /// Capture the default microphone into `path`
+    pub fn microphone(path: impl Into<PathBuf>) -> Self {
+        Self {
+            source: AudioSource::Microphone,
+            path: path.into(),
+        }
+    }
+
+    /// Capture what the default speakers play into `path`
+    pub fn loopback(path: impl Into<PathBuf>) -> Self {
+        Self {
+            source: AudioSource::Loopback,
+            path: path.into(),
+        }
+    }
+}
+
+/// An audio file recorded with a workflow
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioTrack {
+    /// The WAV file
+    pub path: String,
+
+    pub source: AudioSource,
+
+    /// When the first sample was captured (milliseconds since epoch)
+    pub start_time: u64,
+
+    /// Length of the track (milliseconds)
+    pub duration_ms: u64,
+
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl AudioTrack {
+    /// Position in the track of an event recorded at `timestamp`
+    /// (milliseconds since epoch), or None if the track doesn't cover it
+    pub fn offset_of(&self, timestamp: u64) -> Option<u64> {
+        let offset = timestamp.checked_sub(self.start_time)?;
+        (offset <= self.duration_ms).then_some(offset)
+    }
+}
+
+/// Encoding of the samples a capture device delivers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SampleFormat {
+    Float32,
+    Pcm16,
+    Pcm24,
+    Pcm32,
+}
+
+impl SampleFormat {
+    pub(crate) fn bytes_per_sample(self) -> usize {
+        match self {
+            SampleFormat::Pcm16 => 2,
+            SampleFormat::Pcm24 => 3,
+            SampleFormat::Float32 | SampleFormat::Pcm32 => 4,
+        }
+    }
+}
+
+/// Little-endian samples in `format` as 16-bit PCM. A trailing partial
+/// sample is dropped
+pub(crate) fn to_pcm16(data: &[u8], format: SampleFormat) -> Vec<i16> {
+    data.chunks_exact(format.bytes_per_sample())
+        .map(|s| match format {
+            SampleFormat::Float32 => {
+                let value = f32::from_le_bytes([s[0], s[1], s[2], s[3]]);
+                (value.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+            }
+            SampleFormat::Pcm16 => i16::from_le_bytes([s[0], s[1]]),
+            // The high bytes of wider samples are the 16 most significant bits
+            SampleFormat::Pcm24 => i16::from_le_bytes([s[1], s[2]]),
+            SampleFormat::Pcm32 => i16::from_le_bytes([s[2], s[3]]),
+        })
+        .collect()
+}
+
+/// Writes 16-bit PCM samples as a WAV file. The sizes in the header are
+/// only filled in by `finish`
+pub(crate) struct WavWriter<W: Write + Seek> {
+    out: W,
+    sample_rate: u32,
+    channels: u16,
+    data_len: u32,
+}
+
+impl<W: Write + Seek> WavWriter<W> {
+    pub(crate) fn new(mut out: W, sample_rate: u32, channels: u16) -> io::Result<Self> {
+        let block_align = channels * BITS_PER_SAMPLE / 8;
+        out.write_all(b"RIFF")?;
+        out.write_all(&0u32.to_le_bytes())?;
+        out.write_all(b"WAVEfmt ")?;
+        out.write_all(&16u32.to_le_bytes())?;
+        // PCM
+        out.write_all(&1u16.to_le_bytes())?;
+        out.write_all(&channels.to_le_bytes())?;
+        out.write_all(&sample_rate.to_le_bytes())?;
+        out.write_all(&(sample_rate * block_align as u32).to_le_bytes())?;
+        out.write_all(&block_align.to_le_bytes())?;
+        out.write_all(&BITS_PER_SAMPLE.to_le_bytes())?;
+        out.write_all(b"data")?;
+        out.write_all(&0u32.to_le_bytes())?;
+        Ok(Self {
+            out,
+            sample_rate,
+            channels,
+            data_len: 0,
+        })
+    }
+
+    pub(crate) fn write_samples(&mut self, samples: &[i16]) -> io::Result<()> {
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        self.out.write_all(&bytes)?;
+        self.data_len = self.data_len.saturating_add(bytes.len() as u32);
+        Ok(())
+    }
+
+    /// `frames` of silence, one zero sample per channel each
+    pub(crate) fn write_silence(&mut self, frames: usize) -> io::Result<()> {
+        self.write_samples(&vec![0; frames * self.channels as usize])
+    }
+
+    /// Length of what was written so far (milliseconds)
+    pub(crate) fn duration_ms(&self) -> u64 {
+        let frame_len = (self.channels * BITS_PER_SAMPLE / 8) as u64;
+        if frame_len == 0 || self.sample_rate == 0 {
+            return 0;
+        }
+        self.data_len as u64 / frame_len * 1000 / self.sample_rate as u64
+    }
+
+    /// Fill in the sizes in the header and flush
+    pub(crate) fn finish(mut self) -> io::Result<W> {
+        self.out.seek(SeekFrom::Start(4))?;
+        self.out.write_all(&(WAV_HEADER_LEN - 8 + self.data_len).to_le_bytes())?;
+        self.out.seek(SeekFrom::Start(40))?;
+        self.out.write_all(&self.data_len.to_le_bytes())?;
+        self.out.seek(SeekFrom::End(0))?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_wav_writer_fills_in_sizes() {
+        let mut writer = WavWriter::new(Cursor::new(Vec::new()), 1_000, 2).unwrap();
+        writer.write_samples(&[1, -1, 2, -2]).unwrap();
+        writer.write_silence(3).unwrap();
+        // 5 frames of 4 bytes at 1 kHz
+        assert_eq!(writer.duration_ms(), 5);
+        let bytes = writer.finish().unwrap().into_inner();
+
+        assert_eq!(bytes.len(), 44 + 20);
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes(bytes[4..8].try_into().unwrap()), 36 + 20);
+        assert_eq!(u16::from_le_bytes(bytes[22..24].try_into().unwrap()), 2);
+        assert_eq!(u32::from_le_bytes(bytes[24..28].try_into().unwrap()), 1_000);
+        assert_eq!(u32::from_le_bytes(bytes[28..32].try_into().unwrap()), 4_000);
+        assert_eq!(&bytes[36..40], b"data");
+        assert_eq!(u32::from_le_bytes(bytes[40..44].try_into().unwrap()), 20);
+        assert_eq!(i16::from_le_bytes([bytes[46], bytes[47]]), -1);
+        assert!(bytes[52..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_to_pcm16_converts_device_formats() {
+        let floats: Vec<u8> = [0.0f32, 1.0, -1.0, 2.0].iter().flat_map(|f| f.to_le_bytes()).collect();
+        assert_eq!(to_pcm16(&floats, SampleFormat::Float32), vec![0, i16::MAX, -i16::MAX, i16::MAX]);
+
+        assert_eq!(to_pcm16(&[0x34, 0x12, 0xff], SampleFormat::Pcm16), vec![0x1234]);
+        assert_eq!(to_pcm16(&[0x00, 0x34, 0x12], SampleFormat::Pcm24), vec![0x1234]);
+        assert_eq!(to_pcm16(&[0x00, 0x00, 0x00, 0x80], SampleFormat::Pcm32), vec![i16::MIN]);
+    }
+
+    #[test]
+    fn test_offset_of_event_in_track() {
+        let track = AudioTrack {
+            path: "narration.wav".to_string(),
+            source: AudioSource::Microphone,
+            start_time: 10_000,
+            duration_ms: 5_000,
+            sample_rate: 48_000,
+            channels: 1,
+        };
+        assert_eq!(track.offset_of(9_999), None);
+        assert_eq!(track.offset_of(10_000), Some(0));
+        assert_eq!(track.offset_of(12_500), Some(2_500));
+        assert_eq!(track.offset_of(15_001), None);
+    }
+}
@@ -0,0 +1,214 @@
+//! Audio capture through WASAPI in shared mode
+
+use super::{to_pcm16, AudioConfig, AudioSource, AudioTrack, SampleFormat, WavWriter};
+use crate::{Result, WorkflowRecorderError};
+use std::fs::File;
+use std::io::BufWriter;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, info};
+use windows::Win32::Media::Audio::{
+    eCapture, eConsole, eRender, IAudioCaptureClient, IAudioClient, IMMDeviceEnumerator,
+    MMDeviceEnumerator, AUDCLNT_BUFFERFLAGS_SILENT, AUDCLNT_SHAREMODE_SHARED,
+    AUDCLNT_STREAMFLAGS_LOOPBACK, WAVEFORMATEX, WAVEFORMATEXTENSIBLE,
+};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoTaskMemFree, CoUninitialize, CLSCTX_ALL,
+    COINIT_MULTITHREADED,
+};
+
+/// Size of the shared-mode buffer, in 100 ns units
+const BUFFER_DURATION: i64 = 10_000_000;
+/// How often the capture buffer is drained
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+const WAVE_FORMAT_PCM: u16 = 1;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// A running capture, written to its WAV file by a thread of its own
+pub(crate) struct AudioCapture {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<Result<AudioTrack>>,
+}
+
+impl AudioCapture {
+    /// Open the device and start capturing. Fails if the device can't be
+    /// opened, rather than recording without audio
+    pub(crate) fn start(config: AudioConfig, paused: Arc<AtomicBool>) -> Result<Self> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let (started_tx, started_rx) = mpsc::channel();
+        let thread = {
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || {
+                unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) }.ok().map_err(windows_error)?;
+                let result = capture(&config, &stop, &paused, &started_tx);
+                unsafe { CoUninitialize() };
+                result
+            })
+        };
+        match started_rx.recv() {
+            Ok(()) => Ok(Self { stop, thread }),
+            // The thread ended before starting, with the reason as its result
+            Err(_) => Err(match thread.join() {
+                Ok(Err(e)) => WorkflowRecorderError::InitializationError(format!(
+                    "Failed to start audio capture: {}",
+                    e
+                )),
+                _ => WorkflowRecorderError::InitializationError(
+                    "Audio capture stopped unexpectedly".to_string(),
+                ),
+            }),
+        }
+    }
+
+    /// Stop capturing and finish the WAV file
+    pub(crate) fn stop(self) -> Result<AudioTrack> {
+        self.stop.store(true, Ordering::SeqCst);
+        self.thread.join().map_err(|_| {
+            WorkflowRecorderError::RecordingError("Audio capture thread panicked".to_string())
+        })?
+    }
+}
+
+fn capture(
+    config: &AudioConfig,
+    stop: &AtomicBool,
+    paused: &AtomicBool,
+    started: &mpsc::Sender<()>,
+) -> Result<AudioTrack> {
+    let (client, capture_client, format) = unsafe { open(config.source) }?;
+    let file = File::create(&config.path).map_err(|e| {
+        WorkflowRecorderError::InitializationError(format!(
+            "Failed to create audio file {:?}: {}",
+            config.path, e
+        ))
+    })?;
+    let mut wav = WavWriter::new(BufWriter::new(file), format.sample_rate, format.channels)?;
+
+    unsafe { client.Start() }.map_err(windows_error)?;
+    let start_time = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    info!(
+        source = ?config.source,
+        sample_rate = format.sample_rate,
+        channels = format.channels,
+        "Started audio capture to {:?}",
+        config.path
+    );
+    let _ = started.send(());
+
+    let frame_len = format.sample.bytes_per_sample() * format.channels as usize;
+    let result = (|| -> Result<()> {
+        while !stop.load(Ordering::SeqCst) {
+            thread::sleep(POLL_INTERVAL);
+            while unsafe { capture_client.GetNextPacketSize() }.map_err(windows_error)? > 0 {
+                let mut data = std::ptr::null_mut();
+                let mut frames = 0u32;
+                let mut flags = 0u32;
+                unsafe { capture_client.GetBuffer(&mut data, &mut frames, &mut flags, None, None) }
+                    .map_err(windows_error)?;
+                // Paused stretches become silence so later events stay in sync
+                let silent = flags & AUDCLNT_BUFFERFLAGS_SILENT.0 as u32 != 0
+                    || paused.load(Ordering::SeqCst);
+                let written = if silent || data.is_null() {
+                    wav.write_silence(frames as usize)
+                } else {
+                    let bytes = unsafe { std::slice::from_raw_parts(data, frames as usize * frame_len) };
+                    wav.write_samples(&to_pcm16(bytes, format.sample))
+                };
+                unsafe { capture_client.ReleaseBuffer(frames) }.map_err(windows_error)?;
+                written?;
+            }
+        }
+        Ok(())
+    })();
+    let _ = unsafe { client.Stop() };
+
+    let duration_ms = wav.duration_ms();
+    wav.finish()?;
+    result?;
+    debug!(duration_ms, "Stopped audio capture");
+    Ok(AudioTrack {
+        path: config.path.to_string_lossy().into_owned(),
+        source: config.source,
+        start_time,
+        duration_ms,
+        sample_rate: format.sample_rate,
+        channels: format.channels,
+    })
+}
+
+/// The device's shared-mode mix format
+struct MixFormat {
+    sample: SampleFormat,
+    sample_rate: u32,
+    channels: u16,
+}
+
+unsafe fn open(source: AudioSource) -> Result<(IAudioClient, IAudioCaptureClient, MixFormat)> {
+    let enumerator: IMMDeviceEnumerator =
+        CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).map_err(windows_error)?;
+    // Loopback captures from the render endpoint
+    let (flow, flags) = match source {
+        AudioSource::Microphone => (eCapture, 0),
+        AudioSource::Loopback => (eRender, AUDCLNT_STREAMFLAGS_LOOPBACK),
+    };
+    let device = enumerator.GetDefaultAudioEndpoint(flow, eConsole).map_err(|e| {
+        WorkflowRecorderError::InitializationError(format!("No default {:?} device: {}", source, e))
+    })?;
+    let client: IAudioClient = device.Activate(CLSCTX_ALL, None).map_err(windows_error)?;
+
+    let format_ptr = client.GetMixFormat().map_err(windows_error)?;
+    let format = mix_format(&*format_ptr);
+    let initialized = client.Initialize(
+        AUDCLNT_SHAREMODE_SHARED,
+        flags,
+        BUFFER_DURATION,
+        0,
+        format_ptr,
+        None,
+    );
+    CoTaskMemFree(Some(format_ptr as *const _));
+    initialized.map_err(windows_error)?;
+    let format = format?;
+
+    let capture_client: IAudioCaptureClient = client.GetService().map_err(windows_error)?;
+    Ok((client, capture_client, format))
+}
+
+unsafe fn mix_format(format: &WAVEFORMATEX) -> Result<MixFormat> {
+    let tag = match format.wFormatTag {
+        // Extensible sub-formats carry the plain format tag in their first field
+        WAVE_FORMAT_EXTENSIBLE => {
+            let extensible = &*(format as *const WAVEFORMATEX as *const WAVEFORMATEXTENSIBLE);
+            extensible.SubFormat.data1 as u16
+        }
+        tag => tag,
+    };
+    let sample = match (tag, format.wBitsPerSample) {
+        (WAVE_FORMAT_IEEE_FLOAT, 32) => SampleFormat::Float32,
+        (WAVE_FORMAT_PCM, 16) => SampleFormat::Pcm16,
+        (WAVE_FORMAT_PCM, 24) => SampleFormat::Pcm24,
+        (WAVE_FORMAT_PCM, 32) => SampleFormat::Pcm32,
+        (tag, bits) => {
+            return Err(WorkflowRecorderError::InitializationError(format!(
+                "Unsupported audio format {:#x} with {} bits per sample",
+                tag, bits
+            )))
+        }
+    };
+    Ok(MixFormat {
+        sample,
+        sample_rate: format.nSamplesPerSec,
+        channels: format.nChannels,
+    })
+}
+
+fn windows_error(e: windows::core::Error) -> WorkflowRecorderError {
+    WorkflowRecorderError::WindowsError(e.to_string())
+}
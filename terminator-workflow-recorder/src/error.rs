@@ -11,6 +11,10 @@ pub enum WorkflowRecorderError {
     #[error("Failed to record event: {0}")]
     RecordingError(String),
 
+    /// Error when reading a recording written in an incompatible format
+    #[error("Unsupported recording schema version {0}")]
+    UnsupportedSchemaVersion(u32),
+
     /// Error when saving the recorded workflow
     #[error("Failed to save workflow: {0}")]
     SaveError(String),
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
-use terminator::UIElement;
+use terminator::{MonitorInfo, UIElement};
+use crate::audio::AudioTrack;
+use crate::screenshot::EventScreenshot;
 use std::time::SystemTime;
 use std::collections::HashSet;
 use std::sync::LazyLock;
@@ -54,7 +56,7 @@ pub struct Position {
 
 
 /// Represents a rectangle
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Rect {
     pub x: i32,
     pub y: i32,
@@ -114,6 +116,11 @@ pub struct KeyboardEvent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scan_code: Option<u32>,
     
+    /// Whether the key was typed into a password field and removed, see
+    /// [`crate::RedactionConfig`]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub redacted: bool,
+    
     /// Event metadata (UI element, application, etc.)
     pub metadata: EventMetadata,
 }
@@ -253,6 +260,89 @@ pub struct HotkeyEvent {
     pub metadata: EventMetadata,
 }
 
+/// What a marker in the event stream stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MarkerKind {
+    /// Start of a named step; events up to the next segment marker belong to it
+    Segment,
+    /// Recording was paused; nothing is captured until the next `Resumed`
+    Paused,
+    /// Recording was resumed after a pause
+    Resumed,
+}
+
+/// A boundary inserted into the recording by the user rather than captured from input
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarkerEvent {
+    /// The marker label (e.g., "Log in", "Export report")
+    pub label: String,
+    
+    /// The kind of marker
+    pub kind: MarkerKind,
+}
+
+/// How a window's geometry changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WindowChange {
+    Moved,
+    Resized,
+    Minimized,
+    Maximized,
+    /// Back to normal from minimized or maximized
+    Restored,
+}
+
+/// A top-level window settled at a new position, size or state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowGeometryEvent {
+    /// What changed
+    pub change: WindowChange,
+    
+    /// The window title
+    pub title: String,
+    
+    /// The process owning the window
+    pub process_id: u32,
+    
+    /// Window bounds after the change, in screen coordinates
+    pub bounds: Rect,
+    
+    /// Window bounds before the change
+    pub previous_bounds: Rect,
+    
+    /// Event metadata (the window element, application, etc.)
+    pub metadata: EventMetadata,
+}
+
+/// How the display layout changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisplayChange {
+    Connected,
+    Disconnected,
+    /// The display moved in the virtual screen or became (or stopped being) primary
+    Reconfigured,
+    /// The display's scaling (DPI) changed
+    ScaleChanged,
+}
+
+/// A display was connected, disconnected, moved or rescaled
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisplayEvent {
+    /// What changed
+    pub change: DisplayChange,
+    
+    /// The display after the change, or as it was before it was disconnected
+    pub display: MonitorInfo,
+    
+    /// The display before the change, if it was connected already
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<MonitorInfo>,
+    
+    /// Every connected display after the change, to map recorded
+    /// coordinates with [`map_position`](crate::layout::map_position)
+    pub displays: Vec<MonitorInfo>,
+}
+
 /// Represents a workflow event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum WorkflowEvent {
@@ -279,6 +369,34 @@ pub enum WorkflowEvent {
     
     /// A UI Automation focus change event
     UiFocusChanged(UiFocusChangedEvent),
+    
+    /// A segment boundary or pause/resume marker
+    Marker(MarkerEvent),
+    
+    /// A window moved, resized, minimized, maximized or restored
+    WindowGeometry(WindowGeometryEvent),
+    
+    /// A display was connected, disconnected, moved or rescaled
+    Display(DisplayEvent),
+}
+
+impl WorkflowEvent {
+    /// The event's metadata; markers and display changes have none
+    pub fn metadata(&self) -> Option<&EventMetadata> {
+        match self {
+            WorkflowEvent::Mouse(e) => Some(&e.metadata),
+            WorkflowEvent::Keyboard(e) => Some(&e.metadata),
+            WorkflowEvent::Clipboard(e) => Some(&e.metadata),
+            WorkflowEvent::TextSelection(e) => Some(&e.metadata),
+            WorkflowEvent::DragDrop(e) => Some(&e.metadata),
+            WorkflowEvent::Hotkey(e) => Some(&e.metadata),
+            WorkflowEvent::UiPropertyChanged(e) => Some(&e.metadata),
+            WorkflowEvent::UiFocusChanged(e) => Some(&e.metadata),
+            WorkflowEvent::Marker(_) => None,
+            WorkflowEvent::WindowGeometry(e) => Some(&e.metadata),
+            WorkflowEvent::Display(_) => None,
+        }
+    }
 }
 
 /// Represents a recorded event with timestamp
@@ -289,6 +407,32 @@ pub struct RecordedEvent {
     
     /// The event
     pub event: WorkflowEvent,
+
+    /// Screenshot taken for the event, see [`crate::ScreenshotConfig`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screenshot: Option<EventScreenshot>,
+}
+
+impl RecordedEvent {
+    /// Timestamp `event` with the current time
+    pub fn now(event: WorkflowEvent) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        
+        Self { timestamp, event, screenshot: None }
+    }
+}
+
+/// The events between two segment markers of a recorded workflow
+#[derive(Debug, Clone, Copy)]
+pub struct WorkflowSegment<'a> {
+    /// The label of the marker that started the segment
+    pub label: Option<&'a str>,
+    
+    /// The events in the segment, pause/resume markers included
+    pub events: &'a [RecordedEvent],
 }
 
 /// Represents a recorded workflow
@@ -306,6 +450,10 @@ pub struct RecordedWorkflow {
     
     /// The recorded events
     pub events: Vec<RecordedEvent>,
+
+    /// Narration recorded alongside the events, see [`crate::AudioConfig`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio: Option<AudioTrack>,
 }
 
 impl RecordedWorkflow {
@@ -321,20 +469,38 @@ impl RecordedWorkflow {
             start_time: now,
             end_time: None,
             events: Vec::new(),
+            audio: None,
         }
     }
     
     /// Add an event to the workflow
     pub fn add_event(&mut self, event: WorkflowEvent) {
-        let now = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64;
+        self.events.push(RecordedEvent::now(event));
+    }
+    
+    /// Split the events at segment markers. Events before the first marker
+    /// form an unlabeled segment, which is left out when empty
+    pub fn segments(&self) -> Vec<WorkflowSegment<'_>> {
+        let mut segments = Vec::new();
+        let mut label = None;
+        let mut start = 0;
         
-        self.events.push(RecordedEvent {
-            timestamp: now,
-            event,
-        });
+        for (i, recorded) in self.events.iter().enumerate() {
+            if let WorkflowEvent::Marker(marker) = &recorded.event {
+                if marker.kind == MarkerKind::Segment {
+                    if label.is_some() || i > start {
+                        segments.push(WorkflowSegment { label, events: &self.events[start..i] });
+                    }
+                    label = Some(marker.label.as_str());
+                    start = i + 1;
+                }
+            }
+        }
+        if label.is_some() || self.events.len() > start {
+            segments.push(WorkflowSegment { label, events: &self.events[start..] });
+        }
+        
+        segments
     }
     
     /// Finish the recording
@@ -455,19 +621,183 @@ pub struct UiFocusChangedEvent {
     pub metadata: EventMetadata,
 }
 
+/// Identifying details of the element an event targeted, captured at event
+/// time so playback can locate the element instead of replaying coordinates
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElementContext {
+    /// The element's role (control type)
+    pub role: String,
+
+    /// The element's name
+    #[serde(skip_serializing_if = "is_empty_string")]
+    pub name: Option<String>,
+
+    /// The element's automation id
+    #[serde(skip_serializing_if = "is_empty_string")]
+    pub automation_id: Option<String>,
+
+    /// The application that owns the element
+    #[serde(skip_serializing_if = "is_empty_string")]
+    pub application: Option<String>,
+
+    /// The process id of the owning application
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub process_id: Option<u32>,
+
+    /// Whether the element is a password field
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_password: bool,
+
+    /// A terminator selector expected to find the element again
+    pub selector: String,
+}
+
+impl ElementContext {
+    /// Build the context for `element`, preferring an explicitly read automation id
+    pub fn new(element: &UIElement, automation_id: Option<String>) -> Self {
+        let attrs = element.attributes();
+        let automation_id = automation_id
+            .or_else(|| {
+                attrs
+                    .properties
+                    .get("AutomationId")
+                    .cloned()
+                    .flatten()
+                    .and_then(|v| v.as_str().map(str::to_string))
+            })
+            .filter(|id| !is_empty_string(&Some(id.clone())));
+        let name = attrs.name.filter(|n| !is_empty_string(&Some(n.clone())));
+        let selector = Self::suggest_selector(&attrs.role, name.as_deref(), automation_id.as_deref());
+
+        Self {
+            role: attrs.role,
+            name,
+            automation_id,
+            application: Some(element.application_name()).filter(|a| !a.is_empty()),
+            process_id: element.process_id().ok(),
+            is_password: false,
+            selector,
+        }
+    }
+
+    /// The most specific selector available: automation id, then role and name, then role
+    pub fn suggest_selector(role: &str, name: Option<&str>, automation_id: Option<&str>) -> String {
+        match (automation_id, name) {
+            (Some(id), _) if !id.is_empty() => format!("nativeid:{}", id),
+            (_, Some(name)) if !name.is_empty() => format!("{}:{}", role, name),
+            _ => format!("role:{}", role),
+        }
+    }
+}
+
 /// Unified metadata for all workflow events
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventMetadata {
     /// The UI element associated with this event (if available)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ui_element: Option<UIElement>,
+
+    /// Identifying details of the UI element (if available)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub element_context: Option<ElementContext>,
+
+    /// The browser tab the event happened in (for web interactions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browser_context: Option<BrowserContext>,
 }
 
 
 // implement empty() constructor 
 impl EventMetadata {
     pub fn empty() -> Self {
-        Self { ui_element: None }
+        Self { ui_element: None, element_context: None, browser_context: None }
+    }
+
+    /// Metadata for the given element, including its identifying context
+    pub fn with_ui_element(ui_element: Option<UIElement>) -> Self {
+        let element_context = ui_element.as_ref().map(|el| ElementContext::new(el, None));
+        Self { ui_element, element_context, browser_context: None }
+    }
+}
+
+/// Process names of the browsers whose tabs are recorded, without `.exe`
+const BROWSER_PROCESS_NAMES: &[&str] = &[
+    "chrome", "msedge", "firefox", "brave", "opera", "vivaldi", "arc", "chromium", "iexplore",
+];
+
+/// How far up from the event's element the page document is looked for
+const MAX_DOCUMENT_DEPTH: usize = 40;
+
+/// The browser tab an event happened in, so a replay can navigate to the
+/// page first instead of assuming the browser is already on it
+///
+/// Read from the accessibility tree: browsers expose the page as a document
+/// element whose value is the URL and whose name is the page title. The
+/// DevTools protocol is not used, so browsers don't have to be started with
+/// a debugging port.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BrowserContext {
+    /// Process name of the browser, e.g. `chrome` or `msedge`
+    pub browser: String,
+
+    /// URL of the active tab, when the browser exposes it
+    #[serde(skip_serializing_if = "is_empty_string")]
+    pub url: Option<String>,
+
+    /// Title of the active tab
+    #[serde(skip_serializing_if = "is_empty_string")]
+    pub title: Option<String>,
+}
+
+impl BrowserContext {
+    /// The browser `process_name` belongs to, None for other applications
+    pub fn browser_name(process_name: &str) -> Option<String> {
+        let name = process_name.trim().to_lowercase();
+        let name = name.strip_suffix(".exe").unwrap_or(&name);
+        BROWSER_PROCESS_NAMES.contains(&name).then(|| name.to_string())
+    }
+
+    /// The tab title in a browser window title, which ends with the
+    /// browser's name, e.g. `Inbox - Google Chrome`
+    pub fn tab_title(window_title: &str) -> Option<String> {
+        let title = window_title
+            .rsplit_once(" - ")
+            .or_else(|| window_title.rsplit_once(" \u{2014} "))
+            .map_or(window_title, |(title, _)| title)
+            .trim();
+        Some(title.to_string()).filter(|t| !is_empty_string(&Some(t.clone())))
+    }
+
+    /// The tab context of `element`, an element of the browser process
+    /// named `process_name`. None if that process is not a browser
+    pub fn for_element(element: &UIElement, process_name: &str) -> Option<Self> {
+        let browser = Self::browser_name(process_name)?;
+
+        // Page content sits below the document; the browser's own controls don't
+        let mut current = Some(element.clone());
+        for _ in 0..MAX_DOCUMENT_DEPTH {
+            let Some(candidate) = current else { break };
+            if candidate.role().eq_ignore_ascii_case("document") {
+                let url = candidate
+                    .attribute_value("value")
+                    .ok()
+                    .flatten()
+                    .filter(|url| !is_empty_string(&Some(url.clone())));
+                let title = candidate.name().or_else(|| Self::tab_title(&element.window_title()));
+                return Some(Self {
+                    browser,
+                    url,
+                    title: title.filter(|t| !is_empty_string(&Some(t.clone()))),
+                });
+            }
+            current = candidate.parent().ok().flatten();
+        }
+
+        Some(Self {
+            browser,
+            url: None,
+            title: Self::tab_title(&element.window_title()),
+        })
     }
 }
 
@@ -526,12 +856,22 @@ pub struct SerializableEventMetadata {
     /// The UI element associated with this event (if available)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ui_element: Option<SerializableUIElement>,
+
+    /// Identifying details of the UI element (if available)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub element_context: Option<ElementContext>,
+
+    /// The browser tab the event happened in (for web interactions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browser_context: Option<BrowserContext>,
 }
 
 impl From<&EventMetadata> for SerializableEventMetadata {
     fn from(metadata: &EventMetadata) -> Self {
         Self {
             ui_element: metadata.ui_element.as_ref().map(|elem| elem.into()),
+            element_context: metadata.element_context.clone(),
+            browser_context: metadata.browser_context.clone(),
         }
     }
 }
@@ -549,6 +889,8 @@ pub struct SerializableKeyboardEvent {
     pub character: Option<char>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scan_code: Option<u32>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub redacted: bool,
     pub metadata: SerializableEventMetadata,
 }
 
@@ -563,6 +905,7 @@ impl From<&KeyboardEvent> for SerializableKeyboardEvent {
             win_pressed: event.win_pressed,
             character: event.character,
             scan_code: event.scan_code,
+            redacted: event.redacted,
             metadata: (&event.metadata).into(),
         }
     }
@@ -734,6 +1077,30 @@ impl From<&UiFocusChangedEvent> for SerializableUiFocusChangedEvent {
     }
 }
 
+/// Serializable version of WindowGeometryEvent for JSON export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializableWindowGeometryEvent {
+    pub change: WindowChange,
+    pub title: String,
+    pub process_id: u32,
+    pub bounds: Rect,
+    pub previous_bounds: Rect,
+    pub metadata: SerializableEventMetadata,
+}
+
+impl From<&WindowGeometryEvent> for SerializableWindowGeometryEvent {
+    fn from(event: &WindowGeometryEvent) -> Self {
+        Self {
+            change: event.change,
+            title: event.title.clone(),
+            process_id: event.process_id,
+            bounds: event.bounds.clone(),
+            previous_bounds: event.previous_bounds.clone(),
+            metadata: (&event.metadata).into(),
+        }
+    }
+}
+
 /// Serializable version of WorkflowEvent for JSON export
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SerializableWorkflowEvent {
@@ -745,6 +1112,9 @@ pub enum SerializableWorkflowEvent {
     Hotkey(SerializableHotkeyEvent),
     UiPropertyChanged(SerializableUiPropertyChangedEvent),
     UiFocusChanged(SerializableUiFocusChangedEvent),
+    Marker(MarkerEvent),
+    WindowGeometry(SerializableWindowGeometryEvent),
+    Display(DisplayEvent),
 }
 
 impl From<&WorkflowEvent> for SerializableWorkflowEvent {
@@ -758,6 +1128,9 @@ impl From<&WorkflowEvent> for SerializableWorkflowEvent {
             WorkflowEvent::Hotkey(e) => SerializableWorkflowEvent::Hotkey(e.into()),
             WorkflowEvent::UiPropertyChanged(e) => SerializableWorkflowEvent::UiPropertyChanged(e.into()),
             WorkflowEvent::UiFocusChanged(e) => SerializableWorkflowEvent::UiFocusChanged(e.into()),
+            WorkflowEvent::Marker(e) => SerializableWorkflowEvent::Marker(e.clone()),
+            WorkflowEvent::WindowGeometry(e) => SerializableWorkflowEvent::WindowGeometry(e.into()),
+            WorkflowEvent::Display(e) => SerializableWorkflowEvent::Display(e.clone()),
         }
     }
 }
@@ -767,6 +1140,8 @@ impl From<&WorkflowEvent> for SerializableWorkflowEvent {
 pub struct SerializableRecordedEvent {
     pub timestamp: u64,
     pub event: SerializableWorkflowEvent,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screenshot: Option<EventScreenshot>,
 }
 
 impl From<&RecordedEvent> for SerializableRecordedEvent {
@@ -774,6 +1149,7 @@ impl From<&RecordedEvent> for SerializableRecordedEvent {
         Self {
             timestamp: event.timestamp,
             event: (&event.event).into(),
+            screenshot: event.screenshot.clone(),
         }
     }
 }
@@ -786,6 +1162,8 @@ pub struct SerializableRecordedWorkflow {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub end_time: Option<u64>,
     pub events: Vec<SerializableRecordedEvent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio: Option<AudioTrack>,
 }
 
 impl From<&RecordedWorkflow> for SerializableRecordedWorkflow {
@@ -795,6 +1173,116 @@ impl From<&RecordedWorkflow> for SerializableRecordedWorkflow {
             start_time: workflow.start_time,
             end_time: workflow.end_time,
             events: workflow.events.iter().map(|e| e.into()).collect(),
+            audio: workflow.audio.clone(),
+        }
+    }
+}
+
+impl SerializableRecordedWorkflow {
+    /// The events as recorded, e.g. for [`crate::IntentEngine::infer`] on a
+    /// saved workflow. Live UI elements cannot be restored from JSON, so the
+    /// events keep their [`ElementContext`] but have no `ui_element`
+    pub fn recorded_events(&self) -> Vec<RecordedEvent> {
+        self.events.iter().map(RecordedEvent::from).collect()
+    }
+}
+
+impl From<&SerializableEventMetadata> for EventMetadata {
+    fn from(metadata: &SerializableEventMetadata) -> Self {
+        Self {
+            ui_element: None,
+            element_context: metadata.element_context.clone(),
+            browser_context: metadata.browser_context.clone(),
+        }
+    }
+}
+
+impl From<&SerializableWorkflowEvent> for WorkflowEvent {
+    fn from(event: &SerializableWorkflowEvent) -> Self {
+        match event {
+            SerializableWorkflowEvent::Mouse(e) => WorkflowEvent::Mouse(MouseEvent {
+                event_type: e.event_type,
+                button: e.button,
+                position: e.position,
+                scroll_delta: e.scroll_delta,
+                drag_start: e.drag_start,
+                metadata: (&e.metadata).into(),
+            }),
+            SerializableWorkflowEvent::Keyboard(e) => WorkflowEvent::Keyboard(KeyboardEvent {
+                key_code: e.key_code,
+                is_key_down: e.is_key_down,
+                ctrl_pressed: e.ctrl_pressed,
+                alt_pressed: e.alt_pressed,
+                shift_pressed: e.shift_pressed,
+                win_pressed: e.win_pressed,
+                character: e.character,
+                scan_code: e.scan_code,
+                redacted: e.redacted,
+                metadata: (&e.metadata).into(),
+            }),
+            SerializableWorkflowEvent::Clipboard(e) => WorkflowEvent::Clipboard(ClipboardEvent {
+                action: e.action.clone(),
+                content: e.content.clone(),
+                content_size: e.content_size,
+                format: e.format.clone(),
+                truncated: e.truncated,
+                metadata: (&e.metadata).into(),
+            }),
+            SerializableWorkflowEvent::TextSelection(e) => WorkflowEvent::TextSelection(TextSelectionEvent {
+                selected_text: e.selected_text.clone(),
+                start_position: e.start_position,
+                end_position: e.end_position,
+                selection_method: e.selection_method.clone(),
+                selection_length: e.selection_length,
+                metadata: (&e.metadata).into(),
+            }),
+            SerializableWorkflowEvent::DragDrop(e) => WorkflowEvent::DragDrop(DragDropEvent {
+                start_position: e.start_position,
+                end_position: e.end_position,
+                source_element: None,
+                data_type: e.data_type.clone(),
+                content: e.content.clone(),
+                success: e.success,
+                metadata: (&e.metadata).into(),
+            }),
+            SerializableWorkflowEvent::Hotkey(e) => WorkflowEvent::Hotkey(HotkeyEvent {
+                combination: e.combination.clone(),
+                action: e.action.clone(),
+                is_global: e.is_global,
+                metadata: (&e.metadata).into(),
+            }),
+            SerializableWorkflowEvent::UiPropertyChanged(e) => {
+                WorkflowEvent::UiPropertyChanged(UiPropertyChangedEvent {
+                    property_name: e.property_name.clone(),
+                    old_value: e.old_value.clone(),
+                    new_value: e.new_value.clone(),
+                    metadata: (&e.metadata).into(),
+                })
+            }
+            SerializableWorkflowEvent::UiFocusChanged(e) => WorkflowEvent::UiFocusChanged(UiFocusChangedEvent {
+                previous_element: None,
+                metadata: (&e.metadata).into(),
+            }),
+            SerializableWorkflowEvent::Marker(e) => WorkflowEvent::Marker(e.clone()),
+            SerializableWorkflowEvent::WindowGeometry(e) => WorkflowEvent::WindowGeometry(WindowGeometryEvent {
+                change: e.change,
+                title: e.title.clone(),
+                process_id: e.process_id,
+                bounds: e.bounds.clone(),
+                previous_bounds: e.previous_bounds.clone(),
+                metadata: (&e.metadata).into(),
+            }),
+            SerializableWorkflowEvent::Display(e) => WorkflowEvent::Display(e.clone()),
+        }
+    }
+}
+
+impl From<&SerializableRecordedEvent> for RecordedEvent {
+    fn from(event: &SerializableRecordedEvent) -> Self {
+        Self {
+            timestamp: event.timestamp,
+            event: (&event.event).into(),
+            screenshot: event.screenshot.clone(),
         }
     }
 }
@@ -803,6 +1291,24 @@ impl From<&RecordedWorkflow> for SerializableRecordedWorkflow {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_browser_context_helpers() {
+        assert_eq!(BrowserContext::browser_name("chrome.exe").as_deref(), Some("chrome"));
+        assert_eq!(BrowserContext::browser_name("MSEdge").as_deref(), Some("msedge"));
+        assert_eq!(BrowserContext::browser_name("explorer"), None);
+        assert_eq!(BrowserContext::browser_name("notepad.exe"), None);
+
+        assert_eq!(
+            BrowserContext::tab_title("Inbox - Work - Google Chrome").as_deref(),
+            Some("Inbox - Work")
+        );
+        assert_eq!(
+            BrowserContext::tab_title("Docs \u{2014} Mozilla Firefox").as_deref(),
+            Some("Docs")
+        );
+        assert_eq!(BrowserContext::tab_title("New Tab").as_deref(), Some("New Tab"));
+        assert_eq!(BrowserContext::tab_title(" - Google Chrome"), None);
+    }
   
     #[test]
     fn test_empty_string_helper() {
@@ -871,4 +1377,77 @@ mod tests {
         assert!(!is_empty_string(&Some("none selected".to_string())));
     }
 
+    #[test]
+    fn test_suggest_selector() {
+        // Automation id wins over everything else
+        assert_eq!(
+            ElementContext::suggest_selector("Button", Some("Save"), Some("SaveButton")),
+            "nativeid:SaveButton"
+        );
+        // Role and name when there is no automation id
+        assert_eq!(ElementContext::suggest_selector("Button", Some("Save"), None), "Button:Save");
+        assert_eq!(ElementContext::suggest_selector("Button", Some("Save"), Some("")), "Button:Save");
+        // Role alone as a last resort
+        assert_eq!(ElementContext::suggest_selector("Pane", None, None), "role:Pane");
+        assert_eq!(ElementContext::suggest_selector("Pane", Some(""), None), "role:Pane");
+    }
+
+    #[test]
+    fn test_segments() {
+        let marker = |label: &str, kind| WorkflowEvent::Marker(MarkerEvent { label: label.to_string(), kind });
+        let hotkey = || WorkflowEvent::Hotkey(HotkeyEvent {
+            combination: "Ctrl+C".to_string(),
+            action: None,
+            is_global: false,
+            metadata: EventMetadata::empty(),
+        });
+
+        let mut workflow = RecordedWorkflow::new("test".to_string());
+        assert!(workflow.segments().is_empty());
+
+        workflow.add_event(hotkey());
+        workflow.add_event(marker("Log in", MarkerKind::Segment));
+        workflow.add_event(hotkey());
+        workflow.add_event(marker("", MarkerKind::Paused));
+        workflow.add_event(marker("", MarkerKind::Resumed));
+        workflow.add_event(marker("Export", MarkerKind::Segment));
+
+        let segments = workflow.segments();
+        let summary: Vec<_> = segments.iter().map(|s| (s.label, s.events.len())).collect();
+        assert_eq!(summary, vec![(None, 1), (Some("Log in"), 3), (Some("Export"), 0)]);
+    }
+
+    #[test]
+    fn test_recorded_events_from_json() {
+        let context = ElementContext {
+            role: "Button".to_string(),
+            name: Some("Save".to_string()),
+            automation_id: None,
+            application: Some("notepad".to_string()),
+            process_id: Some(7),
+            is_password: false,
+            selector: "Button:Save".to_string(),
+        };
+        let mut workflow = RecordedWorkflow::new("test".to_string());
+        workflow.add_event(WorkflowEvent::Hotkey(HotkeyEvent {
+            combination: "Ctrl+S".to_string(),
+            action: Some("Save".to_string()),
+            is_global: false,
+            metadata: EventMetadata {
+                ui_element: None,
+                element_context: Some(context.clone()),
+                browser_context: None,
+            },
+        }));
+
+        let json = workflow.to_json().unwrap();
+        let events = RecordedWorkflow::from_json(&json).unwrap().recorded_events();
+        assert_eq!(events.len(), 1);
+        let WorkflowEvent::Hotkey(hotkey) = &events[0].event else {
+            panic!("expected a hotkey, got {:?}", events[0].event);
+        };
+        assert_eq!(hotkey.combination, "Ctrl+S");
+        assert_eq!(hotkey.metadata.element_context, Some(context));
+        assert_eq!(events[0].timestamp, workflow.events[0].timestamp);
+    }
 }
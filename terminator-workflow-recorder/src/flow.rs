@@ -0,0 +1,375 @@
+//! Flow diagrams of recorded workflows
+//!
+//! [`FlowGraph`] turns the intents of a recording into a graph for process
+//! mining and documentation: one node per intent, one edge per step to the
+//! next, labelled with the time the user took in between. It is written as
+//! Graphviz DOT, with the steps grouped by application, or as BPMN 2.0 XML
+//! with a diagram that BPMN modelers can open:
+//!
+//! ```
+//! use terminator_workflow_recorder::{FlowGraph, IntentEngine, RecordedEvent};
+//!
+//! # let events: Vec<RecordedEvent> = Vec::new();
+//! let intents = IntentEngine::default().infer(&events);
+//! let graph = FlowGraph::from_intents("Create invoice", &intents);
+//! std::fs::write("invoice.dot", graph.to_dot()).ok();
+//! std::fs::write("invoice.bpmn", graph.to_bpmn()).ok();
+//! ```
+
+use crate::intent::Intent;
+use serde::{Deserialize, Serialize};
+
+// Size and spacing of the BPMN diagram shapes
+const TASK_WIDTH: u32 = 120;
+const TASK_HEIGHT: u32 = 80;
+const EVENT_SIZE: u32 = 36;
+const GAP: u32 = 50;
+const TOP: u32 = 80;
+
+/// One step of a flow: an intent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowNode {
+    /// `n0`, `n1`, ... in recording order
+    pub id: String,
+    /// The intent kind, e.g. `type_text`
+    pub kind: String,
+    /// The intent description
+    pub label: String,
+    /// Application the step was directed at, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub application: Option<String>,
+    /// Timestamp of the first event of the step (milliseconds since epoch)
+    pub start_time: u64,
+    /// Timestamp of the last event of the step (milliseconds since epoch)
+    pub end_time: u64,
+}
+
+impl FlowNode {
+    /// How long the step took
+    pub fn duration_ms(&self) -> u64 {
+        self.end_time.saturating_sub(self.start_time)
+    }
+}
+
+/// A step followed by another
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowEdge {
+    pub from: String,
+    pub to: String,
+    /// Time between the end of `from` and the start of `to`
+    pub delay_ms: u64,
+}
+
+/// The steps of a recording and their order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowGraph {
+    pub name: String,
+    pub nodes: Vec<FlowNode>,
+    pub edges: Vec<FlowEdge>,
+}
+
+impl FlowGraph {
+    /// The graph of `intents`, as [`IntentEngine::infer`](crate::IntentEngine::infer)
+    /// returns them
+    pub fn from_intents(name: impl Into<String>, intents: &[Intent]) -> Self {
+        let nodes: Vec<FlowNode> = intents
+            .iter()
+            .enumerate()
+            .map(|(index, intent)| FlowNode {
+                id: format!("n{}", index),
+                kind: intent.kind.clone(),
+                label: intent.description.clone(),
+                application: intent
+                    .target
+                    .as_ref()
+                    .and_then(|target| target.application.clone())
+                    .filter(|application| !application.is_empty()),
+                start_time: intent.start_time,
+                end_time: intent.end_time,
+            })
+            .collect();
+        let edges = nodes
+            .windows(2)
+            .map(|pair| FlowEdge {
+                from: pair[0].id.clone(),
+                to: pair[1].id.clone(),
+                delay_ms: pair[1].start_time.saturating_sub(pair[0].end_time),
+            })
+            .collect();
+        Self {
+            name: name.into(),
+            nodes,
+            edges,
+        }
+    }
+
+    /// Graphviz DOT, left to right, with the steps of each application in a
+    /// cluster labelled with its name
+    pub fn to_dot(&self) -> String {
+        let mut dot = format!("digraph {} {{\n", dot_string(&self.name));
+        dot.push_str("  rankdir=LR;\n");
+        dot.push_str("  node [shape=box, style=rounded, fontname=\"Helvetica\"];\n");
+        dot.push_str("  edge [fontname=\"Helvetica\", fontsize=10];\n");
+
+        let mut applications: Vec<Option<&str>> = Vec::new();
+        for node in &self.nodes {
+            if !applications.contains(&node.application.as_deref()) {
+                applications.push(node.application.as_deref());
+            }
+        }
+        for (index, application) in applications.iter().enumerate() {
+            let indent = match application {
+                Some(name) => {
+                    dot.push_str(&format!("  subgraph cluster_{} {{\n", index));
+                    dot.push_str(&format!("    label={};\n", dot_string(name)));
+                    "    "
+                }
+                None => "  ",
+            };
+            for node in self.nodes.iter().filter(|node| node.application.as_deref() == *application) {
+                let label = format!("{}\n{} ({})", node.label, node.kind, seconds(node.duration_ms()));
+                dot.push_str(&format!("{}{} [label={}];\n", indent, node.id, dot_string(&label)));
+            }
+            if application.is_some() {
+                dot.push_str("  }\n");
+            }
+        }
+        for edge in &self.edges {
+            dot.push_str(&format!(
+                "  {} -> {} [label={}];\n",
+                edge.from,
+                edge.to,
+                dot_string(&format!("+{}", seconds(edge.delay_ms)))
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// BPMN 2.0 XML: a process from a start event through one user task per
+    /// step to an end event, laid out left to right. Each task documents its
+    /// kind, application and duration; each sequence flow is named after the
+    /// delay before its target
+    pub fn to_bpmn(&self) -> String {
+        let ids: Vec<&str> = std::iter::once("start")
+            .chain(self.nodes.iter().map(|node| node.id.as_str()))
+            .chain(std::iter::once("end"))
+            .collect();
+        // Sequence flow `f{i}` goes from `ids[i]` to `ids[i + 1]`
+        let flows: Vec<(&str, &str, Option<u64>)> = ids
+            .windows(2)
+            .map(|pair| {
+                let delay = self
+                    .edges
+                    .iter()
+                    .find(|edge| edge.from == pair[0] && edge.to == pair[1])
+                    .map(|edge| edge.delay_ms);
+                (pair[0], pair[1], delay)
+            })
+            .collect();
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(concat!(
+            "<bpmn:definitions xmlns:bpmn=\"http://www.omg.org/spec/BPMN/20100524/MODEL\"",
+            " xmlns:bpmndi=\"http://www.omg.org/spec/BPMN/20100524/DI\"",
+            " xmlns:dc=\"http://www.omg.org/spec/DD/20100524/DC\"",
+            " xmlns:di=\"http://www.omg.org/spec/DD/20100524/DI\"",
+            " id=\"definitions\" targetNamespace=\"https://github.com/mediar-ai/terminator\">\n"
+        ));
+        xml.push_str(&format!(
+            "  <bpmn:process id=\"process\" name=\"{}\" isExecutable=\"false\">\n",
+            xml_escape(&self.name)
+        ));
+
+        xml.push_str("    <bpmn:startEvent id=\"start\">\n");
+        push_flow_refs(&mut xml, &flows, "start");
+        xml.push_str("    </bpmn:startEvent>\n");
+        for node in &self.nodes {
+            xml.push_str(&format!(
+                "    <bpmn:userTask id=\"{}\" name=\"{}\">\n",
+                node.id,
+                xml_escape(&node.label)
+            ));
+            let mut documentation = format!("kind: {}", node.kind);
+            if let Some(application) = &node.application {
+                documentation.push_str(&format!("; application: {}", application));
+            }
+            documentation.push_str(&format!("; duration: {}", seconds(node.duration_ms())));
+            xml.push_str(&format!(
+                "      <bpmn:documentation>{}</bpmn:documentation>\n",
+                xml_escape(&documentation)
+            ));
+            push_flow_refs(&mut xml, &flows, &node.id);
+            xml.push_str("    </bpmn:userTask>\n");
+        }
+        xml.push_str("    <bpmn:endEvent id=\"end\">\n");
+        push_flow_refs(&mut xml, &flows, "end");
+        xml.push_str("    </bpmn:endEvent>\n");
+        for (index, (from, to, delay)) in flows.iter().enumerate() {
+            let name = delay
+                .map(|delay| format!(" name=\"+{}\"", seconds(delay)))
+                .unwrap_or_default();
+            xml.push_str(&format!(
+                "    <bpmn:sequenceFlow id=\"f{}\" sourceRef=\"{}\" targetRef=\"{}\"{} />\n",
+                index, from, to, name
+            ));
+        }
+        xml.push_str("  </bpmn:process>\n");
+
+        // Start event, tasks and end event in a row, centered vertically
+        let mut bounds: Vec<(&str, u32, u32, u32, u32)> = Vec::new();
+        let mut x = GAP;
+        for &id in &ids {
+            let (width, height) = if id == "start" || id == "end" {
+                (EVENT_SIZE, EVENT_SIZE)
+            } else {
+                (TASK_WIDTH, TASK_HEIGHT)
+            };
+            bounds.push((id, x, TOP + (TASK_HEIGHT - height) / 2, width, height));
+            x += width + GAP;
+        }
+        xml.push_str("  <bpmndi:BPMNDiagram id=\"diagram\">\n");
+        xml.push_str("    <bpmndi:BPMNPlane id=\"plane\" bpmnElement=\"process\">\n");
+        for (id, x, y, width, height) in &bounds {
+            xml.push_str(&format!(
+                "      <bpmndi:BPMNShape id=\"{0}_di\" bpmnElement=\"{0}\">\n        <dc:Bounds x=\"{1}\" y=\"{2}\" width=\"{3}\" height=\"{4}\" />\n      </bpmndi:BPMNShape>\n",
+                id, x, y, width, height
+            ));
+        }
+        let middle = TOP + TASK_HEIGHT / 2;
+        for index in 0..flows.len() {
+            let (_, from_x, _, from_width, _) = bounds[index];
+            let (_, to_x, _, _, _) = bounds[index + 1];
+            xml.push_str(&format!(
+                "      <bpmndi:BPMNEdge id=\"f{0}_di\" bpmnElement=\"f{0}\">\n        <di:waypoint x=\"{1}\" y=\"{3}\" />\n        <di:waypoint x=\"{2}\" y=\"{3}\" />\n      </bpmndi:BPMNEdge>\n",
+                index,
+                from_x + from_width,
+                to_x,
+                middle
+            ));
+        }
+        xml.push_str("    </bpmndi:BPMNPlane>\n");
+        xml.push_str("  </bpmndi:BPMNDiagram>\n");
+        xml.push_str("</bpmn:definitions>\n");
+        xml
+    }
+}
+
+// The `incoming` and `outgoing` elements of `node`
+fn push_flow_refs(xml: &mut String, flows: &[(&str, &str, Option<u64>)], node: &str) {
+    for (index, (_, to, _)) in flows.iter().enumerate() {
+        if *to == node {
+            xml.push_str(&format!("      <bpmn:incoming>f{}</bpmn:incoming>\n", index));
+        }
+    }
+    for (index, (from, _, _)) in flows.iter().enumerate() {
+        if *from == node {
+            xml.push_str(&format!("      <bpmn:outgoing>f{}</bpmn:outgoing>\n", index));
+        }
+    }
+}
+
+// `1.25s`, or `850ms` under a second
+fn seconds(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else {
+        format!("{:.2}s", ms as f64 / 1000.0).replace(".00s", "s")
+    }
+}
+
+// A quoted DOT string
+fn dot_string(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\r', "")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::ElementContext;
+
+    fn intent(kind: &str, description: &str, application: Option<&str>, start_time: u64, end_time: u64) -> Intent {
+        let mut intent = Intent::new(kind, description).with_target(Some(ElementContext {
+            role: "Button".to_string(),
+            name: None,
+            automation_id: None,
+            application: application.map(str::to_string),
+            process_id: None,
+            is_password: false,
+            selector: "Button".to_string(),
+        }));
+        intent.start_time = start_time;
+        intent.end_time = end_time;
+        intent
+    }
+
+    fn sample() -> FlowGraph {
+        FlowGraph::from_intents(
+            "Invoice",
+            &[
+                intent("type_text", "Typed \"ACME\" into Edit:Customer", Some("erp"), 1_000, 2_500),
+                intent("click", "Clicked Button:Save & close", Some("erp"), 3_000, 3_000),
+                intent("hotkey", "Pressed Ctrl+S", None, 4_200, 4_200),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_graph_orders_intents_with_delays() {
+        let graph = sample();
+        let ids: Vec<_> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+        assert_eq!(ids, ["n0", "n1", "n2"]);
+        assert_eq!(graph.nodes[0].duration_ms(), 1_500);
+        assert_eq!(graph.nodes[0].application.as_deref(), Some("erp"));
+        let edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|edge| (edge.from.as_str(), edge.to.as_str(), edge.delay_ms))
+            .collect();
+        assert_eq!(edges, [("n0", "n1", 500), ("n1", "n2", 1_200)]);
+    }
+
+    #[test]
+    fn test_dot_clusters_applications_and_escapes_labels() {
+        let dot = sample().to_dot();
+        assert!(dot.starts_with("digraph \"Invoice\" {\n"));
+        assert!(dot.contains("subgraph cluster_0 {\n    label=\"erp\";\n    n0 [label=\"Typed \\\"ACME\\\" into Edit:Customer\\ntype_text (1.50s)\"];"));
+        assert!(dot.contains("\n  n2 [label=\"Pressed Ctrl+S\\nhotkey (0ms)\"];"));
+        assert!(dot.contains("n0 -> n1 [label=\"+500ms\"];"));
+        assert!(dot.contains("n1 -> n2 [label=\"+1.20s\"];"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_bpmn_links_start_tasks_and_end() {
+        let bpmn = sample().to_bpmn();
+        assert!(bpmn.contains("<bpmn:userTask id=\"n1\" name=\"Clicked Button:Save &amp; close\">"));
+        assert!(bpmn.contains("<bpmn:sequenceFlow id=\"f0\" sourceRef=\"start\" targetRef=\"n0\" />"));
+        assert!(bpmn.contains("<bpmn:sequenceFlow id=\"f2\" sourceRef=\"n1\" targetRef=\"n2\" name=\"+1.20s\" />"));
+        assert!(bpmn.contains("<bpmn:sequenceFlow id=\"f3\" sourceRef=\"n2\" targetRef=\"end\" />"));
+        assert!(bpmn.contains("<bpmn:documentation>kind: type_text; application: erp; duration: 1.50s</bpmn:documentation>"));
+        assert_eq!(bpmn.matches("<bpmndi:BPMNShape ").count(), 5);
+        assert_eq!(bpmn.matches("<bpmndi:BPMNEdge ").count(), 4);
+    }
+
+    #[test]
+    fn test_empty_recording_goes_from_start_to_end() {
+        let graph = FlowGraph::from_intents("Nothing", &[]);
+        assert!(graph.edges.is_empty());
+        assert!(graph.to_bpmn().contains("sourceRef=\"start\" targetRef=\"end\""));
+        assert_eq!(graph.to_dot().matches("->").count(), 0);
+    }
+}
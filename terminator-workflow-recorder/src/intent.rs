@@ -0,0 +1,548 @@
+//! Inference of high-level intents from recorded events
+//!
+//! A recording is a stream of low-level events: key presses, mouse buttons,
+//! property changes. [`IntentEngine`] folds them into what the user meant to
+//! do, such as "typed `hello` into the Name field" or "selected the Save menu
+//! item", which is what workflow generation and summaries need.
+//!
+//! Intents are found by [`IntentRecognizer`]s tried in turn at each position
+//! of the event stream. The built-in ones cover text entry, menu items,
+//! clicks, hotkeys and clipboard actions; applications with their own idioms
+//! register recognizers of their own, which take precedence:
+//!
+//! ```
+//! use terminator_workflow_recorder::{Intent, IntentEngine, IntentRecognizer, RecordedEvent, WorkflowEvent};
+//!
+//! struct SaveShortcut;
+//!
+//! impl IntentRecognizer for SaveShortcut {
+//!     fn name(&self) -> &str {
+//!         "save_shortcut"
+//!     }
+//!
+//!     fn recognize(&self, events: &[RecordedEvent]) -> Option<(Intent, usize)> {
+//!         match &events[0].event {
+//!             WorkflowEvent::Hotkey(hotkey) if hotkey.combination == "Ctrl+S" => {
+//!                 Some((Intent::new("save", "Saved the document"), 1))
+//!             }
+//!             _ => None,
+//!         }
+//!     }
+//! }
+//!
+//! let mut engine = IntentEngine::default();
+//! engine.register(SaveShortcut);
+//! # let events: Vec<RecordedEvent> = Vec::new();
+//! let intents = engine.infer(&events);
+//! ```
+
+use crate::events::{
+    ClipboardAction, ElementContext, MouseButton, MouseEventType, RecordedEvent, WorkflowEvent,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const VK_BACK: u32 = 0x08;
+const VK_SHIFT: u32 = 0x10;
+const VK_CAPITAL: u32 = 0x14;
+const VK_LSHIFT: u32 = 0xA0;
+const VK_RSHIFT: u32 = 0xA1;
+
+/// Something the user did, inferred from one or more consecutive events
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Intent {
+    /// What kind of intent this is, e.g. `type_text` or `select_menu_item`
+    pub kind: String,
+
+    /// Human-readable summary, e.g. `Typed "hello" into edit:Name`
+    pub description: String,
+
+    /// The element the intent was directed at (if known)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<ElementContext>,
+
+    /// Kind-specific details, e.g. the typed `text`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub details: BTreeMap<String, String>,
+
+    /// Timestamp of the first event of the intent (milliseconds since epoch)
+    pub start_time: u64,
+
+    /// Timestamp of the last event of the intent (milliseconds since epoch)
+    pub end_time: u64,
+
+    /// Index of the first event of the intent in the inferred events
+    pub first_event: usize,
+
+    /// Number of events the intent was inferred from
+    pub event_count: usize,
+}
+
+impl Intent {
+    /// An intent of `kind`. Timing and event range are filled in by the engine
+    pub fn new(kind: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            description: description.into(),
+            target: None,
+            details: BTreeMap::new(),
+            start_time: 0,
+            end_time: 0,
+            first_event: 0,
+            event_count: 0,
+        }
+    }
+
+    /// Set the targeted element
+    pub fn with_target(mut self, target: Option<ElementContext>) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Add a detail
+    pub fn with_detail(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.details.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// Recognizes one kind of intent at the start of an event sequence
+pub trait IntentRecognizer: Send + Sync {
+    /// Name of the recognizer, for logging and [`IntentEngine::recognizers`]
+    fn name(&self) -> &str;
+
+    /// Try to recognize an intent starting at `events[0]` (never empty).
+    /// Returns the intent and how many events it consumed, at least one
+    fn recognize(&self, events: &[RecordedEvent]) -> Option<(Intent, usize)>;
+}
+
+/// Folds recorded events into [`Intent`]s using registered recognizers
+pub struct IntentEngine {
+    recognizers: Vec<Box<dyn IntentRecognizer>>,
+}
+
+impl IntentEngine {
+    /// An engine without any recognizers
+    pub fn new() -> Self {
+        Self {
+            recognizers: Vec::new(),
+        }
+    }
+
+    /// Add a recognizer. It is tried before every recognizer registered
+    /// earlier, including the built-in ones, so it can claim events they
+    /// would otherwise interpret
+    pub fn register<R: IntentRecognizer + 'static>(&mut self, recognizer: R) -> &mut Self {
+        self.recognizers.insert(0, Box::new(recognizer));
+        self
+    }
+
+    /// Names of the registered recognizers, in the order they are tried
+    pub fn recognizers(&self) -> Vec<&str> {
+        self.recognizers.iter().map(|r| r.name()).collect()
+    }
+
+    /// Infer the intents in `events`. At each position the first recognizer
+    /// to match wins and inference resumes after the events it consumed.
+    /// Events no recognizer matches (mouse moves, markers, ...) are skipped
+    pub fn infer(&self, events: &[RecordedEvent]) -> Vec<Intent> {
+        let mut intents = Vec::new();
+        let mut position = 0;
+        while position < events.len() {
+            let remaining = &events[position..];
+            let recognized = self
+                .recognizers
+                .iter()
+                .find_map(|recognizer| recognizer.recognize(remaining));
+            match recognized {
+                Some((mut intent, consumed)) => {
+                    let consumed = consumed.clamp(1, remaining.len());
+                    intent.start_time = remaining[0].timestamp;
+                    intent.end_time = remaining[consumed - 1].timestamp;
+                    intent.first_event = position;
+                    intent.event_count = consumed;
+                    intents.push(intent);
+                    position += consumed;
+                }
+                None => position += 1,
+            }
+        }
+        intents
+    }
+}
+
+impl Default for IntentEngine {
+    /// An engine with the built-in recognizers
+    fn default() -> Self {
+        let mut engine = Self::new();
+        engine
+            .register(ClickRecognizer)
+            .register(ClipboardRecognizer)
+            .register(HotkeyRecognizer)
+            .register(MenuItemRecognizer)
+            .register(TextEntryRecognizer);
+        engine
+    }
+}
+
+impl std::fmt::Debug for IntentEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IntentEngine")
+            .field("recognizers", &self.recognizers())
+            .finish()
+    }
+}
+
+fn describe_target(target: &Option<ElementContext>) -> String {
+    target
+        .as_ref()
+        .map(|t| t.selector.clone())
+        .unwrap_or_else(|| "an unknown element".to_string())
+}
+
+fn same_target(a: &Option<ElementContext>, b: &Option<ElementContext>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.selector == b.selector && a.process_id == b.process_id,
+        _ => true,
+    }
+}
+
+/// Printable characters typed into one element, with backspaces applied.
+/// Ends at a shortcut, a non-printable key or input into another element
+pub struct TextEntryRecognizer;
+
+impl IntentRecognizer for TextEntryRecognizer {
+    fn name(&self) -> &str {
+        "text_entry"
+    }
+
+    fn recognize(&self, events: &[RecordedEvent]) -> Option<(Intent, usize)> {
+        let mut text = String::new();
+        let mut target: Option<ElementContext> = None;
+        let mut consumed = 0;
+        for event in events {
+            match &event.event {
+                WorkflowEvent::Keyboard(key) => {
+                    let context = &key.metadata.element_context;
+                    if !same_target(&target, context) {
+                        break;
+                    }
+                    if key.is_key_down {
+                        if key.ctrl_pressed || key.alt_pressed || key.win_pressed {
+                            break;
+                        }
+                        match key.character {
+                            Some(c) if !c.is_control() => text.push(c),
+                            _ if key.key_code == VK_BACK => {
+                                if text.pop().is_none() {
+                                    break;
+                                }
+                            }
+                            _ if matches!(key.key_code, VK_SHIFT | VK_LSHIFT | VK_RSHIFT | VK_CAPITAL) => {}
+                            _ => break,
+                        }
+                        if target.is_none() {
+                            target = context.clone();
+                        }
+                    } else if consumed == 0 {
+                        // A release left over from an earlier key
+                        break;
+                    }
+                }
+                // The field echoing the typed value
+                WorkflowEvent::UiPropertyChanged(_) if consumed > 0 => {}
+                _ => break,
+            }
+            consumed += 1;
+        }
+
+        if text.is_empty() {
+            return None;
+        }
+        let description = format!("Typed {:?} into {}", text, describe_target(&target));
+        Some((
+            Intent::new("type_text", description)
+                .with_target(target)
+                .with_detail("text", text),
+            consumed,
+        ))
+    }
+}
+
+/// A left click on a menu item, with its button release
+pub struct MenuItemRecognizer;
+
+impl IntentRecognizer for MenuItemRecognizer {
+    fn name(&self) -> &str {
+        "menu_item"
+    }
+
+    fn recognize(&self, events: &[RecordedEvent]) -> Option<(Intent, usize)> {
+        let WorkflowEvent::Mouse(mouse) = &events[0].event else {
+            return None;
+        };
+        if mouse.button != MouseButton::Left
+            || !matches!(mouse.event_type, MouseEventType::Down | MouseEventType::Click)
+        {
+            return None;
+        }
+        let target = mouse.metadata.element_context.as_ref()?;
+        if !target.role.eq_ignore_ascii_case("menuitem") {
+            return None;
+        }
+
+        let item = target.name.clone().unwrap_or_default();
+        let intent = Intent::new("select_menu_item", format!("Selected menu item {:?}", item))
+            .with_target(Some(target.clone()))
+            .with_detail("item", item);
+        Some((intent, 1 + release_follows(events, mouse.event_type, mouse.button)))
+    }
+}
+
+/// Clicks, double clicks and right clicks, with their button release
+pub struct ClickRecognizer;
+
+impl IntentRecognizer for ClickRecognizer {
+    fn name(&self) -> &str {
+        "click"
+    }
+
+    fn recognize(&self, events: &[RecordedEvent]) -> Option<(Intent, usize)> {
+        let WorkflowEvent::Mouse(mouse) = &events[0].event else {
+            return None;
+        };
+        let (kind, verb) = match (mouse.event_type, mouse.button) {
+            (MouseEventType::DoubleClick, _) => ("double_click", "Double-clicked"),
+            (MouseEventType::RightClick, _) | (MouseEventType::Down, MouseButton::Right) => {
+                ("right_click", "Right-clicked")
+            }
+            (MouseEventType::Click, _) | (MouseEventType::Down, MouseButton::Left) => {
+                ("click", "Clicked")
+            }
+            _ => return None,
+        };
+
+        let target = mouse.metadata.element_context.clone();
+        let intent = Intent::new(kind, format!("{} {}", verb, describe_target(&target)))
+            .with_target(target)
+            .with_detail("x", mouse.position.x.to_string())
+            .with_detail("y", mouse.position.y.to_string());
+        Some((intent, 1 + release_follows(events, mouse.event_type, mouse.button)))
+    }
+}
+
+// 1 if a button press at `events[0]` is directly followed by its release
+fn release_follows(events: &[RecordedEvent], event_type: MouseEventType, button: MouseButton) -> usize {
+    if event_type != MouseEventType::Down {
+        return 0;
+    }
+    match events.get(1).map(|e| &e.event) {
+        Some(WorkflowEvent::Mouse(next))
+            if next.event_type == MouseEventType::Up && next.button == button =>
+        {
+            1
+        }
+        _ => 0,
+    }
+}
+
+/// Hotkeys detected by the recorder
+pub struct HotkeyRecognizer;
+
+impl IntentRecognizer for HotkeyRecognizer {
+    fn name(&self) -> &str {
+        "hotkey"
+    }
+
+    fn recognize(&self, events: &[RecordedEvent]) -> Option<(Intent, usize)> {
+        let WorkflowEvent::Hotkey(hotkey) = &events[0].event else {
+            return None;
+        };
+        let description = match &hotkey.action {
+            Some(action) => format!("Pressed {} ({})", hotkey.combination, action),
+            None => format!("Pressed {}", hotkey.combination),
+        };
+        let mut intent = Intent::new("hotkey", description)
+            .with_target(hotkey.metadata.element_context.clone())
+            .with_detail("combination", hotkey.combination.clone());
+        if let Some(action) = &hotkey.action {
+            intent = intent.with_detail("action", action.clone());
+        }
+        Some((intent, 1))
+    }
+}
+
+/// Copy, cut and paste
+pub struct ClipboardRecognizer;
+
+impl IntentRecognizer for ClipboardRecognizer {
+    fn name(&self) -> &str {
+        "clipboard"
+    }
+
+    fn recognize(&self, events: &[RecordedEvent]) -> Option<(Intent, usize)> {
+        let WorkflowEvent::Clipboard(clipboard) = &events[0].event else {
+            return None;
+        };
+        let (kind, verb) = match clipboard.action {
+            ClipboardAction::Copy => ("copy", "Copied"),
+            ClipboardAction::Cut => ("cut", "Cut"),
+            ClipboardAction::Paste => ("paste", "Pasted"),
+            ClipboardAction::Clear => return None,
+        };
+        let content = clipboard.content.clone().unwrap_or_default();
+        let mut intent = Intent::new(kind, format!("{} {:?}", verb, content))
+            .with_target(clipboard.metadata.element_context.clone());
+        if clipboard.content.is_some() {
+            intent = intent.with_detail("content", content);
+        }
+        Some((intent, 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{
+        EventMetadata, HotkeyEvent, KeyboardEvent, MouseEvent, Position, UiPropertyChangedEvent,
+    };
+
+    fn context(role: &str, name: &str) -> EventMetadata {
+        EventMetadata {
+            ui_element: None,
+            element_context: Some(ElementContext {
+                role: role.to_string(),
+                name: Some(name.to_string()),
+                automation_id: None,
+                application: None,
+                process_id: Some(1),
+                is_password: false,
+                selector: ElementContext::suggest_selector(role, Some(name), None),
+            }),
+            browser_context: None,
+        }
+    }
+
+    fn key(key_code: u32, character: Option<char>, is_key_down: bool, metadata: &EventMetadata) -> WorkflowEvent {
+        WorkflowEvent::Keyboard(KeyboardEvent {
+            key_code,
+            is_key_down,
+            ctrl_pressed: false,
+            alt_pressed: false,
+            shift_pressed: false,
+            win_pressed: false,
+            character,
+            scan_code: None,
+            redacted: false,
+            metadata: metadata.clone(),
+        })
+    }
+
+    fn typed(text: &str, metadata: &EventMetadata) -> Vec<WorkflowEvent> {
+        text.chars()
+            .flat_map(|c| {
+                let code = c.to_ascii_uppercase() as u32;
+                [key(code, Some(c), true, metadata), key(code, Some(c), false, metadata)]
+            })
+            .collect()
+    }
+
+    fn mouse(event_type: MouseEventType, metadata: &EventMetadata) -> WorkflowEvent {
+        WorkflowEvent::Mouse(MouseEvent {
+            event_type,
+            button: MouseButton::Left,
+            position: Position { x: 10, y: 20 },
+            scroll_delta: None,
+            drag_start: None,
+            metadata: metadata.clone(),
+        })
+    }
+
+    fn recorded(events: Vec<WorkflowEvent>) -> Vec<RecordedEvent> {
+        events
+            .into_iter()
+            .enumerate()
+            .map(|(i, event)| RecordedEvent { timestamp: 1000 + i as u64, event, screenshot: None })
+            .collect()
+    }
+
+    #[test]
+    fn test_text_entry_and_menu_item() {
+        let field = context("Edit", "Name");
+        let menu = context("MenuItem", "Save");
+        let mut events = typed("hellp", &field);
+        events.push(key(VK_BACK, None, true, &field));
+        events.push(key(VK_BACK, None, false, &field));
+        events.push(WorkflowEvent::UiPropertyChanged(UiPropertyChangedEvent {
+            property_name: "Value".to_string(),
+            old_value: None,
+            new_value: Some("hell".to_string()),
+            metadata: field.clone(),
+        }));
+        events.extend(typed("o", &field));
+        events.push(mouse(MouseEventType::Move, &EventMetadata::empty()));
+        events.push(mouse(MouseEventType::Down, &menu));
+        events.push(mouse(MouseEventType::Up, &menu));
+
+        let intents = IntentEngine::default().infer(&recorded(events));
+        let summary: Vec<_> = intents
+            .iter()
+            .map(|i| (i.kind.as_str(), i.first_event, i.event_count))
+            .collect();
+        assert_eq!(summary, vec![("type_text", 0, 15), ("select_menu_item", 16, 2)]);
+        assert_eq!(intents[0].details["text"], "hello");
+        assert_eq!(intents[0].description, "Typed \"hello\" into Edit:Name");
+        assert_eq!(intents[0].start_time, 1000);
+        assert_eq!(intents[0].end_time, 1014);
+        assert_eq!(intents[1].details["item"], "Save");
+    }
+
+    #[test]
+    fn test_text_entry_splits_on_target_change() {
+        let first = context("Edit", "First");
+        let last = context("Edit", "Last");
+        let mut events = typed("Ada", &first);
+        events.extend(typed("Lovelace", &last));
+
+        let texts: Vec<_> = IntentEngine::default()
+            .infer(&recorded(events))
+            .into_iter()
+            .map(|i| i.details["text"].clone())
+            .collect();
+        assert_eq!(texts, vec!["Ada", "Lovelace"]);
+    }
+
+    #[test]
+    fn test_custom_recognizer_takes_precedence() {
+        struct SaveShortcut;
+
+        impl IntentRecognizer for SaveShortcut {
+            fn name(&self) -> &str {
+                "save_shortcut"
+            }
+
+            fn recognize(&self, events: &[RecordedEvent]) -> Option<(Intent, usize)> {
+                match &events[0].event {
+                    WorkflowEvent::Hotkey(h) if h.combination == "Ctrl+S" => Some((Intent::new("save", "Saved"), 1)),
+                    _ => None,
+                }
+            }
+        }
+
+        let hotkey = |combination: &str| WorkflowEvent::Hotkey(HotkeyEvent {
+            combination: combination.to_string(),
+            action: None,
+            is_global: false,
+            metadata: EventMetadata::empty(),
+        });
+        let events = recorded(vec![hotkey("Ctrl+S"), hotkey("Ctrl+C")]);
+
+        let mut engine = IntentEngine::default();
+        engine.register(SaveShortcut);
+        assert_eq!(engine.recognizers()[0], "save_shortcut");
+        let kinds: Vec<_> = engine.infer(&events).into_iter().map(|i| i.kind).collect();
+        assert_eq!(kinds, vec!["save", "hotkey"]);
+
+        assert!(IntentEngine::new().infer(&events).is_empty());
+    }
+}
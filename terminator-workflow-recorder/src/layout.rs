@@ -0,0 +1,275 @@
+//! Window geometry and display layout
+//!
+//! A replay clicks at recorded coordinates, which only land on the same
+//! element if windows and displays are where they were. The recorder polls
+//! top-level windows and connected displays and records a
+//! [`WindowGeometryEvent`](crate::WindowGeometryEvent) once a window settles
+//! at a new position, size or state, and a [`DisplayEvent`](crate::DisplayEvent)
+//! when a display is connected, disconnected, moved or rescaled.
+//! [`map_position`] moves a recorded point onto another display layout.
+
+use std::collections::HashMap;
+
+use terminator::MonitorInfo;
+
+use crate::events::{DisplayChange, Position, Rect, WindowChange};
+
+/// Whether a window is minimized, maximized or neither
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowState {
+    Normal,
+    Minimized,
+    Maximized,
+}
+
+/// A top-level window as seen by one poll
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowSnapshot {
+    /// Window handle, stable while the window exists
+    pub handle: isize,
+    pub title: String,
+    pub process_id: u32,
+    pub bounds: Rect,
+    pub state: WindowState,
+}
+
+/// A change reported by [`WindowTracker::update`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowUpdate {
+    pub change: WindowChange,
+    pub window: WindowSnapshot,
+    pub previous_bounds: Rect,
+}
+
+/// How `after` differs from `before`. A state change is reported alone since
+/// it moves and resizes the window too; minimized windows have no meaningful
+/// bounds
+pub fn window_changes(before: &WindowSnapshot, after: &WindowSnapshot) -> Vec<WindowChange> {
+    if before.state != after.state {
+        return vec![match after.state {
+            WindowState::Minimized => WindowChange::Minimized,
+            WindowState::Maximized => WindowChange::Maximized,
+            WindowState::Normal => WindowChange::Restored,
+        }];
+    }
+    if after.state == WindowState::Minimized {
+        return Vec::new();
+    }
+    let mut changes = Vec::new();
+    if (before.bounds.x, before.bounds.y) != (after.bounds.x, after.bounds.y) {
+        changes.push(WindowChange::Moved);
+    }
+    if (before.bounds.width, before.bounds.height) != (after.bounds.width, after.bounds.height) {
+        changes.push(WindowChange::Resized);
+    }
+    changes
+}
+
+/// Follows windows from poll to poll. A change is reported once the window
+/// looks the same in two polls in a row, so dragging a window records where
+/// it was dropped rather than every step on the way
+#[derive(Debug, Default)]
+pub struct WindowTracker {
+    // Last reported geometry of each window
+    settled: HashMap<isize, WindowSnapshot>,
+    // Geometry seen once that differs from the settled one
+    pending: HashMap<isize, WindowSnapshot>,
+}
+
+impl WindowTracker {
+    /// Take the windows of one poll and return the changes that settled.
+    /// Windows seen for the first time are not reported
+    pub fn update(&mut self, windows: Vec<WindowSnapshot>) -> Vec<WindowUpdate> {
+        let mut updates = Vec::new();
+        let mut settled = HashMap::with_capacity(windows.len());
+        let mut pending = HashMap::new();
+        for window in windows {
+            let Some(before) = self.settled.remove(&window.handle) else {
+                settled.insert(window.handle, window);
+                continue;
+            };
+            if same_geometry(&before, &window) {
+                settled.insert(window.handle, window);
+                continue;
+            }
+            let stable = self
+                .pending
+                .get(&window.handle)
+                .is_some_and(|seen| same_geometry(seen, &window));
+            if !stable {
+                pending.insert(window.handle, window);
+                settled.insert(before.handle, before);
+                continue;
+            }
+            for change in window_changes(&before, &window) {
+                updates.push(WindowUpdate {
+                    change,
+                    window: window.clone(),
+                    previous_bounds: before.bounds.clone(),
+                });
+            }
+            settled.insert(window.handle, window);
+        }
+        // Windows missing from this poll were closed
+        self.settled = settled;
+        self.pending = pending;
+        updates
+    }
+}
+
+fn same_geometry(a: &WindowSnapshot, b: &WindowSnapshot) -> bool {
+    a.state == b.state && a.bounds == b.bounds
+}
+
+/// How the displays in `after` differ from those in `before`, matched by
+/// name: the change, the display after it (before it, when disconnected)
+/// and the display before it when it was already connected
+pub fn display_changes(
+    before: &[MonitorInfo],
+    after: &[MonitorInfo],
+) -> Vec<(DisplayChange, MonitorInfo, Option<MonitorInfo>)> {
+    let mut changes = Vec::new();
+    for display in after {
+        match before.iter().find(|previous| previous.name == display.name) {
+            None => changes.push((DisplayChange::Connected, display.clone(), None)),
+            Some(previous) if previous.scale_factor != display.scale_factor => {
+                changes.push((DisplayChange::ScaleChanged, display.clone(), Some(previous.clone())))
+            }
+            Some(previous) if previous.bounds != display.bounds || previous.is_primary != display.is_primary => {
+                changes.push((DisplayChange::Reconfigured, display.clone(), Some(previous.clone())))
+            }
+            Some(_) => {}
+        }
+    }
+    for previous in before {
+        if !after.iter().any(|display| display.name == previous.name) {
+            changes.push((DisplayChange::Disconnected, previous.clone(), None));
+        }
+    }
+    changes
+}
+
+/// Where `position`, recorded with the `recorded` displays, lies on the
+/// `current` displays: at the same relative spot on the display with the
+/// same name, or on the primary display if that one is gone. `None` if the
+/// point was on no recorded display or no display is connected
+pub fn map_position(position: Position, recorded: &[MonitorInfo], current: &[MonitorInfo]) -> Option<Position> {
+    let (x, y) = (position.x as f64, position.y as f64);
+    let source = recorded
+        .iter()
+        .find(|display| display.bounds.contains_point(x, y))?;
+    let target = current
+        .iter()
+        .find(|display| display.name == source.name)
+        .or_else(|| current.iter().find(|display| display.is_primary))
+        .or_else(|| current.first())?;
+    if source.bounds.width <= 0.0 || source.bounds.height <= 0.0 {
+        return None;
+    }
+    let relative_x = (x - source.bounds.x) / source.bounds.width;
+    let relative_y = (y - source.bounds.y) / source.bounds.height;
+    Some(Position {
+        x: (target.bounds.x + relative_x * target.bounds.width).round() as i32,
+        y: (target.bounds.y + relative_y * target.bounds.height).round() as i32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use terminator::geometry;
+
+    fn window(x: i32, y: i32, width: i32, height: i32, state: WindowState) -> WindowSnapshot {
+        WindowSnapshot {
+            handle: 1,
+            title: "Untitled - Notepad".to_string(),
+            process_id: 42,
+            bounds: Rect { x, y, width, height },
+            state,
+        }
+    }
+
+    fn display(name: &str, x: f64, width: f64, scale_factor: f64, is_primary: bool) -> MonitorInfo {
+        MonitorInfo {
+            name: name.to_string(),
+            bounds: geometry::Rect::new(x, 0.0, width, 1080.0),
+            scale_factor,
+            refresh_rate: 60.0,
+            is_primary,
+        }
+    }
+
+    #[test]
+    fn test_window_changes() {
+        let normal = window(0, 0, 800, 600, WindowState::Normal);
+        assert_eq!(
+            window_changes(&normal, &window(10, 0, 900, 600, WindowState::Normal)),
+            vec![WindowChange::Moved, WindowChange::Resized]
+        );
+        assert_eq!(
+            window_changes(&normal, &window(-32000, -32000, 160, 28, WindowState::Minimized)),
+            vec![WindowChange::Minimized]
+        );
+        assert_eq!(
+            window_changes(&window(0, 0, 1920, 1040, WindowState::Maximized), &normal),
+            vec![WindowChange::Restored]
+        );
+        assert!(window_changes(&normal, &normal).is_empty());
+    }
+
+    #[test]
+    fn test_tracker_reports_settled_changes_once() {
+        let mut tracker = WindowTracker::default();
+        assert!(tracker.update(vec![window(0, 0, 800, 600, WindowState::Normal)]).is_empty());
+        // Mid-drag positions are not reported
+        assert!(tracker.update(vec![window(50, 0, 800, 600, WindowState::Normal)]).is_empty());
+        assert!(tracker.update(vec![window(100, 0, 800, 600, WindowState::Normal)]).is_empty());
+
+        let updates = tracker.update(vec![window(100, 0, 800, 600, WindowState::Normal)]);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].change, WindowChange::Moved);
+        assert_eq!(updates[0].previous_bounds, Rect { x: 0, y: 0, width: 800, height: 600 });
+        assert!(tracker.update(vec![window(100, 0, 800, 600, WindowState::Normal)]).is_empty());
+    }
+
+    #[test]
+    fn test_tracker_forgets_closed_windows() {
+        let mut tracker = WindowTracker::default();
+        tracker.update(vec![window(0, 0, 800, 600, WindowState::Normal)]);
+        tracker.update(Vec::new());
+        // Reopened elsewhere: a new window, not a move
+        assert!(tracker.update(vec![window(300, 300, 800, 600, WindowState::Normal)]).is_empty());
+        assert!(tracker.update(vec![window(300, 300, 800, 600, WindowState::Normal)]).is_empty());
+    }
+
+    #[test]
+    fn test_display_changes() {
+        let before = vec![display("DISPLAY1", 0.0, 1920.0, 1.0, true), display("DISPLAY2", 1920.0, 1920.0, 1.0, false)];
+        let after = vec![display("DISPLAY1", 0.0, 1920.0, 1.5, true), display("DISPLAY3", 1920.0, 2560.0, 1.0, false)];
+        let changes: Vec<(DisplayChange, String)> = display_changes(&before, &after)
+            .into_iter()
+            .map(|(change, display, _)| (change, display.name))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (DisplayChange::ScaleChanged, "DISPLAY1".to_string()),
+                (DisplayChange::Connected, "DISPLAY3".to_string()),
+                (DisplayChange::Disconnected, "DISPLAY2".to_string()),
+            ]
+        );
+        assert!(display_changes(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn test_map_position() {
+        let recorded = vec![display("DISPLAY1", 0.0, 1920.0, 1.0, true), display("DISPLAY2", 1920.0, 1920.0, 1.0, false)];
+        let current = vec![display("DISPLAY1", 0.0, 3840.0, 2.0, true)];
+        let mapped = map_position(Position { x: 960, y: 540 }, &recorded, &current).unwrap();
+        assert_eq!((mapped.x, mapped.y), (1920, 540));
+        // DISPLAY2 is gone, its points go to the primary display
+        let mapped = map_position(Position { x: 2880, y: 270 }, &recorded, &current).unwrap();
+        assert_eq!((mapped.x, mapped.y), (1920, 270));
+        assert!(map_position(Position { x: -10, y: 0 }, &recorded, &current).is_none());
+    }
+}
@@ -6,16 +6,39 @@
 
 #![cfg_attr(not(target_os = "windows"), allow(unused))]
 
+pub mod audio;
 pub mod events;
 pub mod recorder;
 pub mod error;
+pub mod sink;
+pub mod flow;
+pub mod intent;
+pub mod layout;
+pub mod playback;
+pub mod redaction;
+pub mod screenshot;
 
 pub use events::{
     Position, Rect, MouseButton, MouseEventType, KeyboardEvent, MouseEvent,
     ClipboardAction, ClipboardEvent, TextSelectionEvent, SelectionMethod, DragDropEvent,
-    HotkeyEvent, WorkflowEvent, RecordedEvent, RecordedWorkflow, StructureChangeType,
-    UiStructureChangedEvent, UiPropertyChangedEvent, UiFocusChangedEvent, EventMetadata,
+    HotkeyEvent, MarkerEvent, MarkerKind, WorkflowEvent, RecordedEvent, RecordedWorkflow, WorkflowSegment, StructureChangeType,
+    UiStructureChangedEvent, UiPropertyChangedEvent, UiFocusChangedEvent, EventMetadata, ElementContext, BrowserContext,
+    WindowChange, WindowGeometryEvent, DisplayChange, DisplayEvent,
 };
 pub use recorder::*;
 pub use error::*;
+pub use flow::{FlowEdge, FlowGraph, FlowNode};
+pub use intent::{
+    ClickRecognizer, ClipboardRecognizer, HotkeyRecognizer, Intent, IntentEngine, IntentRecognizer,
+    MenuItemRecognizer, TextEntryRecognizer,
+};
+pub use layout::{map_position, WindowSnapshot, WindowState, WindowTracker, WindowUpdate};
+pub use playback::{PlaybackConfig, PlaybackStep, PlaybackWorkflow};
+pub use redaction::{RedactionConfig, Redactor, REDACTED};
+pub use audio::{AudioConfig, AudioSource, AudioTrack};
+pub use screenshot::{EventScreenshot, ScreenshotConfig, ScreenshotRegion, ScreenshotStorage};
+pub use sink::{
+    read_jsonl, ChannelSink, CustomSink, FileSink, RecorderSink, RecordingHeader, RecordingLine,
+    SCHEMA_VERSION,
+};
 
@@ -0,0 +1,287 @@
+//! Playback timing of recorded workflows
+//!
+//! A recording keeps the user's pace: the seconds spent reading a dialog or
+//! looking away are as long in the recording as the clicks are short.
+//! [`PlaybackWorkflow`] retimes the events for playback. Idle gaps are
+//! collapsed, the timeline starts at zero, and each action gets a wait hint:
+//! how long the UI took to respond to it while recording, measured from the
+//! action to the last property, focus, window or clipboard change before the
+//! next action. The next action never runs before that, whatever the speed:
+//!
+//! ```
+//! use terminator_workflow_recorder::{IntentEngine, PlaybackConfig, PlaybackWorkflow, RecordedEvent};
+//!
+//! # let events: Vec<RecordedEvent> = Vec::new();
+//! let config = PlaybackConfig { speed: 4.0, ..PlaybackConfig::default() };
+//! let playback = PlaybackWorkflow::from_events("Create invoice", &events, &config);
+//! println!("{} ms instead of {} ms", playback.duration_ms, playback.recorded_duration_ms);
+//! let intents = IntentEngine::default().infer(&playback.recorded_events());
+//! ```
+//!
+//! Time spent paused, between a `Paused` and a `Resumed` marker, is dropped.
+
+use crate::events::{MarkerKind, MouseEventType, RecordedEvent, RecordedWorkflow, WorkflowEvent};
+use serde::{Deserialize, Serialize};
+
+/// How a recording is retimed
+#[derive(Debug, Clone)]
+pub struct PlaybackConfig {
+    /// Playback speed relative to the recording; wait hints are not scaled
+    pub speed: f64,
+    /// Longest gap between two events after scaling (milliseconds)
+    pub max_idle_ms: u64,
+    /// Changes later than this after an action are not counted as its
+    /// response (milliseconds)
+    pub response_window_ms: u64,
+}
+
+impl Default for PlaybackConfig {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            max_idle_ms: 1000,
+            response_window_ms: 5000,
+        }
+    }
+}
+
+/// A recorded event placed on the playback timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaybackStep {
+    /// The event, its timestamp replaced by the time since the start of
+    /// playback (milliseconds)
+    pub event: RecordedEvent,
+    /// Time since the previous step
+    pub delay_ms: u64,
+    /// Time the UI took to respond to the event while recording, and the
+    /// least the next action waits for. Zero for events that are not actions
+    pub wait_hint_ms: u64,
+}
+
+/// A recording retimed for playback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaybackWorkflow {
+    pub name: String,
+    pub steps: Vec<PlaybackStep>,
+    /// Time from the first to the last event as recorded
+    pub recorded_duration_ms: u64,
+    /// Time from the first to the last step
+    pub duration_ms: u64,
+}
+
+impl PlaybackWorkflow {
+    /// Retime the events of `workflow`
+    pub fn from_workflow(workflow: &RecordedWorkflow, config: &PlaybackConfig) -> Self {
+        Self::from_events(workflow.name.clone(), &workflow.events, config)
+    }
+
+    /// Retime `events`, in recording order
+    pub fn from_events(name: impl Into<String>, events: &[RecordedEvent], config: &PlaybackConfig) -> Self {
+        let mut steps = Vec::with_capacity(events.len());
+        let mut previous: Option<u64> = None;
+        let mut paused = false;
+        let mut time = 0;
+        // Earliest time the next action may run
+        let mut ready_at = 0;
+
+        for (index, recorded) in events.iter().enumerate() {
+            let gap = match previous {
+                Some(previous) if !paused => recorded.timestamp.saturating_sub(previous),
+                _ => 0,
+            };
+            let mut at = time + scaled(gap, config.speed).min(config.max_idle_ms);
+            let mut wait_hint_ms = 0;
+            if is_action(&recorded.event) {
+                at = at.max(ready_at);
+                wait_hint_ms = response_time(events, index, config.response_window_ms);
+                ready_at = at + wait_hint_ms;
+            }
+            if let WorkflowEvent::Marker(marker) = &recorded.event {
+                match marker.kind {
+                    MarkerKind::Paused => paused = true,
+                    MarkerKind::Resumed => paused = false,
+                    MarkerKind::Segment => {}
+                }
+            }
+
+            steps.push(PlaybackStep {
+                event: RecordedEvent { timestamp: at, ..recorded.clone() },
+                delay_ms: at - time,
+                wait_hint_ms,
+            });
+            time = at;
+            previous = Some(recorded.timestamp);
+        }
+
+        let recorded_duration_ms = match (events.first(), events.last()) {
+            (Some(first), Some(last)) => last.timestamp.saturating_sub(first.timestamp),
+            _ => 0,
+        };
+        Self {
+            name: name.into(),
+            steps,
+            recorded_duration_ms,
+            duration_ms: time,
+        }
+    }
+
+    /// The events with their playback timestamps, e.g. for
+    /// [`IntentEngine::infer`](crate::IntentEngine::infer)
+    pub fn recorded_events(&self) -> Vec<RecordedEvent> {
+        self.steps.iter().map(|step| step.event.clone()).collect()
+    }
+
+    /// How many times faster than the recording playback runs
+    pub fn speedup(&self) -> f64 {
+        match self.duration_ms {
+            0 => 1.0,
+            duration => self.recorded_duration_ms as f64 / duration as f64,
+        }
+    }
+}
+
+fn scaled(gap: u64, speed: f64) -> u64 {
+    if speed > 0.0 {
+        (gap as f64 / speed).round() as u64
+    } else {
+        gap
+    }
+}
+
+/// Input playback performs
+fn is_action(event: &WorkflowEvent) -> bool {
+    match event {
+        WorkflowEvent::Mouse(mouse) => mouse.event_type != MouseEventType::Move,
+        WorkflowEvent::Keyboard(_) | WorkflowEvent::Hotkey(_) | WorkflowEvent::DragDrop(_) => true,
+        _ => false,
+    }
+}
+
+/// Changes the UI makes in response to input
+fn is_response(event: &WorkflowEvent) -> bool {
+    matches!(
+        event,
+        WorkflowEvent::UiPropertyChanged(_)
+            | WorkflowEvent::UiFocusChanged(_)
+            | WorkflowEvent::WindowGeometry(_)
+            | WorkflowEvent::Clipboard(_)
+            | WorkflowEvent::TextSelection(_)
+    )
+}
+
+/// Time from the action at `index` to the last response before the next
+/// action, within `window`
+fn response_time(events: &[RecordedEvent], index: usize, window: u64) -> u64 {
+    let start = events[index].timestamp;
+    let mut latest = 0;
+    for recorded in &events[index + 1..] {
+        let elapsed = recorded.timestamp.saturating_sub(start);
+        if is_action(&recorded.event) || elapsed > window {
+            break;
+        }
+        if is_response(&recorded.event) {
+            latest = elapsed;
+        }
+    }
+    latest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{
+        EventMetadata, HotkeyEvent, MarkerEvent, MouseButton, MouseEvent, Position, UiFocusChangedEvent,
+    };
+
+    fn mouse(event_type: MouseEventType) -> WorkflowEvent {
+        WorkflowEvent::Mouse(MouseEvent {
+            event_type,
+            button: MouseButton::Left,
+            position: Position { x: 10, y: 20 },
+            scroll_delta: None,
+            drag_start: None,
+            metadata: EventMetadata::empty(),
+        })
+    }
+
+    fn hotkey() -> WorkflowEvent {
+        WorkflowEvent::Hotkey(HotkeyEvent {
+            combination: "Ctrl+S".to_string(),
+            action: None,
+            is_global: false,
+            metadata: EventMetadata::empty(),
+        })
+    }
+
+    fn focus() -> WorkflowEvent {
+        WorkflowEvent::UiFocusChanged(UiFocusChangedEvent {
+            previous_element: None,
+            metadata: EventMetadata::empty(),
+        })
+    }
+
+    fn marker(kind: MarkerKind) -> WorkflowEvent {
+        WorkflowEvent::Marker(MarkerEvent { label: String::new(), kind })
+    }
+
+    fn recorded(events: Vec<(u64, WorkflowEvent)>) -> Vec<RecordedEvent> {
+        events
+            .into_iter()
+            .map(|(timestamp, event)| RecordedEvent { timestamp, event, screenshot: None })
+            .collect()
+    }
+
+    fn times(playback: &PlaybackWorkflow) -> Vec<u64> {
+        playback.steps.iter().map(|step| step.event.timestamp).collect()
+    }
+
+    #[test]
+    fn test_idle_gaps_are_collapsed_and_timeline_starts_at_zero() {
+        let events = recorded(vec![
+            (50_000, mouse(MouseEventType::Down)),
+            (50_100, mouse(MouseEventType::Up)),
+            (80_100, hotkey()),
+        ]);
+
+        let playback = PlaybackWorkflow::from_events("idle", &events, &PlaybackConfig::default());
+        assert_eq!(times(&playback), [0, 100, 1_100]);
+        assert_eq!(playback.steps[2].delay_ms, 1_000);
+        assert_eq!(playback.recorded_duration_ms, 30_100);
+        assert_eq!(playback.duration_ms, 1_100);
+        assert!(playback.speedup() > 27.0);
+    }
+
+    #[test]
+    fn test_next_action_waits_for_the_observed_response() {
+        let events = recorded(vec![
+            (1_000, mouse(MouseEventType::Click)),
+            (1_200, focus()),
+            (1_900, focus()),
+            (2_000, hotkey()),
+            (2_050, focus()),
+        ]);
+        let config = PlaybackConfig { speed: 10.0, ..PlaybackConfig::default() };
+
+        let playback = PlaybackWorkflow::from_events("response", &events, &config);
+        let hints: Vec<_> = playback.steps.iter().map(|step| step.wait_hint_ms).collect();
+        assert_eq!(hints, [900, 0, 0, 50, 0]);
+        // The focus changes are sped up, the hotkey still waits 900 ms
+        assert_eq!(times(&playback), [0, 20, 90, 900, 905]);
+    }
+
+    #[test]
+    fn test_late_changes_and_paused_time_are_ignored() {
+        let events = recorded(vec![
+            (0, hotkey()),
+            (100, marker(MarkerKind::Paused)),
+            (60_000, focus()),
+            (60_100, marker(MarkerKind::Resumed)),
+            (60_300, hotkey()),
+        ]);
+        let config = PlaybackConfig { max_idle_ms: 100_000, ..PlaybackConfig::default() };
+
+        let playback = PlaybackWorkflow::from_events("paused", &events, &config);
+        assert_eq!(playback.steps[0].wait_hint_ms, 0);
+        assert_eq!(times(&playback), [0, 100, 100, 100, 300]);
+    }
+}
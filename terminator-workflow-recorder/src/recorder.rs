@@ -1,15 +1,22 @@
+#[cfg(target_os = "windows")]
+use crate::audio::AudioCapture;
+use crate::audio::AudioConfig;
+use crate::events::SerializableRecordedWorkflow;
+use crate::redaction::{RedactionConfig, Redactor};
+use crate::screenshot::{ScreenshotCapturer, ScreenshotConfig};
 use crate::{
-    RecordedWorkflow,
-    WorkflowEvent, WorkflowRecorderError, Result
+    MarkerEvent, MarkerKind, RecordedEvent, RecordedWorkflow, RecorderSink, RecordingHeader,
+    RecordingLine, WorkflowEvent, WorkflowRecorderError, Result
 };
 use std::{
     path::Path,
     sync::{Arc, Mutex},
+    sync::atomic::{AtomicBool, Ordering},
     collections::HashSet,
 };
 use tokio::sync::broadcast;
 use tokio_stream::{Stream};
-use tracing::{info};
+use tracing::{info, warn};
 
 #[cfg(target_os = "windows")]
 mod windows;
@@ -26,9 +33,12 @@ pub struct WorkflowRecorderConfig {
     /// Whether to record keyboard events
     pub record_keyboard: bool,
     
-    /// Whether to record window events
+    /// Whether to record window moves, resizes, minimize/maximize and restore
     pub record_window: bool,
     
+    /// Whether to record displays being connected, disconnected, moved or rescaled
+    pub record_displays: bool,
+    
     /// Whether to capture UI element information
     pub capture_ui_elements: bool,
     
@@ -79,6 +89,25 @@ pub struct WorkflowRecorderConfig {
     
     /// Application/process names to ignore for UI events (case-insensitive)
     pub ignore_applications: HashSet<String>,
+    
+    /// Whether to keep recorded events in memory for `save`. Turn off for long
+    /// recordings that are streamed to sinks instead
+    pub buffer_events: bool,
+    
+    /// Capture a screenshot around clicks and the start of typing bursts.
+    /// Disabled by default
+    pub screenshots: Option<ScreenshotConfig>,
+    
+    /// Record narration from the microphone (or loopback) into a WAV file
+    /// referenced from the workflow. Disabled by default
+    pub audio: Option<AudioConfig>,
+    
+    /// What to remove from events before they are recorded. Keys typed into
+    /// password fields are redacted by default
+    pub redaction: RedactionConfig,
+    
+    /// Record the URL and title of the browser tab for events in a browser
+    pub record_browser_context: bool,
 }
 
 impl Default for WorkflowRecorderConfig {
@@ -87,6 +116,7 @@ impl Default for WorkflowRecorderConfig {
             record_mouse: true,
             record_keyboard: true,
             record_window: true,
+            record_displays: true,
             capture_ui_elements: true,
             record_clipboard: true,
             record_text_selection: true,
@@ -298,6 +328,11 @@ impl Default for WorkflowRecorderConfig {
                 // Snipping Tool application.
                 "SnippingTool.exe".to_string(),
             ].into_iter().collect(),
+            buffer_events: true,
+            screenshots: None,
+            audio: None,
+            redaction: RedactionConfig::default(),
+            record_browser_context: true,
         }
     }
 }
@@ -313,9 +348,19 @@ pub struct WorkflowRecorder {
     /// The configuration
     config: WorkflowRecorderConfig,
     
+    /// Whether event capture is suspended
+    paused: Arc<AtomicBool>,
+    
+    /// Streaming outputs every event is written to
+    sinks: Arc<Mutex<Vec<Box<dyn RecorderSink>>>>,
+    
     /// The platform-specific recorder
     #[cfg(target_os = "windows")]
     windows_recorder: Option<WindowsRecorder>,
+    
+    /// The running audio capture
+    #[cfg(target_os = "windows")]
+    audio_capture: Option<AudioCapture>,
 }
 
 impl WorkflowRecorder {
@@ -328,11 +373,23 @@ impl WorkflowRecorder {
             workflow,
             event_tx,
             config,
+            paused: Arc::new(AtomicBool::new(false)),
+            sinks: Arc::new(Mutex::new(Vec::new())),
             #[cfg(target_os = "windows")]
             windows_recorder: None,
+            #[cfg(target_os = "windows")]
+            audio_capture: None,
         }
     }
 
+    /// Stream every recorded event to `sink` from now on. Add sinks before
+    /// `start` so they receive the header line
+    pub fn add_sink(&mut self, sink: impl RecorderSink + 'static) {
+        if let Ok(mut sinks) = self.sinks.lock() {
+            sinks.push(Box::new(sink));
+        }
+    }
+    
     /// Get a stream of events
     pub fn event_stream(&self) -> impl Stream<Item = WorkflowEvent> {
         let mut rx = self.event_tx.subscribe();
@@ -353,13 +410,36 @@ impl WorkflowRecorder {
             let event_tx = self.event_tx.clone();
             
             // Start the Windows recorder
-            let windows_recorder = WindowsRecorder::new(self.config.clone(), event_tx).await?;
+            let windows_recorder =
+                WindowsRecorder::new(self.config.clone(), event_tx, Arc::clone(&self.paused)).await?;
             self.windows_recorder = Some(windows_recorder);
             
+            if let Some(audio) = self.config.audio.clone() {
+                self.audio_capture = Some(AudioCapture::start(audio, Arc::clone(&self.paused))?);
+            }
+            
+            let header = {
+                let workflow = self.workflow.lock().map_err(|e| {
+                    WorkflowRecorderError::InitializationError(format!("Failed to lock workflow: {}", e))
+                })?;
+                RecordingHeader::new(workflow.name.clone(), workflow.start_time)
+            };
+            Self::write_to_sinks(&self.sinks, &RecordingLine::Header(header));
+            
+            let capturer = self
+                .config
+                .screenshots
+                .clone()
+                .map(ScreenshotCapturer::new)
+                .transpose()?;
+            
             // Start the event processing task
             let event_rx = self.event_tx.subscribe();
+            let sinks = Arc::clone(&self.sinks);
+            let buffer_events = self.config.buffer_events;
+            let redactor = Redactor::new(self.config.redaction.clone());
             tokio::spawn(async move {
-                Self::process_events(workflow, event_rx).await;
+                Self::process_events(workflow, sinks, buffer_events, redactor, capturer, event_rx).await;
             });
             
             Ok(())
@@ -382,16 +462,87 @@ impl WorkflowRecorder {
             if let Some(windows_recorder) = self.windows_recorder.take() {
                 windows_recorder.stop()?;
             }
+            if let Some(audio_capture) = self.audio_capture.take() {
+                let track = audio_capture.stop()?;
+                if let Ok(mut workflow) = self.workflow.lock() {
+                    workflow.audio = Some(track);
+                }
+            }
         }
         
         // Mark the workflow as finished
-        if let Ok(mut workflow) = self.workflow.lock() {
-            workflow.finish();
+        let footer = match self.workflow.lock() {
+            Ok(mut workflow) => {
+                workflow.finish();
+                workflow.end_time.map(|end_time| (end_time, workflow.audio.clone()))
+            }
+            Err(_) => None,
+        };
+        
+        if let Some((end_time, audio)) = footer {
+            Self::write_to_sinks(&self.sinks, &RecordingLine::Footer { end_time, audio });
+        }
+        if let Ok(mut sinks) = self.sinks.lock() {
+            for sink in sinks.iter_mut() {
+                if let Err(e) = sink.flush() {
+                    warn!("Failed to flush recorder sink: {}", e);
+                }
+            }
         }
         
         Ok(())
     }
     
+    /// Pause recording. Hooks stay installed, but input is dropped until
+    /// `resume` is called. Does nothing if already paused
+    pub fn pause(&self) {
+        if !self.paused.swap(true, Ordering::SeqCst) {
+            info!("Pausing workflow recording");
+            self.emit_marker(String::new(), MarkerKind::Paused);
+        }
+    }
+    
+    /// Resume a paused recording. Does nothing if not paused
+    pub fn resume(&self) {
+        if self.paused.swap(false, Ordering::SeqCst) {
+            info!("Resuming workflow recording");
+            self.emit_marker(String::new(), MarkerKind::Resumed);
+        }
+    }
+    
+    /// Whether recording is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+    
+    /// Start a new named segment. Events recorded from now on belong to it
+    /// until the next mark, see `RecordedWorkflow::segments`
+    pub fn mark(&self, label: impl Into<String>) {
+        let label = label.into();
+        info!("Marking workflow segment: {}", label);
+        self.emit_marker(label, MarkerKind::Segment);
+    }
+    
+    /// Send a marker through the event stream, or store it directly when
+    /// nothing is listening yet (e.g. before `start`)
+    fn emit_marker(&self, label: String, kind: MarkerKind) {
+        let event = WorkflowEvent::Marker(MarkerEvent { label, kind });
+        if let Err(broadcast::error::SendError(event)) = self.event_tx.send(event) {
+            if let Ok(mut workflow) = self.workflow.lock() {
+                workflow.add_event(event);
+            }
+        }
+    }
+    
+    /// A snapshot of the events recorded so far, e.g. to hand the workflow to
+    /// another process after `stop` without going through a file
+    pub fn workflow(&self) -> Result<SerializableRecordedWorkflow> {
+        let workflow = self.workflow.lock().map_err(|e| {
+            WorkflowRecorderError::RecordingError(format!("Failed to lock workflow: {}", e))
+        })?;
+        Ok((&*workflow).into())
+    }
+    
     /// Save the recorded workflow to a file
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         info!("Saving workflow recording to {:?}", path.as_ref());
@@ -407,14 +558,40 @@ impl WorkflowRecorder {
         Ok(())
     }
     
+    /// Write a line to every sink. A failing sink is logged and does not stop recording
+    fn write_to_sinks(sinks: &Mutex<Vec<Box<dyn RecorderSink>>>, line: &RecordingLine) {
+        if let Ok(mut sinks) = sinks.lock() {
+            for sink in sinks.iter_mut() {
+                if let Err(e) = sink.write_line(line) {
+                    warn!("Failed to write to recorder sink: {}", e);
+                }
+            }
+        }
+    }
+    
     /// Process events from the event receiver
     async fn process_events(
         workflow: Arc<Mutex<RecordedWorkflow>>,
+        sinks: Arc<Mutex<Vec<Box<dyn RecorderSink>>>>,
+        buffer_events: bool,
+        mut redactor: Redactor,
+        mut capturer: Option<ScreenshotCapturer>,
         mut event_rx: broadcast::Receiver<WorkflowEvent>,
     ) {
         while let Ok(event) = event_rx.recv().await {
-            if let Ok(mut workflow) = workflow.lock() {
-                workflow.add_event(event);
+            // Redact before anything is captured or written
+            let Some(event) = redactor.apply(event) else {
+                continue;
+            };
+            let mut recorded = RecordedEvent::now(event);
+            if let Some(capturer) = capturer.as_mut() {
+                recorded.screenshot = capturer.capture(&recorded).await;
+            }
+            Self::write_to_sinks(&sinks, &RecordingLine::from(&recorded));
+            if buffer_events {
+                if let Ok(mut workflow) = workflow.lock() {
+                    workflow.events.push(recorded);
+                }
             }
         }
     }
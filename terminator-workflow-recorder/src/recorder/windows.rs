@@ -1,7 +1,8 @@
+use crate::layout::{display_changes, WindowSnapshot, WindowState, WindowTracker};
 use crate::{
-    ClipboardAction, ClipboardEvent, EventMetadata, HotkeyEvent, KeyboardEvent, MouseButton,
-    MouseEvent, MouseEventType, Position, Result, UiFocusChangedEvent, UiPropertyChangedEvent,
-    WorkflowEvent, WorkflowRecorderConfig,
+    BrowserContext, ClipboardAction, ClipboardEvent, DisplayEvent, ElementContext, EventMetadata, HotkeyEvent,
+    KeyboardEvent, MouseButton, MouseEvent, MouseEventType, Position, Rect, Result, UiFocusChangedEvent,
+    UiPropertyChangedEvent, WindowGeometryEvent, WorkflowEvent, WorkflowRecorderConfig,
 };
 use arboard::Clipboard;
 use rdev::{Button, EventType, Key};
@@ -16,13 +17,17 @@ use terminator::{convert_uiautomation_element_to_terminator, UIElement};
 use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
 use uiautomation::UIAutomation;
-use windows::Win32::Foundation::{LPARAM, WPARAM};
+use windows::Win32::Foundation::{BOOL, HWND, LPARAM, RECT, WPARAM};
 use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED};
 use windows::Win32::System::Threading::GetCurrentThreadId;
 use windows::Win32::UI::WindowsAndMessaging::{
-    DispatchMessageW, GetMessageW, PostThreadMessageW, TranslateMessage, MSG, WM_QUIT,
+    DispatchMessageW, EnumWindows, GetMessageW, GetWindowRect, GetWindowTextW, GetWindowThreadProcessId,
+    IsIconic, IsWindowVisible, IsZoomed, PostThreadMessageW, TranslateMessage, MSG, WM_QUIT,
 };
 
+/// How often window geometry and the display layout are polled
+const LAYOUT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
 /// The Windows-specific recorder
 pub struct WindowsRecorder {
     /// The event sender
@@ -37,6 +42,9 @@ pub struct WindowsRecorder {
     /// Signal to stop the listener thread
     stop_indicator: Arc<AtomicBool>,
 
+    /// While set, hooks keep running but drop the events they see
+    paused: Arc<AtomicBool>,
+
     /// Modifier key states
     modifier_states: Arc<Mutex<ModifierStates>>,
 
@@ -72,6 +80,7 @@ impl WindowsRecorder {
     pub async fn new(
         config: WorkflowRecorderConfig,
         event_tx: broadcast::Sender<WorkflowEvent>,
+        paused: Arc<AtomicBool>,
     ) -> Result<Self> {
         info!("Initializing comprehensive Windows recorder");
         debug!("Recorder config: {:?}", config);
@@ -95,6 +104,7 @@ impl WindowsRecorder {
             config,
             last_mouse_pos,
             stop_indicator,
+            paused,
             modifier_states,
             last_clipboard_hash,
             last_mouse_move_time,
@@ -187,6 +197,11 @@ impl WindowsRecorder {
         // UI Automation event monitoring
         self.setup_ui_automation_events()?;
 
+        // Window geometry and display layout monitoring
+        if self.config.record_window || self.config.record_displays {
+            self.setup_layout_monitor()?;
+        }
+
         Ok(())
     }
 
@@ -196,12 +211,14 @@ impl WindowsRecorder {
         let last_mouse_pos = Arc::clone(&self.last_mouse_pos);
         let capture_ui_elements = self.config.capture_ui_elements;
         let stop_indicator_clone = Arc::clone(&self.stop_indicator);
+        let paused = Arc::clone(&self.paused);
         let modifier_states = Arc::clone(&self.modifier_states);
         let last_mouse_move_time = Arc::clone(&self.last_mouse_move_time);
         let hotkey_patterns = Arc::clone(&self.hotkey_patterns);
         let mouse_move_throttle = self.config.mouse_move_throttle_ms;
         let track_modifiers = self.config.track_modifier_states;
         let record_hotkeys = self.config.record_hotkeys;
+        let record_browser_context = self.config.record_browser_context;
 
         thread::spawn(move || {
             // PERFORMANCE: Create UIAutomation instance once outside the event loop
@@ -227,6 +244,29 @@ impl WindowsRecorder {
                     return;
                 }
 
+                // Keep key and modifier state current while paused, so keys
+                // held across a resume don't produce stale hotkeys
+                if paused.load(Ordering::SeqCst) {
+                    match event.event_type {
+                        EventType::KeyPress(key) => {
+                            let key_code = key_to_u32(&key);
+                            active_keys.insert(key_code, true);
+                            if track_modifiers {
+                                Self::update_modifier_states(&modifier_states, key_code, true);
+                            }
+                        }
+                        EventType::KeyRelease(key) => {
+                            let key_code = key_to_u32(&key);
+                            active_keys.remove(&key_code);
+                            if track_modifiers {
+                                Self::update_modifier_states(&modifier_states, key_code, false);
+                            }
+                        }
+                        _ => {}
+                    }
+                    return;
+                }
+
                 match event.event_type {
                     EventType::KeyPress(key) => {
                         let key_code = key_to_u32(&key);
@@ -263,13 +303,15 @@ impl WindowsRecorder {
                             None
                         };
 
-                        // Capture UI element for keyboard events if enabled
-                        let mut ui_element = None;
-                        if capture_ui_elements {
+                        // Capture the focused UI element for keyboard events if enabled
+                        let metadata = match &automation {
                             // Use a synchronous approach instead of async to avoid runtime issues
-                            ui_element = Self::get_focused_ui_element(automation.as_ref().unwrap());
-                            // ui_element = None
-                        }
+                            Some(automation) => Self::element_metadata(
+                                automation.get_focused_element().ok(),
+                                record_browser_context,
+                            ),
+                            None => EventMetadata::empty(),
+                        };
 
                         let keyboard_event = KeyboardEvent {
                             key_code,
@@ -280,9 +322,8 @@ impl WindowsRecorder {
                             win_pressed: modifiers.win,
                             character,
                             scan_code: None, // TODO: Get actual scan code
-                            metadata: EventMetadata {
-                                ui_element
-                            },
+                            redacted: false,
+                            metadata,
                         };
 
                         let _ = event_tx.send(WorkflowEvent::Keyboard(keyboard_event));
@@ -322,9 +363,8 @@ impl WindowsRecorder {
                             win_pressed: modifiers.win,
                             character: None,
                             scan_code: None,
-                            metadata: EventMetadata {
-                                ui_element
-                            },
+                            redacted: false,
+                            metadata: EventMetadata::with_ui_element(ui_element),
                         };
                         let _ = event_tx.send(WorkflowEvent::Keyboard(keyboard_event));
                     }
@@ -337,11 +377,14 @@ impl WindowsRecorder {
                                 _ => return,
                             };
 
-                            let mut ui_element = None;
-                            if capture_ui_elements {
-                                ui_element =
-                                    Self::get_focused_ui_element(automation.as_ref().unwrap());
-                            }
+                            // Resolve the element under the cursor, not the focused one
+                            let metadata = match &automation {
+                                Some(automation) => Self::element_metadata(
+                                    Self::get_ui_element_at(automation, x, y),
+                                    record_browser_context,
+                                ),
+                                None => EventMetadata::empty(),
+                            };
 
                             let mouse_event = MouseEvent {
                                 event_type: MouseEventType::Down,
@@ -349,9 +392,7 @@ impl WindowsRecorder {
                                 position: Position { x, y },
                                 scroll_delta: None,
                                 drag_start: None,
-                                metadata: EventMetadata {
-                                    ui_element
-                                },
+                                metadata,
                             };
                             let _ = event_tx.send(WorkflowEvent::Mouse(mouse_event));
                         }
@@ -365,11 +406,14 @@ impl WindowsRecorder {
                                 _ => return,
                             };
 
-                            let mut ui_element = None;
-                            if capture_ui_elements {
-                                ui_element =
-                                    Self::get_focused_ui_element(automation.as_ref().unwrap());
-                            }
+                            // Resolve the element under the cursor, not the focused one
+                            let metadata = match &automation {
+                                Some(automation) => Self::element_metadata(
+                                    Self::get_ui_element_at(automation, x, y),
+                                    record_browser_context,
+                                ),
+                                None => EventMetadata::empty(),
+                            };
 
                             let mouse_event = MouseEvent {
                                 event_type: MouseEventType::Up,
@@ -377,9 +421,7 @@ impl WindowsRecorder {
                                 position: Position { x, y },
                                 scroll_delta: None,
                                 drag_start: None,
-                                metadata: EventMetadata {
-                                    ui_element
-                                },
+                                metadata,
                             };
                             let _ = event_tx.send(WorkflowEvent::Mouse(mouse_event));
                         }
@@ -416,20 +458,20 @@ impl WindowsRecorder {
                                 position: Position { x, y },
                                 scroll_delta: None,
                                 drag_start: None,
-                                metadata: EventMetadata {
-                                    ui_element
-                                },
+                                metadata: EventMetadata::with_ui_element(ui_element),
                             };
                             let _ = event_tx.send(WorkflowEvent::Mouse(mouse_event));
                         }
                     }
                     EventType::Wheel { delta_x, delta_y } => {
                         if let Some((x, y)) = *last_mouse_pos.lock().unwrap() {
-                            let mut ui_element = None;
-                            if capture_ui_elements {
-                                ui_element = None
-                                    // Self::get_focused_ui_element(automation.as_ref().unwrap());
-                            }
+                            let metadata = match &automation {
+                                Some(automation) => Self::element_metadata(
+                                    Self::get_ui_element_at(automation, x, y),
+                                    record_browser_context,
+                                ),
+                                None => EventMetadata::empty(),
+                            };
 
                             let mouse_event = MouseEvent {
                                 event_type: MouseEventType::Wheel,
@@ -437,9 +479,7 @@ impl WindowsRecorder {
                                 position: Position { x, y },
                                 scroll_delta: Some((delta_x as i32, delta_y as i32)),
                                 drag_start: None,
-                                metadata: EventMetadata {
-                                    ui_element
-                                },
+                                metadata,
                             };
                             let _ = event_tx.send(WorkflowEvent::Mouse(mouse_event));
                         }
@@ -480,9 +520,7 @@ impl WindowsRecorder {
                     combination: format!("{:?}", pattern.keys), // TODO: Format properly
                     action: Some(pattern.action.clone()),
                     is_global: true,
-                    metadata: EventMetadata {
-                        ui_element: None
-                    }, // TODO: Pass UI element context from caller
+                    metadata: EventMetadata::empty(), // TODO: Pass UI element context from caller
                 });
             }
         }
@@ -493,6 +531,7 @@ impl WindowsRecorder {
     fn setup_clipboard_monitor(&self) -> Result<()> {
         let event_tx = self.event_tx.clone();
         let stop_indicator = Arc::clone(&self.stop_indicator);
+        let paused = Arc::clone(&self.paused);
         let last_hash = Arc::clone(&self.last_clipboard_hash);
         let max_content_length = self.config.max_clipboard_content_length;
         let capture_ui_elements = self.config.capture_ui_elements;
@@ -523,6 +562,12 @@ impl WindowsRecorder {
                         *last_hash_guard = Some(hash);
                         drop(last_hash_guard);
 
+                        // Content copied while paused is not replayed on resume
+                        if paused.load(Ordering::SeqCst) {
+                            thread::sleep(Duration::from_millis(100));
+                            continue;
+                        }
+
                         let (truncated_content, truncated) = if content.len() > max_content_length {
                             (content[..max_content_length].to_string(), true)
                         } else {
@@ -543,9 +588,7 @@ impl WindowsRecorder {
                             content_size: Some(content.len()),
                             format: Some("text".to_string()),
                             truncated,
-                            metadata: EventMetadata {
-                                ui_element
-                            },
+                            metadata: EventMetadata::with_ui_element(ui_element),
                         };
 
                         let _ = event_tx.send(WorkflowEvent::Clipboard(clipboard_event));
@@ -559,6 +602,125 @@ impl WindowsRecorder {
         Ok(())
     }
 
+    /// Set up polling of window geometry and the display layout, see [`crate::layout`]
+    fn setup_layout_monitor(&self) -> Result<()> {
+        let event_tx = self.event_tx.clone();
+        let stop_indicator = Arc::clone(&self.stop_indicator);
+        let paused = Arc::clone(&self.paused);
+        let record_window = self.config.record_window;
+        let record_displays = self.config.record_displays;
+        let capture_ui_elements = self.config.capture_ui_elements;
+        let ignore_window_titles: Vec<String> = self
+            .config
+            .ignore_window_titles
+            .iter()
+            .map(|title| title.to_lowercase())
+            .collect();
+
+        thread::spawn(move || {
+            let automation = if capture_ui_elements { UIAutomation::new().ok() } else { None };
+            let mut windows = WindowTracker::default();
+            let mut displays = terminator::monitor::list_monitors().unwrap_or_default();
+
+            while !stop_indicator.load(Ordering::SeqCst) {
+                thread::sleep(LAYOUT_POLL_INTERVAL);
+
+                if record_window {
+                    // The tracker keeps following windows while paused, so a
+                    // move made during the pause is not reported on resume
+                    for update in windows.update(Self::top_level_windows()) {
+                        let title = update.window.title.to_lowercase();
+                        if paused.load(Ordering::SeqCst)
+                            || ignore_window_titles.iter().any(|ignored| title.contains(ignored))
+                        {
+                            continue;
+                        }
+                        let element = automation.as_ref().and_then(|automation| {
+                            automation
+                                .element_from_handle(HWND(update.window.handle as *mut _).into())
+                                .ok()
+                        });
+                        let event = WindowGeometryEvent {
+                            change: update.change,
+                            title: update.window.title,
+                            process_id: update.window.process_id,
+                            bounds: update.window.bounds,
+                            previous_bounds: update.previous_bounds,
+                            metadata: Self::element_metadata(element, false),
+                        };
+                        let _ = event_tx.send(WorkflowEvent::WindowGeometry(event));
+                    }
+                }
+
+                if record_displays {
+                    let current = match terminator::monitor::list_monitors() {
+                        Ok(current) => current,
+                        Err(e) => {
+                            debug!("Failed to list displays: {}", e);
+                            continue;
+                        }
+                    };
+                    if !paused.load(Ordering::SeqCst) {
+                        for (change, display, previous) in display_changes(&displays, &current) {
+                            let event = DisplayEvent {
+                                change,
+                                display,
+                                previous,
+                                displays: current.clone(),
+                            };
+                            let _ = event_tx.send(WorkflowEvent::Display(event));
+                        }
+                    }
+                    displays = current;
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Visible, titled top-level windows
+    fn top_level_windows() -> Vec<WindowSnapshot> {
+        unsafe extern "system" fn visit(hwnd: HWND, lparam: LPARAM) -> BOOL {
+            let windows = unsafe { &mut *(lparam.0 as *mut Vec<WindowSnapshot>) };
+            if !unsafe { IsWindowVisible(hwnd) }.as_bool() {
+                return BOOL(1);
+            }
+            let mut title = [0u16; 512];
+            let length = unsafe { GetWindowTextW(hwnd, &mut title) };
+            let mut rect = RECT::default();
+            if length <= 0 || unsafe { GetWindowRect(hwnd, &mut rect) }.is_err() {
+                return BOOL(1);
+            }
+            let mut process_id = 0;
+            unsafe { GetWindowThreadProcessId(hwnd, Some(&mut process_id)) };
+            let state = if unsafe { IsIconic(hwnd) }.as_bool() {
+                WindowState::Minimized
+            } else if unsafe { IsZoomed(hwnd) }.as_bool() {
+                WindowState::Maximized
+            } else {
+                WindowState::Normal
+            };
+            windows.push(WindowSnapshot {
+                handle: hwnd.0 as isize,
+                title: String::from_utf16_lossy(&title[..length as usize]),
+                process_id,
+                bounds: Rect {
+                    x: rect.left,
+                    y: rect.top,
+                    width: rect.right - rect.left,
+                    height: rect.bottom - rect.top,
+                },
+                state,
+            });
+            BOOL(1)
+        }
+
+        let mut windows: Vec<WindowSnapshot> = Vec::new();
+        let _ = unsafe { EnumWindows(Some(visit), LPARAM(&mut windows as *mut Vec<WindowSnapshot> as isize)) };
+        windows
+    }
+
     /// Calculate hash for content comparison
     fn calculate_hash(content: &str) -> u64 {
         use std::collections::hash_map::DefaultHasher;
@@ -581,10 +743,63 @@ impl WindowsRecorder {
         }
     }
 
+    /// Get the UI element at the given screen position
+    fn get_ui_element_at(automation: &UIAutomation, x: i32, y: i32) -> Option<uiautomation::UIElement> {
+        match automation.element_from_point(uiautomation::types::Point::new(x, y)) {
+            Ok(element) => Some(element),
+            Err(e) => {
+                debug!("Failed to get element at ({}, {}): {}", x, y, e);
+                None
+            }
+        }
+    }
+
+    /// Build event metadata for a raw UI Automation element, reading its
+    /// automation id directly so the suggested selector can use it
+    fn element_metadata(element: Option<uiautomation::UIElement>, browser_context: bool) -> EventMetadata {
+        let Some(element) = element else {
+            return EventMetadata::empty();
+        };
+        let automation_id = element.get_automation_id().ok();
+        let is_password = element.is_password().unwrap_or(false);
+        let process_id = element.get_process_id().ok();
+        let ui_element = convert_uiautomation_element_to_terminator(element);
+        let mut element_context = ElementContext::new(&ui_element, automation_id);
+        element_context.is_password = is_password;
+        let browser_context = match process_id {
+            Some(pid) if browser_context => {
+                Self::process_name(pid).and_then(|name| BrowserContext::for_element(&ui_element, &name))
+            }
+            _ => None,
+        };
+        EventMetadata {
+            element_context: Some(element_context),
+            ui_element: Some(ui_element),
+            browser_context,
+        }
+    }
+
+    /// Process name for `pid`, cached per thread since every key press and
+    /// click asks and a process snapshot is expensive
+    fn process_name(pid: u32) -> Option<String> {
+        thread_local! {
+            static NAMES: std::cell::RefCell<HashMap<u32, String>> = std::cell::RefCell::new(HashMap::new());
+        }
+        NAMES.with(|names| {
+            if let Some(name) = names.borrow().get(&pid) {
+                return Some(name.clone());
+            }
+            let name = terminator::platforms::windows::get_process_name_by_pid(pid as i32).ok()?;
+            names.borrow_mut().insert(pid, name.clone());
+            Some(name)
+        })
+    }
+
     /// Set up UI Automation event handlers
     fn setup_ui_automation_events(&self) -> Result<()> {
         let event_tx = self.event_tx.clone();
         let stop_indicator = Arc::clone(&self.stop_indicator);
+        let paused = Arc::clone(&self.paused);
         let ui_automation_thread_id = Arc::clone(&self.ui_automation_thread_id);
         let record_structure_changes = self.config.record_ui_structure_changes;
         let record_property_changes = self.config.record_ui_property_changes;
@@ -742,8 +957,13 @@ impl WindowsRecorder {
 
                 // Spawn a thread to process the focus change data safely
                 let focus_event_tx_clone = focus_event_tx.clone();
+                let focus_paused = Arc::clone(&paused);
                 std::thread::spawn(move || {
                     while let Ok((element_name, ui_element)) = focus_rx.recv() {
+                        if focus_paused.load(Ordering::SeqCst) {
+                            continue;
+                        }
+
                         // Apply filtering
                         if WindowsRecorder::should_ignore_focus_event(
                             &element_name,
@@ -759,9 +979,7 @@ impl WindowsRecorder {
                         // Create a minimal UI element representation
                         let focus_event = UiFocusChangedEvent {
                             previous_element: None,
-                            metadata: EventMetadata {
-                                ui_element
-                            },
+                            metadata: EventMetadata::with_ui_element(ui_element),
                         };
 
                         if let Err(e) =
@@ -895,10 +1113,15 @@ impl WindowsRecorder {
 
                 // Spawn a thread to process the property change data safely
                 let property_event_tx_clone = property_event_tx.clone();
+                let property_paused = Arc::clone(&paused);
                 std::thread::spawn(move || {
                     while let Ok((element_name, property_name, value_string, ui_element)) =
                         property_rx.recv()
                     {
+                        if property_paused.load(Ordering::SeqCst) {
+                            continue;
+                        }
+
                         // Apply filtering
                         if WindowsRecorder::should_ignore_property_event(
                             &element_name,
@@ -919,9 +1142,7 @@ impl WindowsRecorder {
                             property_name: property_name.clone(),
                             old_value: None,
                             new_value: Some(value_string),
-                            metadata: EventMetadata {
-                                ui_element
-                            },
+                            metadata: EventMetadata::with_ui_element(ui_element),
                         };
 
                         if let Err(e) = property_event_tx_clone
@@ -0,0 +1,334 @@
+//! Privacy filters applied to events before they are recorded
+//!
+//! Recordings end up in files, sinks and bug reports, so what must not leave
+//! the machine is removed as events come in, before screenshots are taken or
+//! anything is written:
+//!
+//! - keys typed into password fields (UI Automation `IsPassword`) are
+//!   recorded as redacted key presses, without key code or character
+//! - text matching a scrub pattern is replaced with [`REDACTED`] in
+//!   clipboard contents, selected text and dragged text
+//! - events in denied applications, or outside the allowed ones, are dropped
+
+use crate::{WorkflowEvent, WorkflowRecorderError};
+use regex::Regex;
+use std::collections::HashSet;
+
+/// Replacement for scrubbed text
+pub const REDACTED: &str = "[REDACTED]";
+
+// Keys that move between fields rather than type into them, and reveal
+// nothing about a password: Tab, Enter, Escape
+const NAVIGATION_KEYS: &[u32] = &[0x09, 0x0D, 0x1B];
+
+/// What to remove from recordings, see [`crate::WorkflowRecorderConfig::redaction`]
+#[derive(Debug, Clone)]
+pub struct RedactionConfig {
+    /// Whether to redact keys typed into password fields
+    pub mask_password_fields: bool,
+
+    /// Patterns scrubbed from clipboard contents, selected text and dragged text
+    pub scrub_patterns: Vec<Regex>,
+
+    /// When set, only events in these applications are recorded
+    /// (case-insensitive substring of the application name). Events whose
+    /// application is unknown are dropped too
+    pub allowed_applications: Option<HashSet<String>>,
+
+    /// Events in these applications are never recorded (case-insensitive
+    /// substring of the application name)
+    pub denied_applications: HashSet<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            mask_password_fields: true,
+            scrub_patterns: Vec::new(),
+            allowed_applications: None,
+            denied_applications: HashSet::new(),
+        }
+    }
+}
+
+impl RedactionConfig {
+    /// Add a pattern to scrub, see [`RedactionConfig::scrub_patterns`]
+    pub fn scrub(mut self, pattern: &str) -> Result<Self, WorkflowRecorderError> {
+        let regex = Regex::new(pattern).map_err(|e| {
+            WorkflowRecorderError::InitializationError(format!(
+                "Invalid redaction pattern '{}': {}",
+                pattern, e
+            ))
+        })?;
+        self.scrub_patterns.push(regex);
+        Ok(self)
+    }
+
+    /// Scrub common secrets: payment card numbers, email addresses, and
+    /// bearer tokens or API keys
+    pub fn with_common_patterns(self) -> Self {
+        const COMMON_PATTERNS: &[&str] = &[
+            r"\b(?:\d[ -]?){13,19}\b",
+            r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}",
+            r"(?i)\b(?:bearer\s+[A-Za-z0-9._~+/=-]{16,}|(?:sk|pk|api|key|token)[_-][A-Za-z0-9_-]{16,})",
+        ];
+        COMMON_PATTERNS.iter().fold(self, |config, pattern| {
+            config.scrub(pattern).expect("common patterns are valid")
+        })
+    }
+}
+
+/// Applies a [`RedactionConfig`] to a stream of events. Keeps the state
+/// needed to also redact the release of a key pressed in a password field,
+/// and to drop the release of a key pressed in a filtered-out application
+#[derive(Debug)]
+pub struct Redactor {
+    config: RedactionConfig,
+    masked_keys: HashSet<u32>,
+    dropped_keys: HashSet<u32>,
+}
+
+impl Redactor {
+    pub fn new(config: RedactionConfig) -> Self {
+        Self {
+            config,
+            masked_keys: HashSet::new(),
+            dropped_keys: HashSet::new(),
+        }
+    }
+
+    /// The event as it may be recorded, or `None` when it must be dropped
+    pub fn apply(&mut self, mut event: WorkflowEvent) -> Option<WorkflowEvent> {
+        if !self.keep(&event) {
+            return None;
+        }
+        match &mut event {
+            WorkflowEvent::Keyboard(key) if self.config.mask_password_fields => {
+                let in_password_field = key
+                    .metadata
+                    .element_context
+                    .as_ref()
+                    .is_some_and(|context| context.is_password);
+                // Releases carry no element: mask those whose press was masked
+                let mask = if key.is_key_down {
+                    in_password_field && !NAVIGATION_KEYS.contains(&key.key_code)
+                } else {
+                    self.masked_keys.remove(&key.key_code)
+                };
+                if mask {
+                    if key.is_key_down {
+                        self.masked_keys.insert(key.key_code);
+                    }
+                    key.key_code = 0;
+                    key.character = None;
+                    key.scan_code = None;
+                    key.redacted = true;
+                }
+            }
+            WorkflowEvent::Clipboard(clipboard) => {
+                if let Some(content) = &mut clipboard.content {
+                    self.scrub(content);
+                }
+            }
+            WorkflowEvent::TextSelection(selection) => self.scrub(&mut selection.selected_text),
+            WorkflowEvent::DragDrop(drag) => {
+                if let Some(content) = &mut drag.content {
+                    self.scrub(content);
+                }
+            }
+            _ => {}
+        }
+        Some(event)
+    }
+
+    /// Releases carry no application: keep them only if their press was kept
+    fn keep(&mut self, event: &WorkflowEvent) -> bool {
+        match event {
+            WorkflowEvent::Keyboard(key) if !key.is_key_down => {
+                !self.dropped_keys.remove(&key.key_code)
+            }
+            WorkflowEvent::Keyboard(key) => {
+                let allowed = self.application_allowed(event);
+                if allowed {
+                    self.dropped_keys.remove(&key.key_code);
+                } else {
+                    self.dropped_keys.insert(key.key_code);
+                }
+                allowed
+            }
+            _ => self.application_allowed(event),
+        }
+    }
+
+    fn scrub(&self, text: &mut String) {
+        for pattern in &self.config.scrub_patterns {
+            if pattern.is_match(text) {
+                *text = pattern.replace_all(text, REDACTED).into_owned();
+            }
+        }
+    }
+
+    fn application_allowed(&self, event: &WorkflowEvent) -> bool {
+        let allowed = &self.config.allowed_applications;
+        let denied = &self.config.denied_applications;
+        if allowed.is_none() && denied.is_empty() {
+            return true;
+        }
+        let Some(metadata) = event.metadata() else {
+            // Markers and display changes belong to no application
+            return true;
+        };
+        let application = metadata
+            .element_context
+            .as_ref()
+            .and_then(|context| context.application.as_deref())
+            .map(str::to_lowercase);
+        let matches = |list: &HashSet<String>, name: &str| {
+            list.iter().any(|entry| name.contains(&entry.to_lowercase()))
+        };
+        match application {
+            Some(name) => {
+                !matches(denied, &name) && allowed.as_ref().is_none_or(|list| matches(list, &name))
+            }
+            None => allowed.is_none(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{ClipboardAction, ClipboardEvent, ElementContext, EventMetadata, KeyboardEvent};
+
+    fn metadata(application: &str, is_password: bool) -> EventMetadata {
+        EventMetadata {
+            ui_element: None,
+            element_context: Some(ElementContext {
+                role: "Edit".to_string(),
+                name: Some("Password".to_string()),
+                automation_id: None,
+                application: Some(application.to_string()),
+                process_id: Some(1),
+                is_password,
+                selector: "Edit:Password".to_string(),
+            }),
+            browser_context: None,
+        }
+    }
+
+    fn key(key_code: u32, is_key_down: bool, metadata: EventMetadata) -> WorkflowEvent {
+        WorkflowEvent::Keyboard(KeyboardEvent {
+            key_code,
+            is_key_down,
+            ctrl_pressed: false,
+            alt_pressed: false,
+            shift_pressed: false,
+            win_pressed: false,
+            character: char::from_u32(key_code),
+            scan_code: None,
+            redacted: false,
+            metadata,
+        })
+    }
+
+    fn clipboard(content: &str) -> WorkflowEvent {
+        WorkflowEvent::Clipboard(ClipboardEvent {
+            action: ClipboardAction::Copy,
+            content: Some(content.to_string()),
+            content_size: Some(content.len()),
+            format: None,
+            truncated: false,
+            metadata: metadata("Notepad", false),
+        })
+    }
+
+    fn keyboard(event: Option<WorkflowEvent>) -> KeyboardEvent {
+        match event {
+            Some(WorkflowEvent::Keyboard(key)) => key,
+            other => panic!("expected a keyboard event, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_masks_password_keys_and_their_release() {
+        let mut redactor = Redactor::new(RedactionConfig::default());
+
+        let press = keyboard(redactor.apply(key(0x41, true, metadata("Bank", true))));
+        assert!(press.redacted);
+        assert_eq!((press.key_code, press.character), (0, None));
+        let release = keyboard(redactor.apply(key(0x41, false, EventMetadata::empty())));
+        assert!(release.redacted);
+
+        // Tab out of the field, then type elsewhere
+        assert!(!keyboard(redactor.apply(key(0x09, true, metadata("Bank", true)))).redacted);
+        let other = keyboard(redactor.apply(key(0x42, true, metadata("Bank", false))));
+        assert_eq!((other.key_code, other.redacted), (0x42, false));
+    }
+
+    #[test]
+    fn test_scrubs_clipboard_content() {
+        let config = RedactionConfig::default().scrub(r"\d{4}-\d{4}").unwrap();
+        let mut redactor = Redactor::new(config);
+        match redactor.apply(clipboard("card 1234-5678 exp")) {
+            Some(WorkflowEvent::Clipboard(event)) => {
+                assert_eq!(event.content.as_deref(), Some("card [REDACTED] exp"));
+            }
+            other => panic!("expected a clipboard event, got {:?}", other),
+        }
+        assert!(RedactionConfig::default().scrub("(").is_err());
+    }
+
+    #[test]
+    fn test_common_patterns() {
+        let mut redactor = Redactor::new(RedactionConfig::default().with_common_patterns());
+        let Some(WorkflowEvent::Clipboard(event)) =
+            redactor.apply(clipboard("mail jane@example.com, card 4111 1111 1111 1111"))
+        else {
+            panic!("expected a clipboard event");
+        };
+        assert_eq!(event.content.as_deref(), Some("mail [REDACTED], card [REDACTED]"));
+    }
+
+    #[test]
+    fn test_application_lists() {
+        let config = RedactionConfig {
+            allowed_applications: Some(["notepad".to_string()].into_iter().collect()),
+            ..Default::default()
+        };
+        let mut redactor = Redactor::new(config);
+        assert!(redactor.apply(key(0x41, true, metadata("Notepad", false))).is_some());
+        assert!(redactor.apply(key(0x41, true, metadata("Bank", false))).is_none());
+        assert!(redactor.apply(key(0x41, true, EventMetadata::empty())).is_none());
+
+        let config = RedactionConfig {
+            denied_applications: ["bank".to_string()].into_iter().collect(),
+            ..Default::default()
+        };
+        let mut redactor = Redactor::new(config);
+        assert!(redactor.apply(key(0x41, true, metadata("My Bank", false))).is_none());
+        assert!(redactor.apply(key(0x41, true, EventMetadata::empty())).is_some());
+    }
+
+    #[test]
+    fn test_application_lists_follow_presses_to_their_release() {
+        let config = RedactionConfig {
+            denied_applications: ["bank".to_string()].into_iter().collect(),
+            ..Default::default()
+        };
+        let mut redactor = Redactor::new(config);
+        assert!(redactor.apply(key(0x41, true, metadata("My Bank", false))).is_none());
+        assert!(redactor.apply(key(0x41, false, EventMetadata::empty())).is_none());
+        assert!(redactor.apply(key(0x42, true, metadata("Notepad", false))).is_some());
+        assert!(redactor.apply(key(0x42, false, EventMetadata::empty())).is_some());
+
+        let config = RedactionConfig {
+            allowed_applications: Some(["notepad".to_string()].into_iter().collect()),
+            ..Default::default()
+        };
+        let mut redactor = Redactor::new(config);
+        assert!(redactor.apply(key(0x41, true, metadata("Notepad", false))).is_some());
+        assert!(redactor.apply(key(0x41, false, EventMetadata::empty())).is_some());
+        assert!(redactor.apply(key(0x42, true, metadata("Bank", false))).is_none());
+        assert!(redactor.apply(key(0x42, false, EventMetadata::empty())).is_none());
+    }
+}
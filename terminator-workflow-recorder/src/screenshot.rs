@@ -0,0 +1,381 @@
+//! Screenshots attached to significant events
+//!
+//! With [`WorkflowRecorderConfig::screenshots`](crate::WorkflowRecorderConfig::screenshots)
+//! set, the recorder captures a thumbnail when a mouse button goes down and
+//! when a burst of typing starts, so a recording can be reviewed visually or
+//! used as training data. Captures are throttled, cropped to a region of
+//! interest around the pointer or the focused element, scaled down and
+//! either embedded in the event as base64 or saved next to the recording.
+
+use crate::{MouseEventType, RecordedEvent, Rect, WorkflowEvent, WorkflowRecorderError};
+use base64::{engine::general_purpose, Engine as _};
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use terminator::{Desktop, ImageFormat, ScreenshotResult};
+use tracing::{debug, warn};
+
+/// Where screenshots are stored
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScreenshotStorage {
+    /// Base64 in the event itself
+    Embed,
+    /// As files in this directory, referenced by path from the event
+    Sidecar(PathBuf),
+}
+
+/// Part of the screen that is captured
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScreenshotRegion {
+    /// The whole monitor the event happened on
+    FullScreen,
+    /// A box of this size centered on the pointer, or on the focused element
+    /// for keyboard events
+    AroundPointer { width: u32, height: u32 },
+    /// The event's element plus this many pixels on each side. Falls back to
+    /// the whole monitor when the element has no bounds
+    Element { padding: u32 },
+}
+
+/// Screenshot capture settings
+#[derive(Debug, Clone)]
+pub struct ScreenshotConfig {
+    pub storage: ScreenshotStorage,
+    pub region: ScreenshotRegion,
+    pub format: ImageFormat,
+    /// Thumbnails wider than this are scaled down, keeping the aspect ratio
+    pub max_width: u32,
+    /// Minimum time between two captures (milliseconds)
+    pub min_interval_ms: u64,
+    /// Pause in typing after which the next key starts a new burst and gets a
+    /// capture (milliseconds)
+    pub keyboard_burst_gap_ms: u64,
+}
+
+impl Default for ScreenshotConfig {
+    fn default() -> Self {
+        Self {
+            storage: ScreenshotStorage::Embed,
+            region: ScreenshotRegion::AroundPointer { width: 800, height: 600 },
+            format: ImageFormat::Jpeg { quality: 70 },
+            max_width: 640,
+            min_interval_ms: 500,
+            keyboard_burst_gap_ms: 1000,
+        }
+    }
+}
+
+/// A screenshot taken for an event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventScreenshot {
+    /// MIME type, e.g. `image/jpeg`
+    pub mime_type: String,
+
+    /// Size of the stored image
+    pub width: u32,
+    pub height: u32,
+
+    /// Captured part of the screen, in screen coordinates
+    pub region: Rect,
+
+    /// Base64 image data, for embedded screenshots
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+
+    /// Image file, for sidecar screenshots
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+/// Decides which events get a screenshot
+#[derive(Debug, Default)]
+pub(crate) struct ScreenshotThrottle {
+    last_capture: Option<u64>,
+    last_key: Option<u64>,
+}
+
+impl ScreenshotThrottle {
+    /// Whether `event` should be captured. Call for every event, in order
+    pub(crate) fn should_capture(&mut self, config: &ScreenshotConfig, event: &RecordedEvent) -> bool {
+        let significant = match &event.event {
+            WorkflowEvent::Mouse(mouse) => matches!(
+                mouse.event_type,
+                MouseEventType::Down | MouseEventType::Click | MouseEventType::DoubleClick | MouseEventType::RightClick
+            ),
+            WorkflowEvent::Keyboard(key) if key.is_key_down => {
+                let burst_start = self
+                    .last_key
+                    .is_none_or(|last| event.timestamp.saturating_sub(last) >= config.keyboard_burst_gap_ms);
+                self.last_key = Some(event.timestamp);
+                burst_start
+            }
+            _ => false,
+        };
+        if !significant {
+            return false;
+        }
+        if self
+            .last_capture
+            .is_some_and(|last| event.timestamp.saturating_sub(last) < config.min_interval_ms)
+        {
+            return false;
+        }
+        self.last_capture = Some(event.timestamp);
+        true
+    }
+}
+
+/// Screen area to capture for `event` with `region`, clamped to `monitor`.
+/// `element` is the bounds of the event's element, if known
+pub(crate) fn region_of_interest(
+    region: ScreenshotRegion,
+    event: &WorkflowEvent,
+    element: Option<&Rect>,
+    monitor: &Rect,
+) -> Rect {
+    let focus = match event {
+        WorkflowEvent::Mouse(mouse) => Some((mouse.position.x, mouse.position.y)),
+        _ => element.map(|e| (e.x + e.width / 2, e.y + e.height / 2)),
+    };
+    let wanted = match (region, focus, element) {
+        (ScreenshotRegion::AroundPointer { width, height }, Some((x, y)), _) => Rect {
+            x: x - width as i32 / 2,
+            y: y - height as i32 / 2,
+            width: width as i32,
+            height: height as i32,
+        },
+        (ScreenshotRegion::Element { padding }, _, Some(element)) => Rect {
+            x: element.x - padding as i32,
+            y: element.y - padding as i32,
+            width: element.width + 2 * padding as i32,
+            height: element.height + 2 * padding as i32,
+        },
+        _ => return monitor.clone(),
+    };
+
+    // Shift a box that sticks out back inside, and shrink it if it is too big
+    let width = wanted.width.min(monitor.width);
+    let height = wanted.height.min(monitor.height);
+    let x = wanted.x.clamp(monitor.x, monitor.x + monitor.width - width);
+    let y = wanted.y.clamp(monitor.y, monitor.y + monitor.height - height);
+    Rect { x, y, width, height }
+}
+
+/// Takes the screenshots for a recording
+pub(crate) struct ScreenshotCapturer {
+    desktop: Desktop,
+    config: ScreenshotConfig,
+    throttle: ScreenshotThrottle,
+    sequence: u64,
+}
+
+impl ScreenshotCapturer {
+    pub(crate) fn new(config: ScreenshotConfig) -> crate::Result<Self> {
+        if let ScreenshotStorage::Sidecar(dir) = &config.storage {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                WorkflowRecorderError::InitializationError(format!(
+                    "Failed to create screenshot directory {:?}: {}",
+                    dir, e
+                ))
+            })?;
+        }
+        let desktop = Desktop::new(false, false).map_err(|e| {
+            WorkflowRecorderError::InitializationError(format!("Failed to initialize screen capture: {}", e))
+        })?;
+        Ok(Self {
+            desktop,
+            config,
+            throttle: ScreenshotThrottle::default(),
+            sequence: 0,
+        })
+    }
+
+    /// Screenshot for `event` if it is significant and not throttled.
+    /// Failures are logged and leave the event without a screenshot
+    pub(crate) async fn capture(&mut self, event: &RecordedEvent) -> Option<EventScreenshot> {
+        if !self.throttle.should_capture(&self.config, event) {
+            return None;
+        }
+        match self.try_capture(event).await {
+            Ok(screenshot) => Some(screenshot),
+            Err(e) => {
+                warn!("Failed to capture screenshot for event: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn try_capture(&mut self, event: &RecordedEvent) -> Result<EventScreenshot, String> {
+        let element = event_metadata_bounds(&event.event);
+        let monitors = self.desktop.monitors().map_err(|e| e.to_string())?;
+        let point = match &event.event {
+            WorkflowEvent::Mouse(mouse) => Some((mouse.position.x as f64, mouse.position.y as f64)),
+            _ => element.as_ref().map(|e| ((e.x + e.width / 2) as f64, (e.y + e.height / 2) as f64)),
+        };
+        let monitor = point
+            .and_then(|(x, y)| monitors.iter().find(|m| m.bounds.contains_point(x, y)))
+            .or_else(|| monitors.iter().find(|m| m.is_primary))
+            .ok_or("No monitor to capture")?;
+        let monitor_rect = Rect {
+            x: monitor.bounds.x as i32,
+            y: monitor.bounds.y as i32,
+            width: monitor.bounds.width as i32,
+            height: monitor.bounds.height as i32,
+        };
+        let region = region_of_interest(self.config.region, &event.event, element.as_ref(), &monitor_rect);
+
+        let shot = self
+            .desktop
+            .capture_monitor_by_name(&monitor.name)
+            .await
+            .map_err(|e| e.to_string())?;
+        let image = RgbaImage::from_raw(shot.width, shot.height, shot.image_data)
+            .ok_or("Screenshot buffer has the wrong size")?;
+        let mut cropped = imageops::crop_imm(
+            &image,
+            (region.x - monitor_rect.x).max(0) as u32,
+            (region.y - monitor_rect.y).max(0) as u32,
+            region.width.max(1) as u32,
+            region.height.max(1) as u32,
+        )
+        .to_image();
+        if cropped.width() > self.config.max_width && self.config.max_width > 0 {
+            let height = (cropped.height() as u64 * self.config.max_width as u64 / cropped.width() as u64).max(1);
+            cropped = imageops::resize(&cropped, self.config.max_width, height as u32, FilterType::Triangle);
+        }
+
+        let thumbnail = ScreenshotResult {
+            width: cropped.width(),
+            height: cropped.height(),
+            image_data: cropped.into_raw(),
+        };
+        let encoded = thumbnail.encode(self.config.format).map_err(|e| e.to_string())?;
+        let mut screenshot = EventScreenshot {
+            mime_type: encoded.format.mime_type().to_string(),
+            width: encoded.width,
+            height: encoded.height,
+            region,
+            data: None,
+            path: None,
+        };
+        match &self.config.storage {
+            ScreenshotStorage::Embed => {
+                screenshot.data = Some(general_purpose::STANDARD.encode(&encoded.data));
+            }
+            ScreenshotStorage::Sidecar(dir) => {
+                self.sequence += 1;
+                let path = dir.join(format!(
+                    "{}-{:05}.{}",
+                    event.timestamp,
+                    self.sequence,
+                    encoded.format.extension()
+                ));
+                std::fs::write(&path, &encoded.data)
+                    .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+                screenshot.path = Some(path.to_string_lossy().into_owned());
+            }
+        }
+        debug!(bytes = encoded.data.len(), "Captured event screenshot");
+        Ok(screenshot)
+    }
+}
+
+/// Bounds of the element attached to an event, in screen coordinates
+fn event_metadata_bounds(event: &WorkflowEvent) -> Option<Rect> {
+    let metadata = match event {
+        WorkflowEvent::Mouse(e) => &e.metadata,
+        WorkflowEvent::Keyboard(e) => &e.metadata,
+        _ => return None,
+    };
+    let (x, y, width, height) = metadata.ui_element.as_ref()?.bounds().ok()?;
+    Some(Rect {
+        x: x as i32,
+        y: y as i32,
+        width: width as i32,
+        height: height as i32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventMetadata, KeyboardEvent, MouseButton, MouseEvent, Position};
+
+    fn at(timestamp: u64, event: WorkflowEvent) -> RecordedEvent {
+        RecordedEvent {
+            timestamp,
+            event,
+            screenshot: None,
+        }
+    }
+
+    fn mouse(event_type: MouseEventType, x: i32, y: i32) -> WorkflowEvent {
+        WorkflowEvent::Mouse(MouseEvent {
+            event_type,
+            button: MouseButton::Left,
+            position: Position { x, y },
+            scroll_delta: None,
+            drag_start: None,
+            metadata: EventMetadata::empty(),
+        })
+    }
+
+    fn key_down() -> WorkflowEvent {
+        WorkflowEvent::Keyboard(KeyboardEvent {
+            key_code: 0x41,
+            is_key_down: true,
+            ctrl_pressed: false,
+            alt_pressed: false,
+            shift_pressed: false,
+            win_pressed: false,
+            character: Some('a'),
+            scan_code: None,
+            redacted: false,
+            metadata: EventMetadata::empty(),
+        })
+    }
+
+    #[test]
+    fn test_throttle_captures_clicks_and_burst_starts() {
+        let config = ScreenshotConfig::default();
+        let mut throttle = ScreenshotThrottle::default();
+        let mut captured = |timestamp, event| throttle.should_capture(&config, &at(timestamp, event));
+
+        assert!(captured(1_000, mouse(MouseEventType::Down, 5, 5)));
+        assert!(!captured(1_050, mouse(MouseEventType::Up, 5, 5)));
+        // Within min_interval_ms of the click
+        assert!(!captured(1_200, mouse(MouseEventType::Down, 5, 5)));
+        // First key of a burst, then the rest of it
+        assert!(captured(2_000, key_down()));
+        assert!(!captured(2_100, key_down()));
+        assert!(!captured(2_900, key_down()));
+        // Typing resumes after a pause
+        assert!(captured(4_000, key_down()));
+    }
+
+    #[test]
+    fn test_region_of_interest_stays_on_monitor() {
+        let monitor = Rect { x: 0, y: 0, width: 1920, height: 1080 };
+        let region = ScreenshotRegion::AroundPointer { width: 800, height: 600 };
+
+        let centered = region_of_interest(region, &mouse(MouseEventType::Down, 960, 540), None, &monitor);
+        assert_eq!((centered.x, centered.y, centered.width, centered.height), (560, 240, 800, 600));
+
+        let corner = region_of_interest(region, &mouse(MouseEventType::Down, 10, 1070), None, &monitor);
+        assert_eq!((corner.x, corner.y), (0, 480));
+
+        let element = Rect { x: 100, y: 100, width: 200, height: 30 };
+        let padded = region_of_interest(
+            ScreenshotRegion::Element { padding: 20 },
+            &key_down(),
+            Some(&element),
+            &monitor,
+        );
+        assert_eq!((padded.x, padded.y, padded.width, padded.height), (80, 80, 240, 70));
+
+        // Keyboard event without an element: whole monitor
+        let full = region_of_interest(region, &key_down(), None, &monitor);
+        assert_eq!((full.width, full.height), (1920, 1080));
+    }
+}
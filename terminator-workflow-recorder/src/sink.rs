@@ -0,0 +1,216 @@
+//! Streaming output for recordings
+//!
+//! Besides buffering the whole workflow in memory, the recorder can write every
+//! event to one or more sinks as it happens. The JSONL format written by
+//! [`FileSink`] has one JSON object per line: a header carrying the schema
+//! version, one line per event, and a footer when recording stops. Files are
+//! flushed after every line, so they can be tailed while recording and are
+//! readable up to the last event even if the recorder is killed.
+
+use crate::events::{SerializableRecordedEvent, SerializableRecordedWorkflow};
+use crate::{AudioTrack, RecordedEvent, Result, WorkflowRecorderError};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufWriter, Write};
+use std::path::Path;
+use tokio::sync::mpsc;
+
+/// Version of the JSONL recording format. Bumped on incompatible changes
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// First line of a JSONL recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingHeader {
+    /// The JSONL format version, see [`SCHEMA_VERSION`]
+    pub schema_version: u32,
+
+    /// The name of the workflow
+    pub name: String,
+
+    /// The start time of the recording (milliseconds since epoch)
+    pub start_time: u64,
+
+    /// Version of the recorder crate that wrote the file
+    pub recorder_version: String,
+}
+
+impl RecordingHeader {
+    /// Create a header for the current schema version
+    pub fn new(name: String, start_time: u64) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            name,
+            start_time,
+            recorder_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// One line of a JSONL recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordingLine {
+    /// Written once, before any event
+    Header(RecordingHeader),
+
+    /// A recorded event
+    Event(Box<SerializableRecordedEvent>),
+
+    /// Written when recording stops
+    Footer {
+        /// The end time of the recording (milliseconds since epoch)
+        end_time: u64,
+
+        /// The narration recorded alongside the events, if any
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        audio: Option<AudioTrack>,
+    },
+}
+
+impl From<&RecordedEvent> for RecordingLine {
+    fn from(event: &RecordedEvent) -> Self {
+        RecordingLine::Event(Box::new(event.into()))
+    }
+}
+
+/// Destination for recording lines as they are produced
+pub trait RecorderSink: Send {
+    /// Write one line. Called from the recorder's event task, so it should not block for long
+    fn write_line(&mut self, line: &RecordingLine) -> Result<()>;
+
+    /// Flush buffered output. Called after the footer is written
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes JSONL to a file, flushing after every line
+pub struct FileSink {
+    writer: BufWriter<File>,
+}
+
+impl FileSink {
+    /// Create (or truncate) the file at `path`
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+        })
+    }
+}
+
+impl RecorderSink for FileSink {
+    fn write_line(&mut self, line: &RecordingLine) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, line)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Forwards lines to an in-process consumer
+pub struct ChannelSink {
+    tx: mpsc::UnboundedSender<RecordingLine>,
+}
+
+impl ChannelSink {
+    /// Create a sink and the receiver its lines arrive on
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<RecordingLine>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (Self { tx }, rx)
+    }
+}
+
+impl RecorderSink for ChannelSink {
+    fn write_line(&mut self, line: &RecordingLine) -> Result<()> {
+        self.tx.send(line.clone()).map_err(|_| {
+            WorkflowRecorderError::RecordingError("Channel sink receiver was dropped".to_string())
+        })
+    }
+}
+
+/// Hands lines to a closure, e.g. to upload them or write another format
+pub struct CustomSink<F> {
+    write: F,
+}
+
+impl<F> CustomSink<F>
+where
+    F: FnMut(&RecordingLine) -> Result<()> + Send,
+{
+    pub fn new(write: F) -> Self {
+        Self { write }
+    }
+}
+
+impl<F> RecorderSink for CustomSink<F>
+where
+    F: FnMut(&RecordingLine) -> Result<()> + Send,
+{
+    fn write_line(&mut self, line: &RecordingLine) -> Result<()> {
+        (self.write)(line)
+    }
+}
+
+/// Read a JSONL recording back into a workflow. A missing footer (recording
+/// still running, or killed) leaves `end_time` empty, and a last line cut off
+/// mid-write is skipped
+pub fn read_jsonl<R: BufRead>(reader: R) -> Result<SerializableRecordedWorkflow> {
+    let mut lines = reader.lines().peekable();
+    let header = match lines.next() {
+        Some(line) => match serde_json::from_str::<RecordingLine>(&line?)? {
+            RecordingLine::Header(header) => header,
+            _ => {
+                return Err(WorkflowRecorderError::RecordingError(
+                    "Recording does not start with a header".to_string(),
+                ))
+            }
+        },
+        None => {
+            return Err(WorkflowRecorderError::RecordingError(
+                "Recording is empty".to_string(),
+            ))
+        }
+    };
+    if header.schema_version != SCHEMA_VERSION {
+        return Err(WorkflowRecorderError::UnsupportedSchemaVersion(
+            header.schema_version,
+        ));
+    }
+
+    let mut workflow = SerializableRecordedWorkflow {
+        name: header.name,
+        start_time: header.start_time,
+        end_time: None,
+        events: Vec::new(),
+        audio: None,
+    };
+    while let Some(line) = lines.next() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let parsed = match serde_json::from_str::<RecordingLine>(&line) {
+            Ok(parsed) => parsed,
+            Err(_) if lines.peek().is_none() => break,
+            Err(e) => return Err(e.into()),
+        };
+        match parsed {
+            RecordingLine::Event(event) => workflow.events.push(*event),
+            RecordingLine::Footer { end_time, audio } => {
+                workflow.end_time = Some(end_time);
+                workflow.audio = audio;
+            }
+            RecordingLine::Header(_) => {
+                return Err(WorkflowRecorderError::RecordingError(
+                    "Unexpected second header in recording".to_string(),
+                ))
+            }
+        }
+    }
+    Ok(workflow)
+}
@@ -0,0 +1,111 @@
+//! How an action reaches the application
+//!
+//! A click can be delivered three ways, each failing for different
+//! applications:
+//!
+//! - [`ActionStrategy::UiaPattern`] asks the accessibility API to perform
+//!   the element's action (UIA Invoke on Windows, AXPress on macOS). The
+//!   pointer doesn't move and the window may be covered, but custom-drawn
+//!   controls often don't implement it.
+//! - [`ActionStrategy::SendInputCursor`] moves the real cursor to the
+//!   element and clicks, like a user. Works on anything visible, but needs
+//!   the element unobstructed and takes the pointer away from the user.
+//! - [`ActionStrategy::PostMessage`] posts mouse messages to the element's
+//!   window (Windows only). Reaches windows in the background without
+//!   moving the cursor, but only classic Win32 controls handle them.
+//!
+//! `UIElement::click` tries [`ActionStrategy::ESCALATION`] in order. Choose
+//! one with `UIElement::click_with_strategy`, or give your own order to
+//! `UIElement::click_escalating`. Either way the result reports the
+//! strategy that worked and why the ones before it failed:
+//!
+//! ```no_run
+//! # use terminator::{ActionStrategy, AutomationError, UIElement};
+//! # fn run(button: &UIElement) -> Result<(), AutomationError> {
+//! let result = button.click_escalating(&ActionStrategy::ESCALATION)?;
+//! println!("clicked with {:?} after {} failed attempts", result.strategy, result.attempts.len());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A strategy succeeds when the platform accepts the input; whether the
+//! application reacted is checked by `UIElement::click_and_expect`. Posted
+//! messages are accepted as soon as they are queued, even by windows that
+//! ignore them, so escalation tries them last.
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::AutomationError;
+
+/// A way of delivering an action to the application
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionStrategy {
+    /// The element's own action through the accessibility API
+    UiaPattern,
+    /// Real input at the element's position, moving the cursor
+    SendInputCursor,
+    /// Mouse messages posted to the element's window. Windows only
+    PostMessage,
+}
+
+impl ActionStrategy {
+    /// Order `UIElement::click` tries: the element's own action, then real
+    /// input, then posted messages, which cannot tell whether they worked
+    pub const ESCALATION: [ActionStrategy; 3] = [
+        ActionStrategy::UiaPattern,
+        ActionStrategy::SendInputCursor,
+        ActionStrategy::PostMessage,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ActionStrategy::UiaPattern => "uia_pattern",
+            ActionStrategy::SendInputCursor => "send_input_cursor",
+            ActionStrategy::PostMessage => "post_message",
+        }
+    }
+}
+
+impl std::fmt::Display for ActionStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ActionStrategy {
+    type Err = AutomationError;
+
+    /// Accepts the snake_case names, ignoring case, and the short forms
+    /// `pattern`, `input` and `message`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "uia_pattern" | "pattern" => Ok(ActionStrategy::UiaPattern),
+            "send_input_cursor" | "send_input" | "input" => Ok(ActionStrategy::SendInputCursor),
+            "post_message" | "message" => Ok(ActionStrategy::PostMessage),
+            other => Err(AutomationError::InvalidArgument(format!(
+                "Unknown action strategy '{}': use uia_pattern, send_input_cursor or post_message",
+                other
+            ))),
+        }
+    }
+}
+
+/// A strategy that was tried and failed before another one worked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyAttempt {
+    pub strategy: ActionStrategy,
+    pub error: String,
+    pub duration_ms: u64,
+}
+
+/// The error of an escalation where every strategy failed
+pub(crate) fn escalation_failed(attempts: &[StrategyAttempt]) -> AutomationError {
+    let tried: Vec<String> = attempts
+        .iter()
+        .map(|attempt| format!("{}: {}", attempt.strategy, attempt.error))
+        .collect();
+    AutomationError::PlatformError(format!("Every action strategy failed ({})", tried.join("; ")))
+}
@@ -0,0 +1,241 @@
+//! Per-application automation quirks
+//!
+//! Applications differ in what automating them reliably takes: Office
+//! builds its ribbon and panes slowly, browsers keep the page in a
+//! `Document` element below layers of toolbar, Java applications expose
+//! deep trees whose accessibility actions often do nothing. An
+//! [`AppProfile`] records such quirks for the processes it names: a default
+//! timeout, a search depth, the element searches start in and the order of
+//! click strategies.
+//!
+//! Profiles apply to locators searching within an application's element, as
+//! [`UIElement::locator`] and [`Locator::within`](crate::Locator::within) do,
+//! unless a timeout is set on the locator itself. The profile is looked up
+//! the first time the locator needs it. Built-in profiles for Office,
+//! Chromium browsers, Firefox and Java are off until
+//! [`Desktop::use_builtin_app_profiles`](crate::Desktop::use_builtin_app_profiles)
+//! turns them on; profiles registered at runtime take precedence over them:
+//!
+//! ```no_run
+//! # use terminator::{AppProfile, AutomationError, Desktop};
+//! # use std::time::Duration;
+//! # async fn run(desktop: &Desktop) -> Result<(), AutomationError> {
+//! desktop.register_app_profile(
+//!     AppProfile::new("sap", &["saplogon"]).with_timeout(Duration::from_secs(20)),
+//! );
+//! let app = desktop.application("SAP Logon")?;
+//! let button = app.locator("name:Log On")?.first(None).await?;
+//! if let Some(profile) = AppProfile::for_element(&button) {
+//!     profile.click(&button)?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Java applications without the Access Bridge answer UI Automation with an
+//! empty window; the Windows engine then falls back to MSAA on its own.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tracing::debug;
+
+use crate::{ActionStrategy, AutomationError, ClickResult, UIElement};
+
+/// Profiles registered at runtime, the latest first
+static REGISTERED: RwLock<Vec<AppProfile>> = RwLock::new(Vec::new());
+/// Whether [`AppProfile::builtin`] profiles apply
+static BUILTIN: AtomicBool = AtomicBool::new(false);
+
+/// How to automate the processes of one application
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppProfile {
+    /// Registering a profile replaces the one with the same name
+    pub name: String,
+    /// Process names, without extension and ignoring case, e.g. `winword`
+    pub processes: Vec<String>,
+    /// Default wait for locators within the application
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Depth `Locator::all` searches to unless given one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_depth: Option<usize>,
+    /// Selector of the element searches within a window start in, e.g.
+    /// `role:Document` for the page of a browser
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_root: Option<String>,
+    /// Order [`AppProfile::click`] tries strategies in; empty for the
+    /// default click
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub click_strategies: Vec<ActionStrategy>,
+}
+
+impl AppProfile {
+    /// A profile for `processes` that changes nothing yet
+    pub fn new(name: impl Into<String>, processes: &[&str]) -> Self {
+        Self {
+            name: name.into(),
+            processes: processes.iter().map(|process| normalize(process)).collect(),
+            timeout_ms: None,
+            search_depth: None,
+            search_root: None,
+            click_strategies: Vec::new(),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout_ms = Some(timeout.as_millis() as u64);
+        self
+    }
+
+    pub fn with_search_depth(mut self, depth: usize) -> Self {
+        self.search_depth = Some(depth);
+        self
+    }
+
+    pub fn with_search_root(mut self, selector: impl Into<String>) -> Self {
+        self.search_root = Some(selector.into());
+        self
+    }
+
+    pub fn with_click_strategies(mut self, strategies: &[ActionStrategy]) -> Self {
+        self.click_strategies = strategies.to_vec();
+        self
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_ms.map(Duration::from_millis)
+    }
+
+    /// Whether the profile applies to `process`, a name or executable file
+    /// name such as `WINWORD.EXE`
+    pub fn matches(&self, process: &str) -> bool {
+        let process = normalize(process);
+        self.processes.iter().any(|name| normalize(name) == process)
+    }
+
+    /// The profiles shipped with terminator
+    pub fn builtin() -> Vec<AppProfile> {
+        vec![
+            // Ribbons, task panes and documents load well after the window
+            AppProfile::new(
+                "office",
+                &["winword", "excel", "powerpnt", "outlook", "onenote", "msaccess", "mspub", "visio"],
+            )
+            .with_timeout(Duration::from_secs(60))
+            .with_click_strategies(&[ActionStrategy::UiaPattern, ActionStrategy::SendInputCursor]),
+            AppProfile::new(
+                "chromium",
+                &["chrome", "msedge", "brave", "opera", "vivaldi", "chromium", "google chrome", "microsoft edge"],
+            )
+            .with_search_depth(100)
+            .with_search_root("role:Document"),
+            AppProfile::new("firefox", &["firefox"])
+                .with_search_depth(100)
+                .with_search_root("role:Document"),
+            // Access Bridge actions are often ignored; real input is not
+            AppProfile::new("java", &["java", "javaw", "jp2launcher"])
+                .with_timeout(Duration::from_secs(45))
+                .with_search_depth(100)
+                .with_click_strategies(&[ActionStrategy::SendInputCursor]),
+        ]
+    }
+
+    /// The profile for `process`: the latest registered one that matches,
+    /// else the built-in one when those are on
+    pub fn for_process(process: &str) -> Option<AppProfile> {
+        find(process, builtin_enabled())
+    }
+
+    /// The profile for the process owning `element`
+    pub fn for_element(element: &UIElement) -> Option<AppProfile> {
+        // Spare the process lookup when no profile could match
+        if !builtin_enabled() && REGISTERED.read().unwrap_or_else(|e| e.into_inner()).is_empty() {
+            return None;
+        }
+        let pid = element.process_id().ok()?;
+        let process = process_name(pid)?;
+        let profile = AppProfile::for_process(&process);
+        if let Some(profile) = &profile {
+            debug!(pid, process = %process, profile = %profile.name, "Application profile found");
+        }
+        profile
+    }
+
+    /// Click `element` trying the profile's strategies in order, or with
+    /// the default click when it has none
+    pub fn click(&self, element: &UIElement) -> Result<ClickResult, AutomationError> {
+        if self.click_strategies.is_empty() {
+            element.click()
+        } else {
+            element.click_escalating(&self.click_strategies)
+        }
+    }
+}
+
+/// Profile added with [`Desktop::register_app_profile`](crate::Desktop::register_app_profile)
+pub(crate) fn register(profile: AppProfile) {
+    let mut registered = REGISTERED.write().unwrap_or_else(|e| e.into_inner());
+    registered.retain(|existing| existing.name != profile.name);
+    registered.insert(0, profile);
+}
+
+/// Remove a registered profile; built-in ones stay
+pub(crate) fn unregister(name: &str) -> bool {
+    let mut registered = REGISTERED.write().unwrap_or_else(|e| e.into_inner());
+    let before = registered.len();
+    registered.retain(|profile| profile.name != name);
+    registered.len() != before
+}
+
+/// Registered profiles, the latest first
+pub(crate) fn registered() -> Vec<AppProfile> {
+    REGISTERED.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Set by [`Desktop::use_builtin_app_profiles`](crate::Desktop::use_builtin_app_profiles)
+pub(crate) fn set_builtin_enabled(enabled: bool) {
+    BUILTIN.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn builtin_enabled() -> bool {
+    BUILTIN.load(Ordering::Relaxed)
+}
+
+/// The latest registered profile matching `process`, else the built-in one
+/// if `builtin`
+pub(crate) fn find(process: &str, builtin: bool) -> Option<AppProfile> {
+    let registered = REGISTERED.read().unwrap_or_else(|e| e.into_inner());
+    registered
+        .iter()
+        .find(|profile| profile.matches(process))
+        .cloned()
+        .or_else(|| {
+            builtin
+                .then(AppProfile::builtin)
+                .and_then(|profiles| profiles.into_iter().find(|profile| profile.matches(process)))
+        })
+}
+
+// Lowercase, without the directory and the `.exe` or `.app` extension
+fn normalize(process: &str) -> String {
+    let process = process.trim().to_lowercase();
+    let file_name = process.rsplit(['/', '\\']).next().unwrap_or(&process);
+    file_name
+        .strip_suffix(".exe")
+        .or_else(|| file_name.strip_suffix(".app"))
+        .unwrap_or(file_name)
+        .to_string()
+}
+
+fn process_name(pid: u32) -> Option<String> {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), false, ProcessRefreshKind::nothing());
+    system
+        .process(pid)
+        .map(|process| process.name().to_string_lossy().into_owned())
+}
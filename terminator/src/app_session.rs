@@ -0,0 +1,325 @@
+//! Automation pinned to one application
+//!
+//! A suite that drives a single app otherwise passes its pid or window to
+//! every call. [`Desktop::attach`](crate::Desktop::attach) returns an
+//! [`AppSession`] whose locators, trees, screenshots and events are scoped to
+//! that app:
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop};
+//! # use terminator::app_session::AppLifecycleEvent;
+//! # async fn run(desktop: &Desktop) -> Result<(), AutomationError> {
+//! let app = desktop.attach("notepad")?;
+//! app.on_lifecycle(|event| match event {
+//!     AppLifecycleEvent::Attached { pid } => println!("attached to {}", pid),
+//!     AppLifecycleEvent::Detached { pid } => println!("{} exited", pid),
+//! });
+//! app.locator("role:Edit")?.first(None).await?.type_text("hello", false)?;
+//! let tree = app.get_window_tree(None)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! When the process exits the session detaches, and it attaches again to the
+//! next process of the same app: by name for sessions attached by name, by
+//! executable for sessions attached by pid (Windows only). A background
+//! watcher notices restarts between calls; every call also checks first.
+//! Locators made before a restart keep searching the old process's window.
+
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::time::{Duration, Instant};
+
+use tracing::{debug, info};
+
+use crate::platforms::TreeBuildConfig;
+use crate::{
+    AutomationError, Desktop, Locator, ScreenshotResult, Selector, UIElement, UIEvent,
+    UIEventKind, UIEventSubscription, UINode, UINodeWithHandles,
+};
+
+/// How often the watcher checks that the process is still running
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+const REATTACH_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The application a session is attached to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppTarget {
+    /// Application or process name, as accepted by [`Desktop::application`]
+    Name(String),
+    Pid(u32),
+}
+
+impl From<&str> for AppTarget {
+    /// A string of digits is a pid, anything else a name
+    fn from(target: &str) -> Self {
+        let target = target.trim();
+        match target.parse() {
+            Ok(pid) => AppTarget::Pid(pid),
+            Err(_) => AppTarget::Name(target.to_string()),
+        }
+    }
+}
+
+impl From<String> for AppTarget {
+    fn from(target: String) -> Self {
+        AppTarget::from(target.as_str())
+    }
+}
+
+impl From<u32> for AppTarget {
+    fn from(pid: u32) -> Self {
+        AppTarget::Pid(pid)
+    }
+}
+
+impl fmt::Display for AppTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppTarget::Name(name) => write!(f, "'{}'", name),
+            AppTarget::Pid(pid) => write!(f, "pid {}", pid),
+        }
+    }
+}
+
+/// A change in what a session is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppLifecycleEvent {
+    /// The session attached to process `pid`, at first or after a restart
+    Attached { pid: u32 },
+    /// Process `pid` exited
+    Detached { pid: u32 },
+}
+
+/// Callback for [`AppSession::on_lifecycle`]
+pub type AppLifecycleCallback = Arc<dyn Fn(AppLifecycleEvent) + Send + Sync>;
+
+struct Attachment {
+    pid: u32,
+    app: UIElement,
+}
+
+struct SessionInner {
+    desktop: Desktop,
+    target: AppTarget,
+    // Executable of the first process, to find it again after a restart
+    // when attached by pid
+    process_name: Mutex<Option<String>>,
+    attachment: Mutex<Option<Attachment>>,
+    callbacks: Mutex<Vec<AppLifecycleCallback>>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl SessionInner {
+    fn is_running(&self, pid: u32) -> bool {
+        self.desktop
+            .engine
+            .get_application_by_pid(pid as i32, Some(Duration::ZERO))
+            .is_ok()
+    }
+
+    fn find(&self) -> Result<Attachment, AutomationError> {
+        let process_name = lock(&self.process_name).clone();
+        let app = match (&self.target, process_name) {
+            (AppTarget::Name(name), _) => self.desktop.application(name)?,
+            (AppTarget::Pid(_), Some(process_name)) => self.desktop.application(&process_name)?,
+            (AppTarget::Pid(pid), None) => self
+                .desktop
+                .engine
+                .get_application_by_pid(*pid as i32, Some(Duration::ZERO))?,
+        };
+        let pid = app.process_id()?;
+        if matches!(self.target, AppTarget::Pid(_)) {
+            let mut process_name = lock(&self.process_name);
+            if process_name.is_none() {
+                *process_name = self.desktop.engine.process_name(pid).ok();
+            }
+        }
+        Ok(Attachment { pid, app })
+    }
+
+    /// The attached process and its application element, attaching again
+    /// if the process is gone
+    fn current(&self) -> Result<(u32, UIElement), AutomationError> {
+        let attached = lock(&self.attachment)
+            .as_ref()
+            .map(|attachment| (attachment.pid, attachment.app.clone()));
+        if let Some((pid, app)) = attached {
+            if self.is_running(pid) {
+                return Ok((pid, app));
+            }
+            let mut attachment = lock(&self.attachment);
+            if attachment.as_ref().is_some_and(|attachment| attachment.pid == pid) {
+                *attachment = None;
+                drop(attachment);
+                info!(pid, target = %self.target, "Attached process exited");
+                self.emit(AppLifecycleEvent::Detached { pid });
+            }
+        }
+
+        // Searched without holding the lock: finding an app can take seconds
+        let found = self.find()?;
+        let current = (found.pid, found.app.clone());
+        let mut attachment = lock(&self.attachment);
+        if let Some(other) = attachment.as_ref() {
+            // Another thread attached meanwhile
+            return Ok((other.pid, other.app.clone()));
+        }
+        *attachment = Some(found);
+        drop(attachment);
+        info!(pid = current.0, target = %self.target, "Attached to application");
+        self.emit(AppLifecycleEvent::Attached { pid: current.0 });
+        Ok(current)
+    }
+
+    fn attached_pid(&self) -> Option<u32> {
+        lock(&self.attachment).as_ref().map(|attachment| attachment.pid)
+    }
+
+    // Called without holding the attachment, so callbacks can use the session
+    fn emit(&self, event: AppLifecycleEvent) {
+        let callbacks = lock(&self.callbacks).clone();
+        for callback in callbacks {
+            callback(event);
+        }
+    }
+}
+
+/// Locators, trees, screenshots and events of one application, see the
+/// [module docs](self). Clones share the attachment
+#[derive(Clone)]
+pub struct AppSession {
+    inner: Arc<SessionInner>,
+}
+
+impl AppSession {
+    pub(crate) fn attach(desktop: Desktop, target: AppTarget) -> Result<Self, AutomationError> {
+        let inner = Arc::new(SessionInner {
+            desktop,
+            target,
+            process_name: Mutex::new(None),
+            attachment: Mutex::new(None),
+            callbacks: Mutex::new(Vec::new()),
+        });
+        inner.current()?;
+        watch(Arc::downgrade(&inner))?;
+        Ok(Self { inner })
+    }
+
+    /// The application the session was attached to
+    pub fn target(&self) -> &AppTarget {
+        &self.inner.target
+    }
+
+    /// Process ID of the attached process
+    pub fn pid(&self) -> Result<u32, AutomationError> {
+        self.inner.current().map(|(pid, _)| pid)
+    }
+
+    /// Whether the session is attached to a running process, without
+    /// trying to attach again
+    pub fn is_attached(&self) -> bool {
+        self.inner
+            .attached_pid()
+            .is_some_and(|pid| self.inner.is_running(pid))
+    }
+
+    /// The application element of the attached process
+    pub fn application(&self) -> Result<UIElement, AutomationError> {
+        self.inner.current().map(|(_, app)| app)
+    }
+
+    /// A locator that searches the attached application only
+    pub fn locator(&self, selector: impl Into<Selector>) -> Result<Locator, AutomationError> {
+        Ok(self.inner.desktop.locator(selector).within(self.application()?))
+    }
+
+    /// UI tree of the application's window, see [`Desktop::get_window_tree`]
+    pub fn get_window_tree(&self, config: Option<TreeBuildConfig>) -> Result<UINode, AutomationError> {
+        self.inner.desktop.get_window_tree(self.pid()?, None, config)
+    }
+
+    /// UI tree of the application's window with the element behind each
+    /// node, see [`Desktop::get_window_tree_with_handles`]
+    pub fn get_window_tree_with_handles(
+        &self,
+        config: Option<TreeBuildConfig>,
+    ) -> Result<UINodeWithHandles, AutomationError> {
+        self.inner
+            .desktop
+            .get_window_tree_with_handles(self.pid()?, None, config)
+    }
+
+    /// Screenshot of the application's window
+    pub fn capture(&self) -> Result<ScreenshotResult, AutomationError> {
+        self.application()?.capture()
+    }
+
+    /// Call `callback` for UI events raised by the attached process, following
+    /// it across restarts. See [`Desktop::on_ui_event`]
+    pub fn on_ui_event(
+        &self,
+        kinds: &[UIEventKind],
+        callback: impl Fn(UIEvent) + Send + Sync + 'static,
+    ) -> Result<UIEventSubscription, AutomationError> {
+        let session = Arc::downgrade(&self.inner);
+        self.inner.desktop.on_ui_event(kinds, move |event| {
+            let Some(pid) = session.upgrade().and_then(|inner| inner.attached_pid()) else {
+                return;
+            };
+            let source = event.element.as_ref().and_then(|element| element.process_id().ok());
+            if source == Some(pid) {
+                callback(event);
+            }
+        })
+    }
+
+    /// Call `callback` when the session attaches to or detaches from a process.
+    /// Callbacks run on the thread that noticed the change: the watcher, or the
+    /// caller of a session method
+    pub fn on_lifecycle(&self, callback: impl Fn(AppLifecycleEvent) + Send + Sync + 'static) {
+        lock(&self.inner.callbacks).push(Arc::new(callback));
+    }
+
+    /// Wait until the application runs again after it exited, e.g. after
+    /// restarting it, and return its application element. Returns at once
+    /// while attached. Defaults to 30 seconds
+    pub async fn wait_for_attach(&self, timeout: Option<Duration>) -> Result<UIElement, AutomationError> {
+        let timeout = timeout.unwrap_or(Duration::from_secs(30));
+        let start = Instant::now();
+        loop {
+            match self.inner.current() {
+                Ok((_, app)) => return Ok(app),
+                Err(e) => debug!(target = %self.inner.target, "Not attached yet: {}", e),
+            }
+            if start.elapsed() >= timeout {
+                return Err(AutomationError::Timeout(format!(
+                    "Application {} did not start within {:?}",
+                    self.inner.target, timeout
+                )));
+            }
+            tokio::time::sleep(REATTACH_POLL_INTERVAL).await;
+        }
+    }
+}
+
+// Check the attachment in the background until the last clone of the
+// session is dropped, so lifecycle callbacks fire between calls too
+fn watch(session: Weak<SessionInner>) -> Result<(), AutomationError> {
+    std::thread::Builder::new()
+        .name("terminator-app-session".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(WATCH_INTERVAL);
+            let Some(inner) = session.upgrade() else {
+                break;
+            };
+            if let Err(e) = inner.current() {
+                debug!(target = %inner.target, "Application not running: {}", e);
+            }
+        })
+        .map(|_| ())
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to start app session watcher: {}", e)))
+}
@@ -0,0 +1,191 @@
+//! Streams of attribute values, for waiting on UI state without sleep loops
+//!
+//! [`UIElement::watch_attribute`] yields the current value of an attribute and
+//! then every change, e.g. to await a progress bar reaching 100%:
+//!
+//! ```no_run
+//! # use terminator::UIElement;
+//! # use std::time::Duration;
+//! # async fn run(progress: &UIElement) -> Result<(), terminator::AutomationError> {
+//! progress
+//!     .watch_attribute("value", Duration::from_millis(200))?
+//!     .until(|value| value == "100", Some(Duration::from_secs(60)))
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The attribute is polled at the given interval. Where the platform reports
+//! property changes (UIA on Windows), changes are also picked up as soon as
+//! they happen and polling only catches what the events miss.
+
+use futures::Stream;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::{mpsc, Notify};
+use tracing::debug;
+
+use crate::element::UIElementImpl;
+use crate::ui_events::UIEventSubscription;
+use crate::{AutomationError, UIElement};
+
+/// Value of a named attribute read from the generic element accessors. Used by
+/// platforms without cheaper native lookups
+pub(crate) fn generic_attribute_value<E: UIElementImpl + ?Sized>(
+    element: &E,
+    attribute: &str,
+) -> Result<Option<String>, AutomationError> {
+    Ok(match attribute.to_lowercase().as_str() {
+        "name" => element.name(),
+        "role" => Some(element.role()),
+        "value" => element.attributes().value,
+        "label" => element.attributes().label,
+        "description" => element.attributes().description,
+        "enabled" => Some(element.is_enabled()?.to_string()),
+        "focused" => Some(element.is_focused()?.to_string()),
+        "visible" => Some(element.is_visible()?.to_string()),
+        _ => element
+            .attributes()
+            .properties
+            .get(attribute)
+            .cloned()
+            .flatten()
+            .map(|value| match value {
+                serde_json::Value::String(s) => s,
+                other => other.to_string(),
+            }),
+    })
+}
+
+/// Stream of an attribute's values: the current one first, then each change.
+/// Ends when the attribute can no longer be read, e.g. because the element
+/// was removed. Watching stops when the stream is dropped
+pub struct AttributeWatch {
+    attribute: String,
+    rx: mpsc::UnboundedReceiver<String>,
+    task: tokio::task::JoinHandle<()>,
+    // Kept alive for the lifetime of the watch
+    events: Option<UIEventSubscription>,
+}
+
+impl AttributeWatch {
+    pub(crate) fn start(
+        element: UIElement,
+        attribute: &str,
+        interval: Duration,
+    ) -> Result<Self, AutomationError> {
+        let runtime = tokio::runtime::Handle::try_current().map_err(|_| {
+            AutomationError::UnsupportedOperation(
+                "watch_attribute must be called from within a Tokio runtime".to_string(),
+            )
+        })?;
+
+        let notify = Arc::new(Notify::new());
+        let changed = notify.clone();
+        let events = match element.watch_attribute_changes(attribute, Arc::new(move || changed.notify_one())) {
+            Ok(subscription) => Some(subscription),
+            Err(e) => {
+                debug!(attribute, "Watching by polling only: {}", e);
+                None
+            }
+        };
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let name = attribute.to_string();
+        let task = runtime.spawn(async move {
+            let mut last: Option<String> = None;
+            loop {
+                match element.attribute_value(&name) {
+                    Ok(value) => {
+                        let value = value.unwrap_or_default();
+                        if last.as_ref() != Some(&value) {
+                            if tx.send(value.clone()).is_err() {
+                                break;
+                            }
+                            last = Some(value);
+                        }
+                    }
+                    Err(e) => {
+                        debug!(attribute = %name, "Stopped watching attribute: {}", e);
+                        break;
+                    }
+                }
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {}
+                    _ = notify.notified() => {}
+                    _ = tx.closed() => break,
+                }
+            }
+        });
+
+        Ok(Self {
+            attribute: attribute.to_string(),
+            rx,
+            task,
+            events,
+        })
+    }
+
+    /// The watched attribute
+    pub fn attribute(&self) -> &str {
+        &self.attribute
+    }
+
+    /// Wait for a value matching `predicate` and return it. Waits forever
+    /// without a timeout
+    pub async fn until(
+        mut self,
+        predicate: impl Fn(&str) -> bool,
+        timeout: Option<Duration>,
+    ) -> Result<String, AutomationError> {
+        let attribute = self.attribute.clone();
+        let rx = &mut self.rx;
+        let wait = async {
+            let mut last = None;
+            while let Some(value) = rx.recv().await {
+                if predicate(&value) {
+                    return Ok(value);
+                }
+                last = Some(value);
+            }
+            Err(AutomationError::ElementNotFound(format!(
+                "Element went away while waiting on '{}' (last value: {:?})",
+                attribute, last
+            )))
+        };
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, wait).await.map_err(|_| {
+                AutomationError::Timeout(format!(
+                    "Attribute '{}' did not reach the expected value within {:?}",
+                    attribute, timeout
+                ))
+            })?,
+            None => wait.await,
+        }
+    }
+}
+
+impl Stream for AttributeWatch {
+    type Item = String;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<String>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl Drop for AttributeWatch {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl std::fmt::Debug for AttributeWatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AttributeWatch")
+            .field("attribute", &self.attribute)
+            .field("events", &self.events.is_some())
+            .finish()
+    }
+}
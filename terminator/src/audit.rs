@@ -0,0 +1,490 @@
+//! Accessibility audits of windows
+//!
+//! [`Audit::run`] walks the elements below a window and reports what makes
+//! it hard to use with a keyboard or a screen reader: controls without an
+//! accessible name, interactive controls that can't be reached with the
+//! keyboard, automation IDs shared by several elements, and, from a
+//! screenshot, text whose contrast looks too low.
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop, audit::Audit};
+//! # async fn run(desktop: &Desktop) -> Result<(), AutomationError> {
+//! let window = desktop.locator("window:Settings").first(None).await?;
+//! let report = Audit::new(&window).check_contrast(true).run()?;
+//! std::fs::write("settings-audit.html", report.to_html()).ok();
+//! println!("{} findings in {} elements", report.findings.len(), report.elements_checked);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Contrast is estimated from the two dominant colors of each element's
+//! pixels, so its findings are hints to look at rather than measurements.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+
+use crate::controls::normalize_role;
+use crate::geometry::Rect;
+use crate::{AutomationError, ScreenshotResult, UIElement};
+
+/// How deep below the root elements are audited by default
+pub const DEFAULT_MAX_DEPTH: usize = 30;
+/// WCAG AA minimum contrast for normal text
+pub const DEFAULT_MIN_CONTRAST_RATIO: f64 = 4.5;
+
+/// Controls a user acts on, which need a name and keyboard access
+const INTERACTIVE_ROLES: &[&str] = &[
+    "button", "checkbox", "radiobutton", "combobox", "edit", "hyperlink", "link", "slider",
+    "spinner", "togglebutton", "menuitem", "tabitem", "listitem", "treeitem",
+];
+/// Interactive roles reached with arrow keys inside their container rather
+/// than with Tab, so they are not expected to be focusable on their own
+const CONTAINER_NAVIGATED_ROLES: &[&str] = &["menuitem", "tabitem", "listitem", "treeitem"];
+/// Roles whose name is drawn as text, checked for contrast
+const TEXT_ROLES: &[&str] = &["text", "statictext", "label", "button", "hyperlink", "link", "checkbox", "radiobutton"];
+/// Elements smaller than this, in pixels, have too few pixels to judge contrast
+const MIN_CONTRAST_SAMPLE_SIZE: f64 = 6.0;
+/// Share of an element's pixels the text color must cover to count
+const MIN_FOREGROUND_SHARE: f64 = 0.02;
+
+/// What an audit checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditRule {
+    /// An interactive control or image without an accessible name
+    MissingName,
+    /// An enabled interactive control that can't get keyboard focus
+    NotFocusable,
+    /// Several elements with the same automation ID
+    DuplicateAutomationId,
+    /// Text whose colors look too close to read easily
+    LowContrast,
+}
+
+impl std::fmt::Display for AuditRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            AuditRule::MissingName => "missing_name",
+            AuditRule::NotFocusable => "not_focusable",
+            AuditRule::DuplicateAutomationId => "duplicate_automation_id",
+            AuditRule::LowContrast => "low_contrast",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Blocks users of assistive technology
+    Error,
+    /// Makes the UI harder to use or to automate
+    Warning,
+}
+
+/// One problem found by an audit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditFinding {
+    pub rule: AuditRule,
+    pub severity: Severity,
+    pub message: String,
+    pub role: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automation_id: Option<String>,
+    /// Roles and names from the root down to the element, e.g.
+    /// `Window 'Settings' > Pane > Button`
+    pub location: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bounds: Option<Rect>,
+    /// Estimated contrast ratio, for low-contrast findings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contrast_ratio: Option<f64>,
+}
+
+/// Result of [`Audit::run`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditReport {
+    /// Role and name of the audited root
+    pub root: String,
+    pub elements_checked: usize,
+    /// Findings in tree order, duplicate IDs last
+    pub findings: Vec<AuditFinding>,
+}
+
+impl AuditReport {
+    /// Findings of one rule
+    pub fn by_rule(&self, rule: AuditRule) -> impl Iterator<Item = &AuditFinding> {
+        self.findings.iter().filter(move |f| f.rule == rule)
+    }
+
+    /// Whether anything blocks users of assistive technology
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(|f| f.severity == Severity::Error)
+    }
+
+    pub fn to_json(&self) -> Result<String, AutomationError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| AutomationError::Internal(format!("Failed to serialize audit report: {}", e)))
+    }
+
+    /// A self-contained HTML page listing the findings
+    pub fn to_html(&self) -> String {
+        let mut counts: Vec<(AuditRule, usize)> = Vec::new();
+        for finding in &self.findings {
+            match counts.iter_mut().find(|(rule, _)| *rule == finding.rule) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((finding.rule, 1)),
+            }
+        }
+        let summary: String = counts
+            .iter()
+            .map(|(rule, count)| format!("<li>{}: {}</li>", rule, count))
+            .collect();
+        let rows: String = self
+            .findings
+            .iter()
+            .map(|f| {
+                format!(
+                    "<tr class=\"{}\"><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    if f.severity == Severity::Error { "error" } else { "warning" },
+                    f.rule,
+                    escape_html(&f.role),
+                    escape_html(f.name.as_deref().unwrap_or("")),
+                    escape_html(&f.message),
+                    escape_html(&f.location),
+                )
+            })
+            .collect();
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Accessibility audit: {root}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }}
+tr.error td:first-child {{ border-left: 4px solid #c00; }}
+tr.warning td:first-child {{ border-left: 4px solid #e90; }}
+</style>
+</head>
+<body>
+<h1>Accessibility audit: {root}</h1>
+<p>{checked} elements checked, {total} findings.</p>
+<ul>{summary}</ul>
+<table>
+<tr><th>Rule</th><th>Role</th><th>Name</th><th>Problem</th><th>Location</th></tr>
+{rows}</table>
+</body>
+</html>
+"#,
+            root = escape_html(&self.root),
+            checked = self.elements_checked,
+            total = self.findings.len(),
+            summary = summary,
+            rows = rows,
+        )
+    }
+}
+
+/// An accessibility audit of the elements below a window or other root
+#[derive(Debug, Clone)]
+pub struct Audit {
+    root: UIElement,
+    max_depth: usize,
+    check_contrast: bool,
+    min_contrast_ratio: f64,
+}
+
+// What the walk remembers about an element for the checks after it
+struct Visited {
+    role: String,
+    name: Option<String>,
+    automation_id: Option<String>,
+    location: String,
+    bounds: Option<Rect>,
+}
+
+impl Audit {
+    pub fn new(root: &UIElement) -> Self {
+        Self {
+            root: root.clone(),
+            max_depth: DEFAULT_MAX_DEPTH,
+            check_contrast: false,
+            min_contrast_ratio: DEFAULT_MIN_CONTRAST_RATIO,
+        }
+    }
+
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Whether to screenshot the root and check the contrast of text. Off
+    /// by default; the root has to be visible on screen
+    pub fn check_contrast(mut self, check: bool) -> Self {
+        self.check_contrast = check;
+        self
+    }
+
+    /// Contrast ratio below which text is reported, 4.5 by default
+    pub fn min_contrast_ratio(mut self, ratio: f64) -> Self {
+        self.min_contrast_ratio = ratio;
+        self
+    }
+
+    #[instrument(skip(self))]
+    pub fn run(&self) -> Result<AuditReport, AutomationError> {
+        let root_label = describe(&self.root.role(), self.root.name().as_deref());
+        // One screenshot of the whole root, cropped per element
+        let screenshot = if self.check_contrast {
+            Some((self.root.capture()?, self.root.rect()?))
+        } else {
+            None
+        };
+
+        let mut findings = Vec::new();
+        let mut visited = Vec::new();
+        let mut level = vec![(self.root.clone(), root_label.clone())];
+        for _ in 0..=self.max_depth {
+            let mut next = Vec::new();
+            for (element, location) in level {
+                let entry = self.check(&element, location, screenshot.as_ref(), &mut findings);
+                for child in element.children().unwrap_or_default() {
+                    let child_location = format!(
+                        "{} > {}",
+                        entry.location,
+                        describe(&child.role(), child.name().as_deref())
+                    );
+                    next.push((child, child_location));
+                }
+                visited.push(entry);
+            }
+            if next.is_empty() {
+                break;
+            }
+            level = next;
+        }
+
+        findings.extend(duplicate_ids(&visited));
+        debug!(elements = visited.len(), findings = findings.len(), "Audit finished");
+        Ok(AuditReport {
+            root: root_label,
+            elements_checked: visited.len(),
+            findings,
+        })
+    }
+
+    fn check(
+        &self,
+        element: &UIElement,
+        location: String,
+        screenshot: Option<&(ScreenshotResult, Rect)>,
+        findings: &mut Vec<AuditFinding>,
+    ) -> Visited {
+        let role = element.role();
+        let normalized = normalize_role(&role);
+        let name = element.name().filter(|n| !n.trim().is_empty());
+        let automation_id = element
+            .attribute_value("AutomationId")
+            .ok()
+            .flatten()
+            .filter(|id| !id.trim().is_empty());
+        let bounds = element.rect().ok().filter(|r| !r.is_empty());
+        let visible = element.is_visible().unwrap_or(true);
+        let entry = Visited { role, name, automation_id, location, bounds };
+        let finding = |rule, severity, message: String| AuditFinding {
+            rule,
+            severity,
+            message,
+            role: entry.role.clone(),
+            name: entry.name.clone(),
+            automation_id: entry.automation_id.clone(),
+            location: entry.location.clone(),
+            bounds: entry.bounds,
+            contrast_ratio: None,
+        };
+
+        let interactive = INTERACTIVE_ROLES.contains(&normalized.as_str());
+        if entry.name.is_none() && (interactive || normalized == "image") {
+            let labeled = element.labeled_by().ok().flatten().and_then(|label| label.name()).is_some();
+            if !labeled {
+                let severity = if interactive { Severity::Error } else { Severity::Warning };
+                findings.push(finding(
+                    AuditRule::MissingName,
+                    severity,
+                    format!("{} has no accessible name, so screen readers can't announce it", entry.role),
+                ));
+            }
+        }
+
+        if interactive
+            && visible
+            && !CONTAINER_NAVIGATED_ROLES.contains(&normalized.as_str())
+            && element.is_enabled().unwrap_or(false)
+            && element.is_keyboard_focusable().ok() == Some(false)
+        {
+            findings.push(finding(
+                AuditRule::NotFocusable,
+                Severity::Error,
+                format!("Enabled {} can't be reached with the keyboard", entry.role),
+            ));
+        }
+
+        if let (Some((image, origin)), Some(bounds), true) = (screenshot, entry.bounds, visible) {
+            if entry.name.is_some() && TEXT_ROLES.contains(&normalized.as_str()) {
+                if let Some(hint) = contrast_in(image, origin, &bounds) {
+                    if hint.ratio < self.min_contrast_ratio {
+                        let mut low = finding(
+                            AuditRule::LowContrast,
+                            Severity::Warning,
+                            format!(
+                                "Text colors {} on {} have a contrast of about {:.1}:1, below {:.1}:1",
+                                hex(hint.foreground),
+                                hex(hint.background),
+                                hint.ratio,
+                                self.min_contrast_ratio
+                            ),
+                        );
+                        low.contrast_ratio = Some((hint.ratio * 100.0).round() / 100.0);
+                        findings.push(low);
+                    }
+                }
+            }
+        }
+
+        entry
+    }
+}
+
+fn duplicate_ids(visited: &[Visited]) -> Vec<AuditFinding> {
+    let mut by_id: HashMap<&str, Vec<&Visited>> = HashMap::new();
+    let mut order = Vec::new();
+    for entry in visited {
+        if let Some(id) = entry.automation_id.as_deref() {
+            let elements = by_id.entry(id).or_default();
+            if elements.is_empty() {
+                order.push(id);
+            }
+            elements.push(entry);
+        }
+    }
+    order
+        .into_iter()
+        .filter(|id| by_id[id].len() > 1)
+        .map(|id| {
+            let elements = &by_id[id];
+            let first = elements[0];
+            AuditFinding {
+                rule: AuditRule::DuplicateAutomationId,
+                severity: Severity::Warning,
+                message: format!(
+                    "Automation ID '{}' is shared by {} elements, so it can't identify one",
+                    id,
+                    elements.len()
+                ),
+                role: first.role.clone(),
+                name: first.name.clone(),
+                automation_id: Some(id.to_string()),
+                location: first.location.clone(),
+                bounds: first.bounds,
+                contrast_ratio: None,
+            }
+        })
+        .collect()
+}
+
+/// Dominant colors of a piece of text and their contrast
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContrastHint {
+    pub foreground: [u8; 3],
+    pub background: [u8; 3],
+    pub ratio: f64,
+}
+
+/// WCAG contrast ratio of two sRGB colors, from 1.0 to 21.0
+pub fn contrast_ratio(a: [u8; 3], b: [u8; 3]) -> f64 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    let (light, dark) = if la > lb { (la, lb) } else { (lb, la) };
+    (light + 0.05) / (dark + 0.05)
+}
+
+fn relative_luminance(color: [u8; 3]) -> f64 {
+    let channel = |c: u8| {
+        let c = c as f64 / 255.0;
+        if c <= 0.03928 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+    };
+    0.2126 * channel(color[0]) + 0.7152 * channel(color[1]) + 0.0722 * channel(color[2])
+}
+
+/// Background and text colors of RGBA pixels: the most common color, and
+/// the color contrasting most with it among those covering a noticeable
+/// share of the pixels. Anti-aliased edges are too rare to be picked.
+/// None for a single-colored area
+pub fn contrast_hint(rgba: &[u8]) -> Option<ContrastHint> {
+    // Colors in steps of 16 so noise and anti-aliasing shades merge
+    let mut histogram: HashMap<[u8; 3], usize> = HashMap::new();
+    for pixel in rgba.chunks_exact(4) {
+        let bucket = [pixel[0] & 0xF0, pixel[1] & 0xF0, pixel[2] & 0xF0];
+        *histogram.entry(bucket).or_default() += 1;
+    }
+    let total = rgba.len() / 4;
+    let (&background, _) = histogram.iter().max_by_key(|(color, count)| (**count, **color))?;
+    let min_count = ((total as f64 * MIN_FOREGROUND_SHARE).ceil() as usize).max(1);
+    let foreground = histogram
+        .iter()
+        .filter(|(color, count)| **color != background && **count >= min_count)
+        .map(|(color, _)| (*color, contrast_ratio(*color, background)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    let center = |c: [u8; 3]| c.map(|v| v | 0x08);
+    Some(ContrastHint {
+        foreground: center(foreground.0),
+        background: center(background),
+        ratio: contrast_ratio(center(foreground.0), center(background)),
+    })
+}
+
+// Contrast of the part of `image`, taken of `origin`, covered by `bounds`
+fn contrast_in(image: &ScreenshotResult, origin: &Rect, bounds: &Rect) -> Option<ContrastHint> {
+    let area = bounds.intersection(origin)?;
+    if area.width < MIN_CONTRAST_SAMPLE_SIZE || area.height < MIN_CONTRAST_SAMPLE_SIZE {
+        return None;
+    }
+    let left = ((area.x - origin.x).max(0.0) as usize).min(image.width as usize);
+    let top = ((area.y - origin.y).max(0.0) as usize).min(image.height as usize);
+    let right = (left + area.width as usize).min(image.width as usize);
+    let bottom = (top + area.height as usize).min(image.height as usize);
+    let stride = image.width as usize * 4;
+
+    let mut pixels = Vec::with_capacity((right - left) * (bottom - top) * 4);
+    for row in top..bottom {
+        let start = row * stride + left * 4;
+        let end = row * stride + right * 4;
+        pixels.extend_from_slice(image.image_data.get(start..end)?);
+    }
+    contrast_hint(&pixels)
+}
+
+fn describe(role: &str, name: Option<&str>) -> String {
+    match name.filter(|n| !n.trim().is_empty()) {
+        Some(name) => format!("{} '{}'", role, name),
+        None => role.to_string(),
+    }
+}
+
+fn hex(color: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
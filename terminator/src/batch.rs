@@ -0,0 +1,243 @@
+//! Several element operations in one call
+//!
+//! From Python, Node and over HTTP every call pays a round trip that often
+//! costs more than the operation itself. A [`Batch`] queues find, click,
+//! type and get-text operations and runs them in one go, returning one
+//! [`BatchResult`] per operation:
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop};
+//! # async fn run(desktop: &Desktop) -> Result<(), AutomationError> {
+//! let results = desktop
+//!     .batch()
+//!     .type_text("name:First name", "Ada")
+//!     .type_text("name:Last name", "Lovelace")
+//!     .click("role:Button >> name:Submit")
+//!     .get_text("name:Status")
+//!     .run()
+//!     .await;
+//! println!("{:?}", results.last().and_then(|r| r.text.as_deref()));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Each selector is resolved once per batch and the element reused by later
+//! operations on the same selector. After a failed operation the rest are
+//! skipped, unless [`Batch::stop_on_error`] is turned off. With
+//! [`Batch::within`] the selectors are resolved inside an element found
+//! earlier instead of from the desktop root.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+
+use crate::{Desktop, ErrorCode, UIElement};
+
+/// Depth of the subtree read by [`BatchOp::GetText`], as in the bindings' default
+const GET_TEXT_DEPTH: usize = 1;
+
+/// One queued operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BatchOp {
+    /// Find the element, e.g. to check it exists
+    Find { selector: String },
+    Click { selector: String },
+    TypeText { selector: String, text: String },
+    GetText { selector: String },
+}
+
+impl BatchOp {
+    /// Selector of the element the operation acts on
+    pub fn selector(&self) -> &str {
+        match self {
+            BatchOp::Find { selector }
+            | BatchOp::Click { selector }
+            | BatchOp::TypeText { selector, .. }
+            | BatchOp::GetText { selector } => selector,
+        }
+    }
+}
+
+/// How an operation of a batch ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    Ok,
+    Failed,
+    /// Not run because an earlier operation failed
+    Skipped,
+}
+
+/// Result of one operation of a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResult {
+    /// Position of the operation in the batch
+    pub index: usize,
+    pub op: BatchOp,
+    pub status: BatchStatus,
+    /// Text read by a get-text operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Why the operation failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<ErrorCode>,
+    /// The element the operation acted on
+    #[serde(skip)]
+    pub element: Option<UIElement>,
+}
+
+impl BatchResult {
+    pub fn is_ok(&self) -> bool {
+        self.status == BatchStatus::Ok
+    }
+}
+
+/// Builder for a batch of operations, created by [`Desktop::batch`]
+#[derive(Clone)]
+pub struct Batch {
+    desktop: Desktop,
+    ops: Vec<BatchOp>,
+    root: Option<UIElement>,
+    timeout: Option<Duration>,
+    stop_on_error: bool,
+}
+
+impl Batch {
+    pub(crate) fn new(desktop: Desktop) -> Self {
+        Self {
+            desktop,
+            ops: Vec::new(),
+            root: None,
+            timeout: None,
+            stop_on_error: true,
+        }
+    }
+
+    pub fn find(self, selector: impl Into<String>) -> Self {
+        self.push(BatchOp::Find { selector: selector.into() })
+    }
+
+    pub fn click(self, selector: impl Into<String>) -> Self {
+        self.push(BatchOp::Click { selector: selector.into() })
+    }
+
+    pub fn type_text(self, selector: impl Into<String>, text: impl Into<String>) -> Self {
+        self.push(BatchOp::TypeText {
+            selector: selector.into(),
+            text: text.into(),
+        })
+    }
+
+    pub fn get_text(self, selector: impl Into<String>) -> Self {
+        self.push(BatchOp::GetText { selector: selector.into() })
+    }
+
+    /// Queue an operation, e.g. one deserialized from a request
+    pub fn push(mut self, op: BatchOp) -> Self {
+        self.ops.push(op);
+        self
+    }
+
+    /// Resolve the selectors inside `element` instead of from the desktop root
+    pub fn within(mut self, element: UIElement) -> Self {
+        self.root = Some(element);
+        self
+    }
+
+    /// How long each selector may take to resolve, the locator default otherwise
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Whether to skip the remaining operations after one fails. On by default
+    pub fn stop_on_error(mut self, stop: bool) -> Self {
+        self.stop_on_error = stop;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Run the operations in order, returning one result per operation
+    #[instrument(skip(self), fields(ops = self.ops.len()))]
+    pub async fn run(self) -> Vec<BatchResult> {
+        let mut elements: HashMap<String, UIElement> = HashMap::new();
+        let mut failed = false;
+        let mut results = Vec::with_capacity(self.ops.len());
+
+        for (index, op) in self.ops.into_iter().enumerate() {
+            if failed && self.stop_on_error {
+                results.push(BatchResult {
+                    index,
+                    op,
+                    status: BatchStatus::Skipped,
+                    text: None,
+                    error: None,
+                    error_code: None,
+                    element: None,
+                });
+                continue;
+            }
+
+            let element = match elements.get(op.selector()) {
+                Some(element) => Ok(element.clone()),
+                None => {
+                    let locator = self.desktop.locator(op.selector());
+                    let locator = match &self.root {
+                        Some(root) => locator.within(root.clone()),
+                        None => locator,
+                    };
+                    locator.first(self.timeout).await
+                }
+            };
+            let outcome = element.and_then(|element| {
+                elements.insert(op.selector().to_string(), element.clone());
+                let text = match &op {
+                    BatchOp::Find { .. } => None,
+                    BatchOp::Click { .. } => element.click().map(|_| None)?,
+                    BatchOp::TypeText { text, .. } => element.type_text(text, false).map(|_| None)?,
+                    BatchOp::GetText { .. } => Some(element.text(GET_TEXT_DEPTH)?),
+                };
+                Ok((element, text))
+            });
+
+            results.push(match outcome {
+                Ok((element, text)) => BatchResult {
+                    index,
+                    op,
+                    status: BatchStatus::Ok,
+                    text,
+                    error: None,
+                    error_code: None,
+                    element: Some(element),
+                },
+                Err(e) => {
+                    debug!(index, selector = op.selector(), "Batch operation failed: {}", e);
+                    failed = true;
+                    BatchResult {
+                        index,
+                        op,
+                        status: BatchStatus::Failed,
+                        text: None,
+                        error_code: Some(e.code()),
+                        error: Some(e.to_string()),
+                        element: None,
+                    }
+                }
+            });
+        }
+
+        results
+    }
+}
@@ -0,0 +1,452 @@
+//! Reproducible performance benchmarks, with the `bench` feature
+//!
+//! Automation that is fast on a developer machine can crawl on the target
+//! fleet: a slow VM, an antivirus hooking every process, a heavy theme.
+//! [`Benchmark`] runs fixed scenarios a fixed number of times, after warmup
+//! runs that are not counted, and reports latency statistics as JSON. The
+//! report can be checked against absolute [`Threshold`]s or against a
+//! baseline report from a known-good machine:
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop};
+//! # use terminator::bench::{Benchmark, BenchReport, Scenario, Threshold};
+//! # async fn run(desktop: &Desktop, baseline: &str) -> Result<(), AutomationError> {
+//! let report = Benchmark::new(Scenario::standard()).iterations(20).run(desktop).await;
+//! std::fs::write("bench.json", report.to_json()?).ok();
+//!
+//! let mut regressions = report.check(&[Threshold::median("tree_build:notepad", 500.0)]);
+//! regressions.extend(report.compare(&BenchReport::from_json(baseline)?, 0.25));
+//! for regression in &regressions {
+//!     eprintln!("{}", regression);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Scenarios run against an application by name. Applications that are not
+//! running are opened for the run and closed afterwards.
+
+use std::fmt;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument, warn};
+
+use crate::{AutomationError, Desktop, EnvironmentInfo, UIElement};
+
+/// Version of the report format, bumped when a field changes meaning
+pub const REPORT_VERSION: u32 = 1;
+
+/// What a benchmark measures
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Scenario {
+    /// Building the whole tree of the application's window
+    TreeBuild { app: String },
+    /// Finding the first element matching a selector within the application
+    SelectorResolution { app: String, selector: String },
+    /// Clicking an element, found once before the runs
+    ClickLatency { app: String, selector: String },
+}
+
+impl Scenario {
+    /// The scenarios of a default run, against the platform's text editor:
+    /// Notepad, TextEdit or gedit
+    pub fn standard() -> Vec<Scenario> {
+        let (app, editor) = if cfg!(target_os = "windows") {
+            ("notepad", "role:Document || role:Edit")
+        } else if cfg!(target_os = "macos") {
+            ("TextEdit", "role:TextArea")
+        } else {
+            ("gedit", "role:Text")
+        };
+        vec![
+            Scenario::TreeBuild { app: app.to_string() },
+            Scenario::SelectorResolution {
+                app: app.to_string(),
+                selector: editor.to_string(),
+            },
+            Scenario::ClickLatency {
+                app: app.to_string(),
+                selector: editor.to_string(),
+            },
+        ]
+    }
+
+    /// Stable name the scenario is reported and checked under, e.g.
+    /// `tree_build:notepad`
+    pub fn name(&self) -> String {
+        match self {
+            Scenario::TreeBuild { app } => format!("tree_build:{}", app),
+            Scenario::SelectorResolution { app, selector } => {
+                format!("selector_resolution:{}:{}", app, selector)
+            }
+            Scenario::ClickLatency { app, selector } => format!("click_latency:{}:{}", app, selector),
+        }
+    }
+
+    pub fn app(&self) -> &str {
+        match self {
+            Scenario::TreeBuild { app }
+            | Scenario::SelectorResolution { app, .. }
+            | Scenario::ClickLatency { app, .. } => app,
+        }
+    }
+}
+
+/// Latency statistics of the successful runs of a scenario, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub mean_ms: f64,
+    pub median_ms: f64,
+    /// 95th percentile, nearest rank
+    pub p95_ms: f64,
+}
+
+impl Stats {
+    /// Statistics of `samples`, `None` without samples
+    pub fn from_samples(samples: &[f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let len = sorted.len();
+        let median = if len % 2 == 0 {
+            (sorted[len / 2 - 1] + sorted[len / 2]) / 2.0
+        } else {
+            sorted[len / 2]
+        };
+        let p95_rank = ((len as f64 * 0.95).ceil() as usize).clamp(1, len);
+        Some(Self {
+            min_ms: sorted[0],
+            max_ms: sorted[len - 1],
+            mean_ms: sorted.iter().sum::<f64>() / len as f64,
+            median_ms: median,
+            p95_ms: sorted[p95_rank - 1],
+        })
+    }
+}
+
+/// The runs of one scenario
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioResult {
+    pub name: String,
+    pub scenario: Scenario,
+    /// Duration of each successful run, in order
+    pub samples_ms: Vec<f64>,
+    /// `None` when no run succeeded
+    pub stats: Option<Stats>,
+    /// Nodes in the tree, for tree builds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elements: Option<usize>,
+    /// Why runs, or the setup of the scenario, failed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// Results of a benchmark run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchReport {
+    /// See [`REPORT_VERSION`]
+    pub version: u32,
+    /// When the run started, in milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    /// The machine the run was made on
+    pub environment: EnvironmentInfo,
+    pub warmup: usize,
+    pub iterations: usize,
+    pub results: Vec<ScenarioResult>,
+}
+
+impl BenchReport {
+    pub fn to_json(&self) -> Result<String, AutomationError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| AutomationError::Internal(format!("Failed to serialize benchmark report: {}", e)))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, AutomationError> {
+        serde_json::from_str(json)
+            .map_err(|e| AutomationError::InvalidArgument(format!("Invalid benchmark report: {}", e)))
+    }
+
+    /// The result of the scenario named `name`
+    pub fn result(&self, name: &str) -> Option<&ScenarioResult> {
+        self.results.iter().find(|result| result.name == name)
+    }
+
+    /// Limits exceeded by this run. A scenario with failed runs, or missing
+    /// from the report, exceeds its threshold
+    pub fn check(&self, thresholds: &[Threshold]) -> Vec<Regression> {
+        let mut regressions = Vec::new();
+        for threshold in thresholds {
+            let Some(stats) = self.healthy_stats(&threshold.scenario, &mut regressions) else {
+                continue;
+            };
+            let limits = [
+                ("median_ms", threshold.max_median_ms, stats.median_ms),
+                ("p95_ms", threshold.max_p95_ms, stats.p95_ms),
+            ];
+            for (metric, limit, actual) in limits {
+                if let Some(limit) = limit.filter(|&limit| actual > limit) {
+                    regressions.push(Regression::new(&threshold.scenario, metric, limit, actual));
+                }
+            }
+        }
+        regressions
+    }
+
+    /// Scenarios whose median grew by more than `tolerance` (0.25 for 25%)
+    /// over `baseline`. Scenarios the baseline lacks are not compared
+    pub fn compare(&self, baseline: &BenchReport, tolerance: f64) -> Vec<Regression> {
+        let mut regressions = Vec::new();
+        for base in &baseline.results {
+            let Some(base_stats) = base.stats else {
+                continue;
+            };
+            let Some(stats) = self.healthy_stats(&base.name, &mut regressions) else {
+                continue;
+            };
+            let limit = base_stats.median_ms * (1.0 + tolerance);
+            if stats.median_ms > limit {
+                regressions.push(Regression::new(&base.name, "median_ms", limit, stats.median_ms));
+            }
+        }
+        regressions
+    }
+
+    // Statistics of a scenario whose runs all succeeded, or the regression
+    // explaining why there are none
+    fn healthy_stats(&self, name: &str, regressions: &mut Vec<Regression>) -> Option<Stats> {
+        let Some(result) = self.result(name) else {
+            regressions.push(Regression::new(name, "missing", 0.0, 1.0));
+            return None;
+        };
+        if !result.errors.is_empty() {
+            regressions.push(Regression::new(name, "errors", 0.0, result.errors.len() as f64));
+        }
+        result.stats.filter(|_| result.errors.is_empty())
+    }
+}
+
+/// Latency limits of one scenario, in milliseconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Threshold {
+    /// [`Scenario::name`] of the scenario
+    pub scenario: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_median_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_p95_ms: Option<f64>,
+}
+
+impl Threshold {
+    pub fn median(scenario: impl Into<String>, max_ms: f64) -> Self {
+        Self {
+            scenario: scenario.into(),
+            max_median_ms: Some(max_ms),
+            max_p95_ms: None,
+        }
+    }
+
+    pub fn p95(scenario: impl Into<String>, max_ms: f64) -> Self {
+        Self {
+            scenario: scenario.into(),
+            max_median_ms: None,
+            max_p95_ms: Some(max_ms),
+        }
+    }
+}
+
+/// A scenario over its limit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Regression {
+    pub scenario: String,
+    /// `median_ms` or `p95_ms`, or `errors` for failed runs and `missing`
+    /// for a scenario absent from the report
+    pub metric: String,
+    pub limit: f64,
+    pub actual: f64,
+}
+
+impl Regression {
+    fn new(scenario: &str, metric: &str, limit: f64, actual: f64) -> Self {
+        Self {
+            scenario: scenario.to_string(),
+            metric: metric.to_string(),
+            limit,
+            actual,
+        }
+    }
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.metric.as_str() {
+            "missing" => write!(f, "{}: not in the report", self.scenario),
+            "errors" => write!(f, "{}: {} failed runs", self.scenario, self.actual),
+            metric => write!(
+                f,
+                "{}: {} {:.1} ms over the limit of {:.1} ms",
+                self.scenario, metric, self.actual, self.limit
+            ),
+        }
+    }
+}
+
+/// Scenarios to run and how often, see the [module docs](self)
+#[derive(Debug, Clone)]
+pub struct Benchmark {
+    scenarios: Vec<Scenario>,
+    warmup: usize,
+    iterations: usize,
+    timeout: Duration,
+}
+
+impl Benchmark {
+    /// Two warmup runs and ten measured runs per scenario by default
+    pub fn new(scenarios: Vec<Scenario>) -> Self {
+        Self {
+            scenarios,
+            warmup: 2,
+            iterations: 10,
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// Runs before the measured ones, filling caches
+    pub fn warmup(mut self, runs: usize) -> Self {
+        self.warmup = runs;
+        self
+    }
+
+    /// Measured runs per scenario
+    pub fn iterations(mut self, runs: usize) -> Self {
+        self.iterations = runs.max(1);
+        self
+    }
+
+    /// How long a selector may take to resolve
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Run every scenario in turn
+    #[instrument(skip(self, desktop), fields(scenarios = self.scenarios.len()))]
+    pub async fn run(&self, desktop: &Desktop) -> BenchReport {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let environment = desktop.environment().unwrap_or_else(|_| EnvironmentInfo::unknown());
+
+        let mut opened: Vec<(String, UIElement)> = Vec::new();
+        let mut results = Vec::with_capacity(self.scenarios.len());
+        for scenario in &self.scenarios {
+            let name = scenario.name();
+            let result = match self.app(desktop, scenario.app(), &mut opened) {
+                Ok(app) => self.run_scenario(desktop, scenario, &app).await,
+                Err(e) => ScenarioResult {
+                    name: name.clone(),
+                    scenario: scenario.clone(),
+                    samples_ms: Vec::new(),
+                    stats: None,
+                    elements: None,
+                    errors: vec![e.to_string()],
+                },
+            };
+            match &result.stats {
+                Some(stats) => info!(scenario = %name, median_ms = stats.median_ms, p95_ms = stats.p95_ms, "Benchmarked"),
+                None => warn!(scenario = %name, errors = ?result.errors, "Benchmark scenario failed"),
+            }
+            results.push(result);
+        }
+        for (name, app) in opened {
+            if let Err(e) = app.close() {
+                warn!(app = %name, "Failed to close benchmark application: {}", e);
+            }
+        }
+
+        BenchReport {
+            version: REPORT_VERSION,
+            timestamp_ms,
+            environment,
+            warmup: self.warmup,
+            iterations: self.iterations,
+            results,
+        }
+    }
+
+    // The running application, opening it the first time it is needed
+    fn app(&self, desktop: &Desktop, name: &str, opened: &mut Vec<(String, UIElement)>) -> Result<UIElement, AutomationError> {
+        if let Some((_, app)) = opened.iter().find(|(opened, _)| opened == name) {
+            return Ok(app.clone());
+        }
+        if let Ok(app) = desktop.application(name) {
+            return Ok(app);
+        }
+        let app = desktop.open_application(name)?;
+        opened.push((name.to_string(), app.clone()));
+        Ok(app)
+    }
+
+    async fn run_scenario(&self, desktop: &Desktop, scenario: &Scenario, app: &UIElement) -> ScenarioResult {
+        let mut result = ScenarioResult {
+            name: scenario.name(),
+            scenario: scenario.clone(),
+            samples_ms: Vec::with_capacity(self.iterations),
+            stats: None,
+            elements: None,
+            errors: Vec::new(),
+        };
+        // Found once, so only the click is timed
+        let target = match scenario {
+            Scenario::ClickLatency { selector, .. } => {
+                match desktop.locator(selector.as_str()).within(app.clone()).first(Some(self.timeout)).await {
+                    Ok(element) => Some(element),
+                    Err(e) => {
+                        result.errors.push(e.to_string());
+                        return result;
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        for run in 0..self.warmup + self.iterations {
+            let start = Instant::now();
+            let outcome = match scenario {
+                Scenario::TreeBuild { .. } => app
+                    .process_id()
+                    .and_then(|pid| desktop.get_window_tree(pid, None, None))
+                    .map(|tree| Some(tree.count())),
+                Scenario::SelectorResolution { selector, .. } => desktop
+                    .locator(selector.as_str())
+                    .within(app.clone())
+                    .first(Some(self.timeout))
+                    .await
+                    .map(|_| None),
+                Scenario::ClickLatency { .. } => match &target {
+                    Some(element) => element.click().map(|_| None),
+                    None => unreachable!("click targets are found before the runs"),
+                },
+            };
+            let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+            if run < self.warmup {
+                continue;
+            }
+            match outcome {
+                Ok(elements) => {
+                    result.samples_ms.push(elapsed_ms);
+                    result.elements = elements.or(result.elements);
+                }
+                Err(e) => result.errors.push(e.to_string()),
+            }
+        }
+        result.stats = Stats::from_samples(&result.samples_ms);
+        result
+    }
+}
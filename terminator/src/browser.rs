@@ -0,0 +1,636 @@
+//! Web content through the Chrome DevTools Protocol
+//!
+//! Accessibility trees of web pages are lossy: many elements have no name,
+//! custom widgets lose their role, and nothing can be located by CSS. For a
+//! Chromium-based browser (Chrome, Edge, Brave, ...) started with
+//! `--remote-debugging-port`, [`Desktop::browser`](crate::Desktop::browser)
+//! connects over CDP and finds elements by CSS or XPath. Each
+//! [`BrowserElement`] can be driven through the DOM, or mapped to the
+//! accessibility element drawn at its position and driven like any other
+//! [`UIElement`]:
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop};
+//! # async fn run(desktop: &Desktop) -> Result<(), AutomationError> {
+//! let browser = desktop.browser(None).await?;
+//! let page = browser.page(Some("example.com")).await?;
+//! let button = page.find("form button[type=submit]").await?;
+//! button.click().await?;
+//! // Or click it the way a user would, with real input
+//! button.ui_element().await?.click()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Without a port, the port is read from the `DevToolsActivePort` file of
+//! the known browsers' default profiles, then 9222 to 9229 are tried. Needs
+//! the `browser` feature.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::stream::{SplitSink, StreamExt};
+use futures::SinkExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tracing::{debug, warn};
+
+use crate::geometry::Rect;
+use crate::platforms::AccessibilityEngine;
+use crate::{AutomationError, UIElement};
+
+/// Port Chromium browsers are usually started with for remote debugging
+pub const DEFAULT_DEBUGGING_PORT: u16 = 9222;
+
+const PROBE_PORTS: std::ops::RangeInclusive<u16> = 9222..=9229;
+const PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+const CALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A tab, worker or other debuggable target of the browser
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserTarget {
+    pub id: String,
+    /// "page" for tabs
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub title: String,
+    pub url: String,
+    #[serde(rename = "webSocketDebuggerUrl", default)]
+    pub websocket_url: Option<String>,
+}
+
+/// A Chromium-based browser reachable over CDP
+pub struct Browser {
+    port: u16,
+    http: reqwest::Client,
+    engine: Arc<dyn AccessibilityEngine>,
+}
+
+impl Browser {
+    pub(crate) async fn connect(
+        engine: Arc<dyn AccessibilityEngine>,
+        port: Option<u16>,
+    ) -> Result<Self, AutomationError> {
+        let http = reqwest::Client::new();
+        let port = match port {
+            Some(port) => {
+                version(&http, port).await?;
+                port
+            }
+            None => detect_port(&http).await?,
+        };
+        debug!(port, "Connected to browser");
+        Ok(Browser { port, http, engine })
+    }
+
+    /// The remote debugging port in use
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Open tabs, in the browser's order
+    pub async fn pages(&self) -> Result<Vec<BrowserTarget>, AutomationError> {
+        let targets: Vec<BrowserTarget> = get_json(&self.http, self.port, "/json/list").await?;
+        Ok(targets.into_iter().filter(|t| t.kind == "page").collect())
+    }
+
+    /// Attach to the first tab whose URL or title contains `filter`
+    /// (case-insensitive), or to the first tab without a filter
+    pub async fn page(&self, filter: Option<&str>) -> Result<BrowserPage, AutomationError> {
+        let pages = self.pages().await?;
+        let target = match filter {
+            Some(filter) => {
+                let filter = filter.to_lowercase();
+                pages.into_iter().find(|t| {
+                    t.url.to_lowercase().contains(&filter)
+                        || t.title.to_lowercase().contains(&filter)
+                })
+            }
+            None => pages.into_iter().next(),
+        }
+        .ok_or_else(|| {
+            AutomationError::ElementNotFound(format!(
+                "No browser tab matching {:?}",
+                filter.unwrap_or("")
+            ))
+        })?;
+        BrowserPage::attach(target, self.engine.clone()).await
+    }
+}
+
+/// One tab, attached over its own CDP connection
+pub struct BrowserPage {
+    target: BrowserTarget,
+    handle: PageHandle,
+}
+
+#[derive(Clone)]
+struct PageHandle {
+    cdp: Arc<Connection>,
+    engine: Arc<dyn AccessibilityEngine>,
+}
+
+impl BrowserPage {
+    async fn attach(
+        target: BrowserTarget,
+        engine: Arc<dyn AccessibilityEngine>,
+    ) -> Result<Self, AutomationError> {
+        let url = target.websocket_url.as_deref().ok_or_else(|| {
+            AutomationError::PlatformError(format!(
+                "Tab '{}' is already attached to another debugger",
+                target.title
+            ))
+        })?;
+        let cdp = Arc::new(Connection::open(url).await?);
+        Ok(BrowserPage {
+            target,
+            handle: PageHandle { cdp, engine },
+        })
+    }
+
+    /// The tab as it was when attached
+    pub fn target(&self) -> &BrowserTarget {
+        &self.target
+    }
+
+    /// Evaluate a JavaScript expression and return its value as JSON.
+    /// Promises are awaited
+    pub async fn evaluate(&self, expression: &str) -> Result<Value, AutomationError> {
+        let result = self
+            .handle
+            .cdp
+            .call(
+                "Runtime.evaluate",
+                json!({ "expression": expression, "returnByValue": true, "awaitPromise": true }),
+            )
+            .await?;
+        remote_value(result)
+    }
+
+    /// Load `url` in the tab. Returns once navigation starts
+    pub async fn navigate(&self, url: &str) -> Result<(), AutomationError> {
+        let result = self.handle.cdp.call("Page.navigate", json!({ "url": url })).await?;
+        match result.get("errorText").and_then(Value::as_str) {
+            Some(error) => Err(AutomationError::PlatformError(format!(
+                "Navigation to {} failed: {}",
+                url, error
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// The first element matching a CSS selector
+    pub async fn find(&self, css: &str) -> Result<BrowserElement, AutomationError> {
+        first(self.find_all(css).await?, css)
+    }
+
+    /// All elements matching a CSS selector, in document order
+    pub async fn find_all(&self, css: &str) -> Result<Vec<BrowserElement>, AutomationError> {
+        let css = serde_json::to_string(css).unwrap_or_default();
+        self.query(&format!("Array.from(document.querySelectorAll({}))", css))
+            .await
+    }
+
+    /// The first element matching an XPath expression
+    pub async fn find_xpath(&self, xpath: &str) -> Result<BrowserElement, AutomationError> {
+        first(self.find_all_xpath(xpath).await?, xpath)
+    }
+
+    /// All elements matching an XPath expression, in document order
+    pub async fn find_all_xpath(
+        &self,
+        xpath: &str,
+    ) -> Result<Vec<BrowserElement>, AutomationError> {
+        let xpath = serde_json::to_string(xpath).unwrap_or_default();
+        self.query(&format!(
+            "(() => {{ \
+                const r = document.evaluate({}, document, null, XPathResult.ORDERED_NODE_SNAPSHOT_TYPE, null); \
+                const nodes = []; \
+                for (let i = 0; i < r.snapshotLength; i++) nodes.push(r.snapshotItem(i)); \
+                return nodes; \
+            }})()",
+            xpath
+        ))
+        .await
+    }
+
+    /// Evaluate an expression that returns an array of nodes, keeping a
+    /// remote reference to each
+    async fn query(&self, expression: &str) -> Result<Vec<BrowserElement>, AutomationError> {
+        let cdp = &self.handle.cdp;
+        let result = cdp
+            .call("Runtime.evaluate", json!({ "expression": expression }))
+            .await?;
+        check_exception(&result)?;
+        let array_id = result["result"]["objectId"]
+            .as_str()
+            .ok_or_else(|| AutomationError::Internal("Query returned no array".to_string()))?
+            .to_string();
+        let properties = cdp
+            .call(
+                "Runtime.getProperties",
+                json!({ "objectId": array_id, "ownProperties": true }),
+            )
+            .await?;
+        let mut indexed: Vec<(usize, String)> = properties["result"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|p| {
+                let index = p["name"].as_str()?.parse().ok()?;
+                let object_id = p["value"]["objectId"].as_str()?.to_string();
+                Some((index, object_id))
+            })
+            .collect();
+        indexed.sort_by_key(|(index, _)| *index);
+        let _ = cdp
+            .call("Runtime.releaseObject", json!({ "objectId": array_id }))
+            .await;
+        Ok(indexed
+            .into_iter()
+            .map(|(_, object_id)| BrowserElement {
+                object_id,
+                handle: self.handle.clone(),
+            })
+            .collect())
+    }
+}
+
+/// A DOM element of a tab, which can also be reached as the accessibility
+/// element drawn at its position
+///
+/// The element stays valid while it is in the document and the tab is not
+/// navigated away.
+#[derive(Clone)]
+pub struct BrowserElement {
+    object_id: String,
+    handle: PageHandle,
+}
+
+impl std::fmt::Debug for BrowserElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BrowserElement")
+            .field("object_id", &self.object_id)
+            .finish()
+    }
+}
+
+impl BrowserElement {
+    /// Call `function` with the element as `this` and return its result as JSON
+    pub async fn call_function(
+        &self,
+        function: &str,
+        args: Vec<Value>,
+    ) -> Result<Value, AutomationError> {
+        let arguments: Vec<Value> = args.into_iter().map(|v| json!({ "value": v })).collect();
+        let result = self
+            .handle
+            .cdp
+            .call(
+                "Runtime.callFunctionOn",
+                json!({
+                    "objectId": self.object_id,
+                    "functionDeclaration": function,
+                    "arguments": arguments,
+                    "returnByValue": true,
+                    "awaitPromise": true,
+                }),
+            )
+            .await?;
+        remote_value(result)
+    }
+
+    async fn call_string(&self, function: &str) -> Result<String, AutomationError> {
+        Ok(self
+            .call_function(function, vec![])
+            .await?
+            .as_str()
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    /// Lower-case tag name, like "button"
+    pub async fn tag_name(&self) -> Result<String, AutomationError> {
+        self.call_string("function() { return this.tagName.toLowerCase(); }")
+            .await
+    }
+
+    /// Rendered text of the element and its descendants
+    pub async fn text(&self) -> Result<String, AutomationError> {
+        self.call_string("function() { return this.innerText ?? this.textContent ?? ''; }")
+            .await
+    }
+
+    /// Value of an HTML attribute, `None` when not set
+    pub async fn attribute(&self, name: &str) -> Result<Option<String>, AutomationError> {
+        let value = self
+            .call_function(
+                "function(name) { return this.getAttribute(name); }",
+                vec![json!(name)],
+            )
+            .await?;
+        Ok(value.as_str().map(str::to_string))
+    }
+
+    /// Current value of an input, textarea or select
+    pub async fn value(&self) -> Result<String, AutomationError> {
+        self.call_string("function() { return String(this.value ?? ''); }")
+            .await
+    }
+
+    /// Click through the DOM. Works on covered and off-screen elements, but
+    /// skips the pointer events a real click produces
+    pub async fn click(&self) -> Result<(), AutomationError> {
+        self.call_function(
+            "function() { this.scrollIntoView({ block: 'center', inline: 'center' }); this.click(); }",
+            vec![],
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Focus the element and set its value, firing `input` and `change` so
+    /// frameworks see the edit
+    pub async fn set_value(&self, value: &str) -> Result<(), AutomationError> {
+        self.call_function(
+            "function(value) { \
+                this.focus(); \
+                const proto = Object.getPrototypeOf(this); \
+                const setter = Object.getOwnPropertyDescriptor(proto, 'value')?.set; \
+                if (setter) setter.call(this, value); else this.value = value; \
+                this.dispatchEvent(new Event('input', { bubbles: true })); \
+                this.dispatchEvent(new Event('change', { bubbles: true })); \
+            }",
+            vec![json!(value)],
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Scroll the element into view and return its screen bounds in
+    /// physical pixels
+    ///
+    /// Estimated from the window's position and chrome size, so browser zoom
+    /// other than 100% or docked developer tools put it off by a margin.
+    pub async fn bounds(&self) -> Result<Rect, AutomationError> {
+        let rect = self
+            .call_function(
+                "function() { \
+                    this.scrollIntoView({ block: 'center', inline: 'center' }); \
+                    const r = this.getBoundingClientRect(); \
+                    const border = (window.outerWidth - window.innerWidth) / 2; \
+                    const left = window.screenX + border; \
+                    const top = window.screenY + window.outerHeight - window.innerHeight - border; \
+                    const scale = window.devicePixelRatio || 1; \
+                    return { x: (left + r.left) * scale, y: (top + r.top) * scale, \
+                             width: r.width * scale, height: r.height * scale }; \
+                }",
+                vec![],
+            )
+            .await?;
+        let rect: Rect = serde_json::from_value(rect).map_err(|e| {
+            AutomationError::Internal(format!("Unexpected element bounds: {}", e))
+        })?;
+        if rect.is_empty() {
+            return Err(AutomationError::ElementNotFound(
+                "Browser element has no size, it is not rendered".to_string(),
+            ));
+        }
+        Ok(rect)
+    }
+
+    /// The accessibility element at the center of this element, for input
+    /// through the platform like any other [`UIElement`]
+    pub async fn ui_element(&self) -> Result<UIElement, AutomationError> {
+        let center = self.bounds().await?.center();
+        self.handle.engine.element_at_point(center.x, center.y)
+    }
+}
+
+/// CDP connection to one target. Responses are matched to calls by ID on a
+/// reader task; events are ignored
+struct Connection {
+    sink: tokio::sync::Mutex<SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>>,
+    pending: Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value, String>>>>>,
+    next_id: AtomicU64,
+    reader: JoinHandle<()>,
+}
+
+impl Connection {
+    async fn open(url: &str) -> Result<Self, AutomationError> {
+        let (socket, _) = tokio_tungstenite::connect_async(url).await.map_err(|e| {
+            AutomationError::PlatformError(format!("Failed to connect to {}: {}", url, e))
+        })?;
+        let (sink, mut stream) = socket.split();
+        let pending: Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value, String>>>>> =
+            Arc::default();
+
+        let reader_pending = pending.clone();
+        let reader = tokio::spawn(async move {
+            while let Some(message) = stream.next().await {
+                let text = match message {
+                    Ok(Message::Text(text)) => text,
+                    Ok(Message::Close(_)) => break,
+                    Ok(_) => continue,
+                    Err(e) => {
+                        warn!("CDP connection failed: {}", e);
+                        break;
+                    }
+                };
+                let Ok(message) = serde_json::from_str::<Value>(&text) else {
+                    continue;
+                };
+                let Some(id) = message.get("id").and_then(Value::as_u64) else {
+                    continue;
+                };
+                let response = match message.get("error") {
+                    Some(error) => Err(error["message"].as_str().unwrap_or("unknown").to_string()),
+                    None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+                };
+                let sender = reader_pending.lock().unwrap_or_else(|p| p.into_inner()).remove(&id);
+                if let Some(sender) = sender {
+                    let _ = sender.send(response);
+                }
+            }
+            // Fail calls still waiting
+            reader_pending.lock().unwrap_or_else(|p| p.into_inner()).clear();
+        });
+
+        Ok(Connection {
+            sink: tokio::sync::Mutex::new(sink),
+            pending,
+            next_id: AtomicU64::new(1),
+            reader,
+        })
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, AutomationError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap_or_else(|p| p.into_inner()).insert(id, tx);
+
+        let request = json!({ "id": id, "method": method, "params": params }).to_string();
+        if let Err(e) = self.sink.lock().await.send(Message::text(request)).await {
+            self.pending.lock().unwrap_or_else(|p| p.into_inner()).remove(&id);
+            return Err(AutomationError::PlatformError(format!(
+                "Failed to send {}: {}",
+                method, e
+            )));
+        }
+
+        match tokio::time::timeout(CALL_TIMEOUT, rx).await {
+            Ok(Ok(Ok(result))) => Ok(result),
+            Ok(Ok(Err(error))) => Err(AutomationError::PlatformError(format!(
+                "{} failed: {}",
+                method, error
+            ))),
+            Ok(Err(_)) => Err(AutomationError::PlatformError(
+                "Browser closed the connection".to_string(),
+            )),
+            Err(_) => {
+                self.pending.lock().unwrap_or_else(|p| p.into_inner()).remove(&id);
+                Err(AutomationError::Timeout(format!(
+                    "No response to {} within {:?}",
+                    method, CALL_TIMEOUT
+                )))
+            }
+        }
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+fn first(
+    mut elements: Vec<BrowserElement>,
+    selector: &str,
+) -> Result<BrowserElement, AutomationError> {
+    if elements.is_empty() {
+        return Err(AutomationError::ElementNotFound(format!(
+            "No browser element matches '{}'",
+            selector
+        )));
+    }
+    Ok(elements.swap_remove(0))
+}
+
+fn check_exception(result: &Value) -> Result<(), AutomationError> {
+    match result.get("exceptionDetails") {
+        Some(details) => {
+            let message = details["exception"]["description"]
+                .as_str()
+                .or_else(|| details["text"].as_str())
+                .unwrap_or("unknown error");
+            Err(AutomationError::PlatformError(format!(
+                "JavaScript error: {}",
+                message
+            )))
+        }
+        None => Ok(()),
+    }
+}
+
+/// The value of a `Runtime.evaluate` or `Runtime.callFunctionOn` result
+fn remote_value(result: Value) -> Result<Value, AutomationError> {
+    check_exception(&result)?;
+    Ok(result["result"].get("value").cloned().unwrap_or(Value::Null))
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(
+    http: &reqwest::Client,
+    port: u16,
+    path: &str,
+) -> Result<T, AutomationError> {
+    let url = format!("http://127.0.0.1:{}{}", port, path);
+    let response = http
+        .get(&url)
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| {
+            AutomationError::PlatformError(format!("No browser debugging port at {}: {}", port, e))
+        })?;
+    response
+        .json()
+        .await
+        .map_err(|e| AutomationError::PlatformError(format!("Unexpected reply from {}: {}", url, e)))
+}
+
+async fn version(http: &reqwest::Client, port: u16) -> Result<Value, AutomationError> {
+    get_json(http, port, "/json/version").await
+}
+
+/// First port that answers as a DevTools endpoint
+async fn detect_port(http: &reqwest::Client) -> Result<u16, AutomationError> {
+    let mut candidates: Vec<u16> = active_port_files()
+        .into_iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .filter_map(|contents| contents.lines().next()?.trim().parse().ok())
+        .collect();
+    candidates.extend(PROBE_PORTS);
+
+    for port in candidates {
+        if version(http, port).await.is_ok() {
+            return Ok(port);
+        }
+    }
+    Err(AutomationError::PlatformError(format!(
+        "No Chromium browser with remote debugging found. Start it with --remote-debugging-port={}",
+        DEFAULT_DEBUGGING_PORT
+    )))
+}
+
+/// `DevToolsActivePort` files of the default profiles of Chrome, Edge,
+/// Brave and Chromium. The browser writes its debugging port there
+fn active_port_files() -> Vec<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let (base, profiles) = (
+        std::env::var_os("LOCALAPPDATA").map(PathBuf::from),
+        [
+            "Google/Chrome/User Data",
+            "Microsoft/Edge/User Data",
+            "BraveSoftware/Brave-Browser/User Data",
+            "Chromium/User Data",
+        ],
+    );
+    #[cfg(target_os = "macos")]
+    let (base, profiles) = (
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support")),
+        [
+            "Google/Chrome",
+            "Microsoft Edge",
+            "BraveSoftware/Brave-Browser",
+            "Chromium",
+        ],
+    );
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let (base, profiles) = (
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")),
+        [
+            "google-chrome",
+            "microsoft-edge",
+            "BraveSoftware/Brave-Browser",
+            "chromium",
+        ],
+    );
+
+    base.map(|base| {
+        profiles
+            .iter()
+            .map(|profile| base.join(profile).join("DevToolsActivePort"))
+            .collect()
+    })
+    .unwrap_or_default()
+}
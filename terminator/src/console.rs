@@ -0,0 +1,74 @@
+//! Automation of console (terminal) applications.
+//!
+//! Console windows such as cmd.exe, PowerShell or other text-mode programs
+//! expose almost nothing through the accessibility tree: the whole screen is a
+//! single text area, and typing into it through UIA is unreliable. On Windows
+//! terminator instead attaches to the console of the target process (via
+//! `AttachConsole`) to read its screen buffer and write input records
+//! directly, so terminal steps can run alongside the rest of a desktop
+//! workflow:
+//!
+//! ```no_run
+//! # use terminator::Desktop;
+//! # use std::time::Duration;
+//! # async fn run(desktop: &Desktop, pid: u32) -> Result<(), terminator::AutomationError> {
+//! desktop.send_console_input(pid, "dir\r")?;
+//! let screen = desktop
+//!     .wait_for_console_text(pid, "bytes free", Duration::from_secs(5))
+//!     .await?;
+//! println!("{}", screen.text());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The process id is the one of the program attached to the console (e.g.
+//! `cmd.exe`), not the terminal host window, which on Windows 11 usually
+//! belongs to Windows Terminal.
+//!
+//! A process can only be attached to one console at a time. While a console
+//! call runs, the calling process is detached from its own console and
+//! re-attached to its parent's console afterwards; calls are serialized.
+
+use serde::{Deserialize, Serialize};
+
+/// Snapshot of the visible part of a console screen buffer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsoleScreen {
+    /// Width of the visible window in character cells
+    pub width: u16,
+    /// Height of the visible window in character cells
+    pub height: u16,
+    /// Cursor position (column, row), relative to the visible window
+    pub cursor: (u16, u16),
+    /// Visible lines, top to bottom, with trailing spaces removed
+    pub lines: Vec<String>,
+}
+
+impl ConsoleScreen {
+    /// All visible lines joined with newlines, without trailing blank lines
+    pub fn text(&self) -> String {
+        let end = self
+            .lines
+            .iter()
+            .rposition(|l| !l.is_empty())
+            .map_or(0, |i| i + 1);
+        self.lines[..end].join("\n")
+    }
+
+    /// Whether any visible line contains `needle`
+    pub fn contains(&self, needle: &str) -> bool {
+        self.lines.iter().any(|l| l.contains(needle))
+    }
+
+    /// The last non-empty line, usually the prompt or latest output
+    pub fn last_line(&self) -> Option<&str> {
+        self.lines.iter().rev().find(|l| !l.is_empty()).map(String::as_str)
+    }
+}
+
+/// The UTF-16 units typed for `text`, with every newline sent as a carriage
+/// return, which is what consoles expect for Enter
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub(crate) fn input_units(text: &str) -> Vec<u16> {
+    text.replace("\r\n", "\r").replace('\n', "\r").encode_utf16().collect()
+}
@@ -0,0 +1,453 @@
+//! Typed wrappers for controls that plain clicks drive unreliably
+//!
+//! Combo boxes, check boxes, sliders and menus expose dedicated accessibility
+//! patterns (UIA ExpandCollapse, SelectionItem, Toggle and RangeValue on
+//! Windows). The wrappers use them and only fall back to clicking where an
+//! element lacks the pattern:
+//!
+//! ```no_run
+//! # use terminator::UIElement;
+//! # fn run(country: &UIElement, terms: &UIElement, volume: &UIElement, menu_bar: &UIElement)
+//! #     -> Result<(), terminator::AutomationError> {
+//! country.as_combobox()?.select("Germany")?;
+//! terms.as_checkbox()?.set(true)?;
+//! volume.as_slider()?.set_fraction(0.7)?;
+//! menu_bar.as_menu()?.open_path(&["File", "Export", "PDF"])?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{AutomationError, UIElement};
+
+/// How long the wrappers wait for drop-down lists and submenus to appear
+pub const DEFAULT_CONTROL_TIMEOUT: Duration = Duration::from_secs(2);
+const CONTROL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+// Drop-down items sit in a list below the combo box, sometimes in a scroll viewer
+const ITEM_SEARCH_DEPTH: usize = 4;
+
+const COMBO_BOX_ROLES: &[&str] = &["combobox"];
+const CHECK_BOX_ROLES: &[&str] = &["checkbox", "togglebutton"];
+const SLIDER_ROLES: &[&str] = &["slider", "spinner", "scrollbar"];
+const MENU_ROLES: &[&str] = &["menu", "menubar", "menuitem", "popupmenu"];
+const LIST_ITEM_ROLES: &[&str] = &["listitem", "menuitem"];
+const MENU_ITEM_ROLES: &[&str] = &["menuitem"];
+
+/// Value and bounds of a slider, spinner or progress bar
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RangeValue {
+    pub value: f64,
+    pub minimum: f64,
+    pub maximum: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub small_change: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub large_change: Option<f64>,
+    pub read_only: bool,
+}
+
+impl RangeValue {
+    /// Position of the value between the bounds, from 0.0 to 1.0
+    pub fn fraction(&self) -> f64 {
+        let span = self.maximum - self.minimum;
+        if span <= 0.0 {
+            return 0.0;
+        }
+        ((self.value - self.minimum) / span).clamp(0.0, 1.0)
+    }
+}
+
+/// Role without case, spaces, dashes or underscores, so `ComboBox` (UIA) and
+/// `combo box` (AT-SPI) compare equal
+pub(crate) fn normalize_role(role: &str) -> String {
+    role.chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '-'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn normalized_role(element: &UIElement) -> String {
+    normalize_role(&element.role())
+}
+
+fn has_role(element: &UIElement, roles: &[&str]) -> bool {
+    let role = normalized_role(element);
+    roles.contains(&role.as_str())
+}
+
+fn expect_role(element: &UIElement, roles: &[&str], kind: &str) -> Result<(), AutomationError> {
+    if has_role(element, roles) {
+        Ok(())
+    } else {
+        Err(AutomationError::InvalidArgument(format!(
+            "Element '{}' with role '{}' is not a {}",
+            element.name_or_empty(),
+            element.role(),
+            kind
+        )))
+    }
+}
+
+/// Names compare without case, surrounding spaces or `&` access key markers
+fn name_matches(element: &UIElement, name: &str) -> bool {
+    let clean = |s: &str| s.replace('&', "").trim().to_lowercase();
+    element.name().is_some_and(|n| clean(&n) == clean(name))
+}
+
+/// Breadth-first search below `root`, `max_depth` levels deep
+fn find_descendant(
+    root: &UIElement,
+    max_depth: usize,
+    predicate: &dyn Fn(&UIElement) -> bool,
+) -> Option<UIElement> {
+    let mut level = vec![root.clone()];
+    for _ in 0..max_depth {
+        let mut next = Vec::new();
+        for element in &level {
+            for child in element.children().unwrap_or_default() {
+                if predicate(&child) {
+                    return Some(child);
+                }
+                next.push(child);
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        level = next;
+    }
+    None
+}
+
+fn descendants_with_role(root: &UIElement, roles: &[&str], max_depth: usize) -> Vec<UIElement> {
+    let mut found = Vec::new();
+    let mut level = vec![root.clone()];
+    for _ in 0..max_depth {
+        let mut next = Vec::new();
+        for element in &level {
+            for child in element.children().unwrap_or_default() {
+                if has_role(&child, roles) {
+                    found.push(child.clone());
+                }
+                next.push(child);
+            }
+        }
+        level = next;
+    }
+    found
+}
+
+/// Call `find` until it returns something or `timeout` runs out
+fn poll<T>(timeout: Duration, mut find: impl FnMut() -> Option<T>) -> Option<T> {
+    let start = Instant::now();
+    loop {
+        if let Some(found) = find() {
+            return Some(found);
+        }
+        if start.elapsed() >= timeout {
+            return None;
+        }
+        std::thread::sleep(CONTROL_POLL_INTERVAL);
+    }
+}
+
+fn is_unsupported(error: &AutomationError) -> bool {
+    matches!(
+        error.kind(),
+        AutomationError::UnsupportedOperation(_) | AutomationError::UnsupportedPlatform(_)
+    )
+}
+
+/// Expand `element`, clicking it when it has no expand pattern. Returns
+/// whether it was collapsed before
+fn open(element: &UIElement) -> Result<bool, AutomationError> {
+    match element.is_expanded() {
+        Ok(true) => return Ok(false),
+        Ok(false) => {}
+        Err(e) if is_unsupported(&e) => {}
+        Err(e) => return Err(e),
+    }
+    match element.set_expanded(true) {
+        Err(e) if is_unsupported(&e) => element.click().map(|_| ())?,
+        result => result?,
+    }
+    Ok(true)
+}
+
+fn close(element: &UIElement) {
+    if element.is_expanded().unwrap_or(false) {
+        if let Err(e) = element.set_expanded(false) {
+            debug!("Failed to collapse '{}': {}", element.name_or_empty(), e);
+        }
+    }
+}
+
+/// A drop-down list of options
+#[derive(Debug, Clone)]
+pub struct ComboBox {
+    element: UIElement,
+}
+
+impl ComboBox {
+    pub fn element(&self) -> &UIElement {
+        &self.element
+    }
+
+    /// Open the drop-down list
+    pub fn expand(&self) -> Result<(), AutomationError> {
+        open(&self.element).map(|_| ())
+    }
+
+    /// Close the drop-down list
+    pub fn collapse(&self) -> Result<(), AutomationError> {
+        self.element.set_expanded(false)
+    }
+
+    /// Text of the selected option, if any
+    pub fn selected(&self) -> Result<Option<String>, AutomationError> {
+        if let Some(value) = self.element.attribute_value("value")?.filter(|v| !v.is_empty()) {
+            return Ok(Some(value));
+        }
+        Ok(descendants_with_role(&self.element, LIST_ITEM_ROLES, ITEM_SEARCH_DEPTH)
+            .into_iter()
+            .find(|item| item.is_selected().unwrap_or(false))
+            .and_then(|item| item.name()))
+    }
+
+    /// Names of the options. Opens the list to read them if needed
+    pub fn options(&self) -> Result<Vec<String>, AutomationError> {
+        let opened = open(&self.element)?;
+        let items = poll(DEFAULT_CONTROL_TIMEOUT, || {
+            let items = descendants_with_role(&self.element, LIST_ITEM_ROLES, ITEM_SEARCH_DEPTH);
+            (!items.is_empty()).then_some(items)
+        })
+        .unwrap_or_default();
+        if opened {
+            close(&self.element);
+        }
+        Ok(items.iter().filter_map(UIElement::name).collect())
+    }
+
+    /// Select the option named `name` (case-insensitive)
+    pub fn select(&self, name: &str) -> Result<(), AutomationError> {
+        let opened = open(&self.element)?;
+        let item = poll(DEFAULT_CONTROL_TIMEOUT, || {
+            find_descendant(&self.element, ITEM_SEARCH_DEPTH, &|e: &UIElement| {
+                has_role(e, LIST_ITEM_ROLES) && name_matches(e, name)
+            })
+        });
+        let Some(item) = item else {
+            let available: Vec<String> = descendants_with_role(&self.element, LIST_ITEM_ROLES, ITEM_SEARCH_DEPTH)
+                .iter()
+                .filter_map(UIElement::name)
+                .collect();
+            if opened {
+                close(&self.element);
+            }
+            return Err(AutomationError::ElementNotFound(format!(
+                "No option '{}' in combo box '{}'. Options: {:?}",
+                name,
+                self.element.name_or_empty(),
+                available
+            )));
+        };
+
+        match item.select_item() {
+            Err(e) if is_unsupported(&e) => item.click().map(|_| ())?,
+            result => result?,
+        }
+        // Selecting through the pattern leaves some lists open
+        close(&self.element);
+        Ok(())
+    }
+}
+
+/// A two- or three-state check box or toggle button
+#[derive(Debug, Clone)]
+pub struct CheckBox {
+    element: UIElement,
+}
+
+impl CheckBox {
+    pub fn element(&self) -> &UIElement {
+        &self.element
+    }
+
+    /// `Some(true)` when checked, `None` when indeterminate
+    pub fn is_checked(&self) -> Result<Option<bool>, AutomationError> {
+        self.element.toggle_state()
+    }
+
+    /// Move to the next state
+    pub fn toggle(&self) -> Result<(), AutomationError> {
+        self.element.toggle()
+    }
+
+    /// Check or uncheck, toggling only if needed
+    pub fn set(&self, checked: bool) -> Result<(), AutomationError> {
+        // Three-state boxes may need two toggles
+        for _ in 0..3 {
+            if self.is_checked()? == Some(checked) {
+                return Ok(());
+            }
+            self.toggle()?;
+        }
+        if self.is_checked()? == Some(checked) {
+            return Ok(());
+        }
+        Err(AutomationError::PlatformError(format!(
+            "Check box '{}' did not become {}",
+            self.element.name_or_empty(),
+            if checked { "checked" } else { "unchecked" }
+        )))
+    }
+}
+
+/// A slider or spinner with a numeric value
+#[derive(Debug, Clone)]
+pub struct Slider {
+    element: UIElement,
+}
+
+impl Slider {
+    pub fn element(&self) -> &UIElement {
+        &self.element
+    }
+
+    pub fn range(&self) -> Result<RangeValue, AutomationError> {
+        self.element.range_value()
+    }
+
+    pub fn value(&self) -> Result<f64, AutomationError> {
+        Ok(self.range()?.value)
+    }
+
+    /// Set the value, which must lie between the slider's minimum and maximum
+    pub fn set_value(&self, value: f64) -> Result<(), AutomationError> {
+        let range = self.range()?;
+        if range.read_only {
+            return Err(AutomationError::ElementNotEditable(format!(
+                "Slider '{}' is read-only",
+                self.element.name_or_empty()
+            )));
+        }
+        if !(range.minimum..=range.maximum).contains(&value) {
+            return Err(AutomationError::InvalidArgument(format!(
+                "Value {} is outside the slider's range {}..={}",
+                value, range.minimum, range.maximum
+            )));
+        }
+        self.element.set_range_value(value)
+    }
+
+    /// Set the value as a fraction of the range, from 0.0 (minimum) to 1.0
+    /// (maximum)
+    pub fn set_fraction(&self, fraction: f64) -> Result<(), AutomationError> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(AutomationError::InvalidArgument(format!(
+                "Fraction {} is not between 0.0 and 1.0",
+                fraction
+            )));
+        }
+        let range = self.range()?;
+        self.set_value(range.minimum + (range.maximum - range.minimum) * fraction)
+    }
+}
+
+/// A menu bar, menu or menu item
+#[derive(Debug, Clone)]
+pub struct Menu {
+    element: UIElement,
+}
+
+impl Menu {
+    pub fn element(&self) -> &UIElement {
+        &self.element
+    }
+
+    /// Open each item of `path` in turn and invoke the last one, e.g.
+    /// `["File", "Export", "PDF"]`. Returns the invoked item
+    pub fn open_path(&self, path: &[&str]) -> Result<UIElement, AutomationError> {
+        let Some((last, submenus)) = path.split_last() else {
+            return Err(AutomationError::InvalidArgument("Menu path is empty".to_string()));
+        };
+        // Win32 submenus are top-level popups rather than children of their item
+        let root = crate::platforms::create_engine(false, false)?.get_root_element();
+        let pid = self.element.process_id().ok();
+
+        let mut container = self.element.clone();
+        for name in submenus {
+            let item = self.find_item(&container, &root, pid, name)?;
+            open(&item)?;
+            container = item;
+        }
+        let item = self.find_item(&container, &root, pid, last)?;
+        match item.perform_action("invoke") {
+            Err(e) if is_unsupported(&e) || matches!(e.kind(), AutomationError::PlatformError(_)) => {
+                item.click().map(|_| ())?
+            }
+            result => result?,
+        }
+        Ok(item)
+    }
+
+    fn find_item(
+        &self,
+        container: &UIElement,
+        root: &UIElement,
+        pid: Option<u32>,
+        name: &str,
+    ) -> Result<UIElement, AutomationError> {
+        let matches = |e: &UIElement| has_role(e, MENU_ITEM_ROLES) && name_matches(e, name);
+        poll(DEFAULT_CONTROL_TIMEOUT, || {
+            find_descendant(container, ITEM_SEARCH_DEPTH, &matches).or_else(|| {
+                let pid = pid?;
+                root.children()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|popup| has_role(popup, MENU_ROLES) && popup.process_id().ok() == Some(pid))
+                    .find_map(|popup| find_descendant(&popup, ITEM_SEARCH_DEPTH, &matches))
+            })
+        })
+        .ok_or_else(|| {
+            AutomationError::ElementNotFound(format!(
+                "No menu item '{}' under '{}'",
+                name,
+                container.name_or_empty()
+            ))
+        })
+    }
+}
+
+impl UIElement {
+    /// This element as a combo box. Fails if its role is not a combo box
+    pub fn as_combobox(&self) -> Result<ComboBox, AutomationError> {
+        expect_role(self, COMBO_BOX_ROLES, "combo box")?;
+        Ok(ComboBox { element: self.clone() })
+    }
+
+    /// This element as a check box. Fails if its role is not a check box or
+    /// toggle button
+    pub fn as_checkbox(&self) -> Result<CheckBox, AutomationError> {
+        expect_role(self, CHECK_BOX_ROLES, "check box")?;
+        Ok(CheckBox { element: self.clone() })
+    }
+
+    /// This element as a slider. Fails if its role is not a slider, spinner
+    /// or scroll bar
+    pub fn as_slider(&self) -> Result<Slider, AutomationError> {
+        expect_role(self, SLIDER_ROLES, "slider")?;
+        Ok(Slider { element: self.clone() })
+    }
+
+    /// This element as a menu. Fails if its role is not a menu bar, menu or
+    /// menu item
+    pub fn as_menu(&self) -> Result<Menu, AutomationError> {
+        expect_role(self, MENU_ROLES, "menu")?;
+        Ok(Menu { element: self.clone() })
+    }
+}
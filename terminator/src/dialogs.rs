@@ -0,0 +1,381 @@
+//! Native Open and Save dialogs
+//!
+//! File dialogs differ between Windows versions, applications and platforms,
+//! and a path typed into the wrong field or a click on a button that is still
+//! being laid out is a common cause of failed runs. [`FileDialog`] finds the
+//! dialog that has focus and drives it through the controls it knows:
+//!
+//! ```no_run
+//! # use std::time::Duration;
+//! # use terminator::{AutomationError, Desktop};
+//! # fn run(desktop: &Desktop) -> Result<(), AutomationError> {
+//! let dialog = desktop.file_dialog(Duration::from_secs(5))?;
+//! dialog.set_path(r"C:\Reports\march.pdf")?;
+//! dialog.confirm()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Windows common dialogs are recognized by the automation IDs of their file
+//! name box and buttons, which are the same in the XP, Vista and later
+//! layouts. On macOS, `NSOpenPanel` and `NSSavePanel` are navigated with
+//! their "Go to the folder" sheet. Any other window with a text field and an
+//! Open or Save button is driven as a custom dialog, by role and name, with
+//! Enter and Escape as the last resort.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tracing::debug;
+
+use crate::platforms::AccessibilityEngine;
+use crate::{AutomationError, Desktop, Selector, UIElement};
+
+/// How long [`FileDialog::confirm`] and [`FileDialog::cancel`] wait for the
+/// dialog to close
+pub const DEFAULT_DIALOG_TIMEOUT: Duration = Duration::from_secs(5);
+const DIALOG_POLL_INTERVAL: Duration = Duration::from_millis(100);
+// Lookups by automation ID inside an already open dialog
+const FIELD_LOOKUP_TIMEOUT: Duration = Duration::from_millis(250);
+const FIELD_SEARCH_DEPTH: usize = 8;
+
+// Automation IDs of the common dialog controls: the file name box of the
+// Vista and later Open dialog, of the Save dialog, and of the XP layout,
+// then the default and cancel buttons
+const WINDOWS_FILE_NAME_IDS: &[&str] = &["1148", "1001", "1152"];
+const WINDOWS_CONFIRM_ID: &str = "1";
+const WINDOWS_CANCEL_ID: &str = "2";
+
+const DIALOG_ROLES: &[&str] = &["dialog", "window", "pane", "axsheet", "axdialog", "axwindow", "sheet"];
+const SHEET_ROLES: &[&str] = &["axsheet", "sheet"];
+const EDIT_ROLES: &[&str] = &["edit", "text", "entry", "axtextfield", "textfield", "combobox", "axcombobox"];
+pub(crate) const BUTTON_ROLES: &[&str] = &["button", "splitbutton", "pushbutton", "axbutton"];
+const CONFIRM_NAMES: &[&str] = &["open", "save", "ok", "select", "choose", "select folder", "upload"];
+const CANCEL_NAMES: &[&str] = &["cancel", "close"];
+
+#[cfg(target_os = "macos")]
+const CONFIRM_KEY: &str = "return";
+#[cfg(not(target_os = "macos"))]
+const CONFIRM_KEY: &str = "{Enter}";
+#[cfg(target_os = "macos")]
+const CANCEL_KEY: &str = "escape";
+#[cfg(not(target_os = "macos"))]
+const CANCEL_KEY: &str = "{Esc}";
+
+/// Which layout a [`FileDialog`] was recognized as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileDialogKind {
+    /// A Windows common Open or Save dialog
+    WindowsCommon,
+    /// A macOS `NSOpenPanel` or `NSSavePanel`
+    MacPanel,
+    /// Any other window with a text field and an Open or Save button
+    Custom,
+}
+
+/// Role without case, spaces, dashes or underscores, so `SplitButton` (UIA)
+/// and `push button` (AT-SPI) compare with the role lists
+pub(crate) fn normalized_role(role: &str) -> String {
+    role.chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '-'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+pub(crate) fn has_role(element: &UIElement, roles: &[&str]) -> bool {
+    let role = normalized_role(&element.role());
+    roles.contains(&role.as_str())
+}
+
+/// Name without case, surrounding spaces, `&` access key markers, trailing
+/// colons or ellipses, so `&Open...` and `File name:` compare with the name
+/// lists
+pub(crate) fn normalized_name(name: &str) -> String {
+    let name = name.replace(['&', '…'], "").to_lowercase();
+    name.trim().trim_end_matches(['.', ':']).trim().to_string()
+}
+
+fn has_name_in(element: &UIElement, names: &[&str]) -> bool {
+    element
+        .name()
+        .is_some_and(|name| names.contains(&normalized_name(&name).as_str()))
+}
+
+/// Where the Go to folder sheet of a macOS panel goes for `path`, and the
+/// name to enter afterwards. Save panels go to the folder and take the name
+/// in their name field; open panels, which have none, go to the full path,
+/// which selects the file
+pub(crate) fn mac_go_to_target(path: &str, has_name_field: bool) -> (String, Option<String>) {
+    let path = std::path::Path::new(path);
+    let folder = path.parent().filter(|p| !p.as_os_str().is_empty());
+    match (has_name_field, folder, path.file_name()) {
+        (true, Some(folder), Some(name)) => (
+            folder.to_string_lossy().into_owned(),
+            Some(name.to_string_lossy().into_owned()),
+        ),
+        _ => (path.to_string_lossy().into_owned(), None),
+    }
+}
+
+/// Breadth-first search below `root`, `max_depth` levels deep
+pub(crate) fn find_descendant(
+    root: &UIElement,
+    max_depth: usize,
+    predicate: &dyn Fn(&UIElement) -> bool,
+) -> Option<UIElement> {
+    let mut level = vec![root.clone()];
+    for _ in 0..max_depth {
+        let mut next = Vec::new();
+        for element in &level {
+            for child in element.children().unwrap_or_default() {
+                if predicate(&child) {
+                    return Some(child);
+                }
+                next.push(child);
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        level = next;
+    }
+    None
+}
+
+/// Call `find` until it returns something or `timeout` runs out
+fn poll<T>(timeout: Duration, mut find: impl FnMut() -> Option<T>) -> Option<T> {
+    let start = Instant::now();
+    loop {
+        if let Some(found) = find() {
+            return Some(found);
+        }
+        if start.elapsed() >= timeout {
+            return None;
+        }
+        std::thread::sleep(DIALOG_POLL_INTERVAL);
+    }
+}
+
+/// The editable part of a field: combo boxes hold their text in an edit child
+fn editable(field: UIElement) -> UIElement {
+    if has_role(&field, &["combobox", "axcombobox"]) {
+        if let Some(edit) = find_descendant(&field, 1, &|e| has_role(e, &["edit", "axtextfield"])) {
+            return edit;
+        }
+    }
+    field
+}
+
+/// Put `text` into `field`, typing it when the value cannot be set directly
+/// or does not stick
+fn fill(field: &UIElement, text: &str) -> Result<(), AutomationError> {
+    match field.set_value(text) {
+        Ok(()) if field.attribute_value("value").ok().flatten().as_deref() == Some(text) => {
+            return Ok(());
+        }
+        Ok(()) => debug!("Value did not stick, typing it instead"),
+        Err(e) => debug!("Failed to set value, typing it instead: {}", e),
+    }
+    field.focus()?;
+    #[cfg(target_os = "macos")]
+    field.press_key("cmd+a")?;
+    #[cfg(not(target_os = "macos"))]
+    field.press_key("{Ctrl}a")?;
+    field.type_text(text, false)
+}
+
+/// Whether `element` is gone or hidden
+fn is_closed(element: &UIElement) -> bool {
+    !element.is_visible().unwrap_or(false)
+}
+
+/// A native or custom file dialog, found with [`FileDialog::attach`]
+#[derive(Clone)]
+pub struct FileDialog {
+    engine: Arc<dyn AccessibilityEngine>,
+    element: UIElement,
+    kind: FileDialogKind,
+}
+
+impl std::fmt::Debug for FileDialog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileDialog")
+            .field("name", &self.element.name_or_empty())
+            .field("kind", &self.kind)
+            .finish()
+    }
+}
+
+impl FileDialog {
+    /// Wait up to `timeout` for a file dialog to have focus and attach to it
+    pub fn attach(desktop: &Desktop, timeout: Duration) -> Result<Self, AutomationError> {
+        let engine = desktop.engine.clone();
+        let found = poll(timeout, || {
+            let focused = engine.get_focused_element().ok()?;
+            Self::recognize(&engine, &focused)
+        });
+        found.ok_or_else(|| {
+            AutomationError::ElementNotFound(format!("No file dialog appeared within {:?}", timeout))
+        })
+    }
+
+    /// The dialog around `focused`, if it looks like a file dialog
+    fn recognize(engine: &Arc<dyn AccessibilityEngine>, focused: &UIElement) -> Option<Self> {
+        let window = focused.window().ok().flatten()?;
+        let dialog = |element: UIElement, kind| FileDialog {
+            engine: engine.clone(),
+            element,
+            kind,
+        };
+
+        if cfg!(target_os = "windows") && has_role(&window, DIALOG_ROLES) {
+            let common = WINDOWS_FILE_NAME_IDS.iter().any(|id| {
+                find_by_id(engine.as_ref(), &window, id).is_some()
+            });
+            if common {
+                return Some(dialog(window, FileDialogKind::WindowsCommon));
+            }
+        }
+        if cfg!(target_os = "macos") {
+            // Save panels are usually sheets attached to the document window
+            let panel = if has_role(&window, &["axdialog", "axsheet"]) {
+                Some(window.clone())
+            } else {
+                find_descendant(&window, 2, &|e| has_role(e, SHEET_ROLES))
+            };
+            if let Some(panel) = panel {
+                return Some(dialog(panel, FileDialogKind::MacPanel));
+            }
+        }
+        if has_role(&window, DIALOG_ROLES)
+            && find_descendant(&window, FIELD_SEARCH_DEPTH, &|e| has_role(e, EDIT_ROLES)).is_some()
+            && find_descendant(&window, FIELD_SEARCH_DEPTH, &|e| {
+                has_role(e, BUTTON_ROLES) && has_name_in(e, CONFIRM_NAMES)
+            })
+            .is_some()
+        {
+            return Some(dialog(window, FileDialogKind::Custom));
+        }
+        None
+    }
+
+    pub fn element(&self) -> &UIElement {
+        &self.element
+    }
+
+    pub fn kind(&self) -> FileDialogKind {
+        self.kind
+    }
+
+    /// The field that takes the file name
+    pub fn path_field(&self) -> Result<UIElement, AutomationError> {
+        let field = match self.kind {
+            FileDialogKind::WindowsCommon => WINDOWS_FILE_NAME_IDS
+                .iter()
+                .find_map(|id| find_by_id(self.engine.as_ref(), &self.element, id)),
+            FileDialogKind::MacPanel | FileDialogKind::Custom => {
+                find_descendant(&self.element, FIELD_SEARCH_DEPTH, &|e| has_role(e, EDIT_ROLES))
+            }
+        };
+        field.map(editable).ok_or_else(|| {
+            AutomationError::ElementNotFound(format!(
+                "No file name field in dialog '{}'",
+                self.element.name_or_empty()
+            ))
+        })
+    }
+
+    /// Enter `path` as the file to open or save. Common dialogs and custom
+    /// dialogs take a full path in their file name field; on macOS the panel
+    /// first goes to the folder, then the name is entered
+    pub fn set_path(&self, path: &str) -> Result<(), AutomationError> {
+        debug!(path, kind = ?self.kind, "Setting file dialog path");
+        match self.kind {
+            FileDialogKind::WindowsCommon | FileDialogKind::Custom => fill(&self.path_field()?, path),
+            FileDialogKind::MacPanel => self.set_mac_path(path),
+        }
+    }
+
+    fn set_mac_path(&self, path: &str) -> Result<(), AutomationError> {
+        let name_field = self.path_field().ok();
+        let (target, name) = mac_go_to_target(path, name_field.is_some());
+
+        self.element.press_key("cmd+shift+g")?;
+        let go_to = poll(DEFAULT_DIALOG_TIMEOUT, || {
+            find_descendant(&self.element, FIELD_SEARCH_DEPTH, &|e| has_role(e, SHEET_ROLES))
+        })
+        .ok_or_else(|| AutomationError::ElementNotFound("The Go to folder sheet did not open".to_string()))?;
+        let go_to_field = find_descendant(&go_to, FIELD_SEARCH_DEPTH, &|e| has_role(e, EDIT_ROLES))
+            .ok_or_else(|| AutomationError::ElementNotFound("No path field in the Go to folder sheet".to_string()))?;
+        fill(&editable(go_to_field), &target)?;
+        go_to.press_key(CONFIRM_KEY)?;
+        if poll(DEFAULT_DIALOG_TIMEOUT, || is_closed(&go_to).then_some(())).is_none() {
+            return Err(AutomationError::Timeout(format!("Could not go to '{}'", target)));
+        }
+
+        match (name_field, name) {
+            (Some(field), Some(name)) => fill(&field, &name),
+            _ => Ok(()),
+        }
+    }
+
+    /// Press Open or Save and wait for the dialog to close. Fails with a
+    /// timeout when it stays open, e.g. because the path does not exist or
+    /// an overwrite prompt came up
+    pub fn confirm(&self) -> Result<(), AutomationError> {
+        let button = match self.kind {
+            FileDialogKind::WindowsCommon => find_by_id(self.engine.as_ref(), &self.element, WINDOWS_CONFIRM_ID),
+            FileDialogKind::MacPanel | FileDialogKind::Custom => self.button(CONFIRM_NAMES),
+        };
+        self.press(button, CONFIRM_KEY, "confirm")
+    }
+
+    /// Press Cancel, or Escape when there is no such button, and wait for the
+    /// dialog to close
+    pub fn cancel(&self) -> Result<(), AutomationError> {
+        let button = match self.kind {
+            FileDialogKind::WindowsCommon => find_by_id(self.engine.as_ref(), &self.element, WINDOWS_CANCEL_ID),
+            FileDialogKind::MacPanel | FileDialogKind::Custom => self.button(CANCEL_NAMES),
+        };
+        self.press(button, CANCEL_KEY, "cancel")
+    }
+
+    fn button(&self, names: &[&str]) -> Option<UIElement> {
+        find_descendant(&self.element, FIELD_SEARCH_DEPTH, &|e| {
+            has_role(e, BUTTON_ROLES) && has_name_in(e, names)
+        })
+    }
+
+    fn press(&self, button: Option<UIElement>, key: &str, action: &str) -> Result<(), AutomationError> {
+        let pressed = match &button {
+            Some(button) if button.is_enabled().unwrap_or(true) => match button.perform_action("invoke") {
+                Ok(()) => true,
+                Err(_) => button.click().is_ok(),
+            },
+            _ => false,
+        };
+        if !pressed {
+            debug!(action, "No usable button, pressing {}", key);
+            self.element.press_key(key)?;
+        }
+        if poll(DEFAULT_DIALOG_TIMEOUT, || is_closed(&self.element).then_some(())).is_none() {
+            return Err(AutomationError::Timeout(format!(
+                "File dialog '{}' still open {:?} after {}",
+                self.element.name_or_empty(),
+                DEFAULT_DIALOG_TIMEOUT,
+                action
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Element with automation ID `id` below `root`. Windows only
+fn find_by_id(engine: &dyn AccessibilityEngine, root: &UIElement, id: &str) -> Option<UIElement> {
+    if !cfg!(target_os = "windows") {
+        return None;
+    }
+    engine
+        .find_element(&Selector::NativeId(id.to_string()), Some(root), Some(FIELD_LOOKUP_TIMEOUT))
+        .ok()
+}
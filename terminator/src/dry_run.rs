@@ -0,0 +1,183 @@
+//! Dry runs: actions logged instead of performed
+//!
+//! With [`Desktop::dry_run`] enabled, element actions (clicks, typing, key
+//! presses, setting values, scrolling, ...) and commands are not performed.
+//! Elements are still searched for, so a selector that resolves to nothing
+//! fails as it would for real, and each action that would have run is
+//! recorded with the element it targets:
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop};
+//! # async fn run(desktop: &Desktop) -> Result<(), AutomationError> {
+//! desktop.dry_run(true);
+//! desktop.locator("name:Delete account").click(None).await?;
+//! for action in desktop.simulated_actions() {
+//!     println!("would {} {:?} ({:?})", action.action, action.name, action.enabled);
+//! }
+//! desktop.dry_run(false);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Opening applications, files and URLs still happens, so the elements of
+//! later steps can be found, but [`AppHandle::kill`](crate::AppHandle::kill)
+//! is only recorded. Like middleware, the mode belongs to a `Desktop` and
+//! its clones, and covers the same elements, see [`crate::middleware`].
+//! Middleware is not called for simulated actions. Each desktop keeps the
+//! last [`MAX_SIMULATED_ACTIONS`] actions of its log.
+//!
+//! [`Desktop::dry_run`]: crate::Desktop::dry_run
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::geometry::Rect;
+use crate::middleware::ActionKind;
+use crate::{AutomationError, ClickResult, CommandOutput, UIElement};
+
+/// Simulated actions a desktop keeps; older ones are dropped
+pub const MAX_SIMULATED_ACTIONS: usize = 1000;
+
+/// Dry-run mode and log of one desktop
+#[derive(Debug, Default)]
+pub(crate) struct DryRun {
+    enabled: AtomicBool,
+    simulated: Mutex<VecDeque<SimulatedAction>>,
+}
+
+impl DryRun {
+    /// Mode set with [`Desktop::dry_run`](crate::Desktop::dry_run).
+    /// Enabling starts a new log of simulated actions
+    pub(crate) fn set_enabled(&self, enabled: bool) {
+        if enabled {
+            self.simulated.lock().unwrap_or_else(|p| p.into_inner()).clear();
+        }
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    /// Actions simulated since the dry run started, oldest first
+    pub(crate) fn simulated_actions(&self) -> Vec<SimulatedAction> {
+        self.simulated.lock().unwrap_or_else(|p| p.into_inner()).iter().cloned().collect()
+    }
+
+    fn push(&self, action: SimulatedAction) {
+        let mut simulated = self.simulated.lock().unwrap_or_else(|p| p.into_inner());
+        if simulated.len() >= MAX_SIMULATED_ACTIONS {
+            simulated.pop_front();
+        }
+        simulated.push_back(action);
+    }
+
+    /// Record a command instead of running it. Reports success with no output
+    pub(crate) fn simulate_command(&self, action: &str, details: String) -> CommandOutput {
+        record(&[self], SimulatedAction::new(action, None, Some(details)));
+        CommandOutput {
+            exit_status: Some(0),
+            stdout: String::new(),
+            stderr: String::new(),
+        }
+    }
+}
+
+/// An action a dry run skipped
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatedAction {
+    /// The action, e.g. `click`, `type_text` or `run_command`
+    pub action: String,
+    /// Action arguments: the typed text or value, the key, the command, ...
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// Role of the element acted on, `None` for commands
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub application: Option<String>,
+    /// Where the element is on screen, if it reports bounds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bounds: Option<Rect>,
+    /// Whether the element accepts input, if it reports it. Disabled
+    /// elements are likely to make the real action fail
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// When the action was requested, in milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+}
+
+impl SimulatedAction {
+    fn new(action: &str, element: Option<&UIElement>, details: Option<String>) -> Self {
+        let bounds = element
+            .and_then(|el| el.bounds().ok())
+            .map(|(x, y, width, height)| Rect::new(x, y, width, height));
+        Self {
+            action: action.to_string(),
+            details,
+            role: element.map(|el| el.role()),
+            name: element.and_then(|el| el.name()).filter(|name| !name.is_empty()),
+            application: element.map(|el| el.application_name()).filter(|name| !name.is_empty()),
+            bounds,
+            enabled: element.and_then(|el| el.is_enabled().ok()),
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// What a simulated action returns in place of the real outcome
+pub(crate) trait Simulated {
+    fn simulated(action: &SimulatedAction) -> Self;
+}
+
+impl Simulated for () {
+    fn simulated(_: &SimulatedAction) -> Self {}
+}
+
+impl Simulated for ClickResult {
+    fn simulated(action: &SimulatedAction) -> Self {
+        ClickResult {
+            method: "DryRun".to_string(),
+            strategy: None,
+            attempts: Vec::new(),
+            coordinates: action.bounds.map(|bounds| (bounds.center().x, bounds.center().y)),
+            details: format!("Dry run: {} not performed", action.action),
+        }
+    }
+}
+
+fn record(logs: &[&DryRun], action: SimulatedAction) -> SimulatedAction {
+    info!(
+        action = %action.action,
+        role = ?action.role,
+        name = ?action.name,
+        details = ?action.details,
+        "Dry run: action not performed"
+    );
+    for log in logs {
+        log.push(action.clone());
+    }
+    action
+}
+
+/// Record an element action in the `logs` of the desktops dry-running it
+/// instead of performing it
+pub(crate) fn simulate_action<T: Simulated>(
+    logs: &[&DryRun],
+    action: ActionKind,
+    element: &UIElement,
+    details: Option<String>,
+) -> Result<T, AutomationError> {
+    let action = record(logs, SimulatedAction::new(action.as_str(), Some(element), details));
+    Ok(T::simulated(&action))
+}
@@ -1,12 +1,26 @@
+use crate::action_strategy::{escalation_failed, ActionStrategy, StrategyAttempt};
+use crate::attribute_watch::{generic_attribute_value, AttributeWatch};
+use crate::controls::RangeValue;
+use crate::elevation::{self, IntegrityLevel};
 use crate::errors::AutomationError;
-use crate::selector::Selector;
-use crate::ScreenshotResult;
+use crate::geometry::{Anchor, Point, Rect};
+use crate::l10n;
+use crate::middleware::{self, ActionHooks, ActionKind};
+use crate::monitor::{monitor_for_rect, MonitorInfo};
+use crate::postcondition::{run_verified, ActionMethod, Condition, VerifiedActionResult};
+use crate::selector::{canonical_role, Selector};
+use crate::stale_element::ElementDescriptor;
+use crate::text_pattern::TextRun;
+use crate::ui_events::UIEventSubscription;
+use crate::{EncodedImage, ImageFormat, ScreenshotResult};
 use std::collections::HashMap;
 use std::fmt::Debug;
 use serde::{Deserialize, Serialize, Serializer, Deserializer};
 use serde::ser::SerializeStruct;
-use tracing::{instrument, warn};
+use tracing::{debug, instrument, warn};
 use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use super::{ClickResult, Locator};
 
@@ -55,6 +69,8 @@ pub struct ExploreResponse {
 #[derive(Debug)]
 pub struct UIElement {
     inner: Box<dyn UIElementImpl>,
+    /// Those of the `Desktop` this element was found through, if known
+    hooks: Option<Arc<ActionHooks>>,
 }
 
 /// Serializable version of UIElement for JSON storage and transmission
@@ -235,6 +251,43 @@ impl fmt::Debug for UIElementAttributes {
     }
 }
 
+/// Show state of a top-level window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowState {
+    Normal,
+    Minimized,
+    Maximized,
+}
+
+impl fmt::Display for WindowState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            WindowState::Normal => "normal",
+            WindowState::Minimized => "minimized",
+            WindowState::Maximized => "maximized",
+        })
+    }
+}
+
+/// Check the bounds given to [`UIElement::set_bounds`]: finite, with a
+/// positive size
+pub(crate) fn check_window_bounds(x: f64, y: f64, width: f64, height: f64) -> Result<(), AutomationError> {
+    if !(x.is_finite() && y.is_finite()) {
+        return Err(AutomationError::InvalidArgument(format!(
+            "window position must be finite, got {},{}",
+            x, y
+        )));
+    }
+    if !(width > 0.0 && height > 0.0 && width.is_finite() && height.is_finite()) {
+        return Err(AutomationError::InvalidArgument(format!(
+            "window size must be positive, got {}x{}",
+            width, height
+        )));
+    }
+    Ok(())
+}
+
 /// Interface for platform-specific element implementations
 pub(crate) trait UIElementImpl: Send + Sync + Debug {
     fn object_id(&self) -> usize;
@@ -246,6 +299,24 @@ pub(crate) trait UIElementImpl: Send + Sync + Debug {
     }
     fn children(&self) -> Result<Vec<UIElement>, AutomationError>;
     fn parent(&self) -> Result<Option<UIElement>, AutomationError>;
+    /// The element after this one among its parent's children
+    fn next_sibling(&self) -> Result<Option<UIElement>, AutomationError> {
+        generic_sibling(self, 1)
+    }
+    /// The element before this one among its parent's children
+    fn previous_sibling(&self) -> Result<Option<UIElement>, AutomationError> {
+        generic_sibling(self, -1)
+    }
+    /// Whether the element satisfies `selector`, which
+    /// [`Selector::check_element_predicate`] accepted
+    fn matches(&self, selector: &Selector) -> bool {
+        generic_matches(self, selector)
+    }
+    /// Whether the control behind the handle no longer exists. Platforms
+    /// that cannot tell report false
+    fn is_stale(&self) -> bool {
+        false
+    }
     fn bounds(&self) -> Result<(f64, f64, f64, f64), AutomationError>; // x, y, width, height
     fn click(&self) -> Result<ClickResult, AutomationError>;
     fn double_click(&self) -> Result<ClickResult, AutomationError>;
@@ -273,6 +344,9 @@ pub(crate) trait UIElementImpl: Send + Sync + Debug {
     // New method for keyboard focusable
     fn is_keyboard_focusable(&self) -> Result<bool, AutomationError>;
 
+    /// Whether the element's value cannot be edited (e.g. a read-only text field)
+    fn is_read_only(&self) -> Result<bool, AutomationError>;
+
     // New method for mouse drag
     fn mouse_drag(&self, start_x: f64, start_y: f64, end_x: f64, end_y: f64) -> Result<(), AutomationError>;
 
@@ -281,6 +355,14 @@ pub(crate) trait UIElementImpl: Send + Sync + Debug {
     fn mouse_move(&self, x: f64, y: f64) -> Result<(), AutomationError>;
     fn mouse_release(&self) -> Result<(), AutomationError>;
 
+    /// Post a left click at screen coordinates to the element's window as
+    /// window messages, without moving the cursor
+    fn post_message_click(&self, _x: f64, _y: f64) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedPlatform(
+            "PostMessage clicks are only available on Windows".to_string(),
+        ))
+    }
+
     // New methods to get containing application and window
     fn application(&self) -> Result<Option<UIElement>, AutomationError>;
     fn window(&self) -> Result<Option<UIElement>, AutomationError>;
@@ -297,12 +379,253 @@ pub(crate) trait UIElementImpl: Send + Sync + Debug {
     /// Close the element if it's closable (like windows, applications)
     /// Does nothing for non-closable elements (like buttons, text, etc.)
     fn close(&self) -> Result<(), AutomationError>;
+
+    /// Whether the element's top-level window is always on top
+    fn is_topmost(&self) -> Result<bool, AutomationError>;
+
+    /// Set or clear the always-on-top state of the element's top-level window
+    fn set_topmost(&self, topmost: bool) -> Result<(), AutomationError>;
+
+    /// Opacity of the element's top-level window, from 0.0 (transparent) to 1.0 (opaque)
+    fn opacity(&self) -> Result<f32, AutomationError>;
+
+    /// Set the opacity of the element's top-level window, from 0.0 to 1.0
+    fn set_opacity(&self, opacity: f32) -> Result<(), AutomationError>;
+
+    /// Show state of the element's top-level window
+    fn window_state(&self) -> Result<WindowState, AutomationError> {
+        Err(unsupported_pattern("window_state"))
+    }
+
+    /// Minimize, maximize or restore the element's top-level window
+    fn set_window_state(&self, _state: WindowState) -> Result<(), AutomationError> {
+        Err(unsupported_pattern("set_window_state"))
+    }
+
+    /// Move and resize the element's top-level window, in screen coordinates
+    fn set_window_bounds(&self, _x: f64, _y: f64, _width: f64, _height: f64) -> Result<(), AutomationError> {
+        Err(unsupported_pattern("set_window_bounds"))
+    }
+
+    /// Raise the element's top-level window above the others and give it
+    /// the input focus
+    fn bring_to_front(&self) -> Result<(), AutomationError> {
+        self.activate_window()
+    }
+
+    /// GUID of the virtual desktop the element's top-level window is on
+    fn virtual_desktop_id(&self) -> Result<String, AutomationError> {
+        Err(unsupported_pattern("virtual_desktop_id"))
+    }
+
+    /// Whether the element's top-level window is on the virtual desktop
+    /// currently shown
+    fn is_on_current_virtual_desktop(&self) -> Result<bool, AutomationError> {
+        Err(unsupported_pattern("is_on_current_virtual_desktop"))
+    }
+
+    /// Move the element's top-level window to another virtual desktop
+    fn move_to_virtual_desktop(&self, _desktop_id: &str) -> Result<(), AutomationError> {
+        Err(unsupported_pattern("move_to_virtual_desktop"))
+    }
+
+    /// The element that labels this one, e.g. the text next to an edit box
+    fn labeled_by(&self) -> Result<Option<UIElement>, AutomationError> {
+        Err(unsupported_pattern("labeled_by"))
+    }
+
+    /// Integrity level of the process the element belongs to
+    fn integrity_level(&self) -> Result<IntegrityLevel, AutomationError> {
+        Err(unsupported_pattern("integrity_level"))
+    }
+
+    /// Trigger the element's default action through the accessibility API
+    fn invoke(&self) -> Result<(), AutomationError> {
+        self.perform_action("invoke")
+    }
+
+    /// Move a check box or toggle button to its next state
+    fn toggle(&self) -> Result<(), AutomationError> {
+        self.perform_action("toggle")
+    }
+
+    /// Toggle state of a check box or toggle button: `Some(true)` when on,
+    /// `None` when indeterminate
+    fn toggle_state(&self) -> Result<Option<bool>, AutomationError> {
+        Err(unsupported_pattern("toggle_state"))
+    }
+
+    /// Whether the element (a combo box, menu item, tree item) shows its children
+    fn is_expanded(&self) -> Result<bool, AutomationError> {
+        Err(unsupported_pattern("is_expanded"))
+    }
+
+    /// Expand or collapse the element
+    fn set_expanded(&self, _expanded: bool) -> Result<(), AutomationError> {
+        Err(unsupported_pattern("set_expanded"))
+    }
+
+    /// Whether the element is the selected item of its list, combo box or tab
+    fn is_selected(&self) -> Result<bool, AutomationError> {
+        Err(unsupported_pattern("is_selected"))
+    }
+
+    /// Make the element the selected item of its container
+    fn select_item(&self) -> Result<(), AutomationError> {
+        Err(unsupported_pattern("select_item"))
+    }
+
+    /// Value and bounds of a slider, spinner or progress bar
+    fn range_value(&self) -> Result<RangeValue, AutomationError> {
+        Err(unsupported_pattern("range_value"))
+    }
+
+    /// Set the value of a slider or spinner
+    fn set_range_value(&self, _value: f64) -> Result<(), AutomationError> {
+        Err(unsupported_pattern("set_range_value"))
+    }
+
+    /// Text of the current selection, empty when nothing is selected
+    fn selected_text(&self) -> Result<String, AutomationError> {
+        Err(unsupported_pattern("selected_text"))
+    }
+
+    /// Character offset of the caret, `None` when the element has no caret
+    fn caret_position(&self) -> Result<Option<usize>, AutomationError> {
+        Err(unsupported_pattern("caret_position"))
+    }
+
+    /// Select the characters from `start` up to `end`
+    fn select_range(&self, _start: usize, _end: usize) -> Result<(), AutomationError> {
+        Err(unsupported_pattern("select_range"))
+    }
+
+    /// The text split into runs of the same formatting
+    fn text_runs(&self) -> Result<Vec<TextRun>, AutomationError> {
+        Err(unsupported_pattern("text_with_formatting"))
+    }
+
+    /// Current value of a named attribute, `None` if the element has none
+    fn attribute_value(&self, attribute: &str) -> Result<Option<String>, AutomationError> {
+        generic_attribute_value(self, attribute)
+    }
+
+    /// Call `changed` when `attribute` changes, for attributes the platform
+    /// reports change events for
+    fn watch_attribute_changes(
+        &self,
+        attribute: &str,
+        _changed: Arc<dyn Fn() + Send + Sync>,
+    ) -> Result<UIEventSubscription, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(format!(
+            "No change events for attribute '{}' on this platform",
+            attribute
+        )))
+    }
+}
+
+/// How long `UIElement::type_text` waits for the element to accept input
+pub const DEFAULT_EDITABLE_TIMEOUT: Duration = Duration::from_secs(5);
+const EDITABLE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+// How long typed text may take to show up in the element's value
+const TYPE_SETTLE_TIMEOUT: Duration = Duration::from_secs(1);
+
+// Deeper than any real tree; guards against providers with parent cycles
+const MAX_ANCESTORS: usize = 256;
+
+fn unsupported_pattern(operation: &str) -> AutomationError {
+    AutomationError::UnsupportedOperation(format!("{} is not supported on this platform", operation))
+}
+
+/// Name of a control pattern type for messages, e.g. `InvokePattern` for
+/// `uiautomation::patterns::UIInvokePattern`
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub(crate) fn pattern_name(type_name: &str) -> &str {
+    let path = type_name.split('<').next().unwrap_or(type_name);
+    let name = path.rsplit("::").next().unwrap_or(path);
+    name.strip_prefix("UI").unwrap_or(name)
+}
+
+// Sibling `offset` places away, found among the parent's children
+fn generic_sibling<E: UIElementImpl + ?Sized>(element: &E, offset: isize) -> Result<Option<UIElement>, AutomationError> {
+    let Some(parent) = element.parent()? else {
+        return Ok(None);
+    };
+    let siblings = parent.children()?;
+    let id = element.object_id();
+    let Some(position) = siblings.iter().position(|sibling| sibling.inner.object_id() == id) else {
+        return Ok(None);
+    };
+    Ok(position
+        .checked_add_signed(offset)
+        .and_then(|position| siblings.get(position))
+        .cloned())
+}
+
+// Selector matching on the element's attributes, for platforms without a
+// native predicate. Mirrors the platform searches: names match by
+// case-insensitive substring, roles ignoring case and with their aliases
+fn generic_matches<E: UIElementImpl + ?Sized>(element: &E, selector: &Selector) -> bool {
+    let name_contains = |needle: &str| {
+        element
+            .name()
+            .is_some_and(|name| name.to_lowercase().contains(&needle.to_lowercase()))
+    };
+    let property = |key: &str| {
+        element
+            .attributes()
+            .properties
+            .get(key)
+            .and_then(|value| value.as_ref())
+            .and_then(|value| value.as_str())
+            .map(str::to_string)
+    };
+    match selector {
+        Selector::Role { role, name } => {
+            canonical_role(&element.role()) == canonical_role(role) && name.as_deref().is_none_or(name_contains)
+        }
+        Selector::Name(name) => name_contains(name),
+        Selector::NameL10n(_) => generic_matches(element, &selector.localized()),
+        Selector::LocalizedName(names) => element.name().is_some_and(|name| l10n::is_one_of(&name, names)),
+        Selector::Text(text) => name_contains(text) || element.role().eq_ignore_ascii_case("text"),
+        Selector::Id(id) => element.id().as_deref() == Some(id.as_str()),
+        Selector::NativeId(native_id) => property("AutomationId").as_deref() == Some(native_id.as_str()),
+        Selector::ClassName(class_name) => property("ClassName").as_deref() == Some(class_name.as_str()),
+        Selector::Not(inner) => !generic_matches(element, inner),
+        Selector::And(selectors) => selectors.iter().all(|s| generic_matches(element, s)),
+        Selector::Or(selectors) => selectors.iter().any(|s| generic_matches(element, s)),
+        _ => false,
+    }
+}
+
+// Treat a state check the platform cannot perform as unknown instead of failing
+fn skip_unsupported(result: Result<bool, AutomationError>) -> Result<Option<bool>, AutomationError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if matches!(e.kind(), AutomationError::UnsupportedOperation(_) | AutomationError::UnsupportedPlatform(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
 }
 
 impl UIElement {
     /// Create a new UI element from a platform-specific implementation
     pub(crate) fn new(impl_: Box<dyn UIElementImpl>) -> Self {
-        Self { inner: impl_ }
+        Self { inner: impl_, hooks: None }
+    }
+
+    /// This element, with its actions going through `hooks`
+    pub(crate) fn with_hooks(mut self, hooks: Option<Arc<ActionHooks>>) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    pub(crate) fn hooks(&self) -> Option<&Arc<ActionHooks>> {
+        self.hooks.as_ref()
+    }
+
+    /// Elements reached from this one belong to the same desktop
+    fn related(&self, element: UIElement) -> UIElement {
+        element.with_hooks(self.hooks.clone())
     }
 
     /// Get the element's ID
@@ -323,12 +646,92 @@ impl UIElement {
 
     /// Get child elements
     pub fn children(&self) -> Result<Vec<UIElement>, AutomationError> {
-        self.inner.children()
+        Ok(self.inner.children()?.into_iter().map(|child| self.related(child)).collect())
     }
 
     /// Get parent element
     pub fn parent(&self) -> Result<Option<UIElement>, AutomationError> {
-        self.inner.parent()
+        Ok(self.inner.parent()?.map(|parent| self.related(parent)))
+    }
+
+    /// The parent, its parent and so on up to the root of the tree,
+    /// nearest first
+    pub fn ancestors(&self) -> Result<Vec<UIElement>, AutomationError> {
+        let mut ancestors: Vec<UIElement> = Vec::new();
+        let mut current = self.parent()?;
+        while let Some(element) = current {
+            // Some providers report an element as its own ancestor
+            if element == *self || ancestors.contains(&element) || ancestors.len() >= MAX_ANCESTORS {
+                warn!(depth = ancestors.len(), "Stopped walking ancestors at a cycle or depth limit");
+                break;
+            }
+            current = element.parent()?;
+            ancestors.push(element);
+        }
+        Ok(ancestors)
+    }
+
+    /// The element after this one among its parent's children, `None` for
+    /// the last child
+    pub fn next_sibling(&self) -> Result<Option<UIElement>, AutomationError> {
+        Ok(self.inner.next_sibling()?.map(|sibling| self.related(sibling)))
+    }
+
+    /// The element before this one among its parent's children, `None` for
+    /// the first child
+    pub fn previous_sibling(&self) -> Result<Option<UIElement>, AutomationError> {
+        Ok(self.inner.previous_sibling()?.map(|sibling| self.related(sibling)))
+    }
+
+    /// Whether this element satisfies `selector`. Only selectors that can
+    /// be tested on one element are accepted: role, id, name, text,
+    /// nativeid and classname, and their `not`/`and`/`or` combinations
+    pub fn matches(&self, selector: impl Into<Selector>) -> Result<bool, AutomationError> {
+        let selector = selector.into().localized();
+        selector.check_element_predicate()?;
+        Ok(self.inner.matches(&selector))
+    }
+
+    /// Whether the control behind this handle no longer exists, e.g. a list
+    /// row recreated by virtualization. Stale handles fail every action;
+    /// see [`stale_element`](crate::stale_element) to find the control again
+    pub fn is_stale(&self) -> bool {
+        self.inner.is_stale()
+    }
+
+    /// How to find this element again once its handle is stale
+    pub fn descriptor(&self) -> Result<ElementDescriptor, AutomationError> {
+        ElementDescriptor::capture(self)
+    }
+
+    /// The nearest of this element and its ancestors that satisfies
+    /// `selector`, e.g. the dialog a button is in with `role:Window`.
+    /// Accepts the selectors [`UIElement::matches`] does
+    pub fn closest(&self, selector: impl Into<Selector>) -> Result<Option<UIElement>, AutomationError> {
+        let selector = selector.into().localized();
+        selector.check_element_predicate()?;
+        if self.inner.matches(&selector) {
+            return Ok(Some(self.clone()));
+        }
+        let mut current = self.parent()?;
+        let mut depth = 0;
+        while let Some(element) = current {
+            if element.inner.matches(&selector) {
+                return Ok(Some(element));
+            }
+            depth += 1;
+            if element == *self || depth >= MAX_ANCESTORS {
+                break;
+            }
+            current = element.parent()?;
+        }
+        Ok(None)
+    }
+
+    /// The element that labels this one, as declared by the application
+    /// (UIA `LabeledBy`). `None` if it declares no label
+    pub fn labeled_by(&self) -> Result<Option<UIElement>, AutomationError> {
+        Ok(self.inner.labeled_by()?.map(|label| self.related(label)))
     }
 
     /// Get element bounds (x, y, width, height)
@@ -336,42 +739,304 @@ impl UIElement {
         self.inner.bounds()
     }
 
-    /// Click on this element
+    /// The monitor this element is displayed on, or `None` if it is off-screen
+    pub fn monitor(&self) -> Result<Option<MonitorInfo>, AutomationError> {
+        let rect = self.rect()?;
+        let monitors = crate::monitor::list_monitors()?;
+        Ok(monitor_for_rect(&monitors, &rect).cloned())
+    }
+
+    /// Get element bounds as a [`Rect`] in physical screen pixels
+    pub fn rect(&self) -> Result<Rect, AutomationError> {
+        self.bounds().map(Rect::from)
+    }
+
+    /// Center of the element in physical screen pixels
+    pub fn center(&self) -> Result<Point, AutomationError> {
+        Ok(self.rect()?.center())
+    }
+
+    /// A point relative to the element, e.g. 10px left of its center:
+    /// `element.point_at(Anchor::Center, (-10.0, 0.0))`
+    pub fn point_at(&self, anchor: Anchor, offset: (f64, f64)) -> Result<Point, AutomationError> {
+        Ok(self.rect()?.point_at(anchor, offset))
+    }
+
+    /// Whether the bounds of both elements overlap
+    pub fn intersects(&self, other: &UIElement) -> Result<bool, AutomationError> {
+        Ok(self.rect()?.intersects(&other.rect()?))
+    }
+
+    /// Whether the screen point lies inside this element's bounds
+    pub fn contains_point(&self, x: f64, y: f64) -> Result<bool, AutomationError> {
+        Ok(self.rect()?.contains_point(x, y))
+    }
+
+    /// Click on this element, trying [`ActionStrategy::ESCALATION`] in
+    /// order. The result names the strategy that worked
     #[instrument(skip(self))]
     pub fn click(&self) -> Result<ClickResult, AutomationError> {
-        self.inner.click()
+        middleware::run(ActionKind::Click, self, None, || {
+            self.ensure_on_current_desktop()?;
+            self.ensure_reachable()?;
+            self.escalate(&ActionStrategy::ESCALATION)
+        })
+    }
+
+    /// Click at an anchor point of this element instead of its center, e.g.
+    /// `Anchor::Left` for a toggle on the left or `Anchor::Percent(95.0, 50.0)`
+    /// for an overflow button at the right edge.
+    ///
+    /// The anchor point is kept inside the element's bounds before `offset`
+    /// is applied. The clicked point is reported in `ClickResult::coordinates`.
+    #[instrument(skip(self))]
+    pub fn click_at_anchor(&self, anchor: Anchor, offset: (f64, f64)) -> Result<ClickResult, AutomationError> {
+        let details = format!("{:?} anchor with offset {:?}", anchor, offset);
+        middleware::run(ActionKind::ClickAtAnchor, self, Some(details), || {
+            let rect = self.rect()?;
+            let point = rect
+                .clamp_point(rect.point_at(anchor, (0.0, 0.0)))
+                .offset(offset.0, offset.1);
+            self.inner.mouse_click_and_hold(point.x, point.y)?;
+            self.inner.mouse_release()?;
+            Ok(ClickResult {
+                method: "AnchorClick".to_string(),
+                strategy: Some(ActionStrategy::SendInputCursor),
+                attempts: Vec::new(),
+                coordinates: Some((point.x, point.y)),
+                details: format!("Clicked at {:?} anchor with offset {:?}", anchor, offset),
+            })
+        })
+    }
+
+    /// Click this element delivering the click with `strategy` only; see
+    /// [`action_strategy`](crate::action_strategy)
+    #[instrument(skip(self))]
+    pub fn click_with_strategy(&self, strategy: ActionStrategy) -> Result<ClickResult, AutomationError> {
+        middleware::run(ActionKind::Click, self, Some(format!("{} strategy", strategy)), || {
+            self.ensure_on_current_desktop()?;
+            self.ensure_reachable()?;
+            self.click_by(strategy)
+        })
+    }
+
+    /// Try `strategies` in order until one delivers the click, e.g.
+    /// [`ActionStrategy::ESCALATION`]. The result names the strategy that
+    /// worked and lists the ones that failed before it in `attempts`
+    #[instrument(skip(self))]
+    pub fn click_escalating(&self, strategies: &[ActionStrategy]) -> Result<ClickResult, AutomationError> {
+        if strategies.is_empty() {
+            return Err(AutomationError::InvalidArgument(
+                "No action strategies to try".to_string(),
+            ));
+        }
+        let chain: Vec<&str> = strategies.iter().map(|strategy| strategy.name()).collect();
+        middleware::run(ActionKind::Click, self, Some(format!("escalating {}", chain.join(" > "))), || {
+            self.ensure_on_current_desktop()?;
+            self.ensure_reachable()?;
+            self.escalate(strategies)
+        })
+    }
+
+    fn escalate(&self, strategies: &[ActionStrategy]) -> Result<ClickResult, AutomationError> {
+        let mut attempts = Vec::new();
+        for &strategy in strategies {
+            let start = Instant::now();
+            match self.click_by(strategy) {
+                Ok(mut result) => {
+                    result.attempts = attempts;
+                    return Ok(result);
+                }
+                Err(e) => {
+                    debug!(%strategy, "Click strategy failed: {}", e);
+                    attempts.push(StrategyAttempt {
+                        strategy,
+                        error: e.to_string(),
+                        duration_ms: start.elapsed().as_millis() as u64,
+                    });
+                }
+            }
+        }
+        Err(escalation_failed(&attempts))
+    }
+
+    fn click_by(&self, strategy: ActionStrategy) -> Result<ClickResult, AutomationError> {
+        let (coordinates, details) = match strategy {
+            ActionStrategy::UiaPattern => {
+                self.inner.invoke()?;
+                (None, "Performed the element's default action")
+            }
+            ActionStrategy::SendInputCursor => {
+                let center = self.rect()?.center();
+                self.inner.mouse_click_and_hold(center.x, center.y)?;
+                self.inner.mouse_release()?;
+                (Some((center.x, center.y)), "Clicked the element's center with the cursor")
+            }
+            ActionStrategy::PostMessage => {
+                let center = self.rect()?.center();
+                self.inner.post_message_click(center.x, center.y)?;
+                (
+                    Some((center.x, center.y)),
+                    "Posted mouse button messages for the element's center to its window",
+                )
+            }
+        };
+        Ok(ClickResult {
+            method: strategy.name().to_string(),
+            coordinates,
+            details: details.to_string(),
+            strategy: Some(strategy),
+            attempts: Vec::new(),
+        })
+    }
+
+    /// Click this element and wait up to `timeout` for `condition` to hold,
+    /// retrying with a physical mouse click at its center if the first click
+    /// had no effect
+    #[instrument(skip(self))]
+    pub fn click_and_expect(
+        &self,
+        condition: &Condition,
+        timeout: Duration,
+    ) -> Result<VerifiedActionResult, AutomationError> {
+        let methods: Vec<ActionMethod> = vec![
+            ("click", Box::new(|| self.click().map(Some))),
+            (
+                "mouse",
+                Box::new(|| self.click_at_anchor(Anchor::Center, (0.0, 0.0)).map(Some)),
+            ),
+        ];
+        run_verified(self, "click", condition, timeout, methods)
+    }
+
+    /// Replace this element's value with `text` and check that the value was
+    /// taken, retrying by pasting and then through the value pattern when
+    /// keystrokes were dropped
+    #[instrument(skip(self, text))]
+    pub fn type_text_verified(&self, text: &str) -> Result<VerifiedActionResult, AutomationError> {
+        // Typing appends, so clear the field first where the platform allows it
+        let clear = || {
+            let _ = self.inner.set_value("");
+        };
+        let methods: Vec<ActionMethod> = vec![
+            (
+                "type",
+                Box::new(|| {
+                    self.wait_until_editable(DEFAULT_EDITABLE_TIMEOUT)?;
+                    clear();
+                    self.type_text_with_wait(text, false, None).map(|_| None)
+                }),
+            ),
+            (
+                "clipboard",
+                Box::new(|| {
+                    clear();
+                    self.type_text_with_wait(text, true, None).map(|_| None)
+                }),
+            ),
+            ("value_pattern", Box::new(|| self.set_value(text).map(|_| None))),
+        ];
+        let condition = Condition::ValueEquals(text.to_string());
+        run_verified(self, "type_text", &condition, TYPE_SETTLE_TIMEOUT, methods)
     }
 
     /// Double-click on this element
     #[instrument(skip(self))]
     pub fn double_click(&self) -> Result<ClickResult, AutomationError> {
-        self.inner.double_click()
+        middleware::run(ActionKind::DoubleClick, self, None, || {
+            self.ensure_on_current_desktop()?;
+            self.ensure_reachable()?;
+            self.inner.double_click()
+        })
     }
 
     /// Right-click on this element
     #[instrument(skip(self))]
     pub fn right_click(&self) -> Result<(), AutomationError> {
-        self.inner.right_click()
+        middleware::run(ActionKind::RightClick, self, None, || {
+            self.ensure_on_current_desktop()?;
+            self.ensure_reachable()?;
+            self.inner.right_click()
+        })
     }
 
     /// Hover over this element
     pub fn hover(&self) -> Result<(), AutomationError> {
-        self.inner.hover()
+        middleware::run(ActionKind::Hover, self, None, || self.inner.hover())
     }
 
     /// Focus this element
     pub fn focus(&self) -> Result<(), AutomationError> {
-        self.inner.focus()
+        middleware::run(ActionKind::Focus, self, None, || self.inner.focus())
     }
 
-    /// Type text into this element
+    /// Type text into this element, first waiting until it is enabled,
+    /// keyboard focusable and not read-only
     pub fn type_text(&self, text: &str, use_clipboard: bool) -> Result<(), AutomationError> {
-        self.inner.type_text(text, use_clipboard)
+        self.type_text_with_wait(text, use_clipboard, Some(DEFAULT_EDITABLE_TIMEOUT))
+    }
+
+    /// Type text into this element, waiting up to `editable_timeout` for it to
+    /// accept input. `None` types right away without checking its state.
+    pub fn type_text_with_wait(
+        &self,
+        text: &str,
+        use_clipboard: bool,
+        editable_timeout: Option<Duration>,
+    ) -> Result<(), AutomationError> {
+        middleware::run(ActionKind::TypeText, self, Some(text.to_string()), || {
+            self.ensure_reachable()?;
+            if let Some(timeout) = editable_timeout {
+                self.wait_until_editable(timeout)?;
+            }
+            self.inner.type_text(text, use_clipboard)
+        })
+    }
+
+    /// Wait until this element accepts keyboard input: enabled, keyboard
+    /// focusable and not read-only. Checks the platform cannot perform are
+    /// skipped. Fails with `AutomationError::ElementNotEditable` naming the
+    /// state that still blocks input when `timeout` expires.
+    #[instrument(skip(self))]
+    pub fn wait_until_editable(&self, timeout: Duration) -> Result<(), AutomationError> {
+        let start = Instant::now();
+        loop {
+            match self.editable_blocker()? {
+                None => return Ok(()),
+                Some(reason) if start.elapsed() >= timeout => {
+                    return Err(AutomationError::ElementNotEditable(format!(
+                        "{} '{}' {} after waiting {}ms",
+                        self.role(),
+                        self.name_or_empty(),
+                        reason,
+                        timeout.as_millis()
+                    )));
+                }
+                Some(_) => std::thread::sleep(EDITABLE_POLL_INTERVAL),
+            }
+        }
+    }
+
+    // The first state that prevents typing into this element, if any
+    fn editable_blocker(&self) -> Result<Option<&'static str>, AutomationError> {
+        if skip_unsupported(self.inner.is_enabled())? == Some(false) {
+            return Ok(Some("is still disabled"));
+        }
+        if skip_unsupported(self.inner.is_keyboard_focusable())? == Some(false) {
+            return Ok(Some("is not keyboard focusable"));
+        }
+        if skip_unsupported(self.inner.is_read_only())? == Some(true) {
+            return Ok(Some("is read-only"));
+        }
+        Ok(None)
     }
 
     /// Press a key while this element is focused
     pub fn press_key(&self, key: &str) -> Result<(), AutomationError> {
-        self.inner.press_key(key)
+        middleware::run(ActionKind::PressKey, self, Some(key.to_string()), || {
+            self.ensure_reachable()?;
+            self.inner.press_key(key)
+        })
     }
 
     /// Get text content of this element
@@ -381,7 +1046,7 @@ impl UIElement {
 
     /// Set value of this element
     pub fn set_value(&self, value: &str) -> Result<(), AutomationError> {
-        self.inner.set_value(value)
+        middleware::run(ActionKind::SetValue, self, Some(value.to_string()), || self.inner.set_value(value))
     }
 
     /// Check if element is enabled
@@ -400,9 +1065,151 @@ impl UIElement {
         self.inner.is_focused()
     }
 
+    /// Check if element's value is read-only
+    pub fn is_read_only(&self) -> Result<bool, AutomationError> {
+        self.inner.is_read_only()
+    }
+
+    /// Current value of an attribute: "name", "value", "description",
+    /// "label", "role", "enabled", "focused", "visible" or a platform property
+    /// name (e.g. "AutomationId"). `None` if the element has no such value
+    pub fn attribute_value(&self, attribute: &str) -> Result<Option<String>, AutomationError> {
+        self.inner.attribute_value(attribute)
+    }
+
+    /// Stream the value of `attribute` (see [`UIElement::attribute_value`]):
+    /// the current value first, then every change. The attribute is polled
+    /// every `interval`; on Windows, UIA property change events for "name",
+    /// "value", "description", "enabled", "focused" and "visible" are picked
+    /// up right away. Must be called within a Tokio runtime
+    pub fn watch_attribute(&self, attribute: &str, interval: Duration) -> Result<AttributeWatch, AutomationError> {
+        AttributeWatch::start(self.clone(), attribute, interval)
+    }
+
+    pub(crate) fn watch_attribute_changes(
+        &self,
+        attribute: &str,
+        changed: Arc<dyn Fn() + Send + Sync>,
+    ) -> Result<UIEventSubscription, AutomationError> {
+        self.inner.watch_attribute_changes(attribute, changed)
+    }
+
     /// Perform a named action on this element
     pub fn perform_action(&self, action: &str) -> Result<(), AutomationError> {
-        self.inner.perform_action(action)
+        middleware::run(ActionKind::PerformAction, self, Some(action.to_string()), || {
+            self.inner.perform_action(action)
+        })
+    }
+
+    /// Trigger the element's default action (a button press, a menu item,
+    /// a link) through the accessibility API, without moving the mouse. Works
+    /// on elements that are covered or off screen
+    pub fn invoke(&self) -> Result<(), AutomationError> {
+        middleware::run(ActionKind::PerformAction, self, Some("invoke".to_string()), || {
+            self.inner.invoke()
+        })
+    }
+
+    /// Move a check box or toggle button to its next state without clicking it
+    pub fn toggle(&self) -> Result<(), AutomationError> {
+        middleware::run(ActionKind::PerformAction, self, Some("toggle".to_string()), || {
+            self.inner.toggle()
+        })
+    }
+
+    /// Toggle state: `Some(true)` when on, `None` when indeterminate
+    pub fn toggle_state(&self) -> Result<Option<bool>, AutomationError> {
+        self.inner.toggle_state()
+    }
+
+    /// Whether the element shows its children, e.g. an open combo box or menu
+    pub fn is_expanded(&self) -> Result<bool, AutomationError> {
+        self.inner.is_expanded()
+    }
+
+    /// Expand or collapse a combo box, menu item or tree item
+    pub fn set_expanded(&self, expanded: bool) -> Result<(), AutomationError> {
+        let action = if expanded { "expand" } else { "collapse" };
+        middleware::run(ActionKind::PerformAction, self, Some(action.to_string()), || {
+            self.inner.set_expanded(expanded)
+        })
+    }
+
+    /// Expand a combo box, menu item or tree item, see [`UIElement::set_expanded`]
+    pub fn expand(&self) -> Result<(), AutomationError> {
+        self.set_expanded(true)
+    }
+
+    /// Collapse a combo box, menu item or tree item, see [`UIElement::set_expanded`]
+    pub fn collapse(&self) -> Result<(), AutomationError> {
+        self.set_expanded(false)
+    }
+
+    /// Whether the element is the selected item of its container
+    pub fn is_selected(&self) -> Result<bool, AutomationError> {
+        self.inner.is_selected()
+    }
+
+    /// Select the element in its list, combo box or tab strip without clicking it
+    pub fn select_item(&self) -> Result<(), AutomationError> {
+        middleware::run(ActionKind::PerformAction, self, Some("select".to_string()), || {
+            self.inner.select_item()
+        })
+    }
+
+    /// Select the element in its container, same as [`UIElement::select_item`]
+    pub fn select(&self) -> Result<(), AutomationError> {
+        self.select_item()
+    }
+
+    /// A UI Automation control pattern of the element, for what the element
+    /// API does not cover, e.g. `element.pattern::<UIScrollItemPattern>()`.
+    /// Pattern types come from the `uiautomation` crate, re-exported as
+    /// [`crate::uiautomation`]. Fails with `UnsupportedOperation` if the
+    /// element does not implement the pattern
+    #[cfg(target_os = "windows")]
+    pub fn pattern<P: uiautomation::patterns::UIPattern>(&self) -> Result<P, AutomationError> {
+        crate::platforms::windows::element_pattern::<P>(self)
+    }
+
+    /// Value and bounds of a slider, spinner or progress bar
+    pub fn range_value(&self) -> Result<RangeValue, AutomationError> {
+        self.inner.range_value()
+    }
+
+    /// Set the value of a slider or spinner, within its [`range_value`](UIElement::range_value) bounds
+    pub fn set_range_value(&self, value: f64) -> Result<(), AutomationError> {
+        middleware::run(ActionKind::SetValue, self, Some(value.to_string()), || {
+            self.inner.set_range_value(value)
+        })
+    }
+
+    /// Text selected in an edit control or document, empty when nothing is
+    /// selected. Multiple selections are joined with newlines
+    pub fn selected_text(&self) -> Result<String, AutomationError> {
+        self.inner.selected_text()
+    }
+
+    /// Character offset of the caret in an edit control or document, `None`
+    /// when the element shows no caret (it usually needs the focus)
+    pub fn caret_position(&self) -> Result<Option<usize>, AutomationError> {
+        self.inner.caret_position()
+    }
+
+    /// Select the characters from offset `start` up to `end` in an edit
+    /// control or document. `start == end` places the caret there
+    pub fn select_range(&self, start: usize, end: usize) -> Result<(), AutomationError> {
+        crate::text_pattern::check_range(start, end)?;
+        let details = format!("{}..{}", start, end);
+        middleware::run(ActionKind::PerformAction, self, Some(details), || {
+            self.inner.select_range(start, end)
+        })
+    }
+
+    /// The text of an edit control or document as runs of the same font,
+    /// weight, style and colors, see [`crate::text_pattern`]
+    pub fn text_with_formatting(&self) -> Result<Vec<TextRun>, AutomationError> {
+        self.inner.text_runs().map(crate::text_pattern::merge_runs)
     }
 
     /// Get the underlying implementation as a specific type
@@ -413,17 +1220,18 @@ impl UIElement {
     /// Find elements matching the selector within this element
     pub fn locator(&self, selector: impl Into<Selector>) -> Result<Locator, AutomationError> {
         let selector = selector.into();
-        self.inner.create_locator(selector)
+        Ok(self.inner.create_locator(selector)?.with_hooks(self.hooks.clone()))
     }
 
     /// Scroll the element in a given direction
     pub fn scroll(&self, direction: &str, amount: f64) -> Result<(), AutomationError> {
-        self.inner.scroll(direction, amount)
+        let details = format!("{} {}", direction, amount);
+        middleware::run(ActionKind::Scroll, self, Some(details), || self.inner.scroll(direction, amount))
     }
 
     /// Activate the window containing this element (bring to foreground)
     pub fn activate_window(&self) -> Result<(), AutomationError> {
-        self.inner.activate_window()
+        middleware::run(ActionKind::ActivateWindow, self, None, || self.inner.activate_window())
     }
 
     /// Get the element's name
@@ -439,39 +1247,48 @@ impl UIElement {
 
     /// Drag mouse from start to end coordinates
     pub fn mouse_drag(&self, start_x: f64, start_y: f64, end_x: f64, end_y: f64) -> Result<(), AutomationError> {
-        self.inner.mouse_drag(start_x, start_y, end_x, end_y)
+        let details = format!("({}, {}) -> ({}, {})", start_x, start_y, end_x, end_y);
+        middleware::run(ActionKind::MouseDrag, self, Some(details), || {
+            self.inner.mouse_drag(start_x, start_y, end_x, end_y)
+        })
     }
 
     /// Press and hold mouse at (x, y)
     pub fn mouse_click_and_hold(&self, x: f64, y: f64) -> Result<(), AutomationError> {
-        self.inner.mouse_click_and_hold(x, y)
+        middleware::run(ActionKind::MouseDown, self, Some(format!("({}, {})", x, y)), || {
+            self.inner.mouse_click_and_hold(x, y)
+        })
     }
 
     /// Move mouse to (x, y)
     pub fn mouse_move(&self, x: f64, y: f64) -> Result<(), AutomationError> {
-        self.inner.mouse_move(x, y)
+        middleware::run(ActionKind::MouseMove, self, Some(format!("({}, {})", x, y)), || {
+            self.inner.mouse_move(x, y)
+        })
     }
 
     /// Release mouse button
     pub fn mouse_release(&self) -> Result<(), AutomationError> {
-        self.inner.mouse_release()
+        middleware::run(ActionKind::MouseUp, self, None, || self.inner.mouse_release())
     }
 
     /// Get the containing application element
     pub fn application(&self) -> Result<Option<UIElement>, AutomationError> {
-        self.inner.application()
+        Ok(self.inner.application()?.map(|application| self.related(application)))
     }
 
     /// Get the containing window element (e.g., tab, dialog)
     pub fn window(&self) -> Result<Option<UIElement>, AutomationError> {
-        self.inner.window()
+        Ok(self.inner.window()?.map(|window| self.related(window)))
     }
 
     /// Highlights the element with a colored border.
     /// 
     /// # Arguments
     /// * `color` - Optional BGR color code (32-bit integer). Default: 0x0000FF (red)
-    /// * `duration` - Optional duration for the highlight.
+    /// * `duration` - Optional duration for the highlight. Default: 500ms
+    ///
+    /// Returns right away; the border is drawn by a click-through overlay.
     pub fn highlight(&self, color: Option<u32>, duration: Option<std::time::Duration>) -> Result<(), AutomationError> {
         self.inner.highlight(color, duration)
     }
@@ -481,10 +1298,169 @@ impl UIElement {
         self.inner.capture()
     }
 
+    /// Capture a screenshot of the element encoded as `format`
+    pub fn capture_as(&self, format: ImageFormat) -> Result<EncodedImage, AutomationError> {
+        self.capture()?.encode(format)
+    }
+
     /// Close the element if it's closable (like windows, applications)
     /// Does nothing for non-closable elements (like buttons, text, etc.)
     pub fn close(&self) -> Result<(), AutomationError> {
-        self.inner.close()
+        middleware::run(ActionKind::Close, self, None, || self.inner.close())
+    }
+
+    /// Whether the window containing this element is always on top
+    pub fn is_topmost(&self) -> Result<bool, AutomationError> {
+        self.inner.is_topmost()
+    }
+
+    /// Make the window containing this element always on top, or clear it
+    pub fn set_topmost(&self, topmost: bool) -> Result<(), AutomationError> {
+        middleware::run(ActionKind::ArrangeWindow, self, Some(format!("topmost {}", topmost)), || {
+            self.inner.set_topmost(topmost)
+        })
+    }
+
+    /// Opacity of the window containing this element, from 0.0 (fully
+    /// transparent) to 1.0 (fully opaque)
+    pub fn opacity(&self) -> Result<f32, AutomationError> {
+        self.inner.opacity()
+    }
+
+    /// Set the opacity of the window containing this element.
+    ///
+    /// # Arguments
+    /// * `opacity` - Value between 0.0 (fully transparent) and 1.0 (fully opaque)
+    pub fn set_opacity(&self, opacity: f32) -> Result<(), AutomationError> {
+        if !(0.0..=1.0).contains(&opacity) {
+            return Err(AutomationError::InvalidArgument(format!(
+                "opacity must be between 0.0 and 1.0, got {}",
+                opacity
+            )));
+        }
+        middleware::run(ActionKind::ArrangeWindow, self, Some(format!("opacity {}", opacity)), || {
+            self.inner.set_opacity(opacity)
+        })
+    }
+
+    /// Whether the window containing this element is minimized, maximized
+    /// or neither
+    pub fn window_state(&self) -> Result<WindowState, AutomationError> {
+        self.inner.window_state()
+    }
+
+    /// Maximize the window containing this element
+    pub fn maximize(&self) -> Result<(), AutomationError> {
+        self.arrange_window(WindowState::Maximized)
+    }
+
+    /// Minimize the window containing this element
+    pub fn minimize(&self) -> Result<(), AutomationError> {
+        self.arrange_window(WindowState::Minimized)
+    }
+
+    /// Restore the window containing this element from the minimized or
+    /// maximized state
+    pub fn restore(&self) -> Result<(), AutomationError> {
+        self.arrange_window(WindowState::Normal)
+    }
+
+    fn arrange_window(&self, state: WindowState) -> Result<(), AutomationError> {
+        middleware::run(ActionKind::ArrangeWindow, self, Some(state.to_string()), || {
+            self.inner.set_window_state(state)
+        })
+    }
+
+    /// Move and resize the window containing this element. Restores the
+    /// window first if it is minimized or maximized, so the size sticks.
+    ///
+    /// # Arguments
+    /// * `x`, `y` - Top-left corner in screen coordinates
+    /// * `width`, `height` - Outer size of the window, must be positive
+    pub fn set_bounds(&self, x: f64, y: f64, width: f64, height: f64) -> Result<(), AutomationError> {
+        check_window_bounds(x, y, width, height)?;
+        let details = format!("{},{} {}x{}", x, y, width, height);
+        middleware::run(ActionKind::ArrangeWindow, self, Some(details), || {
+            if matches!(self.inner.window_state(), Ok(state) if state != WindowState::Normal) {
+                self.inner.set_window_state(WindowState::Normal)?;
+            }
+            self.inner.set_window_bounds(x, y, width, height)
+        })
+    }
+
+    /// Raise the window containing this element above all others and give
+    /// it the input focus, restoring it first if it is minimized
+    pub fn bring_to_front(&self) -> Result<(), AutomationError> {
+        middleware::run(ActionKind::ActivateWindow, self, None, || self.inner.bring_to_front())
+    }
+
+    /// Id of the virtual desktop the window containing this element is on,
+    /// see [`crate::VirtualDesktop::id`]
+    pub fn virtual_desktop(&self) -> Result<String, AutomationError> {
+        self.inner.virtual_desktop_id()
+    }
+
+    /// Whether the window containing this element is on the virtual desktop
+    /// currently shown. Elements of windows on other desktops are in the
+    /// tree but cannot receive input
+    pub fn is_on_current_virtual_desktop(&self) -> Result<bool, AutomationError> {
+        self.inner.is_on_current_virtual_desktop()
+    }
+
+    /// Move the window containing this element to another virtual desktop.
+    ///
+    /// Windows only allows this for windows of the calling process, and
+    /// fails with `PermissionDenied` for other applications' windows.
+    ///
+    /// # Arguments
+    /// * `desktop_id` - A [`crate::VirtualDesktop::id`] from [`crate::Desktop::virtual_desktops`]
+    pub fn move_to_desktop(&self, desktop_id: &str) -> Result<(), AutomationError> {
+        middleware::run(ActionKind::ArrangeWindow, self, Some(desktop_id.to_string()), || {
+            self.inner.move_to_virtual_desktop(desktop_id)
+        })
+    }
+
+    // Input sent to a window on another virtual desktop goes nowhere, so
+    // fail instead of reporting a click that did nothing
+    fn ensure_on_current_desktop(&self) -> Result<(), AutomationError> {
+        if skip_unsupported(self.inner.is_on_current_virtual_desktop()).ok().flatten() == Some(false) {
+            return Err(AutomationError::UIUnavailable(format!(
+                "'{}' is in a window on another virtual desktop; switch to it or move the window with move_to_desktop",
+                self.name_or_empty()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Integrity level of the process this element belongs to. Only
+    /// Windows has integrity levels
+    pub fn integrity_level(&self) -> Result<IntegrityLevel, AutomationError> {
+        self.inner.integrity_level()
+    }
+
+    // Windows drops input sent to processes at a higher integrity level
+    // without an error, so fail before sending any
+    fn ensure_reachable(&self) -> Result<(), AutomationError> {
+        let target = match self.inner.integrity_level() {
+            Ok(level) => level,
+            Err(e) if matches!(e.kind(), AutomationError::UnsupportedOperation(_) | AutomationError::UnsupportedPlatform(_)) => {
+                return Ok(());
+            }
+            Err(e) => {
+                debug!("Failed to read the integrity level of '{}': {}", self.name_or_empty(), e);
+                return Ok(());
+            }
+        };
+        let own = elevation::current();
+        if own.can_drive(target) {
+            return Ok(());
+        }
+        Err(AutomationError::ElevationRequired(format!(
+            "'{}' belongs to a process running at {} integrity, above this process ({}); run the automation as administrator or with UIAccess",
+            self.name_or_empty(),
+            target,
+            own.integrity_level.map(|level| level.to_string()).unwrap_or_default()
+        )))
     }
 
     /// Convenience methods to reduce verbosity with optional properties
@@ -620,6 +1596,7 @@ impl Clone for UIElement {
         // that will behave the same way
         Self {
             inner: self.inner.clone_box(),
+            hooks: self.hooks.clone(),
         }
     }
 }
@@ -0,0 +1,137 @@
+//! Integrity levels and the windows automation can reach
+//!
+//! Windows keeps processes from sending input to windows of processes
+//! running at a higher integrity level (User Interface Privilege Isolation).
+//! A click on an application started "as administrator" from a normal
+//! process is dropped without any error. Terminator checks the integrity
+//! level of the target first and fails with
+//! [`AutomationError::ElevationRequired`](crate::AutomationError::ElevationRequired)
+//! instead, and [`Desktop::capabilities`](crate::Desktop::capabilities)
+//! reports up front what the current process can reach:
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop};
+//! # fn run() -> Result<(), AutomationError> {
+//! let desktop = Desktop::new(false, false)?;
+//! let capabilities = desktop.capabilities();
+//! if !capabilities.reaches_elevated_windows() {
+//!     eprintln!("Run elevated or with UIAccess to automate admin applications");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! There are two ways to reach elevated windows: run the automation itself
+//! elevated, or give its executable UIAccess. UIAccess lifts the input
+//! restriction without running as administrator, but Windows only grants it
+//! to an executable that embeds [`UI_ACCESS_MANIFEST`], is signed with a
+//! trusted certificate, and is installed in a secure location such as
+//! `C:\Program Files`.
+
+use serde::{Deserialize, Serialize};
+
+/// Application manifest that requests UIAccess without elevation. Embed it
+/// in the automation executable, e.g. with the `embed-manifest` crate or
+/// `mt.exe`, then sign the executable and install it under `Program Files`
+pub const UI_ACCESS_MANIFEST: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0">
+  <trustInfo xmlns="urn:schemas-microsoft-com:asm.v3">
+    <security>
+      <requestedPrivileges>
+        <requestedExecutionLevel level="asInvoker" uiAccess="true"/>
+      </requestedPrivileges>
+    </security>
+  </trustInfo>
+</assembly>
+"#;
+
+/// Mandatory integrity level of a process, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityLevel {
+    Untrusted,
+    /// Sandboxed processes, e.g. browser renderers
+    Low,
+    /// Normal applications
+    Medium,
+    /// Applications running as administrator
+    High,
+    /// Services
+    System,
+}
+
+impl IntegrityLevel {
+    /// Level of a mandatory label RID, e.g. `0x3000` for high. RIDs between
+    /// the named levels (such as medium plus) count as the level below them
+    pub fn from_rid(rid: u32) -> Self {
+        match rid {
+            0..0x1000 => IntegrityLevel::Untrusted,
+            0x1000..0x2000 => IntegrityLevel::Low,
+            0x2000..0x3000 => IntegrityLevel::Medium,
+            0x3000..0x4000 => IntegrityLevel::High,
+            _ => IntegrityLevel::System,
+        }
+    }
+}
+
+impl std::fmt::Display for IntegrityLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            IntegrityLevel::Untrusted => "untrusted",
+            IntegrityLevel::Low => "low",
+            IntegrityLevel::Medium => "medium",
+            IntegrityLevel::High => "high",
+            IntegrityLevel::System => "system",
+        })
+    }
+}
+
+/// What the current process can automate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutomationCapabilities {
+    /// Integrity level of this process, None where the platform has none
+    pub integrity_level: Option<IntegrityLevel>,
+    /// Whether this process runs as administrator
+    pub elevated: bool,
+    /// Whether this process was granted UIAccess, which lets it send input
+    /// to windows of any integrity level
+    pub ui_access: bool,
+}
+
+impl AutomationCapabilities {
+    /// Capabilities of a platform without integrity levels: no restrictions
+    pub fn unrestricted() -> Self {
+        Self {
+            integrity_level: None,
+            elevated: false,
+            ui_access: false,
+        }
+    }
+
+    /// Whether input from this process reaches windows of a process running
+    /// at `target`
+    pub fn can_drive(&self, target: IntegrityLevel) -> bool {
+        match self.integrity_level {
+            None => true,
+            Some(own) => self.ui_access || target <= own,
+        }
+    }
+
+    /// Whether input from this process reaches applications running as
+    /// administrator
+    pub fn reaches_elevated_windows(&self) -> bool {
+        self.can_drive(IntegrityLevel::High)
+    }
+}
+
+/// Capabilities of the current process, which don't change while it runs
+pub(crate) fn current() -> AutomationCapabilities {
+    #[cfg(target_os = "windows")]
+    {
+        crate::platforms::windows_elevation::current_capabilities()
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        AutomationCapabilities::unrestricted()
+    }
+}
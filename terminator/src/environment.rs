@@ -0,0 +1,54 @@
+//! Information about the desktop environment automation runs in.
+//!
+//! OS build, theme, animation and scaling settings, and assistive technology
+//! all change what the accessibility tree looks like and how long UI takes to
+//! settle. Attach [`EnvironmentInfo`] to bug reports so "works on my machine"
+//! failures can be compared across machines. Settings a platform cannot read
+//! are left as `None`.
+
+use serde::{Deserialize, Serialize};
+
+/// Color theme applications are asked to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+/// Snapshot of the OS settings that affect UI trees and timings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentInfo {
+    /// Operating system family: "windows", "macos" or "linux"
+    pub os: String,
+    /// Human-readable OS version, e.g. "Windows 11 Pro"
+    pub os_version: Option<String>,
+    /// OS build number, e.g. "22631.3296"
+    pub os_build: Option<String>,
+    /// Theme used for application windows
+    pub theme: Option<Theme>,
+    /// Whether window and control animations are enabled
+    pub animations_enabled: Option<bool>,
+    /// Accessibility text size multiplier (1.0 = 100%)
+    pub text_scale_factor: Option<f64>,
+    /// Display scale factor of the primary monitor (1.0 = 100%)
+    pub display_scale_factor: Option<f64>,
+    /// Whether a screen reader is running
+    pub screen_reader_active: Option<bool>,
+}
+
+impl EnvironmentInfo {
+    /// Environment with only the OS family filled in
+    pub fn unknown() -> Self {
+        Self {
+            os: std::env::consts::OS.to_string(),
+            os_version: None,
+            os_build: None,
+            theme: None,
+            animations_enabled: None,
+            text_scale_factor: None,
+            display_scale_factor: None,
+            screen_reader_active: None,
+        }
+    }
+}
@@ -1,10 +1,139 @@
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+use std::time::Duration;
 use thiserror::Error;
 
+use crate::permissions::Permission;
+use crate::verify::VerificationReport;
+
+/// A candidate element that nearly matched a selector which found nothing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelectorSuggestion {
+    /// Role of the candidate element
+    pub role: String,
+    /// Name of the candidate element, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Native automation id (e.g. `AutomationId` on Windows), if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automation_id: Option<String>,
+    /// A selector string that would match this candidate
+    pub selector: String,
+    /// Similarity to the requested selector, from 0.0 to 1.0
+    pub score: f64,
+}
+
+fn format_suggestions(suggestions: &[SelectorSuggestion]) -> String {
+    if suggestions.is_empty() {
+        return String::new();
+    }
+    let list: Vec<String> = suggestions
+        .iter()
+        .map(|s| format!("'{}' ({:.2})", s.selector, s.score))
+        .collect();
+    format!(". Did you mean: {}?", list.join(", "))
+}
+
+/// Stable identifier of an error kind, for mapping errors without parsing
+/// their messages. A code never changes meaning once released
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    ElementNotFound = 1001,
+    ElementNotEditable = 1002,
+    #[serde(rename = "OPERATION_TIMED_OUT")]
+    Timeout = 1003,
+    PermissionDenied = 1004,
+    PlatformError = 1005,
+    UnsupportedOperation = 1006,
+    UnsupportedPlatform = 1007,
+    InvalidArgument = 1008,
+    UiUnavailable = 1009,
+    StalledRun = 1010,
+    VerificationFailed = 1011,
+    ElevationRequired = 1012,
+    MissingPermission = 1013,
+    StaleElement = 1014,
+    #[serde(rename = "INTERNAL_ERROR")]
+    Internal = 1099,
+}
+
+impl ErrorCode {
+    /// The numeric code
+    pub fn as_u16(self) -> u16 {
+        self as u16
+    }
+
+    /// The symbolic name, e.g. `ELEMENT_NOT_FOUND`
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorCode::ElementNotFound => "ELEMENT_NOT_FOUND",
+            ErrorCode::ElementNotEditable => "ELEMENT_NOT_EDITABLE",
+            ErrorCode::Timeout => "OPERATION_TIMED_OUT",
+            ErrorCode::PermissionDenied => "PERMISSION_DENIED",
+            ErrorCode::PlatformError => "PLATFORM_ERROR",
+            ErrorCode::UnsupportedOperation => "UNSUPPORTED_OPERATION",
+            ErrorCode::UnsupportedPlatform => "UNSUPPORTED_PLATFORM",
+            ErrorCode::InvalidArgument => "INVALID_ARGUMENT",
+            ErrorCode::UiUnavailable => "UI_UNAVAILABLE",
+            ErrorCode::StalledRun => "STALLED_RUN",
+            ErrorCode::VerificationFailed => "VERIFICATION_FAILED",
+            ErrorCode::ElevationRequired => "ELEVATION_REQUIRED",
+            ErrorCode::MissingPermission => "MISSING_PERMISSION",
+            ErrorCode::StaleElement => "STALE_ELEMENT",
+            ErrorCode::Internal => "INTERNAL_ERROR",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// What an operation was doing when it failed. Every field is optional
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ErrorContext {
+    /// Selector that was being resolved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    /// Element the search started from, e.g. `Window 'Untitled - Notepad'`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    /// Timeout that applied, in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Error code reported by the platform, such as an HRESULT
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform_code: Option<i64>,
+    /// Nearest-matching candidates of a selector that found nothing, best
+    /// first. Serialized next to the context, see [`AutomationError::suggestions`]
+    #[serde(skip)]
+    pub suggestions: Vec<SelectorSuggestion>,
+}
+
+impl ErrorContext {
+    fn merge(&mut self, other: ErrorContext) {
+        // Context attached closer to the failure wins
+        self.selector = self.selector.take().or(other.selector);
+        self.root = self.root.take().or(other.root);
+        self.timeout_ms = self.timeout_ms.or(other.timeout_ms);
+        self.platform_code = self.platform_code.or(other.platform_code);
+        if self.suggestions.is_empty() {
+            self.suggestions = other.suggestions;
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum AutomationError {
     #[error("Element not found: {0}")]
     ElementNotFound(String),
 
+    #[error("Element not editable: {0}")]
+    ElementNotEditable(String),
+
     #[error("Operation timed out: {0}")]
     Timeout(String),
 
@@ -23,6 +152,200 @@ pub enum AutomationError {
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
 
+    #[error("UI not available: {0}")]
+    UIUnavailable(String),
+
+    #[error("Automation run stalled: {0}")]
+    StalledRun(String),
+
+    #[error("Verification failed: {0}")]
+    VerificationFailed(Box<VerificationReport>),
+
+    /// The target runs at a higher integrity level than this process, so
+    /// Windows would drop the input sent to it
+    #[error("Elevation required: {0}")]
+    ElevationRequired(String),
+
+    /// macOS has not granted a permission the operation needs; see
+    /// [`permissions`](crate::permissions)
+    #[error("Missing permission: {}", .0.hint())]
+    MissingPermission(Permission),
+
+    /// A handle whose element no longer exists, e.g. a list item recreated
+    /// by virtualization; see [`stale_element`](crate::stale_element)
+    #[error("Stale element: {0}")]
+    StaleElement(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
+
+    /// Another error with what was being done when it happened. Created by
+    /// [`AutomationError::with_context`] and never nested
+    #[error("{source}{}", format_suggestions(&.context.suggestions))]
+    WithContext {
+        source: Box<AutomationError>,
+        context: Box<ErrorContext>,
+    },
+}
+
+impl AutomationError {
+    /// The error itself, without any attached context
+    pub fn kind(&self) -> &AutomationError {
+        match self {
+            AutomationError::WithContext { source, .. } => source.kind(),
+            other => other,
+        }
+    }
+
+    /// The error itself, without any attached context
+    pub fn into_kind(self) -> AutomationError {
+        match self {
+            AutomationError::WithContext { source, .. } => source.into_kind(),
+            other => other,
+        }
+    }
+
+    /// Stable code of the error kind
+    pub fn code(&self) -> ErrorCode {
+        match self.kind() {
+            AutomationError::ElementNotFound(_) => ErrorCode::ElementNotFound,
+            AutomationError::ElementNotEditable(_) => ErrorCode::ElementNotEditable,
+            AutomationError::Timeout(_) => ErrorCode::Timeout,
+            AutomationError::PermissionDenied(_) => ErrorCode::PermissionDenied,
+            AutomationError::PlatformError(_) => ErrorCode::PlatformError,
+            AutomationError::UnsupportedOperation(_) => ErrorCode::UnsupportedOperation,
+            AutomationError::UnsupportedPlatform(_) => ErrorCode::UnsupportedPlatform,
+            AutomationError::InvalidArgument(_) => ErrorCode::InvalidArgument,
+            AutomationError::UIUnavailable(_) => ErrorCode::UiUnavailable,
+            AutomationError::StalledRun(_) => ErrorCode::StalledRun,
+            AutomationError::VerificationFailed(_) => ErrorCode::VerificationFailed,
+            AutomationError::ElevationRequired(_) => ErrorCode::ElevationRequired,
+            AutomationError::MissingPermission(_) => ErrorCode::MissingPermission,
+            AutomationError::StaleElement(_) => ErrorCode::StaleElement,
+            AutomationError::Internal(_) => ErrorCode::Internal,
+            AutomationError::WithContext { .. } => unreachable!("context is never nested"),
+        }
+    }
+
+    /// The message without the kind prefix of `Display`, e.g. just the
+    /// selector description of an `ElementNotFound`
+    pub fn detail(&self) -> String {
+        format!("{}{}", self.kind_detail(), format_suggestions(self.suggestions()))
+    }
+
+    fn kind_detail(&self) -> String {
+        match self.kind() {
+            AutomationError::ElementNotFound(msg)
+            | AutomationError::ElementNotEditable(msg)
+            | AutomationError::Timeout(msg)
+            | AutomationError::PermissionDenied(msg)
+            | AutomationError::PlatformError(msg)
+            | AutomationError::UnsupportedOperation(msg)
+            | AutomationError::UnsupportedPlatform(msg)
+            | AutomationError::InvalidArgument(msg)
+            | AutomationError::UIUnavailable(msg)
+            | AutomationError::StalledRun(msg)
+            | AutomationError::ElevationRequired(msg)
+            | AutomationError::StaleElement(msg)
+            | AutomationError::Internal(msg) => msg.clone(),
+            AutomationError::VerificationFailed(report) => report.to_string(),
+            AutomationError::MissingPermission(permission) => permission.hint(),
+            AutomationError::WithContext { .. } => unreachable!("context is never nested"),
+        }
+    }
+
+    /// Context attached with [`AutomationError::with_context`], if any
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            AutomationError::WithContext { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Attach what was being done when the error happened. Fields already
+    /// attached are kept
+    pub fn with_context(self, context: ErrorContext) -> AutomationError {
+        match self {
+            AutomationError::WithContext {
+                source,
+                context: mut existing,
+            } => {
+                existing.merge(context);
+                AutomationError::WithContext {
+                    source,
+                    context: existing,
+                }
+            }
+            other => AutomationError::WithContext {
+                source: Box::new(other),
+                context: Box::new(context),
+            },
+        }
+    }
+
+    /// Attach the selector that was being resolved
+    pub fn with_selector(self, selector: impl Into<String>) -> AutomationError {
+        self.with_context(ErrorContext {
+            selector: Some(selector.into()),
+            ..Default::default()
+        })
+    }
+
+    /// Attach the timeout that applied
+    pub fn with_timeout(self, timeout: Duration) -> AutomationError {
+        self.with_context(ErrorContext {
+            timeout_ms: Some(timeout.as_millis() as u64),
+            ..Default::default()
+        })
+    }
+
+    /// Attach the error code reported by the platform
+    pub fn with_platform_code(self, code: i64) -> AutomationError {
+        self.with_context(ErrorContext {
+            platform_code: Some(code),
+            ..Default::default()
+        })
+    }
+
+    /// Attach the candidates that nearly matched a selector which found nothing
+    pub fn with_suggestions(self, suggestions: Vec<SelectorSuggestion>) -> AutomationError {
+        self.with_context(ErrorContext {
+            suggestions,
+            ..Default::default()
+        })
+    }
+
+    /// Nearest-matching candidates attached to a selector miss, if any
+    pub fn suggestions(&self) -> &[SelectorSuggestion] {
+        self.context().map_or(&[], |context| &context.suggestions)
+    }
+
+    /// The aggregated report of a failed soft verification, if any
+    pub fn verification_report(&self) -> Option<&VerificationReport> {
+        match self.kind() {
+            AutomationError::VerificationFailed(report) => Some(report),
+            _ => None,
+        }
+    }
+
+    /// Whether this error means the element could not be found
+    pub fn is_element_not_found(&self) -> bool {
+        self.code() == ErrorCode::ElementNotFound
+    }
+}
+
+/// Serialized as `{"code", "name", "message", "detail", "context", "suggestions"}`,
+/// the form the bindings, HTTP server and MCP agent report errors in
+impl Serialize for AutomationError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let code = self.code();
+        let mut state = serializer.serialize_struct("AutomationError", 6)?;
+        state.serialize_field("code", &code.as_u16())?;
+        state.serialize_field("name", code.name())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("detail", &self.detail())?;
+        state.serialize_field("context", &self.context())?;
+        state.serialize_field("suggestions", self.suggestions())?;
+        state.end()
+    }
 }
@@ -0,0 +1,325 @@
+//! Retrying assertions for tests
+//!
+//! [`expect`] wraps an element in assertions that poll until they hold or
+//! their timeout expires, so a test does not need sleeps between an action
+//! and its check:
+//!
+//! ```no_run
+//! # use terminator::{Desktop, expect::expect};
+//! # use std::time::Duration;
+//! # async fn run(desktop: &Desktop) -> Result<(), terminator::AutomationError> {
+//! let status = desktop.locator("name:Status").first(None).await?;
+//! expect(&status).to_have_text("Saved")?;
+//! expect(&status).with_timeout(Duration::from_secs(10)).to_have_attribute("enabled", Some("true"))?;
+//!
+//! let spinner = desktop.locator("role:ProgressBar").first(None).await?;
+//! expect(&spinner).not().to_be_visible()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A failed assertion returns `AutomationError::VerificationFailed`. Its
+//! message has the last value seen, an excerpt of the element's subtree and
+//! the path of a screenshot of the element taken at the time of failure.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use tracing::{debug, warn};
+
+use crate::verify::{VerificationFailure, VerificationReport};
+use crate::{AutomationError, UIElement};
+
+/// How long assertions wait for their condition by default
+pub const DEFAULT_EXPECT_TIMEOUT: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+// Depth and size of the subtree shown in failure messages
+const EXCERPT_DEPTH: usize = 2;
+const EXCERPT_MAX_LINES: usize = 20;
+
+/// What an assertion checks
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expectation {
+    Visible,
+    Enabled,
+    Focused,
+    /// The element's text contains the string
+    Text(String),
+    /// The element's value equals the string
+    Value(String),
+    /// The element's name equals the string
+    Name(String),
+    /// The attribute equals the value, or exists when the value is `None`
+    Attribute(String, Option<String>),
+}
+
+impl Expectation {
+    /// Whether the expectation holds, with the value seen for the message
+    pub fn check(&self, element: &UIElement) -> Result<(bool, String), AutomationError> {
+        let flag = |value: bool| (value, value.to_string());
+        match self {
+            Expectation::Visible => element.is_visible().map(flag),
+            Expectation::Enabled => element.is_enabled().map(flag),
+            Expectation::Focused => element.is_focused().map(flag),
+            Expectation::Text(expected) => element
+                .text(1)
+                .map(|text| (text.contains(expected.as_str()), format!("'{}'", text))),
+            Expectation::Value(expected) => {
+                let value = element.value_or_empty();
+                Ok((value == *expected, format!("'{}'", value)))
+            }
+            Expectation::Name(expected) => {
+                let name = element.name_or_empty();
+                Ok((name == *expected, format!("'{}'", name)))
+            }
+            Expectation::Attribute(name, expected) => {
+                let value = element.attribute_value(name)?;
+                let seen = match &value {
+                    Some(value) => format!("'{}'", value),
+                    None => "no value".to_string(),
+                };
+                let passed = match expected {
+                    Some(expected) => value.as_deref() == Some(expected.as_str()),
+                    None => value.is_some(),
+                };
+                Ok((passed, seen))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expectation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expectation::Visible => write!(f, "to be visible"),
+            Expectation::Enabled => write!(f, "to be enabled"),
+            Expectation::Focused => write!(f, "to be focused"),
+            Expectation::Text(text) => write!(f, "to have text containing '{}'", text),
+            Expectation::Value(value) => write!(f, "to have value '{}'", value),
+            Expectation::Name(name) => write!(f, "to have name '{}'", name),
+            Expectation::Attribute(name, Some(value)) => {
+                write!(f, "to have attribute '{}' = '{}'", name, value)
+            }
+            Expectation::Attribute(name, None) => write!(f, "to have attribute '{}'", name),
+        }
+    }
+}
+
+/// Assertions on one element, see [`expect`]
+#[derive(Debug, Clone)]
+pub struct Expect {
+    element: UIElement,
+    timeout: Duration,
+    negated: bool,
+    screenshot_dir: Option<PathBuf>,
+}
+
+/// Start assertions on `element`, retried for [`DEFAULT_EXPECT_TIMEOUT`].
+/// Failure screenshots go to `terminator-expect` in the temp directory
+pub fn expect(element: &UIElement) -> Expect {
+    Expect {
+        element: element.clone(),
+        timeout: DEFAULT_EXPECT_TIMEOUT,
+        negated: false,
+        screenshot_dir: Some(std::env::temp_dir().join("terminator-expect")),
+    }
+}
+
+impl Expect {
+    /// How long to retry before failing
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Directory for failure screenshots, `None` to take none
+    pub fn with_screenshot_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.screenshot_dir = dir;
+        self
+    }
+
+    /// Invert the next assertion: `expect(&el).not().to_be_visible()` waits
+    /// for the element to be hidden
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(mut self) -> Self {
+        self.negated = !self.negated;
+        self
+    }
+
+    pub fn to_be_visible(&self) -> Result<(), AutomationError> {
+        self.to_satisfy(&Expectation::Visible)
+    }
+
+    pub fn to_be_enabled(&self) -> Result<(), AutomationError> {
+        self.to_satisfy(&Expectation::Enabled)
+    }
+
+    pub fn to_be_focused(&self) -> Result<(), AutomationError> {
+        self.to_satisfy(&Expectation::Focused)
+    }
+
+    /// The element's text (one level deep) contains `expected`
+    pub fn to_have_text(&self, expected: &str) -> Result<(), AutomationError> {
+        self.to_satisfy(&Expectation::Text(expected.to_string()))
+    }
+
+    pub fn to_have_value(&self, expected: &str) -> Result<(), AutomationError> {
+        self.to_satisfy(&Expectation::Value(expected.to_string()))
+    }
+
+    pub fn to_have_name(&self, expected: &str) -> Result<(), AutomationError> {
+        self.to_satisfy(&Expectation::Name(expected.to_string()))
+    }
+
+    /// The attribute, as read by [`UIElement::attribute_value`], equals
+    /// `expected`, or has a value at all when `expected` is `None`
+    pub fn to_have_attribute(
+        &self,
+        name: &str,
+        expected: Option<&str>,
+    ) -> Result<(), AutomationError> {
+        self.to_satisfy(&Expectation::Attribute(
+            name.to_string(),
+            expected.map(str::to_string),
+        ))
+    }
+
+    /// Poll until `expectation` holds (or, negated, stops holding). Checks at
+    /// least once. Failing reads count as not met and are reported if the
+    /// timeout expires
+    pub fn to_satisfy(&self, expectation: &Expectation) -> Result<(), AutomationError> {
+        let start = Instant::now();
+        loop {
+            let seen = match expectation.check(&self.element) {
+                Ok((passed, _)) if passed != self.negated => return Ok(()),
+                Ok((_, seen)) => seen,
+                Err(e) => format!("error: {}", e),
+            };
+            if start.elapsed() >= self.timeout {
+                return Err(self.failure(expectation, seen));
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    fn failure(&self, expectation: &Expectation, seen: String) -> AutomationError {
+        let description = format!("{}{}", if self.negated { "not " } else { "" }, expectation);
+        debug!(expectation = %description, %seen, "Expectation failed");
+        let screenshot = self
+            .screenshot_dir
+            .as_ref()
+            .and_then(|dir| self.save_screenshot(dir));
+        let element = self.element.to_serializable();
+        let message = failure_message(
+            &description,
+            self.timeout,
+            &seen,
+            &tree_excerpt(&self.element),
+            screenshot.as_deref(),
+        );
+        AutomationError::VerificationFailed(Box::new(VerificationReport {
+            step: format!(
+                "expect({} '{}')",
+                element.role,
+                element.name.as_deref().unwrap_or("")
+            ),
+            checks: 1,
+            failures: vec![VerificationFailure {
+                check: description,
+                message,
+                element: Some(element),
+                screenshot: None,
+            }],
+        }))
+    }
+
+    fn save_screenshot(&self, dir: &Path) -> Option<PathBuf> {
+        let shot = self.element.capture().ok()?;
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let path = dir.join(format!("expect-{}.png", stamp));
+        let saved = std::fs::create_dir_all(dir)
+            .map_err(|e| e.to_string())
+            .and_then(|_| {
+                image::RgbaImage::from_raw(shot.width, shot.height, shot.image_data)
+                    .ok_or_else(|| "malformed screenshot".to_string())?
+                    .save(&path)
+                    .map_err(|e| e.to_string())
+            });
+        match saved {
+            Ok(()) => Some(path),
+            Err(e) => {
+                warn!(
+                    "Failed to save expectation screenshot {}: {}",
+                    path.display(),
+                    e
+                );
+                None
+            }
+        }
+    }
+}
+
+/// The message of a failed assertion
+pub(crate) fn failure_message(
+    description: &str,
+    timeout: Duration,
+    seen: &str,
+    excerpt: &str,
+    screenshot: Option<&Path>,
+) -> String {
+    let mut message = format!(
+        "expected element {} within {}ms, got {}",
+        description,
+        timeout.as_millis(),
+        seen
+    );
+    if !excerpt.is_empty() {
+        message.push_str("\nTree excerpt:\n");
+        message.push_str(excerpt);
+    }
+    if let Some(path) = screenshot {
+        message.push_str(&format!("\nScreenshot: {}", path.display()));
+    }
+    message
+}
+
+/// One line of a tree excerpt: `role "name"`, indented by depth
+pub(crate) fn excerpt_line(depth: usize, role: &str, name: Option<&str>) -> String {
+    let indent = "  ".repeat(depth + 1);
+    match name.filter(|name| !name.is_empty()) {
+        Some(name) => format!("{}{} \"{}\"", indent, role, name),
+        None => format!("{}{}", indent, role),
+    }
+}
+
+// The element and its children a few levels down, cut to a few lines
+fn tree_excerpt(element: &UIElement) -> String {
+    let mut lines = Vec::new();
+    collect_excerpt(element, 0, &mut lines);
+    if lines.len() > EXCERPT_MAX_LINES {
+        lines.truncate(EXCERPT_MAX_LINES);
+        lines.push("  ...".to_string());
+    }
+    lines.join("\n")
+}
+
+fn collect_excerpt(element: &UIElement, depth: usize, lines: &mut Vec<String>) {
+    if lines.len() > EXCERPT_MAX_LINES {
+        return;
+    }
+    lines.push(excerpt_line(
+        depth,
+        &element.role(),
+        element.name().as_deref(),
+    ));
+    if depth >= EXCERPT_DEPTH {
+        return;
+    }
+    for child in element.children().unwrap_or_default() {
+        collect_excerpt(&child, depth + 1, lines);
+    }
+}
@@ -0,0 +1,185 @@
+//! Focus history
+//!
+//! A focus tracker keeps the last focused elements, oldest first, so a run
+//! that went wrong can be checked for another application taking focus in
+//! the middle of it. Tracking is off until [`Desktop::track_focus`] is
+//! called and follows the platform's focus events, so it is Windows only
+//! like [`Desktop::on_ui_event`]:
+//!
+//! ```no_run
+//! # fn run(desktop: &terminator::Desktop) -> Result<(), terminator::AutomationError> {
+//! desktop.track_focus(50)?;
+//! // ... automation ...
+//! for change in desktop.focus_history() {
+//!     println!("{} {:?} {} {:?}", change.timestamp_ms, change.application, change.role, change.name);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Desktop::track_focus`]: crate::Desktop::track_focus
+//! [`Desktop::on_ui_event`]: crate::Desktop::on_ui_event
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::platforms::AccessibilityEngine;
+use crate::ui_events::{UIEvent, UIEventKind, UIEventSubscription};
+use crate::{AutomationError, UIElement};
+
+/// Focus changes kept when no capacity is given
+pub const DEFAULT_FOCUS_HISTORY: usize = 100;
+
+/// An element that received keyboard focus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FocusRecord {
+    /// When focus moved, in milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub application: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_id: Option<u32>,
+    pub role: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+impl FocusRecord {
+    pub fn from_element(element: &UIElement) -> Self {
+        Self {
+            timestamp_ms: now_ms(),
+            application: Some(element.application_name()).filter(|name| !name.is_empty()),
+            process_id: element.process_id().ok(),
+            role: element.role(),
+            name: element.name().filter(|name| !name.is_empty()),
+            id: element.id(),
+        }
+    }
+
+    // Same element, ignoring when it was focused
+    fn same_element(&self, other: &FocusRecord) -> bool {
+        self.process_id == other.process_id && self.role == other.role && self.name == other.name && self.id == other.id
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Ring buffer of the last focus changes
+#[derive(Debug, Clone)]
+pub struct FocusHistory {
+    records: VecDeque<FocusRecord>,
+    capacity: usize,
+}
+
+impl FocusHistory {
+    /// History of the last `capacity` focus changes, at least one
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            records: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Add a focus change, dropping the oldest when full. Returns false for
+    /// the element that already has focus, which platforms report again
+    /// when its window is reactivated
+    pub fn push(&mut self, record: FocusRecord) -> bool {
+        if self.records.back().is_some_and(|last| last.same_element(&record)) {
+            return false;
+        }
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+        true
+    }
+
+    /// Focus changes, oldest first
+    pub fn records(&self) -> Vec<FocusRecord> {
+        self.records.iter().cloned().collect()
+    }
+
+    /// Focus changes at or after `timestamp_ms`, oldest first
+    pub fn since(&self, timestamp_ms: u64) -> Vec<FocusRecord> {
+        self.records
+            .iter()
+            .filter(|record| record.timestamp_ms >= timestamp_ms)
+            .cloned()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+}
+
+/// Callback invoked with each recorded focus change
+pub type FocusCallback = Arc<dyn Fn(&FocusRecord) + Send + Sync>;
+
+/// Records focus changes into a [`FocusHistory`] until dropped
+pub(crate) struct FocusTracker {
+    history: Arc<Mutex<FocusHistory>>,
+    _subscription: UIEventSubscription,
+}
+
+impl FocusTracker {
+    pub(crate) fn start(
+        engine: &dyn AccessibilityEngine,
+        capacity: usize,
+        on_change: Option<FocusCallback>,
+    ) -> Result<Self, AutomationError> {
+        let history = Arc::new(Mutex::new(FocusHistory::new(capacity)));
+        // Start from the element focused now, so the first change has a "from"
+        if let Ok(element) = engine.get_focused_element() {
+            history
+                .lock()
+                .unwrap_or_else(|p| p.into_inner())
+                .push(FocusRecord::from_element(&element));
+        }
+        let recorded = history.clone();
+        let subscription = engine.subscribe_ui_events(
+            &[UIEventKind::FocusChanged],
+            Arc::new(move |event: UIEvent| {
+                let Some(element) = event.element else {
+                    return;
+                };
+                let record = FocusRecord::from_element(&element);
+                let added = recorded.lock().unwrap_or_else(|p| p.into_inner()).push(record.clone());
+                if let (true, Some(callback)) = (added, &on_change) {
+                    callback(&record);
+                }
+            }),
+        )?;
+        Ok(Self {
+            history,
+            _subscription: subscription,
+        })
+    }
+
+    pub(crate) fn history(&self) -> FocusHistory {
+        self.history.lock().unwrap_or_else(|p| p.into_inner()).clone()
+    }
+}
@@ -0,0 +1,336 @@
+//! Filling whole forms from label/value pairs
+//!
+//! [`Form::fill`] finds the control for each label below a root element and
+//! sets it the way its type needs: text for edit boxes, an option for combo
+//! boxes, a state for check boxes and radio buttons, a number for sliders.
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop, form::Form};
+//! # async fn run(desktop: &Desktop) -> Result<(), AutomationError> {
+//! let window = desktop.locator("window:New customer").first(None).await?;
+//! let report = Form::new(&window).fill([
+//!     ("First name", "Ada"),
+//!     ("Country", "United Kingdom"),
+//!     ("Newsletter", "yes"),
+//! ])?;
+//! if !report.is_complete() {
+//!     println!("{:?} {:?}", report.unmatched, report.failed);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A control is matched to a label, in order of preference, by the label
+//! element it reports (UIA `LabeledBy`), by its own name being the label, by
+//! being the nearest control right of or below a text element showing the
+//! label, and finally by the closest fuzzy match of its name. Each control
+//! is filled at most once.
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::controls::normalize_role;
+use crate::geometry::Rect;
+use crate::spatial::Relation;
+use crate::utils::similarity;
+use crate::{AutomationError, UIElement};
+
+/// How deep below the root controls and labels are looked for
+const FORM_SEARCH_DEPTH: usize = 12;
+/// Labels further than this from a control, in pixels, are not its label
+const MAX_LABEL_DISTANCE: f64 = 300.0;
+/// Default minimum similarity of fuzzy name matches
+pub const DEFAULT_MATCH_THRESHOLD: f64 = 0.75;
+
+const LABEL_ROLES: &[&str] = &["text", "label", "statictext"];
+
+/// How a value is set, derived from the control's role
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    Text,
+    CheckBox,
+    RadioButton,
+    ComboBox,
+    Range,
+}
+
+impl FieldKind {
+    /// Kind of a control with `role`, None for elements that take no input
+    pub fn of_role(role: &str) -> Option<Self> {
+        match normalize_role(role).as_str() {
+            "edit" | "textbox" | "textfield" | "entry" | "passwordtext" => Some(FieldKind::Text),
+            "checkbox" | "togglebutton" => Some(FieldKind::CheckBox),
+            "radiobutton" => Some(FieldKind::RadioButton),
+            "combobox" => Some(FieldKind::ComboBox),
+            "slider" | "spinner" | "spinbutton" => Some(FieldKind::Range),
+            _ => None,
+        }
+    }
+}
+
+/// How a control was matched to its label
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldMatch {
+    /// The control names the label element (UIA `LabeledBy`)
+    LabeledBy,
+    /// The control's own name is the label
+    Name,
+    /// The control is next to a text element showing the label
+    Proximity,
+    /// The control's name resembles the label
+    FuzzyName,
+}
+
+/// A field that was filled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilledField {
+    pub label: String,
+    pub kind: FieldKind,
+    pub matched_by: FieldMatch,
+    /// Name of the control that was filled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub control_name: Option<String>,
+}
+
+/// A field whose control was found but could not be set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedField {
+    pub label: String,
+    pub error: String,
+}
+
+/// Outcome of [`Form::fill`], with fields in the order they were given
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FormFillReport {
+    pub filled: Vec<FilledField>,
+    /// Labels no control was found for
+    pub unmatched: Vec<String>,
+    pub failed: Vec<FailedField>,
+}
+
+impl FormFillReport {
+    /// Whether every field was filled
+    pub fn is_complete(&self) -> bool {
+        self.unmatched.is_empty() && self.failed.is_empty()
+    }
+}
+
+/// The controls below a window or group, filled by label
+#[derive(Debug, Clone)]
+pub struct Form {
+    root: UIElement,
+    match_threshold: f64,
+}
+
+struct Control {
+    element: UIElement,
+    kind: FieldKind,
+    name: Option<String>,
+    label: Option<String>,
+    rect: Option<Rect>,
+    filled: bool,
+}
+
+struct Label {
+    name: String,
+    rect: Rect,
+}
+
+impl Form {
+    pub fn new(root: &UIElement) -> Self {
+        Self {
+            root: root.clone(),
+            match_threshold: DEFAULT_MATCH_THRESHOLD,
+        }
+    }
+
+    /// Minimum similarity, from 0.0 to 1.0, for a fuzzy name match
+    pub fn with_match_threshold(mut self, threshold: f64) -> Self {
+        self.match_threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn root(&self) -> &UIElement {
+        &self.root
+    }
+
+    /// Set the control of each label to its value. Fields that can't be
+    /// matched or set are reported rather than failing the whole form; an
+    /// error means the form itself could not be read
+    pub fn fill<K, V>(&self, fields: impl IntoIterator<Item = (K, V)>) -> Result<FormFillReport, AutomationError>
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let (mut controls, labels) = self.scan()?;
+        let mut report = FormFillReport::default();
+
+        for (label, value) in fields {
+            let (label, value) = (label.as_ref(), value.as_ref());
+            let Some((index, matched_by)) = self.match_control(&controls, &labels, label) else {
+                debug!(label, "No control found for form field");
+                report.unmatched.push(label.to_string());
+                continue;
+            };
+
+            let control = &mut controls[index];
+            control.filled = true;
+            match set_control(&control.element, control.kind, value) {
+                Ok(()) => report.filled.push(FilledField {
+                    label: label.to_string(),
+                    kind: control.kind,
+                    matched_by,
+                    control_name: control.name.clone(),
+                }),
+                Err(e) => report.failed.push(FailedField {
+                    label: label.to_string(),
+                    error: e.to_string(),
+                }),
+            }
+        }
+
+        Ok(report)
+    }
+
+    // Input controls and text labels below the root, in tree order
+    fn scan(&self) -> Result<(Vec<Control>, Vec<Label>), AutomationError> {
+        let mut controls = Vec::new();
+        let mut labels = Vec::new();
+        let mut level = self.root.children()?;
+        for _ in 0..FORM_SEARCH_DEPTH {
+            let mut next = Vec::new();
+            for element in level {
+                let role = element.role();
+                if let Some(kind) = FieldKind::of_role(&role) {
+                    controls.push(Control {
+                        kind,
+                        name: element.name().filter(|n| !n.trim().is_empty()),
+                        label: element.labeled_by().ok().flatten().and_then(|label| label.name()),
+                        rect: element.rect().ok(),
+                        filled: false,
+                        element: element.clone(),
+                    });
+                    // Combo boxes and spinners contain an edit box of their own
+                    if kind != FieldKind::Text {
+                        continue;
+                    }
+                } else if LABEL_ROLES.contains(&normalize_role(&role).as_str()) {
+                    if let (Some(name), Ok(rect)) = (element.name(), element.rect()) {
+                        labels.push(Label { name, rect });
+                    }
+                }
+                next.extend(element.children().unwrap_or_default());
+            }
+            if next.is_empty() {
+                break;
+            }
+            level = next;
+        }
+        Ok((controls, labels))
+    }
+
+    fn match_control(&self, controls: &[Control], labels: &[Label], label: &str) -> Option<(usize, FieldMatch)> {
+        let open = || controls.iter().enumerate().filter(|(_, c)| !c.filled);
+
+        if let Some((index, _)) = open().find(|(_, c)| c.label.as_deref().is_some_and(|l| same_text(l, label))) {
+            return Some((index, FieldMatch::LabeledBy));
+        }
+        if let Some((index, _)) = open().find(|(_, c)| c.name.as_deref().is_some_and(|n| same_text(n, label))) {
+            return Some((index, FieldMatch::Name));
+        }
+
+        // The control nearest to the best-matching text, to its right or below it
+        let anchor = labels
+            .iter()
+            .map(|l| (l, similarity(&l.name, label)))
+            .filter(|(_, score)| *score >= self.match_threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((anchor, _)) = anchor {
+            let nearest = open()
+                .filter_map(|(index, c)| {
+                    let rect = c.rect.as_ref()?;
+                    let distance = [Relation::RightOf, Relation::Below]
+                        .iter()
+                        .filter_map(|relation| relation.distance(rect, &anchor.rect))
+                        .min_by(f64::total_cmp)?;
+                    (distance <= MAX_LABEL_DISTANCE).then_some((index, distance))
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((index, _)) = nearest {
+                return Some((index, FieldMatch::Proximity));
+            }
+        }
+
+        open()
+            .filter_map(|(index, c)| {
+                let names = [c.label.as_deref(), c.name.as_deref()];
+                let score = names.iter().flatten().map(|n| similarity(n, label)).fold(0.0, f64::max);
+                (score >= self.match_threshold).then_some((index, score))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(index, _)| (index, FieldMatch::FuzzyName))
+    }
+}
+
+/// Labels compare without case, surrounding spaces, a trailing colon or
+/// asterisk, or `&` access key markers
+fn same_text(a: &str, b: &str) -> bool {
+    let clean = |s: &str| {
+        s.replace('&', "")
+            .trim()
+            .trim_end_matches([':', '*'])
+            .trim()
+            .to_lowercase()
+    };
+    clean(a) == clean(b)
+}
+
+/// `value` as a check box state: yes/no, true/false, on/off, 1/0, checked/unchecked
+pub(crate) fn parse_checked(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "yes" | "y" | "on" | "1" | "checked" | "x" => Some(true),
+        "false" | "no" | "n" | "off" | "0" | "unchecked" | "" => Some(false),
+        _ => None,
+    }
+}
+
+fn set_control(element: &UIElement, kind: FieldKind, value: &str) -> Result<(), AutomationError> {
+    let invalid = |expected: &str| {
+        AutomationError::InvalidArgument(format!(
+            "'{}' is not {} for '{}'",
+            value,
+            expected,
+            element.name_or_empty()
+        ))
+    };
+    match kind {
+        FieldKind::Text => match element.set_value(value) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                debug!("Setting the value failed, typing instead: {}", e);
+                element.focus()?;
+                element.press_key("{Ctrl}a")?;
+                element.type_text(value, false)
+            }
+        },
+        FieldKind::CheckBox => {
+            let checked = parse_checked(value).ok_or_else(|| invalid("a check box state"))?;
+            element.as_checkbox()?.set(checked)
+        }
+        FieldKind::RadioButton => match parse_checked(value) {
+            Some(true) => element.select(),
+            Some(false) => Err(AutomationError::InvalidArgument(format!(
+                "Radio button '{}' can't be cleared, fill the option to select instead",
+                element.name_or_empty()
+            ))),
+            None => Err(invalid("a radio button state")),
+        },
+        FieldKind::ComboBox => element.as_combobox()?.select(value),
+        FieldKind::Range => {
+            let number = value.trim().parse::<f64>().map_err(|_| invalid("a number"))?;
+            element.as_slider()?.set_value(number)
+        }
+    }
+}
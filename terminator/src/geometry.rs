@@ -0,0 +1,232 @@
+//! Screen geometry helpers for UI elements
+//!
+//! All coordinates are in physical screen pixels, the same space the
+//! platform accessibility APIs report bounds in and mouse input expects.
+//! Use [`Point::to_logical`] / [`Rect::to_logical`] when a value has to be
+//! shown to a DPI-scaled consumer (e.g. a browser or a scaled screenshot).
+
+use serde::{Deserialize, Serialize};
+
+/// A point in physical screen pixels
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    /// Move the point by the given offset
+    pub fn offset(&self, dx: f64, dy: f64) -> Self {
+        Self::new(self.x + dx, self.y + dy)
+    }
+
+    /// Convert to logical pixels for the given scale factor (1.0 = 96 DPI)
+    pub fn to_logical(&self, scale_factor: f64) -> Self {
+        Self::new(self.x / scale_factor, self.y / scale_factor)
+    }
+}
+
+impl From<(f64, f64)> for Point {
+    fn from((x, y): (f64, f64)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl From<Point> for (f64, f64) {
+    fn from(p: Point) -> Self {
+        (p.x, p.y)
+    }
+}
+
+/// A reference point on a rectangle
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Anchor {
+    #[default]
+    Center,
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+    /// Horizontal and vertical position in percent of the width and height
+    Percent(f64, f64),
+}
+
+impl std::str::FromStr for Anchor {
+    type Err = String;
+
+    /// Accepts names like `top_left`, `Center Right` or `bottom-center`, and
+    /// percentages as `30%,50%` or `percent(30, 50)`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_lowercase();
+        if let Some(anchor) = parse_percent_anchor(&normalized) {
+            return anchor;
+        }
+        match normalized.replace(['-', ' '], "_").as_str() {
+            "center" | "middle_center" => Ok(Anchor::Center),
+            "top_left" => Ok(Anchor::TopLeft),
+            "top" | "top_center" => Ok(Anchor::Top),
+            "top_right" => Ok(Anchor::TopRight),
+            "left" | "center_left" | "left_center" => Ok(Anchor::Left),
+            "right" | "center_right" | "right_center" => Ok(Anchor::Right),
+            "bottom_left" => Ok(Anchor::BottomLeft),
+            "bottom" | "bottom_center" => Ok(Anchor::Bottom),
+            "bottom_right" => Ok(Anchor::BottomRight),
+            other => Err(format!("Unknown anchor '{}'", other)),
+        }
+    }
+}
+
+// `30%,50%` or `percent(30, 50)`; None if `s` is not a percentage at all
+fn parse_percent_anchor(s: &str) -> Option<Result<Anchor, String>> {
+    let inner = match s.strip_prefix("percent(").and_then(|r| r.strip_suffix(')')) {
+        Some(inner) => inner,
+        None if s.contains('%') => s,
+        None => return None,
+    };
+    let parts: Vec<&str> = inner.split(',').map(|p| p.trim().trim_end_matches('%').trim()).collect();
+    let parsed = match parts.as_slice() {
+        [x, y] => x.parse::<f64>().ok().zip(y.parse::<f64>().ok()),
+        _ => None,
+    };
+    Some(match parsed {
+        Some((x, y)) => Ok(Anchor::Percent(x, y)),
+        None => Err(format!("Invalid percent anchor '{}', expected e.g. '30%,50%'", s)),
+    })
+}
+
+/// An axis-aligned rectangle in physical screen pixels
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self { x, y, width, height }
+    }
+
+    pub fn left(&self) -> f64 {
+        self.x
+    }
+
+    pub fn top(&self) -> f64 {
+        self.y
+    }
+
+    pub fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0.0 || self.height <= 0.0
+    }
+
+    pub fn center(&self) -> Point {
+        self.point_at(Anchor::Center, (0.0, 0.0))
+    }
+
+    /// The given anchor point, moved by `offset` (dx, dy)
+    pub fn point_at(&self, anchor: Anchor, offset: (f64, f64)) -> Point {
+        let (x, y) = match anchor {
+            Anchor::Center => (self.x + self.width / 2.0, self.y + self.height / 2.0),
+            Anchor::TopLeft => (self.left(), self.top()),
+            Anchor::Top => (self.x + self.width / 2.0, self.top()),
+            Anchor::TopRight => (self.right(), self.top()),
+            Anchor::Left => (self.left(), self.y + self.height / 2.0),
+            Anchor::Right => (self.right(), self.y + self.height / 2.0),
+            Anchor::BottomLeft => (self.left(), self.bottom()),
+            Anchor::Bottom => (self.x + self.width / 2.0, self.bottom()),
+            Anchor::BottomRight => (self.right(), self.bottom()),
+            Anchor::Percent(px, py) => (
+                self.x + self.width * px / 100.0,
+                self.y + self.height * py / 100.0,
+            ),
+        };
+        Point::new(x + offset.0, y + offset.1)
+    }
+
+    /// Whether the point lies inside the rectangle (right and bottom edges excluded)
+    pub fn contains_point(&self, x: f64, y: f64) -> bool {
+        x >= self.left() && x < self.right() && y >= self.top() && y < self.bottom()
+    }
+
+    /// The nearest point that lies inside the rectangle, so that points on the
+    /// right or bottom edge still hit it
+    pub fn clamp_point(&self, point: Point) -> Point {
+        let max_x = (self.right() - 1.0).max(self.left());
+        let max_y = (self.bottom() - 1.0).max(self.top());
+        Point::new(point.x.clamp(self.left(), max_x), point.y.clamp(self.top(), max_y))
+    }
+
+    /// Whether `other` lies entirely inside the rectangle, edges included
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.left() >= self.left()
+            && other.top() >= self.top()
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The overlapping area of both rectangles, if any
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let left = self.left().max(other.left());
+        let top = self.top().max(other.top());
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        if right > left && bottom > top {
+            Some(Rect::new(left, top, right - left, bottom - top))
+        } else {
+            None
+        }
+    }
+
+    /// The smallest rectangle containing both rectangles
+    pub fn union(&self, other: &Rect) -> Rect {
+        let left = self.left().min(other.left());
+        let top = self.top().min(other.top());
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+        Rect::new(left, top, right - left, bottom - top)
+    }
+
+    /// Convert to logical pixels for the given scale factor (1.0 = 96 DPI)
+    pub fn to_logical(&self, scale_factor: f64) -> Self {
+        Self::new(
+            self.x / scale_factor,
+            self.y / scale_factor,
+            self.width / scale_factor,
+            self.height / scale_factor,
+        )
+    }
+}
+
+impl From<(f64, f64, f64, f64)> for Rect {
+    fn from((x, y, width, height): (f64, f64, f64, f64)) -> Self {
+        Self::new(x, y, width, height)
+    }
+}
+
+impl From<Rect> for (f64, f64, f64, f64) {
+    fn from(r: Rect) -> Self {
+        (r.x, r.y, r.width, r.height)
+    }
+}
@@ -0,0 +1,205 @@
+//! System-wide hotkeys such as `Ctrl+Shift+H`
+//!
+//! [`Desktop::register_hotkey`](crate::Desktop::register_hotkey) lets tools
+//! built on terminator offer user-triggered actions (start recording, dump
+//! the tree under the cursor, ...) without their own event loop. Callbacks
+//! run on a single dispatcher thread owned by the platform engine, so they
+//! must be `Send + Sync` and should return quickly. A hotkey stays
+//! registered until the returned [`HotkeyRegistration`] is dropped or
+//! [`HotkeyRegistration::unregister`] is called.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// The non-modifier key of a hotkey
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HotkeyKey {
+    /// A letter (always uppercase) or digit
+    Char(char),
+    /// A function key, `F1` to `F24`
+    F(u8),
+    Space,
+    Enter,
+    Tab,
+    Escape,
+    Backspace,
+    Delete,
+    Insert,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Up,
+    Down,
+    Left,
+    Right,
+    PrintScreen,
+}
+
+impl fmt::Display for HotkeyKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HotkeyKey::Char(c) => write!(f, "{}", c),
+            HotkeyKey::F(n) => write!(f, "F{}", n),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+impl FromStr for HotkeyKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            if c.is_ascii_alphanumeric() {
+                return Ok(HotkeyKey::Char(c.to_ascii_uppercase()));
+            }
+        }
+        let lower = s.to_lowercase();
+        if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+            if (1..=24).contains(&n) {
+                return Ok(HotkeyKey::F(n));
+            }
+        }
+        Ok(match lower.as_str() {
+            "space" => HotkeyKey::Space,
+            "enter" | "return" => HotkeyKey::Enter,
+            "tab" => HotkeyKey::Tab,
+            "escape" | "esc" => HotkeyKey::Escape,
+            "backspace" => HotkeyKey::Backspace,
+            "delete" | "del" => HotkeyKey::Delete,
+            "insert" | "ins" => HotkeyKey::Insert,
+            "home" => HotkeyKey::Home,
+            "end" => HotkeyKey::End,
+            "pageup" | "pgup" => HotkeyKey::PageUp,
+            "pagedown" | "pgdn" => HotkeyKey::PageDown,
+            "up" => HotkeyKey::Up,
+            "down" => HotkeyKey::Down,
+            "left" => HotkeyKey::Left,
+            "right" => HotkeyKey::Right,
+            "printscreen" | "prtsc" => HotkeyKey::PrintScreen,
+            _ => return Err(format!("Unknown hotkey key '{}'", s)),
+        })
+    }
+}
+
+/// A key combination, parsed from strings like `"Ctrl+Shift+H"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hotkey {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// The Windows key on Windows, Command on macOS
+    pub meta: bool,
+    pub key: HotkeyKey,
+}
+
+impl fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (held, name) in [
+            (self.ctrl, "Ctrl"),
+            (self.alt, "Alt"),
+            (self.shift, "Shift"),
+            (self.meta, "Meta"),
+        ] {
+            if held {
+                write!(f, "{}+", name)?;
+            }
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+impl FromStr for Hotkey {
+    type Err = String;
+
+    /// Modifiers and a key separated by `+`, case-insensitive. Modifiers are
+    /// `Ctrl`/`Control`, `Alt`/`Option`, `Shift` and `Win`/`Meta`/`Cmd`/`Super`.
+    /// At least one modifier is required, so plain typing is never swallowed
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        let Some((key, modifiers)) = parts.split_last() else {
+            return Err("Hotkey cannot be empty".to_string());
+        };
+        if key.is_empty() {
+            return Err(format!("Hotkey '{}' has no key", s));
+        }
+
+        let mut hotkey = Hotkey {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            meta: false,
+            key: key.parse()?,
+        };
+        for modifier in modifiers {
+            let flag = match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => &mut hotkey.ctrl,
+                "alt" | "option" => &mut hotkey.alt,
+                "shift" => &mut hotkey.shift,
+                "win" | "meta" | "cmd" | "command" | "super" => &mut hotkey.meta,
+                _ => return Err(format!("Unknown modifier '{}' in hotkey '{}'", modifier, s)),
+            };
+            if *flag {
+                return Err(format!("Modifier '{}' repeated in hotkey '{}'", modifier, s));
+            }
+            *flag = true;
+        }
+        if !(hotkey.ctrl || hotkey.alt || hotkey.shift || hotkey.meta) {
+            return Err(format!("Hotkey '{}' needs at least one modifier", s));
+        }
+        Ok(hotkey)
+    }
+}
+
+/// Callback invoked each time a hotkey is pressed
+pub type HotkeyCallback = Arc<dyn Fn() + Send + Sync>;
+
+/// A registered hotkey, released when dropped
+pub struct HotkeyRegistration {
+    hotkey: Hotkey,
+    unregister: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl HotkeyRegistration {
+    /// Wrap the platform-specific release of a hotkey
+    pub fn new(hotkey: Hotkey, unregister: impl FnOnce() + Send + 'static) -> Self {
+        Self {
+            hotkey,
+            unregister: Some(Box::new(unregister)),
+        }
+    }
+
+    /// The registered key combination
+    pub fn hotkey(&self) -> Hotkey {
+        self.hotkey
+    }
+
+    /// Release the hotkey so other applications can use it again
+    pub fn unregister(mut self) {
+        self.release();
+    }
+
+    fn release(&mut self) {
+        if let Some(unregister) = self.unregister.take() {
+            unregister();
+        }
+    }
+}
+
+impl Drop for HotkeyRegistration {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+impl fmt::Debug for HotkeyRegistration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HotkeyRegistration")
+            .field("hotkey", &self.hotkey.to_string())
+            .field("active", &self.unregister.is_some())
+            .finish()
+    }
+}
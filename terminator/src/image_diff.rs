@@ -0,0 +1,196 @@
+//! Comparing screenshots for visual regression tests
+//!
+//! [`ScreenshotResult::diff`] compares two captures of the same size pixel
+//! by pixel and reports how much changed, where, and how different the two
+//! look overall:
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop, ScreenshotResult};
+//! # async fn run(desktop: &Desktop, baseline: &ScreenshotResult) -> Result<(), AutomationError> {
+//! let window = desktop.application("notepad")?;
+//! let current = window.capture()?;
+//! let diff = current.diff(baseline, 16)?;
+//! if diff.changed_ratio > 0.001 {
+//!     std::fs::write("diff.png", diff.heatmap.to_png()?).ok();
+//!     for region in &diff.regions {
+//!         println!("changed: {:?}", region);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A pixel counts as changed when one of its channels differs by more than
+//! the tolerance, which absorbs anti-aliasing and compression noise.
+//! Changed pixels are grouped into regions by proximity. The perceptual
+//! hash distance compares the overall look instead, and stays small for
+//! captures that differ only by noise or slight shifts.
+
+use image::imageops::{self, FilterType};
+use image::{GrayImage, RgbaImage};
+use serde::Serialize;
+
+use crate::geometry::Rect;
+use crate::{AutomationError, ScreenshotResult};
+
+// Changed pixels less than this many pixels apart belong to the same region
+const REGION_CELL: u32 = 16;
+
+/// Outcome of [`ScreenshotResult::diff`]
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffResult {
+    pub width: u32,
+    pub height: u32,
+    /// Pixels differing by more than the tolerance
+    pub changed_pixels: usize,
+    /// Share of changed pixels, from 0.0 to 1.0
+    pub changed_ratio: f64,
+    /// Bounding boxes of groups of nearby changed pixels, in image
+    /// coordinates, largest first
+    pub regions: Vec<Rect>,
+    /// Bits that differ between the perceptual hashes of the two images,
+    /// from 0 (look alike) to 64
+    pub perceptual_distance: u32,
+    /// The first image dimmed to gray with changed pixels from yellow
+    /// (small change) to red (large change)
+    #[serde(skip)]
+    pub heatmap: ScreenshotResult,
+}
+
+impl DiffResult {
+    /// Whether no pixel changed beyond the tolerance
+    pub fn is_identical(&self) -> bool {
+        self.changed_pixels == 0
+    }
+}
+
+impl ScreenshotResult {
+    /// Compare with `other`, which must have the same size. Pixels whose
+    /// channels all differ by at most `tolerance` count as unchanged
+    pub fn diff(&self, other: &ScreenshotResult, tolerance: u8) -> Result<DiffResult, AutomationError> {
+        self.check_buffer()?;
+        other.check_buffer()?;
+        if (self.width, self.height) != (other.width, other.height) {
+            return Err(AutomationError::InvalidArgument(format!(
+                "Cannot compare a {}x{} screenshot with a {}x{} one",
+                self.width, self.height, other.width, other.height
+            )));
+        }
+
+        let (width, height) = (self.width, self.height);
+        let mut heatmap = Vec::with_capacity(self.image_data.len());
+        let mut changed = vec![false; width as usize * height as usize];
+        let mut changed_pixels = 0;
+        for (index, (a, b)) in self
+            .image_data
+            .chunks_exact(4)
+            .zip(other.image_data.chunks_exact(4))
+            .enumerate()
+        {
+            let difference = a.iter().zip(b).map(|(a, b)| a.abs_diff(*b)).max().unwrap_or(0);
+            if difference > tolerance {
+                changed[index] = true;
+                changed_pixels += 1;
+                heatmap.extend_from_slice(&[255, 255 - difference, 0, 255]);
+            } else {
+                let gray = (luma(a) as u16 * 3 / 10 + 40) as u8;
+                heatmap.extend_from_slice(&[gray, gray, gray, 255]);
+            }
+        }
+
+        Ok(DiffResult {
+            width,
+            height,
+            changed_pixels,
+            changed_ratio: changed_pixels as f64 / changed.len() as f64,
+            regions: changed_regions(&changed, width, height),
+            perceptual_distance: (self.perceptual_hash()? ^ other.perceptual_hash()?).count_ones(),
+            heatmap: ScreenshotResult {
+                image_data: heatmap,
+                width,
+                height,
+            },
+        })
+    }
+
+    /// 64-bit difference hash of the image: similar looking images have
+    /// hashes differing in few bits
+    pub fn perceptual_hash(&self) -> Result<u64, AutomationError> {
+        self.check_buffer()?;
+        let rgba = RgbaImage::from_raw(self.width, self.height, self.image_data.clone())
+            .ok_or_else(|| self.buffer_error())?;
+        let gray: GrayImage = image::DynamicImage::ImageRgba8(rgba).to_luma8();
+        // Each bit compares two horizontally adjacent cells of a 9x8 thumbnail
+        let thumbnail = imageops::resize(&gray, 9, 8, FilterType::Triangle);
+        let mut hash = 0u64;
+        for y in 0..8 {
+            for x in 0..8 {
+                hash <<= 1;
+                if thumbnail.get_pixel(x, y)[0] < thumbnail.get_pixel(x + 1, y)[0] {
+                    hash |= 1;
+                }
+            }
+        }
+        Ok(hash)
+    }
+}
+
+fn luma(pixel: &[u8]) -> u8 {
+    ((pixel[0] as u32 * 299 + pixel[1] as u32 * 587 + pixel[2] as u32 * 114) / 1000) as u8
+}
+
+// Bounding boxes of the changed pixels, grouped through the grid cells
+// they fall in: cells touching each other, diagonals included, form one
+// region
+fn changed_regions(changed: &[bool], width: u32, height: u32) -> Vec<Rect> {
+    let columns = width.div_ceil(REGION_CELL) as usize;
+    let rows = height.div_ceil(REGION_CELL) as usize;
+    // Bounds (left, top, right, bottom) of the changed pixels of each cell
+    let mut cells: Vec<Option<(u32, u32, u32, u32)>> = vec![None; columns * rows];
+    for (index, _) in changed.iter().enumerate().filter(|(_, changed)| **changed) {
+        let (x, y) = (index as u32 % width, index as u32 / width);
+        let cell = &mut cells[(y / REGION_CELL) as usize * columns + (x / REGION_CELL) as usize];
+        *cell = Some(match *cell {
+            Some((left, top, right, bottom)) => (left.min(x), top.min(y), right.max(x), bottom.max(y)),
+            None => (x, y, x, y),
+        });
+    }
+
+    let mut visited = vec![false; cells.len()];
+    let mut regions = Vec::new();
+    for start in 0..cells.len() {
+        if visited[start] || cells[start].is_none() {
+            continue;
+        }
+        visited[start] = true;
+        let mut bounds = cells[start].unwrap_or_default();
+        let mut stack = vec![start];
+        while let Some(cell) = stack.pop() {
+            let (column, row) = ((cell % columns) as isize, (cell / columns) as isize);
+            for (dx, dy) in [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+                let (x, y) = (column + dx, row + dy);
+                if x < 0 || y < 0 || x >= columns as isize || y >= rows as isize {
+                    continue;
+                }
+                let neighbor = y as usize * columns + x as usize;
+                if visited[neighbor] {
+                    continue;
+                }
+                if let Some((left, top, right, bottom)) = cells[neighbor] {
+                    visited[neighbor] = true;
+                    bounds = (bounds.0.min(left), bounds.1.min(top), bounds.2.max(right), bounds.3.max(bottom));
+                    stack.push(neighbor);
+                }
+            }
+        }
+        let (left, top, right, bottom) = bounds;
+        regions.push(Rect::new(
+            left as f64,
+            top as f64,
+            (right - left + 1) as f64,
+            (bottom - top + 1) as f64,
+        ));
+    }
+    regions.sort_by(|a, b| (b.width * b.height).total_cmp(&(a.width * a.height)));
+    regions
+}
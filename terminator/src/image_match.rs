@@ -0,0 +1,318 @@
+//! Locating images on screen by template matching
+//!
+//! Icon-only toolbars often expose buttons without names, and custom-drawn
+//! UIs expose nothing at all. [`Desktop::find_image`](crate::Desktop::find_image)
+//! looks for a reference picture of the control on screen instead:
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop};
+//! # async fn run(desktop: &Desktop) -> Result<(), AutomationError> {
+//! let icon = std::fs::read("icons/bold.png").unwrap();
+//! let matches = desktop.find_image(&icon, 0.9, None).await?;
+//! if let Some(button) = matches.first() {
+//!     button.click()?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Matching uses normalized cross-correlation on grayscale pixels, so it
+//! tolerates brightness and contrast changes but not recoloring. It runs on
+//! a downscaled copy first and only refines the promising spots at full
+//! resolution. [`ImageSearch::scales`] also tries resized templates, for
+//! screens at a different scaling than the one the template was taken on.
+
+use image::GrayImage;
+use serde::Serialize;
+
+use crate::geometry::{Point, Rect};
+use crate::{AutomationError, UIElement};
+
+/// Default minimum confidence of a match
+pub const DEFAULT_IMAGE_THRESHOLD: f32 = 0.9;
+
+// Coarse scores are blurrier than full resolution ones, so candidates are
+// kept from a bit below the threshold
+const COARSE_MARGIN: f32 = 0.15;
+const MAX_CANDIDATES: usize = 200;
+// Smallest template side worth matching at the coarse level
+const MIN_COARSE_SIDE: usize = 6;
+const MAX_COARSE_FACTOR: usize = 16;
+// Matches overlapping a better one by more than this are duplicates
+const MAX_OVERLAP: f64 = 0.3;
+
+/// How to search for a template
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageSearch {
+    /// Minimum confidence of a match, from 0.0 to 1.0
+    pub threshold: f32,
+    /// Part of the screen to search, in physical screen pixels; all
+    /// monitors when `None`
+    pub region: Option<Rect>,
+    /// Template sizes to try, relative to the template image
+    pub scales: Vec<f32>,
+}
+
+impl Default for ImageSearch {
+    fn default() -> Self {
+        Self {
+            threshold: DEFAULT_IMAGE_THRESHOLD,
+            region: None,
+            scales: vec![1.0],
+        }
+    }
+}
+
+impl ImageSearch {
+    /// Also try the template at the sizes it has under common display
+    /// scalings (100% to 200%) relative to each other
+    pub fn multi_scale(mut self) -> Self {
+        self.scales = vec![0.5, 0.67, 0.8, 1.0, 1.25, 1.5, 2.0];
+        self
+    }
+}
+
+/// Where a template image is shown on screen
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageMatch {
+    /// Position in physical screen pixels
+    pub bounds: Rect,
+    /// Normalized cross-correlation, from 0.0 to 1.0
+    pub confidence: f32,
+    /// Size of the template that matched, relative to the template image
+    pub scale: f32,
+    // Any element: clicks at screen points go through element input methods
+    #[serde(skip)]
+    pub(crate) root: UIElement,
+}
+
+impl ImageMatch {
+    /// Center of the match in screen coordinates
+    pub fn center(&self) -> Point {
+        self.bounds.center()
+    }
+
+    /// Left-click the center of the match
+    pub fn click(&self) -> Result<(), AutomationError> {
+        let center = self.center();
+        self.root.mouse_click_and_hold(center.x, center.y)?;
+        self.root.mouse_release()
+    }
+}
+
+/// A grayscale image as floats
+struct Gray {
+    width: usize,
+    height: usize,
+    data: Vec<f32>,
+}
+
+impl Gray {
+    fn from_image(image: &GrayImage) -> Self {
+        Gray {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            data: image.as_raw().iter().map(|&p| p as f32).collect(),
+        }
+    }
+
+    fn at(&self, x: usize, y: usize) -> f32 {
+        self.data[y * self.width + x]
+    }
+
+    /// Copy without the first `x` columns and `y` rows
+    fn crop(&self, x: usize, y: usize) -> Gray {
+        let width = self.width - x;
+        let data = (y..self.height)
+            .flat_map(|row| self.data[row * self.width + x..(row + 1) * self.width].iter().copied())
+            .collect();
+        Gray { width, height: self.height - y, data }
+    }
+
+    /// Box-averaged copy, `factor` times smaller
+    fn downscale(&self, factor: usize) -> Gray {
+        let width = self.width / factor;
+        let height = self.height / factor;
+        let mut data = Vec::with_capacity(width * height);
+        let area = (factor * factor) as f32;
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = 0.0;
+                for dy in 0..factor {
+                    for dx in 0..factor {
+                        sum += self.at(x * factor + dx, y * factor + dy);
+                    }
+                }
+                data.push(sum / area);
+            }
+        }
+        Gray { width, height, data }
+    }
+}
+
+/// A template with its mean removed, ready for correlation
+struct Template {
+    gray: Gray,
+    norm: f32,
+}
+
+impl Template {
+    fn new(mut gray: Gray) -> Option<Self> {
+        let mean = gray.data.iter().sum::<f32>() / gray.data.len() as f32;
+        gray.data.iter_mut().for_each(|p| *p -= mean);
+        let norm = gray.data.iter().map(|p| p * p).sum::<f32>().sqrt();
+        // A flat template correlates with nothing
+        (norm > f32::EPSILON).then_some(Template { gray, norm })
+    }
+
+    /// Normalized cross-correlation with the window of `image` at (x, y)
+    fn score(&self, image: &Gray, x: usize, y: usize) -> f32 {
+        let (width, height) = (self.gray.width, self.gray.height);
+        let count = (width * height) as f32;
+        let mut sum = 0.0;
+        let mut sum_sq = 0.0;
+        let mut cross = 0.0;
+        for ty in 0..height {
+            let row = (y + ty) * image.width + x;
+            let window = &image.data[row..row + width];
+            let template = &self.gray.data[ty * width..(ty + 1) * width];
+            for (&p, &t) in window.iter().zip(template) {
+                sum += p;
+                sum_sq += p * p;
+                cross += p * t;
+            }
+        }
+        // The template has zero mean, so the window mean drops out of `cross`
+        let variance = sum_sq - sum * sum / count;
+        if variance <= f32::EPSILON {
+            return 0.0;
+        }
+        (cross / (variance.sqrt() * self.norm)).clamp(-1.0, 1.0)
+    }
+}
+
+/// Where `template` appears in `image` with a confidence of at least
+/// `threshold`, best first, in image pixels
+pub(crate) fn match_template(
+    image: &GrayImage,
+    template: &GrayImage,
+    threshold: f32,
+) -> Result<Vec<(Rect, f32)>, AutomationError> {
+    let image = Gray::from_image(image);
+    let full = Template::new(Gray::from_image(template)).ok_or_else(|| {
+        AutomationError::InvalidArgument("Template image is a single flat color".to_string())
+    })?;
+    let (width, height) = (full.gray.width, full.gray.height);
+    if width == 0 || height == 0 || width > image.width || height > image.height {
+        return Ok(Vec::new());
+    }
+
+    let mut factor = 1;
+    while factor * 2 <= MAX_COARSE_FACTOR && width.min(height) / (factor * 2) >= MIN_COARSE_SIDE {
+        factor *= 2;
+    }
+
+    let to_rect = |x: usize, y: usize| Rect::new(x as f64, y as f64, width as f64, height as f64);
+    let search_everywhere = || {
+        let all = (0..=image.height - height).flat_map(|y| (0..=image.width - width).map(move |x| (x, y)));
+        suppress_overlaps(
+            all.filter_map(|(x, y)| {
+                let score = full.score(&image, x, y);
+                (score >= threshold).then(|| (to_rect(x, y), score))
+            })
+            .collect(),
+        )
+    };
+
+    // Small templates lose their details when downscaled
+    if factor == 1 {
+        return Ok(search_everywhere());
+    }
+
+    // Promising spots at the coarse level. A match only lines up with the
+    // coarse cells if it starts on their grid, so the template is tried
+    // with each possible number of leading pixels dropped
+    let small = image.downscale(factor);
+    let mut candidates = Vec::new();
+    let mut searched = false;
+    for skip_y in 0..factor {
+        for skip_x in 0..factor {
+            let Some(coarse) = Template::new(full.gray.crop(skip_x, skip_y).downscale(factor)) else {
+                continue;
+            };
+            searched = true;
+            for (cx, cy, score) in coarse_candidates(&small, &coarse, threshold - COARSE_MARGIN) {
+                if let (Some(x), Some(y)) = ((cx * factor).checked_sub(skip_x), (cy * factor).checked_sub(skip_y)) {
+                    candidates.push((x, y, score));
+                }
+            }
+        }
+    }
+    if !searched {
+        return Ok(search_everywhere());
+    }
+    candidates.sort_by(|a, b| b.2.total_cmp(&a.2));
+    candidates.truncate(MAX_CANDIDATES);
+
+    let mut matches = Vec::new();
+    for (cx, cy, _) in candidates {
+        // Downscaling rounds, so look one pixel around
+        let best = (cx.saturating_sub(1)..=(cx + 1).min(image.width - width))
+            .flat_map(|x| (cy.saturating_sub(1)..=(cy + 1).min(image.height - height)).map(move |y| (x, y)))
+            .map(|(x, y)| (x, y, full.score(&image, x, y)))
+            .max_by(|a, b| a.2.total_cmp(&b.2));
+        if let Some((x, y, score)) = best.filter(|(_, _, score)| *score >= threshold) {
+            matches.push((to_rect(x, y), score));
+        }
+    }
+    Ok(suppress_overlaps(matches))
+}
+
+/// Local maxima of at least `min_score`, best first
+fn coarse_candidates(image: &Gray, template: &Template, min_score: f32) -> Vec<(usize, usize, f32)> {
+    let (width, height) = (template.gray.width, template.gray.height);
+    if width > image.width || height > image.height {
+        return Vec::new();
+    }
+    let cols = image.width - width + 1;
+    let rows = image.height - height + 1;
+    let scores: Vec<f32> = (0..rows)
+        .flat_map(|y| (0..cols).map(move |x| (x, y)))
+        .map(|(x, y)| template.score(image, x, y))
+        .collect();
+
+    let mut candidates = Vec::new();
+    for y in 0..rows {
+        for x in 0..cols {
+            let score = scores[y * cols + x];
+            if score < min_score {
+                continue;
+            }
+            let is_peak = (y.saturating_sub(1)..=(y + 1).min(rows - 1)).all(|ny| {
+                (x.saturating_sub(1)..=(x + 1).min(cols - 1)).all(|nx| scores[ny * cols + nx] <= score)
+            });
+            if is_peak {
+                candidates.push((x, y, score));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.2.total_cmp(&a.2));
+    candidates.truncate(MAX_CANDIDATES);
+    candidates
+}
+
+/// Keep the best of overlapping matches, best first
+pub(crate) fn suppress_overlaps(mut matches: Vec<(Rect, f32)>) -> Vec<(Rect, f32)> {
+    matches.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let mut kept: Vec<(Rect, f32)> = Vec::new();
+    for (rect, score) in matches {
+        let duplicate = kept.iter().any(|(other, _)| {
+            let overlap = rect.intersection(other).map_or(0.0, |i| i.width * i.height);
+            overlap > MAX_OVERLAP * (rect.width * rect.height).min(other.width * other.height)
+        });
+        if !duplicate {
+            kept.push((rect, score));
+        }
+    }
+    kept
+}
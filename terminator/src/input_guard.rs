@@ -0,0 +1,212 @@
+//! Blocking of physical keyboard and mouse input during automation
+//!
+//! A user nudging the mouse mid-run can steal focus and send the next
+//! keystrokes to the wrong window. While an [`InputGuard`] is alive, physical
+//! input is dropped and the automation's own input still gets through:
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop};
+//! # async fn run(desktop: &Desktop) -> Result<(), AutomationError> {
+//! let guard = desktop.input_guard()?;
+//! let amount = desktop.locator("name:Amount").first(None).await?;
+//! amount.type_text("42", false)?;
+//! amount.press_key("{Enter}")?;
+//! drop(guard);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Guards nest: input stays blocked while any guard is alive and is released
+//! when the last one is dropped, which also happens when a panic unwinds past
+//! it. [`Desktop::block_user_input`](crate::Desktop::block_user_input) holds a
+//! guard until turned off, and
+//! [`Desktop::set_auto_input_guard`](crate::Desktop::set_auto_input_guard)
+//! makes every element action hold one while it runs. The process exiting
+//! releases the block.
+//!
+//! A hung automation must not lock the user out of the machine, so a block
+//! is lifted after [`MAX_BLOCK`] even if guards are still alive, and guards
+//! acquired later join the lifted block rather than blocking again until all
+//! of them are dropped.
+//!
+//! On Windows an elevated process uses `BlockInput`, which Ctrl+Alt+Del
+//! releases. Otherwise low-level hooks swallow all input that was not
+//! injected; Ctrl+Alt+Del does not remove them, but Ctrl+Alt+Esc, which the
+//! hooks let through, lifts the block. Windows only.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use tracing::{debug, warn};
+
+use crate::AutomationError;
+
+/// Longest a block lasts before input is given back to the user
+pub const MAX_BLOCK: Duration = Duration::from_secs(120);
+
+pub(crate) type Release = Box<dyn FnOnce() + Send>;
+
+struct State {
+    holders: usize,
+    release: Option<Release>,
+    /// Number of blocks put in place so far, to tell them apart
+    blocks: u64,
+    /// Dropped to stop the failsafe of the current block
+    failsafe: Option<mpsc::Sender<()>>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    holders: 0,
+    release: None,
+    blocks: 0,
+    failsafe: None,
+});
+static MANUAL: Mutex<Option<InputGuard>> = Mutex::new(None);
+static AUTO: AtomicBool = AtomicBool::new(false);
+
+fn state() -> MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Keeps physical input blocked until dropped
+#[derive(Debug)]
+#[must_use = "input is unblocked as soon as the guard is dropped"]
+pub struct InputGuard {
+    _private: (),
+}
+
+impl InputGuard {
+    /// Block physical input, or join the block already in place
+    pub fn acquire() -> Result<Self, AutomationError> {
+        Self::acquire_with(block, MAX_BLOCK)
+    }
+
+    /// [`InputGuard::acquire`] with `block` putting the block in place when
+    /// no guard holds one yet, and lifting it after `max_block`
+    pub(crate) fn acquire_with(
+        block: impl FnOnce() -> Result<Release, AutomationError>,
+        max_block: Duration,
+    ) -> Result<Self, AutomationError> {
+        let mut state = state();
+        if state.holders == 0 {
+            let release = block()?;
+            state.blocks += 1;
+            match failsafe(state.blocks, max_block) {
+                Ok(failsafe) => {
+                    state.release = Some(release);
+                    state.failsafe = Some(failsafe);
+                }
+                Err(e) => {
+                    release();
+                    return Err(e);
+                }
+            }
+            debug!("User input blocked");
+        }
+        state.holders += 1;
+        Ok(InputGuard { _private: () })
+    }
+}
+
+impl Drop for InputGuard {
+    fn drop(&mut self) {
+        let release = {
+            let mut state = state();
+            state.holders -= 1;
+            if state.holders == 0 {
+                state.failsafe = None;
+                state.release.take()
+            } else {
+                None
+            }
+        };
+        if let Some(release) = release {
+            release();
+            debug!("User input unblocked");
+        }
+    }
+}
+
+/// Whether physical input is currently blocked by this process
+pub fn is_blocked() -> bool {
+    state().release.is_some()
+}
+
+// Lift the block after `max_block` unless the last guard is dropped first
+fn failsafe(block: u64, max_block: Duration) -> Result<mpsc::Sender<()>, AutomationError> {
+    let (stop, stopped) = mpsc::channel::<()>();
+    thread::Builder::new()
+        .name("input-guard-failsafe".to_string())
+        .spawn(move || {
+            if stopped.recv_timeout(max_block) == Err(RecvTimeoutError::Timeout) {
+                lift(block, "held for too long");
+            }
+        })
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to start input guard failsafe: {}", e)))?;
+    Ok(stop)
+}
+
+/// Give input back to the user while guards are still alive, as the escape
+/// hotkey does
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub(crate) fn lift_current(reason: &str) {
+    let block = state().blocks;
+    lift(block, reason);
+}
+
+// Lift `block` if it is still in place
+fn lift(block: u64, reason: &str) {
+    let release = {
+        let mut state = state();
+        if state.blocks != block {
+            return;
+        }
+        state.failsafe = None;
+        state.release.take()
+    };
+    if let Some(release) = release {
+        release();
+        warn!("User input unblocked before its guards were dropped: {}", reason);
+    }
+}
+
+/// Hold or drop the guard behind `Desktop::block_user_input`
+pub(crate) fn set_blocked(enabled: bool) -> Result<(), AutomationError> {
+    let mut manual = MANUAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    match (enabled, manual.is_some()) {
+        (true, false) => *manual = Some(InputGuard::acquire()?),
+        (false, true) => *manual = None,
+        _ => {}
+    }
+    Ok(())
+}
+
+pub(crate) fn set_auto(enabled: bool) {
+    AUTO.store(enabled, Ordering::Relaxed);
+}
+
+/// A guard for the duration of one element action when automatic guarding
+/// is on. Failing to block only logs a warning: the action still runs
+pub(crate) fn auto_guard() -> Option<InputGuard> {
+    if !AUTO.load(Ordering::Relaxed) {
+        return None;
+    }
+    InputGuard::acquire()
+        .inspect_err(|e| warn!("Failed to block user input for action: {}", e))
+        .ok()
+}
+
+#[cfg(target_os = "windows")]
+fn block() -> Result<Release, AutomationError> {
+    crate::platforms::windows_input_guard::block()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn block() -> Result<Release, AutomationError> {
+    Err(AutomationError::UnsupportedOperation(
+        "block_user_input is only supported on Windows".to_string(),
+    ))
+}
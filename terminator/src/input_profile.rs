@@ -0,0 +1,214 @@
+//! How synthetic keyboard and mouse input is paced
+//!
+//! Some applications drop keys typed faster than a person could, or only
+//! react to clicks once the pointer has hovered over the target. An
+//! [`InputProfile`] sets the delay between keys, whether the pointer travels
+//! to its target along a curved path instead of jumping, and how long it
+//! rests there before clicking:
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop, InputProfile};
+//! # async fn run(desktop: &Desktop) -> Result<(), AutomationError> {
+//! // For everything this desktop does
+//! desktop.set_input_profile(InputProfile::human());
+//!
+//! // Or for a single action
+//! let search = desktop.locator("name:Search").first(None).await?;
+//! InputProfile::instant().scoped(|| search.type_text("quarterly report", false))?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`InputProfile::fast`] is the default and matches the pacing terminator
+//! always used. Profiles only apply to input that is actually synthesized;
+//! actions through accessibility patterns (setting a value, invoking) are
+//! instant regardless. Only the Windows engine paces input so far.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::Point;
+
+/// Pointer positions per second of a mouse movement
+const MOUSE_STEPS_PER_SECOND: f64 = 100.0;
+/// How far the control points of a mouse path bend away from the straight
+/// line, relative to its length
+const MOUSE_PATH_CURVATURE: f64 = 0.3;
+
+/// Pacing of synthetic keyboard and mouse input
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputProfile {
+    /// Pause after each key
+    pub key_delay: Duration,
+    /// Up to this much is added at random to each key's pause
+    pub key_delay_jitter: Duration,
+    /// How long the pointer takes to travel to a click target. Zero jumps
+    /// straight there
+    pub mouse_move_duration: Duration,
+    /// Pause over the target before pressing the button
+    pub click_dwell: Duration,
+    /// Up to this much is added at random to the dwell
+    pub click_dwell_jitter: Duration,
+}
+
+impl Default for InputProfile {
+    fn default() -> Self {
+        Self::fast()
+    }
+}
+
+static DEFAULT_PROFILE: RwLock<Option<InputProfile>> = RwLock::new(None);
+static RNG_STATE: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static SCOPED: RefCell<Option<InputProfile>> = const { RefCell::new(None) };
+}
+
+impl InputProfile {
+    /// No pauses and no pointer movement
+    pub fn instant() -> Self {
+        Self {
+            key_delay: Duration::ZERO,
+            key_delay_jitter: Duration::ZERO,
+            mouse_move_duration: Duration::ZERO,
+            click_dwell: Duration::ZERO,
+            click_dwell_jitter: Duration::ZERO,
+        }
+    }
+
+    /// A short fixed pause between keys, the pointer jumps to its target
+    pub fn fast() -> Self {
+        Self {
+            key_delay: Duration::from_millis(10),
+            ..Self::instant()
+        }
+    }
+
+    /// Typing at irregular, human speed and pointer movement along curves,
+    /// resting briefly over each target before clicking
+    pub fn human() -> Self {
+        Self {
+            key_delay: Duration::from_millis(60),
+            key_delay_jitter: Duration::from_millis(90),
+            mouse_move_duration: Duration::from_millis(350),
+            click_dwell: Duration::from_millis(80),
+            click_dwell_jitter: Duration::from_millis(120),
+        }
+    }
+
+    /// A preset by name: `instant`, `fast` or `human`
+    pub fn named(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "instant" => Some(Self::instant()),
+            "fast" => Some(Self::fast()),
+            "human" | "humanlike" | "human-like" => Some(Self::human()),
+            _ => None,
+        }
+    }
+
+    /// Run `f` with this profile for the input it sends on the current thread
+    pub fn scoped<T>(&self, f: impl FnOnce() -> T) -> T {
+        let previous = SCOPED.with(|scoped| scoped.replace(Some(self.clone())));
+        // Restored even if `f` panics
+        struct Restore(Option<InputProfile>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let previous = self.0.take();
+                SCOPED.with(|scoped| *scoped.borrow_mut() = previous);
+            }
+        }
+        let _restore = Restore(previous);
+        f()
+    }
+
+    /// Whether the pointer travels to click targets instead of jumping
+    pub fn moves_mouse(&self) -> bool {
+        !self.mouse_move_duration.is_zero()
+    }
+
+    /// Pause after the next key
+    pub fn next_key_delay(&self) -> Duration {
+        self.key_delay + random_up_to(self.key_delay_jitter)
+    }
+
+    /// Pause over the next click target
+    pub fn next_click_dwell(&self) -> Duration {
+        self.click_dwell + random_up_to(self.click_dwell_jitter)
+    }
+
+    /// Pointer positions from `from` to `to` along a cubic Bézier curve
+    /// bending to a random side, ending exactly at `to`. One position per
+    /// step of the movement, empty for profiles that don't move the pointer
+    pub fn mouse_path(&self, from: Point, to: Point) -> Vec<Point> {
+        if !self.moves_mouse() {
+            return Vec::new();
+        }
+        let steps = (self.mouse_move_duration.as_secs_f64() * MOUSE_STEPS_PER_SECOND).ceil().max(1.0) as usize;
+        let (dx, dy) = (to.x - from.x, to.y - from.y);
+        // Perpendicular to the straight line, scaled to bend the path
+        let bend = |fraction: f64| {
+            let side = (random_fraction() - 0.5) * 2.0 * MOUSE_PATH_CURVATURE;
+            Point::new(from.x + dx * fraction - dy * side, from.y + dy * fraction + dx * side)
+        };
+        let (c1, c2) = (bend(0.3), bend(0.7));
+
+        (1..=steps)
+            .map(|step| {
+                // Ease in and out: slow start, fast middle, slow arrival
+                let t = step as f64 / steps as f64;
+                let t = t * t * (3.0 - 2.0 * t);
+                cubic_bezier(from, c1, c2, to, t)
+            })
+            .collect()
+    }
+}
+
+/// Profile set with [`Desktop::set_input_profile`](crate::Desktop::set_input_profile)
+pub(crate) fn set_default(profile: InputProfile) {
+    *DEFAULT_PROFILE.write().unwrap_or_else(|e| e.into_inner()) = Some(profile);
+}
+
+/// Profile for input sent now: the scoped one, else the desktop's
+pub(crate) fn current() -> InputProfile {
+    SCOPED
+        .with(|scoped| scoped.borrow().clone())
+        .or_else(|| DEFAULT_PROFILE.read().unwrap_or_else(|e| e.into_inner()).clone())
+        .unwrap_or_default()
+}
+
+pub(crate) fn cubic_bezier(p0: Point, p1: Point, p2: Point, p3: Point, t: f64) -> Point {
+    let u = 1.0 - t;
+    let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+    Point::new(
+        a * p0.x + b * p1.x + c * p2.x + d * p3.x,
+        a * p0.y + b * p1.y + c * p2.y + d * p3.y,
+    )
+}
+
+// Jitter only has to look irregular, so a xorshift seeded from the clock does
+fn random_fraction() -> f64 {
+    let mut x = RNG_STATE.load(Ordering::Relaxed);
+    if x == 0 {
+        x = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0x9E37_79B9_7F4A_7C15)
+            | 1;
+    }
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    RNG_STATE.store(x, Ordering::Relaxed);
+    (x >> 11) as f64 / (1u64 << 53) as f64
+}
+
+fn random_up_to(max: Duration) -> Duration {
+    if max.is_zero() {
+        return Duration::ZERO;
+    }
+    max.mul_f64(random_fraction())
+}
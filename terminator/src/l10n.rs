@@ -0,0 +1,295 @@
+//! Matching elements by name across display languages
+//!
+//! Control names follow the display language of the machine: the same
+//! button is "Cancel", "Abbrechen" or "Annuler". A [`Selector::NameL10n`]
+//! names a translation key instead of a name and matches an element named
+//! after any translation of it, so one workflow runs on machines with
+//! different display languages:
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop, Translations};
+//! # async fn run(desktop: &Desktop) -> Result<(), AutomationError> {
+//! // Common dialog buttons are built in
+//! desktop.locator("l10n:cancel").click(None).await?;
+//!
+//! // Application specific names come from the user's tables
+//! let mut translations = Translations::new();
+//! translations.add("export", "en", ["Export"]).add("export", "de", ["Exportieren"]);
+//! desktop.add_translations(translations);
+//! desktop.locator("role:Button && l10n:export").click(None).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Translations match the whole name, ignoring case and the `&` that marks
+//! access keys, so `l10n:save` finds "&Save" but not "Don't Save".
+//!
+//! Without a locale all translations are tried. Setting one with
+//! [`Desktop::set_locale`] limits matching to that language and English,
+//! which many applications are not translated from. Keys no table knows
+//! match like a plain name. Tables and locale are process-wide: every
+//! [`Desktop`] shares them.
+//!
+//! [`Desktop`]: crate::Desktop
+//! [`Desktop::set_locale`]: crate::Desktop::set_locale
+
+use std::collections::BTreeMap;
+use std::sync::{LazyLock, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AutomationError, Selector};
+
+static USER_TRANSLATIONS: RwLock<Option<Translations>> = RwLock::new(None);
+static LOCALE: RwLock<Option<String>> = RwLock::new(None);
+static BUILTIN_TRANSLATIONS: LazyLock<Translations> = LazyLock::new(|| {
+    let mut translations = Translations::new();
+    for (key, names) in BUILTIN {
+        for (locale, name) in *names {
+            translations.add(key, locale, [*name]);
+        }
+    }
+    translations
+});
+
+/// Localized names by translation key, then by locale (`en`, `de`,
+/// `pt-BR`, ...)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Translations {
+    entries: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+}
+
+impl Translations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Names of common dialog buttons (`ok`, `cancel`, `yes`, `no`,
+    /// `apply`, `close`, `save`, `dont_save`, `open`, `retry`, `next`,
+    /// `back`, `finish`) in English, German, French, Spanish, Italian,
+    /// Portuguese, Dutch, Japanese and Simplified Chinese
+    pub fn builtin() -> Self {
+        BUILTIN_TRANSLATIONS.clone()
+    }
+
+    /// Tables as JSON: `{"export": {"en": ["Export"], "de": ["Exportieren"]}}`
+    pub fn from_json(json: &str) -> Result<Self, AutomationError> {
+        let parsed: Self = serde_json::from_str(json)
+            .map_err(|e| AutomationError::InvalidArgument(format!("Invalid translation table: {}", e)))?;
+        // Through `add`, so keys and locales are normalized
+        let mut translations = Self::new();
+        translations.merge(parsed);
+        Ok(translations)
+    }
+
+    /// Add names of `key` in `locale`. Keys and locales are case insensitive
+    pub fn add<I, S>(&mut self, key: &str, locale: &str, names: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let known = self
+            .entries
+            .entry(normalize_key(key))
+            .or_default()
+            .entry(normalize_locale(locale))
+            .or_default();
+        for name in names {
+            let name = name.into();
+            if !known.contains(&name) {
+                known.push(name);
+            }
+        }
+        self
+    }
+
+    /// Add every name of `other`
+    pub fn merge(&mut self, other: Translations) {
+        for (key, locales) in other.entries {
+            for (locale, names) in locales {
+                self.add(&key, &locale, names);
+            }
+        }
+    }
+
+    /// Translation keys, sorted
+    pub fn keys(&self) -> Vec<String> {
+        self.entries.keys().cloned().collect()
+    }
+
+    /// Names of `key`: in `locale`, its language (`pt` for `pt-BR`) and
+    /// English when the key has names there, in every locale otherwise.
+    /// Empty for unknown keys
+    pub fn names(&self, key: &str, locale: Option<&str>) -> Vec<String> {
+        let Some(locales) = self.entries.get(&normalize_key(key)) else {
+            return Vec::new();
+        };
+        let mut names = Vec::new();
+        let mut add = |found: &Vec<String>| {
+            for name in found {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+        };
+        let mut localized = false;
+        if let Some(locale) = locale.map(normalize_locale) {
+            let language = locale.split('-').next().unwrap_or_default().to_string();
+            for wanted in [locale, language] {
+                if let Some(found) = locales.get(&wanted) {
+                    localized = true;
+                    add(found);
+                }
+            }
+        }
+        if localized {
+            if let Some(english) = locales.get("en") {
+                add(english);
+            }
+        } else {
+            locales.values().for_each(add);
+        }
+        names
+    }
+}
+
+impl Selector {
+    /// This selector with each [`Selector::NameL10n`] replaced by a
+    /// [`Selector::LocalizedName`] of the names it matches. Searches do this
+    /// once, before testing any element
+    pub fn localized(&self) -> Selector {
+        match self {
+            Selector::NameL10n(key) => Selector::LocalizedName(names(key)),
+            Selector::Chain(selectors) => Selector::Chain(selectors.iter().map(Selector::localized).collect()),
+            Selector::And(selectors) => Selector::And(selectors.iter().map(Selector::localized).collect()),
+            Selector::Or(selectors) => Selector::Or(selectors.iter().map(Selector::localized).collect()),
+            Selector::Not(inner) => Selector::Not(Box::new(inner.localized())),
+            Selector::RightOf(anchor) => Selector::RightOf(Box::new(anchor.localized())),
+            Selector::LeftOf(anchor) => Selector::LeftOf(Box::new(anchor.localized())),
+            Selector::Above(anchor) => Selector::Above(Box::new(anchor.localized())),
+            Selector::Below(anchor) => Selector::Below(Box::new(anchor.localized())),
+            Selector::Near { anchor, max_distance } => Selector::Near {
+                anchor: Box::new(anchor.localized()),
+                max_distance: *max_distance,
+            },
+            other => other.clone(),
+        }
+    }
+}
+
+/// Names an element may have for `key` with the tables and locale set
+/// through the desktop: the user's names first, then the built-in ones.
+/// Just `key` when no table knows it
+pub fn names(key: &str) -> Vec<String> {
+    let locale = locale();
+    let mut names = USER_TRANSLATIONS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|user| user.names(key, locale.as_deref()))
+        .unwrap_or_default();
+    for name in BUILTIN_TRANSLATIONS.names(key, locale.as_deref()) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    if names.is_empty() {
+        names.push(key.to_string());
+    }
+    names
+}
+
+/// Whether `name` is one of `names` as a whole, ignoring case and the `&`
+/// marking access keys
+pub(crate) fn is_one_of(name: &str, names: &[String]) -> bool {
+    let name = comparable(name);
+    names.iter().any(|candidate| comparable(candidate) == name)
+}
+
+fn comparable(name: &str) -> String {
+    name.replace('&', "").trim().to_lowercase()
+}
+
+/// Tables added with [`Desktop::add_translations`](crate::Desktop::add_translations)
+pub(crate) fn add_translations(translations: Translations) {
+    USER_TRANSLATIONS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(Translations::new)
+        .merge(translations);
+}
+
+/// Locale set with [`Desktop::set_locale`](crate::Desktop::set_locale)
+pub(crate) fn set_locale(locale: Option<&str>) {
+    *LOCALE.write().unwrap_or_else(|e| e.into_inner()) = locale.map(normalize_locale);
+}
+
+pub(crate) fn locale() -> Option<String> {
+    LOCALE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn normalize_key(key: &str) -> String {
+    key.trim().to_lowercase()
+}
+
+// `pt_BR` and `PT-br` as `pt-br`
+fn normalize_locale(locale: &str) -> String {
+    locale.trim().replace('_', "-").to_lowercase()
+}
+
+// Names as the standard dialogs of each language show them
+const BUILTIN: &[(&str, &[(&str, &str)])] = &[
+    (
+        "ok",
+        &[("en", "OK"), ("de", "OK"), ("fr", "OK"), ("es", "Aceptar"), ("it", "OK"), ("pt", "OK"), ("nl", "OK"), ("ja", "OK"), ("zh-cn", "确定")],
+    ),
+    (
+        "cancel",
+        &[("en", "Cancel"), ("de", "Abbrechen"), ("fr", "Annuler"), ("es", "Cancelar"), ("it", "Annulla"), ("pt", "Cancelar"), ("nl", "Annuleren"), ("ja", "キャンセル"), ("zh-cn", "取消")],
+    ),
+    (
+        "yes",
+        &[("en", "Yes"), ("de", "Ja"), ("fr", "Oui"), ("es", "Sí"), ("it", "Sì"), ("pt", "Sim"), ("nl", "Ja"), ("ja", "はい"), ("zh-cn", "是")],
+    ),
+    (
+        "no",
+        &[("en", "No"), ("de", "Nein"), ("fr", "Non"), ("es", "No"), ("it", "No"), ("pt", "Não"), ("nl", "Nee"), ("ja", "いいえ"), ("zh-cn", "否")],
+    ),
+    (
+        "apply",
+        &[("en", "Apply"), ("de", "Übernehmen"), ("fr", "Appliquer"), ("es", "Aplicar"), ("it", "Applica"), ("pt", "Aplicar"), ("nl", "Toepassen"), ("ja", "適用"), ("zh-cn", "应用")],
+    ),
+    (
+        "close",
+        &[("en", "Close"), ("de", "Schließen"), ("fr", "Fermer"), ("es", "Cerrar"), ("it", "Chiudi"), ("pt", "Fechar"), ("nl", "Sluiten"), ("ja", "閉じる"), ("zh-cn", "关闭")],
+    ),
+    (
+        "save",
+        &[("en", "Save"), ("de", "Speichern"), ("fr", "Enregistrer"), ("es", "Guardar"), ("it", "Salva"), ("pt", "Salvar"), ("pt-pt", "Guardar"), ("nl", "Opslaan"), ("ja", "保存"), ("zh-cn", "保存")],
+    ),
+    (
+        "dont_save",
+        &[("en", "Don't Save"), ("de", "Nicht speichern"), ("fr", "Ne pas enregistrer"), ("es", "No guardar"), ("it", "Non salvare"), ("pt", "Não salvar"), ("pt-pt", "Não guardar"), ("nl", "Niet opslaan"), ("ja", "保存しない"), ("zh-cn", "不保存")],
+    ),
+    (
+        "open",
+        &[("en", "Open"), ("de", "Öffnen"), ("fr", "Ouvrir"), ("es", "Abrir"), ("it", "Apri"), ("pt", "Abrir"), ("nl", "Openen"), ("ja", "開く"), ("zh-cn", "打开")],
+    ),
+    (
+        "retry",
+        &[("en", "Retry"), ("de", "Wiederholen"), ("fr", "Réessayer"), ("es", "Reintentar"), ("it", "Riprova"), ("pt", "Repetir"), ("nl", "Opnieuw"), ("ja", "再試行"), ("zh-cn", "重试")],
+    ),
+    (
+        "next",
+        &[("en", "Next"), ("de", "Weiter"), ("fr", "Suivant"), ("es", "Siguiente"), ("it", "Avanti"), ("pt", "Avançar"), ("nl", "Volgende"), ("ja", "次へ"), ("zh-cn", "下一步")],
+    ),
+    (
+        "back",
+        &[("en", "Back"), ("de", "Zurück"), ("fr", "Précédent"), ("es", "Atrás"), ("it", "Indietro"), ("pt", "Voltar"), ("nl", "Vorige"), ("ja", "戻る"), ("zh-cn", "上一步")],
+    ),
+    (
+        "finish",
+        &[("en", "Finish"), ("de", "Fertig stellen"), ("fr", "Terminer"), ("es", "Finalizar"), ("it", "Fine"), ("pt", "Concluir"), ("nl", "Voltooien"), ("ja", "完了"), ("zh-cn", "完成")],
+    ),
+];
@@ -3,34 +3,172 @@
 //! This module provides a cross-platform API for automating desktop applications
 //! through accessibility APIs, inspired by Playwright's web automation model.
 
-use std::sync::Arc;
-use std::time::{Instant};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use std::fmt;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error, info, instrument, warn};
+use crate::middleware::ActionHooks;
 
+pub mod action_strategy;
+pub mod app_profile;
+pub mod app_session;
+pub mod attribute_watch;
+pub mod audit;
+pub mod batch;
+#[cfg(feature = "bench")]
+pub mod bench;
+#[cfg(feature = "browser")]
+pub mod browser;
+pub mod console;
+pub mod controls;
+pub mod dialogs;
+pub mod dry_run;
 pub mod element;
+pub mod elevation;
+pub mod environment;
+pub mod focus_history;
+pub mod form;
 pub mod errors;
+pub mod expect;
+pub mod geometry;
+pub mod hotkey;
+pub mod image_diff;
+pub mod image_match;
+pub mod input_guard;
+pub mod input_profile;
+pub mod l10n;
 pub mod locator;
+pub mod middleware;
+pub mod monitor;
+pub mod notifications;
+pub mod ocr;
+pub mod permissions;
 pub mod platforms;
+pub mod postcondition;
+pub mod process;
+pub mod screenshot;
+pub mod search_index;
 pub mod selector;
+pub mod selector_cache;
+pub mod selector_path;
+pub mod selector_profile;
+pub mod session;
+pub mod session_trace;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+pub mod spatial;
+pub mod stale_element;
+pub mod text_pattern;
+pub mod tree_diff;
+pub mod tree_handles;
+pub mod tree_query;
+pub mod tree_stream;
+pub mod ui_events;
 #[cfg(test)]
 mod tests;
 pub mod utils;
+pub mod verify;
+pub mod virtual_desktop;
+pub mod watchdog;
+pub mod workflow;
 
-pub use element::{UIElement, UIElementAttributes, SerializableUIElement};
-pub use errors::AutomationError;
-pub use locator::Locator;
+pub use action_strategy::{ActionStrategy, StrategyAttempt};
+pub use app_profile::AppProfile;
+pub use app_session::{AppLifecycleEvent, AppSession, AppTarget};
+pub use attribute_watch::AttributeWatch;
+pub use audit::{Audit, AuditReport};
+pub use batch::{Batch, BatchOp, BatchResult, BatchStatus};
+#[cfg(feature = "browser")]
+pub use browser::{Browser, BrowserElement, BrowserPage};
+pub use console::ConsoleScreen;
+pub use controls::{CheckBox, ComboBox, Menu, RangeValue, Slider};
+pub use dialogs::{FileDialog, FileDialogKind};
+pub use element::{UIElement, UIElementAttributes, SerializableUIElement, WindowState};
+pub use elevation::{AutomationCapabilities, IntegrityLevel};
+pub use environment::{EnvironmentInfo, Theme};
+pub use focus_history::{FocusCallback, FocusHistory, FocusRecord};
+pub use form::{Form, FormFillReport};
+pub use dry_run::SimulatedAction;
+pub use errors::{AutomationError, ErrorCode, ErrorContext};
+pub use expect::{expect, Expect, Expectation};
+pub use geometry::{Anchor, Point, Rect};
+pub use hotkey::{Hotkey, HotkeyKey, HotkeyRegistration};
+pub use image_diff::DiffResult;
+pub use image_match::{ImageMatch, ImageSearch};
+pub use input_guard::InputGuard;
+pub use input_profile::InputProfile;
+pub use l10n::Translations;
+pub use locator::{Locator, RetryPolicy};
+pub use middleware::{ActionContext, ActionKind, Middleware, MiddlewareId};
+pub use monitor::MonitorInfo;
+pub use notifications::{Notification, NotificationCallback};
+pub use ocr::{OcrWord, ScreenMatch};
+pub use permissions::{Permission, PermissionState, PermissionStatus};
+pub use postcondition::{Condition, VerifiedActionResult};
+pub use process::AppHandle;
+pub use screenshot::{EncodedImage, ImageFormat};
+pub use search_index::TreeIndex;
 pub use selector::Selector;
+pub use selector_cache::{AppKey, SelectorCache};
+pub use selector_profile::{HopProfile, SelectorProfile};
+pub use session::SessionState;
+pub use session_trace::{SessionTracer, TraceFormat, TraceRecord, TraceRecordKind};
+pub use stale_element::{ElementDescriptor, RefreshableElement};
+pub use text_pattern::TextRun;
+pub use tree_diff::TreeDiff;
+pub use tree_handles::UINodeWithHandles;
+pub use tree_query::{PrunedTree, QueryMatch};
+pub use tree_stream::{TreeAssembler, TreeChunk, WindowTreeStream};
+pub use ui_events::{UIEvent, UIEventKind, UIEventSubscription};
+pub use verify::{SoftVerifier, VerificationReport};
+pub use virtual_desktop::VirtualDesktop;
+pub use watchdog::{DiagnosticBundle, StallCallback, WatchdogState};
+pub use workflow::{StepCondition, StepGuard, StepResult, StepStatus, Workflow, WorkflowReport, WorkflowStatus, WorkflowStep};
 
 #[cfg(target_os = "windows")]
 pub use platforms::windows::convert_uiautomation_element_to_terminator;
+// Pattern types for `UIElement::pattern`
+#[cfg(target_os = "windows")]
+pub use uiautomation;
+
+const DEFAULT_PAGE_LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+const PAGE_LOAD_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long an application's UI must go without structure changes for
+/// [`Desktop::wait_for_idle`] to consider it idle
+pub const DEFAULT_IDLE_QUIET_PERIOD: Duration = Duration::from_millis(500);
 
 // Define a new struct to hold click result information - move to module level
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClickResult {
+    /// The strategy's name for clicks through an [`ActionStrategy`], else a
+    /// description such as `Double Click`
     pub method: String,
     pub coordinates: Option<(f64, f64)>,
     pub details: String,
+    /// How the click reached the application, if it did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<ActionStrategy>,
+    /// Strategies `UIElement::click_escalating` tried before the one that
+    /// worked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<StrategyAttempt>,
+}
+
+/// Identifies a top-level window captured by `Desktop::get_all_window_trees`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowInfo {
+    /// Process ID owning the window
+    pub pid: u32,
+    /// Window title
+    pub title: String,
+    /// Element ID of the window, if available
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Window bounds (x, y, width, height)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bounds: Option<(f64, f64, f64, f64)>,
 }
 
 /// Holds the output of a terminal command execution
@@ -40,12 +178,48 @@ pub struct CommandOutput {
     pub stderr: String,
 }
 
+/// Whether a node's children were all collected when its tree was built
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubtreeStatus {
+    /// Every child was visited
+    #[default]
+    Complete,
+    /// `TreeBuildConfig::max_total_duration` ran out before every child was visited
+    TimedOut,
+    /// The children could not be read
+    Failed,
+}
+
+impl SubtreeStatus {
+    pub fn is_complete(&self) -> bool {
+        *self == SubtreeStatus::Complete
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubtreeStatus::Complete => "complete",
+            SubtreeStatus::TimedOut => "timed_out",
+            SubtreeStatus::Failed => "failed",
+        }
+    }
+}
+
 /// Represents a node in the UI tree, containing its attributes and children.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct UINode {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
     pub attributes: UIElementAttributes,
+    /// Path selector that re-locates this element, e.g. `/Window[name='App']/Pane[1]/Button[2]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Whether this node or any node below it is missing children
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// Whether this node's own children were all collected
+    #[serde(default, skip_serializing_if = "SubtreeStatus::is_complete")]
+    pub status: SubtreeStatus,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<UINode>,
 }
@@ -61,6 +235,9 @@ impl UINode {
     fn debug_with_depth(&self, f: &mut fmt::Formatter<'_>, current_depth: usize, max_depth: usize) -> fmt::Result {
         let mut debug_struct = f.debug_struct("UINode");
         debug_struct.field("attributes", &self.attributes);
+        if !self.status.is_complete() {
+            debug_struct.field("status", &self.status);
+        }
         
         if !self.children.is_empty() {
             if current_depth < max_depth {
@@ -129,6 +306,11 @@ pub struct ScreenshotResult {
 /// The main entry point for UI automation
 pub struct Desktop {
     engine: Arc<dyn platforms::AccessibilityEngine>,
+    watchdog: Arc<Mutex<Option<watchdog::Watchdog>>>,
+    watchdog_state: Arc<WatchdogState>,
+    selector_cache: Arc<Mutex<Option<Arc<SelectorCache>>>>,
+    focus_tracker: Arc<Mutex<Option<focus_history::FocusTracker>>>,
+    hooks: Arc<ActionHooks>,
 }
 
 impl Desktop {
@@ -143,15 +325,23 @@ impl Desktop {
         let engine = platforms::create_engine(use_background_apps, activate_app)?;
         
         let duration = start.elapsed();
+        let capabilities = elevation::current();
         info!(
             duration_ms = duration.as_millis(),
             use_background_apps,
             activate_app,
+            integrity_level = ?capabilities.integrity_level,
+            ui_access = capabilities.ui_access,
             "Desktop automation engine initialized"
         );
         
         Ok(Self {
             engine: Arc::from(engine),
+            watchdog: Arc::new(Mutex::new(None)),
+            watchdog_state: WatchdogState::new(),
+            selector_cache: Arc::new(Mutex::new(None)),
+            focus_tracker: Arc::new(Mutex::new(None)),
+            hooks: ActionHooks::new(),
         })
     }
 
@@ -183,7 +373,12 @@ impl Desktop {
             "Root element retrieved"
         );
         
-        element
+        self.owned(element)
+    }
+
+    // Elements handed out run their actions through this desktop's middleware
+    fn owned(&self, element: UIElement) -> UIElement {
+        element.with_hooks(Some(self.hooks.clone()))
     }
 
     #[instrument(skip(self, selector))]
@@ -192,7 +387,9 @@ impl Desktop {
         let selector = selector.into();
         info!(?selector, "Creating locator");
         
-        let locator = Locator::new(self.engine.clone(), selector);
+        let locator = Locator::new(self.engine.clone(), selector)
+            .with_watchdog(self.watchdog_state.clone())
+            .with_hooks(Some(self.hooks.clone()));
         
         let duration = start.elapsed();
         info!(
@@ -203,6 +400,48 @@ impl Desktop {
         locator
     }
 
+    /// Queue several find, click, type and get-text operations to run in
+    /// one call, see [`Batch`]
+    pub fn batch(&self) -> Batch {
+        Batch::new(self.clone())
+    }
+
+    /// Create a locator for one logical target that tries several selectors
+    /// in order, e.g. an automation id first and the visible name as a
+    /// fallback.
+    ///
+    /// `target` names what is being looked for. With a selector cache set
+    /// (see [`Desktop::set_selector_cache`]) the selector that resolved the
+    /// target is remembered per application executable and version, and
+    /// tried first next time.
+    #[instrument(skip(self, selectors))]
+    pub fn locator_any(&self, target: &str, selectors: &[&str]) -> Locator {
+        let cache = match self.selector_cache.lock() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+        info!(target, candidates = selectors.len(), cached = cache.is_some(), "Creating fallback locator");
+        Locator::any(
+            self.engine.clone(),
+            target,
+            selectors.iter().map(|s| s.to_string()).collect(),
+            cache,
+        )
+        .with_watchdog(self.watchdog_state.clone())
+        .with_hooks(Some(self.hooks.clone()))
+    }
+
+    /// Enable (or with `None`, disable) learning which selector resolved each
+    /// target of [`Desktop::locator_any`]. Shared by clones of this desktop.
+    #[instrument(skip(self, cache))]
+    pub fn set_selector_cache(&self, cache: Option<Arc<SelectorCache>>) {
+        info!(enabled = cache.is_some(), "Setting selector cache");
+        match self.selector_cache.lock() {
+            Ok(mut guard) => *guard = cache,
+            Err(poisoned) => *poisoned.into_inner() = cache,
+        }
+    }
+
     #[instrument(skip(self))]
     pub fn focused_element(&self) -> Result<UIElement, AutomationError> {
         let start = Instant::now();
@@ -217,7 +456,37 @@ impl Desktop {
             "Focused element retrieved"
         );
         
-        Ok(element)
+        Ok(self.owned(element))
+    }
+
+    /// The deepest element at a screen point, in physical pixels. Windows only.
+    #[instrument(skip(self))]
+    pub fn element_at_point(&self, x: f64, y: f64) -> Result<UIElement, AutomationError> {
+        Ok(self.owned(self.engine.element_at_point(x, y)?))
+    }
+
+    /// Wait up to `timeout` for an Open or Save dialog to have focus and
+    /// attach to it. See [`dialogs`].
+    #[instrument(skip(self))]
+    pub fn file_dialog(&self, timeout: Duration) -> Result<FileDialog, AutomationError> {
+        let start = Instant::now();
+        let dialog = FileDialog::attach(self, timeout)?;
+        info!(
+            duration_ms = start.elapsed().as_millis(),
+            kind = ?dialog.kind(),
+            "File dialog attached"
+        );
+        Ok(dialog)
+    }
+
+    /// Connect to a Chromium-based browser over the DevTools Protocol, on
+    /// `port` or on a detected debugging port. See [`browser`].
+    #[cfg(feature = "browser")]
+    #[instrument(skip(self))]
+    pub async fn browser(&self, port: Option<u16>) -> Result<Browser, AutomationError> {
+        let browser = Browser::connect(self.engine.clone(), port).await?;
+        info!(port = browser.port(), "Connected to browser");
+        Ok(browser)
     }
 
     #[instrument(skip(self))]
@@ -234,7 +503,7 @@ impl Desktop {
             "Applications retrieved"
         );
         
-        Ok(apps)
+        Ok(apps.into_iter().map(|app| self.owned(app)).collect())
     }
 
     #[instrument(skip(self, name))]
@@ -251,9 +520,26 @@ impl Desktop {
             "Application retrieved"
         );
         
-        Ok(app)
+        Ok(self.owned(app))
+    }
+
+    /// Pin locators, trees, screenshots and events to one application, given
+    /// by name or pid (`"notepad"`, `"1234"` or `1234u32`). The session follows
+    /// the app when its process restarts, see [`AppSession`]. Fails if the app
+    /// is not running.
+    #[instrument(skip(self, target))]
+    pub fn attach(&self, target: impl Into<AppTarget>) -> Result<AppSession, AutomationError> {
+        let target = target.into();
+        info!(%target, "Attaching to application");
+        AppSession::attach(self.clone(), target)
     }
 
+    /// Opens an application by name and returns its main window.
+    ///
+    /// On Windows, prefix the name with `uwp:` to launch a packaged UWP or
+    /// WinUI 3 app. The part after the prefix may be a display name, a package
+    /// name or a full AppUserModelID (`Publisher.App_hash!App`); the call waits
+    /// for the app to get past its splash screen.
     #[instrument(skip(self, app_name))]
     pub fn open_application(&self, app_name: &str) -> Result<UIElement, AutomationError> {
         let start = Instant::now();
@@ -267,7 +553,40 @@ impl Desktop {
             "Application opened"
         );
         
-        Ok(app)
+        Ok(self.owned(app))
+    }
+
+    /// Start an executable and keep a handle to its process.
+    ///
+    /// Unlike [`Desktop::open_application`], the handle can wait for the app's
+    /// window, await its exit code and kill it. `env` entries are added to the
+    /// inherited environment; `cwd` defaults to the current directory.
+    #[instrument(skip(self, args, env))]
+    pub fn launch(
+        &self,
+        path: &str,
+        args: &[&str],
+        env: &[(&str, &str)],
+        cwd: Option<&str>,
+    ) -> Result<AppHandle, AutomationError> {
+        let start = Instant::now();
+        info!(path, args = args.len(), "Launching process");
+
+        let handle = AppHandle::launch(self.engine.clone(), self.hooks.clone(), path, args, env, cwd)?;
+
+        let duration = start.elapsed();
+        info!(
+            duration_ms = duration.as_millis(),
+            pid = handle.pid(),
+            "Process launched"
+        );
+
+        Ok(handle)
+    }
+
+    /// Executable name of process `pid`, without extension
+    pub fn process_name(&self, pid: u32) -> Result<String, AutomationError> {
+        self.engine.process_name(pid)
     }
 
     #[instrument(skip(self, app_name))]
@@ -286,12 +605,16 @@ impl Desktop {
         Ok(())
     }
 
+    /// Open a URL and return the browser's window.
+    ///
+    /// Returns as soon as the browser is launched, usually before the page has
+    /// loaded. Use [`Desktop::open_url_and_wait`] to get the page itself.
     #[instrument(skip(self, url, browser))]
-    pub fn open_url(&self, url: &str, browser: Option<&str>) -> Result<(), AutomationError> {
+    pub fn open_url(&self, url: &str, browser: Option<&str>) -> Result<UIElement, AutomationError> {
         let start = Instant::now();
         info!(url, ?browser, "Opening URL");
         
-        self.engine.open_url(url, browser)?;
+        let window = self.engine.open_url(url, browser)?;
         
         let duration = start.elapsed();
         info!(
@@ -299,7 +622,58 @@ impl Desktop {
             "URL opened"
         );
         
-        Ok(())
+        Ok(self.owned(window))
+    }
+
+    /// Open a URL and wait until the browser shows it, returning the page's
+    /// document element so locators can be chained without racing the load.
+    ///
+    /// The page is the document whose URL (its value) matches `url`, ignoring
+    /// scheme, `www.`, case and trailing slashes; redirects deeper into the
+    /// same site still match. For documents that expose no URL, a name
+    /// containing the host is accepted. Waits 30 seconds without a timeout.
+    #[instrument(skip(self, url, browser))]
+    pub async fn open_url_and_wait(
+        &self,
+        url: &str,
+        browser: Option<&str>,
+        timeout: Option<Duration>,
+    ) -> Result<UIElement, AutomationError> {
+        let start = Instant::now();
+        let timeout = timeout.unwrap_or(DEFAULT_PAGE_LOAD_TIMEOUT);
+        info!(url, ?browser, timeout_ms = timeout.as_millis(), "Opening URL and waiting for the page");
+
+        let window = self.engine.open_url(url, browser)?;
+        let documents = Selector::Role {
+            role: "document".to_string(),
+            name: None,
+        };
+        let host = utils::url_host(url);
+        loop {
+            let found = self
+                .engine
+                .find_elements(&documents, Some(&window), Some(Duration::ZERO), None)
+                .unwrap_or_default();
+            let page = found.into_iter().find(|document| {
+                match document.attribute_value("value").ok().flatten() {
+                    Some(shown) if !shown.is_empty() => utils::url_matches(&shown, url),
+                    _ => !host.is_empty()
+                        && document.name().is_some_and(|name| utils::normalize(&name).contains(&host)),
+                }
+            });
+            if let Some(page) = page {
+                self.watchdog_state.record_activity();
+                info!(duration_ms = start.elapsed().as_millis(), "Page loaded");
+                return Ok(page);
+            }
+            if start.elapsed() >= timeout {
+                return Err(AutomationError::Timeout(format!(
+                    "Browser did not show '{}' within {:?}",
+                    url, timeout
+                )));
+            }
+            tokio::time::sleep(PAGE_LOAD_POLL_INTERVAL).await;
+        }
     }
 
     #[instrument(skip(self, file_path))]
@@ -326,6 +700,10 @@ impl Desktop {
     ) -> Result<CommandOutput, AutomationError> {
         let start = Instant::now();
         info!(?windows_command, ?unix_command, "Running command");
+        if self.hooks.dry_run.is_enabled() {
+            let command = if cfg!(target_os = "windows") { windows_command } else { unix_command };
+            return Ok(self.hooks.dry_run.simulate_command("run_command", command.unwrap_or_default().to_string()));
+        }
         
         let output = self.engine.run_command(windows_command, unix_command).await?;
         
@@ -359,6 +737,23 @@ impl Desktop {
         Ok(screenshot)
     }
 
+    /// Capture the primary monitor encoded as `format`
+    #[instrument(skip(self))]
+    pub async fn capture_screen_as(&self, format: ImageFormat) -> Result<EncodedImage, AutomationError> {
+        let screenshot = self.capture_screen().await?;
+
+        let start = Instant::now();
+        let image = screenshot.encode(format)?;
+        info!(
+            duration_ms = start.elapsed().as_millis(),
+            format = %format,
+            bytes = image.data.len(),
+            "Screen capture encoded"
+        );
+
+        Ok(image)
+    }
+
     #[instrument(skip(self))]
     pub async fn get_active_monitor_name(&self) -> Result<String, AutomationError> {
         // Get all windows
@@ -385,6 +780,67 @@ impl Desktop {
         Ok(monitor_name)
     }
 
+    /// List the connected monitors with their bounds, scale factor and refresh rate
+    #[instrument(skip(self))]
+    pub fn monitors(&self) -> Result<Vec<MonitorInfo>, AutomationError> {
+        let start = Instant::now();
+        info!("Listing monitors");
+
+        let monitors = monitor::list_monitors()?;
+
+        let duration = start.elapsed();
+        info!(
+            duration_ms = duration.as_millis(),
+            count = monitors.len(),
+            "Monitors listed"
+        );
+
+        Ok(monitors)
+    }
+
+    /// Draw a border around each of `elements` at once, e.g. to show what a
+    /// locator matched before acting on it. Returns without waiting for the
+    /// highlight to fade. `color` is BGR and defaults to red
+    #[instrument(skip(self, elements))]
+    pub fn highlight_all(
+        &self,
+        elements: &[UIElement],
+        color: Option<u32>,
+        duration: Option<Duration>,
+    ) -> Result<(), AutomationError> {
+        let start = Instant::now();
+        info!(count = elements.len(), "Highlighting elements");
+
+        self.engine.highlight_all(elements, color, duration)?;
+
+        let duration = start.elapsed();
+        info!(duration_ms = duration.as_millis(), count = elements.len(), "Elements highlighted");
+
+        Ok(())
+    }
+
+    /// OS version, theme, animation, scaling and screen reader settings that
+    /// affect UI trees and timings. Useful to attach to bug reports
+    #[instrument(skip(self))]
+    pub fn environment(&self) -> Result<EnvironmentInfo, AutomationError> {
+        let start = Instant::now();
+        info!("Querying environment");
+
+        let mut environment = self.engine.environment()?;
+        if environment.display_scale_factor.is_none() {
+            // Best effort: headless sessions may have no monitor to report
+            environment.display_scale_factor = monitor::list_monitors()
+                .ok()
+                .and_then(|monitors| monitors.into_iter().find(|m| m.is_primary))
+                .map(|m| m.scale_factor);
+        }
+
+        let duration = start.elapsed();
+        info!(duration_ms = duration.as_millis(), "Environment queried");
+
+        Ok(environment)
+    }
+
     #[instrument(skip(self, name))]
     pub async fn capture_monitor_by_name(
         &self,
@@ -406,6 +862,27 @@ impl Desktop {
         Ok(screenshot)
     }
 
+    /// Capture the monitor called `name` encoded as `format`
+    #[instrument(skip(self, name))]
+    pub async fn capture_monitor_by_name_as(
+        &self,
+        name: &str,
+        format: ImageFormat,
+    ) -> Result<EncodedImage, AutomationError> {
+        let screenshot = self.capture_monitor_by_name(name).await?;
+
+        let start = Instant::now();
+        let image = screenshot.encode(format)?;
+        info!(
+            duration_ms = start.elapsed().as_millis(),
+            format = %format,
+            bytes = image.data.len(),
+            "Monitor capture encoded"
+        );
+
+        Ok(image)
+    }
+
     #[instrument(skip(self, image_path))]
     pub async fn ocr_image_path(&self, image_path: &str) -> Result<String, AutomationError> {
         let start = Instant::now();
@@ -447,6 +924,170 @@ impl Desktop {
         Ok(text)
     }
 
+    /// Find where `text` is shown on screen by OCR, for UIs that expose no
+    /// accessibility tree. Searches all monitors, or only `region` (in
+    /// physical screen pixels) when given. Matches are ordered by monitor,
+    /// then in reading order
+    #[instrument(skip(self))]
+    pub async fn find_text_on_screen(
+        &self,
+        text: &str,
+        region: Option<Rect>,
+    ) -> Result<Vec<ScreenMatch>, AutomationError> {
+        if text.trim().is_empty() {
+            return Err(AutomationError::InvalidArgument(
+                "text to find must not be empty".to_string(),
+            ));
+        }
+        let start = Instant::now();
+
+        let root = self.root();
+        let mut matches = Vec::new();
+        for (area, screenshot) in self.capture_areas(region).await? {
+            let words = self.engine.ocr_words(&screenshot)?;
+            for (found, bounds) in ocr::find_phrase(&words, text) {
+                matches.push(ScreenMatch {
+                    text: found,
+                    bounds: Rect::new(bounds.x + area.x, bounds.y + area.y, bounds.width, bounds.height),
+                    root: root.clone(),
+                });
+            }
+        }
+
+        info!(
+            duration_ms = start.elapsed().as_millis(),
+            count = matches.len(),
+            "Text located on screen"
+        );
+        Ok(matches)
+    }
+
+    /// Find where the image `template_png` (PNG or any format the `image`
+    /// crate reads) is shown on screen, for controls without names such as
+    /// toolbar icons. Searches all monitors, or only `region` (in physical
+    /// screen pixels) when given. Matches are ordered by confidence
+    #[instrument(skip(self, template_png))]
+    pub async fn find_image(
+        &self,
+        template_png: &[u8],
+        threshold: f32,
+        region: Option<Rect>,
+    ) -> Result<Vec<ImageMatch>, AutomationError> {
+        let search = ImageSearch {
+            threshold,
+            region,
+            ..Default::default()
+        };
+        self.find_image_with(template_png, &search).await
+    }
+
+    /// [`Desktop::find_image`] with all search options, e.g. to also try
+    /// the template at other sizes
+    #[instrument(skip(self, template_png))]
+    pub async fn find_image_with(
+        &self,
+        template_png: &[u8],
+        search: &ImageSearch,
+    ) -> Result<Vec<ImageMatch>, AutomationError> {
+        if !(0.0..=1.0).contains(&search.threshold) {
+            return Err(AutomationError::InvalidArgument(format!(
+                "threshold must be between 0.0 and 1.0, got {}",
+                search.threshold
+            )));
+        }
+        let template = image::load_from_memory(template_png)
+            .map_err(|e| AutomationError::InvalidArgument(format!("Invalid template image: {}", e)))?
+            .to_luma8();
+        let start = Instant::now();
+
+        let root = self.root();
+        let mut matches = Vec::new();
+        for (area, screenshot) in self.capture_areas(search.region).await? {
+            let screen = image::RgbaImage::from_raw(screenshot.width, screenshot.height, screenshot.image_data)
+                .map(|rgba| image::DynamicImage::ImageRgba8(rgba).to_luma8())
+                .ok_or_else(|| AutomationError::PlatformError("Invalid screenshot data".to_string()))?;
+            for &scale in &search.scales {
+                let width = (template.width() as f32 * scale).round() as u32;
+                let height = (template.height() as f32 * scale).round() as u32;
+                if width == 0 || height == 0 {
+                    continue;
+                }
+                let scaled = if scale == 1.0 {
+                    template.clone()
+                } else {
+                    image::imageops::resize(&template, width, height, image::imageops::FilterType::Triangle)
+                };
+                for (bounds, confidence) in image_match::match_template(&screen, &scaled, search.threshold)? {
+                    matches.push((
+                        Rect::new(bounds.x + area.x, bounds.y + area.y, bounds.width, bounds.height),
+                        confidence,
+                        scale,
+                    ));
+                }
+            }
+        }
+
+        // Sizes close to each other match the same spot
+        let kept = image_match::suppress_overlaps(matches.iter().map(|(r, c, _)| (*r, *c)).collect());
+        let matches: Vec<ImageMatch> = kept
+            .into_iter()
+            .filter_map(|(bounds, confidence)| {
+                let (_, _, scale) = matches.iter().find(|(r, c, _)| *r == bounds && *c == confidence)?;
+                Some(ImageMatch {
+                    bounds,
+                    confidence,
+                    scale: *scale,
+                    root: root.clone(),
+                })
+            })
+            .collect();
+
+        info!(
+            duration_ms = start.elapsed().as_millis(),
+            count = matches.len(),
+            "Image located on screen"
+        );
+        Ok(matches)
+    }
+
+    // Screenshots of the parts of each monitor inside `region` (everything
+    // when `None`), with their position in screen coordinates
+    async fn capture_areas(
+        &self,
+        region: Option<Rect>,
+    ) -> Result<Vec<(Rect, ScreenshotResult)>, AutomationError> {
+        let areas: Vec<(MonitorInfo, Rect)> = monitor::list_monitors()?
+            .into_iter()
+            .filter_map(|monitor| {
+                let area = match &region {
+                    Some(region) => monitor.bounds.intersection(region)?,
+                    None => monitor.bounds,
+                };
+                Some((monitor, area))
+            })
+            .collect();
+        if areas.is_empty() {
+            return Err(AutomationError::InvalidArgument(format!(
+                "Region {:?} is not on any monitor",
+                region
+            )));
+        }
+
+        let mut captures = Vec::with_capacity(areas.len());
+        for (monitor, area) in areas {
+            let screenshot = self.engine.capture_monitor_by_name(&monitor.name).await?;
+            // Relative to the monitor's top-left corner
+            let local = Rect::new(
+                area.x - monitor.bounds.x,
+                area.y - monitor.bounds.y,
+                area.width,
+                area.height,
+            );
+            captures.push((area, ocr::crop(&screenshot, &local)?));
+        }
+        Ok(captures)
+    }
+
     #[instrument(skip(self, title))]
     pub fn activate_browser_window_by_title(&self, title: &str) -> Result<(), AutomationError> {
         let start = Instant::now();
@@ -477,7 +1118,7 @@ impl Desktop {
             "Current browser window retrieved"
         );
         
-        Ok(window)
+        Ok(self.owned(window))
     }
 
     #[instrument(skip(self))]
@@ -494,7 +1135,7 @@ impl Desktop {
             "Current window retrieved"
         );
 
-        Ok(window)
+        Ok(self.owned(window))
     }
 
     #[instrument(skip(self))]
@@ -511,7 +1152,7 @@ impl Desktop {
             "Current application retrieved"
         );
 
-        Ok(application)
+        Ok(self.owned(application))
     }
 
     #[instrument(skip(self, pid, title, config))]
@@ -527,12 +1168,644 @@ impl Desktop {
             duration_ms = duration.as_millis(),
             pid = pid,
             ?title,
+            truncated = window_tree_root.truncated,
             "Window tree retrieved"
         );
 
         Ok(window_tree_root)
     }
 
+    /// `get_window_tree`, with how many elements were built, how many came
+    /// from UIA cache requests and how long it took
+    #[instrument(skip(self, pid, title, config))]
+    pub fn get_window_tree_with_stats(
+        &self,
+        pid: u32,
+        title: Option<&str>,
+        config: Option<crate::platforms::TreeBuildConfig>,
+    ) -> Result<(UINode, crate::platforms::TreeBuildStats), AutomationError> {
+        let (tree, stats) = self.engine.get_window_tree_with_stats(pid, title, config.unwrap_or_default())?;
+        info!(
+            pid,
+            ?title,
+            elements = stats.elements,
+            cached_elements = stats.cached_elements,
+            duration_ms = stats.duration_ms,
+            "Window tree retrieved"
+        );
+        Ok((tree, stats))
+    }
+
+    /// `get_window_tree`, keeping the live element behind each node so a
+    /// node found in the tree can be acted on without searching for it
+    /// again. Windows only.
+    #[instrument(skip(self, pid, title, config))]
+    pub fn get_window_tree_with_handles(
+        &self,
+        pid: u32,
+        title: Option<&str>,
+        config: Option<crate::platforms::TreeBuildConfig>,
+    ) -> Result<UINodeWithHandles, AutomationError> {
+        let tree = self.engine.get_window_tree_with_handles(pid, title, config.unwrap_or_default())?;
+        info!(pid, ?title, elements = tree.len(), "Window tree with handles retrieved");
+        Ok(tree)
+    }
+
+    /// Read the window of `pid` (the one titled `title` if given) once and
+    /// answer later searches of locators created from the returned window
+    /// from an index of its tree, see [`search_index`]. Indexing a window
+    /// again replaces its index. Windows only.
+    #[instrument(skip(self, config))]
+    pub fn index_window(
+        &self,
+        pid: u32,
+        title: Option<&str>,
+        config: Option<crate::platforms::TreeBuildConfig>,
+    ) -> Result<UIElement, AutomationError> {
+        search_index::index_window(self.engine.clone(), pid, title, config.unwrap_or_default())
+    }
+
+    /// Drop the search indexes of the windows of `pid`. Returns how many
+    /// there were
+    #[instrument(skip(self))]
+    pub fn drop_window_index(&self, pid: u32) -> usize {
+        let dropped = search_index::drop_indexes(pid);
+        info!(pid, dropped, "Window search indexes dropped");
+        dropped
+    }
+
+    /// Stream the UI tree of a window breadth-first, one batch of children
+    /// at a time, while it is being built. The window is looked up as by
+    /// `get_window_tree`; dropping the stream stops the walk. Windows only.
+    #[instrument(skip(self, config))]
+    pub fn stream_window_tree(
+        &self,
+        pid: u32,
+        title: Option<&str>,
+        config: Option<crate::platforms::TreeBuildConfig>,
+    ) -> Result<WindowTreeStream, AutomationError> {
+        info!(pid, ?title, "Streaming window tree");
+        let window = self.engine.find_window(pid, title)?;
+        WindowTreeStream::start(self.engine.clone(), window, config.unwrap_or_default())
+    }
+
+    /// Get the UI trees of all visible top-level windows.
+    ///
+    /// Trees are built concurrently, one worker thread per CPU core, which is
+    /// much faster than calling `get_window_tree` for each window in turn.
+    /// Windows whose tree fails to build are skipped.
+    #[instrument(skip(self, config))]
+    pub fn get_all_window_trees(&self, config: Option<crate::platforms::TreeBuildConfig>) -> Result<Vec<(WindowInfo, UINode)>, AutomationError> {
+        let start = Instant::now();
+        info!("Getting all window trees");
+
+        let trees = self.engine.get_all_window_trees(config.unwrap_or_default())?;
+
+        let duration = start.elapsed();
+        info!(
+            duration_ms = duration.as_millis(),
+            window_count = trees.len(),
+            "All window trees retrieved"
+        );
+
+        Ok(trees)
+    }
+
+    /// What this process can automate: its integrity level, whether it runs
+    /// as administrator and whether it has UIAccess. Without elevation or
+    /// UIAccess, actions on applications running as administrator fail with
+    /// `AutomationError::ElevationRequired`; see [`elevation`]
+    pub fn capabilities(&self) -> AutomationCapabilities {
+        elevation::current()
+    }
+
+    /// Whether the OS lets this process automate other applications and
+    /// capture the screen, without prompting. Doesn't need a `Desktop`,
+    /// which can't be created without Accessibility on macOS; see
+    /// [`permissions`]
+    pub fn check_permissions() -> PermissionStatus {
+        let status = permissions::check();
+        debug!(?status, "Permissions checked");
+        status
+    }
+
+    /// Prompt for the missing permissions and open the System Settings pane
+    /// of the first one still missing. Returns the status after prompting;
+    /// granting in the settings takes effect later, so check again
+    pub fn request_permissions() -> PermissionStatus {
+        info!("Requesting permissions");
+        let status = permissions::request();
+        info!(missing = ?status.missing(), "Permissions requested");
+        status
+    }
+
+    /// Open the System Settings pane granting `permission`. macOS only
+    pub fn open_permission_settings(permission: Permission) -> Result<(), AutomationError> {
+        permissions::open_settings(permission)
+    }
+
+    /// Virtual desktops in Task View order, with the one currently shown
+    /// marked by `is_current`
+    #[instrument(skip(self))]
+    pub fn virtual_desktops(&self) -> Result<Vec<VirtualDesktop>, AutomationError> {
+        let start = Instant::now();
+        info!("Listing virtual desktops");
+
+        let desktops = self.engine.virtual_desktops()?;
+
+        let duration = start.elapsed();
+        info!(duration_ms = duration.as_millis(), count = desktops.len(), "Virtual desktops listed");
+
+        Ok(desktops)
+    }
+
+    /// Get the state of the desktop session this process runs in.
+    ///
+    /// Use this before unattended runs to detect locked workstations,
+    /// disconnected RDP sessions and service sessions where UI automation
+    /// cannot work.
+    #[instrument(skip(self))]
+    pub fn session_state(&self) -> Result<SessionState, AutomationError> {
+        let start = Instant::now();
+        info!("Getting session state");
+
+        let state = self.engine.session_state()?;
+
+        let duration = start.elapsed();
+        info!(
+            duration_ms = duration.as_millis(),
+            %state,
+            "Session state retrieved"
+        );
+
+        Ok(state)
+    }
+
+    /// Return `AutomationError::UIUnavailable` if the current session cannot
+    /// be automated (locked, disconnected or a service session).
+    #[instrument(skip(self))]
+    pub fn ensure_ui_available(&self) -> Result<(), AutomationError> {
+        let state = self.session_state()?;
+        if state.is_ui_available() {
+            Ok(())
+        } else {
+            warn!(%state, "UI is not available in the current session");
+            Err(AutomationError::UIUnavailable(format!(
+                "{} ({})",
+                state.describe(),
+                state
+            )))
+        }
+    }
+
+    /// Reconnect the current remote session to the physical console so it
+    /// stays interactive after the RDP client disconnects. Windows only,
+    /// requires administrator rights.
+    #[instrument(skip(self))]
+    pub fn attach_to_console(&self) -> Result<(), AutomationError> {
+        let start = Instant::now();
+        info!("Attaching session to console");
+
+        self.engine.attach_to_console()?;
+
+        let duration = start.elapsed();
+        info!(
+            duration_ms = duration.as_millis(),
+            "Session attached to console"
+        );
+
+        Ok(())
+    }
+
+    /// Read the visible screen of the console attached to process `pid`
+    /// (e.g. cmd.exe or powershell.exe). Windows only.
+    #[instrument(skip(self))]
+    pub fn read_console(&self, pid: u32) -> Result<ConsoleScreen, AutomationError> {
+        let start = Instant::now();
+        info!(pid, "Reading console screen");
+
+        let screen = self.engine.read_console(pid)?;
+
+        let duration = start.elapsed();
+        info!(
+            duration_ms = duration.as_millis(),
+            lines = screen.lines.len(),
+            "Console screen read"
+        );
+
+        Ok(screen)
+    }
+
+    /// Type `text` into the console attached to process `pid`. Newlines are
+    /// sent as Enter. Windows only.
+    #[instrument(skip(self, text))]
+    pub fn send_console_input(&self, pid: u32, text: &str) -> Result<(), AutomationError> {
+        let start = Instant::now();
+        info!(pid, text_len = text.len(), "Sending console input");
+        if self.hooks.dry_run.is_enabled() {
+            self.hooks.dry_run.simulate_command("send_console_input", format!("pid {}: {}", pid, text));
+            return Ok(());
+        }
+
+        self.engine.send_console_input(pid, text)?;
+        self.watchdog_state.record_activity();
+
+        let duration = start.elapsed();
+        info!(duration_ms = duration.as_millis(), "Console input sent");
+
+        Ok(())
+    }
+
+    /// Wait until application `pid` is idle: started up, its UI thread
+    /// answering messages, and no elements added or removed for
+    /// [`DEFAULT_IDLE_QUIET_PERIOD`]. Use instead of sleeping after launching
+    /// an app or opening a dialog. Windows only.
+    pub async fn wait_for_idle(&self, pid: u32, timeout: Duration) -> Result<(), AutomationError> {
+        self.wait_for_idle_with(pid, timeout, DEFAULT_IDLE_QUIET_PERIOD).await
+    }
+
+    /// [`Desktop::wait_for_idle`] with a custom quiet period.
+    #[instrument(skip(self))]
+    pub async fn wait_for_idle_with(
+        &self,
+        pid: u32,
+        timeout: Duration,
+        quiet_period: Duration,
+    ) -> Result<(), AutomationError> {
+        let start = Instant::now();
+        info!(pid, timeout_ms = timeout.as_millis(), "Waiting for application to be idle");
+
+        let engine = self.engine.clone();
+        tokio::task::spawn_blocking(move || engine.wait_until_responsive(pid, timeout))
+            .await
+            .map_err(|e| AutomationError::Internal(format!("Idle wait task failed: {}", e)))??;
+
+        // Structure changes of the app push the end of the quiet period back
+        let quiet = Arc::new(ui_events::QuietPeriod::new(pid, quiet_period, Instant::now()));
+        let changed = quiet.clone();
+        let _subscription = self.engine.subscribe_ui_events(
+            &[UIEventKind::StructureChanged],
+            Arc::new(move |event: UIEvent| {
+                let event_pid = event.element.and_then(|element| element.process_id().ok());
+                changed.record(event_pid, Instant::now());
+            }),
+        )?;
+        while let Some(remaining) = quiet.remaining(Instant::now()) {
+            if start.elapsed() >= timeout {
+                return Err(AutomationError::Timeout(format!(
+                    "UI of process {} kept changing for {:?}",
+                    pid, timeout
+                )));
+            }
+            let wait = remaining.min(timeout.saturating_sub(start.elapsed()));
+            tokio::time::sleep(wait.max(Duration::from_millis(10))).await;
+        }
+
+        info!(pid, duration_ms = start.elapsed().as_millis(), "Application is idle");
+        Ok(())
+    }
+
+    /// Call `callback` for every UI event of the given kinds until the returned
+    /// subscription is dropped. Callbacks run on a background watcher thread.
+    /// Windows only.
+    #[instrument(skip(self, callback))]
+    pub fn on_ui_event(
+        &self,
+        kinds: &[UIEventKind],
+        callback: impl Fn(UIEvent) + Send + Sync + 'static,
+    ) -> Result<UIEventSubscription, AutomationError> {
+        let start = Instant::now();
+        info!(?kinds, "Subscribing to UI events");
+
+        let subscription = self.engine.subscribe_ui_events(kinds, Arc::new(callback))?;
+
+        let duration = start.elapsed();
+        info!(duration_ms = duration.as_millis(), "Subscribed to UI events");
+
+        Ok(subscription)
+    }
+
+    /// Start keeping the last `capacity` focused elements, for
+    /// [`Desktop::focus_history`]. Calling this again starts a new history.
+    /// Windows only. See [`focus_history`]
+    #[instrument(skip(self))]
+    pub fn track_focus(&self, capacity: usize) -> Result<(), AutomationError> {
+        self.track_focus_with(capacity, None)
+    }
+
+    /// Like [`Desktop::track_focus`], calling `on_change` with each focus
+    /// change as it is recorded. The callback runs on the UI event watcher
+    /// thread and should return quickly
+    #[instrument(skip(self, on_change))]
+    pub fn track_focus_with(
+        &self,
+        capacity: usize,
+        on_change: Option<FocusCallback>,
+    ) -> Result<(), AutomationError> {
+        let tracker = focus_history::FocusTracker::start(self.engine.as_ref(), capacity, on_change)?;
+        // Dropping the previous tracker ends its subscription
+        let previous = self
+            .focus_tracker
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .replace(tracker);
+        drop(previous);
+        info!(capacity, "Tracking focus changes");
+        Ok(())
+    }
+
+    /// Stop tracking focus. The history is dropped
+    #[instrument(skip(self))]
+    pub fn stop_focus_tracking(&self) {
+        let previous = self.focus_tracker.lock().unwrap_or_else(|p| p.into_inner()).take();
+        if previous.is_some() {
+            info!("Stopped tracking focus changes");
+        }
+    }
+
+    /// Elements that received focus since [`Desktop::track_focus`], oldest
+    /// first, starting with the one focused when tracking started. Empty
+    /// when focus is not tracked
+    pub fn focus_history(&self) -> Vec<FocusRecord> {
+        self.focus_tracker
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .as_ref()
+            .map(|tracker| tracker.history().records())
+            .unwrap_or_default()
+    }
+
+    /// Call `callback` each time the system-wide `hotkey` (e.g. `"Ctrl+Shift+H"`)
+    /// is pressed, until the returned registration is dropped. Callbacks run
+    /// on a shared dispatcher thread and should return quickly. Fails if
+    /// another application already owns the combination. Windows only.
+    #[instrument(skip(self, callback))]
+    pub fn register_hotkey(
+        &self,
+        hotkey: &str,
+        callback: impl Fn() + Send + Sync + 'static,
+    ) -> Result<HotkeyRegistration, AutomationError> {
+        let start = Instant::now();
+        let parsed: Hotkey = hotkey.parse().map_err(AutomationError::InvalidArgument)?;
+        info!(hotkey = %parsed, "Registering hotkey");
+
+        let registration = self.engine.register_hotkey(parsed, Arc::new(callback))?;
+
+        let duration = start.elapsed();
+        info!(duration_ms = duration.as_millis(), "Hotkey registered");
+
+        Ok(registration)
+    }
+
+    /// The notifications on screen: Windows toasts or macOS Notification
+    /// Center banners. See [`notifications`].
+    #[instrument(skip(self))]
+    pub fn notifications(&self) -> Result<Vec<Notification>, AutomationError> {
+        notifications::current(self.engine.as_ref())
+    }
+
+    /// Call `callback` for each notification that appears, until the returned
+    /// subscription is dropped. Notifications already on screen are not
+    /// reported. Callbacks run on a background polling thread.
+    #[instrument(skip(self, callback))]
+    pub fn subscribe_notifications(
+        &self,
+        callback: impl Fn(Notification) + Send + Sync + 'static,
+    ) -> Result<UIEventSubscription, AutomationError> {
+        info!("Subscribing to notifications");
+        notifications::subscribe(self.engine.clone(), Arc::new(callback))
+    }
+
+    /// Poll until a notification whose app name, title or body contains
+    /// `text` (ignoring case) is on screen
+    #[instrument(skip(self))]
+    pub async fn wait_for_notification(
+        &self,
+        text: &str,
+        timeout: Duration,
+    ) -> Result<Notification, AutomationError> {
+        let start = Instant::now();
+        loop {
+            if let Some(notification) = self.notifications()?.into_iter().find(|n| n.contains(text)) {
+                info!(duration_ms = start.elapsed().as_millis(), "Notification found");
+                return Ok(notification);
+            }
+            if start.elapsed() >= timeout {
+                return Err(AutomationError::Timeout(format!(
+                    "No notification containing '{}' appeared within {:?}",
+                    text, timeout
+                )));
+            }
+            tokio::time::sleep(notifications::NOTIFICATION_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Block physical keyboard and mouse input until called again with
+    /// `false`, so a stray mouse move cannot steal focus during a run. Input
+    /// sent by this crate still gets through. The block is lifted after
+    /// [`input_guard::MAX_BLOCK`], and the user can lift it sooner with
+    /// Ctrl+Alt+Del or Ctrl+Alt+Esc. See [`input_guard`]. Windows only.
+    #[instrument(skip(self))]
+    pub fn block_user_input(&self, enabled: bool) -> Result<(), AutomationError> {
+        input_guard::set_blocked(enabled)?;
+        info!(enabled, "User input blocking changed");
+        Ok(())
+    }
+
+    /// Block physical input until the returned guard is dropped, for a
+    /// sequence of actions that must not be interrupted. Guards nest, and
+    /// input is released even if the sequence panics. Windows only.
+    #[instrument(skip(self))]
+    pub fn input_guard(&self) -> Result<InputGuard, AutomationError> {
+        InputGuard::acquire()
+    }
+
+    /// Block physical input while each element action runs: clicks, typing,
+    /// key presses, ... Actions still run if input cannot be blocked.
+    /// Applies to all elements in the process. Windows only.
+    #[instrument(skip(self))]
+    pub fn set_auto_input_guard(&self, enabled: bool) {
+        input_guard::set_auto(enabled);
+        info!(enabled, "Automatic input guard changed");
+    }
+
+    /// Pace all synthetic keyboard and mouse input with `profile`, see
+    /// [`InputProfile`]. Single actions can still use another profile
+    /// through [`InputProfile::scoped`]
+    pub fn set_input_profile(&self, profile: InputProfile) {
+        info!(?profile, "Input profile changed");
+        input_profile::set_default(profile);
+    }
+
+    /// Profile used for input sent from the current thread
+    pub fn input_profile(&self) -> InputProfile {
+        input_profile::current()
+    }
+
+    /// Apply the built-in profiles for Office, browsers and Java to locators
+    /// within those applications. Off by default. See [`AppProfile::builtin`]
+    pub fn use_builtin_app_profiles(&self, enabled: bool) {
+        info!(enabled, "Built-in application profiles toggled");
+        app_profile::set_builtin_enabled(enabled);
+    }
+
+    /// Apply `profile` to locators within its applications, before the
+    /// built-in profiles and replacing a registered one of the same name.
+    /// See [`AppProfile`]
+    pub fn register_app_profile(&self, profile: AppProfile) {
+        info!(name = %profile.name, processes = ?profile.processes, "Application profile registered");
+        app_profile::register(profile);
+    }
+
+    /// Remove a registered profile; false if there was none by that name
+    pub fn remove_app_profile(&self, name: &str) -> bool {
+        app_profile::unregister(name)
+    }
+
+    /// Registered profiles, the latest first, then the built-in ones when
+    /// they are on
+    pub fn app_profiles(&self) -> Vec<AppProfile> {
+        let mut profiles = app_profile::registered();
+        if app_profile::builtin_enabled() {
+            profiles.extend(AppProfile::builtin());
+        }
+        profiles
+    }
+
+    /// Add names for [`Selector::NameL10n`] keys, tried before the built-in
+    /// ones. Applies to all searches in the process
+    pub fn add_translations(&self, translations: Translations) {
+        info!(keys = ?translations.keys(), "Translations added");
+        l10n::add_translations(translations);
+    }
+
+    /// Limit [`Selector::NameL10n`] matching to `locale` (`de`, `pt-BR`,
+    /// ...) and English, or try every language with `None`. Like
+    /// [`Desktop::add_translations`], applies to all searches in the process
+    pub fn set_locale(&self, locale: Option<&str>) {
+        info!(?locale, "Locale changed");
+        l10n::set_locale(locale);
+    }
+
+    /// Locale set with [`Desktop::set_locale`]
+    pub fn locale(&self) -> Option<String> {
+        l10n::locale()
+    }
+
+    /// Poll the console of process `pid` until `text` appears on screen.
+    #[instrument(skip(self, text))]
+    pub async fn wait_for_console_text(
+        &self,
+        pid: u32,
+        text: &str,
+        timeout: Duration,
+    ) -> Result<ConsoleScreen, AutomationError> {
+        let start = Instant::now();
+        loop {
+            let screen = self.engine.read_console(pid)?;
+            if screen.contains(text) {
+                self.watchdog_state.record_activity();
+                info!(duration_ms = start.elapsed().as_millis(), "Console text found");
+                return Ok(screen);
+            }
+            if start.elapsed() >= timeout {
+                return Err(AutomationError::Timeout(format!(
+                    "Text '{}' did not appear in console of process {} within {:?}",
+                    text, pid, timeout
+                )));
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    /// Arm a watchdog that fires when no action completes successfully for `timeout`.
+    ///
+    /// On a stall a [`DiagnosticBundle`] (screenshot, foreground window and the
+    /// active application's tree) is captured. With a callback the bundle is
+    /// handed to it; without one the run is aborted: waits of this desktop's
+    /// locators fail with `AutomationError::StalledRun` until
+    /// [`Desktop::reset_stall`], and the bundle is available from
+    /// [`Desktop::stall_diagnostics`]. Calling this again replaces the
+    /// previous watchdog. Call it within a tokio runtime, which captures the
+    /// diagnostics.
+    #[instrument(skip(self, on_stall))]
+    pub fn idle_timeout(&self, timeout: Duration, on_stall: Option<StallCallback>) {
+        info!(timeout_ms = timeout.as_millis(), has_callback = on_stall.is_some(), "Arming idle watchdog");
+        let new_watchdog =
+            watchdog::Watchdog::start(self.engine.clone(), self.watchdog_state.clone(), timeout, on_stall);
+        // Dropping the previous watchdog stops its thread
+        let previous = match self.watchdog.lock() {
+            Ok(mut guard) => guard.replace(new_watchdog),
+            Err(poisoned) => poisoned.into_inner().replace(new_watchdog),
+        };
+        drop(previous);
+    }
+
+    /// Disarm the idle watchdog, if one is running.
+    #[instrument(skip(self))]
+    pub fn clear_idle_timeout(&self) {
+        let previous = match self.watchdog.lock() {
+            Ok(mut guard) => guard.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        };
+        if previous.is_some() {
+            info!("Idle watchdog disarmed");
+        }
+    }
+
+    /// Lift the abort of a stalled run, so locator waits work again
+    pub fn reset_stall(&self) {
+        if self.watchdog_state.is_stalled() {
+            info!("Stalled run reset");
+        }
+        self.watchdog_state.reset();
+    }
+
+    /// Diagnostics captured when the watchdog last aborted the run
+    pub fn stall_diagnostics(&self) -> Option<DiagnosticBundle> {
+        self.watchdog_state.last_stall_diagnostics()
+    }
+
+    /// Register middleware called around element actions, e.g. to keep an
+    /// audit log or capture screenshots on failure. Shared by clones of this
+    /// desktop and applied to the elements found through them; see
+    /// [`middleware`] for which elements that covers and the order hooks run in.
+    #[instrument(skip(self, middleware))]
+    pub fn add_middleware(&self, middleware: impl Middleware + 'static) -> MiddlewareId {
+        let id = self.hooks.add(Arc::new(middleware));
+        info!(?id, "Middleware registered");
+        id
+    }
+
+    /// Remove middleware added with [`Desktop::add_middleware`]. Returns
+    /// whether it was still registered with this desktop.
+    #[instrument(skip(self))]
+    pub fn remove_middleware(&self, id: MiddlewareId) -> bool {
+        self.hooks.remove(id)
+    }
+
+    /// Log element actions and commands instead of performing them, while
+    /// still resolving the elements they target. Enabling starts a new log,
+    /// read with [`Desktop::simulated_actions`]. Shared by clones of this
+    /// desktop and applied to the elements found through them, see
+    /// [`crate::dry_run`].
+    #[instrument(skip(self))]
+    pub fn dry_run(&self, enabled: bool) {
+        self.hooks.dry_run.set_enabled(enabled);
+        info!(enabled, "Dry run mode changed");
+    }
+
+    /// Whether actions are only simulated, see [`Desktop::dry_run`]
+    pub fn is_dry_run(&self) -> bool {
+        self.hooks.dry_run.is_enabled()
+    }
+
+    /// Actions skipped since the dry run was enabled, oldest first. Only the
+    /// last [`dry_run::MAX_SIMULATED_ACTIONS`] are kept
+    pub fn simulated_actions(&self) -> Vec<SimulatedAction> {
+        self.hooks.dry_run.simulated_actions()
+    }
+
     /// Get all window elements for a given application by name
     #[instrument(skip(self, app_name))]
     pub async fn windows_for_application(&self, app_name: &str) -> Result<Vec<UIElement>, AutomationError> {
@@ -591,6 +1864,11 @@ impl Clone for Desktop {
     fn clone(&self) -> Self {
         Self {
             engine: self.engine.clone(),
+            watchdog: self.watchdog.clone(),
+            watchdog_state: self.watchdog_state.clone(),
+            selector_cache: self.selector_cache.clone(),
+            focus_tracker: self.focus_tracker.clone(),
+            hooks: self.hooks.clone(),
         }
     }
 }
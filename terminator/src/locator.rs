@@ -1,22 +1,217 @@
 use tracing::{debug, instrument};
 
+use crate::app_profile::AppProfile;
 use crate::platforms::AccessibilityEngine;
 use crate::element::UIElement;
-use crate::errors::AutomationError;
+use crate::errors::{AutomationError, ErrorCode, ErrorContext, SelectorSuggestion};
+use crate::geometry::Rect;
+use crate::search_index;
 use crate::selector::Selector;
-use std::sync::Arc;
-use std::time::Duration;
+use crate::selector_cache::{AppKey, SelectorCache};
+use crate::selector_profile::{self, HopProfile, SelectorProfile};
+use crate::spatial;
+use crate::middleware::ActionHooks;
+use crate::watchdog::{self, WatchdogState};
+use crate::utils::similarity;
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
 // Default timeout if none is specified on the locator itself
 const DEFAULT_LOCATOR_TIMEOUT: Duration = Duration::from_secs(30);
 
+// Bounds for the neighborhood search used to suggest selectors on a miss
+const SUGGESTION_MAX_DEPTH: usize = 6;
+const SUGGESTION_MAX_NODES: usize = 500;
+const SUGGESTION_MIN_SCORE: f64 = 0.5;
+const SUGGESTION_LIMIT: usize = 5;
+
+/// Controls how a locator polls for an element while waiting.
+///
+/// The default reproduces the historical behavior: a fixed 100ms pause
+/// between attempts, no attempt limit and no jitter. The overall wait is
+/// still bounded by the locator timeout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Delay before the second attempt
+    pub initial_delay: Duration,
+    /// Upper bound for the delay between attempts
+    pub max_delay: Duration,
+    /// Factor applied to the delay after each failed attempt (1.0 = fixed delay)
+    pub multiplier: f64,
+    /// Maximum number of attempts, `None` for unlimited (until timeout)
+    pub max_attempts: Option<u32>,
+    /// Random spread applied to each delay, as a fraction (0.0 - 1.0)
+    pub jitter: f64,
+    /// Optional cap on how long a single attempt may search
+    pub attempt_timeout: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(100),
+            multiplier: 1.0,
+            max_attempts: None,
+            jitter: 0.0,
+            attempt_timeout: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Ceiling for the delay between attempts when a backoff leaves
+    /// `max_delay` unset, as the bindings allow
+    pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(5);
+
+    /// Fixed delay between attempts
+    pub fn fixed(delay: Duration) -> Self {
+        Self {
+            initial_delay: delay,
+            max_delay: delay,
+            ..Default::default()
+        }
+    }
+
+    /// Exponential backoff starting at `initial_delay`, doubling up to `max_delay`
+    pub fn exponential(initial_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            initial_delay,
+            max_delay,
+            multiplier: 2.0,
+            ..Default::default()
+        }
+    }
+
+    /// Limit the number of attempts
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// Randomize each delay by up to `jitter` (fraction of the delay)
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Cap how long each individual attempt may search
+    pub fn with_attempt_timeout(mut self, attempt_timeout: Duration) -> Self {
+        self.attempt_timeout = Some(attempt_timeout);
+        self
+    }
+
+    /// Delay to wait after the given (zero-based) failed attempt
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let cap = self.max_delay.max(self.initial_delay);
+        let base = self.initial_delay.as_secs_f64() * self.multiplier.max(1.0).powi(attempt as i32);
+        let capped = if base >= cap.as_secs_f64() {
+            cap
+        } else {
+            Duration::from_secs_f64(base)
+        };
+        if self.jitter > 0.0 {
+            // Spread uniformly within [capped * (1 - jitter), capped * (1 + jitter)]
+            let spread = (pseudo_random() * 2.0 - 1.0) * self.jitter;
+            capped.mul_f64((1.0 + spread).max(0.0))
+        } else {
+            capped
+        }
+    }
+}
+
+// Cheap, dependency-free randomness for retry jitter (not for anything security related)
+fn pseudo_random() -> f64 {
+    use std::cell::Cell;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    thread_local! {
+        static STATE: Cell<u64> = Cell::new(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(0x9E37_79B9_7F4A_7C15)
+                | 1,
+        );
+    }
+
+    STATE.with(|state| {
+        // xorshift64
+        let mut x = state.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        state.set(x);
+        (x >> 11) as f64 / (1u64 << 53) as f64
+    })
+}
+
+/// Candidate selectors for one logical target, see `Desktop::locator_any`
+#[derive(Clone)]
+struct Alternatives {
+    target: String,
+    candidates: Vec<String>,
+    cache: Option<Arc<SelectorCache>>,
+}
+
+/// Narrowing of a locator's matches, applied in order after the search
+#[derive(Clone)]
+enum Refinement {
+    Filter(Arc<dyn Fn(&UIElement) -> bool + Send + Sync>),
+    Visible,
+    Nth(usize),
+}
+
+impl Refinement {
+    fn apply(&self, elements: Vec<UIElement>) -> Vec<UIElement> {
+        match self {
+            Refinement::Filter(predicate) => elements.into_iter().filter(|el| predicate(el)).collect(),
+            Refinement::Visible => elements
+                .into_iter()
+                .filter(|el| el.is_visible().unwrap_or(false))
+                .collect(),
+            Refinement::Nth(index) => elements.into_iter().nth(*index).into_iter().collect(),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Refinement::Filter(_) => "filter".to_string(),
+            Refinement::Visible => "visible".to_string(),
+            Refinement::Nth(index) => format!("nth={}", index),
+        }
+    }
+}
+
+// The app profile of a locator's root, looked up on first use and shared
+// with the locator's clones, and the element its search root resolved to
+#[derive(Default)]
+struct ProfileSlot {
+    profile: OnceLock<Option<AppProfile>>,
+    search_root: Mutex<Option<UIElement>>,
+}
+
 /// A high-level API for finding and interacting with UI elements
 #[derive(Clone)]
 pub struct Locator {
     engine: Arc<dyn AccessibilityEngine>,
     selector: Selector,
-    timeout: Duration, // Default timeout for this locator instance
+    timeout: Option<Duration>, // Default timeout for this locator instance, if set
     root: Option<UIElement>,
+    // Quirks of the application the root belongs to
+    app_profile: Option<Arc<ProfileSlot>>,
+    // Idle clock of the desktop that created the locator
+    watchdog: Option<Arc<WatchdogState>>,
+    // Middleware of the desktop that created the locator, for its matches
+    hooks: Option<Arc<ActionHooks>>,
+    retry: RetryPolicy,
+    viewport: Option<UIElement>,
+    alternatives: Option<Alternatives>,
+    refinements: Vec<Refinement>,
+    // Locator whose match is searched in, for locators nested below a
+    // refined one: the engine cannot express "inside the 2nd match"
+    scope: Option<Box<Locator>>,
 }
 
 impl Locator {
@@ -25,31 +220,516 @@ impl Locator {
         Self {
             engine,
             selector,
-            timeout: DEFAULT_LOCATOR_TIMEOUT, // Use default
+            timeout: None, // Use the app profile's or the default
             root: None,
+            app_profile: None,
+            watchdog: None,
+            hooks: None,
+            retry: RetryPolicy::default(),
+            viewport: None,
+            alternatives: None,
+            refinements: Vec::new(),
+            scope: None,
+        }
+    }
+
+    /// Locator that tries `candidates` in order for `target`, preferring the
+    /// selector `cache` learned for the application on earlier runs
+    pub(crate) fn any(
+        engine: Arc<dyn AccessibilityEngine>,
+        target: &str,
+        candidates: Vec<String>,
+        cache: Option<Arc<SelectorCache>>,
+    ) -> Self {
+        let selector = candidates
+            .first()
+            .map(|s| Selector::from(s.as_str()))
+            .unwrap_or_else(|| Selector::Chain(Vec::new()));
+        Self {
+            alternatives: Some(Alternatives {
+                target: target.to_string(),
+                candidates,
+                cache,
+            }),
+            ..Self::new(engine, selector)
         }
     }
 
     /// Set a default timeout for waiting operations on this locator instance.
     /// This timeout is used if no specific timeout is passed to action/wait methods.
     pub fn set_default_timeout(mut self, timeout: Duration) -> Self {
-        self.timeout = timeout;
+        self.timeout = Some(timeout);
         self
     }
 
-    /// Set the root element for this locator
+    /// Report waits to the idle watchdog of the desktop that created the
+    /// locator, and fail them once it aborted the run
+    pub(crate) fn with_watchdog(mut self, state: Arc<WatchdogState>) -> Self {
+        self.watchdog = Some(state);
+        self
+    }
+
+    /// Run actions on the matches through the middleware of the desktop
+    /// that created the locator
+    pub(crate) fn with_hooks(mut self, hooks: Option<Arc<ActionHooks>>) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Set the polling policy used while waiting for the element
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Set the root element for this locator. The [`AppProfile`] of its
+    /// application, if any, applies to the locator
     pub fn within(mut self, element: UIElement) -> Self {
+        self.app_profile = Some(Arc::default());
         self.root = Some(element);
         self
     }
 
+    /// Ignore the quirks of the root's application, e.g. to reach the
+    /// toolbar of a browser whose profile searches in the page
+    pub fn without_app_profile(mut self) -> Self {
+        self.app_profile = None;
+        self
+    }
+
+    /// Profile applied to this locator, see [`Locator::within`]
+    pub fn app_profile(&self) -> Option<&AppProfile> {
+        let slot = self.app_profile.as_ref()?;
+        slot.profile
+            .get_or_init(|| self.root.as_ref().and_then(AppProfile::for_element))
+            .as_ref()
+    }
+
+    // Timeout of waits without one: the locator's, else the profile's
+    fn default_timeout(&self) -> Duration {
+        self.timeout
+            .or_else(|| self.app_profile().and_then(AppProfile::timeout))
+            .unwrap_or(DEFAULT_LOCATOR_TIMEOUT)
+    }
+
+    /// Search inside `container` and only match elements whose bounds lie
+    /// entirely in its currently visible region, skipping items scrolled out
+    /// of view or only partly in it.
+    ///
+    /// The visible region is the container's bounds clipped to its window.
+    pub fn within_viewport(mut self, container: UIElement) -> Self {
+        self.app_profile = Some(Arc::default());
+        self.root = Some(container.clone());
+        self.viewport = Some(container);
+        self
+    }
+
+    /// Only the `index`-th match (0-based, in tree order), after any filters
+    /// before it. Like [`Locator::filter`] this applies to the matches the
+    /// search collected, so it does not make the search itself cheaper,
+    /// except for `nth(0)` before any other refinement: that search stops
+    /// at the first match
+    pub fn nth(mut self, index: usize) -> Self {
+        self.refinements.push(Refinement::Nth(index));
+        self
+    }
+
+    /// Only matches for which `predicate` returns true. The predicate runs
+    /// on every match of the search, in Rust, after the search
+    pub fn filter(mut self, predicate: impl Fn(&UIElement) -> bool + Send + Sync + 'static) -> Self {
+        self.refinements.push(Refinement::Filter(Arc::new(predicate)));
+        self
+    }
+
+    /// Only the first match that is visible on screen
+    pub fn first_visible(mut self) -> Self {
+        self.refinements.push(Refinement::Visible);
+        self.refinements.push(Refinement::Nth(0));
+        self
+    }
+
+    /// Number of elements matching this locator. Waits up to `timeout` (or
+    /// the default timeout) for a first match, then counts the matches of a
+    /// single search; 0 if nothing appeared in time
+    pub async fn count(&self, timeout: Option<Duration>) -> Result<usize, AutomationError> {
+        match self.all(timeout, None).await {
+            Ok(elements) => Ok(elements.len()),
+            Err(e) if is_miss(&e) => Ok(0),
+            Err(e) => Err(e),
+        }
+    }
+
     /// Get all elements matching this locator, waiting up to the specified timeout.
     /// If no timeout is provided, uses the locator's default timeout.
     pub async fn all(&self, timeout: Option<Duration>, depth: Option<usize>) -> Result<Vec<UIElement>, AutomationError> {
-        let effective_timeout = timeout.unwrap_or(self.timeout);
-        // find_elements itself handles the timeout now
-        self.engine
-            .find_elements(&self.selector, self.root.as_ref(), Some(effective_timeout), depth)
+        let effective_timeout = timeout.unwrap_or_else(|| self.default_timeout());
+        let depth = depth.or_else(|| self.profile_depth());
+        let elements = if self.alternatives.is_some() {
+            // Wait for any candidate (learning which one matched, so it comes
+            // first below), then return everything that candidate matches
+            self.wait(Some(effective_timeout)).await?;
+            let mut found = Vec::new();
+            for (_, locator) in self.candidate_locators() {
+                match locator.find_matches(Duration::ZERO, depth) {
+                    Ok(elements) if !elements.is_empty() => {
+                        found = elements;
+                        break;
+                    }
+                    Ok(_) => {}
+                    Err(e) if is_miss(&e) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            found
+        } else {
+            // find_elements itself handles the timeout now
+            self.find_matches(effective_timeout, depth)?
+        };
+        Ok(elements.into_iter().map(|element| element.with_hooks(self.hooks.clone())).collect())
+    }
+
+    // All matches of one search, honoring the viewport restriction. Trailing
+    // spatial steps narrow the matches of the steps before them, so they are
+    // applied here rather than by the engine
+    fn find_matches(&self, timeout: Duration, depth: Option<usize>) -> Result<Vec<UIElement>, AutomationError> {
+        let root = self.search_root(timeout)?;
+        let first_only = matches!(self.refinements.first(), Some(Refinement::Nth(0)));
+        let mut elements = match spatial::split_trailing(&self.selector) {
+            Some((target, steps)) => {
+                let candidates = self.search(&target, root.as_ref(), timeout, depth)?;
+                spatial::filter(self.engine.as_ref(), candidates, &target, &steps, root.as_ref(), timeout)?
+            }
+            None if first_only && self.viewport.is_none() => {
+                self.search_first(&self.selector, root.as_ref(), timeout, depth)?
+            }
+            None => self.search(&self.selector, root.as_ref(), timeout, depth)?,
+        };
+        if let Some(region) = self.viewport_region()? {
+            elements.retain(|el| in_viewport(&region, el));
+        }
+        Ok(self
+            .refinements
+            .iter()
+            .fold(elements, |elements, refinement| refinement.apply(elements)))
+    }
+
+    // Matches from the index of `root` when it is an indexed window, from
+    // the live tree otherwise
+    fn search(
+        &self,
+        selector: &Selector,
+        root: Option<&UIElement>,
+        timeout: Duration,
+        depth: Option<usize>,
+    ) -> Result<Vec<UIElement>, AutomationError> {
+        if let Some(elements) = search_index::find(root, selector, depth) {
+            return Ok(elements);
+        }
+        self.engine.find_elements(selector, root, Some(timeout), depth)
+    }
+
+    // Like search, for the first match only. The engine's single-element
+    // search has no depth limit, so limited searches collect every match
+    fn search_first(
+        &self,
+        selector: &Selector,
+        root: Option<&UIElement>,
+        timeout: Duration,
+        depth: Option<usize>,
+    ) -> Result<Vec<UIElement>, AutomationError> {
+        if depth.is_some() {
+            return self.search(selector, root, timeout, depth);
+        }
+        if let Some(elements) = search_index::find(root, selector, None) {
+            return Ok(elements.into_iter().take(1).collect());
+        }
+        match self.engine.find_element(selector, root, Some(timeout)) {
+            Ok(element) => Ok(vec![element]),
+            Err(e) if is_miss(&e) => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    // The element searched in: the match of the scope locator, or the root
+    fn search_root(&self, timeout: Duration) -> Result<Option<UIElement>, AutomationError> {
+        match &self.scope {
+            Some(scope) => scope.find_once(timeout).map(Some),
+            None => Ok(self.profiled_root()),
+        }
+    }
+
+    // The root, or within a window the element the app profile searches
+    // in, e.g. the page of a browser. The window when there is none. Once
+    // found, that element is reused until it goes stale
+    fn profiled_root(&self) -> Option<UIElement> {
+        let root = self.root.as_ref()?;
+        let search_root = self.app_profile().and_then(|profile| profile.search_root.as_deref());
+        let (Some(slot), Some(search_root)) = (&self.app_profile, search_root.filter(|_| is_window(root))) else {
+            return Some(root.clone());
+        };
+        let mut cached = slot.search_root.lock().unwrap_or_else(|p| p.into_inner());
+        if let Some(element) = cached.as_ref().filter(|element| !element.is_stale()) {
+            return Some(element.clone());
+        }
+        match self.engine.find_element(&Selector::from(search_root), Some(root), Some(Duration::ZERO)) {
+            Ok(element) => {
+                *cached = Some(element.clone());
+                Some(element)
+            }
+            Err(e) => {
+                debug!("Searching the whole window, {} not found: {}", search_root, e);
+                *cached = None;
+                Some(root.clone())
+            }
+        }
+    }
+
+    // One search attempt, honoring the viewport restriction
+    fn find_once(&self, timeout: Duration) -> Result<UIElement, AutomationError> {
+        if self.alternatives.is_some() {
+            return self.find_any_once();
+        }
+        if self.viewport.is_none()
+            && self.refinements.is_empty()
+            && spatial::split_trailing(&self.selector).is_none()
+        {
+            let root = self.search_root(timeout)?;
+            if let Some(element) = search_index::find(root.as_ref(), &self.selector, self.profile_depth())
+                .and_then(|elements| elements.into_iter().next())
+            {
+                return Ok(element);
+            }
+            return self.engine.find_element(&self.selector, root.as_ref(), Some(timeout));
+        }
+        self.find_matches(timeout, self.profile_depth())?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                AutomationError::ElementNotFound(format!(
+                    "No element matching {}{}",
+                    self.selector_string(),
+                    if self.viewport.is_some() { " inside the visible viewport" } else { "" }
+                ))
+            })
+    }
+
+    // How deep the app profile searches below the root, if it limits it
+    fn profile_depth(&self) -> Option<usize> {
+        self.app_profile().and_then(|profile| profile.search_depth)
+    }
+
+    // One pass over the candidates. Each is only checked against the current
+    // tree so a missing early candidate does not use up the whole timeout
+    fn find_any_once(&self) -> Result<UIElement, AutomationError> {
+        for (selector, locator) in self.candidate_locators() {
+            match locator.find_once(Duration::ZERO) {
+                Ok(element) => {
+                    self.learn(&selector, &element);
+                    return Ok(element);
+                }
+                Err(e) if is_miss(&e) => {
+                    debug!(selector, "Candidate selector did not match");
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(AutomationError::ElementNotFound(format!(
+            "No candidate selector matched '{}'",
+            self.alternatives.as_ref().map(|a| a.target.as_str()).unwrap_or_default()
+        )))
+    }
+
+    // Single-selector locators for each candidate, the learned one first
+    fn candidate_locators(&self) -> Vec<(String, Locator)> {
+        let Some(alternatives) = &self.alternatives else {
+            return Vec::new();
+        };
+        let mut ordered: Vec<String> = self.learned_candidate().into_iter().collect();
+        for candidate in &alternatives.candidates {
+            if !ordered.contains(candidate) {
+                ordered.push(candidate.clone());
+            }
+        }
+        ordered
+            .into_iter()
+            .map(|selector| {
+                let locator = Locator {
+                    selector: Selector::from(selector.as_str()),
+                    alternatives: None,
+                    ..self.clone()
+                };
+                (selector, locator)
+            })
+            .collect()
+    }
+
+    // Candidate the selector cache learned for the application searched in
+    fn learned_candidate(&self) -> Option<String> {
+        let alternatives = self.alternatives.as_ref()?;
+        let cache = alternatives.cache.as_ref()?;
+        cache.learned(&self.search_app()?, &alternatives.target)
+    }
+
+    // Application searched in: the root's, or the focused one's without a root
+    fn search_app(&self) -> Option<AppKey> {
+        let element = match &self.root {
+            Some(root) => root.clone(),
+            None => self.engine.get_focused_element().ok()?,
+        };
+        AppKey::for_element(self.engine.as_ref(), &element)
+    }
+
+    fn learn(&self, selector: &str, element: &UIElement) {
+        let Some(alternatives) = &self.alternatives else {
+            return;
+        };
+        let Some(cache) = &alternatives.cache else {
+            return;
+        };
+        if let Some(app) = AppKey::for_element(self.engine.as_ref(), element) {
+            cache.record(&app, &alternatives.target, selector);
+        }
+    }
+
+    // Visible part of the viewport container, if one is set
+    fn viewport_region(&self) -> Result<Option<Rect>, AutomationError> {
+        let Some(container) = &self.viewport else {
+            return Ok(None);
+        };
+        let mut region = container.rect()?;
+        // The container itself can be partly hidden by its window
+        if let Ok(Some(window)) = container.window() {
+            if let Ok(window_rect) = window.rect() {
+                region = region.intersection(&window_rect).unwrap_or_default();
+            }
+        }
+        Ok(Some(region))
+    }
+
+    /// Run one search, without waiting, and report how long each hop of the
+    /// selector chain took, how many elements it matched and how it matched
+    /// them, to find out why a locator is slow or ambiguous. Every hop is
+    /// searched for all its matches, so the search can take longer than a
+    /// plain [`Locator::wait`] attempt. See [`selector_profile`]
+    pub fn explain(&self) -> SelectorProfile {
+        let (profile, _) = self.explain_matches();
+        debug!(
+            selector = %profile.selector,
+            found = profile.found,
+            duration_ms = profile.duration_ms,
+            "Explained locator"
+        );
+        profile
+    }
+
+    fn explain_matches(&self) -> (SelectorProfile, Vec<UIElement>) {
+        let start = Instant::now();
+        let mut profile = SelectorProfile {
+            selector: self.selector_string(),
+            ..Default::default()
+        };
+        let matches = self.profile_search(&mut profile);
+        profile.found = !matches.is_empty();
+        profile.element = matches.first().map(UIElement::to_serializable);
+        profile.duration_ms = start.elapsed().as_millis() as u64;
+        (profile, matches)
+    }
+
+    // The stages of `find_matches`, one at a time. Hops before the last
+    // continue with their first match, as the engines' chain search does
+    fn profile_search(&self, profile: &mut SelectorProfile) -> Vec<UIElement> {
+        if self.alternatives.is_some() {
+            profile.learned_candidate = self.learned_candidate();
+            for (_, locator) in self.candidate_locators() {
+                let (candidate, matches) = locator.explain_matches();
+                profile.candidates.push(candidate);
+                if !matches.is_empty() {
+                    return matches;
+                }
+            }
+            return Vec::new();
+        }
+
+        let root = match &self.scope {
+            Some(scope) => {
+                let (explained, scope_matches) = scope.explain_matches();
+                profile.scope = Some(Box::new(explained));
+                match scope_matches.into_iter().next() {
+                    Some(element) => Some(element),
+                    None => return Vec::new(),
+                }
+            }
+            None => self.profiled_root(),
+        };
+
+        let (target, spatial_steps) = spatial::split_trailing(&self.selector)
+            .unwrap_or_else(|| (self.selector.clone(), Vec::new()));
+        let hops = match &target {
+            Selector::Chain(hops) => hops.clone(),
+            other => vec![other.clone()],
+        };
+        let mut searched_in = root.clone();
+        let mut matches = Vec::new();
+        for (index, hop) in hops.iter().enumerate() {
+            let start = Instant::now();
+            let found = self
+                .engine
+                .find_elements(hop, searched_in.as_ref(), Some(Duration::ZERO), None);
+            let mut stage = HopProfile {
+                index,
+                selector: format!("{:?}", hop),
+                strategy: selector_profile::strategy(hop).to_string(),
+                searched_in: searched_in.as_ref().map(describe_root),
+                candidates: None,
+                matches: 0,
+                duration_ms: start.elapsed().as_millis() as u64,
+                error: None,
+            };
+            match found {
+                Ok(found) => {
+                    stage.matches = found.len();
+                    matches = found;
+                }
+                Err(e) if is_miss(&e) => matches.clear(),
+                Err(e) => {
+                    stage.error = Some(e.to_string());
+                    matches.clear();
+                }
+            }
+            profile.hops.push(stage);
+            if matches.is_empty() {
+                return matches;
+            }
+            if index + 1 < hops.len() {
+                searched_in = matches.first().cloned();
+            }
+        }
+
+        if !spatial_steps.is_empty() {
+            matches = profile_stage(profile, format!("{:?}", spatial_steps), "spatial", matches, |candidates| {
+                spatial::filter(self.engine.as_ref(), candidates, &target, &spatial_steps, root.as_ref(), Duration::ZERO)
+            });
+        }
+        if !matches.is_empty() && self.viewport.is_some() {
+            matches = profile_stage(profile, "visible viewport".to_string(), "viewport", matches, |candidates| {
+                let region = self.viewport_region()?.unwrap_or_default();
+                Ok(candidates.into_iter().filter(|el| in_viewport(&region, el)).collect())
+            });
+        }
+        for refinement in &self.refinements {
+            if matches.is_empty() {
+                break;
+            }
+            let strategy = match refinement {
+                Refinement::Filter(_) => "filter",
+                Refinement::Visible => "visible",
+                Refinement::Nth(_) => "nth",
+            };
+            matches = profile_stage(profile, refinement.describe(), strategy, matches, |candidates| {
+                Ok(refinement.apply(candidates))
+            });
+        }
+        matches
     }
 
     pub async fn first(&self, timeout: Option<Duration>) -> Result<UIElement, AutomationError> {
@@ -59,38 +739,118 @@ impl Locator {
 
     /// Wait for an element matching the locator to appear, up to the specified timeout.
     /// If no timeout is provided, uses the locator's default timeout.
+    /// Errors carry the selector, timeout and search root as context. A miss
+    /// fails with [`AutomationError::Timeout`], with the elements that
+    /// nearly matched attached as [`AutomationError::suggestions`].
     #[instrument(level = "debug", skip(self, timeout))]
     pub async fn wait(&self, timeout: Option<Duration>) -> Result<UIElement, AutomationError> {
+        let effective_timeout = timeout.unwrap_or_else(|| self.default_timeout());
+        self.wait_for_match(effective_timeout, true).await.map_err(|e| {
+            e.with_context(ErrorContext {
+                selector: Some(self.selector_string()),
+                root: self.root.as_ref().map(describe_root),
+                timeout_ms: Some(effective_timeout.as_millis() as u64),
+                ..Default::default()
+            })
+        })
+    }
+
+    /// Like [`Locator::wait`] for an element that may well be missing, e.g.
+    /// to check that it is gone: `None` once the timeout passes without a
+    /// match. Skips looking for similar elements, which only help explain
+    /// an unexpected miss
+    pub async fn try_wait(&self, timeout: Option<Duration>) -> Result<Option<UIElement>, AutomationError> {
+        let effective_timeout = timeout.unwrap_or_else(|| self.default_timeout());
+        match self.wait_for_match(effective_timeout, false).await {
+            Ok(element) => Ok(Some(element)),
+            Err(e) if is_miss(&e) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // The selector as reported in errors
+    fn selector_string(&self) -> String {
+        let mut parts: Vec<String> = self.scope.iter().map(|scope| scope.selector_string()).collect();
+        parts.push(match &self.alternatives {
+            Some(alternatives) => alternatives.target.clone(),
+            None => format!("{:?}", self.selector),
+        });
+        parts.extend(self.refinements.iter().map(Refinement::describe));
+        parts.join(" >> ")
+    }
+
+    // Times out with suggestions attached, unless `suggest` is false
+    async fn wait_for_match(&self, effective_timeout: Duration, suggest: bool) -> Result<UIElement, AutomationError> {
         debug!("Waiting for element matching selector: {:?}", self.selector);
-        let effective_timeout = timeout.unwrap_or(self.timeout);
         let start = std::time::Instant::now();
+        let mut attempt: u32 = 0;
 
         loop {
+            if let Some(state) = &self.watchdog {
+                state.check_stalled()?;
+            }
             // Calculate remaining time, preventing overflow if already timed out.
             let remaining_time = if start.elapsed() >= effective_timeout {
                 Duration::ZERO
             } else {
                 effective_timeout - start.elapsed()
             };
-            debug!("New wait loop iteration, remaining_time: {:?}", remaining_time);
+            let attempt_timeout = match self.retry.attempt_timeout {
+                Some(cap) => remaining_time.min(cap),
+                None => remaining_time,
+            };
+            debug!("New wait loop iteration {}, remaining_time: {:?}", attempt, remaining_time);
 
-            // Directly use find_element with the calculated (or zero) remaining timeout
-            match self.engine.find_element(
-                &self.selector,
-                self.root.as_ref(),
-                Some(remaining_time), // Pass the safely calculated remaining time
-            ) {
-                Ok(element) => return Ok(element),
-                Err(AutomationError::ElementNotFound(_)) => {
+            // Search with the calculated (or zero) remaining timeout
+            match self.find_once(attempt_timeout) {
+                Ok(element) => {
+                    match &self.watchdog {
+                        Some(state) => state.record_activity(),
+                        None => watchdog::record_activity(),
+                    }
+                    return Ok(element.with_hooks(self.hooks.clone()));
+                }
+                Err(e) if e.is_element_not_found() => {
+                    attempt += 1;
+                    let attempts_exhausted = self
+                        .retry
+                        .max_attempts
+                        .is_some_and(|max| attempt >= max);
                     // Continue looping if not found yet
-                    if start.elapsed() >= effective_timeout {
-                         // Use the original error message format if possible, or create a new one
-                         return Err(AutomationError::Timeout(format!(
-                            "Timed out after {:?} waiting for element {:?}",
-                            effective_timeout, self.selector
-                        )));
+                    if start.elapsed() >= effective_timeout || attempts_exhausted {
+                        // An empty tree on a locked or disconnected session is not an element failure
+                        if let Ok(state) = self.engine.session_state() {
+                            if !state.is_ui_available() {
+                                return Err(AutomationError::UIUnavailable(format!(
+                                    "{} while waiting for element {:?}",
+                                    state.describe(),
+                                    self.selector
+                                )));
+                            }
+                        }
+                        let error = AutomationError::Timeout(format!(
+                            "Timed out after {:?} ({} attempts) waiting for element {:?}",
+                            start.elapsed().min(effective_timeout), attempt, self.selector
+                        ));
+                        if !suggest {
+                            return Err(error);
+                        }
+                        // Walks part of the tree, so off the async workers
+                        let locator = self.clone();
+                        let suggestions = tokio::task::spawn_blocking(move || locator.suggest(SUGGESTION_LIMIT))
+                            .await
+                            .unwrap_or_default();
+                        if suggestions.is_empty() {
+                            return Err(error);
+                        }
+                        return Err(error.with_suggestions(suggestions));
                     }
-                    tokio::time::sleep(Duration::from_millis(100)).await; // Small delay before retry
+                    // Back off according to the retry policy, without overshooting the timeout
+                    let delay = self
+                        .retry
+                        .delay_for_attempt(attempt - 1)
+                        .min(effective_timeout.saturating_sub(start.elapsed()));
+                    tokio::time::sleep(delay).await;
                 }
                  // Propagate other errors immediately
                  Err(e) => return Err(e),
@@ -100,9 +860,83 @@ impl Locator {
         }
     }
 
+    /// Find elements near the search root that closely resemble this locator's
+    /// selector. Used to enrich "not found" errors so callers can auto-correct.
+    ///
+    /// The search is bounded in depth and node count, so it stays cheap even
+    /// on large trees. Results are sorted by descending score.
+    pub fn suggest(&self, limit: usize) -> Vec<SelectorSuggestion> {
+        let (prefix, leaf) = match &self.selector {
+            Selector::Chain(chain) if !chain.is_empty() => {
+                (chain[..chain.len() - 1].to_vec(), chain[chain.len() - 1].clone())
+            }
+            other => (Vec::new(), other.clone()),
+        };
+        let leaf = leaf.localized();
+
+        // Search under the deepest part of the chain that still resolves
+        let mut search_root = self.root.clone();
+        if !prefix.is_empty() {
+            let prefix_selector = Selector::Chain(prefix);
+            if let Ok(el) = self.engine.find_element(
+                &prefix_selector,
+                self.root.as_ref(),
+                Some(Duration::ZERO),
+            ) {
+                search_root = Some(el);
+            }
+        }
+        let search_root = search_root.unwrap_or_else(|| self.engine.get_root_element());
+
+        let mut seen = HashSet::new();
+        let mut suggestions = Vec::new();
+        let mut queue = VecDeque::from([(search_root, 0usize)]);
+        let mut visited = 0usize;
+
+        while let Some((element, depth)) = queue.pop_front() {
+            visited += 1;
+            if visited > SUGGESTION_MAX_NODES {
+                break;
+            }
+
+            if let Some(suggestion) = score_candidate(&leaf, &element) {
+                if suggestion.score >= SUGGESTION_MIN_SCORE && seen.insert(suggestion.selector.clone()) {
+                    suggestions.push(suggestion);
+                }
+            }
+
+            if depth < SUGGESTION_MAX_DEPTH {
+                if let Ok(children) = element.children() {
+                    queue.extend(children.into_iter().map(|c| (c, depth + 1)));
+                }
+            }
+        }
+
+        suggestions.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        suggestions.truncate(limit);
+        debug!(
+            visited,
+            found = suggestions.len(),
+            "Computed selector suggestions for {:?}",
+            self.selector
+        );
+        suggestions
+    }
+
     /// Get a nested locator
     pub fn locator(&self, selector: impl Into<Selector>) -> Locator {
         let next_selector = selector.into();
+        if !self.refinements.is_empty() {
+            // Search inside whatever this locator resolves to
+            return Locator {
+                selector: next_selector,
+                viewport: None,
+                alternatives: None,
+                refinements: Vec::new(),
+                scope: Some(Box::new(self.clone())),
+                ..self.clone()
+            };
+        }
         let new_chain = match self.selector.clone() {
              // If the current selector is already a chain, append to it
              Selector::Chain(mut existing_chain) => {
@@ -120,7 +954,127 @@ impl Locator {
             selector: Selector::Chain(new_chain), // Create the chain variant
             timeout: self.timeout, // Inherit timeout
             root: self.root.clone(), // Inherit root
+            app_profile: self.app_profile.clone(), // Inherit the root's quirks
+            watchdog: self.watchdog.clone(), // Inherit the desktop's idle clock
+            hooks: self.hooks.clone(), // Inherit the desktop's middleware
+            retry: self.retry.clone(), // Inherit retry policy
+            viewport: self.viewport.clone(), // Inherit viewport restriction
+            alternatives: None, // Candidates describe the target itself, not what is inside it
+            refinements: Vec::new(),
+            scope: self.scope.clone(), // Inherit the element searched in
         }
     }
 
-}
\ No newline at end of file
+}
+
+// A search that found nothing, as opposed to one that failed, however the
+// error was wrapped
+fn is_miss(e: &AutomationError) -> bool {
+    e.is_element_not_found() || e.code() == ErrorCode::Timeout
+}
+
+// Run a stage that narrows `candidates` and record it in the profile
+fn profile_stage(
+    profile: &mut SelectorProfile,
+    selector: String,
+    strategy: &str,
+    candidates: Vec<UIElement>,
+    narrow: impl FnOnce(Vec<UIElement>) -> Result<Vec<UIElement>, AutomationError>,
+) -> Vec<UIElement> {
+    let start = Instant::now();
+    let count = candidates.len();
+    let (matches, error) = match narrow(candidates) {
+        Ok(matches) => (matches, None),
+        Err(e) => (Vec::new(), Some(e.to_string())),
+    };
+    profile.hops.push(HopProfile {
+        index: profile.hops.len(),
+        selector,
+        strategy: strategy.to_string(),
+        searched_in: None,
+        candidates: Some(count),
+        matches: matches.len(),
+        duration_ms: start.elapsed().as_millis() as u64,
+        error,
+    });
+    matches
+}
+
+// Role and name of a search root, for error context
+fn describe_root(root: &UIElement) -> String {
+    match root.name().filter(|name| !name.is_empty()) {
+        Some(name) => format!("{} '{}'", root.role(), name),
+        None => root.role(),
+    }
+}
+
+// Whether the element is a top-level window or application, whatever the
+// platform calls it
+fn is_window(element: &UIElement) -> bool {
+    let role = element.role();
+    matches!(
+        role.trim_start_matches("AX").to_lowercase().as_str(),
+        "window" | "application" | "frame"
+    )
+}
+
+// Whether the element's bounds lie inside the visible region
+fn in_viewport(region: &Rect, element: &UIElement) -> bool {
+    match element.rect() {
+        Ok(rect) if !rect.is_empty() => region.contains_rect(&rect),
+        _ => false,
+    }
+}
+
+// Score how closely `element` resembles what `selector` asked for
+fn score_candidate(selector: &Selector, element: &UIElement) -> Option<SelectorSuggestion> {
+    let attrs = element.attributes();
+    let name = attrs.name.clone().filter(|n| !n.is_empty());
+    let automation_id = attrs
+        .properties
+        .get("AutomationId")
+        .and_then(|v| v.as_ref())
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+
+    let (score, selector_string) = match selector {
+        Selector::Name(target) | Selector::Text(target) => {
+            let name = name.as_ref()?;
+            (similarity(name, target), format!("name:{}", name))
+        }
+        Selector::LocalizedName(targets) => {
+            let name = name.as_ref()?;
+            let score = targets.iter().map(|target| similarity(name, target)).fold(0.0, f64::max);
+            (score, format!("name:{}", name))
+        }
+        Selector::NativeId(target) => {
+            let aid = automation_id.as_ref()?;
+            (similarity(aid, target), format!("nativeid:{}", aid))
+        }
+        Selector::Role { role, name: target_name } => {
+            let role_score = if attrs.role.eq_ignore_ascii_case(role) {
+                1.0
+            } else {
+                similarity(&attrs.role, role)
+            };
+            match (target_name, &name) {
+                (Some(target), Some(name)) => (
+                    role_score * 0.4 + similarity(name, target) * 0.6,
+                    format!("name:{}", name),
+                ),
+                (Some(_), None) => return None,
+                (None, _) => (role_score, format!("role:{}", attrs.role)),
+            }
+        }
+        _ => return None,
+    };
+
+    Some(SelectorSuggestion {
+        role: attrs.role,
+        name,
+        automation_id,
+        selector: selector_string,
+        score,
+    })
+}
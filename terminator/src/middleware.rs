@@ -0,0 +1,273 @@
+//! Hooks around element actions
+//!
+//! Middleware registered with [`Desktop::add_middleware`](crate::Desktop::add_middleware)
+//! sees every action performed on a [`UIElement`]: clicks, typing, key
+//! presses, scrolling, ... It is called before the action, which it may veto
+//! by returning an error, and afterwards with the outcome and timing. Typical
+//! uses are audit logs and screenshots on failure:
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use terminator::{ActionContext, AutomationError, Desktop, Middleware};
+//!
+//! struct ScreenshotOnFailure;
+//!
+//! impl Middleware for ScreenshotOnFailure {
+//!     fn on_error(&self, ctx: &ActionContext, error: &AutomationError, _duration: Duration) {
+//!         if let Ok(shot) = ctx.element.capture() {
+//!             eprintln!("{} failed: {} ({}x{} screenshot)", ctx.action, error, shot.width, shot.height);
+//!         }
+//!     }
+//! }
+//!
+//! # fn run(desktop: &Desktop) {
+//! desktop.add_middleware(ScreenshotOnFailure);
+//! # }
+//! ```
+//!
+//! Middleware belongs to the `Desktop` it was added to and its clones. It
+//! sees the actions on elements found through that `Desktop`: by its
+//! locators and methods, and by navigating from such elements (children,
+//! parent, siblings, ...). An element obtained some other way cannot tell
+//! which `Desktop` it belongs to, so its actions go through the middleware
+//! of every `Desktop` alive in the process.
+//!
+//! `before_action` hooks run in registration order and the others in
+//! reverse, so the first middleware registered wraps all later ones. Actions
+//! performed from inside a hook skip middleware, so hooks can act on
+//! elements without recursing.
+
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::dry_run::{self, DryRun, Simulated};
+use crate::watchdog::record_activity;
+use crate::{AutomationError, UIElement, input_guard};
+
+/// An action performed on an element
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionKind {
+    Click,
+    ClickAtAnchor,
+    DoubleClick,
+    RightClick,
+    Hover,
+    Focus,
+    TypeText,
+    PressKey,
+    SetValue,
+    PerformAction,
+    Scroll,
+    ActivateWindow,
+    MouseDrag,
+    MouseDown,
+    MouseMove,
+    MouseUp,
+    Close,
+    ArrangeWindow,
+}
+
+impl ActionKind {
+    /// Snake case name, e.g. `type_text`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActionKind::Click => "click",
+            ActionKind::ClickAtAnchor => "click_at_anchor",
+            ActionKind::DoubleClick => "double_click",
+            ActionKind::RightClick => "right_click",
+            ActionKind::Hover => "hover",
+            ActionKind::Focus => "focus",
+            ActionKind::TypeText => "type_text",
+            ActionKind::PressKey => "press_key",
+            ActionKind::SetValue => "set_value",
+            ActionKind::PerformAction => "perform_action",
+            ActionKind::Scroll => "scroll",
+            ActionKind::ActivateWindow => "activate_window",
+            ActionKind::MouseDrag => "mouse_drag",
+            ActionKind::MouseDown => "mouse_down",
+            ActionKind::MouseMove => "mouse_move",
+            ActionKind::MouseUp => "mouse_up",
+            ActionKind::Close => "close",
+            ActionKind::ArrangeWindow => "arrange_window",
+        }
+    }
+}
+
+impl fmt::Display for ActionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What middleware knows about an action
+#[derive(Debug, Clone)]
+pub struct ActionContext {
+    pub action: ActionKind,
+    /// The element acted on
+    pub element: UIElement,
+    /// Action arguments: the typed text or value, the key, the named action,
+    /// the scroll direction and amount, ... Typed text is passed as is, so
+    /// audit logs should redact it where passwords are entered
+    pub details: Option<String>,
+    /// When the action was requested
+    pub started_at: SystemTime,
+}
+
+/// Callbacks around element actions. All methods default to doing nothing
+pub trait Middleware: Send + Sync {
+    /// Called before the action. Returning an error cancels it, and the error
+    /// is what the caller gets and what `on_error` hooks see
+    fn before_action(&self, _ctx: &ActionContext) -> Result<(), AutomationError> {
+        Ok(())
+    }
+
+    /// Called after the action succeeded
+    fn after_action(&self, _ctx: &ActionContext, _duration: Duration) {}
+
+    /// Called after the action failed or was cancelled
+    fn on_error(&self, _ctx: &ActionContext, _error: &AutomationError, _duration: Duration) {}
+}
+
+/// Identifies a registered middleware, for [`Desktop::remove_middleware`](crate::Desktop::remove_middleware)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MiddlewareId(u64);
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+/// Hooks of the live desktops, for elements that belong to no desktop
+static LIVE: Mutex<Vec<Weak<ActionHooks>>> = Mutex::new(Vec::new());
+
+thread_local! {
+    static IN_HOOK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// What runs around the element actions of one `Desktop`, shared with its
+/// clones, its locators and the elements found through them
+#[derive(Default)]
+pub(crate) struct ActionHooks {
+    middleware: RwLock<Vec<(MiddlewareId, Arc<dyn Middleware>)>>,
+    pub(crate) dry_run: DryRun,
+}
+
+impl fmt::Debug for ActionHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ActionHooks").finish_non_exhaustive()
+    }
+}
+
+impl ActionHooks {
+    /// Hooks that also see the actions of elements belonging to no desktop
+    pub(crate) fn new() -> Arc<Self> {
+        let hooks = Arc::new(Self::default());
+        let mut live = LIVE.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        live.retain(|hooks| hooks.strong_count() > 0);
+        live.push(Arc::downgrade(&hooks));
+        hooks
+    }
+
+    /// Register `middleware`, after those already registered
+    pub(crate) fn add(&self, middleware: Arc<dyn Middleware>) -> MiddlewareId {
+        let id = MiddlewareId(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+        self.middleware
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push((id, middleware));
+        id
+    }
+
+    /// Remove a middleware. Returns whether it was registered here
+    pub(crate) fn remove(&self, id: MiddlewareId) -> bool {
+        let mut middleware = self.middleware.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let before = middleware.len();
+        middleware.retain(|(registered, _)| *registered != id);
+        middleware.len() != before
+    }
+
+    fn middleware(&self) -> Vec<Arc<dyn Middleware>> {
+        self.middleware
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .map(|(_, middleware)| middleware.clone())
+            .collect()
+    }
+}
+
+/// The hooks for actions on `element`: its desktop's, or those of every
+/// live desktop when it belongs to none
+pub(crate) fn hooks_for(element: &UIElement) -> Vec<Arc<ActionHooks>> {
+    match element.hooks() {
+        Some(hooks) => vec![hooks.clone()],
+        None => LIVE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect(),
+    }
+}
+
+fn chain(hooks: &[Arc<ActionHooks>]) -> Vec<Arc<dyn Middleware>> {
+    if IN_HOOK.get() {
+        return Vec::new();
+    }
+    hooks.iter().flat_map(|hooks| hooks.middleware()).collect()
+}
+
+fn in_hook<T>(hook: impl FnOnce() -> T) -> T {
+    IN_HOOK.set(true);
+    let result = hook();
+    IN_HOOK.set(false);
+    result
+}
+
+/// Perform an action on `element` through its middleware, and
+/// record it as activity for the watchdog when it succeeds. During a dry
+/// run the action is only recorded
+pub(crate) fn run<T: Simulated>(
+    action: ActionKind,
+    element: &UIElement,
+    details: Option<String>,
+    perform: impl FnOnce() -> Result<T, AutomationError>,
+) -> Result<T, AutomationError> {
+    let hooks = hooks_for(element);
+    let dry_runs: Vec<&DryRun> = hooks.iter().map(|hooks| &hooks.dry_run).filter(|d| d.is_enabled()).collect();
+    if !dry_runs.is_empty() {
+        return dry_run::simulate_action(&dry_runs, action, element, details);
+    }
+    let _input_guard = input_guard::auto_guard();
+    let chain = chain(&hooks);
+    if chain.is_empty() {
+        return perform().inspect(|_| record_activity());
+    }
+
+    let ctx = ActionContext {
+        action,
+        element: element.clone(),
+        details,
+        started_at: SystemTime::now(),
+    };
+    let start = Instant::now();
+    let result = in_hook(|| chain.iter().try_for_each(|middleware| middleware.before_action(&ctx)))
+        .and_then(|()| perform());
+    let duration = start.elapsed();
+
+    match &result {
+        Ok(_) => {
+            record_activity();
+            in_hook(|| {
+                for middleware in chain.iter().rev() {
+                    middleware.after_action(&ctx, duration);
+                }
+            });
+        }
+        Err(error) => in_hook(|| {
+            for middleware in chain.iter().rev() {
+                middleware.on_error(&ctx, error, duration);
+            }
+        }),
+    }
+    result
+}
@@ -0,0 +1,72 @@
+//! Enumeration of connected monitors for multi-monitor coordinate math
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::AutomationError;
+use crate::geometry::Rect;
+
+/// A connected display
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorInfo {
+    /// Display name, as accepted by `Desktop::capture_monitor_by_name`
+    pub name: String,
+    /// Position and size in virtual screen coordinates (physical pixels)
+    pub bounds: Rect,
+    /// Ratio of physical to logical pixels (1.0 = 100% scaling)
+    pub scale_factor: f64,
+    /// Refresh rate in Hz
+    pub refresh_rate: f64,
+    /// Whether this is the primary display
+    pub is_primary: bool,
+}
+
+fn xcap_error(what: &str, e: impl std::fmt::Display) -> AutomationError {
+    AutomationError::PlatformError(format!("Failed to get monitor {}: {}", what, e))
+}
+
+impl MonitorInfo {
+    fn from_xcap(monitor: &xcap::Monitor) -> Result<Self, AutomationError> {
+        Ok(MonitorInfo {
+            name: monitor.name().map_err(|e| xcap_error("name", e))?,
+            bounds: Rect::new(
+                monitor.x().map_err(|e| xcap_error("position", e))? as f64,
+                monitor.y().map_err(|e| xcap_error("position", e))? as f64,
+                monitor.width().map_err(|e| xcap_error("size", e))? as f64,
+                monitor.height().map_err(|e| xcap_error("size", e))? as f64,
+            ),
+            scale_factor: monitor.scale_factor().map_err(|e| xcap_error("scale factor", e))? as f64,
+            refresh_rate: monitor.frequency().map_err(|e| xcap_error("refresh rate", e))? as f64,
+            is_primary: monitor.is_primary().map_err(|e| xcap_error("primary flag", e))?,
+        })
+    }
+}
+
+/// All connected monitors, in the order the OS reports them
+pub fn list_monitors() -> Result<Vec<MonitorInfo>, AutomationError> {
+    let monitors = xcap::Monitor::all().map_err(|e| {
+        AutomationError::PlatformError(format!("Failed to get monitors: {}", e))
+    })?;
+    monitors.iter().map(MonitorInfo::from_xcap).collect()
+}
+
+/// The monitor `rect` lives on: the one containing its center, otherwise the
+/// one it overlaps the most. `None` if it is off every monitor.
+pub fn monitor_for_rect<'a>(monitors: &'a [MonitorInfo], rect: &Rect) -> Option<&'a MonitorInfo> {
+    let center = rect.center();
+    if let Some(monitor) = monitors
+        .iter()
+        .find(|m| m.bounds.contains_point(center.x, center.y))
+    {
+        return Some(monitor);
+    }
+
+    monitors
+        .iter()
+        .filter_map(|m| {
+            m.bounds
+                .intersection(rect)
+                .map(|overlap| (m, overlap.width * overlap.height))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(m, _)| m)
+}
@@ -0,0 +1,393 @@
+//! Desktop notifications: Windows toasts and macOS Notification Center banners
+//!
+//! Flows that start a download or an export often have to wait for the
+//! "Download complete" toast rather than for anything in the application's
+//! own window. [`Desktop::notifications`](crate::Desktop::notifications)
+//! lists the notifications on screen, and
+//! [`Desktop::subscribe_notifications`](crate::Desktop::subscribe_notifications)
+//! reports each new one with its app, title, body and action buttons:
+//!
+//! ```no_run
+//! # use std::time::Duration;
+//! # use terminator::{AutomationError, Desktop};
+//! # async fn run(desktop: &Desktop) -> Result<(), AutomationError> {
+//! let toast = desktop
+//!     .wait_for_notification("Download complete", Duration::from_secs(120))
+//!     .await?;
+//! toast.click_action("Open folder")?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Notifications are read from the accessibility tree. On Windows they are
+//! the toast views of the shell (`ShellExperienceHost`); notifications
+//! already moved to the Action Center are not listed. On macOS they are the
+//! banners and alerts shown by Notification Center, which requires the
+//! accessibility permission like everything else. New notifications are
+//! found by polling, so a toast that comes and goes within one
+//! [`NOTIFICATION_POLL_INTERVAL`] can be missed.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::debug;
+
+use crate::dialogs::{find_descendant, has_role, BUTTON_ROLES};
+use crate::platforms::AccessibilityEngine;
+use crate::{AutomationError, UIElement, UIEventSubscription};
+
+/// How often subscriptions look for new notifications
+pub const NOTIFICATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+// Toasts nest their texts and buttons a few levels below the toast view
+const NOTIFICATION_SEARCH_DEPTH: usize = 6;
+const TOAST_SEARCH_DEPTH: usize = 8;
+
+// Process hosting Windows toasts, and the automation IDs of its toast views
+#[cfg(target_os = "windows")]
+const WINDOWS_TOAST_HOST: &str = "shellexperiencehost";
+const WINDOWS_TOAST_IDS: &[&str] = &["NormalToastView", "PriorityToastView", "IncomingCallToastView"];
+const MACOS_NOTIFICATION_APP: &str = "Notification Center";
+
+const TEXT_ROLES: &[&str] = &["text", "statictext", "axstatictext", "label"];
+const GROUP_ROLES: &[&str] = &["group", "axgroup"];
+// Parts of a text's automation ID that mark the app name and the title
+const APP_ID_MARKERS: &[&str] = &["attribution", "appname", "appdisplayname"];
+const TITLE_ID_MARKERS: &[&str] = &["title"];
+// Buttons every notification has, which are not actions of its own
+const DISMISS_NAMES: &[&str] = &["dismiss", "close", "clear", "dismissbutton", "closebutton"];
+const CHROME_NAMES: &[&str] = &[
+    "settings",
+    "settingsbutton",
+    "notification settings",
+    "options",
+    "more options",
+    "see more",
+    "expand",
+    "collapse",
+];
+
+/// Callback invoked for each new notification
+pub type NotificationCallback = Arc<dyn Fn(Notification) + Send + Sync>;
+
+/// A notification on screen
+#[derive(Clone)]
+pub struct Notification {
+    /// The app that raised it, where the platform shows it
+    pub app: Option<String>,
+    pub title: Option<String>,
+    pub body: Option<String>,
+    /// Labels of its action buttons, e.g. `["Open", "Show in folder"]`
+    pub actions: Vec<String>,
+    element: UIElement,
+}
+
+impl std::fmt::Debug for Notification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Notification")
+            .field("app", &self.app)
+            .field("title", &self.title)
+            .field("body", &self.body)
+            .field("actions", &self.actions)
+            .finish()
+    }
+}
+
+impl Notification {
+    /// The element of the whole notification
+    pub fn element(&self) -> &UIElement {
+        &self.element
+    }
+
+    /// Whether `text` appears in the app name, title or body, ignoring case
+    pub fn contains(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        [&self.app, &self.title, &self.body]
+            .into_iter()
+            .flatten()
+            .any(|field| field.to_lowercase().contains(&text))
+    }
+
+    /// Click the notification itself, which usually opens the app that raised it
+    pub fn click(&self) -> Result<(), AutomationError> {
+        self.element.click().map(|_| ())
+    }
+
+    /// Click the action button labeled `action` (ignoring case)
+    pub fn click_action(&self, action: &str) -> Result<(), AutomationError> {
+        let wanted = normalized_label(action);
+        let button = find_descendant(&self.element, NOTIFICATION_SEARCH_DEPTH, &|e| {
+            has_role(e, BUTTON_ROLES) && e.name().is_some_and(|name| normalized_label(&name) == wanted)
+        })
+        .ok_or_else(|| {
+            AutomationError::ElementNotFound(format!(
+                "Notification has no action '{}', it has {:?}",
+                action, self.actions
+            ))
+        })?;
+        button.click().map(|_| ())
+    }
+
+    /// Close the notification with its dismiss button
+    pub fn dismiss(&self) -> Result<(), AutomationError> {
+        let button = find_descendant(&self.element, NOTIFICATION_SEARCH_DEPTH, &|e| {
+            has_role(e, BUTTON_ROLES) && button_label(e).is_some_and(|label| DISMISS_NAMES.contains(&label.as_str()))
+        })
+        .ok_or_else(|| AutomationError::ElementNotFound("Notification has no dismiss button".to_string()))?;
+        button.click().map(|_| ())
+    }
+
+    /// What tells two notifications apart while they are on screen
+    fn key(&self) -> (Option<String>, Option<String>, Option<String>) {
+        (self.app.clone(), self.title.clone(), self.body.clone())
+    }
+}
+
+/// A text or button of a notification, with its automation ID where the
+/// platform has one
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NotificationPart {
+    pub(crate) id: Option<String>,
+    pub(crate) text: String,
+}
+
+/// App, title, body and actions out of the texts and buttons of a
+/// notification, in tree order. Texts are recognized by their automation
+/// IDs where they have them; otherwise the first text is the title and the
+/// rest the body
+pub(crate) fn parse_parts(
+    texts: &[NotificationPart],
+    buttons: &[NotificationPart],
+) -> (Option<String>, Option<String>, Option<String>, Vec<String>) {
+    let id_has = |part: &NotificationPart, markers: &[&str]| {
+        part.id
+            .as_deref()
+            .is_some_and(|id| markers.iter().any(|m| id.to_lowercase().contains(m)))
+    };
+    let texts: Vec<&NotificationPart> = texts.iter().filter(|t| !t.text.trim().is_empty()).collect();
+
+    let app = texts.iter().position(|t| id_has(t, APP_ID_MARKERS));
+    let title = texts
+        .iter()
+        .position(|t| id_has(t, TITLE_ID_MARKERS))
+        .or_else(|| texts.iter().position(|t| !id_has(t, APP_ID_MARKERS)));
+    let body: Vec<&str> = texts
+        .iter()
+        .enumerate()
+        .filter(|(i, t)| Some(*i) != title && !id_has(t, APP_ID_MARKERS))
+        .map(|(_, t)| t.text.trim())
+        .collect();
+
+    let actions = buttons
+        .iter()
+        .filter(|b| {
+            let label = normalized_label(&b.text);
+            let id = b.id.as_deref().map(normalized_label).unwrap_or_default();
+            !label.is_empty()
+                && ![&label, &id]
+                    .iter()
+                    .any(|l| DISMISS_NAMES.contains(&l.as_str()) || CHROME_NAMES.contains(&l.as_str()))
+        })
+        .map(|b| b.text.trim().to_string())
+        .collect();
+
+    (
+        app.map(|i| texts[i].text.trim().to_string()),
+        title.map(|i| texts[i].text.trim().to_string()),
+        (!body.is_empty()).then(|| body.join("\n")),
+        actions,
+    )
+}
+
+/// Lowercase without surrounding spaces or `&` access key markers
+fn normalized_label(label: &str) -> String {
+    label.replace('&', "").trim().to_lowercase()
+}
+
+fn automation_id(element: &UIElement) -> Option<String> {
+    element
+        .attributes()
+        .properties
+        .get("AutomationId")
+        .cloned()
+        .flatten()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .filter(|id| !id.is_empty())
+}
+
+/// Name of a button, or its automation ID for icon buttons without one
+fn button_label(element: &UIElement) -> Option<String> {
+    element
+        .name()
+        .filter(|name| !name.trim().is_empty())
+        .or_else(|| automation_id(element))
+        .map(|label| normalized_label(&label))
+}
+
+/// Read the texts and buttons below `element` into a notification
+fn read_notification(element: UIElement) -> Notification {
+    let mut texts = Vec::new();
+    let mut buttons = Vec::new();
+    let mut level = vec![element.clone()];
+    for _ in 0..NOTIFICATION_SEARCH_DEPTH {
+        let mut next = Vec::new();
+        for parent in &level {
+            for child in parent.children().unwrap_or_default() {
+                let part = || NotificationPart {
+                    id: automation_id(&child),
+                    text: child.name().unwrap_or_default(),
+                };
+                if has_role(&child, BUTTON_ROLES) {
+                    buttons.push(part());
+                    continue;
+                }
+                if has_role(&child, TEXT_ROLES) {
+                    let mut text = part();
+                    // macOS keeps static text in the value
+                    if text.text.is_empty() {
+                        text.text = child.attributes().value.unwrap_or_default();
+                    }
+                    texts.push(text);
+                }
+                next.push(child);
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        level = next;
+    }
+
+    let (app, title, body, actions) = parse_parts(&texts, &buttons);
+    Notification {
+        app,
+        title,
+        body,
+        actions,
+        element,
+    }
+}
+
+/// Elements of the notifications on screen
+fn notification_elements(engine: &dyn AccessibilityEngine) -> Result<Vec<UIElement>, AutomationError> {
+    if cfg!(target_os = "windows") {
+        return windows_toasts(engine);
+    }
+    if cfg!(target_os = "macos") {
+        let Ok(center) = engine.get_application_by_name(MACOS_NOTIFICATION_APP) else {
+            return Ok(Vec::new());
+        };
+        // Each banner is a group holding its own texts
+        let mut banners = Vec::new();
+        for window in center.children().unwrap_or_default() {
+            collect_groups_with_text(&window, NOTIFICATION_SEARCH_DEPTH, &mut banners);
+        }
+        return Ok(banners);
+    }
+    Err(AutomationError::UnsupportedPlatform(
+        "Notifications are only supported on Windows and macOS".to_string(),
+    ))
+}
+
+fn windows_toasts(engine: &dyn AccessibilityEngine) -> Result<Vec<UIElement>, AutomationError> {
+    let mut toasts = Vec::new();
+    for window in engine.get_root_element().children()? {
+        if !is_toast_host(&window) {
+            continue;
+        }
+        let mut level = vec![window];
+        for _ in 0..TOAST_SEARCH_DEPTH {
+            let mut next = Vec::new();
+            for element in level {
+                if automation_id(&element).is_some_and(|id| WINDOWS_TOAST_IDS.contains(&id.as_str())) {
+                    toasts.push(element);
+                    continue;
+                }
+                next.extend(element.children().unwrap_or_default());
+            }
+            if next.is_empty() {
+                break;
+            }
+            level = next;
+        }
+    }
+    Ok(toasts)
+}
+
+#[cfg(target_os = "windows")]
+fn is_toast_host(window: &UIElement) -> bool {
+    window
+        .process_id()
+        .ok()
+        .and_then(|pid| crate::platforms::windows::get_process_name_by_pid(pid as i32).ok())
+        .is_some_and(|name| name.to_lowercase().trim_end_matches(".exe") == WINDOWS_TOAST_HOST)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn is_toast_host(_window: &UIElement) -> bool {
+    false
+}
+
+/// The innermost groups below `element` that have text children
+fn collect_groups_with_text(element: &UIElement, depth: usize, found: &mut Vec<UIElement>) {
+    if depth == 0 {
+        return;
+    }
+    for child in element.children().unwrap_or_default() {
+        if !has_role(&child, GROUP_ROLES) {
+            continue;
+        }
+        let before = found.len();
+        collect_groups_with_text(&child, depth - 1, found);
+        if found.len() == before
+            && child.children().unwrap_or_default().iter().any(|c| has_role(c, TEXT_ROLES))
+        {
+            found.push(child);
+        }
+    }
+}
+
+/// The notifications on screen
+pub(crate) fn current(engine: &dyn AccessibilityEngine) -> Result<Vec<Notification>, AutomationError> {
+    Ok(notification_elements(engine)?
+        .into_iter()
+        .map(read_notification)
+        .collect())
+}
+
+/// Poll for notifications on a background thread and pass each one that
+/// wasn't on screen at the previous poll to `callback`. Notifications
+/// already shown when subscribing are not reported
+pub(crate) fn subscribe(
+    engine: Arc<dyn AccessibilityEngine>,
+    callback: NotificationCallback,
+) -> Result<UIEventSubscription, AutomationError> {
+    let mut seen: HashSet<_> = current(engine.as_ref())?.iter().map(Notification::key).collect();
+    let stop = Arc::new(AtomicBool::new(false));
+    let running = Arc::clone(&stop);
+    std::thread::Builder::new()
+        .name("terminator-notifications".to_string())
+        .spawn(move || {
+            while !running.load(Ordering::SeqCst) {
+                std::thread::sleep(NOTIFICATION_POLL_INTERVAL);
+                let notifications = match current(engine.as_ref()) {
+                    Ok(notifications) => notifications,
+                    Err(e) => {
+                        debug!("Failed to read notifications: {}", e);
+                        continue;
+                    }
+                };
+                let keys = notifications.iter().map(Notification::key).collect();
+                for notification in notifications {
+                    if !seen.contains(&notification.key()) && !running.load(Ordering::SeqCst) {
+                        debug!(title = ?notification.title, "New notification");
+                        callback(notification);
+                    }
+                }
+                seen = keys;
+            }
+        })
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to start notification watcher: {}", e)))?;
+    Ok(UIEventSubscription::new(move || stop.store(true, Ordering::SeqCst)))
+}
@@ -0,0 +1,136 @@
+//! Locating text on screen by OCR
+//!
+//! Applications that draw their own UI (games, canvas-based apps, remote
+//! sessions such as Citrix) expose no accessibility tree to search.
+//! [`Desktop::find_text_on_screen`](crate::Desktop::find_text_on_screen)
+//! captures the screen, recognizes the words on it and returns where a piece
+//! of text is shown, so it can still be clicked:
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop};
+//! # async fn run(desktop: &Desktop) -> Result<(), AutomationError> {
+//! let matches = desktop.find_text_on_screen("Start game", None).await?;
+//! if let Some(button) = matches.first() {
+//!     button.click()?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Text is matched case-insensitively, ignoring spacing, and may span
+//! several consecutive words of a line.
+
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::{Point, Rect};
+use crate::{AutomationError, ScreenshotResult, UIElement};
+
+/// A word recognized in an image
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OcrWord {
+    pub text: String,
+    /// Position in the image, in pixels
+    pub bounds: Rect,
+    /// Index of the line of text the word belongs to
+    pub line: usize,
+}
+
+/// Where a piece of text is shown on screen
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreenMatch {
+    /// The recognized words that make up the match, as shown on screen
+    pub text: String,
+    /// Position in physical screen pixels
+    pub bounds: Rect,
+    // Any element: clicks at screen points go through element input methods
+    #[serde(skip)]
+    pub(crate) root: UIElement,
+}
+
+impl ScreenMatch {
+    /// Center of the match in screen coordinates
+    pub fn center(&self) -> Point {
+        self.bounds.center()
+    }
+
+    /// Left-click the center of the match
+    pub fn click(&self) -> Result<(), AutomationError> {
+        let center = self.center();
+        self.root.mouse_click_and_hold(center.x, center.y)?;
+        self.root.mouse_release()
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Runs of consecutive words on one line that contain `text`, with their
+/// joined text and combined bounds. Each run is as short as possible
+pub(crate) fn find_phrase(words: &[OcrWord], text: &str) -> Vec<(String, Rect)> {
+    let needle = normalize(text);
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let normalized: Vec<String> = words.iter().map(|word| normalize(&word.text)).collect();
+
+    let mut matches = Vec::new();
+    for start in 0..words.len() {
+        let mut joined = String::new();
+        for end in start..words.len() {
+            if words[end].line != words[start].line {
+                break;
+            }
+            joined.push_str(&normalized[end]);
+            if !joined.contains(&needle) {
+                continue;
+            }
+            // A run that still matches without its first word is found
+            // from the next start
+            let rest: String = normalized[start + 1..=end].concat();
+            if start == end || !rest.contains(&needle) {
+                let run = &words[start..=end];
+                let text = run.iter().map(|w| w.text.as_str()).collect::<Vec<_>>().join(" ");
+                let bounds = run
+                    .iter()
+                    .skip(1)
+                    .fold(run[0].bounds, |bounds, word| bounds.union(&word.bounds));
+                matches.push((text, bounds));
+            }
+            break;
+        }
+    }
+    matches
+}
+
+/// The part of `screenshot` inside `area`, given in image pixels
+pub(crate) fn crop(screenshot: &ScreenshotResult, area: &Rect) -> Result<ScreenshotResult, AutomationError> {
+    let image = image::RgbaImage::from_raw(
+        screenshot.width,
+        screenshot.height,
+        screenshot.image_data.clone(),
+    )
+    .ok_or_else(|| {
+        AutomationError::InvalidArgument("Invalid screenshot data for buffer creation".to_string())
+    })?;
+    let full = Rect::new(0.0, 0.0, screenshot.width as f64, screenshot.height as f64);
+    let area = full.intersection(area).ok_or_else(|| {
+        AutomationError::InvalidArgument(format!("Region {:?} is outside the screenshot", area))
+    })?;
+    let cropped = image::imageops::crop_imm(
+        &image,
+        area.x as u32,
+        area.y as u32,
+        area.width as u32,
+        area.height as u32,
+    )
+    .to_image();
+    Ok(ScreenshotResult {
+        width: cropped.width(),
+        height: cropped.height(),
+        image_data: cropped.into_raw(),
+    })
+}
@@ -0,0 +1,212 @@
+//! Operating system permissions automation depends on
+//!
+//! macOS only lets a process read and drive the UI of other applications
+//! once the user allows it under Accessibility in System Settings, and only
+//! lets it capture the screen once they allow it under Screen Recording.
+//! Without Accessibility `Desktop::new` fails; without Screen Recording,
+//! captures show nothing but the wallpaper and the menu bar. Both are
+//! granted to the application the automation runs in (the terminal, the
+//! IDE or the bundled executable), not to the script itself.
+//!
+//! Check them before a run, and prompt for the missing ones:
+//!
+//! ```no_run
+//! use terminator::Desktop;
+//!
+//! let status = Desktop::check_permissions();
+//! if !status.all_granted() {
+//!     let status = Desktop::request_permissions();
+//!     for permission in status.missing() {
+//!         eprintln!("Missing {} permission: {}", permission, permission.settings_url());
+//!     }
+//! }
+//! ```
+//!
+//! Operations that need a missing permission fail with
+//! [`AutomationError::MissingPermission`](crate::AutomationError::MissingPermission),
+//! naming it. Other platforms have no such permissions and report them as
+//! not applicable.
+
+use serde::{Deserialize, Serialize};
+
+use crate::AutomationError;
+
+/// A permission the user grants in System Settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    /// Reading and driving the UI of other applications
+    Accessibility,
+    /// Capturing the screen and windows of other applications
+    ScreenRecording,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 2] = [Permission::Accessibility, Permission::ScreenRecording];
+
+    /// Name of the permission as System Settings shows it
+    pub fn name(self) -> &'static str {
+        match self {
+            Permission::Accessibility => "Accessibility",
+            Permission::ScreenRecording => "Screen Recording",
+        }
+    }
+
+    /// Link that opens the System Settings pane granting the permission
+    pub fn settings_url(self) -> &'static str {
+        match self {
+            Permission::Accessibility => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility"
+            }
+            Permission::ScreenRecording => {
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture"
+            }
+        }
+    }
+
+    /// What to do to grant the permission
+    pub fn hint(self) -> String {
+        let restart = match self {
+            Permission::Accessibility => "",
+            // macOS only applies it to processes started afterwards
+            Permission::ScreenRecording => ", then restart it",
+        };
+        format!(
+            "{} is not granted. Allow this application in System Settings > Privacy & Security > {}{}",
+            self.name(),
+            self.name(),
+            restart
+        )
+    }
+}
+
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Whether a permission is granted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    /// The platform has no such permission
+    NotApplicable,
+}
+
+impl PermissionState {
+    /// Whether operations needing the permission can run
+    pub fn allows(self) -> bool {
+        self != PermissionState::Denied
+    }
+}
+
+/// The permissions of the current process, from
+/// [`Desktop::check_permissions`](crate::Desktop::check_permissions)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionStatus {
+    pub accessibility: PermissionState,
+    pub screen_recording: PermissionState,
+}
+
+impl PermissionStatus {
+    /// The status on platforms without permissions
+    pub fn not_applicable() -> Self {
+        Self {
+            accessibility: PermissionState::NotApplicable,
+            screen_recording: PermissionState::NotApplicable,
+        }
+    }
+
+    pub fn state(&self, permission: Permission) -> PermissionState {
+        match permission {
+            Permission::Accessibility => self.accessibility,
+            Permission::ScreenRecording => self.screen_recording,
+        }
+    }
+
+    /// Permissions that are denied
+    pub fn missing(&self) -> Vec<Permission> {
+        Permission::ALL
+            .into_iter()
+            .filter(|&permission| !self.state(permission).allows())
+            .collect()
+    }
+
+    pub fn all_granted(&self) -> bool {
+        self.missing().is_empty()
+    }
+
+    /// Fail with [`AutomationError::MissingPermission`] if `permission` is
+    /// denied
+    pub fn require(&self, permission: Permission) -> Result<(), AutomationError> {
+        match self.state(permission).allows() {
+            true => Ok(()),
+            false => Err(AutomationError::MissingPermission(permission)),
+        }
+    }
+
+    /// Fail with [`AutomationError::MissingPermission`] for the first
+    /// denied permission
+    pub fn require_all(&self) -> Result<(), AutomationError> {
+        Permission::ALL
+            .into_iter()
+            .try_for_each(|permission| self.require(permission))
+    }
+}
+
+/// Permissions of the current process, without prompting
+pub(crate) fn check() -> PermissionStatus {
+    #[cfg(target_os = "macos")]
+    {
+        crate::platforms::macos_permissions::status(false)
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        PermissionStatus::not_applicable()
+    }
+}
+
+/// Show the system prompts for the missing permissions, then open the
+/// System Settings pane of the first one still missing. macOS prompts for
+/// each permission once; later requests only open the pane
+pub(crate) fn request() -> PermissionStatus {
+    #[cfg(target_os = "macos")]
+    {
+        let status = crate::platforms::macos_permissions::status(true);
+        if let Some(&permission) = status.missing().first() {
+            if let Err(e) = open_settings(permission) {
+                tracing::warn!("Failed to open the settings of {}: {}", permission, e);
+            }
+        }
+        status
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        PermissionStatus::not_applicable()
+    }
+}
+
+/// Open the System Settings pane granting `permission`
+pub(crate) fn open_settings(permission: Permission) -> Result<(), AutomationError> {
+    if !cfg!(target_os = "macos") {
+        return Err(AutomationError::UnsupportedPlatform(format!(
+            "{} permission settings only exist on macOS",
+            permission
+        )));
+    }
+    let status = std::process::Command::new("open")
+        .arg(permission.settings_url())
+        .status()
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to run open: {}", e)))?;
+    if !status.success() {
+        return Err(AutomationError::PlatformError(format!(
+            "open {} exited with {}",
+            permission.settings_url(),
+            status
+        )));
+    }
+    Ok(())
+}
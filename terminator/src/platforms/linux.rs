@@ -1,7 +1,7 @@
 use crate::element::UIElementImpl;
 use crate::platforms::AccessibilityEngine;
 use crate::{AutomationError, Locator, Selector, UIElement, UIElementAttributes};
-use crate::{ClickResult, CommandOutput, ScreenshotResult, UINode};
+use crate::{ActionStrategy, ClickResult, CommandOutput, ScreenshotResult, UINode};
 use atspi::{State, StateSet};
 use std::collections::hash_map::DefaultHasher;
 use std::default::Default;
@@ -15,7 +15,7 @@ use std::sync::{OnceLock, mpsc};
 use std::thread;
 use std::time::Duration;
 use tokio::time::sleep;
-use tracing::debug;
+use tracing::{Instrument, debug};
 
 use atspi::{
     AccessibilityConnection, Role,
@@ -483,6 +483,13 @@ fn find_elements_inner<'a>(
                     "Selector::Path is not implemented for Linux".to_string(),
                 ));
             }
+            Selector::RightOf(_)
+            | Selector::LeftOf(_)
+            | Selector::Above(_)
+            | Selector::Below(_)
+            | Selector::Near { .. } => {
+                return Err(crate::spatial::standalone_error(selector));
+            }
             Selector::ClassName(_) => {
                 return Err(AutomationError::UnsupportedPlatform(
                     "Selector::ClassName is not implemented for Linux".to_string(),
@@ -498,6 +505,28 @@ fn find_elements_inner<'a>(
                     "Selector::Text is not implemented for Linux".to_string(),
                 ));
             }
+            Selector::Not(_) | Selector::And(_) | Selector::Or(_) => {
+                return Err(AutomationError::UnsupportedPlatform(
+                    "Not/And/Or selectors are not implemented for Linux".to_string(),
+                ));
+            }
+            Selector::NameL10n(_) => {
+                return find_elements_inner(linux_engine, &selector.localized(), root, depth).await;
+            }
+            Selector::LocalizedName(names) => {
+                // One name search per translation, since Or is not supported,
+                // keeping the elements named after one as a whole
+                let mut found: Vec<UIElement> = Vec::new();
+                for name in names {
+                    for element in find_elements_inner(linux_engine, &Selector::Name(name.clone()), root, depth).await? {
+                        let whole = element.name().is_some_and(|n| crate::l10n::is_one_of(&n, names));
+                        if whole && !found.contains(&element) {
+                            found.push(element);
+                        }
+                    }
+                }
+                return Ok(found);
+            }
             Selector::Id(target_id) => {
                 // Traverse the tree from root, collect elements whose object_id matches target_id
                 let root_binding = linux_engine.get_root_element();
@@ -526,11 +555,13 @@ fn find_elements_inner<'a>(
                 } else {
                     vec![linux_engine.get_root_element()]
                 };
-                for sel in chain {
+                for (i, sel) in chain.iter().enumerate() {
                     let mut next_elements = Vec::new();
                     for elem in &current_elements {
-                        let found =
-                            find_elements_inner(linux_engine, sel, Some(elem), depth).await?;
+                        let hop = crate::platforms::selector_hop_span(i, chain.len(), sel, Some(elem));
+                        let found = find_elements_inner(linux_engine, sel, Some(elem), depth)
+                            .instrument(hop)
+                            .await?;
                         next_elements.extend(found);
                     }
                     if next_elements.is_empty() {
@@ -1377,6 +1408,31 @@ impl UIElementImpl for LinuxUIElement {
         resp_rx.recv().unwrap().unwrap_or_default()
     }
 
+    fn is_stale(&self) -> bool {
+        // The registry forgets the object once the application drops it, so
+        // even its role can no longer be read
+        let (resp_tx, resp_rx) = std::sync::mpsc::channel();
+        let this = self.clone();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let gone = rt.block_on(async move {
+                let proxy = match AccessibleProxy::builder(&this.connection)
+                    .destination(this.destination.as_str())
+                    .and_then(|builder| builder.path(this.path.as_str()))
+                {
+                    Ok(builder) => builder.build().await,
+                    Err(_) => return true,
+                };
+                match proxy {
+                    Ok(proxy) => proxy.get_role().await.is_err(),
+                    Err(_) => true,
+                }
+            });
+            let _ = resp_tx.send(gone);
+        });
+        resp_rx.recv().unwrap_or(false)
+    }
+
     fn attributes(&self) -> UIElementAttributes {
         let mut attrs = UIElementAttributes::default();
         attrs.role = self.role();
@@ -1541,6 +1597,8 @@ impl UIElementImpl for LinuxUIElement {
                 device_controller.generate_mouse_event(x, y, "b1r").await?;
                 Ok(ClickResult {
                     method: "click".to_string(),
+                    strategy: Some(ActionStrategy::SendInputCursor),
+                    attempts: Vec::new(),
                     coordinates: Some((x as f64, y as f64)),
                     details: format!("Clicked at ({}, {})", x, y),
                 })
@@ -1575,6 +1633,8 @@ impl UIElementImpl for LinuxUIElement {
                 device_controller.generate_mouse_event(x, y, "b1r").await?;
                 Ok(ClickResult {
                     method: "double_click".to_string(),
+                    strategy: Some(ActionStrategy::SendInputCursor),
+                    attempts: Vec::new(),
                     coordinates: Some((x as f64, y as f64)),
                     details: format!("Double clicked at ({}, {})", x, y),
                 })
@@ -1919,6 +1979,29 @@ impl UIElementImpl for LinuxUIElement {
         resp_rx.recv().unwrap()
     }
 
+    fn is_read_only(&self) -> Result<bool, AutomationError> {
+        use std::sync::mpsc;
+        let (resp_tx, resp_rx): (
+            mpsc::Sender<Result<bool, AutomationError>>,
+            mpsc::Receiver<Result<bool, AutomationError>>,
+        ) = mpsc::channel();
+        let this = self.clone();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let result = rt.block_on(async move {
+                let proxy = AccessibleProxy::builder(&this.connection)
+                    .destination(this.destination.as_str())?
+                    .path(this.path.as_str())?
+                    .build()
+                    .await?;
+                let states = proxy.get_state().await?;
+                Ok(states.contains(state::State::ReadOnly))
+            });
+            let _ = resp_tx.send(result);
+        });
+        resp_rx.recv().unwrap()
+    }
+
     fn perform_action(&self, action: &str) -> Result<(), AutomationError> {
         use std::sync::mpsc;
         let (resp_tx, resp_rx): (
@@ -2191,6 +2274,30 @@ impl UIElementImpl for LinuxUIElement {
             "Linux implementation is not yet available".to_string(),
         ))
     }
+
+    fn is_topmost(&self) -> Result<bool, AutomationError> {
+        Err(AutomationError::UnsupportedPlatform(
+            "Linux implementation is not yet available".to_string(),
+        ))
+    }
+
+    fn set_topmost(&self, _topmost: bool) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedPlatform(
+            "Linux implementation is not yet available".to_string(),
+        ))
+    }
+
+    fn opacity(&self) -> Result<f32, AutomationError> {
+        Err(AutomationError::UnsupportedPlatform(
+            "Linux implementation is not yet available".to_string(),
+        ))
+    }
+
+    fn set_opacity(&self, _opacity: f32) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedPlatform(
+            "Linux implementation is not yet available".to_string(),
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -1,8 +1,9 @@
 use crate::platforms::AccessibilityEngine;
 use crate::{
-    AutomationError, Locator, Selector, UIElement, UIElementAttributes, element::UIElementImpl,
+    AutomationError, Locator, Permission, Selector, UIElement, UIElementAttributes,
+    element::UIElementImpl,
 };
-use crate::{ClickResult, ScreenshotResult};
+use crate::{ActionStrategy, ClickResult, ScreenshotResult};
 
 use accessibility::AXUIElementAttributes;
 use accessibility::{AXAttribute, AXUIElement};
@@ -12,7 +13,6 @@ use core_foundation::array::{
 };
 use core_foundation::base::{CFGetTypeID, TCFType};
 use core_foundation::boolean::CFBoolean;
-use core_foundation::dictionary::CFDictionary;
 use core_foundation::string::CFString;
 use core_graphics::display::{CGPoint, CGSize};
 use core_graphics::event::{CGEvent, CGEventFlags, CGKeyCode};
@@ -109,6 +109,16 @@ impl fmt::Debug for ThreadSafeAXUIElement {
     }
 }
 
+// Without Screen Recording, captures succeed but only show the wallpaper
+// and the menu bar
+fn require_screen_recording() -> Result<(), AutomationError> {
+    if super::macos_permissions::screen_capture_allowed(false) {
+        Ok(())
+    } else {
+        Err(AutomationError::MissingPermission(Permission::ScreenRecording))
+    }
+}
+
 pub struct MacOSEngine {
     system_wide: ThreadSafeAXUIElement,
     use_background_apps: bool,
@@ -117,30 +127,9 @@ pub struct MacOSEngine {
 
 impl MacOSEngine {
     pub fn new(use_background_apps: bool, activate_app: bool) -> Result<Self, AutomationError> {
-        // Check accessibility permissions using FFI directly
-        // Since accessibility::AXIsProcessTrustedWithOptions is not available
-        let accessibility_enabled = unsafe {
-            use core_foundation::dictionary::CFDictionaryRef;
-
-            #[link(name = "ApplicationServices", kind = "framework")]
-            unsafe extern "C" {
-                fn AXIsProcessTrustedWithOptions(options: CFDictionaryRef) -> bool;
-            }
-
-            let check_attr = CFString::new("AXTrustedCheckOptionPrompt");
-            let options = CFDictionary::from_CFType_pairs(&[(
-                check_attr.as_CFType(),
-                CFBoolean::true_value().as_CFType(),
-            )])
-            .as_concrete_TypeRef();
-
-            AXIsProcessTrustedWithOptions(options)
-        };
-
-        if !accessibility_enabled {
-            return Err(AutomationError::PermissionDenied(
-                "Accessibility permissions not granted".to_string(),
-            ));
+        // Prompts the user the first time, like before the permissions API
+        if !super::macos_permissions::accessibility_trusted(true) {
+            return Err(AutomationError::MissingPermission(Permission::Accessibility));
         }
 
         Ok(Self {
@@ -419,6 +408,8 @@ impl MacOSUIElement {
                 debug!("Successfully clicked element with AXPress");
                 Ok(ClickResult {
                     method: "AXPress".to_string(),
+                    strategy: Some(ActionStrategy::UiaPattern),
+                    attempts: Vec::new(),
                     coordinates: None,
                     details: "Used accessibility AXPress action".to_string(),
                 })
@@ -437,6 +428,8 @@ impl MacOSUIElement {
                 debug!("Successfully clicked element with AXClick");
                 Ok(ClickResult {
                     method: "AXClick".to_string(),
+                    strategy: Some(ActionStrategy::UiaPattern),
+                    attempts: Vec::new(),
                     coordinates: None,
                     details: "Used accessibility AXClick action".to_string(),
                 })
@@ -450,10 +443,10 @@ impl MacOSUIElement {
 
     fn click_mouse_simulation(&self) -> Result<ClickResult, AutomationError> {
         match self.bounds() {
-            Ok((x, y, width, height)) => {
+            Ok(bounds) => {
                 // Calculate center point of the element
-                let center_x = x + width / 2.0;
-                let center_y = y + height / 2.0;
+                let center = crate::geometry::Rect::from(bounds).center();
+                let (center_x, center_y) = (center.x, center.y);
 
                 // Use CGEventCreateMouseEvent to simulate mouse click
                 use core_graphics::event::{CGEvent, CGEventType, CGMouseButton};
@@ -523,6 +516,8 @@ impl MacOSUIElement {
 
                 Ok(ClickResult {
                     method: "MouseSimulation".to_string(),
+                    strategy: Some(ActionStrategy::SendInputCursor),
+                    attempts: Vec::new(),
                     coordinates: Some((center_x, center_y)),
                     details: format!(
                         "Used mouse simulation at coordinates ({:.1}, {:.1}), element bounds: ({:.1}, {:.1}, {:.1}, {:.1})",
@@ -699,6 +694,11 @@ impl UIElementImpl for MacOSUIElement {
             .to_string()
     }
 
+    fn is_stale(&self) -> bool {
+        // kAXErrorInvalidUIElement: the element was destroyed
+        matches!(self.element.0.role(), Err(accessibility::Error::Ax(-25202)))
+    }
+
     fn attributes(&self) -> UIElementAttributes {
         let _span = tracing::span!(tracing::Level::DEBUG, "attributes").entered();
         let start = std::time::Instant::now();
@@ -1113,6 +1113,8 @@ impl UIElementImpl for MacOSUIElement {
                 // Return information about both clicks
                 Ok(ClickResult {
                     method: second_click.method,
+                    strategy: second_click.strategy,
+                    attempts: Vec::new(),
                     coordinates: second_click.coordinates,
                     details: format!(
                         "Double-click: First click: {}, Second click: {}",
@@ -1477,9 +1479,8 @@ impl UIElementImpl for MacOSUIElement {
         let _ = self.focus();
 
         // Get element bounds to determine where to scroll
-        let (x, y, width, height) = self.bounds()?;
-        let center_x = x + width / 2.0;
-        let center_y = y + height / 2.0;
+        let center = crate::geometry::Rect::from(self.bounds()?).center();
+        let (center_x, center_y) = (center.x, center.y);
 
         // Create event source
         let source =
@@ -1546,8 +1547,15 @@ impl UIElementImpl for MacOSUIElement {
     }
 
     fn is_keyboard_focusable(&self) -> Result<bool, AutomationError> {
-        // Not implemented for macOS yet
-        Ok(false)
+        Err(AutomationError::UnsupportedOperation(
+            "is_keyboard_focusable is not implemented for macOS yet".to_string(),
+        ))
+    }
+
+    fn is_read_only(&self) -> Result<bool, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "is_read_only is not implemented for macOS yet".to_string(),
+        ))
     }
 
     fn mouse_click_and_hold(&self, _x: f64, _y: f64) -> Result<(), AutomationError> {
@@ -1722,6 +1730,30 @@ impl UIElementImpl for MacOSUIElement {
             }
         }
     }
+
+    fn is_topmost(&self) -> Result<bool, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "is_topmost is not implemented for macOS yet".to_string(),
+        ))
+    }
+
+    fn set_topmost(&self, _topmost: bool) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "set_topmost is not implemented for macOS yet".to_string(),
+        ))
+    }
+
+    fn opacity(&self) -> Result<f32, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "opacity is not implemented for macOS yet".to_string(),
+        ))
+    }
+
+    fn set_opacity(&self, _opacity: f32) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "set_opacity is not implemented for macOS yet".to_string(),
+        ))
+    }
 }
 
 // Helper function to parse AXUIElement attribute values into appropriate types
@@ -2355,6 +2387,13 @@ impl AccessibilityEngine for MacOSEngine {
                     let is_last_selector = i == selectors.len() - 1;
 
                     for root_element in &current_roots {
+                        let _hop = crate::platforms::selector_hop_span(
+                            i,
+                            selectors.len(),
+                            selector,
+                            Some(root_element),
+                        )
+                        .entered();
                         // Find elements matching the current selector within the current root
                         let found_elements = self
                             .find_elements(selector, Some(root_element), timeout, None)
@@ -2392,9 +2431,38 @@ impl AccessibilityEngine for MacOSEngine {
                 }
                 Ok(current_roots) // Return all elements found by the last selector
             }
+            Selector::RightOf(_)
+            | Selector::LeftOf(_)
+            | Selector::Above(_)
+            | Selector::Below(_)
+            | Selector::Near { .. } => Err(crate::spatial::standalone_error(&actual_selector)),
             Selector::ClassName(_) => Err(AutomationError::UnsupportedOperation(
                 "ClassName selector is not yet supported for macOS".to_string(),
             )),
+            Selector::NameL10n(_) => {
+                let root = self.wrap_element(start_element.clone());
+                self.find_elements(&actual_selector.localized(), Some(&root), timeout, None)
+            }
+            Selector::LocalizedName(names) => {
+                // One name search per translation, since Or is not supported,
+                // keeping the elements named after one as a whole
+                let root = self.wrap_element(start_element.clone());
+                let mut found: Vec<UIElement> = Vec::new();
+                for name in names {
+                    for element in self.find_elements(&Selector::Name(name.clone()), Some(&root), timeout, None)? {
+                        let whole = element.name().is_some_and(|n| crate::l10n::is_one_of(&n, names));
+                        if whole && !found.contains(&element) {
+                            found.push(element);
+                        }
+                    }
+                }
+                Ok(found)
+            }
+            Selector::Not(_) | Selector::And(_) | Selector::Or(_) => {
+                Err(AutomationError::UnsupportedOperation(
+                    "Not/And/Or selectors are not yet supported for macOS".to_string(),
+                ))
+            }
         }
     }
 
@@ -2628,7 +2696,14 @@ impl AccessibilityEngine for MacOSEngine {
                 let mut current_element = self.wrap_element(start_element); // Start with the initial root
                 debug!("Find with chain, current_element: {:?}", current_element);
 
-                for selector in selectors {
+                for (i, selector) in selectors.iter().enumerate() {
+                    let _hop = crate::platforms::selector_hop_span(
+                        i,
+                        selectors.len(),
+                        selector,
+                        Some(&current_element),
+                    )
+                    .entered();
                     // Find exactly one element matching the current selector within the current element
                     let found_elements =
                         self.find_elements(selector, Some(&current_element), timeout, None)?;
@@ -2658,9 +2733,31 @@ impl AccessibilityEngine for MacOSEngine {
                 // If the loop completes, current_element holds the final result
                 Ok(current_element)
             }
+            Selector::RightOf(_)
+            | Selector::LeftOf(_)
+            | Selector::Above(_)
+            | Selector::Below(_)
+            | Selector::Near { .. } => Err(crate::spatial::standalone_error(&actual_selector)),
             Selector::ClassName(_) => Err(AutomationError::UnsupportedOperation(
                 "ClassName selector is not yet supported for macOS".to_string(),
             )),
+            Selector::NameL10n(_) | Selector::LocalizedName(_) => {
+                let root = self.wrap_element(start_element.clone());
+                self.find_elements(&actual_selector, Some(&root), timeout, None)?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| {
+                        AutomationError::ElementNotFound(format!(
+                            "Element named after a translation not found: {:?}",
+                            actual_selector
+                        ))
+                    })
+            }
+            Selector::Not(_) | Selector::And(_) | Selector::Or(_) => {
+                Err(AutomationError::UnsupportedOperation(
+                    "Not/And/Or selectors are not yet supported for macOS".to_string(),
+                ))
+            }
         }
     }
 
@@ -2772,6 +2869,7 @@ impl AccessibilityEngine for MacOSEngine {
     }
 
     async fn capture_screen(&self) -> Result<ScreenshotResult, AutomationError> {
+        require_screen_recording()?;
         // Directly call the implementation logic
         let monitors = xcap::Monitor::all().map_err(|e| {
             AutomationError::PlatformError(format!("Failed to get monitors: {}", e))
@@ -2811,6 +2909,7 @@ impl AccessibilityEngine for MacOSEngine {
         &self,
         name: &str,
     ) -> Result<ScreenshotResult, AutomationError> {
+        require_screen_recording()?;
         // Directly call the implementation logic
         let monitors = xcap::Monitor::all().map_err(|e| {
             AutomationError::PlatformError(format!("Failed to get monitors: {}", e))
@@ -3194,12 +3293,87 @@ impl AccessibilityEngine for MacOSEngine {
         &self,
         pid: u32,
         title: Option<&str>,
-        _config: crate::platforms::TreeBuildConfig,
+        config: crate::platforms::TreeBuildConfig,
     ) -> Result<crate::UINode, AutomationError> {
-        Err(AutomationError::UnsupportedOperation(format!(
-            "get_window_tree for PID {} and title {:?} not yet implemented for macOS",
-            pid, title
-        )))
+        let app = ThreadSafeAXUIElement::application(pid as i32);
+        match app.0.role() {
+            Ok(role) if role.to_string() == "AXApplication" => {}
+            _ => {
+                return Err(AutomationError::ElementNotFound(format!(
+                    "Application with PID {} not found",
+                    pid
+                )));
+            }
+        }
+
+        let windows: Vec<(ThreadSafeAXUIElement, String)> = app
+            .0
+            .windows()
+            .map(|windows| {
+                windows
+                    .iter()
+                    .map(|w| {
+                        let window = ThreadSafeAXUIElement::new(w.clone());
+                        let name = window.0.title().map(|t| t.to_string()).unwrap_or_default();
+                        (window, name)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Exact title first, then a substring match, then the first window.
+        // Apps without windows (e.g. menu bar extras) get their whole AX tree
+        let root = match title {
+            Some(title) => {
+                let title_lower = title.to_lowercase();
+                windows
+                    .iter()
+                    .find(|(_, name)| name.to_lowercase() == title_lower)
+                    .or_else(|| windows.iter().find(|(_, name)| name.to_lowercase().contains(&title_lower)))
+                    .or_else(|| {
+                        let names: Vec<&String> = windows.iter().map(|(_, name)| name).collect();
+                        warn!(
+                            "No window titled '{}' for PID {}, falling back to first window. Available: {:?}",
+                            title, pid, names
+                        );
+                        windows.first()
+                    })
+                    .map(|(window, _)| window.clone())
+            }
+            None => windows.first().map(|(window, _)| window.clone()),
+        }
+        .unwrap_or_else(|| {
+            info!("PID {} has no windows, building the tree from the application element", pid);
+            app.clone()
+        });
+
+        // The messaging timeout set on the application applies to every element in it
+        let timeout_secs = config.timeout_per_operation_ms.map(|ms| ms as f32 / 1000.0);
+        if let Some(secs) = timeout_secs {
+            set_messaging_timeout(&app, secs);
+        }
+
+        info!("Building UI tree for PID {} with config: {:?}", pid, config);
+        let mut builder = MacOSTreeBuilder::new(self, &config);
+        let tree = builder.build(&root, 0);
+
+        if timeout_secs.is_some() {
+            // 0 restores the system default
+            set_messaging_timeout(&app, 0.0);
+        }
+
+        info!(
+            "Tree building completed for PID: {}. Stats: elements={}, depth={}, errors={}",
+            pid, builder.elements_processed, builder.max_depth_reached, builder.errors_encountered
+        );
+        if builder.truncated_subtrees > 0 {
+            warn!(
+                "Tree for PID {} is partial: {} subtrees not fully visited within {:?}",
+                pid, builder.truncated_subtrees, config.max_total_duration
+            );
+        }
+
+        Ok(tree)
     }
 
     async fn get_active_monitor_name(&self) -> Result<String, AutomationError> {
@@ -3232,3 +3406,161 @@ impl AccessibilityEngine for MacOSEngine {
         self
     }
 }
+
+// AXError codes for elements that simply have no children
+const K_AX_ERROR_ATTRIBUTE_UNSUPPORTED: i32 = -25205;
+const K_AX_ERROR_NO_VALUE: i32 = -25212;
+
+// Roles whose full attributes are worth loading in `PropertyLoadingMode::Smart`
+const SMART_MODE_ROLES: &[&str] = &[
+    "AXButton",
+    "AXCheckBox",
+    "AXComboBox",
+    "AXMenuItem",
+    "AXPopUpButton",
+    "AXRadioButton",
+    "AXSearchField",
+    "AXSheet",
+    "AXTextArea",
+    "AXTextField",
+    "AXWindow",
+];
+
+// Limit how long a single AX request to the element's application may block
+fn set_messaging_timeout(element: &ThreadSafeAXUIElement, seconds: f32) {
+    #[link(name = "ApplicationServices", kind = "framework")]
+    unsafe extern "C" {
+        fn AXUIElementSetMessagingTimeout(element: *mut ::std::os::raw::c_void, timeout: f32) -> i32;
+    }
+
+    let result = unsafe {
+        AXUIElementSetMessagingTimeout(
+            element.0.as_concrete_TypeRef() as *mut ::std::os::raw::c_void,
+            seconds,
+        )
+    };
+    if result != 0 {
+        debug!("Failed to set AX messaging timeout: error code {}", result);
+    }
+}
+
+// Builds `UINode` trees from AX elements according to a `TreeBuildConfig`
+struct MacOSTreeBuilder<'a> {
+    engine: &'a MacOSEngine,
+    property_mode: crate::platforms::PropertyLoadingMode,
+    batch_size: usize,
+    yield_every_n_elements: usize,
+    deadline: Option<Instant>,
+    elements_processed: usize,
+    max_depth_reached: usize,
+    errors_encountered: usize,
+    truncated_subtrees: usize,
+}
+
+impl<'a> MacOSTreeBuilder<'a> {
+    fn new(engine: &'a MacOSEngine, config: &crate::platforms::TreeBuildConfig) -> Self {
+        Self {
+            engine,
+            property_mode: config.property_mode.clone(),
+            batch_size: config.batch_size.unwrap_or(50).max(1),
+            yield_every_n_elements: config.yield_every_n_elements.unwrap_or(50).max(1),
+            deadline: config.max_total_duration.map(|d| Instant::now() + d),
+            elements_processed: 0,
+            max_depth_reached: 0,
+            errors_encountered: 0,
+            truncated_subtrees: 0,
+        }
+    }
+
+    fn deadline_passed(&self) -> bool {
+        self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    fn build(&mut self, element: &ThreadSafeAXUIElement, depth: usize) -> crate::UINode {
+        self.elements_processed += 1;
+        self.max_depth_reached = self.max_depth_reached.max(depth);
+
+        // Yield CPU periodically, AX calls are expensive for the target app too
+        if self.elements_processed % self.yield_every_n_elements == 0 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        let ui_element = self.engine.wrap_element(element.clone());
+        let attributes = self.attributes(element, &ui_element);
+        let mut node = crate::UINode {
+            id: ui_element.id(),
+            attributes,
+            path: None,
+            truncated: false,
+            status: crate::SubtreeStatus::Complete,
+            children: Vec::new(),
+        };
+
+        // Out of time: keep this node, but don't descend any further
+        if self.deadline_passed() {
+            self.truncated_subtrees += 1;
+            node.truncated = true;
+            node.status = crate::SubtreeStatus::TimedOut;
+            return node;
+        }
+
+        let children: Vec<ThreadSafeAXUIElement> = match element.0.children() {
+            Ok(children) => children
+                .iter()
+                .map(|child| ThreadSafeAXUIElement::new(child.clone()))
+                .collect(),
+            Err(accessibility::Error::Ax(code))
+                if code == K_AX_ERROR_ATTRIBUTE_UNSUPPORTED || code == K_AX_ERROR_NO_VALUE =>
+            {
+                Vec::new()
+            }
+            Err(e) => {
+                debug!("Failed to get children at depth {}: {:?}", depth, e);
+                self.errors_encountered += 1;
+                node.status = crate::SubtreeStatus::Failed;
+                Vec::new()
+            }
+        };
+
+        'batches: for batch in children.chunks(self.batch_size) {
+            for child in batch {
+                if self.deadline_passed() {
+                    node.status = crate::SubtreeStatus::TimedOut;
+                    self.truncated_subtrees += 1;
+                    break 'batches;
+                }
+                node.children.push(self.build(child, depth + 1));
+            }
+
+            // Small yield between full batches to keep the target app responsive
+            if batch.len() == self.batch_size && children.len() > self.batch_size {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        }
+
+        node.truncated =
+            !node.status.is_complete() || node.children.iter().any(|child| child.truncated);
+        node
+    }
+
+    fn attributes(&self, element: &ThreadSafeAXUIElement, ui_element: &UIElement) -> UIElementAttributes {
+        let load_all = match self.property_mode {
+            crate::platforms::PropertyLoadingMode::Fast => false,
+            crate::platforms::PropertyLoadingMode::Complete => true,
+            crate::platforms::PropertyLoadingMode::Smart => element
+                .0
+                .role()
+                .map(|role| SMART_MODE_ROLES.contains(&role.to_string().as_str()))
+                .unwrap_or(false),
+        };
+        if load_all {
+            ui_element.attributes()
+        } else {
+            UIElementAttributes {
+                role: ui_element.role(),
+                name: ui_element.name().filter(|name| !name.is_empty()),
+                ..Default::default()
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Accessibility and Screen Recording checks through the TCC APIs
+
+use core_foundation::base::TCFType;
+use core_foundation::boolean::CFBoolean;
+use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+use core_foundation::string::CFString;
+
+use crate::permissions::{PermissionState, PermissionStatus};
+
+#[link(name = "ApplicationServices", kind = "framework")]
+unsafe extern "C" {
+    fn AXIsProcessTrustedWithOptions(options: CFDictionaryRef) -> bool;
+}
+
+// Available since macOS 10.15
+#[link(name = "CoreGraphics", kind = "framework")]
+unsafe extern "C" {
+    fn CGPreflightScreenCaptureAccess() -> bool;
+    fn CGRequestScreenCaptureAccess() -> bool;
+}
+
+/// Whether the process may use the accessibility API. With `prompt`, macOS
+/// asks the user to grant it if it has not asked before
+pub(crate) fn accessibility_trusted(prompt: bool) -> bool {
+    let key = CFString::new("AXTrustedCheckOptionPrompt");
+    let value = if prompt {
+        CFBoolean::true_value()
+    } else {
+        CFBoolean::false_value()
+    };
+    let options = CFDictionary::from_CFType_pairs(&[(key.as_CFType(), value.as_CFType())]);
+    unsafe { AXIsProcessTrustedWithOptions(options.as_concrete_TypeRef()) }
+}
+
+/// Whether the process may capture the screen. With `prompt`, macOS asks
+/// the user to grant it if it has not asked before
+pub(crate) fn screen_capture_allowed(prompt: bool) -> bool {
+    unsafe {
+        if prompt {
+            CGRequestScreenCaptureAccess()
+        } else {
+            CGPreflightScreenCaptureAccess()
+        }
+    }
+}
+
+pub(crate) fn status(prompt: bool) -> PermissionStatus {
+    let state = |granted: bool| match granted {
+        true => PermissionState::Granted,
+        false => PermissionState::Denied,
+    };
+    PermissionStatus {
+        accessibility: state(accessibility_trusted(prompt)),
+        screen_recording: state(screen_capture_allowed(prompt)),
+    }
+}
@@ -1,4 +1,13 @@
+use crate::console::ConsoleScreen;
+use crate::environment::EnvironmentInfo;
+use crate::hotkey::{Hotkey, HotkeyCallback, HotkeyRegistration};
+use crate::ocr::OcrWord;
+use crate::selector_cache::AppKey;
+use crate::session::SessionState;
+use crate::ui_events::{UIEventCallback, UIEventKind, UIEventSubscription};
+use crate::virtual_desktop::VirtualDesktop;
 use crate::{AutomationError, Selector, UIElement, UINode};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -13,6 +22,63 @@ pub struct TreeBuildConfig {
     pub yield_every_n_elements: Option<usize>,
     /// Optional batch size for processing elements
     pub batch_size: Option<usize>,
+    /// Optional time budget for the whole tree. When it runs out, the nodes
+    /// built so far are returned and the unvisited ones marked as truncated
+    pub max_total_duration: Option<Duration>,
+    /// Fetch each element's children with the properties of `property_mode`
+    /// in one cross-process call (UIA CacheRequest), where the platform
+    /// supports it
+    pub use_cache_request: bool,
+}
+
+/// How a tree was built, from [`AccessibilityEngine::get_window_tree_with_stats`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TreeBuildStats {
+    /// Nodes in the tree
+    pub elements: usize,
+    /// Depth of the deepest node, the root being 0
+    pub max_depth: usize,
+    /// Elements whose attributes were read from a cache request
+    pub cached_elements: usize,
+    /// Cache requests made, one per element whose children were fetched
+    pub cache_requests: usize,
+    /// Lookups the cache could not serve, done without it
+    pub uncached_fallbacks: usize,
+    pub errors: usize,
+    /// Subtrees left incomplete when the time budget ran out
+    pub truncated_subtrees: usize,
+    pub duration_ms: u64,
+}
+
+impl TreeBuildStats {
+    /// Figures that can be read off a finished tree, for engines that do not
+    /// track their own
+    pub fn for_tree(tree: &UINode, duration: Duration) -> Self {
+        fn walk(node: &UINode, depth: usize, stats: &mut TreeBuildStats) {
+            stats.elements += 1;
+            stats.max_depth = stats.max_depth.max(depth);
+            if !node.status.is_complete() {
+                stats.truncated_subtrees += 1;
+            }
+            for child in &node.children {
+                walk(child, depth + 1, stats);
+            }
+        }
+        let mut stats = TreeBuildStats {
+            duration_ms: duration.as_millis() as u64,
+            ..Default::default()
+        };
+        walk(tree, 0, &mut stats);
+        stats
+    }
+
+    /// Share of the elements served from the cache, from 0.0 to 1.0
+    pub fn cache_hit_rate(&self) -> f64 {
+        if self.elements == 0 {
+            return 0.0;
+        }
+        self.cached_elements as f64 / self.elements as f64
+    }
 }
 
 /// Defines how much element property data to load
@@ -33,6 +99,8 @@ impl Default for TreeBuildConfig {
             timeout_per_operation_ms: Some(50),
             yield_every_n_elements: Some(50),
             batch_size: Some(50),
+            max_total_duration: None,
+            use_cache_request: true,
         }
     }
 }
@@ -142,9 +210,174 @@ pub trait AccessibilityEngine: Send + Sync {
         config: TreeBuildConfig
     ) -> Result<UINode, AutomationError>;
 
+    /// `get_window_tree`, with figures on how the tree was built
+    fn get_window_tree_with_stats(
+        &self,
+        pid: u32,
+        title: Option<&str>,
+        config: TreeBuildConfig,
+    ) -> Result<(UINode, TreeBuildStats), AutomationError> {
+        let start = std::time::Instant::now();
+        let tree = self.get_window_tree(pid, title, config)?;
+        let stats = TreeBuildStats::for_tree(&tree, start.elapsed());
+        Ok((tree, stats))
+    }
+
+    /// `get_window_tree`, keeping the element behind each node
+    fn get_window_tree_with_handles(
+        &self,
+        _pid: u32,
+        _title: Option<&str>,
+        _config: TreeBuildConfig,
+    ) -> Result<crate::UINodeWithHandles, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "get_window_tree_with_handles is only supported on Windows".to_string(),
+        ))
+    }
+
     /// Get the name of the currently active monitor
     async fn get_active_monitor_name(&self) -> Result<String, AutomationError>;
 
+    /// Build the UI trees of all visible top-level windows, concurrently where supported
+    fn get_all_window_trees(
+        &self,
+        _config: TreeBuildConfig,
+    ) -> Result<Vec<(crate::WindowInfo, UINode)>, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "get_all_window_trees is only supported on Windows".to_string(),
+        ))
+    }
+
+    /// The window `get_window_tree` builds its tree from
+    fn find_window(&self, _pid: u32, _title: Option<&str>) -> Result<UIElement, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "find_window is only supported on Windows".to_string(),
+        ))
+    }
+
+    /// Executable name of process `pid`, without extension
+    fn process_name(&self, _pid: u32) -> Result<String, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "process_name is only supported on Windows".to_string(),
+        ))
+    }
+
+    /// Attributes of `element` as a tree built with `mode` holds them
+    fn tree_node_attributes(
+        &self,
+        element: &UIElement,
+        _mode: &PropertyLoadingMode,
+    ) -> crate::UIElementAttributes {
+        element.attributes()
+    }
+
+    /// Get the state of the desktop session this process runs in.
+    /// Platforms without session detection report `SessionState::Unknown`.
+    fn session_state(&self) -> Result<SessionState, AutomationError> {
+        Ok(SessionState::Unknown)
+    }
+
+    /// Move the current (disconnected) remote session to the physical console
+    /// so that it stays interactive without an RDP client attached.
+    fn attach_to_console(&self) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "attach_to_console is only supported on Windows".to_string(),
+        ))
+    }
+
+    /// Read the visible screen buffer of the console attached to `pid`
+    fn read_console(&self, _pid: u32) -> Result<ConsoleScreen, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "read_console is only supported on Windows".to_string(),
+        ))
+    }
+
+    /// Write `text` as keyboard input to the console attached to `pid`
+    fn send_console_input(&self, _pid: u32, _text: &str) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "send_console_input is only supported on Windows".to_string(),
+        ))
+    }
+
+    /// OS version, theme, scaling and accessibility settings. Platforms fill
+    /// in what they can read
+    fn environment(&self) -> Result<EnvironmentInfo, AutomationError> {
+        Ok(EnvironmentInfo::unknown())
+    }
+
+    /// Highlight several elements at once. Platforms without a shared overlay
+    /// highlight them one by one
+    fn highlight_all(
+        &self,
+        elements: &[UIElement],
+        color: Option<u32>,
+        duration: Option<Duration>,
+    ) -> Result<(), AutomationError> {
+        for element in elements {
+            element.highlight(color, duration)?;
+        }
+        Ok(())
+    }
+
+    /// Executable and file version of the process `pid`, used to key
+    /// learned selectors
+    fn app_key(&self, _pid: u32) -> Result<AppKey, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "app_key is only supported on Windows".to_string(),
+        ))
+    }
+
+    /// Deliver UI events of the given kinds to `callback` from a background thread
+    fn subscribe_ui_events(
+        &self,
+        _kinds: &[UIEventKind],
+        _callback: UIEventCallback,
+    ) -> Result<UIEventSubscription, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "subscribe_ui_events is only supported on Windows".to_string(),
+        ))
+    }
+
+    /// Register a system-wide hotkey whose callback runs on a dispatcher thread
+    fn register_hotkey(
+        &self,
+        _hotkey: Hotkey,
+        _callback: HotkeyCallback,
+    ) -> Result<HotkeyRegistration, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "register_hotkey is only supported on Windows".to_string(),
+        ))
+    }
+
+    /// Wait until process `pid` has finished starting up and its UI thread
+    /// answers messages
+    fn wait_until_responsive(&self, _pid: u32, _timeout: Duration) -> Result<(), AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "wait_for_idle is only supported on Windows".to_string(),
+        ))
+    }
+
+    /// The deepest element at a screen point, in physical pixels
+    fn element_at_point(&self, _x: f64, _y: f64) -> Result<UIElement, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "element_at_point is only supported on Windows".to_string(),
+        ))
+    }
+
+    /// Words recognized in a screenshot, with their positions
+    fn ocr_words(&self, _screenshot: &crate::ScreenshotResult) -> Result<Vec<OcrWord>, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "find_text_on_screen is only supported on Windows".to_string(),
+        ))
+    }
+
+    /// Virtual desktops in the order the user arranged them
+    fn virtual_desktops(&self) -> Result<Vec<VirtualDesktop>, AutomationError> {
+        Err(AutomationError::UnsupportedOperation(
+            "virtual_desktops is only supported on Windows".to_string(),
+        ))
+    }
+
     /// Enable downcasting to concrete engine types
     fn as_any(&self) -> &dyn std::any::Any;
 }
@@ -154,9 +387,33 @@ pub mod linux;
 #[cfg(target_os = "macos")]
 pub mod macos;
 #[cfg(target_os = "macos")]
+pub(crate) mod macos_permissions;
+#[cfg(target_os = "macos")]
 pub mod tree_search;
 #[cfg(target_os = "windows")]
 pub mod windows;
+#[cfg(target_os = "windows")]
+mod windows_cache;
+#[cfg(target_os = "windows")]
+pub(crate) mod windows_elevation;
+#[cfg(target_os = "windows")]
+mod windows_hotkeys;
+#[cfg(target_os = "windows")]
+mod windows_idle;
+#[cfg(target_os = "windows")]
+pub(crate) mod windows_input_guard;
+#[cfg(target_os = "windows")]
+mod windows_msaa;
+#[cfg(target_os = "windows")]
+mod windows_ocr;
+#[cfg(target_os = "windows")]
+mod windows_overlay;
+#[cfg(target_os = "windows")]
+mod windows_text;
+#[cfg(target_os = "windows")]
+mod windows_virtual_desktops;
+#[cfg(target_os = "windows")]
+pub(crate) mod windows_window_events;
 #[cfg(all(target_os = "windows", test))]
 pub mod windows_tests;
 
@@ -197,3 +454,28 @@ pub fn create_engine(
         ))
     }
 }
+
+/// Span for one hop of a selector chain, so profiles show which hop dominates.
+/// The root element is only described when the span is actually recorded
+pub(crate) fn selector_hop_span(
+    hop: usize,
+    hops: usize,
+    selector: &Selector,
+    root: Option<&UIElement>,
+) -> tracing::Span {
+    let span = tracing::debug_span!(
+        "selector_hop",
+        hop = hop + 1,
+        hops,
+        selector = ?selector,
+        root.role = tracing::field::Empty,
+        root.name = tracing::field::Empty,
+    );
+    if !span.is_disabled() {
+        if let Some(root) = root {
+            span.record("root.role", root.role().as_str());
+            span.record("root.name", root.name_or_empty().as_str());
+        }
+    }
+    span
+}
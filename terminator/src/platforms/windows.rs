@@ -1,8 +1,15 @@
 use crate::element::UIElementImpl;
+use crate::console::{self, ConsoleScreen};
+use crate::environment::{EnvironmentInfo, Theme};
+use crate::hotkey::{Hotkey, HotkeyCallback, HotkeyRegistration};
+use crate::selector_cache::AppKey;
 use crate::platforms::AccessibilityEngine;
+use crate::session::{tscon_error, InputDesktop, SessionState};
+use crate::ui_events::{UIEvent, UIEventCallback, UIEventKind, UIEventSubscription};
 use crate::utils::normalize;
+use super::windows_cache::{add_mode_attributes, TreeCache};
 use crate::{AutomationError, Locator, Selector, UIElement, UIElementAttributes};
-use crate::{ClickResult, ScreenshotResult};
+use crate::{ActionStrategy, ClickResult, ScreenshotResult};
 use image::DynamicImage;
 use image::{ImageBuffer, Rgba};
 use serde_json::Value;
@@ -18,10 +25,12 @@ use tracing::info;
 use tracing::warn;
 use uiautomation::UIAutomation;
 use uiautomation::controls::ControlType;
-use uiautomation::filters::{ClassNameFilter, ControlTypeFilter, NameFilter, OrFilter};
+use uiautomation::filters::{ClassNameFilter, ControlTypeFilter, MatcherFilter, NameFilter, OrFilter};
 use uiautomation::inputs::Mouse;
 use uiautomation::patterns;
-use uiautomation::types::{Point, TreeScope, UIProperty};
+use uiautomation::types::{
+    ExpandCollapseState, Point, ToggleState, TreeScope, UIProperty, WindowVisualState,
+};
 use uiautomation::variants::Variant;
 use uni_ocr::{OcrEngine, OcrProvider};
 
@@ -38,6 +47,46 @@ use windows::Win32::System::Com::CLSCTX_ALL;
 use windows::Win32::System::Com::CoCreateInstance;
 use windows::Win32::System::Com::CoInitializeEx;
 use windows::Win32::System::Com::COINIT_MULTITHREADED;
+use windows::Win32::System::Com::CoUninitialize;
+
+use windows::Win32::System::RemoteDesktop::ProcessIdToSessionId;
+use windows::Win32::System::RemoteDesktop::WTSConnectState;
+use windows::Win32::System::RemoteDesktop::WTSDisconnected;
+use windows::Win32::System::RemoteDesktop::WTSFreeMemory;
+use windows::Win32::System::RemoteDesktop::WTSQuerySessionInformationW;
+use windows::Win32::System::RemoteDesktop::WTS_CONNECTSTATE_CLASS;
+use windows::Win32::System::RemoteDesktop::WTS_CURRENT_SERVER_HANDLE;
+use windows::Win32::System::RemoteDesktop::WTS_CURRENT_SESSION;
+
+use windows::Win32::System::StationsAndDesktops::CloseDesktop;
+use windows::Win32::System::StationsAndDesktops::DESKTOP_CONTROL_FLAGS;
+use windows::Win32::System::StationsAndDesktops::DESKTOP_SWITCHDESKTOP;
+use windows::Win32::System::StationsAndDesktops::GetUserObjectInformationW;
+use windows::Win32::System::StationsAndDesktops::OpenInputDesktop;
+use windows::Win32::System::StationsAndDesktops::UOI_NAME;
+
+use windows::Win32::Foundation::GENERIC_READ;
+use windows::Win32::Foundation::GENERIC_WRITE;
+use windows::Win32::Storage::FileSystem::CreateFileW;
+use windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES;
+use windows::Win32::Storage::FileSystem::FILE_SHARE_READ;
+use windows::Win32::Storage::FileSystem::FILE_SHARE_WRITE;
+use windows::Win32::Storage::FileSystem::OPEN_EXISTING;
+
+use windows::Win32::System::Console::ATTACH_PARENT_PROCESS;
+use windows::Win32::System::Console::AttachConsole;
+use windows::Win32::System::Console::CONSOLE_SCREEN_BUFFER_INFO;
+use windows::Win32::System::Console::COORD;
+use windows::Win32::System::Console::FreeConsole;
+use windows::Win32::System::Console::GetConsoleScreenBufferInfo;
+use windows::Win32::System::Console::GetConsoleWindow;
+use windows::Win32::System::Console::INPUT_RECORD;
+use windows::Win32::System::Console::INPUT_RECORD_0;
+use windows::Win32::System::Console::KEY_EVENT;
+use windows::Win32::System::Console::KEY_EVENT_RECORD;
+use windows::Win32::System::Console::KEY_EVENT_RECORD_0;
+use windows::Win32::System::Console::ReadConsoleOutputCharacterW;
+use windows::Win32::System::Console::WriteConsoleInputW;
 
 use windows::Win32::System::Diagnostics::ToolHelp::CreateToolhelp32Snapshot;
 use windows::Win32::System::Diagnostics::ToolHelp::Process32FirstW;
@@ -46,6 +95,7 @@ use windows::Win32::System::Diagnostics::ToolHelp::PROCESSENTRY32W;
 use windows::Win32::System::Diagnostics::ToolHelp::TH32CS_SNAPPROCESS;
 
 use windows::Win32::System::Threading::CREATE_NEW_CONSOLE;
+use windows::Win32::System::Threading::GetCurrentProcessId;
 use windows::Win32::System::Threading::CreateProcessW;
 use windows::Win32::System::Threading::PROCESS_INFORMATION;
 use windows::Win32::System::Threading::STARTUPINFOW;
@@ -58,6 +108,26 @@ use windows::Win32::UI::Shell::IApplicationActivationManager;
 // Define a default timeout duration
 const DEFAULT_FIND_TIMEOUT: Duration = Duration::from_millis(5000);
 
+// How long to wait for an application's window to show up in the window registry
+const WINDOW_REGISTRY_TIMEOUT: Duration = Duration::from_millis(5000);
+
+// Matcher search after the window registry had no match: the window is
+// usually there already, with a title not containing the name
+const FALLBACK_MATCHER_TIMEOUT: Duration = Duration::from_millis(1000);
+
+// How long to wait for a UWP app to get past its splash screen
+const UWP_LAUNCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+// Window classes hosting UWP / WinUI content. Their children are sometimes only
+// reachable through the raw view (XAML islands, CoreWindow behind ApplicationFrameHost)
+const XAML_HOST_CLASS_NAMES: &[&str] = &[
+    "ApplicationFrameWindow",
+    "Windows.UI.Core.CoreWindow",
+    "DesktopWindowXamlSource",
+    "Windows.UI.Composition.DesktopWindowContentBridge",
+    "Microsoft.UI.Content.DesktopChildSiteBridge",
+];
+
 // List of common browser process names (without .exe)
 const KNOWN_BROWSER_PROCESS_NAMES: &[&str] = &[
     "chrome", "firefox", "msedge", "edge", "iexplore", "opera", "brave", "vivaldi", "browser", "arc", "explorer"
@@ -128,6 +198,15 @@ impl Drop for HandleGuard {
     }
 }
 
+// RAII guard balancing a successful CoInitializeEx on the current thread
+struct ComGuard;
+
+impl Drop for ComGuard {
+    fn drop(&mut self) {
+        unsafe { CoUninitialize() };
+    }
+}
+
 // thread-safety
 #[derive(Clone)]
 pub struct ThreadSafeWinUIAutomation(Arc<UIAutomation>);
@@ -164,7 +243,7 @@ impl WindowsEngine {
         }
 
         let automation =
-            UIAutomation::new_direct().map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+            UIAutomation::new_direct().map_err(AutomationError::from)?;
         let arc_automation = ThreadSafeWinUIAutomation(Arc::new(automation));
         Ok(Self {
             automation: arc_automation,
@@ -173,6 +252,24 @@ impl WindowsEngine {
         })
     }
 
+    // UIA element of the window `matches` accepts in the window registry
+    fn window_from_registry(
+        &self,
+        timeout: Duration,
+        matches: impl Fn(&super::windows_window_events::WindowEntry) -> bool,
+    ) -> Option<uiautomation::UIElement> {
+        let window = super::windows_window_events::wait_for_window(timeout, matches)?;
+        let hwnd = windows::Win32::Foundation::HWND(window.hwnd as *mut _);
+        match self.automation.0.element_from_handle(hwnd.into()) {
+            Ok(ele) => Some(ele),
+            Err(e) => {
+                // Closed since it was registered
+                debug!("Window {:?} from registry is gone: {}", window.title, e);
+                None
+            }
+        }
+    }
+
     /// Extract browser-specific information from window titles
     pub fn extract_browser_info(title: &str) -> (bool, Vec<String>) {
         let title_lower = title.to_lowercase();
@@ -300,6 +397,193 @@ impl WindowsEngine {
         
         best_match.map(|window| (window, best_score))
     }
+
+    /// The tree of a window with its build stats and, with `keep_handles`,
+    /// the element behind each node in depth-first order
+    fn build_window_tree(
+        &self,
+        pid: u32,
+        title: Option<&str>,
+        config: crate::platforms::TreeBuildConfig,
+        keep_handles: bool,
+    ) -> Result<(crate::UINode, crate::platforms::TreeBuildStats, Vec<UIElement>), AutomationError> {
+        info!("Getting window tree for PID: {} and title: {:?} with config: {:?}", pid, title, config);
+        let start = std::time::Instant::now();
+        let selected_window = self.find_window_for_pid(pid, title)?;
+
+        // Wrap the raw OS element into our UIElement
+        let mut window_element_wrapper = UIElement::new(Box::new(WindowsUIElement {
+            element: ThreadSafeWinUIElement(Arc::new(selected_window)),
+        }));
+
+        // Build the UI tree with configurable performance optimizations
+        info!("Building UI tree with config: {:?}", config);
+        
+        // Use configured tree building approach
+        let mut context = TreeBuildingContext::new(&config);
+        if keep_handles {
+            context.handles = Some(Vec::new());
+        }
+        context.cache = TreeCache::for_config(&self.automation.0, &config);
+        if let Some(cached) = context.cache.as_ref().and_then(|cache| cache.refresh(&window_element_wrapper)) {
+            window_element_wrapper = cached;
+        }
+        
+        let mut result = build_ui_node_tree_configurable(&window_element_wrapper, 0, &mut context)?;
+        assign_tree_paths(&mut result);
+        let stats = context.stats(start.elapsed());
+        
+        info!("Tree building completed for PID: {}. Stats: elements={}, depth={}, cached={}, cache_requests={}, fallbacks={}, errors={}", 
+              pid, stats.elements, stats.max_depth, stats.cached_elements,
+              stats.cache_requests, stats.uncached_fallbacks, stats.errors);
+        if context.truncated_subtrees > 0 {
+            warn!("Tree for PID {} is partial: {} subtrees not fully visited within {:?}",
+                  pid, context.truncated_subtrees, config.max_total_duration);
+        }
+        
+        // Log cache effectiveness
+        info!("Cache hit rate: {:.1}%", stats.cache_hit_rate() * 100.0);
+        
+        Ok((result, stats, context.handles.unwrap_or_default()))
+    }
+
+    /// The top-level window of process `pid` that window trees are built
+    /// from, the one best matching `title` if given
+    fn find_window_for_pid(
+        &self,
+        pid: u32,
+        title: Option<&str>,
+    ) -> Result<uiautomation::UIElement, AutomationError> {
+        let root_ele_os = self.automation.0.get_root_element().map_err(|e| {
+            error!("Failed to get root element: {}", e);
+            uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get root element: {}", e)))
+        })?;
+
+        // Find all windows for the given process ID
+        // Search for both Window and Pane control types since some applications use panes as main containers
+        let window_matcher = self
+            .automation
+            .0
+            .create_matcher()
+            .from_ref(&root_ele_os)
+            .filter(Box::new(OrFilter {
+                left: Box::new(ControlTypeFilter {
+                    control_type: ControlType::Window,
+                }),
+                right: Box::new(ControlTypeFilter {
+                    control_type: ControlType::Pane,
+                }),
+            }))
+            .depth(3)
+            .timeout(3000);
+
+        let windows = window_matcher.find_all().map_err(|e| {
+            error!("Failed to find windows: {}", e);
+            uia_error(e, |e| AutomationError::ElementNotFound(format!("Failed to find windows: {}", e)))
+        })?;
+
+        info!("Found {} total windows, filtering by PID: {}", windows.len(), pid);
+
+        // Filter windows by process ID first
+        let mut pid_matching_windows = Vec::new();
+        let mut window_debug_info = Vec::new(); // For debugging
+
+        for window in windows {
+            match window.get_process_id() {
+                Ok(window_pid) => {
+                    let window_name = window.get_name().unwrap_or_else(|_| "Unknown".to_string());
+                    window_debug_info.push(format!("PID: {}, Name: {}", window_pid, window_name));
+                    
+                    if window_pid == pid || uwp_frame_hosts_pid(&window, pid) {
+                        pid_matching_windows.push((window, window_name));
+                    }
+                }
+                Err(e) => {
+                    debug!("Failed to get process ID for window: {}", e);
+                }
+            }
+        }
+
+        if pid_matching_windows.is_empty() {
+            error!("No windows found for PID: {}", pid);
+            debug!("Available windows: {:?}", window_debug_info);
+            return Err(AutomationError::ElementNotFound(format!(
+                "No windows found for process ID {}. Available windows: {:?}",
+                pid, window_debug_info
+            )));
+        }
+
+        info!("Found {} windows for PID: {}", pid_matching_windows.len(), pid);
+
+        // Enhanced title matching logic for PID-based search
+        let selected_window = if let Some(title) = title {
+            info!("Filtering {} windows by title: '{}'", pid_matching_windows.len(), title);
+            
+            // Use the enhanced title matching helper
+            match self.find_best_title_match(&pid_matching_windows, title) {
+                Some((window, score)) => {
+                    if score < 1.0 {
+                        info!("Using best match with similarity {:.2} for PID {}: '{}'", 
+                              score, pid, window.get_name().unwrap_or_default());
+                    }
+                    window
+                }
+                None => {
+                    let window_names: Vec<&String> = pid_matching_windows.iter().map(|(_, name)| name).collect();
+                    warn!("No good title match found for '{}' in PID {}, falling back to first window. Available: {:?}", 
+                          title, pid, window_names);
+                    pid_matching_windows[0].0.clone()
+                }
+            }
+        } else {
+            info!("No title filter provided, using first window with PID {}", pid);
+            pid_matching_windows[0].0.clone()
+        };
+            
+        let selected_window_name = selected_window.get_name().unwrap_or_else(|_| "Unknown".to_string());
+        info!("Selected window: '{}' for PID: {} (title filter: {:?})", 
+              selected_window_name, pid, title);
+
+        Ok(selected_window)
+    }
+
+    // Searches below a XAML host whose content only the raw view shows, as
+    // `children` does: from each island root, which may match itself
+    fn find_in_xaml_islands(
+        &self,
+        selector: &Selector,
+        islands: Vec<uiautomation::UIElement>,
+        timeout: Option<Duration>,
+        depth: Option<usize>,
+    ) -> Result<Vec<UIElement>, AutomationError> {
+        let predicate = selector.check_element_predicate().is_ok().then(|| selector.localized());
+        let mut found = Vec::new();
+        for island in islands {
+            if predicate.as_ref().is_some_and(|predicate| element_matches(predicate, &island)) {
+                found.push(wrap_uia_element(island.clone()));
+            }
+            let island = wrap_uia_element(island);
+            match self.find_elements(selector, Some(&island), timeout, depth) {
+                Ok(elements) => found.extend(elements),
+                Err(e) if e.is_element_not_found() => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(found)
+    }
+}
+
+// The island roots to search from when `root` is a XAML host hiding its
+// content from the control view
+fn xaml_islands_below(root: &UIElement) -> Option<Vec<uiautomation::UIElement>> {
+    let host = root.as_any().downcast_ref::<WindowsUIElement>()?;
+    xaml_island_roots(&*host.element.0)
+}
+
+fn wrap_uia_element(element: uiautomation::UIElement) -> UIElement {
+    UIElement::new(Box::new(WindowsUIElement {
+        element: ThreadSafeWinUIElement(Arc::new(element)),
+    }))
 }
 
 #[async_trait::async_trait]
@@ -319,7 +603,7 @@ impl AccessibilityEngine for WindowsEngine {
             .unwrap();
         let ele = root_element
             .find_first(TreeScope::Subtree, &condition)
-            .map_err(|e| AutomationError::ElementNotFound(e.to_string()))?;
+            .map_err(|e| uia_error(e, AutomationError::ElementNotFound))?;
         let arc_ele = ThreadSafeWinUIElement(Arc::new(ele));
 
         Ok(UIElement::new(Box::new(WindowsUIElement {
@@ -332,7 +616,26 @@ impl AccessibilityEngine for WindowsEngine {
             .automation
             .0
             .get_focused_element()
-            .map_err(|e| AutomationError::ElementNotFound(e.to_string()))?;
+            .map_err(|e| uia_error(e, AutomationError::ElementNotFound))?;
+        let arc_element = ThreadSafeWinUIElement(Arc::new(element));
+
+        Ok(UIElement::new(Box::new(WindowsUIElement {
+            element: arc_element,
+        })))
+    }
+
+    fn wait_until_responsive(&self, pid: u32, timeout: Duration) -> Result<(), AutomationError> {
+        crate::platforms::windows_idle::wait_until_responsive(pid, timeout)
+    }
+
+    fn element_at_point(&self, x: f64, y: f64) -> Result<UIElement, AutomationError> {
+        let element = self
+            .automation
+            .0
+            .element_from_point(Point::new(x.round() as i32, y.round() as i32))
+            .map_err(|e| {
+                uia_error(e, |e| AutomationError::ElementNotFound(format!("No element at ({}, {}): {}", x, y, e)))
+            })?;
         let arc_element = ThreadSafeWinUIElement(Arc::new(element));
 
         Ok(UIElement::new(Box::new(WindowsUIElement {
@@ -340,6 +643,14 @@ impl AccessibilityEngine for WindowsEngine {
         })))
     }
 
+    fn ocr_words(&self, screenshot: &ScreenshotResult) -> Result<Vec<crate::ocr::OcrWord>, AutomationError> {
+        crate::platforms::windows_ocr::recognize_words(screenshot)
+    }
+
+    fn virtual_desktops(&self) -> Result<Vec<crate::VirtualDesktop>, AutomationError> {
+        crate::platforms::windows_virtual_desktops::list()
+    }
+
     fn get_applications(&self) -> Result<Vec<UIElement>, AutomationError> {
         let root = self.automation.0.get_root_element().unwrap();
         let condition = self
@@ -353,7 +664,7 @@ impl AccessibilityEngine for WindowsEngine {
             .unwrap();
         let elements = root
             .find_all(TreeScope::Subtree, &condition)
-            .map_err(|e| AutomationError::ElementNotFound(e.to_string()))?;
+            .map_err(|e| uia_error(e, AutomationError::ElementNotFound))?;
         let arc_elements: Vec<UIElement> = elements
             .into_iter()
             .map(|ele| {
@@ -375,9 +686,25 @@ impl AccessibilityEngine for WindowsEngine {
             .unwrap_or(name);
         debug!("using search name: {}", search_name);
 
-        // first find element by matcher
         let root_ele = self.automation.0.get_root_element().unwrap();
         let search_name_norm = normalize(search_name);
+
+        // first wait for a window with that title in the window registry, or
+        // of the process with that name when it is already running
+        let running_pid = get_pid_by_name(search_name).map(|pid| pid as u32);
+        if let Some(ele) = self.window_from_registry(WINDOW_REGISTRY_TIMEOUT, |w| {
+            normalize(&w.title).contains(&search_name_norm) || Some(w.pid) == running_pid
+        }) {
+            return Ok(convert_uiautomation_element_to_terminator(ele));
+        }
+
+        // then by matcher, in case the hooks are unavailable or the name is
+        // not in the title
+        let matcher_timeout = if super::windows_window_events::is_running() {
+            FALLBACK_MATCHER_TIMEOUT
+        } else {
+            WINDOW_REGISTRY_TIMEOUT
+        };
         let matcher = self
             .automation
             .0
@@ -389,10 +716,10 @@ impl AccessibilityEngine for WindowsEngine {
             }))
             .from_ref(&root_ele)
             .depth(7)
-            .timeout(5000);
+            .timeout(matcher_timeout.as_millis() as u64);
         let ele_res = matcher
             .find_first()
-            .map_err(|e| AutomationError::ElementNotFound(e.to_string()));
+            .map_err(|e| uia_error(e, AutomationError::ElementNotFound));
 
         // fallback to find by pid
         let ele = match ele_res {
@@ -420,7 +747,7 @@ impl AccessibilityEngine for WindowsEngine {
                     .unwrap();
                 root_ele
                     .find_first(TreeScope::Subtree, &condition)
-                    .map_err(|e| AutomationError::ElementNotFound(e.to_string()))?
+                    .map_err(|e| uia_error(e, AutomationError::ElementNotFound))?
             }
         };
         let arc_ele = ThreadSafeWinUIElement(Arc::new(ele));
@@ -430,9 +757,16 @@ impl AccessibilityEngine for WindowsEngine {
     }
 
     fn get_application_by_pid(&self, pid: i32, timeout: Option<Duration>) -> Result<UIElement, AutomationError> {
+        // A window of the process already in the window registry. Only the
+        // matcher waits: UWP windows belong to ApplicationFrameHost, so the
+        // registry cannot tell when one of them opens
+        if let Some(ele) = self.window_from_registry(Duration::ZERO, |w| w.pid == pid as u32) {
+            return Ok(convert_uiautomation_element_to_terminator(ele));
+        }
+
         let root_ele = self.automation.0.get_root_element().unwrap();
         let timeout_ms = timeout.unwrap_or(DEFAULT_FIND_TIMEOUT).as_millis() as u64;
-        
+
         // Create a matcher with timeout
         let matcher = self
             .automation
@@ -449,17 +783,18 @@ impl AccessibilityEngine for WindowsEngine {
             }))
             .filter_fn(Box::new(move |e: &uiautomation::UIElement| {
                 match e.get_process_id() {
-                    Ok(element_pid) => Ok(element_pid == pid as u32),
+                    // UWP windows belong to ApplicationFrameHost, the app's pid is on the hosted CoreWindow
+                    Ok(element_pid) => Ok(element_pid == pid as u32 || uwp_frame_hosts_pid(e, pid as u32)),
                     Err(_) => Ok(false),
                 }
             }))
             .timeout(timeout_ms);
 
         let ele = matcher.find_first().map_err(|e| {
-            AutomationError::ElementNotFound(format!(
+            uia_error(e, |e| AutomationError::ElementNotFound(format!(
                 "Application with PID {} not found within {}ms timeout: {}",
                 pid, timeout_ms, e
-            ))
+            )))
         })?;
         
         let arc_ele = ThreadSafeWinUIElement(Arc::new(ele));
@@ -476,6 +811,13 @@ impl AccessibilityEngine for WindowsEngine {
         timeout: Option<Duration>,
         depth: Option<usize>,
     ) -> Result<Vec<UIElement>, AutomationError> {
+        if let Some(legacy) = root.and_then(super::windows_msaa::legacy_root) {
+            return super::windows_msaa::find_all(&legacy, selector, depth, None);
+        }
+        if let Some(islands) = root.and_then(xaml_islands_below) {
+            return self.find_in_xaml_islands(selector, islands, timeout, depth);
+        }
+
         let root_ele = if let Some(el) = root {
             if let Some(ele) = el.as_any().downcast_ref::<WindowsUIElement>() {
                 &ele.element.0
@@ -509,10 +851,10 @@ impl AccessibilityEngine for WindowsEngine {
                     .timeout(timeout_ms as u64);
                 
                 let elements = matcher_builder.find_all().map_err(|e| {
-                    AutomationError::ElementNotFound(format!(
+                    uia_error(e, |e| AutomationError::ElementNotFound(format!(
                         "Role: '{}' (mapped to {:?}), Name: {:?}, Err: {}",
                         role, win_control_type, name, e
-                    ))
+                    )))
                 })?;
 
                 debug!("found {} elements with role: {} (mapped to {:?}), name_filter: {:?}", elements.len(), role, win_control_type, name);
@@ -556,7 +898,7 @@ impl AccessibilityEngine for WindowsEngine {
                 debug!("Starting element search with timeout: {}ms", timeout_ms);
                 let elements = matcher.find_all().map_err(|e| {
                     debug!("Element search failed: {}", e);
-                    AutomationError::ElementNotFound(format!("ID: '{}', Err: {}", id, e))
+                    uia_error(e, |e| AutomationError::ElementNotFound(format!("ID: '{}', Err: {}", id, e)))
                 })?;
 
                 debug!("Found {} elements matching ID: {}", elements.len(), id);
@@ -584,11 +926,11 @@ impl AccessibilityEngine for WindowsEngine {
                     .timeout(timeout_ms as u64);
 
                 let elements = matcher.find_all().map_err(|e| {
-                    AutomationError::ElementNotFound(format!(
+                    uia_error(e, |e| AutomationError::ElementNotFound(format!(
                         "Name: '{}', Err: {}",
                         name,
                         e.to_string()
-                    ))
+                    )))
                 })?;
 
                 return Ok(elements
@@ -600,6 +942,10 @@ impl AccessibilityEngine for WindowsEngine {
                     })
                     .collect());
             }
+            Selector::NameL10n(_) => {
+                // Searched as the names of its translations
+                return self.find_elements(&selector.localized(), root, timeout, depth);
+            }
             Selector::Text(text) => {
                 let filter = OrFilter {
                     left: Box::new(NameFilter {
@@ -623,11 +969,11 @@ impl AccessibilityEngine for WindowsEngine {
 
                 // Get the first matching element
                 let elements = matcher.find_all().map_err(|e| {
-                    AutomationError::ElementNotFound(format!(
+                    uia_error(e, |e| AutomationError::ElementNotFound(format!(
                         "Text: '{}', Err: {}",
                         text,
                         e.to_string()
-                    ))
+                    )))
                 })?;
 
                 return Ok(elements
@@ -639,10 +985,9 @@ impl AccessibilityEngine for WindowsEngine {
                     })
                     .collect());
             }
-            Selector::Path(_) => {
-                return Err(AutomationError::UnsupportedOperation(
-                    "`Path` selector not supported".to_string(),
-                ));
+            Selector::Path(path) => {
+                let root = root.cloned().unwrap_or_else(|| self.get_root_element());
+                return find_by_path(path, &root, Duration::from_millis(timeout_ms as u64), depth);
             }
             Selector::NativeId(automation_id) => {    // for windows passing `UIProperty::AutomationID` as `NativeId`
                 debug!("searching for elements using AutomationId: {}", automation_id);
@@ -673,8 +1018,8 @@ impl AccessibilityEngine for WindowsEngine {
                 debug!("searching elements with timeout: {}ms", timeout_ms);
                 let elements = matcher.find_all().map_err(|e| {
                     debug!("Elements search failed: {}", e);
-                    AutomationError::ElementNotFound(format!(
-                        "AutomationId: '{}', Err: {}", automation_id, e))
+                    uia_error(e, |e| AutomationError::ElementNotFound(format!(
+                        "AutomationId: '{}', Err: {}", automation_id, e)))
                 })?;
 
                 debug!("found {} elements matching AutomationID: {}", elements.len(), automation_id);
@@ -718,6 +1063,13 @@ impl AccessibilityEngine for WindowsEngine {
                     let is_last_selector = i == selectors.len() - 1;
 
                     for root_element in &current_roots {
+                        let _hop = crate::platforms::selector_hop_span(
+                            i,
+                            selectors.len(),
+                            selector,
+                            root_element.as_ref(),
+                        )
+                        .entered();
                         // Find elements matching the current selector within the current root
                         let found_elements = self.find_elements(
                             selector,
@@ -753,6 +1105,13 @@ impl AccessibilityEngine for WindowsEngine {
                 // Convert Vec<Option<UIElement>> to Vec<UIElement> by filtering out None values
                 return Ok(current_roots.into_iter().filter_map(|x| x).collect());
             }
+            Selector::RightOf(_)
+            | Selector::LeftOf(_)
+            | Selector::Above(_)
+            | Selector::Below(_)
+            | Selector::Near { .. } => {
+                return Err(crate::spatial::standalone_error(selector));
+            }
             Selector::ClassName(classname) => {
                 debug!("searching elements by class name: {}", classname);
                 let matcher = self
@@ -766,11 +1125,38 @@ impl AccessibilityEngine for WindowsEngine {
                     .depth(depth.unwrap_or(50) as u32)
                     .timeout(timeout_ms as u64);
                 let elements = matcher.find_all().map_err(|e| {
-                    AutomationError::ElementNotFound(format!(
+                    uia_error(e, |e| AutomationError::ElementNotFound(format!(
                         "ClassName: '{}', Err: {}",
                         classname,
                         e.to_string()
-                    ))
+                    )))
+                })?;
+                return Ok(elements
+                    .into_iter()
+                    .map(|ele| {
+                        UIElement::new(Box::new(WindowsUIElement {
+                            element: ThreadSafeWinUIElement(Arc::new(ele)),
+                        }))
+                    })
+                    .collect());
+            }
+            Selector::Not(_) | Selector::And(_) | Selector::Or(_) | Selector::LocalizedName(_) => {
+                selector.check_element_predicate()?;
+                debug!("searching elements by boolean selector: {:?}", selector);
+                // Evaluated per element during the walk, so only matches are
+                // ever wrapped and returned
+                let matcher = self
+                    .automation
+                    .0
+                    .create_matcher()
+                    .from_ref(root_ele)
+                    .filter(Box::new(SelectorFilter {
+                        selector: selector.localized(),
+                    }))
+                    .depth(depth.unwrap_or(50) as u32)
+                    .timeout(timeout_ms as u64);
+                let elements = matcher.find_all().map_err(|e| {
+                    uia_error(e, |e| AutomationError::ElementNotFound(format!("{:?}, Err: {}", selector, e)))
                 })?;
                 return Ok(elements
                     .into_iter()
@@ -792,6 +1178,19 @@ impl AccessibilityEngine for WindowsEngine {
         root: Option<&UIElement>,
         timeout: Option<Duration>,
     ) -> Result<UIElement, AutomationError> {
+        if let Some(legacy) = root.and_then(super::windows_msaa::legacy_root) {
+            return super::windows_msaa::find_first(&legacy, selector, timeout.unwrap_or(DEFAULT_FIND_TIMEOUT));
+        }
+        if let Some(islands) = root.and_then(xaml_islands_below) {
+            return self
+                .find_in_xaml_islands(selector, islands, timeout, None)?
+                .into_iter()
+                .next()
+                .ok_or_else(|| {
+                    AutomationError::ElementNotFound(format!("{:?} not found in the XAML island", selector))
+                });
+        }
+
         let root_ele = if let Some(el) = root {
             if let Some(ele) = el.as_any().downcast_ref::<WindowsUIElement>() {
                 &ele.element.0
@@ -822,10 +1221,10 @@ impl AccessibilityEngine for WindowsEngine {
                     .timeout(timeout_ms as u64);
 
                 let element = matcher_builder.find_first().map_err(|e| {
-                     AutomationError::ElementNotFound(format!(
+                     uia_error(e, |e| AutomationError::ElementNotFound(format!(
                         "Role: '{}' (mapped to {:?}), Name: {:?}, Root: {:?}, Err: {}",
                         role, win_control_type, name, root, e
-                    ))
+                    )))
                 })?;
 
                 let arc_ele = ThreadSafeWinUIElement(Arc::new(element));
@@ -863,7 +1262,7 @@ impl AccessibilityEngine for WindowsEngine {
                 debug!("Starting element search with timeout: {}ms", timeout_ms);
                 let element = matcher.find_first().map_err(|e| {
                     debug!("Element search failed: {}", e);
-                    AutomationError::ElementNotFound(format!("ID: '{}', Err: {}", id, e))
+                    uia_error(e, |e| AutomationError::ElementNotFound(format!("ID: '{}', Err: {}", id, e)))
                 })?;
 
                 debug!("Found element matching ID: {}", id);
@@ -887,11 +1286,11 @@ impl AccessibilityEngine for WindowsEngine {
                     .timeout(timeout_ms as u64);
 
                 let element = matcher.find_first().map_err(|e| {
-                    AutomationError::ElementNotFound(format!(
+                    uia_error(e, |e| AutomationError::ElementNotFound(format!(
                         "Name: '{}', Err: {}",
                         name,
                         e.to_string()
-                    ))
+                    )))
                 })?;
 
                 let arc_ele = ThreadSafeWinUIElement(Arc::new(element));
@@ -899,6 +1298,10 @@ impl AccessibilityEngine for WindowsEngine {
                     element: arc_ele,
                 })));
             }
+            Selector::NameL10n(_) => {
+                // Searched as the names of its translations
+                self.find_element(&selector.localized(), root, timeout)
+            }
             Selector::Text(text) => {
                 let filter = OrFilter {
                     left: Box::new(NameFilter {
@@ -922,10 +1325,10 @@ impl AccessibilityEngine for WindowsEngine {
 
                 // Get the first matching element
                 let element = matcher.find_first().map_err(|e| {
-                    AutomationError::ElementNotFound(format!(
+                    uia_error(e, |e| AutomationError::ElementNotFound(format!(
                         "Text: '{}', Root: {:?}, Err: {}",
                         text, root, e
-                    ))
+                    )))
                 })?;
 
                 let arc_ele = ThreadSafeWinUIElement(Arc::new(element));
@@ -933,10 +1336,10 @@ impl AccessibilityEngine for WindowsEngine {
                     element: arc_ele,
                 })));
             }
-            Selector::Path(_) => {
-                return Err(AutomationError::UnsupportedOperation(
-                    "`Path` selector not supported".to_string(),
-                ));
+            Selector::Path(path) => {
+                let root = root.cloned().unwrap_or_else(|| self.get_root_element());
+                return find_by_path(path, &root, Duration::from_millis(timeout_ms as u64), None)
+                    .map(|mut elements| elements.remove(0));
             }
             Selector::NativeId(automation_id) => {    // for windows passing `UIProperty::AutomationID` as `NativeId`
                 debug!("searching for element using AutomationId: {}", automation_id);
@@ -968,8 +1371,8 @@ impl AccessibilityEngine for WindowsEngine {
 
                 let element = matcher.find_first().map_err(|e| {
                     debug!("Element search failed: {}", e);
-                    AutomationError::ElementNotFound(format!(
-                        "AutomationId: '{}', Err: {}", automation_id, e))
+                    uia_error(e, |e| AutomationError::ElementNotFound(format!(
+                        "AutomationId: '{}', Err: {}", automation_id, e)))
                 })?;
 
                 let arc_ele = ThreadSafeWinUIElement(Arc::new(element));
@@ -996,7 +1399,14 @@ impl AccessibilityEngine for WindowsEngine {
 
                 // Recursively find the element by traversing the chain.
                 let mut current_element = root.cloned();
-                for selector in selectors {
+                for (i, selector) in selectors.iter().enumerate() {
+                    let _hop = crate::platforms::selector_hop_span(
+                        i,
+                        selectors.len(),
+                        selector,
+                        current_element.as_ref(),
+                    )
+                    .entered();
                     let found_element =
                         self.find_element(selector, current_element.as_ref(), timeout)?;
                     current_element = Some(found_element);
@@ -1009,6 +1419,13 @@ impl AccessibilityEngine for WindowsEngine {
                     )
                 });
             }
+            Selector::RightOf(_)
+            | Selector::LeftOf(_)
+            | Selector::Above(_)
+            | Selector::Below(_)
+            | Selector::Near { .. } => {
+                return Err(crate::spatial::standalone_error(selector));
+            }
             Selector::ClassName(classname) => {
                 debug!("searching element by class name: {}", classname);
                 let matcher = self
@@ -1022,17 +1439,37 @@ impl AccessibilityEngine for WindowsEngine {
                     .depth(50)
                     .timeout(timeout_ms as u64);
                 let element = matcher.find_first().map_err(|e| {
-                    AutomationError::ElementNotFound(format!(
+                    uia_error(e, |e| AutomationError::ElementNotFound(format!(
                         "ClassName: '{}', Err: {}",
                         classname,
                         e.to_string()
-                    ))
+                    )))
                 })?;
                 let arc_ele = ThreadSafeWinUIElement(Arc::new(element));
                 return Ok(UIElement::new(Box::new(WindowsUIElement {
                     element: arc_ele,
                 })));
             }
+            Selector::Not(_) | Selector::And(_) | Selector::Or(_) | Selector::LocalizedName(_) => {
+                selector.check_element_predicate()?;
+                debug!("searching element by boolean selector: {:?}", selector);
+                let matcher = self
+                    .automation
+                    .0
+                    .create_matcher()
+                    .from_ref(root_ele)
+                    .filter(Box::new(SelectorFilter {
+                        selector: selector.localized(),
+                    }))
+                    .depth(50)
+                    .timeout(timeout_ms as u64);
+                let element = matcher.find_first().map_err(|e| {
+                    uia_error(e, |e| AutomationError::ElementNotFound(format!("{:?}, Err: {}", selector, e)))
+                })?;
+                return Ok(UIElement::new(Box::new(WindowsUIElement {
+                    element: ThreadSafeWinUIElement(Arc::new(element)),
+                })));
+            }
         }
     }
 
@@ -1299,7 +1736,7 @@ impl AccessibilityEngine for WindowsEngine {
             .0
             .get_root_element() // Cache root element lookup
             .map_err(|e| {
-                AutomationError::PlatformError(format!("Failed to get root element: {}", e))
+                uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get root element: {}", e)))
             })?;
 
         // Find top-level windows
@@ -1316,13 +1753,13 @@ impl AccessibilityEngine for WindowsEngine {
             .timeout(5000);
 
         let window = window_matcher.find_first().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to find top-level windows: {}", e))
+            uia_error(e, |e| AutomationError::PlatformError(format!("Failed to find top-level windows: {}", e)))
         })?;
 
         // TODO: focus part does not work (at least in browser firefox)
         // If find_first succeeds, 'window' is the UIElement. Now try to focus it.
         window.set_focus().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to set focus on window/tab: {}", e))
+            uia_error(e, |e| AutomationError::PlatformError(format!("Failed to set focus on window/tab: {}", e)))
         })?; // Map focus error
 
         Ok(()) // If focus succeeds, return Ok
@@ -1334,10 +1771,10 @@ impl AccessibilityEngine for WindowsEngine {
             .automation
             .0
             .get_focused_element()
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to get focused element: {}", e)))?;
+            .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get focused element: {}", e))))?;
 
         let pid = focused_element_raw.get_process_id().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to get process ID for focused element: {}", e))
+            uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get process ID for focused element: {}", e)))
         })?;
 
         let process_name_raw = get_process_name_by_pid(pid as i32)?;
@@ -1430,7 +1867,7 @@ impl AccessibilityEngine for WindowsEngine {
             .automation
             .0
             .get_focused_element()
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to get focused element: {}", e)))?;
+            .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get focused element: {}", e))))?;
 
         let mut current_element_arc = Arc::new(focused_element_raw);
 
@@ -1445,18 +1882,18 @@ impl AccessibilityEngine for WindowsEngine {
                     }
                 }
                 Err(e) => {
-                    return Err(AutomationError::PlatformError(format!(
+                    return Err(uia_error(e, |e| AutomationError::PlatformError(format!(
                         "Failed to get control type during window search: {}",
                         e
-                    )));
+                    ))));
                 }
             }
 
             match current_element_arc.get_cached_parent() {
                 Ok(parent_uia_element) => {
                     // Check if parent is same as current (e.g. desktop root's parent is itself)
-                    let current_runtime_id = current_element_arc.get_runtime_id().map_err(|e| AutomationError::PlatformError(format!("Failed to get runtime_id for current element: {}", e)))?;
-                    let parent_runtime_id = parent_uia_element.get_runtime_id().map_err(|e| AutomationError::PlatformError(format!("Failed to get runtime_id for parent element: {}", e)))?;
+                    let current_runtime_id = current_element_arc.get_runtime_id().map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get runtime_id for current element: {}", e))))?;
+                    let parent_runtime_id = parent_uia_element.get_runtime_id().map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get runtime_id for parent element: {}", e))))?;
 
                     if parent_runtime_id == current_runtime_id {
                         debug!("Parent element has same runtime ID as current, stopping window search.");
@@ -1484,11 +1921,11 @@ impl AccessibilityEngine for WindowsEngine {
             .automation
             .0
             .get_focused_element()
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to get focused element: {}", e)))?;
+            .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get focused element: {}", e))))?;
 
         let pid = focused_element_raw
             .get_process_id()
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to get PID for focused element: {}", e)))?;
+            .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get PID for focused element: {}", e))))?;
 
         self.get_application_by_pid(pid as i32, Some(DEFAULT_FIND_TIMEOUT))
     }
@@ -1499,15 +1936,57 @@ impl AccessibilityEngine for WindowsEngine {
         title: Option<&str>, 
         config: crate::platforms::TreeBuildConfig
     ) -> Result<crate::UINode, AutomationError> {
-        info!("Getting window tree for PID: {} and title: {:?} with config: {:?}", pid, title, config);
+        self.get_window_tree_with_stats(pid, title, config).map(|(tree, _)| tree)
+    }
+
+    fn get_window_tree_with_stats(
+        &self,
+        pid: u32,
+        title: Option<&str>,
+        config: crate::platforms::TreeBuildConfig,
+    ) -> Result<(crate::UINode, crate::platforms::TreeBuildStats), AutomationError> {
+        self.build_window_tree(pid, title, config, false)
+            .map(|(tree, stats, _)| (tree, stats))
+    }
+
+    fn get_window_tree_with_handles(
+        &self,
+        pid: u32,
+        title: Option<&str>,
+        config: crate::platforms::TreeBuildConfig,
+    ) -> Result<crate::UINodeWithHandles, AutomationError> {
+        let (tree, _, handles) = self.build_window_tree(pid, title, config, true)?;
+        crate::UINodeWithHandles::new(tree, handles)
+    }
+
+    fn find_window(&self, pid: u32, title: Option<&str>) -> Result<UIElement, AutomationError> {
+        let window = self.find_window_for_pid(pid, title)?;
+        Ok(UIElement::new(Box::new(WindowsUIElement {
+            element: ThreadSafeWinUIElement(Arc::new(window)),
+        })))
+    }
+
+    fn process_name(&self, pid: u32) -> Result<String, AutomationError> {
+        get_process_name_by_pid(pid as i32)
+    }
+
+    fn tree_node_attributes(
+        &self,
+        element: &UIElement,
+        mode: &crate::platforms::PropertyLoadingMode,
+    ) -> UIElementAttributes {
+        get_configurable_attributes(element, mode)
+    }
+
+    fn get_all_window_trees(
+        &self,
+        config: crate::platforms::TreeBuildConfig,
+    ) -> Result<Vec<(crate::WindowInfo, crate::UINode)>, AutomationError> {
         let root_ele_os = self.automation.0.get_root_element().map_err(|e| {
-            error!("Failed to get root element: {}", e);
-            AutomationError::PlatformError(format!("Failed to get root element: {}", e))
+            uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get root element: {}", e)))
         })?;
 
-        // Find all windows for the given process ID
-        // Search for both Window and Pane control types since some applications use panes as main containers
-        let window_matcher = self
+        let windows = self
             .automation
             .0
             .create_matcher()
@@ -1520,115 +1999,206 @@ impl AccessibilityEngine for WindowsEngine {
                     control_type: ControlType::Pane,
                 }),
             }))
-            .depth(3)
-            .timeout(3000);
-
-        let windows = window_matcher.find_all().map_err(|e| {
-            error!("Failed to find windows: {}", e);
-            AutomationError::ElementNotFound(format!("Failed to find windows: {}", e))
-        })?;
-
-        info!("Found {} total windows, filtering by PID: {}", windows.len(), pid);
+            .depth(2)
+            .timeout(3000)
+            .find_all()
+            .map_err(|e| uia_error(e, |e| AutomationError::ElementNotFound(format!("Failed to find windows: {}", e))))?;
 
-        // Filter windows by process ID first
-        let mut pid_matching_windows = Vec::new();
-        let mut window_debug_info = Vec::new(); // For debugging
+        // Skip minimized / hidden windows, they only add noise to a desktop snapshot
+        let mut queue: std::collections::VecDeque<(usize, crate::WindowInfo, UIElement)> = windows
+            .into_iter()
+            .filter(|w| !w.is_offscreen().unwrap_or(false))
+            .enumerate()
+            .filter_map(|(index, window)| {
+                let pid = window.get_process_id().ok()?;
+                let element = UIElement::new(Box::new(WindowsUIElement {
+                    element: ThreadSafeWinUIElement(Arc::new(window)),
+                }));
+                let info = crate::WindowInfo {
+                    pid,
+                    title: element.name().unwrap_or_default(),
+                    id: element.id(),
+                    bounds: element.bounds().ok(),
+                };
+                Some((index, info, element))
+            })
+            .collect();
 
-        for window in windows {
-            match window.get_process_id() {
-                Ok(window_pid) => {
-                    let window_name = window.get_name().unwrap_or_else(|_| "Unknown".to_string());
-                    window_debug_info.push(format!("PID: {}, Name: {}", window_pid, window_name));
-                    
-                    if window_pid == pid {
-                        pid_matching_windows.push((window, window_name));
+        let window_count = queue.len();
+        let workers = thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(4)
+            .min(window_count.max(1));
+        info!("Building {} window trees on {} worker threads", window_count, workers);
+
+        // One budget for the whole desktop, not one per window
+        let deadline = config.max_total_duration.map(|d| std::time::Instant::now() + d);
+        let queue = std::sync::Mutex::new(std::mem::take(&mut queue));
+        let results = std::sync::Mutex::new(Vec::with_capacity(window_count));
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| {
+                    // Each worker joins the multithreaded apartment before touching UIA
+                    let hr = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+                    if hr.is_err() && hr != HRESULT(0x80010106u32 as i32) {
+                        error!("Failed to initialize COM on tree worker: {}", hr);
+                        return;
                     }
-                }
-                Err(e) => {
-                    debug!("Failed to get process ID for window: {}", e);
-                }
+                    // Leave the apartment when the worker is done, after its UIA objects are gone
+                    let _com = hr.is_ok().then_some(ComGuard);
+                    loop {
+                        let next = queue.lock().ok().and_then(|mut q| q.pop_front());
+                        let Some((index, info, window)) = next else {
+                            break;
+                        };
+                        let mut context = TreeBuildingContext::new(&config);
+                        context.deadline = deadline;
+                        context.cache = UIAutomation::new_direct()
+                            .ok()
+                            .and_then(|automation| TreeCache::for_config(&automation, &config));
+                        let window = context
+                            .cache
+                            .as_ref()
+                            .and_then(|cache| cache.refresh(&window))
+                            .unwrap_or(window);
+                        match build_ui_node_tree_configurable(&window, 0, &mut context) {
+                            Ok(mut tree) => {
+                                assign_tree_paths(&mut tree);
+                                debug!(
+                                    "Built tree for '{}' (pid {}): {} elements",
+                                    info.title, info.pid, context.elements_processed
+                                );
+                                if let Ok(mut results) = results.lock() {
+                                    results.push((index, info, tree));
+                                }
+                            }
+                            Err(e) => warn!("Skipping window '{}' (pid {}): {}", info.title, info.pid, e),
+                        }
+                    }
+                });
             }
-        }
+        });
 
-        if pid_matching_windows.is_empty() {
-            error!("No windows found for PID: {}", pid);
-            debug!("Available windows: {:?}", window_debug_info);
-            return Err(AutomationError::ElementNotFound(format!(
-                "No windows found for process ID {}. Available windows: {:?}",
-                pid, window_debug_info
-            )));
+        // Keep the desktop's z-order regardless of which worker finished first
+        let mut results = results.into_inner().unwrap_or_default();
+        results.sort_by_key(|(index, _, _)| *index);
+        Ok(results.into_iter().map(|(_, info, tree)| (info, tree)).collect())
+    }
+
+    fn session_state(&self) -> Result<SessionState, AutomationError> {
+        query_session_state()
+    }
+
+    fn attach_to_console(&self) -> Result<(), AutomationError> {
+        let session_id = current_session_id()?;
+        info!("Reconnecting session {} to the console", session_id);
+
+        // tscon moves the session to the physical console without locking it,
+        // which keeps the desktop interactive after the RDP client is gone
+        let output = std::process::Command::new("tscon")
+            .args([&session_id.to_string(), "/dest:console"])
+            .output()
+            .map_err(|e| AutomationError::PlatformError(format!("Failed to run tscon: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(tscon_error(session_id, &String::from_utf8_lossy(&output.stderr)));
         }
 
-        info!("Found {} windows for PID: {}", pid_matching_windows.len(), pid);
+        Ok(())
+    }
 
-        // Enhanced title matching logic for PID-based search
-        let selected_window = if let Some(title) = title {
-            info!("Filtering {} windows by title: '{}'", pid_matching_windows.len(), title);
-            
-            // Use the enhanced title matching helper
-            match self.find_best_title_match(&pid_matching_windows, title) {
-                Some((window, score)) => {
-                    if score < 1.0 {
-                        info!("Using best match with similarity {:.2} for PID {}: '{}'", 
-                              score, pid, window.get_name().unwrap_or_default());
-                    }
-                    window
-                }
-                None => {
-                    let window_names: Vec<&String> = pid_matching_windows.iter().map(|(_, name)| name).collect();
-                    warn!("No good title match found for '{}' in PID {}, falling back to first window. Available: {:?}", 
-                          title, pid, window_names);
-                    pid_matching_windows[0].0.clone()
-                }
+    fn read_console(&self, pid: u32) -> Result<ConsoleScreen, AutomationError> {
+        with_attached_console(pid, || {
+            let handle = open_console_buffer("CONOUT$")?;
+            let screen = read_screen_buffer(handle);
+            unsafe {
+                let _ = CloseHandle(handle);
             }
-        } else {
-            info!("No title filter provided, using first window with PID {}", pid);
-            pid_matching_windows[0].0.clone()
-        };
-            
-        let selected_window_name = selected_window.get_name().unwrap_or_else(|_| "Unknown".to_string());
-        info!("Selected window: '{}' for PID: {} (title filter: {:?})", 
-              selected_window_name, pid, title);
+            screen
+        })
+    }
 
-        // Wrap the raw OS element into our UIElement
-        let window_element_wrapper = UIElement::new(Box::new(WindowsUIElement {
-            element: ThreadSafeWinUIElement(Arc::new(selected_window)),
-        }));
+    fn subscribe_ui_events(
+        &self,
+        kinds: &[UIEventKind],
+        callback: UIEventCallback,
+    ) -> Result<UIEventSubscription, AutomationError> {
+        spawn_ui_event_watcher(kinds.to_vec(), callback)
+    }
 
-        // Build the UI tree with configurable performance optimizations
-        info!("Building UI tree with config: {:?}", config);
-        
-        // Use configured tree building approach
-        let mut context = TreeBuildingContext {
-            config: TreeBuildingConfig {
-                timeout_per_operation_ms: config.timeout_per_operation_ms.unwrap_or(50),
-                yield_every_n_elements: config.yield_every_n_elements.unwrap_or(50),
-                batch_size: config.batch_size.unwrap_or(50),
-            },
-            property_mode: config.property_mode.clone(),
-            elements_processed: 0,
-            max_depth_reached: 0,
-            cache_hits: 0,
-            fallback_calls: 0,
-            errors_encountered: 0,
-        };
-        
-        let result = build_ui_node_tree_configurable(&window_element_wrapper, 0, &mut context)?;
-        
-        info!("Tree building completed for PID: {}. Stats: elements={}, depth={}, cache_hits={}, fallbacks={}, errors={}", 
-              pid, context.elements_processed, context.max_depth_reached, 
-              context.cache_hits, context.fallback_calls, context.errors_encountered);
-        
-        // Log cache effectiveness
-        let cache_hit_rate = if context.elements_processed > 0 {
-            (context.cache_hits as f64 / context.elements_processed as f64) * 100.0
-        } else {
-            0.0
-        };
-        
-        info!("Cache hit rate: {:.1}%", cache_hit_rate);
-        
-        Ok(result)
+    fn register_hotkey(
+        &self,
+        hotkey: Hotkey,
+        callback: HotkeyCallback,
+    ) -> Result<HotkeyRegistration, AutomationError> {
+        super::windows_hotkeys::register(hotkey, callback)
+    }
+
+    fn environment(&self) -> Result<EnvironmentInfo, AutomationError> {
+        Ok(query_environment())
+    }
+
+    fn highlight_all(
+        &self,
+        elements: &[UIElement],
+        color: Option<u32>,
+        duration: Option<Duration>,
+    ) -> Result<(), AutomationError> {
+        let rects: Vec<(i32, i32, i32, i32)> = elements
+            .iter()
+            .filter_map(|element| match element.bounds() {
+                Ok((x, y, width, height)) => Some((x as i32, y as i32, width as i32, height as i32)),
+                Err(e) => {
+                    debug!("Skipping element without bounds in highlight_all: {}", e);
+                    None
+                }
+            })
+            .collect();
+        if rects.is_empty() && !elements.is_empty() {
+            return Err(AutomationError::PlatformError(
+                "None of the elements to highlight has bounds".to_string(),
+            ));
+        }
+        super::windows_overlay::highlight(
+            &rects,
+            color.unwrap_or(super::windows_overlay::DEFAULT_COLOR),
+            duration.unwrap_or(super::windows_overlay::DEFAULT_DURATION),
+        )
+    }
+
+    fn app_key(&self, pid: u32) -> Result<AppKey, AutomationError> {
+        use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+        let pid = Pid::from_u32(pid);
+        let mut system = System::new();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            false,
+            ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet),
+        );
+        let exe = system
+            .process(pid)
+            .and_then(|process| process.exe())
+            .ok_or_else(|| {
+                AutomationError::PlatformError(format!("Executable of process {} not found", pid))
+            })?;
+        let file_name = exe
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| exe.to_string_lossy().into_owned());
+        Ok(AppKey::new(file_name, file_version(exe)))
+    }
+
+    fn send_console_input(&self, pid: u32, text: &str) -> Result<(), AutomationError> {
+        let records = console_key_records(text);
+        with_attached_console(pid, || {
+            let handle = open_console_buffer("CONIN$")?;
+            let result = write_input_records(handle, &records);
+            unsafe {
+                let _ = CloseHandle(handle);
+            }
+            result
+        })
     }
 
     /// Enable downcasting to concrete engine types
@@ -1651,12 +2221,56 @@ struct TreeBuildingContext {
     property_mode: crate::platforms::PropertyLoadingMode,
     elements_processed: usize,
     max_depth_reached: usize,
-    cache_hits: usize,
     fallback_calls: usize,
     errors_encountered: usize,
+    deadline: Option<std::time::Instant>,
+    truncated_subtrees: usize,
+    cache: Option<TreeCache>,
+    cached_elements: usize,
+    cache_requests: usize,
+    // Element of each node built, depth-first, when the caller keeps handles
+    handles: Option<Vec<UIElement>>,
 }
 
 impl TreeBuildingContext {
+    fn new(config: &crate::platforms::TreeBuildConfig) -> Self {
+        Self {
+            config: TreeBuildingConfig {
+                timeout_per_operation_ms: config.timeout_per_operation_ms.unwrap_or(50),
+                yield_every_n_elements: config.yield_every_n_elements.unwrap_or(50),
+                batch_size: config.batch_size.unwrap_or(50),
+            },
+            property_mode: config.property_mode.clone(),
+            elements_processed: 0,
+            max_depth_reached: 0,
+            fallback_calls: 0,
+            errors_encountered: 0,
+            deadline: config.max_total_duration.map(|d| std::time::Instant::now() + d),
+            truncated_subtrees: 0,
+            cache: None,
+            cached_elements: 0,
+            cache_requests: 0,
+            handles: None,
+        }
+    }
+
+    fn stats(&self, duration: Duration) -> crate::platforms::TreeBuildStats {
+        crate::platforms::TreeBuildStats {
+            elements: self.elements_processed,
+            max_depth: self.max_depth_reached,
+            cached_elements: self.cached_elements,
+            cache_requests: self.cache_requests,
+            uncached_fallbacks: self.fallback_calls,
+            errors: self.errors_encountered,
+            truncated_subtrees: self.truncated_subtrees,
+            duration_ms: duration.as_millis() as u64,
+        }
+    }
+
+    fn deadline_passed(&self) -> bool {
+        self.deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline)
+    }
+
     fn should_yield(&self) -> bool {
         self.elements_processed % self.config.yield_every_n_elements == 0 && self.elements_processed > 0
     }
@@ -1669,10 +2283,6 @@ impl TreeBuildingContext {
         self.max_depth_reached = self.max_depth_reached.max(depth);
     }
     
-    fn increment_cache_hit(&mut self) {
-        self.cache_hits += 1;
-    }
-    
     fn increment_fallback(&mut self) {
         self.fallback_calls += 1;
     }
@@ -1685,12 +2295,21 @@ impl TreeBuildingContext {
 
 // Safe element children access
 fn get_element_children_safe(element: &UIElement, context: &mut TreeBuildingContext) -> Result<Vec<UIElement>, AutomationError> {
-    // Primarily use the standard children method
+    // Children and their properties in one call when caching
+    if let Some(cache) = &context.cache {
+        context.cache_requests += 1;
+        match cache.children(element) {
+            Ok(children) => return Ok(children),
+            Err(e) => {
+                debug!("Cache request for children failed, fetching them uncached: {}", e);
+                context.fallback_calls += 1;
+            }
+        }
+    }
+
+    // Otherwise use the standard children method
     match element.children() {
-        Ok(children) => {
-            context.increment_cache_hit(); // Count this as successful
-            Ok(children)
-        },
+        Ok(children) => Ok(children),
         Err(_) => {
             context.increment_fallback();
             // Only use timeout version if regular call fails
@@ -1737,6 +2356,100 @@ pub struct WindowsUIElement {
     element: ThreadSafeWinUIElement,
 }
 
+impl WindowsUIElement {
+    // One step through the control view. The walker fails where there is no
+    // element to step to (past the last sibling, above the desktop)
+    fn walk(
+        &self,
+        step: impl FnOnce(&uiautomation::core::UITreeWalker, &uiautomation::UIElement) -> uiautomation::Result<uiautomation::UIElement>,
+    ) -> Result<Option<UIElement>, AutomationError> {
+        let automation = create_ui_automation_with_com_init()?;
+        let walker = automation
+            .get_control_view_walker()
+            .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to create control view walker: {}", e))))?;
+        Ok(step(&walker, &self.element.0).ok().map(|element| {
+            UIElement::new(Box::new(WindowsUIElement {
+                element: ThreadSafeWinUIElement(Arc::new(element)),
+            }))
+        }))
+    }
+
+    // Where a click lands: the clickable point, else the center of the bounds
+    fn click_point(&self) -> Option<(f64, f64)> {
+        if let Ok(Some(point)) = self.element.0.get_clickable_point() {
+            return Some((point.get_x() as f64, point.get_y() as f64));
+        }
+        let center = crate::geometry::Rect::from(self.bounds().ok()?).center();
+        Some((center.x, center.y))
+    }
+
+    // Handle of the top-level window that contains this element
+    fn top_level_hwnd(&self) -> Result<windows::Win32::Foundation::HWND, AutomationError> {
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::UI::WindowsAndMessaging::{GetAncestor, GA_ROOT};
+
+        let own_handle: HWND = self
+            .element
+            .0
+            .get_native_window_handle()
+            .map(|h| h.into())
+            .unwrap_or_default();
+
+        let hwnd = if !own_handle.0.is_null() {
+            own_handle
+        } else {
+            // Most controls are windowless, fall back to the containing window
+            let window = self.window()?.ok_or_else(|| {
+                AutomationError::ElementNotFound("No containing window found for element".to_string())
+            })?;
+            let win_element = window
+                .as_any()
+                .downcast_ref::<WindowsUIElement>()
+                .ok_or_else(|| AutomationError::PlatformError("Unexpected element type".to_string()))?;
+            win_element
+                .element
+                .0
+                .get_native_window_handle()
+                .map(|h| h.into())
+                .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get window handle: {}", e))))?
+        };
+
+        if hwnd.0.is_null() {
+            return Err(AutomationError::PlatformError(
+                "Element has no native window handle".to_string(),
+            ));
+        }
+
+        let root = unsafe { GetAncestor(hwnd, GA_ROOT) };
+        Ok(if root.0.is_null() { hwnd } else { root })
+    }
+
+    // A pattern of the top-level window containing this element, which is
+    // the element itself for windows
+    fn window_pattern<T: patterns::UIPattern>(&self) -> Option<T> {
+        if let Ok(pattern) = self.element.0.get_pattern::<T>() {
+            return Some(pattern);
+        }
+        let window = self.window().ok()??;
+        let win_element = window.as_any().downcast_ref::<WindowsUIElement>()?;
+        win_element.element.0.get_pattern::<T>().ok()
+    }
+
+    // A control pattern, or `UnsupportedOperation` if the element lacks it
+    fn pattern<T: patterns::UIPattern>(&self, name: &str) -> Result<T, AutomationError> {
+        self.element.0.get_pattern::<T>().map_err(|e| {
+            uia_error(e, |e| {
+                AutomationError::UnsupportedOperation(format!(
+                    "Element '{}' does not support {}: {}",
+                    self.element.0.get_name().unwrap_or_default(),
+                    name,
+                    e
+                ))
+            })
+        })
+    }
+}
+
 impl Debug for WindowsUIElement {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("WindowsUIElement").finish()
@@ -1873,6 +2586,18 @@ impl UIElementImpl for WindowsUIElement {
             }
         };
 
+        // XAML islands and UWP CoreWindows may hide their content from the control view
+        let children = if children.is_empty() {
+            xaml_island_roots(&*self.element.0).unwrap_or_default()
+        } else {
+            children
+        };
+
+        // Legacy Win32 and Java windows may only expose their content through MSAA
+        if children.is_empty() {
+            return Ok(super::windows_msaa::fallback_children(&self.element.0));
+        }
+
         // Wrap the platform elements into our UIElement trait objects
         Ok(children
             .into_iter()
@@ -1893,13 +2618,31 @@ impl UIElementImpl for WindowsUIElement {
                 }));
                 Ok(Some(par_ele))
             }
-            Err(e) => Err(AutomationError::ElementNotFound(e.to_string())),
+            // Elements found without a cache request have no cached parent
+            Err(_) => self.walk(|walker, element| walker.get_parent(element)),
         }
     }
 
+    fn next_sibling(&self) -> Result<Option<UIElement>, AutomationError> {
+        self.walk(|walker, element| walker.get_next_sibling(element))
+    }
+
+    fn previous_sibling(&self) -> Result<Option<UIElement>, AutomationError> {
+        self.walk(|walker, element| walker.get_previous_sibling(element))
+    }
+
+    fn matches(&self, selector: &Selector) -> bool {
+        element_matches(selector, &self.element.0)
+    }
+
+    fn is_stale(&self) -> bool {
+        // Other failures, e.g. a provider that is busy, don't mean it is gone
+        matches!(self.element.0.get_runtime_id(), Err(e) if e.code() == UIA_E_ELEMENTNOTAVAILABLE)
+    }
+
     fn bounds(&self) -> Result<(f64, f64, f64, f64), AutomationError> {
         let rect = self.element.0.get_bounding_rectangle()
-            .map_err(|e| AutomationError::ElementNotFound(e.to_string()))?;
+            .map_err(|e| uia_error(e, AutomationError::ElementNotFound))?;
         Ok((
             rect.get_left() as f64,
             rect.get_top() as f64,
@@ -1912,11 +2655,22 @@ impl UIElementImpl for WindowsUIElement {
         self.element.0.try_focus();
         debug!("attempting to click element: {:?}", self.element.0);
 
+        // The clicks below move the pointer themselves; paced profiles get
+        // it there first
+        let profile = crate::input_profile::current();
+        if profile.moves_mouse() || !profile.click_dwell.is_zero() {
+            if let Some((x, y)) = self.click_point() {
+                approach(x, y);
+            }
+        }
+
         let click_result = self.element.0.click();
 
         if click_result.is_ok() {
             return Ok(ClickResult {
                 method: "Single Click".to_string(),
+                strategy: Some(ActionStrategy::SendInputCursor),
+                attempts: Vec::new(),
                 coordinates: None,
                 details: "Clicked by Mouse".to_string(),
             });
@@ -1932,6 +2686,8 @@ impl UIElementImpl for WindowsUIElement {
                     let mouse = Mouse::default();
                     mouse.click(point).map(|_| ClickResult {
                         method: "Single Click (Clickable Point)".to_string(),
+                        strategy: Some(ActionStrategy::SendInputCursor),
+                        attempts: Vec::new(),
                         coordinates: Some((point.get_x() as f64, point.get_y() as f64)),
                         details: "Clicked by Mouse using element's clickable point".to_string(),
                     })
@@ -1947,11 +2703,11 @@ impl UIElementImpl for WindowsUIElement {
         // If first method fails, try using the bounding rectangle
         if let Err(_) = click_result {
             debug!("clickable point unavailable, falling back to bounding rectangle");
-            if let Ok(rect) = self.element.0.get_bounding_rectangle() {
-                println!("bounding rectangle: {:?}", rect);
+            if let Ok(bounds) = self.bounds() {
+                debug!("bounding rectangle: {:?}", bounds);
                 // Calculate center point of the element
-                let center_x = rect.get_left() + rect.get_width() / 2;
-                let center_y = rect.get_top() + rect.get_height() / 2;
+                let center = crate::geometry::Rect::from(bounds).center();
+                let (center_x, center_y) = (center.x.round() as i32, center.y.round() as i32);
 
                 let point = Point::new(center_x, center_y);
                 let mouse = Mouse::default();
@@ -1959,10 +2715,12 @@ impl UIElementImpl for WindowsUIElement {
                 debug!("clicking at center point: ({}, {})", center_x, center_y);
                 mouse
                     .click(point)
-                    .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+                    .map_err(AutomationError::from)?;
 
                 return Ok(ClickResult {
                     method: "Single Click (Fallback)".to_string(),
+                    strategy: Some(ActionStrategy::SendInputCursor),
+                    attempts: Vec::new(),
                     coordinates: Some((center_x as f64, center_y as f64)),
                     details: "Clicked by Mouse using element's center coordinates".to_string(),
                 });
@@ -1970,7 +2728,7 @@ impl UIElementImpl for WindowsUIElement {
         }
 
         // Return the result of the first attempt or propagate the error
-        click_result.map_err(|e| AutomationError::PlatformError(e.to_string()))
+        click_result.map_err(AutomationError::from)
     }
 
     fn double_click(&self) -> Result<ClickResult, AutomationError> {
@@ -1979,16 +2737,19 @@ impl UIElementImpl for WindowsUIElement {
             .element
             .0
             .get_clickable_point()
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?
+            .map_err(AutomationError::from)?
             .ok_or_else(|| {
                 AutomationError::PlatformError("No clickable point found".to_string())
             })?;
+        approach(point.get_x() as f64, point.get_y() as f64);
         let mouse = Mouse::default();
         mouse
             .double_click(point)
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+            .map_err(AutomationError::from)?;
         Ok(ClickResult {
             method: "Double Click".to_string(),
+            strategy: Some(ActionStrategy::SendInputCursor),
+            attempts: Vec::new(),
             coordinates: Some((point.get_x() as f64, point.get_y() as f64)),
             details: "Clicked by Mouse".to_string(),
         })
@@ -2000,14 +2761,15 @@ impl UIElementImpl for WindowsUIElement {
             .element
             .0
             .get_clickable_point()
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?
+            .map_err(AutomationError::from)?
             .ok_or_else(|| {
                 AutomationError::PlatformError("No clickable point found".to_string())
             })?;
+        approach(point.get_x() as f64, point.get_y() as f64);
         let mouse = Mouse::default();
         mouse
             .right_click(point)
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+            .map_err(AutomationError::from)?;
         Ok(())
     }
 
@@ -2021,7 +2783,7 @@ impl UIElementImpl for WindowsUIElement {
         self.element
             .0
             .set_focus()
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))
+            .map_err(AutomationError::from)
     }
 
     fn activate_window(&self) -> Result<(), AutomationError> {
@@ -2039,7 +2801,7 @@ impl UIElementImpl for WindowsUIElement {
             .element
             .0
             .get_control_type()
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+            .map_err(AutomationError::from)?;
         
         debug!("typing text with control_type: {:#?}, use_clipboard: {}", control_type, use_clipboard);
 
@@ -2047,13 +2809,25 @@ impl UIElementImpl for WindowsUIElement {
             self.element
                 .0
                 .send_text_by_clipboard(text)
-                .map_err(|e| AutomationError::PlatformError(e.to_string()))
+                .map_err(AutomationError::from)
         } else {
-            // Use standard typing method
-            self.element
-                .0
-                .send_text(text, 10)
-                .map_err(|e| AutomationError::PlatformError(e.to_string()))
+            let profile = crate::input_profile::current();
+            if profile.key_delay_jitter.is_zero() {
+                return self
+                    .element
+                    .0
+                    .send_text(text, profile.key_delay.as_millis() as u64)
+                    .map_err(AutomationError::from);
+            }
+            // A different pause after every key
+            for ch in text.chars() {
+                self.element
+                    .0
+                    .send_text(&ch.to_string(), 0)
+                    .map_err(AutomationError::from)?;
+                thread::sleep(profile.next_key_delay());
+            }
+            Ok(())
         }
     }
 
@@ -2062,13 +2836,14 @@ impl UIElementImpl for WindowsUIElement {
             .element
             .0
             .get_control_type()
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to get control type: {:?}", e)))?;
+            .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get control type: {}", e))))?;
         // check if element accepts input, similar :D
         debug!("pressing key with control_type: {:#?}", control_type);
+        let delay = crate::input_profile::current().next_key_delay();
         self.element
             .0
-            .send_keys(key, 10)
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to press key: {:?}", e)))
+            .send_keys(key, delay.as_millis() as u64)
+            .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to press key: {}", e))))
     }
 
     fn get_text(&self, max_depth: usize) -> Result<String, AutomationError> {
@@ -2178,11 +2953,12 @@ impl UIElementImpl for WindowsUIElement {
     }
 
     fn set_value(&self, value: &str) -> Result<(), AutomationError> {
+        let _span = pattern_span(&self.element.0, "ValuePattern", "set_value").entered();
         let value_par = self
             .element
             .0
             .get_pattern::<patterns::UIValuePattern>()
-            .map_err(|e| AutomationError::PlatformError(e.to_string()));
+            .map_err(AutomationError::from);
         debug!(
             "setting value: {:#?} to ui element {:#?}",
             &value, &self.element.0
@@ -2190,7 +2966,7 @@ impl UIElementImpl for WindowsUIElement {
 
         if let Ok(v) = value_par {
             v.set_value(value)
-                .map_err(|e| AutomationError::PlatformError(e.to_string()))
+                .map_err(AutomationError::from)
         } else {
             Err(AutomationError::PlatformError(
                 "`UIValuePattern` is not found".to_string(),
@@ -2200,56 +2976,39 @@ impl UIElementImpl for WindowsUIElement {
 
     fn is_enabled(&self) -> Result<bool, AutomationError> {
         self.element.0.is_enabled()
-            .map_err(|e| AutomationError::ElementNotFound(e.to_string()))
+            .map_err(|e| uia_error(e, AutomationError::ElementNotFound))
     }
 
     fn is_visible(&self) -> Result<bool, AutomationError> {
         self.element.0.is_offscreen()
             .map(|is_offscreen| !is_offscreen)
-            .map_err(|e| AutomationError::ElementNotFound(e.to_string()))
+            .map_err(|e| uia_error(e, AutomationError::ElementNotFound))
     }
 
     fn is_focused(&self) -> Result<bool, AutomationError> {
         self.element.0.has_keyboard_focus()
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to get keyboard focus state: {}", e)))
+            .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get keyboard focus state: {}", e))))
     }
 
     fn perform_action(&self, action: &str) -> Result<(), AutomationError> {
         // actions those don't take args
         match action {
             "focus" => self.focus(),
-            "invoke" => {
-                let invoke_pat = self
-                    .element
-                    .0
-                    .get_pattern::<patterns::UIInvokePattern>()
-                    .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
-                invoke_pat
-                    .invoke()
-                    .map_err(|e| AutomationError::PlatformError(e.to_string()))
-            }
+            "invoke" => self.invoke(),
             "click" => self.click().map(|_| ()),
             "double_click" => self.double_click().map(|_| ()),
             "right_click" => self.right_click().map(|_| ()),
-            "toggle" => {
-                let toggle_pattern = self
-                    .element
-                    .0
-                    .get_pattern::<patterns::UITogglePattern>()
-                    .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
-                toggle_pattern
-                    .toggle()
-                    .map_err(|e| AutomationError::PlatformError(e.to_string()))
-            }
+            "toggle" => self.toggle(),
             "expand_collapse" => {
+                let _span = pattern_span(&self.element.0, "ExpandCollapsePattern", "expand").entered();
                 let expand_collapse_pattern = self
                     .element
                     .0
                     .get_pattern::<patterns::UIExpandCollapsePattern>()
-                    .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+                    .map_err(AutomationError::from)?;
                 expand_collapse_pattern
                     .expand()
-                    .map_err(|e| AutomationError::PlatformError(e.to_string()))
+                    .map_err(AutomationError::from)
             }
             _ => Err(AutomationError::UnsupportedOperation(format!(
                 "action '{}' not supported",
@@ -2313,10 +3072,21 @@ impl UIElementImpl for WindowsUIElement {
 
     fn is_keyboard_focusable(&self) -> Result<bool, AutomationError> {
         let variant = self.element.0.get_property_value(UIProperty::IsKeyboardFocusable)
-            .map_err(|e| AutomationError::PlatformError(e.to_string()))?;
+            .map_err(AutomationError::from)?;
         variant.try_into().map_err(|e| AutomationError::PlatformError(format!("Failed to convert IsKeyboardFocusable to bool: {:?}", e)))
     }
 
+    fn is_read_only(&self) -> Result<bool, AutomationError> {
+        let _span = pattern_span(&self.element.0, "ValuePattern", "is_read_only").entered();
+        // Elements without a value (e.g. documents) have nothing to lock
+        match self.element.0.get_pattern::<patterns::UIValuePattern>() {
+            Ok(pattern) => pattern
+                .is_readonly()
+                .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get IsReadOnly: {}", e)))),
+            Err(_) => Ok(false),
+        }
+    }
+
     // New method for mouse drag
     fn mouse_drag(&self, start_x: f64, start_y: f64, end_x: f64, end_y: f64) -> Result<(), AutomationError> {
         use std::thread::sleep;
@@ -2331,6 +3101,7 @@ impl UIElementImpl for WindowsUIElement {
 
     // New mouse control methods
     fn mouse_click_and_hold(&self, x: f64, y: f64) -> Result<(), AutomationError> {
+        approach(x, y);
         use windows::Win32::UI::Input::KeyboardAndMouse::{
             INPUT, INPUT_0, INPUT_MOUSE, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_MOVE, MOUSEEVENTF_ABSOLUTE, MOUSEINPUT, SendInput,
         };
@@ -2376,6 +3147,7 @@ impl UIElementImpl for WindowsUIElement {
         Ok(())
     }
     fn mouse_move(&self, x: f64, y: f64) -> Result<(), AutomationError> {
+        travel_to(x, y);
         use windows::Win32::UI::Input::KeyboardAndMouse::{
             INPUT, INPUT_0, INPUT_MOUSE, MOUSEEVENTF_MOVE, MOUSEEVENTF_ABSOLUTE, MOUSEINPUT, SendInput,
         };
@@ -2429,11 +3201,53 @@ impl UIElementImpl for WindowsUIElement {
         Ok(())
     }
 
+    fn post_message_click(&self, x: f64, y: f64) -> Result<(), AutomationError> {
+        use windows::Win32::Foundation::{LPARAM, POINT, WPARAM};
+        use windows::Win32::Graphics::Gdi::ScreenToClient;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            ChildWindowFromPointEx, PostMessageW, CWP_SKIPINVISIBLE, CWP_SKIPTRANSPARENT, WM_LBUTTONDOWN,
+            WM_LBUTTONUP,
+        };
+        // MK_LBUTTON: the left button is down while the message is handled
+        const MK_LBUTTON: usize = 0x0001;
+
+        let screen = POINT {
+            x: x.round() as i32,
+            y: y.round() as i32,
+        };
+        // The innermost child window under the point handles the click
+        let mut hwnd = self.top_level_hwnd()?;
+        loop {
+            let mut client = screen;
+            let _ = unsafe { ScreenToClient(hwnd, &mut client) };
+            let child = unsafe { ChildWindowFromPointEx(hwnd, client, CWP_SKIPINVISIBLE | CWP_SKIPTRANSPARENT) };
+            if child.0.is_null() || child == hwnd {
+                break;
+            }
+            hwnd = child;
+        }
+
+        let mut client = screen;
+        if !unsafe { ScreenToClient(hwnd, &mut client) }.as_bool() {
+            return Err(AutomationError::PlatformError(
+                "Failed to convert the click point to window coordinates".to_string(),
+            ));
+        }
+        let lparam = LPARAM((((client.y as u16 as u32) << 16) | client.x as u16 as u32) as isize);
+        unsafe {
+            PostMessageW(Some(hwnd), WM_LBUTTONDOWN, WPARAM(MK_LBUTTON), lparam)
+                .and_then(|_| PostMessageW(Some(hwnd), WM_LBUTTONUP, WPARAM(0), lparam))
+        }
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to post mouse messages: {}", e)))?;
+        debug!(?hwnd, x = client.x, y = client.y, "Posted click messages");
+        Ok(())
+    }
+
 
     fn application(&self) -> Result<Option<UIElement>, AutomationError> {
         // Get the process ID of the current element
         let pid = self.element.0.get_process_id().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to get process ID for element: {}", e))
+            uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get process ID for element: {}", e)))
         })?;
 
         // Create a WindowsEngine instance to use its methods.
@@ -2445,7 +3259,7 @@ impl UIElementImpl for WindowsUIElement {
         // Get the application element by PID
         match engine.get_application_by_pid(pid as i32, Some(DEFAULT_FIND_TIMEOUT)) { // Cast pid to i32
             Ok(app_element) => Ok(Some(app_element)),
-            Err(AutomationError::ElementNotFound(_)) => {
+            Err(e) if e.is_element_not_found() => {
                 // If the specific application element is not found by PID, return None.
                 debug!("Application element not found for PID {}", pid);
                 Ok(None)
@@ -2471,10 +3285,12 @@ impl UIElementImpl for WindowsUIElement {
                     }
                 }
                 Err(e) => {
-                    return Err(AutomationError::PlatformError(format!(
-                        "Failed to get control type for element during window search (iteration {}): {}",
-                        i, e
-                    )));
+                    return Err(uia_error(e, |e| {
+                        AutomationError::PlatformError(format!(
+                            "Failed to get control type for element during window search (iteration {}): {}",
+                            i, e
+                        ))
+                    }));
                 }
             }
 
@@ -2483,8 +3299,8 @@ impl UIElementImpl for WindowsUIElement {
                 Ok(parent_uia_element) => {
                     // Check if parent is same as current (e.g. desktop root's parent is itself)
                     // This requires getting runtime IDs, which can also fail.
-                    let current_runtime_id = current_element_arc.get_runtime_id().map_err(|e| AutomationError::PlatformError(format!("Failed to get runtime_id for current element: {}", e)))?;
-                    let parent_runtime_id = parent_uia_element.get_runtime_id().map_err(|e| AutomationError::PlatformError(format!("Failed to get runtime_id for parent element: {}", e)))?;
+                    let current_runtime_id = current_element_arc.get_runtime_id().map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get runtime_id for current element: {}", e))))?;
+                    let parent_runtime_id = parent_uia_element.get_runtime_id().map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get runtime_id for parent element: {}", e))))?;
 
                     if parent_runtime_id == current_runtime_id {
                         debug!("Parent element has same runtime ID as current, stopping window search.");
@@ -2504,128 +3320,27 @@ impl UIElementImpl for WindowsUIElement {
     }
 
     fn highlight(&self, color: Option<u32>, duration: Option<std::time::Duration>) -> Result<(), AutomationError> {
-        use windows::Win32::Graphics::Gdi::{
-            GetDC, ReleaseDC, CreatePen, SelectObject, DeleteObject, Rectangle,
-            PS_SOLID, NULL_BRUSH, GetStockObject, HGDIOBJ
-        };
-        use windows::Win32::Foundation::{COLORREF, POINT};
-        use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
-        use std::time::Instant;
-
-        self.element.0.try_focus();
-
-        // Get the element's bounding rectangle
         let rect = self.element.0.get_bounding_rectangle().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to get element bounds: {}", e))
+            uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get element bounds: {}", e)))
         })?;
 
-        // Helper function to get scale factor from cursor position
-        fn get_scale_factor_from_cursor() -> f64 {
-            let mut point = POINT { x: 0, y: 0 };
-            unsafe {
-                let _ = GetCursorPos(&mut point);
-            }
-            match xcap::Monitor::from_point(point.x, point.y) {
-                Ok(monitor) => match monitor.scale_factor() {
-                    Ok(factor) => factor as f64,
-                    Err(e) => {
-                        error!("Failed to get scale factor from cursor position: {}", e);
-                        1.0 // Fallback to default scale factor
-                    }
-                },
-                Err(e) => {
-                    error!("Failed to get monitor from cursor position: {}", e);
-                    1.0 // Fallback to default scale factor
-                }
-            }
-        }
-
-        // Helper function to get scale factor from focused window
-        fn get_scale_factor_from_focused_window() -> Option<f64> {
-            match xcap::Window::all() {
-                Ok(windows) => {
-                    windows.iter()
-                        .find(|w| w.is_focused().unwrap_or(false))
-                        .and_then(|focused_window| {
-                            focused_window.current_monitor().ok()
-                        })
-                        .and_then(|monitor| {
-                            monitor.scale_factor().ok().map(|factor| factor as f64)
-                        })
-                },
-                Err(e) => {
-                    error!("Failed to get windows: {}", e);
-                    None
-                }
-            }
-        }
-
-        // Try to get scale factor from focused window first, fall back to cursor position
-        let scale_factor = get_scale_factor_from_focused_window()
-            .unwrap_or_else(get_scale_factor_from_cursor);
-
-        // Constants for border appearance
-        const BORDER_SIZE: i32 = 4;
-        const DEFAULT_RED_COLOR: u32 = 0x0000FF; // Pure red in BGR format
-
-        // Use provided color or default to red
-        let highlight_color = color.unwrap_or(DEFAULT_RED_COLOR);
-
-        // Scale the coordinates and dimensions
-        let x = (rect.get_left() as f64 * scale_factor) as i32;
-        let y = (rect.get_top() as f64 * scale_factor) as i32;
-        let width = (rect.get_width() as f64 * scale_factor) as i32;
-        let height = (rect.get_height() as f64 * scale_factor) as i32;
-
-        // Spawn a thread to handle the highlighting
-        thread::spawn(move || {
-            let start_time = Instant::now();
-            let duration = duration.unwrap_or(std::time::Duration::from_millis(500));
-
-            while start_time.elapsed() < duration {
-                // Get the screen DC
-                let hdc = unsafe { GetDC(None) };
-                if hdc.0.is_null() {
-                    return;
-                }
-
-                unsafe {
-                    // Create a pen for drawing with the specified color
-                    let hpen = CreatePen(PS_SOLID, BORDER_SIZE, COLORREF(highlight_color));
-                    if hpen.0.is_null() {
-                        ReleaseDC(None, hdc);
-                        return;
-                    }
-
-                    // Save current objects
-                    let old_pen = SelectObject(hdc, HGDIOBJ(hpen.0));
-                    let null_brush = GetStockObject(NULL_BRUSH);
-                    let old_brush = SelectObject(hdc, null_brush);
-
-                    // Draw the border rectangle
-                    let _ = Rectangle(hdc, x, y, x + width, y + height);
-
-                    // Restore original objects and clean up
-                    SelectObject(hdc, old_brush);
-                    SelectObject(hdc, old_pen);
-                    let _ = DeleteObject(HGDIOBJ(hpen.0));
-                    ReleaseDC(None, hdc);
-                }
-            }
-        });
-
-        Ok(())
+        super::windows_overlay::highlight(
+            &[(rect.get_left(), rect.get_top(), rect.get_width(), rect.get_height())],
+            color.unwrap_or(super::windows_overlay::DEFAULT_COLOR),
+            duration.unwrap_or(super::windows_overlay::DEFAULT_DURATION),
+        )
     }
+
     fn process_id(&self) -> Result<u32, AutomationError> {
         self.element.0.get_process_id().map_err(|e| {
-            AutomationError::PlatformError(format!("Failed to get process ID for element: {}", e))
+            uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get process ID for element: {}", e)))
         })
     }
 
     fn close(&self) -> Result<(), AutomationError> {
         // Check the control type to determine if this element is closable
         let control_type = self.element.0.get_control_type()
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to get control type: {}", e)))?;
+            .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get control type: {}", e))))?;
             
         match control_type {
             ControlType::Window | ControlType::Pane => {
@@ -2633,16 +3348,17 @@ impl UIElementImpl for WindowsUIElement {
                 
                 // First try using the WindowPattern to close the window
                 if let Ok(window_pattern) = self.element.0.get_pattern::<patterns::UIWindowPattern>() {
+                    let _span = pattern_span(&self.element.0, "WindowPattern", "close").entered();
                     debug!("Attempting to close window using WindowPattern");
                     return window_pattern.close()
-                        .map_err(|e| AutomationError::PlatformError(format!("Failed to close window: {}", e)));
+                        .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to close window: {}", e))));
                 }
                 
                 // Fallback: try to send Alt+F4 to close the window
                 debug!("WindowPattern not available, trying Alt+F4 as fallback");
                 self.element.0.try_focus(); // Focus first
                 self.element.0.send_keys("%{F4}", 10) // Alt+F4
-                    .map_err(|e| AutomationError::PlatformError(format!("Failed to send Alt+F4: {}", e)))
+                    .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to send Alt+F4: {}", e))))
             },
             ControlType::Button => {
                 // For buttons, check if it's a close button by name/text
@@ -2664,82 +3380,447 @@ impl UIElementImpl for WindowsUIElement {
         }
     }
 
-    fn capture(&self) -> Result<ScreenshotResult, AutomationError> {
-        // Get the raw UIAutomation bounds
-        let rect = self.element.0.get_bounding_rectangle()
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to get bounding rectangle: {}", e)))?;
+    fn is_topmost(&self) -> Result<bool, AutomationError> {
+        use windows::Win32::UI::WindowsAndMessaging::{GetWindowLongW, GWL_EXSTYLE, WS_EX_TOPMOST};
 
-        // Get all monitors that intersect with the element
-        let mut intersected_monitors = Vec::new();
-        let monitors = xcap::Monitor::all()
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitors: {}", e)))?;
+        let hwnd = self.top_level_hwnd()?;
+        let ex_style = unsafe { GetWindowLongW(hwnd, GWL_EXSTYLE) } as u32;
+        Ok(ex_style & WS_EX_TOPMOST.0 != 0)
+    }
 
-        for monitor in monitors {
-            let monitor_x = monitor.x()
-                .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitor x: {}", e)))? as i32;
-            let monitor_y = monitor.y()
-                .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitor y: {}", e)))? as i32;
-            let monitor_width = monitor.width()
-                .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitor width: {}", e)))? as i32;
-            let monitor_height = monitor.height()
-                .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitor height: {}", e)))? as i32;
+    fn set_topmost(&self, topmost: bool) -> Result<(), AutomationError> {
+        use windows::Win32::UI::WindowsAndMessaging::{
+            SetWindowPos, HWND_NOTOPMOST, HWND_TOPMOST, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE,
+        };
+
+        let hwnd = self.top_level_hwnd()?;
+        let insert_after = if topmost { HWND_TOPMOST } else { HWND_NOTOPMOST };
+        unsafe {
+            SetWindowPos(
+                hwnd,
+                Some(insert_after),
+                0,
+                0,
+                0,
+                0,
+                SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+            )
+        }
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to set topmost state: {}", e)))
+    }
+
+    fn opacity(&self) -> Result<f32, AutomationError> {
+        use windows::Win32::UI::WindowsAndMessaging::{
+            GetLayeredWindowAttributes, GetWindowLongW, GWL_EXSTYLE, LAYERED_WINDOW_ATTRIBUTES_FLAGS,
+            LWA_ALPHA, WS_EX_LAYERED,
+        };
+
+        let hwnd = self.top_level_hwnd()?;
+        let ex_style = unsafe { GetWindowLongW(hwnd, GWL_EXSTYLE) } as u32;
+        // Windows without the layered style are always fully opaque
+        if ex_style & WS_EX_LAYERED.0 == 0 {
+            return Ok(1.0);
+        }
+
+        let mut alpha = 255u8;
+        let mut flags = LAYERED_WINDOW_ATTRIBUTES_FLAGS(0);
+        unsafe { GetLayeredWindowAttributes(hwnd, None, Some(&mut alpha), Some(&mut flags)) }
+            .map_err(|e| AutomationError::PlatformError(format!("Failed to get window opacity: {}", e)))?;
+
+        if flags.0 & LWA_ALPHA.0 == 0 {
+            return Ok(1.0);
+        }
+        Ok(alpha as f32 / 255.0)
+    }
+
+    fn set_opacity(&self, opacity: f32) -> Result<(), AutomationError> {
+        use windows::Win32::Foundation::COLORREF;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            GetWindowLongW, SetLayeredWindowAttributes, SetWindowLongW, GWL_EXSTYLE, LWA_ALPHA,
+            WS_EX_LAYERED,
+        };
+
+        let hwnd = self.top_level_hwnd()?;
+        let alpha = (opacity.clamp(0.0, 1.0) * 255.0).round() as u8;
+        unsafe {
+            // Opacity requires the layered window style
+            let ex_style = GetWindowLongW(hwnd, GWL_EXSTYLE) as u32;
+            if ex_style & WS_EX_LAYERED.0 == 0 {
+                SetWindowLongW(hwnd, GWL_EXSTYLE, (ex_style | WS_EX_LAYERED.0) as i32);
+            }
+            SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA)
+        }
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to set window opacity: {}", e)))
+    }
+
+    fn window_state(&self) -> Result<crate::WindowState, AutomationError> {
+        use windows::Win32::UI::WindowsAndMessaging::{IsIconic, IsZoomed};
+
+        if let Some(pattern) = self.window_pattern::<patterns::UIWindowPattern>() {
+            if let Ok(state) = pattern.get_window_visual_state() {
+                return Ok(match state {
+                    WindowVisualState::Minimized => crate::WindowState::Minimized,
+                    WindowVisualState::Maximized => crate::WindowState::Maximized,
+                    _ => crate::WindowState::Normal,
+                });
+            }
+        }
+        let hwnd = self.top_level_hwnd()?;
+        Ok(if unsafe { IsIconic(hwnd) }.as_bool() {
+            crate::WindowState::Minimized
+        } else if unsafe { IsZoomed(hwnd) }.as_bool() {
+            crate::WindowState::Maximized
+        } else {
+            crate::WindowState::Normal
+        })
+    }
+
+    fn set_window_state(&self, state: crate::WindowState) -> Result<(), AutomationError> {
+        use windows::Win32::UI::WindowsAndMessaging::{
+            ShowWindow, SW_MAXIMIZE, SW_MINIMIZE, SW_RESTORE,
+        };
+
+        if let Some(pattern) = self.window_pattern::<patterns::UIWindowPattern>() {
+            let _span = pattern_span(&self.element.0, "WindowPattern", "set_window_state").entered();
+            let visual_state = match state {
+                crate::WindowState::Normal => WindowVisualState::Normal,
+                crate::WindowState::Minimized => WindowVisualState::Minimized,
+                crate::WindowState::Maximized => WindowVisualState::Maximized,
+            };
+            match pattern.set_window_visual_state(visual_state) {
+                Ok(()) => return Ok(()),
+                // Some windows refuse the pattern, e.g. while a modal dialog is open
+                Err(e) => debug!("WindowPattern failed to set {} state: {}, using ShowWindow", state, e),
+            }
+        }
+        let hwnd = self.top_level_hwnd()?;
+        let command = match state {
+            crate::WindowState::Normal => SW_RESTORE,
+            crate::WindowState::Minimized => SW_MINIMIZE,
+            crate::WindowState::Maximized => SW_MAXIMIZE,
+        };
+        // The return value is the previous visibility, not success
+        let _ = unsafe { ShowWindow(hwnd, command) };
+        Ok(())
+    }
+
+    fn set_window_bounds(&self, x: f64, y: f64, width: f64, height: f64) -> Result<(), AutomationError> {
+        use windows::Win32::UI::WindowsAndMessaging::{SetWindowPos, SWP_NOACTIVATE, SWP_NOZORDER};
 
-            // Check if element intersects with this monitor
-            if rect.get_left() < monitor_x + monitor_width &&
-               rect.get_left() + rect.get_width() as i32 > monitor_x &&
-               rect.get_top() < monitor_y + monitor_height &&
-               rect.get_top() + rect.get_height() as i32 > monitor_y {
-                intersected_monitors.push(monitor);
+        if let Some(pattern) = self.window_pattern::<patterns::UITransformPattern>() {
+            if pattern.can_move().unwrap_or(false) && pattern.can_resize().unwrap_or(false) {
+                let _span = pattern_span(&self.element.0, "TransformPattern", "set_window_bounds").entered();
+                let moved = pattern.move_to(x, y).and_then(|_| pattern.resize(width, height));
+                match moved {
+                    Ok(()) => return Ok(()),
+                    Err(e) => debug!("TransformPattern failed to set bounds: {}, using SetWindowPos", e),
+                }
             }
         }
+        let hwnd = self.top_level_hwnd()?;
+        unsafe {
+            SetWindowPos(
+                hwnd,
+                None,
+                x.round() as i32,
+                y.round() as i32,
+                width.round() as i32,
+                height.round() as i32,
+                SWP_NOZORDER | SWP_NOACTIVATE,
+            )
+        }
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to set window bounds: {}", e)))
+    }
+
+    fn bring_to_front(&self) -> Result<(), AutomationError> {
+        use windows::Win32::UI::WindowsAndMessaging::{
+            BringWindowToTop, IsIconic, SetForegroundWindow, ShowWindow, SW_RESTORE,
+        };
+
+        let hwnd = self.top_level_hwnd()?;
+        unsafe {
+            if IsIconic(hwnd).as_bool() {
+                let _ = ShowWindow(hwnd, SW_RESTORE);
+            }
+            let _ = BringWindowToTop(hwnd);
+            if SetForegroundWindow(hwnd).as_bool() {
+                return Ok(());
+            }
+        }
+        // Windows only lets the foreground process change the foreground
+        // window; focusing through UI Automation is not subject to that
+        debug!("SetForegroundWindow refused, focusing the window instead");
+        self.activate_window()
+    }
+
+    fn virtual_desktop_id(&self) -> Result<String, AutomationError> {
+        crate::platforms::windows_virtual_desktops::window_desktop_id(self.top_level_hwnd()?)
+    }
+
+    fn is_on_current_virtual_desktop(&self) -> Result<bool, AutomationError> {
+        crate::platforms::windows_virtual_desktops::is_on_current_desktop(self.top_level_hwnd()?)
+    }
+
+    fn move_to_virtual_desktop(&self, desktop_id: &str) -> Result<(), AutomationError> {
+        crate::platforms::windows_virtual_desktops::move_window(self.top_level_hwnd()?, desktop_id)
+    }
+
+    fn labeled_by(&self) -> Result<Option<UIElement>, AutomationError> {
+        // Elements without a label report a null element, which surfaces as an error
+        Ok(self.element.0.get_labeled_by().ok().map(|label| {
+            UIElement::new(Box::new(WindowsUIElement {
+                element: ThreadSafeWinUIElement(Arc::new(label)),
+            }))
+        }))
+    }
+
+    fn integrity_level(&self) -> Result<crate::elevation::IntegrityLevel, AutomationError> {
+        let pid = self
+            .element
+            .0
+            .get_process_id()
+            .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get process ID: {}", e))))?;
+        crate::platforms::windows_elevation::process_integrity_level(pid as u32)
+    }
+
+    fn invoke(&self) -> Result<(), AutomationError> {
+        let pattern = self.pattern::<patterns::UIInvokePattern>("InvokePattern")?;
+        let _span = pattern_span(&self.element.0, "InvokePattern", "invoke").entered();
+        pattern
+            .invoke()
+            .map_err(AutomationError::from)
+    }
+
+    fn toggle(&self) -> Result<(), AutomationError> {
+        let pattern = self.pattern::<patterns::UITogglePattern>("TogglePattern")?;
+        let _span = pattern_span(&self.element.0, "TogglePattern", "toggle").entered();
+        pattern
+            .toggle()
+            .map_err(AutomationError::from)
+    }
+
+    fn toggle_state(&self) -> Result<Option<bool>, AutomationError> {
+        let _span = pattern_span(&self.element.0, "TogglePattern", "toggle_state").entered();
+        let pattern = self.pattern::<patterns::UITogglePattern>("TogglePattern")?;
+        let state = pattern
+            .get_toggle_state()
+            .map_err(AutomationError::from)?;
+        Ok(match state {
+            ToggleState::On => Some(true),
+            ToggleState::Off => Some(false),
+            ToggleState::Indeterminate => None,
+        })
+    }
+
+    fn is_expanded(&self) -> Result<bool, AutomationError> {
+        let _span = pattern_span(&self.element.0, "ExpandCollapsePattern", "is_expanded").entered();
+        let pattern = self.pattern::<patterns::UIExpandCollapsePattern>("ExpandCollapsePattern")?;
+        let state = pattern
+            .get_state()
+            .map_err(AutomationError::from)?;
+        Ok(matches!(state, ExpandCollapseState::Expanded | ExpandCollapseState::PartiallyExpanded))
+    }
+
+    fn set_expanded(&self, expanded: bool) -> Result<(), AutomationError> {
+        let _span = pattern_span(&self.element.0, "ExpandCollapsePattern", "set_expanded").entered();
+        let pattern = self.pattern::<patterns::UIExpandCollapsePattern>("ExpandCollapsePattern")?;
+        if expanded { pattern.expand() } else { pattern.collapse() }
+            .map_err(AutomationError::from)
+    }
+
+    fn is_selected(&self) -> Result<bool, AutomationError> {
+        let _span = pattern_span(&self.element.0, "SelectionItemPattern", "is_selected").entered();
+        self.pattern::<patterns::UISelectionItemPattern>("SelectionItemPattern")?
+            .is_selected()
+            .map_err(AutomationError::from)
+    }
+
+    fn select_item(&self) -> Result<(), AutomationError> {
+        let _span = pattern_span(&self.element.0, "SelectionItemPattern", "select").entered();
+        self.pattern::<patterns::UISelectionItemPattern>("SelectionItemPattern")?
+            .select()
+            .map_err(AutomationError::from)
+    }
+
+    fn range_value(&self) -> Result<crate::controls::RangeValue, AutomationError> {
+        let _span = pattern_span(&self.element.0, "RangeValuePattern", "range_value").entered();
+        let pattern = self.pattern::<patterns::UIRangeValuePattern>("RangeValuePattern")?;
+        let read = |value: uiautomation::Result<f64>| {
+            value.map_err(AutomationError::from)
+        };
+        Ok(crate::controls::RangeValue {
+            value: read(pattern.get_value())?,
+            minimum: read(pattern.get_minimum())?,
+            maximum: read(pattern.get_maximum())?,
+            small_change: read(pattern.get_small_change()).ok(),
+            large_change: read(pattern.get_large_change()).ok(),
+            read_only: pattern.is_readonly().unwrap_or(false),
+        })
+    }
+
+    fn set_range_value(&self, value: f64) -> Result<(), AutomationError> {
+        let _span = pattern_span(&self.element.0, "RangeValuePattern", "set_range_value").entered();
+        self.pattern::<patterns::UIRangeValuePattern>("RangeValuePattern")?
+            .set_value(value)
+            .map_err(AutomationError::from)
+    }
+
+    fn selected_text(&self) -> Result<String, AutomationError> {
+        let _span = pattern_span(&self.element.0, "TextPattern", "selected_text").entered();
+        super::windows_text::selected_text(&self.element.0)
+    }
+
+    fn caret_position(&self) -> Result<Option<usize>, AutomationError> {
+        let _span = pattern_span(&self.element.0, "TextPattern", "caret_position").entered();
+        super::windows_text::caret_position(&self.element.0)
+    }
+
+    fn select_range(&self, start: usize, end: usize) -> Result<(), AutomationError> {
+        let _span = pattern_span(&self.element.0, "TextPattern", "select_range").entered();
+        super::windows_text::select_range(&self.element.0, start, end)
+    }
 
-        if intersected_monitors.is_empty() {
-            return Err(AutomationError::PlatformError("Element is not visible on any monitor".to_string()));
+    fn text_runs(&self) -> Result<Vec<crate::text_pattern::TextRun>, AutomationError> {
+        let _span = pattern_span(&self.element.0, "TextPattern", "text_with_formatting").entered();
+        super::windows_text::text_runs(&self.element.0)
+    }
+
+    fn attribute_value(&self, attribute: &str) -> Result<Option<String>, AutomationError> {
+        match attribute.to_lowercase().as_str() {
+            // `attributes()` skips the value, and progress bars and sliders
+            // only have a range value
+            "value" => {
+                if let Ok(pattern) = self.element.0.get_pattern::<patterns::UIValuePattern>() {
+                    return pattern
+                        .get_value()
+                        .map(Some)
+                        .map_err(|e| uia_error(e, AutomationError::ElementNotFound));
+                }
+                match self.element.0.get_pattern::<patterns::UIRangeValuePattern>() {
+                    Ok(pattern) => pattern
+                        .get_value()
+                        .map(|value| Some(value.to_string()))
+                        .map_err(|e| uia_error(e, AutomationError::ElementNotFound)),
+                    Err(_) => Ok(None),
+                }
+            }
+            "description" => Ok(self.element.0.get_help_text().ok().filter(|s| !s.is_empty())),
+            _ => crate::attribute_watch::generic_attribute_value(self, attribute),
         }
+    }
 
-        // If element spans multiple monitors, capture from the primary monitor
-        let monitor = &intersected_monitors[0];
-        let scale_factor = monitor.scale_factor()
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to get scale factor: {}", e)))?;
+    fn watch_attribute_changes(
+        &self,
+        attribute: &str,
+        changed: Arc<dyn Fn() + Send + Sync>,
+    ) -> Result<UIEventSubscription, AutomationError> {
+        let properties = match attribute.to_lowercase().as_str() {
+            "name" => vec![UIProperty::Name],
+            "value" => vec![UIProperty::ValueValue, UIProperty::RangeValueValue],
+            "description" => vec![UIProperty::HelpText],
+            "enabled" => vec![UIProperty::IsEnabled],
+            "focused" => vec![UIProperty::HasKeyboardFocus],
+            "visible" => vec![UIProperty::IsOffscreen],
+            _ => {
+                return Err(AutomationError::UnsupportedOperation(format!(
+                    "No UIA change events for attribute '{}'",
+                    attribute
+                )));
+            }
+        };
+        spawn_property_watcher(
+            ThreadSafeWinUIElement(self.element.0.clone()),
+            properties,
+            changed,
+        )
+    }
+
+    fn capture(&self) -> Result<ScreenshotResult, AutomationError> {
+        // Get the raw UIAutomation bounds
+        let rect = self.element.0.get_bounding_rectangle()
+            .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get bounding rectangle: {}", e))))?;
+
+        capture_screen_region(rect.get_left(), rect.get_top(), rect.get_width(), rect.get_height())
+    }
+}
 
-        // Get monitor bounds
+/// Capture a screen rectangle, in physical pixels of the monitor it starts on
+pub(crate) fn capture_screen_region(
+    left: i32,
+    top: i32,
+    width: i32,
+    height: i32,
+) -> Result<ScreenshotResult, AutomationError> {
+    // Get all monitors that intersect with the element
+    let mut intersected_monitors = Vec::new();
+    let monitors = xcap::Monitor::all()
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitors: {}", e)))?;
+
+    for monitor in monitors {
         let monitor_x = monitor.x()
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitor x: {}", e)))? as u32;
+            .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitor x: {}", e)))? as i32;
         let monitor_y = monitor.y()
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitor y: {}", e)))? as u32;
+            .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitor y: {}", e)))? as i32;
         let monitor_width = monitor.width()
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitor width: {}", e)))? as u32;
+            .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitor width: {}", e)))? as i32;
         let monitor_height = monitor.height()
-            .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitor height: {}", e)))? as u32;
-
-        // Calculate scaled coordinates
-        let scaled_x = (rect.get_left() as f64 * scale_factor as f64) as u32;
-        let scaled_y = (rect.get_top() as f64 * scale_factor as f64) as u32;
-        let scaled_width = (rect.get_width() as f64 * scale_factor as f64) as u32;
-        let scaled_height = (rect.get_height() as f64 * scale_factor as f64) as u32;
+            .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitor height: {}", e)))? as i32;
+
+        // Check if element intersects with this monitor
+        if left < monitor_x + monitor_width &&
+           left + width > monitor_x &&
+           top < monitor_y + monitor_height &&
+           top + height > monitor_y {
+            intersected_monitors.push(monitor);
+        }
+    }
 
-        // Convert to relative coordinates for capture_region
-        let rel_x = if scaled_x >= monitor_x { scaled_x - monitor_x } else { 0 };
-        let rel_y = if scaled_y >= monitor_y { scaled_y - monitor_y } else { 0 };
-        
-        // Ensure width and height don't exceed monitor bounds
-        let rel_width = std::cmp::min(scaled_width, monitor_width - rel_x);
-        let rel_height = std::cmp::min(scaled_height, monitor_height - rel_y);
-
-        // Capture the screen region
-        let capture = monitor.capture_region(
-            rel_x,
-            rel_y,
-            rel_width,
-            rel_height
-        ).map_err(|e| AutomationError::PlatformError(format!("Failed to capture region: {}", e)))?;
+    if intersected_monitors.is_empty() {
+        return Err(AutomationError::PlatformError("Element is not visible on any monitor".to_string()));
+    }
 
-        Ok(ScreenshotResult {
-            image_data: capture.to_vec(),
-            width: rel_width,
-            height: rel_height,
-        })
-    } 
+    // If element spans multiple monitors, capture from the primary monitor
+    let monitor = &intersected_monitors[0];
+    let scale_factor = monitor.scale_factor()
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to get scale factor: {}", e)))?;
+
+    // Get monitor bounds
+    let monitor_x = monitor.x()
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitor x: {}", e)))? as u32;
+    let monitor_y = monitor.y()
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitor y: {}", e)))? as u32;
+    let monitor_width = monitor.width()
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitor width: {}", e)))? as u32;
+    let monitor_height = monitor.height()
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to get monitor height: {}", e)))? as u32;
+
+    // Calculate scaled coordinates
+    let scaled_x = (left as f64 * scale_factor as f64) as u32;
+    let scaled_y = (top as f64 * scale_factor as f64) as u32;
+    let scaled_width = (width as f64 * scale_factor as f64) as u32;
+    let scaled_height = (height as f64 * scale_factor as f64) as u32;
+
+    // Convert to relative coordinates for capture_region
+    let rel_x = if scaled_x >= monitor_x { scaled_x - monitor_x } else { 0 };
+    let rel_y = if scaled_y >= monitor_y { scaled_y - monitor_y } else { 0 };
+    
+    // Ensure width and height don't exceed monitor bounds
+    let rel_width = std::cmp::min(scaled_width, monitor_width - rel_x);
+    let rel_height = std::cmp::min(scaled_height, monitor_height - rel_y);
+
+    // Capture the screen region
+    let capture = monitor.capture_region(
+        rel_x,
+        rel_y,
+        rel_width,
+        rel_height
+    ).map_err(|e| AutomationError::PlatformError(format!("Failed to capture region: {}", e)))?;
+
+    Ok(ScreenshotResult {
+        image_data: capture.to_vec(),
+        width: rel_width,
+        height: rel_height,
+    })
 }
 
 #[allow(dead_code)]
@@ -2753,15 +3834,52 @@ pub enum ActivateOptions {
 
 impl From<windows::core::Error> for AutomationError {
     fn from(error: windows::core::Error) -> Self {
-        AutomationError::PlatformError(error.to_string())
+        AutomationError::PlatformError(error.to_string()).with_platform_code(error.code().0 as i64)
     }
 }
 
-// Launches a UWP application and returns its UIElement
-fn launch_uwp_app(engine: &WindowsEngine, uwp_app_name: &str) -> Result<UIElement, AutomationError> {
+/// HRESULT of calls on an element whose control no longer exists
+const UIA_E_ELEMENTNOTAVAILABLE: i32 = 0x8004_0201_u32 as i32;
+
+impl From<uiautomation::Error> for AutomationError {
+    fn from(error: uiautomation::Error) -> Self {
+        uia_error(error, AutomationError::PlatformError)
+    }
+}
+
+// `StaleElement` when the element's control is gone, so callers holding it
+// can find it again; `otherwise` of the message for other failures. Either
+// way the HRESULT is kept as the platform code
+fn uia_error(error: uiautomation::Error, otherwise: impl FnOnce(String) -> AutomationError) -> AutomationError {
+    let code = error.code();
+    let error = if code == UIA_E_ELEMENTNOTAVAILABLE {
+        AutomationError::StaleElement(error.to_string())
+    } else {
+        otherwise(error.to_string())
+    };
+    error.with_platform_code(code as i64)
+}
+
+// Resolves an AppUserModelID and display name from an app name, a package
+// family name or an AppUserModelID
+// The package family of a name that already is an AppUserModelID, e.g.
+// Microsoft.WindowsCalculator_8wekyb3d8bbwe!App
+fn app_user_model_id_family(name: &str) -> Option<&str> {
+    let (family, app_id) = name.split_once('!')?;
+    (family.contains('_') && !family.contains(' ') && !app_id.is_empty()).then_some(family)
+}
+
+fn resolve_app_user_model_id(uwp_app_name: &str) -> Result<(String, String), AutomationError> {
+    if let Some(family) = app_user_model_id_family(uwp_app_name) {
+        let display_name = get_uwp_app_info_from_startapps(uwp_app_name)
+            .map(|(_, name)| name)
+            .unwrap_or_else(|_| family.split('_').next().unwrap_or(family).to_string());
+        return Ok((uwp_app_name.to_string(), display_name));
+    }
+
     // First try to get app info using Get-StartApps
-    let (app_user_model_id, display_name) = match get_uwp_app_info_from_startapps(uwp_app_name) {
-        Ok(info) => info,
+    match get_uwp_app_info_from_startapps(uwp_app_name) {
+        Ok(info) => Ok(info),
         Err(_) => {
             // Fallback to AppX package approach
             debug!("Failed to find app in Get-StartApps, falling back to AppX package search");
@@ -2779,9 +3897,15 @@ fn launch_uwp_app(engine: &WindowsEngine, uwp_app_name: &str) -> Result<UIElemen
                 package_family_name.trim(),
                 app_id.trim()
             );
-            (app_user_model_id, display_name)
+            Ok((app_user_model_id, display_name))
         }
-    };
+    }
+}
+
+// Launches a UWP application and returns its UIElement
+fn launch_uwp_app(engine: &WindowsEngine, uwp_app_name: &str) -> Result<UIElement, AutomationError> {
+    let (app_user_model_id, display_name) = resolve_app_user_model_id(uwp_app_name)?;
+    debug!("Resolved '{}' to AppUserModelID '{}'", uwp_app_name, app_user_model_id);
 
     // Launch the UWP app using Windows API
     let pid = unsafe {
@@ -2811,7 +3935,7 @@ fn launch_uwp_app(engine: &WindowsEngine, uwp_app_name: &str) -> Result<UIElemen
     };
 
     if pid > 0 {
-        get_application_pid(engine, pid as i32, &display_name)
+        wait_for_uwp_window(engine, pid, &display_name)
     } else {
         Err(Error::new(
             HRESULT(0x80004005u32 as i32),
@@ -2820,6 +3944,176 @@ fn launch_uwp_app(engine: &WindowsEngine, uwp_app_name: &str) -> Result<UIElemen
     }
 }
 
+// Waits for the launched app's window to get past its splash screen, falling
+// back to the generic pid / child pid / name lookup when it never shows up
+fn wait_for_uwp_window(engine: &WindowsEngine, pid: u32, display_name: &str) -> Result<UIElement, AutomationError> {
+    let start = std::time::Instant::now();
+    while start.elapsed() < UWP_LAUNCH_TIMEOUT {
+        if let Ok(app) = engine.get_application_by_pid(pid as i32, Some(Duration::from_millis(500))) {
+            let ready = app
+                .as_any()
+                .downcast_ref::<WindowsUIElement>()
+                .map(|w| !is_uwp_splash_screen(&*w.element.0))
+                .unwrap_or(true);
+            if ready {
+                debug!("UWP app '{}' ready after {:?}", display_name, start.elapsed());
+                app.activate_window()?;
+                return Ok(app);
+            }
+            debug!("UWP app '{}' still showing its splash screen", display_name);
+        }
+        thread::sleep(Duration::from_millis(250));
+    }
+    warn!(
+        "UWP app '{}' (pid {}) not ready after {:?}, falling back to generic lookup",
+        display_name, pid, UWP_LAUNCH_TIMEOUT
+    );
+    get_application_pid(engine, pid as i32, display_name)
+}
+
+// Move the pointer to (x, y) along the path of the current input profile.
+// Does nothing for profiles that jump; the caller's input moves it then
+fn travel_to(x: f64, y: f64) {
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::UI::WindowsAndMessaging::{GetCursorPos, SetCursorPos};
+
+    let profile = crate::input_profile::current();
+    if !profile.moves_mouse() {
+        return;
+    }
+    let mut from = POINT::default();
+    if unsafe { GetCursorPos(&mut from) }.is_err() {
+        return;
+    }
+    let path = profile.mouse_path(
+        crate::geometry::Point::new(from.x as f64, from.y as f64),
+        crate::geometry::Point::new(x, y),
+    );
+    let pause = profile.mouse_move_duration / path.len().max(1) as u32;
+    for point in path {
+        let _ = unsafe { SetCursorPos(point.x.round() as i32, point.y.round() as i32) };
+        thread::sleep(pause);
+    }
+}
+
+// Travel to a click target and rest there as the current input profile asks
+fn approach(x: f64, y: f64) {
+    travel_to(x, y);
+    let dwell = crate::input_profile::current().next_click_dwell();
+    if !dwell.is_zero() {
+        thread::sleep(dwell);
+    }
+}
+
+fn class_name_of(element: &uiautomation::UIElement) -> String {
+    element
+        .get_cached_classname()
+        .or_else(|_| element.get_classname())
+        .unwrap_or_default()
+}
+
+// What the XAML host checks read of an element, so their logic does not
+// depend on live windows
+trait RawViewNode: Sized {
+    fn class_name(&self) -> String;
+    // Whether the control view shows no children, as for XAML islands
+    fn control_view_is_empty(&self) -> bool;
+    fn raw_children(&self) -> Result<Vec<Self>, AutomationError>;
+}
+
+impl RawViewNode for uiautomation::UIElement {
+    fn class_name(&self) -> String {
+        class_name_of(self)
+    }
+
+    fn control_view_is_empty(&self) -> bool {
+        create_ui_automation_with_com_init()
+            .and_then(|automation| {
+                automation
+                    .get_control_view_walker()
+                    .map_err(|e| AutomationError::PlatformError(e.to_string()))
+            })
+            .map(|walker| walker.get_first_child(self).is_err())
+            .unwrap_or(false)
+    }
+
+    fn raw_children(&self) -> Result<Vec<Self>, AutomationError> {
+        raw_view_children(self)
+    }
+}
+
+// Whether the element hosts UWP / WinUI content
+fn is_xaml_host<E: RawViewNode>(element: &E) -> bool {
+    XAML_HOST_CLASS_NAMES.contains(&element.class_name().as_str())
+}
+
+// Where to search below `host` when it is a XAML host hiding its content
+// from the control view: its raw view children. None for other elements
+fn xaml_island_roots<E: RawViewNode>(host: &E) -> Option<Vec<E>> {
+    if !is_xaml_host(host) || !host.control_view_is_empty() {
+        return None;
+    }
+    match host.raw_children() {
+        Ok(children) if !children.is_empty() => Some(children),
+        Ok(_) => None,
+        Err(e) => {
+            debug!("Raw view fallback for XAML host failed: {}", e);
+            None
+        }
+    }
+}
+
+// Children through the raw view walker, which also exposes XAML island content
+fn raw_view_children(element: &uiautomation::UIElement) -> Result<Vec<uiautomation::UIElement>, AutomationError> {
+    let automation = create_ui_automation_with_com_init()?;
+    let walker = automation
+        .get_raw_view_walker()
+        .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to create raw view walker: {}", e))))?;
+    let mut children = Vec::new();
+    let mut next = walker.get_first_child(element).ok();
+    while let Some(child) = next {
+        next = walker.get_next_sibling(&child).ok();
+        children.push(child);
+    }
+    Ok(children)
+}
+
+// ApplicationFrameHost owns the frame window of every UWP app; the app itself
+// owns the CoreWindow inside it
+fn uwp_frame_hosts_pid(window: &uiautomation::UIElement, pid: u32) -> bool {
+    if class_name_of(window) != "ApplicationFrameWindow" {
+        return false;
+    }
+    raw_view_children(window)
+        .unwrap_or_default()
+        .iter()
+        .any(|child| {
+            class_name_of(child) == "Windows.UI.Core.CoreWindow"
+                && child.get_process_id().map(|p| p == pid).unwrap_or(false)
+        })
+}
+
+// A UWP window is on its splash screen while its CoreWindow exposes no content
+fn is_uwp_splash_screen<E: RawViewNode + Clone>(window: &E) -> bool {
+    let core_window = match window.class_name().as_str() {
+        "Windows.UI.Core.CoreWindow" => Some(window.clone()),
+        "ApplicationFrameWindow" => window
+            .raw_children()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|child| child.class_name() == "Windows.UI.Core.CoreWindow"),
+        // Unpackaged WinUI 3 and Win32 windows have no splash screen
+        _ => return false,
+    };
+    match core_window {
+        Some(core_window) => core_window
+            .raw_children()
+            .map(|children| children.is_empty())
+            .unwrap_or(true),
+        None => true,
+    }
+}
+
 // Gets UWP app information using Get-StartApps
 fn get_uwp_app_info_from_startapps(uwp_app_name: &str) -> Result<(String, String), AutomationError> {
     let command = format!(
@@ -3140,7 +4434,55 @@ fn launch_regular_application(engine: &WindowsEngine, app_name: &str) -> Result<
 }
 
 // make easier to pass roles
-fn map_generic_role_to_win_roles(role: &str) -> ControlType {
+/// Tests each element of a matcher walk against a selector built from
+/// `Not`, `And` and `Or`, see [`Selector::check_element_predicate`]
+struct SelectorFilter {
+    selector: Selector,
+}
+
+impl MatcherFilter for SelectorFilter {
+    fn judge(&self, element: &uiautomation::UIElement) -> uiautomation::Result<bool> {
+        Ok(element_matches(&self.selector, element))
+    }
+}
+
+// Mirrors what the matching single selector search finds. Properties that
+// cannot be read count as not matching
+fn element_matches(selector: &Selector, element: &uiautomation::UIElement) -> bool {
+    let name_contains = |needle: &str| {
+        element
+            .get_name()
+            .is_ok_and(|name| name.to_lowercase().contains(&needle.to_lowercase()))
+    };
+    match selector {
+        Selector::Role { role, name } => {
+            element.get_control_type().ok() == Some(map_generic_role_to_win_roles(role))
+                && name.as_deref().is_none_or(name_contains)
+        }
+        Selector::Name(name) => name_contains(name),
+        Selector::NameL10n(_) => element_matches(&selector.localized(), element),
+        Selector::LocalizedName(names) => element
+            .get_name()
+            .is_ok_and(|name| crate::l10n::is_one_of(&name, names)),
+        Selector::Text(text) => {
+            name_contains(text) || element.get_control_type().ok() == Some(ControlType::Text)
+        }
+        Selector::Id(id) => generate_element_id(element).is_ok_and(|calculated| calculated.to_string() == *id),
+        Selector::NativeId(automation_id) => element
+            .get_automation_id()
+            .is_ok_and(|id| id == *automation_id),
+        Selector::ClassName(classname) => element
+            .get_classname()
+            .is_ok_and(|class| class == *classname),
+        Selector::Not(inner) => !element_matches(inner, element),
+        Selector::And(selectors) => selectors.iter().all(|s| element_matches(s, element)),
+        Selector::Or(selectors) => selectors.iter().any(|s| element_matches(s, element)),
+        // Rejected by check_element_predicate before the search starts
+        _ => false,
+    }
+}
+
+pub(crate) fn map_generic_role_to_win_roles(role: &str) -> ControlType {
     match role.to_lowercase().as_str() {
         "pane" | "app" | "application" => ControlType::Pane,
         "window" | "dialog" => ControlType::Window,
@@ -3252,26 +4594,26 @@ fn generate_element_id(element: &uiautomation::UIElement) -> Result<usize, Autom
     // Try cached versions first, fallback to live versions
     let control_type = element.get_cached_control_type()
         .or_else(|_| element.get_control_type())
-        .map_err(|e| AutomationError::PlatformError(format!("Failed to get control type: {}", e)))?;
+        .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get control type: {}", e))))?;
     let name = element.get_cached_name()
         .or_else(|_| element.get_name())
-        .map_err(|e| AutomationError::PlatformError(format!("Failed to get name: {}", e)))?;
+        .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get name: {}", e))))?;
     let automation_id = element.get_cached_automation_id()
         .or_else(|_| element.get_automation_id())
-        .map_err(|e| AutomationError::PlatformError(format!("Failed to get automation ID: {}", e)))?;
+        .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get automation ID: {}", e))))?;
     let class_name = element.get_cached_classname()
         .or_else(|_| element.get_classname())
-        .map_err(|e| AutomationError::PlatformError(format!("Failed to get classname: {}", e)))?;
+        .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get classname: {}", e))))?;
     let bounds = element.get_cached_bounding_rectangle()
         .or_else(|_| element.get_bounding_rectangle())
-        .map_err(|e| AutomationError::PlatformError(format!("Failed to get bounding rectangle: {}", e)))?;
+        .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get bounding rectangle: {}", e))))?;
     // runtime_id is fundamental and less likely to have a distinct cached vs. live fetch issue here
     // It's usually retrieved when the element handle is obtained.
     let runtime_id = element.get_runtime_id()
-        .map_err(|e| AutomationError::PlatformError(format!("Failed to get runtime ID: {}", e)))?;
+        .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get runtime ID: {}", e))))?;
     let help_text = element.get_cached_help_text()
         .or_else(|_| element.get_help_text())
-        .map_err(|e| AutomationError::PlatformError(format!("Failed to get help text: {}", e)))?;
+        .map_err(|e| uia_error(e, |e| AutomationError::PlatformError(format!("Failed to get help text: {}", e))))?;
 
     // Create a stable string representation
     let id_string = format!(
@@ -3307,8 +4649,25 @@ pub fn convert_uiautomation_element_to_terminator(element: uiautomation::UIEleme
     }))
 }
 
+/// The UI Automation element behind `element`, `None` for other element types
+pub(crate) fn raw_element(element: &UIElement) -> Option<uiautomation::UIElement> {
+    element
+        .as_any()
+        .downcast_ref::<WindowsUIElement>()
+        .map(|win_element| win_element.element.0.as_ref().clone())
+}
+
+/// A UI Automation pattern of `element`, see [`UIElement::pattern`]
+pub(crate) fn element_pattern<P: patterns::UIPattern>(element: &UIElement) -> Result<P, AutomationError> {
+    let win_element = element
+        .as_any()
+        .downcast_ref::<WindowsUIElement>()
+        .ok_or_else(|| AutomationError::PlatformError("Unexpected element type".to_string()))?;
+    win_element.pattern::<P>(crate::element::pattern_name(std::any::type_name::<P>()))
+}
+
 // Helper function to create UIAutomation instance with proper COM initialization
-fn create_ui_automation_with_com_init() -> Result<UIAutomation, AutomationError> {
+pub(crate) fn create_ui_automation_with_com_init() -> Result<UIAutomation, AutomationError> {
     unsafe {
         let hr = CoInitializeEx(None, COINIT_MULTITHREADED);
         if hr.is_err() && hr != HRESULT(0x80010106u32 as i32) {
@@ -3317,7 +4676,7 @@ fn create_ui_automation_with_com_init() -> Result<UIAutomation, AutomationError>
         }
     }
     
-    UIAutomation::new_direct().map_err(|e| AutomationError::PlatformError(e.to_string()))
+    UIAutomation::new_direct().map_err(AutomationError::from)
 }
 
 fn build_ui_node_tree_configurable(
@@ -3327,6 +4686,9 @@ fn build_ui_node_tree_configurable(
 ) -> Result<crate::UINode, AutomationError> {
     context.increment_element_count();
     context.update_max_depth(current_depth);
+    if let Some(handles) = context.handles.as_mut() {
+        handles.push(element.clone());
+    }
     
     // Yield CPU periodically to prevent freezing while processing everything
     if context.should_yield() {
@@ -3334,10 +4696,36 @@ fn build_ui_node_tree_configurable(
         thread::sleep(Duration::from_millis(1));
     }
     
-    // Get element attributes with configurable property loading
-    let attributes = get_configurable_attributes(element, &context.property_mode);
+    // Get element attributes with configurable property loading, from the
+    // cache request that fetched the element if there was one
+    let attributes = match context.cache.as_ref().and_then(|cache| cache.attributes(element)) {
+        Some(attributes) => {
+            context.cached_elements += 1;
+            attributes
+        }
+        None => {
+            if context.cache.is_some() {
+                context.increment_fallback();
+            }
+            get_configurable_attributes(element, &context.property_mode)
+        }
+    };
     
     let mut children_nodes = Vec::new();
+    let mut status = crate::SubtreeStatus::Complete;
+    
+    // Out of time: keep this node, but don't descend any further
+    if context.deadline_passed() {
+        context.truncated_subtrees += 1;
+        return Ok(crate::UINode {
+            id: element.id(),
+            attributes,
+            path: None,
+            truncated: true,
+            status: crate::SubtreeStatus::TimedOut,
+            children: children_nodes,
+        });
+    }
     
     // Get children with safe strategy
     match get_element_children_safe(element, context) {
@@ -3345,18 +4733,41 @@ fn build_ui_node_tree_configurable(
             debug!("Processing {} children at depth {} (using safe strategy)", children_elements.len(), current_depth);
             
             // Process children in efficient batches
-            for batch in children_elements.chunks(context.config.batch_size) {
+            for (batch_index, batch) in children_elements.chunks(context.config.batch_size).enumerate() {
+                let _batch = tracing::debug_span!(
+                    "tree_batch",
+                    depth = current_depth,
+                    batch = batch_index,
+                    size = batch.len(),
+                    parent.role = attributes.role.as_str(),
+                    parent.name = attributes.name.as_deref().unwrap_or_default(),
+                )
+                .entered();
                 for child_element in batch {
+                    if context.deadline_passed() {
+                        status = crate::SubtreeStatus::TimedOut;
+                        break;
+                    }
+                    let handle_count = context.handles.as_ref().map_or(0, Vec::len);
                     match build_ui_node_tree_configurable(child_element, current_depth + 1, context) {
                         Ok(child_node) => children_nodes.push(child_node),
                         Err(e) => {
                             debug!("Failed to process child element: {}. Continuing with next child.", e);
                             context.increment_errors();
+                            // Drop the handles of the subtree that was not kept
+                            if let Some(handles) = context.handles.as_mut() {
+                                handles.truncate(handle_count);
+                            }
                             // Continue processing - we want the full tree
                         }
                     }
                 }
                 
+                if !status.is_complete() {
+                    context.truncated_subtrees += 1;
+                    break;
+                }
+                
                 // Small yield between large batches to maintain responsiveness
                 if batch.len() == context.config.batch_size && children_elements.len() > context.config.batch_size {
                     thread::sleep(Duration::from_millis(1));
@@ -3366,64 +4777,692 @@ fn build_ui_node_tree_configurable(
         Err(e) => {
             debug!("Failed to get children for element: {}. Proceeding with no children.", e);
             context.increment_errors();
+            status = crate::SubtreeStatus::Failed;
         }
     }
     
+    let truncated = !status.is_complete() || children_nodes.iter().any(|child| child.truncated);
     Ok(crate::UINode {
         id: element.id(),
         attributes,
+        path: None,
+        truncated,
+        status,
         children: children_nodes,
     })
 }
 
+// Paths for a window tree, starting with the window's role and title so they
+// resolve from the desktop root
+fn assign_tree_paths(tree: &mut crate::UINode) {
+    let root = crate::selector_path::root_step(&tree.attributes.role, tree.attributes.name.as_deref());
+    crate::selector_path::assign_paths(tree, root);
+}
+
+// Elements matching a path selector, polling until at least one matches or
+// the timeout expires. A search still running at the timeout is cut short
+fn find_by_path(
+    path: &str,
+    root: &UIElement,
+    timeout: Duration,
+    depth: Option<usize>,
+) -> Result<Vec<UIElement>, AutomationError> {
+    let steps = crate::selector_path::parse_path(path).map_err(AutomationError::InvalidArgument)?;
+    let start = std::time::Instant::now();
+    let deadline = start + timeout;
+    loop {
+        let elements = match crate::selector_path::resolve_path(root, &steps, depth, Some(deadline)) {
+            Ok(elements) => elements,
+            Err(e) if e.code() == crate::ErrorCode::Timeout => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        if !elements.is_empty() {
+            return Ok(elements);
+        }
+        if start.elapsed() >= timeout {
+            return Err(AutomationError::ElementNotFound(format!(
+                "No element found for path '{}'",
+                path
+            )));
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
 /// Get element attributes based on the configured property loading mode
 fn get_configurable_attributes(element: &UIElement, property_mode: &crate::platforms::PropertyLoadingMode) -> UIElementAttributes {
-    match property_mode {
-        crate::platforms::PropertyLoadingMode::Fast => {
-            // Only essential properties - current optimized version
-            element.attributes()
-        },
-        crate::platforms::PropertyLoadingMode::Complete => {
-            // Get full attributes by temporarily bypassing optimization
-            get_complete_attributes(element)
-        },
-        crate::platforms::PropertyLoadingMode::Smart => {
-            // Load properties based on element type
-            get_smart_attributes(element)
+    let mut attributes = element.attributes();
+    // The same fields a cached tree of this mode has, read live
+    if let Some(raw) = raw_element(element) {
+        let string = |property: UIProperty| -> Option<String> {
+            raw.get_property_value(property)
+                .ok()
+                .and_then(|value| value.try_into().ok())
+                .filter(|value: &String| !value.is_empty())
+        };
+        let boolean = |property: UIProperty| -> Option<bool> {
+            raw.get_property_value(property)
+                .ok()
+                .and_then(|value| value.try_into().ok())
+        };
+        add_mode_attributes(&mut attributes, property_mode, string, boolean);
+    }
+    attributes
+}
+
+// Session id of the current process
+fn current_session_id() -> Result<u32, AutomationError> {
+    let mut session_id = 0u32;
+    unsafe {
+        ProcessIdToSessionId(GetCurrentProcessId(), &mut session_id).map_err(|e| {
+            AutomationError::PlatformError(format!("Failed to get session id: {}", e))
+        })?;
+    }
+    Ok(session_id)
+}
+
+// Detect whether the current session can be automated
+fn query_session_state() -> Result<SessionState, AutomationError> {
+    let session_id = current_session_id()?;
+    Ok(SessionState::for_windows_session(
+        session_id,
+        session_disconnected(),
+        &input_desktop(),
+    ))
+}
+
+// Whether the client of the current session has disconnected
+fn session_disconnected() -> bool {
+    unsafe {
+        let mut buffer = PWSTR::null();
+        let mut bytes_returned = 0u32;
+        if WTSQuerySessionInformationW(
+            Some(WTS_CURRENT_SERVER_HANDLE),
+            WTS_CURRENT_SESSION,
+            WTSConnectState,
+            &mut buffer,
+            &mut bytes_returned,
+        )
+        .is_err()
+            || buffer.is_null()
+        {
+            debug!("WTSQuerySessionInformationW failed, skipping connect state check");
+            return false;
         }
+        let state = *(buffer.0 as *const WTS_CONNECTSTATE_CLASS);
+        WTSFreeMemory(buffer.0 as *mut _);
+        state == WTSDisconnected
     }
 }
 
-/// Get complete attributes for an element (all properties)
-fn get_complete_attributes(element: &UIElement) -> UIElementAttributes {
-    // This would be the original attributes() implementation
-    // For now, just use the current optimized one
-    // TODO: Implement full property loading when needed
-    element.attributes()
+// The desktop receiving input. While locked it is the secure "Winlogon"
+// desktop, which a normal process cannot open
+fn input_desktop() -> InputDesktop {
+    unsafe {
+        let desktop = match OpenInputDesktop(DESKTOP_CONTROL_FLAGS(0), false, DESKTOP_SWITCHDESKTOP) {
+            Ok(desktop) => desktop,
+            Err(e) => {
+                debug!("OpenInputDesktop failed: {}", e);
+                return InputDesktop::Unreachable;
+            }
+        };
+
+        let mut name_buf = [0u16; 256];
+        let mut needed = 0u32;
+        let name_ok = GetUserObjectInformationW(
+            HANDLE(desktop.0),
+            UOI_NAME,
+            Some(name_buf.as_mut_ptr() as *mut _),
+            (name_buf.len() * 2) as u32,
+            Some(&mut needed as *mut u32),
+        )
+        .is_ok();
+        let _ = CloseDesktop(desktop);
+
+        InputDesktop::Open(name_ok.then(|| {
+            let len = name_buf.iter().position(|&c| c == 0).unwrap_or(name_buf.len());
+            String::from_utf16_lossy(&name_buf[..len])
+        }))
+    }
 }
 
-/// Get smart attributes based on element type
-fn get_smart_attributes(element: &UIElement) -> UIElementAttributes {
-    let role = element.role();
-    
-    // Load different properties based on element type
-    match role.as_str() {
-        "Button" | "MenuItem" => {
-            // For interactive elements, load name and enabled state
-            element.attributes()
-        },
-        "Edit" | "Text" => {
-            // For text elements, load value and text content
-            element.attributes()
-        },
-        "Window" | "Dialog" => {
-            // For containers, load name and description
-            element.attributes()
-        },
-        _ => {
-            // Default to fast loading for unknown types
-            element.attributes()
+// AttachConsole is process-wide, so console calls must not interleave
+static CONSOLE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+const CONSOLE_VK_RETURN: u16 = 0x0D;
+
+// Run `f` while attached to the console of `pid`, then return to our own console
+fn with_attached_console<T>(
+    pid: u32,
+    f: impl FnOnce() -> Result<T, AutomationError>,
+) -> Result<T, AutomationError> {
+    let _guard = CONSOLE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let had_console = unsafe { !GetConsoleWindow().is_invalid() };
+    unsafe {
+        let _ = FreeConsole();
+    }
+
+    let result = match unsafe { AttachConsole(pid) } {
+        Ok(()) => {
+            let result = f();
+            unsafe {
+                let _ = FreeConsole();
+            }
+            result
+        }
+        Err(e) => Err(AutomationError::PlatformError(format!(
+            "Failed to attach to console of process {}: {}",
+            pid, e
+        ))),
+    };
+
+    if had_console {
+        unsafe {
+            let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+        }
+    }
+    result
+}
+
+// Open the attached console's input (CONIN$) or output (CONOUT$) buffer
+fn open_console_buffer(name: &str) -> Result<HANDLE, AutomationError> {
+    unsafe {
+        CreateFileW(
+            &HSTRING::from(name),
+            (GENERIC_READ | GENERIC_WRITE).0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            None,
+            OPEN_EXISTING,
+            FILE_FLAGS_AND_ATTRIBUTES(0),
+            None,
+        )
+    }
+    .map_err(|e| AutomationError::PlatformError(format!("Failed to open {}: {}", name, e)))
+}
+
+// Read the characters in the visible window of a screen buffer
+fn read_screen_buffer(handle: HANDLE) -> Result<ConsoleScreen, AutomationError> {
+    let mut info = CONSOLE_SCREEN_BUFFER_INFO::default();
+    unsafe { GetConsoleScreenBufferInfo(handle, &mut info) }.map_err(|e| {
+        AutomationError::PlatformError(format!("Failed to get console buffer info: {}", e))
+    })?;
+
+    let window = info.srWindow;
+    let width = (window.Right - window.Left + 1).max(0) as u16;
+    let height = (window.Bottom - window.Top + 1).max(0) as u16;
+    let mut buffer = vec![0u16; width as usize];
+    let mut lines = Vec::with_capacity(height as usize);
+    for row in window.Top..=window.Bottom {
+        let mut read = 0u32;
+        let origin = COORD { X: window.Left, Y: row };
+        unsafe { ReadConsoleOutputCharacterW(handle, &mut buffer, origin, &mut read) }.map_err(
+            |e| AutomationError::PlatformError(format!("Failed to read console row {}: {}", row, e)),
+        )?;
+        let line = String::from_utf16_lossy(&buffer[..read as usize]);
+        lines.push(line.trim_end().to_string());
+    }
+
+    let cursor = info.dwCursorPosition;
+    Ok(ConsoleScreen {
+        width,
+        height,
+        cursor: (
+            (cursor.X - window.Left).max(0) as u16,
+            (cursor.Y - window.Top).max(0) as u16,
+        ),
+        lines,
+    })
+}
+
+// Key down/up record pairs for each UTF-16 unit; newlines become Enter
+fn console_key_records(text: &str) -> Vec<INPUT_RECORD> {
+    let mut records = Vec::new();
+    for unit in console::input_units(text) {
+        let virtual_key = if unit == '\r' as u16 { CONSOLE_VK_RETURN } else { 0 };
+        for key_down in [true, false] {
+            records.push(INPUT_RECORD {
+                EventType: KEY_EVENT as u16,
+                Event: INPUT_RECORD_0 {
+                    KeyEvent: KEY_EVENT_RECORD {
+                        bKeyDown: key_down.into(),
+                        wRepeatCount: 1,
+                        wVirtualKeyCode: virtual_key,
+                        wVirtualScanCode: 0,
+                        uChar: KEY_EVENT_RECORD_0 { UnicodeChar: unit },
+                        dwControlKeyState: 0,
+                    },
+                },
+            });
+        }
+    }
+    records
+}
+
+// Write all records, looping when the console accepts only part of them
+fn write_input_records(handle: HANDLE, records: &[INPUT_RECORD]) -> Result<(), AutomationError> {
+    let mut remaining = records;
+    while !remaining.is_empty() {
+        let mut written = 0u32;
+        unsafe { WriteConsoleInputW(handle, remaining, &mut written) }.map_err(|e| {
+            AutomationError::PlatformError(format!("Failed to write console input: {}", e))
+        })?;
+        if written == 0 {
+            return Err(AutomationError::PlatformError(
+                "Console accepted no input".to_string(),
+            ));
+        }
+        remaining = &remaining[written as usize..];
+    }
+    Ok(())
+}
+
+// Properties reported as PropertyChanged events
+const WATCHED_PROPERTIES: [UIProperty; 3] = [
+    UIProperty::Name,
+    UIProperty::ValueValue,
+    UIProperty::HasKeyboardFocus,
+];
+
+// Run UIA event handlers on a dedicated STA thread with its own message pump.
+// UIA delivers events on that thread, so elements are converted there before
+// being handed to the callback.
+fn spawn_ui_event_watcher(
+    kinds: Vec<UIEventKind>,
+    callback: UIEventCallback,
+) -> Result<UIEventSubscription, AutomationError> {
+    use uiautomation::events::{
+        CustomFocusChangedEventHandler, CustomPropertyChangedEventHandlerFn,
+        CustomStructureChangedEventHandlerFn, UIFocusChangedEventHandler,
+        UIPropertyChangedEventHandler, UIStructureChangeEventHandler,
+    };
+
+    struct FocusHandler {
+        callback: UIEventCallback,
+    }
+
+    impl CustomFocusChangedEventHandler for FocusHandler {
+        fn handle(&self, sender: &uiautomation::UIElement) -> uiautomation::Result<()> {
+            (self.callback)(UIEvent {
+                kind: UIEventKind::FocusChanged,
+                element: Some(convert_uiautomation_element_to_terminator(sender.clone())),
+                detail: None,
+                value: None,
+            });
+            Ok(())
+        }
+    }
+
+    debug!("Starting UI event watcher for {:?}", kinds);
+    spawn_uia_watcher("UI event watcher", move |automation| {
+        let root = automation.get_root_element()?;
+
+        if kinds.contains(&UIEventKind::FocusChanged) {
+            let handler = UIFocusChangedEventHandler::from(FocusHandler {
+                callback: callback.clone(),
+            });
+            automation.add_focus_changed_event_handler(None, &handler)?;
+        }
+
+        if kinds.contains(&UIEventKind::StructureChanged) {
+            let structure_callback = callback.clone();
+            let handler: Box<CustomStructureChangedEventHandlerFn> =
+                Box::new(move |sender, change_type, _runtime_id| {
+                    structure_callback(UIEvent {
+                        kind: UIEventKind::StructureChanged,
+                        element: Some(convert_uiautomation_element_to_terminator(
+                            sender.clone(),
+                        )),
+                        detail: Some(format!("{:?}", change_type)),
+                        value: None,
+                    });
+                    Ok(())
+                });
+            automation.add_structure_changed_event_handler(
+                &root,
+                TreeScope::Subtree,
+                None,
+                &UIStructureChangeEventHandler::from(handler),
+            )?;
+        }
+
+        if kinds.contains(&UIEventKind::PropertyChanged) {
+            let property_callback = callback.clone();
+            let handler: Box<CustomPropertyChangedEventHandlerFn> =
+                Box::new(move |sender, property, value| {
+                    property_callback(UIEvent {
+                        kind: UIEventKind::PropertyChanged,
+                        element: Some(convert_uiautomation_element_to_terminator(
+                            sender.clone(),
+                        )),
+                        detail: Some(format!("{:?}", property)),
+                        value: value.get_string().ok(),
+                    });
+                    Ok(())
+                });
+            automation.add_property_changed_event_handler(
+                &root,
+                TreeScope::Subtree,
+                None,
+                &UIPropertyChangedEventHandler::from(handler),
+                &WATCHED_PROPERTIES,
+            )?;
+        }
+        Ok(())
+    })
+}
+
+// Call `changed` whenever one of `properties` of `element` changes
+fn spawn_property_watcher(
+    element: ThreadSafeWinUIElement,
+    properties: Vec<UIProperty>,
+    changed: Arc<dyn Fn() + Send + Sync>,
+) -> Result<UIEventSubscription, AutomationError> {
+    use uiautomation::events::{CustomPropertyChangedEventHandlerFn, UIPropertyChangedEventHandler};
+
+    spawn_uia_watcher("Property watcher", move |automation| {
+        let handler: Box<CustomPropertyChangedEventHandlerFn> =
+            Box::new(move |_sender, _property, _value| {
+                changed();
+                Ok(())
+            });
+        automation.add_property_changed_event_handler(
+            &element.0,
+            TreeScope::Element,
+            None,
+            &UIPropertyChangedEventHandler::from(handler),
+            &properties,
+        )
+    })
+}
+
+// Start an STA thread that registers UIA event handlers with `register` and
+// pumps messages until the returned subscription posts WM_QUIT
+fn spawn_uia_watcher<F>(label: &'static str, register: F) -> Result<UIEventSubscription, AutomationError>
+where
+    F: FnOnce(&UIAutomation) -> Result<(), uiautomation::Error> + Send + 'static,
+{
+    use windows::Win32::System::Com::COINIT_APARTMENTTHREADED;
+    use windows::Win32::System::Threading::GetCurrentThreadId;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        DispatchMessageW, GetMessageW, MSG, TranslateMessage,
+    };
+
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel::<Result<u32, AutomationError>>();
+
+    thread::spawn(move || {
+        let com_initialized = unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok() };
+        let automation = match UIAutomation::new_direct() {
+            Ok(automation) => automation,
+            Err(e) => {
+                let _ = ready_tx.send(Err(AutomationError::from(e)));
+                return;
+            }
+        };
+
+        if let Err(e) = register(&automation) {
+            let _ = automation.remove_all_event_handlers();
+            let _ = ready_tx.send(Err(AutomationError::PlatformError(format!(
+                "Failed to register UI event handlers: {}",
+                e
+            ))));
+            return;
+        }
+
+        let _ = ready_tx.send(Ok(unsafe { GetCurrentThreadId() }));
+        debug!("{} started", label);
+
+        // Pump messages until the subscription posts WM_QUIT
+        unsafe {
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, None, 0, 0).0 > 0 {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        }
+
+        let _ = automation.remove_all_event_handlers();
+        drop(automation);
+        if com_initialized {
+            unsafe { CoUninitialize() };
+        }
+        debug!("{} stopped", label);
+    });
+
+    let thread_id = ready_rx.recv().map_err(|_| {
+        AutomationError::Internal(format!("{} exited before starting", label))
+    })??;
+
+    Ok(UIEventSubscription::new(move || unsafe {
+        use windows::Win32::Foundation::{LPARAM, WPARAM};
+        use windows::Win32::UI::WindowsAndMessaging::{PostThreadMessageW, WM_QUIT};
+        let _ = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+    }))
+}
+
+const PERSONALIZE_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize";
+const ACCESSIBILITY_KEY: &str = r"Software\Microsoft\Accessibility";
+const CURRENT_VERSION_KEY: &str = r"SOFTWARE\Microsoft\Windows NT\CurrentVersion";
+
+// Read a DWORD registry value, None if the key or value does not exist
+fn read_registry_dword(
+    root: windows::Win32::System::Registry::HKEY,
+    subkey: &str,
+    value: &str,
+) -> Option<u32> {
+    use windows::Win32::System::Registry::{RRF_RT_REG_DWORD, RegGetValueW};
+
+    let mut data: u32 = 0;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let status = unsafe {
+        RegGetValueW(
+            root,
+            &HSTRING::from(subkey),
+            &HSTRING::from(value),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut data as *mut u32 as *mut std::ffi::c_void),
+            Some(&mut size),
+        )
+    };
+    status.is_ok().then_some(data)
+}
+
+// A boolean SystemParametersInfo setting, None if the call fails
+fn read_system_flag(
+    action: windows::Win32::UI::WindowsAndMessaging::SYSTEM_PARAMETERS_INFO_ACTION,
+) -> Option<bool> {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS, SystemParametersInfoW,
+    };
+
+    // Win32 BOOL
+    let mut flag: i32 = 0;
+    unsafe {
+        SystemParametersInfoW(
+            action,
+            0,
+            Some(&mut flag as *mut i32 as *mut std::ffi::c_void),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    }
+    .ok()
+    .map(|_| flag != 0)
+}
+
+// "major.minor.build.revision" from the executable's version resource
+fn file_version(path: &std::path::Path) -> Option<String> {
+    use windows::Win32::Storage::FileSystem::{
+        GetFileVersionInfoSizeW, GetFileVersionInfoW, VS_FIXEDFILEINFO, VerQueryValueW,
+    };
+
+    let path = HSTRING::from(path.as_os_str());
+    unsafe {
+        let size = GetFileVersionInfoSizeW(&path, None);
+        if size == 0 {
+            return None;
+        }
+        let mut data = vec![0u8; size as usize];
+        GetFileVersionInfoW(&path, None, size, data.as_mut_ptr() as *mut _).ok()?;
+
+        let mut info: *mut std::ffi::c_void = std::ptr::null_mut();
+        let mut len = 0u32;
+        if !VerQueryValueW(data.as_ptr() as *const _, &HSTRING::from("\\"), &mut info, &mut len)
+            .as_bool()
+            || info.is_null()
+            || (len as usize) < std::mem::size_of::<VS_FIXEDFILEINFO>()
+        {
+            return None;
+        }
+        let info = &*(info as *const VS_FIXEDFILEINFO);
+        Some(format!(
+            "{}.{}.{}.{}",
+            info.dwFileVersionMS >> 16,
+            info.dwFileVersionMS & 0xffff,
+            info.dwFileVersionLS >> 16,
+            info.dwFileVersionLS & 0xffff
+        ))
+    }
+}
+
+fn query_environment() -> EnvironmentInfo {
+    use windows::Win32::System::Registry::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+    use windows::Win32::UI::WindowsAndMessaging::{SPI_GETCLIENTAREAANIMATION, SPI_GETSCREENREADER};
+
+    // sysinfo reports the build number as the kernel version; the update
+    // revision (UBR) distinguishes cumulative updates of the same build
+    let os_build = sysinfo::System::kernel_version().map(|build| {
+        match read_registry_dword(HKEY_LOCAL_MACHINE, CURRENT_VERSION_KEY, "UBR") {
+            Some(revision) => format!("{}.{}", build, revision),
+            None => build,
         }
+    });
+
+    let theme = read_registry_dword(HKEY_CURRENT_USER, PERSONALIZE_KEY, "AppsUseLightTheme")
+        .map(|light| if light == 0 { Theme::Dark } else { Theme::Light })
+        // The value is missing until the user first changes the theme
+        .or(Some(Theme::Light));
+
+    // Missing until "Make text bigger" is changed from its default of 100%
+    let text_scale_percent =
+        read_registry_dword(HKEY_CURRENT_USER, ACCESSIBILITY_KEY, "TextScaleFactor").unwrap_or(100);
+
+    EnvironmentInfo {
+        os: std::env::consts::OS.to_string(),
+        os_version: sysinfo::System::long_os_version(),
+        os_build,
+        theme,
+        animations_enabled: read_system_flag(SPI_GETCLIENTAREAANIMATION),
+        text_scale_factor: Some(text_scale_percent as f64 / 100.0),
+        display_scale_factor: None,
+        screen_reader_active: read_system_flag(SPI_GETSCREENREADER),
     }
 }
 
+/// Span around a single UIA pattern call. Element details are only read
+/// (one COM round trip each) when the span is recorded
+fn pattern_span(
+    element: &uiautomation::UIElement,
+    pattern: &'static str,
+    call: &'static str,
+) -> tracing::Span {
+    let span = tracing::debug_span!(
+        "uia_pattern",
+        pattern,
+        call,
+        element.control_type = tracing::field::Empty,
+        element.name = tracing::field::Empty,
+    );
+    if !span.is_disabled() {
+        if let Ok(control_type) = element.get_control_type() {
+            span.record("element.control_type", tracing::field::debug(control_type));
+        }
+        if let Ok(name) = element.get_name() {
+            span.record("element.name", name.as_str());
+        }
+    }
+    span
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A window as the raw view shows it
+    #[derive(Debug, Clone)]
+    struct Node {
+        class_name: &'static str,
+        control_children: usize,
+        raw_children: Vec<Node>,
+    }
+
+    fn node(class_name: &'static str, control_children: usize, raw_children: Vec<Node>) -> Node {
+        Node {
+            class_name,
+            control_children,
+            raw_children,
+        }
+    }
+
+    impl RawViewNode for Node {
+        fn class_name(&self) -> String {
+            self.class_name.to_string()
+        }
+
+        fn control_view_is_empty(&self) -> bool {
+            self.control_children == 0
+        }
+
+        fn raw_children(&self) -> Result<Vec<Self>, AutomationError> {
+            Ok(self.raw_children.clone())
+        }
+    }
+
+    #[test]
+    fn test_app_user_model_id_family() {
+        assert_eq!(
+            app_user_model_id_family("Microsoft.WindowsCalculator_8wekyb3d8bbwe!App"),
+            Some("Microsoft.WindowsCalculator_8wekyb3d8bbwe")
+        );
+        // Names to look up in the Start menu and the installed packages
+        assert_eq!(app_user_model_id_family("calculator"), None);
+        assert_eq!(app_user_model_id_family("Hello!"), None);
+        assert_eq!(app_user_model_id_family("Microsoft.WindowsCalculator_8wekyb3d8bbwe!"), None);
+        assert_eq!(app_user_model_id_family("My App_1!Run"), None);
+    }
+
+    #[test]
+    fn test_uwp_splash_screen() {
+        let content = node("Windows.UI.Xaml.Controls.Grid", 3, vec![]);
+        let loading = node("ApplicationFrameWindow", 1, vec![node("Windows.UI.Core.CoreWindow", 0, vec![])]);
+        assert!(is_uwp_splash_screen(&loading));
+        let loaded = node(
+            "ApplicationFrameWindow",
+            1,
+            vec![node("Windows.UI.Core.CoreWindow", 1, vec![content.clone()])],
+        );
+        assert!(!is_uwp_splash_screen(&loaded));
+        assert!(!is_uwp_splash_screen(&node("Windows.UI.Core.CoreWindow", 1, vec![content.clone()])));
+        // A frame whose app window is not there yet
+        assert!(is_uwp_splash_screen(&node("ApplicationFrameWindow", 0, vec![])));
+        // Win32 and unpackaged WinUI 3 windows
+        assert!(!is_uwp_splash_screen(&node("Notepad", 0, vec![])));
+        assert!(!is_uwp_splash_screen(&node("WinUIDesktopWin32WindowClass", 0, vec![])));
+    }
+
+    #[test]
+    fn test_xaml_island_roots() {
+        let button = node("Button", 0, vec![]);
+        let island = node("DesktopWindowXamlSource", 0, vec![button.clone()]);
+        assert!(is_xaml_host(&island));
+        let roots = xaml_island_roots(&island).unwrap();
+        assert_eq!(roots.len(), 1);
+        assert_eq!(roots[0].class_name, "Button");
+
+        // Hosts whose content the control view shows are searched as usual
+        assert!(xaml_island_roots(&node("DesktopWindowXamlSource", 1, vec![button.clone()])).is_none());
+        // So are empty hosts and other elements
+        assert!(xaml_island_roots(&node("Windows.UI.Core.CoreWindow", 0, vec![])).is_none());
+        assert!(!is_xaml_host(&node("Notepad", 0, vec![button.clone()])));
+        assert!(xaml_island_roots(&node("Notepad", 0, vec![button])).is_none());
+    }
+}
@@ -155,6 +155,8 @@ mod performance_benchmarks {
                 timeout_per_operation_ms: Some(50),
                 yield_every_n_elements: Some(50),
                 batch_size: Some(50),
+                max_total_duration: None,
+                use_cache_request: true,
             };
             
             match engine.get_window_tree(pid, Some(&window_title), config) {
@@ -0,0 +1,180 @@
+//! UIA cache requests for tree building
+//!
+//! Reading an element's properties one by one costs a cross-process COM call
+//! each. A [`TreeCache`] asks UIA for all children of an element together
+//! with the properties the [`PropertyLoadingMode`] needs, in a single call,
+//! and reads the attributes back from the cache. Elements the cache cannot
+//! serve fall back to the live lookups.
+
+use std::collections::HashMap;
+
+use tracing::debug;
+use uiautomation::core::{UICacheRequest, UICondition};
+use uiautomation::types::{TreeScope, UIProperty};
+use uiautomation::UIAutomation;
+
+use super::windows::{convert_uiautomation_element_to_terminator, raw_element};
+use super::{PropertyLoadingMode, TreeBuildConfig};
+use crate::{AutomationError, UIElement, UIElementAttributes};
+
+/// Properties fetched with the children for each loading mode
+pub(crate) fn cached_properties(mode: &PropertyLoadingMode) -> &'static [UIProperty] {
+    match mode {
+        PropertyLoadingMode::Fast => &[
+            UIProperty::ControlType,
+            UIProperty::Name,
+            UIProperty::AutomationId,
+        ],
+        PropertyLoadingMode::Smart => &[
+            UIProperty::ControlType,
+            UIProperty::Name,
+            UIProperty::AutomationId,
+            UIProperty::ValueValue,
+            UIProperty::IsKeyboardFocusable,
+        ],
+        PropertyLoadingMode::Complete => &[
+            UIProperty::ControlType,
+            UIProperty::Name,
+            UIProperty::AutomationId,
+            UIProperty::ValueValue,
+            UIProperty::IsKeyboardFocusable,
+            UIProperty::HelpText,
+            UIProperty::ClassName,
+            UIProperty::IsEnabled,
+        ],
+    }
+}
+
+/// A cache request and the condition matching every child
+pub(crate) struct TreeCache {
+    request: UICacheRequest,
+    condition: UICondition,
+    mode: PropertyLoadingMode,
+}
+
+impl TreeCache {
+    /// The cache for a tree built with `config`, `None` if the config turns
+    /// caching off or UIA refuses the request
+    pub(crate) fn for_config(automation: &UIAutomation, config: &TreeBuildConfig) -> Option<Self> {
+        if !config.use_cache_request {
+            return None;
+        }
+        match Self::new(automation, &config.property_mode) {
+            Ok(cache) => Some(cache),
+            Err(e) => {
+                debug!("Building the tree without a cache request: {}", e);
+                None
+            }
+        }
+    }
+
+    fn new(automation: &UIAutomation, mode: &PropertyLoadingMode) -> Result<Self, AutomationError> {
+        let request = automation.create_cache_request().map_err(AutomationError::from)?;
+        for property in cached_properties(mode) {
+            request.add_property(*property).map_err(AutomationError::from)?;
+        }
+        // Only the element itself; children come from `find_all_build_cache`
+        request
+            .set_tree_scope(TreeScope::Element)
+            .map_err(AutomationError::from)?;
+        let condition = automation.create_true_condition().map_err(AutomationError::from)?;
+        Ok(Self {
+            request,
+            condition,
+            mode: mode.clone(),
+        })
+    }
+
+    /// `element` with its properties cached, for the root of a tree
+    pub(crate) fn refresh(&self, element: &UIElement) -> Option<UIElement> {
+        let raw = raw_element(element)?;
+        raw.build_updated_cache(&self.request)
+            .ok()
+            .map(convert_uiautomation_element_to_terminator)
+    }
+
+    /// All children of `element` with their properties, in one call
+    pub(crate) fn children(&self, element: &UIElement) -> Result<Vec<UIElement>, AutomationError> {
+        let raw = raw_element(element).ok_or_else(|| {
+            AutomationError::PlatformError("Not a UI Automation element".to_string())
+        })?;
+        let children = raw
+            .find_all_build_cache(TreeScope::Children, &self.condition, &self.request)
+            .map_err(AutomationError::from)?;
+        Ok(children
+            .into_iter()
+            .map(convert_uiautomation_element_to_terminator)
+            .collect())
+    }
+
+    /// Attributes read from the cache, `None` if `element` was not fetched
+    /// through this cache
+    pub(crate) fn attributes(&self, element: &UIElement) -> Option<UIElementAttributes> {
+        let raw = raw_element(element)?;
+        let role = raw.get_cached_control_type().ok()?.to_string();
+        let name = raw.get_cached_name().ok().filter(|name| !name.is_empty());
+        let mut properties = HashMap::new();
+        let cached_string = |property: UIProperty| -> Option<String> {
+            raw.get_cached_property_value(property)
+                .ok()
+                .and_then(|value| value.try_into().ok())
+                .filter(|value: &String| !value.is_empty())
+        };
+        let cached_bool = |property: UIProperty| -> Option<bool> {
+            raw.get_cached_property_value(property)
+                .ok()
+                .and_then(|value| value.try_into().ok())
+        };
+
+        // Same rule as the live attributes: the id only stands in for a missing name
+        if name.is_none() {
+            if let Some(automation_id) = cached_string(UIProperty::AutomationId) {
+                properties.insert(
+                    "AutomationId".to_string(),
+                    Some(serde_json::Value::String(automation_id)),
+                );
+            }
+        }
+        let mut attributes = UIElementAttributes {
+            role,
+            name,
+            label: None,
+            value: None,
+            description: None,
+            properties,
+            is_keyboard_focusable: None,
+        };
+        add_mode_attributes(&mut attributes, &self.mode, cached_string, cached_bool);
+        Some(attributes)
+    }
+}
+
+/// Fill in the fields `mode` loads beyond the role, name and id, reading
+/// properties with `string` and `boolean`. Cached and live attributes both
+/// go through here so a tree has the same fields however it was built
+pub(crate) fn add_mode_attributes(
+    attributes: &mut UIElementAttributes,
+    mode: &PropertyLoadingMode,
+    string: impl Fn(UIProperty) -> Option<String>,
+    boolean: impl Fn(UIProperty) -> Option<bool>,
+) {
+    if matches!(mode, PropertyLoadingMode::Fast) {
+        return;
+    }
+    attributes.value = string(UIProperty::ValueValue);
+    attributes.is_keyboard_focusable = boolean(UIProperty::IsKeyboardFocusable);
+    if !matches!(mode, PropertyLoadingMode::Complete) {
+        return;
+    }
+    attributes.description = string(UIProperty::HelpText);
+    if let Some(class_name) = string(UIProperty::ClassName) {
+        attributes
+            .properties
+            .insert("ClassName".to_string(), Some(serde_json::Value::String(class_name)));
+    }
+    if let Some(enabled) = boolean(UIProperty::IsEnabled) {
+        attributes
+            .properties
+            .insert("IsEnabled".to_string(), Some(serde_json::Value::Bool(enabled)));
+    }
+}
@@ -0,0 +1,137 @@
+//! Integrity levels of processes, read from their access tokens
+//!
+//! Opening the token of an elevated process is denied to a process at a
+//! lower level; that denial is taken to mean the target is elevated, since a
+//! process of the same user at the same level could always be opened.
+
+use std::ffi::c_void;
+use std::sync::OnceLock;
+use tracing::debug;
+use windows::Win32::Foundation::{CloseHandle, ERROR_ACCESS_DENIED, HANDLE};
+use windows::Win32::Security::{
+    GetSidSubAuthority, GetSidSubAuthorityCount, GetTokenInformation, TokenElevation,
+    TokenIntegrityLevel, TokenUIAccess, TOKEN_ELEVATION, TOKEN_INFORMATION_CLASS,
+    TOKEN_MANDATORY_LABEL, TOKEN_QUERY,
+};
+use windows::Win32::System::Threading::{
+    GetCurrentProcess, OpenProcess, OpenProcessToken, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+
+use crate::elevation::{AutomationCapabilities, IntegrityLevel};
+use crate::AutomationError;
+
+/// Capabilities of this process, read once
+pub(crate) fn current_capabilities() -> AutomationCapabilities {
+    static CAPABILITIES: OnceLock<AutomationCapabilities> = OnceLock::new();
+    CAPABILITIES
+        .get_or_init(|| {
+            let capabilities = match read_own_capabilities() {
+                Ok(capabilities) => capabilities,
+                Err(e) => {
+                    debug!("Failed to read the integrity level of this process: {}", e);
+                    AutomationCapabilities::unrestricted()
+                }
+            };
+            debug!(?capabilities, "Automation capabilities of this process");
+            capabilities
+        })
+        .clone()
+}
+
+fn read_own_capabilities() -> Result<AutomationCapabilities, AutomationError> {
+    let token = Token::open(unsafe { GetCurrentProcess() })?;
+    let elevation: TOKEN_ELEVATION = token.query(TokenElevation)?;
+    let ui_access: u32 = token.query(TokenUIAccess)?;
+    Ok(AutomationCapabilities {
+        integrity_level: Some(token.integrity_level()?),
+        elevated: elevation.TokenIsElevated != 0,
+        ui_access: ui_access != 0,
+    })
+}
+
+/// Integrity level of process `pid`
+pub(crate) fn process_integrity_level(pid: u32) -> Result<IntegrityLevel, AutomationError> {
+    let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) }.map_err(|e| {
+        AutomationError::PlatformError(format!("Failed to open process {}: {}", pid, e))
+    })?;
+    let token = Token::open(process);
+    let _ = unsafe { CloseHandle(process) };
+    match token {
+        Ok(token) => token.integrity_level(),
+        Err(AutomationError::PermissionDenied(_)) => Ok(IntegrityLevel::High),
+        Err(e) => Err(e),
+    }
+}
+
+/// An access token opened for querying, closed on drop
+struct Token(HANDLE);
+
+impl Token {
+    fn open(process: HANDLE) -> Result<Self, AutomationError> {
+        let mut token = HANDLE::default();
+        unsafe { OpenProcessToken(process, TOKEN_QUERY, &mut token) }.map_err(|e| {
+            if e.code() == ERROR_ACCESS_DENIED.to_hresult() {
+                AutomationError::PermissionDenied(format!("Failed to open process token: {}", e))
+            } else {
+                AutomationError::PlatformError(format!("Failed to open process token: {}", e))
+            }
+        })?;
+        Ok(Self(token))
+    }
+
+    /// A fixed-size piece of token information
+    fn query<T: Default>(&self, class: TOKEN_INFORMATION_CLASS) -> Result<T, AutomationError> {
+        let mut value = T::default();
+        let mut length = 0u32;
+        unsafe {
+            GetTokenInformation(
+                self.0,
+                class,
+                Some(&mut value as *mut T as *mut c_void),
+                std::mem::size_of::<T>() as u32,
+                &mut length,
+            )
+        }
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to query token: {}", e)))?;
+        Ok(value)
+    }
+
+    fn integrity_level(&self) -> Result<IntegrityLevel, AutomationError> {
+        // The label is followed by its SID, so the size has to be asked for
+        let mut length = 0u32;
+        let _ = unsafe { GetTokenInformation(self.0, TokenIntegrityLevel, None, 0, &mut length) };
+        if length == 0 {
+            return Err(AutomationError::PlatformError(
+                "Token has no integrity level".to_string(),
+            ));
+        }
+        // u64 keeps the buffer aligned for the label's pointer
+        let mut buffer = vec![0u64; (length as usize).div_ceil(8)];
+        unsafe {
+            GetTokenInformation(
+                self.0,
+                TokenIntegrityLevel,
+                Some(buffer.as_mut_ptr() as *mut c_void),
+                length,
+                &mut length,
+            )
+        }
+        .map_err(|e| {
+            AutomationError::PlatformError(format!("Failed to query integrity level: {}", e))
+        })?;
+
+        let label = unsafe { &*(buffer.as_ptr() as *const TOKEN_MANDATORY_LABEL) };
+        let sid = label.Label.Sid;
+        let rid = unsafe {
+            let count = *GetSidSubAuthorityCount(sid);
+            *GetSidSubAuthority(sid, count.saturating_sub(1) as u32)
+        };
+        Ok(IntegrityLevel::from_rid(rid))
+    }
+}
+
+impl Drop for Token {
+    fn drop(&mut self) {
+        let _ = unsafe { CloseHandle(self.0) };
+    }
+}
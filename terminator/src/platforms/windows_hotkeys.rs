@@ -0,0 +1,199 @@
+//! Dispatcher thread for system-wide hotkeys
+//!
+//! `RegisterHotKey` without a window posts `WM_HOTKEY` to the thread that
+//! registered the key, so every hotkey is registered on one dedicated thread
+//! that pumps messages and calls the callbacks. Other threads queue register
+//! and unregister requests and post a message to wake it.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::mpsc;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread;
+use tracing::{debug, warn};
+use windows::Win32::Foundation::{LPARAM, WPARAM};
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    HOT_KEY_MODIFIERS, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT, MOD_WIN, RegisterHotKey,
+    UnregisterHotKey,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetMessageW, MSG, PM_NOREMOVE, PeekMessageW, PostThreadMessageW, WM_APP, WM_HOTKEY, WM_USER,
+};
+
+use crate::AutomationError;
+use crate::hotkey::{Hotkey, HotkeyCallback, HotkeyKey, HotkeyRegistration};
+
+const WM_REQUESTS: u32 = WM_APP + 2;
+
+enum Request {
+    Register {
+        id: i32,
+        hotkey: Hotkey,
+        callback: HotkeyCallback,
+        reply: mpsc::Sender<Result<(), String>>,
+    },
+    Unregister {
+        id: i32,
+    },
+}
+
+static REQUESTS: Mutex<Vec<Request>> = Mutex::new(Vec::new());
+static DISPATCHER_THREAD: OnceLock<Result<u32, String>> = OnceLock::new();
+static NEXT_ID: AtomicI32 = AtomicI32::new(1);
+
+fn requests() -> MutexGuard<'static, Vec<Request>> {
+    REQUESTS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Register `hotkey` system-wide. Fails if another application already owns it
+pub(crate) fn register(
+    hotkey: Hotkey,
+    callback: HotkeyCallback,
+) -> Result<HotkeyRegistration, AutomationError> {
+    let thread_id = dispatcher_thread()?;
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let (reply_tx, reply_rx) = mpsc::channel();
+    submit(
+        thread_id,
+        Request::Register {
+            id,
+            hotkey,
+            callback,
+            reply: reply_tx,
+        },
+    )?;
+    reply_rx
+        .recv()
+        .map_err(|_| AutomationError::Internal("Hotkey dispatcher stopped".to_string()))?
+        .map_err(AutomationError::PlatformError)?;
+
+    Ok(HotkeyRegistration::new(hotkey, move || {
+        if let Err(e) = submit(thread_id, Request::Unregister { id }) {
+            warn!("Failed to unregister hotkey: {}", e);
+        }
+    }))
+}
+
+fn submit(thread_id: u32, request: Request) -> Result<(), AutomationError> {
+    requests().push(request);
+    unsafe { PostThreadMessageW(thread_id, WM_REQUESTS, WPARAM(0), LPARAM(0)) }.map_err(|e| {
+        AutomationError::PlatformError(format!("Failed to signal hotkey dispatcher: {}", e))
+    })
+}
+
+// Thread ID of the dispatcher thread, starting it on first use
+fn dispatcher_thread() -> Result<u32, AutomationError> {
+    DISPATCHER_THREAD
+        .get_or_init(|| {
+            let (ready_tx, ready_rx) = mpsc::channel();
+            thread::Builder::new()
+                .name("hotkey-dispatcher".to_string())
+                .spawn(move || run(ready_tx))
+                .map_err(|e| format!("Failed to start hotkey dispatcher thread: {}", e))?;
+            ready_rx
+                .recv()
+                .map_err(|_| "Hotkey dispatcher thread exited during startup".to_string())
+        })
+        .clone()
+        .map_err(AutomationError::PlatformError)
+}
+
+fn run(ready: mpsc::Sender<u32>) {
+    // Create this thread's message queue before publishing its ID, so
+    // messages posted right away are not lost
+    let mut msg = MSG::default();
+    unsafe {
+        let _ = PeekMessageW(&mut msg, None, WM_USER, WM_USER, PM_NOREMOVE);
+    }
+    let _ = ready.send(unsafe { GetCurrentThreadId() });
+
+    let mut callbacks: HashMap<i32, (Hotkey, HotkeyCallback)> = HashMap::new();
+    while unsafe { GetMessageW(&mut msg, None, 0, 0) }.as_bool() {
+        match msg.message {
+            WM_REQUESTS => {
+                let pending = std::mem::take(&mut *requests());
+                for request in pending {
+                    handle(request, &mut callbacks);
+                }
+            }
+            WM_HOTKEY => {
+                if let Some((hotkey, callback)) = callbacks.get(&(msg.wParam.0 as i32)) {
+                    debug!(%hotkey, "Hotkey pressed");
+                    callback();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn handle(request: Request, callbacks: &mut HashMap<i32, (Hotkey, HotkeyCallback)>) {
+    match request {
+        Request::Register {
+            id,
+            hotkey,
+            callback,
+            reply,
+        } => {
+            let result = unsafe { RegisterHotKey(None, id, modifiers(&hotkey), virtual_key(hotkey.key)) }
+                .map_err(|e| format!("Failed to register hotkey {}: {}", hotkey, e));
+            if result.is_ok() {
+                debug!(%hotkey, id, "Registered hotkey");
+                callbacks.insert(id, (hotkey, callback));
+            }
+            let _ = reply.send(result);
+        }
+        Request::Unregister { id } => {
+            if let Some((hotkey, _)) = callbacks.remove(&id) {
+                if let Err(e) = unsafe { UnregisterHotKey(None, id) } {
+                    warn!(%hotkey, "Failed to unregister hotkey: {}", e);
+                } else {
+                    debug!(%hotkey, id, "Unregistered hotkey");
+                }
+            }
+        }
+    }
+}
+
+// Holding the keys down fires once instead of auto-repeating
+fn modifiers(hotkey: &Hotkey) -> HOT_KEY_MODIFIERS {
+    let mut modifiers = MOD_NOREPEAT;
+    if hotkey.ctrl {
+        modifiers |= MOD_CONTROL;
+    }
+    if hotkey.alt {
+        modifiers |= MOD_ALT;
+    }
+    if hotkey.shift {
+        modifiers |= MOD_SHIFT;
+    }
+    if hotkey.meta {
+        modifiers |= MOD_WIN;
+    }
+    modifiers
+}
+
+fn virtual_key(key: HotkeyKey) -> u32 {
+    match key {
+        // Virtual-key codes of letters and digits are their uppercase ASCII codes
+        HotkeyKey::Char(c) => c as u32,
+        HotkeyKey::F(n) => 0x70 + (n as u32 - 1),
+        HotkeyKey::Space => 0x20,
+        HotkeyKey::Enter => 0x0D,
+        HotkeyKey::Tab => 0x09,
+        HotkeyKey::Escape => 0x1B,
+        HotkeyKey::Backspace => 0x08,
+        HotkeyKey::Delete => 0x2E,
+        HotkeyKey::Insert => 0x2D,
+        HotkeyKey::Home => 0x24,
+        HotkeyKey::End => 0x23,
+        HotkeyKey::PageUp => 0x21,
+        HotkeyKey::PageDown => 0x22,
+        HotkeyKey::Left => 0x25,
+        HotkeyKey::Up => 0x26,
+        HotkeyKey::Right => 0x27,
+        HotkeyKey::Down => 0x28,
+        HotkeyKey::PrintScreen => 0x2C,
+    }
+}
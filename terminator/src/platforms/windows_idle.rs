@@ -0,0 +1,118 @@
+//! Whether a process is ready for input
+//!
+//! `WaitForInputIdle` only covers the first time a GUI process waits for
+//! input after starting. A UI thread that is busy later is caught by sending
+//! its windows `WM_NULL` with a short timeout: a thread that is not pumping
+//! messages cannot answer.
+
+use std::time::{Duration, Instant};
+use tracing::debug;
+use windows::Win32::Foundation::{BOOL, CloseHandle, HWND, LPARAM, WAIT_FAILED, WAIT_TIMEOUT, WPARAM};
+use windows::Win32::System::Threading::{
+    OpenProcess, WaitForInputIdle, PROCESS_QUERY_INFORMATION, PROCESS_SYNCHRONIZE,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    EnumWindows, GetWindowThreadProcessId, IsWindowVisible, SendMessageTimeoutW, SMTO_ABORTIFHUNG,
+    SMTO_BLOCK, WM_NULL,
+};
+
+use crate::AutomationError;
+
+/// How long one window gets to answer a probe
+const PROBE_TIMEOUT: Duration = Duration::from_millis(200);
+const PROBE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Wait until process `pid` has finished starting up and all of its visible
+/// top-level windows answer messages
+pub(crate) fn wait_until_responsive(pid: u32, timeout: Duration) -> Result<(), AutomationError> {
+    let start = Instant::now();
+    wait_for_input_idle(pid, timeout)?;
+
+    loop {
+        let hung = hung_windows(pid);
+        if hung == 0 {
+            debug!(pid, elapsed_ms = start.elapsed().as_millis(), "Process is responsive");
+            return Ok(());
+        }
+        if start.elapsed() >= timeout {
+            return Err(AutomationError::Timeout(format!(
+                "{} window(s) of process {} still not responding after {:?}",
+                hung, pid, timeout
+            )));
+        }
+        std::thread::sleep(PROBE_INTERVAL);
+    }
+}
+
+fn wait_for_input_idle(pid: u32, timeout: Duration) -> Result<(), AutomationError> {
+    let process = unsafe { OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_SYNCHRONIZE, false, pid) }
+        .map_err(|e| {
+            AutomationError::PlatformError(format!("Failed to open process {}: {}", pid, e))
+        })?;
+    let millis = timeout.as_millis().min(u32::MAX as u128 - 1) as u32;
+    let result = unsafe { WaitForInputIdle(process, millis) };
+    let _ = unsafe { CloseHandle(process) };
+
+    match result {
+        0 => Ok(()),
+        r if r == WAIT_TIMEOUT.0 => Err(AutomationError::Timeout(format!(
+            "Process {} did not become ready for input within {:?}",
+            pid, timeout
+        ))),
+        // Console and other processes without a message queue have no input
+        // idle state; only the window probe applies to them
+        r if r == WAIT_FAILED.0 => {
+            debug!(pid, "WaitForInputIdle not applicable");
+            Ok(())
+        }
+        r => Err(AutomationError::PlatformError(format!(
+            "WaitForInputIdle on process {} returned {}",
+            pid, r
+        ))),
+    }
+}
+
+/// Number of visible top-level windows of `pid` that fail to answer `WM_NULL`
+fn hung_windows(pid: u32) -> usize {
+    windows_of(pid)
+        .into_iter()
+        .filter(|hwnd| {
+            let answered = unsafe {
+                SendMessageTimeoutW(
+                    *hwnd,
+                    WM_NULL,
+                    WPARAM(0),
+                    LPARAM(0),
+                    SMTO_ABORTIFHUNG | SMTO_BLOCK,
+                    PROBE_TIMEOUT.as_millis() as u32,
+                    None,
+                )
+            };
+            answered.0 == 0
+        })
+        .count()
+}
+
+fn windows_of(pid: u32) -> Vec<HWND> {
+    struct Search {
+        pid: u32,
+        found: Vec<HWND>,
+    }
+
+    unsafe extern "system" fn visit(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let search = unsafe { &mut *(lparam.0 as *mut Search) };
+        let mut window_pid = 0;
+        unsafe { GetWindowThreadProcessId(hwnd, Some(&mut window_pid)) };
+        if window_pid == search.pid && unsafe { IsWindowVisible(hwnd) }.as_bool() {
+            search.found.push(hwnd);
+        }
+        BOOL(1)
+    }
+
+    let mut search = Search {
+        pid,
+        found: Vec::new(),
+    };
+    let _ = unsafe { EnumWindows(Some(visit), LPARAM(&mut search as *mut Search as isize)) };
+    search.found
+}
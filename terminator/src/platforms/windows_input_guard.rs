@@ -0,0 +1,161 @@
+//! Thread that keeps physical input blocked
+//!
+//! `BlockInput` can only be undone by the thread that called it, and
+//! low-level hooks run on the thread that installed them, which has to pump
+//! messages. So each block gets a thread that installs it, pumps messages
+//! until it is told to quit, and then removes it. If the thread dies, Windows
+//! lifts `BlockInput` and drops the hooks on its own.
+//!
+//! Ctrl+Alt+Del does not remove the hooks, so the keyboard hook lets
+//! Ctrl+Alt+Esc through and lifts the block when it sees it.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use tracing::{debug, warn};
+use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    BlockInput, VK_CONTROL, VK_ESCAPE, VK_LCONTROL, VK_LMENU, VK_MENU, VK_RCONTROL, VK_RMENU,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, GetMessageW, HC_ACTION, HHOOK, KBDLLHOOKSTRUCT, LLKHF_INJECTED, LLMHF_INJECTED,
+    MSG, MSLLHOOKSTRUCT, PM_NOREMOVE, PeekMessageW, PostThreadMessageW, SetWindowsHookExW,
+    UnhookWindowsHookEx, WH_KEYBOARD_LL, WH_MOUSE_LL, WM_KEYDOWN, WM_QUIT, WM_SYSKEYDOWN, WM_USER,
+};
+
+use crate::AutomationError;
+
+// Physical modifier state for the escape hotkey. The hook swallows the
+// modifiers, so the system's key state does not see them
+static CTRL_DOWN: AtomicBool = AtomicBool::new(false);
+static ALT_DOWN: AtomicBool = AtomicBool::new(false);
+
+enum Method {
+    BlockInput,
+    Hooks { keyboard: HHOOK, mouse: HHOOK },
+}
+
+/// Start blocking physical input. The returned closure lifts the block
+pub(crate) fn block() -> Result<Box<dyn FnOnce() + Send>, AutomationError> {
+    let (ready_tx, ready_rx) = mpsc::channel();
+    thread::Builder::new()
+        .name("input-guard".to_string())
+        .spawn(move || run(ready_tx))
+        .map_err(|e| {
+            AutomationError::PlatformError(format!("Failed to start input guard thread: {}", e))
+        })?;
+    let thread_id = ready_rx.recv().map_err(|_| {
+        AutomationError::Internal("Input guard thread exited during startup".to_string())
+    })??;
+
+    Ok(Box::new(move || {
+        if let Err(e) = unsafe { PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0)) } {
+            warn!("Failed to stop input guard thread: {}", e);
+        }
+    }))
+}
+
+fn run(ready: mpsc::Sender<Result<u32, AutomationError>>) {
+    // Create this thread's message queue before publishing its ID, so a
+    // release right away is not lost
+    let mut msg = MSG::default();
+    unsafe {
+        let _ = PeekMessageW(&mut msg, None, WM_USER, WM_USER, PM_NOREMOVE);
+    }
+    let method = match start() {
+        Ok(method) => method,
+        Err(e) => {
+            let _ = ready.send(Err(e));
+            return;
+        }
+    };
+    let _ = ready.send(Ok(unsafe { GetCurrentThreadId() }));
+
+    // Hooks are called from inside GetMessageW
+    while unsafe { GetMessageW(&mut msg, None, 0, 0) }.0 > 0 {}
+
+    stop(method);
+}
+
+fn start() -> Result<Method, AutomationError> {
+    // Needs an elevated process
+    if unsafe { BlockInput(true) }.is_ok() {
+        debug!("Blocking input with BlockInput");
+        return Ok(Method::BlockInput);
+    }
+
+    CTRL_DOWN.store(false, Ordering::Relaxed);
+    ALT_DOWN.store(false, Ordering::Relaxed);
+    let keyboard = unsafe { SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_hook), None, 0) }
+        .map_err(|e| {
+            AutomationError::PlatformError(format!("Failed to install keyboard hook: {}", e))
+        })?;
+    let mouse = match unsafe { SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_hook), None, 0) } {
+        Ok(mouse) => mouse,
+        Err(e) => {
+            let _ = unsafe { UnhookWindowsHookEx(keyboard) };
+            return Err(AutomationError::PlatformError(format!(
+                "Failed to install mouse hook: {}",
+                e
+            )));
+        }
+    };
+    debug!("Blocking input with low-level hooks");
+    Ok(Method::Hooks { keyboard, mouse })
+}
+
+fn stop(method: Method) {
+    match method {
+        Method::BlockInput => {
+            if let Err(e) = unsafe { BlockInput(false) } {
+                warn!("Failed to unblock input: {}", e);
+            }
+        }
+        Method::Hooks { keyboard, mouse } => {
+            for hook in [keyboard, mouse] {
+                if let Err(e) = unsafe { UnhookWindowsHookEx(hook) } {
+                    warn!("Failed to remove input hook: {}", e);
+                }
+            }
+        }
+    }
+}
+
+// Returning a non-zero value swallows the event. Injected events, from
+// SendInput and so from this crate, are passed on, and so is the escape
+// hotkey
+unsafe extern "system" fn keyboard_hook(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 {
+        let event = unsafe { &*(lparam.0 as *const KBDLLHOOKSTRUCT) };
+        if (event.flags & LLKHF_INJECTED).0 == 0 {
+            let down = matches!(wparam.0 as u32, WM_KEYDOWN | WM_SYSKEYDOWN);
+            let key = event.vkCode as u16;
+            if [VK_CONTROL, VK_LCONTROL, VK_RCONTROL].iter().any(|vk| vk.0 == key) {
+                CTRL_DOWN.store(down, Ordering::Relaxed);
+            } else if [VK_MENU, VK_LMENU, VK_RMENU].iter().any(|vk| vk.0 == key) {
+                ALT_DOWN.store(down, Ordering::Relaxed);
+            } else if key == VK_ESCAPE.0
+                && down
+                && CTRL_DOWN.load(Ordering::Relaxed)
+                && ALT_DOWN.load(Ordering::Relaxed)
+            {
+                // Posts the quit message that removes these hooks
+                crate::input_guard::lift_current("escape hotkey pressed");
+                return unsafe { CallNextHookEx(None, code, wparam, lparam) };
+            }
+            return LRESULT(1);
+        }
+    }
+    unsafe { CallNextHookEx(None, code, wparam, lparam) }
+}
+
+unsafe extern "system" fn mouse_hook(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 {
+        let event = unsafe { &*(lparam.0 as *const MSLLHOOKSTRUCT) };
+        if event.flags & LLMHF_INJECTED == 0 {
+            return LRESULT(1);
+        }
+    }
+    unsafe { CallNextHookEx(None, code, wparam, lparam) }
+}
@@ -0,0 +1,738 @@
+//! MSAA fallback for applications without a UI Automation tree
+//!
+//! Some legacy Win32 and Java applications answer UIA with an empty window
+//! while still exposing their controls through MSAA `IAccessible`, which
+//! IAccessible2 implementations also provide. When a window or pane has no
+//! UIA children, its MSAA client object is enumerated instead and each
+//! accessible object is wrapped in an [`MsaaUIElement`], so callers keep
+//! working with ordinary [`UIElement`]s. Operations that concern the whole
+//! window (activation, closing, opacity, ...) go to the UIA element of the
+//! window hosting the object.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+use uiautomation::controls::ControlType;
+use uiautomation::inputs::{Keyboard, Mouse};
+use uiautomation::types::Point;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::Accessibility::{
+    AccessibleChildren, AccessibleObjectFromWindow, IAccessible, WindowFromAccessibleObject,
+    ROLE_SYSTEM_ALERT, ROLE_SYSTEM_APPLICATION, ROLE_SYSTEM_BUTTONMENU, ROLE_SYSTEM_CELL,
+    ROLE_SYSTEM_CHECKBUTTON, ROLE_SYSTEM_CLIENT, ROLE_SYSTEM_COLUMNHEADER, ROLE_SYSTEM_COMBOBOX,
+    ROLE_SYSTEM_DIALOG, ROLE_SYSTEM_DOCUMENT, ROLE_SYSTEM_GRAPHIC, ROLE_SYSTEM_GROUPING,
+    ROLE_SYSTEM_LINK, ROLE_SYSTEM_LIST, ROLE_SYSTEM_LISTITEM, ROLE_SYSTEM_MENUBAR,
+    ROLE_SYSTEM_MENUITEM, ROLE_SYSTEM_MENUPOPUP, ROLE_SYSTEM_OUTLINE, ROLE_SYSTEM_OUTLINEITEM,
+    ROLE_SYSTEM_PAGETAB, ROLE_SYSTEM_PAGETABLIST, ROLE_SYSTEM_PANE, ROLE_SYSTEM_PROGRESSBAR,
+    ROLE_SYSTEM_PUSHBUTTON, ROLE_SYSTEM_RADIOBUTTON, ROLE_SYSTEM_ROW, ROLE_SYSTEM_ROWHEADER,
+    ROLE_SYSTEM_SCROLLBAR, ROLE_SYSTEM_SEPARATOR, ROLE_SYSTEM_SLIDER, ROLE_SYSTEM_SPINBUTTON,
+    ROLE_SYSTEM_SPLITBUTTON, ROLE_SYSTEM_STATICTEXT, ROLE_SYSTEM_STATUSBAR, ROLE_SYSTEM_TABLE,
+    ROLE_SYSTEM_TEXT, ROLE_SYSTEM_TITLEBAR, ROLE_SYSTEM_TOOLBAR, ROLE_SYSTEM_TOOLTIP,
+    ROLE_SYSTEM_WINDOW, SELFLAG_TAKEFOCUS, SELFLAG_TAKESELECTION, STATE_SYSTEM_FOCUSABLE,
+    STATE_SYSTEM_FOCUSED, STATE_SYSTEM_INVISIBLE, STATE_SYSTEM_OFFSCREEN, STATE_SYSTEM_READONLY,
+    STATE_SYSTEM_UNAVAILABLE,
+};
+use windows::Win32::UI::WindowsAndMessaging::{GetWindowThreadProcessId, IsWindow, OBJID_CLIENT};
+use windows::core::{BSTR, IUnknown, Interface, VARIANT};
+
+use super::windows::{
+    WindowsEngine, capture_screen_region, convert_uiautomation_element_to_terminator,
+    create_ui_automation_with_com_init, map_generic_role_to_win_roles,
+};
+use crate::element::UIElementImpl;
+use crate::{ActionStrategy, AutomationError, ClickResult, Locator, ScreenshotResult, Selector, UIElement, UIElementAttributes};
+
+const CHILDID_SELF: i32 = 0;
+
+// Interval between searches while waiting for an MSAA element to appear
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+struct ThreadSafeAccessible(IAccessible);
+
+// The engine runs COM multithreaded, where MSAA proxies can be called from any thread
+unsafe impl Send for ThreadSafeAccessible {}
+unsafe impl Sync for ThreadSafeAccessible {}
+
+/// An element reached through MSAA rather than UI Automation
+#[derive(Clone)]
+pub struct MsaaUIElement {
+    accessible: Arc<ThreadSafeAccessible>,
+    // CHILDID_SELF for full accessible objects, otherwise the 1-based index of
+    // a simple element (list item, menu entry, ...) owned by `accessible`
+    child_id: i32,
+    hwnd: isize,
+}
+
+impl MsaaUIElement {
+    fn new(accessible: IAccessible, child_id: i32, hwnd: HWND) -> Self {
+        Self {
+            accessible: Arc::new(ThreadSafeAccessible(accessible)),
+            child_id,
+            hwnd: hwnd.0 as isize,
+        }
+    }
+
+    fn acc(&self) -> &IAccessible {
+        &self.accessible.0
+    }
+
+    fn child(&self) -> VARIANT {
+        VARIANT::from(self.child_id)
+    }
+
+    fn hwnd(&self) -> HWND {
+        HWND(self.hwnd as *mut _)
+    }
+
+    fn string_property(&self, get: impl FnOnce(&IAccessible, &VARIANT) -> windows::core::Result<BSTR>) -> Option<String> {
+        get(self.acc(), &self.child())
+            .ok()
+            .map(|value| value.to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn msaa_name(&self) -> Option<String> {
+        self.string_property(|acc, child| unsafe { acc.get_accName(child) })
+    }
+
+    fn msaa_value(&self) -> Option<String> {
+        self.string_property(|acc, child| unsafe { acc.get_accValue(child) })
+    }
+
+    fn msaa_description(&self) -> Option<String> {
+        self.string_property(|acc, child| unsafe { acc.get_accDescription(child) })
+    }
+
+    fn msaa_role(&self) -> u32 {
+        unsafe { self.acc().get_accRole(&self.child()) }
+            .ok()
+            .and_then(|role| i32::try_from(&role).ok())
+            .unwrap_or(0) as u32
+    }
+
+    fn state(&self) -> Result<u32, AutomationError> {
+        let state = unsafe { self.acc().get_accState(&self.child()) }
+            .map_err(|e| AutomationError::ElementNotFound(format!("Failed to read MSAA state: {}", e)))?;
+        Ok(i32::try_from(&state).unwrap_or(0) as u32)
+    }
+
+    fn has_state(&self, flag: u32) -> Result<bool, AutomationError> {
+        Ok(self.state()? & flag != 0)
+    }
+
+    // The UIA element of the window that hosts this object
+    fn host(&self) -> Result<UIElement, AutomationError> {
+        let automation = create_ui_automation_with_com_init()?;
+        let element = automation
+            .element_from_handle(self.hwnd().into())
+            .map_err(|e| AutomationError::ElementNotFound(format!("Host window of MSAA element is gone: {}", e)))?;
+        Ok(convert_uiautomation_element_to_terminator(element))
+    }
+
+    fn center(&self) -> Result<Point, AutomationError> {
+        let (x, y, width, height) = self.bounds()?;
+        Ok(Point::new((x + width / 2.0) as i32, (y + height / 2.0) as i32))
+    }
+
+    fn select(&self, flags: i32) -> Result<(), AutomationError> {
+        unsafe { self.acc().accSelect(flags, &self.child()) }
+            .map_err(|e| AutomationError::PlatformError(format!("MSAA accSelect failed: {}", e)))
+    }
+
+    fn do_default_action(&self) -> Result<(), AutomationError> {
+        unsafe { self.acc().accDoDefaultAction(&self.child()) }
+            .map_err(|e| AutomationError::PlatformError(format!("MSAA accDoDefaultAction failed: {}", e)))
+    }
+}
+
+impl std::fmt::Debug for MsaaUIElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MsaaUIElement")
+            .field("role", &self.role())
+            .field("name", &self.msaa_name())
+            .field("child_id", &self.child_id)
+            .finish()
+    }
+}
+
+impl UIElementImpl for MsaaUIElement {
+    fn object_id(&self) -> usize {
+        // MSAA has no stable identifiers, so derive one from what identifies the object on screen
+        let mut hasher = DefaultHasher::new();
+        self.hwnd.hash(&mut hasher);
+        self.child_id.hash(&mut hasher);
+        self.msaa_role().hash(&mut hasher);
+        self.msaa_name().hash(&mut hasher);
+        if let Ok((x, y, width, height)) = self.bounds() {
+            (x as i64, y as i64, width as i64, height as i64).hash(&mut hasher);
+        }
+        hasher.finish() as usize
+    }
+
+    fn id(&self) -> Option<String> {
+        Some(self.object_id().to_string())
+    }
+
+    fn role(&self) -> String {
+        role_name(self.msaa_role()).to_string()
+    }
+
+    fn is_stale(&self) -> bool {
+        // The accessible objects die with their window
+        !unsafe { IsWindow(Some(self.hwnd())) }.as_bool()
+    }
+
+    fn attributes(&self) -> UIElementAttributes {
+        let mut properties = std::collections::HashMap::new();
+        properties.insert("Source".to_string(), Some(serde_json::Value::String("MSAA".to_string())));
+        properties.insert("LegacyRole".to_string(), Some(serde_json::Value::from(self.msaa_role())));
+        if let Some(action) = self.string_property(|acc, child| unsafe { acc.get_accDefaultAction(child) }) {
+            properties.insert("DefaultAction".to_string(), Some(serde_json::Value::String(action)));
+        }
+        UIElementAttributes {
+            role: self.role(),
+            name: self.msaa_name(),
+            label: None,
+            value: self.msaa_value(),
+            description: self.msaa_description(),
+            properties,
+            is_keyboard_focusable: self.is_keyboard_focusable().ok(),
+        }
+    }
+
+    fn name(&self) -> Option<String> {
+        self.msaa_name()
+    }
+
+    fn children(&self) -> Result<Vec<UIElement>, AutomationError> {
+        if self.child_id != CHILDID_SELF {
+            return Ok(Vec::new());
+        }
+        Ok(accessible_children(self.acc(), self.hwnd())?
+            .into_iter()
+            .map(|child| UIElement::new(Box::new(child)))
+            .collect())
+    }
+
+    fn parent(&self) -> Result<Option<UIElement>, AutomationError> {
+        if self.child_id != CHILDID_SELF {
+            let owner = MsaaUIElement {
+                child_id: CHILDID_SELF,
+                ..self.clone()
+            };
+            return Ok(Some(UIElement::new(Box::new(owner))));
+        }
+        let parent = match unsafe { self.acc().accParent() } {
+            Ok(parent) => parent.cast::<IAccessible>()?,
+            Err(_) => return Ok(None),
+        };
+        let parent_hwnd = window_of(&parent, self.hwnd());
+        let parent = MsaaUIElement::new(parent, CHILDID_SELF, parent_hwnd);
+        // Above the client object is the window itself, which UIA does expose
+        if parent.msaa_role() == ROLE_SYSTEM_WINDOW {
+            return self.host().map(Some);
+        }
+        Ok(Some(UIElement::new(Box::new(parent))))
+    }
+
+    fn bounds(&self) -> Result<(f64, f64, f64, f64), AutomationError> {
+        let (mut x, mut y, mut width, mut height) = (0, 0, 0, 0);
+        unsafe {
+            self.acc()
+                .accLocation(&mut x, &mut y, &mut width, &mut height, &self.child())
+        }
+        .map_err(|e| AutomationError::ElementNotFound(format!("Failed to get MSAA location: {}", e)))?;
+        Ok((x as f64, y as f64, width as f64, height as f64))
+    }
+
+    fn click(&self) -> Result<ClickResult, AutomationError> {
+        match self.do_default_action() {
+            Ok(()) => Ok(ClickResult {
+                method: "MSAA DoDefaultAction".to_string(),
+                strategy: Some(ActionStrategy::UiaPattern),
+                attempts: Vec::new(),
+                coordinates: None,
+                details: "Performed the MSAA default action".to_string(),
+            }),
+            Err(e) => {
+                debug!("MSAA default action failed, clicking instead: {}", e);
+                let point = self.center()?;
+                Mouse::default()
+                    .click(point)
+                    .map_err(AutomationError::from)?;
+                Ok(ClickResult {
+                    method: "Single Click (MSAA bounds)".to_string(),
+                    strategy: Some(ActionStrategy::SendInputCursor),
+                    attempts: Vec::new(),
+                    coordinates: Some((point.get_x() as f64, point.get_y() as f64)),
+                    details: "Clicked the center of the MSAA location".to_string(),
+                })
+            }
+        }
+    }
+
+    fn double_click(&self) -> Result<ClickResult, AutomationError> {
+        let point = self.center()?;
+        Mouse::default()
+            .double_click(point)
+            .map_err(AutomationError::from)?;
+        Ok(ClickResult {
+            method: "Double Click (MSAA bounds)".to_string(),
+            strategy: Some(ActionStrategy::SendInputCursor),
+            attempts: Vec::new(),
+            coordinates: Some((point.get_x() as f64, point.get_y() as f64)),
+            details: "Double-clicked the center of the MSAA location".to_string(),
+        })
+    }
+
+    fn right_click(&self) -> Result<(), AutomationError> {
+        Mouse::default()
+            .right_click(self.center()?)
+            .map_err(AutomationError::from)
+    }
+
+    fn hover(&self) -> Result<(), AutomationError> {
+        let point = self.center()?;
+        self.mouse_move(point.get_x() as f64, point.get_y() as f64)
+    }
+
+    fn focus(&self) -> Result<(), AutomationError> {
+        self.select(SELFLAG_TAKEFOCUS as i32)
+    }
+
+    fn type_text(&self, text: &str, use_clipboard: bool) -> Result<(), AutomationError> {
+        if use_clipboard {
+            debug!("Clipboard typing is not available for MSAA elements, typing key by key");
+        }
+        self.focus()?;
+        Keyboard::new()
+            .interval(10)
+            .send_text(text)
+            .map_err(AutomationError::from)
+    }
+
+    fn press_key(&self, key: &str) -> Result<(), AutomationError> {
+        self.focus()?;
+        Keyboard::new()
+            .interval(10)
+            .send_keys(key)
+            .map_err(|e| AutomationError::PlatformError(format!("Failed to press key: {:?}", e)))
+    }
+
+    fn get_text(&self, max_depth: usize) -> Result<String, AutomationError> {
+        fn collect(element: &MsaaUIElement, depth: usize, max_depth: usize, texts: &mut Vec<String>) {
+            texts.extend(element.msaa_name());
+            texts.extend(element.msaa_value());
+            if depth >= max_depth || element.child_id != CHILDID_SELF {
+                return;
+            }
+            for child in accessible_children(element.acc(), element.hwnd()).unwrap_or_default() {
+                collect(&child, depth + 1, max_depth, texts);
+            }
+        }
+
+        let mut texts = Vec::new();
+        collect(self, 0, max_depth, &mut texts);
+        Ok(texts.join("\n"))
+    }
+
+    fn set_value(&self, value: &str) -> Result<(), AutomationError> {
+        unsafe { self.acc().put_accValue(&self.child(), &BSTR::from(value)) }
+            .map_err(|e| AutomationError::PlatformError(format!("MSAA put_accValue failed: {}", e)))
+    }
+
+    fn is_enabled(&self) -> Result<bool, AutomationError> {
+        Ok(!self.has_state(STATE_SYSTEM_UNAVAILABLE)?)
+    }
+
+    fn is_visible(&self) -> Result<bool, AutomationError> {
+        Ok(!self.has_state(STATE_SYSTEM_INVISIBLE | STATE_SYSTEM_OFFSCREEN)?)
+    }
+
+    fn is_focused(&self) -> Result<bool, AutomationError> {
+        self.has_state(STATE_SYSTEM_FOCUSED)
+    }
+
+    fn perform_action(&self, action: &str) -> Result<(), AutomationError> {
+        match action {
+            "focus" => self.focus(),
+            "invoke" | "default" => self.do_default_action(),
+            "select" => self.select(SELFLAG_TAKESELECTION as i32),
+            "click" => self.click().map(|_| ()),
+            "double_click" => self.double_click().map(|_| ()),
+            "right_click" => self.right_click(),
+            _ => Err(AutomationError::UnsupportedOperation(format!(
+                "action '{}' not supported for MSAA elements",
+                action
+            ))),
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn create_locator(&self, selector: Selector) -> Result<Locator, AutomationError> {
+        let engine = WindowsEngine::new(false, false)?;
+        Ok(Locator::new(Arc::new(engine), selector).within(UIElement::new(Box::new(self.clone()))))
+    }
+
+    fn scroll(&self, direction: &str, amount: f64) -> Result<(), AutomationError> {
+        self.focus()?;
+        self.host()?.scroll(direction, amount)
+    }
+
+    fn activate_window(&self) -> Result<(), AutomationError> {
+        self.host()?.activate_window()
+    }
+
+    fn clone_box(&self) -> Box<dyn UIElementImpl> {
+        Box::new(self.clone())
+    }
+
+    fn is_keyboard_focusable(&self) -> Result<bool, AutomationError> {
+        self.has_state(STATE_SYSTEM_FOCUSABLE)
+    }
+
+    fn is_read_only(&self) -> Result<bool, AutomationError> {
+        self.has_state(STATE_SYSTEM_READONLY)
+    }
+
+    fn mouse_drag(&self, start_x: f64, start_y: f64, end_x: f64, end_y: f64) -> Result<(), AutomationError> {
+        self.host()?.mouse_drag(start_x, start_y, end_x, end_y)
+    }
+
+    fn mouse_click_and_hold(&self, x: f64, y: f64) -> Result<(), AutomationError> {
+        self.host()?.mouse_click_and_hold(x, y)
+    }
+
+    fn mouse_move(&self, x: f64, y: f64) -> Result<(), AutomationError> {
+        self.host()?.mouse_move(x, y)
+    }
+
+    fn mouse_release(&self) -> Result<(), AutomationError> {
+        self.host()?.mouse_release()
+    }
+
+    fn application(&self) -> Result<Option<UIElement>, AutomationError> {
+        self.host()?.application()
+    }
+
+    fn window(&self) -> Result<Option<UIElement>, AutomationError> {
+        self.host()?.window()
+    }
+
+    fn highlight(&self, color: Option<u32>, duration: Option<Duration>) -> Result<(), AutomationError> {
+        let (x, y, width, height) = self.bounds()?;
+        super::windows_overlay::highlight(
+            &[(x as i32, y as i32, width as i32, height as i32)],
+            color.unwrap_or(super::windows_overlay::DEFAULT_COLOR),
+            duration.unwrap_or(super::windows_overlay::DEFAULT_DURATION),
+        )
+    }
+
+    fn process_id(&self) -> Result<u32, AutomationError> {
+        let mut pid = 0;
+        unsafe { GetWindowThreadProcessId(self.hwnd(), Some(&mut pid)) };
+        if pid == 0 {
+            return Err(AutomationError::PlatformError(
+                "Failed to get process ID of the MSAA host window".to_string(),
+            ));
+        }
+        Ok(pid)
+    }
+
+    fn capture(&self) -> Result<ScreenshotResult, AutomationError> {
+        let (x, y, width, height) = self.bounds()?;
+        capture_screen_region(x as i32, y as i32, width as i32, height as i32)
+    }
+
+    fn close(&self) -> Result<(), AutomationError> {
+        self.host()?.close()
+    }
+
+    fn is_topmost(&self) -> Result<bool, AutomationError> {
+        self.host()?.is_topmost()
+    }
+
+    fn set_topmost(&self, topmost: bool) -> Result<(), AutomationError> {
+        self.host()?.set_topmost(topmost)
+    }
+
+    fn opacity(&self) -> Result<f32, AutomationError> {
+        self.host()?.opacity()
+    }
+
+    fn set_opacity(&self, opacity: f32) -> Result<(), AutomationError> {
+        self.host()?.set_opacity(opacity)
+    }
+}
+
+// Handle of the window an accessible object belongs to, or `fallback`
+fn window_of(accessible: &IAccessible, fallback: HWND) -> HWND {
+    let mut hwnd = HWND::default();
+    match unsafe { WindowFromAccessibleObject(accessible, Some(&mut hwnd)) } {
+        Ok(()) if !hwnd.0.is_null() => hwnd,
+        _ => fallback,
+    }
+}
+
+fn accessible_children(accessible: &IAccessible, hwnd: HWND) -> Result<Vec<MsaaUIElement>, AutomationError> {
+    let count = unsafe { accessible.accChildCount() }
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to count MSAA children: {}", e)))?;
+    if count <= 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut slots = vec![VARIANT::default(); count as usize];
+    let mut obtained = 0;
+    unsafe { AccessibleChildren(accessible, 0, &mut slots, &mut obtained) }
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to enumerate MSAA children: {}", e)))?;
+
+    Ok(slots
+        .iter()
+        .take(obtained.max(0) as usize)
+        .filter_map(|slot| {
+            // Either a full accessible object or the id of a simple element of the parent
+            if let Ok(child_id) = i32::try_from(slot) {
+                return Some(MsaaUIElement::new(accessible.clone(), child_id, hwnd));
+            }
+            let child = IUnknown::try_from(slot).ok()?.cast::<IAccessible>().ok()?;
+            let child_hwnd = window_of(&child, hwnd);
+            Some(MsaaUIElement::new(child, CHILDID_SELF, child_hwnd))
+        })
+        .collect())
+}
+
+fn client_object(hwnd: HWND) -> Option<IAccessible> {
+    let mut accessible: Option<IAccessible> = None;
+    unsafe {
+        AccessibleObjectFromWindow(
+            hwnd,
+            OBJID_CLIENT.0 as u32,
+            &IAccessible::IID,
+            &mut accessible as *mut _ as *mut _,
+        )
+    }
+    .ok()?;
+    accessible
+}
+
+/// MSAA children of the client area of `element`'s window, for windows and
+/// panes UIA reports as empty. Empty when the element has no window handle or
+/// MSAA has nothing either
+pub(crate) fn fallback_children(element: &uiautomation::UIElement) -> Vec<UIElement> {
+    let is_container = matches!(
+        element.get_control_type(),
+        Ok(ControlType::Window) | Ok(ControlType::Pane)
+    );
+    if !is_container {
+        return Vec::new();
+    }
+    let Ok(handle) = element.get_native_window_handle() else {
+        return Vec::new();
+    };
+    let hwnd: HWND = handle.into();
+    if hwnd.0.is_null() {
+        return Vec::new();
+    }
+    let Some(client) = client_object(hwnd) else {
+        return Vec::new();
+    };
+
+    match accessible_children(&client, hwnd) {
+        Ok(children) => {
+            if !children.is_empty() {
+                debug!(count = children.len(), "UIA tree is empty, using MSAA children");
+            }
+            children
+                .into_iter()
+                .map(|child| UIElement::new(Box::new(child)))
+                .collect()
+        }
+        Err(e) => {
+            debug!("MSAA fallback failed: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Root to search with MSAA instead of UIA: an MSAA element, or a window or
+/// pane whose content only MSAA exposes
+pub(crate) fn legacy_root(root: &UIElement) -> Option<UIElement> {
+    if root.as_any().downcast_ref::<MsaaUIElement>().is_some() {
+        return Some(root.clone());
+    }
+    let role = root.role();
+    if role != ControlType::Window.to_string() && role != ControlType::Pane.to_string() {
+        return None;
+    }
+    let children = root.children().ok()?;
+    let msaa_only = !children.is_empty()
+        && children
+            .iter()
+            .all(|child| child.as_any().downcast_ref::<MsaaUIElement>().is_some());
+    msaa_only.then(|| root.clone())
+}
+
+/// Elements under `root` matching `selector`, in tree order. Only selectors
+/// on what MSAA exposes are supported: role, name, text, id, their boolean
+/// combinations and chains of those
+pub(crate) fn find_all(
+    root: &UIElement,
+    selector: &Selector,
+    depth: Option<usize>,
+    limit: Option<usize>,
+) -> Result<Vec<UIElement>, AutomationError> {
+    let selector = selector.localized();
+    let steps = match &selector {
+        Selector::Chain(steps) => steps.as_slice(),
+        single => std::slice::from_ref(single),
+    };
+    let mut scope = vec![root.clone()];
+    for (i, step) in steps.iter().enumerate() {
+        let last = i + 1 == steps.len();
+        let mut matches = Vec::new();
+        for element in &scope {
+            collect_matches(element, step, 0, depth.unwrap_or(50), if last { limit } else { None }, &mut matches)?;
+        }
+        scope = matches;
+        if scope.is_empty() {
+            break;
+        }
+    }
+    Ok(scope)
+}
+
+/// Like [`find_all`] for the first match, retrying until `timeout`
+pub(crate) fn find_first(
+    root: &UIElement,
+    selector: &Selector,
+    timeout: Duration,
+) -> Result<UIElement, AutomationError> {
+    let start = Instant::now();
+    loop {
+        if let Some(found) = find_all(root, selector, None, Some(1))?.into_iter().next() {
+            return Ok(found);
+        }
+        if start.elapsed() >= timeout {
+            return Err(AutomationError::ElementNotFound(format!(
+                "No MSAA element matching {:?} within {:?}",
+                selector, timeout
+            )));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn collect_matches(
+    element: &UIElement,
+    selector: &Selector,
+    depth: usize,
+    max_depth: usize,
+    limit: Option<usize>,
+    matches: &mut Vec<UIElement>,
+) -> Result<(), AutomationError> {
+    if depth >= max_depth {
+        return Ok(());
+    }
+    for child in element.children()? {
+        if limit.is_some_and(|limit| matches.len() >= limit) {
+            return Ok(());
+        }
+        if selector_matches(&child, selector)? {
+            matches.push(child.clone());
+        }
+        collect_matches(&child, selector, depth + 1, max_depth, limit, matches)?;
+    }
+    Ok(())
+}
+
+fn contains_ignore_case(haystack: Option<String>, needle: &str) -> bool {
+    haystack.is_some_and(|h| h.to_lowercase().contains(&needle.to_lowercase()))
+}
+
+fn selector_matches(element: &UIElement, selector: &Selector) -> Result<bool, AutomationError> {
+    Ok(match selector {
+        Selector::Role { role, name } => {
+            let element_role = element.role();
+            (element_role.eq_ignore_ascii_case(role)
+                || element_role == map_generic_role_to_win_roles(role).to_string())
+                && name.as_ref().is_none_or(|name| contains_ignore_case(element.name(), name))
+        }
+        Selector::Name(name) => contains_ignore_case(element.name(), name),
+        Selector::NameL10n(_) => selector_matches(element, &selector.localized())?,
+        Selector::LocalizedName(names) => element.name().is_some_and(|name| crate::l10n::is_one_of(&name, names)),
+        Selector::Text(text) => {
+            let attributes = element.attributes();
+            contains_ignore_case(attributes.name, text) || contains_ignore_case(attributes.value, text)
+        }
+        Selector::Id(id) => element.id().as_deref() == Some(id.as_str()),
+        Selector::Not(inner) => !selector_matches(element, inner)?,
+        Selector::And(selectors) => {
+            for selector in selectors {
+                if !selector_matches(element, selector)? {
+                    return Ok(false);
+                }
+            }
+            true
+        }
+        Selector::Or(selectors) => {
+            for selector in selectors {
+                if selector_matches(element, selector)? {
+                    return Ok(true);
+                }
+            }
+            false
+        }
+        other => {
+            return Err(AutomationError::UnsupportedOperation(format!(
+                "Selector {:?} is not supported for elements only exposed through MSAA",
+                other
+            )))
+        }
+    })
+}
+
+/// UIA-style role names for MSAA roles
+fn role_name(role: u32) -> &'static str {
+    match role {
+        ROLE_SYSTEM_PUSHBUTTON | ROLE_SYSTEM_BUTTONMENU => "Button",
+        ROLE_SYSTEM_SPLITBUTTON => "SplitButton",
+        ROLE_SYSTEM_CHECKBUTTON => "CheckBox",
+        ROLE_SYSTEM_RADIOBUTTON => "RadioButton",
+        ROLE_SYSTEM_TEXT => "Edit",
+        ROLE_SYSTEM_STATICTEXT => "Text",
+        ROLE_SYSTEM_COMBOBOX => "ComboBox",
+        ROLE_SYSTEM_LIST => "List",
+        ROLE_SYSTEM_LISTITEM => "ListItem",
+        ROLE_SYSTEM_MENUBAR => "MenuBar",
+        ROLE_SYSTEM_MENUPOPUP => "Menu",
+        ROLE_SYSTEM_MENUITEM => "MenuItem",
+        ROLE_SYSTEM_OUTLINE => "Tree",
+        ROLE_SYSTEM_OUTLINEITEM => "TreeItem",
+        ROLE_SYSTEM_PAGETABLIST => "Tab",
+        ROLE_SYSTEM_PAGETAB => "TabItem",
+        ROLE_SYSTEM_TABLE => "Table",
+        ROLE_SYSTEM_ROW => "DataItem",
+        ROLE_SYSTEM_CELL => "DataItem",
+        ROLE_SYSTEM_COLUMNHEADER | ROLE_SYSTEM_ROWHEADER => "HeaderItem",
+        ROLE_SYSTEM_LINK => "Hyperlink",
+        ROLE_SYSTEM_GRAPHIC => "Image",
+        ROLE_SYSTEM_PROGRESSBAR => "ProgressBar",
+        ROLE_SYSTEM_SLIDER => "Slider",
+        ROLE_SYSTEM_SPINBUTTON => "Spinner",
+        ROLE_SYSTEM_SCROLLBAR => "ScrollBar",
+        ROLE_SYSTEM_SEPARATOR => "Separator",
+        ROLE_SYSTEM_STATUSBAR => "StatusBar",
+        ROLE_SYSTEM_TOOLBAR => "ToolBar",
+        ROLE_SYSTEM_TOOLTIP => "ToolTip",
+        ROLE_SYSTEM_TITLEBAR => "TitleBar",
+        ROLE_SYSTEM_GROUPING => "Group",
+        ROLE_SYSTEM_DOCUMENT => "Document",
+        ROLE_SYSTEM_DIALOG | ROLE_SYSTEM_ALERT | ROLE_SYSTEM_WINDOW => "Window",
+        ROLE_SYSTEM_APPLICATION | ROLE_SYSTEM_CLIENT | ROLE_SYSTEM_PANE => "Pane",
+        _ => "Custom",
+    }
+}
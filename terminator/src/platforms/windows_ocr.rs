@@ -0,0 +1,76 @@
+//! Word positions from the Windows OCR engine
+//!
+//! `uni-ocr` only returns the recognized text. `Windows.Media.Ocr` also
+//! reports the bounding box of every word, which is what locating text on
+//! screen needs.
+
+use tracing::debug;
+use windows::Graphics::Imaging::{BitmapPixelFormat, SoftwareBitmap};
+use windows::Media::Ocr::OcrEngine;
+use windows::Storage::Streams::DataWriter;
+
+use crate::geometry::Rect;
+use crate::ocr::OcrWord;
+use crate::{AutomationError, ScreenshotResult};
+
+fn ocr_error(what: &str, e: windows::core::Error) -> AutomationError {
+    AutomationError::PlatformError(format!("{}: {}", what, e))
+}
+
+/// Words in `screenshot`, in reading order, recognized in the user's
+/// display languages
+pub(crate) fn recognize_words(screenshot: &ScreenshotResult) -> Result<Vec<OcrWord>, AutomationError> {
+    let max_dimension = OcrEngine::MaxImageDimension().map_err(|e| ocr_error("Failed to query OCR engine", e))?;
+    if screenshot.width > max_dimension || screenshot.height > max_dimension {
+        return Err(AutomationError::InvalidArgument(format!(
+            "Image of {}x{} exceeds the OCR limit of {} pixels per side, pass a smaller region",
+            screenshot.width, screenshot.height, max_dimension
+        )));
+    }
+    let engine = OcrEngine::TryCreateFromUserProfileLanguages()
+        .map_err(|e| ocr_error("No OCR language installed for the user's languages", e))?;
+
+    // The engine reads BGRA, screenshots are RGBA
+    let mut pixels = screenshot.image_data.clone();
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    let buffer = DataWriter::new()
+        .and_then(|writer| {
+            writer.WriteBytes(&pixels)?;
+            writer.DetachBuffer()
+        })
+        .map_err(|e| ocr_error("Failed to copy image for OCR", e))?;
+    let bitmap = SoftwareBitmap::CreateCopyFromBuffer(
+        &buffer,
+        BitmapPixelFormat::Bgra8,
+        screenshot.width as i32,
+        screenshot.height as i32,
+    )
+    .map_err(|e| ocr_error("Failed to create bitmap for OCR", e))?;
+
+    let result = engine
+        .RecognizeAsync(&bitmap)
+        .and_then(|operation| operation.get())
+        .map_err(|e| ocr_error("OCR recognition failed", e))?;
+    let lines = result.Lines().map_err(|e| ocr_error("Failed to read OCR lines", e))?;
+
+    let mut words = Vec::new();
+    for (line, ocr_line) in lines.into_iter().enumerate() {
+        let Ok(ocr_words) = ocr_line.Words() else {
+            continue;
+        };
+        for word in ocr_words {
+            let (Ok(text), Ok(rect)) = (word.Text(), word.BoundingRect()) else {
+                continue;
+            };
+            words.push(OcrWord {
+                text: text.to_string(),
+                bounds: Rect::new(rect.X as f64, rect.Y as f64, rect.Width as f64, rect.Height as f64),
+                line,
+            });
+        }
+    }
+    debug!(words = words.len(), "OCR recognized words");
+    Ok(words)
+}
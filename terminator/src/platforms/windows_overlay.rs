@@ -0,0 +1,306 @@
+//! Click-through overlay windows that draw highlight borders around elements
+//!
+//! One borderless, topmost, layered window covers each monitor and is only
+//! shown while highlights are active. The windows live on a dedicated thread
+//! with its own message loop; other threads add highlights to a shared list
+//! and post a message to that thread to repaint. Element bounds and monitor
+//! rectangles are both in this process's screen coordinates, so no DPI
+//! scaling is needed.
+
+use std::sync::mpsc;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{debug, error};
+use windows::Win32::Foundation::{BOOL, COLORREF, HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    BeginPaint, CreateSolidBrush, DeleteObject, EndPaint, EnumDisplayMonitors, FillRect,
+    InvalidateRect, HDC, HGDIOBJ, HMONITOR, PAINTSTRUCT,
+};
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetClientRect, GetMessageW,
+    GetWindowRect, KillTimer, PostThreadMessageW, RegisterClassW, SetLayeredWindowAttributes,
+    SetTimer, ShowWindow, TranslateMessage, LWA_COLORKEY, MSG, SW_HIDE, SW_SHOWNOACTIVATE,
+    WM_APP, WM_ERASEBKGND, WM_PAINT, WM_TIMER, WNDCLASSW, WS_EX_LAYERED, WS_EX_NOACTIVATE,
+    WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_EX_TRANSPARENT, WS_POPUP,
+};
+use windows::core::w;
+
+use crate::AutomationError;
+
+/// Red, in the BGR order `COLORREF` uses
+pub(crate) const DEFAULT_COLOR: u32 = 0x0000FF;
+pub(crate) const DEFAULT_DURATION: Duration = Duration::from_millis(500);
+
+// Painted but fully transparent. Highlights in exactly this color are invisible
+const TRANSPARENT_KEY: COLORREF = COLORREF(0x00010101);
+const BORDER_SIZE: i32 = 4;
+const WM_REFRESH: u32 = WM_APP + 1;
+const EXPIRY_CHECK_MS: u32 = 50;
+
+#[derive(Clone, Copy)]
+struct Highlight {
+    rect: RECT,
+    color: COLORREF,
+    until: Instant,
+}
+
+static HIGHLIGHTS: Mutex<Vec<Highlight>> = Mutex::new(Vec::new());
+static OVERLAY_THREAD: OnceLock<Result<u32, String>> = OnceLock::new();
+
+fn highlights() -> MutexGuard<'static, Vec<Highlight>> {
+    HIGHLIGHTS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Draw a border of `color` (BGR) just outside each `(x, y, width, height)`
+/// rectangle for `duration`. Returns right away
+pub(crate) fn highlight(
+    rects: &[(i32, i32, i32, i32)],
+    color: u32,
+    duration: Duration,
+) -> Result<(), AutomationError> {
+    let thread_id = overlay_thread()?;
+    let until = Instant::now() + duration;
+    highlights().extend(rects.iter().map(|&(x, y, width, height)| Highlight {
+        rect: RECT {
+            left: x,
+            top: y,
+            right: x + width,
+            bottom: y + height,
+        },
+        color: COLORREF(color),
+        until,
+    }));
+    unsafe { PostThreadMessageW(thread_id, WM_REFRESH, WPARAM(0), LPARAM(0)) }.map_err(|e| {
+        AutomationError::PlatformError(format!("Failed to signal highlight overlay: {}", e))
+    })
+}
+
+// Thread ID of the overlay thread, starting it on first use
+fn overlay_thread() -> Result<u32, AutomationError> {
+    OVERLAY_THREAD
+        .get_or_init(|| {
+            let (ready_tx, ready_rx) = mpsc::channel();
+            thread::Builder::new()
+                .name("highlight-overlay".to_string())
+                .spawn(move || run(ready_tx))
+                .map_err(|e| format!("Failed to start highlight overlay thread: {}", e))?;
+            ready_rx
+                .recv()
+                .map_err(|_| "Highlight overlay thread exited during startup".to_string())?
+        })
+        .clone()
+        .map_err(AutomationError::PlatformError)
+}
+
+fn run(ready: mpsc::Sender<Result<u32, String>>) {
+    let class = WNDCLASSW {
+        lpfnWndProc: Some(window_proc),
+        lpszClassName: w!("TerminatorHighlightOverlay"),
+        ..Default::default()
+    };
+    // Registering the class also creates this thread's message queue, so
+    // messages posted once the thread ID is published are not lost
+    if unsafe { RegisterClassW(&class) } == 0 {
+        let _ = ready.send(Err(format!(
+            "Failed to register highlight overlay window class: {}",
+            windows::core::Error::from_win32()
+        )));
+        return;
+    }
+    let _ = ready.send(Ok(unsafe { GetCurrentThreadId() }));
+
+    let mut overlays: Vec<(RECT, HWND)> = Vec::new();
+    let mut timer = 0;
+    let mut msg = MSG::default();
+    while unsafe { GetMessageW(&mut msg, None, 0, 0) }.as_bool() {
+        if msg.hwnd.0.is_null() && (msg.message == WM_REFRESH || msg.message == WM_TIMER) {
+            refresh(&mut overlays, &mut timer, msg.message == WM_REFRESH);
+            continue;
+        }
+        unsafe {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+}
+
+// Drop expired highlights, then show and repaint the overlays while any are
+// left and hide them otherwise. A timer runs only while something is shown
+fn refresh(overlays: &mut Vec<(RECT, HWND)>, timer: &mut usize, added: bool) {
+    let (active, expired) = {
+        let mut highlights = highlights();
+        let before = highlights.len();
+        let now = Instant::now();
+        highlights.retain(|h| h.until > now);
+        (!highlights.is_empty(), highlights.len() < before)
+    };
+
+    if !active {
+        if *timer != 0 {
+            unsafe {
+                let _ = KillTimer(None, *timer);
+            }
+            *timer = 0;
+        }
+        for (_, hwnd) in overlays.iter() {
+            unsafe {
+                let _ = ShowWindow(*hwnd, SW_HIDE);
+            }
+        }
+        return;
+    }
+
+    sync_monitors(overlays);
+    if *timer == 0 {
+        *timer = unsafe { SetTimer(None, 0, EXPIRY_CHECK_MS, None) };
+    }
+    if added || expired {
+        for (_, hwnd) in overlays.iter() {
+            unsafe {
+                let _ = ShowWindow(*hwnd, SW_SHOWNOACTIVATE);
+                let _ = InvalidateRect(Some(*hwnd), None, true);
+            }
+        }
+    }
+}
+
+// Keep one overlay per monitor, recreating them when the layout changed
+fn sync_monitors(overlays: &mut Vec<(RECT, HWND)>) {
+    let monitors = monitor_rects();
+    if overlays.iter().map(|(rect, _)| *rect).eq(monitors.iter().copied()) {
+        return;
+    }
+    debug!("Creating highlight overlays for {} monitors", monitors.len());
+    for (_, hwnd) in overlays.drain(..) {
+        unsafe {
+            let _ = DestroyWindow(hwnd);
+        }
+    }
+    overlays.extend(
+        monitors
+            .into_iter()
+            .filter_map(|rect| create_overlay(rect).map(|hwnd| (rect, hwnd))),
+    );
+}
+
+fn monitor_rects() -> Vec<RECT> {
+    unsafe extern "system" fn collect(
+        _monitor: HMONITOR,
+        _hdc: HDC,
+        rect: *mut RECT,
+        data: LPARAM,
+    ) -> BOOL {
+        let rects = unsafe { &mut *(data.0 as *mut Vec<RECT>) };
+        rects.push(unsafe { *rect });
+        true.into()
+    }
+
+    let mut rects: Vec<RECT> = Vec::new();
+    unsafe {
+        let _ = EnumDisplayMonitors(
+            None,
+            None,
+            Some(collect),
+            LPARAM(&mut rects as *mut Vec<RECT> as isize),
+        );
+    }
+    rects
+}
+
+fn create_overlay(rect: RECT) -> Option<HWND> {
+    let hwnd = unsafe {
+        CreateWindowExW(
+            WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+            w!("TerminatorHighlightOverlay"),
+            w!(""),
+            WS_POPUP,
+            rect.left,
+            rect.top,
+            rect.right - rect.left,
+            rect.bottom - rect.top,
+            None,
+            None,
+            None,
+            None,
+        )
+    };
+    let hwnd = match hwnd {
+        Ok(hwnd) => hwnd,
+        Err(e) => {
+            error!("Failed to create highlight overlay window: {}", e);
+            return None;
+        }
+    };
+    if let Err(e) = unsafe { SetLayeredWindowAttributes(hwnd, TRANSPARENT_KEY, 0, LWA_COLORKEY) } {
+        error!("Failed to make highlight overlay transparent: {}", e);
+        unsafe {
+            let _ = DestroyWindow(hwnd);
+        }
+        return None;
+    }
+    Some(hwnd)
+}
+
+unsafe extern "system" fn window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_PAINT => {
+            paint(hwnd);
+            LRESULT(0)
+        }
+        // WM_PAINT fills the whole window, skip the default erase to avoid flicker
+        WM_ERASEBKGND => LRESULT(1),
+        _ => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+    }
+}
+
+fn paint(hwnd: HWND) {
+    let highlights = highlights().clone();
+    unsafe {
+        let mut ps = PAINTSTRUCT::default();
+        let hdc = BeginPaint(hwnd, &mut ps);
+
+        let mut client = RECT::default();
+        let mut origin = RECT::default();
+        let _ = GetClientRect(hwnd, &mut client);
+        let _ = GetWindowRect(hwnd, &mut origin);
+
+        let background = CreateSolidBrush(TRANSPARENT_KEY);
+        FillRect(hdc, &client, background);
+        let _ = DeleteObject(HGDIOBJ(background.0));
+
+        for highlight in highlights {
+            // Screen coordinates to this monitor's window
+            let r = RECT {
+                left: highlight.rect.left - origin.left,
+                top: highlight.rect.top - origin.top,
+                right: highlight.rect.right - origin.left,
+                bottom: highlight.rect.bottom - origin.top,
+            };
+            let brush = CreateSolidBrush(highlight.color);
+            for edge in border(r) {
+                FillRect(hdc, &edge, brush);
+            }
+            let _ = DeleteObject(HGDIOBJ(brush.0));
+        }
+
+        let _ = EndPaint(hwnd, &ps);
+    }
+}
+
+// The four strips of a border drawn around `r`, so it doesn't cover the element
+fn border(r: RECT) -> [RECT; 4] {
+    let b = BORDER_SIZE;
+    [
+        RECT { left: r.left - b, top: r.top - b, right: r.right + b, bottom: r.top },
+        RECT { left: r.left - b, top: r.bottom, right: r.right + b, bottom: r.bottom + b },
+        RECT { left: r.left - b, top: r.top, right: r.left, bottom: r.bottom },
+        RECT { left: r.right, top: r.top, right: r.right + b, bottom: r.bottom },
+    ]
+}
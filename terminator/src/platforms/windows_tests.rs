@@ -2,6 +2,7 @@ use super::windows::*;
 use std::process;
 use std::time::Instant;
 use crate::platforms::AccessibilityEngine;
+use super::windows_window_events::WindowRegistry;
 
 #[test]
 fn test_get_process_name_by_pid_current_process() {
@@ -391,6 +392,8 @@ fn test_unified_tree_api_with_config() {
         timeout_per_operation_ms: Some(50),
         yield_every_n_elements: Some(50),
         batch_size: Some(50),
+        max_total_duration: None,
+        use_cache_request: true,
     };
 
     let start_fast = std::time::Instant::now();
@@ -403,6 +406,8 @@ fn test_unified_tree_api_with_config() {
         timeout_per_operation_ms: Some(100),
         yield_every_n_elements: Some(25),
         batch_size: Some(25),
+        max_total_duration: None,
+        use_cache_request: true,
     };
 
     let start_full = std::time::Instant::now();
@@ -443,4 +448,102 @@ fn test_unified_tree_api_with_config() {
 
     // Clean up
     let _ = app.close();
-} 
\ No newline at end of file
+} 
+
+#[test]
+fn test_window_registry_finds_latest_shown_window() {
+    let mut registry = WindowRegistry::default();
+    registry.shown(1, 100, "Untitled - Notepad".to_string());
+    registry.shown(2, 200, "Calculator".to_string());
+    registry.shown(3, 101, "notes.txt - Notepad".to_string());
+
+    let found = registry.find(|w| w.title.contains("Notepad")).unwrap();
+    assert_eq!(found.hwnd, 3);
+
+    // A rename counts as shown again
+    registry.shown(1, 100, "todo.txt - Notepad".to_string());
+    assert_eq!(registry.find(|w| w.title.contains("Notepad")).unwrap().hwnd, 1);
+    assert_eq!(registry.len(), 3);
+
+    registry.removed(1);
+    assert_eq!(registry.find(|w| w.title.contains("Notepad")).unwrap().hwnd, 3);
+    assert!(registry.find(|w| w.pid == 100).is_none());
+    assert_eq!(registry.find(|w| w.pid == 200).unwrap().title, "Calculator");
+}
+
+#[test]
+fn test_element_navigation() {
+    let engine = match WindowsEngine::new(false, false) {
+        Ok(engine) => engine,
+        Err(_) => {
+            println!("Cannot create WindowsEngine, skipping navigation test");
+            return;
+        }
+    };
+    let app = match engine.open_application("notepad") {
+        Ok(app) => app,
+        Err(e) => {
+            println!("Could not open notepad: {}, skipping navigation test", e);
+            return;
+        }
+    };
+    std::thread::sleep(std::time::Duration::from_millis(1000));
+
+    let edit = engine
+        .find_element(&crate::Selector::Role { role: "Document".to_string(), name: None }, Some(&app), None)
+        .or_else(|_| engine.find_element(&crate::Selector::Role { role: "Edit".to_string(), name: None }, Some(&app), None));
+    if let Ok(edit) = edit {
+        let ancestors = edit.ancestors().expect("Should walk ancestors");
+        assert!(!ancestors.is_empty(), "An edit box should have ancestors");
+
+        let window = edit.closest("role:Window").expect("Should search ancestors");
+        let window = window.expect("An edit box should be in a window");
+        assert!(ancestors.contains(&window), "closest should return one of the ancestors");
+        assert!(edit.closest("role:Button && name:NoSuchButton").unwrap().is_none());
+        assert!(edit.closest("/Window/Edit").is_err(), "Paths cannot be tested on one element");
+
+        if let Some(next) = edit.next_sibling().expect("Should step to the next sibling") {
+            let back = next.previous_sibling().expect("Should step back");
+            assert_eq!(back.as_ref(), Some(&edit));
+        }
+    }
+
+    let _ = app.close();
+}
+
+#[test]
+fn test_stale_element_descriptor() {
+    let engine = match WindowsEngine::new(false, false) {
+        Ok(engine) => engine,
+        Err(_) => {
+            println!("Cannot create WindowsEngine, skipping stale element test");
+            return;
+        }
+    };
+    let app = match engine.open_application("notepad") {
+        Ok(app) => app,
+        Err(e) => {
+            println!("Could not open notepad: {}, skipping stale element test", e);
+            return;
+        }
+    };
+    std::thread::sleep(std::time::Duration::from_millis(1000));
+
+    let edit = engine
+        .find_element(&crate::Selector::Role { role: "Document".to_string(), name: None }, Some(&app), None)
+        .or_else(|_| engine.find_element(&crate::Selector::Role { role: "Edit".to_string(), name: None }, Some(&app), None));
+    if let Ok(edit) = edit {
+        assert!(!edit.is_stale(), "A shown edit box is not stale");
+        let descriptor = edit.descriptor().expect("Should describe a live element");
+        assert!(descriptor.path.starts_with("/Window"), "Path should start at the window: {}", descriptor.path);
+        let found = descriptor.resolve(&engine.get_root_element()).expect("Should find the element again");
+        assert_eq!(found, edit);
+
+        let _ = app.close();
+        std::thread::sleep(std::time::Duration::from_millis(1000));
+        assert!(edit.is_stale(), "The edit box of a closed window is stale");
+        return;
+    }
+
+    let _ = app.close();
+}
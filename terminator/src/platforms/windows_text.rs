@@ -0,0 +1,177 @@
+//! UIA TextPattern for selections, the caret and formatted text
+//!
+//! Positions are counted in characters from the start of the document
+//! range, by measuring the text between it and an endpoint.
+
+use crate::text_pattern::{colorref_to_hex, TextRun, BOLD_FONT_WEIGHT};
+use crate::AutomationError;
+use uiautomation::patterns::{UITextPattern, UITextPattern2, UITextRange};
+use uiautomation::types::{TextAttribute, TextPatternRangeEndpoint, TextUnit};
+use uiautomation::variants::Variant;
+
+// Upper bound for documents whose format units never end
+const MAX_TEXT_RUNS: usize = 10_000;
+
+fn text_pattern(element: &uiautomation::UIElement) -> Result<UITextPattern, AutomationError> {
+    element.get_pattern::<UITextPattern>().map_err(|e| {
+        AutomationError::UnsupportedOperation(format!(
+            "Element '{}' does not support TextPattern: {}",
+            element.get_name().unwrap_or_default(),
+            e
+        ))
+    })
+}
+
+/// Characters between the start of the document and the start of `range`
+fn offset_of(pattern: &UITextPattern, range: &UITextRange) -> Result<usize, AutomationError> {
+    let before = pattern.get_document_range().map_err(AutomationError::from)?;
+    before
+        .move_endpoint_by_range(
+            TextPatternRangeEndpoint::End,
+            range,
+            TextPatternRangeEndpoint::Start,
+        )
+        .map_err(AutomationError::from)?;
+    Ok(before.get_text(-1).map_err(AutomationError::from)?.chars().count())
+}
+
+/// An empty range at the start of the document
+fn document_start(pattern: &UITextPattern) -> Result<UITextRange, AutomationError> {
+    let range = pattern.get_document_range().map_err(AutomationError::from)?;
+    range
+        .move_endpoint_by_range(
+            TextPatternRangeEndpoint::End,
+            &range,
+            TextPatternRangeEndpoint::Start,
+        )
+        .map_err(AutomationError::from)?;
+    Ok(range)
+}
+
+pub(crate) fn selected_text(element: &uiautomation::UIElement) -> Result<String, AutomationError> {
+    let selection = text_pattern(element)?
+        .get_selection()
+        .map_err(AutomationError::from)?;
+    let texts = selection
+        .iter()
+        .map(|range| range.get_text(-1).map_err(AutomationError::from))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(texts.join("\n"))
+}
+
+pub(crate) fn caret_position(
+    element: &uiautomation::UIElement,
+) -> Result<Option<usize>, AutomationError> {
+    let pattern = text_pattern(element)?;
+    if let Ok(pattern2) = element.get_pattern::<UITextPattern2>() {
+        let (active, caret) = pattern2.get_caret_range().map_err(AutomationError::from)?;
+        if !active {
+            return Ok(None);
+        }
+        return offset_of(&pattern, &caret).map(Some);
+    }
+    // Without TextPattern2 the caret is the end of the (empty) selection
+    let selection = pattern.get_selection().map_err(AutomationError::from)?;
+    match selection.first() {
+        Some(range) => {
+            let caret = document_start(&pattern)?;
+            caret
+                .move_endpoint_by_range(
+                    TextPatternRangeEndpoint::Start,
+                    range,
+                    TextPatternRangeEndpoint::End,
+                )
+                .map_err(AutomationError::from)?;
+            offset_of(&pattern, &caret).map(Some)
+        }
+        None => Ok(None),
+    }
+}
+
+pub(crate) fn select_range(
+    element: &uiautomation::UIElement,
+    start: usize,
+    end: usize,
+) -> Result<(), AutomationError> {
+    let pattern = text_pattern(element)?;
+    let range = document_start(&pattern)?;
+    // The end first, so the start never passes it
+    let moved = range
+        .move_endpoint_by_unit(
+            TextPatternRangeEndpoint::End,
+            TextUnit::Character,
+            end as i32,
+        )
+        .map_err(AutomationError::from)?;
+    if (moved as usize) < end {
+        return Err(AutomationError::InvalidArgument(format!(
+            "Range end {} is past the end of the text ({} characters)",
+            end, moved
+        )));
+    }
+    range
+        .move_endpoint_by_unit(
+            TextPatternRangeEndpoint::Start,
+            TextUnit::Character,
+            start as i32,
+        )
+        .map_err(AutomationError::from)?;
+    range.select().map_err(AutomationError::from)
+}
+
+pub(crate) fn text_runs(
+    element: &uiautomation::UIElement,
+) -> Result<Vec<TextRun>, AutomationError> {
+    let pattern = text_pattern(element)?;
+    let run = document_start(&pattern)?;
+    let mut runs = Vec::new();
+    let mut start = 0;
+    while runs.len() < MAX_TEXT_RUNS {
+        let moved = run
+            .move_endpoint_by_unit(TextPatternRangeEndpoint::End, TextUnit::Format, 1)
+            .map_err(AutomationError::from)?;
+        if moved == 0 {
+            break;
+        }
+        let text = run.get_text(-1).map_err(AutomationError::from)?;
+        if text.is_empty() {
+            break;
+        }
+        let next_start = start + text.chars().count();
+        runs.push(read_run(&run, text, start));
+        start = next_start;
+        run.move_endpoint_by_range(
+            TextPatternRangeEndpoint::Start,
+            &run,
+            TextPatternRangeEndpoint::End,
+        )
+        .map_err(AutomationError::from)?;
+    }
+    Ok(runs)
+}
+
+// Attributes a range does not report, or reports as mixed, are left unset
+fn read_run(range: &UITextRange, text: String, start: usize) -> TextRun {
+    let int = |attribute: TextAttribute| attribute_value::<i32>(range, attribute);
+    let color =
+        |attribute: TextAttribute| int(attribute).map(|color| colorref_to_hex(color as u32));
+    TextRun {
+        text,
+        start,
+        font_name: attribute_value::<String>(range, TextAttribute::FontName)
+            .filter(|name| !name.is_empty()),
+        font_size: attribute_value::<f64>(range, TextAttribute::FontSize),
+        bold: int(TextAttribute::FontWeight).is_some_and(|weight| weight >= BOLD_FONT_WEIGHT),
+        italic: attribute_value::<bool>(range, TextAttribute::IsItalic).unwrap_or(false),
+        underline: int(TextAttribute::UnderlineStyle).is_some_and(|style| style != 0),
+        foreground_color: color(TextAttribute::ForegroundColor),
+        background_color: color(TextAttribute::BackgroundColor),
+    }
+}
+
+fn attribute_value<T: TryFrom<Variant>>(
+    range: &UITextRange,
+    attribute: TextAttribute,
+) -> Option<T> {
+    range.get_attribute_value(attribute).ok()?.try_into().ok()
+}
@@ -0,0 +1,175 @@
+//! Virtual desktops through `IVirtualDesktopManager` and the registry
+//!
+//! The documented COM interface only answers questions about single
+//! windows. Explorer keeps the list of desktops, their order and names in
+//! the registry, which is read for enumeration.
+
+use windows::core::{GUID, HSTRING};
+use windows::Win32::Foundation::{E_ACCESSDENIED, HWND};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_ALL, COINIT_MULTITHREADED,
+};
+use windows::Win32::System::Registry::{
+    RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_BINARY, RRF_RT_REG_SZ,
+};
+use windows::Win32::System::RemoteDesktop::ProcessIdToSessionId;
+use windows::Win32::System::Threading::GetCurrentProcessId;
+use windows::Win32::UI::Shell::{IVirtualDesktopManager, VirtualDesktopManager};
+use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+
+use crate::virtual_desktop::{format_guid, parse_guid, parse_guid_list, VirtualDesktop};
+use crate::AutomationError;
+
+const VIRTUAL_DESKTOPS_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Explorer\VirtualDesktops";
+const SESSION_INFO_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Explorer\SessionInfo";
+
+// COM initialized once per thread that uses the manager, and uninitialized
+// when the thread exits if that succeeded. Threads already in a
+// single-threaded apartment keep it; the manager works in either
+struct ThreadCom {
+    initialized: bool,
+}
+
+impl Drop for ThreadCom {
+    fn drop(&mut self) {
+        if self.initialized {
+            unsafe { CoUninitialize() };
+        }
+    }
+}
+
+thread_local! {
+    static COM: ThreadCom = ThreadCom {
+        initialized: unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) }.is_ok(),
+    };
+}
+
+fn manager() -> Result<IVirtualDesktopManager, AutomationError> {
+    COM.with(|_| {});
+    unsafe {
+        CoCreateInstance(&VirtualDesktopManager, None, CLSCTX_ALL).map_err(|e| {
+            AutomationError::PlatformError(format!("Failed to create virtual desktop manager: {}", e))
+        })
+    }
+}
+
+// Read a registry value of the given type into bytes, None if it does not exist
+fn read_registry_bytes(
+    subkey: &str,
+    value: &str,
+    flags: windows::Win32::System::Registry::REG_ROUTINE_FLAGS,
+) -> Option<Vec<u8>> {
+    let subkey = HSTRING::from(subkey);
+    let value = HSTRING::from(value);
+    let mut size = 0u32;
+    unsafe {
+        RegGetValueW(HKEY_CURRENT_USER, &subkey, &value, flags, None, None, Some(&mut size))
+            .ok()
+            .ok()?;
+        let mut data = vec![0u8; size as usize];
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            &subkey,
+            &value,
+            flags,
+            None,
+            Some(data.as_mut_ptr() as *mut std::ffi::c_void),
+            Some(&mut size),
+        )
+        .ok()
+        .ok()?;
+        data.truncate(size as usize);
+        Some(data)
+    }
+}
+
+fn read_registry_string(subkey: &str, value: &str) -> Option<String> {
+    let bytes = read_registry_bytes(subkey, value, RRF_RT_REG_SZ)?;
+    let wide: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|&c| c != 0)
+        .collect();
+    Some(String::from_utf16_lossy(&wide)).filter(|name| !name.is_empty())
+}
+
+// Windows 11 keeps the current desktop next to the list, Windows 10 per session
+fn current_desktop_id() -> Option<u128> {
+    let bytes = read_registry_bytes(VIRTUAL_DESKTOPS_KEY, "CurrentVirtualDesktop", RRF_RT_REG_BINARY)
+        .or_else(|| {
+            let mut session = 0u32;
+            unsafe { ProcessIdToSessionId(GetCurrentProcessId(), &mut session) }.ok()?;
+            let key = format!(r"{}\{}\VirtualDesktops", SESSION_INFO_KEY, session);
+            read_registry_bytes(&key, "CurrentVirtualDesktop", RRF_RT_REG_BINARY)
+        })?;
+    parse_guid_list(&bytes).first().copied()
+}
+
+/// Virtual desktops in Task View order
+pub(crate) fn list() -> Result<Vec<VirtualDesktop>, AutomationError> {
+    let mut ids = read_registry_bytes(VIRTUAL_DESKTOPS_KEY, "VirtualDesktopIDs", RRF_RT_REG_BINARY)
+        .map(|bytes| parse_guid_list(&bytes))
+        .unwrap_or_default();
+    let mut current = current_desktop_id();
+
+    // Explorer only writes the list once a second desktop has been created
+    if ids.is_empty() {
+        let foreground = unsafe { GetForegroundWindow() };
+        let id = window_desktop(foreground)?;
+        ids.push(id);
+        current = Some(id);
+    }
+
+    Ok(ids
+        .into_iter()
+        .enumerate()
+        .map(|(index, id)| {
+            let key = format!(r"{}\Desktops\{}", VIRTUAL_DESKTOPS_KEY, format_guid(id));
+            VirtualDesktop {
+                id: format_guid(id),
+                name: read_registry_string(&key, "Name").unwrap_or_else(|| format!("Desktop {}", index + 1)),
+                index,
+                is_current: current == Some(id),
+            }
+        })
+        .collect())
+}
+
+fn window_desktop(hwnd: HWND) -> Result<u128, AutomationError> {
+    let id = unsafe { manager()?.GetWindowDesktopId(hwnd) }.map_err(|e| {
+        AutomationError::PlatformError(format!("Failed to get the virtual desktop of the window: {}", e))
+    })?;
+    if id == GUID::zeroed() {
+        // Windows shown on all desktops, and windows not shown at all
+        return Err(AutomationError::UnsupportedOperation(
+            "Window does not belong to a single virtual desktop".to_string(),
+        ));
+    }
+    Ok(id.to_u128())
+}
+
+/// GUID of the virtual desktop `hwnd` is on
+pub(crate) fn window_desktop_id(hwnd: HWND) -> Result<String, AutomationError> {
+    window_desktop(hwnd).map(format_guid)
+}
+
+pub(crate) fn is_on_current_desktop(hwnd: HWND) -> Result<bool, AutomationError> {
+    unsafe { manager()?.IsWindowOnCurrentVirtualDesktop(hwnd) }
+        .map(|on_current| on_current.as_bool())
+        .map_err(|e| AutomationError::PlatformError(format!("Failed to check the virtual desktop of the window: {}", e)))
+}
+
+pub(crate) fn move_window(hwnd: HWND, desktop_id: &str) -> Result<(), AutomationError> {
+    let id = parse_guid(desktop_id).ok_or_else(|| {
+        AutomationError::InvalidArgument(format!("'{}' is not a virtual desktop id", desktop_id))
+    })?;
+    unsafe { manager()?.MoveWindowToDesktop(hwnd, &GUID::from_u128(id)) }.map_err(|e| {
+        if e.code() == E_ACCESSDENIED {
+            AutomationError::PermissionDenied(
+                "Windows only lets a process move its own windows between virtual desktops".to_string(),
+            )
+        } else {
+            AutomationError::PlatformError(format!("Failed to move window to desktop {}: {}", desktop_id, e))
+        }
+    })
+}
@@ -0,0 +1,230 @@
+//! Registry of top-level windows kept up to date by window event hooks
+//!
+//! Looking for a freshly launched application's window with a UI Automation
+//! matcher walks the desktop until the matcher times out, over and over
+//! while the window is not there yet. Instead, one dedicated thread hooks
+//! window show, hide, rename and destroy events with `SetWinEventHook` and
+//! keeps a registry of the visible, titled top-level windows, seeded with
+//! `EnumWindows`. Lookups wait on the registry and wake up as soon as a
+//! matching window is shown or renamed.
+
+use std::collections::BTreeMap;
+use std::sync::mpsc;
+use std::sync::{Condvar, Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+use windows::Win32::Foundation::{BOOL, HWND, LPARAM};
+use windows::Win32::UI::Accessibility::{HWINEVENTHOOK, SetWinEventHook};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CHILDID_SELF, DispatchMessageW, EVENT_OBJECT_DESTROY, EVENT_OBJECT_HIDE, EVENT_OBJECT_NAMECHANGE,
+    EVENT_OBJECT_SHOW, EnumWindows, GA_ROOT, GetAncestor, GetMessageW, GetWindowTextW,
+    GetWindowThreadProcessId, IsWindowVisible, MSG, OBJID_WINDOW, TranslateMessage, WINEVENT_OUTOFCONTEXT,
+    WINEVENT_SKIPOWNPROCESS,
+};
+
+/// A visible, titled top-level window
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WindowEntry {
+    pub hwnd: isize,
+    pub pid: u32,
+    pub title: String,
+    // Order in which windows were shown or renamed, the latest highest
+    sequence: u64,
+}
+
+/// Windows by handle. Kept apart from the hooks so the bookkeeping can be
+/// tested without them
+#[derive(Debug, Default)]
+pub(crate) struct WindowRegistry {
+    windows: BTreeMap<isize, WindowEntry>,
+    next_sequence: u64,
+}
+
+impl WindowRegistry {
+    /// Add a window or update its title
+    pub(crate) fn shown(&mut self, hwnd: isize, pid: u32, title: String) {
+        self.next_sequence += 1;
+        self.windows.insert(
+            hwnd,
+            WindowEntry {
+                hwnd,
+                pid,
+                title,
+                sequence: self.next_sequence,
+            },
+        );
+    }
+
+    pub(crate) fn removed(&mut self, hwnd: isize) {
+        self.windows.remove(&hwnd);
+    }
+
+    /// The matching window shown or renamed last
+    pub(crate) fn find(&self, matches: impl Fn(&WindowEntry) -> bool) -> Option<WindowEntry> {
+        self.windows
+            .values()
+            .filter(|window| matches(window))
+            .max_by_key(|window| window.sequence)
+            .cloned()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.windows.len()
+    }
+}
+
+struct Shared {
+    registry: Mutex<WindowRegistry>,
+    changed: Condvar,
+}
+
+static SHARED: Shared = Shared {
+    registry: Mutex::new(WindowRegistry {
+        windows: BTreeMap::new(),
+        next_sequence: 0,
+    }),
+    changed: Condvar::new(),
+};
+static HOOK_THREAD: OnceLock<Result<(), String>> = OnceLock::new();
+
+fn registry() -> MutexGuard<'static, WindowRegistry> {
+    SHARED.registry.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Wait up to `timeout` for a window `matches` accepts, checking windows
+/// already open first. `None` on timeout, or when the hooks could not be
+/// installed and callers have to search the tree themselves
+pub(crate) fn wait_for_window(
+    timeout: Duration,
+    matches: impl Fn(&WindowEntry) -> bool,
+) -> Option<WindowEntry> {
+    if let Err(e) = HOOK_THREAD.get_or_init(start_hooks) {
+        debug!("Window registry unavailable: {}", e);
+        return None;
+    }
+    let start = Instant::now();
+    let mut registry = registry();
+    loop {
+        if let Some(window) = registry.find(&matches) {
+            debug!(hwnd = window.hwnd, pid = window.pid, title = %window.title, elapsed_ms = start.elapsed().as_millis(), "Window found in registry");
+            return Some(window);
+        }
+        let remaining = timeout.checked_sub(start.elapsed()).filter(|d| !d.is_zero())?;
+        registry = SHARED
+            .changed
+            .wait_timeout(registry, remaining)
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .0;
+    }
+}
+
+/// Whether the hooks were installed, in which case the registry knows every
+/// visible, titled top-level window
+pub(crate) fn is_running() -> bool {
+    matches!(HOOK_THREAD.get(), Some(Ok(())))
+}
+
+fn start_hooks() -> Result<(), String> {
+    let (ready_tx, ready_rx) = mpsc::channel();
+    thread::Builder::new()
+        .name("window-events".to_string())
+        .spawn(move || run(ready_tx))
+        .map_err(|e| format!("Failed to start window event thread: {}", e))?;
+    ready_rx
+        .recv()
+        .map_err(|_| "Window event thread exited during startup".to_string())?
+}
+
+fn run(ready: mpsc::Sender<Result<(), String>>) {
+    // Out-of-context hooks are called on this thread while it pumps messages
+    let show_hooks = unsafe {
+        SetWinEventHook(
+            EVENT_OBJECT_DESTROY,
+            EVENT_OBJECT_HIDE,
+            None,
+            Some(on_event),
+            0,
+            0,
+            WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+        )
+    };
+    let rename_hook = unsafe {
+        SetWinEventHook(
+            EVENT_OBJECT_NAMECHANGE,
+            EVENT_OBJECT_NAMECHANGE,
+            None,
+            Some(on_event),
+            0,
+            0,
+            WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+        )
+    };
+    if show_hooks.is_invalid() || rename_hook.is_invalid() {
+        let _ = ready.send(Err("SetWinEventHook failed".to_string()));
+        return;
+    }
+
+    // Seed after hooking, so no window shown in between is missed
+    seed();
+    debug!(windows = registry().len(), "Window registry started");
+    let _ = ready.send(Ok(()));
+
+    let mut msg = MSG::default();
+    while unsafe { GetMessageW(&mut msg, None, 0, 0) }.as_bool() {
+        unsafe {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+    warn!("Window event thread stopped");
+}
+
+fn seed() {
+    unsafe extern "system" fn visit(hwnd: HWND, _: LPARAM) -> BOOL {
+        update(hwnd);
+        BOOL(1)
+    }
+    let _ = unsafe { EnumWindows(Some(visit), LPARAM(0)) };
+}
+
+unsafe extern "system" fn on_event(
+    _hook: HWINEVENTHOOK,
+    event: u32,
+    hwnd: HWND,
+    object: i32,
+    child: i32,
+    _thread: u32,
+    _time: u32,
+) {
+    // Only events about windows themselves, not their contents
+    if hwnd.is_invalid() || object != OBJID_WINDOW.0 || child != CHILDID_SELF as i32 {
+        return;
+    }
+    match event {
+        EVENT_OBJECT_SHOW | EVENT_OBJECT_NAMECHANGE => update(hwnd),
+        EVENT_OBJECT_HIDE | EVENT_OBJECT_DESTROY => {
+            registry().removed(hwnd.0 as isize);
+        }
+        _ => return,
+    }
+    SHARED.changed.notify_all();
+}
+
+// Record `hwnd` if it is a visible, titled top-level window, forget it otherwise
+fn update(hwnd: HWND) {
+    let handle = hwnd.0 as isize;
+    let top_level = unsafe { GetAncestor(hwnd, GA_ROOT) } == hwnd;
+    if !top_level {
+        return;
+    }
+    let mut title = [0u16; 512];
+    let length = unsafe { GetWindowTextW(hwnd, &mut title) };
+    if length <= 0 || !unsafe { IsWindowVisible(hwnd) }.as_bool() {
+        registry().removed(handle);
+        return;
+    }
+    let mut pid = 0;
+    unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+    registry().shown(handle, pid, String::from_utf16_lossy(&title[..length as usize]));
+}
@@ -0,0 +1,226 @@
+//! Post-conditions for actions that can "succeed" without any effect
+//!
+//! A click on a button that is not wired up yet, or keystrokes dropped by a
+//! busy text field, report success but change nothing. The verified actions
+//! on [`UIElement`] check a [`Condition`] after each attempt and retry with a
+//! different input method until it holds:
+//!
+//! ```no_run
+//! # use terminator::{Desktop, postcondition::Condition};
+//! # use std::time::Duration;
+//! # async fn run(desktop: &Desktop) -> Result<(), terminator::AutomationError> {
+//! let ok = desktop.locator("name:OK").first(None).await?;
+//! let result = ok.click_and_expect(&Condition::Gone, Duration::from_secs(2))?;
+//! println!("dialog closed by attempt {} ({})", result.attempt, result.method);
+//!
+//! let field = desktop.locator("name:Email").first(None).await?;
+//! field.type_text_verified("user@example.com")?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! When every attempt fails the error is `AutomationError::VerificationFailed`
+//! with one failure per attempt.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+use crate::verify::{VerificationFailure, VerificationReport};
+use crate::{AutomationError, ClickResult, UIElement};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The expected effect of an action on the element it was performed on
+#[derive(Clone)]
+pub enum Condition {
+    /// The element is no longer visible or no longer exists (e.g. its dialog closed)
+    Gone,
+    /// The element has keyboard focus
+    Focused,
+    /// The element is enabled
+    Enabled,
+    /// The element is disabled
+    Disabled,
+    /// The element's name equals the string
+    NameEquals(String),
+    /// The element's value equals the string
+    ValueEquals(String),
+    /// The element's value contains the string
+    ValueContains(String),
+    /// The element's text contains the string
+    TextContains(String),
+    /// Any other check, with a description used in error messages
+    Custom(String, Arc<dyn Fn(&UIElement) -> bool + Send + Sync>),
+}
+
+impl Condition {
+    pub fn custom(
+        description: impl Into<String>,
+        check: impl Fn(&UIElement) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Condition::Custom(description.into(), Arc::new(check))
+    }
+
+    /// Build a condition from its kind name (`gone`, `focused`, `enabled`,
+    /// `disabled`, `name_equals`, `value_equals`, `value_contains` or
+    /// `text_contains`, snake_case or camelCase) and its expected string
+    pub fn from_kind(kind: &str, value: Option<String>) -> Result<Self, String> {
+        let needs_value = |value: Option<String>| {
+            value.ok_or_else(|| format!("Condition '{}' requires a value", kind))
+        };
+        match kind.replace('_', "").to_lowercase().as_str() {
+            "gone" => Ok(Condition::Gone),
+            "focused" => Ok(Condition::Focused),
+            "enabled" => Ok(Condition::Enabled),
+            "disabled" => Ok(Condition::Disabled),
+            "nameequals" => Ok(Condition::NameEquals(needs_value(value)?)),
+            "valueequals" => Ok(Condition::ValueEquals(needs_value(value)?)),
+            "valuecontains" => Ok(Condition::ValueContains(needs_value(value)?)),
+            "textcontains" => Ok(Condition::TextContains(needs_value(value)?)),
+            _ => Err(format!("Unknown condition '{}'", kind)),
+        }
+    }
+
+    /// Whether the condition holds right now. Failing reads count as not met
+    pub fn is_met(&self, element: &UIElement) -> bool {
+        match self {
+            Condition::Gone => !matches!(element.is_visible(), Ok(true)),
+            Condition::Focused => matches!(element.is_focused(), Ok(true)),
+            Condition::Enabled => matches!(element.is_enabled(), Ok(true)),
+            Condition::Disabled => matches!(element.is_enabled(), Ok(false)),
+            Condition::NameEquals(name) => element.name_or_empty() == *name,
+            Condition::ValueEquals(value) => element.value_or_empty() == *value,
+            Condition::ValueContains(value) => element.value_or_empty().contains(value.as_str()),
+            Condition::TextContains(text) => element
+                .text(1)
+                .map(|t| t.contains(text.as_str()))
+                .unwrap_or(false),
+            Condition::Custom(_, check) => check(element),
+        }
+    }
+
+    /// Poll until the condition holds or `timeout` expires. Checks at least once
+    pub fn wait(&self, element: &UIElement, timeout: Duration) -> bool {
+        let start = Instant::now();
+        loop {
+            if self.is_met(element) {
+                return true;
+            }
+            if start.elapsed() >= timeout {
+                return false;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Gone => write!(f, "element is gone"),
+            Condition::Focused => write!(f, "element is focused"),
+            Condition::Enabled => write!(f, "element is enabled"),
+            Condition::Disabled => write!(f, "element is disabled"),
+            Condition::NameEquals(name) => write!(f, "name is '{}'", name),
+            Condition::ValueEquals(value) => write!(f, "value is '{}'", value),
+            Condition::ValueContains(value) => write!(f, "value contains '{}'", value),
+            Condition::TextContains(text) => write!(f, "text contains '{}'", text),
+            Condition::Custom(description, _) => write!(f, "{}", description),
+        }
+    }
+}
+
+impl fmt::Debug for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Condition({})", self)
+    }
+}
+
+/// One try of a verified action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionAttempt {
+    /// Input method used, e.g. "click", "mouse" or "clipboard"
+    pub method: String,
+    /// Error returned by the action itself, if it failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Whether the post-condition held after this attempt
+    pub condition_met: bool,
+    pub duration_ms: u64,
+}
+
+/// Outcome of an action whose effect was verified
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifiedActionResult {
+    /// Input method of the attempt that produced the expected effect
+    pub method: String,
+    /// 1-based number of that attempt
+    pub attempt: usize,
+    /// Every attempt made, in order
+    pub attempts: Vec<ActionAttempt>,
+    /// Click details of the successful attempt, for clicks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub click: Option<ClickResult>,
+}
+
+/// An input method tried by a verified action
+pub(crate) type ActionMethod<'a> =
+    (&'static str, Box<dyn Fn() -> Result<Option<ClickResult>, AutomationError> + 'a>);
+
+/// Run `methods` in order until `condition` holds within `timeout` after one of them
+pub(crate) fn run_verified(
+    element: &UIElement,
+    action: &str,
+    condition: &Condition,
+    timeout: Duration,
+    methods: Vec<ActionMethod<'_>>,
+) -> Result<VerifiedActionResult, AutomationError> {
+    let mut attempts = Vec::with_capacity(methods.len());
+
+    for (method, run) in methods {
+        let start = Instant::now();
+        let (error, click, condition_met) = match run() {
+            Ok(click) => (None, click, condition.wait(element, timeout)),
+            Err(e) => (Some(e.to_string()), None, false),
+        };
+        debug!(action, method, condition_met, error = ?error, "Verified action attempt");
+        attempts.push(ActionAttempt {
+            method: method.to_string(),
+            error,
+            condition_met,
+            duration_ms: start.elapsed().as_millis() as u64,
+        });
+
+        if condition_met {
+            return Ok(VerifiedActionResult {
+                method: method.to_string(),
+                attempt: attempts.len(),
+                attempts,
+                click,
+            });
+        }
+    }
+
+    let failures = attempts
+        .iter()
+        .enumerate()
+        .map(|(index, attempt)| VerificationFailure {
+            check: format!("attempt {} ({})", index + 1, attempt.method),
+            message: match &attempt.error {
+                Some(error) => error.clone(),
+                None => format!("expected {} within {}ms", condition, timeout.as_millis()),
+            },
+            element: Some(element.to_serializable()),
+            screenshot: None,
+        })
+        .collect();
+
+    Err(AutomationError::VerificationFailed(Box::new(VerificationReport {
+        step: format!("{} until {}", action, condition),
+        checks: attempts.len(),
+        failures,
+    })))
+}
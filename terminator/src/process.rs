@@ -0,0 +1,219 @@
+//! Launching applications and managing their processes
+//!
+//! Unlike `Desktop::open_application`, which starts an app by name and
+//! forgets about it, [`Desktop::launch`](crate::Desktop::launch) starts an
+//! executable with explicit arguments, environment and working directory and
+//! returns an [`AppHandle`] that can wait for the app's window, await its
+//! exit code, and kill it when a test is done:
+//!
+//! ```no_run
+//! # use terminator::Desktop;
+//! # use std::time::Duration;
+//! # async fn run(desktop: &Desktop) -> Result<(), terminator::AutomationError> {
+//! let app = desktop
+//!     .launch("notepad.exe", &["notes.txt"], &[], None)?
+//!     .kill_on_drop(true);
+//! let window = app.wait_for_window(Some(Duration::from_secs(10))).await?;
+//! window.locator("name:Text Editor")?.first(None).await?.type_text("hello", false)?;
+//! app.kill()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Some launchers start the real app in another process and exit right away
+//! (e.g. Windows Store apps); their handle only tracks the launcher.
+
+use std::process::{Child, Command, ExitStatus};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+use crate::middleware::ActionHooks;
+use crate::platforms::AccessibilityEngine;
+use crate::{AutomationError, UIElement};
+
+const DEFAULT_WINDOW_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+struct ProcessInner {
+    child: Mutex<Child>,
+    kill_on_drop: AtomicBool,
+}
+
+impl ProcessInner {
+    fn child(&self) -> MutexGuard<'_, Child> {
+        self.child.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Drop for ProcessInner {
+    fn drop(&mut self) {
+        if !self.kill_on_drop.load(Ordering::Relaxed) {
+            return;
+        }
+        let child = self.child.get_mut().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Ok(None) = child.try_wait() {
+            debug!(pid = child.id(), "Killing launched process on drop");
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// A process started by `Desktop::launch`. Clones refer to the same process
+#[derive(Clone)]
+pub struct AppHandle {
+    engine: Arc<dyn AccessibilityEngine>,
+    // Of the desktop that launched the process, for its dry-run mode
+    hooks: Arc<ActionHooks>,
+    inner: Arc<ProcessInner>,
+    pid: u32,
+    path: String,
+}
+
+impl AppHandle {
+    pub(crate) fn launch(
+        engine: Arc<dyn AccessibilityEngine>,
+        hooks: Arc<ActionHooks>,
+        path: &str,
+        args: &[&str],
+        env: &[(&str, &str)],
+        cwd: Option<&str>,
+    ) -> Result<Self, AutomationError> {
+        let mut command = Command::new(path);
+        command.args(args);
+        command.envs(env.iter().copied());
+        if let Some(cwd) = cwd {
+            command.current_dir(cwd);
+        }
+        let child = command.spawn().map_err(|e| {
+            AutomationError::PlatformError(format!("Failed to launch '{}': {}", path, e))
+        })?;
+        let pid = child.id();
+        info!(path, pid, "Launched process");
+
+        Ok(Self {
+            engine,
+            hooks,
+            inner: Arc::new(ProcessInner {
+                child: Mutex::new(child),
+                kill_on_drop: AtomicBool::new(false),
+            }),
+            pid,
+            path: path.to_string(),
+        })
+    }
+
+    /// Kill the process when the last clone of this handle is dropped, so
+    /// tests clean up after themselves even when they fail early
+    pub fn kill_on_drop(self, enabled: bool) -> Self {
+        self.inner.kill_on_drop.store(enabled, Ordering::Relaxed);
+        self
+    }
+
+    /// Process ID of the launched process
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Executable the process was started from
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Exit status if the process has exited, without waiting
+    fn try_status(&self) -> Result<Option<ExitStatus>, AutomationError> {
+        self.inner.child().try_wait().map_err(|e| {
+            AutomationError::PlatformError(format!(
+                "Failed to query process {}: {}",
+                self.pid, e
+            ))
+        })
+    }
+
+    /// Whether the process is still running
+    pub fn is_running(&self) -> bool {
+        matches!(self.try_status(), Ok(None))
+    }
+
+    /// Exit code if the process has exited. `None` while it runs, and also
+    /// when it was terminated without one (e.g. by a signal)
+    pub fn exit_code(&self) -> Result<Option<i32>, AutomationError> {
+        Ok(self.try_status()?.and_then(|status| status.code()))
+    }
+
+    /// Terminate the process and reap it. Does nothing if it already exited
+    pub fn kill(&self) -> Result<(), AutomationError> {
+        let mut child = self.inner.child();
+        if child.try_wait().ok().flatten().is_some() {
+            return Ok(());
+        }
+        if self.hooks.dry_run.is_enabled() {
+            self.hooks.dry_run.simulate_command("kill", format!("pid {}: {}", self.pid, self.path));
+            return Ok(());
+        }
+        child.kill().map_err(|e| {
+            AutomationError::PlatformError(format!("Failed to kill process {}: {}", self.pid, e))
+        })?;
+        let _ = child.wait();
+        info!(pid = self.pid, "Killed launched process");
+        Ok(())
+    }
+
+    /// Wait for the process to exit and return its exit code (`None` if it
+    /// was terminated without one). Waits forever without a timeout
+    pub async fn wait_for_exit(&self, timeout: Option<Duration>) -> Result<Option<i32>, AutomationError> {
+        let start = Instant::now();
+        loop {
+            if let Some(status) = self.try_status()? {
+                debug!(pid = self.pid, ?status, "Launched process exited");
+                return Ok(status.code());
+            }
+            if timeout.is_some_and(|t| start.elapsed() >= t) {
+                return Err(AutomationError::Timeout(format!(
+                    "Process {} ('{}') still running after {:?}",
+                    self.pid,
+                    self.path,
+                    start.elapsed()
+                )));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Wait until the process shows a window in the accessibility tree and
+    /// return its application element. Defaults to 30 seconds
+    pub async fn wait_for_window(&self, timeout: Option<Duration>) -> Result<UIElement, AutomationError> {
+        let timeout = timeout.unwrap_or(DEFAULT_WINDOW_TIMEOUT);
+        let start = Instant::now();
+        loop {
+            match self.engine.get_application_by_pid(self.pid as i32, Some(Duration::ZERO)) {
+                Ok(app) => return Ok(app),
+                Err(e) => debug!(pid = self.pid, "No window yet: {}", e),
+            }
+            if let Some(status) = self.try_status()? {
+                return Err(AutomationError::ElementNotFound(format!(
+                    "Process {} ('{}') exited with {} before showing a window",
+                    self.pid, self.path, status
+                )));
+            }
+            if start.elapsed() >= timeout {
+                return Err(AutomationError::Timeout(format!(
+                    "Process {} ('{}') showed no window within {:?}",
+                    self.pid, self.path, timeout
+                )));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+impl std::fmt::Debug for AppHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AppHandle")
+            .field("pid", &self.pid)
+            .field("path", &self.path)
+            .finish()
+    }
+}
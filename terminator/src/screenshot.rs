@@ -0,0 +1,191 @@
+//! Encoding of screenshots to PNG, JPEG and WebP
+//!
+//! Captures return raw RGBA, which is what OCR and image comparisons want but
+//! is large to store or send: a 4K desktop is over 30MB. Encode it once here
+//! instead of in every consumer:
+//!
+//! ```no_run
+//! # use terminator::{Desktop, ImageFormat};
+//! # async fn run(desktop: &Desktop) -> Result<(), terminator::AutomationError> {
+//! let png = desktop.capture_screen().await?.to_png()?;
+//! let preview = desktop.capture_screen_as(ImageFormat::Jpeg { quality: 70 }).await?;
+//! std::fs::write("screen.jpg", &preview.data).ok();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::io::Cursor;
+use std::str::FromStr;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{DynamicImage, ExtendedColorType, ImageEncoder, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::{AutomationError, ScreenshotResult};
+
+/// Default JPEG quality, from 1 to 100
+pub const DEFAULT_JPEG_QUALITY: u8 = 85;
+
+/// Encoding of a screenshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Png,
+    /// Lossy, without alpha. `quality` goes from 1 to 100
+    Jpeg { quality: u8 },
+    /// Lossless
+    WebP,
+}
+
+impl ImageFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg { .. } => "image/jpeg",
+            ImageFormat::WebP => "image/webp",
+        }
+    }
+
+    /// File extension without the dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg { .. } => "jpg",
+            ImageFormat::WebP => "webp",
+        }
+    }
+}
+
+impl fmt::Display for ImageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageFormat::Jpeg { quality } => write!(f, "jpeg:{}", quality),
+            other => f.write_str(other.extension()),
+        }
+    }
+}
+
+/// Parses `png`, `webp`, `jpeg` or `jpg`, and `jpeg:<quality>`
+impl FromStr for ImageFormat {
+    type Err = AutomationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_lowercase();
+        let (name, quality) = match lower.split_once(':') {
+            Some((name, quality)) => (name, Some(quality)),
+            None => (lower.as_str(), None),
+        };
+        match (name, quality) {
+            ("png", None) => Ok(ImageFormat::Png),
+            ("webp", None) => Ok(ImageFormat::WebP),
+            ("jpeg" | "jpg", None) => Ok(ImageFormat::Jpeg {
+                quality: DEFAULT_JPEG_QUALITY,
+            }),
+            ("jpeg" | "jpg", Some(quality)) => quality
+                .parse::<u8>()
+                .ok()
+                .filter(|q| (1..=100).contains(q))
+                .map(|quality| ImageFormat::Jpeg { quality })
+                .ok_or_else(|| {
+                    AutomationError::InvalidArgument(format!(
+                        "JPEG quality must be between 1 and 100, got '{}'",
+                        quality
+                    ))
+                }),
+            _ => Err(AutomationError::InvalidArgument(format!(
+                "Unknown image format '{}'. Expected png, jpeg, jpeg:<quality> or webp",
+                s
+            ))),
+        }
+    }
+}
+
+/// A screenshot encoded as an image file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncodedImage {
+    pub format: ImageFormat,
+    /// The file contents
+    pub data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ScreenshotResult {
+    /// Encode as PNG
+    pub fn to_png(&self) -> Result<Vec<u8>, AutomationError> {
+        self.check_buffer()?;
+        let mut png = Vec::new();
+        PngEncoder::new(&mut png)
+            .write_image(&self.image_data, self.width, self.height, ExtendedColorType::Rgba8)
+            .map_err(|e| encoding_error("PNG", e))?;
+        Ok(png)
+    }
+
+    /// Encode as JPEG. `quality` is clamped to 1..=100
+    pub fn to_jpeg(&self, quality: u8) -> Result<Vec<u8>, AutomationError> {
+        // JPEG has no alpha channel
+        let rgb = DynamicImage::ImageRgba8(self.to_rgba_image()?).to_rgb8();
+        let mut jpeg = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpeg, quality.clamp(1, 100))
+            .encode_image(&rgb)
+            .map_err(|e| encoding_error("JPEG", e))?;
+        Ok(jpeg)
+    }
+
+    /// Encode as lossless WebP, usually noticeably smaller than PNG for
+    /// screenshots
+    pub fn to_webp(&self) -> Result<Vec<u8>, AutomationError> {
+        self.check_buffer()?;
+        let mut webp = Cursor::new(Vec::new());
+        WebPEncoder::new_lossless(&mut webp)
+            .write_image(&self.image_data, self.width, self.height, ExtendedColorType::Rgba8)
+            .map_err(|e| encoding_error("WebP", e))?;
+        Ok(webp.into_inner())
+    }
+
+    /// Encode in `format`
+    pub fn encode(&self, format: ImageFormat) -> Result<EncodedImage, AutomationError> {
+        let data = match format {
+            ImageFormat::Png => self.to_png()?,
+            ImageFormat::Jpeg { quality } => self.to_jpeg(quality)?,
+            ImageFormat::WebP => self.to_webp()?,
+        };
+        Ok(EncodedImage {
+            format,
+            data,
+            width: self.width,
+            height: self.height,
+        })
+    }
+
+    fn to_rgba_image(&self) -> Result<RgbaImage, AutomationError> {
+        self.check_buffer()?;
+        RgbaImage::from_raw(self.width, self.height, self.image_data.clone())
+            .ok_or_else(|| self.buffer_error())
+    }
+
+    // The encoders panic on a buffer of the wrong size
+    pub(crate) fn check_buffer(&self) -> Result<(), AutomationError> {
+        let expected = self.width as usize * self.height as usize * 4;
+        if self.image_data.len() != expected || expected == 0 {
+            return Err(self.buffer_error());
+        }
+        Ok(())
+    }
+
+    pub(crate) fn buffer_error(&self) -> AutomationError {
+        AutomationError::InvalidArgument(format!(
+            "Screenshot buffer of {} bytes does not hold a {}x{} RGBA image",
+            self.image_data.len(),
+            self.width,
+            self.height
+        ))
+    }
+}
+
+fn encoding_error(format: &str, error: image::ImageError) -> AutomationError {
+    AutomationError::Internal(format!("Failed to encode screenshot as {}: {}", format, error))
+}
@@ -0,0 +1,307 @@
+//! Search indexes of large windows queried over and over
+//!
+//! Every locator search walks the window's live tree again, which takes
+//! seconds in windows with thousands of elements. An index reads the tree
+//! once with its elements, keeps them by role and by name trigram, and
+//! answers later searches in that window from memory:
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop};
+//! # async fn run(desktop: &Desktop, pid: u32) -> Result<(), AutomationError> {
+//! let window = desktop.index_window(pid, None, None)?;
+//! for row in window.locator("role:DataItem && name:Invoice").all(None, None).await? {
+//!     println!("{:?}", row.name());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Locators use the index when they search in the indexed window itself,
+//! without a depth limit, for role, id, name, text and translated name
+//! selectors and their `&&`/`||` combinations. Other searches, and searches
+//! the index has no match for, walk the tree as usual.
+//!
+//! The index follows structure and name change events of the window's
+//! process: after one, the next search reads the tree again. Windows only,
+//! like the trees with handles it is built from.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use tracing::{debug, info, warn};
+
+use crate::l10n;
+use crate::platforms::{AccessibilityEngine, TreeBuildConfig};
+use crate::selector::canonical_role;
+use crate::ui_events::{UIEvent, UIEventKind, UIEventSubscription};
+use crate::{AutomationError, Selector, UIElement, UINode};
+
+static INDEXES: Mutex<Vec<Arc<WindowIndex>>> = Mutex::new(Vec::new());
+
+/// Role and name lookup over the nodes of a tree, numbered depth-first with
+/// the root as 0 like [`UINodeWithHandles`](crate::UINodeWithHandles)
+#[derive(Debug, Clone, Default)]
+pub struct TreeIndex {
+    roles: Vec<String>,
+    names: Vec<String>,
+    ids: Vec<Option<String>>,
+    by_role: HashMap<String, Vec<usize>>,
+    by_trigram: HashMap<[char; 3], Vec<usize>>,
+}
+
+impl TreeIndex {
+    pub fn new(tree: &UINode) -> Self {
+        let mut index = Self::default();
+        index.add(tree);
+        index
+    }
+
+    fn add(&mut self, node: &UINode) {
+        let number = self.roles.len();
+        let role = node.attributes.role.to_lowercase();
+        let name = node.attributes.name.as_deref().unwrap_or_default().to_lowercase();
+        self.by_role.entry(role.clone()).or_default().push(number);
+        let mut seen = BTreeSet::new();
+        for trigram in trigrams(&name) {
+            if seen.insert(trigram) {
+                self.by_trigram.entry(trigram).or_default().push(number);
+            }
+        }
+        self.roles.push(role);
+        self.names.push(name);
+        self.ids.push(node.id.clone());
+        for child in &node.children {
+            self.add(child);
+        }
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.roles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roles.is_empty()
+    }
+
+    /// Numbers of the nodes below the root matching `selector`, in
+    /// depth-first order, as a search from the root would find them.
+    /// `None` for selectors the index cannot answer
+    pub fn matches(&self, selector: &Selector) -> Option<Vec<usize>> {
+        let mut numbers = self.matching(selector)?;
+        numbers.remove(&0);
+        Some(numbers.into_iter().collect())
+    }
+
+    fn matching(&self, selector: &Selector) -> Option<BTreeSet<usize>> {
+        match selector {
+            Selector::Role { role, name } => {
+                let with_role = self.with_role(role);
+                Some(match name {
+                    Some(name) => self.named(name).intersection(&with_role).copied().collect(),
+                    None => with_role,
+                })
+            }
+            Selector::Name(name) => Some(self.named(name)),
+            Selector::NameL10n(_) => self.matching(&selector.localized()),
+            // Access-key markers get in the way of trigrams, so every name is checked
+            Selector::LocalizedName(names) => Some(
+                (0..self.len())
+                    .filter(|&number| l10n::is_one_of(&self.names[number], names))
+                    .collect(),
+            ),
+            Selector::Text(text) => {
+                let mut numbers = self.named(text);
+                numbers.extend(self.with_role("text"));
+                Some(numbers)
+            }
+            Selector::Id(id) => Some(
+                (0..self.len())
+                    .filter(|&number| self.ids[number].as_deref() == Some(id.as_str()))
+                    .collect(),
+            ),
+            Selector::And(selectors) => {
+                let mut operands = selectors.iter().map(|s| self.matching(s));
+                let first = operands.next()??;
+                operands.try_fold(first, |numbers, operand| {
+                    Some(numbers.intersection(&operand?).copied().collect())
+                })
+            }
+            Selector::Or(selectors) => selectors.iter().try_fold(BTreeSet::new(), |mut numbers, s| {
+                numbers.extend(self.matching(s)?);
+                Some(numbers)
+            }),
+            _ => None,
+        }
+    }
+
+    // Nodes with `role` or one of its aliases, ignoring case
+    fn with_role(&self, role: &str) -> BTreeSet<usize> {
+        self.by_role.get(&canonical_role(role)).into_iter().flatten().copied().collect()
+    }
+
+    // Nodes whose name contains `needle`, ignoring case. Names are looked up
+    // by the trigrams of the needle and then checked in full
+    fn named(&self, needle: &str) -> BTreeSet<usize> {
+        let needle = needle.to_lowercase();
+        let mut postings: Vec<&Vec<usize>> = Vec::new();
+        for trigram in trigrams(&needle) {
+            match self.by_trigram.get(&trigram) {
+                Some(numbers) => postings.push(numbers),
+                None => return BTreeSet::new(),
+            }
+        }
+        let candidates: Box<dyn Iterator<Item = usize> + '_> = match postings.iter().min_by_key(|numbers| numbers.len()) {
+            Some(shortest) => Box::new(shortest.iter().copied()),
+            // Shorter than a trigram
+            None => Box::new(0..self.len()),
+        };
+        candidates
+            .filter(|&number| self.names[number].contains(&needle))
+            .collect()
+    }
+}
+
+fn trigrams(text: &str) -> impl Iterator<Item = [char; 3]> {
+    let chars: Vec<char> = text.chars().collect();
+    (0..chars.len().saturating_sub(2)).map(move |i| [chars[i], chars[i + 1], chars[i + 2]])
+}
+
+struct Indexed {
+    index: TreeIndex,
+    elements: Vec<UIElement>,
+}
+
+fn read_window(
+    engine: &dyn AccessibilityEngine,
+    pid: u32,
+    title: Option<&str>,
+    config: &TreeBuildConfig,
+) -> Result<Indexed, AutomationError> {
+    let start = Instant::now();
+    let (tree, elements) = engine.get_window_tree_with_handles(pid, title, config.clone())?.into_parts();
+    let index = TreeIndex::new(&tree);
+    debug!(pid, nodes = index.len(), duration_ms = start.elapsed().as_millis(), "Window indexed");
+    Ok(Indexed { index, elements })
+}
+
+/// The index of one window with what it needs to read the tree again
+pub(crate) struct WindowIndex {
+    engine: Arc<dyn AccessibilityEngine>,
+    /// The window as first read, which locators search in
+    window: UIElement,
+    pid: u32,
+    title: Option<String>,
+    config: TreeBuildConfig,
+    indexed: Mutex<Indexed>,
+    stale: Arc<AtomicBool>,
+    // In a mutex only for the index to be shared between threads
+    _subscription: Mutex<UIEventSubscription>,
+}
+
+impl WindowIndex {
+    // Elements matching `selector`, reading the tree again first if it changed
+    fn find(&self, selector: &Selector) -> Option<Vec<UIElement>> {
+        let mut indexed = self.indexed.lock().unwrap_or_else(|p| p.into_inner());
+        // Cleared before reading, so changes made meanwhile mark it again
+        if self.stale.swap(false, Ordering::SeqCst) {
+            match read_window(self.engine.as_ref(), self.pid, self.title.as_deref(), &self.config) {
+                Ok(rebuilt) => *indexed = rebuilt,
+                Err(e) => {
+                    warn!(pid = self.pid, "Failed to index window again: {}", e);
+                    self.stale.store(true, Ordering::SeqCst);
+                    return None;
+                }
+            }
+        }
+        let numbers = indexed.index.matches(selector)?;
+        Some(
+            numbers
+                .into_iter()
+                .filter_map(|number| indexed.elements.get(number).cloned())
+                .collect(),
+        )
+    }
+}
+
+/// Index the window of `pid` (the one titled `title` if given), replacing
+/// an index it already has. Returns the window
+pub(crate) fn index_window(
+    engine: Arc<dyn AccessibilityEngine>,
+    pid: u32,
+    title: Option<&str>,
+    config: TreeBuildConfig,
+) -> Result<UIElement, AutomationError> {
+    let stale = Arc::new(AtomicBool::new(false));
+    let marked = stale.clone();
+    let subscription = engine.subscribe_ui_events(
+        &[UIEventKind::StructureChanged, UIEventKind::PropertyChanged],
+        Arc::new(move |event: UIEvent| {
+            if event.kind == UIEventKind::PropertyChanged && event.detail.as_deref() != Some("Name") {
+                return;
+            }
+            let from_app = event
+                .element
+                .and_then(|element| element.process_id().ok())
+                .is_none_or(|event_pid| event_pid == pid);
+            if from_app {
+                marked.store(true, Ordering::SeqCst);
+            }
+        }),
+    )?;
+    // Read after subscribing, so no change made in between is missed
+    let indexed = read_window(engine.as_ref(), pid, title, &config)?;
+    let nodes = indexed.index.len();
+    let window = indexed.elements.first().cloned().ok_or_else(|| {
+        AutomationError::ElementNotFound(format!("Window of process {} has no elements", pid))
+    })?;
+    let window_index = WindowIndex {
+        engine,
+        window: window.clone(),
+        pid,
+        title: title.map(str::to_string),
+        config,
+        indexed: Mutex::new(indexed),
+        stale,
+        _subscription: Mutex::new(subscription),
+    };
+
+    let mut indexes = INDEXES.lock().unwrap_or_else(|p| p.into_inner());
+    indexes.retain(|existing| existing.window != window);
+    indexes.push(Arc::new(window_index));
+    info!(pid, nodes, "Window search index built");
+    Ok(window)
+}
+
+/// Drop the indexes of the windows of `pid`, returning how many there were
+pub(crate) fn drop_indexes(pid: u32) -> usize {
+    let mut indexes = INDEXES.lock().unwrap_or_else(|p| p.into_inner());
+    let before = indexes.len();
+    indexes.retain(|index| index.pid != pid);
+    before - indexes.len()
+}
+
+/// Elements matching `selector` in `root` from its index, if `root` is an
+/// indexed window and the index can answer. `None` means the tree must be
+/// walked, as does an empty result: the elements may have appeared since
+pub(crate) fn find(root: Option<&UIElement>, selector: &Selector, depth: Option<usize>) -> Option<Vec<UIElement>> {
+    let root = root?;
+    if depth.is_some() {
+        return None;
+    }
+    let index = {
+        let indexes = INDEXES.lock().unwrap_or_else(|p| p.into_inner());
+        if indexes.is_empty() {
+            return None;
+        }
+        indexes
+            .iter()
+            .find(|index| index.window == *root)
+            .cloned()?
+    };
+    let elements = index.find(selector)?;
+    debug!(?selector, matches = elements.len(), "Searched window index");
+    (!elements.is_empty()).then_some(elements)
+}
@@ -1,5 +1,7 @@
 use std::collections::BTreeMap;
 
+use crate::AutomationError;
+
 /// Represents ways to locate a UI element
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Selector {
@@ -9,6 +11,13 @@ pub enum Selector {
     Id(String),
     /// Select by name/label
     Name(String),
+    /// Select by the name of a translation key in any display language, see
+    /// [`crate::l10n`]
+    NameL10n(String),
+    /// Select by whole name, any of these, ignoring case and `&` access-key
+    /// markers. What [`Selector::localized`] turns a
+    /// [`Selector::NameL10n`] into
+    LocalizedName(Vec<String>),
     /// Select by text content
     Text(String),
     /// Select using XPath-like query
@@ -23,16 +32,97 @@ pub enum Selector {
     Chain(Vec<Selector>),
     /// Select by class name
     ClassName(String),
+    /// Narrow the previous chain step to elements right of the anchor, in the same row
+    RightOf(Box<Selector>),
+    /// Narrow the previous chain step to elements left of the anchor, in the same row
+    LeftOf(Box<Selector>),
+    /// Narrow the previous chain step to elements above the anchor, in the same column
+    Above(Box<Selector>),
+    /// Narrow the previous chain step to elements below the anchor, in the same column
+    Below(Box<Selector>),
+    /// Narrow the previous chain step to elements within `max_distance` pixels of the anchor
+    Near { anchor: Box<Selector>, max_distance: u32 },
+    /// Elements not matching the inner selector
+    Not(Box<Selector>),
+    /// Elements matching every selector
+    And(Vec<Selector>),
+    /// Elements matching any of the selectors
+    Or(Vec<Selector>),
+}
+
+impl Selector {
+    /// Check that this selector can be tested against a single element, as
+    /// the operands of `Not`, `And` and `Or` must be. Paths, chains and
+    /// spatial steps depend on other elements and cannot
+    pub(crate) fn check_element_predicate(&self) -> Result<(), AutomationError> {
+        match self {
+            Selector::Role { .. }
+            | Selector::Id(_)
+            | Selector::Name(_)
+            | Selector::NameL10n(_)
+            | Selector::LocalizedName(_)
+            | Selector::Text(_)
+            | Selector::NativeId(_)
+            | Selector::ClassName(_) => Ok(()),
+            Selector::Not(inner) => inner.check_element_predicate(),
+            Selector::And(selectors) | Selector::Or(selectors) => {
+                if selectors.is_empty() {
+                    return Err(AutomationError::InvalidArgument(
+                        "And/Or selectors need at least one operand".to_string(),
+                    ));
+                }
+                selectors.iter().try_for_each(Selector::check_element_predicate)
+            }
+            other => Err(AutomationError::InvalidArgument(format!(
+                "{:?} cannot be combined with not/and/or: only role, id, name, text, nativeid and classname selectors can",
+                other
+            ))),
+        }
+    }
 }
 
+/// `role` lowercased, with the aliases role selectors accept (`app`,
+/// `dialog`, `data`, `url`, `title`) replaced by the role they stand for
+pub(crate) fn canonical_role(role: &str) -> String {
+    let role = role.to_lowercase();
+    match role.as_str() {
+        "app" | "application" => "pane".to_string(),
+        "dialog" => "window".to_string(),
+        "data" => "dataitem".to_string(),
+        "url" | "urlfield" => "edit".to_string(),
+        "title" => "titlebar".to_string(),
+        _ => role,
+    }
+}
+
+/// Parses the prefix syntax (`role:button`, `name:OK`, `/Window/Button`, ...)
+/// and boolean combinators on top of it, from loosest to tightest binding:
+/// `a || b`, `a && b` and `a:not(b)`. The operand of `:not(...)` may also be
+/// an attribute predicate, e.g. `role=button:not([name="Cancel"])`.
+///
+/// Quote a value to match it literally, e.g. `name:"Save && close"`. A
+/// string whose operands would be empty or could not be combined, e.g.
+/// `name:Tom &&`, is not split either and reads as a single selector
 impl From<&str> for Selector {
     fn from(s: &str) -> Self {
+        // Paths have their own predicate syntax
+        if !s.starts_with('/') {
+            if let Some(selector) = parse_combinators(s) {
+                return selector;
+            }
+            if s.starts_with('[') {
+                if let Some(selector) = parse_predicates(s) {
+                    return selector;
+                }
+            }
+        }
+
         // Make common UI roles like "window", "button", etc. default to Role selectors
         // instead of Name selectors
         match s {
             // if role:button 
             _ if s.starts_with("role:") => Selector::Role {
-                role: s[5..].to_string(),
+                role: literal(&s[5..]).to_string(),
                 name: None,
             },
             "app" | "application" | "window" | "button" | "checkbox" | "menu" | "menuitem" | "menubar" | "textfield"
@@ -47,28 +137,203 @@ impl From<&str> for Selector {
             },
             _ if s.starts_with("Name:") || s.starts_with("name:") => {
                 let parts: Vec<&str> = s.splitn(2, ':').collect();
-                Selector::Name(parts[1].to_string())
+                Selector::Name(literal(parts[1]).to_string())
             }
+            _ if s.to_lowercase().starts_with("l10n:") => Selector::NameL10n(s[5..].trim().to_string()),
             _ if s.to_lowercase().starts_with("classname:") => {
                 let parts: Vec<&str> = s.splitn(2, ':').collect();
-                Selector::ClassName(parts[1].to_string())
+                Selector::ClassName(literal(parts[1]).to_string())
             }
             _ if s.to_lowercase().starts_with("nativeid:") => {
                 let parts: Vec<&str> = s.splitn(2, ':').collect();
-                Selector::NativeId(parts[1].trim().to_string())
+                Selector::NativeId(literal(parts[1].trim()).to_string())
             }
-            _ if s.starts_with("id:") => Selector::Id(s[3..].to_string()),
-            _ if s.starts_with("text:") => Selector::Text(s[5..].to_string()),
+            _ if s.starts_with("id:") => Selector::Id(literal(&s[3..]).to_string()),
+            _ if s.starts_with("text:") => Selector::Text(literal(&s[5..]).to_string()),
+            // Paths may contain ':' inside predicates, e.g. [name='C:\Users']
+            _ if s.starts_with('/') => Selector::Path(s.to_string()),
+            // Spatial steps wrap the anchor selector, e.g. rightof:name:Name
+            _ if s.to_lowercase().starts_with("rightof:") => Selector::RightOf(Box::new(Selector::from(&s[8..]))),
+            _ if s.to_lowercase().starts_with("leftof:") => Selector::LeftOf(Box::new(Selector::from(&s[7..]))),
+            _ if s.to_lowercase().starts_with("above:") => Selector::Above(Box::new(Selector::from(&s[6..]))),
+            _ if s.to_lowercase().starts_with("below:") => Selector::Below(Box::new(Selector::from(&s[6..]))),
+            _ if s.to_lowercase().starts_with("near:") => Selector::Near {
+                anchor: Box::new(Selector::from(&s[5..])),
+                max_distance: crate::spatial::DEFAULT_NEAR_DISTANCE,
+            },
+            _ if s.to_lowercase().starts_with("role=") => Selector::Role {
+                role: unquote(&s[5..]).to_string(),
+                name: None,
+            },
             _ if s.contains(':') => {
                 let parts: Vec<&str> = s.splitn(2, ':').collect();
                 Selector::Role {
                     role: parts[0].to_string(),
-                    name: Some(parts[1].to_string()),
+                    name: Some(literal(parts[1]).to_string()),
                 }
             }
             _ if s.starts_with('#') => Selector::Id(s[1..].to_string()),
-            _ if s.starts_with('/') => Selector::Path(s.to_string()),
             _ => Selector::Name(s.to_string()),
         }
     }
 }
+
+/// `||`, `&&` and `:not(...)` combinations, loosest first. None when `s`
+/// has none, or when one of its operands is empty or cannot be tested on
+/// one element, so that `s` is read literally
+fn parse_combinators(s: &str) -> Option<Selector> {
+    let selector = if let Some(operands) = split_top_level(s, "||") {
+        Selector::Or(operands.into_iter().map(Selector::from).collect())
+    } else if let Some(operands) = split_top_level(s, "&&") {
+        Selector::And(operands.into_iter().map(Selector::from).collect())
+    } else {
+        parse_not(s)?
+    };
+    selector.check_element_predicate().ok()?;
+    Some(selector)
+}
+
+/// Byte offsets of `op` in `s` outside quotes, brackets and parentheses,
+/// ignoring ASCII case. Empty when the brackets are unbalanced. Only a
+/// quote right after `:`, `=` or `[` opens a quoted value, so apostrophes
+/// in names (`name:Don't Save`) do not
+fn find_top_level(s: &str, op: &str) -> Vec<usize> {
+    let mut found = Vec::new();
+    let mut depth = 0i32;
+    let mut quote = None;
+    let mut previous = None;
+    // Operators do not overlap, e.g. in `|||`
+    let mut skip_to = 0;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') if matches!(previous, Some(':' | '=' | '[')) => quote = Some(c),
+            (None, _) if depth == 0
+                && i >= skip_to
+                && s.get(i..i + op.len()).is_some_and(|at| at.eq_ignore_ascii_case(op)) =>
+            {
+                found.push(i);
+                skip_to = i + op.len();
+            }
+            (None, '(' | '[') => depth += 1,
+            (None, ')' | ']') => depth -= 1,
+            _ => {}
+        }
+        if !c.is_whitespace() {
+            previous = Some(c);
+        }
+    }
+    if depth != 0 || quote.is_some() {
+        return Vec::new();
+    }
+    found
+}
+
+/// Split `s` at each top-level `op`, see [`find_top_level`]. None when `op`
+/// does not occur there or leaves an empty operand
+fn split_top_level<'a>(s: &'a str, op: &str) -> Option<Vec<&'a str>> {
+    let found = find_top_level(s, op);
+    if found.is_empty() {
+        return None;
+    }
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for at in found {
+        parts.push(s[start..at].trim());
+        start = at + op.len();
+    }
+    parts.push(s[start..].trim());
+    if parts.iter().any(|part| part.is_empty()) {
+        return None;
+    }
+    Some(parts)
+}
+
+/// `base:not(a):not(b)` as `And([base, Not(a), Not(b)])`. None unless `s`
+/// ends in balanced `:not(...)` groups
+fn parse_not(s: &str) -> Option<Selector> {
+    let start = *find_top_level(s, ":not(").first()?;
+    let base = s[..start].trim();
+    if base.is_empty() {
+        return None;
+    }
+    let mut operands = vec![Selector::from(base)];
+    let mut rest = &s[start..];
+    while !rest.is_empty() {
+        if !rest.get(..5)?.eq_ignore_ascii_case(":not(") {
+            return None;
+        }
+        let inner = &rest[5..];
+        let close = closing_paren(inner)?;
+        operands.push(Selector::Not(Box::new(Selector::from(&inner[..close]))));
+        rest = inner[close + 1..].trim_start();
+    }
+    Some(Selector::And(operands))
+}
+
+// Index of the `)` closing a group whose `(` was just before `s`
+fn closing_paren(s: &str) -> Option<usize> {
+    let mut depth = 0i32;
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') if depth == 0 => return Some(i),
+            (None, ')') => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `[name="Cancel"][role=button]` as the selectors for each attribute, and'ed
+/// together. None if `s` is not made of such predicates
+fn parse_predicates(s: &str) -> Option<Selector> {
+    let mut selectors = Vec::new();
+    let mut rest = s.trim();
+    while !rest.is_empty() {
+        let body = rest.strip_prefix('[')?;
+        let end = body.find(']')?;
+        let (key, value) = body[..end].split_once('=')?;
+        let value = unquote(value).to_string();
+        selectors.push(match key.trim().to_lowercase().as_str() {
+            "role" => Selector::Role { role: value, name: None },
+            "name" => Selector::Name(value),
+            "text" => Selector::Text(value),
+            "id" => Selector::Id(value),
+            "nativeid" | "automationid" => Selector::NativeId(value),
+            "classname" => Selector::ClassName(value),
+            _ => return None,
+        });
+        rest = body[end + 1..].trim_start();
+    }
+    match selectors.len() {
+        0 => None,
+        1 => selectors.pop(),
+        _ => Some(Selector::And(selectors)),
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    let s = s.trim();
+    for q in ['"', '\''] {
+        if let Some(inner) = s.strip_prefix(q).and_then(|s| s.strip_suffix(q)) {
+            return inner;
+        }
+    }
+    s
+}
+
+/// A prefix value with the quotes around it removed, if it is quoted
+fn literal(value: &str) -> &str {
+    for q in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(q).and_then(|v| v.strip_suffix(q)).filter(|_| value.len() > 1) {
+            return inner;
+        }
+    }
+    value
+}
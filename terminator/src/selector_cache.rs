@@ -0,0 +1,212 @@
+//! Opt-in store of the selectors that actually resolved a logical target
+//!
+//! A locator created with [`Desktop::locator_any`](crate::Desktop::locator_any)
+//! tries several candidate selectors for one target, e.g. an automation id
+//! first and the visible name as a fallback. With a cache attached, the
+//! candidate that resolved is remembered per application executable and
+//! version, and tried first on later runs. When the application updates, the
+//! version changes and the candidates are tried in order again.
+//!
+//! ```no_run
+//! # use terminator::{Desktop, SelectorCache};
+//! # use std::sync::Arc;
+//! # async fn run(desktop: &Desktop) -> Result<(), terminator::AutomationError> {
+//! desktop.set_selector_cache(Some(Arc::new(SelectorCache::open("selectors.json")?)));
+//! let save = desktop
+//!     .locator_any("save button", &["nativeid:SaveButton", "name:Save", "button:Save"])
+//!     .first(None)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+use crate::platforms::AccessibilityEngine;
+use crate::{AutomationError, UIElement};
+
+/// Identifies an application build, so learned selectors are not reused
+/// across versions whose UI may differ
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AppKey {
+    /// Executable file name, lowercase (e.g. "notepad.exe")
+    pub exe: String,
+    /// File version of the executable, if the platform reports one
+    pub version: Option<String>,
+}
+
+impl AppKey {
+    pub fn new(exe: impl Into<String>, version: Option<String>) -> Self {
+        Self {
+            exe: exe.into().to_lowercase(),
+            version,
+        }
+    }
+
+    /// Application that owns `element`: the executable reported by the
+    /// platform, otherwise the application name without a version
+    pub(crate) fn for_element(
+        engine: &dyn AccessibilityEngine,
+        element: &UIElement,
+    ) -> Option<Self> {
+        let pid = element.process_id().ok()?;
+        match engine.app_key(pid) {
+            Ok(key) => Some(key),
+            Err(e) => {
+                debug!(pid, "Falling back to application name for selector cache: {}", e);
+                let name = element.application_name();
+                (!name.is_empty()).then(|| AppKey::new(name, None))
+            }
+        }
+    }
+}
+
+impl fmt::Display for AppKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.version {
+            Some(version) => write!(f, "{}@{}", self.exe, version),
+            None => write!(f, "{}", self.exe),
+        }
+    }
+}
+
+/// A selector that resolved a target
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LearnedSelector {
+    pub selector: String,
+    /// How often it resolved the target
+    pub hits: u64,
+    /// Unix time of the last resolution, in seconds
+    pub last_used: u64,
+}
+
+// app key ("exe@version") -> target -> learned selector
+type Entries = BTreeMap<String, BTreeMap<String, LearnedSelector>>;
+
+/// Learned selectors, optionally persisted as JSON. Safe to share between
+/// threads and desktops
+#[derive(Debug, Default)]
+pub struct SelectorCache {
+    path: Option<PathBuf>,
+    entries: Mutex<Entries>,
+}
+
+impl SelectorCache {
+    /// Cache that lives only as long as the process
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Cache backed by the JSON file at `path`. The file is created on the
+    /// first write and rewritten whenever a new selector is learned
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, AutomationError> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).map_err(|e| {
+                AutomationError::InvalidArgument(format!(
+                    "Invalid selector cache {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Entries::new(),
+            Err(e) => {
+                return Err(AutomationError::PlatformError(format!(
+                    "Failed to read selector cache {}: {}",
+                    path.display(),
+                    e
+                )));
+            }
+        };
+        Ok(Self {
+            path: Some(path),
+            entries: Mutex::new(entries),
+        })
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The selector that last resolved `target` in this application build
+    pub fn learned(&self, app: &AppKey, target: &str) -> Option<String> {
+        self.entries()
+            .get(&app.to_string())
+            .and_then(|targets| targets.get(target))
+            .map(|learned| learned.selector.clone())
+    }
+
+    /// Remember that `selector` resolved `target`. Persisted right away when
+    /// the selector is new for the target; failures to write are logged
+    pub fn record(&self, app: &AppKey, target: &str, selector: &str) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let changed = {
+            let mut entries = self.entries();
+            let targets = entries.entry(app.to_string()).or_default();
+            match targets.get_mut(target) {
+                Some(learned) if learned.selector == selector => {
+                    learned.hits += 1;
+                    learned.last_used = now;
+                    false
+                }
+                _ => {
+                    targets.insert(
+                        target.to_string(),
+                        LearnedSelector {
+                            selector: selector.to_string(),
+                            hits: 1,
+                            last_used: now,
+                        },
+                    );
+                    true
+                }
+            }
+        };
+        if changed {
+            debug!(app = %app, target, selector, "Learned selector");
+            if let Err(e) = self.save() {
+                warn!("Failed to persist selector cache: {}", e);
+            }
+        }
+    }
+
+    /// Drop what was learned for `target`, e.g. after its UI was redesigned
+    pub fn forget(&self, app: &AppKey, target: &str) {
+        let removed = self
+            .entries()
+            .get_mut(&app.to_string())
+            .and_then(|targets| targets.remove(target))
+            .is_some();
+        if removed {
+            if let Err(e) = self.save() {
+                warn!("Failed to persist selector cache: {}", e);
+            }
+        }
+    }
+
+    /// Write the cache to its file. Does nothing for in-memory caches
+    pub fn save(&self) -> Result<(), AutomationError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(&*self.entries()).map_err(|e| {
+            AutomationError::PlatformError(format!("Failed to serialize selector cache: {}", e))
+        })?;
+        std::fs::write(path, json).map_err(|e| {
+            AutomationError::PlatformError(format!(
+                "Failed to write selector cache {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
@@ -0,0 +1,378 @@
+//! XPath-like path selectors such as `/Window[name='Untitled - Notepad']/Pane[1]/Button[name='OK']`
+//!
+//! A path is a list of steps, each separated by `/` (direct children) or `//`
+//! (any descendant). A step is a role, or `*` for any role, followed by
+//! optional predicates:
+//!
+//! - `[2]`: the second match (1-based) among the candidates of one parent.
+//!   For `//` steps whose parents are nested, the candidates of the outer
+//!   parent include those of the inner one, which is not searched again
+//! - `[name='OK']`: exact name match. `automationid` and `classname` work the same way.
+//!   Values are in single or double quotes; a quote of the same kind inside
+//!   the value is doubled, as in XPath: `[name='Don''t "save"']`
+//!
+//! Paths start at the search root, the desktop unless a root element is
+//! given. Trees built by `Desktop::get_window_tree` carry the path of every
+//! node in [`UINode::path`], so elements found in a dumped tree can be
+//! re-located with `desktop.locator(node.path)`.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::Hash;
+use std::time::Instant;
+
+use crate::{AutomationError, UIElement, UINode};
+
+/// How a step relates to the elements matched by the previous step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// `/`: direct children
+    Child,
+    /// `//`: descendants at any depth
+    Descendant,
+}
+
+/// One `/`-separated part of a path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathStep {
+    pub axis: Axis,
+    /// Role to match, case-insensitively. `None` for the `*` wildcard
+    pub role: Option<String>,
+    /// Attribute predicates, all of which must match exactly
+    pub attributes: Vec<(String, String)>,
+    /// 1-based position among the matches of one parent
+    pub index: Option<usize>,
+}
+
+impl PathStep {
+    /// Whether `element` satisfies the role and attribute predicates
+    pub fn matches(&self, element: &UIElement) -> bool {
+        if let Some(role) = &self.role {
+            if !element.role().eq_ignore_ascii_case(role) {
+                return false;
+            }
+        }
+        self.attributes.iter().all(|(key, expected)| {
+            let actual = match key.as_str() {
+                "name" => element.name_or_empty(),
+                "automationid" | "id" => native_id(element).unwrap_or_default(),
+                "classname" | "class" => element
+                    .attributes()
+                    .properties
+                    .get("ClassName")
+                    .and_then(|v| v.as_ref())
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                _ => return false,
+            };
+            actual == *expected
+        })
+    }
+}
+
+fn native_id(element: &UIElement) -> Option<String> {
+    element
+        .attributes()
+        .properties
+        .get("AutomationId")
+        .and_then(|v| v.as_ref())
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+impl fmt::Display for PathStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separator = match self.axis {
+            Axis::Child => "/",
+            Axis::Descendant => "//",
+        };
+        write!(f, "{}{}", separator, self.role.as_deref().unwrap_or("*"))?;
+        for (key, value) in &self.attributes {
+            write!(f, "[{}={}]", key, quote(value))?;
+        }
+        if let Some(index) = self.index {
+            write!(f, "[{}]", index)?;
+        }
+        Ok(())
+    }
+}
+
+// Single quotes unless the value contains one, doubling them only when it
+// contains both kinds
+fn quote(value: &str) -> String {
+    if !value.contains('\'') {
+        format!("'{}'", value)
+    } else if !value.contains('"') {
+        format!("\"{}\"", value)
+    } else {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+}
+
+/// Parse a path selector into its steps
+pub fn parse_path(path: &str) -> Result<Vec<PathStep>, String> {
+    let chars: Vec<char> = path.trim().chars().collect();
+    let mut steps = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i] != '/' {
+            return Err(format!("Expected '/' at position {} of path '{}'", i, path));
+        }
+        let axis = if chars.get(i + 1) == Some(&'/') {
+            i += 2;
+            Axis::Descendant
+        } else {
+            i += 1;
+            Axis::Child
+        };
+
+        let start = i;
+        while i < chars.len() && chars[i] != '/' && chars[i] != '[' {
+            i += 1;
+        }
+        let role: String = chars[start..i].iter().collect::<String>().trim().to_string();
+        if role.is_empty() {
+            return Err(format!("Missing role at position {} of path '{}'", start, path));
+        }
+
+        let mut step = PathStep {
+            axis,
+            role: (role != "*").then_some(role),
+            attributes: Vec::new(),
+            index: None,
+        };
+
+        while chars.get(i) == Some(&'[') {
+            let (predicate, next) = read_predicate(&chars, i + 1)
+                .ok_or_else(|| format!("Unterminated '[' at position {} of path '{}'", i, path))?;
+            i = next;
+            apply_predicate(&mut step, &predicate)
+                .map_err(|e| format!("{} in path '{}'", e, path))?;
+        }
+        steps.push(step);
+    }
+
+    if steps.is_empty() {
+        return Err("Path selector cannot be empty".to_string());
+    }
+    Ok(steps)
+}
+
+// Text up to the closing ']' (quotes may contain ']' and '/'), and the position after it
+fn read_predicate(chars: &[char], mut i: usize) -> Option<(String, usize)> {
+    let mut predicate = String::new();
+    let mut quote: Option<char> = None;
+    while i < chars.len() {
+        let c = chars[i];
+        match quote {
+            // A doubled quote is part of the value
+            Some(q) if c == q && chars.get(i + 1) == Some(&q) => {
+                predicate.push(c);
+                i += 1;
+            }
+            Some(q) if c == q => quote = None,
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if c == ']' => return Some((predicate, i + 1)),
+            _ => {}
+        }
+        predicate.push(c);
+        i += 1;
+    }
+    None
+}
+
+fn apply_predicate(step: &mut PathStep, predicate: &str) -> Result<(), String> {
+    let predicate = predicate.trim();
+    if let Ok(index) = predicate.parse::<usize>() {
+        if index == 0 {
+            return Err("Path indexes start at 1".to_string());
+        }
+        step.index = Some(index);
+        return Ok(());
+    }
+
+    let (key, value) = predicate
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid predicate '[{}]'", predicate))?;
+    let key = key.trim().trim_start_matches('@').to_lowercase();
+    let value = value.trim();
+    let unquoted = ['\'', '"']
+        .into_iter()
+        .find_map(|q| {
+            let inner = value.strip_prefix(q)?.strip_suffix(q)?;
+            Some(inner.replace(&format!("{q}{q}"), &q.to_string()))
+        })
+        .ok_or_else(|| format!("Predicate value must be quoted in '[{}]'", predicate))?;
+    if !matches!(
+        key.as_str(),
+        "name" | "automationid" | "id" | "classname" | "class"
+    ) {
+        return Err(format!("Unsupported path attribute '{}'", key));
+    }
+    step.attributes.push((key, unquoted.to_string()));
+    Ok(())
+}
+
+/// How deep a `//` step searches below each element unless a depth is given
+pub const DEFAULT_PATH_DEPTH: usize = 50;
+
+/// Elements matching `steps`, starting from the children of `root`, in
+/// document order and each once. `//` steps search at most `depth` levels
+/// down ([`DEFAULT_PATH_DEPTH`] by default), and the search fails with a
+/// timeout once `deadline` has passed
+pub fn resolve_path(
+    root: &UIElement,
+    steps: &[PathStep],
+    depth: Option<usize>,
+    deadline: Option<Instant>,
+) -> Result<Vec<UIElement>, AutomationError> {
+    let limits = Limits {
+        depth: depth.unwrap_or(DEFAULT_PATH_DEPTH),
+        deadline,
+    };
+    walk(
+        root,
+        steps,
+        &limits,
+        |element| element.children().unwrap_or_default(),
+        |step, element| step.matches(element),
+    )
+}
+
+/// Bounds of a path search
+pub(crate) struct Limits {
+    pub(crate) depth: usize,
+    pub(crate) deadline: Option<Instant>,
+}
+
+impl Limits {
+    fn check(&self) -> Result<(), AutomationError> {
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => {
+                Err(AutomationError::Timeout("Path search timed out".to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// [`resolve_path`] over any tree, given how to list an element's children
+/// and whether it matches a step
+pub(crate) fn walk<T: Clone + Eq + Hash>(
+    root: &T,
+    steps: &[PathStep],
+    limits: &Limits,
+    children: impl Fn(&T) -> Vec<T>,
+    matches: impl Fn(&PathStep, &T) -> bool,
+) -> Result<Vec<T>, AutomationError> {
+    let mut current = vec![root.clone()];
+    for step in steps {
+        let mut next = Vec::new();
+        let mut found = HashSet::new();
+        // Everything below the parents searched so far. A parent among them
+        // was searched along with its ancestor
+        let mut searched = HashSet::new();
+        for parent in &current {
+            limits.check()?;
+            let candidates = match step.axis {
+                Axis::Child => children(parent),
+                Axis::Descendant if searched.contains(parent) => continue,
+                Axis::Descendant => {
+                    let below = descendants(parent, &children, limits)?;
+                    searched.extend(below.iter().cloned());
+                    below
+                }
+            };
+            let matching = candidates.into_iter().filter(|element| matches(step, element));
+            let picked: Vec<T> = match step.index.map(|index| index.checked_sub(1)) {
+                // Indexes start at 1, so `[0]` picks nothing
+                Some(Some(skip)) => matching.skip(skip).take(1).collect(),
+                Some(None) => Vec::new(),
+                None => matching.collect(),
+            };
+            next.extend(picked.into_iter().filter(|element| found.insert(element.clone())));
+        }
+        if next.is_empty() {
+            return Ok(next);
+        }
+        current = next;
+    }
+    Ok(current)
+}
+
+// Descendants down to the depth limit, in document (depth-first) order
+fn descendants<T>(element: &T, children: &impl Fn(&T) -> Vec<T>, limits: &Limits) -> Result<Vec<T>, AutomationError> {
+    let mut result = Vec::new();
+    let mut stack: Vec<(T, usize)> = children(element).into_iter().rev().map(|child| (child, 1)).collect();
+    while let Some((element, depth)) = stack.pop() {
+        limits.check()?;
+        if depth < limits.depth {
+            stack.extend(children(&element).into_iter().rev().map(|child| (child, depth + 1)));
+        }
+        result.push(element);
+    }
+    Ok(result)
+}
+
+/// Path step for the root of a tree: its role and, if it has one, its name
+pub fn root_step(role: &str, name: Option<&str>) -> String {
+    let step = PathStep {
+        axis: Axis::Child,
+        role: Some(role.to_string()),
+        attributes: name
+            .filter(|n| !n.is_empty())
+            .map(|n| vec![("name".to_string(), n.to_string())])
+            .unwrap_or_default(),
+        index: None,
+    };
+    step.to_string()
+}
+
+/// Fill in [`UINode::path`] below `node`, whose own path is `path`. Children
+/// are addressed by role and position among siblings with the same role
+pub fn assign_paths(node: &mut UINode, path: String) {
+    let steps = child_steps(node.children.iter().map(|child| child.attributes.role.as_str()));
+    for (child, step) in node.children.iter_mut().zip(steps) {
+        assign_paths(child, format!("{}{}", path, step));
+    }
+    node.path = Some(path);
+}
+
+/// Path steps addressing each of a node's children, given their roles in order
+pub fn child_steps<'a>(roles: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut seen: Vec<(String, usize)> = Vec::new();
+    let mut steps = Vec::new();
+    for (i, role) in roles.into_iter().enumerate() {
+        let role = role.to_string();
+        let step = if role.is_empty() {
+            // Without a role, `*[n]` is the n-th child of any role
+            PathStep {
+                axis: Axis::Child,
+                role: None,
+                attributes: Vec::new(),
+                index: Some(i + 1),
+            }
+        } else {
+            let position = match seen.iter_mut().find(|(r, _)| r.eq_ignore_ascii_case(&role)) {
+                Some((_, count)) => {
+                    *count += 1;
+                    *count
+                }
+                None => {
+                    seen.push((role.clone(), 1));
+                    1
+                }
+            };
+            PathStep {
+                axis: Axis::Child,
+                role: Some(role),
+                attributes: Vec::new(),
+                index: Some(position),
+            }
+        };
+        steps.push(step.to_string());
+    }
+    steps
+}
@@ -0,0 +1,165 @@
+//! Selector profiles
+//!
+//! [`Locator::explain`](crate::Locator::explain) runs one search the way
+//! [`Locator::wait`](crate::Locator::wait) would and reports each stage of
+//! it: every hop of the selector chain with the element it searched in, how
+//! long it took, how many elements matched and how the hop matches
+//! elements, then spatial steps, the viewport restriction and refinements
+//! with how many of their candidates were kept. Ordinary searches are not
+//! profiled.
+//!
+//! ```no_run
+//! # fn run(desktop: &terminator::Desktop) {
+//! let profile = desktop.locator("name:Settings").locator("role:Button").explain();
+//! if let Some(hop) = profile.slowest_hop() {
+//!     println!("{} took {}ms", hop.selector, hop.duration_ms);
+//! }
+//! println!("{}", profile);
+//! # }
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::element::SerializableUIElement;
+use crate::selector::Selector;
+
+/// One stage of a search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HopProfile {
+    /// Position among the stages, 0 for the first hop
+    pub index: usize,
+    /// The hop's selector, or the spatial steps or refinement applied
+    pub selector: String,
+    /// How the stage matches: the selector kind (`role`, `name`, `id`,
+    /// `path`, ...) or `spatial`, `viewport`, `filter`, `visible`, `nth`
+    pub strategy: String,
+    /// Element searched in, `None` for the whole desktop
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub searched_in: Option<String>,
+    /// Elements the stage chose from. Only known for stages that narrow
+    /// the matches of the hops before them: the platforms do not report how
+    /// many elements a hop examined
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub candidates: Option<usize>,
+    /// Elements that matched. Hops before the last continue with the first
+    pub matches: usize,
+    pub duration_ms: u64,
+    /// Why the stage failed, other than by matching nothing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Report of [`Locator::explain`](crate::Locator::explain)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SelectorProfile {
+    /// The locator's selector, as reported in errors
+    pub selector: String,
+    pub found: bool,
+    /// Stages of the search in the order they ran. Stops at the first stage
+    /// that matched nothing
+    pub hops: Vec<HopProfile>,
+    /// Search for the element this locator is nested in, for locators below
+    /// a refined one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<Box<SelectorProfile>>,
+    /// Candidate selectors of a `Desktop::locator_any` locator, in the order
+    /// they were tried, up to the one that matched
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<SelectorProfile>,
+    /// Candidate the selector cache had learned for the application, tried first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub learned_candidate: Option<String>,
+    /// First element found
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element: Option<SerializableUIElement>,
+    pub duration_ms: u64,
+}
+
+impl SelectorProfile {
+    /// The stage that took longest, including those of the scope and the
+    /// candidates
+    pub fn slowest_hop(&self) -> Option<&HopProfile> {
+        self.all_hops().max_by_key(|hop| hop.duration_ms)
+    }
+
+    fn all_hops(&self) -> Box<dyn Iterator<Item = &HopProfile> + '_> {
+        Box::new(
+            self.scope
+                .iter()
+                .flat_map(|scope| scope.all_hops())
+                .chain(self.candidates.iter().flat_map(|candidate| candidate.all_hops()))
+                .chain(self.hops.iter()),
+        )
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        let pad = "  ".repeat(indent);
+        writeln!(
+            f,
+            "{}{}: {} in {}ms",
+            pad,
+            self.selector,
+            if self.found { "found" } else { "not found" },
+            self.duration_ms
+        )?;
+        if let Some(scope) = &self.scope {
+            writeln!(f, "{}  within:", pad)?;
+            scope.fmt_indented(f, indent + 2)?;
+        }
+        for candidate in &self.candidates {
+            let learned = self.learned_candidate.as_deref() == Some(candidate.selector.as_str());
+            writeln!(f, "{}  candidate{}:", pad, if learned { " (learned)" } else { "" })?;
+            candidate.fmt_indented(f, indent + 2)?;
+        }
+        for hop in &self.hops {
+            write!(f, "{}  {}. [{}] {}", pad, hop.index + 1, hop.strategy, hop.selector)?;
+            if let Some(root) = &hop.searched_in {
+                write!(f, " in {}", root)?;
+            }
+            match hop.candidates {
+                Some(candidates) => write!(f, ": {} of {} kept", hop.matches, candidates)?,
+                None => write!(f, ": {} matches", hop.matches)?,
+            }
+            write!(f, ", {}ms", hop.duration_ms)?;
+            if let Some(error) = &hop.error {
+                write!(f, " ({})", error)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SelectorProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+/// How a selector matches elements, for [`HopProfile::strategy`]
+pub(crate) fn strategy(selector: &Selector) -> &'static str {
+    match selector {
+        Selector::Role { name: Some(_), .. } => "role_and_name",
+        Selector::Role { .. } => "role",
+        Selector::Id(_) => "id",
+        Selector::Name(_) => "name",
+        Selector::NameL10n(_) | Selector::LocalizedName(_) => "name_l10n",
+        Selector::Text(_) => "text",
+        Selector::Path(_) => "path",
+        Selector::NativeId(_) => "native_id",
+        Selector::Attributes(_) => "attributes",
+        Selector::Filter(_) => "filter",
+        Selector::Chain(_) => "chain",
+        Selector::ClassName(_) => "class_name",
+        Selector::RightOf(_)
+        | Selector::LeftOf(_)
+        | Selector::Above(_)
+        | Selector::Below(_)
+        | Selector::Near { .. } => "spatial",
+        Selector::Not(_) => "not",
+        Selector::And(_) => "and",
+        Selector::Or(_) => "or",
+    }
+}
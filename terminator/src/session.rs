@@ -0,0 +1,111 @@
+//! Interactive session detection for unattended runs.
+//!
+//! UI automation only works when the process runs inside an interactive,
+//! unlocked desktop session. On CI agents and build servers this is often not
+//! the case: the machine may be locked, the RDP client may have disconnected,
+//! or the process may run as a service in session 0. In all of these cases the
+//! accessibility tree is either empty or unreachable, and element lookups fail
+//! with confusing "not found" errors.
+//!
+//! Use [`crate::Desktop::session_state`] to check the session before running a
+//! workflow, and [`crate::Desktop::ensure_ui_available`] to fail fast with
+//! [`crate::AutomationError::UIUnavailable`] instead of waiting for timeouts.
+//!
+//! On Windows, a disconnected RDP session can be moved back to the physical
+//! console with [`crate::Desktop::attach_to_console`]. This keeps the session
+//! interactive after the RDP client closes, which is the usual setup for
+//! unattended automation on virtual machines. It requires administrator
+//! rights.
+
+use serde::{Deserialize, Serialize};
+
+use crate::AutomationError;
+
+/// State of the desktop session the automation process is running in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionState {
+    /// Interactive session with an unlocked input desktop
+    Active,
+    /// Session is interactive but the workstation is locked (secure desktop)
+    Locked,
+    /// Remote session whose client has disconnected
+    Disconnected,
+    /// Non-interactive service session (e.g. Windows session 0)
+    Service,
+    /// State could not be determined on this platform
+    Unknown,
+}
+
+impl SessionState {
+    /// Whether UI automation can be expected to work in this state.
+    ///
+    /// `Unknown` is treated as available so platforms without session
+    /// detection keep their current behavior.
+    pub fn is_ui_available(&self) -> bool {
+        matches!(self, SessionState::Active | SessionState::Unknown)
+    }
+
+    /// Short human-readable explanation, used in error messages
+    pub fn describe(&self) -> &'static str {
+        match self {
+            SessionState::Active => "session is active",
+            SessionState::Locked => "workstation is locked",
+            SessionState::Disconnected => "remote session is disconnected",
+            SessionState::Service => "process runs in a non-interactive service session",
+            SessionState::Unknown => "session state is unknown",
+        }
+    }
+
+    /// The state of Windows session `session_id`, given whether its client
+    /// disconnected and which desktop receives input
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub(crate) fn for_windows_session(session_id: u32, disconnected: bool, input_desktop: &InputDesktop) -> Self {
+        // Session 0 is reserved for services and never has an interactive desktop
+        if session_id == 0 {
+            return SessionState::Service;
+        }
+        if disconnected {
+            return SessionState::Disconnected;
+        }
+        match input_desktop {
+            InputDesktop::Unreachable => SessionState::Locked,
+            InputDesktop::Open(Some(name)) if !name.eq_ignore_ascii_case("Default") => SessionState::Locked,
+            InputDesktop::Open(_) => SessionState::Active,
+        }
+    }
+}
+
+/// The desktop receiving input, as far as the process can see it
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub(crate) enum InputDesktop {
+    /// It could not be opened, as with the secure desktop of a locked session
+    Unreachable,
+    /// It was opened; its name when that could be read
+    Open(Option<String>),
+}
+
+/// The error for a failed `tscon` run moving `session_id` to the console
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub(crate) fn tscon_error(session_id: u32, stderr: &str) -> AutomationError {
+    if stderr.to_lowercase().contains("access is denied") {
+        return AutomationError::PermissionDenied(
+            "attaching to the console requires administrator rights".to_string(),
+        );
+    }
+    AutomationError::PlatformError(format!("tscon failed for session {}: {}", session_id, stderr.trim()))
+}
+
+impl std::fmt::Display for SessionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            SessionState::Active => "active",
+            SessionState::Locked => "locked",
+            SessionState::Disconnected => "disconnected",
+            SessionState::Service => "service",
+            SessionState::Unknown => "unknown",
+        };
+        write!(f, "{}", s)
+    }
+}
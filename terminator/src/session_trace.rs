@@ -0,0 +1,515 @@
+//! Structured traces of automation sessions
+//!
+//! The `tracing` logs of a run are meant to be read by people. A
+//! [`SessionTracer`] records the same calls as data instead: every traced
+//! `Desktop`, `Locator` and `UIElement` call with its arguments, start time,
+//! duration and error, linked to the call it was made from. Traces are saved
+//! as JSON lines, to be loaded back with [`SessionTracer::load`], or as
+//! OTLP/JSON for OpenTelemetry tooling.
+//!
+//! The tracer is a `tracing` layer, which sees the instrumented API calls,
+//! and a [`Middleware`], which sees element actions and their outcome. It is
+//! opt-in and has to be installed as both:
+//!
+//! ```no_run
+//! use terminator::{Desktop, SessionTracer, TraceFormat};
+//! use tracing_subscriber::prelude::*;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let tracer = SessionTracer::new();
+//! tracing_subscriber::registry()
+//!     .with(tracer.clone())
+//!     .with(tracing_subscriber::fmt::layer())
+//!     .init();
+//!
+//! let desktop = Desktop::new(false, false)?;
+//! desktop.add_middleware(tracer.clone());
+//! let result = desktop.locator("name:Save").first(None).await.and_then(|save| save.click());
+//!
+//! if result.is_err() {
+//!     tracer.save("failed-run.jsonl", TraceFormat::JsonLines)?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Calls fail in a trace when they log an error, or, for element actions,
+//! when the action returns one. Text typed or set on elements is recorded as
+//! its length unless [`SessionTracer::with_text`] is enabled.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher, RandomState};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::middleware::{ActionContext, ActionKind, Middleware};
+use crate::AutomationError;
+
+/// Records kept by default before the oldest are dropped
+pub const DEFAULT_MAX_RECORDS: usize = 100_000;
+
+/// What a trace record describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceRecordKind {
+    /// An instrumented API call, such as `Desktop::open_application`
+    Call,
+    /// An action on an element, such as a click
+    Action,
+}
+
+/// One call of an automation session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceRecord {
+    /// Unique within the session
+    pub id: u64,
+    /// The call this one was made from
+    pub parent_id: Option<u64>,
+    /// The top-level call this one belongs to, its own id for top-level
+    /// calls. Correlates everything done on behalf of one API call
+    pub root_id: u64,
+    pub kind: TraceRecordKind,
+    /// Function or action name, e.g. `find_element` or `click`
+    pub name: String,
+    /// Module the call was made in
+    pub target: String,
+    /// Arguments of the call, and for actions the element acted on
+    pub args: Map<String, Value>,
+    /// Start time, in microseconds since the Unix epoch
+    pub start_unix_us: u64,
+    pub duration_ms: f64,
+    /// Why the call failed
+    pub error: Option<String>,
+}
+
+/// File format of a saved trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// One JSON [`TraceRecord`] per line
+    JsonLines,
+    /// An OpenTelemetry `ExportTraceServiceRequest` in its JSON encoding,
+    /// with the session as one trace
+    OtlpJson,
+}
+
+struct TracerState {
+    session_id: String,
+    next_id: AtomicU64,
+    records: Mutex<VecDeque<TraceRecord>>,
+}
+
+/// Records the calls of an automation session, see the [module
+/// documentation](self). Clones share the same records
+#[derive(Clone)]
+pub struct SessionTracer {
+    state: Arc<TracerState>,
+    record_text: bool,
+    max_records: usize,
+}
+
+impl Default for SessionTracer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for SessionTracer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionTracer")
+            .field("session_id", &self.state.session_id)
+            .field("record_text", &self.record_text)
+            .field("max_records", &self.max_records)
+            .finish()
+    }
+}
+
+// Traced spans entered on this thread, innermost last, as (id, root id).
+// Element actions run synchronously inside them
+thread_local! {
+    static ENTERED: RefCell<Vec<(u64, u64)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A traced span that has not closed yet
+struct OpenSpan {
+    id: u64,
+    parent_id: Option<u64>,
+    root_id: u64,
+    args: Map<String, Value>,
+    start: SystemTime,
+    started: Instant,
+    error: Option<String>,
+}
+
+impl SessionTracer {
+    /// A tracer with a new random session id
+    pub fn new() -> Self {
+        let random = |seed: u64| {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u64(seed);
+            hasher.finish()
+        };
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos() as u64;
+        Self {
+            state: Arc::new(TracerState {
+                session_id: format!("{:016x}{:016x}", random(nanos), random(!nanos)),
+                next_id: AtomicU64::new(1),
+                records: Mutex::new(VecDeque::new()),
+            }),
+            record_text: false,
+            max_records: DEFAULT_MAX_RECORDS,
+        }
+    }
+
+    /// Record text typed or set on elements as is, instead of its length.
+    /// Leave off when the session may enter passwords
+    pub fn with_text(mut self, record_text: bool) -> Self {
+        self.record_text = record_text;
+        self
+    }
+
+    /// Keep at most `max_records` records, dropping the oldest
+    pub fn with_max_records(mut self, max_records: usize) -> Self {
+        self.max_records = max_records.max(1);
+        self
+    }
+
+    /// Random 128-bit id of the session, as 32 hex digits
+    pub fn session_id(&self) -> &str {
+        &self.state.session_id
+    }
+
+    /// Records of the calls that finished so far, in the order they finished
+    pub fn records(&self) -> Vec<TraceRecord> {
+        self.lock().iter().cloned().collect()
+    }
+
+    /// Forget all records
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// The records as JSON lines
+    pub fn to_json_lines(&self) -> Result<String, AutomationError> {
+        let mut lines = String::new();
+        for record in self.lock().iter() {
+            let line = serde_json::to_string(record)
+                .map_err(|e| AutomationError::Internal(format!("Failed to serialize trace record: {}", e)))?;
+            lines.push_str(&line);
+            lines.push('\n');
+        }
+        Ok(lines)
+    }
+
+    /// The records as an OTLP/JSON trace export request
+    pub fn to_otlp_json(&self) -> Value {
+        let spans: Vec<Value> = self
+            .lock()
+            .iter()
+            .map(|record| otlp_span(&self.state.session_id, record))
+            .collect();
+        json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [
+                        otlp_attribute("service.name", &Value::from("terminator")),
+                        otlp_attribute("session.id", &Value::from(self.state.session_id.as_str())),
+                    ]
+                },
+                "scopeSpans": [{
+                    "scope": { "name": "terminator", "version": env!("CARGO_PKG_VERSION") },
+                    "spans": spans,
+                }]
+            }]
+        })
+    }
+
+    /// Write the records to `path`
+    pub fn save(&self, path: impl AsRef<Path>, format: TraceFormat) -> Result<(), AutomationError> {
+        let contents = match format {
+            TraceFormat::JsonLines => self.to_json_lines()?,
+            TraceFormat::OtlpJson => self.to_otlp_json().to_string(),
+        };
+        std::fs::write(path.as_ref(), contents).map_err(|e| {
+            AutomationError::PlatformError(format!("Failed to write trace to {}: {}", path.as_ref().display(), e))
+        })
+    }
+
+    /// Read records saved as [`TraceFormat::JsonLines`]
+    pub fn load(path: impl AsRef<Path>) -> Result<Vec<TraceRecord>, AutomationError> {
+        let contents = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+            AutomationError::PlatformError(format!("Failed to read trace from {}: {}", path.as_ref().display(), e))
+        })?;
+        parse_json_lines(&contents)
+    }
+
+    fn next_id(&self) -> u64 {
+        self.state.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn push(&self, record: TraceRecord) {
+        let mut records = self.lock();
+        if records.len() >= self.max_records {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<TraceRecord>> {
+        self.state.records.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn record_action(&self, ctx: &ActionContext, duration: Duration, error: Option<String>) {
+        let (parent_id, root_id) = ENTERED.with(|entered| entered.borrow().last().copied()).unzip();
+        let id = self.next_id();
+
+        let mut args = Map::new();
+        let element = match ctx.element.name() {
+            Some(name) if !name.is_empty() => format!("{}:{}", ctx.element.role(), name),
+            _ => ctx.element.role(),
+        };
+        args.insert("element".to_string(), Value::from(element));
+        if let Some(details) = &ctx.details {
+            let carries_text = matches!(ctx.action, ActionKind::TypeText | ActionKind::SetValue);
+            let details = if carries_text && !self.record_text {
+                format!("[{} characters]", details.chars().count())
+            } else {
+                details.clone()
+            };
+            args.insert("details".to_string(), Value::from(details));
+        }
+
+        self.push(TraceRecord {
+            id,
+            parent_id,
+            root_id: root_id.unwrap_or(id),
+            kind: TraceRecordKind::Action,
+            name: ctx.action.as_str().to_string(),
+            target: "terminator::element".to_string(),
+            args,
+            start_unix_us: unix_micros(ctx.started_at),
+            duration_ms: duration.as_secs_f64() * 1000.0,
+            error,
+        });
+    }
+}
+
+/// Records from JSON lines, skipping blank lines
+pub(crate) fn parse_json_lines(contents: &str) -> Result<Vec<TraceRecord>, AutomationError> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(number, line)| {
+            serde_json::from_str(line).map_err(|e| {
+                AutomationError::InvalidArgument(format!("Invalid trace record on line {}: {}", number + 1, e))
+            })
+        })
+        .collect()
+}
+
+fn unix_micros(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64
+}
+
+fn otlp_attribute(key: &str, value: &Value) -> Value {
+    let value = match value {
+        Value::Bool(flag) => json!({ "boolValue": flag }),
+        // OTLP/JSON encodes 64-bit integers as strings
+        Value::Number(number) if number.is_i64() || number.is_u64() => json!({ "intValue": number.to_string() }),
+        Value::Number(number) => json!({ "doubleValue": number }),
+        Value::String(text) => json!({ "stringValue": text }),
+        other => json!({ "stringValue": other.to_string() }),
+    };
+    json!({ "key": key, "value": value })
+}
+
+pub(crate) fn otlp_span(trace_id: &str, record: &TraceRecord) -> Value {
+    let start_ns = record.start_unix_us as u128 * 1000;
+    let end_ns = start_ns + (record.duration_ms * 1_000_000.0) as u128;
+    let mut attributes = vec![
+        otlp_attribute("code.namespace", &Value::from(record.target.as_str())),
+        otlp_attribute("terminator.kind", &serde_json::to_value(record.kind).unwrap_or_default()),
+        otlp_attribute("terminator.root_id", &Value::from(format!("{:016x}", record.root_id))),
+    ];
+    attributes.extend(record.args.iter().map(|(key, value)| otlp_attribute(&format!("arg.{}", key), value)));
+    let status = match &record.error {
+        Some(message) => json!({ "code": 2, "message": message }),
+        None => json!({ "code": 1 }),
+    };
+    let mut span = json!({
+        "traceId": trace_id,
+        "spanId": format!("{:016x}", record.id),
+        "name": record.name,
+        // SPAN_KIND_INTERNAL
+        "kind": 1,
+        "startTimeUnixNano": start_ns.to_string(),
+        "endTimeUnixNano": end_ns.to_string(),
+        "attributes": attributes,
+        "status": status,
+    });
+    if let Some(parent_id) = record.parent_id {
+        span["parentSpanId"] = Value::from(format!("{:016x}", parent_id));
+    }
+    span
+}
+
+// Only calls into terminator itself, not its dependencies
+fn is_traced(metadata: &Metadata<'_>) -> bool {
+    metadata.target().starts_with("terminator")
+}
+
+/// Span and event fields as JSON values
+struct FieldVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().to_string(), Value::from(format!("{:?}", value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+}
+
+impl<S> Layer<S> for SessionTracer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if !is_traced(attrs.metadata()) {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let parent = span.scope().skip(1).find_map(|ancestor| {
+            ancestor.extensions().get::<OpenSpan>().map(|open| (open.id, open.root_id))
+        });
+        let own_id = self.next_id();
+        let mut args = Map::new();
+        attrs.record(&mut FieldVisitor(&mut args));
+        span.extensions_mut().insert(OpenSpan {
+            id: own_id,
+            parent_id: parent.map(|(parent_id, _)| parent_id),
+            root_id: parent.map_or(own_id, |(_, root_id)| root_id),
+            args,
+            start: SystemTime::now(),
+            started: Instant::now(),
+            error: None,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(open) = span.extensions_mut().get_mut::<OpenSpan>() {
+                values.record(&mut FieldVisitor(&mut open.args));
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let has_error_field = event.metadata().fields().iter().any(|field| field.name() == "error");
+        if *event.metadata().level() != Level::ERROR && !has_error_field {
+            return;
+        }
+        let Some(scope) = ctx.event_scope(event) else {
+            return;
+        };
+        for span in scope {
+            let mut extensions = span.extensions_mut();
+            if let Some(open) = extensions.get_mut::<OpenSpan>() {
+                if open.error.is_none() {
+                    let mut fields = Map::new();
+                    event.record(&mut FieldVisitor(&mut fields));
+                    let message = fields.get("error").or_else(|| fields.get("message")).cloned();
+                    open.error = Some(match message {
+                        Some(Value::String(text)) => text,
+                        Some(other) => other.to_string(),
+                        None => "error".to_string(),
+                    });
+                }
+                break;
+            }
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(open) = span.extensions().get::<OpenSpan>() {
+                ENTERED.with(|entered| entered.borrow_mut().push((open.id, open.root_id)));
+            }
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(open) = span.extensions().get::<OpenSpan>() {
+                ENTERED.with(|entered| {
+                    let mut entered = entered.borrow_mut();
+                    if let Some(position) = entered.iter().rposition(|(entered_id, _)| *entered_id == open.id) {
+                        entered.remove(position);
+                    }
+                });
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(open) = span.extensions_mut().remove::<OpenSpan>() else {
+            return;
+        };
+        self.push(TraceRecord {
+            id: open.id,
+            parent_id: open.parent_id,
+            root_id: open.root_id,
+            kind: TraceRecordKind::Call,
+            name: span.name().to_string(),
+            target: span.metadata().target().to_string(),
+            args: open.args,
+            start_unix_us: unix_micros(open.start),
+            duration_ms: open.started.elapsed().as_secs_f64() * 1000.0,
+            error: open.error,
+        });
+    }
+}
+
+impl Middleware for SessionTracer {
+    fn after_action(&self, ctx: &ActionContext, duration: Duration) {
+        self.record_action(ctx, duration, None);
+    }
+
+    fn on_error(&self, ctx: &ActionContext, error: &AutomationError, duration: Duration) {
+        self.record_action(ctx, duration, Some(error.to_string()));
+    }
+}
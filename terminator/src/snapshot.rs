@@ -0,0 +1,219 @@
+//! Compact binary snapshots of UI trees
+//!
+//! A window tree as JSON repeats the same roles, names and property keys
+//! thousands of times. Snapshots store each distinct string once and refer
+//! to it by index, and encode the rest as MessagePack, which makes them a
+//! fraction of the size of the JSON and much faster to read back. Useful for
+//! keeping a tree per test run:
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop, UINode};
+//! # fn run(desktop: &Desktop, pid: u32) -> Result<(), AutomationError> {
+//! let tree = desktop.get_window_tree(pid, None, None)?;
+//! tree.save("snapshots/checkout.uitree")?;
+//!
+//! let before = UINode::load("snapshots/checkout.uitree")?;
+//! let diff = before.diff(&tree);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`UINode::load`] also reads trees saved as JSON, so existing archives keep
+//! working.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AutomationError, SubtreeStatus, UIElementAttributes, UINode};
+
+/// Start of every snapshot, followed by the format version
+const MAGIC: &[u8; 4] = b"TUIS";
+const VERSION: u8 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    strings: Vec<String>,
+    root: CompactNode,
+}
+
+/// A [`UINode`] with its strings replaced by indexes into
+/// [`Snapshot::strings`]
+#[derive(Serialize, Deserialize)]
+struct CompactNode {
+    id: Option<u32>,
+    role: u32,
+    name: Option<u32>,
+    label: Option<u32>,
+    value: Option<u32>,
+    description: Option<u32>,
+    properties: Vec<(u32, Option<serde_json::Value>)>,
+    is_keyboard_focusable: Option<bool>,
+    path: Option<u32>,
+    truncated: bool,
+    status: SubtreeStatus,
+    children: Vec<CompactNode>,
+}
+
+#[derive(Default)]
+struct Interner {
+    strings: Vec<String>,
+    indexes: HashMap<String, u32>,
+}
+
+impl Interner {
+    fn intern(&mut self, s: &str) -> u32 {
+        if let Some(&index) = self.indexes.get(s) {
+            return index;
+        }
+        let index = self.strings.len() as u32;
+        self.strings.push(s.to_string());
+        self.indexes.insert(s.to_string(), index);
+        index
+    }
+
+    fn intern_opt(&mut self, s: &Option<String>) -> Option<u32> {
+        s.as_deref().map(|s| self.intern(s))
+    }
+
+    fn compact(&mut self, node: &UINode) -> CompactNode {
+        let attributes = &node.attributes;
+        // Sorted so that equal trees give equal bytes
+        let mut properties: Vec<_> = attributes
+            .properties
+            .iter()
+            .map(|(key, value)| (key.as_str(), value))
+            .collect();
+        properties.sort_by_key(|(key, _)| *key);
+        CompactNode {
+            id: self.intern_opt(&node.id),
+            role: self.intern(&attributes.role),
+            name: self.intern_opt(&attributes.name),
+            label: self.intern_opt(&attributes.label),
+            value: self.intern_opt(&attributes.value),
+            description: self.intern_opt(&attributes.description),
+            properties: properties
+                .into_iter()
+                .map(|(key, value)| (self.intern(key), value.clone()))
+                .collect(),
+            is_keyboard_focusable: attributes.is_keyboard_focusable,
+            path: self.intern_opt(&node.path),
+            truncated: node.truncated,
+            status: node.status,
+            children: node.children.iter().map(|child| self.compact(child)).collect(),
+        }
+    }
+}
+
+fn expand(node: CompactNode, strings: &[String]) -> Result<UINode, AutomationError> {
+    let string = |index: u32| {
+        strings.get(index as usize).cloned().ok_or_else(|| {
+            AutomationError::InvalidArgument(format!("Snapshot refers to missing string {}", index))
+        })
+    };
+    let string_opt = |index: Option<u32>| index.map(string).transpose();
+
+    let mut properties = HashMap::with_capacity(node.properties.len());
+    for (key, value) in node.properties {
+        properties.insert(string(key)?, value);
+    }
+    Ok(UINode {
+        id: string_opt(node.id)?,
+        attributes: UIElementAttributes {
+            role: string(node.role)?,
+            name: string_opt(node.name)?,
+            label: string_opt(node.label)?,
+            value: string_opt(node.value)?,
+            description: string_opt(node.description)?,
+            properties,
+            is_keyboard_focusable: node.is_keyboard_focusable,
+        },
+        path: string_opt(node.path)?,
+        truncated: node.truncated,
+        status: node.status,
+        children: node
+            .children
+            .into_iter()
+            .map(|child| expand(child, strings))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+impl UINode {
+    /// Encode this tree as a binary snapshot
+    pub fn to_snapshot(&self) -> Result<Vec<u8>, AutomationError> {
+        let mut interner = Interner::default();
+        let root = interner.compact(self);
+        let snapshot = Snapshot {
+            strings: interner.strings,
+            root,
+        };
+        let mut bytes = Vec::with_capacity(1024);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        rmp_serde::encode::write(&mut bytes, &snapshot).map_err(|e| {
+            AutomationError::Internal(format!("Failed to encode snapshot: {}", e))
+        })?;
+        Ok(bytes)
+    }
+
+    /// Decode a tree from bytes made by [`UINode::to_snapshot`]
+    pub fn from_snapshot(bytes: &[u8]) -> Result<Self, AutomationError> {
+        let Some(payload) = bytes.strip_prefix(MAGIC.as_slice()) else {
+            return Err(AutomationError::InvalidArgument(
+                "Not a UI tree snapshot".to_string(),
+            ));
+        };
+        match payload.split_first() {
+            Some((&VERSION, payload)) => {
+                let snapshot: Snapshot = rmp_serde::from_slice(payload).map_err(|e| {
+                    AutomationError::InvalidArgument(format!("Malformed snapshot: {}", e))
+                })?;
+                expand(snapshot.root, &snapshot.strings)
+            }
+            Some((version, _)) => Err(AutomationError::InvalidArgument(format!(
+                "Unsupported snapshot version {}",
+                version
+            ))),
+            None => Err(AutomationError::InvalidArgument(
+                "Truncated snapshot".to_string(),
+            )),
+        }
+    }
+
+    /// Write this tree to `path` as a binary snapshot
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), AutomationError> {
+        let path = path.as_ref();
+        let bytes = self.to_snapshot()?;
+        std::fs::write(path, bytes).map_err(|e| {
+            AutomationError::PlatformError(format!(
+                "Failed to write snapshot {}: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    /// Read a tree from `path`, saved either by [`UINode::save`] or as JSON
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AutomationError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|e| {
+            AutomationError::PlatformError(format!(
+                "Failed to read snapshot {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        if bytes.starts_with(MAGIC) {
+            return Self::from_snapshot(&bytes);
+        }
+        serde_json::from_slice(&bytes).map_err(|e| {
+            AutomationError::InvalidArgument(format!(
+                "{} is neither a snapshot nor a JSON tree: {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
@@ -0,0 +1,192 @@
+//! Selectors relative to another element's position, such as "the edit box to
+//! the right of the 'Name' label"
+//!
+//! A spatial selector names an anchor and narrows the matches of the selector
+//! before it, so it is always the last step of a chain:
+//!
+//! ```no_run
+//! # use terminator::{Desktop, Selector};
+//! # async fn run(desktop: &Desktop) -> Result<(), terminator::AutomationError> {
+//! let name_field = desktop
+//!     .locator("role:edit")
+//!     .locator(Selector::RightOf(Box::new("name:Name".into())))
+//!     .first(None)
+//!     .await?;
+//! // Same as desktop.locator("role:edit").locator("rightof:name:Name")
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Positions come from element bounds. `RightOf` and `LeftOf` only match
+//! elements that share part of the anchor's vertical span (the same row),
+//! `Above` and `Below` ones that share part of its horizontal span (the same
+//! column). Matches are ordered closest first.
+//!
+//! The anchor is searched where the candidates are: in
+//! `window:Form >> role:edit >> rightof:name:Name` it is the "Name" label of
+//! that window, even when another window has one too.
+
+use std::time::Duration;
+use tracing::debug;
+
+use crate::geometry::Rect;
+use crate::platforms::AccessibilityEngine;
+use crate::{AutomationError, Selector, UIElement};
+
+/// Default `max_distance` of `near:` selectors, in pixels
+pub const DEFAULT_NEAR_DISTANCE: u32 = 50;
+
+/// How a match must be placed relative to the anchor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Relation {
+    RightOf,
+    LeftOf,
+    Above,
+    Below,
+    /// Within this many pixels of the anchor, in any direction
+    Near(f64),
+}
+
+impl Relation {
+    /// The relation and anchor of a spatial selector
+    pub fn of(selector: &Selector) -> Option<(Relation, &Selector)> {
+        match selector {
+            Selector::RightOf(anchor) => Some((Relation::RightOf, anchor)),
+            Selector::LeftOf(anchor) => Some((Relation::LeftOf, anchor)),
+            Selector::Above(anchor) => Some((Relation::Above, anchor)),
+            Selector::Below(anchor) => Some((Relation::Below, anchor)),
+            Selector::Near {
+                anchor,
+                max_distance,
+            } => Some((Relation::Near(*max_distance as f64), anchor)),
+            _ => None,
+        }
+    }
+
+    /// Distance between `candidate` and `anchor` if the candidate is placed
+    /// as required, `None` otherwise. Elements that contain the anchor (its
+    /// window, a surrounding group) never match
+    pub fn distance(&self, candidate: &Rect, anchor: &Rect) -> Option<f64> {
+        if candidate.is_empty() || contains(candidate, anchor) {
+            return None;
+        }
+        let same_row = candidate.top() < anchor.bottom() && candidate.bottom() > anchor.top();
+        let same_column = candidate.left() < anchor.right() && candidate.right() > anchor.left();
+        match self {
+            Relation::RightOf if same_row && candidate.left() >= anchor.right() => {
+                Some(candidate.left() - anchor.right())
+            }
+            Relation::LeftOf if same_row && candidate.right() <= anchor.left() => {
+                Some(anchor.left() - candidate.right())
+            }
+            Relation::Above if same_column && candidate.bottom() <= anchor.top() => {
+                Some(anchor.top() - candidate.bottom())
+            }
+            Relation::Below if same_column && candidate.top() >= anchor.bottom() => {
+                Some(candidate.top() - anchor.bottom())
+            }
+            Relation::Near(max_distance) => {
+                let dx = (anchor.left() - candidate.right())
+                    .max(candidate.left() - anchor.right())
+                    .max(0.0);
+                let dy = (anchor.top() - candidate.bottom())
+                    .max(candidate.top() - anchor.bottom())
+                    .max(0.0);
+                let distance = dx.hypot(dy);
+                (distance <= *max_distance).then_some(distance)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn contains(outer: &Rect, inner: &Rect) -> bool {
+    outer.left() <= inner.left()
+        && outer.top() <= inner.top()
+        && outer.right() >= inner.right()
+        && outer.bottom() >= inner.bottom()
+}
+
+/// Split a chain ending in spatial steps into the selector for the candidates
+/// and the trailing spatial steps. `None` if the selector does not end in a
+/// spatial step or has nothing before it
+pub(crate) fn split_trailing(selector: &Selector) -> Option<(Selector, Vec<Selector>)> {
+    let Selector::Chain(steps) = selector else {
+        return None;
+    };
+    let split = steps
+        .iter()
+        .rposition(|step| Relation::of(step).is_none())?
+        + 1;
+    if split == steps.len() {
+        return None;
+    }
+    let target = match &steps[..split] {
+        [single] => single.clone(),
+        prefix => Selector::Chain(prefix.to_vec()),
+    };
+    Some((target, steps[split..].to_vec()))
+}
+
+/// The selector for an anchor of the spatial steps after `target`: searched
+/// where the candidates are, i.e. after every step of `target` but the
+/// last, so a label in another window with the same name is not picked
+pub(crate) fn anchor_selector(target: &Selector, anchor: &Selector) -> Selector {
+    match target {
+        Selector::Chain(steps) if steps.len() > 1 => {
+            let mut steps = steps[..steps.len() - 1].to_vec();
+            steps.push(anchor.clone());
+            Selector::Chain(steps)
+        }
+        _ => anchor.clone(),
+    }
+}
+
+/// Keep the `candidates` of `target` placed as `spatial` requires relative
+/// to their anchors, closest first. Anchors are searched within `root`, in
+/// the context of `target` (see [`anchor_selector`])
+pub(crate) fn filter(
+    engine: &dyn AccessibilityEngine,
+    candidates: Vec<UIElement>,
+    target: &Selector,
+    spatial: &[Selector],
+    root: Option<&UIElement>,
+    timeout: Duration,
+) -> Result<Vec<UIElement>, AutomationError> {
+    let mut constraints = Vec::with_capacity(spatial.len());
+    for step in spatial {
+        let Some((relation, anchor)) = Relation::of(step) else {
+            continue;
+        };
+        let anchor = engine.find_element(&anchor_selector(target, anchor), root, Some(timeout))?;
+        constraints.push((relation, anchor.rect()?, anchor));
+    }
+
+    let mut matches: Vec<(f64, UIElement)> = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let rect = candidate.rect().ok()?;
+            let mut total = 0.0;
+            for (relation, anchor_rect, anchor) in &constraints {
+                if candidate == *anchor {
+                    return None;
+                }
+                total += relation.distance(&rect, anchor_rect)?;
+            }
+            Some((total, candidate))
+        })
+        .collect();
+    // Stable, so equally distant matches keep tree order
+    matches.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    debug!(matches = matches.len(), "Applied spatial selectors");
+    Ok(matches.into_iter().map(|(_, element)| element).collect())
+}
+
+/// Error for a spatial selector that reached a platform engine directly, i.e.
+/// without candidates to narrow
+pub(crate) fn standalone_error(selector: &Selector) -> AutomationError {
+    AutomationError::InvalidArgument(format!(
+        "Spatial selector {:?} must be the last step after a selector for the elements to pick from, e.g. role:edit then rightof:name:Name",
+        selector
+    ))
+}
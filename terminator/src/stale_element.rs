@@ -0,0 +1,173 @@
+//! Detecting and refreshing element handles that outlived their element
+//!
+//! A [`UIElement`] refers to one instance of a control. When the application
+//! recreates the control, as virtualized lists do with rows scrolled out of
+//! view and back, or as dialogs do when reopened, the handle keeps pointing at
+//! the old instance and every call on it fails. [`UIElement::is_stale`] tells
+//! such handles apart.
+//!
+//! An [`ElementDescriptor`], captured while the element exists, is a
+//! [path](crate::selector_path) from the desktop root that finds the
+//! recreated control again. [`RefreshableElement`] keeps both and swaps in
+//! the recreated control when its handle goes stale, so caches of elements
+//! heal themselves:
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop, RefreshableElement};
+//! # async fn run(desktop: &Desktop) -> Result<(), AutomationError> {
+//! let row = desktop.locator("role:ListItem").first(None).await?;
+//! let row = RefreshableElement::new(row);
+//! // ... the list scrolls and recreates its rows ...
+//! row.get(desktop)?.click()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Handles that cannot be refreshed fail with
+//! [`AutomationError::StaleElement`]. On Windows, so do calls on a
+//! `UIElement` whose control is gone, which tells caches to refresh it.
+//! Platforms that cannot tell a dead element apart report such handles as
+//! not stale.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::selector_path::{parse_path, resolve_path, Axis, PathStep};
+use crate::{AutomationError, Desktop, UIElement};
+
+/// How to find an element again once its handle is stale
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ElementDescriptor {
+    /// Path from the desktop root, e.g.
+    /// `/Window[name='Inbox']/List/ListItem[name='Re: invoice'][2]`. Empty
+    /// for the root itself
+    pub path: String,
+    pub role: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl ElementDescriptor {
+    /// Describe `element`, which must still exist. Each step names the
+    /// element's role, its name if it has one, and its position among the
+    /// siblings sharing both when there are several
+    pub fn capture(element: &UIElement) -> Result<Self, AutomationError> {
+        if element.is_stale() {
+            return Err(AutomationError::StaleElement(format!(
+                "Cannot describe {}, it no longer exists",
+                element.role()
+            )));
+        }
+        // Root first; the root itself is where paths start
+        let mut chain = element.ancestors()?;
+        chain.reverse();
+        chain.push(element.clone());
+
+        let mut path = String::new();
+        for pair in chain.windows(2) {
+            path.push_str(&step(&pair[0], &pair[1])?.to_string());
+        }
+        Ok(Self {
+            path,
+            role: element.role(),
+            name: element.name().filter(|name| !name.is_empty()),
+        })
+    }
+
+    /// The first element at the path below `root`, normally
+    /// [`Desktop::root`]
+    pub fn resolve(&self, root: &UIElement) -> Result<UIElement, AutomationError> {
+        if self.path.is_empty() {
+            return Ok(root.clone());
+        }
+        let steps = parse_path(&self.path).map_err(AutomationError::InvalidArgument)?;
+        resolve_path(root, &steps, None, None)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| AutomationError::StaleElement(format!("{} no longer exists at {}", self, self.path)))
+    }
+}
+
+impl fmt::Display for ElementDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{} '{}'", self.role, name),
+            None => f.write_str(&self.role),
+        }
+    }
+}
+
+// The step from `parent` to its child `element`
+fn step(parent: &UIElement, element: &UIElement) -> Result<PathStep, AutomationError> {
+    let mut step = PathStep {
+        axis: Axis::Child,
+        role: Some(element.role()),
+        attributes: element
+            .name()
+            .filter(|name| !name.is_empty())
+            .map(|name| vec![("name".to_string(), name)])
+            .unwrap_or_default(),
+        index: None,
+    };
+    let alike: Vec<UIElement> = parent
+        .children()?
+        .into_iter()
+        .filter(|sibling| step.matches(sibling))
+        .collect();
+    if alike.len() > 1 {
+        // Providers that hide the element from its parent's children leave
+        // the first match
+        step.index = alike.iter().position(|sibling| sibling == element).map(|i| i + 1);
+    }
+    Ok(step)
+}
+
+/// An element that is found again through its [`ElementDescriptor`] when
+/// its handle goes stale. Clones share the element
+#[derive(Debug, Clone)]
+pub struct RefreshableElement {
+    element: Arc<Mutex<UIElement>>,
+    descriptor: Option<ElementDescriptor>,
+}
+
+impl RefreshableElement {
+    /// Keep `element` and how to find it again. Elements that cannot be
+    /// described, e.g. because an ancestor cannot be read, are kept but
+    /// fail with [`AutomationError::StaleElement`] once stale
+    pub fn new(element: UIElement) -> Self {
+        let descriptor = match ElementDescriptor::capture(&element) {
+            Ok(descriptor) => Some(descriptor),
+            Err(e) => {
+                debug!("Element cannot be refreshed: {}", e);
+                None
+            }
+        };
+        Self {
+            element: Arc::new(Mutex::new(element)),
+            descriptor,
+        }
+    }
+
+    pub fn descriptor(&self) -> Option<&ElementDescriptor> {
+        self.descriptor.as_ref()
+    }
+
+    /// The element, found again below the desktop root if its handle is
+    /// stale
+    pub fn get(&self, desktop: &Desktop) -> Result<UIElement, AutomationError> {
+        let mut element = self.element.lock().unwrap_or_else(|p| p.into_inner());
+        if !element.is_stale() {
+            return Ok(element.clone());
+        }
+        let descriptor = self.descriptor.as_ref().ok_or_else(|| {
+            AutomationError::StaleElement("The element no longer exists and cannot be found again".to_string())
+        })?;
+        let fresh = descriptor.resolve(&desktop.root())?;
+        info!(element = %descriptor, "Refreshed stale element");
+        *element = fresh.clone();
+        Ok(fresh)
+    }
+}
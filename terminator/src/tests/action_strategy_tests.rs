@@ -0,0 +1,65 @@
+use crate::action_strategy::escalation_failed;
+use crate::{ActionStrategy, AutomationError, ClickResult, StrategyAttempt};
+
+#[test]
+fn test_strategies_parse_from_names_and_short_forms() {
+    for strategy in ActionStrategy::ESCALATION {
+        assert_eq!(strategy.name().parse::<ActionStrategy>().unwrap(), strategy);
+    }
+    assert_eq!("Pattern".parse::<ActionStrategy>().unwrap(), ActionStrategy::UiaPattern);
+    assert_eq!("send-input".parse::<ActionStrategy>().unwrap(), ActionStrategy::SendInputCursor);
+    assert_eq!("message".parse::<ActionStrategy>().unwrap(), ActionStrategy::PostMessage);
+    assert!(matches!(
+        "keyboard".parse::<ActionStrategy>(),
+        Err(AutomationError::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn test_escalation_starts_without_moving_the_cursor() {
+    assert_eq!(ActionStrategy::ESCALATION[0], ActionStrategy::UiaPattern);
+}
+
+#[test]
+fn test_escalation_posts_messages_last() {
+    // Posting succeeds once queued, so nothing after it would ever run
+    assert_eq!(ActionStrategy::ESCALATION.last(), Some(&ActionStrategy::PostMessage));
+}
+
+#[test]
+fn test_failed_escalation_lists_every_attempt() {
+    let attempts = vec![
+        StrategyAttempt {
+            strategy: ActionStrategy::UiaPattern,
+            error: "no invoke pattern".to_string(),
+            duration_ms: 3,
+        },
+        StrategyAttempt {
+            strategy: ActionStrategy::PostMessage,
+            error: "no window".to_string(),
+            duration_ms: 1,
+        },
+    ];
+    let message = escalation_failed(&attempts).to_string();
+    assert!(message.contains("uia_pattern: no invoke pattern"));
+    assert!(message.contains("post_message: no window"));
+}
+
+#[test]
+fn test_click_result_reports_strategy_only_when_known() {
+    let result = ClickResult {
+        method: "uia_pattern".to_string(),
+        coordinates: None,
+        details: String::new(),
+        strategy: Some(ActionStrategy::UiaPattern),
+        attempts: Vec::new(),
+    };
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["strategy"], "uia_pattern");
+    assert!(json.get("attempts").is_none());
+
+    // Results serialized before strategies existed still load
+    let old: ClickResult =
+        serde_json::from_str(r#"{"method": "Single Click", "coordinates": null, "details": ""}"#).unwrap();
+    assert_eq!(old.strategy, None);
+}
@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use crate::app_profile::{self, AppProfile};
+use crate::ActionStrategy;
+
+#[test]
+fn test_matches_ignores_case_path_and_extension() {
+    let profile = AppProfile::new("office", &["WinWord"]);
+    assert_eq!(profile.processes, ["winword"]);
+    assert!(profile.matches("WINWORD.EXE"));
+    assert!(profile.matches(r"C:\Program Files\Microsoft Office\root\Office16\WINWORD.EXE"));
+    assert!(profile.matches("winword"));
+    assert!(!profile.matches("winword2"));
+}
+
+#[test]
+fn test_builtin_profiles() {
+    let chrome = app_profile::find("chrome.exe", true).unwrap();
+    assert_eq!(chrome.name, "chromium");
+    assert_eq!(chrome.search_root.as_deref(), Some("role:Document"));
+
+    let excel = app_profile::find("EXCEL.EXE", true).unwrap();
+    assert_eq!(excel.name, "office");
+    assert!(excel.timeout().unwrap() > Duration::from_secs(30));
+
+    let java = app_profile::find("javaw.exe", true).unwrap();
+    assert_eq!(java.click_strategies, [ActionStrategy::SendInputCursor]);
+
+    assert!(app_profile::find("notepad.exe", true).is_none());
+}
+
+#[test]
+fn test_builtin_profiles_are_off_by_default() {
+    assert!(!app_profile::builtin_enabled());
+    assert!(AppProfile::for_process("chrome.exe").is_none());
+    assert!(app_profile::find("chrome.exe", false).is_none());
+}
+
+#[test]
+fn test_registered_profiles_take_precedence() {
+    let profile = AppProfile::new("test-firefox", &["firefox"]).with_timeout(Duration::from_millis(1234));
+    app_profile::register(profile.clone());
+    assert_eq!(AppProfile::for_process("firefox.exe"), Some(profile));
+
+    // Registering again under the same name replaces it
+    app_profile::register(AppProfile::new("test-firefox", &["firefox"]).with_search_depth(7));
+    let registered: Vec<_> = app_profile::registered()
+        .into_iter()
+        .filter(|profile| profile.name == "test-firefox")
+        .collect();
+    assert_eq!(registered.len(), 1);
+    assert_eq!(registered[0].search_depth, Some(7));
+
+    assert!(app_profile::unregister("test-firefox"));
+    assert!(!app_profile::unregister("test-firefox"));
+    assert_eq!(app_profile::find("firefox", true).unwrap().name, "firefox");
+}
+
+#[test]
+fn test_profile_round_trips_through_json() {
+    let json = r#"{"name": "sap", "processes": ["saplogon"], "timeout_ms": 20000, "click_strategies": ["send_input_cursor"]}"#;
+    let profile: AppProfile = serde_json::from_str(json).unwrap();
+    assert_eq!(profile.timeout(), Some(Duration::from_secs(20)));
+    assert_eq!(profile.search_root, None);
+    assert_eq!(profile.click_strategies, [ActionStrategy::SendInputCursor]);
+
+    let back: AppProfile = serde_json::from_str(&serde_json::to_string(&profile).unwrap()).unwrap();
+    assert_eq!(back, profile);
+}
@@ -0,0 +1,25 @@
+use crate::AppTarget;
+
+#[test]
+fn test_digits_are_a_pid() {
+    assert_eq!(AppTarget::from("1234"), AppTarget::Pid(1234));
+    assert_eq!(AppTarget::from(" 42 "), AppTarget::Pid(42));
+    assert_eq!(AppTarget::from(7u32), AppTarget::Pid(7));
+}
+
+#[test]
+fn test_other_strings_are_a_name() {
+    assert_eq!(AppTarget::from("notepad"), AppTarget::Name("notepad".to_string()));
+    assert_eq!(AppTarget::from("app2"), AppTarget::Name("app2".to_string()));
+    // Too large for a pid
+    assert_eq!(
+        AppTarget::from("99999999999"),
+        AppTarget::Name("99999999999".to_string())
+    );
+}
+
+#[test]
+fn test_display_names_the_target() {
+    assert_eq!(AppTarget::from("calc").to_string(), "'calc'");
+    assert_eq!(AppTarget::Pid(12).to_string(), "pid 12");
+}
@@ -0,0 +1,73 @@
+use crate::audit::{contrast_hint, contrast_ratio, AuditFinding, AuditReport, AuditRule, Severity};
+
+fn pixels(colors: &[([u8; 3], usize)]) -> Vec<u8> {
+    colors
+        .iter()
+        .flat_map(|(color, count)| std::iter::repeat([color[0], color[1], color[2], 255]).take(*count))
+        .flatten()
+        .collect()
+}
+
+#[test]
+fn test_contrast_ratio() {
+    assert!((contrast_ratio([0, 0, 0], [255, 255, 255]) - 21.0).abs() < 0.01);
+    assert!((contrast_ratio([255, 255, 255], [255, 255, 255]) - 1.0).abs() < 0.01);
+    // Symmetric
+    assert_eq!(contrast_ratio([118, 118, 118], [255, 255, 255]), contrast_ratio([255, 255, 255], [118, 118, 118]));
+    // #767676 on white is the classic barely-passing grey
+    assert!((contrast_ratio([118, 118, 118], [255, 255, 255]) - 4.54).abs() < 0.01);
+}
+
+#[test]
+fn test_contrast_hint_picks_text_over_anti_aliasing() {
+    // White background, dark text, a few mid-grey edge pixels
+    let hint = contrast_hint(&pixels(&[([255, 255, 255], 900), ([20, 20, 20], 90), ([128, 128, 128], 10)])).unwrap();
+    assert_eq!(hint.background, [248, 248, 248]);
+    assert_eq!(hint.foreground, [24, 24, 24]);
+    assert!(hint.ratio > 15.0);
+
+    // Light grey text on white
+    let hint = contrast_hint(&pixels(&[([250, 250, 250], 900), ([190, 190, 190], 100)])).unwrap();
+    assert!(hint.ratio < 2.0);
+
+    assert!(contrast_hint(&pixels(&[([40, 40, 40], 500)])).is_none());
+    assert!(contrast_hint(&[]).is_none());
+}
+
+#[test]
+fn test_report_output() {
+    let finding = |rule, severity, name: Option<&str>| AuditFinding {
+        rule,
+        severity,
+        message: "Problem <here>".to_string(),
+        role: "Button".to_string(),
+        name: name.map(str::to_string),
+        automation_id: None,
+        location: "Window 'A & B' > Button".to_string(),
+        bounds: None,
+        contrast_ratio: None,
+    };
+    let report = AuditReport {
+        root: "Window 'A & B'".to_string(),
+        elements_checked: 12,
+        findings: vec![
+            finding(AuditRule::MissingName, Severity::Error, None),
+            finding(AuditRule::LowContrast, Severity::Warning, Some("OK")),
+        ],
+    };
+
+    assert!(report.has_errors());
+    assert_eq!(report.by_rule(AuditRule::LowContrast).count(), 1);
+    assert_eq!(report.by_rule(AuditRule::NotFocusable).count(), 0);
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+    assert_eq!(json["findings"][0]["rule"], "missing_name");
+    assert_eq!(json["findings"][0]["severity"], "error");
+    assert!(json["findings"][0].get("name").is_none());
+
+    let html = report.to_html();
+    assert!(html.contains("Accessibility audit: Window 'A &amp; B'"));
+    assert!(html.contains("Problem &lt;here&gt;"));
+    assert!(html.contains("<li>missing_name: 1</li>"));
+    assert!(!html.contains("<here>"));
+}
@@ -0,0 +1,42 @@
+use crate::batch::{BatchOp, BatchResult, BatchStatus};
+use crate::ErrorCode;
+
+#[test]
+fn test_batch_op_json_uses_op_tag() {
+    let op = BatchOp::TypeText {
+        selector: "name:First name".to_string(),
+        text: "Ada".to_string(),
+    };
+    let json = serde_json::to_value(&op).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"op": "type_text", "selector": "name:First name", "text": "Ada"})
+    );
+
+    let parsed: Vec<BatchOp> = serde_json::from_str(
+        r#"[{"op": "click", "selector": "name:Submit"}, {"op": "get_text", "selector": "name:Status"}]"#,
+    )
+    .unwrap();
+    assert_eq!(parsed[0], BatchOp::Click { selector: "name:Submit".to_string() });
+    assert_eq!(parsed[1].selector(), "name:Status");
+    assert!(serde_json::from_str::<BatchOp>(r#"{"op": "drag", "selector": "x"}"#).is_err());
+}
+
+#[test]
+fn test_batch_result_json_omits_empty_fields() {
+    let result = BatchResult {
+        index: 2,
+        op: BatchOp::Find { selector: "name:Missing".to_string() },
+        status: BatchStatus::Failed,
+        text: None,
+        error: Some("Element not found: name:Missing".to_string()),
+        error_code: Some(ErrorCode::ElementNotFound),
+        element: None,
+    };
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["status"], "failed");
+    assert_eq!(json["error_code"], "ELEMENT_NOT_FOUND");
+    assert!(json.get("text").is_none());
+    assert!(json.get("element").is_none());
+    assert!(!result.is_ok());
+}
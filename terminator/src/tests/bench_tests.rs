@@ -0,0 +1,107 @@
+use crate::bench::{BenchReport, Scenario, ScenarioResult, Stats, Threshold, REPORT_VERSION};
+use crate::EnvironmentInfo;
+
+fn result(app: &str, samples_ms: &[f64], errors: &[&str]) -> ScenarioResult {
+    let scenario = Scenario::TreeBuild { app: app.to_string() };
+    ScenarioResult {
+        name: scenario.name(),
+        scenario,
+        samples_ms: samples_ms.to_vec(),
+        stats: Stats::from_samples(samples_ms),
+        elements: Some(42),
+        errors: errors.iter().map(|e| e.to_string()).collect(),
+    }
+}
+
+fn report(results: Vec<ScenarioResult>) -> BenchReport {
+    BenchReport {
+        version: REPORT_VERSION,
+        timestamp_ms: 1_700_000_000_000,
+        environment: EnvironmentInfo::unknown(),
+        warmup: 2,
+        iterations: 10,
+        results,
+    }
+}
+
+#[test]
+fn test_stats_from_samples() {
+    assert_eq!(Stats::from_samples(&[]), None);
+
+    let samples: Vec<f64> = (1..=20).rev().map(f64::from).collect();
+    let stats = Stats::from_samples(&samples).unwrap();
+    assert_eq!(stats.min_ms, 1.0);
+    assert_eq!(stats.max_ms, 20.0);
+    assert_eq!(stats.mean_ms, 10.5);
+    assert_eq!(stats.median_ms, 10.5);
+    assert_eq!(stats.p95_ms, 19.0);
+
+    let stats = Stats::from_samples(&[7.0, 3.0, 5.0]).unwrap();
+    assert_eq!(stats.median_ms, 5.0);
+    assert_eq!(stats.p95_ms, 7.0);
+}
+
+#[test]
+fn test_scenario_names() {
+    let names: Vec<String> = Scenario::standard().iter().map(Scenario::name).collect();
+    assert_eq!(names.len(), 3);
+    assert!(names[0].starts_with("tree_build:"));
+    assert!(names[1].starts_with("selector_resolution:"));
+    assert!(names[2].starts_with("click_latency:"));
+}
+
+#[test]
+fn test_report_round_trips_through_json() {
+    let report = report(vec![result("notepad", &[10.0, 12.0], &[])]);
+    let json = report.to_json().unwrap();
+    assert!(json.contains("\"kind\": \"tree_build\""));
+    assert_eq!(BenchReport::from_json(&json).unwrap(), report);
+    assert!(BenchReport::from_json("{}").is_err());
+}
+
+#[test]
+fn test_check_reports_exceeded_thresholds_and_failures() {
+    let report = report(vec![
+        result("notepad", &[10.0, 20.0, 30.0], &[]),
+        result("calc", &[5.0], &["Element not found"]),
+    ]);
+
+    let regressions = report.check(&[
+        Threshold::median("tree_build:notepad", 25.0),
+        Threshold::p95("tree_build:notepad", 25.0),
+        Threshold::median("tree_build:calc", 100.0),
+        Threshold::median("tree_build:gedit", 100.0),
+    ]);
+    let metrics: Vec<(&str, &str)> = regressions
+        .iter()
+        .map(|r| (r.scenario.as_str(), r.metric.as_str()))
+        .collect();
+    assert_eq!(
+        metrics,
+        [
+            ("tree_build:notepad", "p95_ms"),
+            ("tree_build:calc", "errors"),
+            ("tree_build:gedit", "missing"),
+        ]
+    );
+    assert_eq!(
+        regressions[0].to_string(),
+        "tree_build:notepad: p95_ms 30.0 ms over the limit of 25.0 ms"
+    );
+}
+
+#[test]
+fn test_compare_against_baseline() {
+    let baseline = report(vec![result("notepad", &[100.0], &[]), result("calc", &[50.0], &[])]);
+    let current = report(vec![
+        result("notepad", &[120.0], &[]),
+        result("calc", &[70.0], &[]),
+        result("gedit", &[1_000.0], &[]),
+    ]);
+
+    let regressions = current.compare(&baseline, 0.25);
+    assert_eq!(regressions.len(), 1, "{:?}", regressions);
+    assert_eq!(regressions[0].scenario, "tree_build:calc");
+    assert_eq!(regressions[0].limit, 62.5);
+    assert!(current.compare(&current, 0.0).is_empty());
+}
@@ -0,0 +1,36 @@
+use crate::console::input_units;
+use crate::ConsoleScreen;
+
+fn screen(lines: &[&str]) -> ConsoleScreen {
+    ConsoleScreen {
+        width: 80,
+        height: lines.len() as u16,
+        cursor: (0, 0),
+        lines: lines.iter().map(|l| l.to_string()).collect(),
+    }
+}
+
+#[test]
+fn test_text_drops_trailing_blank_lines() {
+    let output = screen(&["Microsoft Windows", "", "C:\\>dir", "", ""]);
+    assert_eq!(output.text(), "Microsoft Windows\n\nC:\\>dir");
+    assert_eq!(screen(&["", ""]).text(), "");
+    assert_eq!(screen(&[]).text(), "");
+}
+
+#[test]
+fn test_contains_and_last_line() {
+    let output = screen(&["Volume in drive C", "  3 Dir(s)  1,024 bytes free", "C:\\>", ""]);
+    assert!(output.contains("bytes free"));
+    assert!(!output.contains("bytes free\nC:"));
+    assert_eq!(output.last_line(), Some("C:\\>"));
+    assert_eq!(screen(&["", ""]).last_line(), None);
+}
+
+#[test]
+fn test_input_sends_newlines_as_carriage_returns() {
+    let expected: Vec<u16> = "dir\rcls\r".encode_utf16().collect();
+    assert_eq!(input_units("dir\r\ncls\n"), expected);
+    assert_eq!(input_units("dir\rcls\r"), expected);
+    assert_eq!(input_units("é😀"), vec![0xe9, 0xd83d, 0xde00]);
+}
@@ -0,0 +1,21 @@
+use crate::RangeValue;
+
+fn range(value: f64, minimum: f64, maximum: f64) -> RangeValue {
+    RangeValue {
+        value,
+        minimum,
+        maximum,
+        small_change: None,
+        large_change: None,
+        read_only: false,
+    }
+}
+
+#[test]
+fn test_range_value_fraction() {
+    assert_eq!(range(70.0, 0.0, 100.0).fraction(), 0.7);
+    assert_eq!(range(0.0, -10.0, 10.0).fraction(), 0.5);
+    // Out-of-range values and empty ranges stay within 0..=1
+    assert_eq!(range(150.0, 0.0, 100.0).fraction(), 1.0);
+    assert_eq!(range(5.0, 5.0, 5.0).fraction(), 0.0);
+}
@@ -0,0 +1,34 @@
+use crate::dialogs::{mac_go_to_target, normalized_name, normalized_role};
+
+#[test]
+fn test_roles_compare_across_platforms() {
+    assert_eq!(normalized_role("SplitButton"), "splitbutton");
+    assert_eq!(normalized_role("push button"), "pushbutton");
+    assert_eq!(normalized_role("AXTextField"), "axtextfield");
+    assert_eq!(normalized_role("combo_box"), "combobox");
+    assert_eq!(normalized_role("menu-item"), "menuitem");
+}
+
+#[test]
+fn test_names_ignore_access_keys_and_punctuation() {
+    assert_eq!(normalized_name("&Open"), "open");
+    assert_eq!(normalized_name(" Save... "), "save");
+    assert_eq!(normalized_name("Select Folder…"), "select folder");
+    assert_eq!(normalized_name("File name:"), "file name");
+    assert_eq!(normalized_name("Cancel"), "cancel");
+}
+
+#[test]
+fn test_mac_panels_go_to_the_folder_when_they_take_a_name() {
+    assert_eq!(
+        mac_go_to_target("/Users/me/Reports/march.pdf", true),
+        ("/Users/me/Reports".to_string(), Some("march.pdf".to_string()))
+    );
+    // Open panels select the file by going to its full path
+    assert_eq!(
+        mac_go_to_target("/Users/me/Reports/march.pdf", false),
+        ("/Users/me/Reports/march.pdf".to_string(), None)
+    );
+    // A bare name has no folder to go to
+    assert_eq!(mac_go_to_target("march.pdf", true), ("march.pdf".to_string(), None));
+}
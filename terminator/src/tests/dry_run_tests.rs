@@ -0,0 +1,66 @@
+use crate::dry_run::{DryRun, Simulated, SimulatedAction, MAX_SIMULATED_ACTIONS};
+use crate::geometry::Rect;
+use crate::ClickResult;
+
+fn simulated_click(bounds: Option<Rect>) -> SimulatedAction {
+    SimulatedAction {
+        action: "click".to_string(),
+        details: None,
+        role: Some("Button".to_string()),
+        name: Some("Delete".to_string()),
+        application: None,
+        bounds,
+        enabled: Some(true),
+        timestamp_ms: 0,
+    }
+}
+
+#[test]
+fn test_simulated_click_result() {
+    let result = ClickResult::simulated(&simulated_click(Some(Rect::new(10.0, 20.0, 100.0, 40.0))));
+    assert_eq!(result.method, "DryRun");
+    assert_eq!(result.coordinates, Some((60.0, 40.0)));
+    assert!(result.details.contains("click not performed"), "{}", result.details);
+    assert_eq!(ClickResult::simulated(&simulated_click(None)).coordinates, None);
+}
+
+#[test]
+fn test_simulated_command_is_recorded() {
+    let dry_run = DryRun::default();
+    let output = dry_run.simulate_command("run_command", "rm -rf build".to_string());
+    assert_eq!(output.exit_status, Some(0));
+    assert!(output.stdout.is_empty());
+    let recorded = dry_run.simulated_actions();
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(recorded[0].action, "run_command");
+    assert_eq!(recorded[0].details.as_deref(), Some("rm -rf build"));
+    assert!(recorded[0].role.is_none());
+    assert!(recorded[0].timestamp_ms > 0);
+
+    // Each desktop has its own log
+    assert!(DryRun::default().simulated_actions().is_empty());
+}
+
+#[test]
+fn test_simulated_log_keeps_latest_actions() {
+    let dry_run = DryRun::default();
+    for i in 0..MAX_SIMULATED_ACTIONS + 5 {
+        dry_run.simulate_command("run_command", i.to_string());
+    }
+    let recorded = dry_run.simulated_actions();
+    assert_eq!(recorded.len(), MAX_SIMULATED_ACTIONS);
+    assert_eq!(recorded[0].details.as_deref(), Some("5"));
+
+    dry_run.set_enabled(true);
+    assert!(dry_run.is_enabled());
+    assert!(dry_run.simulated_actions().is_empty());
+}
+
+#[test]
+fn test_simulated_action_json() {
+    let json = serde_json::to_value(simulated_click(None)).unwrap();
+    assert_eq!(json["action"], "click");
+    assert_eq!(json["name"], "Delete");
+    assert!(json.get("bounds").is_none());
+    assert!(json.get("details").is_none());
+}
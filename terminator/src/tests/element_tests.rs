@@ -0,0 +1,42 @@
+use crate::element::{check_window_bounds, pattern_name};
+use crate::{ErrorCode, WindowState};
+
+#[test]
+fn test_window_state_names() {
+    let states = [
+        (WindowState::Normal, "normal"),
+        (WindowState::Minimized, "minimized"),
+        (WindowState::Maximized, "maximized"),
+    ];
+    for (state, name) in states {
+        assert_eq!(state.to_string(), name);
+        assert_eq!(serde_json::to_value(state).unwrap(), name);
+        assert_eq!(serde_json::from_value::<WindowState>(name.into()).unwrap(), state);
+    }
+}
+
+#[test]
+fn test_window_bounds_validation() {
+    assert!(check_window_bounds(0.0, 0.0, 800.0, 600.0).is_ok());
+    // Windows can sit left of or above the primary monitor
+    assert!(check_window_bounds(-1920.0, -200.0, 800.0, 600.0).is_ok());
+
+    for (x, y, width, height) in [
+        (0.0, 0.0, 0.0, 600.0),
+        (0.0, 0.0, 800.0, -1.0),
+        (0.0, 0.0, f64::NAN, 600.0),
+        (0.0, 0.0, 800.0, f64::INFINITY),
+        (f64::NAN, 0.0, 800.0, 600.0),
+    ] {
+        let error = check_window_bounds(x, y, width, height).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::InvalidArgument, "{x},{y} {width}x{height}");
+    }
+}
+
+#[test]
+fn test_pattern_names_match_the_hand_written_ones() {
+    assert_eq!(pattern_name("uiautomation::patterns::UIInvokePattern"), "InvokePattern");
+    assert_eq!(pattern_name("uiautomation::patterns::UIScrollItemPattern"), "ScrollItemPattern");
+    assert_eq!(pattern_name("my_crate::Wrapper<uiautomation::patterns::UITogglePattern>"), "Wrapper");
+    assert_eq!(pattern_name("CustomPattern"), "CustomPattern");
+}
@@ -0,0 +1,48 @@
+use crate::elevation::{AutomationCapabilities, IntegrityLevel, UI_ACCESS_MANIFEST};
+
+#[test]
+fn test_integrity_level_from_rid() {
+    assert_eq!(IntegrityLevel::from_rid(0x0000), IntegrityLevel::Untrusted);
+    assert_eq!(IntegrityLevel::from_rid(0x1000), IntegrityLevel::Low);
+    assert_eq!(IntegrityLevel::from_rid(0x2000), IntegrityLevel::Medium);
+    // Medium plus, e.g. of UIAccess processes
+    assert_eq!(IntegrityLevel::from_rid(0x2100), IntegrityLevel::Medium);
+    assert_eq!(IntegrityLevel::from_rid(0x3000), IntegrityLevel::High);
+    assert_eq!(IntegrityLevel::from_rid(0x4000), IntegrityLevel::System);
+    assert_eq!(IntegrityLevel::from_rid(0x5000), IntegrityLevel::System);
+}
+
+#[test]
+fn test_medium_process_cannot_drive_elevated_windows() {
+    let medium = AutomationCapabilities {
+        integrity_level: Some(IntegrityLevel::Medium),
+        elevated: false,
+        ui_access: false,
+    };
+    assert!(medium.can_drive(IntegrityLevel::Low));
+    assert!(medium.can_drive(IntegrityLevel::Medium));
+    assert!(!medium.can_drive(IntegrityLevel::High));
+    assert!(!medium.reaches_elevated_windows());
+
+    let ui_access = AutomationCapabilities { ui_access: true, ..medium.clone() };
+    assert!(ui_access.can_drive(IntegrityLevel::System));
+
+    let elevated = AutomationCapabilities {
+        integrity_level: Some(IntegrityLevel::High),
+        elevated: true,
+        ..medium
+    };
+    assert!(elevated.reaches_elevated_windows());
+    assert!(!elevated.can_drive(IntegrityLevel::System));
+}
+
+#[test]
+fn test_platforms_without_integrity_levels_are_unrestricted() {
+    assert!(AutomationCapabilities::unrestricted().can_drive(IntegrityLevel::System));
+}
+
+#[test]
+fn test_manifest_requests_ui_access_without_elevation() {
+    assert!(UI_ACCESS_MANIFEST.contains(r#"level="asInvoker""#));
+    assert!(UI_ACCESS_MANIFEST.contains(r#"uiAccess="true""#));
+}
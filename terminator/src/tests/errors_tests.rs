@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+use crate::errors::{ErrorCode, ErrorContext, SelectorSuggestion};
+use crate::{AutomationError, Permission};
+
+#[test]
+fn test_codes_are_stable() {
+    let cases = [
+        (AutomationError::ElementNotFound("x".into()), 1001, "ELEMENT_NOT_FOUND"),
+        (AutomationError::Timeout("x".into()), 1003, "OPERATION_TIMED_OUT"),
+        (AutomationError::InvalidArgument("x".into()), 1008, "INVALID_ARGUMENT"),
+        (AutomationError::UIUnavailable("x".into()), 1009, "UI_UNAVAILABLE"),
+        (AutomationError::ElevationRequired("x".into()), 1012, "ELEVATION_REQUIRED"),
+        (AutomationError::MissingPermission(Permission::Accessibility), 1013, "MISSING_PERMISSION"),
+        (AutomationError::StaleElement("x".into()), 1014, "STALE_ELEMENT"),
+        (AutomationError::Internal("x".into()), 1099, "INTERNAL_ERROR"),
+    ];
+    for (error, code, name) in cases {
+        assert_eq!(error.code().as_u16(), code);
+        assert_eq!(error.code().name(), name);
+        assert_eq!(serde_json::to_value(error.code()).unwrap(), name);
+    }
+}
+
+#[test]
+fn test_context_wraps_without_changing_the_kind() {
+    let error = AutomationError::Timeout("waiting for element".into())
+        .with_selector("name:Save")
+        .with_timeout(Duration::from_secs(2));
+
+    assert_eq!(error.code(), ErrorCode::Timeout);
+    assert!(matches!(error.kind(), AutomationError::Timeout(_)));
+    assert_eq!(error.to_string(), "Operation timed out: waiting for element");
+    assert_eq!(error.detail(), "waiting for element");
+    let context = error.context().unwrap();
+    assert_eq!(context.selector.as_deref(), Some("name:Save"));
+    assert_eq!(context.timeout_ms, Some(2000));
+    assert!(matches!(error.into_kind(), AutomationError::Timeout(_)));
+}
+
+#[test]
+fn test_inner_context_wins() {
+    let error = AutomationError::PlatformError("access denied".into())
+        .with_platform_code(-2147024891)
+        .with_selector("role:button")
+        .with_context(ErrorContext {
+            selector: Some("outer".into()),
+            root: Some("Window 'App'".into()),
+            ..Default::default()
+        });
+
+    let context = error.context().unwrap();
+    assert_eq!(context.selector.as_deref(), Some("role:button"));
+    assert_eq!(context.root.as_deref(), Some("Window 'App'"));
+    assert_eq!(context.platform_code, Some(-2147024891));
+}
+
+#[test]
+fn test_serialized_form() {
+    let error = AutomationError::ElementNotFound("No match".into())
+        .with_suggestions(vec![SelectorSuggestion {
+            role: "Button".into(),
+            name: Some("Save".into()),
+            automation_id: None,
+            selector: "name:Save".into(),
+            score: 0.9,
+        }])
+        .with_selector("name:Sav");
+
+    assert!(matches!(error.kind(), AutomationError::ElementNotFound(_)));
+    assert_eq!(error.suggestions()[0].selector, "name:Save");
+    assert_eq!(
+        error.to_string(),
+        "Element not found: No match. Did you mean: 'name:Save' (0.90)?"
+    );
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(json["code"], 1001);
+    assert_eq!(json["name"], "ELEMENT_NOT_FOUND");
+    assert_eq!(json["detail"], "No match. Did you mean: 'name:Save' (0.90)?");
+    assert_eq!(json["context"]["selector"], "name:Sav");
+    assert!(json["context"].get("suggestions").is_none());
+    assert_eq!(json["suggestions"][0]["selector"], "name:Save");
+
+    let plain = serde_json::to_value(AutomationError::Internal("boom".into())).unwrap();
+    assert!(plain["context"].is_null());
+    assert_eq!(plain["message"], "Internal error: boom");
+}
@@ -0,0 +1,66 @@
+use std::path::Path;
+use std::time::Duration;
+
+use crate::expect::{excerpt_line, failure_message};
+use crate::Expectation;
+
+#[test]
+fn test_expectation_descriptions() {
+    assert_eq!(Expectation::Visible.to_string(), "to be visible");
+    assert_eq!(
+        Expectation::Text("Saved".to_string()).to_string(),
+        "to have text containing 'Saved'"
+    );
+    assert_eq!(
+        Expectation::Attribute("enabled".to_string(), Some("true".to_string())).to_string(),
+        "to have attribute 'enabled' = 'true'"
+    );
+    assert_eq!(
+        Expectation::Attribute("description".to_string(), None).to_string(),
+        "to have attribute 'description'"
+    );
+}
+
+#[test]
+fn test_excerpt_line_indents_by_depth() {
+    assert_eq!(
+        excerpt_line(0, "Window", Some("Notepad")),
+        "  Window \"Notepad\""
+    );
+    assert_eq!(excerpt_line(2, "Pane", Some("")), "      Pane");
+    assert_eq!(excerpt_line(1, "Button", None), "    Button");
+}
+
+#[test]
+fn test_failure_message_has_excerpt_and_screenshot() {
+    let excerpt = format!(
+        "{}\n{}",
+        excerpt_line(0, "Text", Some("Status")),
+        excerpt_line(1, "Text", Some("Saving"))
+    );
+    let message = failure_message(
+        "to have text containing 'Saved'",
+        Duration::from_secs(5),
+        "'Saving'",
+        &excerpt,
+        Some(Path::new("/tmp/expect-1.png")),
+    );
+    assert_eq!(
+        message,
+        "expected element to have text containing 'Saved' within 5000ms, got 'Saving'\n\
+         Tree excerpt:\n  Text \"Status\"\n    Text \"Saving\"\n\
+         Screenshot: /tmp/expect-1.png"
+    );
+
+    let bare = failure_message(
+        "to be visible",
+        Duration::from_millis(200),
+        "false",
+        "",
+        None,
+    );
+    assert_eq!(
+        bare,
+        "expected element to be visible within 200ms, got false"
+    );
+}
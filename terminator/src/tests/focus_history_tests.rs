@@ -0,0 +1,46 @@
+use crate::focus_history::{FocusHistory, FocusRecord};
+
+fn record(timestamp_ms: u64, application: &str, name: &str) -> FocusRecord {
+    FocusRecord {
+        timestamp_ms,
+        application: Some(application.to_string()),
+        process_id: Some(application.len() as u32),
+        role: "Edit".to_string(),
+        name: Some(name.to_string()),
+        id: None,
+    }
+}
+
+#[test]
+fn test_focus_history_drops_oldest_when_full() {
+    let mut history = FocusHistory::new(2);
+    assert!(history.push(record(1, "notepad", "Text Editor")));
+    assert!(history.push(record(2, "chrome", "Address and search bar")));
+    assert!(history.push(record(3, "notepad", "Text Editor")));
+    assert_eq!(history.len(), 2);
+    let timestamps: Vec<_> = history.records().into_iter().map(|r| r.timestamp_ms).collect();
+    assert_eq!(timestamps, vec![2, 3]);
+}
+
+#[test]
+fn test_focus_history_skips_repeated_focus() {
+    let mut history = FocusHistory::new(10);
+    assert!(history.push(record(1, "notepad", "Text Editor")));
+    assert!(!history.push(record(5, "notepad", "Text Editor")));
+    assert!(history.push(record(6, "notepad", "Search")));
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.records()[0].timestamp_ms, 1);
+}
+
+#[test]
+fn test_focus_history_since() {
+    let mut history = FocusHistory::new(10);
+    history.push(record(100, "notepad", "Text Editor"));
+    history.push(record(200, "teams", "Chat"));
+    history.push(record(300, "notepad", "Text Editor"));
+    let stolen: Vec<_> = history.since(200).into_iter().map(|r| r.application.unwrap()).collect();
+    assert_eq!(stolen, vec!["teams", "notepad"]);
+    assert!(history.since(301).is_empty());
+    // A capacity of 0 still keeps the last change
+    assert_eq!(FocusHistory::new(0).capacity(), 1);
+}
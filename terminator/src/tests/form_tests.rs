@@ -0,0 +1,25 @@
+use crate::form::{parse_checked, FieldKind};
+
+#[test]
+fn test_field_kind_of_role() {
+    assert_eq!(FieldKind::of_role("Edit"), Some(FieldKind::Text));
+    assert_eq!(FieldKind::of_role("text field"), Some(FieldKind::Text));
+    assert_eq!(FieldKind::of_role("ComboBox"), Some(FieldKind::ComboBox));
+    assert_eq!(FieldKind::of_role("combo box"), Some(FieldKind::ComboBox));
+    assert_eq!(FieldKind::of_role("CheckBox"), Some(FieldKind::CheckBox));
+    assert_eq!(FieldKind::of_role("radio_button"), Some(FieldKind::RadioButton));
+    assert_eq!(FieldKind::of_role("Slider"), Some(FieldKind::Range));
+    assert_eq!(FieldKind::of_role("Button"), None);
+    assert_eq!(FieldKind::of_role("Text"), None);
+}
+
+#[test]
+fn test_parse_checked() {
+    for value in ["yes", "True", " on ", "1", "checked"] {
+        assert_eq!(parse_checked(value), Some(true), "{}", value);
+    }
+    for value in ["no", "FALSE", "off", "0", "unchecked", ""] {
+        assert_eq!(parse_checked(value), Some(false), "{}", value);
+    }
+    assert_eq!(parse_checked("maybe"), None);
+}
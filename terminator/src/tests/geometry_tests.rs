@@ -0,0 +1,62 @@
+use crate::geometry::{Anchor, Point, Rect};
+
+#[test]
+fn test_rect_anchor_points() {
+    let rect = Rect::new(100.0, 50.0, 200.0, 100.0);
+    assert_eq!(rect.center().x, 200.0);
+    assert_eq!(rect.center().y, 100.0);
+
+    let left_of_center = rect.point_at(Anchor::Center, (-10.0, 0.0));
+    assert_eq!((left_of_center.x, left_of_center.y), (190.0, 100.0));
+
+    let bottom_right = rect.point_at(Anchor::BottomRight, (0.0, 0.0));
+    assert_eq!((bottom_right.x, bottom_right.y), (300.0, 150.0));
+
+    let percent = rect.point_at(Anchor::Percent(25.0, 10.0), (0.0, 0.0));
+    assert_eq!((percent.x, percent.y), (150.0, 60.0));
+}
+
+#[test]
+fn test_rect_clamp_point() {
+    let rect = Rect::new(100.0, 50.0, 200.0, 100.0);
+    // Edge anchors are pulled inside the rectangle
+    assert_eq!(rect.clamp_point(Point::new(300.0, 150.0)), Point::new(299.0, 149.0));
+    assert_eq!(rect.clamp_point(Point::new(0.0, 0.0)), Point::new(100.0, 50.0));
+    // Points already inside are unchanged
+    assert_eq!(rect.clamp_point(Point::new(150.0, 75.0)), Point::new(150.0, 75.0));
+}
+
+#[test]
+fn test_rect_contains_and_intersects() {
+    let a = Rect::new(0.0, 0.0, 100.0, 100.0);
+    let b = Rect::new(50.0, 50.0, 100.0, 100.0);
+    let c = Rect::new(100.0, 0.0, 10.0, 10.0);
+
+    assert!(a.contains_point(0.0, 0.0));
+    assert!(!a.contains_point(100.0, 50.0));
+    assert!(a.contains_rect(&Rect::new(90.0, 0.0, 10.0, 100.0)));
+    assert!(!a.contains_rect(&b));
+    assert!(a.intersects(&b));
+    assert_eq!(a.intersection(&b), Some(Rect::new(50.0, 50.0, 50.0, 50.0)));
+    // Touching edges do not overlap
+    assert!(!a.intersects(&c));
+}
+
+#[test]
+fn test_rect_union() {
+    let a = Rect::new(0.0, 0.0, 100.0, 20.0);
+    let b = Rect::new(150.0, 10.0, 50.0, 30.0);
+    assert_eq!(a.union(&b), Rect::new(0.0, 0.0, 200.0, 40.0));
+    assert_eq!(a.union(&a), a);
+}
+
+#[test]
+fn test_anchor_from_str() {
+    assert_eq!("top-left".parse::<Anchor>(), Ok(Anchor::TopLeft));
+    assert_eq!("Bottom Right".parse::<Anchor>(), Ok(Anchor::BottomRight));
+    assert!("middle".parse::<Anchor>().is_err());
+    assert_eq!("center-right".parse::<Anchor>(), Ok(Anchor::Right));
+    assert_eq!("30%,50%".parse::<Anchor>(), Ok(Anchor::Percent(30.0, 50.0)));
+    assert_eq!("percent(10, 90)".parse::<Anchor>(), Ok(Anchor::Percent(10.0, 90.0)));
+    assert!("30%".parse::<Anchor>().is_err());
+}
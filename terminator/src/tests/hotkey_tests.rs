@@ -0,0 +1,34 @@
+use crate::hotkey::{Hotkey, HotkeyKey};
+
+#[test]
+fn test_parse_hotkey() {
+    let hotkey: Hotkey = "Ctrl+Shift+H".parse().unwrap();
+    assert!(hotkey.ctrl && hotkey.shift);
+    assert!(!hotkey.alt && !hotkey.meta);
+    assert_eq!(hotkey.key, HotkeyKey::Char('H'));
+
+    let hotkey: Hotkey = " cmd + alt + f12 ".parse().unwrap();
+    assert!(hotkey.meta && hotkey.alt);
+    assert_eq!(hotkey.key, HotkeyKey::F(12));
+
+    let hotkey: Hotkey = "Control+PgDn".parse().unwrap();
+    assert_eq!(hotkey.key, HotkeyKey::PageDown);
+}
+
+#[test]
+fn test_parse_hotkey_errors() {
+    assert!("".parse::<Hotkey>().is_err());
+    assert!("H".parse::<Hotkey>().is_err(), "a modifier is required");
+    assert!("Ctrl+".parse::<Hotkey>().is_err());
+    assert!("Ctrl+Ctrl+H".parse::<Hotkey>().is_err());
+    assert!("Hyper+H".parse::<Hotkey>().is_err());
+    assert!("Ctrl+F25".parse::<Hotkey>().is_err());
+    assert!("Ctrl+Banana".parse::<Hotkey>().is_err());
+}
+
+#[test]
+fn test_hotkey_display_roundtrip() {
+    let hotkey: Hotkey = "shift+win+ctrl+space".parse().unwrap();
+    assert_eq!(hotkey.to_string(), "Ctrl+Shift+Meta+Space");
+    assert_eq!(hotkey.to_string().parse::<Hotkey>().unwrap(), hotkey);
+}
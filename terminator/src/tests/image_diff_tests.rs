@@ -0,0 +1,83 @@
+use crate::geometry::Rect;
+use crate::{AutomationError, ScreenshotResult};
+
+fn solid(width: u32, height: u32, rgba: [u8; 4]) -> ScreenshotResult {
+    ScreenshotResult {
+        image_data: rgba.repeat((width * height) as usize),
+        width,
+        height,
+    }
+}
+
+fn paint(shot: &mut ScreenshotResult, area: (u32, u32, u32, u32), rgba: [u8; 4]) {
+    let (x, y, width, height) = area;
+    for row in y..y + height {
+        for column in x..x + width {
+            let offset = ((row * shot.width + column) * 4) as usize;
+            shot.image_data[offset..offset + 4].copy_from_slice(&rgba);
+        }
+    }
+}
+
+#[test]
+fn test_diff_of_identical_images() {
+    let shot = solid(64, 32, [200, 200, 200, 255]);
+    let diff = shot.diff(&shot.clone(), 0).unwrap();
+    assert!(diff.is_identical());
+    assert_eq!(diff.changed_ratio, 0.0);
+    assert!(diff.regions.is_empty());
+    assert_eq!(diff.perceptual_distance, 0);
+    assert_eq!((diff.heatmap.width, diff.heatmap.height), (64, 32));
+}
+
+#[test]
+fn test_diff_tolerance_absorbs_noise() {
+    let baseline = solid(32, 32, [100, 100, 100, 255]);
+    let mut noisy = baseline.clone();
+    paint(&mut noisy, (0, 0, 32, 16), [108, 95, 100, 255]);
+    assert_eq!(noisy.diff(&baseline, 0).unwrap().changed_pixels, 32 * 16);
+    assert!(noisy.diff(&baseline, 8).unwrap().is_identical());
+}
+
+#[test]
+fn test_diff_groups_changes_into_regions() {
+    let baseline = solid(200, 100, [255, 255, 255, 255]);
+    let mut current = baseline.clone();
+    // A button that changed color and, far from it, a moved cursor
+    paint(&mut current, (10, 10, 40, 20), [0, 120, 215, 255]);
+    paint(&mut current, (180, 80, 2, 10), [0, 0, 0, 255]);
+
+    let diff = current.diff(&baseline, 16).unwrap();
+    assert_eq!(diff.changed_pixels, 40 * 20 + 2 * 10);
+    assert_eq!(diff.regions, vec![Rect::new(10.0, 10.0, 40.0, 20.0), Rect::new(180.0, 80.0, 2.0, 10.0)]);
+
+    // Changed pixels are red to yellow in the heatmap, the rest gray
+    let pixel = |x: u32, y: u32| {
+        let offset = ((y * 200 + x) * 4) as usize;
+        diff.heatmap.image_data[offset..offset + 4].to_vec()
+    };
+    assert_eq!(pixel(181, 85), vec![255, 0, 0, 255]);
+    let unchanged = pixel(100, 50);
+    assert!(unchanged[0] == unchanged[1] && unchanged[1] == unchanged[2]);
+}
+
+#[test]
+fn test_diff_rejects_different_sizes() {
+    let err = solid(10, 10, [0, 0, 0, 255]).diff(&solid(10, 20, [0, 0, 0, 255]), 0).unwrap_err();
+    assert!(matches!(err, AutomationError::InvalidArgument(_)));
+}
+
+#[test]
+fn test_perceptual_hash_tells_layouts_apart() {
+    let mut left_dark = solid(64, 64, [255, 255, 255, 255]);
+    paint(&mut left_dark, (0, 0, 32, 64), [0, 0, 0, 255]);
+    let mut right_dark = solid(64, 64, [255, 255, 255, 255]);
+    paint(&mut right_dark, (32, 0, 32, 64), [0, 0, 0, 255]);
+    // Slightly brighter, same layout
+    let mut brighter = solid(64, 64, [255, 255, 255, 255]);
+    paint(&mut brighter, (0, 0, 32, 64), [20, 20, 20, 255]);
+
+    let hash = left_dark.perceptual_hash().unwrap();
+    assert_eq!((hash ^ brighter.perceptual_hash().unwrap()).count_ones(), 0);
+    assert!(left_dark.diff(&right_dark, 0).unwrap().perceptual_distance > 0);
+}
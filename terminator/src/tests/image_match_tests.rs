@@ -0,0 +1,59 @@
+use image::GrayImage;
+
+use crate::image_match::{match_template, suppress_overlaps};
+use crate::Rect;
+
+// Deterministic texture, so that every window looks different
+fn screen(width: u32, height: u32) -> GrayImage {
+    GrayImage::from_fn(width, height, |x, y| {
+        let v = (x * 7919 + y * 104729 + (x * y) % 251) % 256;
+        image::Luma([v as u8])
+    })
+}
+
+#[test]
+fn test_match_template_finds_exact_copy() {
+    let image = screen(200, 120);
+    let template = image::imageops::crop_imm(&image, 57, 33, 24, 18).to_image();
+    let matches = match_template(&image, &template, 0.95).unwrap();
+    assert!(!matches.is_empty());
+    let (bounds, confidence) = matches[0];
+    assert_eq!(bounds, Rect::new(57.0, 33.0, 24.0, 18.0));
+    assert!(confidence > 0.99);
+}
+
+#[test]
+fn test_match_template_ignores_brightness() {
+    let image = screen(120, 80);
+    let template = image::imageops::crop_imm(&image, 10, 20, 8, 8).to_image();
+    // Darker and with less contrast, as under a dimmed theme
+    let dimmed = GrayImage::from_fn(120, 80, |x, y| image::Luma([image.get_pixel(x, y)[0] / 2 + 10]));
+    let matches = match_template(&dimmed, &template, 0.95).unwrap();
+    assert_eq!(matches[0].0, Rect::new(10.0, 20.0, 8.0, 8.0));
+}
+
+#[test]
+fn test_match_template_rejects_flat_template() {
+    let image = screen(50, 50);
+    let flat = GrayImage::from_pixel(8, 8, image::Luma([128]));
+    assert!(match_template(&image, &flat, 0.9).is_err());
+    // Larger than the image: nothing to find
+    let large = screen(60, 60);
+    assert!(match_template(&image, &large, 0.9).unwrap().is_empty());
+}
+
+#[test]
+fn test_suppress_overlaps_keeps_best() {
+    let kept = suppress_overlaps(vec![
+        (Rect::new(0.0, 0.0, 10.0, 10.0), 0.91),
+        (Rect::new(1.0, 1.0, 10.0, 10.0), 0.97),
+        (Rect::new(50.0, 0.0, 10.0, 10.0), 0.93),
+    ]);
+    assert_eq!(
+        kept,
+        vec![
+            (Rect::new(1.0, 1.0, 10.0, 10.0), 0.97),
+            (Rect::new(50.0, 0.0, 10.0, 10.0), 0.93),
+        ]
+    );
+}
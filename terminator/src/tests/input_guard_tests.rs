@@ -0,0 +1,77 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::input_guard::{is_blocked, lift_current, Release, MAX_BLOCK};
+use crate::{AutomationError, InputGuard};
+
+// Counts how often the block is put in place and released
+fn counting_block(blocks: &Arc<AtomicUsize>, releases: &Arc<AtomicUsize>) -> Result<Release, AutomationError> {
+    blocks.fetch_add(1, Ordering::SeqCst);
+    let releases = releases.clone();
+    Ok(Box::new(move || {
+        releases.fetch_add(1, Ordering::SeqCst);
+    }))
+}
+
+// One test, as the block is process-wide
+#[test]
+fn test_guards_nest_and_release_with_the_last_one() {
+    let blocks = Arc::new(AtomicUsize::new(0));
+    let releases = Arc::new(AtomicUsize::new(0));
+    let acquire = || InputGuard::acquire_with(|| counting_block(&blocks, &releases), MAX_BLOCK).unwrap();
+
+    let outer = acquire();
+    let inner = acquire();
+    assert!(is_blocked());
+    assert_eq!(blocks.load(Ordering::SeqCst), 1);
+
+    drop(inner);
+    assert!(is_blocked());
+    assert_eq!(releases.load(Ordering::SeqCst), 0);
+    drop(outer);
+    assert!(!is_blocked());
+    assert_eq!(releases.load(Ordering::SeqCst), 1);
+
+    // A failed block leaves input unblocked and the next guard blocks again
+    let error = InputGuard::acquire_with(|| Err(AutomationError::PermissionDenied("no".to_string())), MAX_BLOCK);
+    assert!(error.is_err());
+    assert!(!is_blocked());
+
+    // Unwinding past a guard releases the block
+    let panicked = std::panic::catch_unwind(|| {
+        let _guard = acquire();
+        panic!("step failed");
+    });
+    assert!(panicked.is_err());
+    assert!(!is_blocked());
+    assert_eq!(blocks.load(Ordering::SeqCst), 2);
+    assert_eq!(releases.load(Ordering::SeqCst), 2);
+
+    // A block held for too long is lifted while its guard is alive, and
+    // guards joining it do not block again
+    let guard = InputGuard::acquire_with(|| counting_block(&blocks, &releases), Duration::from_millis(20)).unwrap();
+    std::thread::sleep(Duration::from_millis(500));
+    assert!(!is_blocked());
+    assert_eq!(releases.load(Ordering::SeqCst), 3);
+    let joined = acquire();
+    assert!(!is_blocked());
+    drop(joined);
+    drop(guard);
+    assert_eq!((blocks.load(Ordering::SeqCst), releases.load(Ordering::SeqCst)), (3, 3));
+
+    // So is one the user escaped from
+    let guard = acquire();
+    lift_current("escape hotkey pressed");
+    assert!(!is_blocked());
+    drop(guard);
+    assert_eq!((blocks.load(Ordering::SeqCst), releases.load(Ordering::SeqCst)), (4, 4));
+
+    // The failsafe of a released block leaves the next one alone
+    drop(InputGuard::acquire_with(|| counting_block(&blocks, &releases), Duration::from_millis(20)).unwrap());
+    let guard = acquire();
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(is_blocked());
+    drop(guard);
+    assert_eq!((blocks.load(Ordering::SeqCst), releases.load(Ordering::SeqCst)), (6, 6));
+}
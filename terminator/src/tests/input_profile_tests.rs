@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use crate::geometry::Point;
+use crate::input_profile::{cubic_bezier, current, InputProfile};
+
+#[test]
+fn test_presets() {
+    assert_eq!(InputProfile::default(), InputProfile::fast());
+    assert_eq!(InputProfile::named("Human"), Some(InputProfile::human()));
+    assert_eq!(InputProfile::named("robotic"), None);
+
+    let instant = InputProfile::instant();
+    assert_eq!(instant.next_key_delay(), Duration::ZERO);
+    assert_eq!(instant.next_click_dwell(), Duration::ZERO);
+    assert!(instant.mouse_path(Point::new(0.0, 0.0), Point::new(100.0, 100.0)).is_empty());
+}
+
+#[test]
+fn test_human_delays_stay_in_range() {
+    let human = InputProfile::human();
+    for _ in 0..100 {
+        let delay = human.next_key_delay();
+        assert!(delay >= human.key_delay && delay <= human.key_delay + human.key_delay_jitter);
+    }
+}
+
+#[test]
+fn test_mouse_path_ends_on_target() {
+    let human = InputProfile::human();
+    let (from, to) = (Point::new(10.0, 20.0), Point::new(410.0, 320.0));
+    let path = human.mouse_path(from, to);
+    assert_eq!(path.len(), 35);
+    let last = path.last().unwrap();
+    assert!((last.x - to.x).abs() < 1e-9 && (last.y - to.y).abs() < 1e-9);
+
+    let mid = cubic_bezier(from, from, to, to, 0.5);
+    assert!((mid.x - 210.0).abs() < 1e-9 && (mid.y - 170.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_scoped_profile_is_restored() {
+    let before = current();
+    let inside = InputProfile::instant().scoped(current);
+    assert_eq!(inside, InputProfile::instant());
+    assert_eq!(current(), before);
+}
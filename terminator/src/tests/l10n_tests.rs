@@ -0,0 +1,86 @@
+use crate::l10n::{self, Translations};
+use crate::{Selector, TreeIndex};
+
+use super::node;
+
+#[test]
+fn test_parse_l10n_selector() {
+    assert_eq!(Selector::from("l10n:cancel"), Selector::NameL10n("cancel".to_string()));
+    assert_eq!(Selector::from("L10N: ok"), Selector::NameL10n("ok".to_string()));
+    assert!(Selector::from("role:Button && l10n:ok").check_element_predicate().is_ok());
+}
+
+#[test]
+fn test_builtin_translations() {
+    let builtin = Translations::builtin();
+    let cancel = builtin.names("Cancel", None);
+    assert!(cancel.contains(&"Abbrechen".to_string()));
+    assert!(cancel.contains(&"Annuler".to_string()));
+    assert_eq!(builtin.names("ok", Some("es")), vec!["Aceptar", "OK"]);
+    assert!(builtin.names("unknown_key", None).is_empty());
+}
+
+#[test]
+fn test_locale_prefers_region_then_language_then_english() {
+    let builtin = Translations::builtin();
+    assert_eq!(builtin.names("save", Some("pt_PT")), vec!["Guardar", "Salvar", "Save"]);
+    assert_eq!(builtin.names("save", Some("pt-BR")), vec!["Salvar", "Save"]);
+    // A locale no table covers falls back to every language
+    assert_eq!(builtin.names("yes", Some("sv")).len(), builtin.names("yes", None).len());
+}
+
+#[test]
+fn test_user_translations_from_json() {
+    let translations = Translations::from_json(r#"{"Export": {"de_DE": ["Exportieren"], "en": ["Export"]}}"#).unwrap();
+    assert_eq!(translations.keys(), vec!["export"]);
+    assert_eq!(translations.names("export", Some("de-de")), vec!["Exportieren", "Export"]);
+    assert!(Translations::from_json(r#"{"export": "Export"}"#).is_err());
+}
+
+#[test]
+fn test_localized_selector() {
+    let mut translations = Translations::new();
+    translations
+        .add("l10n_tests_print", "en", ["Print"])
+        .add("l10n_tests_print", "fr", ["Imprimer"]);
+    l10n::add_translations(translations);
+
+    let selector = Selector::from("role:Button && l10n:l10n_tests_print").localized();
+    assert_eq!(
+        selector,
+        Selector::And(vec![
+            Selector::Role {
+                role: "Button".to_string(),
+                name: None
+            },
+            Selector::LocalizedName(vec!["Print".to_string(), "Imprimer".to_string()]),
+        ])
+    );
+    // Keys no table knows are plain names
+    assert_eq!(
+        Selector::from("l10n:Quarterly report").localized(),
+        Selector::LocalizedName(vec!["Quarterly report".to_string()])
+    );
+}
+
+#[test]
+fn test_translations_match_whole_names() {
+    // "Save" and "Don't Save" side by side, as in a close prompt
+    let index = TreeIndex::new(&node(
+        "Window",
+        "Notepad",
+        vec![
+            node("Button", "&Save", vec![]),
+            node("Button", "Don't Save", vec![]),
+            node("Button", "Cancel", vec![]),
+        ],
+    ));
+    assert_eq!(index.matches(&Selector::from("l10n:save")), Some(vec![1]));
+    assert_eq!(index.matches(&Selector::from("l10n:dont_save")), Some(vec![2]));
+    assert_eq!(index.matches(&Selector::from("role:Button && l10n:CANCEL")), Some(vec![3]));
+
+    let names = vec!["Save".to_string()];
+    assert!(l10n::is_one_of("&save", &names));
+    assert!(!l10n::is_one_of("Don't Save", &names));
+    assert!(!l10n::is_one_of("Save as", &names));
+}
@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use crate::RetryPolicy;
+
+#[test]
+fn test_fixed_retry_policy_keeps_delay() {
+    let policy = RetryPolicy::fixed(Duration::from_millis(100));
+    for attempt in 0..5 {
+        assert_eq!(policy.delay_for_attempt(attempt), Duration::from_millis(100));
+    }
+}
+
+#[test]
+fn test_exponential_retry_policy_is_capped() {
+    let policy = RetryPolicy::exponential(Duration::from_millis(50), Duration::from_millis(300));
+    assert_eq!(policy.delay_for_attempt(0), Duration::from_millis(50));
+    assert_eq!(policy.delay_for_attempt(1), Duration::from_millis(100));
+    assert_eq!(policy.delay_for_attempt(2), Duration::from_millis(200));
+    assert_eq!(policy.delay_for_attempt(3), Duration::from_millis(300));
+    assert_eq!(policy.delay_for_attempt(10), Duration::from_millis(300));
+}
+
+#[test]
+fn test_retry_policy_jitter_stays_in_range() {
+    let policy = RetryPolicy::fixed(Duration::from_millis(100)).with_jitter(0.5);
+    for attempt in 0..50 {
+        let delay = policy.delay_for_attempt(attempt);
+        assert!(delay >= Duration::from_millis(50) && delay <= Duration::from_millis(150));
+    }
+}
@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use crate::middleware::{ActionHooks, ActionKind, Middleware};
+
+struct Noop;
+
+impl Middleware for Noop {}
+
+#[test]
+fn test_register_and_unregister_middleware() {
+    let hooks = ActionHooks::new();
+    let first = hooks.add(Arc::new(Noop));
+    let second = hooks.add(Arc::new(Noop));
+    assert_ne!(first, second);
+
+    assert!(hooks.remove(first));
+    assert!(!hooks.remove(first));
+    assert!(hooks.remove(second));
+}
+
+#[test]
+fn test_middleware_belongs_to_one_desktop() {
+    let hooks = ActionHooks::new();
+    let other = ActionHooks::new();
+    let id = hooks.add(Arc::new(Noop));
+    assert!(!other.remove(id));
+    assert!(hooks.remove(id));
+}
+
+#[test]
+fn test_action_kind_names() {
+    assert_eq!(ActionKind::TypeText.to_string(), "type_text");
+    assert_eq!(ActionKind::ClickAtAnchor.as_str(), "click_at_anchor");
+    assert_eq!(ActionKind::MouseDown.as_str(), "mouse_down");
+}
@@ -1,8 +1,116 @@
+mod action_strategy_tests;
+
+mod app_profile_tests;
+
+mod app_session_tests;
+
+mod audit_tests;
+
+mod batch_tests;
+
+#[cfg(feature = "bench")]
+mod bench_tests;
+
+mod console_tests;
+
+mod controls_tests;
+
+mod dialogs_tests;
+
+mod dry_run_tests;
+
 mod e2e_tests;
 
+mod element_tests;
+
+mod elevation_tests;
+
+mod errors_tests;
+
+mod expect_tests;
+
 
 mod firefox_window_tests;
 
+mod focus_history_tests;
+
+mod form_tests;
+
+mod geometry_tests;
+
+mod hotkey_tests;
+
+mod image_diff_tests;
+
+mod image_match_tests;
+
+mod input_guard_tests;
+
+mod input_profile_tests;
+
+mod l10n_tests;
+
+mod locator_tests;
+
+mod middleware_tests;
+
+mod monitor_tests;
+
+mod notification_tests;
+
+mod ocr_tests;
+
+mod permissions_tests;
+
+mod postcondition_tests;
+
+mod screenshot_tests;
+
+mod search_index_tests;
+
+mod selector_tests;
+
+mod selector_cache_tests;
+
+mod selector_path_tests;
+
+mod selector_profile_tests;
+
+mod session_tests;
+
+mod session_trace_tests;
+
+#[cfg(feature = "snapshot")]
+mod snapshot_tests;
+
+mod spatial_tests;
+
+mod stale_element_tests;
+
+mod text_pattern_tests;
+
+mod tree_build_stats_tests;
+
+mod tree_diff_tests;
+
+mod tree_handles_tests;
+
+mod tree_query_tests;
+
+mod tree_stream_tests;
+
+mod ui_events_tests;
+
+mod url_tests;
+
+mod verify_tests;
+
+mod virtual_desktop_tests;
+
+mod watchdog_tests;
+
+mod workflow_tests;
+
 // Initialize tracing for tests
 pub fn init_tracing() {
     use tracing_subscriber::{fmt, EnvFilter};
@@ -15,3 +123,19 @@ pub fn init_tracing() {
         .init();
 }
 
+// Tree node for tests of code working on built trees. An empty `name` means
+// none
+pub(crate) fn node(role: &str, name: &str, children: Vec<crate::UINode>) -> crate::UINode {
+    crate::UINode {
+        id: None,
+        attributes: crate::UIElementAttributes {
+            role: role.to_string(),
+            name: Some(name.to_string()).filter(|name| !name.is_empty()),
+            ..Default::default()
+        },
+        path: None,
+        truncated: false,
+        status: crate::SubtreeStatus::Complete,
+        children,
+    }
+}
@@ -0,0 +1,41 @@
+use crate::geometry::Rect;
+use crate::monitor::{monitor_for_rect, MonitorInfo};
+
+fn monitor(name: &str, bounds: Rect, is_primary: bool) -> MonitorInfo {
+    MonitorInfo {
+        name: name.to_string(),
+        bounds,
+        scale_factor: 1.0,
+        refresh_rate: 60.0,
+        is_primary,
+    }
+}
+
+fn side_by_side() -> Vec<MonitorInfo> {
+    vec![
+        monitor("primary", Rect::new(0.0, 0.0, 1920.0, 1080.0), true),
+        monitor("right", Rect::new(1920.0, 0.0, 2560.0, 1440.0), false),
+    ]
+}
+
+#[test]
+fn test_monitor_for_rect_uses_center() {
+    let monitors = side_by_side();
+    let on_primary = Rect::new(100.0, 100.0, 300.0, 200.0);
+    assert_eq!(monitor_for_rect(&monitors, &on_primary).unwrap().name, "primary");
+
+    // Straddles the edge, but the center is on the right monitor
+    let straddling = Rect::new(1800.0, 100.0, 400.0, 200.0);
+    assert_eq!(monitor_for_rect(&monitors, &straddling).unwrap().name, "right");
+}
+
+#[test]
+fn test_monitor_for_rect_falls_back_to_largest_overlap() {
+    let monitors = side_by_side();
+    // Center is just below the primary monitor, in the gap next to the taller right one
+    let below_primary = Rect::new(1500.0, 1000.0, 200.0, 200.0);
+    assert_eq!(monitor_for_rect(&monitors, &below_primary).unwrap().name, "primary");
+
+    let off_screen = Rect::new(-500.0, -500.0, 100.0, 100.0);
+    assert!(monitor_for_rect(&monitors, &off_screen).is_none());
+}
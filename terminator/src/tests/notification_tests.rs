@@ -0,0 +1,46 @@
+use crate::notifications::{parse_parts, NotificationPart};
+
+fn part(id: Option<&str>, text: &str) -> NotificationPart {
+    NotificationPart {
+        id: id.map(str::to_string),
+        text: text.to_string(),
+    }
+}
+
+#[test]
+fn test_parse_windows_toast_by_automation_id() {
+    let texts = [
+        part(Some("Attribution"), "Microsoft Edge"),
+        part(Some("Title"), "Download complete"),
+        part(Some("Content"), "report.pdf"),
+        part(Some("Content"), "4.2 MB"),
+    ];
+    let buttons = [
+        part(Some("SettingsButton"), "Notification settings"),
+        part(Some("DismissButton"), "Dismiss"),
+        part(None, "Open file"),
+        part(None, "Show in folder"),
+    ];
+
+    let (app, title, body, actions) = parse_parts(&texts, &buttons);
+    assert_eq!(app.as_deref(), Some("Microsoft Edge"));
+    assert_eq!(title.as_deref(), Some("Download complete"));
+    assert_eq!(body.as_deref(), Some("report.pdf\n4.2 MB"));
+    assert_eq!(actions, vec!["Open file", "Show in folder"]);
+}
+
+#[test]
+fn test_parse_notification_without_ids() {
+    let texts = [part(None, "  "), part(None, "Build finished"), part(None, "All 212 tests passed")];
+    let buttons = [part(None, "&Close"), part(None, "Reply")];
+
+    let (app, title, body, actions) = parse_parts(&texts, &buttons);
+    assert_eq!(app, None);
+    assert_eq!(title.as_deref(), Some("Build finished"));
+    assert_eq!(body.as_deref(), Some("All 212 tests passed"));
+    assert_eq!(actions, vec!["Reply"]);
+
+    let (_, title, body, actions) = parse_parts(&[], &[]);
+    assert_eq!((title, body), (None, None));
+    assert!(actions.is_empty());
+}
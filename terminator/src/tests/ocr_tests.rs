@@ -0,0 +1,52 @@
+use crate::ocr::{crop, find_phrase, OcrWord};
+use crate::{Rect, ScreenshotResult};
+
+fn word(text: &str, x: f64, line: usize) -> OcrWord {
+    OcrWord {
+        text: text.to_string(),
+        bounds: Rect::new(x, line as f64 * 30.0, 40.0, 20.0),
+        line,
+    }
+}
+
+#[test]
+fn test_find_phrase_single_word_ignores_case() {
+    let words = vec![word("File", 0.0, 0), word("Save", 50.0, 0), word("save", 0.0, 1)];
+    let matches = find_phrase(&words, "SAVE");
+    assert_eq!(matches.len(), 2);
+    assert_eq!(matches[0], ("Save".to_string(), Rect::new(50.0, 0.0, 40.0, 20.0)));
+}
+
+#[test]
+fn test_find_phrase_spans_words_of_one_line() {
+    let words = vec![
+        word("Press", 0.0, 0),
+        word("Start", 50.0, 0),
+        word("game", 100.0, 0),
+        word("Start", 0.0, 1),
+        word("game", 0.0, 2),
+    ];
+    let matches = find_phrase(&words, "start game");
+    // The words on lines 1 and 2 are not one phrase
+    assert_eq!(matches, vec![("Start game".to_string(), Rect::new(50.0, 0.0, 90.0, 20.0))]);
+}
+
+#[test]
+fn test_find_phrase_empty_text() {
+    assert!(find_phrase(&[word("Save", 0.0, 0)], "  ").is_empty());
+}
+
+#[test]
+fn test_crop_screenshot() {
+    // 4x2 image whose red channel is the pixel index
+    let screenshot = ScreenshotResult {
+        image_data: (0..8u8).flat_map(|i| [i, 0, 0, 255]).collect(),
+        width: 4,
+        height: 2,
+    };
+    let cropped = crop(&screenshot, &Rect::new(1.0, 1.0, 10.0, 10.0)).unwrap();
+    assert_eq!((cropped.width, cropped.height), (3, 1));
+    let reds: Vec<u8> = cropped.image_data.chunks(4).map(|p| p[0]).collect();
+    assert_eq!(reds, vec![5, 6, 7]);
+    assert!(crop(&screenshot, &Rect::new(10.0, 10.0, 5.0, 5.0)).is_err());
+}
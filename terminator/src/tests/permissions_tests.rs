@@ -0,0 +1,46 @@
+use crate::{AutomationError, Permission, PermissionState, PermissionStatus};
+
+fn status(accessibility: PermissionState, screen_recording: PermissionState) -> PermissionStatus {
+    PermissionStatus {
+        accessibility,
+        screen_recording,
+    }
+}
+
+#[test]
+fn test_not_applicable_counts_as_granted() {
+    let status = PermissionStatus::not_applicable();
+    assert!(status.all_granted());
+    assert!(status.require_all().is_ok());
+}
+
+#[test]
+fn test_missing_lists_denied_permissions() {
+    let status = status(PermissionState::Granted, PermissionState::Denied);
+    assert_eq!(status.missing(), vec![Permission::ScreenRecording]);
+    assert!(!status.all_granted());
+    assert!(status.require(Permission::Accessibility).is_ok());
+}
+
+#[test]
+fn test_require_names_the_missing_permission() {
+    let status = status(PermissionState::Denied, PermissionState::Denied);
+    let error = status.require_all().unwrap_err();
+    assert!(matches!(error, AutomationError::MissingPermission(Permission::Accessibility)));
+    assert!(error.to_string().contains("Privacy & Security > Accessibility"));
+
+    let error = status.require(Permission::ScreenRecording).unwrap_err();
+    assert!(error.detail().starts_with("Screen Recording is not granted"));
+}
+
+#[test]
+fn test_settings_urls_open_the_privacy_panes() {
+    assert!(Permission::Accessibility.settings_url().ends_with("?Privacy_Accessibility"));
+    assert!(Permission::ScreenRecording.settings_url().ends_with("?Privacy_ScreenCapture"));
+}
+
+#[test]
+fn test_status_serializes_in_snake_case() {
+    let json = serde_json::to_value(status(PermissionState::Granted, PermissionState::NotApplicable)).unwrap();
+    assert_eq!(json, serde_json::json!({"accessibility": "granted", "screen_recording": "not_applicable"}));
+}
@@ -0,0 +1,27 @@
+use crate::postcondition::Condition;
+
+#[test]
+fn test_condition_from_kind() {
+    assert!(matches!(Condition::from_kind("gone", None), Ok(Condition::Gone)));
+    assert!(matches!(
+        Condition::from_kind("valueEquals", Some("42".to_string())),
+        Ok(Condition::ValueEquals(v)) if v == "42"
+    ));
+    assert!(matches!(
+        Condition::from_kind("text_contains", Some("Saved".to_string())),
+        Ok(Condition::TextContains(v)) if v == "Saved"
+    ));
+
+    assert!(Condition::from_kind("value_equals", None).is_err());
+    assert!(Condition::from_kind("clicked", None).is_err());
+}
+
+#[test]
+fn test_condition_display() {
+    assert_eq!(Condition::Gone.to_string(), "element is gone");
+    assert_eq!(
+        Condition::ValueContains("@".to_string()).to_string(),
+        "value contains '@'"
+    );
+    assert_eq!(Condition::custom("row was added", |_| true).to_string(), "row was added");
+}
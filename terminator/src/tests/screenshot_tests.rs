@@ -0,0 +1,56 @@
+use crate::{AutomationError, ImageFormat, ScreenshotResult};
+
+fn gradient(width: u32, height: u32) -> ScreenshotResult {
+    let mut image_data = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            image_data.extend_from_slice(&[(x * 8) as u8, (y * 8) as u8, 128, 255]);
+        }
+    }
+    ScreenshotResult {
+        image_data,
+        width,
+        height,
+    }
+}
+
+#[test]
+fn test_png_round_trips() {
+    let shot = gradient(16, 8);
+    let png = shot.to_png().unwrap();
+    assert!(png.starts_with(b"\x89PNG"));
+    let decoded = image::load_from_memory(&png).unwrap().to_rgba8();
+    assert_eq!(decoded.dimensions(), (16, 8));
+    assert_eq!(decoded.into_raw(), shot.image_data);
+}
+
+#[test]
+fn test_encode_formats() {
+    let shot = gradient(16, 8);
+    let jpeg = shot.encode(ImageFormat::Jpeg { quality: 50 }).unwrap();
+    assert!(jpeg.data.starts_with(&[0xFF, 0xD8]));
+    assert_eq!((jpeg.width, jpeg.height), (16, 8));
+
+    let webp = shot.encode(ImageFormat::WebP).unwrap();
+    assert!(webp.data.starts_with(b"RIFF"));
+    let decoded = image::load_from_memory(&webp.data).unwrap().to_rgba8();
+    assert_eq!(decoded.into_raw(), shot.image_data);
+}
+
+#[test]
+fn test_wrong_buffer_size_is_an_error() {
+    let mut shot = gradient(4, 4);
+    shot.image_data.pop();
+    assert!(matches!(shot.to_png(), Err(AutomationError::InvalidArgument(_))));
+    assert!(matches!(shot.to_jpeg(80), Err(AutomationError::InvalidArgument(_))));
+}
+
+#[test]
+fn test_parse_image_format() {
+    assert_eq!("PNG".parse::<ImageFormat>().unwrap(), ImageFormat::Png);
+    assert_eq!("jpg".parse::<ImageFormat>().unwrap(), ImageFormat::Jpeg { quality: 85 });
+    assert_eq!("jpeg:60".parse::<ImageFormat>().unwrap(), ImageFormat::Jpeg { quality: 60 });
+    assert!("jpeg:0".parse::<ImageFormat>().is_err());
+    assert!("gif".parse::<ImageFormat>().is_err());
+    assert_eq!(ImageFormat::Jpeg { quality: 60 }.to_string(), "jpeg:60");
+}
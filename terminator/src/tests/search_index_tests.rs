@@ -0,0 +1,101 @@
+use crate::{Selector, TreeIndex, UINode};
+
+// Ids made of the role and name, as `Selector::Id` sees them
+fn node(role: &str, name: &str, children: Vec<UINode>) -> UINode {
+    UINode {
+        id: Some(format!("{}-{}", role, name)),
+        ..super::node(role, name, children)
+    }
+}
+
+// Numbered depth-first: 0 Invoices, 1 Table, 2 Invoice 1001, 3 Invoice 1002,
+// 4 Toolbar, 5 Save, 6 Save as, 7 Status
+fn sample() -> TreeIndex {
+    TreeIndex::new(&node(
+        "Window",
+        "Invoices",
+        vec![
+            node(
+                "DataGrid",
+                "Table",
+                vec![node("DataItem", "Invoice 1001", vec![]), node("DataItem", "Invoice 1002", vec![])],
+            ),
+            node(
+                "ToolBar",
+                "Toolbar",
+                vec![node("Button", "Save", vec![]), node("Button", "Save as", vec![])],
+            ),
+            node("Text", "Status", vec![]),
+        ],
+    ))
+}
+
+#[test]
+fn test_index_counts_nodes() {
+    assert_eq!(sample().len(), 8);
+    assert!(TreeIndex::default().is_empty());
+}
+
+#[test]
+fn test_name_matches_substrings_ignoring_case() {
+    let index = sample();
+    assert_eq!(index.matches(&Selector::Name("invoice".to_string())), Some(vec![2, 3]));
+    assert_eq!(index.matches(&Selector::Name("1002".to_string())), Some(vec![3]));
+    assert_eq!(index.matches(&Selector::Name("SAVE".to_string())), Some(vec![5, 6]));
+    assert_eq!(index.matches(&Selector::Name("Missing".to_string())), Some(vec![]));
+}
+
+#[test]
+fn test_short_names_are_matched_without_trigrams() {
+    let index = sample();
+    assert_eq!(index.matches(&Selector::Name("as".to_string())), Some(vec![6]));
+}
+
+#[test]
+fn test_root_is_not_a_match() {
+    let index = sample();
+    assert_eq!(index.matches(&Selector::Name("Invoices".to_string())), Some(vec![]));
+    assert_eq!(index.matches(&Selector::from("role:window")), Some(vec![]));
+}
+
+#[test]
+fn test_role_selectors() {
+    let index = sample();
+    assert_eq!(index.matches(&Selector::from("role:button")), Some(vec![5, 6]));
+    assert_eq!(index.matches(&Selector::from("role:data")), Some(vec![2, 3]));
+    assert_eq!(
+        index.matches(&Selector::Role {
+            role: "Button".to_string(),
+            name: Some("as".to_string()),
+        }),
+        Some(vec![6])
+    );
+}
+
+#[test]
+fn test_text_matches_names_and_text_elements() {
+    let index = sample();
+    assert_eq!(index.matches(&Selector::Text("1001".to_string())), Some(vec![2, 7]));
+}
+
+#[test]
+fn test_id_matches_exactly() {
+    let index = sample();
+    assert_eq!(index.matches(&Selector::Id("Button-Save".to_string())), Some(vec![5]));
+}
+
+#[test]
+fn test_and_or_combine_operands() {
+    let index = sample();
+    assert_eq!(index.matches(&Selector::from("role:DataItem && name:1002")), Some(vec![3]));
+    assert_eq!(index.matches(&Selector::from("name:Status || name:1001")), Some(vec![2, 7]));
+}
+
+#[test]
+fn test_unsupported_selectors_need_a_walk() {
+    let index = sample();
+    assert_eq!(index.matches(&Selector::from("nativeid:SaveButton")), None);
+    let chain = Selector::Chain(vec![Selector::from("role:ToolBar"), Selector::from("name:Save")]);
+    assert_eq!(index.matches(&chain), None);
+    assert_eq!(index.matches(&Selector::from("role:Button && nativeid:SaveButton")), None);
+}
@@ -0,0 +1,51 @@
+use crate::selector_cache::{AppKey, SelectorCache};
+
+fn notepad(version: &str) -> AppKey {
+    AppKey::new("Notepad.exe", Some(version.to_string()))
+}
+
+#[test]
+fn test_learned_selector_is_per_app_version() {
+    let cache = SelectorCache::in_memory();
+    cache.record(&notepad("11.0"), "save button", "nativeid:SaveButton");
+
+    assert_eq!(
+        cache.learned(&notepad("11.0"), "save button").as_deref(),
+        Some("nativeid:SaveButton")
+    );
+    // An update may have changed the UI, so nothing is reused
+    assert_eq!(cache.learned(&notepad("11.1"), "save button"), None);
+    assert_eq!(cache.learned(&notepad("11.0"), "open button"), None);
+}
+
+#[test]
+fn test_record_replaces_and_forget_removes() {
+    let cache = SelectorCache::in_memory();
+    let app = notepad("11.0");
+    cache.record(&app, "save button", "nativeid:SaveButton");
+    cache.record(&app, "save button", "name:Save");
+    assert_eq!(cache.learned(&app, "save button").as_deref(), Some("name:Save"));
+
+    cache.forget(&app, "save button");
+    assert_eq!(cache.learned(&app, "save button"), None);
+}
+
+#[test]
+fn test_cache_persists_to_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("selectors.json");
+    let app = notepad("11.0");
+
+    let cache = SelectorCache::open(&path).unwrap();
+    cache.record(&app, "save button", "name:Save");
+    drop(cache);
+
+    let reopened = SelectorCache::open(&path).unwrap();
+    assert_eq!(reopened.learned(&app, "save button").as_deref(), Some("name:Save"));
+}
+
+#[test]
+fn test_app_key_display() {
+    assert_eq!(notepad("11.0").to_string(), "notepad.exe@11.0");
+    assert_eq!(AppKey::new("gedit", None).to_string(), "gedit");
+}
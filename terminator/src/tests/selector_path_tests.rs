@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::selector_path::{assign_paths, parse_path, root_step, walk, Axis, Limits, PathStep};
+use crate::{AutomationError, ErrorCode, Selector};
+
+use super::node;
+
+#[test]
+fn test_parse_path_with_indexes_and_predicates() {
+    let steps = parse_path("/Window[2]/Pane/Button[name='OK']").unwrap();
+    assert_eq!(steps.len(), 3);
+    assert_eq!(steps[0].role.as_deref(), Some("Window"));
+    assert_eq!(steps[0].index, Some(2));
+    assert_eq!(steps[1].index, None);
+    assert_eq!(
+        steps[2].attributes,
+        vec![("name".to_string(), "OK".to_string())]
+    );
+}
+
+#[test]
+fn test_parse_path_wildcards_and_descendants() {
+    let steps = parse_path("/*//Edit[@AutomationId=\"search\"][1]").unwrap();
+    assert_eq!(steps[0].role, None);
+    assert_eq!(steps[0].axis, Axis::Child);
+    assert_eq!(steps[1].axis, Axis::Descendant);
+    assert_eq!(
+        steps[1].attributes,
+        vec![("automationid".to_string(), "search".to_string())]
+    );
+    assert_eq!(steps[1].index, Some(1));
+}
+
+#[test]
+fn test_parse_path_keeps_separators_inside_quotes() {
+    let steps = parse_path("/Window[name='C:/Users [admin]']/Button").unwrap();
+    assert_eq!(steps.len(), 2);
+    assert_eq!(steps[0].attributes[0].1, "C:/Users [admin]");
+}
+
+#[test]
+fn test_names_with_both_quotes_round_trip() {
+    let name = r#"Don't "save""#;
+    let step = root_step("Button", Some(name));
+    assert_eq!(step, r#"/Button[name='Don''t "save"']"#);
+    let steps = parse_path(&step).unwrap();
+    assert_eq!(steps[0].attributes[0].1, name);
+    assert_eq!(parse_path("/Button[name=\"say \"\"hi\"\"\"]").unwrap()[0].attributes[0].1, "say \"hi\"");
+}
+
+#[test]
+fn test_parse_path_rejects_invalid_paths() {
+    assert!(parse_path("").is_err());
+    assert!(parse_path("/Window[0]").is_err());
+    assert!(parse_path("/Window[name=OK]").is_err());
+    assert!(parse_path("/Window[color='red']").is_err());
+    assert!(parse_path("/Window[name='OK'").is_err());
+    assert!(parse_path("//").is_err());
+}
+
+#[test]
+fn test_path_strings_parse_as_path_selectors() {
+    assert_eq!(
+        Selector::from("/Window[name='C:\\temp']/Button[1]"),
+        Selector::Path("/Window[name='C:\\temp']/Button[1]".to_string())
+    );
+}
+
+#[test]
+fn test_assign_paths_indexes_siblings_by_role() {
+    let mut tree = node(
+        "Window",
+        "",
+        vec![
+            node("Button", "", vec![]),
+            node("Pane", "", vec![node("Button", "", vec![])]),
+            node("Button", "", vec![]),
+            node("", "", vec![]),
+        ],
+    );
+    assign_paths(&mut tree, root_step("Window", Some("It's here")));
+
+    assert_eq!(tree.path.as_deref(), Some("/Window[name=\"It's here\"]"));
+    let paths: Vec<_> = tree.children.iter().map(|c| c.path.clone().unwrap()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "/Window[name=\"It's here\"]/Button[1]",
+            "/Window[name=\"It's here\"]/Pane[1]",
+            "/Window[name=\"It's here\"]/Button[2]",
+            "/Window[name=\"It's here\"]/*[4]",
+        ]
+    );
+    assert_eq!(
+        tree.children[1].children[0].path.as_deref(),
+        Some("/Window[name=\"It's here\"]/Pane[1]/Button[1]")
+    );
+    // Emitted paths parse back
+    for path in paths {
+        assert!(parse_path(&path).is_ok(), "{}", path);
+    }
+}
+
+// A tree of numbered elements: (id, role, parent)
+fn resolve(tree: &[(u32, &str, u32)], path: &str, limits: &Limits) -> Result<Vec<u32>, AutomationError> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (id, _, parent) in tree {
+        children.entry(*parent).or_default().push(*id);
+    }
+    let role = |id: &u32| tree.iter().find(|(i, _, _)| i == id).map(|(_, role, _)| *role).unwrap_or_default();
+    walk(
+        &0,
+        &parse_path(path).unwrap(),
+        limits,
+        |id| children.get(id).cloned().unwrap_or_default(),
+        |step, id| step.role.as_deref().is_none_or(|r| r == role(id)),
+    )
+}
+
+const UNLIMITED: Limits = Limits {
+    depth: 50,
+    deadline: None,
+};
+
+#[test]
+fn test_nested_descendant_parents_find_each_element_once() {
+    // Pane 1 holds button 2 and pane 3, which holds buttons 4 and 5
+    let tree = [(1, "Pane", 0), (2, "Button", 1), (3, "Pane", 1), (4, "Button", 3), (5, "Button", 3), (6, "Button", 0)];
+    assert_eq!(resolve(&tree, "//Pane//Button", &UNLIMITED).unwrap(), vec![2, 4, 5]);
+    // Counted within the outer pane, not again within the inner one
+    assert_eq!(resolve(&tree, "//Pane//Button[2]", &UNLIMITED).unwrap(), vec![4]);
+    assert_eq!(resolve(&tree, "//Button", &UNLIMITED).unwrap(), vec![2, 4, 5, 6]);
+    assert_eq!(resolve(&tree, "/Pane/Pane/Button[2]", &UNLIMITED).unwrap(), vec![5]);
+}
+
+#[test]
+fn test_path_search_limits() {
+    let tree = [(1, "Pane", 0), (2, "Pane", 1), (3, "Button", 2), (4, "Button", 1)];
+    let shallow = Limits {
+        depth: 2,
+        deadline: None,
+    };
+    assert_eq!(resolve(&tree, "//Button", &shallow).unwrap(), vec![4]);
+
+    let expired = Limits {
+        depth: 50,
+        deadline: Some(Instant::now()),
+    };
+    assert_eq!(resolve(&tree, "//Button", &expired).unwrap_err().code(), ErrorCode::Timeout);
+
+    // Steps built by hand may index from 0, which picks nothing
+    let step = PathStep {
+        axis: Axis::Child,
+        role: None,
+        attributes: Vec::new(),
+        index: Some(0),
+    };
+    let found = walk(&0, &[step], &UNLIMITED, |id| if *id == 0 { vec![1] } else { vec![] }, |_, _| true);
+    assert!(found.unwrap().is_empty());
+}
@@ -0,0 +1,80 @@
+use crate::selector_profile::{strategy, HopProfile, SelectorProfile};
+use crate::Selector;
+
+fn hop(index: usize, selector: &str, matches: usize, duration_ms: u64) -> HopProfile {
+    HopProfile {
+        index,
+        selector: selector.to_string(),
+        strategy: strategy(&Selector::from(selector)).to_string(),
+        searched_in: None,
+        candidates: None,
+        matches,
+        duration_ms,
+        error: None,
+    }
+}
+
+#[test]
+fn test_strategy_names_selector_kinds() {
+    assert_eq!(strategy(&Selector::from("role:Button")), "role");
+    assert_eq!(strategy(&Selector::from("Button:OK")), "role_and_name");
+    assert_eq!(strategy(&Selector::from("name:Save")), "name");
+    assert_eq!(strategy(&Selector::from("nativeid:search")), "native_id");
+    assert_eq!(strategy(&Selector::from("/Window/Button[1]")), "path");
+    assert_eq!(strategy(&Selector::from("rightof:name:Email")), "spatial");
+    assert_eq!(strategy(&Selector::from("role:Button || name:OK")), "or");
+}
+
+#[test]
+fn test_slowest_hop_includes_scope_and_candidates() {
+    let scope = SelectorProfile {
+        selector: "role:List".to_string(),
+        found: true,
+        hops: vec![hop(0, "role:List", 3, 40)],
+        ..Default::default()
+    };
+    let candidate = SelectorProfile {
+        selector: "name:Settings".to_string(),
+        hops: vec![hop(0, "name:Settings", 0, 4100)],
+        ..Default::default()
+    };
+    let profile = SelectorProfile {
+        selector: "Settings".to_string(),
+        scope: Some(Box::new(scope)),
+        candidates: vec![candidate],
+        hops: vec![hop(0, "role:Button", 1, 12)],
+        ..Default::default()
+    };
+    let slowest = profile.slowest_hop().unwrap();
+    assert_eq!(slowest.selector, "name:Settings");
+    assert_eq!(slowest.duration_ms, 4100);
+    assert!(SelectorProfile::default().slowest_hop().is_none());
+}
+
+#[test]
+fn test_profile_display_and_json() {
+    let mut narrowed = hop(1, "nth=0", 1, 0);
+    narrowed.strategy = "nth".to_string();
+    narrowed.candidates = Some(3);
+    let mut first = hop(0, "role:Button", 3, 250);
+    first.searched_in = Some("Window 'Settings'".to_string());
+    let profile = SelectorProfile {
+        selector: "role:Button >> nth=0".to_string(),
+        found: true,
+        hops: vec![first, narrowed],
+        duration_ms: 251,
+        ..Default::default()
+    };
+
+    let text = profile.to_string();
+    assert!(text.starts_with("role:Button >> nth=0: found in 251ms"), "{}", text);
+    assert!(text.contains("1. [role] role:Button in Window 'Settings': 3 matches, 250ms"), "{}", text);
+    assert!(text.contains("2. [nth] nth=0: 1 of 3 kept, 0ms"), "{}", text);
+
+    let json = serde_json::to_value(&profile).unwrap();
+    assert_eq!(json["hops"][0]["strategy"], "role");
+    assert!(json["hops"][0].get("candidates").is_none());
+    assert_eq!(json["hops"][1]["candidates"], 3);
+    assert!(json.get("scope").is_none());
+    assert!(json.get("candidates").is_none());
+}
@@ -0,0 +1,134 @@
+use crate::{AutomationError, Selector};
+
+fn role(role: &str) -> Selector {
+    Selector::Role {
+        role: role.to_string(),
+        name: None,
+    }
+}
+
+#[test]
+fn test_not_with_attribute_predicate() {
+    assert_eq!(
+        Selector::from(r#"role=button:not([name="Cancel"])"#),
+        Selector::And(vec![
+            role("button"),
+            Selector::Not(Box::new(Selector::Name("Cancel".to_string()))),
+        ])
+    );
+
+    // Prefix syntax inside not(), and several negations
+    assert_eq!(
+        Selector::from("role:button:not(name:Cancel):not(nativeid:closeBtn)"),
+        Selector::And(vec![
+            role("button"),
+            Selector::Not(Box::new(Selector::Name("Cancel".to_string()))),
+            Selector::Not(Box::new(Selector::NativeId("closeBtn".to_string()))),
+        ])
+    );
+}
+
+#[test]
+fn test_or_binds_looser_than_and() {
+    assert_eq!(
+        Selector::from("role:edit && name:Amount || name:Total"),
+        Selector::Or(vec![
+            Selector::And(vec![role("edit"), Selector::Name("Amount".to_string())]),
+            Selector::Name("Total".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn test_operators_inside_quotes_are_not_split() {
+    assert_eq!(
+        Selector::from(r#"role:button && [name="Save && close"]"#),
+        Selector::And(vec![role("button"), Selector::Name("Save && close".to_string())])
+    );
+}
+
+#[test]
+fn test_apostrophes_in_names_do_not_open_quotes() {
+    let name = |name: &str| Selector::Name(name.to_string());
+    assert_eq!(
+        Selector::from("name:Don't Save || name:Cancel"),
+        Selector::Or(vec![name("Don't Save"), name("Cancel")])
+    );
+    assert_eq!(
+        Selector::from("name:Cancel || name:Don't Save"),
+        Selector::Or(vec![name("Cancel"), name("Don't Save")])
+    );
+    assert_eq!(
+        Selector::from("name:Don't Save && role:button"),
+        Selector::And(vec![name("Don't Save"), role("button")])
+    );
+    assert_eq!(
+        Selector::from("role:button && name:Don't Save"),
+        Selector::And(vec![role("button"), name("Don't Save")])
+    );
+    // Quoted values still hide operators
+    assert_eq!(
+        Selector::from("name:'A || B' || name:It's"),
+        Selector::Or(vec![name("A || B"), name("It's")])
+    );
+}
+
+#[test]
+fn test_quoted_values_are_literal() {
+    let name = |name: &str| Selector::Name(name.to_string());
+    assert_eq!(Selector::from(r#"name:"Save && close""#), name("Save && close"));
+    assert_eq!(Selector::from("name:'Rock || Roll'"), name("Rock || Roll"));
+    assert_eq!(Selector::from(r#"name:"Filter:not(done)""#), name("Filter:not(done)"));
+    assert_eq!(
+        Selector::from(r#"button:"Yes && No""#),
+        Selector::Role { role: "button".to_string(), name: Some("Yes && No".to_string()) }
+    );
+    assert_eq!(Selector::from("name:\""), name("\""));
+}
+
+#[test]
+fn test_invalid_combinations_are_read_literally() {
+    let name = |name: &str| Selector::Name(name.to_string());
+    assert_eq!(Selector::from("name:Tom &&"), name("Tom &&"));
+    assert_eq!(Selector::from("|| Jerry"), name("|| Jerry"));
+    assert_eq!(Selector::from("name:Smile :) || more"), name("Smile :) || more"));
+    assert_eq!(
+        Selector::from("role:button && /Window/Button"),
+        role("button && /Window/Button")
+    );
+}
+
+#[test]
+fn test_plain_selectors_are_unchanged() {
+    assert_eq!(Selector::from("name:OK"), Selector::Name("OK".to_string()));
+    assert!(matches!(Selector::from("/Window/Button[name='A || B']"), Selector::Path(_)));
+    // An unbalanced not() is not a combinator
+    assert_eq!(
+        Selector::from("role:button:not(name:Cancel"),
+        role("button:not(name:Cancel")
+    );
+}
+
+#[test]
+fn test_operands_must_describe_one_element() {
+    assert!(Selector::from("role:button && name:OK").check_element_predicate().is_ok());
+    assert!(matches!(
+        Selector::And(vec![role("button"), Selector::Path("/Window/Button".to_string())]).check_element_predicate(),
+        Err(AutomationError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        Selector::Or(Vec::new()).check_element_predicate(),
+        Err(AutomationError::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn test_canonical_role_resolves_aliases() {
+    use crate::selector::canonical_role;
+
+    assert_eq!(canonical_role("Button"), "button");
+    assert_eq!(canonical_role("Dialog"), "window");
+    assert_eq!(canonical_role("app"), "pane");
+    assert_eq!(canonical_role("url"), "edit");
+    assert_eq!(canonical_role("data"), "dataitem");
+}
@@ -0,0 +1,50 @@
+use crate::errors::ErrorCode;
+use crate::session::{tscon_error, InputDesktop};
+use crate::SessionState;
+
+#[test]
+fn test_windows_session_state_mapping() {
+    let default = InputDesktop::Open(Some("Default".to_string()));
+    let cases = [
+        (0, false, default.clone(), SessionState::Service),
+        (0, true, InputDesktop::Unreachable, SessionState::Service),
+        (2, true, default.clone(), SessionState::Disconnected),
+        (2, false, InputDesktop::Unreachable, SessionState::Locked),
+        (2, false, InputDesktop::Open(Some("Winlogon".to_string())), SessionState::Locked),
+        (2, false, InputDesktop::Open(Some("default".to_string())), SessionState::Active),
+        (2, false, InputDesktop::Open(None), SessionState::Active),
+        (2, false, default, SessionState::Active),
+    ];
+    for (session_id, disconnected, desktop, expected) in cases {
+        assert_eq!(
+            SessionState::for_windows_session(session_id, disconnected, &desktop),
+            expected,
+            "session {session_id}, disconnected {disconnected}, {desktop:?}"
+        );
+    }
+}
+
+#[test]
+fn test_ui_availability_and_serialization() {
+    assert!(SessionState::Active.is_ui_available());
+    assert!(SessionState::Unknown.is_ui_available());
+    assert!(!SessionState::Locked.is_ui_available());
+    assert!(!SessionState::Disconnected.is_ui_available());
+    assert!(!SessionState::Service.is_ui_available());
+
+    assert_eq!(serde_json::to_value(SessionState::Disconnected).unwrap(), "disconnected");
+    assert_eq!(SessionState::Service.to_string(), "service");
+}
+
+#[test]
+fn test_tscon_errors() {
+    let denied = tscon_error(3, "Could not connect sessionID 3 to sessionname console\r\nError [5]:Access is denied.\r\n");
+    assert_eq!(denied.code(), ErrorCode::PermissionDenied);
+
+    let other = tscon_error(3, "  Session 3 not found  \r\n");
+    assert_eq!(other.code(), ErrorCode::PlatformError);
+    assert_eq!(
+        other.to_string(),
+        "Platform-specific error: tscon failed for session 3: Session 3 not found"
+    );
+}
@@ -0,0 +1,84 @@
+use tracing::{error, info_span};
+use tracing_subscriber::prelude::*;
+
+use crate::session_trace::{otlp_span, parse_json_lines};
+use crate::{SessionTracer, TraceRecord, TraceRecordKind};
+
+fn run_traced(tracer: &SessionTracer, work: impl FnOnce()) {
+    let subscriber = tracing_subscriber::registry().with(tracer.clone());
+    tracing::subscriber::with_default(subscriber, work);
+}
+
+#[test]
+fn test_records_nested_calls_with_args_and_errors() {
+    let tracer = SessionTracer::new();
+    run_traced(&tracer, || {
+        let outer = info_span!(target: "terminator::locator", "first", selector = "name:Save");
+        let _outer = outer.enter();
+        let inner = info_span!(target: "terminator::platforms", "find_element", depth = 3u64);
+        let _inner = inner.enter();
+        error!(target: "terminator::platforms", error = "not found", "Lookup failed");
+        // Spans of other crates are not traced
+        let _other = info_span!(target: "hyper::client", "request").entered();
+    });
+
+    let records = tracer.records();
+    assert_eq!(records.len(), 2);
+    let (inner, outer) = (&records[0], &records[1]);
+    assert_eq!((outer.name.as_str(), outer.kind), ("first", TraceRecordKind::Call));
+    assert_eq!(outer.args["selector"], "name:Save");
+    assert_eq!((outer.parent_id, outer.root_id), (None, outer.id));
+    assert_eq!(outer.error, None);
+
+    assert_eq!(inner.args["depth"], 3);
+    assert_eq!((inner.parent_id, inner.root_id), (Some(outer.id), outer.id));
+    assert_eq!(inner.error.as_deref(), Some("not found"));
+}
+
+#[test]
+fn test_json_lines_round_trip_and_record_limit() {
+    let tracer = SessionTracer::new().with_max_records(2);
+    run_traced(&tracer, || {
+        for name in ["open_application", "locator", "capture_screen"] {
+            let _span = info_span!(target: "terminator", "call", name).entered();
+        }
+    });
+
+    let records = tracer.records();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].args["name"], "locator");
+    let lines = tracer.to_json_lines().unwrap();
+    let parsed = parse_json_lines(&lines).unwrap();
+    assert_eq!(parsed.len(), records.len());
+    for (parsed, record) in parsed.into_iter().zip(&records) {
+        // Parsed floats may differ from the original in the last bit
+        assert!((parsed.duration_ms - record.duration_ms).abs() < 1e-9);
+        assert_eq!(TraceRecord { duration_ms: record.duration_ms, ..parsed }, *record);
+    }
+    assert!(parse_json_lines("{not json").is_err());
+
+    tracer.clear();
+    assert!(tracer.records().is_empty());
+}
+
+#[test]
+fn test_otlp_span_encoding() {
+    let tracer = SessionTracer::new();
+    run_traced(&tracer, || {
+        let _outer = info_span!(target: "terminator", "outer").entered();
+        let _inner = info_span!(target: "terminator", "inner", retries = 2u64).entered();
+    });
+    let records = tracer.records();
+    let span = otlp_span(tracer.session_id(), &records[0]);
+
+    assert_eq!(tracer.session_id().len(), 32);
+    assert_eq!(span["traceId"], tracer.session_id());
+    assert_eq!(span["name"], "inner");
+    assert_eq!(span["parentSpanId"], format!("{:016x}", records[1].id));
+    assert_eq!(span["status"]["code"], 1);
+    let attributes = span["attributes"].as_array().unwrap();
+    assert!(attributes.iter().any(|a| a["key"] == "arg.retries" && a["value"]["intValue"] == "2"));
+
+    let export = tracer.to_otlp_json();
+    assert_eq!(export["resourceSpans"][0]["scopeSpans"][0]["spans"].as_array().unwrap().len(), 2);
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use crate::{SubtreeStatus, UINode};
+
+// With ids, so they are part of the round trip
+fn node(role: &str, name: &str, children: Vec<UINode>) -> UINode {
+    UINode {
+        id: Some(format!("{}-{}", role, name)),
+        ..super::node(role, name, children)
+    }
+}
+
+fn sample_tree() -> UINode {
+    let mut edit = node("Edit", "Amount", vec![]);
+    edit.attributes.value = Some("42".to_string());
+    edit.attributes.is_keyboard_focusable = Some(true);
+    edit.attributes.properties = HashMap::from([
+        ("AutomationId".to_string(), Some(serde_json::json!("amount"))),
+        ("IsPassword".to_string(), Some(serde_json::json!(false))),
+    ]);
+    edit.path = Some("/Window[name='App']/Edit[1]".to_string());
+    // Repeated roles and names are what interning saves on
+    let items = (0..20)
+        .map(|i| node("ListItem", &format!("Item {}", i % 4), vec![]))
+        .collect();
+    let mut list = node("List", "Items", items);
+    list.status = SubtreeStatus::TimedOut;
+    list.truncated = true;
+    let mut root = node("Window", "App", vec![edit, list]);
+    root.truncated = true;
+    root
+}
+
+#[test]
+fn test_snapshot_round_trip() {
+    let tree = sample_tree();
+    let restored = UINode::from_snapshot(&tree.to_snapshot().unwrap()).unwrap();
+    assert_eq!(
+        serde_json::to_value(&restored).unwrap(),
+        serde_json::to_value(&tree).unwrap()
+    );
+}
+
+#[test]
+fn test_snapshot_is_smaller_than_json() {
+    let tree = sample_tree();
+    let snapshot = tree.to_snapshot().unwrap();
+    let json = serde_json::to_vec(&tree).unwrap();
+    assert!(snapshot.len() < json.len() / 2, "{} vs {} bytes", snapshot.len(), json.len());
+}
+
+#[test]
+fn test_snapshot_rejects_foreign_bytes() {
+    assert!(UINode::from_snapshot(b"{\"attributes\":{}}").is_err());
+    let mut snapshot = sample_tree().to_snapshot().unwrap();
+    snapshot[4] = 99;
+    assert!(UINode::from_snapshot(&snapshot).is_err());
+}
+
+#[test]
+fn test_load_reads_snapshots_and_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let tree = sample_tree();
+
+    let binary = dir.path().join("tree.uitree");
+    tree.save(&binary).unwrap();
+    let json = dir.path().join("tree.json");
+    std::fs::write(&json, serde_json::to_vec(&tree).unwrap()).unwrap();
+
+    for path in [binary, json] {
+        let loaded = UINode::load(&path).unwrap();
+        assert_eq!(loaded.children.len(), 2);
+        assert_eq!(loaded.children[1].status, SubtreeStatus::TimedOut);
+    }
+}
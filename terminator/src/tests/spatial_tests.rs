@@ -0,0 +1,103 @@
+use crate::geometry::Rect;
+use crate::spatial::{anchor_selector, split_trailing, Relation};
+use crate::Selector;
+
+// A 100x20 label at (100, 100)
+fn label() -> Rect {
+    Rect::new(100.0, 100.0, 100.0, 20.0)
+}
+
+#[test]
+fn test_right_of_requires_same_row() {
+    let edit = Rect::new(210.0, 98.0, 150.0, 24.0);
+    assert_eq!(Relation::RightOf.distance(&edit, &label()), Some(10.0));
+    assert_eq!(Relation::LeftOf.distance(&edit, &label()), None);
+
+    // Right of the label but on the next row
+    let next_row = Rect::new(210.0, 130.0, 150.0, 24.0);
+    assert_eq!(Relation::RightOf.distance(&next_row, &label()), None);
+}
+
+#[test]
+fn test_below_requires_same_column() {
+    let below = Rect::new(120.0, 140.0, 50.0, 20.0);
+    assert_eq!(Relation::Below.distance(&below, &label()), Some(20.0));
+    assert_eq!(Relation::Above.distance(&below, &label()), None);
+
+    let elsewhere = Rect::new(400.0, 140.0, 50.0, 20.0);
+    assert_eq!(Relation::Below.distance(&elsewhere, &label()), None);
+}
+
+#[test]
+fn test_near_and_containers() {
+    // 30 px right and 40 px below the label's bottom-right corner
+    let diagonal = Rect::new(230.0, 160.0, 10.0, 10.0);
+    assert_eq!(Relation::Near(50.0).distance(&diagonal, &label()), Some(50.0));
+    assert_eq!(Relation::Near(49.0).distance(&diagonal, &label()), None);
+
+    // The window around the label is never a match
+    let window = Rect::new(0.0, 0.0, 800.0, 600.0);
+    assert_eq!(Relation::Near(50.0).distance(&window, &label()), None);
+    assert_eq!(Relation::RightOf.distance(&Rect::default(), &label()), None);
+}
+
+#[test]
+fn test_spatial_selector_parsing() {
+    assert_eq!(
+        Selector::from("rightof:name:Name"),
+        Selector::RightOf(Box::new(Selector::Name("Name".to_string())))
+    );
+    assert!(matches!(
+        Selector::from("near:role:button:OK"),
+        Selector::Near { max_distance: 50, .. }
+    ));
+}
+
+#[test]
+fn test_split_trailing() {
+    let chain = Selector::Chain(vec![
+        Selector::from("window:Form"),
+        Selector::from("role:edit"),
+        Selector::from("rightof:name:Name"),
+        Selector::from("below:name:Contact"),
+    ]);
+    let (target, steps) = split_trailing(&chain).unwrap();
+    assert_eq!(
+        target,
+        Selector::Chain(vec![Selector::from("window:Form"), Selector::from("role:edit")])
+    );
+    assert_eq!(steps.len(), 2);
+
+    let (target, _) =
+        split_trailing(&Selector::Chain(vec![Selector::from("role:edit"), Selector::from("above:name:OK")]))
+            .unwrap();
+    assert_eq!(target, Selector::from("role:edit"));
+
+    // Nothing to narrow, or no spatial step at the end
+    assert!(split_trailing(&Selector::from("rightof:name:Name")).is_none());
+    assert!(split_trailing(&Selector::Chain(vec![Selector::from("role:edit")])).is_none());
+}
+
+#[test]
+fn test_anchor_is_searched_where_the_candidates_are() {
+    // Two windows both have a "Name" label: the anchor must be the one in
+    // the window the edit boxes are searched in
+    let chain = Selector::Chain(vec![
+        Selector::from("window:Customer B"),
+        Selector::from("role:edit"),
+        Selector::from("rightof:name:Name"),
+    ]);
+    let (target, steps) = split_trailing(&chain).unwrap();
+    let (_, anchor) = Relation::of(&steps[0]).unwrap();
+    assert_eq!(
+        anchor_selector(&target, anchor),
+        Selector::Chain(vec![Selector::from("window:Customer B"), Selector::from("name:Name")])
+    );
+
+    // Without a prefix the anchor is searched from the root
+    let (target, steps) =
+        split_trailing(&Selector::Chain(vec![Selector::from("role:edit"), Selector::from("rightof:name:Name")]))
+            .unwrap();
+    let (_, anchor) = Relation::of(&steps[0]).unwrap();
+    assert_eq!(anchor_selector(&target, anchor), Selector::from("name:Name"));
+}
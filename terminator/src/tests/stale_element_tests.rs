@@ -0,0 +1,35 @@
+use crate::{AutomationError, ElementDescriptor, ErrorCode};
+
+fn descriptor(name: Option<&str>) -> ElementDescriptor {
+    ElementDescriptor {
+        path: "/Window[name='Inbox']/List/ListItem[name='Re: invoice'][2]".to_string(),
+        role: "ListItem".to_string(),
+        name: name.map(str::to_string),
+    }
+}
+
+#[test]
+fn test_descriptor_display_names_the_element() {
+    assert_eq!(descriptor(Some("Re: invoice")).to_string(), "ListItem 'Re: invoice'");
+    assert_eq!(descriptor(None).to_string(), "ListItem");
+}
+
+#[test]
+fn test_descriptor_round_trips_through_json() {
+    let json = serde_json::to_value(descriptor(None)).unwrap();
+    assert!(json.get("name").is_none(), "Missing names are left out");
+    let back: ElementDescriptor = serde_json::from_value(json).unwrap();
+    assert_eq!(back, descriptor(None));
+
+    let named = descriptor(Some("Re: invoice"));
+    let back: ElementDescriptor = serde_json::from_str(&serde_json::to_string(&named).unwrap()).unwrap();
+    assert_eq!(back, named);
+}
+
+#[test]
+fn test_stale_element_error() {
+    let error = AutomationError::StaleElement("ListItem 'Re: invoice' no longer exists".into());
+    assert_eq!(error.code(), ErrorCode::StaleElement);
+    assert_eq!(error.to_string(), "Stale element: ListItem 'Re: invoice' no longer exists");
+    assert_eq!(error.detail(), "ListItem 'Re: invoice' no longer exists");
+}
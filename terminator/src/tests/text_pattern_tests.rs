@@ -0,0 +1,52 @@
+use crate::text_pattern::{check_range, colorref_to_hex, merge_runs};
+use crate::{AutomationError, TextRun};
+
+fn run(text: &str, start: usize, bold: bool) -> TextRun {
+    TextRun {
+        text: text.to_string(),
+        start,
+        font_name: Some("Calibri".to_string()),
+        font_size: Some(11.0),
+        bold,
+        italic: false,
+        underline: false,
+        foreground_color: Some("#000000".to_string()),
+        background_color: None,
+    }
+}
+
+#[test]
+fn test_merge_runs_joins_same_format() {
+    let runs = merge_runs(vec![
+        run("Hello ", 0, false),
+        run("wor", 6, false),
+        run("", 9, true),
+        run("ld", 9, false),
+        run("!", 11, true),
+    ]);
+    assert_eq!(runs, vec![run("Hello world", 0, false), run("!", 11, true)]);
+    assert_eq!(runs[0].end(), 11);
+}
+
+#[test]
+fn test_merge_runs_keeps_gaps() {
+    // Runs that do not touch stay apart even with the same format
+    let runs = merge_runs(vec![run("ab", 0, false), run("cd", 5, false)]);
+    assert_eq!(runs.len(), 2);
+}
+
+#[test]
+fn test_colorref_to_hex() {
+    assert_eq!(colorref_to_hex(0x000000ff), "#ff0000");
+    assert_eq!(colorref_to_hex(0x00ff0000), "#0000ff");
+    assert_eq!(colorref_to_hex(0x00123456), "#563412");
+}
+
+#[test]
+fn test_check_range_rejects_reversed_bounds() {
+    assert!(check_range(2, 2).is_ok());
+    assert!(matches!(
+        check_range(5, 2),
+        Err(AutomationError::InvalidArgument(_))
+    ));
+}
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use crate::platforms::{TreeBuildConfig, TreeBuildStats};
+use crate::{SubtreeStatus, UINode};
+
+fn node(role: &str, status: SubtreeStatus, children: Vec<UINode>) -> UINode {
+    UINode {
+        truncated: !status.is_complete(),
+        status,
+        ..super::node(role, "", children)
+    }
+}
+
+#[test]
+fn test_stats_read_off_a_tree() {
+    let tree = node(
+        "Window",
+        SubtreeStatus::Complete,
+        vec![
+            node(
+                "Pane",
+                SubtreeStatus::Complete,
+                vec![node("Button", SubtreeStatus::Complete, vec![])],
+            ),
+            node("List", SubtreeStatus::TimedOut, vec![]),
+        ],
+    );
+    let stats = TreeBuildStats::for_tree(&tree, Duration::from_millis(42));
+    assert_eq!(stats.elements, 4);
+    assert_eq!(stats.max_depth, 2);
+    assert_eq!(stats.truncated_subtrees, 1);
+    assert_eq!(stats.duration_ms, 42);
+    // Nothing is known about caching from the tree alone
+    assert_eq!(stats.cached_elements, 0);
+    assert_eq!(stats.cache_hit_rate(), 0.0);
+}
+
+#[test]
+fn test_cache_hit_rate() {
+    let stats = TreeBuildStats {
+        elements: 200,
+        cached_elements: 150,
+        ..Default::default()
+    };
+    assert_eq!(stats.cache_hit_rate(), 0.75);
+}
+
+#[test]
+fn test_cache_requests_on_by_default() {
+    assert!(TreeBuildConfig::default().use_cache_request);
+}
@@ -0,0 +1,52 @@
+use super::node;
+
+#[test]
+fn test_diff_reports_added_dialog() {
+    let before = node("Window", "App", vec![node("Button", "Delete", vec![])]);
+    let after = node(
+        "Window",
+        "App",
+        vec![
+            node("Button", "Delete", vec![]),
+            node("Window", "Confirm", vec![node("Button", "OK", vec![])]),
+        ],
+    );
+
+    let diff = before.diff(&after);
+    assert!(diff.removed.is_empty());
+    assert!(diff.changed.is_empty());
+    let added = diff.find_added("window", Some("Confirm")).expect("dialog added");
+    assert_eq!(added.path, "Window[App]/Window[Confirm]");
+    assert_eq!(added.node.children.len(), 1);
+}
+
+#[test]
+fn test_diff_reports_removed_and_changed_nodes() {
+    let mut edit = node("Edit", "Search", vec![]);
+    let before = node("Window", "App", vec![edit.clone(), node("Text", "Hint", vec![])]);
+    edit.attributes.value = Some("hello".to_string());
+    let after = node("Window", "App", vec![edit]);
+
+    let diff = before.diff(&after);
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(diff.removed[0].path, "Window[App]/Text[Hint]");
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].path, "Window[App]/Edit[Search]");
+    assert_eq!(diff.changed[0].changes[0].attribute, "value");
+    assert_eq!(diff.changed[0].changes[0].after.as_deref(), Some("hello"));
+}
+
+#[test]
+fn test_diff_matches_duplicate_siblings_by_position() {
+    let before = node("List", "Items", vec![node("ListItem", "Row", vec![])]);
+    let after = node(
+        "List",
+        "Items",
+        vec![node("ListItem", "Row", vec![]), node("ListItem", "Row", vec![])],
+    );
+
+    let diff = before.diff(&after);
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.added[0].path, "List[Items]/ListItem[Row#1]");
+    assert!(before.diff(&before).is_empty());
+}
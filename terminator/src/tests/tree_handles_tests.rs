@@ -0,0 +1,56 @@
+use crate::{UINode, UINodeWithHandles};
+
+use super::node;
+
+fn sample() -> UINode {
+    node(
+        "Window",
+        "App",
+        vec![
+            node(
+                "Pane",
+                "Body",
+                vec![
+                    node("Edit", "Name", vec![]),
+                    node("Button", "Save", vec![node("Text", "Save", vec![])]),
+                ],
+            ),
+            node("Pane", "Footer", vec![node("Hyperlink", "Help", vec![])]),
+        ],
+    )
+}
+
+fn name(node: &UINode) -> &str {
+    node.attributes.name.as_deref().unwrap_or_default()
+}
+
+#[test]
+fn test_nth_node_counts_depth_first() {
+    let tree = sample();
+    let names: Vec<&str> = (0..tree.count())
+        .map(|i| name(tree.nth_node(i).unwrap()))
+        .collect();
+    assert_eq!(names, ["App", "Body", "Name", "Save", "Save", "Footer", "Help"]);
+    assert!(tree.nth_node(tree.count()).is_none());
+}
+
+#[test]
+fn test_node_number_matches_index_path() {
+    let tree = sample();
+    assert_eq!(tree.node_number(&[]), Some(0));
+    assert_eq!(tree.node_number(&[0, 1, 0]), Some(4));
+    assert_eq!(tree.node_number(&[1, 0]), Some(6));
+    assert_eq!(tree.node_number(&[2]), None);
+    for path in [vec![0], vec![0, 1], vec![1]] {
+        let number = tree.node_number(&path).unwrap();
+        assert_eq!(
+            name(tree.nth_node(number).unwrap()),
+            name(tree.node_at(&path).unwrap())
+        );
+    }
+}
+
+#[test]
+fn test_handles_must_cover_every_node() {
+    assert!(UINodeWithHandles::new(sample(), Vec::new()).is_err());
+}
@@ -0,0 +1,73 @@
+use crate::tree_query::{is_interactive, INTERACTIVE_ROLES};
+use crate::UINode;
+
+use super::node;
+
+fn sample() -> UINode {
+    node(
+        "Window",
+        "App",
+        vec![
+            node(
+                "Pane",
+                "Body",
+                vec![
+                    node("Group", "Form", vec![node("Edit", "Name", vec![]), node("Text", "Hint", vec![])]),
+                    node("Button", "Save", vec![node("Text", "Save", vec![])]),
+                ],
+            ),
+            node("Pane", "Footer", vec![node("Hyperlink", "Help", vec![])]),
+        ],
+    )
+}
+
+#[test]
+fn test_query_returns_index_paths() {
+    let tree = sample();
+    let matches = tree.query(|n| n.attributes.role == "Text");
+    let paths: Vec<_> = matches.iter().map(|m| m.index_path.clone()).collect();
+    assert_eq!(paths, vec![vec![0, 0, 1], vec![0, 1, 0]]);
+    assert!(matches[0].node.children.is_empty());
+    assert_eq!(tree.node_at(&paths[1]).unwrap().attributes.name.as_deref(), Some("Save"));
+}
+
+#[test]
+fn test_prune_lifts_kept_nodes_to_nearest_kept_ancestor() {
+    let tree = sample();
+    let pruned = tree.prune(&["button", "edit", "text"], None);
+
+    let roles: Vec<_> = pruned.tree.children.iter().map(|n| n.attributes.role.as_str()).collect();
+    assert_eq!(roles, vec!["Edit", "Text", "Button"]);
+    assert_eq!(pruned.tree.children[2].children[0].attributes.role, "Text");
+    assert_eq!(pruned.kept_count, 5);
+    assert_eq!(pruned.original_count, 9);
+
+    assert_eq!(pruned.origin(&[]), Some(&[][..]));
+    assert_eq!(pruned.origin(&[2]), Some(&[0, 1][..]));
+    assert_eq!(pruned.origin(&[2, 0]), Some(&[0, 1, 0][..]));
+    for (pruned_path, original_path) in &pruned.origins {
+        assert_eq!(
+            pruned.tree.node_at(pruned_path).unwrap().attributes.name,
+            tree.node_at(original_path).unwrap().attributes.name
+        );
+    }
+}
+
+#[test]
+fn test_prune_respects_max_depth() {
+    let pruned = sample().prune(INTERACTIVE_ROLES, Some(2));
+    let names: Vec<_> = pruned
+        .tree
+        .children
+        .iter()
+        .map(|n| n.attributes.name.as_deref().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Save", "Help"]);
+}
+
+#[test]
+fn test_filter_interactive() {
+    let pruned = sample().filter(is_interactive, None);
+    assert_eq!(pruned.kept_count, 4);
+    assert_eq!(pruned.origin(&[0]), Some(&[0, 0, 0][..]));
+}
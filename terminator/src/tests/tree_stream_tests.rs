@@ -0,0 +1,78 @@
+use crate::tree_stream::{TreeAssembler, TreeChunk};
+use crate::{SubtreeStatus, UINode};
+
+use super::node;
+
+fn leaf(role: &str, name: &str) -> UINode {
+    node(role, name, vec![])
+}
+
+fn chunk(parent: Option<usize>, first_index: usize, nodes: Vec<UINode>) -> TreeChunk {
+    TreeChunk {
+        parent,
+        first_index,
+        depth: 0,
+        nodes,
+        status: SubtreeStatus::Complete,
+    }
+}
+
+fn names(node: &UINode) -> Vec<String> {
+    node.children
+        .iter()
+        .map(|c| c.attributes.name.clone().unwrap_or_default())
+        .collect()
+}
+
+#[test]
+fn test_assembler_rebuilds_breadth_first_chunks() {
+    let mut assembler = TreeAssembler::new();
+    assembler.push(chunk(None, 0, vec![leaf("Window", "App")])).unwrap();
+    // The window's children split over two chunks
+    assembler
+        .push(chunk(Some(0), 1, vec![leaf("Pane", "Body"), leaf("Pane", "Footer")]))
+        .unwrap();
+    assembler.push(chunk(Some(0), 3, vec![leaf("Button", "Close")])).unwrap();
+    assembler
+        .push(chunk(Some(1), 4, vec![leaf("Edit", "Name"), leaf("Button", "Save")]))
+        .unwrap();
+    assembler.push(chunk(Some(2), 6, vec![leaf("Hyperlink", "Help")])).unwrap();
+
+    let tree = assembler.finish().unwrap();
+    assert_eq!(names(&tree), ["Body", "Footer", "Close"]);
+    assert_eq!(names(&tree.children[0]), ["Name", "Save"]);
+    assert_eq!(names(&tree.children[1]), ["Help"]);
+    assert!(tree.children[2].children.is_empty());
+    assert!(!tree.truncated);
+}
+
+#[test]
+fn test_assembler_marks_unread_subtrees() {
+    let mut assembler = TreeAssembler::new();
+    assembler.push(chunk(None, 0, vec![leaf("Window", "App")])).unwrap();
+    assembler
+        .push(chunk(Some(0), 1, vec![leaf("Pane", "Body"), leaf("Pane", "Footer")]))
+        .unwrap();
+    assembler
+        .push(TreeChunk {
+            status: SubtreeStatus::TimedOut,
+            ..chunk(Some(2), 3, vec![])
+        })
+        .unwrap();
+
+    let tree = assembler.finish().unwrap();
+    assert!(tree.truncated);
+    assert!(tree.status.is_complete());
+    assert!(!tree.children[0].truncated);
+    assert_eq!(tree.children[1].status, SubtreeStatus::TimedOut);
+    assert!(tree.children[1].truncated);
+}
+
+#[test]
+fn test_assembler_rejects_out_of_order_chunks() {
+    let mut assembler = TreeAssembler::new();
+    assert!(assembler.push(chunk(Some(0), 0, vec![leaf("Pane", "Body")])).is_err());
+    assembler.push(chunk(None, 0, vec![leaf("Window", "App")])).unwrap();
+    assert!(assembler.push(chunk(Some(0), 5, vec![leaf("Pane", "Body")])).is_err());
+    assert!(TreeAssembler::new().finish().is_none());
+}
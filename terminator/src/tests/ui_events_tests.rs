@@ -0,0 +1,38 @@
+use std::time::{Duration, Instant};
+
+use crate::ui_events::QuietPeriod;
+
+const PERIOD: Duration = Duration::from_millis(500);
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+#[test]
+fn test_quiet_period_counts_from_the_last_change() {
+    let start = Instant::now();
+    let quiet = QuietPeriod::new(42, PERIOD, start);
+    assert_eq!(quiet.remaining(start), Some(PERIOD));
+    assert_eq!(quiet.remaining(start + ms(200)), Some(ms(300)));
+    assert_eq!(quiet.remaining(start + PERIOD), None);
+
+    quiet.record(Some(42), start + ms(400));
+    assert_eq!(quiet.remaining(start + PERIOD), Some(ms(400)));
+    assert_eq!(quiet.remaining(start + ms(900)), None);
+}
+
+#[test]
+fn test_quiet_period_ignores_other_processes() {
+    let start = Instant::now();
+    let quiet = QuietPeriod::new(42, PERIOD, start);
+    quiet.record(Some(7), start + ms(300));
+    assert_eq!(quiet.remaining(start + PERIOD), None);
+
+    // Events whose element is gone may still come from the app
+    quiet.record(None, start + ms(300));
+    assert_eq!(quiet.remaining(start + PERIOD), Some(ms(300)));
+
+    // Late delivery of an older event does not move the last change back
+    quiet.record(Some(42), start + ms(100));
+    assert_eq!(quiet.remaining(start + PERIOD), Some(ms(300)));
+}
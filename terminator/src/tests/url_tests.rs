@@ -0,0 +1,20 @@
+use crate::utils::{normalize_url, url_host, url_matches};
+
+#[test]
+fn test_normalize_url() {
+    assert_eq!(normalize_url("https://www.Example.com/"), "example.com");
+    assert_eq!(normalize_url("example.com"), "example.com");
+    assert_eq!(normalize_url(" http://example.com/docs/ "), "example.com/docs");
+    assert_eq!(url_host("https://www.example.com:8080/path?q=1"), "example.com");
+    assert_eq!(url_host("example.com#top"), "example.com");
+}
+
+#[test]
+fn test_url_matches() {
+    assert!(url_matches("https://www.example.com/", "example.com"));
+    assert!(url_matches("example.com/en/welcome", "https://example.com"));
+    assert!(url_matches("https://example.com/?ref=1", "example.com"));
+    assert!(!url_matches("https://example.com.evil.org/", "example.com"));
+    assert!(!url_matches("https://other.com/", "example.com"));
+    assert!(!url_matches("https://example.com/", ""));
+}
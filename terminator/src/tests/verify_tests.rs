@@ -0,0 +1,28 @@
+use crate::verify::SoftVerifier;
+use crate::AutomationError;
+
+#[test]
+fn test_soft_verifier_collects_all_failures() {
+    let mut verify = SoftVerifier::new("summary screen");
+    assert!(verify.check("title shown", true, ""));
+    assert!(!verify.check("total is 42", false, "got 41"));
+    assert!(verify
+        .check_result("load totals", Err::<(), _>(AutomationError::Timeout("slow".into())))
+        .is_none());
+
+    assert_eq!(verify.check_count(), 3);
+    assert_eq!(verify.failures().len(), 2);
+
+    let err = verify.finish().unwrap_err();
+    let report = err.verification_report().expect("report attached");
+    assert_eq!(report.step, "summary screen");
+    assert_eq!(report.failures[0].check, "total is 42");
+    assert!(err.to_string().contains("2 of 3 checks failed"));
+}
+
+#[test]
+fn test_soft_verifier_passes_without_failures() {
+    let mut verify = SoftVerifier::new("empty");
+    verify.check("always", true, "");
+    assert!(verify.finish().is_ok());
+}
@@ -0,0 +1,24 @@
+use crate::virtual_desktop::{format_guid, parse_guid, parse_guid_list};
+
+const ID: u128 = 0x2B2B1AE6_7BA0_4FC4_9F0E_4E7A0C1B6E0F;
+
+#[test]
+fn test_format_and_parse_guid() {
+    assert_eq!(format_guid(ID), "{2B2B1AE6-7BA0-4FC4-9F0E-4E7A0C1B6E0F}");
+    assert_eq!(parse_guid("{2B2B1AE6-7BA0-4FC4-9F0E-4E7A0C1B6E0F}"), Some(ID));
+    assert_eq!(parse_guid("2b2b1ae6-7ba0-4fc4-9f0e-4e7a0c1b6e0f"), Some(ID));
+    assert_eq!(parse_guid("2B2B1AE6-7BA0-4FC4-9F0E"), None);
+    assert_eq!(parse_guid("{2B2B1AE6-7BA0-4FC4-9F0E-4E7A0C1B6E0G}"), None);
+}
+
+#[test]
+fn test_parse_guid_list_uses_binary_layout() {
+    let mut bytes = vec![
+        0xE6, 0x1A, 0x2B, 0x2B, 0xA0, 0x7B, 0xC4, 0x4F, 0x9F, 0x0E, 0x4E, 0x7A, 0x0C, 0x1B, 0x6E, 0x0F,
+    ];
+    bytes.extend_from_slice(&[0u8; 15]);
+    bytes.push(1);
+    // A trailing partial GUID is ignored
+    bytes.extend_from_slice(&[0xFF; 3]);
+    assert_eq!(parse_guid_list(&bytes), vec![ID, 1]);
+}
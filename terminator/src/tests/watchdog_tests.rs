@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use crate::watchdog::{self, DiagnosticBundle, WatchdogState};
+use crate::ErrorCode;
+
+fn bundle() -> DiagnosticBundle {
+    DiagnosticBundle {
+        idle_for: Duration::from_secs(90),
+        screenshot: None,
+        foreground_window: None,
+        active_app_tree: None,
+        errors: Vec::new(),
+    }
+}
+
+#[test]
+fn test_abort_lasts_until_reset() {
+    let state = WatchdogState::new();
+    assert!(state.check_stalled().is_ok());
+
+    state.abort(bundle());
+    assert!(state.is_stalled());
+    // Unrelated actions succeeding do not lift the abort
+    state.record_activity();
+    watchdog::record_activity();
+    let error = state.check_stalled().unwrap_err();
+    assert_eq!(error.code(), ErrorCode::StalledRun);
+
+    state.reset();
+    assert!(state.check_stalled().is_ok());
+    assert_eq!(state.last_stall_diagnostics().unwrap().idle_for, Duration::from_secs(90));
+}
+
+#[test]
+fn test_states_are_independent() {
+    let stalled = WatchdogState::new();
+    let other = WatchdogState::new();
+    stalled.abort(bundle());
+    assert!(stalled.is_stalled());
+    assert!(!other.is_stalled());
+    assert!(other.last_stall_diagnostics().is_none());
+}
+
+#[test]
+fn test_element_activity_reaches_every_state() {
+    let state = WatchdogState::new();
+    std::thread::sleep(Duration::from_millis(30));
+    assert!(state.idle_for() >= Duration::from_millis(30));
+    watchdog::record_activity();
+    assert!(state.idle_for() < Duration::from_millis(30));
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use crate::workflow::{expand_variables, StepCondition, Workflow, WorkflowStep};
+use crate::AutomationError;
+
+const WORKFLOW: &str = r#"{
+    "name": "Save a note",
+    "variables": {"note": "Hello"},
+    "steps": [
+        {"action": "click", "selector_chain": ["name:Untitled - Notepad", "role:Document"]},
+        {"action": "type", "text": "${note}"},
+        {"action": "if",
+         "condition": {"exists": {"selector_chain": ["role:Window", "name:Save As"]}},
+         "then": [{"action": "press_key", "key": "{Enter}"}],
+         "else": [{"action": "set", "variable": "saved", "value": "no"}]},
+        {"action": "wait", "duration_ms": 500}
+    ]
+}"#;
+
+#[test]
+fn test_workflow_from_json() {
+    let workflow = Workflow::from_json(WORKFLOW).unwrap();
+    assert_eq!(workflow.name.as_deref(), Some("Save a note"));
+    assert!(workflow.stop_on_error);
+    assert_eq!(workflow.steps.len(), 4);
+    assert_eq!(workflow.step_count(), 6);
+    assert_eq!(
+        workflow.steps[1],
+        WorkflowStep::Type { selector_chain: None, text: "${note}".to_string(), timeout_ms: None }
+    );
+    let WorkflowStep::If { condition, then, otherwise } = &workflow.steps[2] else {
+        panic!("expected an if step, got {:?}", workflow.steps[2]);
+    };
+    assert!(matches!(condition, StepCondition::Exists { timeout_ms: None, .. }));
+    assert_eq!(then[0].action(), "press_key");
+    assert_eq!(otherwise[0].action(), "set");
+    assert!(workflow.validate().is_ok());
+
+    // Serializes to the format it is read from
+    let json = serde_json::to_value(&workflow).unwrap();
+    assert_eq!(json["steps"][0]["action"], "click");
+    assert_eq!(json["steps"][2]["else"][0]["variable"], "saved");
+    assert_eq!(Workflow::from_json(&json.to_string()).unwrap(), workflow);
+}
+
+#[test]
+fn test_workflow_rejects_unknown_actions() {
+    let err = Workflow::from_json(r#"{"steps": [{"action": "drag"}]}"#).unwrap_err();
+    assert!(matches!(err, AutomationError::InvalidArgument(_)));
+}
+
+#[test]
+fn test_workflow_validate() {
+    let workflow = |json: &str| Workflow::from_json(json).unwrap().validate();
+
+    assert!(workflow(r#"{"steps": []}"#).is_err());
+    // Nothing located yet
+    let err = workflow(r#"{"steps": [{"action": "type", "text": "x"}]}"#).unwrap_err();
+    assert!(err.to_string().contains("Step 0 (type)"), "{}", err);
+    assert!(workflow(r#"{"steps": [{"action": "locate", "selector_chain": []}]}"#).is_err());
+    assert!(
+        workflow(r#"{"steps": [{"action": "open_application", "app": "notepad"}, {"action": "type", "text": "x"}]}"#)
+            .is_ok()
+    );
+    // Only one branch locates an element
+    let err = workflow(
+        r#"{"steps": [
+            {"action": "if", "condition": {"equals": {"variable": "a", "value": "b"}},
+             "then": [{"action": "locate", "selector_chain": ["name:OK"]}]},
+            {"action": "click"}
+        ]}"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("Step 1 (click)"), "{}", err);
+    // Nested steps are checked too
+    let err = workflow(
+        r#"{"steps": [
+            {"action": "if", "condition": {"not_equals": {"variable": "a", "value": "b"}},
+             "else": [{"action": "click"}]}
+        ]}"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("Step 0.else.0 (click)"), "{}", err);
+}
+
+#[test]
+fn test_expand_variables() {
+    let variables = BTreeMap::from([
+        ("name".to_string(), "Ada".to_string()),
+        ("file".to_string(), "notes.txt".to_string()),
+    ]);
+    assert_eq!(
+        expand_variables("Hello ${name}, saving ${ file }", &variables).unwrap(),
+        "Hello Ada, saving notes.txt"
+    );
+    assert_eq!(expand_variables("no variables", &variables).unwrap(), "no variables");
+    assert_eq!(expand_variables("$name {name}", &variables).unwrap(), "$name {name}");
+    assert!(expand_variables("${missing}", &variables).is_err());
+    assert!(expand_variables("${name", &variables).is_err());
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_workflow_from_yaml() {
+    let workflow = Workflow::from_yaml(
+        r#"
+stop_on_error: false
+steps:
+  - action: open_application
+    app: notepad
+  - action: extract
+    variable: title
+    property: name
+  - action: assert
+    text_contains: "${title}"
+"#,
+    )
+    .unwrap();
+    assert!(!workflow.stop_on_error);
+    assert_eq!(
+        workflow.steps[1],
+        WorkflowStep::Extract {
+            selector_chain: None,
+            variable: "title".to_string(),
+            property: Some("name".to_string()),
+            timeout_ms: None,
+        }
+    );
+    assert!(workflow.validate().is_ok());
+}
@@ -0,0 +1,102 @@
+//! Structured text of editors and documents
+//!
+//! [`UIElement::text`](crate::UIElement::text) flattens an element to plain
+//! strings. Rich edit controls, Word and browsers' editable regions also
+//! expose their content through a text pattern (UIA TextPattern on
+//! Windows), which keeps the selection, the caret and the formatting:
+//!
+//! ```no_run
+//! # use terminator::UIElement;
+//! # fn run(editor: &UIElement) -> Result<(), terminator::AutomationError> {
+//! editor.select_range(0, 5)?;
+//! assert_eq!(editor.selected_text()?, "Hello");
+//! for run in editor.text_with_formatting()? {
+//!     if run.bold {
+//!         println!("bold at {}: {}", run.start, run.text);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Positions are character offsets from the start of the document.
+
+use serde::{Deserialize, Serialize};
+
+/// Font weight from which text counts as bold (CSS/GDI semibold)
+pub const BOLD_FONT_WEIGHT: i32 = 600;
+
+/// A stretch of text with the same formatting
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextRun {
+    pub text: String,
+    /// Character offset of the run in the document
+    pub start: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_name: Option<String>,
+    /// Size in points
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<f64>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    /// Text color as `#rrggbb`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub foreground_color: Option<String>,
+    /// Highlight color as `#rrggbb`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background_color: Option<String>,
+}
+
+impl TextRun {
+    /// Offset just past the run's last character
+    pub fn end(&self) -> usize {
+        self.start + self.text.chars().count()
+    }
+
+    /// Whether `other` is formatted the same way
+    pub fn same_format(&self, other: &TextRun) -> bool {
+        self.font_name == other.font_name
+            && self.font_size == other.font_size
+            && self.bold == other.bold
+            && self.italic == other.italic
+            && self.underline == other.underline
+            && self.foreground_color == other.foreground_color
+            && self.background_color == other.background_color
+    }
+}
+
+/// Join adjacent runs with the same formatting and drop empty ones.
+/// Platforms split formatting units on boundaries that change nothing
+/// reported here, e.g. spelling marks or a change of language
+pub fn merge_runs(runs: Vec<TextRun>) -> Vec<TextRun> {
+    let mut merged: Vec<TextRun> = Vec::with_capacity(runs.len());
+    for run in runs.into_iter().filter(|run| !run.text.is_empty()) {
+        match merged.last_mut() {
+            Some(last) if last.same_format(&run) && last.end() == run.start => {
+                last.text.push_str(&run.text);
+            }
+            _ => merged.push(run),
+        }
+    }
+    merged
+}
+
+/// `#rrggbb` for a Windows `COLORREF` (`0x00bbggrr`)
+pub fn colorref_to_hex(color: u32) -> String {
+    let red = color & 0xff;
+    let green = (color >> 8) & 0xff;
+    let blue = (color >> 16) & 0xff;
+    format!("#{:02x}{:02x}{:02x}", red, green, blue)
+}
+
+/// Check the bounds of a range to select, `start..end` in characters
+pub(crate) fn check_range(start: usize, end: usize) -> Result<(), crate::AutomationError> {
+    if start > end {
+        return Err(crate::AutomationError::InvalidArgument(format!(
+            "Range start {} is after its end {}",
+            start, end
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,191 @@
+//! Structured diffing of [`UINode`] trees
+//!
+//! Children are matched by role and name (falling back to their position
+//! among same-named siblings), so a diff reports what a user would call
+//! "a new dialog appeared" rather than a wall of index shifts.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{UIElementAttributes, UINode};
+
+/// A node present in only one of the two trees
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeChange {
+    /// Path from the root, e.g. `Window[Notepad]/Pane[0]/Button[Save]`
+    pub path: String,
+    pub role: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The whole subtree that was added or removed
+    pub node: UINode,
+}
+
+/// A single attribute whose value differs between the two trees
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeChange {
+    pub attribute: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+/// A node present in both trees with different attributes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeModification {
+    pub path: String,
+    pub role: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub changes: Vec<AttributeChange>,
+}
+
+/// Difference between two UI trees
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TreeDiff {
+    pub added: Vec<NodeChange>,
+    pub removed: Vec<NodeChange>,
+    pub changed: Vec<NodeModification>,
+}
+
+impl TreeDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// First added node with the given role (case-insensitive) and, if given, name
+    pub fn find_added(&self, role: &str, name: Option<&str>) -> Option<&NodeChange> {
+        self.added.iter().find(|c| matches_node(&c.role, c.name.as_deref(), role, name))
+    }
+
+    /// First removed node with the given role (case-insensitive) and, if given, name
+    pub fn find_removed(&self, role: &str, name: Option<&str>) -> Option<&NodeChange> {
+        self.removed.iter().find(|c| matches_node(&c.role, c.name.as_deref(), role, name))
+    }
+}
+
+fn matches_node(node_role: &str, node_name: Option<&str>, role: &str, name: Option<&str>) -> bool {
+    node_role.eq_ignore_ascii_case(role) && name.is_none_or(|n| node_name == Some(n))
+}
+
+impl UINode {
+    /// Compare this tree (before) with `other` (after)
+    pub fn diff(&self, other: &UINode) -> TreeDiff {
+        let mut diff = TreeDiff::default();
+        let path = segment(&self.attributes, 0);
+        if self.attributes.role != other.attributes.role {
+            // Different roots: nothing to match against
+            diff.removed.push(node_change(path, self));
+            diff.added.push(node_change(segment(&other.attributes, 0), other));
+            return diff;
+        }
+        diff_nodes(self, other, &path, &mut diff);
+        diff
+    }
+}
+
+fn diff_nodes(before: &UINode, after: &UINode, path: &str, diff: &mut TreeDiff) {
+    let changes = attribute_changes(&before.attributes, &after.attributes);
+    if !changes.is_empty() {
+        diff.changed.push(NodeModification {
+            path: path.to_string(),
+            role: after.attributes.role.clone(),
+            name: after.attributes.name.clone(),
+            changes,
+        });
+    }
+
+    let before_keys = child_keys(&before.children);
+    let after_keys = child_keys(&after.children);
+    let mut matched = vec![false; after.children.len()];
+
+    for (i, key) in before_keys.iter().enumerate() {
+        let child = &before.children[i];
+        let child_path = format!("{}/{}", path, key.1);
+        match after_keys.iter().position(|k| k == key) {
+            Some(j) => {
+                matched[j] = true;
+                diff_nodes(child, &after.children[j], &child_path, diff);
+            }
+            None => diff.removed.push(node_change(child_path, child)),
+        }
+    }
+    for (j, child) in after.children.iter().enumerate() {
+        if !matched[j] {
+            let child_path = format!("{}/{}", path, after_keys[j].1);
+            diff.added.push(node_change(child_path, child));
+        }
+    }
+}
+
+// (role, name, occurrence) of a child among its siblings
+type ChildKey = (String, Option<String>, usize);
+
+// The key plus the printable path segment for each child
+fn child_keys(children: &[UINode]) -> Vec<(ChildKey, String)> {
+    let mut seen: Vec<(String, Option<String>)> = Vec::new();
+    children
+        .iter()
+        .map(|child| {
+            let id = (child.attributes.role.clone(), non_empty(&child.attributes.name));
+            let occurrence = seen.iter().filter(|s| **s == id).count();
+            seen.push(id.clone());
+            let seg = segment(&child.attributes, occurrence);
+            ((id.0, id.1, occurrence), seg)
+        })
+        .collect()
+}
+
+fn segment(attrs: &UIElementAttributes, occurrence: usize) -> String {
+    match non_empty(&attrs.name) {
+        Some(name) if occurrence == 0 => format!("{}[{}]", attrs.role, name),
+        Some(name) => format!("{}[{}#{}]", attrs.role, name, occurrence),
+        None => format!("{}[{}]", attrs.role, occurrence),
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value.as_ref().filter(|v| !v.is_empty()).cloned()
+}
+
+fn node_change(path: String, node: &UINode) -> NodeChange {
+    NodeChange {
+        path,
+        role: node.attributes.role.clone(),
+        name: non_empty(&node.attributes.name),
+        node: node.clone(),
+    }
+}
+
+fn attribute_changes(before: &UIElementAttributes, after: &UIElementAttributes) -> Vec<AttributeChange> {
+    let mut changes = Vec::new();
+    let mut compare = |attribute: &str, b: Option<String>, a: Option<String>| {
+        if b != a {
+            changes.push(AttributeChange {
+                attribute: attribute.to_string(),
+                before: b,
+                after: a,
+            });
+        }
+    };
+    // Children are matched by name, so this only fires for the roots
+    compare("name", non_empty(&before.name), non_empty(&after.name));
+    compare("label", non_empty(&before.label), non_empty(&after.label));
+    compare("value", non_empty(&before.value), non_empty(&after.value));
+    compare("description", non_empty(&before.description), non_empty(&after.description));
+    compare(
+        "is_keyboard_focusable",
+        before.is_keyboard_focusable.map(|v| v.to_string()),
+        after.is_keyboard_focusable.map(|v| v.to_string()),
+    );
+
+    let mut keys: Vec<&String> = before.properties.keys().chain(after.properties.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        let b = before.properties.get(key).cloned().flatten().map(|v| v.to_string());
+        let a = after.properties.get(key).cloned().flatten().map(|v| v.to_string());
+        compare(&format!("properties.{}", key), b, a);
+    }
+    changes
+}
@@ -0,0 +1,134 @@
+//! Window trees that keep their live elements
+//!
+//! A [`UINode`] only holds attributes, so acting on a node found in a tree
+//! means locating it again by its `path`. A [`UINodeWithHandles`] keeps the
+//! element each node was read from, numbered depth-first with the window as
+//! 0, so the tree can be inspected and then acted on directly:
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop};
+//! # async fn run(desktop: &Desktop, pid: u32) -> Result<(), AutomationError> {
+//! let tree = desktop.get_window_tree_with_handles(pid, None, None)?;
+//! let save = tree
+//!     .find(|node| node.attributes.role == "Button" && node.attributes.name.as_deref() == Some("Save"))
+//!     .expect("no Save button");
+//! tree.element(save).unwrap().click()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The handles are live: an element that closed since the tree was built
+//! fails when used, and the node's `path` can locate its replacement.
+
+use crate::{AutomationError, UIElement, UINode};
+
+/// A window tree with the element behind each of its nodes
+#[derive(Debug, Clone)]
+pub struct UINodeWithHandles {
+    pub tree: UINode,
+    /// One element per node, in depth-first order
+    handles: Vec<UIElement>,
+}
+
+impl UINodeWithHandles {
+    /// Pair `tree` with its elements, listed in depth-first order
+    pub fn new(tree: UINode, handles: Vec<UIElement>) -> Result<Self, AutomationError> {
+        let count = tree.count();
+        if count != handles.len() {
+            return Err(AutomationError::Internal(format!(
+                "Tree has {} nodes but {} element handles",
+                count,
+                handles.len()
+            )));
+        }
+        Ok(Self { tree, handles })
+    }
+
+    /// Number of nodes, and of handles
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    /// The element of node number `index`
+    pub fn element(&self, index: usize) -> Option<&UIElement> {
+        self.handles.get(index)
+    }
+
+    /// Node number `index`
+    pub fn node(&self, index: usize) -> Option<&UINode> {
+        self.tree.nth_node(index)
+    }
+
+    /// The element of the node reached by following `index_path` from the
+    /// root, as used by [`UINode::node_at`]
+    pub fn element_at(&self, index_path: &[usize]) -> Option<&UIElement> {
+        self.element(self.tree.node_number(index_path)?)
+    }
+
+    /// Number of the first node, depth-first, matching `predicate`
+    pub fn find(&self, predicate: impl Fn(&UINode) -> bool) -> Option<usize> {
+        self.find_all(predicate).into_iter().next()
+    }
+
+    /// Numbers of every node matching `predicate`, depth-first
+    pub fn find_all(&self, predicate: impl Fn(&UINode) -> bool) -> Vec<usize> {
+        let mut numbers = Vec::new();
+        let mut next = 0;
+        collect_numbers(&self.tree, &predicate, &mut next, &mut numbers);
+        numbers
+    }
+
+    /// The tree and its elements, in depth-first order
+    pub fn into_parts(self) -> (UINode, Vec<UIElement>) {
+        (self.tree, self.handles)
+    }
+}
+
+impl UINode {
+    /// Node number `index` counting depth-first from this one, which is 0
+    pub fn nth_node(&self, index: usize) -> Option<&UINode> {
+        if index == 0 {
+            return Some(self);
+        }
+        let mut first = 1;
+        for child in &self.children {
+            let count = child.count();
+            if index < first + count {
+                return child.nth_node(index - first);
+            }
+            first += count;
+        }
+        None
+    }
+
+    /// Depth-first number of the node reached by following `index_path`
+    pub fn node_number(&self, index_path: &[usize]) -> Option<usize> {
+        let mut node = self;
+        let mut number = 0;
+        for &index in index_path {
+            let child = node.children.get(index)?;
+            number += 1 + node.children[..index].iter().map(UINode::count).sum::<usize>();
+            node = child;
+        }
+        Some(number)
+    }
+}
+
+fn collect_numbers(
+    node: &UINode,
+    predicate: &impl Fn(&UINode) -> bool,
+    next: &mut usize,
+    numbers: &mut Vec<usize>,
+) {
+    if predicate(node) {
+        numbers.push(*next);
+    }
+    *next += 1;
+    for child in &node.children {
+        collect_numbers(child, predicate, next, numbers);
+    }
+}
@@ -0,0 +1,239 @@
+//! Filtering of [`UINode`] trees down to the nodes worth looking at
+//!
+//! A serialized window tree is mostly panes, groups and layout containers.
+//! [`UINode::prune`] keeps the nodes with the given roles and lifts them to
+//! their nearest kept ancestor, so the tree handed to a model or a log keeps
+//! its shape but loses the noise:
+//!
+//! ```no_run
+//! # use terminator::UINode;
+//! # fn run(tree: &UINode) {
+//! use terminator::tree_query::INTERACTIVE_ROLES;
+//!
+//! let pruned = tree.prune(INTERACTIVE_ROLES, None);
+//! // Index path of the first kept control in `tree`
+//! let original = pruned.origin(&[0]);
+//! # }
+//! ```
+//!
+//! Each pruned node remembers where it came from, as the child indices from
+//! the original root, so a match found in the small tree can be looked up in
+//! the full one with [`UINode::node_at`]. The `path` selector of each node is
+//! kept as is and still re-locates the live element.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::UINode;
+
+/// Roles of controls a user can act on
+pub const INTERACTIVE_ROLES: &[&str] = &[
+    "Button",
+    "SplitButton",
+    "CheckBox",
+    "RadioButton",
+    "ComboBox",
+    "Edit",
+    "Document",
+    "Hyperlink",
+    "ListItem",
+    "MenuItem",
+    "TabItem",
+    "TreeItem",
+    "DataItem",
+    "Slider",
+    "Spinner",
+    "ScrollBar",
+];
+
+/// Whether `node` is a control a user can act on: its role is one of
+/// [`INTERACTIVE_ROLES`] or it takes keyboard focus
+pub fn is_interactive(node: &UINode) -> bool {
+    has_role(node, INTERACTIVE_ROLES) || node.attributes.is_keyboard_focusable == Some(true)
+}
+
+/// Whether `node` is on screen as far as its properties tell. Nodes without
+/// an `IsOffscreen` or `visible` property count as visible
+pub fn is_visible(node: &UINode) -> bool {
+    let property = |key: &str| {
+        node.attributes
+            .properties
+            .get(key)
+            .cloned()
+            .flatten()
+            .and_then(|value| match value {
+                serde_json::Value::Bool(b) => Some(b),
+                serde_json::Value::String(s) => s.parse().ok(),
+                _ => None,
+            })
+    };
+    match (property("IsOffscreen"), property("visible")) {
+        (Some(offscreen), _) => !offscreen,
+        (None, Some(visible)) => visible,
+        (None, None) => true,
+    }
+}
+
+fn has_role(node: &UINode, roles: &[&str]) -> bool {
+    roles
+        .iter()
+        .any(|role| node.attributes.role.eq_ignore_ascii_case(role))
+}
+
+/// A node matched by [`UINode::query`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryMatch {
+    /// The matching node without its children
+    pub node: UINode,
+    /// Child indices leading from the root to the node, empty for the root
+    pub index_path: Vec<usize>,
+}
+
+/// A filtered tree and where each of its nodes came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrunedTree {
+    pub tree: UINode,
+    /// Index path of each node in `tree` mapped to its index path in the
+    /// original tree
+    pub origins: HashMap<Vec<usize>, Vec<usize>>,
+    /// Number of nodes in the original tree
+    pub original_count: usize,
+    /// Number of nodes in `tree`
+    pub kept_count: usize,
+}
+
+impl PrunedTree {
+    /// Index path in the original tree of the node at `index_path` in the
+    /// pruned one
+    pub fn origin(&self, index_path: &[usize]) -> Option<&[usize]> {
+        self.origins.get(index_path).map(Vec::as_slice)
+    }
+}
+
+impl UINode {
+    /// The descendant reached by following `index_path` from this node
+    pub fn node_at(&self, index_path: &[usize]) -> Option<&UINode> {
+        index_path
+            .iter()
+            .try_fold(self, |node, &index| node.children.get(index))
+    }
+
+    /// Number of nodes in this tree, including this one
+    pub fn count(&self) -> usize {
+        1 + self.children.iter().map(UINode::count).sum::<usize>()
+    }
+
+    /// Every node matching `predicate`, in depth-first order
+    pub fn query(&self, predicate: impl Fn(&UINode) -> bool) -> Vec<QueryMatch> {
+        let mut matches = Vec::new();
+        let mut index_path = Vec::new();
+        collect_matches(self, &predicate, &mut index_path, &mut matches);
+        matches
+    }
+
+    /// Keep the root and the nodes whose role is one of `keep_roles`
+    /// (case-insensitive), lifting each kept node to its nearest kept
+    /// ancestor. With `max_depth`, nodes deeper than that many levels below
+    /// the root in the original tree are dropped
+    pub fn prune(&self, keep_roles: &[&str], max_depth: Option<usize>) -> PrunedTree {
+        self.filter(|node| has_role(node, keep_roles), max_depth)
+    }
+
+    /// Like [`prune`](UINode::prune), keeping the nodes that match `predicate`,
+    /// e.g. `|n| is_interactive(n) && is_visible(n)`
+    pub fn filter(&self, predicate: impl Fn(&UINode) -> bool, max_depth: Option<usize>) -> PrunedTree {
+        let mut root = strip_children(self);
+        let mut origins = HashMap::new();
+        origins.insert(Vec::new(), Vec::new());
+        let mut original_path = Vec::new();
+        let mut pruned_path = Vec::new();
+        for (index, child) in self.children.iter().enumerate() {
+            original_path.push(index);
+            keep_matching(
+                child,
+                &predicate,
+                max_depth,
+                &mut original_path,
+                &mut root.children,
+                &mut pruned_path,
+                &mut origins,
+            );
+            original_path.pop();
+        }
+        let kept_count = root.count();
+        PrunedTree {
+            tree: root,
+            origins,
+            original_count: self.count(),
+            kept_count,
+        }
+    }
+}
+
+fn strip_children(node: &UINode) -> UINode {
+    UINode {
+        id: node.id.clone(),
+        attributes: node.attributes.clone(),
+        path: node.path.clone(),
+        truncated: node.truncated,
+        status: node.status,
+        children: Vec::new(),
+    }
+}
+
+fn collect_matches(
+    node: &UINode,
+    predicate: &impl Fn(&UINode) -> bool,
+    index_path: &mut Vec<usize>,
+    matches: &mut Vec<QueryMatch>,
+) {
+    if predicate(node) {
+        matches.push(QueryMatch {
+            node: strip_children(node),
+            index_path: index_path.clone(),
+        });
+    }
+    for (index, child) in node.children.iter().enumerate() {
+        index_path.push(index);
+        collect_matches(child, predicate, index_path, matches);
+        index_path.pop();
+    }
+}
+
+/// Add `node` to `siblings` if it matches, otherwise its matching descendants.
+/// `pruned_path` is the path of the parent the siblings belong to
+fn keep_matching(
+    node: &UINode,
+    predicate: &impl Fn(&UINode) -> bool,
+    max_depth: Option<usize>,
+    original_path: &mut Vec<usize>,
+    siblings: &mut Vec<UINode>,
+    pruned_path: &mut Vec<usize>,
+    origins: &mut HashMap<Vec<usize>, Vec<usize>>,
+) {
+    if max_depth.is_some_and(|max| original_path.len() > max) {
+        return;
+    }
+
+    let kept = predicate(node);
+    let mut kept_node = None;
+    let children = if kept {
+        pruned_path.push(siblings.len());
+        origins.insert(pruned_path.clone(), original_path.clone());
+        &mut kept_node.insert(strip_children(node)).children
+    } else {
+        &mut *siblings
+    };
+
+    for (index, child) in node.children.iter().enumerate() {
+        original_path.push(index);
+        keep_matching(child, predicate, max_depth, original_path, children, pruned_path, origins);
+        original_path.pop();
+    }
+
+    if let Some(kept_node) = kept_node {
+        pruned_path.pop();
+        siblings.push(kept_node);
+    }
+}
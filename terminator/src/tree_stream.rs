@@ -0,0 +1,282 @@
+//! Window trees delivered piece by piece while they are built
+//!
+//! Building the tree of a large application can take many seconds.
+//! [`Desktop::stream_window_tree`](crate::Desktop::stream_window_tree) walks
+//! it breadth-first on a background thread and yields each batch of children
+//! as soon as it is read, so processing can start right away and stop once
+//! enough has been seen:
+//!
+//! ```no_run
+//! # use futures::StreamExt;
+//! # use terminator::{AutomationError, Desktop};
+//! # async fn run(desktop: &Desktop, pid: u32) -> Result<(), AutomationError> {
+//! let mut stream = desktop.stream_window_tree(pid, None, None)?;
+//! while let Some(chunk) = stream.next().await {
+//!     if chunk.nodes.iter().any(|n| n.attributes.name.as_deref() == Some("Save")) {
+//!         break; // Dropping the stream stops the walk
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Nodes are numbered in the order they are streamed, the window being 0, and
+//! arrive without children: each chunk names the index of its parent.
+//! [`TreeAssembler`] puts the chunks back together into a [`UINode`] tree.
+
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tracing::debug;
+
+use crate::platforms::{AccessibilityEngine, TreeBuildConfig};
+use crate::{AutomationError, SubtreeStatus, UIElement, UINode};
+
+/// Chunks buffered ahead of the consumer before the walk waits
+const CHANNEL_CAPACITY: usize = 16;
+
+/// Default number of nodes per chunk
+const DEFAULT_BATCH_SIZE: usize = 50;
+
+/// A batch of nodes that share a parent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeChunk {
+    /// Index of the parent node, `None` for the chunk holding the window
+    pub parent: Option<usize>,
+    /// Index of the first node in `nodes`; the rest follow consecutively
+    pub first_index: usize,
+    /// Depth of the nodes, the window being at depth 0
+    pub depth: usize,
+    /// The nodes, with empty `children`. Their paths are filled in
+    pub nodes: Vec<UINode>,
+    /// Whether the parent's children were read. A parent whose children
+    /// could not be read, or were not reached before the time budget ran out,
+    /// gets a single empty chunk with `Failed` or `TimedOut`
+    pub status: SubtreeStatus,
+}
+
+/// Stream of [`TreeChunk`]s of one window, in breadth-first order. Ends when
+/// the whole tree has been streamed or the time budget of the build config
+/// has run out. The walk stops when the stream is dropped
+pub struct WindowTreeStream {
+    rx: mpsc::Receiver<TreeChunk>,
+    timed_out: Arc<AtomicBool>,
+}
+
+impl WindowTreeStream {
+    pub(crate) fn start(
+        engine: Arc<dyn AccessibilityEngine>,
+        window: UIElement,
+        config: TreeBuildConfig,
+    ) -> Result<Self, AutomationError> {
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let timed_out = Arc::new(AtomicBool::new(false));
+        let flag = timed_out.clone();
+        std::thread::Builder::new()
+            .name("tree-stream".to_string())
+            .spawn(move || {
+                let streamed = walk(engine.as_ref(), window, &config, &tx);
+                debug!(nodes = streamed.nodes, timed_out = streamed.timed_out, "Tree stream ended");
+                flag.store(streamed.timed_out, Ordering::Relaxed);
+            })
+            .map_err(|e| {
+                AutomationError::PlatformError(format!("Failed to start tree stream thread: {}", e))
+            })?;
+        Ok(WindowTreeStream { rx, timed_out })
+    }
+
+    /// Whether the time budget ran out before the whole tree was streamed.
+    /// Only meaningful once the stream has ended
+    pub fn timed_out(&self) -> bool {
+        self.timed_out.load(Ordering::Relaxed)
+    }
+}
+
+impl Stream for WindowTreeStream {
+    type Item = TreeChunk;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<TreeChunk>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl std::fmt::Debug for WindowTreeStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WindowTreeStream")
+            .field("timed_out", &self.timed_out())
+            .finish()
+    }
+}
+
+struct Streamed {
+    nodes: usize,
+    timed_out: bool,
+}
+
+fn walk(
+    engine: &dyn AccessibilityEngine,
+    window: UIElement,
+    config: &TreeBuildConfig,
+    tx: &mpsc::Sender<TreeChunk>,
+) -> Streamed {
+    let deadline = config.max_total_duration.map(|d| Instant::now() + d);
+    let batch_size = config.batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1);
+    let node = |element: &UIElement| UINode {
+        id: element.id(),
+        attributes: engine.tree_node_attributes(element, &config.property_mode),
+        path: None,
+        truncated: false,
+        status: SubtreeStatus::Complete,
+        children: Vec::new(),
+    };
+
+    let mut root = node(&window);
+    let root_path = crate::selector_path::root_step(&root.attributes.role, root.attributes.name.as_deref());
+    root.path = Some(root_path.clone());
+    let chunk = TreeChunk {
+        parent: None,
+        first_index: 0,
+        depth: 0,
+        nodes: vec![root],
+        status: SubtreeStatus::Complete,
+    };
+    if tx.blocking_send(chunk).is_err() {
+        return Streamed { nodes: 1, timed_out: false };
+    }
+
+    // Nodes whose children are still to be read: index, element, depth, path
+    let mut queue = std::collections::VecDeque::from([(0, window, 0, root_path)]);
+    let mut next_index = 1;
+    while let Some((parent, element, depth, path)) = queue.pop_front() {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            // Tell the consumer which nodes were never expanded
+            let unvisited = std::iter::once((parent, depth))
+                .chain(queue.iter().map(|(index, _, depth, _)| (*index, *depth)));
+            for (parent, depth) in unvisited {
+                let chunk = empty_chunk(parent, next_index, depth + 1, SubtreeStatus::TimedOut);
+                if tx.blocking_send(chunk).is_err() {
+                    break;
+                }
+            }
+            return Streamed { nodes: next_index, timed_out: true };
+        }
+        let children = match element.children() {
+            Ok(children) => children,
+            Err(e) => {
+                debug!("Failed to read children of node {}: {}", parent, e);
+                let chunk = empty_chunk(parent, next_index, depth + 1, SubtreeStatus::Failed);
+                if tx.blocking_send(chunk).is_err() {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        let mut nodes: Vec<UINode> = children.iter().map(&node).collect();
+        let steps = crate::selector_path::child_steps(nodes.iter().map(|n| n.attributes.role.as_str()));
+        for (i, ((child, node), step)) in children.into_iter().zip(&mut nodes).zip(steps).enumerate() {
+            let child_path = format!("{}{}", path, step);
+            node.path = Some(child_path.clone());
+            queue.push_back((next_index + i, child, depth + 1, child_path));
+        }
+
+        let mut nodes = nodes.into_iter().peekable();
+        while nodes.peek().is_some() {
+            let batch: Vec<UINode> = nodes.by_ref().take(batch_size).collect();
+            let chunk = TreeChunk {
+                parent: Some(parent),
+                first_index: next_index,
+                depth: depth + 1,
+                status: SubtreeStatus::Complete,
+                nodes: batch,
+            };
+            next_index += chunk.nodes.len();
+            if tx.blocking_send(chunk).is_err() {
+                // The consumer dropped the stream
+                return Streamed { nodes: next_index, timed_out: false };
+            }
+        }
+    }
+    Streamed { nodes: next_index, timed_out: false }
+}
+
+fn empty_chunk(parent: usize, first_index: usize, depth: usize, status: SubtreeStatus) -> TreeChunk {
+    TreeChunk {
+        parent: Some(parent),
+        first_index,
+        depth,
+        nodes: Vec::new(),
+        status,
+    }
+}
+
+/// Rebuilds a [`UINode`] tree from streamed chunks
+#[derive(Debug, Default)]
+pub struct TreeAssembler {
+    nodes: Vec<UINode>,
+    parents: Vec<Option<usize>>,
+}
+
+impl TreeAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a chunk. Chunks must be pushed in the order they were streamed
+    pub fn push(&mut self, chunk: TreeChunk) -> Result<(), AutomationError> {
+        if chunk.first_index != self.nodes.len() {
+            return Err(AutomationError::InvalidArgument(format!(
+                "Expected a chunk starting at node {}, got one starting at {}",
+                self.nodes.len(),
+                chunk.first_index
+            )));
+        }
+        if let Some(parent) = chunk.parent {
+            let Some(parent_node) = self.nodes.get_mut(parent) else {
+                return Err(AutomationError::InvalidArgument(format!(
+                    "Chunk refers to unknown parent node {}",
+                    parent
+                )));
+            };
+            if !chunk.status.is_complete() {
+                parent_node.status = chunk.status;
+            }
+        }
+        for node in chunk.nodes {
+            self.nodes.push(node);
+            self.parents.push(chunk.parent);
+        }
+        Ok(())
+    }
+
+    /// The tree built from the chunks pushed so far, `None` before the first.
+    /// Nodes whose children were not read are marked as truncated
+    pub fn finish(mut self) -> Option<UINode> {
+        // Children always come after their parent, so attach from the back
+        for index in (1..self.nodes.len()).rev() {
+            let node = self.nodes.pop().expect("index is in bounds");
+            if let Some(parent) = self.parents[index] {
+                self.nodes[parent].children.push(node);
+            }
+        }
+        let mut root = self.nodes.pop()?;
+        fix_up(&mut root);
+        Some(root)
+    }
+}
+
+/// Restore sibling order, reversed by attaching from the back, and
+/// propagate `truncated`
+fn fix_up(node: &mut UINode) {
+    node.children.reverse();
+    for child in &mut node.children {
+        fix_up(child);
+    }
+    node.truncated = !node.status.is_complete() || node.children.iter().any(|c| c.truncated);
+}
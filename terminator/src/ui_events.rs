@@ -0,0 +1,149 @@
+//! Subscription to UI events (focus, structure and property changes)
+//!
+//! Events are delivered on a background watcher thread owned by the platform
+//! engine, so callbacks must be `Send + Sync` and should return quickly. The
+//! subscription stays active until the returned [`UIEventSubscription`] is
+//! dropped or [`UIEventSubscription::unsubscribe`] is called.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::UIElement;
+
+/// The kind of UI event to subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UIEventKind {
+    /// Keyboard focus moved to another element
+    FocusChanged,
+    /// Elements were added, removed or reordered
+    StructureChanged,
+    /// The name, value or focus state of an element changed
+    PropertyChanged,
+}
+
+impl UIEventKind {
+    pub const ALL: [UIEventKind; 3] = [
+        UIEventKind::FocusChanged,
+        UIEventKind::StructureChanged,
+        UIEventKind::PropertyChanged,
+    ];
+}
+
+impl std::fmt::Display for UIEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            UIEventKind::FocusChanged => "focus_changed",
+            UIEventKind::StructureChanged => "structure_changed",
+            UIEventKind::PropertyChanged => "property_changed",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for UIEventKind {
+    type Err = String;
+
+    /// Accepts snake_case (`focus_changed`) and camelCase (`focusChanged`) names
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.replace('_', "").to_lowercase().as_str() {
+            "focuschanged" => Ok(UIEventKind::FocusChanged),
+            "structurechanged" => Ok(UIEventKind::StructureChanged),
+            "propertychanged" => Ok(UIEventKind::PropertyChanged),
+            _ => Err(format!("Unknown UI event kind '{}'", s)),
+        }
+    }
+}
+
+/// A UI event delivered to a subscription callback
+#[derive(Debug, Clone)]
+pub struct UIEvent {
+    pub kind: UIEventKind,
+    /// The element the event was raised on
+    pub element: Option<UIElement>,
+    /// The changed property for property changes, the change type for structure changes
+    pub detail: Option<String>,
+    /// The new property value for property changes
+    pub value: Option<String>,
+}
+
+/// Callback invoked for each UI event
+pub type UIEventCallback = Arc<dyn Fn(UIEvent) + Send + Sync>;
+
+/// An active UI event subscription, cancelled when dropped
+pub struct UIEventSubscription {
+    stop: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl UIEventSubscription {
+    /// Wrap the platform-specific teardown of a subscription
+    pub fn new(stop: impl FnOnce() + Send + 'static) -> Self {
+        Self {
+            stop: Some(Box::new(stop)),
+        }
+    }
+
+    /// Stop delivering events
+    pub fn unsubscribe(mut self) {
+        self.cancel();
+    }
+
+    fn cancel(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            stop();
+        }
+    }
+}
+
+impl Drop for UIEventSubscription {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+impl std::fmt::Debug for UIEventSubscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UIEventSubscription")
+            .field("active", &self.stop.is_some())
+            .finish()
+    }
+}
+
+/// How long the UI of one process has gone without structure changes, for
+/// [`Desktop::wait_for_idle`](crate::Desktop::wait_for_idle)
+#[derive(Debug)]
+pub(crate) struct QuietPeriod {
+    pid: u32,
+    period: Duration,
+    last_change: Mutex<Instant>,
+}
+
+impl QuietPeriod {
+    /// A period of `period` for process `pid`, starting at `start`
+    pub(crate) fn new(pid: u32, period: Duration, start: Instant) -> Self {
+        Self {
+            pid,
+            period,
+            last_change: Mutex::new(start),
+        }
+    }
+
+    /// Restart the period for a change at `at` on an element of process
+    /// `event_pid`. Changes of other processes don't count; those whose
+    /// process is unknown do
+    pub(crate) fn record(&self, event_pid: Option<u32>, at: Instant) {
+        if event_pid.is_none_or(|event_pid| event_pid == self.pid) {
+            let mut last_change = self.last_change.lock().unwrap_or_else(|p| p.into_inner());
+            *last_change = (*last_change).max(at);
+        }
+    }
+
+    /// How much longer the UI must stay unchanged at `now`, `None` once it
+    /// has been quiet for the whole period
+    pub(crate) fn remaining(&self, now: Instant) -> Option<Duration> {
+        let last_change = *self.last_change.lock().unwrap_or_else(|p| p.into_inner());
+        let quiet_for = now.saturating_duration_since(last_change);
+        (quiet_for < self.period).then(|| self.period - quiet_for)
+    }
+}
@@ -15,3 +15,69 @@ pub fn normalize(s: &str) -> String {
         .collect::<String>()
         .to_lowercase()
 }
+
+/// Similarity between two strings in `0.0..=1.0`, based on normalized Levenshtein distance.
+/// Substring matches score at least 0.8 so partial names rank above unrelated ones.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a = normalize(a);
+    let b = normalize(b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a == b {
+        return 1.0;
+    }
+
+    let a_chars: Vec<char> = a.chars().collect();
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut curr = vec![0; b_chars.len() + 1];
+    for (i, ca) in a_chars.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b_chars.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    let distance = prev[b_chars.len()];
+    let max_len = a_chars.len().max(b_chars.len());
+    let score = 1.0 - distance as f64 / max_len as f64;
+
+    if a.contains(&b) || b.contains(&a) {
+        score.max(0.8)
+    } else {
+        score
+    }
+}
+
+/// `url` as browsers display it: without scheme, `www.`, trailing slashes or
+/// case, so `https://www.Example.com/` and `example.com` compare equal
+pub fn normalize_url(url: &str) -> String {
+    let url = url.trim().to_lowercase();
+    let url = url.split_once("://").map_or(url.as_str(), |(_, rest)| rest);
+    let url = url.strip_prefix("www.").unwrap_or(url);
+    url.trim_end_matches('/').to_string()
+}
+
+/// Host of `url` in its normalized form, e.g. `example.com` for
+/// `https://www.example.com/path?q=1`
+pub fn url_host(url: &str) -> String {
+    let normalized = normalize_url(url);
+    let end = normalized.find(['/', '?', '#', ':']).unwrap_or(normalized.len());
+    normalized[..end].to_string()
+}
+
+/// Whether a browser showing `shown` is displaying `target` or a page it led
+/// to on the same site, e.g. after a redirect to a localized path
+pub fn url_matches(shown: &str, target: &str) -> bool {
+    let shown = normalize_url(shown);
+    let target = normalize_url(target);
+    if target.is_empty() {
+        return false;
+    }
+    match shown.strip_prefix(&target) {
+        Some(rest) => rest.is_empty() || rest.starts_with(['/', '?', '#']),
+        None => false,
+    }
+}
@@ -0,0 +1,267 @@
+//! Soft assertions for validating a whole screen in one step
+//!
+//! A [`SoftVerifier`] keeps going after a failed check and reports every
+//! mismatch together when the step is finished, instead of stopping at the
+//! first one:
+//!
+//! ```no_run
+//! # use terminator::{Desktop, verify::SoftVerifier};
+//! # async fn run(desktop: &Desktop) -> Result<(), terminator::AutomationError> {
+//! let mut verify = SoftVerifier::new("checkout summary").with_artifacts(true);
+//! let total = desktop.locator("name:Total").first(None).await?;
+//! verify.expect_visible(&total);
+//! verify.expect_text(&total, "42.00");
+//! verify.check("cart is not empty", true, "");
+//! verify.finish()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+use crate::{AutomationError, ScreenshotResult, SerializableUIElement, UIElement};
+
+/// A single failed check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationFailure {
+    /// What was being checked
+    pub check: String,
+    /// Why the check failed
+    pub message: String,
+    /// The element under test, if the check was about an element
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub element: Option<SerializableUIElement>,
+    /// Screenshot of the element at the time of failure
+    #[serde(skip)]
+    pub screenshot: Option<ScreenshotResult>,
+}
+
+/// Outcome of all checks made by a [`SoftVerifier`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationReport {
+    /// Name of the verified step
+    pub step: String,
+    /// Number of checks made
+    pub checks: usize,
+    /// All failed checks, in the order they were made
+    pub failures: Vec<VerificationFailure>,
+}
+
+impl VerificationReport {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Write `report.json` and one PNG per failure screenshot into `dir`.
+    ///
+    /// Returns the paths of all written files.
+    pub fn write_bundle(&self, dir: impl AsRef<Path>) -> Result<Vec<PathBuf>, AutomationError> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)
+            .map_err(|e| AutomationError::Internal(format!("Failed to create {}: {}", dir.display(), e)))?;
+
+        let mut written = Vec::new();
+        let report_path = dir.join("report.json");
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| AutomationError::Internal(format!("Failed to serialize report: {}", e)))?;
+        std::fs::write(&report_path, json)
+            .map_err(|e| AutomationError::Internal(format!("Failed to write {}: {}", report_path.display(), e)))?;
+        written.push(report_path);
+
+        for (index, failure) in self.failures.iter().enumerate() {
+            let Some(shot) = &failure.screenshot else {
+                continue;
+            };
+            let path = dir.join(format!("failure-{}.png", index + 1));
+            match image::RgbaImage::from_raw(shot.width, shot.height, shot.image_data.clone()) {
+                Some(img) => {
+                    img.save(&path).map_err(|e| {
+                        AutomationError::Internal(format!("Failed to write {}: {}", path.display(), e))
+                    })?;
+                    written.push(path);
+                }
+                None => warn!("Skipping malformed screenshot for failure {}", index + 1),
+            }
+        }
+
+        Ok(written)
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} of {} checks failed",
+            self.step,
+            self.failures.len(),
+            self.checks
+        )?;
+        for (index, failure) in self.failures.iter().enumerate() {
+            write!(f, "\n  {}) {}: {}", index + 1, failure.check, failure.message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Accumulates check failures across one step and reports them together
+#[derive(Debug)]
+pub struct SoftVerifier {
+    step: String,
+    checks: usize,
+    failures: Vec<VerificationFailure>,
+    capture_artifacts: bool,
+}
+
+impl SoftVerifier {
+    pub fn new(step: impl Into<String>) -> Self {
+        Self {
+            step: step.into(),
+            checks: 0,
+            failures: Vec::new(),
+            capture_artifacts: false,
+        }
+    }
+
+    /// Capture a screenshot of the element under test for each failed element check
+    pub fn with_artifacts(mut self, capture: bool) -> Self {
+        self.capture_artifacts = capture;
+        self
+    }
+
+    /// Record a check on an arbitrary condition
+    pub fn check(&mut self, description: &str, condition: bool, message: &str) -> bool {
+        self.checks += 1;
+        if !condition {
+            self.fail(description, message.to_string(), None);
+        }
+        condition
+    }
+
+    /// Record a fallible operation as a check, returning its value on success
+    pub fn check_result<T>(&mut self, description: &str, result: Result<T, AutomationError>) -> Option<T> {
+        self.checks += 1;
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.fail(description, e.to_string(), None);
+                None
+            }
+        }
+    }
+
+    /// Check that the element is visible
+    pub fn expect_visible(&mut self, element: &UIElement) -> bool {
+        let result = element.is_visible();
+        self.element_check(element, "visible", result.map(|v| (v, "element is not visible".to_string())))
+    }
+
+    /// Check that the element is enabled
+    pub fn expect_enabled(&mut self, element: &UIElement) -> bool {
+        let result = element.is_enabled();
+        self.element_check(element, "enabled", result.map(|v| (v, "element is disabled".to_string())))
+    }
+
+    /// Check that the element's text contains `expected`
+    pub fn expect_text(&mut self, element: &UIElement, expected: &str) -> bool {
+        let result = element.text(1).map(|text| {
+            (
+                text.contains(expected),
+                format!("expected text containing '{}', got '{}'", expected, text),
+            )
+        });
+        self.element_check(element, &format!("text contains '{}'", expected), result)
+    }
+
+    /// Check that the element's name equals `expected`
+    pub fn expect_name(&mut self, element: &UIElement, expected: &str) -> bool {
+        let name = element.name_or_empty();
+        let result = Ok((
+            name == expected,
+            format!("expected name '{}', got '{}'", expected, name),
+        ));
+        self.element_check(element, &format!("name is '{}'", expected), result)
+    }
+
+    /// Check that the element's value equals `expected`
+    pub fn expect_value(&mut self, element: &UIElement, expected: &str) -> bool {
+        let value = element.value_or_empty();
+        let result = Ok((
+            value == expected,
+            format!("expected value '{}', got '{}'", expected, value),
+        ));
+        self.element_check(element, &format!("value is '{}'", expected), result)
+    }
+
+    pub fn failures(&self) -> &[VerificationFailure] {
+        &self.failures
+    }
+
+    pub fn check_count(&self) -> usize {
+        self.checks
+    }
+
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// The report of all checks made so far
+    pub fn report(&self) -> VerificationReport {
+        VerificationReport {
+            step: self.step.clone(),
+            checks: self.checks,
+            failures: self.failures.clone(),
+        }
+    }
+
+    /// End the step, returning `AutomationError::VerificationFailed` with every
+    /// failure if any check failed
+    pub fn finish(self) -> Result<VerificationReport, AutomationError> {
+        let report = VerificationReport {
+            step: self.step,
+            checks: self.checks,
+            failures: self.failures,
+        };
+        if report.passed() {
+            debug!(step = %report.step, checks = report.checks, "Verification passed");
+            Ok(report)
+        } else {
+            Err(AutomationError::VerificationFailed(Box::new(report)))
+        }
+    }
+
+    fn element_check(
+        &mut self,
+        element: &UIElement,
+        description: &str,
+        result: Result<(bool, String), AutomationError>,
+    ) -> bool {
+        self.checks += 1;
+        let (passed, message) = match result {
+            Ok(outcome) => outcome,
+            Err(e) => (false, e.to_string()),
+        };
+        if !passed {
+            let check = format!("{} {}", element.to_serializable().role, description);
+            self.fail(&check, message, Some(element));
+        }
+        passed
+    }
+
+    fn fail(&mut self, check: &str, message: String, element: Option<&UIElement>) {
+        debug!(step = %self.step, check, %message, "Soft check failed");
+        let screenshot = match element {
+            Some(el) if self.capture_artifacts => el.capture().ok(),
+            _ => None,
+        };
+        self.failures.push(VerificationFailure {
+            check: check.to_string(),
+            message,
+            element: element.map(|el| el.to_serializable()),
+            screenshot,
+        });
+    }
+}
@@ -0,0 +1,79 @@
+//! Virtual desktops
+//!
+//! Windows keeps the windows of every virtual desktop in the accessibility
+//! tree, so locators find windows that are not shown. Input sent to them
+//! goes nowhere: clicks on elements of another virtual desktop therefore fail
+//! with [`AutomationError::UIUnavailable`](crate::AutomationError::UIUnavailable)
+//! instead of silently doing nothing.
+//!
+//! ```no_run
+//! # use terminator::{AutomationError, Desktop};
+//! # async fn run(desktop: &Desktop) -> Result<(), AutomationError> {
+//! let current = desktop.virtual_desktops()?.into_iter().find(|d| d.is_current);
+//! let window = desktop.locator("window:Report").first(None).await?;
+//! if let (Some(current), false) = (current, window.is_on_current_virtual_desktop()?) {
+//!     window.move_to_desktop(&current.id)?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Windows only lets a process move its own windows between virtual
+//! desktops; windows of other applications have to be switched to instead.
+
+use serde::{Deserialize, Serialize};
+
+/// A virtual desktop, as shown in Task View
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VirtualDesktop {
+    /// GUID of the desktop, e.g. `{2B2B1AE6-7BA0-4FC4-9F0E-4E7A0C1B6E0F}`
+    pub id: String,
+    /// Name given by the user, or `Desktop N` like Task View shows
+    pub name: String,
+    /// Position in Task View, from 0
+    pub index: usize,
+    /// Whether this is the desktop currently shown
+    pub is_current: bool,
+}
+
+/// `value` as a registry-style GUID string
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub(crate) fn format_guid(value: u128) -> String {
+    format!(
+        "{{{:08X}-{:04X}-{:04X}-{:04X}-{:012X}}}",
+        value >> 96,
+        (value >> 80) & 0xFFFF,
+        (value >> 64) & 0xFFFF,
+        (value >> 48) & 0xFFFF,
+        value & 0xFFFF_FFFF_FFFF
+    )
+}
+
+/// A GUID string, with or without braces, as a number
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub(crate) fn parse_guid(text: &str) -> Option<u128> {
+    let text = text.trim().trim_start_matches('{').trim_end_matches('}');
+    let groups: Vec<&str> = text.split('-').collect();
+    let lengths: Vec<usize> = groups.iter().map(|group| group.len()).collect();
+    if lengths != [8, 4, 4, 4, 12] || !groups.iter().all(|g| g.chars().all(|c| c.is_ascii_hexdigit())) {
+        return None;
+    }
+    u128::from_str_radix(&groups.concat(), 16).ok()
+}
+
+/// GUIDs stored back to back in their binary layout, whose first three
+/// fields are little-endian, as in the registry list of virtual desktops
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub(crate) fn parse_guid_list(bytes: &[u8]) -> Vec<u128> {
+    bytes
+        .chunks_exact(16)
+        .map(|raw| {
+            let data1 = u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as u128;
+            let data2 = u16::from_le_bytes([raw[4], raw[5]]) as u128;
+            let data3 = u16::from_le_bytes([raw[6], raw[7]]) as u128;
+            let mut data4 = [0u8; 8];
+            data4.copy_from_slice(&raw[8..]);
+            (data1 << 96) | (data2 << 80) | (data3 << 64) | u64::from_be_bytes(data4) as u128
+        })
+        .collect()
+}
@@ -0,0 +1,235 @@
+//! Idle watchdog for unattended automation runs
+//!
+//! Every successful action (clicks, typing, key presses, locator waits, ...)
+//! records a heartbeat. When a watchdog is armed through
+//! [`Desktop::idle_timeout`](crate::Desktop::idle_timeout) and no heartbeat
+//! arrives for the configured duration, a [`DiagnosticBundle`] is captured and
+//! either handed to a user callback or stored while the run is aborted with
+//! [`AutomationError::StalledRun`].
+//!
+//! Each `Desktop` (and its clones) has its own idle clock and stall flag, see
+//! [`WatchdogState`]. Its locators and its own methods report to it. Element
+//! actions cannot tell which `Desktop` found their element, so they count as
+//! activity for every `Desktop` in the process. An abort lasts until
+//! [`Desktop::reset_stall`](crate::Desktop::reset_stall); later actions
+//! don't lift it.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::platforms::{AccessibilityEngine, TreeBuildConfig};
+use crate::{AutomationError, ScreenshotResult, SerializableUIElement, UINode};
+
+/// Callback invoked with the diagnostics captured when a run stalls
+pub type StallCallback = Arc<dyn Fn(DiagnosticBundle) + Send + Sync>;
+
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+/// States of the live desktops, for heartbeats that belong to no desktop
+static STATES: Mutex<Vec<Weak<WatchdogState>>> = Mutex::new(Vec::new());
+
+fn now_ms() -> u64 {
+    EPOCH.get_or_init(Instant::now).elapsed().as_millis() as u64
+}
+
+/// Idle clock and stall flag of one `Desktop`, shared with its clones,
+/// locators and watchdog thread
+#[derive(Debug, Default)]
+pub struct WatchdogState {
+    last_activity_ms: AtomicU64,
+    stalled: AtomicBool,
+    last_bundle: Mutex<Option<DiagnosticBundle>>,
+}
+
+impl WatchdogState {
+    /// A state that also receives the heartbeats of element actions
+    pub(crate) fn new() -> Arc<Self> {
+        let state = Arc::new(Self::default());
+        state.record_activity();
+        let mut states = STATES.lock().unwrap_or_else(|p| p.into_inner());
+        states.retain(|state| state.strong_count() > 0);
+        states.push(Arc::downgrade(&state));
+        state
+    }
+
+    /// Record that an action completed successfully. Does not lift an abort
+    pub fn record_activity(&self) {
+        self.last_activity_ms.store(now_ms(), Ordering::Relaxed);
+    }
+
+    /// Time elapsed since the last successful action
+    pub fn idle_for(&self) -> Duration {
+        Duration::from_millis(now_ms().saturating_sub(self.last_activity_ms.load(Ordering::Relaxed)))
+    }
+
+    /// Whether an aborting watchdog has fired since the last reset
+    pub fn is_stalled(&self) -> bool {
+        self.stalled.load(Ordering::Relaxed)
+    }
+
+    /// Return `AutomationError::StalledRun` if an aborting watchdog has fired
+    pub fn check_stalled(&self) -> Result<(), AutomationError> {
+        if self.is_stalled() {
+            return Err(AutomationError::StalledRun(format!(
+                "no action completed for {}s",
+                self.idle_for().as_secs()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Lift an abort and restart the idle clock. The diagnostics of the
+    /// stall are kept
+    pub fn reset(&self) {
+        self.stalled.store(false, Ordering::Relaxed);
+        self.record_activity();
+    }
+
+    /// The diagnostics captured by the last aborting watchdog, if any
+    pub fn last_stall_diagnostics(&self) -> Option<DiagnosticBundle> {
+        self.last_bundle.lock().unwrap_or_else(|p| p.into_inner()).clone()
+    }
+
+    pub(crate) fn abort(&self, bundle: DiagnosticBundle) {
+        *self.last_bundle.lock().unwrap_or_else(|p| p.into_inner()) = Some(bundle);
+        self.stalled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Record that an action completed successfully, for every `Desktop`.
+///
+/// Called internally by element actions; call it yourself when a long step
+/// that does not go through terminator should count as progress.
+pub fn record_activity() {
+    let states = STATES.lock().unwrap_or_else(|p| p.into_inner());
+    for state in states.iter().filter_map(Weak::upgrade) {
+        state.record_activity();
+    }
+}
+
+/// Diagnostics captured when the watchdog detects a stalled run
+#[derive(Debug, Clone)]
+pub struct DiagnosticBundle {
+    /// How long the run had been idle when the bundle was captured
+    pub idle_for: Duration,
+    /// Screenshot of the primary monitor
+    pub screenshot: Option<ScreenshotResult>,
+    /// The window that had focus
+    pub foreground_window: Option<SerializableUIElement>,
+    /// UI tree of the focused application
+    pub active_app_tree: Option<UINode>,
+    /// Errors hit while collecting the pieces above
+    pub errors: Vec<String>,
+}
+
+impl DiagnosticBundle {
+    /// Collect a screenshot, the foreground window and the active app's
+    /// tree. The engine's async calls run on `runtime`; without one nothing
+    /// is captured and `errors` says why
+    pub fn capture(
+        engine: &dyn AccessibilityEngine,
+        runtime: Option<&tokio::runtime::Handle>,
+        idle_for: Duration,
+    ) -> Self {
+        let mut errors = Vec::new();
+        let Some(runtime) = runtime else {
+            errors.push("no async runtime to capture diagnostics on".to_string());
+            return Self {
+                idle_for,
+                screenshot: None,
+                foreground_window: None,
+                active_app_tree: None,
+                errors,
+            };
+        };
+
+        let screenshot = runtime
+            .block_on(engine.capture_screen())
+            .map_err(|e| errors.push(format!("screenshot: {e}")))
+            .ok();
+
+        let foreground_window = runtime
+            .block_on(engine.get_current_window())
+            .map(|w| w.to_serializable())
+            .map_err(|e| errors.push(format!("foreground window: {e}")))
+            .ok();
+
+        let active_app_tree = runtime
+            .block_on(engine.get_current_application())
+            .and_then(|app| app.process_id())
+            .and_then(|pid| engine.get_window_tree(pid, None, TreeBuildConfig::default()))
+            .map_err(|e| errors.push(format!("active app tree: {e}")))
+            .ok();
+
+        Self {
+            idle_for,
+            screenshot,
+            foreground_window,
+            active_app_tree,
+            errors,
+        }
+    }
+}
+
+/// A running watchdog thread, stopped when dropped
+pub(crate) struct Watchdog {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    /// Watch `state`. Must be called within a tokio runtime for the
+    /// diagnostics to include the screenshot and windows
+    pub(crate) fn start(
+        engine: Arc<dyn AccessibilityEngine>,
+        state: Arc<WatchdogState>,
+        timeout: Duration,
+        on_stall: Option<StallCallback>,
+    ) -> Self {
+        state.record_activity();
+        let runtime = tokio::runtime::Handle::try_current().ok();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let poll = (timeout / 4).clamp(Duration::from_millis(100), Duration::from_secs(1));
+
+        let thread = std::thread::spawn(move || {
+            // Fire once per stall; a new heartbeat re-arms the watchdog
+            let mut fired = false;
+            while !stop_flag.load(Ordering::Relaxed) {
+                std::thread::sleep(poll);
+                let idle = state.idle_for();
+                if idle < timeout {
+                    fired = false;
+                    continue;
+                }
+                if fired || state.is_stalled() {
+                    continue;
+                }
+                fired = true;
+                warn!(idle_ms = idle.as_millis(), "Automation run stalled, capturing diagnostics");
+                let bundle = DiagnosticBundle::capture(engine.as_ref(), runtime.as_ref(), idle);
+                debug!(errors = ?bundle.errors, "Diagnostic bundle captured");
+                match &on_stall {
+                    Some(callback) => callback(bundle),
+                    None => state.abort(bundle),
+                }
+            }
+        });
+
+        Self {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,777 @@
+//! Declarative workflows
+//!
+//! A [`Workflow`] is a list of steps written as JSON, or YAML with the
+//! `yaml` feature, so automations can be authored without writing code.
+//! The MCP agent's `execute_workflow` tool and `terminator-cli run` take the
+//! same format:
+//!
+//! ```yaml
+//! name: Save a note
+//! variables:
+//!   note: Hello
+//! steps:
+//!   - action: open_application
+//!     app: notepad
+//!   - action: type
+//!     selector_chain: ["role:Document"]
+//!     text: "${note}"
+//!   - action: press_key
+//!     key: "{Ctrl}s"
+//!   - action: if
+//!     condition:
+//!       exists:
+//!         selector_chain: ["role:Window", "name:Save As"]
+//!     then:
+//!       - action: type
+//!         selector_chain: ["role:Window", "name:Save As", "role:Edit"]
+//!         text: "note.txt"
+//!       - action: press_key
+//!         key: "{Enter}"
+//!   - action: extract
+//!     selector_chain: ["role:Window"]
+//!     property: name
+//!     variable: title
+//!   - action: assert
+//!     text_contains: "note"
+//! ```
+//!
+//! `${name}` in selectors, text, keys and values is replaced by a variable.
+//! Variables come from the workflow's `variables`, from the caller of
+//! [`Workflow::run`], which take precedence, and from `set` and `extract`
+//! steps. Steps without a `selector_chain` act on the element found by the
+//! last step that had one.
+//!
+//! The whole workflow is checked before any step runs. A failed step stops
+//! the run unless `stop_on_error` is false; either way the
+//! [`WorkflowReport`] has the outcome of every step that ran.
+//!
+//! [`Workflow::run_guarded`] asks a [`StepGuard`] before each step, `if`
+//! branches included, so embedders can refuse steps one by one.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{debug, info, instrument};
+
+use crate::verify::{VerificationFailure, VerificationReport};
+use crate::{AutomationError, Desktop, ErrorCode, Locator, Selector, UIElement};
+
+/// How long `exists` and `not_exists` conditions, and `not_exists` asserts,
+/// wait unless they set `timeout_ms`
+const CONDITION_TIMEOUT: Duration = Duration::from_secs(1);
+/// Depth of the subtree read for text, as in the bindings' default
+const TEXT_DEPTH: usize = 1;
+
+fn default_true() -> bool {
+    true
+}
+
+/// A named list of steps with default variables
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Workflow {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Default values, overridden by the variables passed to [`Workflow::run`]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, String>,
+    pub steps: Vec<WorkflowStep>,
+    /// Stop at the first failed step. On by default
+    #[serde(default = "default_true")]
+    pub stop_on_error: bool,
+}
+
+/// One step of a [`Workflow`]. Steps without a `selector_chain` act on the
+/// element found by the last step that had one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum WorkflowStep {
+    /// Find an element for the following steps
+    Locate {
+        selector_chain: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_ms: Option<u64>,
+    },
+    Click {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        selector_chain: Option<Vec<String>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_ms: Option<u64>,
+    },
+    Type {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        selector_chain: Option<Vec<String>>,
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_ms: Option<u64>,
+    },
+    PressKey {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        selector_chain: Option<Vec<String>>,
+        /// Key or combination, with special keys in curly braces
+        key: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_ms: Option<u64>,
+    },
+    /// Check the element's state without acting on it
+    Assert {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        selector_chain: Option<Vec<String>>,
+        /// `exists` (default), `not_exists`, `visible`, `enabled` or `focused`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        condition: Option<String>,
+        /// Text the element's name, value or text must contain
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        text_contains: Option<String>,
+        /// How long to wait for the element. `not_exists` waits 1 s by
+        /// default, as `if` conditions do, since it holds only once the wait
+        /// is over
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_ms: Option<u64>,
+    },
+    /// Pause between steps
+    Wait { duration_ms: u64 },
+    /// Open an application by name; its window becomes the current element
+    OpenApplication { app: String },
+    /// Set a variable
+    Set { variable: String, value: String },
+    /// Read the element's `text` (default), `name` or `value` into a variable
+    Extract {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        selector_chain: Option<Vec<String>>,
+        variable: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        property: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_ms: Option<u64>,
+    },
+    /// Run `then` if the condition holds, `else` otherwise
+    If {
+        condition: StepCondition,
+        #[serde(default)]
+        then: Vec<WorkflowStep>,
+        #[serde(default, rename = "else", skip_serializing_if = "Vec::is_empty")]
+        otherwise: Vec<WorkflowStep>,
+    },
+}
+
+/// Condition of an `if` step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum StepCondition {
+    /// The selector chain finds an element within `timeout_ms` (1s by default)
+    Exists {
+        selector_chain: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_ms: Option<u64>,
+    },
+    /// The selector chain finds nothing within `timeout_ms` (1s by default)
+    NotExists {
+        selector_chain: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout_ms: Option<u64>,
+    },
+    /// The variable is set to `value`
+    Equals { variable: String, value: String },
+    /// The variable is unset or not set to `value`
+    NotEquals { variable: String, value: String },
+}
+
+/// Decides whether each step of a run may run, e.g. to apply a policy to
+/// the steps of a workflow one by one
+#[async_trait::async_trait]
+pub trait StepGuard: Send + Sync {
+    /// Called before `step` runs, with the variables as they are at that
+    /// point. An error fails the step without running it
+    async fn before_step(
+        &self,
+        step: &WorkflowStep,
+        variables: &BTreeMap<String, String>,
+    ) -> Result<(), AutomationError>;
+}
+
+impl WorkflowStep {
+    /// The `action` tag of the step
+    pub fn action(&self) -> &'static str {
+        match self {
+            WorkflowStep::Locate { .. } => "locate",
+            WorkflowStep::Click { .. } => "click",
+            WorkflowStep::Type { .. } => "type",
+            WorkflowStep::PressKey { .. } => "press_key",
+            WorkflowStep::Assert { .. } => "assert",
+            WorkflowStep::Wait { .. } => "wait",
+            WorkflowStep::OpenApplication { .. } => "open_application",
+            WorkflowStep::Set { .. } => "set",
+            WorkflowStep::Extract { .. } => "extract",
+            WorkflowStep::If { .. } => "if",
+        }
+    }
+
+    /// How the step gets its element: `None` for steps without one,
+    /// `Some(None)` for the current element
+    fn element_source(&self) -> Option<Option<&Vec<String>>> {
+        match self {
+            WorkflowStep::Locate { selector_chain, .. } => Some(Some(selector_chain)),
+            WorkflowStep::Click { selector_chain, .. }
+            | WorkflowStep::Type { selector_chain, .. }
+            | WorkflowStep::PressKey { selector_chain, .. }
+            | WorkflowStep::Assert { selector_chain, .. }
+            | WorkflowStep::Extract { selector_chain, .. } => Some(selector_chain.as_ref()),
+            WorkflowStep::Wait { .. }
+            | WorkflowStep::OpenApplication { .. }
+            | WorkflowStep::Set { .. }
+            | WorkflowStep::If { .. } => None,
+        }
+    }
+}
+
+/// How a step ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Success,
+    Error,
+}
+
+/// Outcome of one step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepResult {
+    /// Position of the step in the workflow, or of the `if` step containing it
+    pub step: usize,
+    /// Position inside `if` steps, e.g. `then.0` or `then.1.else.0`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    pub action: String,
+    pub status: StepStatus,
+    /// What the step did, e.g. the element it acted on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+    /// Why the step failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<ErrorCode>,
+    pub duration_ms: u64,
+}
+
+/// How a whole run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkflowStatus {
+    Success,
+    /// Some steps failed and others succeeded
+    Partial,
+    Error,
+}
+
+/// Outcome of [`Workflow::run`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowReport {
+    pub status: WorkflowStatus,
+    /// Results of the steps that ran, `if` steps before the steps of their branch
+    pub results: Vec<StepResult>,
+    pub steps_failed: usize,
+    /// Variables at the end of the run
+    pub variables: BTreeMap<String, String>,
+    pub duration_ms: u64,
+}
+
+impl WorkflowReport {
+    pub fn succeeded(&self) -> bool {
+        self.status == WorkflowStatus::Success
+    }
+}
+
+impl Workflow {
+    pub fn new(steps: Vec<WorkflowStep>) -> Self {
+        Self {
+            name: None,
+            variables: BTreeMap::new(),
+            steps,
+            stop_on_error: true,
+        }
+    }
+
+    /// Number of steps, counting those inside `if` branches
+    pub fn step_count(&self) -> usize {
+        count_steps(&self.steps)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, AutomationError> {
+        serde_json::from_str(json)
+            .map_err(|e| AutomationError::InvalidArgument(format!("Invalid workflow: {}", e)))
+    }
+
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, AutomationError> {
+        serde_norway::from_str(yaml)
+            .map_err(|e| AutomationError::InvalidArgument(format!("Invalid workflow: {}", e)))
+    }
+
+    /// Check the steps without running them: selector chains must not be
+    /// empty, and steps without one need an element from an earlier step
+    pub fn validate(&self) -> Result<(), AutomationError> {
+        if self.steps.is_empty() {
+            return Err(AutomationError::InvalidArgument(
+                "Workflow has no steps".to_string(),
+            ));
+        }
+        validate_steps(&self.steps, "", false).map(|_| ())
+    }
+
+    /// Run the steps on `desktop`. `variables` override the workflow's
+    /// defaults. Fails only if the workflow is invalid; failed steps are
+    /// reported in the [`WorkflowReport`]
+    pub async fn run(
+        &self,
+        desktop: &Desktop,
+        variables: BTreeMap<String, String>,
+    ) -> Result<WorkflowReport, AutomationError> {
+        self.run_inner(desktop, variables, None).await
+    }
+
+    /// Like [`Workflow::run`], asking `guard` before each step. Steps it
+    /// refuses fail like any other step
+    pub async fn run_guarded(
+        &self,
+        desktop: &Desktop,
+        variables: BTreeMap<String, String>,
+        guard: &dyn StepGuard,
+    ) -> Result<WorkflowReport, AutomationError> {
+        self.run_inner(desktop, variables, Some(guard)).await
+    }
+
+    #[instrument(skip(self, desktop, variables, guard), fields(name = ?self.name, steps = self.steps.len()))]
+    async fn run_inner(
+        &self,
+        desktop: &Desktop,
+        variables: BTreeMap<String, String>,
+        guard: Option<&dyn StepGuard>,
+    ) -> Result<WorkflowReport, AutomationError> {
+        self.validate()?;
+        let start = Instant::now();
+        let mut variables = variables;
+        for (name, value) in &self.variables {
+            variables.entry(name.clone()).or_insert_with(|| value.clone());
+        }
+        let mut run = Run {
+            desktop,
+            guard,
+            stop_on_error: self.stop_on_error,
+            variables,
+            current: None,
+            results: Vec::new(),
+            failed: 0,
+            stopped: false,
+        };
+        for (index, step) in self.steps.iter().enumerate() {
+            run.step(index, None, step).await;
+            if run.stopped {
+                break;
+            }
+        }
+
+        let status = match run.failed {
+            0 => WorkflowStatus::Success,
+            failed if failed == run.results.len() => WorkflowStatus::Error,
+            _ => WorkflowStatus::Partial,
+        };
+        info!(?status, failed = run.failed, "Workflow finished");
+        Ok(WorkflowReport {
+            status,
+            steps_failed: run.failed,
+            results: run.results,
+            variables: run.variables,
+            duration_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+}
+
+fn count_steps(steps: &[WorkflowStep]) -> usize {
+    steps
+        .iter()
+        .map(|step| match step {
+            WorkflowStep::If { then, otherwise, .. } => 1 + count_steps(then) + count_steps(otherwise),
+            _ => 1,
+        })
+        .sum()
+}
+
+// Returns whether an element is current after the steps
+fn validate_steps(steps: &[WorkflowStep], prefix: &str, mut has_element: bool) -> Result<bool, AutomationError> {
+    for (index, step) in steps.iter().enumerate() {
+        let position = format!("{}{}", prefix, index);
+        let invalid = |reason: &str| {
+            AutomationError::InvalidArgument(format!(
+                "Step {} ({}): {}",
+                position,
+                step.action(),
+                reason
+            ))
+        };
+        match step {
+            WorkflowStep::OpenApplication { .. } => has_element = true,
+            WorkflowStep::If { condition, then, otherwise } => {
+                if let StepCondition::Exists { selector_chain, .. }
+                | StepCondition::NotExists { selector_chain, .. } = condition
+                {
+                    if selector_chain.is_empty() {
+                        return Err(invalid("selector_chain cannot be empty"));
+                    }
+                }
+                let then = validate_steps(then, &format!("{}.then.", position), has_element)?;
+                let otherwise = validate_steps(otherwise, &format!("{}.else.", position), has_element)?;
+                has_element = then && otherwise;
+            }
+            _ => match step.element_source() {
+                Some(Some(chain)) if chain.is_empty() => {
+                    return Err(invalid("selector_chain cannot be empty"))
+                }
+                Some(None) if !has_element => {
+                    return Err(invalid("no selector_chain and no element located by an earlier step"))
+                }
+                Some(_) => has_element = true,
+                None => {}
+            },
+        }
+    }
+    Ok(has_element)
+}
+
+/// Replace `${name}` with the variable's value. Unknown variables are an error
+pub fn expand_variables(text: &str, variables: &BTreeMap<String, String>) -> Result<String, AutomationError> {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find('}').ok_or_else(|| {
+            AutomationError::InvalidArgument(format!("Unclosed '${{' in '{}'", text))
+        })?;
+        let name = after[..end].trim();
+        let value = variables.get(name).ok_or_else(|| {
+            AutomationError::InvalidArgument(format!("Unknown variable '{}' in '{}'", name, text))
+        })?;
+        expanded.push_str(value);
+        rest = &after[end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// A failed `assert`, reported like other verification failures
+fn assertion_failed(check: String, message: String, element: Option<&UIElement>) -> AutomationError {
+    AutomationError::VerificationFailed(Box::new(VerificationReport {
+        step: "assert".to_string(),
+        checks: 1,
+        failures: vec![VerificationFailure {
+            check,
+            message,
+            element: element.map(UIElement::to_serializable),
+            screenshot: None,
+        }],
+    }))
+}
+
+fn element_summary(element: &UIElement) -> Value {
+    json!({
+        "name": element.name().unwrap_or_default(),
+        "role": element.role(),
+        "id": element.id().unwrap_or_default(),
+    })
+}
+
+// State of one run
+struct Run<'a> {
+    desktop: &'a Desktop,
+    guard: Option<&'a dyn StepGuard>,
+    stop_on_error: bool,
+    variables: BTreeMap<String, String>,
+    current: Option<UIElement>,
+    results: Vec<StepResult>,
+    failed: usize,
+    stopped: bool,
+}
+
+impl Run<'_> {
+    // Boxed since `if` steps recurse
+    fn step<'s>(
+        &'s mut self,
+        index: usize,
+        branch: Option<String>,
+        step: &'s WorkflowStep,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send + 's>> {
+        Box::pin(async move {
+            let start = Instant::now();
+            let outcome = match self.guard {
+                Some(guard) => match guard.before_step(step, &self.variables).await {
+                    Ok(()) => self.perform(step).await,
+                    Err(e) => Err(e),
+                },
+                None => self.perform(step).await,
+            };
+            let duration_ms = start.elapsed().as_millis() as u64;
+            let (result, taken) = match outcome {
+                Ok((details, taken)) => (
+                    StepResult {
+                        step: index,
+                        branch: branch.clone(),
+                        action: step.action().to_string(),
+                        status: StepStatus::Success,
+                        details: Some(details),
+                        reason: None,
+                        error_code: None,
+                        duration_ms,
+                    },
+                    taken,
+                ),
+                Err(e) => {
+                    debug!(index, ?branch, action = step.action(), "Workflow step failed: {}", e);
+                    self.failed += 1;
+                    self.stopped = self.stop_on_error;
+                    (
+                        StepResult {
+                            step: index,
+                            branch: branch.clone(),
+                            action: step.action().to_string(),
+                            status: StepStatus::Error,
+                            details: None,
+                            reason: Some(e.to_string()),
+                            error_code: Some(e.code()),
+                            duration_ms,
+                        },
+                        None,
+                    )
+                }
+            };
+            self.results.push(result);
+
+            // The branch of an `if` step
+            let Some((label, steps)) = taken else {
+                return;
+            };
+            let prefix = match &branch {
+                Some(branch) => format!("{}.{}", branch, label),
+                None => label.to_string(),
+            };
+            for (position, nested) in steps.iter().enumerate() {
+                if self.stopped {
+                    break;
+                }
+                self.step(index, Some(format!("{}.{}", prefix, position)), nested)
+                    .await;
+            }
+        })
+    }
+
+    // The step's details, and for `if` steps the branch to run
+    async fn perform<'s>(
+        &mut self,
+        step: &'s WorkflowStep,
+    ) -> Result<(Value, Option<(&'static str, &'s [WorkflowStep])>), AutomationError> {
+        let details = match step {
+            WorkflowStep::Locate { selector_chain, timeout_ms } => {
+                let element = self.element(Some(selector_chain), *timeout_ms).await?;
+                json!({"element": element_summary(&element)})
+            }
+            WorkflowStep::Click { selector_chain, timeout_ms } => {
+                let element = self.element(selector_chain.as_ref(), *timeout_ms).await?;
+                let result = element.click()?;
+                json!({"element": element_summary(&element), "method": result.method})
+            }
+            WorkflowStep::Type { selector_chain, text, timeout_ms } => {
+                let element = self.element(selector_chain.as_ref(), *timeout_ms).await?;
+                let text = self.expand(text)?;
+                element.type_text(&text, false)?;
+                json!({"element": element_summary(&element), "text_typed": text})
+            }
+            WorkflowStep::PressKey { selector_chain, key, timeout_ms } => {
+                let element = self.element(selector_chain.as_ref(), *timeout_ms).await?;
+                let key = self.expand(key)?;
+                element.press_key(&key)?;
+                json!({"element": element_summary(&element), "key_pressed": key})
+            }
+            WorkflowStep::Assert {
+                selector_chain,
+                condition,
+                text_contains,
+                timeout_ms,
+            } => {
+                self.assert(
+                    selector_chain.as_ref(),
+                    condition.as_deref(),
+                    text_contains.as_deref(),
+                    *timeout_ms,
+                )
+                .await?
+            }
+            WorkflowStep::Wait { duration_ms } => {
+                tokio::time::sleep(Duration::from_millis(*duration_ms)).await;
+                json!({"waited_ms": duration_ms})
+            }
+            WorkflowStep::OpenApplication { app } => {
+                let app = self.expand(app)?;
+                let element = self.desktop.open_application(&app)?;
+                self.current = Some(element.clone());
+                json!({"application": app, "element": element_summary(&element)})
+            }
+            WorkflowStep::Set { variable, value } => {
+                let value = self.expand(value)?;
+                self.variables.insert(variable.clone(), value.clone());
+                json!({"variable": variable, "value": value})
+            }
+            WorkflowStep::Extract {
+                selector_chain,
+                variable,
+                property,
+                timeout_ms,
+            } => {
+                let element = self.element(selector_chain.as_ref(), *timeout_ms).await?;
+                let property = property.as_deref().unwrap_or("text");
+                let value = match property {
+                    "text" => element.text(TEXT_DEPTH)?,
+                    "name" => element.name_or_empty(),
+                    "value" => element.value_or_empty(),
+                    other => {
+                        return Err(AutomationError::InvalidArgument(format!(
+                            "Invalid property '{}'. Valid properties: text, name, value",
+                            other
+                        )))
+                    }
+                };
+                self.variables.insert(variable.clone(), value.clone());
+                json!({"element": element_summary(&element), "variable": variable, "value": value})
+            }
+            WorkflowStep::If { condition, then, otherwise } => {
+                let met = self.condition(condition).await?;
+                let (label, steps) = if met { ("then", then) } else { ("else", otherwise) };
+                return Ok((
+                    json!({"condition_met": met, "branch": label}),
+                    Some((label, steps.as_slice())),
+                ));
+            }
+        };
+        Ok((details, None))
+    }
+
+    async fn assert(
+        &mut self,
+        selector_chain: Option<&Vec<String>>,
+        condition: Option<&str>,
+        text_contains: Option<&str>,
+        timeout_ms: Option<u64>,
+    ) -> Result<Value, AutomationError> {
+        let condition = condition.unwrap_or("exists").to_lowercase();
+        if condition == "not_exists" {
+            let found = match selector_chain {
+                Some(chain) => {
+                    let timeout = timeout_ms.map(Duration::from_millis).unwrap_or(CONDITION_TIMEOUT);
+                    self.locator(chain)?.try_wait(Some(timeout)).await?
+                }
+                None => Some(self.element(None, None).await?),
+            };
+            return match found {
+                Some(element) => Err(assertion_failed(
+                    "not_exists".to_string(),
+                    format!("Element exists: {}", element_summary(&element)),
+                    Some(&element),
+                )),
+                None => Ok(json!({"condition": condition, "condition_met": true})),
+            };
+        }
+        let element = self.element(selector_chain, timeout_ms).await?;
+        let met = match condition.as_str() {
+            "exists" => true,
+            "visible" => element.is_visible()?,
+            "enabled" => element.is_enabled()?,
+            "focused" => element.is_focused()?,
+            other => {
+                return Err(AutomationError::InvalidArgument(format!(
+                    "Invalid condition '{}'. Valid conditions: exists, not_exists, visible, enabled, focused",
+                    other
+                )))
+            }
+        };
+        if !met {
+            return Err(assertion_failed(
+                condition.clone(),
+                format!("Element is not {}", condition),
+                Some(&element),
+            ));
+        }
+        if let Some(expected) = text_contains {
+            let expected = self.expand(expected)?;
+            let attributes = element.attributes();
+            let text = element.text(TEXT_DEPTH).unwrap_or_default();
+            let contains = [attributes.name, attributes.value, Some(text)]
+                .iter()
+                .flatten()
+                .any(|t| t.contains(expected.as_str()));
+            if !contains {
+                return Err(assertion_failed(
+                    "text_contains".to_string(),
+                    format!("Element text does not contain '{}'", expected),
+                    Some(&element),
+                ));
+            }
+        }
+        Ok(json!({"condition": condition, "condition_met": true, "element": element_summary(&element)}))
+    }
+
+    async fn condition(&self, condition: &StepCondition) -> Result<bool, AutomationError> {
+        match condition {
+            StepCondition::Exists { selector_chain, timeout_ms }
+            | StepCondition::NotExists { selector_chain, timeout_ms } => {
+                let timeout = timeout_ms.map(Duration::from_millis).unwrap_or(CONDITION_TIMEOUT);
+                let exists = self.locator(selector_chain)?.try_wait(Some(timeout)).await?.is_some();
+                Ok(exists == matches!(condition, StepCondition::Exists { .. }))
+            }
+            StepCondition::Equals { variable, value } => {
+                Ok(self.variables.get(variable) == Some(&self.expand(value)?))
+            }
+            StepCondition::NotEquals { variable, value } => {
+                Ok(self.variables.get(variable) != Some(&self.expand(value)?))
+            }
+        }
+    }
+
+    fn expand(&self, text: &str) -> Result<String, AutomationError> {
+        expand_variables(text, &self.variables)
+    }
+
+    fn locator(&self, selector_chain: &[String]) -> Result<Locator, AutomationError> {
+        let mut selectors = selector_chain.iter();
+        let first = selectors.next().ok_or_else(|| {
+            AutomationError::InvalidArgument("selector_chain cannot be empty".to_string())
+        })?;
+        let mut locator = self.desktop.locator(Selector::from(self.expand(first)?.as_str()));
+        for selector in selectors {
+            locator = locator.locator(Selector::from(self.expand(selector)?.as_str()));
+        }
+        Ok(locator)
+    }
+
+    /// Element for a step: located by `selector_chain`, which then becomes
+    /// the current element, or the current element
+    async fn element(
+        &mut self,
+        selector_chain: Option<&Vec<String>>,
+        timeout_ms: Option<u64>,
+    ) -> Result<UIElement, AutomationError> {
+        let Some(chain) = selector_chain else {
+            return self.current.clone().ok_or_else(|| {
+                AutomationError::InvalidArgument("No element located by an earlier step".to_string())
+            });
+        };
+        let element = self
+            .locator(chain)?
+            .wait(timeout_ms.map(Duration::from_millis))
+            .await?;
+        self.current = Some(element.clone());
+        Ok(element)
+    }
+}